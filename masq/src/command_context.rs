@@ -125,12 +125,14 @@ impl CommandContext for CommandContextReal {
 impl CommandContextReal {
     pub fn new(
         daemon_ui_port: u16,
+        ui_token_opt: Option<String>,
         foreground_terminal_interface: Option<TerminalWrapper>,
         generic_broadcast_handle: Box<dyn BroadcastHandle>,
     ) -> Result<Self, ContextError> {
         let mut connection = ConnectionManager::new();
         match connection.connect(
             daemon_ui_port,
+            ui_token_opt,
             generic_broadcast_handle,
             REDIRECT_TIMEOUT_MILLIS,
         ) {
@@ -217,7 +219,8 @@ mod tests {
         let handle = server.start();
         let broadcast_handle = BroadcastHandleInactive;
 
-        let subject = CommandContextReal::new(port, None, Box::new(broadcast_handle)).unwrap();
+        let subject =
+            CommandContextReal::new(port, None, None, Box::new(broadcast_handle)).unwrap();
 
         assert_eq!(subject.active_port(), Some(port));
         handle.stop();
@@ -236,7 +239,8 @@ mod tests {
         let stop_handle = server.start();
         let broadcast_handle = BroadcastHandleInactive;
 
-        let mut subject = CommandContextReal::new(port, None, Box::new(broadcast_handle)).unwrap();
+        let mut subject =
+            CommandContextReal::new(port, None, None, Box::new(broadcast_handle)).unwrap();
         subject.stdin = Box::new(stdin);
         subject.stdout = Box::new(stdout);
         subject.stderr = Box::new(stderr);
@@ -274,7 +278,7 @@ mod tests {
         let port = find_free_port();
         let broadcast_handle = BroadcastHandleInactive;
 
-        let result = CommandContextReal::new(port, None, Box::new(broadcast_handle));
+        let result = CommandContextReal::new(port, None, None, Box::new(broadcast_handle));
 
         match result {
             Err(ConnectionRefused(_)) => (),
@@ -294,7 +298,8 @@ mod tests {
         });
         let stop_handle = server.start();
         let broadcast_handle = BroadcastHandleInactive;
-        let mut subject = CommandContextReal::new(port, None, Box::new(broadcast_handle)).unwrap();
+        let mut subject =
+            CommandContextReal::new(port, None, None, Box::new(broadcast_handle)).unwrap();
 
         let response = subject.transact(
             UiSetupRequest { values: vec![] }.tmb(1),
@@ -312,7 +317,8 @@ mod tests {
         let server = MockWebSocketsServer::new(port).queue_string("disconnect");
         let stop_handle = server.start();
         let broadcast_handle = BroadcastHandleInactive;
-        let mut subject = CommandContextReal::new(port, None, Box::new(broadcast_handle)).unwrap();
+        let mut subject =
+            CommandContextReal::new(port, None, None, Box::new(broadcast_handle)).unwrap();
 
         let response = subject.transact(
             UiSetupRequest { values: vec![] }.tmb(1),
@@ -338,7 +344,7 @@ mod tests {
         let server = MockWebSocketsServer::new(port);
         let stop_handle = server.start();
         let broadcast_handle = BroadcastHandleInactive;
-        let subject_result = CommandContextReal::new(port, None, Box::new(broadcast_handle));
+        let subject_result = CommandContextReal::new(port, None, None, Box::new(broadcast_handle));
         let mut subject = subject_result.unwrap();
         subject.stdin = Box::new(stdin);
         subject.stdout = Box::new(stdout);
@@ -13,6 +13,7 @@ pub trait CommandProcessorFactory {
         terminal_interface: Option<TerminalWrapper>,
         generic_broadcast_handle: Box<dyn BroadcastHandle>,
         ui_port: u16,
+        ui_token_opt: Option<String>,
     ) -> Result<Box<dyn CommandProcessor>, CommandError>;
 }
 
@@ -25,8 +26,14 @@ impl CommandProcessorFactory for CommandProcessorFactoryReal {
         terminal_interface: Option<TerminalWrapper>,
         generic_broadcast_handle: Box<dyn BroadcastHandle>,
         ui_port: u16,
+        ui_token_opt: Option<String>,
     ) -> Result<Box<dyn CommandProcessor>, CommandError> {
-        match CommandContextReal::new(ui_port, terminal_interface, generic_broadcast_handle) {
+        match CommandContextReal::new(
+            ui_port,
+            ui_token_opt,
+            terminal_interface,
+            generic_broadcast_handle,
+        ) {
             Ok(context) => Ok(Box::new(CommandProcessorReal { context })),
             Err(ContextError::ConnectionRefused(s)) => Err(CommandError::ConnectionProblem(s)),
             Err(e) => panic!("Unexpected error: {:?}", e),
@@ -107,7 +114,7 @@ mod tests {
         let subject = CommandProcessorFactoryReal::new();
         let broadcast_handle = BroadcastHandleInactive;
 
-        let result = subject.make(None, Box::new(broadcast_handle), ui_port);
+        let result = subject.make(None, Box::new(broadcast_handle), ui_port, None);
 
         match result.err() {
             Some(CommandError::ConnectionProblem(_)) => (),
@@ -152,7 +159,12 @@ mod tests {
         let p_f = CommandProcessorFactoryReal::new();
         let stop_handle = server.start();
         let mut processor = p_f
-            .make(Some(terminal_interface), generic_broadcast_handle, ui_port)
+            .make(
+                Some(terminal_interface),
+                generic_broadcast_handle,
+                ui_port,
+                None,
+            )
             .unwrap();
         processor
             .process(Box::new(CheckPasswordCommand {
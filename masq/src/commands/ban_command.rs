@@ -0,0 +1,408 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use crate::terminal::terminal_interface::TerminalWrapper;
+use clap::{App, Arg, SubCommand};
+use masq_lib::messages::{
+    UiBanChangedBroadcast, UiBanRequest, UiBanResponse, UiUnbanRequest, UiUnbanResponse,
+};
+use masq_lib::{as_any_ref_in_trait_impl, short_writeln};
+use std::io::Write;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BanCommand {
+    Ban {
+        wallet: String,
+        reason_opt: Option<String>,
+    },
+    Unban {
+        wallet: String,
+    },
+}
+
+const BAN_SUBCOMMAND_ABOUT: &str =
+    "Bans a wallet so that the Node refuses to serve it. A ban made this way stays in force \
+     until it's lifted with the unban command, even if the wallet pays its debts.";
+const UNBAN_SUBCOMMAND_ABOUT: &str =
+    "Lifts the ban of a wallet, no matter whether the ban was made by hand or by the Node itself.";
+const WALLET_ARG_HELP: &str = "Address of the wallet, starting with 0x.";
+const REASON_ARG_HELP: &str = "Note to remember why the wallet was banned.";
+
+impl BanCommand {
+    pub fn new_ban(pieces: &[String]) -> Result<Self, String> {
+        match ban_subcommand().get_matches_from_safe(pieces) {
+            Ok(matches) => Ok(Self::Ban {
+                wallet: matches
+                    .value_of("wallet")
+                    .expect("wallet is not properly required")
+                    .to_string(),
+                reason_opt: matches.value_of("reason").map(|reason| reason.to_string()),
+            }),
+            Err(e) => Err(format!("{}", e)),
+        }
+    }
+
+    pub fn new_unban(pieces: &[String]) -> Result<Self, String> {
+        match unban_subcommand().get_matches_from_safe(pieces) {
+            Ok(matches) => Ok(Self::Unban {
+                wallet: matches
+                    .value_of("wallet")
+                    .expect("wallet is not properly required")
+                    .to_string(),
+            }),
+            Err(e) => Err(format!("{}", e)),
+        }
+    }
+
+    pub fn handle_broadcast(
+        body: UiBanChangedBroadcast,
+        stdout: &mut dyn Write,
+        term_interface: &TerminalWrapper,
+    ) {
+        let _lock = term_interface.lock();
+        match (body.banned, body.reason_opt) {
+            (true, Some(reason)) => short_writeln!(
+                stdout,
+                "\nWallet {} has been banned: {}\n",
+                body.wallet,
+                reason
+            ),
+            (true, None) => short_writeln!(stdout, "\nWallet {} has been banned\n", body.wallet),
+            (false, _) => short_writeln!(stdout, "\nWallet {} has been unbanned\n", body.wallet),
+        }
+        stdout.flush().expect("flush failed");
+    }
+}
+
+impl Command for BanCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        match self {
+            Self::Ban { wallet, reason_opt } => {
+                let input = UiBanRequest {
+                    wallet: wallet.clone(),
+                    reason_opt: reason_opt.clone(),
+                };
+                let response: UiBanResponse =
+                    transaction(input, context, STANDARD_COMMAND_TIMEOUT_MILLIS)?;
+                if response.already_banned {
+                    short_writeln!(
+                        context.stdout(),
+                        "Wallet {} was already banned; it won't be unbanned automatically now",
+                        wallet
+                    )
+                } else {
+                    short_writeln!(context.stdout(), "Wallet {} has been banned", wallet)
+                }
+            }
+            Self::Unban { wallet } => {
+                let input = UiUnbanRequest {
+                    wallet: wallet.clone(),
+                };
+                let response: UiUnbanResponse =
+                    transaction(input, context, STANDARD_COMMAND_TIMEOUT_MILLIS)?;
+                if response.was_banned {
+                    short_writeln!(context.stdout(), "Wallet {} has been unbanned", wallet)
+                } else {
+                    short_writeln!(context.stdout(), "Wallet {} was not banned", wallet)
+                }
+            }
+        }
+        Ok(())
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+fn wallet_arg() -> Arg<'static, 'static> {
+    Arg::with_name("wallet")
+        .help(WALLET_ARG_HELP)
+        .value_name("WALLET")
+        .index(1)
+        .required(true)
+        .case_insensitive(false)
+}
+
+pub fn ban_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("ban")
+        .about(BAN_SUBCOMMAND_ABOUT)
+        .arg(wallet_arg())
+        .arg(
+            Arg::with_name("reason")
+                .help(REASON_ARG_HELP)
+                .long("reason")
+                .value_name("REASON")
+                .required(false)
+                .case_insensitive(false),
+        )
+}
+
+pub fn unban_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("unban")
+        .about(UNBAN_SUBCOMMAND_ABOUT)
+        .arg(wallet_arg())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_factory::{CommandFactory, CommandFactoryError, CommandFactoryReal};
+    use crate::test_utils::mocks::{CommandContextMock, TerminalPassiveMock};
+    use masq_lib::constants::BAN_REQUEST_ERROR;
+    use masq_lib::messages::ToMessageBody;
+    use masq_lib::test_utils::fake_stream_holder::ByteArrayWriter;
+    use std::sync::{Arc, Mutex};
+
+    const WALLET: &str = "0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440";
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            BAN_SUBCOMMAND_ABOUT,
+            "Bans a wallet so that the Node refuses to serve it. A ban made this way stays in force \
+             until it's lifted with the unban command, even if the wallet pays its debts."
+        );
+        assert_eq!(
+            UNBAN_SUBCOMMAND_ABOUT,
+            "Lifts the ban of a wallet, no matter whether the ban was made by hand or by the Node itself."
+        );
+        assert_eq!(WALLET_ARG_HELP, "Address of the wallet, starting with 0x.");
+        assert_eq!(
+            REASON_ARG_HELP,
+            "Note to remember why the wallet was banned."
+        );
+    }
+
+    #[test]
+    fn command_factory_makes_ban_and_unban_commands() {
+        let factory = CommandFactoryReal::new();
+
+        let ban = factory
+            .make(&[
+                "ban".to_string(),
+                WALLET.to_string(),
+                "--reason".to_string(),
+                "Spammer".to_string(),
+            ])
+            .unwrap();
+        let ban_without_reason = factory
+            .make(&["ban".to_string(), WALLET.to_string()])
+            .unwrap();
+        let unban = factory
+            .make(&["unban".to_string(), WALLET.to_string()])
+            .unwrap();
+
+        assert_eq!(
+            ban.as_any().downcast_ref::<BanCommand>().unwrap(),
+            &BanCommand::Ban {
+                wallet: WALLET.to_string(),
+                reason_opt: Some("Spammer".to_string())
+            }
+        );
+        assert_eq!(
+            ban_without_reason
+                .as_any()
+                .downcast_ref::<BanCommand>()
+                .unwrap(),
+            &BanCommand::Ban {
+                wallet: WALLET.to_string(),
+                reason_opt: None
+            }
+        );
+        assert_eq!(
+            unban.as_any().downcast_ref::<BanCommand>().unwrap(),
+            &BanCommand::Unban {
+                wallet: WALLET.to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn command_factory_complains_about_missing_wallet() {
+        let factory = CommandFactoryReal::new();
+
+        let ban_result = factory.make(&["ban".to_string()]);
+        let unban_result = factory.make(&["unban".to_string()]);
+
+        match (ban_result, unban_result) {
+            (
+                Err(CommandFactoryError::CommandSyntax(ban_msg)),
+                Err(CommandFactoryError::CommandSyntax(unban_msg)),
+            ) => {
+                assert!(ban_msg.contains("<WALLET>"), "{}", ban_msg);
+                assert!(unban_msg.contains("<WALLET>"), "{}", unban_msg);
+            }
+            x => panic!("Expected two syntax errors, got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn ban_command_bans_a_wallet() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiBanResponse {
+                already_banned: false,
+            }
+            .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = BanCommand::Ban {
+            wallet: WALLET.to_string(),
+            reason_opt: Some("Spammer".to_string()),
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            *transact_params_arc.lock().unwrap(),
+            vec![(
+                UiBanRequest {
+                    wallet: WALLET.to_string(),
+                    reason_opt: Some("Spammer".to_string())
+                }
+                .tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            format!("Wallet {} has been banned\n", WALLET)
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn ban_command_reports_a_wallet_that_was_banned_already() {
+        let mut context = CommandContextMock::new().transact_result(Ok(UiBanResponse {
+            already_banned: true,
+        }
+        .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let subject = BanCommand::Ban {
+            wallet: WALLET.to_string(),
+            reason_opt: None,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            format!(
+                "Wallet {} was already banned; it won't be unbanned automatically now\n",
+                WALLET
+            )
+        );
+    }
+
+    #[test]
+    fn unban_command_unbans_a_wallet() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiUnbanResponse { was_banned: true }.tmb(0)))
+            .transact_result(Ok(UiUnbanResponse { was_banned: false }.tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let subject = BanCommand::Unban {
+            wallet: WALLET.to_string(),
+        };
+
+        let first_result = subject.execute(&mut context);
+        let second_result = subject.execute(&mut context);
+
+        assert_eq!(first_result, Ok(()));
+        assert_eq!(second_result, Ok(()));
+        let expected_request = UiUnbanRequest {
+            wallet: WALLET.to_string(),
+        };
+        assert_eq!(
+            *transact_params_arc.lock().unwrap(),
+            vec![
+                (
+                    expected_request.clone().tmb(0),
+                    STANDARD_COMMAND_TIMEOUT_MILLIS
+                ),
+                (expected_request.tmb(0), STANDARD_COMMAND_TIMEOUT_MILLIS)
+            ]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            format!(
+                "Wallet {} has been unbanned\nWallet {} was not banned\n",
+                WALLET, WALLET
+            )
+        );
+    }
+
+    #[test]
+    fn ban_command_passes_on_a_rejection() {
+        let mut context = CommandContextMock::new().transact_result(Err(
+            ContextError::PayloadError(BAN_REQUEST_ERROR, "Invalid wallet".to_string()),
+        ));
+        let stdout_arc = context.stdout_arc();
+        let subject = BanCommand::Ban {
+            wallet: "booga".to_string(),
+            reason_opt: None,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                BAN_REQUEST_ERROR,
+                "Invalid wallet".to_string()
+            ))
+        );
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn handle_broadcast_announces_bans_and_unbans() {
+        let term_interface = TerminalWrapper::new(Arc::new(TerminalPassiveMock::new()));
+        let mut stdout = ByteArrayWriter::new();
+
+        BanCommand::handle_broadcast(
+            UiBanChangedBroadcast {
+                wallet: WALLET.to_string(),
+                banned: true,
+                reason_opt: Some("Delinquent".to_string()),
+            },
+            &mut stdout,
+            &term_interface,
+        );
+        BanCommand::handle_broadcast(
+            UiBanChangedBroadcast {
+                wallet: WALLET.to_string(),
+                banned: true,
+                reason_opt: None,
+            },
+            &mut stdout,
+            &term_interface,
+        );
+        BanCommand::handle_broadcast(
+            UiBanChangedBroadcast {
+                wallet: WALLET.to_string(),
+                banned: false,
+                reason_opt: None,
+            },
+            &mut stdout,
+            &term_interface,
+        );
+
+        assert_eq!(
+            stdout.get_string(),
+            format!(
+                "\nWallet {0} has been banned: Delinquent\n\n\
+                 \nWallet {0} has been banned\n\n\
+                 \nWallet {0} has been unbanned\n\n",
+                WALLET
+            )
+        );
+    }
+}
@@ -0,0 +1,188 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use clap::{App, SubCommand};
+use masq_lib::messages::{UiBanListRequest, UiBanListResponse, UiBannedWallet};
+use masq_lib::{as_any_ref_in_trait_impl, short_writeln};
+use std::fmt::Debug;
+use time::macros::format_description;
+use time::OffsetDateTime;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BanListCommand {}
+
+const BAN_LIST_SUBCOMMAND_ABOUT: &str =
+    "Displays the wallets the Node refuses to serve, with the reason and time of each ban. \
+     Only valid if Node is already running.";
+
+pub fn ban_list_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("ban-list").about(BAN_LIST_SUBCOMMAND_ABOUT)
+}
+
+impl Command for BanListCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let response: UiBanListResponse = transaction(
+            UiBanListRequest {},
+            context,
+            STANDARD_COMMAND_TIMEOUT_MILLIS,
+        )?;
+        if response.banned.is_empty() {
+            short_writeln!(context.stdout(), "No wallets are banned.");
+            return Ok(());
+        }
+        short_writeln!(
+            context.stdout(),
+            "{:<42}   {:<9}   {:<19}   Reason",
+            "Wallet",
+            "Origin",
+            "Banned at (UTC)"
+        );
+        response
+            .banned
+            .iter()
+            .for_each(|banned| short_writeln!(context.stdout(), "{}", Self::render_row(banned)));
+        Ok(())
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+impl Default for BanListCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BanListCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn render_row(banned: &UiBannedWallet) -> String {
+        let origin = if banned.manual { "manual" } else { "automatic" };
+        let banned_at = banned
+            .timestamp_s_opt
+            .and_then(|timestamp_s| OffsetDateTime::from_unix_timestamp(timestamp_s as i64).ok())
+            .and_then(|date_time| {
+                date_time
+                    .format(format_description!(
+                        "[year]-[month]-[day] [hour]:[minute]:[second]"
+                    ))
+                    .ok()
+            })
+            .unwrap_or_else(|| "unknown".to_string());
+        format!(
+            "{:<42}   {:<9}   {:<19}   {}",
+            banned.wallet,
+            origin,
+            banned_at,
+            banned.reason_opt.as_deref().unwrap_or("")
+        )
+        .trim_end()
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError::ConnectionDropped;
+    use crate::command_factory::{CommandFactory, CommandFactoryReal};
+    use crate::commands::commands_common::CommandError::ConnectionProblem;
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::ToMessageBody;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            BAN_LIST_SUBCOMMAND_ABOUT,
+            "Displays the wallets the Node refuses to serve, with the reason and time of each ban. \
+             Only valid if Node is already running."
+        );
+    }
+
+    #[test]
+    fn testing_command_factory_here() {
+        let factory = CommandFactoryReal::new();
+        let mut context = CommandContextMock::new()
+            .transact_result(Ok(UiBanListResponse { banned: vec![] }.tmb(0)));
+        let subject = factory.make(&["ban-list".to_string()]).unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn ban_list_command_displays_every_ban() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiBanListResponse {
+                banned: vec![
+                    UiBannedWallet {
+                        wallet: "0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440".to_string(),
+                        reason_opt: Some("Spammer".to_string()),
+                        timestamp_s_opt: Some(1_700_000_000),
+                        manual: true,
+                    },
+                    UiBannedWallet {
+                        wallet: "0xA884A2F1A5Ec6C2e499644666a5E6af97B966888".to_string(),
+                        reason_opt: None,
+                        timestamp_s_opt: None,
+                        manual: false,
+                    },
+                ],
+            }
+            .tmb(11)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = BanListCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            *transact_params_arc.lock().unwrap(),
+            vec![(UiBanListRequest {}.tmb(0), STANDARD_COMMAND_TIMEOUT_MILLIS)]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Wallet                                       Origin      Banned at (UTC)       Reason\n\
+             0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440   manual      2023-11-14 22:13:20   Spammer\n\
+             0xA884A2F1A5Ec6C2e499644666a5E6af97B966888   automatic   unknown\n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn ban_list_command_tells_when_nobody_is_banned() {
+        let mut context = CommandContextMock::new()
+            .transact_result(Ok(UiBanListResponse { banned: vec![] }.tmb(11)));
+        let stdout_arc = context.stdout_arc();
+        let subject = BanListCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "No wallets are banned.\n"
+        );
+    }
+
+    #[test]
+    fn ban_list_command_handles_send_failure() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ConnectionDropped("blah".to_string())));
+        let subject = BanListCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Err(ConnectionProblem("blah".to_string())));
+    }
+}
@@ -0,0 +1,258 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use clap::{App, SubCommand};
+use masq_lib::messages::{
+    CrashReason, UiCrashHistoryRequest, UiCrashHistoryResponse, UiCrashRecord, UiRestartDecision,
+};
+use masq_lib::{as_any_ref_in_trait_impl, short_writeln};
+use std::fmt::Debug;
+use time::macros::format_description;
+use time::OffsetDateTime;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CrashHistoryCommand {}
+
+const CRASH_HISTORY_SUBCOMMAND_ABOUT: &str =
+    "Shows the Daemon's restart policy and the Node crashes it has seen since it started, along with \
+     what it decided to do about each one.";
+
+pub fn crash_history_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("crash-history").about(CRASH_HISTORY_SUBCOMMAND_ABOUT)
+}
+
+impl Command for CrashHistoryCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let response: UiCrashHistoryResponse = transaction(
+            UiCrashHistoryRequest {},
+            context,
+            STANDARD_COMMAND_TIMEOUT_MILLIS,
+        )?;
+        match response.restart_policy_opt {
+            Some(policy) => short_writeln!(
+                context.stdout(),
+                "Restart policy: {} (MaxRestarts|InitialBackoffSec|MaxBackoffSec|CrashLoopWindowSec)",
+                policy
+            ),
+            None => short_writeln!(
+                context.stdout(),
+                "No restart policy: a crashed Node stays down."
+            ),
+        }
+        if response.crashes.is_empty() {
+            short_writeln!(context.stdout(), "No crashes recorded.");
+            return Ok(());
+        }
+        short_writeln!(
+            context.stdout(),
+            "{:<19}   {:>10}   {:<10}   {:>9}   {}",
+            "Time (UTC)",
+            "Process",
+            "Decision",
+            "Delay (s)",
+            "Reason"
+        );
+        response
+            .crashes
+            .iter()
+            .for_each(|crash| short_writeln!(context.stdout(), "{}", Self::render_row(crash)));
+        Ok(())
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+impl Default for CrashHistoryCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CrashHistoryCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn render_row(crash: &UiCrashRecord) -> String {
+        let time = OffsetDateTime::from_unix_timestamp(crash.timestamp_s as i64)
+            .ok()
+            .and_then(|date_time| {
+                date_time
+                    .format(format_description!(
+                        "[year]-[month]-[day] [hour]:[minute]:[second]"
+                    ))
+                    .ok()
+            })
+            .unwrap_or_else(|| "unknown".to_string());
+        let decision = match crash.decision {
+            UiRestartDecision::NoRestartPolicy => "Stay down",
+            UiRestartDecision::CleanExit => "Clean exit",
+            UiRestartDecision::Restart => "Restart",
+            UiRestartDecision::CrashLoop => "Crash loop",
+        };
+        let delay = match crash.restart_delay_s_opt {
+            Some(delay_s) => delay_s.to_string(),
+            None => "-".to_string(),
+        };
+        format!(
+            "{:<19}   {:>10}   {:<10}   {:>9}   {}",
+            time,
+            crash.process_id,
+            decision,
+            delay,
+            Self::render_reason(&crash.crash_reason)
+        )
+    }
+
+    fn render_reason(crash_reason: &CrashReason) -> String {
+        match crash_reason {
+            CrashReason::ChildWaitFailure(msg) => {
+                format!("Daemon couldn't wait on the child process: {}", msg.trim())
+            }
+            CrashReason::NoInformation => "No information".to_string(),
+            CrashReason::Unrecognized(msg) => msg.lines().next().unwrap_or("").to_string(),
+            CrashReason::DaemonCrashed => "Daemon crashed".to_string(),
+            CrashReason::NodePanic { message, location } => {
+                format!("Panic at {}: {}", location, message)
+            }
+            CrashReason::ActorPanic {
+                actor,
+                message,
+                location,
+            } => format!("{} panicked at {}: {}", actor, location, message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError::ConnectionDropped;
+    use crate::command_factory::{CommandFactory, CommandFactoryReal};
+    use crate::commands::commands_common::CommandError::ConnectionProblem;
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::ToMessageBody;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            CRASH_HISTORY_SUBCOMMAND_ABOUT,
+            "Shows the Daemon's restart policy and the Node crashes it has seen since it started, \
+             along with what it decided to do about each one."
+        );
+    }
+
+    #[test]
+    fn testing_command_factory_here() {
+        let factory = CommandFactoryReal::new();
+        let mut context = CommandContextMock::new().transact_result(Ok(UiCrashHistoryResponse {
+            restart_policy_opt: None,
+            crashes: vec![],
+        }
+        .tmb(0)));
+        let subject = factory.make(&["crash-history".to_string()]).unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn crash_history_command_displays_policy_and_every_crash() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiCrashHistoryResponse {
+                restart_policy_opt: Some("2|10|600|3600".to_string()),
+                crashes: vec![
+                    UiCrashRecord {
+                        timestamp_s: 1_700_000_000,
+                        process_id: 12345,
+                        crash_reason: CrashReason::Unrecognized(
+                            "Panic: booga\nstack backtrace".to_string(),
+                        ),
+                        decision: UiRestartDecision::Restart,
+                        restart_delay_s_opt: Some(10),
+                    },
+                    UiCrashRecord {
+                        timestamp_s: 1_700_000_050,
+                        process_id: 13579,
+                        crash_reason: CrashReason::ActorPanic {
+                            actor: "ACCOUNTANT".to_string(),
+                            message: "Booga".to_string(),
+                            location: "src/accountant/mod.rs:12:34".to_string(),
+                        },
+                        decision: UiRestartDecision::Restart,
+                        restart_delay_s_opt: Some(20),
+                    },
+                    UiCrashRecord {
+                        timestamp_s: 1_700_000_100,
+                        process_id: 23456,
+                        crash_reason: CrashReason::NoInformation,
+                        decision: UiRestartDecision::CrashLoop,
+                        restart_delay_s_opt: None,
+                    },
+                ],
+            }
+            .tmb(11)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = CrashHistoryCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            *transact_params_arc.lock().unwrap(),
+            vec![(
+                UiCrashHistoryRequest {}.tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            concat!(
+                "Restart policy: 2|10|600|3600 (MaxRestarts|InitialBackoffSec|MaxBackoffSec|CrashLoopWindowSec)\n",
+                "Time (UTC)               Process   Decision     Delay (s)   Reason\n",
+                "2023-11-14 22:13:20        12345   Restart             10   Panic: booga\n",
+                "2023-11-14 22:14:10        13579   Restart             20   ACCOUNTANT panicked at src/accountant/mod.rs:12:34: Booga\n",
+                "2023-11-14 22:15:00        23456   Crash loop           -   No information\n",
+            )
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn crash_history_command_tells_when_there_is_no_policy_and_no_crash() {
+        let mut context = CommandContextMock::new().transact_result(Ok(UiCrashHistoryResponse {
+            restart_policy_opt: None,
+            crashes: vec![],
+        }
+        .tmb(11)));
+        let stdout_arc = context.stdout_arc();
+        let subject = CrashHistoryCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "No restart policy: a crashed Node stays down.\nNo crashes recorded.\n"
+        );
+    }
+
+    #[test]
+    fn crash_history_command_handles_send_failure() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ConnectionDropped("blah".to_string())));
+        let subject = CrashHistoryCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Err(ConnectionProblem("blah".to_string())));
+    }
+}
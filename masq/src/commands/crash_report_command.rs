@@ -0,0 +1,200 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use clap::{App, SubCommand};
+use masq_lib::messages::{UiCrashReport, UiCrashReportRequest, UiCrashReportResponse};
+use masq_lib::{as_any_ref_in_trait_impl, short_writeln};
+use std::fmt::Debug;
+use std::io::Write;
+use time::macros::format_description;
+use time::OffsetDateTime;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CrashReportCommand {}
+
+const CRASH_REPORT_SUBCOMMAND_ABOUT: &str =
+    "Shows the report the Node wrote into its data directory the last time it panicked: the panic \
+     message and location, the actor that was told to crash if any, its last log lines and a backtrace.";
+
+pub fn crash_report_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("crash-report").about(CRASH_REPORT_SUBCOMMAND_ABOUT)
+}
+
+impl Command for CrashReportCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let response: UiCrashReportResponse = transaction(
+            UiCrashReportRequest {},
+            context,
+            STANDARD_COMMAND_TIMEOUT_MILLIS,
+        )?;
+        match response.report_opt {
+            Some(report) => Self::render_report(&report, context.stdout()),
+            None => short_writeln!(
+                context.stdout(),
+                "No crash report found in the Node's data directory."
+            ),
+        }
+        Ok(())
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+impl Default for CrashReportCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CrashReportCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn render_report(report: &UiCrashReport, stdout: &mut dyn Write) {
+        let time = OffsetDateTime::from_unix_timestamp(report.timestamp_s as i64)
+            .ok()
+            .and_then(|date_time| {
+                date_time
+                    .format(format_description!(
+                        "[year]-[month]-[day] [hour]:[minute]:[second]"
+                    ))
+                    .ok()
+            })
+            .unwrap_or_else(|| "unknown".to_string());
+        short_writeln!(
+            stdout,
+            "The Node {} running as process {} panicked at {} UTC.",
+            report.version,
+            report.process_id,
+            time
+        );
+        if let Some(actor) = &report.actor_opt {
+            short_writeln!(stdout, "Actor:    {}", actor);
+        }
+        short_writeln!(stdout, "Location: {}", report.location);
+        short_writeln!(stdout, "Message:  {}", report.panic_message);
+        short_writeln!(stdout, "\nLast {} log lines:", report.log_lines.len());
+        report
+            .log_lines
+            .iter()
+            .for_each(|line| short_writeln!(stdout, "    {}", line));
+        short_writeln!(stdout, "\nBacktrace:\n{}", report.backtrace.trim_end());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError::ConnectionDropped;
+    use crate::command_factory::{CommandFactory, CommandFactoryReal};
+    use crate::commands::commands_common::CommandError::ConnectionProblem;
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::ToMessageBody;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            CRASH_REPORT_SUBCOMMAND_ABOUT,
+            "Shows the report the Node wrote into its data directory the last time it panicked: \
+             the panic message and location, the actor that was told to crash if any, its last \
+             log lines and a backtrace."
+        );
+    }
+
+    #[test]
+    fn testing_command_factory_here() {
+        let factory = CommandFactoryReal::new();
+        let mut context = CommandContextMock::new()
+            .transact_result(Ok(UiCrashReportResponse { report_opt: None }.tmb(0)));
+        let subject = factory.make(&["crash-report".to_string()]).unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn crash_report_command_displays_the_report() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiCrashReportResponse {
+                report_opt: Some(UiCrashReport {
+                    timestamp_s: 1_700_000_000,
+                    process_id: 4321,
+                    version: "0.9.0".to_string(),
+                    panic_message: "Booga".to_string(),
+                    location: "src/accountant/mod.rs:12:34".to_string(),
+                    actor_opt: Some("ACCOUNTANT".to_string()),
+                    backtrace: "   0: rust_begin_unwind\n   1: core::panicking::panic_fmt\n"
+                        .to_string(),
+                    log_lines: vec!["first line".to_string(), "last line".to_string()],
+                }),
+            }
+            .tmb(11)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = CrashReportCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            *transact_params_arc.lock().unwrap(),
+            vec![(
+                UiCrashReportRequest {}.tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            concat!(
+                "The Node 0.9.0 running as process 4321 panicked at 2023-11-14 22:13:20 UTC.\n",
+                "Actor:    ACCOUNTANT\n",
+                "Location: src/accountant/mod.rs:12:34\n",
+                "Message:  Booga\n",
+                "\n",
+                "Last 2 log lines:\n",
+                "    first line\n",
+                "    last line\n",
+                "\n",
+                "Backtrace:\n",
+                "   0: rust_begin_unwind\n",
+                "   1: core::panicking::panic_fmt\n",
+            )
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn crash_report_command_tells_when_there_is_no_report() {
+        let mut context = CommandContextMock::new()
+            .transact_result(Ok(UiCrashReportResponse { report_opt: None }.tmb(11)));
+        let stdout_arc = context.stdout_arc();
+        let subject = CrashReportCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "No crash report found in the Node's data directory.\n"
+        );
+    }
+
+    #[test]
+    fn crash_report_command_handles_send_failure() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ConnectionDropped("blah".to_string())));
+        let subject = CrashReportCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Err(ConnectionProblem("blah".to_string())));
+    }
+}
@@ -24,6 +24,10 @@ const NO_STATS_ARG_HELP: &str = "Disables statistics that display by default, co
 const GWEI_HELP: &str =
     "Orders money values rendering in gwei of MASQ instead of whole MASQs as the default.";
 const ORDERED_HELP: &str = "Determines in what ordering the top records will be returned. This option works only with the '--top' argument.";
+const EXPORT_HELP: &str = "Fetches all payable and receivable records page by page and writes them into the file given by '--output' instead of displaying them. \
+ Balances are exported in gwei of MASQ and ages in seconds. This argument cannot be combined with other arguments of this command.";
+const OUTPUT_HELP: &str =
+    "Path of the file the '--export' argument writes into. An existing file is overwritten.";
 
 pub fn financials_subcommand() -> App<'static, 'static> {
     SubCommand::with_name("financials")
@@ -92,6 +96,29 @@ pub fn financials_subcommand() -> App<'static, 'static> {
                 .possible_values(&["balance", "age"])
                 .required(false),
         )
+        .arg(
+            Arg::with_name("export")
+                .help(EXPORT_HELP)
+                .value_name("FORMAT")
+                .long("export")
+                .short("e")
+                .case_insensitive(false)
+                .takes_value(true)
+                .possible_values(&["csv", "json"])
+                .requires("output")
+                .conflicts_with_all(&["top", "payable", "receivable", "no-stats", "gwei"])
+                .required(false),
+        )
+        .arg(
+            Arg::with_name("output")
+                .help(OUTPUT_HELP)
+                .value_name("FILE")
+                .long("output")
+                .case_insensitive(false)
+                .takes_value(true)
+                .requires("export")
+                .required(false),
+        )
         .groups(&[
             ArgGroup::with_name("at_least_one_query")
                 .args(&["receivable", "payable", "top"])
@@ -183,6 +210,12 @@ mod tests {
             "Orders money values rendering in gwei of MASQ instead of whole MASQs as the default."
         );
         assert_eq!(ORDERED_HELP, "Determines in what ordering the top records will be returned. This option works only with the '--top' argument.");
+        assert_eq!(EXPORT_HELP, "Fetches all payable and receivable records page by page and writes them into the file given by '--output' instead of displaying them. \
+            Balances are exported in gwei of MASQ and ages in seconds. This argument cannot be combined with other arguments of this command.");
+        assert_eq!(
+            OUTPUT_HELP,
+            "Path of the file the '--export' argument writes into. An existing file is overwritten."
+        );
     }
 
     #[test]
@@ -2,6 +2,7 @@
 
 pub(in crate::commands::financials_command) mod restricted {
     use masq_lib::messages::{CustomQueries, RangeQuery};
+    use std::path::PathBuf;
 
     #[derive(Debug, PartialEq, Eq)]
     pub struct CustomQueryInput {
@@ -26,4 +27,16 @@ pub(in crate::commands::financials_command) mod restricted {
         pub words: Vec<String>,
         pub is_gwei: bool,
     }
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct ExportInput {
+        pub format: ExportFormat,
+        pub output_path: PathBuf,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum ExportFormat {
+        Csv,
+        Json,
+    }
 }
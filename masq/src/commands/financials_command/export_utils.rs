@@ -0,0 +1,170 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+pub(in crate::commands::financials_command) mod restricted {
+    use crate::commands::financials_command::data_structures::restricted::ExportFormat;
+    use masq_lib::messages::{UiPayableAccount, UiReceivableAccount};
+    use serde_json::json;
+
+    pub const EXPORT_PAGE_SIZE: u16 = 500;
+
+    const CSV_HEADER: &str = "table,wallet,age_s,balance_wei,pending_payable_hash";
+
+    pub fn render_export(
+        format: ExportFormat,
+        payable: &[UiPayableAccount],
+        receivable: &[UiReceivableAccount],
+    ) -> String {
+        match format {
+            ExportFormat::Csv => render_csv(payable, receivable),
+            ExportFormat::Json => format!(
+                "{:#}\n",
+                json!({
+                    "payable": payable,
+                    "receivable": receivable,
+                })
+            ),
+        }
+    }
+
+    fn render_csv(payable: &[UiPayableAccount], receivable: &[UiReceivableAccount]) -> String {
+        let payable_lines = payable.iter().map(|account| {
+            format!(
+                "payable,{},{},{},{}",
+                account.wallet,
+                account.age_s,
+                balance_wei(&account.balance_wei_opt, account.balance_gwei as i128),
+                account.pending_payable_hash_opt.as_deref().unwrap_or("")
+            )
+        });
+        let receivable_lines = receivable.iter().map(|account| {
+            format!(
+                "receivable,{},{},{},",
+                account.wallet,
+                account.age_s,
+                balance_wei(&account.balance_wei_opt, account.balance_gwei as i128)
+            )
+        });
+        std::iter::once(CSV_HEADER.to_string())
+            .chain(payable_lines)
+            .chain(receivable_lines)
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    // Nodes older than the export feature send no wei; their gwei is the best we have
+    fn balance_wei(balance_wei_opt: &Option<String>, balance_gwei: i128) -> String {
+        match balance_wei_opt {
+            Some(balance_wei) => balance_wei.clone(),
+            None => (balance_gwei * 1_000_000_000).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::restricted::*;
+    use crate::commands::financials_command::data_structures::restricted::ExportFormat;
+    use masq_lib::messages::{UiPayableAccount, UiReceivableAccount};
+
+    fn accounts() -> (Vec<UiPayableAccount>, Vec<UiReceivableAccount>) {
+        (
+            vec![
+                UiPayableAccount {
+                    wallet: "0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440".to_string(),
+                    age_s: 150_000,
+                    balance_gwei: 8_456_789,
+                    pending_payable_hash_opt: Some(
+                        "0x0290db1d56121112f4d45c1c3f36252f7a4d35f4b2d64ab6d9f4f2df24fb3c9a"
+                            .to_string(),
+                    ),
+                    balance_wei_opt: Some("8456789123456789".to_string()),
+                },
+                UiPayableAccount {
+                    wallet: "0xA884A2F1A5Ec6C2e499644666a5E6af97B966888".to_string(),
+                    age_s: 5_645_405_400,
+                    balance_gwei: 884_332_566,
+                    pending_payable_hash_opt: None,
+                    balance_wei_opt: None,
+                },
+            ],
+            vec![UiReceivableAccount {
+                wallet: "0x6e250504DdfFDb986C4F0bb8Df162503B4118b05".to_string(),
+                age_s: 22_000,
+                balance_gwei: -2_000_000,
+                balance_wei_opt: Some("-2000000000000001".to_string()),
+            }],
+        )
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(EXPORT_PAGE_SIZE, 500)
+    }
+
+    #[test]
+    fn render_export_writes_csv_with_both_tables_in_one_file() {
+        let (payable, receivable) = accounts();
+
+        let result = render_export(ExportFormat::Csv, &payable, &receivable);
+
+        assert_eq!(
+            result,
+            "table,wallet,age_s,balance_wei,pending_payable_hash\n\
+            payable,0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440,150000,8456789123456789,\
+            0x0290db1d56121112f4d45c1c3f36252f7a4d35f4b2d64ab6d9f4f2df24fb3c9a\n\
+            payable,0xA884A2F1A5Ec6C2e499644666a5E6af97B966888,5645405400,884332566000000000,\n\
+            receivable,0x6e250504DdfFDb986C4F0bb8Df162503B4118b05,22000,-2000000000000001,\n"
+        )
+    }
+
+    #[test]
+    fn render_export_writes_json_in_the_shape_of_ui_messages() {
+        let (payable, receivable) = accounts();
+
+        let result = render_export(ExportFormat::Json, &payable, &receivable);
+
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(
+            parsed,
+            serde_json::json!({
+                "payable": [
+                    {
+                        "wallet": "0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440",
+                        "ageS": 150000,
+                        "balanceGwei": 8456789,
+                        "pendingPayableHashOpt": "0x0290db1d56121112f4d45c1c3f36252f7a4d35f4b2d64ab6d9f4f2df24fb3c9a",
+                        "balanceWeiOpt": "8456789123456789"
+                    },
+                    {
+                        "wallet": "0xA884A2F1A5Ec6C2e499644666a5E6af97B966888",
+                        "ageS": 5645405400_u64,
+                        "balanceGwei": 884332566,
+                        "pendingPayableHashOpt": null,
+                        "balanceWeiOpt": null
+                    }
+                ],
+                "receivable": [
+                    {
+                        "wallet": "0x6e250504DdfFDb986C4F0bb8Df162503B4118b05",
+                        "ageS": 22000,
+                        "balanceGwei": -2000000,
+                        "balanceWeiOpt": "-2000000000000001"
+                    }
+                ]
+            })
+        );
+        assert!(result.ends_with("}\n"))
+    }
+
+    #[test]
+    fn render_export_of_empty_books() {
+        let csv = render_export(ExportFormat::Csv, &[], &[]);
+        let json = render_export(ExportFormat::Json, &[], &[]);
+
+        assert_eq!(csv, "table,wallet,age_s,balance_wei,pending_payable_hash\n");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            serde_json::json!({"payable": [], "receivable": []})
+        );
+    }
+}
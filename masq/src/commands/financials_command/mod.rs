@@ -2,6 +2,7 @@
 
 pub mod args_validation;
 pub mod data_structures;
+pub mod export_utils;
 pub mod parsing_and_value_dressing;
 pub mod pretty_print_utils;
 #[cfg(test)]
@@ -13,7 +14,11 @@ use crate::commands::commands_common::{
 };
 use crate::commands::financials_command::args_validation::financials_subcommand;
 use crate::commands::financials_command::data_structures::restricted::{
-    CustomQueryInput, ProcessAccountsMetadata, RangeQueryInput, UserOriginalTypingOfRanges,
+    CustomQueryInput, ExportFormat, ExportInput, ProcessAccountsMetadata, RangeQueryInput,
+    UserOriginalTypingOfRanges,
+};
+use crate::commands::financials_command::export_utils::restricted::{
+    render_export, EXPORT_PAGE_SIZE,
 };
 use crate::commands::financials_command::parsing_and_value_dressing::restricted::{
     parse_masq_range_to_gwei, parse_time_params, split_time_range,
@@ -21,13 +26,15 @@ use crate::commands::financials_command::parsing_and_value_dressing::restricted:
 use crate::commands::financials_command::pretty_print_utils::restricted::process_gwei_into_requested_format;
 use crate::commands::financials_command::pretty_print_utils::restricted::{
     financial_status_totals_title, main_title_for_tops_opt, no_records_found, prepare_metadata,
-    render_accounts_generic, subtitle_for_tops, title_for_custom_query,
+    render_accounts_generic, render_runway, subtitle_for_tops, title_for_custom_query,
     triple_or_single_blank_line, StringValuesFormattableAccount,
 };
+use crate::terminal::terminal_interface::TerminalWrapper;
 use clap::ArgMatches;
 use masq_lib::messages::{
-    CustomQueries, QueryResults, RangeQuery, TopRecordsConfig, UiFinancialStatistics,
-    UiFinancialsRequest, UiFinancialsResponse,
+    CustomQueries, FinancialsPage, QueryResults, RangeQuery, TopRecordsConfig,
+    UiFinancialStatistics, UiFinancialsRequest, UiFinancialsResponse, UiPayableAccount,
+    UiReceivableAccount, UiWalletBalanceWarningBroadcast,
 };
 use masq_lib::short_writeln;
 use masq_lib::utils::ExpectValue;
@@ -35,6 +42,7 @@ use num::CheckedMul;
 use std::fmt::{Debug, Display};
 use std::io::Write;
 use std::num::ParseIntError;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 #[derive(Debug, PartialEq, Eq)]
@@ -43,14 +51,19 @@ pub struct FinancialsCommand {
     gwei_precision: bool,
     top_records_opt: Option<TopRecordsConfig>,
     custom_queries_opt: Option<CustomQueryInput>,
+    export_opt: Option<ExportInput>,
 }
 
 impl Command for FinancialsCommand {
     fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        if let Some(export) = self.export_opt.as_ref() {
+            return Self::export(export, context);
+        }
         let input = UiFinancialsRequest {
             stats_required: self.stats_required,
             top_records_opt: self.top_records_opt,
             custom_queries_opt: self.custom_queries_opt.as_ref().map(|cq| cq.query.clone()),
+            page_opt: None,
         };
         let output: Result<UiFinancialsResponse, CommandError> =
             transaction(input, context, STANDARD_COMMAND_TIMEOUT_MILLIS);
@@ -87,14 +100,80 @@ impl FinancialsCommand {
         let top_records_opt = Self::parse_top_records_args(&matches);
         let gwei_precision = matches.is_present("gwei");
         let custom_queries_opt = Self::parse_custom_query_args(&matches);
+        let export_opt = Self::parse_export_args(&matches);
         Ok(Self {
             stats_required,
             top_records_opt,
             custom_queries_opt,
             gwei_precision,
+            export_opt,
         })
     }
 
+    fn export(export: &ExportInput, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let (payable, receivable) = Self::fetch_all_pages(context)?;
+        let content = render_export(export.format, &payable, &receivable);
+        match std::fs::write(&export.output_path, content) {
+            Ok(()) => {
+                short_writeln!(
+                    context.stdout(),
+                    "Exported {} payable and {} receivable records into {}",
+                    payable.len(),
+                    receivable.len(),
+                    export.output_path.display()
+                );
+                Ok(())
+            }
+            Err(e) => {
+                let msg = format!(
+                    "Writing the export into {} failed: {}",
+                    export.output_path.display(),
+                    e
+                );
+                short_writeln!(context.stderr(), "{}", msg);
+                Err(CommandError::Other(msg))
+            }
+        }
+    }
+
+    fn fetch_all_pages(
+        context: &mut dyn CommandContext,
+    ) -> Result<(Vec<UiPayableAccount>, Vec<UiReceivableAccount>), CommandError> {
+        let mut payable = vec![];
+        let mut receivable = vec![];
+        let mut offset = 0_u32;
+        loop {
+            let input = UiFinancialsRequest {
+                stats_required: false,
+                top_records_opt: None,
+                custom_queries_opt: None,
+                page_opt: Some(FinancialsPage {
+                    offset,
+                    count: EXPORT_PAGE_SIZE,
+                }),
+            };
+            let response: UiFinancialsResponse =
+                match transaction(input, context, STANDARD_COMMAND_TIMEOUT_MILLIS) {
+                    Ok(response) => response,
+                    Err(e) => {
+                        short_writeln!(context.stderr(), "Financials retrieval failed: {:?}", e);
+                        return Err(e);
+                    }
+                };
+            let results = response.query_results_opt.expectv("query results");
+            let payable_page = results.payable_opt.expectv("payable");
+            let receivable_page = results.receivable_opt.expectv("receivable");
+            let is_last_page = payable_page.len() < EXPORT_PAGE_SIZE as usize
+                && receivable_page.len() < EXPORT_PAGE_SIZE as usize;
+            payable.extend(payable_page);
+            receivable.extend(receivable_page);
+            if is_last_page {
+                return Ok((payable, receivable));
+            }
+            offset += EXPORT_PAGE_SIZE as u32;
+        }
+    }
+
     fn process_command_response(
         &self,
         response: UiFinancialsResponse,
@@ -135,6 +214,35 @@ impl FinancialsCommand {
             gwei_flag,
             stdout
         );
+        if let Some(runway) = stats.runway_opt {
+            dump_parameter_line(
+                stdout,
+                "Service fee runway:",
+                &render_runway(runway.service_fee_runway_hours_opt),
+            );
+            dump_parameter_line(
+                stdout,
+                "Transaction fee runway:",
+                &render_runway(runway.transaction_fee_runway_hours_opt),
+            );
+        }
+    }
+
+    pub fn handle_broadcast(
+        body: UiWalletBalanceWarningBroadcast,
+        stdout: &mut dyn Write,
+        term_interface: &TerminalWrapper,
+    ) {
+        let _lock = term_interface.lock();
+        short_writeln!(
+            stdout,
+            "\nConsuming wallet {} has less than {} days of spending left. Runway: MASQ {}, gas {}\n",
+            body.consuming_wallet,
+            body.warning_days,
+            render_runway(body.runway.service_fee_runway_hours_opt),
+            render_runway(body.runway.transaction_fee_runway_hours_opt)
+        );
+        stdout.flush().expect("flush failed");
     }
 
     fn process_queried_records(
@@ -205,6 +313,21 @@ impl FinancialsCommand {
         }
     }
 
+    fn parse_export_args(matches: &ArgMatches) -> Option<ExportInput> {
+        matches.value_of("export").map(|format| ExportInput {
+            format: match format {
+                "csv" => ExportFormat::Csv,
+                "json" => ExportFormat::Json,
+                x => panic!("Clap did not catch invalid export format {}", x),
+            },
+            output_path: PathBuf::from(
+                matches
+                    .value_of("output")
+                    .expect("output should be required with export"),
+            ),
+        })
+    }
+
     fn parse_top_records_args(matches: &ArgMatches) -> Option<TopRecordsConfig> {
         matches.value_of("top").map(|str| TopRecordsConfig {
             count: str
@@ -321,12 +444,14 @@ mod tests {
     use crate::commands::commands_common::CommandError::ConnectionProblem;
     use crate::commands::financials_command::args_validation::financials_subcommand;
     use crate::commands::financials_command::test_utils::transpose_inputs_to_nested_tuples;
-    use crate::test_utils::mocks::CommandContextMock;
+    use crate::test_utils::mocks::{CommandContextMock, TerminalPassiveMock};
     use atty::Stream;
     use masq_lib::messages::{
         ToMessageBody, TopRecordsOrdering, UiFinancialStatistics, UiFinancialsResponse,
-        UiPayableAccount, UiReceivableAccount,
+        UiPayableAccount, UiReceivableAccount, UiWalletRunway,
     };
+    use masq_lib::test_utils::fake_stream_holder::ByteArrayWriter;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
     use masq_lib::ui_gateway::MessageBody;
     use masq_lib::utils::slice_of_strs_to_vec_of_strings;
     use regex::Regex;
@@ -359,7 +484,8 @@ mod tests {
                 UiFinancialsRequest {
                     stats_required: true,
                     top_records_opt: None,
-                    custom_queries_opt: None
+                    custom_queries_opt: None,
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -396,7 +522,8 @@ mod tests {
                         count: 20,
                         ordered_by: TopRecordsOrdering::Balance
                     }),
-                    custom_queries_opt: None
+                    custom_queries_opt: None,
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -433,7 +560,8 @@ mod tests {
                         count: 10,
                         ordered_by: TopRecordsOrdering::Balance
                     }),
-                    custom_queries_opt: None
+                    custom_queries_opt: None,
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -482,7 +610,8 @@ mod tests {
                             min_amount_gwei: 3000000,
                             max_amount_gwei: 5600070000
                         })
-                    })
+                    }),
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -723,7 +852,8 @@ mod tests {
                         count: 123,
                         ordered_by: TopRecordsOrdering::Balance
                     }),
-                    custom_queries_opt: None
+                    custom_queries_opt: None,
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -771,7 +901,8 @@ mod tests {
                             min_amount_gwei: 4000,
                             max_amount_gwei: 50003000000
                         })
-                    })
+                    }),
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -808,7 +939,8 @@ mod tests {
                         count: 7,
                         ordered_by: TopRecordsOrdering::Age
                     }),
-                    custom_queries_opt: None
+                    custom_queries_opt: None,
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -862,7 +994,8 @@ mod tests {
                         "05000", "0010000", "040", "050"
                     ]))
                 }),
-                gwei_precision: false
+                gwei_precision: false,
+                export_opt: None,
             }
         );
     }
@@ -897,7 +1030,8 @@ mod tests {
                         "5000", "10000", "-050", "-040"
                     ]))
                 }),
-                gwei_precision: false
+                gwei_precision: false,
+                export_opt: None,
             }
         );
     }
@@ -911,6 +1045,7 @@ mod tests {
                 total_paid_payable_gwei: 78_455_555,
                 total_unpaid_receivable_gwei: -55_000_400,
                 total_paid_receivable_gwei: 1_278_766_555_456,
+                runway_opt: None,
             }),
             query_results_opt: None,
         };
@@ -932,7 +1067,8 @@ mod tests {
                 UiFinancialsRequest {
                     stats_required: true,
                     top_records_opt: None,
-                    custom_queries_opt: None
+                    custom_queries_opt: None,
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -952,6 +1088,67 @@ mod tests {
         assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
     }
 
+    #[test]
+    fn financials_command_shows_the_runway_of_the_consuming_wallet() {
+        let expected_response = UiFinancialsResponse {
+            stats_opt: Some(UiFinancialStatistics {
+                total_unpaid_and_pending_payable_gwei: 1_166_880_215,
+                total_paid_payable_gwei: 78_455_555,
+                total_unpaid_receivable_gwei: -55_000_400,
+                total_paid_receivable_gwei: 1_278_766_555_456,
+                runway_opt: Some(UiWalletRunway {
+                    service_fee_runway_hours_opt: Some(84),
+                    transaction_fee_runway_hours_opt: None,
+                }),
+            }),
+            query_results_opt: None,
+        };
+        let mut context = CommandContextMock::new().transact_result(Ok(expected_response.tmb(31)));
+        let stdout_arc = context.stdout_arc();
+        let subject = FinancialsCommand::new(&["financials".to_string()]).unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "\
+                \n\
+                Financial status totals in MASQ\n\
+                \n\
+                Unpaid and pending payable:       1.16\n\
+                Paid payable:                     0.07\n\
+                Unpaid receivable:                -0.05\n\
+                Paid receivable:                  1,278.76\n\
+                Service fee runway:               3.5 days\n\
+                Transaction fee runway:           unlimited\n"
+        );
+    }
+
+    #[test]
+    fn wallet_balance_warning_broadcast_is_displayed() {
+        let mut stdout = ByteArrayWriter::new();
+        let term_interface = TerminalWrapper::new(Arc::new(TerminalPassiveMock::new()));
+        let body = UiWalletBalanceWarningBroadcast {
+            consuming_wallet: "0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440".to_string(),
+            service_fee_balance_wei: 1_000_000_000_000_000_000,
+            transaction_fee_balance_wei: 5_000_000_000_000_000,
+            runway: UiWalletRunway {
+                service_fee_runway_hours_opt: Some(36),
+                transaction_fee_runway_hours_opt: Some(240),
+            },
+            warning_days: 3,
+        };
+
+        FinancialsCommand::handle_broadcast(body, &mut stdout, &term_interface);
+
+        assert_eq!(
+            stdout.get_string(),
+            "\nConsuming wallet 0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440 has less than 3 days of \
+             spending left. Runway: MASQ 1.5 days, gas 10.0 days\n\n"
+        )
+    }
+
     #[test]
     fn are_both_sets_to_be_displayed_works_for_top_records() {
         //top records always print as a pair so it always consists of both sets
@@ -1020,6 +1217,7 @@ mod tests {
                 total_paid_payable_gwei: 235555554578,
                 total_unpaid_receivable_gwei: 0,
                 total_paid_receivable_gwei: 665557,
+                runway_opt: None,
             }),
             query_results_opt: Some(if for_top_records {
                 QueryResults {
@@ -1028,7 +1226,7 @@ mod tests {
                             wallet: "0xA884A2F1A5Ec6C2e499644666a5E6af97B966888".to_string(),
                             age_s: 5645405400,
                             balance_gwei: 68843325667,
-                            pending_payable_hash_opt: None,
+                            pending_payable_hash_opt: None, balance_wei_opt: None,
                         },
                         UiPayableAccount {
                             wallet: "0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440".to_string(),
@@ -1037,7 +1235,7 @@ mod tests {
                             pending_payable_hash_opt: Some(
                                 "0x0290db1d56121112f4d45c1c3f36348644f6afd20b759b762f1dba9c4949066e"
                                     .to_string(),
-                            ),
+                            ), balance_wei_opt: None,
                         },
                     ]),
                     receivable_opt: Some(vec![
@@ -1045,11 +1243,13 @@ mod tests {
                             wallet: "0x6e250504DdfFDb986C4F0bb8Df162503B4118b05".to_string(),
                             age_s: 22000,
                             balance_gwei: 2444533124512,
+                            balance_wei_opt: None,
                         },
                         UiReceivableAccount {
                             wallet: "0x8bA50675e590b545D2128905b89039256Eaa24F6".to_string(),
                             age_s: 19000,
                             balance_gwei: -328123256546,
+                            balance_wei_opt: None,
                         },
                     ]),
                 }
@@ -1063,6 +1263,7 @@ mod tests {
                             "0x0290db1d56121112f4d45c1c3f36348644f6afd20b759b762f1dba9c4949066e"
                                 .to_string(),
                         ),
+                        balance_wei_opt: None,
                     }]),
                     receivable_opt: None,
                 }
@@ -1095,7 +1296,8 @@ mod tests {
                         count: 123,
                         ordered_by: TopRecordsOrdering::Balance
                     }),
-                    custom_queries_opt: None
+                    custom_queries_opt: None,
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -1172,7 +1374,8 @@ mod tests {
                             min_amount_gwei: 3000000,
                             max_amount_gwei: 5600070000
                         })
-                    })
+                    }),
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -1231,7 +1434,8 @@ mod tests {
                         count: 123,
                         ordered_by: TopRecordsOrdering::Balance
                     }),
-                    custom_queries_opt: None
+                    custom_queries_opt: None,
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -1310,7 +1514,8 @@ mod tests {
                             min_amount_gwei: 4000,
                             max_amount_gwei: 455000000
                         })
-                    })
+                    }),
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -1356,11 +1561,13 @@ mod tests {
                         "0x0290db1d56121112f4d45c1c3f36348644f6afd20b759b762f1dba9c4949066e"
                             .to_string(),
                     ),
+                    balance_wei_opt: None,
                 }]),
                 receivable_opt: Some(vec![UiReceivableAccount {
                     wallet: "0x8bA50675e590b545D2128905b89039256Eaa24F6".to_string(),
                     age_s: 45700,
                     balance_gwei: 5050330000,
+                    balance_wei_opt: None,
                 }]),
             }),
         };
@@ -1401,7 +1608,8 @@ mod tests {
                             min_amount_gwei: 800000000,
                             max_amount_gwei: i64::MAX
                         })
-                    })
+                    }),
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -1433,6 +1641,7 @@ mod tests {
                 total_paid_payable_gwei: 55555,
                 total_unpaid_receivable_gwei: 221144,
                 total_paid_receivable_gwei: 66555,
+                runway_opt: None,
             }),
             query_results_opt: Some(QueryResults {
                 payable_opt: Some(vec![]),
@@ -1460,7 +1669,8 @@ mod tests {
                         count: 10,
                         ordered_by: TopRecordsOrdering::Balance
                     }),
-                    custom_queries_opt: None
+                    custom_queries_opt: None,
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -1510,6 +1720,7 @@ mod tests {
                 total_paid_payable_gwei: 55555,
                 total_unpaid_receivable_gwei: 221144,
                 total_paid_receivable_gwei: 66555,
+                runway_opt: None,
             }),
             query_results_opt: Some(QueryResults {
                 payable_opt: None,
@@ -1553,7 +1764,8 @@ mod tests {
                             min_amount_gwei: 111000000000,
                             max_amount_gwei: 10000000000000
                         })
-                    })
+                    }),
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -1595,10 +1807,11 @@ mod tests {
     #[test]
     fn financials_command_only_top_records_demanded() {
         let transact_params_arc = Arc::new(Mutex::new(vec![]));
-        let expected_response = UiFinancialsResponse {
-            stats_opt: None,
-            query_results_opt: Some(QueryResults {
-                payable_opt: Some(vec![
+        let expected_response =
+            UiFinancialsResponse {
+                stats_opt: None,
+                query_results_opt: Some(QueryResults {
+                    payable_opt: Some(vec![
                     UiPayableAccount {
                         wallet: "0xA884A2F1A5Ec6C2e499644666a5E6af97B966888".to_string(),
                         age_s: 5405400,
@@ -1606,29 +1819,31 @@ mod tests {
                         pending_payable_hash_opt: Some(
                             "0x3648c8b8c7e067ac30b80b6936159326d564dd13b7ae465b26647154ada2c638"
                                 .to_string(),
-                        ),
+                        ), balance_wei_opt: None,
                     },
                     UiPayableAccount {
                         wallet: "0xEA674fdac714fd979de3EdF0F56AA9716B198ec8".to_string(),
                         age_s: 28120444,
                         balance_gwei: 97524120,
-                        pending_payable_hash_opt: None,
-                    },
-                ]),
-                receivable_opt: Some(vec![
-                    UiReceivableAccount {
-                        wallet: "0xaa22968a5263f165F014d3F21A443f10a116EDe0".to_string(),
-                        age_s: 566668,
-                        balance_gwei: 550,
-                    },
-                    UiReceivableAccount {
-                        wallet: "0x6e250504DdfFDb986C4F0bb8Df162503B4118b05".to_string(),
-                        age_s: 11111111,
-                        balance_gwei: -4551012,
+                        pending_payable_hash_opt: None, balance_wei_opt: None,
                     },
                 ]),
-            }),
-        };
+                    receivable_opt: Some(vec![
+                        UiReceivableAccount {
+                            wallet: "0xaa22968a5263f165F014d3F21A443f10a116EDe0".to_string(),
+                            age_s: 566668,
+                            balance_gwei: 550,
+                            balance_wei_opt: None,
+                        },
+                        UiReceivableAccount {
+                            wallet: "0x6e250504DdfFDb986C4F0bb8Df162503B4118b05".to_string(),
+                            age_s: 11111111,
+                            balance_gwei: -4551012,
+                            balance_wei_opt: None,
+                        },
+                    ]),
+                }),
+            };
         let args = slice_of_strs_to_vec_of_strings(&["financials", "--no-stats", "--top", "7"]);
         let mut context = CommandContextMock::new()
             .transact_params(&transact_params_arc)
@@ -1650,7 +1865,8 @@ mod tests {
                         count: 7,
                         ordered_by: TopRecordsOrdering::Balance
                     }),
-                    custom_queries_opt: None
+                    custom_queries_opt: None,
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -1681,10 +1897,11 @@ mod tests {
     #[test]
     fn financials_command_only_payable_demanded() {
         let transact_params_arc = Arc::new(Mutex::new(vec![]));
-        let expected_response = UiFinancialsResponse {
-            stats_opt: None,
-            query_results_opt: Some(QueryResults {
-                payable_opt: Some(vec![
+        let expected_response =
+            UiFinancialsResponse {
+                stats_opt: None,
+                query_results_opt: Some(QueryResults {
+                    payable_opt: Some(vec![
                     UiPayableAccount {
                         wallet: "0x6e250504DdfFDb986C4F0bb8Df162503B4118b05".to_string(),
                         age_s: 4445,
@@ -1692,24 +1909,24 @@ mod tests {
                         pending_payable_hash_opt: Some(
                             "0x5fe272ed1e941cc05fbd624ec4b1546cd03c25d53e24ba2c18b11feb83cd4581"
                                 .to_string(),
-                        ),
+                        ), balance_wei_opt: None,
                     },
                     UiPayableAccount {
                         wallet: "0xA884A2F1A5Ec6C2e499644666a5E6af97B966888".to_string(),
                         age_s: 70000,
                         balance_gwei: 708090,
-                        pending_payable_hash_opt: None,
+                        pending_payable_hash_opt: None, balance_wei_opt: None,
                     },
                     UiPayableAccount {
                         wallet: "0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440".to_string(),
                         age_s: 6089909,
                         balance_gwei: 66658,
-                        pending_payable_hash_opt: None,
+                        pending_payable_hash_opt: None, balance_wei_opt: None,
                     },
                 ]),
-                receivable_opt: None,
-            }),
-        };
+                    receivable_opt: None,
+                }),
+            };
         let args = slice_of_strs_to_vec_of_strings(&[
             "financials",
             "--payable",
@@ -1741,7 +1958,8 @@ mod tests {
                             max_amount_gwei: 1000000000000
                         }),
                         receivable_opt: None
-                    })
+                    }),
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -1772,16 +1990,19 @@ mod tests {
                         wallet: "0x6e250504DdfFDb986C4F0bb8Df162503B4118b05".to_string(),
                         age_s: 4445,
                         balance_gwei: 9898999888,
+                        balance_wei_opt: None,
                     },
                     UiReceivableAccount {
                         wallet: "0xA884A2F1A5Ec6C2e499644666a5E6af97B966888".to_string(),
                         age_s: 70000,
                         balance_gwei: 708090,
+                        balance_wei_opt: None,
                     },
                     UiReceivableAccount {
                         wallet: "0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440".to_string(),
                         age_s: 6089909,
                         balance_gwei: 66658,
+                        balance_wei_opt: None,
                     },
                 ]),
             }),
@@ -1818,7 +2039,8 @@ mod tests {
                             min_amount_gwei: 66000000000,
                             max_amount_gwei: 980000000000
                         })
-                    })
+                    }),
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -1858,7 +2080,8 @@ mod tests {
                 UiFinancialsRequest {
                     stats_required: true,
                     top_records_opt: None,
-                    custom_queries_opt: None
+                    custom_queries_opt: None,
+                    page_opt: None,
                 }
                 .tmb(0),
                 STANDARD_COMMAND_TIMEOUT_MILLIS
@@ -1870,4 +2093,256 @@ mod tests {
             "Financials retrieval failed: ConnectionProblem(\"Booga\")\n"
         );
     }
+
+    fn page_request(offset: u32) -> MessageBody {
+        UiFinancialsRequest {
+            stats_required: false,
+            top_records_opt: None,
+            custom_queries_opt: None,
+            page_opt: Some(FinancialsPage {
+                offset,
+                count: EXPORT_PAGE_SIZE,
+            }),
+        }
+        .tmb(0)
+    }
+
+    fn page_response(
+        payable: Vec<UiPayableAccount>,
+        receivable: Vec<UiReceivableAccount>,
+    ) -> MessageBody {
+        UiFinancialsResponse {
+            stats_opt: None,
+            query_results_opt: Some(QueryResults {
+                payable_opt: Some(payable),
+                receivable_opt: Some(receivable),
+            }),
+        }
+        .tmb(0)
+    }
+
+    fn make_ui_payable_account(n: usize) -> UiPayableAccount {
+        UiPayableAccount {
+            wallet: format!("0x{:040x}", n),
+            age_s: 1000 + n as u64,
+            balance_gwei: 5_000_000 + n as u64,
+            pending_payable_hash_opt: None,
+            balance_wei_opt: Some(format!("{}000000123", 5_000_000 + n as u64)),
+        }
+    }
+
+    #[test]
+    fn financials_command_parses_export_args() {
+        let args = slice_of_strs_to_vec_of_strings(&[
+            "financials",
+            "--export",
+            "json",
+            "--output",
+            "books/export.json",
+        ]);
+
+        let result = FinancialsCommand::new(&args).unwrap();
+
+        assert_eq!(
+            result,
+            FinancialsCommand {
+                stats_required: true,
+                top_records_opt: None,
+                custom_queries_opt: None,
+                gwei_precision: false,
+                export_opt: Some(ExportInput {
+                    format: ExportFormat::Json,
+                    output_path: PathBuf::from("books/export.json"),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn financials_command_refuses_export_without_output_or_with_other_queries() {
+        let make =
+            |args: &[&str]| match FinancialsCommand::new(&slice_of_strs_to_vec_of_strings(args)) {
+                Err(msg) => msg,
+                Ok(command) => panic!("we expected an error but got: {:?}", command),
+            };
+
+        let without_output = make(&["financials", "--export", "csv"]);
+        let with_top = make(&[
+            "financials",
+            "--export",
+            "csv",
+            "--output",
+            "export.csv",
+            "--top",
+            "10",
+        ]);
+        let unknown_format = make(&["financials", "--export", "xml", "--output", "export.xml"]);
+
+        assert!(
+            without_output.contains("The following required arguments were not provided"),
+            "{}",
+            without_output
+        );
+        assert!(
+            without_output.contains("--output <FILE>"),
+            "{}",
+            without_output
+        );
+        assert!(
+            with_top.contains("cannot be used with '--export <FORMAT>'"),
+            "{}",
+            with_top
+        );
+        assert!(
+            unknown_format.contains("isn't a valid value for"),
+            "{}",
+            unknown_format
+        );
+    }
+
+    #[test]
+    fn financials_command_exports_all_pages_into_csv_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "financials_command",
+            "financials_command_exports_all_pages_into_csv_file",
+        );
+        let output_path = home_dir.join("export.csv");
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let full_page = (0..EXPORT_PAGE_SIZE as usize)
+            .map(make_ui_payable_account)
+            .collect::<Vec<_>>();
+        let receivable = UiReceivableAccount {
+            wallet: "0x6e250504DdfFDb986C4F0bb8Df162503B4118b05".to_string(),
+            age_s: 22_000,
+            balance_gwei: -2_000_000,
+            balance_wei_opt: None,
+        };
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(page_response(full_page, vec![receivable])))
+            .transact_result(Ok(page_response(
+                vec![UiPayableAccount {
+                    pending_payable_hash_opt: Some("0x0290db1d".to_string()),
+                    ..make_ui_payable_account(EXPORT_PAGE_SIZE as usize)
+                }],
+                vec![],
+            )));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = FinancialsCommand::new(&slice_of_strs_to_vec_of_strings(&[
+            "financials",
+            "--export",
+            "csv",
+            "--output",
+            output_path.to_str().unwrap(),
+        ]))
+        .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![
+                (page_request(0), STANDARD_COMMAND_TIMEOUT_MILLIS),
+                (
+                    page_request(EXPORT_PAGE_SIZE as u32),
+                    STANDARD_COMMAND_TIMEOUT_MILLIS
+                )
+            ]
+        );
+        let exported = std::fs::read_to_string(&output_path).unwrap();
+        let lines = exported.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1 + EXPORT_PAGE_SIZE as usize + 2);
+        assert_eq!(
+            lines[0],
+            "table,wallet,age_s,balance_wei,pending_payable_hash"
+        );
+        assert_eq!(
+            lines[1],
+            "payable,0x0000000000000000000000000000000000000000,1000,5000000000000123,"
+        );
+        assert_eq!(
+            lines[EXPORT_PAGE_SIZE as usize + 1],
+            "payable,0x00000000000000000000000000000000000001f4,1500,5000500000000123,0x0290db1d"
+        );
+        assert_eq!(
+            lines[EXPORT_PAGE_SIZE as usize + 2],
+            "receivable,0x6e250504DdfFDb986C4F0bb8Df162503B4118b05,22000,-2000000000000000,"
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            format!(
+                "Exported 501 payable and 1 receivable records into {}\n",
+                output_path.display()
+            )
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn financials_command_export_reports_file_that_cannot_be_written() {
+        let home_dir = ensure_node_home_directory_exists(
+            "financials_command",
+            "financials_command_export_reports_file_that_cannot_be_written",
+        );
+        let output_path = home_dir.join("nonexistent_directory").join("export.json");
+        let mut context =
+            CommandContextMock::new().transact_result(Ok(page_response(vec![], vec![])));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = FinancialsCommand::new(&slice_of_strs_to_vec_of_strings(&[
+            "financials",
+            "--export",
+            "json",
+            "--output",
+            output_path.to_str().unwrap(),
+        ]))
+        .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        let msg = match result {
+            Err(CommandError::Other(msg)) => msg,
+            x => panic!("we expected CommandError::Other but got: {:?}", x),
+        };
+        assert!(
+            msg.starts_with(&format!(
+                "Writing the export into {} failed: ",
+                output_path.display()
+            )),
+            "{}",
+            msg
+        );
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            format!("{}\n", msg)
+        );
+    }
+
+    #[test]
+    fn financials_command_export_sad_path() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ConnectionDropped("Booga".to_string())));
+        let stderr_arc = context.stderr_arc();
+        let subject = FinancialsCommand::new(&slice_of_strs_to_vec_of_strings(&[
+            "financials",
+            "--export",
+            "csv",
+            "--output",
+            "never_written.csv",
+        ]))
+        .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Err(ConnectionProblem("Booga".to_string())));
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "Financials retrieval failed: ConnectionProblem(\"Booga\")\n"
+        );
+        assert!(!std::path::Path::new("never_written.csv").exists())
+    }
 }
@@ -55,6 +55,14 @@ pub(in crate::commands::financials_command) mod restricted {
         );
     }
 
+    // The Node reports in hours how long the consuming wallet lasts; none means indefinitely
+    pub fn render_runway(runway_hours_opt: Option<u64>) -> String {
+        match runway_hours_opt {
+            Some(hours) => format!("{:.1} days", hours as f64 / 24.0),
+            None => "unlimited".to_string(),
+        }
+    }
+
     pub fn main_title_for_tops_opt(fin_com: &FinancialsCommand, stdout: &mut dyn Write) {
         if let Some(tr_config) = fin_com.top_records_opt.as_ref() {
             short_writeln!(stdout, "Up to {} top accounts\n", tr_config.count)
@@ -250,7 +258,7 @@ pub(in crate::commands::financials_command) mod restricted {
 #[cfg(test)]
 mod tests {
     use crate::commands::financials_command::pretty_print_utils::restricted::{
-        figure_out_max_widths, StringValuesFormattableAccount,
+        figure_out_max_widths, render_runway, StringValuesFormattableAccount,
     };
 
     #[derive(Clone)]
@@ -271,6 +279,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn render_runway_works() {
+        let results = [Some(0), Some(84), Some(1_000), None]
+            .into_iter()
+            .map(render_runway)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            results,
+            vec![
+                "0.0 days".to_string(),
+                "3.5 days".to_string(),
+                "41.7 days".to_string(),
+                "unlimited".to_string()
+            ]
+        )
+    }
+
     #[test]
     fn figure_out_max_widths_works() {
         let mut vec_of_accounts = vec![
@@ -1,18 +1,24 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+pub mod ban_command;
+pub mod ban_list_command;
 pub mod change_password_command;
 pub mod check_password_command;
 pub mod commands_common;
 pub mod configuration_command;
 pub mod connection_status_command;
 pub mod crash_command;
+pub mod crash_history_command;
+pub mod crash_report_command;
 pub mod descriptor_command;
 pub mod financials_command;
 pub mod generate_wallets_command;
+pub mod reconciliation_command;
 pub mod recover_wallets_command;
 pub mod scan_command;
 pub mod set_configuration_command;
 pub mod setup_command;
 pub mod shutdown_command;
+pub mod simulate_thresholds_command;
 pub mod start_command;
 pub mod wallet_addresses_command;
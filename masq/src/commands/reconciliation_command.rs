@@ -0,0 +1,198 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use clap::{App, SubCommand};
+use masq_lib::messages::{
+    UiReconciliationAccount, UiReconciliationRequest, UiReconciliationResponse,
+};
+use masq_lib::{as_any_ref_in_trait_impl, short_writeln};
+use std::fmt::Debug;
+use time::macros::format_description;
+use time::OffsetDateTime;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ReconciliationCommand {}
+
+const RECONCILIATION_SUBCOMMAND_ABOUT: &str =
+    "Compares what the Node has billed each consuming wallet with what that wallet has acknowledged \
+     in signed service receipts. Only valid if Node is already running.";
+
+pub fn reconciliation_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("reconciliation").about(RECONCILIATION_SUBCOMMAND_ABOUT)
+}
+
+impl Command for ReconciliationCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let response: UiReconciliationResponse = transaction(
+            UiReconciliationRequest {},
+            context,
+            STANDARD_COMMAND_TIMEOUT_MILLIS,
+        )?;
+        if response.accounts.is_empty() {
+            short_writeln!(context.stdout(), "No service receipts have been received.");
+            return Ok(());
+        }
+        short_writeln!(
+            context.stdout(),
+            "{:<42}   {:<19}   {:>8}   {:>26}   {:>26}   {:>26}",
+            "Wallet",
+            "Since (UTC)",
+            "Receipts",
+            "Billed (wei)",
+            "Acknowledged (wei)",
+            "Discrepancy (wei)"
+        );
+        response
+            .accounts
+            .iter()
+            .for_each(|account| short_writeln!(context.stdout(), "{}", Self::render_row(account)));
+        Ok(())
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+impl Default for ReconciliationCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReconciliationCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn render_row(account: &UiReconciliationAccount) -> String {
+        let since = OffsetDateTime::from_unix_timestamp(account.since_s as i64)
+            .ok()
+            .and_then(|date_time| {
+                date_time
+                    .format(format_description!(
+                        "[year]-[month]-[day] [hour]:[minute]:[second]"
+                    ))
+                    .ok()
+            })
+            .unwrap_or_else(|| "unknown".to_string());
+        format!(
+            "{:<42}   {:<19}   {:>8}   {:>26}   {:>26}   {:>26}",
+            account.wallet,
+            since,
+            account.receipt_count,
+            account.billed_wei,
+            account.acknowledged_wei,
+            account.discrepancy_wei
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError::ConnectionDropped;
+    use crate::command_factory::{CommandFactory, CommandFactoryReal};
+    use crate::commands::commands_common::CommandError::ConnectionProblem;
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::ToMessageBody;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            RECONCILIATION_SUBCOMMAND_ABOUT,
+            "Compares what the Node has billed each consuming wallet with what that wallet has \
+             acknowledged in signed service receipts. Only valid if Node is already running."
+        );
+    }
+
+    #[test]
+    fn testing_command_factory_here() {
+        let factory = CommandFactoryReal::new();
+        let mut context = CommandContextMock::new()
+            .transact_result(Ok(UiReconciliationResponse { accounts: vec![] }.tmb(0)));
+        let subject = factory.make(&["reconciliation".to_string()]).unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn reconciliation_command_displays_every_account() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiReconciliationResponse {
+                accounts: vec![
+                    UiReconciliationAccount {
+                        wallet: "0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440".to_string(),
+                        since_s: 1_700_000_000,
+                        receipt_count: 3,
+                        billed_wei: 123_456_789_012_345_678_901,
+                        acknowledged_wei: 123_456_789_012_345_678_000,
+                        discrepancy_wei: 901,
+                    },
+                    UiReconciliationAccount {
+                        wallet: "0xA884A2F1A5Ec6C2e499644666a5E6af97B966888".to_string(),
+                        since_s: 1_700_086_400,
+                        receipt_count: 1,
+                        billed_wei: 1_000,
+                        acknowledged_wei: 1_500,
+                        discrepancy_wei: -500,
+                    },
+                ],
+            }
+            .tmb(11)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = ReconciliationCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            *transact_params_arc.lock().unwrap(),
+            vec![(
+                UiReconciliationRequest {}.tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Wallet                                       Since (UTC)           Receipts                 Billed (wei)           Acknowledged (wei)            Discrepancy (wei)\n\
+             0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440   2023-11-14 22:13:20          3        123456789012345678901        123456789012345678000                          901\n\
+             0xA884A2F1A5Ec6C2e499644666a5E6af97B966888   2023-11-15 22:13:20          1                         1000                         1500                         -500\n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn reconciliation_command_tells_when_there_are_no_receipts() {
+        let mut context = CommandContextMock::new()
+            .transact_result(Ok(UiReconciliationResponse { accounts: vec![] }.tmb(11)));
+        let stdout_arc = context.stdout_arc();
+        let subject = ReconciliationCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "No service receipts have been received.\n"
+        );
+    }
+
+    #[test]
+    fn reconciliation_command_handles_send_failure() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ConnectionDropped("blah".to_string())));
+        let subject = ReconciliationCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Err(ConnectionProblem("blah".to_string())));
+    }
+}
@@ -5,6 +5,7 @@ use masq_lib::as_any_ref_in_trait_impl;
 use masq_lib::messages::{UiSetConfigurationRequest, UiSetConfigurationResponse};
 use masq_lib::shared_schema::gas_price_arg;
 use masq_lib::shared_schema::min_hops_arg;
+use masq_lib::shared_schema::{blockchain_service_quorum_arg, blockchain_service_url_arg};
 use masq_lib::short_writeln;
 use masq_lib::utils::ExpectValue;
 
@@ -68,6 +69,8 @@ pub fn set_configurationify<'a>(shared_schema_arg: Arg<'a, 'a>) -> Arg<'a, 'a> {
 pub fn set_configuration_subcommand() -> App<'static, 'static> {
     SubCommand::with_name("set-configuration")
         .about(SET_CONFIGURATION_ABOUT)
+        .arg(set_configurationify(blockchain_service_quorum_arg()))
+        .arg(set_configurationify(blockchain_service_url_arg()))
         .arg(set_configurationify(gas_price_arg()))
         .arg(set_configurationify(min_hops_arg()))
         .arg(
@@ -81,7 +84,13 @@ pub fn set_configuration_subcommand() -> App<'static, 'static> {
         )
         .group(
             ArgGroup::with_name("parameter")
-                .args(&["gas-price", "min-hops", "start-block"])
+                .args(&[
+                    "blockchain-service-quorum",
+                    "blockchain-service-url",
+                    "gas-price",
+                    "min-hops",
+                    "start-block",
+                ])
                 .required(true),
         )
 }
@@ -133,6 +142,11 @@ mod tests {
         test_command_execution("--start-block", "123456");
         test_command_execution("--gas-price", "123456");
         test_command_execution("--min-hops", "6");
+        test_command_execution(
+            "--blockchain-service-url",
+            "https://infura.io/ID,http://localhost:8545",
+        );
+        test_command_execution("--blockchain-service-quorum", "2");
     }
 
     #[test]
@@ -140,6 +154,8 @@ mod tests {
         set_configuration_command_throws_err_for_missing_value("--start-block");
         set_configuration_command_throws_err_for_missing_value("--gas-price");
         set_configuration_command_throws_err_for_missing_value("--min-hops");
+        set_configuration_command_throws_err_for_missing_value("--blockchain-service-url");
+        set_configuration_command_throws_err_for_missing_value("--blockchain-service-quorum");
     }
 
     #[test]
@@ -10,7 +10,7 @@ use masq_lib::messages::{
     UiSetupBroadcast, UiSetupInner, UiSetupRequest, UiSetupRequestValue, UiSetupResponse,
     UiSetupResponseValue, UiSetupResponseValueStatus,
 };
-use masq_lib::shared_schema::{data_directory_arg, shared_app};
+use masq_lib::shared_schema::{data_directory_arg, ui_shared_app};
 use masq_lib::short_writeln;
 use masq_lib::utils::{index_of_from, DATA_DIRECTORY_DAEMON_HELP};
 use std::fmt::Debug;
@@ -23,7 +23,7 @@ const SETUP_COMMAND_ABOUT: &str =
     "Establishes (if Node is not already running) and displays startup parameters for MASQNode.";
 
 pub fn setup_subcommand() -> App<'static, 'static> {
-    shared_app(SubCommand::with_name("setup").about(SETUP_COMMAND_ABOUT))
+    ui_shared_app(SubCommand::with_name("setup").about(SETUP_COMMAND_ABOUT))
         .arg(data_directory_arg(DATA_DIRECTORY_DAEMON_HELP.as_str()))
 }
 
@@ -0,0 +1,460 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use masq_lib::messages::{
+    UiSimulateThresholdsRequest, UiSimulateThresholdsResponse, UiSimulatedAccount,
+    UiThresholdEventType,
+};
+use masq_lib::{as_any_ref_in_trait_impl, short_writeln};
+use std::fmt::Debug;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SimulateThresholdsCommand {
+    payment_thresholds: String,
+    window_s: u64,
+    step_s: u64,
+    payables_opt: Option<Vec<UiSimulatedAccount>>,
+    receivables_opt: Option<Vec<UiSimulatedAccount>>,
+}
+
+const SIMULATE_THRESHOLDS_SUBCOMMAND_ABOUT: &str =
+    "Shows which payables the Node would pay and which debtors it would ban or unban if the given \
+     payment thresholds applied, while the accounts age through a window of time. Uses the Node's \
+     own accounts unless synthetic ones are given. Only valid if Node is already running.";
+const THRESHOLDS_ARG_HELP: &str =
+    "Payment thresholds to try, in the same format as --payment-thresholds of the Node: \
+     DebtThresholdGwei|MaturityThresholdSec|PaymentGracePeriodSec|PermanentDebtAllowedGwei|\
+     ThresholdIntervalSec|UnbanBelowGwei.";
+const WINDOW_ARG_HELP: &str = "Length of the simulated window of time, in seconds.";
+const STEP_ARG_HELP: &str =
+    "How often, in seconds, the simulation looks at the accounts, as a scan of the Node would.";
+const PAYABLE_ARG_HELP: &str =
+    "Synthetic payable as Wallet|BalanceGwei|AgeSec. May be repeated. Instead of the Node's own \
+     accounts, only synthetic ones are simulated once any are given.";
+const RECEIVABLE_ARG_HELP: &str =
+    "Synthetic receivable as Wallet|BalanceGwei|AgeSec, with |banned appended if the debtor is \
+     already banned. May be repeated.";
+const DEFAULT_WINDOW_S: &str = "86400";
+const DEFAULT_STEP_S: &str = "600";
+
+pub fn simulate_thresholds_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("simulate-thresholds")
+        .about(SIMULATE_THRESHOLDS_SUBCOMMAND_ABOUT)
+        .arg(
+            Arg::with_name("thresholds")
+                .help(THRESHOLDS_ARG_HELP)
+                .value_name("THRESHOLDS")
+                .index(1)
+                .required(true)
+                .case_insensitive(false),
+        )
+        .arg(
+            Arg::with_name("window")
+                .help(WINDOW_ARG_HELP)
+                .long("window")
+                .value_name("SECONDS")
+                .default_value(DEFAULT_WINDOW_S)
+                .validator(validate_seconds),
+        )
+        .arg(
+            Arg::with_name("step")
+                .help(STEP_ARG_HELP)
+                .long("step")
+                .value_name("SECONDS")
+                .default_value(DEFAULT_STEP_S)
+                .validator(validate_seconds),
+        )
+        .arg(
+            Arg::with_name("payable")
+                .help(PAYABLE_ARG_HELP)
+                .long("payable")
+                .value_name("ACCOUNT")
+                .multiple(true)
+                .number_of_values(1)
+                .validator(|spec| parse_account(&spec, false).map(|_| ())),
+        )
+        .arg(
+            Arg::with_name("receivable")
+                .help(RECEIVABLE_ARG_HELP)
+                .long("receivable")
+                .value_name("ACCOUNT")
+                .multiple(true)
+                .number_of_values(1)
+                .validator(|spec| parse_account(&spec, true).map(|_| ())),
+        )
+}
+
+impl Command for SimulateThresholdsCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let input = UiSimulateThresholdsRequest {
+            payment_thresholds: self.payment_thresholds.clone(),
+            window_s: self.window_s,
+            step_s: self.step_s,
+            payables_opt: self.payables_opt.clone(),
+            receivables_opt: self.receivables_opt.clone(),
+        };
+        let response: UiSimulateThresholdsResponse =
+            transaction(input, context, STANDARD_COMMAND_TIMEOUT_MILLIS)?;
+        if response.events.is_empty() {
+            short_writeln!(
+                context.stdout(),
+                "Nothing would be paid, banned or unbanned within {} seconds.",
+                self.window_s
+            );
+            return Ok(());
+        }
+        short_writeln!(
+            context.stdout(),
+            "{:>10}   {:<8}   {:<42}   {:>20}   {:>10}",
+            "After (s)",
+            "Event",
+            "Wallet",
+            "Balance (gwei)",
+            "Age (s)"
+        );
+        response.events.iter().for_each(|event| {
+            short_writeln!(
+                context.stdout(),
+                "{:>10}   {:<8}   {:<42}   {:>20}   {:>10}",
+                event.offset_s,
+                match event.event {
+                    UiThresholdEventType::Paid => "paid",
+                    UiThresholdEventType::Banned => "banned",
+                    UiThresholdEventType::Unbanned => "unbanned",
+                },
+                event.wallet,
+                event.balance_gwei,
+                event.age_s
+            )
+        });
+        Ok(())
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+impl SimulateThresholdsCommand {
+    pub fn new(pieces: &[String]) -> Result<Self, String> {
+        let matches = match simulate_thresholds_subcommand().get_matches_from_safe(pieces) {
+            Ok(matches) => matches,
+            Err(e) => return Err(format!("{}", e)),
+        };
+        let seconds = |name: &str| {
+            matches
+                .value_of(name)
+                .expect("default value is missing")
+                .parse::<u64>()
+                .expect("validator failed")
+        };
+        let (payables_opt, receivables_opt) = match (
+            accounts(&matches, "payable"),
+            accounts(&matches, "receivable"),
+        ) {
+            (None, None) => (None, None),
+            (payables_opt, receivables_opt) => (
+                Some(payables_opt.unwrap_or_default()),
+                Some(receivables_opt.unwrap_or_default()),
+            ),
+        };
+        Ok(Self {
+            payment_thresholds: matches
+                .value_of("thresholds")
+                .expect("thresholds are not properly required")
+                .to_string(),
+            window_s: seconds("window"),
+            step_s: seconds("step"),
+            payables_opt,
+            receivables_opt,
+        })
+    }
+}
+
+fn accounts(matches: &ArgMatches, name: &str) -> Option<Vec<UiSimulatedAccount>> {
+    matches.values_of(name).map(|specs| {
+        specs
+            .map(|spec| parse_account(spec, name == "receivable").expect("validator failed"))
+            .collect()
+    })
+}
+
+fn parse_account(spec: &str, may_be_banned: bool) -> Result<UiSimulatedAccount, String> {
+    let pieces = spec.split('|').collect::<Vec<&str>>();
+    let banned = match (pieces.len(), pieces.get(3)) {
+        (3, None) => false,
+        (4, Some(&"banned")) if may_be_banned => true,
+        _ => {
+            return Err(format!(
+                "Account '{}' must look like Wallet|BalanceGwei|AgeSec{}",
+                spec,
+                if may_be_banned { "[|banned]" } else { "" }
+            ))
+        }
+    };
+    let balance_gwei = pieces[1]
+        .parse::<i64>()
+        .map_err(|_| format!("Balance '{}' is not a whole number of gwei", pieces[1]))?;
+    let age_s = pieces[2]
+        .parse::<u64>()
+        .map_err(|_| format!("Age '{}' is not a whole number of seconds", pieces[2]))?;
+    Ok(UiSimulatedAccount {
+        wallet: pieces[0].to_string(),
+        balance_gwei,
+        age_s,
+        banned,
+    })
+}
+
+fn validate_seconds(seconds: String) -> Result<(), String> {
+    match seconds.parse::<u64>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("'{}' is not a whole number of seconds", seconds)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError::ConnectionDropped;
+    use crate::command_factory::{CommandFactory, CommandFactoryError, CommandFactoryReal};
+    use crate::commands::commands_common::CommandError::ConnectionProblem;
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::{ToMessageBody, UiThresholdEvent};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            SIMULATE_THRESHOLDS_SUBCOMMAND_ABOUT,
+            "Shows which payables the Node would pay and which debtors it would ban or unban if \
+             the given payment thresholds applied, while the accounts age through a window of \
+             time. Uses the Node's own accounts unless synthetic ones are given. Only valid if \
+             Node is already running."
+        );
+        assert_eq!(DEFAULT_WINDOW_S, "86400");
+        assert_eq!(DEFAULT_STEP_S, "600");
+    }
+
+    #[test]
+    fn testing_command_factory_here() {
+        let factory = CommandFactoryReal::new();
+        let mut context = CommandContextMock::new()
+            .transact_result(Ok(UiSimulateThresholdsResponse { events: vec![] }.tmb(0)));
+        let subject = factory
+            .make(&[
+                "simulate-thresholds".to_string(),
+                "1000|1200|1200|500|21600|500".to_string(),
+            ])
+            .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn command_without_accounts_asks_node_to_use_its_books() {
+        let result = SimulateThresholdsCommand::new(&[
+            "simulate-thresholds".to_string(),
+            "1000|1200|1200|500|21600|500".to_string(),
+        ]);
+
+        assert_eq!(
+            result,
+            Ok(SimulateThresholdsCommand {
+                payment_thresholds: "1000|1200|1200|500|21600|500".to_string(),
+                window_s: 86_400,
+                step_s: 600,
+                payables_opt: None,
+                receivables_opt: None,
+            })
+        )
+    }
+
+    #[test]
+    fn command_with_any_synthetic_account_sends_both_lists() {
+        let result = SimulateThresholdsCommand::new(&[
+            "simulate-thresholds".to_string(),
+            "1000|1200|1200|500|21600|500".to_string(),
+            "--window".to_string(),
+            "3600".to_string(),
+            "--step".to_string(),
+            "60".to_string(),
+            "--receivable".to_string(),
+            "0xabc|2000|7200|banned".to_string(),
+            "--receivable".to_string(),
+            "0xdef|-5|0".to_string(),
+        ]);
+
+        assert_eq!(
+            result,
+            Ok(SimulateThresholdsCommand {
+                payment_thresholds: "1000|1200|1200|500|21600|500".to_string(),
+                window_s: 3_600,
+                step_s: 60,
+                payables_opt: Some(vec![]),
+                receivables_opt: Some(vec![
+                    UiSimulatedAccount {
+                        wallet: "0xabc".to_string(),
+                        balance_gwei: 2000,
+                        age_s: 7200,
+                        banned: true,
+                    },
+                    UiSimulatedAccount {
+                        wallet: "0xdef".to_string(),
+                        balance_gwei: -5,
+                        age_s: 0,
+                        banned: false,
+                    }
+                ]),
+            })
+        )
+    }
+
+    #[test]
+    fn command_rejects_malformed_accounts() {
+        let factory = CommandFactoryReal::new();
+        let make = |option: &str, account: &str| {
+            factory.make(&[
+                "simulate-thresholds".to_string(),
+                "1000|1200|1200|500|21600|500".to_string(),
+                option.to_string(),
+                account.to_string(),
+            ])
+        };
+        let syntax_error = |result: Result<Box<dyn Command>, CommandFactoryError>| match result {
+            Err(CommandFactoryError::CommandSyntax(msg)) => msg,
+            x => panic!("Expected syntax error, got {:?}", x.map(|_| ())),
+        };
+
+        let banned_payable = syntax_error(make("--payable", "0xabc|2000|7200|banned"));
+        let short_receivable = syntax_error(make("--receivable", "0xabc|2000"));
+        let bad_balance = syntax_error(make("--payable", "0xabc|lots|7200"));
+        let bad_age = syntax_error(make("--receivable", "0xabc|2000|-1"));
+
+        assert!(
+            banned_payable.contains(
+                "Account '0xabc|2000|7200|banned' must look like Wallet|BalanceGwei|AgeSec"
+            ),
+            "{}",
+            banned_payable
+        );
+        assert!(
+            short_receivable
+                .contains("Account '0xabc|2000' must look like Wallet|BalanceGwei|AgeSec[|banned]"),
+            "{}",
+            short_receivable
+        );
+        assert!(
+            bad_balance.contains("Balance 'lots' is not a whole number of gwei"),
+            "{}",
+            bad_balance
+        );
+        assert!(
+            bad_age.contains("Age '-1' is not a whole number of seconds"),
+            "{}",
+            bad_age
+        );
+    }
+
+    #[test]
+    fn simulate_thresholds_command_displays_every_event() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiSimulateThresholdsResponse {
+                events: vec![
+                    UiThresholdEvent {
+                        offset_s: 0,
+                        wallet: "0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440".to_string(),
+                        event: UiThresholdEventType::Unbanned,
+                        balance_gwei: 150,
+                        age_s: 1_000_000,
+                    },
+                    UiThresholdEvent {
+                        offset_s: 1_200,
+                        wallet: "0xA884A2F1A5Ec6C2e499644666a5E6af97B966888".to_string(),
+                        event: UiThresholdEventType::Paid,
+                        balance_gwei: 1_234_567,
+                        age_s: 5_000,
+                    },
+                ],
+            }
+            .tmb(11)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = SimulateThresholdsCommand::new(&[
+            "simulate-thresholds".to_string(),
+            "1000|1200|1200|500|21600|500".to_string(),
+        ])
+        .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiSimulateThresholdsRequest {
+                    payment_thresholds: "1000|1200|1200|500|21600|500".to_string(),
+                    window_s: 86_400,
+                    step_s: 600,
+                    payables_opt: None,
+                    receivables_opt: None,
+                }
+                .tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            concat!(
+                " After (s)   Event      Wallet                                             Balance (gwei)      Age (s)\n",
+                "         0   unbanned   0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440                    150      1000000\n",
+                "      1200   paid       0xA884A2F1A5Ec6C2e499644666a5E6af97B966888                1234567         5000\n",
+            )
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn simulate_thresholds_command_says_when_nothing_would_happen() {
+        let mut context = CommandContextMock::new()
+            .transact_result(Ok(UiSimulateThresholdsResponse { events: vec![] }.tmb(11)));
+        let stdout_arc = context.stdout_arc();
+        let subject = SimulateThresholdsCommand::new(&[
+            "simulate-thresholds".to_string(),
+            "1000|1200|1200|500|21600|500".to_string(),
+            "--window".to_string(),
+            "3600".to_string(),
+        ])
+        .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Nothing would be paid, banned or unbanned within 3600 seconds.\n"
+        );
+    }
+
+    #[test]
+    fn simulate_thresholds_command_reports_transaction_failure() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ConnectionDropped("Booga".to_string())));
+        let subject = SimulateThresholdsCommand::new(&[
+            "simulate-thresholds".to_string(),
+            "1000|1200|1200|500|21600|500".to_string(),
+        ])
+        .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Err(ConnectionProblem("Booga".to_string())));
+    }
+}
@@ -1,13 +1,19 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use crate::commands::ban_command::BanCommand;
 use crate::commands::change_password_command::ChangePasswordCommand;
+use crate::commands::financials_command::FinancialsCommand;
 use crate::commands::setup_command::SetupCommand;
 use crate::notifications::crashed_notification::CrashNotifier;
+use crate::notifications::payments_adjusted_notification::PaymentsAdjustedNotification;
+use crate::notifications::restarted_notification::RestartNotifier;
 use crate::terminal::terminal_interface::TerminalWrapper;
 use crossbeam_channel::{unbounded, RecvError, Sender};
 use masq_lib::messages::{
-    FromMessageBody, UiConnectionChangeBroadcast, UiLogBroadcast, UiNewPasswordBroadcast,
-    UiNodeCrashedBroadcast, UiSetupBroadcast, UiUndeliveredFireAndForget,
+    FromMessageBody, UiBanChangedBroadcast, UiConnectionChangeBroadcast, UiLogBroadcast,
+    UiNewPasswordBroadcast, UiNodeCrashedBroadcast, UiNodeRestartedBroadcast,
+    UiPaymentsAdjustedBroadcast, UiSetupBroadcast, UiUndeliveredFireAndForget,
+    UiWalletBalanceWarningBroadcast,
 };
 use masq_lib::ui_gateway::MessageBody;
 use masq_lib::utils::ExpectValue;
@@ -95,6 +101,8 @@ impl BroadcastHandlerReal {
                     SetupCommand::handle_broadcast(body, stdout, terminal_interface);
                 } else if let Ok((body, _)) = UiNodeCrashedBroadcast::fmb(message_body.clone()) {
                     CrashNotifier::handle_broadcast(body, stdout, terminal_interface);
+                } else if let Ok((body, _)) = UiNodeRestartedBroadcast::fmb(message_body.clone()) {
+                    RestartNotifier::handle_broadcast(body, stdout, terminal_interface);
                 } else if let Ok((body, _)) = UiNewPasswordBroadcast::fmb(message_body.clone()) {
                     ChangePasswordCommand::handle_broadcast(body, stdout, terminal_interface);
                 } else if let Ok((body, _)) = UiUndeliveredFireAndForget::fmb(message_body.clone())
@@ -111,6 +119,19 @@ impl BroadcastHandlerReal {
                         stdout,
                         terminal_interface,
                     );
+                } else if let Ok((body, _)) = UiBanChangedBroadcast::fmb(message_body.clone()) {
+                    BanCommand::handle_broadcast(body, stdout, terminal_interface);
+                } else if let Ok((body, _)) =
+                    UiWalletBalanceWarningBroadcast::fmb(message_body.clone())
+                {
+                    FinancialsCommand::handle_broadcast(body, stdout, terminal_interface);
+                } else if let Ok((body, _)) = UiPaymentsAdjustedBroadcast::fmb(message_body.clone())
+                {
+                    PaymentsAdjustedNotification::handle_broadcast(
+                        body,
+                        stdout,
+                        terminal_interface,
+                    );
                 } else {
                     handle_unrecognized_broadcast(message_body, stderr, terminal_interface)
                 }
@@ -194,7 +215,8 @@ mod tests {
     use masq_lib::messages::UiSetupResponseValueStatus::{Configured, Default};
     use masq_lib::messages::{
         CrashReason, SerializableLogLevel, ToMessageBody, UiConnectionChangeBroadcast,
-        UiConnectionStage, UiLogBroadcast, UiNodeCrashedBroadcast,
+        UiConnectionStage, UiLogBroadcast, UiNodeCrashedBroadcast, UiNodeRestartedBroadcast,
+        UiUnpaidPayment, UiWalletRunway,
     };
     use masq_lib::messages::{UiSetupBroadcast, UiSetupResponseValue, UiSetupResponseValueStatus};
     use masq_lib::ui_gateway::MessagePath;
@@ -294,6 +316,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn broadcast_of_restarted_triggers_correct_handler() {
+        let (factory, handle) = TestStreamFactory::new();
+        let subject = BroadcastHandlerReal::new(Some(TerminalWrapper::new(Arc::new(
+            TerminalPassiveMock::new(),
+        ))))
+        .start(Box::new(factory));
+        let message = UiNodeRestartedBroadcast {
+            crashed_process_id: 1234,
+            new_process_id: 2345,
+            redirect_ui_port: 5333,
+            restart_count: 1,
+        }
+        .tmb(0);
+
+        subject.send(message);
+
+        let stdout = handle.stdout_so_far();
+        assert_eq!(
+            stdout,
+            "\nThe Daemon restarted the Node that crashed as process 1234; it is now running as \
+            process 2345 with its UI on port 5333.\nThat was restart 1 within the crash-loop window.\n\n"
+                .to_string()
+        );
+        assert_eq!(
+            handle.stderr_so_far(),
+            "".to_string(),
+            "stderr: '{}'",
+            stdout
+        );
+    }
+
     #[test]
     fn broadcast_of_new_password_triggers_correct_handler() {
         let (factory, handle) = TestStreamFactory::new();
@@ -318,6 +372,107 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ban_changed_broadcast_triggers_correct_handler() {
+        let (factory, handle) = TestStreamFactory::new();
+        let subject = BroadcastHandlerReal::new(Some(TerminalWrapper::new(Arc::new(
+            TerminalPassiveMock::new(),
+        ))))
+        .start(Box::new(factory));
+        let message = UiBanChangedBroadcast {
+            wallet: "0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440".to_string(),
+            banned: false,
+            reason_opt: None,
+        }
+        .tmb(0);
+
+        subject.send(message);
+
+        let stdout = handle.stdout_so_far();
+        assert_eq!(
+            stdout,
+            "\nWallet 0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440 has been unbanned\n\n".to_string()
+        );
+        assert_eq!(
+            handle.stderr_so_far(),
+            "".to_string(),
+            "stderr: '{}'",
+            stdout
+        );
+    }
+
+    #[test]
+    fn wallet_balance_warning_broadcast_triggers_correct_handler() {
+        let (factory, handle) = TestStreamFactory::new();
+        let subject = BroadcastHandlerReal::new(Some(TerminalWrapper::new(Arc::new(
+            TerminalPassiveMock::new(),
+        ))))
+        .start(Box::new(factory));
+        let message = UiWalletBalanceWarningBroadcast {
+            consuming_wallet: "0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440".to_string(),
+            service_fee_balance_wei: 1_000_000_000_000_000_000,
+            transaction_fee_balance_wei: 0,
+            runway: UiWalletRunway {
+                service_fee_runway_hours_opt: None,
+                transaction_fee_runway_hours_opt: Some(0),
+            },
+            warning_days: 3,
+        }
+        .tmb(0);
+
+        subject.send(message);
+
+        let stdout = handle.stdout_so_far();
+        assert_eq!(
+            stdout,
+            "\nConsuming wallet 0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440 has less than 3 days of \
+             spending left. Runway: MASQ unlimited, gas 0.0 days\n\n"
+                .to_string()
+        );
+        assert_eq!(
+            handle.stderr_so_far(),
+            "".to_string(),
+            "stderr: '{}'",
+            stdout
+        );
+    }
+
+    #[test]
+    fn payments_adjusted_broadcast_triggers_correct_handler() {
+        let (factory, handle) = TestStreamFactory::new();
+        let subject = BroadcastHandlerReal::new(Some(TerminalWrapper::new(Arc::new(
+            TerminalPassiveMock::new(),
+        ))))
+        .start(Box::new(factory));
+        let message = UiPaymentsAdjustedBroadcast {
+            paid_in_full_count: 1,
+            reduced: vec![],
+            unpaid: vec![UiUnpaidPayment {
+                wallet: "0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440".to_string(),
+                balance_wei: 5_000,
+                reason: "transaction fee balance too low".to_string(),
+            }],
+        }
+        .tmb(0);
+
+        subject.send(message);
+
+        let stdout = handle.stdout_so_far();
+        assert_eq!(
+            stdout,
+            "\nThe consuming wallet couldn't cover all the payables; 1 paid in full.\n\
+             5,000 wei owed to 0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440 left unpaid: \
+             transaction fee balance too low\n\n"
+                .to_string()
+        );
+        assert_eq!(
+            handle.stderr_so_far(),
+            "".to_string(),
+            "stderr: '{}'",
+            stdout
+        );
+    }
+
     #[test]
     fn broadcast_of_undelivered_ff_message_triggers_correct_handler() {
         let (factory, handle) = TestStreamFactory::new();
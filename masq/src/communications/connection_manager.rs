@@ -9,6 +9,7 @@ use crossbeam_channel::{unbounded, RecvTimeoutError};
 use crossbeam_channel::{Receiver, RecvError, Sender};
 use masq_lib::messages::{CrashReason, FromMessageBody, ToMessageBody, UiNodeCrashedBroadcast};
 use masq_lib::messages::{UiRedirect, NODE_UI_PROTOCOL};
+use masq_lib::ui_auth::ui_token_headers;
 use masq_lib::ui_gateway::{MessageBody, MessagePath};
 use masq_lib::ui_traffic_converter::UiTrafficConverter;
 use masq_lib::utils::localhost;
@@ -84,12 +85,18 @@ impl ConnectionManager {
     pub fn connect(
         &mut self,
         port: u16,
+        ui_token_opt: Option<String>,
         generic_broadcast_handle: Box<dyn BroadcastHandle>,
         timeout_millis: u64,
     ) -> Result<(), ClientListenerError> {
         let (demand_tx, demand_rx) = unbounded();
         let (listener_to_manager_tx, listener_to_manager_rx) = unbounded();
-        let talker_half = make_client_listener(port, listener_to_manager_tx, timeout_millis)?;
+        let talker_half = make_client_listener(
+            port,
+            ui_token_opt.as_deref(),
+            listener_to_manager_tx,
+            timeout_millis,
+        )?;
         let (conversation_return_tx, conversation_return_rx) = unbounded();
         let (redirect_order_tx, redirect_order_rx) = unbounded();
         let (redirect_response_tx, redirect_response_rx) = unbounded();
@@ -104,6 +111,7 @@ impl ConnectionManager {
             active_port: Some(port),
             daemon_port: port,
             node_port: None,
+            ui_token_opt,
             conversations: HashMap::new(),
             conversations_waiting: HashSet::new(),
             next_context_id: 1,
@@ -155,12 +163,17 @@ impl ConnectionManager {
 
 fn make_client_listener(
     port: u16,
+    ui_token_opt: Option<&str>,
     listener_to_manager_tx: Sender<Result<MessageBody, ClientListenerError>>,
     timeout_millis: u64,
 ) -> Result<Writer<TcpStream>, ClientListenerError> {
     let url = format!("ws://{}:{}", localhost(), port);
     let builder = ClientBuilder::new(url.as_str()).expect("Bad URL");
-    let result = builder.add_protocol(NODE_UI_PROTOCOL);
+    let builder = builder.add_protocol(NODE_UI_PROTOCOL);
+    let result = match ui_token_opt {
+        Some(ui_token) => builder.custom_headers(&ui_token_headers(ui_token)),
+        None => builder,
+    };
     let result = match connect_insecure_timeout(result, timeout_millis) {
         Err(RecvTimeoutError::Disconnected) => return Err(ClientListenerError::Closed),
         Err(RecvTimeoutError::Timeout) => return Err(ClientListenerError::Timeout),
@@ -193,6 +206,7 @@ struct CmsInner {
     active_port: Option<u16>,
     daemon_port: u16,
     node_port: Option<u16>,
+    ui_token_opt: Option<String>,
     conversations: HashMap<u64, Sender<Result<MessageBody, NodeConversationTermination>>>,
     conversations_waiting: HashSet<u64>,
     next_context_id: u64,
@@ -378,6 +392,7 @@ impl ConnectionManagerThread {
         let (listener_to_manager_tx, listener_to_manager_rx) = unbounded();
         let talker_half = match make_client_listener(
             redirect_order.port,
+            inner.ui_token_opt.as_deref(),
             listener_to_manager_tx,
             redirect_order.timeout_millis,
         ) {
@@ -451,6 +466,7 @@ impl ConnectionManagerThread {
         inner.listener_to_manager_rx = listener_to_manager_rx;
         match make_client_listener(
             inner.active_port.expect("Active port disappeared!"),
+            inner.ui_token_opt.as_deref(),
             listener_to_manager_tx,
             FALLBACK_TIMEOUT_MILLIS,
         ) {
@@ -569,6 +585,8 @@ mod tests {
     use masq_lib::test_utils::utils::is_running_under_github_actions;
     use masq_lib::utils::{find_free_port, running_test};
     use std::hash::Hash;
+    use std::io::Read;
+    use std::net::{SocketAddr, TcpListener};
     use std::sync::{Arc, Mutex};
     use std::thread;
     use std::time::Duration;
@@ -611,11 +629,52 @@ mod tests {
         thread::sleep(Duration::from_millis(500)); // let the server get started
         let mut subject = ConnectionManager::new();
         subject
-            .connect(port, Box::new(BroadcastHandleMock::new()), 1000)
+            .connect(port, None, Box::new(BroadcastHandleMock::new()), 1000)
             .unwrap();
         (subject, stop_handle)
     }
 
+    fn capture_upgrade_request(ui_token_opt: Option<&str>) -> String {
+        let listener = TcpListener::bind(SocketAddr::new(localhost(), 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (request_tx, request_rx) = unbounded();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![];
+            let mut buf = [0u8; 1024];
+            while !String::from_utf8_lossy(&request).contains("\r\n\r\n") {
+                let len = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..len]);
+            }
+            request_tx
+                .send(String::from_utf8_lossy(&request).to_string())
+                .unwrap();
+        });
+
+        // The fake server never completes the handshake; only the request it received matters
+        let _ = make_client_listener(port, ui_token_opt, unbounded().0, 200);
+
+        request_rx.recv_timeout(Duration::from_secs(5)).unwrap()
+    }
+
+    #[test]
+    fn make_client_listener_presents_the_ui_token_when_there_is_one() {
+        let request = capture_upgrade_request(Some("booga"));
+
+        assert!(
+            request.contains("X-MASQ-UI-Token: booga\r\n"),
+            "{}",
+            request
+        );
+    }
+
+    #[test]
+    fn make_client_listener_presents_no_ui_token_when_there_is_none() {
+        let request = capture_upgrade_request(None);
+
+        assert!(!request.contains("X-MASQ-UI-Token"), "{}", request);
+    }
+
     #[test]
     fn handle_demand_brings_the_party_to_a_close_if_the_channel_fails() {
         let inner = make_inner();
@@ -1176,6 +1235,7 @@ mod tests {
                     total_paid_payable_gwei: 22,
                     total_unpaid_receivable_gwei: 29,
                     total_paid_receivable_gwei: 32,
+                    runway_opt: None,
                 }),
                 query_results_opt: None,
             }
@@ -1195,13 +1255,14 @@ mod tests {
             stats_required: true,
             top_records_opt: None,
             custom_queries_opt: None,
+            page_opt: None,
         }
         .tmb(1);
         let send_params_arc = Arc::new(Mutex::new(vec![]));
         let broadcast_handler = BroadcastHandleMock::new().send_params(&send_params_arc);
         let mut subject = ConnectionManager::new();
         subject
-            .connect(daemon_port, Box::new(broadcast_handler), 1000)
+            .connect(daemon_port, None, Box::new(broadcast_handler), 1000)
             .unwrap();
         let conversation = subject.start_conversation();
 
@@ -1218,6 +1279,7 @@ mod tests {
                     total_paid_payable_gwei: 22,
                     total_unpaid_receivable_gwei: 29,
                     total_paid_receivable_gwei: 32,
+                    runway_opt: None,
                 }),
                 query_results_opt: None
             }
@@ -1454,7 +1516,7 @@ mod tests {
         let mut subject = ConnectionManager::new();
         thread::sleep(Duration::from_millis(500)); // let the server get started
         subject
-            .connect(port, Box::new(BroadcastHandleMock::new()), 1000)
+            .connect(port, None, Box::new(BroadcastHandleMock::new()), 1000)
             .unwrap();
         let conversation1 = subject.start_conversation();
         let conversation2 = subject.start_conversation();
@@ -1481,6 +1543,7 @@ mod tests {
             active_port: Some(0),
             daemon_port: 0,
             node_port: None,
+            ui_token_opt: None,
             conversations: HashMap::new(),
             conversations_waiting: HashSet::new(),
             next_context_id: 0,
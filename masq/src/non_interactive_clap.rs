@@ -2,6 +2,8 @@
 
 use crate::schema::app;
 use clap::{value_t, ArgMatches};
+use masq_lib::ui_auth::{default_ui_token_file_opt, UiTokens};
+use std::path::{Path, PathBuf};
 
 #[allow(clippy::upper_case_acronyms)]
 pub trait NIClapFactory {
@@ -19,16 +21,42 @@ impl NIClapFactory for NIClapFactoryReal {
 }
 
 pub trait NonInteractiveClap {
-    fn non_interactive_initial_clap_operations(&self, args: &[String]) -> u16;
+    fn non_interactive_initial_clap_operations(&self, args: &[String]) -> (u16, Option<String>);
 }
 
 pub struct NonInteractiveClapReal;
 
 //partly tested by integration tests
 impl NonInteractiveClap for NonInteractiveClapReal {
-    fn non_interactive_initial_clap_operations(&self, args: &[String]) -> u16 {
+    fn non_interactive_initial_clap_operations(&self, args: &[String]) -> (u16, Option<String>) {
         let matches = handle_help_or_version_if_required(args);
-        value_t!(matches, "ui-port", u16).expect("ui-port is not properly defaulted")
+        let ui_port = value_t!(matches, "ui-port", u16).expect("ui-port is not properly defaulted");
+        let ui_token_opt = ui_token_opt(
+            matches.value_of("ui-token"),
+            matches.value_of("ui-token-file"),
+            default_ui_token_file_opt(),
+        );
+        (ui_port, ui_token_opt)
+    }
+}
+
+// Without a token on the command line, masq presents the admin token from the file the Daemon and
+// the Node create by default, if it's there to be read
+fn ui_token_opt(
+    ui_token_opt: Option<&str>,
+    ui_token_file_opt: Option<&str>,
+    default_ui_token_file_opt: Option<PathBuf>,
+) -> Option<String> {
+    match (ui_token_opt, ui_token_file_opt) {
+        (Some(ui_token), _) => Some(ui_token.to_string()),
+        (None, Some(ui_token_file)) => Some(
+            UiTokens::read_from(Path::new(ui_token_file))
+                .expect("ui-token-file was validated")
+                .admin,
+        ),
+        (None, None) => default_ui_token_file_opt
+            .and_then(|ui_token_file| UiTokens::read_from(&ui_token_file).ok())
+            .map(|tokens| tokens.admin),
     }
 }
 
@@ -40,6 +68,7 @@ fn handle_help_or_version_if_required<'a>(args: &[String]) -> ArgMatches<'a> {
 mod tests {
     use super::*;
     use masq_lib::constants::DEFAULT_UI_PORT;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
     use masq_lib::utils::to_string;
 
     #[test]
@@ -51,7 +80,7 @@ mod tests {
                 .collect::<Vec<String>>(),
         );
 
-        assert_eq!(result, DEFAULT_UI_PORT)
+        assert_eq!(result.0, DEFAULT_UI_PORT)
     }
 
     #[test]
@@ -63,6 +92,82 @@ mod tests {
                 .collect::<Vec<String>>(),
         );
 
-        assert_eq!(result, 10000)
+        assert_eq!(result.0, 10000)
+    }
+
+    #[test]
+    fn non_interactive_clap_real_accepts_a_ui_token() {
+        let result = NonInteractiveClapReal.non_interactive_initial_clap_operations(
+            &vec!["masq", "--ui-token", "booga", "descriptor"]
+                .iter()
+                .map(to_string)
+                .collect::<Vec<String>>(),
+        );
+
+        assert_eq!(result, (DEFAULT_UI_PORT, Some("booga".to_string())))
+    }
+
+    #[test]
+    fn non_interactive_clap_real_takes_the_admin_token_from_a_ui_token_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "non_interactive_clap",
+            "non_interactive_clap_real_takes_the_admin_token_from_a_ui_token_file",
+        );
+        let path = home_dir.join("ui_tokens");
+        std::fs::write(&path, "admin=booga\nread-only=wooga\n").unwrap();
+
+        let result = NonInteractiveClapReal.non_interactive_initial_clap_operations(
+            &vec![
+                "masq",
+                "--ui-token-file",
+                path.to_str().unwrap(),
+                "shutdown",
+            ]
+            .iter()
+            .map(to_string)
+            .collect::<Vec<String>>(),
+        );
+
+        assert_eq!(result, (DEFAULT_UI_PORT, Some("booga".to_string())))
+    }
+
+    #[test]
+    fn ui_token_opt_takes_the_admin_token_from_the_default_ui_token_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "non_interactive_clap",
+            "ui_token_opt_takes_the_admin_token_from_the_default_ui_token_file",
+        );
+        let path = home_dir.join("ui_tokens");
+        std::fs::write(&path, "admin=booga\nread-only=wooga\n").unwrap();
+
+        let result = ui_token_opt(None, None, Some(path));
+
+        assert_eq!(result, Some("booga".to_string()))
+    }
+
+    #[test]
+    fn ui_token_opt_prefers_the_command_line_to_the_default_ui_token_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "non_interactive_clap",
+            "ui_token_opt_prefers_the_command_line_to_the_default_ui_token_file",
+        );
+        let path = home_dir.join("ui_tokens");
+        std::fs::write(&path, "admin=booga\nread-only=wooga\n").unwrap();
+
+        let result = ui_token_opt(Some("agoob"), None, Some(path));
+
+        assert_eq!(result, Some("agoob".to_string()))
+    }
+
+    #[test]
+    fn ui_token_opt_does_without_a_token_if_there_is_no_default_ui_token_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "non_interactive_clap",
+            "ui_token_opt_does_without_a_token_if_there_is_no_default_ui_token_file",
+        );
+
+        let result = ui_token_opt(None, None, Some(home_dir.join("ui_tokens")));
+
+        assert_eq!(result, None)
     }
 }
@@ -81,7 +81,7 @@ impl Main {
 
 impl Command<u8> for Main {
     fn go(&mut self, streams: &mut StdStreams<'_>, args: &[String]) -> u8 {
-        let ui_port = self
+        let (ui_port, ui_token_opt) = self
             .non_interactive_clap_factory
             .make()
             .non_interactive_initial_clap_operations(args);
@@ -100,6 +100,7 @@ impl Command<u8> for Main {
             terminal_interface,
             generic_broadcast_handle,
             ui_port,
+            ui_token_opt,
         ) {
             Ok(processor) => processor,
             Err(error) => {
@@ -234,8 +235,10 @@ mod tests {
             ]
         );
         let mut p_make_params = p_make_params_arc.lock().unwrap();
-        let (terminal_interface, broadcast_handle, ui_port) = p_make_params.pop().unwrap();
+        let (terminal_interface, broadcast_handle, ui_port, ui_token_opt) =
+            p_make_params.pop().unwrap();
         assert_eq!(ui_port, 5333);
+        assert_eq!(ui_token_opt, None);
         assert!(terminal_interface.is_none());
         assert!(broadcast_handle
             .as_any()
@@ -413,7 +416,7 @@ mod tests {
     }
 
     #[test]
-    fn noninteractive_mode_works_when_special_ui_port_is_required() {
+    fn noninteractive_mode_works_when_special_ui_port_and_ui_token_are_required() {
         let c_make_params_arc = Arc::new(Mutex::new(vec![]));
         let command_factory = CommandFactoryMock::new()
             .make_params(&c_make_params_arc)
@@ -439,6 +442,8 @@ mod tests {
                 "masq".to_string(),
                 "--ui-port".to_string(),
                 "10000".to_string(),
+                "--ui-token".to_string(),
+                "booga".to_string(),
                 "setup".to_string(),
             ],
         );
@@ -447,8 +452,10 @@ mod tests {
         let c_make_params = c_make_params_arc.lock().unwrap();
         assert_eq!(*c_make_params, vec![vec!["setup".to_string(),],]);
         let mut p_make_params = p_make_params_arc.lock().unwrap();
-        let (terminal_interface, broadcast_handle, ui_port) = p_make_params.pop().unwrap();
+        let (terminal_interface, broadcast_handle, ui_port, ui_token_opt) =
+            p_make_params.pop().unwrap();
         assert_eq!(ui_port, 10000);
+        assert_eq!(ui_token_opt, Some("booga".to_string()));
         assert!(terminal_interface.is_none());
         assert!(broadcast_handle
             .as_any()
@@ -45,6 +45,14 @@ impl CrashNotifier {
             CrashReason::NoInformation => panic!("Should never get here"),
             CrashReason::Unrecognized(msg) => msg,
             CrashReason::DaemonCrashed => panic!("Should never get here"),
+            CrashReason::NodePanic { message, location } => {
+                format!("the Node panicked at {}: {}", location, message)
+            }
+            CrashReason::ActorPanic {
+                actor,
+                message,
+                location,
+            } => format!("the {} actor panicked at {}: {}", actor, location, message),
         }
     }
 
@@ -101,6 +109,43 @@ mod tests {
         assert_eq!(stderr.get_string(), "".to_string());
     }
 
+    #[test]
+    pub fn handles_actor_panic() {
+        running_test();
+        let mut stdout = ByteArrayWriter::new();
+        let msg = UiNodeCrashedBroadcast {
+            process_id: 12345,
+            crash_reason: CrashReason::ActorPanic {
+                actor: "ACCOUNTANT".to_string(),
+                message: "Booga".to_string(),
+                location: "src/accountant/mod.rs:12:34".to_string(),
+            },
+        };
+        let term_interface = TerminalWrapper::new(Arc::new(TerminalPassiveMock::new()));
+
+        CrashNotifier::handle_broadcast(msg, &mut stdout, &term_interface);
+
+        assert_eq! (stdout.get_string(), "\nThe Node running as process 12345 terminated:\n------\nthe ACCOUNTANT actor panicked at src/accountant/mod.rs:12:34: Booga\n------\nThe Daemon is once more accepting setup changes.\n\n".to_string());
+    }
+
+    #[test]
+    pub fn handles_node_panic() {
+        running_test();
+        let mut stdout = ByteArrayWriter::new();
+        let msg = UiNodeCrashedBroadcast {
+            process_id: 12345,
+            crash_reason: CrashReason::NodePanic {
+                message: "Booga".to_string(),
+                location: "src/main.rs:1:2".to_string(),
+            },
+        };
+        let term_interface = TerminalWrapper::new(Arc::new(TerminalPassiveMock::new()));
+
+        CrashNotifier::handle_broadcast(msg, &mut stdout, &term_interface);
+
+        assert_eq! (stdout.get_string(), "\nThe Node running as process 12345 terminated:\n------\nthe Node panicked at src/main.rs:1:2: Booga\n------\nThe Daemon is once more accepting setup changes.\n\n".to_string());
+    }
+
     #[test]
     pub fn handles_no_information_failure() {
         running_test();
@@ -2,3 +2,5 @@
 
 pub mod connection_change_notification;
 pub mod crashed_notification;
+pub mod payments_adjusted_notification;
+pub mod restarted_notification;
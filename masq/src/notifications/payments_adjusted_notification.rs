@@ -0,0 +1,84 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::terminal::terminal_interface::TerminalWrapper;
+use masq_lib::messages::UiPaymentsAdjustedBroadcast;
+use masq_lib::short_writeln;
+use std::io::Write;
+use thousands::Separable;
+
+pub struct PaymentsAdjustedNotification {}
+
+impl PaymentsAdjustedNotification {
+    pub fn handle_broadcast(
+        response: UiPaymentsAdjustedBroadcast,
+        stdout: &mut dyn Write,
+        term_interface: &TerminalWrapper,
+    ) {
+        let _lock = term_interface.lock();
+        short_writeln!(
+            stdout,
+            "\nThe consuming wallet couldn't cover all the payables; {} paid in full.",
+            response.paid_in_full_count
+        );
+        response.reduced.iter().for_each(|reduced| {
+            short_writeln!(
+                stdout,
+                "{} wei owed to {} reduced to {} wei",
+                reduced.original_balance_wei.separate_with_commas(),
+                reduced.wallet,
+                reduced.adjusted_balance_wei.separate_with_commas()
+            )
+        });
+        response.unpaid.iter().for_each(|unpaid| {
+            short_writeln!(
+                stdout,
+                "{} wei owed to {} left unpaid: {}",
+                unpaid.balance_wei.separate_with_commas(),
+                unpaid.wallet,
+                unpaid.reason
+            )
+        });
+        short_writeln!(stdout, "");
+        stdout.flush().expect("flush failed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::mocks::TerminalPassiveMock;
+    use masq_lib::messages::{UiReducedPayment, UiUnpaidPayment};
+    use masq_lib::test_utils::fake_stream_holder::ByteArrayWriter;
+    use std::sync::Arc;
+
+    #[test]
+    pub fn handles_payments_adjusted() {
+        let mut stdout = ByteArrayWriter::new();
+        let msg = UiPaymentsAdjustedBroadcast {
+            paid_in_full_count: 2,
+            reduced: vec![UiReducedPayment {
+                wallet: "0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440".to_string(),
+                original_balance_wei: 3_000_000_000,
+                adjusted_balance_wei: 1_500_000_000,
+            }],
+            unpaid: vec![UiUnpaidPayment {
+                wallet: "0x0000000000000000000000000000000000000002".to_string(),
+                balance_wei: 1_000_000,
+                reason: "MASQ balance too low".to_string(),
+            }],
+        };
+        let term_interface = TerminalWrapper::new(Arc::new(TerminalPassiveMock::new()));
+
+        PaymentsAdjustedNotification::handle_broadcast(msg, &mut stdout, &term_interface);
+
+        assert_eq!(
+            stdout.get_string(),
+            "\nThe consuming wallet couldn't cover all the payables; 2 paid in full.\n\
+            3,000,000,000 wei owed to 0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440 reduced to \
+            1,500,000,000 wei\n\
+            1,000,000 wei owed to 0x0000000000000000000000000000000000000002 left unpaid: \
+            MASQ balance too low\n\n"
+                .to_string()
+        );
+    }
+}
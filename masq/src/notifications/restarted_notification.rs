@@ -0,0 +1,57 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::terminal::terminal_interface::TerminalWrapper;
+use masq_lib::messages::UiNodeRestartedBroadcast;
+use masq_lib::short_writeln;
+use std::io::Write;
+
+pub struct RestartNotifier {}
+
+impl RestartNotifier {
+    pub fn handle_broadcast(
+        response: UiNodeRestartedBroadcast,
+        stdout: &mut dyn Write,
+        term_interface: &TerminalWrapper,
+    ) {
+        let _lock = term_interface.lock();
+        short_writeln!(
+            stdout,
+            "\nThe Daemon restarted the Node that crashed as process {}; it is now running as process {} \
+            with its UI on port {}.\nThat was restart {} within the crash-loop window.\n",
+            response.crashed_process_id,
+            response.new_process_id,
+            response.redirect_ui_port,
+            response.restart_count
+        );
+        stdout.flush().expect("flush failed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::mocks::TerminalPassiveMock;
+    use masq_lib::test_utils::fake_stream_holder::ByteArrayWriter;
+    use std::sync::Arc;
+
+    #[test]
+    pub fn handles_restart() {
+        let mut stdout = ByteArrayWriter::new();
+        let msg = UiNodeRestartedBroadcast {
+            crashed_process_id: 12345,
+            new_process_id: 23456,
+            redirect_ui_port: 5333,
+            restart_count: 2,
+        };
+        let term_interface = TerminalWrapper::new(Arc::new(TerminalPassiveMock::new()));
+
+        RestartNotifier::handle_broadcast(msg, &mut stdout, &term_interface);
+
+        assert_eq!(
+            stdout.get_string(),
+            "\nThe Daemon restarted the Node that crashed as process 12345; it is now running as \
+            process 23456 with its UI on port 5333.\nThat was restart 2 within the crash-loop window.\n\n"
+                .to_string()
+        );
+    }
+}
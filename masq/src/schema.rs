@@ -1,5 +1,7 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use crate::commands::ban_command::{ban_subcommand, unban_subcommand};
+use crate::commands::ban_list_command::ban_list_subcommand;
 use crate::commands::change_password_command::{
     change_password_subcommand, set_password_subcommand,
 };
@@ -7,19 +9,25 @@ use crate::commands::check_password_command::check_password_subcommand;
 use crate::commands::configuration_command::configuration_subcommand;
 use crate::commands::connection_status_command::connection_status_subcommand;
 use crate::commands::crash_command::crash_subcommand;
+use crate::commands::crash_history_command::crash_history_subcommand;
+use crate::commands::crash_report_command::crash_report_subcommand;
 use crate::commands::descriptor_command::descriptor_subcommand;
 use crate::commands::financials_command::args_validation::financials_subcommand;
 use crate::commands::generate_wallets_command::generate_wallets_subcommand;
+use crate::commands::reconciliation_command::reconciliation_subcommand;
 use crate::commands::recover_wallets_command::recover_wallets_subcommand;
 use crate::commands::scan_command::scan_subcommand;
 use crate::commands::set_configuration_command::set_configuration_subcommand;
 use crate::commands::setup_command::setup_subcommand;
 use crate::commands::shutdown_command::shutdown_subcommand;
+use crate::commands::simulate_thresholds_command::simulate_thresholds_subcommand;
 use crate::commands::start_command::start_subcommand;
 use crate::commands::wallet_addresses_command::wallet_addresses_subcommand;
 use clap::{App, AppSettings, Arg};
 use lazy_static::lazy_static;
 use masq_lib::constants::{DEFAULT_UI_PORT, HIGHEST_USABLE_PORT, LOWEST_USABLE_INSECURE_PORT};
+use masq_lib::ui_auth::UiTokens;
+use std::path::Path;
 
 lazy_static! {
     static ref UI_PORT_HELP: String = format!(
@@ -35,6 +43,14 @@ const APP_VERSION: &str = "1.0.0";
 const APP_AUTHOR: &str = "MASQ";
 const APP_ABOUT: &str =
     "masq is a command-line user interface to the MASQ Daemon and the MASQ Node";
+const UI_TOKEN_HELP: &str =
+    "If the Daemon or Node requires UIs to authenticate, the token to present when connecting. \
+     An admin token permits every command; a read-only token permits only commands that don't \
+     change anything.";
+const UI_TOKEN_FILE_HELP: &str =
+    "If the Daemon or Node requires UIs to authenticate, the path to its UI token file. masq \
+     will present the admin token it contains. Can't be combined with --ui-token. Without either \
+     parameter, masq presents the admin token from the default UI token file, if there is one.";
 
 pub fn app_head() -> App<'static, 'static> {
     App::new(APP_NAME)
@@ -61,21 +77,44 @@ pub fn app() -> App<'static, 'static> {
                 .validator(validate_ui_port)
                 .help(UI_PORT_HELP.as_str()),
         )
+        .arg(
+            Arg::with_name("ui-token")
+                .long("ui-token")
+                .value_name("UI-TOKEN")
+                .takes_value(true)
+                .help(UI_TOKEN_HELP),
+        )
+        .arg(
+            Arg::with_name("ui-token-file")
+                .long("ui-token-file")
+                .value_name("UI-TOKEN-FILE")
+                .takes_value(true)
+                .conflicts_with("ui-token")
+                .validator(validate_ui_token_file)
+                .help(UI_TOKEN_FILE_HELP),
+        )
+        .subcommand(ban_subcommand())
+        .subcommand(ban_list_subcommand())
         .subcommand(change_password_subcommand())
         .subcommand(check_password_subcommand())
         .subcommand(crash_subcommand())
+        .subcommand(crash_history_subcommand())
+        .subcommand(crash_report_subcommand())
         .subcommand(configuration_subcommand())
         .subcommand(connection_status_subcommand())
         .subcommand(descriptor_subcommand())
         .subcommand(financials_subcommand())
         .subcommand(generate_wallets_subcommand())
+        .subcommand(reconciliation_subcommand())
         .subcommand(recover_wallets_subcommand())
         .subcommand(scan_subcommand())
         .subcommand(set_configuration_subcommand())
         .subcommand(set_password_subcommand())
         .subcommand(setup_subcommand())
         .subcommand(shutdown_subcommand())
+        .subcommand(simulate_thresholds_subcommand())
         .subcommand(start_subcommand())
+        .subcommand(unban_subcommand())
         .subcommand(wallet_addresses_subcommand())
 }
 
@@ -87,9 +126,14 @@ fn validate_ui_port(port: String) -> Result<(), String> {
     }
 }
 
+fn validate_ui_token_file(path: String) -> Result<(), String> {
+    UiTokens::read_from(Path::new(&path)).map(|_| ())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
 
     #[test]
     fn constants_have_correct_values() {
@@ -112,5 +156,48 @@ mod tests {
             DEFAULT_UI_PORT_STRING.to_string(),
             format!("{}", DEFAULT_UI_PORT)
         );
+        assert_eq!(
+            UI_TOKEN_HELP,
+            "If the Daemon or Node requires UIs to authenticate, the token to present when connecting. \
+             An admin token permits every command; a read-only token permits only commands that don't \
+             change anything."
+        );
+        assert_eq!(
+            UI_TOKEN_FILE_HELP,
+            "If the Daemon or Node requires UIs to authenticate, the path to its UI token file. masq \
+             will present the admin token it contains. Can't be combined with --ui-token. Without either \
+             parameter, masq presents the admin token from the default UI token file, if there is one."
+        );
+    }
+
+    #[test]
+    fn validate_ui_token_file_accepts_a_good_token_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "schema",
+            "validate_ui_token_file_accepts_a_good_token_file",
+        );
+        let path = home_dir.join("ui_tokens");
+        std::fs::write(&path, "admin=booga\nread-only=wooga\n").unwrap();
+
+        let result = validate_ui_token_file(path.to_string_lossy().to_string());
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn validate_ui_token_file_rejects_a_bad_token_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "schema",
+            "validate_ui_token_file_rejects_a_bad_token_file",
+        );
+        let path = home_dir.join("ui_tokens");
+        std::fs::write(&path, "admin=booga\n").unwrap();
+
+        let result = validate_ui_token_file(path.to_string_lossy().to_string());
+
+        assert_eq!(
+            result,
+            Err(format!("UI token file {:?} has no 'read-only' token", path))
+        );
     }
 }
@@ -220,9 +220,16 @@ impl CommandProcessorMock {
     }
 }
 
+type ProcessorFactoryMakeParams = (
+    Option<TerminalWrapper>,
+    Box<dyn BroadcastHandle>,
+    u16,
+    Option<String>,
+);
+
 #[derive(Default)]
 pub struct CommandProcessorFactoryMock {
-    make_params: Arc<Mutex<Vec<(Option<TerminalWrapper>, Box<dyn BroadcastHandle>, u16)>>>,
+    make_params: Arc<Mutex<Vec<ProcessorFactoryMakeParams>>>,
     make_results: RefCell<Vec<Result<Box<dyn CommandProcessor>, CommandError>>>,
 }
 
@@ -232,11 +239,13 @@ impl CommandProcessorFactory for CommandProcessorFactoryMock {
         terminal_interface: Option<TerminalWrapper>,
         generic_broadcast_handle: Box<dyn BroadcastHandle>,
         ui_port: u16,
+        ui_token_opt: Option<String>,
     ) -> Result<Box<dyn CommandProcessor>, CommandError> {
         self.make_params.lock().unwrap().push((
             terminal_interface,
             generic_broadcast_handle,
             ui_port,
+            ui_token_opt,
         ));
         self.make_results.borrow_mut().remove(0)
     }
@@ -247,10 +256,7 @@ impl CommandProcessorFactoryMock {
         Self::default()
     }
 
-    pub fn make_params(
-        mut self,
-        params: &Arc<Mutex<Vec<(Option<TerminalWrapper>, Box<dyn BroadcastHandle>, u16)>>>,
-    ) -> Self {
+    pub fn make_params(mut self, params: &Arc<Mutex<Vec<ProcessorFactoryMakeParams>>>) -> Self {
         self.make_params = params.clone();
         self
     }
@@ -272,8 +278,8 @@ impl NIClapFactory for NIClapFactoryMock {
 pub struct NonInteractiveClapMock;
 
 impl NonInteractiveClap for NonInteractiveClapMock {
-    fn non_interactive_initial_clap_operations(&self, _args: &[String]) -> u16 {
-        DEFAULT_UI_PORT
+    fn non_interactive_initial_clap_operations(&self, _args: &[String]) -> (u16, Option<String>) {
+        (DEFAULT_UI_PORT, None)
     }
 }
 
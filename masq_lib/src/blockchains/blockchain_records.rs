@@ -17,6 +17,7 @@ pub const CHAINS: [BlockchainRecord; 5] = [
         literal_identifier: POLYGON_MAINNET_FULL_IDENTIFIER,
         contract: POLYGON_MAINNET_CONTRACT_ADDRESS,
         contract_creation_block: POLYGON_MAINNET_CONTRACT_CREATION_BLOCK,
+        gas_limit_const_part: POLYGON_GAS_LIMIT_CONST_PART,
     },
     BlockchainRecord {
         self_id: Chain::EthMainnet,
@@ -24,6 +25,7 @@ pub const CHAINS: [BlockchainRecord; 5] = [
         literal_identifier: ETH_MAINNET_FULL_IDENTIFIER,
         contract: ETH_MAINNET_CONTRACT_ADDRESS,
         contract_creation_block: ETH_MAINNET_CONTRACT_CREATION_BLOCK,
+        gas_limit_const_part: ETH_GAS_LIMIT_CONST_PART,
     },
     BlockchainRecord {
         self_id: Chain::PolyAmoy,
@@ -31,6 +33,7 @@ pub const CHAINS: [BlockchainRecord; 5] = [
         literal_identifier: POLYGON_AMOY_FULL_IDENTIFIER,
         contract: AMOY_TESTNET_CONTRACT_ADDRESS,
         contract_creation_block: AMOY_TESTNET_CONTRACT_CREATION_BLOCK,
+        gas_limit_const_part: POLYGON_GAS_LIMIT_CONST_PART,
     },
     BlockchainRecord {
         self_id: Chain::EthRopsten,
@@ -38,6 +41,7 @@ pub const CHAINS: [BlockchainRecord; 5] = [
         literal_identifier: ETH_ROPSTEN_FULL_IDENTIFIER,
         contract: ROPSTEN_TESTNET_CONTRACT_ADDRESS,
         contract_creation_block: ROPSTEN_TESTNET_CONTRACT_CREATION_BLOCK,
+        gas_limit_const_part: ETH_GAS_LIMIT_CONST_PART,
     },
     BlockchainRecord {
         self_id: Chain::Dev,
@@ -45,6 +49,7 @@ pub const CHAINS: [BlockchainRecord; 5] = [
         literal_identifier: DEV_CHAIN_FULL_IDENTIFIER,
         contract: MULTINODE_TESTNET_CONTRACT_ADDRESS,
         contract_creation_block: MULTINODE_TESTNET_CONTRACT_CREATION_BLOCK,
+        gas_limit_const_part: ETH_GAS_LIMIT_CONST_PART,
     },
 ];
 
@@ -55,8 +60,12 @@ pub struct BlockchainRecord {
     pub literal_identifier: &'static str,
     pub contract: Address,
     pub contract_creation_block: u64,
+    pub gas_limit_const_part: u64,
 }
 
+const ETH_GAS_LIMIT_CONST_PART: u64 = 55_000;
+const POLYGON_GAS_LIMIT_CONST_PART: u64 = 70_000;
+
 const POLYGON_MAINNET_CONTRACT_ADDRESS: Address = H160([
     0xee, 0x9a, 0x35, 0x2f, 0x6a, 0xac, 0x4a, 0xf1, 0xa5, 0xb9, 0xf4, 0x67, 0xf6, 0xa9, 0x3e, 0x0f,
     0xfb, 0xe9, 0xdd, 0x35,
@@ -163,6 +172,7 @@ mod tests {
                 literal_identifier: "eth-mainnet",
                 contract: ETH_MAINNET_CONTRACT_ADDRESS,
                 contract_creation_block: ETH_MAINNET_CONTRACT_CREATION_BLOCK,
+                gas_limit_const_part: 55_000,
             }
         );
     }
@@ -179,6 +189,7 @@ mod tests {
                 literal_identifier: "eth-ropsten",
                 contract: ROPSTEN_TESTNET_CONTRACT_ADDRESS,
                 contract_creation_block: ROPSTEN_TESTNET_CONTRACT_CREATION_BLOCK,
+                gas_limit_const_part: 55_000,
             }
         );
     }
@@ -195,6 +206,7 @@ mod tests {
                 literal_identifier: "polygon-mainnet",
                 contract: POLYGON_MAINNET_CONTRACT_ADDRESS,
                 contract_creation_block: POLYGON_MAINNET_CONTRACT_CREATION_BLOCK,
+                gas_limit_const_part: 70_000,
             }
         );
     }
@@ -211,6 +223,7 @@ mod tests {
                 literal_identifier: "polygon-amoy",
                 contract: AMOY_TESTNET_CONTRACT_ADDRESS,
                 contract_creation_block: AMOY_TESTNET_CONTRACT_CREATION_BLOCK,
+                gas_limit_const_part: 70_000,
             }
         );
     }
@@ -227,6 +240,7 @@ mod tests {
                 literal_identifier: "dev",
                 contract: MULTINODE_TESTNET_CONTRACT_ADDRESS,
                 contract_creation_block: 0,
+                gas_limit_const_part: 55_000,
             }
         );
     }
@@ -0,0 +1,440 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::blockchains::blockchain_records::{BlockchainRecord, CHAINS};
+use crate::blockchains::chains::Chain;
+use ethereum_types::Address;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_derive::Deserialize;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+pub const CHAIN_REGISTRY_FILE_NAME: &str = "chains.json";
+// Even the simplest transaction costs this much gas, so a smaller constant part can't be right
+pub const MIN_GAS_LIMIT_CONST_PART: u64 = 21_000;
+
+lazy_static! {
+    // Records are leaked on registration so that they can be handed out as 'static like the built-in ones;
+    // registration happens once at startup, so nothing accumulates.
+    static ref CUSTOM_CHAINS: RwLock<Vec<&'static BlockchainRecord>> = RwLock::new(vec![]);
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ChainDefinition {
+    chain_id: u64,
+    identifier: String,
+    contract: String,
+    contract_creation_block: u64,
+    gas_limit_const_part: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChainRegistryError {
+    Unreadable(String),
+    Malformed(String),
+    InvalidDefinition { identifier: String, msg: String },
+}
+
+impl Display for ChainRegistryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unreadable(msg) => write!(f, "Chain definitions could not be read: {}", msg),
+            Self::Malformed(msg) => write!(f, "Chain definitions are malformed: {}", msg),
+            Self::InvalidDefinition { identifier, msg } => {
+                write!(
+                    f,
+                    "Definition of chain '{}' is invalid: {}",
+                    identifier, msg
+                )
+            }
+        }
+    }
+}
+
+// Reads chain definitions from the registry file in the given directory, if there is one, and makes
+// them available next to the built-in chains. Returns the number of definitions found.
+pub fn load_chain_registry(directory: &Path) -> Result<usize, ChainRegistryError> {
+    let path = directory.join(CHAIN_REGISTRY_FILE_NAME);
+    let json = match fs::read_to_string(&path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(0),
+        Err(e) => {
+            return Err(ChainRegistryError::Unreadable(format!(
+                "{}: {}",
+                path.display(),
+                e
+            )))
+        }
+    };
+    let records = parse_chain_definitions(&json)?;
+    let count = records.len();
+    register_custom_chains(records)?;
+    Ok(count)
+}
+
+pub fn parse_chain_definitions(json: &str) -> Result<Vec<BlockchainRecord>, ChainRegistryError> {
+    let definitions: Vec<ChainDefinition> =
+        serde_json::from_str(json).map_err(|e| ChainRegistryError::Malformed(e.to_string()))?;
+    definitions
+        .into_iter()
+        .try_fold(vec![], |mut so_far: Vec<BlockchainRecord>, definition| {
+            let record = validate_definition(definition, &so_far)?;
+            so_far.push(record);
+            Ok(so_far)
+        })
+}
+
+pub fn register_custom_chains(records: Vec<BlockchainRecord>) -> Result<(), ChainRegistryError> {
+    let mut registry = CUSTOM_CHAINS.write().expect("Chain registry is poisoned");
+    if let Some(conflicting) = records.iter().find(|record| {
+        registry.iter().any(|registered| {
+            (registered.num_chain_id == record.num_chain_id
+                || registered.literal_identifier == record.literal_identifier)
+                && *registered != *record
+        })
+    }) {
+        return Err(ChainRegistryError::InvalidDefinition {
+            identifier: conflicting.literal_identifier.to_string(),
+            msg: "conflicts with a different definition registered earlier".to_string(),
+        });
+    }
+    records.into_iter().for_each(|record| {
+        if !registry.iter().any(|registered| **registered == record) {
+            registry.push(Box::leak(Box::new(record)))
+        }
+    });
+    Ok(())
+}
+
+pub fn custom_chain_record_opt(num_chain_id: u64) -> Option<&'static BlockchainRecord> {
+    CUSTOM_CHAINS
+        .read()
+        .expect("Chain registry is poisoned")
+        .iter()
+        .find(|record| record.num_chain_id == num_chain_id)
+        .copied()
+}
+
+// The built-in chains first, in their order of significance, followed by the registered ones
+pub fn all_chain_records() -> Vec<&'static BlockchainRecord> {
+    let registry = CUSTOM_CHAINS.read().expect("Chain registry is poisoned");
+    CHAINS.iter().chain(registry.iter().copied()).collect()
+}
+
+fn validate_definition(
+    definition: ChainDefinition,
+    validated_so_far: &[BlockchainRecord],
+) -> Result<BlockchainRecord, ChainRegistryError> {
+    let invalid = |msg: String| ChainRegistryError::InvalidDefinition {
+        identifier: definition.identifier.clone(),
+        msg,
+    };
+    let identifier_regex = Regex::new("^[a-z0-9]+(-[a-z0-9]+)*$").expect("Bad regex");
+    if !identifier_regex.is_match(&definition.identifier) {
+        return Err(invalid(
+            "identifier must consist of lowercase letters and digits, optionally separated by single hyphens"
+                .to_string(),
+        ));
+    }
+    if let Some(clashing) = CHAINS.iter().chain(validated_so_far.iter()).find(|record| {
+        record.num_chain_id == definition.chain_id
+            || record.literal_identifier == definition.identifier
+    }) {
+        return Err(invalid(format!(
+            "chain id {} or identifier clashes with chain '{}'",
+            definition.chain_id, clashing.literal_identifier
+        )));
+    }
+    if definition.chain_id == 0 {
+        return Err(invalid("chain id must not be zero".to_string()));
+    }
+    let contract_regex = Regex::new("^0x[0-9a-fA-F]{40}$").expect("Bad regex");
+    if !contract_regex.is_match(&definition.contract) {
+        return Err(invalid(format!(
+            "contract '{}' is not a 0x-prefixed 20-byte hexadecimal address",
+            definition.contract
+        )));
+    }
+    let contract =
+        Address::from_str(&definition.contract[2..]).expect("Regex let in a bad address");
+    if definition.gas_limit_const_part < MIN_GAS_LIMIT_CONST_PART {
+        return Err(invalid(format!(
+            "gas limit constant part {} is less than the minimum of {}",
+            definition.gas_limit_const_part, MIN_GAS_LIMIT_CONST_PART
+        )));
+    }
+    Ok(BlockchainRecord {
+        self_id: Chain::Custom(definition.chain_id),
+        num_chain_id: definition.chain_id,
+        literal_identifier: Box::leak(definition.identifier.into_boxed_str()),
+        contract,
+        contract_creation_block: definition.contract_creation_block,
+        gas_limit_const_part: definition.gas_limit_const_part,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchains::chains::chain_from_chain_identifier_opt;
+    use crate::test_utils::utils::ensure_node_home_directory_exists;
+    use ethereum_types::H160;
+
+    // The registry is global, so every test here registers chains with ids and identifiers of its own
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(CHAIN_REGISTRY_FILE_NAME, "chains.json");
+        assert_eq!(MIN_GAS_LIMIT_CONST_PART, 21_000);
+    }
+
+    #[test]
+    fn load_chain_registry_makes_defined_chains_usable() {
+        let home_dir = ensure_node_home_directory_exists(
+            "chain_registry",
+            "load_chain_registry_makes_defined_chains_usable",
+        );
+        fs::write(
+            home_dir.join(CHAIN_REGISTRY_FILE_NAME),
+            r#"[
+                {
+                    "chainId": 31337,
+                    "identifier": "anvil-local",
+                    "contract": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+                    "contractCreationBlock": 1,
+                    "gasLimitConstPart": 60000
+                },
+                {
+                    "chainId": 8453,
+                    "identifier": "base-mainnet",
+                    "contract": "0x0000000000000000000000000000000000000001",
+                    "contractCreationBlock": 17000000,
+                    "gasLimitConstPart": 80000
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        let result = load_chain_registry(&home_dir);
+
+        assert_eq!(result, Ok(2));
+        let chain = chain_from_chain_identifier_opt("anvil-local").unwrap();
+        assert_eq!(chain, Chain::Custom(31337));
+        assert_eq!(Chain::from("anvil-local"), chain);
+        assert_eq!(
+            chain.rec(),
+            &BlockchainRecord {
+                self_id: Chain::Custom(31337),
+                num_chain_id: 31337,
+                literal_identifier: "anvil-local",
+                contract: H160([
+                    0x5f, 0xbd, 0xb2, 0x31, 0x56, 0x78, 0xaf, 0xec, 0xb3, 0x67, 0xf0, 0x32, 0xd9,
+                    0x3f, 0x64, 0x2f, 0x64, 0x18, 0x0a, 0xa3,
+                ]),
+                contract_creation_block: 1,
+                gas_limit_const_part: 60_000,
+            }
+        );
+        assert_eq!(chain.is_mainnet(), false);
+        assert_eq!(Chain::Custom(8453).rec().literal_identifier, "base-mainnet");
+        let all_identifiers = all_chain_records()
+            .into_iter()
+            .map(|record| record.literal_identifier)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            &all_identifiers[0..5],
+            &CHAINS
+                .iter()
+                .map(|record| record.literal_identifier)
+                .collect::<Vec<_>>()[..]
+        );
+        assert!(all_identifiers.contains(&"anvil-local"));
+        assert!(all_identifiers.contains(&"base-mainnet"));
+    }
+
+    #[test]
+    fn load_chain_registry_is_content_without_a_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "chain_registry",
+            "load_chain_registry_is_content_without_a_file",
+        );
+
+        let result = load_chain_registry(&home_dir);
+
+        assert_eq!(result, Ok(0));
+    }
+
+    #[test]
+    fn load_chain_registry_complains_about_unreadable_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "chain_registry",
+            "load_chain_registry_complains_about_unreadable_file",
+        );
+        fs::create_dir_all(home_dir.join(CHAIN_REGISTRY_FILE_NAME)).unwrap();
+
+        let result = load_chain_registry(&home_dir);
+
+        match result {
+            Err(ChainRegistryError::Unreadable(msg)) => {
+                assert!(msg.contains(CHAIN_REGISTRY_FILE_NAME), "{}", msg)
+            }
+            x => panic!("Expected Unreadable, got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn registering_the_same_definitions_twice_is_harmless() {
+        let json = make_definition_json(424242, "twice-registered", 55_000);
+        let records = parse_chain_definitions(&json).unwrap();
+        register_custom_chains(records).unwrap();
+        let records = parse_chain_definitions(&json).unwrap();
+
+        let result = register_custom_chains(records);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            all_chain_records()
+                .into_iter()
+                .filter(|record| record.num_chain_id == 424242)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn registering_a_conflicting_definition_is_an_error() {
+        let records =
+            parse_chain_definitions(&make_definition_json(434343, "conflicted", 55_000)).unwrap();
+        register_custom_chains(records).unwrap();
+        let records =
+            parse_chain_definitions(&make_definition_json(434343, "conflicted", 65_000)).unwrap();
+
+        let result = register_custom_chains(records);
+
+        assert_eq!(
+            result,
+            Err(ChainRegistryError::InvalidDefinition {
+                identifier: "conflicted".to_string(),
+                msg: "conflicts with a different definition registered earlier".to_string()
+            })
+        );
+        assert_eq!(Chain::Custom(434343).rec().gas_limit_const_part, 55_000);
+    }
+
+    #[test]
+    fn malformed_json_is_rejected() {
+        let result = parse_chain_definitions(r#"[{"chainId": 5, "identifier": "goerli"}]"#);
+
+        match result {
+            Err(ChainRegistryError::Malformed(msg)) => {
+                assert!(msg.contains("missing field `contract`"), "{}", msg)
+            }
+            x => panic!("Expected Malformed, got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn invalid_definitions_are_rejected() {
+        assert_invalid(
+            make_definition_json(5, "Goerli Testnet", 55_000),
+            "Goerli Testnet",
+            "identifier must consist of lowercase letters and digits, optionally separated by single hyphens",
+        );
+        assert_invalid(
+            make_definition_json(137, "my-polygon", 55_000),
+            "my-polygon",
+            "chain id 137 or identifier clashes with chain 'polygon-mainnet'",
+        );
+        assert_invalid(
+            make_definition_json(5, "dev", 55_000),
+            "dev",
+            "chain id 5 or identifier clashes with chain 'dev'",
+        );
+        assert_invalid(
+            make_definition_json(0, "zero", 55_000),
+            "zero",
+            "chain id must not be zero",
+        );
+        assert_invalid(
+            make_definition_json(5, "goerli", 20_999),
+            "goerli",
+            "gas limit constant part 20999 is less than the minimum of 21000",
+        );
+        assert_invalid(
+            r#"[{"chainId": 5, "identifier": "goerli", "contract": "5FbDB2315678afecb367f032d93F642f64180aa3",
+                 "contractCreationBlock": 0, "gasLimitConstPart": 55000}]"#
+                .to_string(),
+            "goerli",
+            "contract '5FbDB2315678afecb367f032d93F642f64180aa3' is not a 0x-prefixed 20-byte hexadecimal address",
+        );
+    }
+
+    #[test]
+    fn duplicates_within_one_file_are_rejected() {
+        let json = format!(
+            "[{},{}]",
+            make_definition_object(5, "goerli", 55_000),
+            make_definition_object(6, "goerli", 55_000)
+        );
+
+        assert_invalid(
+            json,
+            "goerli",
+            "chain id 6 or identifier clashes with chain 'goerli'",
+        );
+    }
+
+    #[test]
+    fn chain_registry_error_is_displayed_properly() {
+        assert_eq!(
+            ChainRegistryError::Unreadable("booga".to_string()).to_string(),
+            "Chain definitions could not be read: booga"
+        );
+        assert_eq!(
+            ChainRegistryError::Malformed("booga".to_string()).to_string(),
+            "Chain definitions are malformed: booga"
+        );
+        assert_eq!(
+            ChainRegistryError::InvalidDefinition {
+                identifier: "goerli".to_string(),
+                msg: "booga".to_string()
+            }
+            .to_string(),
+            "Definition of chain 'goerli' is invalid: booga"
+        );
+    }
+
+    fn assert_invalid(json: String, identifier: &str, msg: &str) {
+        assert_eq!(
+            parse_chain_definitions(&json),
+            Err(ChainRegistryError::InvalidDefinition {
+                identifier: identifier.to_string(),
+                msg: msg.to_string()
+            })
+        );
+    }
+
+    fn make_definition_json(chain_id: u64, identifier: &str, gas_limit_const_part: u64) -> String {
+        format!(
+            "[{}]",
+            make_definition_object(chain_id, identifier, gas_limit_const_part)
+        )
+    }
+
+    fn make_definition_object(
+        chain_id: u64,
+        identifier: &str,
+        gas_limit_const_part: u64,
+    ) -> String {
+        format!(
+            r#"{{"chainId": {}, "identifier": "{}", "contract": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+                "contractCreationBlock": 0, "gasLimitConstPart": {}}}"#,
+            chain_id, identifier, gas_limit_const_part
+        )
+    }
+}
@@ -1,10 +1,8 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::blockchains::blockchain_records::{BlockchainRecord, CHAINS};
-use crate::constants::{
-    DEFAULT_CHAIN, DEV_CHAIN_FULL_IDENTIFIER, ETH_MAINNET_FULL_IDENTIFIER,
-    ETH_ROPSTEN_FULL_IDENTIFIER, POLYGON_AMOY_FULL_IDENTIFIER, POLYGON_MAINNET_FULL_IDENTIFIER,
-};
+use crate::blockchains::chain_registry::{all_chain_records, custom_chain_record_opt};
+use crate::constants::DEFAULT_CHAIN;
 use serde_derive::{Deserialize, Serialize};
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
@@ -14,6 +12,8 @@ pub enum Chain {
     PolyMainnet,
     PolyAmoy,
     Dev,
+    // Defined in the chain registry file rather than built in; identified by its numeric chain id
+    Custom(u64),
 }
 
 impl Default for Chain {
@@ -24,28 +24,19 @@ impl Default for Chain {
 
 impl From<&str> for Chain {
     fn from(str: &str) -> Self {
-        if str == POLYGON_MAINNET_FULL_IDENTIFIER {
-            Chain::PolyMainnet
-        } else if str == ETH_MAINNET_FULL_IDENTIFIER {
-            Chain::EthMainnet
-        } else if str == POLYGON_AMOY_FULL_IDENTIFIER {
-            Chain::PolyAmoy
-        } else if str == ETH_ROPSTEN_FULL_IDENTIFIER {
-            Chain::EthRopsten
-        } else if str == DEV_CHAIN_FULL_IDENTIFIER {
-            Chain::Dev
-        } else {
+        chain_from_chain_identifier_opt(str).unwrap_or_else(|| {
             panic!("Clap let in a wrong value for chain: '{}'; if this happens we need to track down the slit", str)
-        }
+        })
     }
 }
 
 impl Chain {
-    pub fn rec(&self) -> &BlockchainRecord {
-        CHAINS
-            .iter()
-            .find(|b| &b.self_id == self)
-            .unwrap_or_else(|| panic!("BlockchainRecord for '{:?}' doesn't exist", self))
+    pub fn rec(&self) -> &'static BlockchainRecord {
+        match self {
+            Chain::Custom(num_chain_id) => custom_chain_record_opt(*num_chain_id),
+            _ => CHAINS.iter().find(|b| &b.self_id == self),
+        }
+        .unwrap_or_else(|| panic!("BlockchainRecord for '{:?}' doesn't exist", self))
         //untested panic - but works as an expect()
     }
 
@@ -67,16 +58,17 @@ pub fn chain_from_chain_identifier_opt(identifier: &str) -> Option<Chain> {
 
 fn return_record_opt_standard_impl(
     closure: &dyn Fn(&&BlockchainRecord) -> bool,
-) -> Option<&BlockchainRecord> {
-    return_record_opt_body(closure, &CHAINS)
+) -> Option<&'static BlockchainRecord> {
+    return_record_opt_body(closure, &all_chain_records())
 }
 
 fn return_record_opt_body<'a>(
     closure: &dyn Fn(&&'a BlockchainRecord) -> bool,
-    collection_of_chains: &'a [BlockchainRecord],
+    collection_of_chains: &[&'a BlockchainRecord],
 ) -> Option<&'a BlockchainRecord> {
     let filtered = collection_of_chains
         .iter()
+        .copied()
         .filter(closure)
         .collect::<Vec<&BlockchainRecord>>();
     match filtered.len() {
@@ -100,7 +92,7 @@ mod tests {
         record_two.literal_identifier = "Jooodooo";
         let mut record_three = make_defaulted_blockchain_record();
         record_three.literal_identifier = searched_name;
-        let collection = [record_one, record_two, record_three];
+        let collection = [&record_one, &record_two, &record_three];
 
         let _ = return_record_opt_body(
             &|b: &&BlockchainRecord| b.literal_identifier == searched_name,
@@ -136,6 +128,7 @@ mod tests {
             literal_identifier: "",
             contract: Default::default(),
             contract_creation_block: 0,
+            gas_limit_const_part: 0,
         }
     }
 
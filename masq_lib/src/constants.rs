@@ -5,7 +5,7 @@ use crate::data_version::DataVersion;
 use const_format::concatcp;
 
 pub const DEFAULT_CHAIN: Chain = Chain::PolyMainnet;
-pub const CURRENT_SCHEMA_VERSION: usize = 9;
+pub const CURRENT_SCHEMA_VERSION: usize = 16;
 
 pub const HIGHEST_RANDOM_CLANDESTINE_PORT: u16 = 9999;
 pub const HTTP_PORT: u16 = 80;
@@ -13,12 +13,31 @@ pub const TLS_PORT: u16 = 443;
 pub const LOWEST_USABLE_INSECURE_PORT: u16 = 1025;
 pub const HIGHEST_USABLE_PORT: u16 = 65535;
 pub const DEFAULT_UI_PORT: u16 = 5333;
+pub const MAX_CLANDESTINE_PORT_COUNT: usize = 8;
+pub const MIN_CLANDESTINE_PORT_ROTATION_SECS: u64 = 60;
+pub const MIN_RELAY_BATCH_LATENCY_MS: u64 = 10;
+pub const MAX_RELAY_BATCH_LATENCY_MS: u64 = 5000;
+pub const MIN_STUCK_PAYABLE_SEC: u64 = 60;
+pub const MAX_STUCK_PAYABLE_SEC: u64 = 21_600;
+pub const MIN_CONFIRMATION_DEPTH: u64 = 1;
+pub const MAX_CONFIRMATION_DEPTH: u64 = 1000;
+pub const MIN_LEDGER_DAYS: u64 = 1;
+pub const MAX_LEDGER_DAYS: u64 = 3650;
+pub const MAX_BALANCE_WARNING_DAYS: u64 = 365;
+pub const DEFAULT_LOG_ROTATION_SIZE_MB: u64 = 100;
+pub const MIN_LOG_ROTATION_SIZE_MB: u64 = 1;
+pub const MAX_LOG_ROTATION_SIZE_MB: u64 = 10_000;
+pub const DEFAULT_LOG_RETENTION_FILES: u64 = 50;
+pub const MIN_LOG_RETENTION_FILES: u64 = 1;
+pub const MAX_LOG_RETENTION_FILES: u64 = 1000;
 
 pub const MASQ_URL_PREFIX: &str = "masq://";
 pub const CURRENT_LOGFILE_NAME: &str = "MASQNode_rCURRENT.log";
 pub const MASQ_PROMPT: &str = "masq> ";
 
 pub const DEFAULT_GAS_PRICE: u64 = 1; //TODO ?? Really
+pub const DEFAULT_BLOCKCHAIN_SERVICE_QUORUM: u16 = 1;
+pub const DEFAULT_MULTISEND_MAX_CREDITORS: u16 = 100;
 
 pub const WALLET_ADDRESS_LENGTH: usize = 42;
 pub const MASQ_TOTAL_SUPPLY: u64 = 37_500_000;
@@ -41,6 +60,7 @@ pub const DNS_RESOLVER_FAILURE_CURRENT_VERSION: DataVersion = DataVersion { majo
 pub const GOSSIP_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
 pub const GOSSIP_FAILURE_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
 pub const NODE_RECORD_INNER_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
+pub const SERVICE_RECEIPT_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
 
 //error codes
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -71,12 +91,16 @@ pub const UNMARSHAL_ERROR: u64 = UI_NODE_COMMUNICATION_PREFIX | 4;
 pub const SETUP_ERROR: u64 = UI_NODE_COMMUNICATION_PREFIX | 5;
 pub const TIMEOUT_ERROR: u64 = UI_NODE_COMMUNICATION_PREFIX | 6;
 pub const SCAN_ERROR: u64 = UI_NODE_COMMUNICATION_PREFIX | 7;
+pub const UI_PERMISSION_ERROR: u64 = UI_NODE_COMMUNICATION_PREFIX | 8;
 
 //accountant
 pub const ACCOUNTANT_PREFIX: u64 = 0x0040_0000_0000_0000;
 pub const REQUEST_WITH_NO_VALUES: u64 = ACCOUNTANT_PREFIX | 1;
 pub const REQUEST_WITH_MUTUALLY_EXCLUSIVE_PARAMS: u64 = ACCOUNTANT_PREFIX | 2;
 pub const VALUE_EXCEEDS_ALLOWED_LIMIT: u64 = ACCOUNTANT_PREFIX | 3;
+pub const INVALID_LEDGER_QUERY: u64 = ACCOUNTANT_PREFIX | 4;
+pub const BAN_REQUEST_ERROR: u64 = ACCOUNTANT_PREFIX | 5;
+pub const INVALID_THRESHOLDS_SIMULATION: u64 = ACCOUNTANT_PREFIX | 6;
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
@@ -85,6 +109,11 @@ pub const COMBINED_PARAMETERS_DELIMITER: char = '|';
 //descriptor
 pub const CENTRAL_DELIMITER: char = '@';
 pub const CHAIN_IDENTIFIER_DELIMITER: char = ':';
+pub const CRYPTO_SUITE_DELIMITER: char = '.';
+
+//crypto suites
+pub const CURVE25519_CRYPTO_SUITE_IDENTIFIER: &str = "curve25519";
+pub const X25519_MLKEM768_CRYPTO_SUITE_IDENTIFIER: &str = "x25519-mlkem768";
 
 //chains
 const POLYGON_FAMILY: &str = "polygon";
@@ -110,10 +139,29 @@ mod tests {
         assert_eq!(LOWEST_USABLE_INSECURE_PORT, 1025);
         assert_eq!(HIGHEST_USABLE_PORT, 65535);
         assert_eq!(DEFAULT_UI_PORT, 5333);
+        assert_eq!(MAX_CLANDESTINE_PORT_COUNT, 8);
+        assert_eq!(MIN_CLANDESTINE_PORT_ROTATION_SECS, 60);
+        assert_eq!(MIN_RELAY_BATCH_LATENCY_MS, 10);
+        assert_eq!(MAX_RELAY_BATCH_LATENCY_MS, 5000);
+        assert_eq!(MIN_STUCK_PAYABLE_SEC, 60);
+        assert_eq!(MAX_STUCK_PAYABLE_SEC, 21_600);
+        assert_eq!(MIN_CONFIRMATION_DEPTH, 1);
+        assert_eq!(MAX_CONFIRMATION_DEPTH, 1000);
+        assert_eq!(MIN_LEDGER_DAYS, 1);
+        assert_eq!(MAX_LEDGER_DAYS, 3650);
+        assert_eq!(MAX_BALANCE_WARNING_DAYS, 365);
+        assert_eq!(DEFAULT_LOG_ROTATION_SIZE_MB, 100);
+        assert_eq!(MIN_LOG_ROTATION_SIZE_MB, 1);
+        assert_eq!(MAX_LOG_ROTATION_SIZE_MB, 10_000);
+        assert_eq!(DEFAULT_LOG_RETENTION_FILES, 50);
+        assert_eq!(MIN_LOG_RETENTION_FILES, 1);
+        assert_eq!(MAX_LOG_RETENTION_FILES, 1000);
         assert_eq!(MASQ_URL_PREFIX, "masq://");
         assert_eq!(CURRENT_LOGFILE_NAME, "MASQNode_rCURRENT.log");
         assert_eq!(MASQ_PROMPT, "masq> ");
         assert_eq!(DEFAULT_GAS_PRICE, 1);
+        assert_eq!(DEFAULT_BLOCKCHAIN_SERVICE_QUORUM, 1);
+        assert_eq!(DEFAULT_MULTISEND_MAX_CREDITORS, 100);
         assert_eq!(WALLET_ADDRESS_LENGTH, 42);
         assert_eq!(MASQ_TOTAL_SUPPLY, 37_500_000);
         assert_eq!(WEIS_IN_GWEI, 1_000_000_000);
@@ -148,6 +196,7 @@ mod tests {
         assert_eq!(SETUP_ERROR, UI_NODE_COMMUNICATION_PREFIX | 5);
         assert_eq!(TIMEOUT_ERROR, UI_NODE_COMMUNICATION_PREFIX | 6);
         assert_eq!(SCAN_ERROR, UI_NODE_COMMUNICATION_PREFIX | 7);
+        assert_eq!(UI_PERMISSION_ERROR, UI_NODE_COMMUNICATION_PREFIX | 8);
         assert_eq!(ACCOUNTANT_PREFIX, 0x0040_0000_0000_0000);
         assert_eq!(REQUEST_WITH_NO_VALUES, ACCOUNTANT_PREFIX | 1);
         assert_eq!(
@@ -155,8 +204,14 @@ mod tests {
             ACCOUNTANT_PREFIX | 2
         );
         assert_eq!(VALUE_EXCEEDS_ALLOWED_LIMIT, ACCOUNTANT_PREFIX | 3);
+        assert_eq!(INVALID_LEDGER_QUERY, ACCOUNTANT_PREFIX | 4);
+        assert_eq!(BAN_REQUEST_ERROR, ACCOUNTANT_PREFIX | 5);
+        assert_eq!(INVALID_THRESHOLDS_SIMULATION, ACCOUNTANT_PREFIX | 6);
         assert_eq!(CENTRAL_DELIMITER, '@');
         assert_eq!(CHAIN_IDENTIFIER_DELIMITER, ':');
+        assert_eq!(CRYPTO_SUITE_DELIMITER, '.');
+        assert_eq!(CURVE25519_CRYPTO_SUITE_IDENTIFIER, "curve25519");
+        assert_eq!(X25519_MLKEM768_CRYPTO_SUITE_IDENTIFIER, "x25519-mlkem768");
         assert_eq!(POLYGON_FAMILY, "polygon");
         assert_eq!(ETH_FAMILY, "eth");
         assert_eq!(MAINNET, "mainnet");
@@ -187,6 +242,10 @@ mod tests {
             NODE_RECORD_INNER_CURRENT_VERSION,
             DataVersion { major: 0, minor: 1 }
         );
+        assert_eq!(
+            SERVICE_RECEIPT_CURRENT_VERSION,
+            DataVersion { major: 0, minor: 1 }
+        );
     }
 
     #[test]
@@ -198,6 +257,7 @@ mod tests {
             GOSSIP_CURRENT_VERSION,
             GOSSIP_FAILURE_CURRENT_VERSION,
             NODE_RECORD_INNER_CURRENT_VERSION,
+            SERVICE_RECEIPT_CURRENT_VERSION,
         ]
         .into_iter()
         .for_each(|item| {
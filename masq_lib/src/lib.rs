@@ -23,5 +23,6 @@ pub mod data_version;
 pub mod shared_schema;
 pub mod test_utils;
 pub mod type_obfuscation;
+pub mod ui_auth;
 pub mod ui_gateway;
 pub mod ui_traffic_converter;
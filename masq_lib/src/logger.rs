@@ -22,19 +22,20 @@ use log::Level;
 use log::Metadata;
 #[allow(unused_imports)]
 use log::Record;
+use serde_derive::Serialize;
 use std::sync::Mutex;
 use std::{io, thread};
 use time::format_description::parse;
 use time::OffsetDateTime;
 
-pub static mut POINTER_TO_FORMAT_FUNCTION: fn(
-    &mut dyn io::Write,
-    OffsetDateTime,
-    &Record,
-) -> Result<(), io::Error> = heading_format_function;
+pub type FormatFunction = fn(&mut dyn io::Write, OffsetDateTime, &Record) -> Result<(), io::Error>;
+
+pub static mut POINTER_TO_FORMAT_FUNCTION: FormatFunction = heading_format_function;
 const UI_MESSAGE_LOG_LEVEL: Level = Level::Info;
 pub const TIME_FORMATTING_STRING: &str =
     "[year]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:3]";
+pub const JSON_TIME_FORMATTING_STRING: &str =
+    "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z";
 
 lazy_static! {
     pub static ref LOG_RECIPIENT_OPT: Mutex<Option<Recipient<NodeToUiMessage>>> = Mutex::new(None);
@@ -284,8 +285,7 @@ pub fn real_format_function(
     let timestamp = timestamp
         .format(&parse(TIME_FORMATTING_STRING).expect("Unable to parse the formatting type."))
         .expect("Unable to format date and time.");
-    let thread_id_str = format!("{:?}", thread::current().id());
-    let thread_id = &thread_id_str[9..(thread_id_str.len() - 1)];
+    let thread_id = current_thread_id();
     let level = record.level();
     let name = record.module_path().unwrap_or("<unnamed>");
     write.write_fmt(format_args!(
@@ -295,6 +295,41 @@ pub fn real_format_function(
     write.write_fmt(*record.args())
 }
 
+#[derive(Serialize)]
+struct JsonLogLine<'a> {
+    timestamp: String,
+    level: &'a str,
+    thread: String,
+    component: &'a str,
+    message: String,
+}
+
+// One self-contained JSON object per line, so log collectors don't have to parse the text format;
+// the timestamp is always UTC.
+pub fn json_format_function(
+    write: &mut dyn io::Write,
+    timestamp: OffsetDateTime,
+    record: &Record,
+) -> Result<(), io::Error> {
+    let line = JsonLogLine {
+        timestamp: timestamp
+            .format(
+                &parse(JSON_TIME_FORMATTING_STRING).expect("Unable to parse the formatting type."),
+            )
+            .expect("Unable to format date and time."),
+        level: record.level().as_str(),
+        thread: current_thread_id(),
+        component: record.module_path().unwrap_or("<unnamed>"),
+        message: record.args().to_string(),
+    };
+    serde_json::to_writer(write, &line).map_err(io::Error::from)
+}
+
+fn current_thread_id() -> String {
+    let thread_id_str = format!("{:?}", thread::current().id());
+    thread_id_str[9..(thread_id_str.len() - 1)].to_string()
+}
+
 #[cfg(feature = "log_recipient_test")]
 lazy_static! {
     pub static ref INITIALIZATION_COUNTER: Mutex<MutexIncrementInset> =
@@ -815,6 +850,40 @@ mod tests {
         assert_between(&another_log[..prefix_len], &before_str, &after_str);
     }
 
+    #[test]
+    fn json_format_function_writes_one_json_object_per_record() {
+        let timestamp =
+            OffsetDateTime::from_unix_timestamp_nanos(1_700_000_000_123_456_789).unwrap();
+        let mut buffer: Vec<u8> = vec![];
+        let args = format_args!("Message with \"quotes\"\nand a newline");
+        let record = Record::builder()
+            .args(args)
+            .module_path(Some("JsonComponent"))
+            .level(Level::Warn)
+            .build();
+
+        json_format_function(&mut buffer, timestamp, &record).unwrap();
+
+        let text = String::from_utf8(buffer).unwrap();
+        assert_eq!(text.lines().count(), 1, "{}", text);
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "timestamp": "2023-11-14T22:13:20.123Z",
+                "level": "WARN",
+                "thread": thread_id_as_string(thread::current().id()),
+                "component": "JsonComponent",
+                "message": "Message with \"quotes\"\nand a newline",
+            })
+        );
+        assert!(
+            text.starts_with("{\"timestamp\":\"2023-11-14T22:13:20.123Z\",\"level\":\"WARN\","),
+            "{}",
+            text
+        );
+    }
+
     #[test]
     fn trace_is_not_computed_when_log_level_is_debug() {
         let logger = make_logger_at_level(Level::Debug);
@@ -397,6 +397,15 @@ pub enum CrashReason {
     NoInformation,
     Unrecognized(String),
     DaemonCrashed,
+    NodePanic {
+        message: String,
+        location: String,
+    },
+    ActorPanic {
+        actor: String,
+        message: String,
+        location: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -408,6 +417,81 @@ pub struct UiNodeCrashedBroadcast {
 }
 fire_and_forget_message!(UiNodeCrashedBroadcast, "crashed");
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiNodeRestartedBroadcast {
+    #[serde(rename = "crashedProcessId")]
+    pub crashed_process_id: u32,
+    #[serde(rename = "newProcessId")]
+    pub new_process_id: u32,
+    #[serde(rename = "redirectUiPort")]
+    pub redirect_ui_port: u16,
+    #[serde(rename = "restartCount")]
+    pub restart_count: u32,
+}
+fire_and_forget_message!(UiNodeRestartedBroadcast, "restarted");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiCrashHistoryRequest {}
+conversation_message!(UiCrashHistoryRequest, "crashHistory");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum UiRestartDecision {
+    NoRestartPolicy,
+    CleanExit,
+    Restart,
+    CrashLoop,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiCrashRecord {
+    #[serde(rename = "timestampS")]
+    pub timestamp_s: u64,
+    #[serde(rename = "processId")]
+    pub process_id: u32,
+    #[serde(rename = "crashReason")]
+    pub crash_reason: CrashReason,
+    pub decision: UiRestartDecision,
+    #[serde(rename = "restartDelaySOpt")]
+    pub restart_delay_s_opt: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiCrashHistoryResponse {
+    #[serde(rename = "restartPolicyOpt")]
+    pub restart_policy_opt: Option<String>,
+    pub crashes: Vec<UiCrashRecord>,
+}
+conversation_message!(UiCrashHistoryResponse, "crashHistory");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiCrashReportRequest {}
+conversation_message!(UiCrashReportRequest, "crashReport");
+
+// Written by a panicking Node into its data directory and read back by the Daemon
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiCrashReport {
+    #[serde(rename = "timestampS")]
+    pub timestamp_s: u64,
+    #[serde(rename = "processId")]
+    pub process_id: u32,
+    pub version: String,
+    #[serde(rename = "panicMessage")]
+    pub panic_message: String,
+    pub location: String,
+    #[serde(rename = "actorOpt")]
+    pub actor_opt: Option<String>,
+    pub backtrace: String,
+    #[serde(rename = "logLines")]
+    pub log_lines: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiCrashReportResponse {
+    #[serde(rename = "reportOpt")]
+    pub report_opt: Option<UiCrashReport>,
+}
+conversation_message!(UiCrashReportResponse, "crashReport");
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct UiRedirect {
     pub port: u16,
@@ -434,6 +518,51 @@ fire_and_forget_message!(UiUnmarshalError, "unmarshalError");
 // These messages are sent to or by the Node only
 ///////////////////////////////////////////////////////////////////
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiBanRequest {
+    pub wallet: String,
+    #[serde(rename = "reasonOpt")]
+    pub reason_opt: Option<String>,
+}
+conversation_message!(UiBanRequest, "ban");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiBanResponse {
+    #[serde(rename = "alreadyBanned")]
+    pub already_banned: bool,
+}
+conversation_message!(UiBanResponse, "ban");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiBanChangedBroadcast {
+    pub wallet: String,
+    pub banned: bool,
+    #[serde(rename = "reasonOpt")]
+    pub reason_opt: Option<String>,
+}
+fire_and_forget_message!(UiBanChangedBroadcast, "banChanged");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiBanListRequest {}
+conversation_message!(UiBanListRequest, "banList");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiBanListResponse {
+    pub banned: Vec<UiBannedWallet>,
+}
+conversation_message!(UiBanListResponse, "banList");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiBannedWallet {
+    pub wallet: String,
+    #[serde(rename = "reasonOpt")]
+    pub reason_opt: Option<String>,
+    #[serde(rename = "timestampSOpt")]
+    pub timestamp_s_opt: Option<u64>,
+    // Manual bans are never lifted by the Node on its own
+    pub manual: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct UiChangePasswordRequest {
     #[serde(rename = "oldPasswordOpt")]
@@ -593,9 +722,19 @@ pub struct UiFinancialsRequest {
     pub top_records_opt: Option<TopRecordsConfig>,
     #[serde(rename = "customQueriesOpt")]
     pub custom_queries_opt: Option<CustomQueries>,
+    #[serde(rename = "pageOpt")]
+    pub page_opt: Option<FinancialsPage>,
 }
 conversation_message!(UiFinancialsRequest, "financials");
 
+// Pages through all records of both tables, ordered by wallet address; the last page of a table
+// is the first one coming back with fewer than 'count' records
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct FinancialsPage {
+    pub offset: u32,
+    pub count: u16,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
 pub struct TopRecordsConfig {
     pub count: u16,
@@ -660,8 +799,61 @@ pub struct UiFinancialStatistics {
     pub total_unpaid_receivable_gwei: i64,
     #[serde(rename = "totalPaidReceivableGwei")]
     pub total_paid_receivable_gwei: u64,
+    // Missing until the Node has learned the balances of its consuming wallet
+    #[serde(rename = "runwayOpt")]
+    pub runway_opt: Option<UiWalletRunway>,
+}
+
+// How long the consuming wallet will last at the recent rate of spending; a missing value means
+// nothing has been spent lately
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct UiWalletRunway {
+    #[serde(rename = "serviceFeeRunwayHoursOpt")]
+    pub service_fee_runway_hours_opt: Option<u64>,
+    #[serde(rename = "transactionFeeRunwayHoursOpt")]
+    pub transaction_fee_runway_hours_opt: Option<u64>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiWalletBalanceWarningBroadcast {
+    #[serde(rename = "consumingWallet")]
+    pub consuming_wallet: String,
+    #[serde(rename = "serviceFeeBalanceWei")]
+    pub service_fee_balance_wei: u128,
+    #[serde(rename = "transactionFeeBalanceWei")]
+    pub transaction_fee_balance_wei: u128,
+    pub runway: UiWalletRunway,
+    #[serde(rename = "warningDays")]
+    pub warning_days: u64,
+}
+fire_and_forget_message!(UiWalletBalanceWarningBroadcast, "walletBalanceWarning");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiReducedPayment {
+    pub wallet: String,
+    #[serde(rename = "originalBalanceWei")]
+    pub original_balance_wei: u128,
+    #[serde(rename = "adjustedBalanceWei")]
+    pub adjusted_balance_wei: u128,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiUnpaidPayment {
+    pub wallet: String,
+    #[serde(rename = "balanceWei")]
+    pub balance_wei: u128,
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiPaymentsAdjustedBroadcast {
+    #[serde(rename = "paidInFullCount")]
+    pub paid_in_full_count: u64,
+    pub reduced: Vec<UiReducedPayment>,
+    pub unpaid: Vec<UiUnpaidPayment>,
+}
+fire_and_forget_message!(UiPaymentsAdjustedBroadcast, "paymentsAdjusted");
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct QueryResults {
     #[serde(rename = "payableOpt")]
@@ -679,6 +871,9 @@ pub struct UiPayableAccount {
     pub balance_gwei: u64,
     #[serde(rename = "pendingPayableHashOpt")]
     pub pending_payable_hash_opt: Option<String>,
+    // Wei amounts outgrow the numbers JSON parsers handle exactly; hence a decimal string
+    #[serde(rename = "balanceWeiOpt")]
+    pub balance_wei_opt: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
@@ -688,6 +883,44 @@ pub struct UiReceivableAccount {
     pub age_s: u64,
     #[serde(rename = "balanceGwei")]
     pub balance_gwei: i64,
+    #[serde(rename = "balanceWeiOpt")]
+    pub balance_wei_opt: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiLedgerRequest {
+    #[serde(rename = "walletOpt")]
+    pub wallet_opt: Option<String>,
+    #[serde(rename = "fromTimestampSOpt")]
+    pub from_timestamp_s_opt: Option<u64>,
+    #[serde(rename = "toTimestampSOpt")]
+    pub to_timestamp_s_opt: Option<u64>,
+    #[serde(rename = "maxEntriesOpt")]
+    pub max_entries_opt: Option<u16>,
+}
+conversation_message!(UiLedgerRequest, "ledger");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct UiLedgerResponse {
+    pub entries: Vec<UiLedgerEntry>,
+}
+conversation_message!(UiLedgerResponse, "ledger");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct UiLedgerEntry {
+    #[serde(rename = "timestampS")]
+    pub timestamp_s: u64,
+    pub wallet: String,
+    #[serde(rename = "entryType")]
+    pub entry_type: String,
+    pub bytes: u64,
+    #[serde(rename = "amountWei")]
+    pub amount_wei: u128,
+    #[serde(rename = "transactionHashOpt")]
+    pub transaction_hash_opt: Option<String>,
+    // Present only on rows that summarize several entries rolled up into one per day
+    #[serde(rename = "entryCountOpt")]
+    pub entry_count_opt: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -747,6 +980,33 @@ pub enum SerializableLogLevel {
 pub struct UiNewPasswordBroadcast {}
 fire_and_forget_message!(UiNewPasswordBroadcast, "newPassword");
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiReconciliationRequest {}
+conversation_message!(UiReconciliationRequest, "reconciliation");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct UiReconciliationResponse {
+    pub accounts: Vec<UiReconciliationAccount>,
+}
+conversation_message!(UiReconciliationResponse, "reconciliation");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct UiReconciliationAccount {
+    pub wallet: String,
+    // When the earliest service acknowledged in the stored receipts was provided
+    #[serde(rename = "sinceS")]
+    pub since_s: u64,
+    #[serde(rename = "receiptCount")]
+    pub receipt_count: u64,
+    #[serde(rename = "billedWei")]
+    pub billed_wei: u128,
+    #[serde(rename = "acknowledgedWei")]
+    pub acknowledged_wei: u128,
+    // Billed minus acknowledged; positive when the consumer acknowledges less than we billed
+    #[serde(rename = "discrepancyWei")]
+    pub discrepancy_wei: i128,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct UiRecoverSeedSpec {
     #[serde(rename = "mnemonicPhrase")]
@@ -829,6 +1089,70 @@ conversation_message!(UiShutdownRequest, "shutdown");
 pub struct UiShutdownResponse {}
 conversation_message!(UiShutdownResponse, "shutdown");
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiSimulatedAccount {
+    pub wallet: String,
+    #[serde(rename = "balanceGwei")]
+    pub balance_gwei: i64,
+    #[serde(rename = "ageS")]
+    pub age_s: u64,
+    pub banned: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiSimulateThresholdsRequest {
+    #[serde(rename = "paymentThresholds")]
+    pub payment_thresholds: String,
+    #[serde(rename = "windowS")]
+    pub window_s: u64,
+    #[serde(rename = "stepS")]
+    pub step_s: u64,
+    // If neither list is supplied, the Node simulates against its own books
+    #[serde(rename = "payablesOpt")]
+    pub payables_opt: Option<Vec<UiSimulatedAccount>>,
+    #[serde(rename = "receivablesOpt")]
+    pub receivables_opt: Option<Vec<UiSimulatedAccount>>,
+}
+conversation_message!(UiSimulateThresholdsRequest, "simulateThresholds");
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiThresholdEventType {
+    Paid,
+    Banned,
+    Unbanned,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiThresholdEvent {
+    #[serde(rename = "offsetS")]
+    pub offset_s: u64,
+    pub wallet: String,
+    pub event: UiThresholdEventType,
+    #[serde(rename = "balanceGwei")]
+    pub balance_gwei: i64,
+    #[serde(rename = "ageS")]
+    pub age_s: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiSimulateThresholdsResponse {
+    pub events: Vec<UiThresholdEvent>,
+}
+conversation_message!(UiSimulateThresholdsResponse, "simulateThresholds");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiUnbanRequest {
+    pub wallet: String,
+}
+conversation_message!(UiUnbanRequest, "unban");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiUnbanResponse {
+    #[serde(rename = "wasBanned")]
+    pub was_banned: bool,
+}
+conversation_message!(UiUnbanResponse, "unban");
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct UiWalletAddressesRequest {
     #[serde(rename = "dbPassword")]
@@ -1211,4 +1535,369 @@ mod tests {
             Err("Unrecognized ordering: 'upside-down'".to_string())
         );
     }
+
+    #[test]
+    fn ui_financials_request_can_page_and_still_reads_requests_without_paging() {
+        let message_body = |json: &str| MessageBody {
+            opcode: "financials".to_string(),
+            path: Conversation(1234),
+            payload: Ok(json.to_string()),
+        };
+        let without_paging =
+            r#"{"statsRequired": true, "topRecordsOpt": null, "customQueriesOpt": null}"#;
+        let with_paging = r#"{"statsRequired": false, "topRecordsOpt": null, "customQueriesOpt": null, "pageOpt": {"offset": 1000, "count": 500}}"#;
+
+        let (without_paging_result, _) =
+            UiFinancialsRequest::fmb(message_body(without_paging)).unwrap();
+        let (with_paging_result, _) = UiFinancialsRequest::fmb(message_body(with_paging)).unwrap();
+
+        assert_eq!(without_paging_result.page_opt, None);
+        assert!(without_paging_result.stats_required);
+        assert_eq!(
+            with_paging_result.page_opt,
+            Some(FinancialsPage {
+                offset: 1000,
+                count: 500
+            })
+        );
+    }
+
+    #[test]
+    fn ui_ledger_response_carries_wei_amounts_beyond_u64() {
+        let json = r#"
+            {
+                "entries": [
+                    {
+                        "timestampS": 1700000000,
+                        "wallet": "0x000000000000000000000000000000626f6f6761",
+                        "entryType": "paymentReceived",
+                        "bytes": 0,
+                        "amountWei": 123456789012345678901234,
+                        "transactionHashOpt": "0x1234",
+                        "entryCountOpt": null
+                    }
+                ]
+            }
+        "#
+        .to_string();
+        let message_body = MessageBody {
+            opcode: "ledger".to_string(),
+            path: Conversation(4321),
+            payload: Ok(json),
+        };
+
+        let (result, context_id) = UiLedgerResponse::fmb(message_body).unwrap();
+
+        assert_eq!(
+            result,
+            UiLedgerResponse {
+                entries: vec![UiLedgerEntry {
+                    timestamp_s: 1_700_000_000,
+                    wallet: "0x000000000000000000000000000000626f6f6761".to_string(),
+                    entry_type: "paymentReceived".to_string(),
+                    bytes: 0,
+                    amount_wei: 123_456_789_012_345_678_901_234,
+                    transaction_hash_opt: Some("0x1234".to_string()),
+                    entry_count_opt: None,
+                }]
+            }
+        );
+        assert_eq!(context_id, 4321);
+    }
+
+    #[test]
+    fn ui_crash_history_response_tells_what_the_daemon_decided() {
+        let json = r#"
+            {
+                "restartPolicyOpt": "5|10|600|3600",
+                "crashes": [
+                    {
+                        "timestampS": 1700000000,
+                        "processId": 4321,
+                        "crashReason": {"Unrecognized": "Booga"},
+                        "decision": "Restart",
+                        "restartDelaySOpt": 20
+                    },
+                    {
+                        "timestampS": 1700000100,
+                        "processId": 5432,
+                        "crashReason": "NoInformation",
+                        "decision": "CrashLoop",
+                        "restartDelaySOpt": null
+                    }
+                ]
+            }
+        "#
+        .to_string();
+        let message_body = MessageBody {
+            opcode: "crashHistory".to_string(),
+            path: Conversation(4321),
+            payload: Ok(json),
+        };
+
+        let (result, context_id) = UiCrashHistoryResponse::fmb(message_body).unwrap();
+
+        assert_eq!(
+            result,
+            UiCrashHistoryResponse {
+                restart_policy_opt: Some("5|10|600|3600".to_string()),
+                crashes: vec![
+                    UiCrashRecord {
+                        timestamp_s: 1_700_000_000,
+                        process_id: 4321,
+                        crash_reason: CrashReason::Unrecognized("Booga".to_string()),
+                        decision: UiRestartDecision::Restart,
+                        restart_delay_s_opt: Some(20),
+                    },
+                    UiCrashRecord {
+                        timestamp_s: 1_700_000_100,
+                        process_id: 5432,
+                        crash_reason: CrashReason::NoInformation,
+                        decision: UiRestartDecision::CrashLoop,
+                        restart_delay_s_opt: None,
+                    }
+                ]
+            }
+        );
+        assert_eq!(context_id, 4321);
+    }
+
+    #[test]
+    fn ui_crash_report_response_carries_panic_details() {
+        let json = r#"
+            {
+                "reportOpt": {
+                    "timestampS": 1700000000,
+                    "processId": 4321,
+                    "version": "0.9.0",
+                    "panicMessage": "Booga",
+                    "location": "src/accountant/mod.rs:12:34",
+                    "actorOpt": "ACCOUNTANT",
+                    "backtrace": "0: rust_begin_unwind",
+                    "logLines": ["first line", "last line"]
+                }
+            }
+        "#
+        .to_string();
+        let message_body = MessageBody {
+            opcode: "crashReport".to_string(),
+            path: Conversation(4321),
+            payload: Ok(json),
+        };
+
+        let (result, context_id) = UiCrashReportResponse::fmb(message_body).unwrap();
+
+        assert_eq!(
+            result,
+            UiCrashReportResponse {
+                report_opt: Some(UiCrashReport {
+                    timestamp_s: 1_700_000_000,
+                    process_id: 4321,
+                    version: "0.9.0".to_string(),
+                    panic_message: "Booga".to_string(),
+                    location: "src/accountant/mod.rs:12:34".to_string(),
+                    actor_opt: Some("ACCOUNTANT".to_string()),
+                    backtrace: "0: rust_begin_unwind".to_string(),
+                    log_lines: vec!["first line".to_string(), "last line".to_string()],
+                })
+            }
+        );
+        assert_eq!(context_id, 4321);
+    }
+
+    #[test]
+    fn crash_reasons_from_panics_serialize_as_tagged_objects() {
+        let actor_panic = CrashReason::ActorPanic {
+            actor: "ACCOUNTANT".to_string(),
+            message: "Booga".to_string(),
+            location: "src/accountant/mod.rs:12:34".to_string(),
+        };
+
+        let json = serde_json::to_string(&actor_panic).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"ActorPanic":{"actor":"ACCOUNTANT","message":"Booga","location":"src/accountant/mod.rs:12:34"}}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<CrashReason>(&json).unwrap(),
+            actor_panic
+        );
+    }
+
+    #[test]
+    fn ui_reconciliation_response_carries_signed_discrepancies() {
+        let json = r#"
+            {
+                "accounts": [
+                    {
+                        "wallet": "0x000000000000000000000000000000626f6f6761",
+                        "sinceS": 1700000000,
+                        "receiptCount": 3,
+                        "billedWei": 123456789012345678901234,
+                        "acknowledgedWei": 123456789012345678901334,
+                        "discrepancyWei": -100
+                    }
+                ]
+            }
+        "#
+        .to_string();
+        let message_body = MessageBody {
+            opcode: "reconciliation".to_string(),
+            path: Conversation(4321),
+            payload: Ok(json),
+        };
+
+        let (result, context_id) = UiReconciliationResponse::fmb(message_body).unwrap();
+
+        assert_eq!(
+            result,
+            UiReconciliationResponse {
+                accounts: vec![UiReconciliationAccount {
+                    wallet: "0x000000000000000000000000000000626f6f6761".to_string(),
+                    since_s: 1_700_000_000,
+                    receipt_count: 3,
+                    billed_wei: 123_456_789_012_345_678_901_234,
+                    acknowledged_wei: 123_456_789_012_345_678_901_334,
+                    discrepancy_wei: -100,
+                }]
+            }
+        );
+        assert_eq!(context_id, 4321);
+    }
+
+    #[test]
+    fn ui_simulate_thresholds_request_leaves_out_accounts_to_use_the_books() {
+        let json = r#"
+            {
+                "paymentThresholds": "1000|1200|1200|500|21600|500",
+                "windowS": 86400,
+                "stepS": 600,
+                "payablesOpt": null,
+                "receivablesOpt": [
+                    {
+                        "wallet": "0x000000000000000000000000000000626f6f6761",
+                        "balanceGwei": 2000,
+                        "ageS": 3600,
+                        "banned": false
+                    }
+                ]
+            }
+        "#
+        .to_string();
+        let message_body = MessageBody {
+            opcode: "simulateThresholds".to_string(),
+            path: Conversation(4321),
+            payload: Ok(json),
+        };
+
+        let (result, context_id) = UiSimulateThresholdsRequest::fmb(message_body).unwrap();
+
+        assert_eq!(
+            result,
+            UiSimulateThresholdsRequest {
+                payment_thresholds: "1000|1200|1200|500|21600|500".to_string(),
+                window_s: 86_400,
+                step_s: 600,
+                payables_opt: None,
+                receivables_opt: Some(vec![UiSimulatedAccount {
+                    wallet: "0x000000000000000000000000000000626f6f6761".to_string(),
+                    balance_gwei: 2000,
+                    age_s: 3600,
+                    banned: false,
+                }]),
+            }
+        );
+        assert_eq!(context_id, 4321);
+    }
+
+    #[test]
+    fn ui_simulate_thresholds_response_names_event_types() {
+        let json = r#"
+            {
+                "events": [
+                    {
+                        "offsetS": 1200,
+                        "wallet": "0x000000000000000000000000000000626f6f6761",
+                        "event": "Banned",
+                        "balanceGwei": 2000,
+                        "ageS": 4800
+                    }
+                ]
+            }
+        "#
+        .to_string();
+        let message_body = MessageBody {
+            opcode: "simulateThresholds".to_string(),
+            path: Conversation(4321),
+            payload: Ok(json),
+        };
+
+        let (result, context_id) = UiSimulateThresholdsResponse::fmb(message_body).unwrap();
+
+        assert_eq!(
+            result,
+            UiSimulateThresholdsResponse {
+                events: vec![UiThresholdEvent {
+                    offset_s: 1200,
+                    wallet: "0x000000000000000000000000000000626f6f6761".to_string(),
+                    event: UiThresholdEventType::Banned,
+                    balance_gwei: 2000,
+                    age_s: 4800,
+                }]
+            }
+        );
+        assert_eq!(context_id, 4321);
+    }
+
+    #[test]
+    fn ui_ban_list_response_deserializes_bans_with_and_without_details() {
+        let json = r#"
+            {
+                "banned": [
+                    {
+                        "wallet": "0x000000000000000000000000000000626f6f6761",
+                        "reasonOpt": "Delinquent",
+                        "timestampSOpt": 1700000000,
+                        "manual": false
+                    },
+                    {
+                        "wallet": "0x00000000000000000000000000000000006f6f67",
+                        "reasonOpt": null,
+                        "timestampSOpt": null,
+                        "manual": true
+                    }
+                ]
+            }
+        "#
+        .to_string();
+        let message_body = MessageBody {
+            opcode: "banList".to_string(),
+            path: Conversation(1234),
+            payload: Ok(json),
+        };
+
+        let (result, context_id) = UiBanListResponse::fmb(message_body).unwrap();
+
+        assert_eq!(
+            result,
+            UiBanListResponse {
+                banned: vec![
+                    UiBannedWallet {
+                        wallet: "0x000000000000000000000000000000626f6f6761".to_string(),
+                        reason_opt: Some("Delinquent".to_string()),
+                        timestamp_s_opt: Some(1_700_000_000),
+                        manual: false,
+                    },
+                    UiBannedWallet {
+                        wallet: "0x00000000000000000000000000000000006f6f67".to_string(),
+                        reason_opt: None,
+                        timestamp_s_opt: None,
+                        manual: true,
+                    }
+                ]
+            }
+        );
+        assert_eq!(context_id, 1234);
+    }
 }
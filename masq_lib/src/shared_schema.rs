@@ -1,22 +1,52 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::constants::{
-    DEFAULT_GAS_PRICE, DEFAULT_UI_PORT, DEV_CHAIN_FULL_IDENTIFIER, ETH_MAINNET_FULL_IDENTIFIER,
-    ETH_ROPSTEN_FULL_IDENTIFIER, HIGHEST_USABLE_PORT, LOWEST_USABLE_INSECURE_PORT,
-    POLYGON_AMOY_FULL_IDENTIFIER, POLYGON_MAINNET_FULL_IDENTIFIER,
+    CURVE25519_CRYPTO_SUITE_IDENTIFIER, DEFAULT_GAS_PRICE, DEFAULT_UI_PORT,
+    DEV_CHAIN_FULL_IDENTIFIER, ETH_MAINNET_FULL_IDENTIFIER, ETH_ROPSTEN_FULL_IDENTIFIER,
+    HIGHEST_USABLE_PORT, LOWEST_USABLE_INSECURE_PORT, POLYGON_AMOY_FULL_IDENTIFIER,
+    POLYGON_MAINNET_FULL_IDENTIFIER, X25519_MLKEM768_CRYPTO_SUITE_IDENTIFIER,
 };
 use crate::crash_point::CrashPoint;
 use clap::{App, Arg};
 use lazy_static::lazy_static;
 
+pub const BALANCE_WARNING_DAYS_HELP: &str =
+    "How many days' worth of spending the consuming wallet must hold before the Node warns you about it. The \
+     Node estimates how fast it spends MASQ and gas from the services it consumed lately and, when either \
+     balance would run out sooner, broadcasts a warning to the UIs. Must be between 0 and 365; 0 turns the \
+     warnings off [default: 3]";
 pub const BLOCKCHAIN_SERVICE_HELP: &str =
     "The Ethereum client you wish to use to provide Blockchain \
      exit services from your MASQ Node (e.g. http://localhost:8545, \
      https://ropsten.infura.io/v3/YOUR-PROJECT-ID, https://mainnet.infura.io/v3/YOUR-PROJECT-ID), \
-     https://polygon-mainnet.infura.io/v3/YOUR-PROJECT-ID";
+     https://polygon-mainnet.infura.io/v3/YOUR-PROJECT-ID. You may list several clients separated by commas; \
+     the Node then turns to the next one whenever a client fails or throttles it";
+pub const BLOCKCHAIN_SERVICE_QUORUM_HELP: &str =
+    "How many of the clients listed in --blockchain-service-url must agree, ignoring errors, before the Node \
+     believes what they say about payments received and about receipts of payments sent. With more than one, \
+     every such question goes to all the clients. Must be between 1 and the number of clients [default: 1]";
 pub const CHAIN_HELP: &str =
     "The blockchain network MASQ Node will configure itself to use. You must ensure the \
-    Ethereum client specified by --blockchain-service-url communicates with the same blockchain network.";
+    Ethereum client specified by --blockchain-service-url communicates with the same blockchain network. \
+    Besides the built-in networks, you can use any network defined in the chains.json file of the MASQ \
+    directory that contains the data directories of the individual networks.";
+pub const CLANDESTINE_WEBSOCKET_HELP: &str =
+    "Whether the Node should disguise its clandestine traffic as WebSocket traffic, which looks much \
+     like ordinary web traffic to firewalls and censors. 'off' means the Node neither accepts nor originates \
+     WebSocket connections. 'accept' means the Node accepts WebSocket connections from other Nodes on its \
+     clandestine port, as well as ordinary ones, but originates only ordinary connections. 'on' means the \
+     Node both accepts and originates WebSocket connections; the Nodes it connects to must accept them too.";
+pub const CLANDESTINE_PORT_COUNT_HELP: &str =
+    "How many clandestine ports the Node should listen on and advertise to other Nodes. The first one is always \
+     --clandestine-port; the rest are chosen at random from unused ports each time the Node starts. When one of \
+     another Node's clandestine ports can't be reached, your Node tries the others before giving up on it. \
+     --clandestine-port-count is meaningless except in --neighborhood-mode standard. Must be between 1 and 8 \
+     [default: 1]";
+pub const CLANDESTINE_PORT_ROTATION_HELP: &str =
+    "If specified, the Node will close its extra clandestine ports (see --clandestine-port-count) every so many \
+     seconds, open fresh ones chosen at random, and tell its neighbors about the change. --clandestine-port is never \
+     rotated, so that Nodes that remember it can always reach yours. --clandestine-port-rotation is meaningless \
+     unless --clandestine-port-count is greater than 1. Must be at least 60 [default: no rotation]";
 pub const CONFIG_FILE_HELP: &str =
     "Optional TOML file containing configuration that doesn't often change. Should contain only \
      scalar items, string or numeric, whose names are exactly the same as the command-line parameters \
@@ -24,6 +54,12 @@ pub const CONFIG_FILE_HELP: &str =
      look for your config file starting in the --data-directory. If you specify an absolute path, \
      --data-directory will be ignored when searching for the config file. A few parameters \
      (such as --config-file, --generate-wallet, and --recover-wallet) must not be specified in a config file.";
+pub const CONFIRMATION_DEPTH_HELP: &str =
+    "How many blocks must be mined on top of the one carrying a payment to you before the Node \
+     believes the payment and credits it to the debtor. Payments credited while they have fewer than twice as many \
+     confirmations are checked once more, and if a chain reorganization has made them vanish, the Node takes the \
+     credit back. A higher value protects you better and makes the Node slower to notice payments. Must be between \
+     1 and 1000 [default: 12]";
 pub const CONSUMING_PRIVATE_KEY_HELP: &str = "The private key for the Ethereum wallet from which you wish to pay \
      other Nodes for routing and exit services. Mostly this is used for testing; be careful using it for real \
      traffic, because this value is very sensitive: anyone who sees it can use it to drain your consuming wallet. \
@@ -31,6 +67,17 @@ pub const CONSUMING_PRIVATE_KEY_HELP: &str = "The private key for the Ethereum w
      make sure you haven't already set up a consuming wallet with a derivation path, and make sure that you always \
      supply exactly the same private key every time you run the Node. A consuming private key is 64 case-insensitive \
      hexadecimal digits.";
+pub const CONSUMING_WALLET_ADDRESS_HELP: &str =
+    "The address of the Ethereum wallet from which you wish to pay other Nodes, when its private \
+     key is held by an external signer (see --external-signer-url) rather than by the Node. Addresses must begin \
+     with 0x followed by 40 hexadecimal digits (case-insensitive). Don't supply it together with \
+     --consuming-private-key, nor if you have already set up a consuming wallet in the database.";
+pub const CRYPTO_SUITE_HELP: &str =
+    "Which cryptographic suite the Node should use for its keys and for the encryption of CORES \
+     packages. 'curve25519' is the classic suite every Node understands. 'x25519-mlkem768' adds post-quantum ML-KEM \
+     protection on top of it: packages addressed to Nodes that also use 'x25519-mlkem768' are protected by both, \
+     while Nodes that don't still reach yours and are reached by it through the classic suite. Keys are regenerated \
+     every time the Node starts, so you may change this freely between runs [default: curve25519]";
 pub const DATA_DIRECTORY_HELP: &str =
     "Directory in which the Node will store its persistent state, including at least its database \
     and by default its configuration file as well.\nNote: any existing database in the data directory \
@@ -48,17 +95,48 @@ pub const EARNING_WALLET_HELP: &str =
      (case-insensitive). If you already have a derivation-path earning wallet, don't supply this. \
      If you have supplied an earning wallet address before, either don't supply it again or be \
      careful to supply exactly the same one you supplied before.";
+pub const EXTERNAL_SIGNER_URL_HELP: &str =
+    "The URL of an external signer, such as Clef, that holds the private key of the wallet named by \
+     --consuming-wallet-address. The Node asks it to sign each payment over JSON-RPC (account_signTransaction) and \
+     never sees the key. Run the signer on the same machine or a trusted network, because the Node sends it \
+     unencrypted requests. A Node whose consuming wallet is signed for externally can't prove to the Nodes on its \
+     routes that it will pay them, so it doesn't originate routes longer than one hop.";
 pub const IP_ADDRESS_HELP: &str = "The public IP address of your MASQ Node: that is, the IPv4 \
      address at which other Nodes can contact yours. If you're running your Node behind \
      a router, this will be the IP address of the router. If this IP address starts with 192.168 or 10.0, \
      it's a local address rather than a public address, and other Nodes won't be able to see yours. \
      --ip is meaningless except in --neighborhood-mode standard.";
+pub const LEDGER_RETENTION_DAYS_HELP: &str =
+    "How many days the Node keeps entries in its ledger of services and payments. Older entries, \
+     including the daily summaries produced by --ledger-rollup-days, are deleted. Must be between 1 and 3650; \
+     if you leave it out, the ledger is never trimmed.";
+pub const LEDGER_ROLLUP_DAYS_HELP: &str =
+    "How many days the Node keeps every single service entry in its ledger of services and payments. \
+     Older service entries are combined into one summary per wallet, service and day, which keeps the \
+     ledger small without losing the totals. Blockchain payments are never combined. Must be between 1 and \
+     3650 [default: 30]";
+pub const LOG_COMPRESSION_HELP: &str =
+    "Whether the Node compresses its rotated logfiles into zip archives. Say 'off' if your log pipeline \
+     wants to read the rotated files directly [default: on]";
+pub const LOG_FORMAT_HELP: &str =
+    "The format of the lines in the Node's logfile. 'text' is the traditional human-readable format. 'json' \
+     writes one JSON object per line with the fields timestamp, level, thread, component and message, so that \
+     log collectors can read it without parsing text [default: text]";
 pub const LOG_LEVEL_HELP: &str =
     "The minimum severity of the logs that should appear in the Node's logfile. You should probably not specify \
      a level lower than the default unless you're doing testing or forensics: a Node at the 'trace' log level \
      generates a lot of log traffic. This will both consume your disk space and degrade your Node's performance. \
      You should probably not specify a level higher than the default unless you have security concerns about \
      persistent logs being kept on your computer: if your Node crashes, it's good to know why.";
+pub const LOG_RETENTION_HELP: &str =
+    "How many rotated logfiles the Node keeps beside the current one. When there are more, the oldest ones are \
+     deleted. Must be between 1 and 1000 [default: 50]";
+pub const LOG_ROTATION_AGE_HELP: &str =
+    "Besides rotating its logfile when it gets too big, the Node can also start a new logfile every hour or \
+     every day. Say 'never', 'hourly' or 'daily' here [default: never]";
+pub const LOG_ROTATION_SIZE_HELP: &str =
+    "How many megabytes the Node writes to its logfile before it moves the logfile aside and starts a new one. \
+     Must be between 1 and 10000 [default: 100]";
 pub const NEIGHBORS_HELP: &str = "One or more Node descriptors for running Nodes in the MASQ \
      One or more Node descriptors for active Nodes in the MASQ Network to which you'd like your Node to connect \
      on startup. A Node descriptor looks similar to one of these:\n\n\
@@ -108,6 +186,13 @@ pub const MAPPING_PROTOCOL_HELP: &str =
     public IP address with the --ip parameter. If the Node communicates successfully with your router, \
     it will remember the protocol it used, and on its next run it will try that protocol first, unless \
     you specify a different protocol on the command line.";
+pub const METRICS_PORT_HELP: &str =
+    "If specified, the Node serves operational metrics in the Prometheus text format at \
+     http://127.0.0.1:<METRICS-PORT>/metrics: active streams, bytes relayed and exited, Gossip by handler, route \
+     query latency, scan durations and outcomes, payable and receivable totals, and the connection stage. The \
+     endpoint listens only on localhost and, unless --ui-authentication is off, demands a token from the UI token \
+     file in an X-MASQ-UI-Token or 'Authorization: Bearer' header. Must be between 1025 and 65535 \
+     [default: no metrics endpoint]";
 pub const MIN_HOPS_HELP: &str =
     "The Node is a system that routes data through multiple Nodes to enhance security and privacy. \
     However, the level of anonymity and security provided depends on the number of hops specified \
@@ -128,11 +213,35 @@ pub const MIN_HOPS_HELP: &str =
     If you want to specify a minimum hops count, you can do so by entering a number after the \
     '--min-hops' parameter. For example, '--min-hops 4' would require at least 4 hops. If you fail \
     to provide this argument, the system will default to a minimum hops count of 3.";
+pub const MULTISEND_CONTRACT_HELP: &str =
+    "The address of a Disperse-compatible multisend contract on the chain the Node pays on. When supplied, the \
+     Node pays the creditors of each payment cycle together in as few transactions through this contract as \
+     --multisend-max-creditors allows, rather than in one transaction per creditor, saving on fees. The consuming \
+     wallet must first approve the contract to spend its MASQ tokens; while the allowance is too low for a cycle, \
+     the Node pays each creditor by a transfer of its own.";
+pub const MULTISEND_MAX_CREDITORS_HELP: &str =
+    "The most creditors the Node pays in one transaction through the --multisend-contract. Each creditor adds to \
+     the gas the transaction needs, and a transaction exceeding the block gas limit of the chain would never be \
+     mined, so a payment cycle with more creditors is split into several transactions. Defaults to 100.";
+pub const PRICING_STRATEGY_HELP: &str =
+    "Lets your Node adjust the rate pack it advertises while it runs, within bounds set around the \
+     --rate-pack you configured. 'fixed' always charges the configured rate pack. 'load' charges more the more \
+     streams your Node is serving as an exit. 'market' follows the median rate pack of the other Nodes your Node \
+     knows about. Add the lower and upper bounds as percentages of the configured rate pack, separated by '|', \
+     like this: 'load|50|200'. The lower bound may not be above 100 nor the upper bound below 100. Your Node \
+     reconsiders its prices every five minutes and tells its neighbors only about moves of 10% or more. \
+     [default: fixed|50|200]";
 pub const REAL_USER_HELP: &str =
     "The user whose identity Node will assume when dropping privileges after bootstrapping. Since Node refuses to \
      run with root privilege after bootstrapping, you might want to use this if you start the Node as root, or if \
      you start the Node using pkexec or some other method that doesn't populate the SUDO_xxx variables. Use a value \
      like <uid>:<gid>:<home directory>.";
+pub const RELAY_BATCH_LATENCY_HELP: &str =
+    "If specified, the Node holds each CORES package it relays for other Nodes for a random interval of up to this \
+     many milliseconds, then sends the packages that are due in shuffled batches, so that an observer can't match the \
+     packages leaving your Node with the ones arriving. Bigger values hide traffic better, but make routes through \
+     your Node slower. The Node logs how much delay it has actually added every few minutes. Must be between 10 and \
+     5000 [default: no batching]";
 pub const SCANS_HELP: &str =
     "The Node, when running, performs various periodic scans, including scanning for payables that need to be paid, \
     for pending payables that have arrived (and are no longer pending), for incoming receivables that need to be \
@@ -141,6 +250,23 @@ pub const SCANS_HELP: &str =
     started when the Node starts, and will have to be triggered later manually and individually with the \
     MASQNode-UIv2 'scan' command. (If you don't, you'll most likely be delinquency-banned by all your neighbors.) \
     This parameter is most useful for testing.";
+pub const STUCK_PAYABLE_SEC_HELP: &str =
+    "A payment transaction that hasn't been mined for this many seconds is considered stuck, and the Node \
+     replaces it with one of the same nonce but fees raised by at least 10%, so that it is more attractive to the \
+     miners; whichever of them gets mined settles the debt. The same time then goes by before the next replacement. \
+     The fees are never raised above your gas-price, so if the Node warns you it can't replace a transaction, you may \
+     want to raise gas-price. Must be between 60 and 21600 [default: 1800]";
+pub const UI_TOKEN_FILE_HELP: &str =
+    "A file holding the tokens that user interfaces must present to connect to the UI port: one line \
+     'admin=<TOKEN>' for full control and one line 'read-only=<TOKEN>' for requests that only read. If the file \
+     doesn't exist, it is created with random tokens and made readable only by the real user; you can also write \
+     your own secrets into it beforehand, as long as no other user may read or write it. masq finds the tokens by \
+     itself in the default file [default: ui_tokens in the MASQ directory that holds the data directories of all \
+     the chains]";
+pub const UI_AUTHENTICATION_HELP: &str =
+    "Whether user interfaces must authenticate with a token from the UI token file to connect to the UI port. \
+     With 'off', any process on this machine can connect with full control; only use it when nothing else runs \
+     here [default: on]";
 pub const RATE_PACK_HELP: &str = "\
      These four parameters specify your rates that your Node will use for charging other Nodes for your provided \
      services. These are ever present values, defaulted if left unspecified. The parameters must be always supplied \
@@ -215,6 +341,8 @@ lazy_static! {
     );
     pub static ref GAS_PRICE_HELP: String = format!(
        "The Gas Price is the amount of gwei you will pay per unit of gas used in a transaction. \
+       On chains with an EIP-1559 fee market, it is the ceiling for the max fee per gas, which \
+       is otherwise derived from the fees paid in recent blocks. \
        If left unspecified, MASQ Node will use the previously stored value (Default {}).",
        DEFAULT_GAS_PRICE);
 }
@@ -222,12 +350,15 @@ lazy_static! {
 // These Args are needed in more than one clap schema. To avoid code duplication, they're defined here and referred
 // to from multiple places.
 pub fn chain_arg<'a>() -> Arg<'a, 'a> {
+    unvalidated_chain_arg().validator(common_validators::validate_chain)
+}
+
+fn unvalidated_chain_arg<'a>() -> Arg<'a, 'a> {
     Arg::with_name("chain")
         .long("chain")
         .value_name("CHAIN")
         .min_values(0)
         .max_values(1)
-        .possible_values(official_chain_names())
         .help(CHAIN_HELP)
 }
 
@@ -287,6 +418,25 @@ where
         .help(help)
 }
 
+pub fn blockchain_service_quorum_arg<'a>() -> Arg<'a, 'a> {
+    Arg::with_name("blockchain-service-quorum")
+        .long("blockchain-service-quorum")
+        .value_name("CLIENTS")
+        .min_values(0)
+        .max_values(1)
+        .validator(common_validators::validate_non_zero_u16)
+        .help(BLOCKCHAIN_SERVICE_QUORUM_HELP)
+}
+
+pub fn blockchain_service_url_arg<'a>() -> Arg<'a, 'a> {
+    Arg::with_name("blockchain-service-url")
+        .long("blockchain-service-url")
+        .value_name("URL")
+        .min_values(0)
+        .max_values(1)
+        .help(BLOCKCHAIN_SERVICE_HELP)
+}
+
 pub fn gas_price_arg<'a>() -> Arg<'a, 'a> {
     Arg::with_name("gas-price")
         .long("gas-price")
@@ -330,6 +480,25 @@ pub fn real_user_arg<'a>() -> Arg<'a, 'a> {
         .hidden(true)
 }
 
+pub fn ui_token_file_arg(help: &str) -> Arg {
+    Arg::with_name("ui-token-file")
+        .long("ui-token-file")
+        .value_name("UI-TOKEN-FILE")
+        .required(false)
+        .takes_value(true)
+        .help(help)
+}
+
+pub fn ui_authentication_arg(help: &str) -> Arg {
+    Arg::with_name("ui-authentication")
+        .long("ui-authentication")
+        .value_name("UI-AUTHENTICATION")
+        .takes_value(true)
+        .possible_values(&["on", "off"])
+        .case_insensitive(true)
+        .help(help)
+}
+
 pub fn ui_port_arg(help: &str) -> Arg {
     Arg::with_name("ui-port")
         .long("ui-port")
@@ -351,15 +520,31 @@ fn common_parameter_with_separate_u64_values<'a>(name: &'a str, help: &'a str) -
 }
 
 pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
+    shared_app_with_chain_arg(head, chain_arg())
+}
+
+// Chains defined in the registry file are known only where the Daemon and the Node run, so the UI
+// passes any chain name on and lets the Daemon judge it.
+pub fn ui_shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
+    shared_app_with_chain_arg(head, unvalidated_chain_arg())
+}
+
+fn shared_app_with_chain_arg(
+    head: App<'static, 'static>,
+    chain_arg: Arg<'static, 'static>,
+) -> App<'static, 'static> {
     head.arg(
-        Arg::with_name("blockchain-service-url")
-            .long("blockchain-service-url")
-            .value_name("URL")
+        Arg::with_name("balance-warning-days")
+            .long("balance-warning-days")
+            .value_name("DAYS")
             .min_values(0)
             .max_values(1)
-            .help(BLOCKCHAIN_SERVICE_HELP),
+            .validator(common_validators::validate_balance_warning_days)
+            .help(BALANCE_WARNING_DAYS_HELP),
     )
-    .arg(chain_arg())
+    .arg(blockchain_service_url_arg())
+    .arg(blockchain_service_quorum_arg())
+    .arg(chain_arg)
     .arg(
         Arg::with_name("clandestine-port")
             .long("clandestine-port")
@@ -368,7 +553,44 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .validator(common_validators::validate_clandestine_port)
             .help(&CLANDESTINE_PORT_HELP),
     )
+    .arg(
+        Arg::with_name("clandestine-port-count")
+            .long("clandestine-port-count")
+            .value_name("CLANDESTINE-PORT-COUNT")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_clandestine_port_count)
+            .help(CLANDESTINE_PORT_COUNT_HELP),
+    )
+    .arg(
+        Arg::with_name("clandestine-port-rotation")
+            .long("clandestine-port-rotation")
+            .value_name("SECONDS")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_clandestine_port_rotation)
+            .help(CLANDESTINE_PORT_ROTATION_HELP),
+    )
+    .arg(
+        Arg::with_name("clandestine-websocket")
+            .long("clandestine-websocket")
+            .value_name("CLANDESTINE-WEBSOCKET")
+            .min_values(0)
+            .max_values(1)
+            .possible_values(&["off", "accept", "on"])
+            .case_insensitive(true)
+            .help(CLANDESTINE_WEBSOCKET_HELP),
+    )
     .arg(config_file_arg())
+    .arg(
+        Arg::with_name("confirmation-depth")
+            .long("confirmation-depth")
+            .value_name("BLOCKS")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_confirmation_depth)
+            .help(CONFIRMATION_DEPTH_HELP),
+    )
     .arg(
         Arg::with_name("consuming-private-key")
             .long("consuming-private-key")
@@ -378,6 +600,15 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .validator(common_validators::validate_private_key)
             .help(CONSUMING_PRIVATE_KEY_HELP),
     )
+    .arg(
+        Arg::with_name("consuming-wallet-address")
+            .long("consuming-wallet-address")
+            .value_name("ADDRESS")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_ethereum_address)
+            .help(CONSUMING_WALLET_ADDRESS_HELP),
+    )
     .arg(
         Arg::with_name("crash-point")
             .long("crash-point")
@@ -388,6 +619,19 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .case_insensitive(true)
             .hidden(true),
     )
+    .arg(
+        Arg::with_name("crypto-suite")
+            .long("crypto-suite")
+            .value_name("CRYPTO-SUITE")
+            .min_values(0)
+            .max_values(1)
+            .possible_values(&[
+                CURVE25519_CRYPTO_SUITE_IDENTIFIER,
+                X25519_MLKEM768_CRYPTO_SUITE_IDENTIFIER,
+            ])
+            .case_insensitive(true)
+            .help(CRYPTO_SUITE_HELP),
+    )
     .arg(data_directory_arg(DATA_DIRECTORY_HELP))
     .arg(db_password_arg(DB_PASSWORD_HELP))
     .arg(
@@ -403,6 +647,14 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
         EARNING_WALLET_HELP,
         common_validators::validate_ethereum_address,
     ))
+    .arg(
+        Arg::with_name("external-signer-url")
+            .long("external-signer-url")
+            .value_name("URL")
+            .min_values(0)
+            .max_values(1)
+            .help(EXTERNAL_SIGNER_URL_HELP),
+    )
     .arg(
         Arg::with_name("fake-public-key")
             .long("fake-public-key")
@@ -421,6 +673,44 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .validator(common_validators::validate_ip_address)
             .help(IP_ADDRESS_HELP),
     )
+    .arg(
+        Arg::with_name("ledger-retention-days")
+            .long("ledger-retention-days")
+            .value_name("DAYS")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_ledger_days)
+            .help(LEDGER_RETENTION_DAYS_HELP),
+    )
+    .arg(
+        Arg::with_name("ledger-rollup-days")
+            .long("ledger-rollup-days")
+            .value_name("DAYS")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_ledger_days)
+            .help(LEDGER_ROLLUP_DAYS_HELP),
+    )
+    .arg(
+        Arg::with_name("log-compression")
+            .long("log-compression")
+            .value_name("LOG-COMPRESSION")
+            .min_values(0)
+            .max_values(1)
+            .possible_values(&["on", "off"])
+            .case_insensitive(true)
+            .help(LOG_COMPRESSION_HELP),
+    )
+    .arg(
+        Arg::with_name("log-format")
+            .long("log-format")
+            .value_name("LOG-FORMAT")
+            .min_values(0)
+            .max_values(1)
+            .possible_values(&["text", "json"])
+            .case_insensitive(true)
+            .help(LOG_FORMAT_HELP),
+    )
     .arg(
         Arg::with_name("log-level")
             .long("log-level")
@@ -431,6 +721,34 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .case_insensitive(true)
             .help(LOG_LEVEL_HELP),
     )
+    .arg(
+        Arg::with_name("log-retention")
+            .long("log-retention")
+            .value_name("FILES")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_log_retention)
+            .help(LOG_RETENTION_HELP),
+    )
+    .arg(
+        Arg::with_name("log-rotation-age")
+            .long("log-rotation-age")
+            .value_name("LOG-ROTATION-AGE")
+            .min_values(0)
+            .max_values(1)
+            .possible_values(&["never", "hourly", "daily"])
+            .case_insensitive(true)
+            .help(LOG_ROTATION_AGE_HELP),
+    )
+    .arg(
+        Arg::with_name("log-rotation-size")
+            .long("log-rotation-size")
+            .value_name("MEGABYTES")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_log_rotation_size)
+            .help(LOG_ROTATION_SIZE_HELP),
+    )
     .arg(
         Arg::with_name("mapping-protocol")
             .long("mapping-protocol")
@@ -441,7 +759,34 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .case_insensitive(true)
             .help(MAPPING_PROTOCOL_HELP),
     )
+    .arg(
+        Arg::with_name("metrics-port")
+            .long("metrics-port")
+            .value_name("METRICS-PORT")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_ui_port)
+            .help(METRICS_PORT_HELP),
+    )
     .arg(min_hops_arg())
+    .arg(
+        Arg::with_name("multisend-contract")
+            .long("multisend-contract")
+            .value_name("MULTISEND-CONTRACT")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_ethereum_address)
+            .help(MULTISEND_CONTRACT_HELP),
+    )
+    .arg(
+        Arg::with_name("multisend-max-creditors")
+            .long("multisend-max-creditors")
+            .value_name("MULTISEND-MAX-CREDITORS")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_non_zero_u16)
+            .help(MULTISEND_MAX_CREDITORS_HELP),
+    )
     .arg(
         Arg::with_name("neighborhood-mode")
             .long("neighborhood-mode")
@@ -459,7 +804,24 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .min_values(0)
             .help(NEIGHBORS_HELP),
     )
+    .arg(
+        Arg::with_name("pricing-strategy")
+            .long("pricing-strategy")
+            .value_name("PRICING-STRATEGY")
+            .min_values(0)
+            .max_values(1)
+            .help(PRICING_STRATEGY_HELP),
+    )
     .arg(real_user_arg())
+    .arg(
+        Arg::with_name("relay-batch-latency")
+            .long("relay-batch-latency")
+            .value_name("MILLISECONDS")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_relay_batch_latency)
+            .help(RELAY_BATCH_LATENCY_HELP),
+    )
     .arg(
         Arg::with_name("scans")
             .long("scans")
@@ -468,6 +830,15 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .possible_values(&["on", "off"])
             .help(SCANS_HELP),
     )
+    .arg(
+        Arg::with_name("stuck-payable-sec")
+            .long("stuck-payable-sec")
+            .value_name("SECONDS")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_stuck_payable_sec)
+            .help(STUCK_PAYABLE_SEC_HELP),
+    )
     .arg(common_parameter_with_separate_u64_values(
         "scan-intervals",
         SCAN_INTERVALS_HELP,
@@ -483,7 +854,14 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
 }
 
 pub mod common_validators {
-    use crate::constants::LOWEST_USABLE_INSECURE_PORT;
+    use crate::blockchains::chains::chain_from_chain_identifier_opt;
+    use crate::constants::{
+        LOWEST_USABLE_INSECURE_PORT, MAX_BALANCE_WARNING_DAYS, MAX_CLANDESTINE_PORT_COUNT,
+        MAX_CONFIRMATION_DEPTH, MAX_LEDGER_DAYS, MAX_LOG_RETENTION_FILES, MAX_LOG_ROTATION_SIZE_MB,
+        MAX_RELAY_BATCH_LATENCY_MS, MAX_STUCK_PAYABLE_SEC, MIN_CLANDESTINE_PORT_ROTATION_SECS,
+        MIN_CONFIRMATION_DEPTH, MIN_LEDGER_DAYS, MIN_LOG_RETENTION_FILES, MIN_LOG_ROTATION_SIZE_MB,
+        MIN_RELAY_BATCH_LATENCY_MS, MIN_STUCK_PAYABLE_SEC,
+    };
     use regex::Regex;
     use std::net::IpAddr;
     use std::str::FromStr;
@@ -520,6 +898,81 @@ pub mod common_validators {
         }
     }
 
+    pub fn validate_clandestine_port_count(count: String) -> Result<(), String> {
+        match count.parse::<usize>() {
+            Ok(count) if (1..=MAX_CLANDESTINE_PORT_COUNT).contains(&count) => Ok(()),
+            _ => Err(count),
+        }
+    }
+
+    pub fn validate_clandestine_port_rotation(seconds: String) -> Result<(), String> {
+        match seconds.parse::<u64>() {
+            Ok(seconds) if seconds >= MIN_CLANDESTINE_PORT_ROTATION_SECS => Ok(()),
+            _ => Err(seconds),
+        }
+    }
+
+    pub fn validate_relay_batch_latency(millis: String) -> Result<(), String> {
+        match millis.parse::<u64>() {
+            Ok(millis)
+                if (MIN_RELAY_BATCH_LATENCY_MS..=MAX_RELAY_BATCH_LATENCY_MS).contains(&millis) =>
+            {
+                Ok(())
+            }
+            _ => Err(millis),
+        }
+    }
+
+    pub fn validate_balance_warning_days(days: String) -> Result<(), String> {
+        match days.parse::<u64>() {
+            Ok(days) if days <= MAX_BALANCE_WARNING_DAYS => Ok(()),
+            _ => Err(days),
+        }
+    }
+
+    pub fn validate_stuck_payable_sec(seconds: String) -> Result<(), String> {
+        match seconds.parse::<u64>() {
+            Ok(seconds) if (MIN_STUCK_PAYABLE_SEC..=MAX_STUCK_PAYABLE_SEC).contains(&seconds) => {
+                Ok(())
+            }
+            _ => Err(seconds),
+        }
+    }
+
+    pub fn validate_confirmation_depth(blocks: String) -> Result<(), String> {
+        match blocks.parse::<u64>() {
+            Ok(blocks) if (MIN_CONFIRMATION_DEPTH..=MAX_CONFIRMATION_DEPTH).contains(&blocks) => {
+                Ok(())
+            }
+            _ => Err(blocks),
+        }
+    }
+
+    pub fn validate_ledger_days(days: String) -> Result<(), String> {
+        match days.parse::<u64>() {
+            Ok(days) if (MIN_LEDGER_DAYS..=MAX_LEDGER_DAYS).contains(&days) => Ok(()),
+            _ => Err(days),
+        }
+    }
+
+    pub fn validate_log_retention(files: String) -> Result<(), String> {
+        match files.parse::<u64>() {
+            Ok(count) if (MIN_LOG_RETENTION_FILES..=MAX_LOG_RETENTION_FILES).contains(&count) => {
+                Ok(())
+            }
+            _ => Err(files),
+        }
+    }
+
+    pub fn validate_log_rotation_size(megabytes: String) -> Result<(), String> {
+        match megabytes.parse::<u64>() {
+            Ok(size) if (MIN_LOG_ROTATION_SIZE_MB..=MAX_LOG_ROTATION_SIZE_MB).contains(&size) => {
+                Ok(())
+            }
+            _ => Err(megabytes),
+        }
+    }
+
     pub fn validate_private_key(key: String) -> Result<(), String> {
         if Regex::new("^[0-9a-fA-F]{64}$")
             .expect("Failed to compile regular expression")
@@ -591,6 +1044,13 @@ pub mod common_validators {
         }
     }
 
+    pub fn validate_chain(chain: String) -> Result<(), String> {
+        match chain_from_chain_identifier_opt(&chain) {
+            Some(_) => Ok(()),
+            None => Err(chain),
+        }
+    }
+
     pub fn validate_ui_port(port: String) -> Result<(), String> {
         match str::parse::<u16>(&port) {
             Ok(port_number) if port_number < LOWEST_USABLE_INSECURE_PORT => Err(port),
@@ -675,20 +1135,37 @@ mod tests {
     use crate::blockchains::chains::Chain;
     use crate::shared_schema::common_validators::validate_non_zero_u16;
     use crate::shared_schema::{common_validators, official_chain_names};
+    use clap::ErrorKind;
 
     #[test]
     fn constants_have_correct_values() {
+        assert_eq!(
+            BALANCE_WARNING_DAYS_HELP,
+            "How many days' worth of spending the consuming wallet must hold before the Node warns you \
+             about it. The Node estimates how fast it spends MASQ and gas from the services it consumed \
+             lately and, when either balance would run out sooner, broadcasts a warning to the UIs. Must \
+             be between 0 and 365; 0 turns the warnings off [default: 3]"
+        );
         assert_eq!(
             BLOCKCHAIN_SERVICE_HELP,
             "The Ethereum client you wish to use to provide Blockchain \
              exit services from your MASQ Node (e.g. http://localhost:8545, \
              https://ropsten.infura.io/v3/YOUR-PROJECT-ID, https://mainnet.infura.io/v3/YOUR-PROJECT-ID), \
-             https://polygon-mainnet.infura.io/v3/YOUR-PROJECT-ID"
+             https://polygon-mainnet.infura.io/v3/YOUR-PROJECT-ID. You may list several clients separated by commas; \
+             the Node then turns to the next one whenever a client fails or throttles it"
+        );
+        assert_eq!(
+            BLOCKCHAIN_SERVICE_QUORUM_HELP,
+            "How many of the clients listed in --blockchain-service-url must agree, ignoring errors, before the Node \
+             believes what they say about payments received and about receipts of payments sent. With more than one, \
+             every such question goes to all the clients. Must be between 1 and the number of clients [default: 1]"
         );
         assert_eq!(
             CHAIN_HELP,
             "The blockchain network MASQ Node will configure itself to use. You must ensure the \
-             Ethereum client specified by --blockchain-service-url communicates with the same blockchain network."
+             Ethereum client specified by --blockchain-service-url communicates with the same blockchain network. \
+             Besides the built-in networks, you can use any network defined in the chains.json file of the MASQ \
+             directory that contains the data directories of the individual networks."
         );
         assert_eq!(
             CONFIG_FILE_HELP,
@@ -699,6 +1176,14 @@ mod tests {
              --data-directory will be ignored when searching for the config file. A few parameters \
              (such as --config-file, --generate-wallet, and --recover-wallet) must not be specified in a config file."
         );
+        assert_eq!(
+            CONFIRMATION_DEPTH_HELP,
+            "How many blocks must be mined on top of the one carrying a payment to you before the Node \
+             believes the payment and credits it to the debtor. Payments credited while they have fewer than twice as many \
+             confirmations are checked once more, and if a chain reorganization has made them vanish, the Node takes the \
+             credit back. A higher value protects you better and makes the Node slower to notice payments. Must be between \
+             1 and 1000 [default: 12]"
+        );
         assert_eq!(
             CONSUMING_PRIVATE_KEY_HELP,
             "The private key for the Ethereum wallet from which you wish to pay \
@@ -709,6 +1194,44 @@ mod tests {
              supply exactly the same private key every time you run the Node. A consuming private key is 64 case-insensitive \
              hexadecimal digits."
         );
+        assert_eq!(
+            CLANDESTINE_PORT_COUNT_HELP,
+            "How many clandestine ports the Node should listen on and advertise to other Nodes. The first one is always \
+             --clandestine-port; the rest are chosen at random from unused ports each time the Node starts. When one of \
+             another Node's clandestine ports can't be reached, your Node tries the others before giving up on it. \
+             --clandestine-port-count is meaningless except in --neighborhood-mode standard. Must be between 1 and 8 \
+             [default: 1]"
+        );
+        assert_eq!(
+            CLANDESTINE_PORT_ROTATION_HELP,
+            "If specified, the Node will close its extra clandestine ports (see --clandestine-port-count) every so many \
+             seconds, open fresh ones chosen at random, and tell its neighbors about the change. --clandestine-port is never \
+             rotated, so that Nodes that remember it can always reach yours. --clandestine-port-rotation is meaningless \
+             unless --clandestine-port-count is greater than 1. Must be at least 60 [default: no rotation]"
+        );
+        assert_eq!(
+            CLANDESTINE_WEBSOCKET_HELP,
+            "Whether the Node should disguise its clandestine traffic as WebSocket traffic, which looks much \
+             like ordinary web traffic to firewalls and censors. 'off' means the Node neither accepts nor originates \
+             WebSocket connections. 'accept' means the Node accepts WebSocket connections from other Nodes on its \
+             clandestine port, as well as ordinary ones, but originates only ordinary connections. 'on' means the \
+             Node both accepts and originates WebSocket connections; the Nodes it connects to must accept them too."
+        );
+        assert_eq!(
+            CONSUMING_WALLET_ADDRESS_HELP,
+            "The address of the Ethereum wallet from which you wish to pay other Nodes, when its private \
+             key is held by an external signer (see --external-signer-url) rather than by the Node. Addresses must begin \
+             with 0x followed by 40 hexadecimal digits (case-insensitive). Don't supply it together with \
+             --consuming-private-key, nor if you have already set up a consuming wallet in the database."
+        );
+        assert_eq!(
+            CRYPTO_SUITE_HELP,
+            "Which cryptographic suite the Node should use for its keys and for the encryption of CORES \
+             packages. 'curve25519' is the classic suite every Node understands. 'x25519-mlkem768' adds post-quantum ML-KEM \
+             protection on top of it: packages addressed to Nodes that also use 'x25519-mlkem768' are protected by both, \
+             while Nodes that don't still reach yours and are reached by it through the classic suite. Keys are regenerated \
+             every time the Node starts, so you may change this freely between runs [default: curve25519]"
+        );
         assert_eq!(
             DATA_DIRECTORY_HELP,
             "Directory in which the Node will store its persistent state, including at \
@@ -735,6 +1258,14 @@ mod tests {
              If you have supplied an earning wallet address before, either don't supply it again or be \
              careful to supply exactly the same one you supplied before."
         );
+        assert_eq!(
+            EXTERNAL_SIGNER_URL_HELP,
+            "The URL of an external signer, such as Clef, that holds the private key of the wallet named by \
+             --consuming-wallet-address. The Node asks it to sign each payment over JSON-RPC (account_signTransaction) and \
+             never sees the key. Run the signer on the same machine or a trusted network, because the Node sends it \
+             unencrypted requests. A Node whose consuming wallet is signed for externally can't prove to the Nodes on its \
+             routes that it will pay them, so it doesn't originate routes longer than one hop."
+        );
         assert_eq!(
             IP_ADDRESS_HELP,
             "The public IP address of your MASQ Node: that is, the IPv4 \
@@ -743,6 +1274,30 @@ mod tests {
              it's a local address rather than a public address, and other Nodes won't be able to see yours. \
              --ip is meaningless except in --neighborhood-mode standard."
         );
+        assert_eq!(
+            LEDGER_RETENTION_DAYS_HELP,
+            "How many days the Node keeps entries in its ledger of services and payments. Older entries, \
+             including the daily summaries produced by --ledger-rollup-days, are deleted. Must be between 1 and 3650; \
+             if you leave it out, the ledger is never trimmed."
+        );
+        assert_eq!(
+            LEDGER_ROLLUP_DAYS_HELP,
+            "How many days the Node keeps every single service entry in its ledger of services and payments. \
+             Older service entries are combined into one summary per wallet, service and day, which keeps the \
+             ledger small without losing the totals. Blockchain payments are never combined. Must be between 1 and \
+             3650 [default: 30]"
+        );
+        assert_eq!(
+            LOG_COMPRESSION_HELP,
+            "Whether the Node compresses its rotated logfiles into zip archives. Say 'off' if your log pipeline \
+             wants to read the rotated files directly [default: on]"
+        );
+        assert_eq!(
+            LOG_FORMAT_HELP,
+            "The format of the lines in the Node's logfile. 'text' is the traditional human-readable format. 'json' \
+             writes one JSON object per line with the fields timestamp, level, thread, component and message, so that \
+             log collectors can read it without parsing text [default: text]"
+        );
         assert_eq!(
             LOG_LEVEL_HELP,
             "The minimum severity of the logs that should appear in the Node's logfile. You should probably not specify \
@@ -750,6 +1305,21 @@ mod tests {
              generates a lot of log traffic. This will both consume your disk space and degrade your Node's performance. \
              You should probably not specify a level higher than the default unless you have security concerns about \
              persistent logs being kept on your computer: if your Node crashes, it's good to know why.");
+        assert_eq!(
+            LOG_RETENTION_HELP,
+            "How many rotated logfiles the Node keeps beside the current one. When there are more, the oldest ones are \
+             deleted. Must be between 1 and 1000 [default: 50]"
+        );
+        assert_eq!(
+            LOG_ROTATION_AGE_HELP,
+            "Besides rotating its logfile when it gets too big, the Node can also start a new logfile every hour or \
+             every day. Say 'never', 'hourly' or 'daily' here [default: never]"
+        );
+        assert_eq!(
+            LOG_ROTATION_SIZE_HELP,
+            "How many megabytes the Node writes to its logfile before it moves the logfile aside and starts a new one. \
+             Must be between 1 and 10000 [default: 100]"
+        );
         assert_eq!(
             NEIGHBORS_HELP,
             "One or more Node descriptors for running Nodes in the MASQ \
@@ -801,6 +1371,15 @@ mod tests {
              it will remember the protocol it used, and on its next run it will try that protocol first, unless \
              you specify a different protocol on the command line."
         );
+        assert_eq!(
+            METRICS_PORT_HELP,
+            "If specified, the Node serves operational metrics in the Prometheus text format at \
+             http://127.0.0.1:<METRICS-PORT>/metrics: active streams, bytes relayed and exited, Gossip by handler, route \
+             query latency, scan durations and outcomes, payable and receivable totals, and the connection stage. The \
+             endpoint listens only on localhost and, unless --ui-authentication is off, demands a token from the UI token \
+             file in an X-MASQ-UI-Token or 'Authorization: Bearer' header. Must be between 1025 and 65535 \
+             [default: no metrics endpoint]"
+        );
         assert_eq!(
             MIN_HOPS_HELP,
             "The Node is a system that routes data through multiple Nodes to enhance security and privacy. \
@@ -823,6 +1402,30 @@ mod tests {
              '--min-hops' parameter. For example, '--min-hops 4' would require at least 4 hops. If you fail \
              to provide this argument, the system will default to a minimum hops count of 3."
         );
+        assert_eq!(
+            MULTISEND_CONTRACT_HELP,
+            "The address of a Disperse-compatible multisend contract on the chain the Node pays on. When supplied, the \
+             Node pays the creditors of each payment cycle together in as few transactions through this contract as \
+             --multisend-max-creditors allows, rather than in one transaction per creditor, saving on fees. The consuming \
+             wallet must first approve the contract to spend its MASQ tokens; while the allowance is too low for a cycle, \
+             the Node pays each creditor by a transfer of its own."
+        );
+        assert_eq!(
+            MULTISEND_MAX_CREDITORS_HELP,
+            "The most creditors the Node pays in one transaction through the --multisend-contract. Each creditor adds to \
+             the gas the transaction needs, and a transaction exceeding the block gas limit of the chain would never be \
+             mined, so a payment cycle with more creditors is split into several transactions. Defaults to 100."
+        );
+        assert_eq!(
+            PRICING_STRATEGY_HELP,
+            "Lets your Node adjust the rate pack it advertises while it runs, within bounds set around the \
+             --rate-pack you configured. 'fixed' always charges the configured rate pack. 'load' charges more the more \
+             streams your Node is serving as an exit. 'market' follows the median rate pack of the other Nodes your Node \
+             knows about. Add the lower and upper bounds as percentages of the configured rate pack, separated by '|', \
+             like this: 'load|50|200'. The lower bound may not be above 100 nor the upper bound below 100. Your Node \
+             reconsiders its prices every five minutes and tells its neighbors only about moves of 10% or more. \
+             [default: fixed|50|200]"
+        );
         assert_eq!(
             REAL_USER_HELP,
             "The user whose identity Node will assume when dropping privileges after bootstrapping. Since Node refuses to \
@@ -830,6 +1433,37 @@ mod tests {
              you start the Node using pkexec or some other method that doesn't populate the SUDO_xxx variables. Use a value \
              like <uid>:<gid>:<home directory>."
         );
+        assert_eq!(
+            RELAY_BATCH_LATENCY_HELP,
+            "If specified, the Node holds each CORES package it relays for other Nodes for a random interval of up to this \
+             many milliseconds, then sends the packages that are due in shuffled batches, so that an observer can't match the \
+             packages leaving your Node with the ones arriving. Bigger values hide traffic better, but make routes through \
+             your Node slower. The Node logs how much delay it has actually added every few minutes. Must be between 10 and \
+             5000 [default: no batching]"
+        );
+        assert_eq!(
+            STUCK_PAYABLE_SEC_HELP,
+            "A payment transaction that hasn't been mined for this many seconds is considered stuck, and the Node \
+             replaces it with one of the same nonce but fees raised by at least 10%, so that it is more attractive to the \
+             miners; whichever of them gets mined settles the debt. The same time then goes by before the next replacement. \
+             The fees are never raised above your gas-price, so if the Node warns you it can't replace a transaction, you may \
+             want to raise gas-price. Must be between 60 and 21600 [default: 1800]"
+        );
+        assert_eq!(
+            UI_TOKEN_FILE_HELP,
+            "A file holding the tokens that user interfaces must present to connect to the UI port: one line \
+             'admin=<TOKEN>' for full control and one line 'read-only=<TOKEN>' for requests that only read. If the file \
+             doesn't exist, it is created with random tokens and made readable only by the real user; you can also write \
+             your own secrets into it beforehand, as long as no other user may read or write it. masq finds the tokens by \
+             itself in the default file [default: ui_tokens in the MASQ directory that holds the data directories of all \
+             the chains]"
+        );
+        assert_eq!(
+            UI_AUTHENTICATION_HELP,
+            "Whether user interfaces must authenticate with a token from the UI token file to connect to the UI port. \
+             With 'off', any process on this machine can connect with full control; only use it when nothing else runs \
+             here [default: on]"
+        );
 
         assert_eq!(
             DEFAULT_UI_PORT_VALUE.to_string(),
@@ -859,6 +1493,8 @@ mod tests {
             GAS_PRICE_HELP.to_string(),
             format!(
                 "The Gas Price is the amount of gwei you will pay per unit of gas used in a transaction. \
+                 On chains with an EIP-1559 fee market, it is the ceiling for the max fee per gas, which \
+                 is otherwise derived from the fees paid in recent blocks. \
                  If left unspecified, MASQ Node will use the previously stored value (Default {}).",
                 DEFAULT_GAS_PRICE
             )
@@ -990,6 +1626,34 @@ mod tests {
         assert_eq!(Err(String::from("65536")), result);
     }
 
+    #[test]
+    fn validate_chain_accepts_built_in_chains() {
+        official_chain_names().iter().for_each(|name| {
+            assert_eq!(common_validators::validate_chain(name.to_string()), Ok(()))
+        })
+    }
+
+    #[test]
+    fn validate_chain_rejects_unknown_chains() {
+        let result = common_validators::validate_chain("bitcoin".to_string());
+
+        assert_eq!(result, Err("bitcoin".to_string()));
+    }
+
+    #[test]
+    fn only_the_ui_schema_lets_unknown_chains_through() {
+        let args = vec!["test", "--chain", "my-private-chain"];
+
+        let node_result = shared_app(App::new("test")).get_matches_from_safe(args.clone());
+        let ui_result = ui_shared_app(App::new("test")).get_matches_from_safe(args);
+
+        assert_eq!(node_result.err().unwrap().kind, ErrorKind::ValueValidation);
+        assert_eq!(
+            ui_result.unwrap().value_of("chain"),
+            Some("my-private-chain")
+        );
+    }
+
     #[test]
     fn validate_ui_port_works() {
         let result = common_validators::validate_ui_port(String::from("5335"));
@@ -1104,6 +1768,195 @@ mod tests {
         )
     }
 
+    #[test]
+    fn validate_clandestine_port_count_accepts_one_through_eight() {
+        let results = ["1", "4", "8"]
+            .iter()
+            .map(|count| common_validators::validate_clandestine_port_count(count.to_string()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(results, vec![Ok(()), Ok(()), Ok(())])
+    }
+
+    #[test]
+    fn validate_clandestine_port_count_rejects_everything_else() {
+        let results = ["0", "9", "-1", "booga"]
+            .iter()
+            .map(|count| common_validators::validate_clandestine_port_count(count.to_string()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            results,
+            vec![
+                Err("0".to_string()),
+                Err("9".to_string()),
+                Err("-1".to_string()),
+                Err("booga".to_string())
+            ]
+        )
+    }
+
+    #[test]
+    fn validate_clandestine_port_rotation_requires_at_least_a_minute() {
+        let results = ["60", "3600", "59", "0", "booga"]
+            .iter()
+            .map(|seconds| {
+                common_validators::validate_clandestine_port_rotation(seconds.to_string())
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            results,
+            vec![
+                Ok(()),
+                Ok(()),
+                Err("59".to_string()),
+                Err("0".to_string()),
+                Err("booga".to_string())
+            ]
+        )
+    }
+
+    #[test]
+    fn validate_relay_batch_latency_accepts_ten_through_five_thousand() {
+        let results = ["10", "250", "5000", "9", "5001", "booga"]
+            .iter()
+            .map(|millis| common_validators::validate_relay_batch_latency(millis.to_string()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            results,
+            vec![
+                Ok(()),
+                Ok(()),
+                Ok(()),
+                Err("9".to_string()),
+                Err("5001".to_string()),
+                Err("booga".to_string())
+            ]
+        )
+    }
+
+    #[test]
+    fn validate_confirmation_depth_accepts_one_through_one_thousand() {
+        let results = ["1", "12", "1000", "0", "1001", "booga"]
+            .iter()
+            .map(|blocks| common_validators::validate_confirmation_depth(blocks.to_string()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            results,
+            vec![
+                Ok(()),
+                Ok(()),
+                Ok(()),
+                Err("0".to_string()),
+                Err("1001".to_string()),
+                Err("booga".to_string())
+            ]
+        )
+    }
+
+    #[test]
+    fn validate_ledger_days_accepts_one_through_three_thousand_six_hundred_fifty() {
+        let results = ["1", "30", "3650", "0", "3651", "booga"]
+            .iter()
+            .map(|days| common_validators::validate_ledger_days(days.to_string()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            results,
+            vec![
+                Ok(()),
+                Ok(()),
+                Ok(()),
+                Err("0".to_string()),
+                Err("3651".to_string()),
+                Err("booga".to_string())
+            ]
+        )
+    }
+
+    #[test]
+    fn validate_log_retention_accepts_one_through_one_thousand() {
+        let results = ["1", "50", "1000", "0", "1001", "booga"]
+            .iter()
+            .map(|files| common_validators::validate_log_retention(files.to_string()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            results,
+            vec![
+                Ok(()),
+                Ok(()),
+                Ok(()),
+                Err("0".to_string()),
+                Err("1001".to_string()),
+                Err("booga".to_string())
+            ]
+        )
+    }
+
+    #[test]
+    fn validate_log_rotation_size_accepts_one_through_ten_thousand() {
+        let results = ["1", "100", "10000", "0", "10001", "booga"]
+            .iter()
+            .map(|megabytes| common_validators::validate_log_rotation_size(megabytes.to_string()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            results,
+            vec![
+                Ok(()),
+                Ok(()),
+                Ok(()),
+                Err("0".to_string()),
+                Err("10001".to_string()),
+                Err("booga".to_string())
+            ]
+        )
+    }
+
+    #[test]
+    fn validate_balance_warning_days_accepts_zero_through_three_hundred_sixty_five() {
+        let results = ["0", "3", "365", "366", "-1", "booga"]
+            .iter()
+            .map(|days| common_validators::validate_balance_warning_days(days.to_string()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            results,
+            vec![
+                Ok(()),
+                Ok(()),
+                Ok(()),
+                Err("366".to_string()),
+                Err("-1".to_string()),
+                Err("booga".to_string())
+            ]
+        )
+    }
+
+    #[test]
+    fn validate_stuck_payable_sec_accepts_sixty_through_twenty_one_thousand_six_hundred() {
+        let results = ["60", "1800", "21600", "59", "21601", "booga"]
+            .iter()
+            .map(|seconds| common_validators::validate_stuck_payable_sec(seconds.to_string()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            results,
+            vec![
+                Ok(()),
+                Ok(()),
+                Ok(()),
+                Err("59".to_string()),
+                Err("21601".to_string()),
+                Err("booga".to_string())
+            ]
+        )
+    }
+
     #[test]
     fn validate_non_zero_u16_happy_path() {
         let result = validate_non_zero_u16("456".to_string());
@@ -2,6 +2,7 @@
 
 use crate::messages::{FromMessageBody, ToMessageBody, UiMessageError};
 use crate::test_utils::ui_connection::ReceiveResult::{Correct, MarshalError, TransactionError};
+use crate::ui_auth::ui_token_headers;
 use crate::ui_gateway::MessagePath::Conversation;
 use crate::ui_gateway::MessageTarget::ClientId;
 use crate::ui_gateway::NodeToUiMessage;
@@ -19,12 +20,27 @@ pub struct UiConnection {
 
 impl UiConnection {
     pub fn make(port: u16, protocol: &str) -> Result<UiConnection, String> {
-        let client_builder =
+        Self::make_with_token_opt(port, protocol, None)
+    }
+
+    pub fn make_with_token(port: u16, protocol: &str, token: &str) -> Result<UiConnection, String> {
+        Self::make_with_token_opt(port, protocol, Some(token))
+    }
+
+    fn make_with_token_opt(
+        port: u16,
+        protocol: &str,
+        token_opt: Option<&str>,
+    ) -> Result<UiConnection, String> {
+        let mut client_builder =
             match ClientBuilder::new(format!("ws://{}:{}", localhost(), port).as_str()) {
-                Ok(cb) => cb,
+                Ok(cb) => cb.add_protocol(protocol),
                 Err(e) => return Err(format!("{:?}", e)),
             };
-        let client = match client_builder.add_protocol(protocol).connect_insecure() {
+        if let Some(token) = token_opt {
+            client_builder = client_builder.custom_headers(&ui_token_headers(token));
+        }
+        let client = match client_builder.connect_insecure() {
             Ok(c) => c,
             Err(e) => return Err(format!("{:?}", e)),
         };
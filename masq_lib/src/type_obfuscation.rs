@@ -1,35 +1,24 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use std::any::TypeId;
-use std::mem::transmute;
+use std::any::{Any, TypeId};
+use std::fmt::Debug;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+// Hides the type of a vector from the code that only passes it on, while the data stays
+// a proper vector underneath, so it can be cloned, compared and dropped as such
+#[derive(Debug)]
 pub struct Obfuscated {
     type_id: TypeId,
-    bytes: Vec<u8>,
+    data: Box<dyn ObfuscatedVector>,
 }
 
 impl Obfuscated {
-    // Although we're asking the compiler for a cast between two types
-    // where one is generic and both could possibly be of a different
-    // size, which almost applies to an unsupported kind of operation,
-    // the compiler stays calm here. The use of vectors at the input as
-    // well as output lets us avoid the above depicted situation.
-    //
-    // If you wish to write an implementation allowing more arbitrary
-    // types on your own, instead of helping yourself by a library like
-    // 'bytemuck', consider these functions from the std library,
-    // 'mem::transmute_copy' or 'mem::forget()', which will renew
-    // the compiler's trust for you. However, the true adventure will
-    // begin when you are supposed to write code to realign the plain
-    // bytes backwards to your desired type...
-
-    pub fn obfuscate_vector<D: 'static>(data: Vec<D>) -> Obfuscated {
-        let bytes = unsafe { transmute::<Vec<D>, Vec<u8>>(data) };
-
+    pub fn obfuscate_vector<D>(data: Vec<D>) -> Obfuscated
+    where
+        D: Clone + PartialEq + Debug + Send + Sync + 'static,
+    {
         Obfuscated {
             type_id: TypeId::of::<D>(),
-            bytes,
+            data: Box::new(data),
         }
     }
 
@@ -38,23 +27,57 @@ impl Obfuscated {
             panic!("Forbidden! You're trying to interpret obfuscated data as the wrong type.")
         }
 
-        unsafe { transmute::<Vec<u8>, Vec<D>>(self.bytes) }
+        *self
+            .data
+            .into_any()
+            .downcast::<Vec<D>>()
+            .expect("type id matched but the vector didn't")
+    }
+}
+
+impl Clone for Obfuscated {
+    fn clone(&self) -> Self {
+        Obfuscated {
+            type_id: self.type_id,
+            data: self.data.clone_boxed(),
+        }
+    }
+}
+
+impl PartialEq for Obfuscated {
+    fn eq(&self, other: &Self) -> bool {
+        self.type_id == other.type_id && self.data.equals(other.data.as_ref())
+    }
+}
+
+impl Eq for Obfuscated {}
+
+trait ObfuscatedVector: Debug + Send + Sync {
+    fn clone_boxed(&self) -> Box<dyn ObfuscatedVector>;
+    fn equals(&self, other: &dyn ObfuscatedVector) -> bool;
+    fn as_any(&self) -> &dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<D> ObfuscatedVector for Vec<D>
+where
+    D: Clone + PartialEq + Debug + Send + Sync + 'static,
+{
+    fn clone_boxed(&self) -> Box<dyn ObfuscatedVector> {
+        Box::new(self.clone())
+    }
+
+    fn equals(&self, other: &dyn ObfuscatedVector) -> bool {
+        other.as_any().downcast_ref::<Vec<D>>() == Some(self)
     }
 
-    // Proper casting from a non vec structure into a vector of bytes
-    // is difficult and ideally requires an involvement of a library
-    // like bytemuck.
-    // If you think we do need such cast, place other methods in here
-    // and don't remove the ones above because:
-    //    a) bytemuck will force you to implement its 'Pod' trait which
-    //       might imply an (at minimum) ugly implementation for a std
-    //       type like a Vec because both the trait and the type have
-    //       their definitions situated externally to our project,
-    //       therefore you might need to solve it by introducing
-    //       a super-trait from our code
-    //    b) using our simple 'obfuscate_vector' function will always
-    //       be fairly more efficient than if done with help of
-    //       the other library
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
 }
 
 #[cfg(test)]
@@ -80,4 +103,21 @@ mod tests {
         let obfuscated_data = Obfuscated::obfuscate_vector(data.clone());
         let _: Vec<u128> = obfuscated_data.expose_vector();
     }
+
+    #[test]
+    fn obfuscated_data_can_be_cloned_and_compared() {
+        let data = vec!["first".to_string(), "second".to_string()];
+        let obfuscated_data = Obfuscated::obfuscate_vector(data.clone());
+
+        let clone = obfuscated_data.clone();
+
+        assert_eq!(clone, obfuscated_data);
+        assert_ne!(
+            clone,
+            Obfuscated::obfuscate_vector(vec!["first".to_string()])
+        );
+        assert_ne!(clone, Obfuscated::obfuscate_vector(vec![1_u8, 2]));
+        drop(obfuscated_data);
+        assert_eq!(clone.expose_vector::<String>(), data)
+    }
 }
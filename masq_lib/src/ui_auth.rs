@@ -0,0 +1,311 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use dirs::data_local_dir;
+use std::fs;
+use std::path::{Path, PathBuf};
+use websocket::header::Headers;
+
+pub const UI_TOKEN_HEADER: &str = "X-MASQ-UI-Token";
+pub const UI_TOKEN_FILE_NAME: &str = "ui_tokens";
+pub const ADMIN_TOKEN_KEY: &str = "admin";
+pub const READ_ONLY_TOKEN_KEY: &str = "read-only";
+
+// Requests a read-only client may make: none of them changes the Node's state or
+// reveals secrets that are protected by the database password.
+pub const READ_ONLY_OPCODES: &[&str] = &[
+    "banList",
+    "connectionStatus",
+    "crashHistory",
+    "crashReport",
+    "descriptor",
+    "financials",
+    "ledger",
+    "reconciliation",
+    "simulateThresholds",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UiPermission {
+    ReadOnly,
+    Admin,
+}
+
+impl UiPermission {
+    pub fn permits(self, opcode: &str) -> bool {
+        match self {
+            UiPermission::Admin => true,
+            UiPermission::ReadOnly => READ_ONLY_OPCODES.contains(&opcode),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UiTokens {
+    pub admin: String,
+    pub read_only: String,
+}
+
+impl UiTokens {
+    pub fn new(admin: &str, read_only: &str) -> Self {
+        Self {
+            admin: admin.to_string(),
+            read_only: read_only.to_string(),
+        }
+    }
+
+    pub fn read_from(path: &Path) -> Result<UiTokens, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Couldn't read UI token file {:?}: {}", path, e))?;
+        Self::parse(&contents).map_err(|e| format!("UI token file {:?} {}", path, e))
+    }
+
+    pub fn parse(contents: &str) -> Result<UiTokens, String> {
+        let mut admin_opt = None;
+        let mut read_only_opt = None;
+        for line in contents
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        {
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => return Err(format!("contains a line without '=': '{}'", line)),
+            };
+            if value.is_empty() {
+                return Err(format!("has an empty '{}' token", key));
+            }
+            match key {
+                ADMIN_TOKEN_KEY => admin_opt = Some(value.to_string()),
+                READ_ONLY_TOKEN_KEY => read_only_opt = Some(value.to_string()),
+                _ => return Err(format!("contains an unknown key '{}'", key)),
+            }
+        }
+        match (admin_opt, read_only_opt) {
+            (Some(admin), Some(read_only)) if admin == read_only => {
+                Err("uses the same token for admin and read-only access".to_string())
+            }
+            (Some(admin), Some(read_only)) => Ok(UiTokens { admin, read_only }),
+            (None, _) => Err(format!("has no '{}' token", ADMIN_TOKEN_KEY)),
+            (_, None) => Err(format!("has no '{}' token", READ_ONLY_TOKEN_KEY)),
+        }
+    }
+
+    pub fn file_contents(&self) -> String {
+        format!(
+            "{}={}\n{}={}\n",
+            ADMIN_TOKEN_KEY, self.admin, READ_ONLY_TOKEN_KEY, self.read_only
+        )
+    }
+
+    pub fn permission_of(&self, token: &str) -> Option<UiPermission> {
+        if constant_time_eq(token, &self.admin) {
+            Some(UiPermission::Admin)
+        } else if constant_time_eq(token, &self.read_only) {
+            Some(UiPermission::ReadOnly)
+        } else {
+            None
+        }
+    }
+}
+
+// Where the Daemon and the Node keep their tokens unless they're told otherwise: beside the data
+// directories of all the chains, so that a Node on any chain shares them with the Daemon
+pub fn ui_token_file_in(masq_directory: &Path) -> PathBuf {
+    masq_directory.join(UI_TOKEN_FILE_NAME)
+}
+
+// masq runs as the real user, so its own MASQ directory is the one the Daemon and the Node use
+pub fn default_ui_token_file_opt() -> Option<PathBuf> {
+    data_local_dir().map(|dir| ui_token_file_in(&dir.join("MASQ")))
+}
+
+pub fn ui_token_headers(token: &str) -> Headers {
+    let mut headers = Headers::new();
+    headers.set_raw(UI_TOKEN_HEADER, vec![token.as_bytes().to_vec()]);
+    headers
+}
+
+// Comparison time doesn't depend on where the first mismatch is, so a local process can't
+// guess a token a character at a time by timing rejected handshakes.
+fn constant_time_eq(candidate: &str, expected: &str) -> bool {
+    let candidate = candidate.as_bytes();
+    let expected = expected.as_bytes();
+    if candidate.len() != expected.len() {
+        return false;
+    }
+    candidate
+        .iter()
+        .zip(expected.iter())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::utils::ensure_node_home_directory_exists;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(UI_TOKEN_HEADER, "X-MASQ-UI-Token");
+        assert_eq!(UI_TOKEN_FILE_NAME, "ui_tokens");
+        assert_eq!(ADMIN_TOKEN_KEY, "admin");
+        assert_eq!(READ_ONLY_TOKEN_KEY, "read-only");
+    }
+
+    #[test]
+    fn admin_permission_permits_everything() {
+        vec![
+            "shutdown",
+            "setConfiguration",
+            "recoverWallets",
+            "descriptor",
+        ]
+        .into_iter()
+        .for_each(|opcode| assert!(UiPermission::Admin.permits(opcode)));
+    }
+
+    #[test]
+    fn read_only_permission_permits_only_read_only_opcodes() {
+        vec![
+            "descriptor",
+            "financials",
+            "connectionStatus",
+            "crashHistory",
+        ]
+        .into_iter()
+        .for_each(|opcode| assert!(UiPermission::ReadOnly.permits(opcode)));
+        vec![
+            "shutdown",
+            "setConfiguration",
+            "recoverWallets",
+            "setup",
+            "start",
+            "crash",
+            "configuration",
+            "walletAddresses",
+            "checkPassword",
+        ]
+        .into_iter()
+        .for_each(|opcode| assert!(!UiPermission::ReadOnly.permits(opcode)));
+    }
+
+    #[test]
+    fn file_contents_round_trip_through_parse() {
+        let subject = UiTokens::new("booga", "wooga");
+
+        let result = UiTokens::parse(&subject.file_contents());
+
+        assert_eq!(result, Ok(subject));
+    }
+
+    #[test]
+    fn parse_tolerates_comments_blank_lines_and_whitespace() {
+        let contents = "# my own secrets\n\n  read-only = wooga \nadmin=booga\n";
+
+        let result = UiTokens::parse(contents);
+
+        assert_eq!(result, Ok(UiTokens::new("booga", "wooga")));
+    }
+
+    #[test]
+    fn parse_complains_about_malformed_contents() {
+        vec![
+            ("admin=booga", "has no 'read-only' token"),
+            ("read-only=wooga", "has no 'admin' token"),
+            (
+                "admin=booga\nread-only=booga",
+                "uses the same token for admin and read-only access",
+            ),
+            ("admin=booga\nread-only=", "has an empty 'read-only' token"),
+            ("admin booga", "contains a line without '=': 'admin booga'"),
+            ("root=booga", "contains an unknown key 'root'"),
+        ]
+        .into_iter()
+        .for_each(|(contents, expected)| {
+            assert_eq!(
+                UiTokens::parse(contents),
+                Err(expected.to_string()),
+                "{}",
+                contents
+            )
+        });
+    }
+
+    #[test]
+    fn read_from_reads_a_token_file() {
+        let home_dir = ensure_node_home_directory_exists("ui_auth", "read_from_reads_a_token_file");
+        let path = home_dir.join("ui_tokens");
+        fs::write(&path, "admin=booga\nread-only=wooga\n").unwrap();
+
+        let result = UiTokens::read_from(&path);
+
+        assert_eq!(result, Ok(UiTokens::new("booga", "wooga")));
+    }
+
+    #[test]
+    fn read_from_complains_about_a_missing_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "ui_auth",
+            "read_from_complains_about_a_missing_file",
+        );
+        let path = home_dir.join("ui_tokens");
+
+        let result = UiTokens::read_from(&path).err().unwrap();
+
+        assert!(
+            result.starts_with(&format!("Couldn't read UI token file {:?}: ", path)),
+            "{}",
+            result
+        );
+    }
+
+    #[test]
+    fn read_from_names_the_file_with_bad_contents() {
+        let home_dir = ensure_node_home_directory_exists(
+            "ui_auth",
+            "read_from_names_the_file_with_bad_contents",
+        );
+        let path = home_dir.join("ui_tokens");
+        fs::write(&path, "admin=booga\n").unwrap();
+
+        let result = UiTokens::read_from(&path);
+
+        assert_eq!(
+            result,
+            Err(format!("UI token file {:?} has no 'read-only' token", path))
+        );
+    }
+
+    #[test]
+    fn ui_token_file_in_puts_the_file_in_the_masq_directory() {
+        let result = ui_token_file_in(Path::new("/home/booga/.local/share/MASQ"));
+
+        assert_eq!(
+            result,
+            PathBuf::from("/home/booga/.local/share/MASQ/ui_tokens")
+        );
+    }
+
+    #[test]
+    fn ui_token_headers_carry_the_token() {
+        let result = ui_token_headers("booga");
+
+        assert_eq!(
+            result.get_raw(UI_TOKEN_HEADER),
+            Some(&[b"booga".to_vec()][..])
+        );
+        assert_eq!(result.len(), 1);
+    }
+
+    #[test]
+    fn permission_of_recognizes_each_token() {
+        let subject = UiTokens::new("booga", "wooga");
+
+        assert_eq!(subject.permission_of("booga"), Some(UiPermission::Admin));
+        assert_eq!(subject.permission_of("wooga"), Some(UiPermission::ReadOnly));
+        assert_eq!(subject.permission_of("boog"), None);
+        assert_eq!(subject.permission_of("boogb"), None);
+        assert_eq!(subject.permission_of(""), None);
+    }
+}
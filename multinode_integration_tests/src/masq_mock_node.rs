@@ -256,16 +256,34 @@ impl MASQMockNode {
         target_key: &PublicKey,
         target_addr: SocketAddr,
     ) -> Result<(), Error> {
+        let data_hunk = self.make_package_data_hunk(
+            transmit_port,
+            package,
+            masquerader,
+            target_key,
+            target_addr,
+        );
+        self.transmit_data(data_hunk)
+    }
+
+    // Produces the exact bytes transmit_package() would send, so that they can be sent again later.
+    pub fn make_package_data_hunk(
+        &self,
+        transmit_port: u16,
+        package: IncipientCoresPackage,
+        masquerader: &dyn Masquerader,
+        target_key: &PublicKey,
+        target_addr: SocketAddr,
+    ) -> DataHunk {
         let (lcp, _) =
             LiveCoresPackage::from_incipient(package, self.signing_cryptde().unwrap()).unwrap();
         let encrypted_data = encodex(self.signing_cryptde().unwrap(), target_key, &lcp).unwrap();
         let masked_data = masquerader.mask(encrypted_data.as_slice()).unwrap();
-        let data_hunk = DataHunk::new(
+        DataHunk::new(
             SocketAddr::new(self.ip_address(), transmit_port),
             target_addr,
             masked_data,
-        );
-        self.transmit_data(data_hunk)
+        )
     }
 
     pub fn transmit_gossip(
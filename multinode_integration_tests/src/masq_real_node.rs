@@ -255,6 +255,8 @@ impl NodeStartupConfig {
         if let Some(ref ui_port) = self.ui_port_opt {
             args.push("--ui-port".to_string());
             args.push(ui_port.to_string());
+            args.push("--ui-authentication".to_string());
+            args.push("off".to_string());
         }
         args
     }
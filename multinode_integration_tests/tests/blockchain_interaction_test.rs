@@ -22,7 +22,15 @@ use multinode_integration_tests_lib::utils::{
     UrlHolder,
 };
 use node_lib::accountant::db_access_objects::utils::CustomQuery;
+use node_lib::blockchain::blockchain_interface::blockchain_interface_web3::{
+    BlockchainInterfaceWeb3, REQUESTS_IN_PARALLEL,
+};
+use node_lib::blockchain::blockchain_interface::data_structures::FeeHistory;
+use node_lib::blockchain::blockchain_interface::BlockchainInterface;
 use node_lib::sub_lib::wallet::Wallet;
+use serde_json::json;
+use web3::transports::Http;
+use web3::types::U256;
 
 #[test]
 fn debtors_are_credited_once_but_not_twice() {
@@ -169,6 +177,47 @@ fn blockchain_bridge_starts_properly_on_bootstrap() {
     )
 }
 
+#[test]
+fn fee_history_for_eip1559_transactions_is_fetched_from_blockchain_service() {
+    let cluster = MASQNodeCluster::start().unwrap();
+    let mbcs_port = find_free_port();
+    let blockchain_client_server = MBCSBuilder::new(mbcs_port)
+        .response(
+            json!({
+                "oldestBlock": "0x3e8",
+                "baseFeePerGas": ["0x6fc23ac00", "0x77359400"],
+                "gasUsedRatio": [0.5],
+                "reward": [["0x3b9aca00"]]
+            }),
+            0,
+        )
+        .start();
+    let (event_loop_handle, http) =
+        Http::with_max_parallel(&blockchain_client_server.url(), REQUESTS_IN_PARALLEL).unwrap();
+    let subject = BlockchainInterfaceWeb3::new(http, event_loop_handle, cluster.chain);
+
+    let result = subject.lower_interface().get_fee_history(10, 50.0);
+
+    assert_eq!(
+        result,
+        Ok(FeeHistory {
+            oldest_block: U256::from(1000),
+            base_fee_per_gas: vec![
+                U256::from(30_000_000_000_u64),
+                U256::from(2_000_000_000_u64)
+            ],
+            reward: vec![vec![U256::from(1_000_000_000_u64)]]
+        })
+    );
+    let requests = blockchain_client_server.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(
+        requests[0].contains(r#""method":"eth_feeHistory","params":["0xa","latest",[50.0]]"#),
+        "{}",
+        requests[0]
+    );
+}
+
 #[derive(Serialize)]
 struct LogObject {
     // Strings are all hexadecimal
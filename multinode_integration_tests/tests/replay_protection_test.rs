@@ -0,0 +1,165 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use multinode_integration_tests_lib::masq_mock_node::MASQMockNode;
+use multinode_integration_tests_lib::masq_node::{MASQNode, MASQNodeUtils, PortSelector};
+use multinode_integration_tests_lib::masq_node_cluster::MASQNodeCluster;
+use multinode_integration_tests_lib::masq_real_node::MASQRealNode;
+use multinode_integration_tests_lib::neighborhood_constructor::{
+    construct_neighborhood, do_not_modify_config,
+};
+use node_lib::json_masquerader::JsonMasquerader;
+use node_lib::masquerader::Masquerader;
+use node_lib::neighborhood::gossip::Gossip_0v1;
+use node_lib::neighborhood::neighborhood_database::NeighborhoodDatabase;
+use node_lib::neighborhood::node_record::NodeRecord;
+use node_lib::sub_lib::dispatcher::Component;
+use node_lib::sub_lib::hopper::{IncipientCoresPackage, MessageType};
+use node_lib::sub_lib::neighborhood::GossipFailure_0v1;
+use node_lib::sub_lib::route::{Route, RouteSegment};
+use node_lib::test_utils::neighborhood_test_utils::{db_from_node, make_node_record};
+use std::io;
+use std::time::Duration;
+
+#[test]
+// Given: real_node and mock_node are full neighbors.
+// Given: mock_node sends real_node a CORES package that real_node must relay back to mock_node.
+// When: mock_node sends real_node exactly the same bytes again.
+// Then: real_node relays the first package but discards and logs the replay.
+fn relay_node_discards_replayed_cores_packages() {
+    let mut cluster = MASQNodeCluster::start().unwrap();
+    let (real_node, mock_node) = create_neighborhood(&mut cluster);
+    let masquerader = JsonMasquerader::new();
+    let data_hunk = mock_node.make_package_data_hunk(
+        mock_node.port_list()[0],
+        create_relayed_icp(&mock_node, &real_node, &cluster),
+        &masquerader,
+        real_node.main_public_key(),
+        real_node.socket_addr(PortSelector::First),
+    );
+    mock_node.transmit_data(data_hunk.clone()).unwrap();
+    mock_node
+        .wait_for_package(&masquerader, Duration::from_secs(2))
+        .unwrap();
+
+    mock_node.transmit_data(data_hunk).unwrap();
+
+    ensure_no_further_traffic(&mock_node, &masquerader);
+    MASQNodeUtils::wrote_log_containing(
+        real_node.name(),
+        "Discarding replayed CORES package",
+        Duration::from_secs(2),
+    );
+}
+
+#[test]
+// Given: real_node and mock_node are full neighbors.
+// When: mock_node sends real_node two different CORES packages along the same Route.
+// Then: real_node relays both, since the same Route carrying a new payload is not a replay.
+fn relay_node_relays_new_packages_on_a_reused_route() {
+    let mut cluster = MASQNodeCluster::start().unwrap();
+    let (real_node, mock_node) = create_neighborhood(&mut cluster);
+    let masquerader = JsonMasquerader::new();
+    let route = make_relay_route(&mock_node, &real_node, &cluster);
+    let packages = vec![
+        make_icp(
+            &mock_node,
+            route.clone(),
+            MessageType::Gossip(Gossip_0v1::new(vec![]).into()),
+        ),
+        make_icp(
+            &mock_node,
+            route,
+            MessageType::GossipFailure(GossipFailure_0v1::NoSuitableNeighbors.into()),
+        ),
+    ];
+
+    packages.into_iter().for_each(|package| {
+        mock_node
+            .transmit_package(
+                mock_node.port_list()[0],
+                package,
+                &masquerader,
+                real_node.main_public_key(),
+                real_node.socket_addr(PortSelector::First),
+            )
+            .unwrap();
+        mock_node
+            .wait_for_package(&masquerader, Duration::from_secs(2))
+            .unwrap();
+    });
+}
+
+fn create_neighborhood(cluster: &mut MASQNodeCluster) -> (MASQRealNode, MASQMockNode) {
+    let mut real_node: NodeRecord = make_node_record(1234, true);
+    let mut mock_node: NodeRecord = make_node_record(2345, true);
+    full_neighbor(&mut real_node, &mut mock_node);
+    let mut db: NeighborhoodDatabase = db_from_node(&real_node);
+    full_neighbor(db.root_mut(), &mut mock_node);
+    db.add_node(mock_node.clone()).unwrap();
+    let (_, masq_real_node, mut node_map) =
+        construct_neighborhood(cluster, db, vec![], do_not_modify_config());
+    let masq_mock_node = node_map.remove(mock_node.public_key()).unwrap();
+    (masq_real_node, masq_mock_node)
+}
+
+fn full_neighbor(one: &mut NodeRecord, another: &mut NodeRecord) {
+    one.add_half_neighbor_key(another.public_key().clone())
+        .unwrap();
+    another
+        .add_half_neighbor_key(one.public_key().clone())
+        .unwrap();
+}
+
+fn make_relay_route(
+    mock_node: &MASQMockNode,
+    real_node: &MASQRealNode,
+    cluster: &MASQNodeCluster,
+) -> Route {
+    Route::one_way(
+        RouteSegment::new(
+            vec![
+                mock_node.main_public_key(),
+                real_node.main_public_key(),
+                mock_node.main_public_key(),
+            ],
+            Component::Neighborhood,
+        ),
+        mock_node.main_cryptde_null().unwrap(),
+        mock_node.consuming_wallet(),
+        Some(cluster.chain.rec().contract),
+    )
+    .unwrap()
+}
+
+fn make_icp(mock_node: &MASQMockNode, route: Route, payload: MessageType) -> IncipientCoresPackage {
+    IncipientCoresPackage::new(
+        mock_node.main_cryptde_null().unwrap(),
+        route,
+        payload,
+        mock_node.main_public_key(),
+    )
+    .unwrap()
+}
+
+fn create_relayed_icp(
+    mock_node: &MASQMockNode,
+    real_node: &MASQRealNode,
+    cluster: &MASQNodeCluster,
+) -> IncipientCoresPackage {
+    make_icp(
+        mock_node,
+        make_relay_route(mock_node, real_node, cluster),
+        MessageType::Gossip(Gossip_0v1::new(vec![]).into()),
+    )
+}
+
+fn ensure_no_further_traffic(mock_node: &MASQMockNode, masquerader: &dyn Masquerader) {
+    match mock_node.wait_for_package(masquerader, Duration::from_secs(1)) {
+        Ok((addr1, addr2, lcp)) => panic!(
+            "Should not have received package, but: {:?} -> {:?}:\n{:?}",
+            addr1, addr2, lcp
+        ),
+        Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => (), // expected: pass
+        Err(ref e) => panic!("Unexpected error: {:?}", e),
+    }
+}
@@ -0,0 +1,344 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::accountant::payment_adjuster::saturate_u256;
+use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
+use masq_lib::messages::UiWalletRunway;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_IN_HOUR: u64 = 3_600;
+const HOURS_IN_DAY: u64 = 24;
+// Only the spending of this many recent hours counts toward the forecast
+pub const FORECAST_WINDOW_HOURS: u64 = 24;
+
+// Estimates how long the consuming wallet will last by extrapolating what the Node has recently
+// spent on services against the balances the BlockchainBridge reported last
+pub struct BalanceForecast {
+    observed_since: SystemTime,
+    debt_threshold_wei: u128,
+    // Spending summed per hour since the epoch
+    hourly_spending: Vec<(u64, u128)>,
+    latest_balances_opt: Option<LatestBalances>,
+    warning_issued: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LatestBalances {
+    balances: ConsumingWalletBalances,
+    transaction_fee_per_payment_wei: u128,
+}
+
+impl BalanceForecast {
+    pub fn new(observed_since: SystemTime, debt_threshold_wei: u128) -> Self {
+        Self {
+            observed_since,
+            debt_threshold_wei,
+            hourly_spending: vec![],
+            latest_balances_opt: None,
+            warning_issued: false,
+        }
+    }
+
+    pub fn record_spending(&mut self, timestamp: SystemTime, amount_wei: u128) {
+        let hour = hour_of(timestamp);
+        match self.hourly_spending.iter_mut().find(|(h, _)| *h == hour) {
+            Some((_, sum)) => *sum = sum.saturating_add(amount_wei),
+            None => self.hourly_spending.push((hour, amount_wei)),
+        }
+        self.hourly_spending
+            .retain(|(h, _)| h + FORECAST_WINDOW_HOURS > hour);
+    }
+
+    pub fn update_balances(
+        &mut self,
+        balances: ConsumingWalletBalances,
+        transaction_fee_per_payment_wei: u128,
+    ) {
+        self.latest_balances_opt = Some(LatestBalances {
+            balances,
+            transaction_fee_per_payment_wei,
+        })
+    }
+
+    pub fn latest_balances_opt(&self) -> Option<ConsumingWalletBalances> {
+        self.latest_balances_opt.map(|latest| latest.balances)
+    }
+
+    pub fn daily_spending_wei(&self, now: SystemTime) -> u128 {
+        let now_hour = hour_of(now);
+        let spent = self
+            .hourly_spending
+            .iter()
+            .filter(|(h, _)| *h <= now_hour && h + FORECAST_WINDOW_HOURS > now_hour)
+            .fold(0_u128, |sum, (_, amount)| sum.saturating_add(*amount));
+        // A young Node has watched its spending for less than the whole window
+        let observed_secs = now
+            .duration_since(self.observed_since)
+            .unwrap_or_default()
+            .as_secs()
+            .clamp(SECONDS_IN_HOUR, FORECAST_WINDOW_HOURS * SECONDS_IN_HOUR);
+        spent.saturating_mul((HOURS_IN_DAY * SECONDS_IN_HOUR) as u128) / observed_secs as u128
+    }
+
+    // None until the balances are known; the debts not paid yet are already spoken for
+    pub fn runway(&self, outstanding_debt_wei: u128, now: SystemTime) -> Option<UiWalletRunway> {
+        let latest = self.latest_balances_opt?;
+        let daily_spending_wei = self.daily_spending_wei(now);
+        if daily_spending_wei == 0 {
+            return Some(UiWalletRunway {
+                service_fee_runway_hours_opt: None,
+                transaction_fee_runway_hours_opt: None,
+            });
+        }
+        let service_fee_available_wei =
+            saturate_u256(latest.balances.masq_token_balance_in_minor_units)
+                .saturating_sub(outstanding_debt_wei);
+        let debt_threshold_wei = self.debt_threshold_wei.max(1);
+        let payments_per_day = ((daily_spending_wei - 1) / debt_threshold_wei + 1).max(1);
+        let transaction_fees_per_day_wei =
+            payments_per_day.saturating_mul(latest.transaction_fee_per_payment_wei);
+        Some(UiWalletRunway {
+            service_fee_runway_hours_opt: Some(hours_lasting(
+                service_fee_available_wei,
+                daily_spending_wei,
+            )),
+            transaction_fee_runway_hours_opt: match transaction_fees_per_day_wei {
+                0 => None,
+                per_day => Some(hours_lasting(
+                    saturate_u256(latest.balances.transaction_fee_balance_in_minor_units),
+                    per_day,
+                )),
+            },
+        })
+    }
+
+    // True only when the runway has just become shorter than the limit, so that the UIs aren't
+    // warned again at every check while it stays short
+    pub fn is_newly_short(&mut self, runway: &UiWalletRunway, warning_days: u64) -> bool {
+        let is_short = warning_days > 0
+            && [
+                runway.service_fee_runway_hours_opt,
+                runway.transaction_fee_runway_hours_opt,
+            ]
+            .iter()
+            .flatten()
+            .any(|hours| *hours < warning_days * HOURS_IN_DAY);
+        let is_newly_short = is_short && !self.warning_issued;
+        self.warning_issued = is_short;
+        is_newly_short
+    }
+}
+
+fn hour_of(timestamp: SystemTime) -> u64 {
+    timestamp
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / SECONDS_IN_HOUR
+}
+
+fn hours_lasting(balance_wei: u128, daily_spending_wei: u128) -> u64 {
+    u64::try_from(balance_wei.saturating_mul(HOURS_IN_DAY as u128) / daily_spending_wei)
+        .unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use web3::types::U256;
+
+    const WEI_IN_MASQ: u128 = 1_000_000_000_000_000_000;
+
+    fn at_hour(hour: u64) -> SystemTime {
+        UNIX_EPOCH
+            + Duration::from_secs(1_700_000_000 / SECONDS_IN_HOUR * SECONDS_IN_HOUR)
+            + Duration::from_secs(hour * SECONDS_IN_HOUR)
+    }
+
+    fn an_hour_before(time: SystemTime) -> SystemTime {
+        time - Duration::from_secs(SECONDS_IN_HOUR)
+    }
+
+    fn balances(masq: u128, gas: u128) -> ConsumingWalletBalances {
+        ConsumingWalletBalances::new(U256::from(gas), U256::from(masq))
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(SECONDS_IN_HOUR, 3_600);
+        assert_eq!(HOURS_IN_DAY, 24);
+        assert_eq!(FORECAST_WINDOW_HOURS, 24);
+    }
+
+    #[test]
+    fn spending_of_a_whole_window_is_the_daily_spending() {
+        let mut subject = BalanceForecast::new(an_hour_before(at_hour(0)), WEI_IN_MASQ);
+        (0..24).for_each(|hour| subject.record_spending(at_hour(hour), 1_000));
+
+        let result = subject.daily_spending_wei(at_hour(23));
+
+        assert_eq!(result, 24_000)
+    }
+
+    #[test]
+    fn spending_of_a_young_node_is_extrapolated_to_a_day() {
+        let mut subject = BalanceForecast::new(at_hour(0), WEI_IN_MASQ);
+        subject.record_spending(at_hour(0), 1_000);
+        subject.record_spending(at_hour(5), 2_000);
+
+        let result = subject.daily_spending_wei(at_hour(6));
+
+        assert_eq!(result, 12_000)
+    }
+
+    #[test]
+    fn spending_observed_for_less_than_an_hour_counts_as_an_hour() {
+        let mut subject = BalanceForecast::new(at_hour(0), WEI_IN_MASQ);
+        subject.record_spending(at_hour(0), 1_000);
+
+        let result = subject.daily_spending_wei(at_hour(0) + Duration::from_secs(60));
+
+        assert_eq!(result, 24_000)
+    }
+
+    #[test]
+    fn spending_older_than_the_window_is_forgotten() {
+        let mut subject = BalanceForecast::new(at_hour(0), WEI_IN_MASQ);
+        subject.record_spending(at_hour(0), 1_000_000);
+        subject.record_spending(at_hour(10), 1_000);
+        subject.record_spending(at_hour(30), 2_000);
+
+        let result = subject.daily_spending_wei(at_hour(30));
+
+        assert_eq!(result, 3_000);
+        assert_eq!(
+            subject.hourly_spending,
+            vec![(hour_of(at_hour(10)), 1_000), (hour_of(at_hour(30)), 2_000)]
+        );
+    }
+
+    #[test]
+    fn spending_in_the_same_hour_is_summed_even_if_reported_late() {
+        let mut subject = BalanceForecast::new(at_hour(0), WEI_IN_MASQ);
+        subject.record_spending(at_hour(1), 1_000);
+        subject.record_spending(at_hour(2), 2_000);
+        subject.record_spending(at_hour(1) + Duration::from_secs(10), 500);
+
+        assert_eq!(
+            subject.hourly_spending,
+            vec![(hour_of(at_hour(1)), 1_500), (hour_of(at_hour(2)), 2_000)]
+        );
+    }
+
+    #[test]
+    fn runway_is_unknown_before_the_balances_are() {
+        let mut subject = BalanceForecast::new(at_hour(0), WEI_IN_MASQ);
+        subject.record_spending(at_hour(0), 1_000);
+
+        let result = subject.runway(0, at_hour(1));
+
+        assert_eq!(result, None);
+        assert_eq!(subject.latest_balances_opt(), None)
+    }
+
+    #[test]
+    fn runway_is_unlimited_without_spending() {
+        let mut subject = BalanceForecast::new(at_hour(0), WEI_IN_MASQ);
+        subject.update_balances(balances(WEI_IN_MASQ, 1_000), 100);
+
+        let result = subject.runway(0, at_hour(30));
+
+        assert_eq!(
+            result,
+            Some(UiWalletRunway {
+                service_fee_runway_hours_opt: None,
+                transaction_fee_runway_hours_opt: None,
+            })
+        );
+        assert_eq!(
+            subject.latest_balances_opt(),
+            Some(balances(WEI_IN_MASQ, 1_000))
+        )
+    }
+
+    #[test]
+    fn runway_is_computed_from_the_balances_and_the_daily_spending() {
+        let mut subject = BalanceForecast::new(an_hour_before(at_hour(0)), 10 * WEI_IN_MASQ);
+        (0..24).for_each(|hour| subject.record_spending(at_hour(hour), WEI_IN_MASQ));
+        subject.update_balances(balances(120 * WEI_IN_MASQ, 9_000_000), 1_000_000);
+
+        let result = subject.runway(24 * WEI_IN_MASQ, at_hour(23));
+
+        // 96 MASQ left after the debts at 24 MASQ a day; three payments of 1,000,000 wei a day
+        assert_eq!(
+            result,
+            Some(UiWalletRunway {
+                service_fee_runway_hours_opt: Some(96),
+                transaction_fee_runway_hours_opt: Some(72),
+            })
+        )
+    }
+
+    #[test]
+    fn runway_counts_at_least_one_payment_a_day() {
+        let mut subject = BalanceForecast::new(at_hour(0), 10 * WEI_IN_MASQ);
+        subject.record_spending(at_hour(0), 1_000);
+        subject.update_balances(balances(WEI_IN_MASQ, 2_000_000), 1_000_000);
+
+        let result = subject.runway(0, at_hour(24) - Duration::from_secs(1));
+
+        assert_eq!(result.unwrap().transaction_fee_runway_hours_opt, Some(48))
+    }
+
+    #[test]
+    fn runway_is_zero_when_the_debts_exceed_the_balance() {
+        let mut subject = BalanceForecast::new(at_hour(0), WEI_IN_MASQ);
+        subject.record_spending(at_hour(0), 1_000);
+        subject.update_balances(balances(5_000, 0), 0);
+
+        let result = subject.runway(6_000, at_hour(1));
+
+        assert_eq!(
+            result,
+            Some(UiWalletRunway {
+                service_fee_runway_hours_opt: Some(0),
+                transaction_fee_runway_hours_opt: None,
+            })
+        )
+    }
+
+    #[test]
+    fn is_newly_short_fires_once_until_the_runway_recovers() {
+        let mut subject = BalanceForecast::new(at_hour(0), WEI_IN_MASQ);
+        let short = UiWalletRunway {
+            service_fee_runway_hours_opt: Some(100),
+            transaction_fee_runway_hours_opt: Some(71),
+        };
+        let long = UiWalletRunway {
+            service_fee_runway_hours_opt: Some(72),
+            transaction_fee_runway_hours_opt: None,
+        };
+
+        let results = vec![
+            subject.is_newly_short(&short, 3),
+            subject.is_newly_short(&short, 3),
+            subject.is_newly_short(&long, 3),
+            subject.is_newly_short(&short, 3),
+        ];
+
+        assert_eq!(results, vec![true, false, false, true])
+    }
+
+    #[test]
+    fn is_newly_short_never_fires_when_warnings_are_off() {
+        let mut subject = BalanceForecast::new(at_hour(0), WEI_IN_MASQ);
+        let empty = UiWalletRunway {
+            service_fee_runway_hours_opt: Some(0),
+            transaction_fee_runway_hours_opt: Some(0),
+        };
+
+        let result = subject.is_newly_short(&empty, 0);
+
+        assert_eq!(result, false)
+    }
+}
@@ -1,12 +1,15 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use crate::accountant::db_access_objects::utils::{DaoFactoryReal, VigilantRusqliteFlatten};
+use crate::accountant::db_access_objects::utils::{
+    from_time_t, to_time_t, DaoFactoryReal, VigilantRusqliteFlatten,
+};
 use crate::database::rusqlite_wrappers::ConnectionWrapper;
 use crate::sub_lib::wallet::Wallet;
 use lazy_static::lazy_static;
 use rusqlite::{Error, ErrorCode, ToSql};
 use std::collections::HashSet;
 use std::sync::RwLock;
+use std::time::SystemTime;
 
 lazy_static! {
     pub static ref BAN_CACHE: BannedCache = BannedCache::default();
@@ -63,10 +66,34 @@ impl BannedCacheLoader for BannedCacheLoaderReal {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BannedAccount {
+    pub wallet: Wallet,
+    pub reason_opt: Option<String>,
+    pub timestamp_opt: Option<SystemTime>,
+    pub manual: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BanOrigin {
+    // Imposed by the receivable scanner and lifted by it once the debt is paid
+    Automatic,
+    // Imposed by the operator and lifted only by the operator
+    Manual,
+}
+
 pub trait BannedDao: Send {
-    fn ban_list(&self) -> Vec<Wallet>;
-    fn ban(&self, wallet: &Wallet);
-    fn unban(&self, wallet: &Wallet);
+    fn ban_list(&self) -> Vec<BannedAccount>;
+    // Returns false if the wallet had been banned already
+    fn ban(
+        &self,
+        wallet: &Wallet,
+        timestamp: SystemTime,
+        reason_opt: Option<String>,
+        origin: BanOrigin,
+    ) -> bool;
+    // Returns false if the wallet had not been banned
+    fn unban(&self, wallet: &Wallet) -> bool;
 }
 
 pub trait BannedDaoFactory {
@@ -87,35 +114,77 @@ impl BannedDaoReal {
     pub fn new(conn: Box<dyn ConnectionWrapper>) -> Self {
         Self { conn }
     }
+
+    fn make_ban_manual(&self, wallet: &Wallet, reason_opt: Option<String>) {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "update banned set manual = 1, reason = coalesce(?, reason) where wallet_address = ?",
+            )
+            .expect("Failed to prepare a statement");
+        let params: &[&dyn ToSql] = &[&reason_opt, &wallet];
+        if let Err(e) = stmt.execute(params) {
+            panic!(
+                "Could not turn the ban for {} into a manual one because of database corruption: {}",
+                wallet, e
+            )
+        }
+    }
 }
 
 impl BannedDao for BannedDaoReal {
-    fn ban_list(&self) -> Vec<Wallet> {
+    fn ban_list(&self) -> Vec<BannedAccount> {
         let mut stmt = self
             .conn
-            .prepare("select wallet_address from banned")
+            .prepare(
+                "select wallet_address, reason, timestamp, manual from banned order by wallet_address",
+            )
             .expect("Failed to prepare a statement");
-        stmt.query_map([], |row| row.get(0))
-            .expect("Couldn't retrieve delinquency-ban list: database corrupt")
-            .vigilant_flatten()
-            .collect()
+        stmt.query_map([], |row| {
+            Ok(BannedAccount {
+                wallet: row.get(0)?,
+                reason_opt: row.get(1)?,
+                timestamp_opt: row.get::<usize, Option<i64>>(2)?.map(from_time_t),
+                manual: row.get::<usize, i64>(3)? != 0,
+            })
+        })
+        .expect("Couldn't retrieve delinquency-ban list: database corrupt")
+        .vigilant_flatten()
+        .collect()
     }
 
-    fn ban(&self, wallet: &Wallet) {
+    fn ban(
+        &self,
+        wallet: &Wallet,
+        timestamp: SystemTime,
+        reason_opt: Option<String>,
+        origin: BanOrigin,
+    ) -> bool {
         if BAN_CACHE.is_banned(wallet) {
-            return;
+            // An operator can take over an automatic ban so that payment doesn't lift it
+            if origin == BanOrigin::Manual {
+                self.make_ban_manual(wallet, reason_opt)
+            }
+            return false;
         }
 
         let mut stmt = self
             .conn
-            .prepare("insert into banned (wallet_address) values (?)")
+            .prepare(
+                "insert into banned (wallet_address, reason, timestamp, manual) values (?, ?, ?, ?)",
+            )
             .expect("Failed to prepare a statement");
-        let params: &[&dyn ToSql] = &[&wallet];
+        let manual = i64::from(origin == BanOrigin::Manual);
+        let params: &[&dyn ToSql] = &[&wallet, &reason_opt, &to_time_t(timestamp), &manual];
         match stmt.execute(params) {
-            Ok(_) => BAN_CACHE.insert(wallet.clone()),
+            Ok(_) => {
+                BAN_CACHE.insert(wallet.clone());
+                true
+            }
             Err(e) => match e {
                 Error::SqliteFailure(e, _) if e.code == ErrorCode::ConstraintViolation => {
-                    BAN_CACHE.insert(wallet.clone())
+                    BAN_CACHE.insert(wallet.clone());
+                    false
                 }
                 _ => panic!(
                     "Could not initiate delinquency ban for {} because of database corruption: {}",
@@ -125,9 +194,9 @@ impl BannedDao for BannedDaoReal {
         }
     }
 
-    fn unban(&self, wallet: &Wallet) {
+    fn unban(&self, wallet: &Wallet) -> bool {
         if !BAN_CACHE.is_banned(wallet) {
-            return;
+            return false;
         }
 
         let mut stmt = self
@@ -136,7 +205,10 @@ impl BannedDao for BannedDaoReal {
             .expect("Failed to prepare a statement");
         let params: &[&dyn ToSql] = &[&wallet];
         match stmt.execute(params) {
-            Ok(_) => BAN_CACHE.remove(wallet),
+            Ok(rows) => {
+                BAN_CACHE.remove(wallet);
+                rows > 0
+            }
             Err(e) => panic!(
                 "Could not terminate delinquency ban for {} because of database corruption: {}",
                 wallet, e
@@ -170,21 +242,30 @@ mod tests {
             BannedDaoReal::new(conn)
         };
 
-        subject.ban(&make_wallet("donalddrumph"));
+        let result = subject.ban(
+            &make_wallet("donalddrumph"),
+            from_time_t(1_700_000_000),
+            Some("Delinquent".to_string()),
+            BanOrigin::Automatic,
+        );
+
+        assert_eq!(result, true);
 
         let conn = db_initializer
             .initialize(&home_dir, DbInitializationConfig::test_default())
             .unwrap();
-        let mut stmt = conn.prepare("select wallet_address from banned").unwrap();
+        let mut stmt = conn
+            .prepare("select wallet_address, reason, timestamp, manual from banned")
+            .unwrap();
         let mut banned_addresses = stmt.query([]).unwrap();
+        let row = banned_addresses.next().unwrap().unwrap();
         assert_eq!(
-            "0x0000000000000000646f6e616c646472756d7068",
-            banned_addresses
-                .next()
-                .unwrap()
-                .unwrap()
-                .get_unwrap::<usize, String>(0)
+            row.get_unwrap::<usize, String>(0),
+            "0x0000000000000000646f6e616c646472756d7068"
         );
+        assert_eq!(row.get_unwrap::<usize, String>(1), "Delinquent");
+        assert_eq!(row.get_unwrap::<usize, i64>(2), 1_700_000_000);
+        assert_eq!(row.get_unwrap::<usize, i64>(3), 0);
     }
 
     #[test]
@@ -198,11 +279,103 @@ mod tests {
             BannedDaoReal::new(conn)
         };
 
-        subject.ban(&make_wallet("no_duplicate_wallets"));
-        subject.ban(&make_wallet("no_duplicate_wallets"));
+        let first_result = subject.ban(
+            &make_wallet("no_duplicate_wallets"),
+            from_time_t(1_000),
+            None,
+            BanOrigin::Automatic,
+        );
+        let second_result = subject.ban(
+            &make_wallet("no_duplicate_wallets"),
+            from_time_t(2_000),
+            None,
+            BanOrigin::Automatic,
+        );
 
+        assert_eq!(first_result, true);
+        assert_eq!(second_result, false);
         let ban_list = subject.ban_list();
-        assert_eq!(vec![make_wallet("no_duplicate_wallets")], ban_list);
+        assert_eq!(
+            ban_list,
+            vec![BannedAccount {
+                wallet: make_wallet("no_duplicate_wallets"),
+                reason_opt: None,
+                timestamp_opt: Some(from_time_t(1_000)),
+                manual: false
+            }]
+        );
+    }
+
+    #[test]
+    fn ban_list_reports_details_of_all_bans_ordered_by_wallet() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "banned_dao",
+            "ban_list_reports_details_of_all_bans_ordered_by_wallet",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        conn.prepare(
+            "insert into banned (wallet_address, reason, timestamp, manual) values \
+            ('0x00000000000000000000000000000000006c6973', 'Spammer', 1500, 1), \
+            ('0x000000000000000000000000000000000000006c', null, null, 0)",
+        )
+        .unwrap()
+        .execute([])
+        .unwrap();
+        let subject = BannedDaoReal::new(conn);
+
+        let result = subject.ban_list();
+
+        assert_eq!(
+            result,
+            vec![
+                BannedAccount {
+                    wallet: make_wallet("l"),
+                    reason_opt: None,
+                    timestamp_opt: None,
+                    manual: false
+                },
+                BannedAccount {
+                    wallet: make_wallet("lis"),
+                    reason_opt: Some("Spammer".to_string()),
+                    timestamp_opt: Some(from_time_t(1500)),
+                    manual: true
+                }
+            ]
+        )
+    }
+
+    #[test]
+    fn manual_ban_takes_over_an_automatic_one() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "banned_dao",
+            "manual_ban_takes_over_an_automatic_one",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = BannedDaoReal::new(conn);
+        let wallet = make_wallet("taken_over_ban");
+        subject.ban(
+            &wallet,
+            from_time_t(3_000),
+            Some("Delinquent".to_string()),
+            BanOrigin::Automatic,
+        );
+
+        let result = subject.ban(&wallet, from_time_t(4_000), None, BanOrigin::Manual);
+
+        assert_eq!(result, false);
+        assert_eq!(
+            subject.ban_list(),
+            vec![BannedAccount {
+                wallet,
+                reason_opt: Some("Delinquent".to_string()),
+                timestamp_opt: Some(from_time_t(3_000)),
+                manual: true
+            }]
+        )
     }
 
     #[test]
@@ -217,7 +390,12 @@ mod tests {
             BannedDaoReal::new(conn)
         };
 
-        subject.ban(&make_wallet("forgot_to_init"));
+        subject.ban(
+            &make_wallet("forgot_to_init"),
+            SystemTime::now(),
+            None,
+            BanOrigin::Automatic,
+        );
     }
 
     #[test]
@@ -239,8 +417,9 @@ mod tests {
         BAN_CACHE.insert(wallet.clone());
         let subject = BannedDaoReal::new(conn);
 
-        subject.unban(wallet);
+        let result = subject.unban(wallet);
 
+        assert_eq!(result, true);
         let conn = db_initializer
             .initialize(&home_dir, DbInitializationConfig::test_default())
             .unwrap();
@@ -263,9 +442,9 @@ mod tests {
             .unwrap();
         let subject = BannedDaoReal::new(conn);
 
-        subject.unban(&make_wallet("hey_im_not_banned"));
+        let result = subject.unban(&make_wallet("hey_im_not_banned"));
 
-        // No panic: test passes
+        assert_eq!(result, false);
     }
 
     #[test]
@@ -309,7 +488,7 @@ mod tests {
         let subject = BannedDaoReal::new(conn);
 
         let ban_me_baby = make_wallet("BAN_ME_BABY");
-        subject.ban(&ban_me_baby.clone());
+        subject.ban(&ban_me_baby, SystemTime::now(), None, BanOrigin::Manual);
 
         assert!(BAN_CACHE.is_banned(&ban_me_baby))
     }
@@ -0,0 +1,604 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::accountant::checked_conversion;
+use crate::accountant::db_access_objects::utils::{
+    from_time_t, to_time_t, DaoFactoryReal, VigilantRusqliteFlatten,
+};
+use crate::accountant::db_big_integer::big_int_divider::BigIntDivider;
+use crate::database::rusqlite_wrappers::{ConnectionWrapper, TransactionSafeWrapper};
+use crate::sub_lib::wallet::Wallet;
+use masq_lib::utils::ExpectValue;
+use rusqlite::{Row, ToSql};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::SystemTime;
+use web3::types::H256;
+
+const SECONDS_IN_DAY: i64 = 86_400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LedgerEntryType {
+    RoutingProvided,
+    ExitProvided,
+    RoutingConsumed,
+    ExitConsumed,
+    PaymentSent,
+    PaymentReceived,
+    PaymentReversed,
+}
+
+impl Display for LedgerEntryType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::RoutingProvided => "routingProvided",
+            Self::ExitProvided => "exitProvided",
+            Self::RoutingConsumed => "routingConsumed",
+            Self::ExitConsumed => "exitConsumed",
+            Self::PaymentSent => "paymentSent",
+            Self::PaymentReceived => "paymentReceived",
+            Self::PaymentReversed => "paymentReversed",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for LedgerEntryType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "routingProvided" => Ok(Self::RoutingProvided),
+            "exitProvided" => Ok(Self::ExitProvided),
+            "routingConsumed" => Ok(Self::RoutingConsumed),
+            "exitConsumed" => Ok(Self::ExitConsumed),
+            "paymentSent" => Ok(Self::PaymentSent),
+            "paymentReceived" => Ok(Self::PaymentReceived),
+            "paymentReversed" => Ok(Self::PaymentReversed),
+            x => Err(format!("Unrecognized ledger entry type: '{}'", x)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerEntry {
+    pub timestamp: SystemTime,
+    pub wallet: Wallet,
+    pub entry_type: LedgerEntryType,
+    pub bytes: u64,
+    pub amount_wei: u128,
+    pub transaction_hash_opt: Option<H256>,
+    //None for single entries; the number of entries summarized by a rolled-up one otherwise
+    pub entry_count_opt: Option<u64>,
+}
+
+impl LedgerEntry {
+    pub fn service(
+        timestamp: SystemTime,
+        wallet: Wallet,
+        entry_type: LedgerEntryType,
+        bytes: u64,
+        amount_wei: u128,
+    ) -> Self {
+        Self {
+            timestamp,
+            wallet,
+            entry_type,
+            bytes,
+            amount_wei,
+            transaction_hash_opt: None,
+            entry_count_opt: None,
+        }
+    }
+
+    pub fn payment(
+        timestamp: SystemTime,
+        wallet: Wallet,
+        entry_type: LedgerEntryType,
+        amount_wei: u128,
+        transaction_hash: H256,
+    ) -> Self {
+        Self {
+            timestamp,
+            wallet,
+            entry_type,
+            bytes: 0,
+            amount_wei,
+            transaction_hash_opt: Some(transaction_hash),
+            entry_count_opt: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LedgerQuery {
+    pub wallet_opt: Option<Wallet>,
+    pub from_opt: Option<SystemTime>,
+    pub to_opt: Option<SystemTime>,
+    pub max_entries_opt: Option<u16>,
+}
+
+pub trait LedgerDao: Send {
+    fn record(&self, entries: &[LedgerEntry]);
+    // Newest first; both ends of the time range are inclusive
+    fn entries(&self, query: &LedgerQuery) -> Vec<LedgerEntry>;
+    // Combines single service entries from whole days preceding the given moment into one entry
+    // per wallet, entry type and day; payments are left alone
+    fn roll_up(&mut self, before: SystemTime);
+    fn delete_older_than(&self, cutoff: SystemTime);
+}
+
+pub trait LedgerDaoFactory {
+    fn make(&self) -> Box<dyn LedgerDao>;
+}
+
+impl LedgerDaoFactory for DaoFactoryReal {
+    fn make(&self) -> Box<dyn LedgerDao> {
+        Box::new(LedgerDaoReal::new(self.make_connection()))
+    }
+}
+
+pub struct LedgerDaoReal {
+    conn: Box<dyn ConnectionWrapper>,
+}
+
+impl LedgerDaoReal {
+    pub fn new(conn: Box<dyn ConnectionWrapper>) -> Self {
+        Self { conn }
+    }
+
+    fn create_ledger_entry(row: &Row) -> rusqlite::Result<LedgerEntry> {
+        let timestamp: i64 = row.get(0).expectv("timestamp");
+        let wallet: Wallet = row.get(1).expectv("wallet address");
+        let entry_type: String = row.get(2).expectv("entry type");
+        let bytes: i64 = row.get(3).expectv("bytes");
+        let amount_high_b: i64 = row.get(4).expectv("amount high bytes");
+        let amount_low_b: i64 = row.get(5).expectv("amount low bytes");
+        let transaction_hash_opt: Option<String> = row.get(6).expectv("transaction hash");
+        let entry_count_opt: Option<i64> = row.get(7).expectv("entry count");
+        Ok(LedgerEntry {
+            timestamp: from_time_t(timestamp),
+            wallet,
+            entry_type: LedgerEntryType::from_str(&entry_type)
+                .expect("entry type inserted right turned wrong"),
+            bytes: checked_conversion::<i64, u64>(bytes),
+            amount_wei: checked_conversion::<i128, u128>(BigIntDivider::reconstitute(
+                amount_high_b,
+                amount_low_b,
+            )),
+            transaction_hash_opt: transaction_hash_opt.map(|hash| {
+                H256::from_str(&hash[2..]).expect("transaction hash inserted right turned wrong")
+            }),
+            entry_count_opt: entry_count_opt.map(checked_conversion::<i64, u64>),
+        })
+    }
+
+    fn roll_up_in_txn(txn: &TransactionSafeWrapper, day_boundary: i64) -> rusqlite::Result<()> {
+        let selection = "transaction_hash is null and entry_count is null and timestamp < ?";
+        let mut summaries: BTreeMap<(i64, String, String), (u64, u128, u64)> = BTreeMap::new();
+        txn.prepare(&format!(
+            "select timestamp, wallet_address, entry_type, bytes, amount_high_b, amount_low_b \
+            from ledger where {}",
+            selection
+        ))?
+        .query_map([day_boundary], |row| {
+            let timestamp: i64 = row.get(0)?;
+            let wallet: String = row.get(1)?;
+            let entry_type: String = row.get(2)?;
+            let bytes: i64 = row.get(3)?;
+            let amount_high_b: i64 = row.get(4)?;
+            let amount_low_b: i64 = row.get(5)?;
+            Ok((
+                (timestamp - timestamp % SECONDS_IN_DAY, wallet, entry_type),
+                checked_conversion::<i64, u64>(bytes),
+                checked_conversion::<i128, u128>(BigIntDivider::reconstitute(
+                    amount_high_b,
+                    amount_low_b,
+                )),
+            ))
+        })?
+        .vigilant_flatten()
+        .for_each(|(key, bytes, amount_wei)| {
+            let summary = summaries.entry(key).or_insert((0, 0, 0));
+            summary.0 += bytes;
+            summary.1 += amount_wei;
+            summary.2 += 1
+        });
+
+        txn.prepare(&format!("delete from ledger where {}", selection))?
+            .execute([day_boundary])?;
+
+        summaries.into_iter().try_for_each(
+            |((day, wallet, entry_type), (bytes, amount_wei, entry_count))| {
+                let (amount_high_b, amount_low_b) =
+                    BigIntDivider::deconstruct(checked_conversion::<u128, i128>(amount_wei));
+                txn.prepare(
+                    "insert into ledger (timestamp, wallet_address, entry_type, bytes, \
+                    amount_high_b, amount_low_b, transaction_hash, entry_count) \
+                    values (?, ?, ?, ?, ?, ?, null, ?)",
+                )?
+                .execute(rusqlite::params![
+                    day,
+                    wallet,
+                    entry_type,
+                    checked_conversion::<u64, i64>(bytes),
+                    amount_high_b,
+                    amount_low_b,
+                    checked_conversion::<u64, i64>(entry_count)
+                ])
+                .map(|_| ())
+            },
+        )
+    }
+}
+
+impl LedgerDao for LedgerDaoReal {
+    fn record(&self, entries: &[LedgerEntry]) {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "insert into ledger (timestamp, wallet_address, entry_type, bytes, amount_high_b, \
+                amount_low_b, transaction_hash, entry_count) values (?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .expect("Failed to prepare a statement");
+        entries.iter().for_each(|entry| {
+            let (amount_high_b, amount_low_b) =
+                BigIntDivider::deconstruct(checked_conversion::<u128, i128>(entry.amount_wei));
+            if let Err(e) = stmt.execute(rusqlite::params![
+                to_time_t(entry.timestamp),
+                entry.wallet,
+                entry.entry_type.to_string(),
+                checked_conversion::<u64, i64>(entry.bytes),
+                amount_high_b,
+                amount_low_b,
+                entry.transaction_hash_opt.map(|hash| format!("{:?}", hash)),
+                entry.entry_count_opt.map(checked_conversion::<u64, i64>)
+            ]) {
+                panic!(
+                    "Could not record {} for {} in the ledger because of database corruption: {}",
+                    entry.entry_type, entry.wallet, e
+                )
+            }
+        })
+    }
+
+    fn entries(&self, query: &LedgerQuery) -> Vec<LedgerEntry> {
+        let from_opt = query.from_opt.map(to_time_t);
+        let to_opt = query.to_opt.map(to_time_t);
+        let mut conditions: Vec<&str> = vec![];
+        let mut params: Vec<(&str, &dyn ToSql)> = vec![];
+        if let Some(wallet) = query.wallet_opt.as_ref() {
+            conditions.push("wallet_address = :wallet");
+            params.push((":wallet", wallet));
+        }
+        if let Some(from) = from_opt.as_ref() {
+            conditions.push("timestamp >= :from");
+            params.push((":from", from));
+        }
+        if let Some(to) = to_opt.as_ref() {
+            conditions.push("timestamp <= :to");
+            params.push((":to", to));
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("where {}", conditions.join(" and "))
+        };
+        let limit_clause = match query.max_entries_opt {
+            Some(max_entries) => format!("limit {}", max_entries),
+            None => String::new(),
+        };
+        let sql = format!(
+            "select timestamp, wallet_address, entry_type, bytes, amount_high_b, amount_low_b, \
+            transaction_hash, entry_count from ledger {} order by timestamp desc, rowid desc {}",
+            where_clause, limit_clause
+        );
+        self.conn
+            .prepare(&sql)
+            .expect("Couldn't prepare statement")
+            .query_map(params.as_slice(), Self::create_ledger_entry)
+            .expect("Couldn't retrieve ledger entries: database corruption")
+            .vigilant_flatten()
+            .collect()
+    }
+
+    fn roll_up(&mut self, before: SystemTime) {
+        let before = to_time_t(before);
+        let day_boundary = before - before % SECONDS_IN_DAY;
+        let txn = self
+            .conn
+            .transaction()
+            .expect("Couldn't start a transaction for the ledger roll-up");
+        match Self::roll_up_in_txn(&txn, day_boundary) {
+            Ok(()) => txn
+                .commit()
+                .expect("Couldn't commit the ledger roll-up: database corruption"),
+            Err(e) => panic!(
+                "Could not roll up the ledger because of database corruption: {}",
+                e
+            ),
+        }
+    }
+
+    fn delete_older_than(&self, cutoff: SystemTime) {
+        let mut stmt = self
+            .conn
+            .prepare("delete from ledger where timestamp < ?")
+            .expect("Failed to prepare a statement");
+        if let Err(e) = stmt.execute([to_time_t(cutoff)]) {
+            panic!(
+                "Could not trim the ledger because of database corruption: {}",
+                e
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::test_utils::make_tx_hash;
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal,
+    };
+    use crate::test_utils::make_wallet;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use std::time::Duration;
+
+    fn make_subject(test_name: &str) -> LedgerDaoReal {
+        let home_dir = ensure_node_home_directory_exists("ledger_dao", test_name);
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        LedgerDaoReal::new(conn)
+    }
+
+    fn at(time_t: i64) -> SystemTime {
+        from_time_t(time_t)
+    }
+
+    #[test]
+    fn entry_types_are_stored_under_stable_names() {
+        let all = vec![
+            (LedgerEntryType::RoutingProvided, "routingProvided"),
+            (LedgerEntryType::ExitProvided, "exitProvided"),
+            (LedgerEntryType::RoutingConsumed, "routingConsumed"),
+            (LedgerEntryType::ExitConsumed, "exitConsumed"),
+            (LedgerEntryType::PaymentSent, "paymentSent"),
+            (LedgerEntryType::PaymentReceived, "paymentReceived"),
+            (LedgerEntryType::PaymentReversed, "paymentReversed"),
+        ];
+
+        all.into_iter().for_each(|(entry_type, name)| {
+            assert_eq!(entry_type.to_string(), name);
+            assert_eq!(LedgerEntryType::from_str(name), Ok(entry_type))
+        });
+        assert_eq!(
+            LedgerEntryType::from_str("booga"),
+            Err("Unrecognized ledger entry type: 'booga'".to_string())
+        )
+    }
+
+    #[test]
+    fn recorded_entries_come_back_newest_first() {
+        let subject = make_subject("recorded_entries_come_back_newest_first");
+        let service = LedgerEntry::service(
+            at(1_700_000_000),
+            make_wallet("routing"),
+            LedgerEntryType::RoutingProvided,
+            1234,
+            56_789,
+        );
+        let payment = LedgerEntry::payment(
+            at(1_700_000_100),
+            make_wallet("debtor"),
+            LedgerEntryType::PaymentReceived,
+            u64::MAX as u128 * 1000,
+            make_tx_hash(123),
+        );
+
+        subject.record(&[service.clone(), payment.clone()]);
+
+        let result = subject.entries(&LedgerQuery::default());
+        assert_eq!(result, vec![payment, service])
+    }
+
+    #[test]
+    fn entries_can_be_filtered_by_wallet_time_range_and_count() {
+        let subject = make_subject("entries_can_be_filtered_by_wallet_time_range_and_count");
+        let wallet = make_wallet("wallet");
+        let make_entry = |time_t: i64, wallet: &Wallet| {
+            LedgerEntry::service(
+                at(time_t),
+                wallet.clone(),
+                LedgerEntryType::ExitConsumed,
+                100,
+                1000,
+            )
+        };
+        subject.record(&[
+            make_entry(1_000, &wallet),
+            make_entry(2_000, &wallet),
+            make_entry(2_000, &make_wallet("other")),
+            make_entry(3_000, &wallet),
+            make_entry(4_000, &wallet),
+        ]);
+
+        let by_wallet_and_range = subject.entries(&LedgerQuery {
+            wallet_opt: Some(wallet.clone()),
+            from_opt: Some(at(2_000)),
+            to_opt: Some(at(3_000)),
+            max_entries_opt: None,
+        });
+        let limited = subject.entries(&LedgerQuery {
+            wallet_opt: None,
+            from_opt: Some(at(2_000)),
+            to_opt: None,
+            max_entries_opt: Some(2),
+        });
+
+        assert_eq!(
+            by_wallet_and_range,
+            vec![make_entry(3_000, &wallet), make_entry(2_000, &wallet)]
+        );
+        assert_eq!(
+            limited,
+            vec![make_entry(4_000, &wallet), make_entry(3_000, &wallet)]
+        );
+    }
+
+    #[test]
+    fn roll_up_summarizes_old_service_entries_per_wallet_type_and_day() {
+        let mut subject =
+            make_subject("roll_up_summarizes_old_service_entries_per_wallet_type_and_day");
+        let day_one = 19_000 * SECONDS_IN_DAY;
+        let day_two = day_one + SECONDS_IN_DAY;
+        let day_three = day_two + SECONDS_IN_DAY;
+        let wallet_a = make_wallet("wallet_a");
+        let wallet_b = make_wallet("wallet_b");
+        let service = |time_t: i64, wallet: &Wallet, entry_type, bytes, amount_wei| {
+            LedgerEntry::service(at(time_t), wallet.clone(), entry_type, bytes, amount_wei)
+        };
+        let old_payment = LedgerEntry::payment(
+            at(day_one + 50),
+            wallet_a.clone(),
+            LedgerEntryType::PaymentSent,
+            5_000_000,
+            make_tx_hash(789),
+        );
+        let recent_service = service(
+            day_three + 10,
+            &wallet_a,
+            LedgerEntryType::RoutingProvided,
+            7,
+            70,
+        );
+        subject.record(&[
+            service(
+                day_one + 10,
+                &wallet_a,
+                LedgerEntryType::RoutingProvided,
+                100,
+                u64::MAX as u128,
+            ),
+            service(
+                day_one + 20,
+                &wallet_a,
+                LedgerEntryType::RoutingProvided,
+                200,
+                u64::MAX as u128,
+            ),
+            service(
+                day_one + 30,
+                &wallet_a,
+                LedgerEntryType::ExitProvided,
+                300,
+                3000,
+            ),
+            service(
+                day_one + 40,
+                &wallet_b,
+                LedgerEntryType::RoutingProvided,
+                400,
+                4000,
+            ),
+            service(
+                day_two + 10,
+                &wallet_a,
+                LedgerEntryType::RoutingProvided,
+                500,
+                5000,
+            ),
+            old_payment.clone(),
+            recent_service.clone(),
+        ]);
+
+        subject.roll_up(at(day_three + 3600));
+        subject.roll_up(at(day_three + 7200));
+
+        let result = subject.entries(&LedgerQuery::default());
+        let summary =
+            |time_t: i64, wallet: &Wallet, entry_type, bytes, amount_wei, count| LedgerEntry {
+                entry_count_opt: Some(count),
+                ..service(time_t, wallet, entry_type, bytes, amount_wei)
+            };
+        assert_eq!(
+            result,
+            vec![
+                recent_service,
+                summary(
+                    day_two,
+                    &wallet_a,
+                    LedgerEntryType::RoutingProvided,
+                    500,
+                    5000,
+                    1
+                ),
+                old_payment,
+                summary(
+                    day_one,
+                    &wallet_b,
+                    LedgerEntryType::RoutingProvided,
+                    400,
+                    4000,
+                    1
+                ),
+                summary(
+                    day_one,
+                    &wallet_a,
+                    LedgerEntryType::RoutingProvided,
+                    300,
+                    2 * u64::MAX as u128,
+                    2
+                ),
+                summary(
+                    day_one,
+                    &wallet_a,
+                    LedgerEntryType::ExitProvided,
+                    300,
+                    3000,
+                    1
+                ),
+            ]
+        )
+    }
+
+    #[test]
+    fn roll_up_leaves_the_day_in_progress_alone() {
+        let mut subject = make_subject("roll_up_leaves_the_day_in_progress_alone");
+        let today = 19_000 * SECONDS_IN_DAY;
+        let entry = LedgerEntry::service(
+            at(today + 10),
+            make_wallet("wallet"),
+            LedgerEntryType::ExitConsumed,
+            1,
+            2,
+        );
+        subject.record(&[entry.clone()]);
+
+        subject.roll_up(at(today + 20));
+
+        assert_eq!(subject.entries(&LedgerQuery::default()), vec![entry])
+    }
+
+    #[test]
+    fn delete_older_than_trims_the_ledger() {
+        let subject = make_subject("delete_older_than_trims_the_ledger");
+        let now = SystemTime::now();
+        let make_entry = |timestamp: SystemTime| {
+            LedgerEntry::payment(
+                from_time_t(to_time_t(timestamp)),
+                make_wallet("creditor"),
+                LedgerEntryType::PaymentSent,
+                1_000,
+                make_tx_hash(1),
+            )
+        };
+        let fresh = make_entry(now - Duration::from_secs(100));
+        subject.record(&[make_entry(now - Duration::from_secs(10_000)), fresh.clone()]);
+
+        subject.delete_older_than(now - Duration::from_secs(1_000));
+
+        assert_eq!(subject.entries(&LedgerQuery::default()), vec![fresh])
+    }
+}
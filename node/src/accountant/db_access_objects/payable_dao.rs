@@ -937,6 +937,8 @@ mod tests {
             attempt: 1,
             amount: balance_change_1,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let fingerprint_2 = PendingPayableFingerprint {
             rowid: rowid_2,
@@ -945,6 +947,8 @@ mod tests {
             attempt: 1,
             amount: balance_change_2,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let previous_timestamp_1 = from_time_t(previous_timestamp_1_s);
         let previous_timestamp_2 = from_time_t(previous_timestamp_2_s);
@@ -1399,6 +1403,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn custom_query_in_page_mode_walks_through_accounts_by_wallet() {
+        //Unlike top records, pages include accounts of balances smaller than one gwei
+        let now = now_time_t();
+        let main_test_setup = accounts_for_tests_of_top_records(now);
+        let subject = custom_query_test_body_for_payable(
+            "custom_query_in_page_mode_walks_through_accounts_by_wallet",
+            main_test_setup,
+        );
+
+        let second_page = subject
+            .custom_query(CustomQuery::Page {
+                offset: 2,
+                count: 2,
+            })
+            .unwrap();
+        let last_page = subject
+            .custom_query(CustomQuery::Page {
+                offset: 4,
+                count: 2,
+            })
+            .unwrap();
+        let beyond_last_page = subject.custom_query(CustomQuery::Page {
+            offset: 5,
+            count: 2,
+        });
+
+        assert_eq!(
+            second_page,
+            vec![
+                PayableAccount {
+                    wallet: Wallet::new("0x3333333333333333333333333333333333333333"),
+                    balance_wei: 999_999_999,
+                    last_paid_timestamp: from_time_t(now - 86_000),
+                    pending_payable_opt: None
+                },
+                PayableAccount {
+                    wallet: Wallet::new("0x4444444444444444444444444444444444444444"),
+                    balance_wei: 10_000_000_100,
+                    last_paid_timestamp: from_time_t(now - 86_300),
+                    pending_payable_opt: None
+                },
+            ]
+        );
+        assert_eq!(
+            last_page,
+            vec![PayableAccount {
+                wallet: Wallet::new("0x5555555555555555555555555555555555555555"),
+                balance_wei: 10_000_000_100,
+                last_paid_timestamp: from_time_t(now - 86_401),
+                pending_payable_opt: Some(PendingPayableId::new(
+                    1,
+                    H256::from_str(
+                        "abc4546cce78230a2312e12f3acb78747340456fe5237896666100143abcd223"
+                    )
+                    .unwrap()
+                ))
+            },]
+        );
+        assert_eq!(beyond_last_page, None)
+    }
+
     #[test]
     fn custom_query_handles_empty_table_in_range_mode() {
         let main_test_setup = |_conn: &dyn ConnectionWrapper, _insert: InsertPayableHelperFn| {};
@@ -4,8 +4,9 @@ use crate::accountant::db_access_objects::utils::{
     from_time_t, to_time_t, DaoFactoryReal, VigilantRusqliteFlatten,
 };
 use crate::accountant::db_big_integer::big_int_divider::BigIntDivider;
+use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::TransactionFeeModel;
 use crate::accountant::{checked_conversion, comma_joined_stringifiable};
-use crate::blockchain::blockchain_bridge::PendingPayableFingerprint;
+use crate::blockchain::blockchain_bridge::{PendingPayableFingerprint, ResubmissionData};
 use crate::database::rusqlite_wrappers::ConnectionWrapper;
 use crate::sub_lib::wallet::Wallet;
 use masq_lib::utils::ExpectValue;
@@ -39,11 +40,16 @@ pub trait PendingPayableDao {
     fn insert_new_fingerprints(
         &self,
         hashes_and_amounts: &[(H256, u128)],
+        resubmission_data: &[ResubmissionData],
         batch_wide_timestamp: SystemTime,
     ) -> Result<(), PendingPayableDaoError>;
     fn delete_fingerprints(&self, ids: &[u64]) -> Result<(), PendingPayableDaoError>;
     fn increment_scan_attempts(&self, ids: &[u64]) -> Result<(), PendingPayableDaoError>;
     fn mark_failures(&self, ids: &[u64]) -> Result<(), PendingPayableDaoError>;
+    fn record_replacements(
+        &self,
+        replacements: &[PendingPayableReplacement],
+    ) -> Result<(), PendingPayableDaoError>;
 }
 
 impl PendingPayableDao for PendingPayableDaoReal<'_> {
@@ -58,7 +64,8 @@ impl PendingPayableDao for PendingPayableDaoReal<'_> {
         }
 
         let sql = format!(
-            "select transaction_hash, rowid from pending_payable where transaction_hash in ({})",
+            "select transaction_hash, rowid from pending_payable where transaction_hash in ({}) \
+             order by rowid",
             comma_joined_stringifiable(hashes, |hash| format!("'{:?}'", hash))
         );
 
@@ -91,7 +98,10 @@ impl PendingPayableDao for PendingPayableDaoReal<'_> {
             .conn
             .prepare(
                 "select rowid, transaction_hash, amount_high_b, amount_low_b, \
-                 payable_timestamp, attempt from pending_payable where process_error is null",
+                 payable_timestamp, attempt, nonce, recipient_address, max_fee_per_gas_wei_high_b, \
+                 max_fee_per_gas_wei_low_b, max_priority_fee_per_gas_wei_high_b, \
+                 max_priority_fee_per_gas_wei_low_b, replaced_hashes from pending_payable \
+                 where process_error is null",
             )
             .expect("Internal error");
         stm.query_map([], |row| {
@@ -101,21 +111,49 @@ impl PendingPayableDao for PendingPayableDaoReal<'_> {
             let amount_low_bytes: i64 = Self::get_with_expect(row, 3);
             let timestamp: i64 = Self::get_with_expect(row, 4);
             let attempt: u16 = Self::get_with_expect(row, 5);
+            let nonce_opt: Option<i64> = Self::get_with_expect(row, 6);
+            let recipient_wallet_opt: Option<Wallet> = Self::get_with_expect(row, 7);
+            let max_fee_per_gas_high_bytes_opt: Option<i64> = Self::get_with_expect(row, 8);
+            let max_fee_per_gas_low_bytes_opt: Option<i64> = Self::get_with_expect(row, 9);
+            let max_priority_fee_per_gas_high_bytes_opt: Option<i64> =
+                Self::get_with_expect(row, 10);
+            let max_priority_fee_per_gas_low_bytes_opt: Option<i64> =
+                Self::get_with_expect(row, 11);
+            let replaced_hashes: String = Self::get_with_expect(row, 12);
+            let resubmission_data_opt = match (
+                nonce_opt,
+                recipient_wallet_opt,
+                max_fee_per_gas_high_bytes_opt.zip(max_fee_per_gas_low_bytes_opt),
+            ) {
+                (Some(nonce), Some(recipient_wallet), Some(max_fee_per_gas_bytes)) => {
+                    Some(ResubmissionData {
+                        nonce: checked_conversion::<i64, u64>(nonce),
+                        recipient_wallet,
+                        fee_model: Self::fee_model_from_columns(
+                            max_fee_per_gas_bytes,
+                            max_priority_fee_per_gas_high_bytes_opt
+                                .zip(max_priority_fee_per_gas_low_bytes_opt),
+                        ),
+                    })
+                }
+                _ => None,
+            };
             Ok(PendingPayableFingerprint {
                 rowid,
                 timestamp: from_time_t(timestamp),
-                hash: H256::from_str(&transaction_hash[2..]).unwrap_or_else(|e| {
-                    panic!(
-                        "Invalid hash format (\"{}\": {:?}) - database corrupt",
-                        transaction_hash, e
-                    )
-                }),
+                hash: Self::parse_hash(&transaction_hash),
                 attempt,
                 amount: checked_conversion::<i128, u128>(BigIntDivider::reconstitute(
                     amount_high_bytes,
                     amount_low_bytes,
                 )),
                 process_error: None,
+                replaced_hashes: replaced_hashes
+                    .split(',')
+                    .filter(|hash| !hash.is_empty())
+                    .map(Self::parse_hash)
+                    .collect(),
+                resubmission_data_opt,
             })
         })
         .expect("rusqlite failure")
@@ -126,28 +164,58 @@ impl PendingPayableDao for PendingPayableDaoReal<'_> {
     fn insert_new_fingerprints(
         &self,
         hashes_and_amounts: &[(H256, u128)],
+        resubmission_data: &[ResubmissionData],
         batch_wide_timestamp: SystemTime,
     ) -> Result<(), PendingPayableDaoError> {
         fn values_clause_for_fingerprints_to_insert(
             hashes_and_amounts: &[(H256, u128)],
+            resubmission_data: &[ResubmissionData],
             batch_wide_timestamp: SystemTime,
         ) -> String {
             let time_t = to_time_t(batch_wide_timestamp);
-            comma_joined_stringifiable(hashes_and_amounts, |(hash, amount)| {
+            let fingerprints = hashes_and_amounts
+                .iter()
+                .zip(resubmission_data.iter())
+                .collect::<Vec<_>>();
+            comma_joined_stringifiable(&fingerprints, |((hash, amount), resubmission_data)| {
                 let amount_checked = checked_conversion::<u128, i128>(*amount);
                 let (high_bytes, low_bytes) = BigIntDivider::deconstruct(amount_checked);
+                let [max_fee_high_b, max_fee_low_b, max_priority_fee_high_b, max_priority_fee_low_b] =
+                    PendingPayableDaoReal::fee_model_to_columns(resubmission_data.fee_model);
                 format!(
-                    "('{:?}', {}, {}, {}, 1, null)",
-                    hash, high_bytes, low_bytes, time_t
+                    "('{:?}', {}, {}, {}, 1, null, {}, '{}', {}, {}, {}, {}, '')",
+                    hash,
+                    high_bytes,
+                    low_bytes,
+                    time_t,
+                    checked_conversion::<u64, i64>(resubmission_data.nonce),
+                    resubmission_data.recipient_wallet,
+                    max_fee_high_b,
+                    max_fee_low_b,
+                    max_priority_fee_high_b,
+                    max_priority_fee_low_b
                 )
             })
         }
 
+        if hashes_and_amounts.len() != resubmission_data.len() {
+            panic!(
+                "Each of {} new fingerprints requires its resubmission data but got {}",
+                hashes_and_amounts.len(),
+                resubmission_data.len()
+            )
+        }
         let insert_sql = format!(
             "insert into pending_payable (\
-            transaction_hash, amount_high_b, amount_low_b, payable_timestamp, attempt, process_error\
+            transaction_hash, amount_high_b, amount_low_b, payable_timestamp, attempt, process_error, \
+            nonce, recipient_address, max_fee_per_gas_wei_high_b, max_fee_per_gas_wei_low_b, \
+            max_priority_fee_per_gas_wei_high_b, max_priority_fee_per_gas_wei_low_b, replaced_hashes\
             ) values {}",
-            values_clause_for_fingerprints_to_insert(hashes_and_amounts, batch_wide_timestamp)
+            values_clause_for_fingerprints_to_insert(
+                hashes_and_amounts,
+                resubmission_data,
+                batch_wide_timestamp
+            )
         );
         match self
             .conn
@@ -222,6 +290,43 @@ impl PendingPayableDao for PendingPayableDaoReal<'_> {
             Err(e) => Err(PendingPayableDaoError::ErrorMarkFailed(e.to_string())),
         }
     }
+
+    fn record_replacements(
+        &self,
+        replacements: &[PendingPayableReplacement],
+    ) -> Result<(), PendingPayableDaoError> {
+        replacements.iter().try_for_each(|replacement| {
+            let [max_fee_high_b, max_fee_low_b, max_priority_fee_high_b, max_priority_fee_low_b] =
+                Self::fee_model_to_columns(replacement.fee_model);
+            let sql = format!(
+                "update pending_payable set transaction_hash = '{:?}', replaced_hashes = '{}', \
+                max_fee_per_gas_wei_high_b = {}, max_fee_per_gas_wei_low_b = {}, \
+                max_priority_fee_per_gas_wei_high_b = {}, max_priority_fee_per_gas_wei_low_b = {} \
+                where rowid = {}",
+                replacement.hash,
+                replacement
+                    .replaced_hashes
+                    .iter()
+                    .map(|hash| format!("{:?}", hash))
+                    .collect::<Vec<String>>()
+                    .join(","),
+                max_fee_high_b,
+                max_fee_low_b,
+                max_priority_fee_high_b,
+                max_priority_fee_low_b,
+                replacement.rowid
+            );
+            match self.conn.prepare(&sql).expect("Internal error").execute([]) {
+                Ok(1) => Ok(()),
+                Ok(num) => panic!(
+                    "Database corrupt: replacing transaction {:?} at rowid {}: expected to update \
+                    1 row but did {}",
+                    replacement.hash, replacement.rowid, num
+                ),
+                Err(e) => Err(PendingPayableDaoError::UpdateFailed(e.to_string())),
+            }
+        })
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -239,6 +344,15 @@ impl PendingPayable {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingPayableReplacement {
+    pub rowid: u64,
+    pub hash: H256,
+    // All the hashes the transaction had before, the oldest first
+    pub replaced_hashes: Vec<H256>,
+    pub fee_model: TransactionFeeModel,
+}
+
 #[derive(Debug)]
 pub struct PendingPayableDaoReal<'a> {
     conn: Box<dyn ConnectionWrapper + 'a>,
@@ -256,6 +370,70 @@ impl<'a> PendingPayableDaoReal<'a> {
     fn serialize_ids(ids: &[u64]) -> String {
         comma_joined_stringifiable(ids, |id| id.to_string())
     }
+
+    fn parse_hash(hash: &str) -> H256 {
+        H256::from_str(&hash[2..]).unwrap_or_else(|e| {
+            panic!(
+                "Invalid hash format (\"{}\": {:?}) - database corrupt",
+                hash, e
+            )
+        })
+    }
+
+    // Fees in wei are split into the high and low bytes just like amounts; a legacy gas price
+    // goes into the max fee columns while the priority fee ones stay null
+    fn fee_model_to_columns(fee_model: TransactionFeeModel) -> [String; 4] {
+        fn deconstruct(fee: u128) -> (String, String) {
+            let (high_bytes, low_bytes) =
+                BigIntDivider::deconstruct(checked_conversion::<u128, i128>(fee));
+            (high_bytes.to_string(), low_bytes.to_string())
+        }
+
+        match fee_model {
+            TransactionFeeModel::Legacy { gas_price_wei } => {
+                let (high_bytes, low_bytes) = deconstruct(gas_price_wei);
+                [
+                    high_bytes,
+                    low_bytes,
+                    "null".to_string(),
+                    "null".to_string(),
+                ]
+            }
+            TransactionFeeModel::Eip1559 {
+                max_fee_per_gas_wei,
+                max_priority_fee_per_gas_wei,
+            } => {
+                let (max_fee_high_bytes, max_fee_low_bytes) = deconstruct(max_fee_per_gas_wei);
+                let (priority_fee_high_bytes, priority_fee_low_bytes) =
+                    deconstruct(max_priority_fee_per_gas_wei);
+                [
+                    max_fee_high_bytes,
+                    max_fee_low_bytes,
+                    priority_fee_high_bytes,
+                    priority_fee_low_bytes,
+                ]
+            }
+        }
+    }
+
+    fn fee_model_from_columns(
+        max_fee_per_gas_bytes: (i64, i64),
+        max_priority_fee_per_gas_bytes_opt: Option<(i64, i64)>,
+    ) -> TransactionFeeModel {
+        fn reconstitute((high_bytes, low_bytes): (i64, i64)) -> u128 {
+            checked_conversion::<i128, u128>(BigIntDivider::reconstitute(high_bytes, low_bytes))
+        }
+
+        match max_priority_fee_per_gas_bytes_opt {
+            None => TransactionFeeModel::Legacy {
+                gas_price_wei: reconstitute(max_fee_per_gas_bytes),
+            },
+            Some(max_priority_fee_per_gas_bytes) => TransactionFeeModel::Eip1559 {
+                max_fee_per_gas_wei: reconstitute(max_fee_per_gas_bytes),
+                max_priority_fee_per_gas_wei: reconstitute(max_priority_fee_per_gas_bytes),
+            },
+        }
+    }
 }
 
 pub trait PendingPayableDaoFactory {
@@ -272,11 +450,13 @@ impl PendingPayableDaoFactory for DaoFactoryReal {
 mod tests {
     use crate::accountant::checked_conversion;
     use crate::accountant::db_access_objects::pending_payable_dao::{
-        PendingPayableDao, PendingPayableDaoError, PendingPayableDaoReal,
+        PendingPayableDao, PendingPayableDaoError, PendingPayableDaoReal, PendingPayableReplacement,
     };
     use crate::accountant::db_access_objects::utils::from_time_t;
     use crate::accountant::db_big_integer::big_int_divider::BigIntDivider;
-    use crate::blockchain::blockchain_bridge::PendingPayableFingerprint;
+    use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::TransactionFeeModel;
+    use crate::accountant::test_utils::make_resubmission_data;
+    use crate::blockchain::blockchain_bridge::{PendingPayableFingerprint, ResubmissionData};
     use crate::blockchain::test_utils::make_tx_hash;
     use crate::database::db_initializer::{
         DbInitializationConfig, DbInitializer, DbInitializerReal, DATABASE_FILE,
@@ -302,12 +482,21 @@ mod tests {
         let amount_1 = 55556;
         let hash_2 = make_tx_hash(6789);
         let amount_2 = 44445;
+        let resubmission_data_1 = make_resubmission_data(3);
+        let resubmission_data_2 = ResubmissionData {
+            fee_model: TransactionFeeModel::Eip1559 {
+                max_fee_per_gas_wei: 0x3FFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF,
+                max_priority_fee_per_gas_wei: 2_000_000_000,
+            },
+            ..make_resubmission_data(4)
+        };
         let batch_wide_timestamp = from_time_t(200_000_000);
         let subject = PendingPayableDaoReal::new(wrapped_conn);
 
         let _ = subject
             .insert_new_fingerprints(
                 &[(hash_1, amount_1), (hash_2, amount_2)],
+                &[resubmission_data_1.clone(), resubmission_data_2.clone()],
                 batch_wide_timestamp,
             )
             .unwrap();
@@ -322,7 +511,9 @@ mod tests {
                     hash: hash_1,
                     attempt: 1,
                     amount: amount_1,
-                    process_error: None
+                    process_error: None,
+                    replaced_hashes: vec![],
+                    resubmission_data_opt: Some(resubmission_data_1),
                 },
                 PendingPayableFingerprint {
                     rowid: 2,
@@ -330,7 +521,9 @@ mod tests {
                     hash: hash_2,
                     attempt: 1,
                     amount: amount_2,
-                    process_error: None
+                    process_error: None,
+                    replaced_hashes: vec![],
+                    resubmission_data_opt: Some(resubmission_data_2),
                 }
             ]
         )
@@ -358,7 +551,11 @@ mod tests {
         let timestamp = from_time_t(200_000_000);
         let subject = PendingPayableDaoReal::new(Box::new(wrapped_conn));
 
-        let result = subject.insert_new_fingerprints(&[(hash, amount)], timestamp);
+        let result = subject.insert_new_fingerprints(
+            &[(hash, amount)],
+            &[make_resubmission_data(1)],
+            timestamp,
+        );
 
         assert_eq!(
             result,
@@ -386,7 +583,32 @@ mod tests {
         let batch_wide_timestamp = from_time_t(200_000_000);
         let subject = PendingPayableDaoReal::new(Box::new(wrapped_conn));
 
-        let _ = subject.insert_new_fingerprints(&[(hash_1, amount_1)], batch_wide_timestamp);
+        let _ = subject.insert_new_fingerprints(
+            &[(hash_1, amount_1)],
+            &[make_resubmission_data(1)],
+            batch_wide_timestamp,
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Each of 2 new fingerprints requires its resubmission data but got 1"
+    )]
+    fn insert_new_fingerprints_panics_on_missing_resubmission_data() {
+        let home_dir = ensure_node_home_directory_exists(
+            "pending_payable_dao",
+            "insert_new_fingerprints_panics_on_missing_resubmission_data",
+        );
+        let wrapped_conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = PendingPayableDaoReal::new(wrapped_conn);
+
+        let _ = subject.insert_new_fingerprints(
+            &[(make_tx_hash(123), 5555), (make_tx_hash(456), 6666)],
+            &[make_resubmission_data(1)],
+            from_time_t(200_000_000),
+        );
     }
 
     #[test]
@@ -411,7 +633,11 @@ mod tests {
         let fingerprints_init_input = vec![(hash_1, 4567), (hash_2, 6789)];
         {
             subject
-                .insert_new_fingerprints(&fingerprints_init_input, timestamp)
+                .insert_new_fingerprints(
+                    &fingerprints_init_input,
+                    &[make_resubmission_data(1), make_resubmission_data(2)],
+                    timestamp,
+                )
                 .unwrap();
         }
 
@@ -434,6 +660,47 @@ mod tests {
         assert_eq!(result.rowid_results.len(), 2);
     }
 
+    #[test]
+    fn fingerprints_rowids_returns_all_fingerprints_of_a_multisend_transaction_in_order() {
+        let home_dir = ensure_node_home_directory_exists(
+            "pending_payable_dao",
+            "fingerprints_rowids_returns_all_fingerprints_of_a_multisend_transaction_in_order",
+        );
+        let wrapped_conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = PendingPayableDaoReal::new(wrapped_conn);
+        let multisend_hash =
+            H256::from_str("b4bc263278d3a82a652a8d73a6bfd8ec0ba1a63923bbb4f38147fb8a943da26a")
+                .unwrap();
+        let lone_hash =
+            H256::from_str("5a2909e7bb71943c82a94d9beb04e230351541fc14619ee8bb9b7372ea88ba39")
+                .unwrap();
+        subject
+            .insert_new_fingerprints(
+                &[
+                    (multisend_hash, 4567),
+                    (lone_hash, 5678),
+                    (multisend_hash, 6789),
+                ],
+                &[
+                    make_resubmission_data(1),
+                    make_resubmission_data(2),
+                    make_resubmission_data(3),
+                ],
+                from_time_t(195_000_000),
+            )
+            .unwrap();
+
+        let result = subject.fingerprints_rowids(&[multisend_hash, lone_hash, multisend_hash]);
+
+        assert_eq!(
+            result.rowid_results,
+            vec![(1, multisend_hash), (2, lone_hash), (3, multisend_hash)]
+        );
+        assert!(result.no_rowid_results.is_empty())
+    }
+
     #[test]
     fn fingerprints_rowids_when_nonexistent_records() {
         let home_dir = ensure_node_home_directory_exists(
@@ -452,10 +719,18 @@ mod tests {
         // this test, and in the end, I delete the first one. It leaves a single record still in but with the rowid 2 instead of
         // just an ambiguous 1
         subject
-            .insert_new_fingerprints(&[(hash_2, 8901234)], SystemTime::now())
+            .insert_new_fingerprints(
+                &[(hash_2, 8901234)],
+                &[make_resubmission_data(1)],
+                SystemTime::now(),
+            )
             .unwrap();
         subject
-            .insert_new_fingerprints(&[(hash_3, 1234567)], SystemTime::now())
+            .insert_new_fingerprints(
+                &[(hash_3, 1234567)],
+                &[make_resubmission_data(1)],
+                SystemTime::now(),
+            )
             .unwrap();
         subject.delete_fingerprints(&[1]).unwrap();
 
@@ -484,6 +759,7 @@ mod tests {
             subject
                 .insert_new_fingerprints(
                     &[(hash_1, amount_1), (hash_2, amount_2)],
+                    &[make_resubmission_data(1), make_resubmission_data(2)],
                     batch_wide_timestamp,
                 )
                 .unwrap();
@@ -500,7 +776,9 @@ mod tests {
                     hash: hash_1,
                     attempt: 1,
                     amount: amount_1,
-                    process_error: None
+                    process_error: None,
+                    replaced_hashes: vec![],
+                    resubmission_data_opt: Some(make_resubmission_data(1)),
                 },
                 PendingPayableFingerprint {
                     rowid: 2,
@@ -508,7 +786,9 @@ mod tests {
                     hash: hash_2,
                     attempt: 1,
                     amount: amount_2,
-                    process_error: None
+                    process_error: None,
+                    replaced_hashes: vec![],
+                    resubmission_data_opt: Some(make_resubmission_data(2)),
                 }
             ]
         )
@@ -529,7 +809,11 @@ mod tests {
         let amount = 333;
         {
             subject
-                .insert_new_fingerprints(&[(make_tx_hash(11119), 2000), (hash, amount)], timestamp)
+                .insert_new_fingerprints(
+                    &[(make_tx_hash(11119), 2000), (hash, amount)],
+                    &[make_resubmission_data(1), make_resubmission_data(2)],
+                    timestamp,
+                )
                 .unwrap();
             subject.mark_failures(&[1]).unwrap();
         }
@@ -544,7 +828,9 @@ mod tests {
                 hash,
                 attempt: 1,
                 amount,
-                process_error: None
+                process_error: None,
+                replaced_hashes: vec![],
+                resubmission_data_opt: Some(make_resubmission_data(2)),
             }]
         )
     }
@@ -593,6 +879,11 @@ mod tests {
                         (make_tx_hash(2345), 5555),
                         (make_tx_hash(3456), 2222),
                     ],
+                    &[
+                        make_resubmission_data(1),
+                        make_resubmission_data(2),
+                        make_resubmission_data(3),
+                    ],
                     SystemTime::now(),
                 )
                 .unwrap();
@@ -655,7 +946,11 @@ mod tests {
         let subject = PendingPayableDaoReal::new(conn);
         {
             subject
-                .insert_new_fingerprints(&[(make_tx_hash(666666), 5555)], SystemTime::now())
+                .insert_new_fingerprints(
+                    &[(make_tx_hash(666666), 5555)],
+                    &[make_resubmission_data(1)],
+                    SystemTime::now(),
+                )
                 .unwrap();
         }
 
@@ -680,6 +975,11 @@ mod tests {
             subject
                 .insert_new_fingerprints(
                     &[(hash_1, 1122), (hash_2, 2233), (hash_3, 3344)],
+                    &[
+                        make_resubmission_data(1),
+                        make_resubmission_data(2),
+                        make_resubmission_data(3),
+                    ],
                     timestamp,
                 )
                 .unwrap();
@@ -762,7 +1062,11 @@ mod tests {
         let subject = PendingPayableDaoReal::new(conn);
         {
             subject
-                .insert_new_fingerprints(&[(hash_1, amount_1), (hash_2, amount_2)], timestamp)
+                .insert_new_fingerprints(
+                    &[(hash_1, amount_1), (hash_2, amount_2)],
+                    &[make_resubmission_data(1), make_resubmission_data(2)],
+                    timestamp,
+                )
                 .unwrap();
         }
 
@@ -792,6 +1096,8 @@ mod tests {
                         amount_low_b,
                     )),
                     process_error,
+                    replaced_hashes: vec![],
+                    resubmission_data_opt: None,
                 })
             })
             .unwrap()
@@ -806,7 +1112,9 @@ mod tests {
                     hash: hash_1,
                     attempt: 1,
                     amount: amount_1,
-                    process_error: None
+                    process_error: None,
+                    replaced_hashes: vec![],
+                    resubmission_data_opt: None,
                 },
                 PendingPayableFingerprint {
                     rowid: 2,
@@ -814,7 +1122,9 @@ mod tests {
                     hash: hash_2,
                     attempt: 1,
                     amount: amount_2,
-                    process_error: Some("ERROR".to_string())
+                    process_error: Some("ERROR".to_string()),
+                    replaced_hashes: vec![],
+                    resubmission_data_opt: None,
                 }
             ]
         )
@@ -863,4 +1173,149 @@ mod tests {
 
         let _ = subject.mark_failures(&[10, 20]);
     }
+
+    #[test]
+    fn record_replacements_works() {
+        let home_dir =
+            ensure_node_home_directory_exists("pending_payable_dao", "record_replacements_works");
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let hash_1 = make_tx_hash(555);
+        let hash_2 = make_tx_hash(666);
+        let hash_3 = make_tx_hash(777);
+        let timestamp = from_time_t(190_000_000);
+        let subject = PendingPayableDaoReal::new(conn);
+        {
+            subject
+                .insert_new_fingerprints(
+                    &[(hash_1, 1234), (hash_2, 2345)],
+                    &[make_resubmission_data(1), make_resubmission_data(2)],
+                    timestamp,
+                )
+                .unwrap();
+        }
+        let replacements = vec![
+            PendingPayableReplacement {
+                rowid: 2,
+                hash: hash_3,
+                replaced_hashes: vec![make_tx_hash(444), hash_2],
+                fee_model: TransactionFeeModel::Eip1559 {
+                    max_fee_per_gas_wei: 150_000_000_000,
+                    max_priority_fee_per_gas_wei: 3_000_000_000,
+                },
+            },
+            PendingPayableReplacement {
+                rowid: 1,
+                hash: make_tx_hash(888),
+                replaced_hashes: vec![hash_1],
+                fee_model: TransactionFeeModel::Legacy {
+                    gas_price_wei: 160_000_000_000,
+                },
+            },
+        ];
+
+        let result = subject.record_replacements(&replacements);
+
+        assert_eq!(result, Ok(()));
+        let records = subject.return_all_errorless_fingerprints();
+        assert_eq!(
+            records,
+            vec![
+                PendingPayableFingerprint {
+                    rowid: 1,
+                    timestamp,
+                    hash: make_tx_hash(888),
+                    attempt: 1,
+                    amount: 1234,
+                    process_error: None,
+                    replaced_hashes: vec![hash_1],
+                    resubmission_data_opt: Some(ResubmissionData {
+                        fee_model: TransactionFeeModel::Legacy {
+                            gas_price_wei: 160_000_000_000
+                        },
+                        ..make_resubmission_data(1)
+                    }),
+                },
+                PendingPayableFingerprint {
+                    rowid: 2,
+                    timestamp,
+                    hash: hash_3,
+                    attempt: 1,
+                    amount: 2345,
+                    process_error: None,
+                    replaced_hashes: vec![make_tx_hash(444), hash_2],
+                    resubmission_data_opt: Some(ResubmissionData {
+                        fee_model: TransactionFeeModel::Eip1559 {
+                            max_fee_per_gas_wei: 150_000_000_000,
+                            max_priority_fee_per_gas_wei: 3_000_000_000,
+                        },
+                        ..make_resubmission_data(2)
+                    }),
+                }
+            ]
+        )
+    }
+
+    #[test]
+    fn record_replacements_sad_path() {
+        let home_dir = ensure_node_home_directory_exists(
+            "pending_payable_dao",
+            "record_replacements_sad_path",
+        );
+        {
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap();
+        }
+        let conn_read_only = Connection::open_with_flags(
+            home_dir.join(DATABASE_FILE),
+            OpenFlags::SQLITE_OPEN_READ_ONLY,
+        )
+        .unwrap();
+        let wrapped_conn = ConnectionWrapperReal::new(conn_read_only);
+        let subject = PendingPayableDaoReal::new(Box::new(wrapped_conn));
+        let replacement = PendingPayableReplacement {
+            rowid: 1,
+            hash: make_tx_hash(123),
+            replaced_hashes: vec![make_tx_hash(456)],
+            fee_model: TransactionFeeModel::Legacy {
+                gas_price_wei: 1_000_000_000,
+            },
+        };
+
+        let result = subject.record_replacements(&[replacement]);
+
+        assert_eq!(
+            result,
+            Err(PendingPayableDaoError::UpdateFailed(
+                "attempt to write a readonly database".to_string()
+            ))
+        )
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Database corrupt: replacing transaction 0x000000000000000000000000000000000000000000000000000000000000007b at rowid 5: expected to update 1 row but did 0"
+    )]
+    fn record_replacements_panics_on_nonexistent_record() {
+        let home_dir = ensure_node_home_directory_exists(
+            "pending_payable_dao",
+            "record_replacements_panics_on_nonexistent_record",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = PendingPayableDaoReal::new(conn);
+        let replacement = PendingPayableReplacement {
+            rowid: 5,
+            hash: make_tx_hash(123),
+            replaced_hashes: vec![],
+            fee_model: TransactionFeeModel::Legacy {
+                gas_price_wei: 1_000_000_000,
+            },
+        };
+
+        let _ = subject.record_replacements(&[replacement]);
+    }
 }
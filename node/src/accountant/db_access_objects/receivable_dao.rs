@@ -28,7 +28,9 @@ use masq_lib::utils::ExpectValue;
 use rusqlite::OptionalExtension;
 use rusqlite::Row;
 use rusqlite::{named_params, Error};
+use std::str::FromStr;
 use std::time::SystemTime;
+use web3::types::H256;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum ReceivableDaoError {
@@ -62,8 +64,14 @@ pub trait ReceivableDao {
         &mut self,
         now: SystemTime,
         transactions: &[BlockchainTransaction],
+        orphaned_transactions: &[BlockchainTransaction],
     ) -> TransactionSafeWrapper;
 
+    fn credited_transactions_since(&self, block_number: u64) -> Vec<BlockchainTransaction>;
+
+    // Credited payments below the lowest block a scan can look back to are no longer rechecked
+    fn forget_credited_transactions_before(&self, block_number: u64);
+
     fn new_delinquencies(
         &self,
         now: SystemTime,
@@ -148,19 +156,31 @@ impl ReceivableDao for ReceivableDaoReal {
         &mut self,
         timestamp: SystemTime,
         received_payments: &[BlockchainTransaction],
+        orphaned_payments: &[BlockchainTransaction],
     ) -> TransactionSafeWrapper<'_> {
         let accounting_result = match self.conn.transaction() {
             Ok(txn) => {
                 let big_int_db_processor = &*self.big_int_db_processor;
                 let logger = &self.logger;
 
-                Self::process_received_payments_and_return_txn(
+                Self::reverse_orphaned_payments_and_return_txn(
                     big_int_db_processor,
-                    received_payments,
-                    timestamp,
+                    orphaned_payments,
                     txn,
                     logger,
                 )
+                .and_then(|txn| {
+                    Self::process_received_payments_and_return_txn(
+                        big_int_db_processor,
+                        received_payments,
+                        timestamp,
+                        txn,
+                        logger,
+                    )
+                })
+                .and_then(|txn| {
+                    Self::record_credited_payments_and_return_txn(received_payments, txn)
+                })
             }
             // Even though done with the accountancy, we still haven't updated the start block.
             // We will proceed that as the last thing because of our concern about data continuity
@@ -187,6 +207,34 @@ impl ReceivableDao for ReceivableDaoReal {
         }
     }
 
+    fn credited_transactions_since(&self, block_number: u64) -> Vec<BlockchainTransaction> {
+        let sql = "select transaction_hash, block_number, block_hash, wallet_address, amount_high_b, \
+            amount_low_b from received_payment where block_number >= ? order by block_number, rowid";
+        self.conn
+            .prepare(sql)
+            .expect("Couldn't prepare statement")
+            .query_map(
+                [checked_conversion::<u64, i64>(block_number)],
+                Self::create_credited_transaction,
+            )
+            .expect("Couldn't retrieve credited transactions: database corruption")
+            .vigilant_flatten()
+            .collect()
+    }
+
+    fn forget_credited_transactions_before(&self, block_number: u64) {
+        let mut stmt = self
+            .conn
+            .prepare("delete from received_payment where block_number < ?")
+            .expect("Failed to prepare a statement");
+        if let Err(e) = stmt.execute([checked_conversion::<u64, i64>(block_number)]) {
+            panic!(
+                "Could not forget credited payments because of database corruption: {}",
+                e
+            )
+        }
+    }
+
     fn new_delinquencies(
         &self,
         now: SystemTime,
@@ -233,7 +281,8 @@ impl ReceivableDao for ReceivableDaoReal {
             select r.wallet_address, r.balance_high_b, r.balance_low_b, r.last_received_timestamp
             from receivable r inner join banned b on r.wallet_address = b.wallet_address
             where
-                (r.balance_high_b < :unban_balance_high_b) or ((balance_high_b = :unban_balance_high_b) and (balance_low_b <= :unban_balance_low_b))
+                b.manual = 0 and
+                ((r.balance_high_b < :unban_balance_high_b) or ((balance_high_b = :unban_balance_high_b) and (balance_low_b <= :unban_balance_low_b)))
         "
         );
         let mut stmt = self.conn.prepare(sql).expect("Couldn't prepare statement");
@@ -392,6 +441,112 @@ impl ReceivableDaoReal {
         }
     }
 
+    fn reverse_orphaned_payments_and_return_txn<'txn>(
+        big_int_db_processor: &dyn BigIntDbProcessor<ReceivableDaoReal>,
+        orphaned_payments: &[BlockchainTransaction],
+        txn: TransactionSafeWrapper<'txn>,
+        logger: &Logger,
+    ) -> Result<TransactionSafeWrapper<'txn>, ReceivableDaoError> {
+        // The timestamp of the last payment is left alone; it was the best we knew at that time
+        let main_sql = "update receivable set balance_high_b = balance_high_b + :balance_high_b, \
+                 balance_low_b = balance_low_b + :balance_low_b where wallet_address = :wallet";
+        let update_clause_with_compensated_overflow =
+            "update receivable set balance_high_b = :balance_high_b, \
+                 balance_low_b = :balance_low_b where wallet_address = :wallet";
+
+        orphaned_payments
+            .iter()
+            .try_for_each(|orphaned_payment| -> Result<(), ReceivableDaoError> {
+            let params = SQLParamsBuilder::default()
+                .key(WalletAddress(&orphaned_payment.from))
+                .wei_change(WeiChange::new(
+                    "balance",
+                    orphaned_payment.wei_amount,
+                    WeiChangeDirection::Addition,
+                ))
+                .build();
+
+            match big_int_db_processor.execute(
+                Either::Right(&txn),
+                BigIntSqlConfig::new(main_sql, update_clause_with_compensated_overflow, params),
+            ) {
+                Ok(_) => Ok(()),
+                Err(BigIntDatabaseError::General(err_msg)) => {
+                    Err(ReceivableDaoError::RusqliteError(err_msg))
+                }
+                Err(BigIntDatabaseError::RowChangeMismatch { .. }) => {
+                    if Self::is_row_present(&txn, &orphaned_payment.from) {
+                        Err(RusqliteError(format!(
+                            "Reversal of orphaned payment with {} wei ran without producing a change \
+                            in the database, despite the record for wallet {} exists.",
+                            orphaned_payment.wei_amount, orphaned_payment.from
+                        )))
+                    } else {
+                        debug!(
+                            logger,
+                            "Orphaned payment from {} concerned no known debtor; nothing to reverse",
+                            orphaned_payment.from
+                        );
+                        Ok(())
+                    }
+                }
+            }?;
+
+            txn.prepare("delete from received_payment where transaction_hash = ?")?
+                .execute([format!("{:?}", orphaned_payment.transaction_hash)])?;
+            Ok(())
+        })?;
+
+        Ok(txn)
+    }
+
+    fn record_credited_payments_and_return_txn<'txn>(
+        received_payments: &[BlockchainTransaction],
+        txn: TransactionSafeWrapper<'txn>,
+    ) -> Result<TransactionSafeWrapper<'txn>, ReceivableDaoError> {
+        let sql = "insert or replace into received_payment (transaction_hash, block_number, \
+            block_hash, wallet_address, amount_high_b, amount_low_b) values (?, ?, ?, ?, ?, ?)";
+        received_payments.iter().try_for_each(
+            |received_payment| -> Result<(), ReceivableDaoError> {
+                let (amount_high_b, amount_low_b) = BigIntDivider::deconstruct(
+                    checked_conversion::<u128, i128>(received_payment.wei_amount),
+                );
+                txn.prepare(sql)?.execute(rusqlite::params![
+                    format!("{:?}", received_payment.transaction_hash),
+                    checked_conversion::<u64, i64>(received_payment.block_number),
+                    format!("{:?}", received_payment.block_hash),
+                    received_payment.from,
+                    amount_high_b,
+                    amount_low_b
+                ])?;
+                Ok::<(), ReceivableDaoError>(())
+            },
+        )?;
+
+        Ok(txn)
+    }
+
+    fn create_credited_transaction(row: &Row) -> rusqlite::Result<BlockchainTransaction> {
+        let transaction_hash: String = row.get(0).expectv("transaction hash");
+        let block_number: i64 = row.get(1).expectv("block number");
+        let block_hash: String = row.get(2).expectv("block hash");
+        let from: Wallet = row.get(3).expectv("wallet address");
+        let amount_high_b: i64 = row.get(4).expectv("amount high bytes");
+        let amount_low_b: i64 = row.get(5).expectv("amount low bytes");
+        Ok(BlockchainTransaction {
+            block_number: checked_conversion::<i64, u64>(block_number),
+            block_hash: H256::from_str(&block_hash[2..])
+                .expect("block hash inserted right turned wrong"),
+            transaction_hash: H256::from_str(&transaction_hash[2..])
+                .expect("transaction hash inserted right turned wrong"),
+            from,
+            wei_amount: checked_conversion::<i128, u128>(BigIntDivider::reconstitute(
+                amount_high_b,
+                amount_low_b,
+            )),
+        })
+    }
+
     fn is_row_present(conn: &TransactionSafeWrapper, wallet: &Wallet) -> bool {
         conn.prepare("select * from receivable where wallet_address = ?")
             .expect("internal sqlite error")
@@ -500,6 +655,7 @@ mod tests {
         assert_account_creation_fn_fails_on_finding_wrong_columns_and_value_types,
         make_receivable_account, trick_rusqlite_with_read_only_conn,
     };
+    use crate::blockchain::test_utils::make_tx_hash;
     use crate::database::db_initializer::{DbInitializationConfig, DbInitializer, DATABASE_FILE};
     use crate::database::db_initializer::{DbInitializerReal, ExternalData};
     use crate::database::rusqlite_wrappers::ConnectionWrapperReal;
@@ -571,11 +727,13 @@ mod tests {
         );
         let payments = vec![BlockchainTransaction {
             block_number: 42u64,
+            block_hash: make_tx_hash(1001),
+            transaction_hash: make_tx_hash(1),
             from: make_wallet("some_address"),
             wei_amount: u128::MAX,
         }];
 
-        let _ = subject.more_money_received(SystemTime::now(), &payments);
+        let _ = subject.more_money_received(SystemTime::now(), &payments, &[]);
     }
 
     #[test]
@@ -596,11 +754,13 @@ mod tests {
 
         let payments = vec![BlockchainTransaction {
             block_number: 42u64,
+            block_hash: make_tx_hash(1002),
+            transaction_hash: make_tx_hash(2),
             from: make_wallet("some_address"),
             wei_amount: 18446744073709551615,
         }];
 
-        let _ = subject.more_money_received(SystemTime::now(), &payments);
+        let _ = subject.more_money_received(SystemTime::now(), &payments, &[]);
     }
 
     #[test]
@@ -797,15 +957,19 @@ mod tests {
                 from: debtor1.clone(),
                 wei_amount: first_newly_received,
                 block_number: 35_u64,
+                block_hash: make_tx_hash(1003),
+                transaction_hash: make_tx_hash(3),
             },
             BlockchainTransaction {
                 from: debtor2.clone(),
                 wei_amount: second_newly_received,
                 block_number: 57_u64,
+                block_hash: make_tx_hash(1004),
+                transaction_hash: make_tx_hash(4),
             },
         ];
 
-        let txn = subject.more_money_received(payment_time, &transactions);
+        let txn = subject.more_money_received(payment_time, &transactions, &[]);
 
         txn.commit().unwrap();
         let status1 = subject.account_status(&debtor1).unwrap();
@@ -824,6 +988,177 @@ mod tests {
         );
     }
 
+    #[test]
+    fn more_money_received_records_credited_payments_so_that_they_can_be_rechecked() {
+        let home_dir = ensure_node_home_directory_exists(
+            "receivable_dao",
+            "more_money_received_records_credited_payments_so_that_they_can_be_rechecked",
+        );
+        let debtor = make_wallet("debtor");
+        let mut subject = ReceivableDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        subject
+            .more_money_receivable(SystemTime::UNIX_EPOCH, &debtor, 1_000_000)
+            .unwrap();
+        let payment_1 = BlockchainTransaction {
+            block_number: 100,
+            block_hash: make_tx_hash(1100),
+            transaction_hash: make_tx_hash(11),
+            from: debtor.clone(),
+            wei_amount: 1_000,
+        };
+        let payment_2 = BlockchainTransaction {
+            block_number: 120,
+            block_hash: make_tx_hash(1120),
+            transaction_hash: make_tx_hash(12),
+            from: debtor.clone(),
+            wei_amount: u64::MAX as u128 + 3,
+        };
+        let payment_3 = BlockchainTransaction {
+            block_number: 120,
+            block_hash: make_tx_hash(1120),
+            transaction_hash: make_tx_hash(13),
+            from: make_wallet("unknown_debtor"),
+            wei_amount: 3_000,
+        };
+
+        let txn = subject.more_money_received(
+            SystemTime::now(),
+            &[payment_1.clone(), payment_2.clone(), payment_3.clone()],
+            &[],
+        );
+
+        txn.commit().unwrap();
+        assert_eq!(
+            subject.credited_transactions_since(0),
+            vec![payment_1, payment_2.clone(), payment_3.clone()]
+        );
+        assert_eq!(
+            subject.credited_transactions_since(101),
+            vec![payment_2, payment_3]
+        );
+        assert_eq!(subject.credited_transactions_since(121), vec![]);
+    }
+
+    #[test]
+    fn forget_credited_transactions_before_drops_only_older_blocks() {
+        let home_dir = ensure_node_home_directory_exists(
+            "receivable_dao",
+            "forget_credited_transactions_before_drops_only_older_blocks",
+        );
+        let mut subject = ReceivableDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        let make_payment = |block_number: u64, hash: u32| BlockchainTransaction {
+            block_number,
+            block_hash: make_tx_hash(1000 + hash),
+            transaction_hash: make_tx_hash(hash),
+            from: make_wallet("debtor"),
+            wei_amount: 1_000,
+        };
+        let kept_payments = vec![make_payment(110, 2), make_payment(120, 3)];
+        subject
+            .more_money_received(
+                SystemTime::now(),
+                &[
+                    make_payment(100, 1),
+                    kept_payments[0].clone(),
+                    kept_payments[1].clone(),
+                ],
+                &[],
+            )
+            .commit()
+            .unwrap();
+
+        subject.forget_credited_transactions_before(110);
+
+        assert_eq!(subject.credited_transactions_since(0), kept_payments);
+    }
+
+    #[test]
+    fn more_money_received_reverses_orphaned_payments_and_forgets_them() {
+        init_test_logging();
+        let test_name = "more_money_received_reverses_orphaned_payments_and_forgets_them";
+        let home_dir = ensure_node_home_directory_exists("receivable_dao", test_name);
+        let debtor = make_wallet("debtor");
+        let first_payment_time = SystemTime::now()
+            .checked_sub(Duration::from_secs(1000))
+            .unwrap();
+        let mut subject = ReceivableDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        subject.logger = Logger::new(test_name);
+        subject
+            .more_money_receivable(SystemTime::UNIX_EPOCH, &debtor, 1_000_000)
+            .unwrap();
+        let orphaned_payment = BlockchainTransaction {
+            block_number: 100,
+            block_hash: make_tx_hash(1100),
+            transaction_hash: make_tx_hash(11),
+            from: debtor.clone(),
+            wei_amount: 40_000,
+        };
+        let orphaned_payment_from_stranger = BlockchainTransaction {
+            block_number: 100,
+            block_hash: make_tx_hash(1100),
+            transaction_hash: make_tx_hash(12),
+            from: make_wallet("stranger"),
+            wei_amount: 5_000,
+        };
+        let surviving_payment = BlockchainTransaction {
+            block_number: 99,
+            block_hash: make_tx_hash(1099),
+            transaction_hash: make_tx_hash(10),
+            from: debtor.clone(),
+            wei_amount: 300_000,
+        };
+        subject
+            .more_money_received(
+                first_payment_time,
+                &[
+                    surviving_payment.clone(),
+                    orphaned_payment.clone(),
+                    orphaned_payment_from_stranger.clone(),
+                ],
+                &[],
+            )
+            .commit()
+            .unwrap();
+        let remined_payment = BlockchainTransaction {
+            block_number: 101,
+            block_hash: make_tx_hash(2101),
+            transaction_hash: make_tx_hash(14),
+            from: debtor.clone(),
+            wei_amount: 7_000,
+        };
+
+        let txn = subject.more_money_received(
+            SystemTime::now(),
+            &[remined_payment.clone()],
+            &[orphaned_payment, orphaned_payment_from_stranger],
+        );
+
+        txn.commit().unwrap();
+        let status = subject.account_status(&debtor).unwrap();
+        assert_eq!(status.balance_wei, 1_000_000 - 300_000 - 7_000);
+        assert_eq!(
+            subject.credited_transactions_since(0),
+            vec![surviving_payment, remined_payment]
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "DEBUG: {test_name}: Orphaned payment from {} concerned no known debtor; \
+            nothing to reverse",
+            make_wallet("stranger")
+        ));
+    }
+
     #[test]
     fn more_money_received_ignores_unknown_address_without_affecting_the_good_ones() {
         init_test_logging();
@@ -862,22 +1197,28 @@ mod tests {
         subject.logger = logger;
         let transaction_1 = BlockchainTransaction {
             block_number: 4444,
+            block_hash: make_tx_hash(1005),
+            transaction_hash: make_tx_hash(5),
             from: first_tracked_wallet.clone(),
             wei_amount: 1111,
         };
         let transaction_2 = BlockchainTransaction {
             block_number: 4446,
+            block_hash: make_tx_hash(1006),
+            transaction_hash: make_tx_hash(6),
             from: unknown_wallet.clone(),
             wei_amount: 2222,
         };
         let transaction_3 = BlockchainTransaction {
             block_number: 4450,
+            block_hash: make_tx_hash(1007),
+            transaction_hash: make_tx_hash(7),
             from: second_tracked_wallet.clone(),
             wei_amount: 9999,
         };
         let transactions = vec![transaction_1, transaction_2, transaction_3];
 
-        let txn = subject.more_money_received(time_of_change, transactions.as_slice());
+        let txn = subject.more_money_received(time_of_change, transactions.as_slice(), &[]);
 
         txn.commit().unwrap();
         let actual_record_1 = subject.account_status(&first_tracked_wallet).unwrap();
@@ -933,13 +1274,15 @@ mod tests {
         let mut subject = ReceivableDaoReal::new(Box::new(conn));
         let transaction = BlockchainTransaction {
             block_number: 123_456,
+            block_hash: make_tx_hash(1008),
+            transaction_hash: make_tx_hash(8),
             from: wallet,
             wei_amount: 45_678,
         };
         let transactions = vec![transaction];
 
         let caught_err = catch_unwind(AssertUnwindSafe(|| {
-            let _ = subject.more_money_received(time_of_change, transactions.as_slice());
+            let _ = subject.more_money_received(time_of_change, transactions.as_slice(), &[]);
         }))
         .unwrap_err();
 
@@ -980,12 +1323,14 @@ mod tests {
         };
         let transaction = BlockchainTransaction {
             block_number: 123_456,
+            block_hash: make_tx_hash(1009),
+            transaction_hash: make_tx_hash(9),
             from: make_wallet("abc"),
             wei_amount: 1,
         };
         let transactions = vec![transaction];
 
-        subject.more_money_received(SystemTime::now(), &transactions);
+        subject.more_money_received(SystemTime::now(), &transactions, &[]);
     }
 
     #[test]
@@ -1046,18 +1391,22 @@ mod tests {
         subject.logger = Logger::new(test_name);
         let first_transaction = BlockchainTransaction {
             block_number: 123_456,
+            block_hash: make_tx_hash(1010),
+            transaction_hash: make_tx_hash(10),
             from: first_wallet.clone(),
             wei_amount: 45_678,
         };
         let second_transaction = BlockchainTransaction {
             block_number: 789_123,
+            block_hash: make_tx_hash(1011),
+            transaction_hash: make_tx_hash(11),
             from: second_wallet,
             wei_amount: 111_222,
         };
         let transactions = vec![first_transaction, second_transaction];
 
         let result = catch_unwind(AssertUnwindSafe(|| {
-            let _ = subject.more_money_received(time_of_change, &transactions);
+            let _ = subject.more_money_received(time_of_change, &transactions, &[]);
         }));
 
         let caught_panic = result.unwrap_err();
@@ -1149,6 +1498,8 @@ mod tests {
         .unwrap();
         let suspect = BlockchainTransaction {
             block_number: 1234,
+            block_hash: make_tx_hash(1012),
+            transaction_hash: make_tx_hash(12),
             from: wallet,
             wei_amount: 1_000_000_000,
         };
@@ -1474,6 +1825,42 @@ mod tests {
         assert_eq!(result.len(), 1);
     }
 
+    #[test]
+    fn paid_delinquencies_leaves_manual_bans_alone() {
+        let payment_thresholds = PaymentThresholds {
+            maturity_threshold_sec: 0,
+            payment_grace_period_sec: 0,
+            permanent_debt_allowed_gwei: 0,
+            debt_threshold_gwei: 0,
+            threshold_interval_sec: 0,
+            unban_below_gwei: 50,
+        };
+        let mut automatically_banned = make_receivable_account(1234, false);
+        automatically_banned.balance_wei = gwei_to_wei(25);
+        let mut manually_banned = make_receivable_account(2345, false);
+        manually_banned.balance_wei = gwei_to_wei(25);
+        let home_dir = ensure_node_home_directory_exists(
+            "receivable_dao",
+            "paid_delinquencies_leaves_manual_bans_alone",
+        );
+        let db_initializer = DbInitializerReal::default();
+        let conn = db_initializer
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        add_receivable_account(&conn, &automatically_banned);
+        add_receivable_account(&conn, &manually_banned);
+        add_banned_account(&conn, &automatically_banned);
+        conn.prepare("insert into banned (wallet_address, manual) values (?, 1)")
+            .unwrap()
+            .execute(&[&manually_banned.wallet])
+            .unwrap();
+        let subject = ReceivableDaoReal::new(conn);
+
+        let result = subject.paid_delinquencies(&payment_thresholds);
+
+        assert_eq!(result, vec![automatically_banned]);
+    }
+
     #[test]
     fn custom_query_handles_empty_table_in_top_records_mode() {
         let main_test_setup = |_conn: &dyn ConnectionWrapper, _insert: InsertReceivableHelperFn| {};
@@ -1609,6 +1996,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn custom_query_in_page_mode_walks_through_accounts_by_wallet() {
+        //Unlike top records, pages include accounts with negative balances and those whose balances
+        //are smaller than one gwei
+        let now = now_time_t();
+        let main_test_setup = |conn: &dyn ConnectionWrapper, insert: InsertReceivableHelperFn| {
+            common_setup_of_accounts_for_tests_of_top_records(now)(conn, insert);
+            insert(
+                conn,
+                "0x6666666666666666666666666666666666666666",
+                gwei_to_wei(-560_001),
+                now - 50_000,
+            );
+            insert(
+                conn,
+                "0x7777777777777777777777777777777777777777",
+                -999_999_999, //above -1 gwei
+                now - 50_000,
+            );
+        };
+        let subject = custom_query_test_body_for_receivable(
+            "custom_query_in_page_mode_walks_through_accounts_by_wallet",
+            main_test_setup,
+        );
+
+        let second_page = subject
+            .custom_query(CustomQuery::Page {
+                offset: 3,
+                count: 3,
+            })
+            .unwrap();
+        let beyond_last_page = subject.custom_query(CustomQuery::Page {
+            offset: 7,
+            count: 3,
+        });
+
+        assert_eq!(
+            second_page,
+            vec![
+                ReceivableAccount {
+                    wallet: Wallet::new("0x4444444444444444444444444444444444444444"),
+                    balance_wei: 1_000_000_000,
+                    last_received_timestamp: from_time_t(now - 86_111),
+                },
+                ReceivableAccount {
+                    wallet: Wallet::new("0x5555555555555555555555555555555555555555"),
+                    balance_wei: 32_000_000_200,
+                    last_received_timestamp: from_time_t(now - 86_480),
+                },
+                ReceivableAccount {
+                    wallet: Wallet::new("0x6666666666666666666666666666666666666666"),
+                    balance_wei: gwei_to_wei(-560_001),
+                    last_received_timestamp: from_time_t(now - 50_000),
+                },
+            ]
+        );
+        assert_eq!(beyond_last_page, None)
+    }
+
     #[test]
     fn custom_query_handles_empty_table_in_range_mode() {
         let main_test_setup = |_conn: &dyn ConnectionWrapper, _insert: InsertReceivableHelperFn| {};
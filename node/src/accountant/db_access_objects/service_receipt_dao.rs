@@ -0,0 +1,398 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::accountant::checked_conversion;
+use crate::accountant::db_access_objects::utils::{
+    from_time_t, to_time_t, DaoFactoryReal, VigilantRusqliteFlatten,
+};
+use crate::accountant::db_big_integer::big_int_divider::BigIntDivider;
+use crate::database::rusqlite_wrappers::ConnectionWrapper;
+use crate::sub_lib::accountant::ServiceReceipt_0v1;
+use crate::sub_lib::cryptde::{CryptData, PublicKey};
+use crate::sub_lib::wallet::Wallet;
+use masq_lib::utils::ExpectValue;
+use rusqlite::Row;
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredServiceReceipt {
+    pub receipt: ServiceReceipt_0v1,
+    pub received: SystemTime,
+}
+
+// Route IDs are chosen by the consumer, so without a cap a single consumer could fill the table
+pub const MAX_RECEIPTS_PER_CONSUMER: usize = 1_000;
+
+pub trait ServiceReceiptDao: Send {
+    // Keeps only the latest receipt per consumer, route, provider wallet and first service; since
+    // receipts are cumulative, one acknowledging fewer services than the stored one is stale and
+    // gets dropped. Route IDs start over when the consumer restarts, but the first service doesn't.
+    // Beyond MAX_RECEIPTS_PER_CONSUMER receipts signed with one consumer key, the oldest ones go.
+    fn store(&self, receipt: &ServiceReceipt_0v1, received: SystemTime);
+    fn receipts(&self) -> Vec<StoredServiceReceipt>;
+    fn delete_older_than(&self, cutoff: SystemTime);
+}
+
+pub trait ServiceReceiptDaoFactory {
+    fn make(&self) -> Box<dyn ServiceReceiptDao>;
+}
+
+impl ServiceReceiptDaoFactory for DaoFactoryReal {
+    fn make(&self) -> Box<dyn ServiceReceiptDao> {
+        Box::new(ServiceReceiptDaoReal::new(self.make_connection()))
+    }
+}
+
+pub struct ServiceReceiptDaoReal {
+    conn: Box<dyn ConnectionWrapper>,
+    max_receipts_per_consumer: usize,
+}
+
+impl ServiceReceiptDaoReal {
+    pub fn new(conn: Box<dyn ConnectionWrapper>) -> Self {
+        Self {
+            conn,
+            max_receipts_per_consumer: MAX_RECEIPTS_PER_CONSUMER,
+        }
+    }
+
+    fn enforce_cap(&self, receipt: &ServiceReceipt_0v1) {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "delete from service_receipt where consumer_key = ? and rowid not in \
+                (select rowid from service_receipt where consumer_key = ? \
+                order by received desc, rowid desc limit ?)",
+            )
+            .expect("Failed to prepare a statement");
+        if let Err(e) = stmt.execute(rusqlite::params![
+            receipt.consumer_key.as_slice(),
+            receipt.consumer_key.as_slice(),
+            checked_conversion::<usize, i64>(self.max_receipts_per_consumer)
+        ]) {
+            panic!(
+                "Could not trim service receipts from {} because of database corruption: {}",
+                receipt.consumer_key, e
+            )
+        }
+    }
+
+    fn create_stored_receipt(row: &Row) -> rusqlite::Result<StoredServiceReceipt> {
+        let consumer_key: Vec<u8> = row.get(0).expectv("consumer key");
+        let consuming_wallet: Wallet = row.get(1).expectv("consuming wallet address");
+        let provider_wallet: Wallet = row.get(2).expectv("provider wallet address");
+        let route_id: i64 = row.get(3).expectv("route id");
+        let first_service: i64 = row.get(4).expectv("first service");
+        let services: i64 = row.get(5).expectv("services");
+        let bytes: i64 = row.get(6).expectv("bytes");
+        let amount_high_b: i64 = row.get(7).expectv("amount high bytes");
+        let amount_low_b: i64 = row.get(8).expectv("amount low bytes");
+        let signature: Vec<u8> = row.get(9).expectv("signature");
+        let received: i64 = row.get(10).expectv("received");
+        Ok(StoredServiceReceipt {
+            receipt: ServiceReceipt_0v1 {
+                consumer_key: PublicKey::from(consumer_key),
+                consuming_wallet,
+                provider_wallet,
+                route_id: checked_conversion::<i64, u32>(route_id),
+                first_service_s: checked_conversion::<i64, u64>(first_service),
+                services: checked_conversion::<i64, u64>(services),
+                bytes: checked_conversion::<i64, u64>(bytes),
+                amount_wei: checked_conversion::<i128, u128>(BigIntDivider::reconstitute(
+                    amount_high_b,
+                    amount_low_b,
+                )),
+                signature: CryptData::from(signature),
+            },
+            received: from_time_t(received),
+        })
+    }
+}
+
+impl ServiceReceiptDao for ServiceReceiptDaoReal {
+    fn store(&self, receipt: &ServiceReceipt_0v1, received: SystemTime) {
+        let (amount_high_b, amount_low_b) =
+            BigIntDivider::deconstruct(checked_conversion::<u128, i128>(receipt.amount_wei));
+        let mut stmt = self
+            .conn
+            .prepare(
+                "insert into service_receipt (consumer_key, consuming_wallet_address, \
+                provider_wallet_address, route_id, first_service, services, bytes, amount_high_b, \
+                amount_low_b, signature, received) values (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+                on conflict (consumer_key, route_id, provider_wallet_address, first_service) do update set \
+                consuming_wallet_address = excluded.consuming_wallet_address, \
+                first_service = excluded.first_service, services = excluded.services, \
+                bytes = excluded.bytes, amount_high_b = excluded.amount_high_b, \
+                amount_low_b = excluded.amount_low_b, signature = excluded.signature, \
+                received = excluded.received where excluded.services >= services",
+            )
+            .expect("Failed to prepare a statement");
+        if let Err(e) = stmt.execute(rusqlite::params![
+            receipt.consumer_key.as_slice(),
+            receipt.consuming_wallet,
+            receipt.provider_wallet,
+            receipt.route_id,
+            checked_conversion::<u64, i64>(receipt.first_service_s),
+            checked_conversion::<u64, i64>(receipt.services),
+            checked_conversion::<u64, i64>(receipt.bytes),
+            amount_high_b,
+            amount_low_b,
+            receipt.signature.as_slice(),
+            to_time_t(received)
+        ]) {
+            panic!(
+                "Could not store service receipt from {} because of database corruption: {}",
+                receipt.consuming_wallet, e
+            )
+        }
+        self.enforce_cap(receipt)
+    }
+
+    fn receipts(&self) -> Vec<StoredServiceReceipt> {
+        self.conn
+            .prepare(
+                "select consumer_key, consuming_wallet_address, provider_wallet_address, route_id, \
+                first_service, services, bytes, amount_high_b, amount_low_b, signature, received \
+                from service_receipt order by first_service, route_id",
+            )
+            .expect("Couldn't prepare statement")
+            .query_map([], Self::create_stored_receipt)
+            .expect("Couldn't retrieve service receipts: database corruption")
+            .vigilant_flatten()
+            .collect()
+    }
+
+    fn delete_older_than(&self, cutoff: SystemTime) {
+        let mut stmt = self
+            .conn
+            .prepare("delete from service_receipt where received < ?")
+            .expect("Failed to prepare a statement");
+        if let Err(e) = stmt.execute([to_time_t(cutoff)]) {
+            panic!(
+                "Could not trim service receipts because of database corruption: {}",
+                e
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal,
+    };
+    use crate::sub_lib::cryptde_null::CryptDENull;
+    use crate::test_utils::make_wallet;
+    use masq_lib::test_utils::utils::{ensure_node_home_directory_exists, TEST_DEFAULT_CHAIN};
+
+    fn make_subject(test_name: &str) -> ServiceReceiptDaoReal {
+        let home_dir = ensure_node_home_directory_exists("service_receipt_dao", test_name);
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        ServiceReceiptDaoReal::new(conn)
+    }
+
+    fn make_receipt(consumer: &str, route_id: u32, services: u64) -> ServiceReceipt_0v1 {
+        let cryptde = CryptDENull::from(&PublicKey::new(consumer.as_bytes()), TEST_DEFAULT_CHAIN);
+        ServiceReceipt_0v1 {
+            consumer_key: PublicKey::new(&[]),
+            consuming_wallet: make_wallet(consumer),
+            provider_wallet: make_wallet("provider"),
+            route_id,
+            first_service_s: 1_700_000_000,
+            services,
+            bytes: services * 1000,
+            amount_wei: u64::MAX as u128 * services as u128,
+            signature: CryptData::new(&[]),
+        }
+        .signed(&cryptde)
+    }
+
+    fn at(time_t: i64) -> SystemTime {
+        from_time_t(time_t)
+    }
+
+    #[test]
+    fn stored_receipts_come_back_intact() {
+        let subject = make_subject("stored_receipts_come_back_intact");
+        let first = make_receipt("consumer_a", 1, 3);
+        let second = make_receipt("consumer_b", 2, 5);
+
+        subject.store(&first, at(1_700_000_100));
+        subject.store(&second, at(1_700_000_200));
+
+        assert_eq!(
+            subject.receipts(),
+            vec![
+                StoredServiceReceipt {
+                    receipt: first,
+                    received: at(1_700_000_100)
+                },
+                StoredServiceReceipt {
+                    receipt: second,
+                    received: at(1_700_000_200)
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn a_later_receipt_replaces_the_earlier_one_for_the_same_route() {
+        let subject = make_subject("a_later_receipt_replaces_the_earlier_one_for_the_same_route");
+        let later = make_receipt("consumer", 1, 7);
+        let other_route = make_receipt("consumer", 2, 1);
+
+        subject.store(&make_receipt("consumer", 1, 4), at(1_700_000_100));
+        subject.store(&other_route, at(1_700_000_150));
+        subject.store(&later, at(1_700_000_200));
+
+        assert_eq!(
+            subject.receipts(),
+            vec![
+                StoredServiceReceipt {
+                    receipt: later,
+                    received: at(1_700_000_200)
+                },
+                StoredServiceReceipt {
+                    receipt: other_route,
+                    received: at(1_700_000_150)
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn a_stale_receipt_does_not_replace_a_newer_one() {
+        let subject = make_subject("a_stale_receipt_does_not_replace_a_newer_one");
+        let newer = make_receipt("consumer", 1, 7);
+        subject.store(&newer, at(1_700_000_100));
+
+        subject.store(&make_receipt("consumer", 1, 4), at(1_700_000_200));
+
+        assert_eq!(
+            subject.receipts(),
+            vec![StoredServiceReceipt {
+                receipt: newer,
+                received: at(1_700_000_100)
+            }]
+        )
+    }
+
+    #[test]
+    fn a_receipt_from_a_later_run_of_the_consumer_is_kept_beside_the_earlier_one() {
+        let subject = make_subject(
+            "a_receipt_from_a_later_run_of_the_consumer_is_kept_beside_the_earlier_one",
+        );
+        let cryptde = CryptDENull::from(&PublicKey::new(b"consumer"), TEST_DEFAULT_CHAIN);
+        let earlier_run = make_receipt("consumer", 1, 7);
+        let later_run = ServiceReceipt_0v1 {
+            first_service_s: 1_700_100_000,
+            ..make_receipt("consumer", 1, 2)
+        }
+        .signed(&cryptde);
+        subject.store(&earlier_run, at(1_700_000_100));
+
+        subject.store(&later_run, at(1_700_100_100));
+
+        assert_eq!(
+            subject.receipts(),
+            vec![
+                StoredServiceReceipt {
+                    receipt: earlier_run,
+                    received: at(1_700_000_100)
+                },
+                StoredServiceReceipt {
+                    receipt: later_run,
+                    received: at(1_700_100_100)
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn the_cap_applies_per_consumer_key_whatever_wallets_the_receipts_name() {
+        let mut subject =
+            make_subject("the_cap_applies_per_consumer_key_whatever_wallets_the_receipts_name");
+        subject.max_receipts_per_consumer = 2;
+        let cryptde = CryptDENull::from(&PublicKey::new(b"consumer"), TEST_DEFAULT_CHAIN);
+        let naming = |wallet: &str, route_id: u32| {
+            ServiceReceipt_0v1 {
+                consuming_wallet: make_wallet(wallet),
+                ..make_receipt("consumer", route_id, 1)
+            }
+            .signed(&cryptde)
+        };
+        let second = naming("wallet_b", 2);
+        let third = naming("wallet_c", 3);
+        subject.store(&naming("wallet_a", 1), at(1_000));
+        subject.store(&second, at(2_000));
+
+        subject.store(&third, at(3_000));
+
+        assert_eq!(
+            subject.receipts(),
+            vec![
+                StoredServiceReceipt {
+                    receipt: second,
+                    received: at(2_000)
+                },
+                StoredServiceReceipt {
+                    receipt: third,
+                    received: at(3_000)
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn only_the_newest_receipts_of_one_consumer_are_kept_beyond_the_cap() {
+        let mut subject =
+            make_subject("only_the_newest_receipts_of_one_consumer_are_kept_beyond_the_cap");
+        subject.max_receipts_per_consumer = 2;
+        let other_consumer = make_receipt("other", 1, 1);
+        let second = make_receipt("consumer", 2, 1);
+        let third = make_receipt("consumer", 3, 1);
+        subject.store(&other_consumer, at(1_000));
+        subject.store(&make_receipt("consumer", 1, 1), at(2_000));
+        subject.store(&second, at(3_000));
+
+        subject.store(&third, at(4_000));
+
+        assert_eq!(
+            subject.receipts(),
+            vec![
+                StoredServiceReceipt {
+                    receipt: other_consumer,
+                    received: at(1_000)
+                },
+                StoredServiceReceipt {
+                    receipt: second,
+                    received: at(3_000)
+                },
+                StoredServiceReceipt {
+                    receipt: third,
+                    received: at(4_000)
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn delete_older_than_trims_receipts() {
+        let subject = make_subject("delete_older_than_trims_receipts");
+        let fresh = make_receipt("consumer_b", 2, 5);
+        subject.store(&make_receipt("consumer_a", 1, 3), at(1_000));
+        subject.store(&fresh, at(3_000));
+
+        subject.delete_older_than(at(2_000));
+
+        assert_eq!(
+            subject.receipts(),
+            vec![StoredServiceReceipt {
+                receipt: fresh,
+                received: at(3_000)
+            }]
+        )
+    }
+}
@@ -1,5 +1,7 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use crate::accountant::db_access_objects::banned_dao::BannedAccount;
+use crate::accountant::db_access_objects::ledger_dao::LedgerEntry;
 use crate::accountant::db_access_objects::payable_dao::PayableAccount;
 use crate::accountant::db_access_objects::receivable_dao::ReceivableAccount;
 use crate::accountant::db_big_integer::big_int_divider::BigIntDivider;
@@ -11,7 +13,8 @@ use crate::database::rusqlite_wrappers::ConnectionWrapper;
 use crate::sub_lib::accountant::PaymentThresholds;
 use masq_lib::constants::WEIS_IN_GWEI;
 use masq_lib::messages::{
-    RangeQuery, TopRecordsConfig, TopRecordsOrdering, UiPayableAccount, UiReceivableAccount,
+    FinancialsPage, RangeQuery, TopRecordsConfig, TopRecordsOrdering, UiBannedWallet,
+    UiLedgerEntry, UiPayableAccount, UiReceivableAccount,
 };
 use rusqlite::{Row, Statement, ToSql};
 use std::fmt::{Debug, Display};
@@ -71,12 +74,25 @@ impl<T> From<TopRecordsConfig> for CustomQuery<T> {
     }
 }
 
+impl<T> From<FinancialsPage> for CustomQuery<T> {
+    fn from(page: FinancialsPage) -> Self {
+        CustomQuery::Page {
+            offset: page.offset,
+            count: page.count,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CustomQuery<N> {
     TopRecords {
         count: u16,
         ordered_by: TopRecordsOrdering,
     },
+    Page {
+        offset: u32,
+        count: u16,
+    },
     RangeQuery {
         min_age_s: u64,
         max_age_s: u64,
@@ -108,7 +124,7 @@ pub struct AssemblerFeeder {
     pub limit_clause: &'static str,
 }
 
-//be aware that balances smaller than one gwei won't be shown,
+//be aware that balances smaller than one gwei won't be shown except in pages,
 //if there aren't any bigger ones the function returns None
 impl<N: Copy + Display> CustomQuery<N> {
     pub fn query<R, S, F1, F2>(
@@ -140,6 +156,20 @@ impl<N: Copy + Display> CustomQuery<N> {
                     vec![(":limit_count", Box::new(count as i64))],
                 )
             }
+            // Pages serve exports of the books, which have to be complete down to the last wei
+            Self::Page { offset, count } => (
+                stm_assembler(AssemblerFeeder {
+                    main_where_clause: "where wallet_address is not null",
+                    where_clause_extension: "",
+                    order_by_first_param: "wallet_address asc",
+                    order_by_second_param: variant_top.age_ordering_clause,
+                    limit_clause: "limit :limit_count offset :offset",
+                }),
+                vec![
+                    (":limit_count", Box::new(count as i64)),
+                    (":offset", Box::new(offset as i64)),
+                ],
+            ),
             Self::RangeQuery {
                 min_age_s: min_age,
                 max_age_s: max_age,
@@ -275,6 +305,52 @@ pub fn remap_payable_accounts(accounts: Vec<PayableAccount>) -> Vec<UiPayableAcc
             pending_payable_hash_opt: account
                 .pending_payable_opt
                 .map(|full_id| full_id.hash.to_string()),
+            balance_wei_opt: None,
+        })
+        .collect()
+}
+
+// Pages aren't limited to balances of at least one gwei, so their accounts carry the exact balance
+pub fn remap_payable_page(accounts: Vec<PayableAccount>) -> Vec<UiPayableAccount> {
+    accounts
+        .into_iter()
+        .map(|account| UiPayableAccount {
+            wallet: account.wallet.to_string(),
+            age_s: to_age(account.last_paid_timestamp),
+            balance_gwei: (account.balance_wei / (WEIS_IN_GWEI as u128)) as u64,
+            pending_payable_hash_opt: account
+                .pending_payable_opt
+                .map(|full_id| full_id.hash.to_string()),
+            balance_wei_opt: Some(account.balance_wei.to_string()),
+        })
+        .collect()
+}
+
+pub fn remap_ledger_entries(entries: Vec<LedgerEntry>) -> Vec<UiLedgerEntry> {
+    entries
+        .into_iter()
+        .map(|entry| UiLedgerEntry {
+            timestamp_s: checked_conversion::<i64, u64>(to_time_t(entry.timestamp)),
+            wallet: entry.wallet.to_string(),
+            entry_type: entry.entry_type.to_string(),
+            bytes: entry.bytes,
+            amount_wei: entry.amount_wei,
+            transaction_hash_opt: entry.transaction_hash_opt.map(|hash| format!("{:?}", hash)),
+            entry_count_opt: entry.entry_count_opt,
+        })
+        .collect()
+}
+
+pub fn remap_banned_accounts(accounts: Vec<BannedAccount>) -> Vec<UiBannedWallet> {
+    accounts
+        .into_iter()
+        .map(|account| UiBannedWallet {
+            wallet: account.wallet.to_string(),
+            reason_opt: account.reason_opt,
+            timestamp_s_opt: account
+                .timestamp_opt
+                .map(|timestamp| checked_conversion::<i64, u64>(to_time_t(timestamp))),
+            manual: account.manual,
         })
         .collect()
 }
@@ -293,7 +369,19 @@ pub fn remap_receivable_accounts(accounts: Vec<ReceivableAccount>) -> Vec<UiRece
                                                  account.wallet,
                                                  account.balance_wei
                 )}
-            },
+            }, balance_wei_opt: None,
+        })
+        .collect()
+}
+
+pub fn remap_receivable_page(accounts: Vec<ReceivableAccount>) -> Vec<UiReceivableAccount> {
+    accounts
+        .into_iter()
+        .map(|account| UiReceivableAccount {
+            wallet: account.wallet.to_string(),
+            age_s: to_age(account.last_received_timestamp),
+            balance_gwei: (account.balance_wei / (WEIS_IN_GWEI as i128)) as i64,
+            balance_wei_opt: Some(account.balance_wei.to_string()),
         })
         .collect()
 }
@@ -508,6 +596,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn remap_of_pages_keeps_exact_balances_even_below_one_gwei() {
+        let last_paid_timestamp = SystemTime::now();
+        let payables = vec![PayableAccount {
+            wallet: make_wallet("abc123"),
+            balance_wei: 565_122_333,
+            last_paid_timestamp,
+            pending_payable_opt: None,
+        }];
+        let receivables = vec![ReceivableAccount {
+            wallet: make_wallet("ac3665c"),
+            balance_wei: -4_290_122_333,
+            last_received_timestamp: last_paid_timestamp,
+        }];
+
+        let payable_result = remap_payable_page(payables);
+        let receivable_result = remap_receivable_page(receivables);
+
+        assert_eq!(payable_result[0].balance_gwei, 0);
+        assert_eq!(
+            payable_result[0].balance_wei_opt,
+            Some("565122333".to_string())
+        );
+        assert_eq!(receivable_result[0].balance_gwei, -4);
+        assert_eq!(
+            receivable_result[0].balance_wei_opt,
+            Some("-4290122333".to_string())
+        );
+    }
+
     #[test]
     #[should_panic(
         expected = "Broken code: PayableAccount with less than 1 gwei passed through db query constraints; \
@@ -86,7 +86,10 @@ pub(in crate::accountant) mod visibility_restricted_module {
         msg: &UiFinancialsRequest,
         context_id: u64,
     ) -> Result<(), MessageBody> {
-        if !msg.stats_required && msg.top_records_opt.is_none() && msg.custom_queries_opt.is_none()
+        if !msg.stats_required
+            && msg.top_records_opt.is_none()
+            && msg.custom_queries_opt.is_none()
+            && msg.page_opt.is_none()
         {
             Err(MessageBody {
                 opcode: OPCODE_FINANCIALS.to_string(),
@@ -102,6 +105,14 @@ pub(in crate::accountant) mod visibility_restricted_module {
                 path: MessagePath::Conversation(context_id),
                 payload: Err((REQUEST_WITH_MUTUALLY_EXCLUSIVE_PARAMS, "Requesting top records and the more customized subset of records is not allowed both at the same time".to_string())),
             })
+        } else if msg.page_opt.is_some()
+            && (msg.top_records_opt.is_some() || msg.custom_queries_opt.is_some())
+        {
+            Err(MessageBody {
+                opcode: OPCODE_FINANCIALS.to_string(),
+                path: MessagePath::Conversation(context_id),
+                payload: Err((REQUEST_WITH_MUTUALLY_EXCLUSIVE_PARAMS, "Requesting a page of all records together with top records or the more customized subset of records is not allowed".to_string())),
+            })
         } else {
             Ok(())
         }
@@ -1,33 +1,50 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+pub mod balance_forecast;
 pub mod db_access_objects;
 pub mod db_big_integer;
 pub mod financials;
 pub mod payment_adjuster;
 pub mod scanners;
+pub mod threshold_simulator;
 
 #[cfg(test)]
 pub mod test_utils;
 
 use core::fmt::Debug;
-use masq_lib::constants::{SCAN_ERROR, WEIS_IN_GWEI};
+use masq_lib::constants::{
+    BAN_REQUEST_ERROR, INVALID_LEDGER_QUERY, INVALID_THRESHOLDS_SIMULATION, SCAN_ERROR,
+    WEIS_IN_GWEI,
+};
 use std::cell::{Ref, RefCell};
 
+use crate::accountant::balance_forecast::BalanceForecast;
+use crate::accountant::db_access_objects::banned_dao::{BanOrigin, BannedDao};
+use crate::accountant::db_access_objects::ledger_dao::{
+    LedgerDao, LedgerEntry, LedgerEntryType, LedgerQuery,
+};
 use crate::accountant::db_access_objects::payable_dao::{PayableDao, PayableDaoError};
-use crate::accountant::db_access_objects::pending_payable_dao::PendingPayableDao;
+use crate::accountant::db_access_objects::pending_payable_dao::{
+    PendingPayableDao, PendingPayableReplacement,
+};
 use crate::accountant::db_access_objects::receivable_dao::{ReceivableDao, ReceivableDaoError};
+use crate::accountant::db_access_objects::service_receipt_dao::ServiceReceiptDao;
 use crate::accountant::db_access_objects::utils::{
-    remap_payable_accounts, remap_receivable_accounts, CustomQuery, DaoFactoryReal,
+    remap_banned_accounts, remap_ledger_entries, remap_payable_accounts, remap_payable_page,
+    remap_receivable_accounts, remap_receivable_page, CustomQuery, DaoFactoryReal,
 };
 use crate::accountant::financials::visibility_restricted_module::{
     check_query_is_within_tech_limits, financials_entry_check,
 };
+use crate::accountant::payment_adjuster::saturate_u256;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::{
     BlockchainAgentWithContextMessage, QualifiedPayablesMessage,
 };
 use crate::accountant::scanners::{BeginScanError, ScanSchedulers, Scanners};
+use crate::accountant::threshold_simulator::{SimulatedPayable, SimulatedReceivable};
 use crate::blockchain::blockchain_bridge::{
-    PendingPayableFingerprint, PendingPayableFingerprintSeeds, RetrieveTransactions,
+    PendingPayableFingerprint, PendingPayableFingerprintSeeds, RequestConsumingWalletBalances,
+    RetrieveTransactions,
 };
 use crate::blockchain::blockchain_interface::data_structures::errors::PayableTransactionError;
 use crate::blockchain::blockchain_interface::data_structures::{
@@ -35,14 +52,17 @@ use crate::blockchain::blockchain_interface::data_structures::{
 };
 use crate::bootstrapper::BootstrapperConfig;
 use crate::database::db_initializer::DbInitializationConfig;
-use crate::sub_lib::accountant::AccountantSubs;
+use crate::metrics::{NodeMetrics, ScanOutcome};
 use crate::sub_lib::accountant::DaoFactories;
 use crate::sub_lib::accountant::FinancialStatistics;
 use crate::sub_lib::accountant::ReportExitServiceProvidedMessage;
 use crate::sub_lib::accountant::ReportRoutingServiceProvidedMessage;
 use crate::sub_lib::accountant::ReportServicesConsumedMessage;
+use crate::sub_lib::accountant::ServiceReceipt_0v1;
+use crate::sub_lib::accountant::{AccountantSubs, PaymentThresholds};
 use crate::sub_lib::accountant::{MessageIdGenerator, MessageIdGeneratorReal};
-use crate::sub_lib::blockchain_bridge::OutboundPaymentsInstructions;
+use crate::sub_lib::blockchain_bridge::{ConsumingWalletBalances, OutboundPaymentsInstructions};
+use crate::sub_lib::hopper::ExpiredCoresPackage;
 use crate::sub_lib::neighborhood::{ConfigChange, ConfigChangeMsg};
 use crate::sub_lib::peer_actors::{BindMessage, StartMessage};
 use crate::sub_lib::utils::{handle_ui_crash_request, NODE_MAILBOX_CAPACITY};
@@ -61,25 +81,38 @@ use masq_lib::logger::Logger;
 use masq_lib::messages::UiFinancialsResponse;
 use masq_lib::messages::{FromMessageBody, ToMessageBody, UiFinancialsRequest};
 use masq_lib::messages::{
-    QueryResults, ScanType, UiFinancialStatistics, UiPayableAccount, UiReceivableAccount,
-    UiScanRequest,
+    QueryResults, ScanType, UiBanChangedBroadcast, UiBanListRequest, UiBanListResponse,
+    UiBanRequest, UiBanResponse, UiFinancialStatistics, UiLedgerRequest, UiLedgerResponse,
+    UiPayableAccount, UiReceivableAccount, UiReconciliationAccount, UiReconciliationRequest,
+    UiReconciliationResponse, UiScanRequest, UiSimulateThresholdsRequest,
+    UiSimulateThresholdsResponse, UiUnbanRequest, UiUnbanResponse, UiWalletBalanceWarningBroadcast,
 };
-use masq_lib::ui_gateway::MessageTarget::ClientId;
-use masq_lib::ui_gateway::{MessageBody, MessagePath};
+use masq_lib::ui_gateway::MessageTarget::{AllClients, AllExcept, ClientId};
+use masq_lib::ui_gateway::{MessageBody, MessagePath, MessageTarget};
 use masq_lib::ui_gateway::{NodeFromUiMessage, NodeToUiMessage};
 use masq_lib::utils::ExpectValue;
 use std::any::type_name;
+use std::collections::BTreeMap;
 #[cfg(test)]
 use std::default::Default;
 use std::fmt::Display;
 use std::ops::{Div, Mul};
 use std::path::Path;
 use std::rc::Rc;
-use std::time::SystemTime;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use web3::types::{TransactionReceipt, H256};
 
 pub const CRASH_KEY: &str = "ACCOUNTANT";
 pub const DEFAULT_PENDING_TOO_LONG_SEC: u64 = 21_600; //6 hours
+pub const DEFAULT_PENDING_STUCK_SEC: u64 = 1_800; //30 minutes
+pub const DEFAULT_CONFIRMATION_DEPTH: u64 = 12; //blocks
+pub const DEFAULT_LEDGER_ROLLUP_DAYS: u64 = 30;
+// Receipts only matter for reconciling recent accounts, so they are trimmed even if the ledger isn't
+pub const DEFAULT_SERVICE_RECEIPT_RETENTION_DAYS: u64 = 90;
+pub const DEFAULT_BALANCE_WARNING_DAYS: u64 = 3;
+const SECONDS_IN_DAY: u64 = 86_400;
 
 pub struct Accountant {
     suppress_initial_scans: bool,
@@ -88,18 +121,30 @@ pub struct Accountant {
     payable_dao: Box<dyn PayableDao>,
     receivable_dao: Box<dyn ReceivableDao>,
     pending_payable_dao: Box<dyn PendingPayableDao>,
+    ledger_dao: Box<dyn LedgerDao>,
+    banned_dao: Box<dyn BannedDao>,
+    service_receipt_dao: Box<dyn ServiceReceiptDao>,
+    ledger_rollup_days: u64,
+    ledger_retention_days_opt: Option<u64>,
+    balance_warning_days: u64,
+    balance_forecast: BalanceForecast,
     crashable: bool,
     scanners: Scanners,
     scan_schedulers: ScanSchedulers,
     financial_statistics: Rc<RefCell<FinancialStatistics>>,
+    ban_changes: Rc<RefCell<Vec<UiBanChangedBroadcast>>>,
     outbound_payments_instructions_sub_opt: Option<Recipient<OutboundPaymentsInstructions>>,
     qualified_payables_sub_opt: Option<Recipient<QualifiedPayablesMessage>>,
     retrieve_transactions_sub_opt: Option<Recipient<RetrieveTransactions>>,
     request_transaction_receipts_subs_opt: Option<Recipient<RequestTransactionReceipts>>,
     report_inbound_payments_sub_opt: Option<Recipient<ReceivedPayments>>,
     report_sent_payables_sub_opt: Option<Recipient<SentPayables>>,
+    request_consuming_wallet_balances_sub_opt: Option<Recipient<RequestConsumingWalletBalances>>,
     ui_message_sub_opt: Option<Recipient<NodeToUiMessage>>,
     message_id_generator: Box<dyn MessageIdGenerator>,
+    metrics: Arc<NodeMetrics>,
+    // The ledger totals cost a database query apiece, so they're only refreshed if someone's listening
+    ledger_totals_in_metrics: bool,
     logger: Logger,
 }
 
@@ -124,10 +169,20 @@ pub struct ReceivedPayments {
     // a problem? Do we want to correct the timestamp? Discuss.
     pub timestamp: SystemTime,
     pub payments: Vec<BlockchainTransaction>,
+    // The earliest block re-examined in this scan; every payment credited from here on
+    // is expected to show up again in `payments` unless a reorg has orphaned it
+    pub scanned_from_block: u64,
     pub new_start_block: u64,
     pub response_skeleton_opt: Option<ResponseSkeleton>,
 }
 
+#[derive(Debug, Message, PartialEq, Eq)]
+pub struct ReportConsumingWalletBalances {
+    pub consuming_wallet: Wallet,
+    pub balances: ConsumingWalletBalances,
+    pub transaction_fee_per_payment_wei: u128,
+}
+
 #[derive(Debug, Message, PartialEq)]
 pub struct SentPayables {
     pub payment_procedure_result: Result<Vec<ProcessedPayableFallible>, PayableTransactionError>,
@@ -200,6 +255,18 @@ impl Handler<StartMessage> for Accountant {
     }
 }
 
+impl Handler<ReportConsumingWalletBalances> for Accountant {
+    type Result = ();
+
+    fn handle(
+        &mut self,
+        msg: ReportConsumingWalletBalances,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        self.handle_report_consuming_wallet_balances(msg)
+    }
+}
+
 impl Handler<ReceivedPayments> for Accountant {
     type Result = ();
 
@@ -245,6 +312,10 @@ impl Handler<ScanForPayables> for Accountant {
 
     fn handle(&mut self, msg: ScanForPayables, ctx: &mut Self::Context) -> Self::Result {
         self.handle_request_of_scan_for_payable(msg.response_skeleton_opt);
+        // The scheduled scans also keep the forecast of the consuming wallet's balances fresh
+        if msg.response_skeleton_opt.is_none() {
+            self.request_consuming_wallet_balances();
+        }
         self.schedule_next_scan(ScanType::Payables, ctx);
     }
 }
@@ -262,6 +333,7 @@ impl Handler<ScanForReceivables> for Accountant {
     type Result = ();
 
     fn handle(&mut self, msg: ScanForReceivables, ctx: &mut Self::Context) -> Self::Result {
+        self.maintain_ledger(SystemTime::now());
         self.handle_request_of_scan_for_receivable(msg.response_skeleton_opt);
         self.schedule_next_scan(ScanType::Receivables, ctx);
     }
@@ -271,40 +343,7 @@ impl Handler<ScanError> for Accountant {
     type Result = ();
 
     fn handle(&mut self, scan_error: ScanError, _ctx: &mut Self::Context) -> Self::Result {
-        error!(self.logger, "Received ScanError: {:?}", scan_error);
-        match scan_error.scan_type {
-            ScanType::Payables => {
-                self.scanners.payable.mark_as_ended(&self.logger);
-            }
-            ScanType::PendingPayables => {
-                self.scanners.pending_payable.mark_as_ended(&self.logger);
-            }
-            ScanType::Receivables => {
-                self.scanners.receivable.mark_as_ended(&self.logger);
-            }
-        };
-        if let Some(response_skeleton) = scan_error.response_skeleton_opt {
-            let error_msg = NodeToUiMessage {
-                target: ClientId(response_skeleton.client_id),
-                body: MessageBody {
-                    opcode: "scan".to_string(),
-                    path: MessagePath::Conversation(response_skeleton.context_id),
-                    payload: Err((
-                        SCAN_ERROR,
-                        format!(
-                            "{:?} scan failed: '{}'",
-                            scan_error.scan_type, scan_error.msg
-                        ),
-                    )),
-                },
-            };
-            error!(self.logger, "Sending UiScanResponse: {:?}", error_msg);
-            self.ui_message_sub_opt
-                .as_ref()
-                .expect("UIGateway not bound")
-                .try_send(error_msg)
-                .expect("UiGateway is dead");
-        }
+        self.handle_scan_error(scan_error)
     }
 }
 
@@ -344,6 +383,18 @@ impl Handler<ReportServicesConsumedMessage> for Accountant {
     }
 }
 
+impl Handler<ExpiredCoresPackage<ServiceReceipt_0v1>> for Accountant {
+    type Result = ();
+
+    fn handle(
+        &mut self,
+        msg: ExpiredCoresPackage<ServiceReceipt_0v1>,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        self.handle_service_receipt(msg, SystemTime::now());
+    }
+}
+
 pub trait SkeletonOptHolder {
     fn skeleton_opt(&self) -> Option<ResponseSkeleton>;
 }
@@ -351,9 +402,17 @@ pub trait SkeletonOptHolder {
 #[derive(Debug, PartialEq, Eq, Message, Clone)]
 pub struct RequestTransactionReceipts {
     pub pending_payable: Vec<PendingPayableFingerprint>,
+    pub stuck_payables_opt: Option<StuckPayables>,
     pub response_skeleton_opt: Option<ResponseSkeleton>,
 }
 
+// Those of the pending payables that are to be replaced unless found mined by now
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StuckPayables {
+    pub consuming_wallet: Wallet,
+    pub rowids: Vec<u64>,
+}
+
 impl SkeletonOptHolder for RequestTransactionReceipts {
     fn skeleton_opt(&self) -> Option<ResponseSkeleton> {
         self.response_skeleton_opt
@@ -363,6 +422,7 @@ impl SkeletonOptHolder for RequestTransactionReceipts {
 #[derive(Debug, PartialEq, Message, Clone)]
 pub struct ReportTransactionReceipts {
     pub fingerprints_with_receipts: Vec<(Option<TransactionReceipt>, PendingPayableFingerprint)>,
+    pub replacements: Vec<PendingPayableReplacement>,
     pub response_skeleton_opt: Option<ResponseSkeleton>,
 }
 
@@ -398,6 +458,19 @@ impl Handler<NodeFromUiMessage> for Accountant {
         let client_id = msg.client_id;
         if let Ok((request, context_id)) = UiFinancialsRequest::fmb(msg.body.clone()) {
             self.handle_financials(&request, client_id, context_id)
+        } else if let Ok((request, context_id)) = UiLedgerRequest::fmb(msg.body.clone()) {
+            self.handle_ledger(&request, client_id, context_id)
+        } else if let Ok((_, context_id)) = UiReconciliationRequest::fmb(msg.body.clone()) {
+            self.handle_reconciliation(client_id, context_id)
+        } else if let Ok((_, context_id)) = UiBanListRequest::fmb(msg.body.clone()) {
+            self.handle_ban_list(client_id, context_id)
+        } else if let Ok((request, context_id)) = UiBanRequest::fmb(msg.body.clone()) {
+            self.handle_ban(request, client_id, context_id)
+        } else if let Ok((request, context_id)) = UiUnbanRequest::fmb(msg.body.clone()) {
+            self.handle_unban(request, client_id, context_id)
+        } else if let Ok((request, context_id)) = UiSimulateThresholdsRequest::fmb(msg.body.clone())
+        {
+            self.handle_simulate_thresholds(&request, client_id, context_id)
         } else if let Ok((body, context_id)) = UiScanRequest::fmb(msg.body.clone()) {
             self.handle_externally_triggered_scan(
                 ctx,
@@ -422,11 +495,23 @@ impl Accountant {
         let payable_dao = dao_factories.payable_dao_factory.make();
         let pending_payable_dao = dao_factories.pending_payable_dao_factory.make();
         let receivable_dao = dao_factories.receivable_dao_factory.make();
+        let ledger_dao = dao_factories.ledger_dao_factory.make();
+        let banned_dao = dao_factories.banned_dao_factory.make();
+        let service_receipt_dao = dao_factories.service_receipt_dao_factory.make();
+        let ban_changes = Rc::new(RefCell::new(vec![]));
+        let balance_forecast = BalanceForecast::new(
+            SystemTime::now(),
+            gwei_to_wei(payment_thresholds.debt_threshold_gwei),
+        );
         let scanners = Scanners::new(
             dao_factories,
             Rc::new(payment_thresholds),
             config.when_pending_too_long_sec,
+            config.when_pending_stuck_sec,
+            config.confirmation_depth,
             Rc::clone(&financial_statistics),
+            Rc::clone(&ban_changes),
+            config.metrics.clone(),
         );
 
         Accountant {
@@ -436,18 +521,29 @@ impl Accountant {
             payable_dao,
             receivable_dao,
             pending_payable_dao,
+            ledger_dao,
+            banned_dao,
+            service_receipt_dao,
+            ledger_rollup_days: config.ledger_rollup_days,
+            ledger_retention_days_opt: config.ledger_retention_days_opt,
+            balance_warning_days: config.balance_warning_days,
+            balance_forecast,
             scanners,
             crashable: config.crash_point == CrashPoint::Message,
             scan_schedulers: ScanSchedulers::new(scan_intervals),
             financial_statistics: Rc::clone(&financial_statistics),
+            ban_changes,
             outbound_payments_instructions_sub_opt: None,
             qualified_payables_sub_opt: None,
             report_sent_payables_sub_opt: None,
+            request_consuming_wallet_balances_sub_opt: None,
             retrieve_transactions_sub_opt: None,
             report_inbound_payments_sub_opt: None,
             request_transaction_receipts_subs_opt: None,
             ui_message_sub_opt: None,
             message_id_generator: Box::new(MessageIdGeneratorReal::default()),
+            metrics: config.metrics.clone(),
+            ledger_totals_in_metrics: config.metrics_port_opt.is_some(),
             logger: Logger::new("Accountant"),
         }
     }
@@ -460,11 +556,13 @@ impl Accountant {
             report_routing_service_provided: recipient!(addr, ReportRoutingServiceProvidedMessage),
             report_exit_service_provided: recipient!(addr, ReportExitServiceProvidedMessage),
             report_services_consumed: recipient!(addr, ReportServicesConsumedMessage),
+            service_receipt: recipient!(addr, ExpiredCoresPackage<ServiceReceipt_0v1>),
             report_payable_payments_setup: recipient!(addr, BlockchainAgentWithContextMessage),
             report_inbound_payments: recipient!(addr, ReceivedPayments),
             init_pending_payable_fingerprints: recipient!(addr, PendingPayableFingerprintSeeds),
             report_transaction_receipts: recipient!(addr, ReportTransactionReceipts),
             report_sent_payments: recipient!(addr, SentPayables),
+            report_consuming_wallet_balances: recipient!(addr, ReportConsumingWalletBalances),
             scan_errors: recipient!(addr, ScanError),
             ui_message_sub: recipient!(addr, NodeFromUiMessage),
         }
@@ -481,6 +579,7 @@ impl Accountant {
         timestamp: SystemTime,
         payload_size: usize,
         wallet: &Wallet,
+        entry_type: LedgerEntryType,
     ) {
         let byte_charge = byte_rate as u128 * (payload_size as u128);
         let total_charge = service_rate as u128 + byte_charge;
@@ -488,7 +587,7 @@ impl Accountant {
             match self.receivable_dao
                 .as_ref()
                 .more_money_receivable(timestamp,wallet, total_charge) {
-                Ok(_) => (),
+                Ok(_) => self.record_in_ledger(timestamp, wallet, entry_type, payload_size, total_charge),
                 Err(ReceivableDaoError::SignConversion(_)) => error! (
                     self.logger,
                     "Overflow error recording service provided for {}: service rate {}, byte rate {}, payload size {}. Skipping",
@@ -509,12 +608,13 @@ impl Accountant {
     }
 
     fn record_service_consumed(
-        &self,
+        &mut self,
         service_rate: u64,
         byte_rate: u64,
         timestamp: SystemTime,
         payload_size: usize,
         wallet: &Wallet,
+        entry_type: LedgerEntryType,
     ) {
         let byte_charge = byte_rate as u128 * (payload_size as u128);
         let total_charge = service_rate as u128 + byte_charge;
@@ -522,7 +622,10 @@ impl Accountant {
             match self.payable_dao
                 .as_ref()
                 .more_money_payable(timestamp, wallet,total_charge){
-                Ok(_) => (),
+                Ok(_) => {
+                    self.balance_forecast.record_spending(timestamp, total_charge);
+                    self.record_in_ledger(timestamp, wallet, entry_type, payload_size, total_charge)
+                },
                 Err(PayableDaoError::SignConversion(_)) => error! (
                     self.logger,
                     "Overflow error recording consumed services from {}: total charge {}, service rate {}, byte rate {}, payload size {}. Skipping",
@@ -543,6 +646,102 @@ impl Accountant {
         }
     }
 
+    fn record_in_ledger(
+        &self,
+        timestamp: SystemTime,
+        wallet: &Wallet,
+        entry_type: LedgerEntryType,
+        payload_size: usize,
+        total_charge: u128,
+    ) {
+        self.ledger_dao.record(&[LedgerEntry::service(
+            timestamp,
+            wallet.clone(),
+            entry_type,
+            payload_size as u64,
+            total_charge,
+        )])
+    }
+
+    fn maintain_ledger(&mut self, now: SystemTime) {
+        let days_ago = |days: u64| now - Duration::from_secs(days * SECONDS_IN_DAY);
+        self.ledger_dao.roll_up(days_ago(self.ledger_rollup_days));
+        if let Some(retention_days) = self.ledger_retention_days_opt {
+            self.ledger_dao.delete_older_than(days_ago(retention_days));
+        }
+        let receipt_retention_days = self
+            .ledger_retention_days_opt
+            .unwrap_or(DEFAULT_SERVICE_RECEIPT_RETENTION_DAYS);
+        self.service_receipt_dao
+            .delete_older_than(days_ago(receipt_retention_days))
+    }
+
+    fn handle_service_receipt(
+        &self,
+        msg: ExpiredCoresPackage<ServiceReceipt_0v1>,
+        received: SystemTime,
+    ) {
+        let receipt = msg.payload;
+        let speaks_for_payer = match &msg.paying_wallet {
+            Some(wallet) => wallet.address() == receipt.consuming_wallet.address(),
+            None => false,
+        };
+        if !speaks_for_payer {
+            warning!(
+                self.logger,
+                "Ignoring service receipt for route {} from {}: it didn't come from that wallet",
+                receipt.route_id,
+                receipt.consuming_wallet
+            );
+            return;
+        }
+        if receipt.provider_wallet.address() != self.earning_wallet.address() {
+            warning!(
+                self.logger,
+                "Ignoring service receipt for route {} from {}: it acknowledges services to {}, not to {}",
+                receipt.route_id,
+                receipt.consuming_wallet,
+                receipt.provider_wallet,
+                self.earning_wallet
+            );
+            return;
+        }
+        if let Some(complaint) = Self::complaint_about_receipt_range(&receipt) {
+            warning!(
+                self.logger,
+                "Ignoring service receipt for route {} from {}: {}",
+                receipt.route_id,
+                receipt.consuming_wallet,
+                complaint
+            );
+            return;
+        }
+        debug!(
+            self.logger,
+            "{} acknowledges {} services worth {} wei on route {}",
+            receipt.consuming_wallet,
+            receipt.services,
+            receipt.amount_wei,
+            receipt.route_id
+        );
+        self.service_receipt_dao.store(&receipt, received)
+    }
+
+    // Receipts come from other Nodes, so their figures must be checked before they go into the
+    // database, whose columns are signed
+    fn complaint_about_receipt_range(receipt: &ServiceReceipt_0v1) -> Option<String> {
+        if receipt.amount_wei > i128::MAX as u128 {
+            Some(format!("amount {} wei is out of range", receipt.amount_wei))
+        } else if [receipt.first_service_s, receipt.services, receipt.bytes]
+            .iter()
+            .any(|figure| *figure > i64::MAX as u64)
+        {
+            Some("its figures are out of range".to_string())
+        } else {
+            None
+        }
+    }
+
     fn our_wallet(&self, wallet: &Wallet) -> bool {
         match &self.consuming_wallet_opt {
             Some(ref consuming) if consuming.address() == wallet.address() => true,
@@ -569,6 +768,11 @@ impl Accountant {
                 .blockchain_bridge
                 .request_transaction_receipts,
         );
+        self.request_consuming_wallet_balances_sub_opt = Some(
+            msg.peer_actors
+                .blockchain_bridge
+                .request_consuming_wallet_balances,
+        );
         info!(self.logger, "Accountant bound");
     }
 
@@ -615,6 +819,7 @@ impl Accountant {
             msg.timestamp,
             msg.payload_size,
             &msg.paying_wallet,
+            LedgerEntryType::RoutingProvided,
         );
     }
 
@@ -636,6 +841,7 @@ impl Accountant {
             msg.timestamp,
             msg.payload_size,
             &msg.paying_wallet,
+            LedgerEntryType::ExitProvided,
         );
     }
 
@@ -662,6 +868,7 @@ impl Accountant {
             msg.timestamp,
             msg.exit.payload_size,
             &msg.exit.earning_wallet,
+            LedgerEntryType::ExitConsumed,
         );
         msg.routing.iter().for_each(|routing_service| {
             debug!(
@@ -677,11 +884,59 @@ impl Accountant {
                 msg.timestamp,
                 msg.routing_payload_size,
                 &routing_service.earning_wallet,
+                LedgerEntryType::RoutingConsumed,
             );
         })
     }
 
+    fn request_consuming_wallet_balances(&self) {
+        if let Some(consuming_wallet) = self.consuming_wallet_opt.as_ref() {
+            self.request_consuming_wallet_balances_sub_opt
+                .as_ref()
+                .expect("BlockchainBridge is unbound")
+                .try_send(RequestConsumingWalletBalances {
+                    consuming_wallet: consuming_wallet.clone(),
+                })
+                .expect("BlockchainBridge is dead")
+        }
+    }
+
+    fn handle_report_consuming_wallet_balances(&mut self, msg: ReportConsumingWalletBalances) {
+        self.balance_forecast
+            .update_balances(msg.balances, msg.transaction_fee_per_payment_wei);
+        let runway = self
+            .balance_forecast
+            .runway(self.payable_dao.total(), SystemTime::now())
+            .expectv("runway");
+        if self
+            .balance_forecast
+            .is_newly_short(&runway, self.balance_warning_days)
+        {
+            warning!(
+                self.logger,
+                "Consuming wallet {} is running low: at the recent rate of spending, its MASQ will last {} \
+                and its gas {}. Fund it to keep paying for services",
+                msg.consuming_wallet,
+                describe_runway(runway.service_fee_runway_hours_opt),
+                describe_runway(runway.transaction_fee_runway_hours_opt)
+            );
+            let broadcast = UiWalletBalanceWarningBroadcast {
+                consuming_wallet: msg.consuming_wallet.to_string(),
+                service_fee_balance_wei: saturate_u256(
+                    msg.balances.masq_token_balance_in_minor_units,
+                ),
+                transaction_fee_balance_wei: saturate_u256(
+                    msg.balances.transaction_fee_balance_in_minor_units,
+                ),
+                runway,
+                warning_days: self.balance_warning_days,
+            };
+            self.send_to_ui(AllClients, broadcast.tmb(0))
+        }
+    }
+
     fn handle_payable_payment_setup(&mut self, msg: BlockchainAgentWithContextMessage) {
+        let response_skeleton_opt = msg.response_skeleton_opt;
         let blockchain_bridge_instructions = match self
             .scanners
             .payable
@@ -690,18 +945,63 @@ impl Accountant {
             Ok(Either::Left(finalized_msg)) => finalized_msg,
             Ok(Either::Right(unaccepted_msg)) => {
                 //TODO we will eventually query info from Neighborhood before the adjustment, according to GH-699
-                self.scanners
+                let (adjusted_msg, summary) = self
+                    .scanners
                     .payable
-                    .perform_payment_adjustment(unaccepted_msg, &self.logger)
+                    .perform_payment_adjustment(unaccepted_msg, &self.logger);
+                self.send_to_ui(AllClients, summary.tmb(0));
+                adjusted_msg
+            }
+            Err(msg) => {
+                return self.handle_scan_error(ScanError {
+                    scan_type: ScanType::Payables,
+                    response_skeleton_opt,
+                    msg,
+                })
             }
-            Err(_e) => todo!("be completed by GH-711"),
         };
         self.outbound_payments_instructions_sub_opt
             .as_ref()
             .expect("BlockchainBridge is unbound")
             .try_send(blockchain_bridge_instructions)
             .expect("BlockchainBridge is dead")
-        //TODO implement send point for ScanError; be completed by GH-711
+    }
+
+    fn handle_scan_error(&mut self, scan_error: ScanError) {
+        error!(self.logger, "Received ScanError: {:?}", scan_error);
+        match scan_error.scan_type {
+            ScanType::Payables => {
+                self.scanners.payable.mark_as_ended(&self.logger);
+            }
+            ScanType::PendingPayables => {
+                self.scanners.pending_payable.mark_as_ended(&self.logger);
+            }
+            ScanType::Receivables => {
+                self.scanners.receivable.mark_as_ended(&self.logger);
+            }
+        };
+        if let Some(response_skeleton) = scan_error.response_skeleton_opt {
+            let error_msg = NodeToUiMessage {
+                target: ClientId(response_skeleton.client_id),
+                body: MessageBody {
+                    opcode: "scan".to_string(),
+                    path: MessagePath::Conversation(response_skeleton.context_id),
+                    payload: Err((
+                        SCAN_ERROR,
+                        format!(
+                            "{:?} scan failed: '{}'",
+                            scan_error.scan_type, scan_error.msg
+                        ),
+                    )),
+                },
+            };
+            error!(self.logger, "Sending UiScanResponse: {:?}", error_msg);
+            self.ui_message_sub_opt
+                .as_ref()
+                .expect("UIGateway not bound")
+                .try_send(error_msg)
+                .expect("UiGateway is dead");
+        }
     }
 
     fn handle_financials(&self, msg: &UiFinancialsRequest, client_id: u64, context_id: u64) {
@@ -732,85 +1032,403 @@ impl Accountant {
         .tmb(context_id)
     }
 
-    fn request_payable_accounts_by_specific_mode(
-        &self,
-        mode: CustomQuery<u64>,
-    ) -> Option<Vec<UiPayableAccount>> {
-        self.payable_dao
-            .custom_query(mode)
-            .map(remap_payable_accounts)
-    }
-
-    fn request_receivable_accounts_by_specific_mode(
-        &self,
-        mode: CustomQuery<i64>,
-    ) -> Option<Vec<UiReceivableAccount>> {
-        self.receivable_dao
-            .custom_query(mode)
-            .map(remap_receivable_accounts)
+    fn handle_ledger(&self, msg: &UiLedgerRequest, client_id: u64, context_id: u64) {
+        let body = self.compute_ledger(msg, context_id);
+        self.ui_message_sub_opt
+            .as_ref()
+            .expect("UiGateway not bound")
+            .try_send(NodeToUiMessage {
+                target: ClientId(client_id),
+                body,
+            })
+            .expect("UiGateway is dead");
     }
 
-    fn process_stats(&self, msg: &UiFinancialsRequest) -> Option<UiFinancialStatistics> {
-        if msg.stats_required {
-            let financial_statistics = self.financial_statistics();
-            Some(UiFinancialStatistics {
-                total_unpaid_and_pending_payable_gwei: wei_to_gwei(self.payable_dao.total()),
-                total_paid_payable_gwei: wei_to_gwei(financial_statistics.total_paid_payable_wei),
-                total_unpaid_receivable_gwei: wei_to_gwei(self.receivable_dao.total()),
-                total_paid_receivable_gwei: wei_to_gwei(
-                    financial_statistics.total_paid_receivable_wei,
-                ),
-            })
-        } else {
-            None
+    fn compute_ledger(&self, msg: &UiLedgerRequest, context_id: u64) -> MessageBody {
+        let error = |message: String| MessageBody {
+            opcode: "ledger".to_string(),
+            path: MessagePath::Conversation(context_id),
+            payload: Err((INVALID_LEDGER_QUERY, message)),
+        };
+        let wallet_opt = match msg.wallet_opt.as_deref().map(Wallet::from_str).transpose() {
+            Ok(wallet_opt) => wallet_opt,
+            Err(e) => return error(format!("Invalid wallet in ledger request: {:?}", e)),
+        };
+        let time_opt = |timestamp_s_opt: Option<u64>| match timestamp_s_opt {
+            Some(timestamp_s) if timestamp_s > i64::MAX as u64 => Err(format!(
+                "Timestamp {} in ledger request is out of range",
+                timestamp_s
+            )),
+            timestamp_s_opt => Ok(timestamp_s_opt
+                .map(|timestamp_s| SystemTime::UNIX_EPOCH + Duration::from_secs(timestamp_s))),
+        };
+        let (from_opt, to_opt) = match (
+            time_opt(msg.from_timestamp_s_opt),
+            time_opt(msg.to_timestamp_s_opt),
+        ) {
+            (Ok(Some(from)), Ok(Some(to))) if from > to => {
+                return error(format!(
+                    "Ledger request asks for entries from {} to {}, which is no time at all",
+                    msg.from_timestamp_s_opt.expectv("from timestamp"),
+                    msg.to_timestamp_s_opt.expectv("to timestamp")
+                ))
+            }
+            (Ok(from_opt), Ok(to_opt)) => (from_opt, to_opt),
+            (Err(message), _) | (_, Err(message)) => return error(message),
+        };
+        let entries = self.ledger_dao.entries(&LedgerQuery {
+            wallet_opt,
+            from_opt,
+            to_opt,
+            max_entries_opt: msg.max_entries_opt,
+        });
+        UiLedgerResponse {
+            entries: remap_ledger_entries(entries),
         }
+        .tmb(context_id)
     }
 
-    fn process_top_records_query(&self, msg: &UiFinancialsRequest) -> Option<QueryResults> {
-        msg.top_records_opt.map(|config| {
-            let payable = self
-                .request_payable_accounts_by_specific_mode(config.into())
-                .unwrap_or_default();
-            let receivable = self
-                .request_receivable_accounts_by_specific_mode(config.into())
-                .unwrap_or_default();
+    fn handle_reconciliation(&self, client_id: u64, context_id: u64) {
+        let accounts = self.compute_reconciliation();
+        self.send_to_ui(
+            ClientId(client_id),
+            UiReconciliationResponse { accounts }.tmb(context_id),
+        )
+    }
 
-            QueryResults {
-                payable_opt: Some(payable),
-                receivable_opt: Some(receivable),
-            }
-        })
+    fn compute_reconciliation(&self) -> Vec<UiReconciliationAccount> {
+        let mut acknowledged: BTreeMap<String, (Wallet, u64, u64, u128)> = BTreeMap::new();
+        self.service_receipt_dao
+            .receipts()
+            .into_iter()
+            .for_each(|stored| {
+                let receipt = stored.receipt;
+                let account = acknowledged
+                    .entry(receipt.consuming_wallet.to_string())
+                    .or_insert((receipt.consuming_wallet.clone(), u64::MAX, 0, 0));
+                account.1 = account.1.min(receipt.first_service_s);
+                account.2 += 1;
+                account.3 = account.3.saturating_add(receipt.amount_wei)
+            });
+        acknowledged
+            .into_values()
+            .map(|(wallet, since_s, receipt_count, acknowledged_wei)| {
+                let billed_wei: u128 = self
+                    .ledger_dao
+                    .entries(&LedgerQuery {
+                        wallet_opt: Some(wallet.clone()),
+                        from_opt: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(since_s)),
+                        to_opt: None,
+                        max_entries_opt: None,
+                    })
+                    .into_iter()
+                    .filter(|entry| {
+                        matches!(
+                            entry.entry_type,
+                            LedgerEntryType::RoutingProvided | LedgerEntryType::ExitProvided
+                        )
+                    })
+                    .fold(0u128, |sum, entry| sum.saturating_add(entry.amount_wei));
+                let signed = |wei: u128| i128::try_from(wei).unwrap_or(i128::MAX);
+                UiReconciliationAccount {
+                    wallet: wallet.to_string(),
+                    since_s,
+                    receipt_count,
+                    billed_wei,
+                    acknowledged_wei,
+                    discrepancy_wei: signed(billed_wei).saturating_sub(signed(acknowledged_wei)),
+                }
+            })
+            .collect()
     }
 
-    fn process_custom_queries(
+    fn handle_simulate_thresholds(
         &self,
-        msg: &UiFinancialsRequest,
+        msg: &UiSimulateThresholdsRequest,
+        client_id: u64,
         context_id: u64,
-    ) -> Result<Option<QueryResults>, MessageBody> {
-        Ok(match msg.custom_queries_opt.as_ref() {
-            Some(specs) => {
-                let payable_opt = if let Some(query_specs) = specs.payable_opt.as_ref() {
-                    let query = CustomQuery::from(query_specs);
-                    check_query_is_within_tech_limits(&query, "payable", context_id)?;
-                    self.request_payable_accounts_by_specific_mode(query)
-                } else {
-                    None
-                };
-                let receivable_opt = if let Some(query_specs) = specs.receivable_opt.as_ref() {
-                    let query = CustomQuery::from(query_specs);
-                    check_query_is_within_tech_limits(&query, "receivable", context_id)?;
-                    self.request_receivable_accounts_by_specific_mode(query)
-                } else {
-                    None
-                };
-
-                Some(QueryResults {
-                    payable_opt,
-                    receivable_opt,
-                })
-            }
-            None => None,
-        })
+    ) {
+        let body = self.compute_threshold_simulation(msg, context_id);
+        self.send_to_ui(ClientId(client_id), body)
+    }
+
+    fn compute_threshold_simulation(
+        &self,
+        msg: &UiSimulateThresholdsRequest,
+        context_id: u64,
+    ) -> MessageBody {
+        let error = |message: String| MessageBody {
+            opcode: "simulateThresholds".to_string(),
+            path: MessagePath::Conversation(context_id),
+            payload: Err((INVALID_THRESHOLDS_SIMULATION, message)),
+        };
+        let payment_thresholds = match PaymentThresholds::try_from(msg.payment_thresholds.as_str())
+        {
+            Ok(payment_thresholds) => payment_thresholds,
+            Err(e) => return error(format!("Invalid payment thresholds: {}", e)),
+        };
+        let accounts = match (&msg.payables_opt, &msg.receivables_opt) {
+            (None, None) => Ok(self.simulated_accounts_from_books()),
+            (payables_opt, receivables_opt) => {
+                threshold_simulator::payables_from_ui(payables_opt.as_deref().unwrap_or_default())
+                    .and_then(|payables| {
+                        threshold_simulator::receivables_from_ui(
+                            receivables_opt.as_deref().unwrap_or_default(),
+                        )
+                        .map(|receivables| (payables, receivables))
+                    })
+            }
+        };
+        match accounts.and_then(|(payables, receivables)| {
+            threshold_simulator::simulate_thresholds(
+                &payment_thresholds,
+                &payables,
+                &receivables,
+                msg.window_s,
+                msg.step_s,
+            )
+        }) {
+            Ok(events) => UiSimulateThresholdsResponse {
+                events: threshold_simulator::events_to_ui(events),
+            }
+            .tmb(context_id),
+            Err(message) => error(message),
+        }
+    }
+
+    fn simulated_accounts_from_books(&self) -> (Vec<SimulatedPayable>, Vec<SimulatedReceivable>) {
+        let now = SystemTime::now();
+        let mut receivables = vec![];
+        loop {
+            let page = self
+                .receivable_dao
+                .custom_query(CustomQuery::Page {
+                    offset: checked_conversion::<usize, u32>(receivables.len()),
+                    count: u16::MAX,
+                })
+                .unwrap_or_default();
+            let last_page = page.len() < u16::MAX as usize;
+            receivables.extend(page);
+            if last_page {
+                break;
+            }
+        }
+        (
+            threshold_simulator::payables_from_books(self.payable_dao.non_pending_payables(), now),
+            threshold_simulator::receivables_from_books(
+                receivables,
+                &self.banned_dao.ban_list(),
+                now,
+            ),
+        )
+    }
+
+    fn handle_ban_list(&self, client_id: u64, context_id: u64) {
+        let banned = remap_banned_accounts(self.banned_dao.ban_list());
+        self.send_to_ui(
+            ClientId(client_id),
+            UiBanListResponse { banned }.tmb(context_id),
+        )
+    }
+
+    fn handle_ban(&self, msg: UiBanRequest, client_id: u64, context_id: u64) {
+        let wallet = match self.parse_wallet_to_ban("ban", &msg.wallet, context_id) {
+            Ok(wallet) => wallet,
+            Err(body) => return self.send_to_ui(ClientId(client_id), body),
+        };
+        let newly_banned = self.banned_dao.ban(
+            &wallet,
+            SystemTime::now(),
+            msg.reason_opt.clone(),
+            BanOrigin::Manual,
+        );
+        if newly_banned {
+            info!(
+                self.logger,
+                "Wallet {} banned at the operator's request", wallet
+            );
+            self.send_to_ui(
+                AllExcept(client_id),
+                UiBanChangedBroadcast {
+                    wallet: wallet.to_string(),
+                    banned: true,
+                    reason_opt: msg.reason_opt,
+                }
+                .tmb(0),
+            )
+        }
+        self.send_to_ui(
+            ClientId(client_id),
+            UiBanResponse {
+                already_banned: !newly_banned,
+            }
+            .tmb(context_id),
+        )
+    }
+
+    fn handle_unban(&self, msg: UiUnbanRequest, client_id: u64, context_id: u64) {
+        let wallet = match self.parse_wallet_to_ban("unban", &msg.wallet, context_id) {
+            Ok(wallet) => wallet,
+            Err(body) => return self.send_to_ui(ClientId(client_id), body),
+        };
+        let was_banned = self.banned_dao.unban(&wallet);
+        if was_banned {
+            info!(
+                self.logger,
+                "Wallet {} unbanned at the operator's request", wallet
+            );
+            self.send_to_ui(
+                AllExcept(client_id),
+                UiBanChangedBroadcast {
+                    wallet: wallet.to_string(),
+                    banned: false,
+                    reason_opt: None,
+                }
+                .tmb(0),
+            )
+        }
+        self.send_to_ui(
+            ClientId(client_id),
+            UiUnbanResponse { was_banned }.tmb(context_id),
+        )
+    }
+
+    fn parse_wallet_to_ban(
+        &self,
+        opcode: &str,
+        wallet: &str,
+        context_id: u64,
+    ) -> Result<Wallet, MessageBody> {
+        Wallet::from_str(wallet).map_err(|e| MessageBody {
+            opcode: opcode.to_string(),
+            path: MessagePath::Conversation(context_id),
+            payload: Err((
+                BAN_REQUEST_ERROR,
+                format!("Invalid wallet '{}': {:?}", wallet, e),
+            )),
+        })
+    }
+
+    fn broadcast_ban_changes(&self) {
+        let ban_changes = self.ban_changes.take();
+        ban_changes
+            .into_iter()
+            .for_each(|change| self.send_to_ui(AllClients, change.tmb(0)))
+    }
+
+    fn send_to_ui(&self, target: MessageTarget, body: MessageBody) {
+        self.ui_message_sub_opt
+            .as_ref()
+            .expect("UiGateway not bound")
+            .try_send(NodeToUiMessage { target, body })
+            .expect("UiGateway is dead")
+    }
+
+    fn request_payable_accounts_by_specific_mode(
+        &self,
+        mode: CustomQuery<u64>,
+    ) -> Option<Vec<UiPayableAccount>> {
+        self.payable_dao
+            .custom_query(mode)
+            .map(remap_payable_accounts)
+    }
+
+    fn request_receivable_accounts_by_specific_mode(
+        &self,
+        mode: CustomQuery<i64>,
+    ) -> Option<Vec<UiReceivableAccount>> {
+        self.receivable_dao
+            .custom_query(mode)
+            .map(remap_receivable_accounts)
+    }
+
+    fn process_stats(&self, msg: &UiFinancialsRequest) -> Option<UiFinancialStatistics> {
+        if msg.stats_required {
+            let financial_statistics = self.financial_statistics();
+            let total_unpaid_and_pending_payable_wei = self.payable_dao.total();
+            Some(UiFinancialStatistics {
+                total_unpaid_and_pending_payable_gwei: wei_to_gwei(
+                    total_unpaid_and_pending_payable_wei,
+                ),
+                total_paid_payable_gwei: wei_to_gwei(financial_statistics.total_paid_payable_wei),
+                total_unpaid_receivable_gwei: wei_to_gwei(self.receivable_dao.total()),
+                total_paid_receivable_gwei: wei_to_gwei(
+                    financial_statistics.total_paid_receivable_wei,
+                ),
+                runway_opt: self
+                    .balance_forecast
+                    .runway(total_unpaid_and_pending_payable_wei, SystemTime::now()),
+            })
+        } else {
+            None
+        }
+    }
+
+    fn process_top_records_query(&self, msg: &UiFinancialsRequest) -> Option<QueryResults> {
+        msg.top_records_opt.map(|config| {
+            let payable = self
+                .request_payable_accounts_by_specific_mode(config.into())
+                .unwrap_or_default();
+            let receivable = self
+                .request_receivable_accounts_by_specific_mode(config.into())
+                .unwrap_or_default();
+
+            QueryResults {
+                payable_opt: Some(payable),
+                receivable_opt: Some(receivable),
+            }
+        })
+    }
+
+    fn process_page_query(&self, msg: &UiFinancialsRequest) -> Option<QueryResults> {
+        msg.page_opt.map(|page| {
+            let payable = self
+                .payable_dao
+                .custom_query(page.into())
+                .map(remap_payable_page)
+                .unwrap_or_default();
+            let receivable = self
+                .receivable_dao
+                .custom_query(page.into())
+                .map(remap_receivable_page)
+                .unwrap_or_default();
+
+            QueryResults {
+                payable_opt: Some(payable),
+                receivable_opt: Some(receivable),
+            }
+        })
+    }
+
+    fn process_custom_queries(
+        &self,
+        msg: &UiFinancialsRequest,
+        context_id: u64,
+    ) -> Result<Option<QueryResults>, MessageBody> {
+        Ok(match msg.custom_queries_opt.as_ref() {
+            Some(specs) => {
+                let payable_opt = if let Some(query_specs) = specs.payable_opt.as_ref() {
+                    let query = CustomQuery::from(query_specs);
+                    check_query_is_within_tech_limits(&query, "payable", context_id)?;
+                    self.request_payable_accounts_by_specific_mode(query)
+                } else {
+                    None
+                };
+                let receivable_opt = if let Some(query_specs) = specs.receivable_opt.as_ref() {
+                    let query = CustomQuery::from(query_specs);
+                    check_query_is_within_tech_limits(&query, "receivable", context_id)?;
+                    self.request_receivable_accounts_by_specific_mode(query)
+                } else {
+                    None
+                };
+
+                Some(QueryResults {
+                    payable_opt,
+                    receivable_opt,
+                })
+            }
+            None => None,
+        })
     }
 
     fn process_queries_of_records(
@@ -823,7 +1441,8 @@ impl Accountant {
             Ok(query_results) => query_results,
             Err(message_body) => return Err(message_body),
         };
-        match vec![top_records_opt, custom_query_records_opt]
+        let page_opt = self.process_page_query(msg);
+        match vec![top_records_opt, custom_query_records_opt, page_opt]
             .into_iter()
             .find(|results| results.is_some())
         {
@@ -845,6 +1464,7 @@ impl Accountant {
             ),
             None => Err(BeginScanError::NoConsumingWalletFound),
         };
+        self.record_scan_outcome(ScanType::Payables, &result);
 
         match result {
             Ok(scan_message) => {
@@ -868,13 +1488,14 @@ impl Accountant {
     ) {
         let result = match self.consuming_wallet_opt.clone() {
             Some(consuming_wallet) => self.scanners.pending_payable.begin_scan(
-                consuming_wallet, // This argument is not used and is therefore irrelevant
+                consuming_wallet,
                 SystemTime::now(),
                 response_skeleton_opt,
                 &self.logger,
             ),
             None => Err(BeginScanError::NoConsumingWalletFound),
         };
+        self.record_scan_outcome(ScanType::PendingPayables, &result);
 
         match result {
             Ok(scan_message) => self
@@ -895,12 +1516,14 @@ impl Accountant {
         &mut self,
         response_skeleton_opt: Option<ResponseSkeleton>,
     ) {
-        match self.scanners.receivable.begin_scan(
+        let result = self.scanners.receivable.begin_scan(
             self.earning_wallet.clone(),
             SystemTime::now(),
             response_skeleton_opt,
             &self.logger,
-        ) {
+        );
+        self.record_scan_outcome(ScanType::Receivables, &result);
+        match result {
             Ok(scan_message) => self
                 .retrieve_transactions_sub_opt
                 .as_ref()
@@ -913,6 +1536,22 @@ impl Accountant {
                 response_skeleton_opt.is_some(),
             ),
         };
+        self.broadcast_ban_changes();
+        if self.ledger_totals_in_metrics {
+            self.metrics
+                .set_ledger_totals(self.payable_dao.total(), self.receivable_dao.total());
+        }
+    }
+
+    fn record_scan_outcome<T>(&self, scan_type: ScanType, result: &Result<T, BeginScanError>) {
+        let outcome = match result {
+            Ok(_) => ScanOutcome::Started,
+            Err(BeginScanError::NothingToProcess) => ScanOutcome::NothingToProcess,
+            Err(BeginScanError::NoConsumingWalletFound) => ScanOutcome::NoConsumingWallet,
+            Err(BeginScanError::ScanAlreadyRunning(_)) => ScanOutcome::AlreadyRunning,
+            Err(BeginScanError::CalledFromNullScanner) => return,
+        };
+        self.metrics.record_scan_outcome(scan_type, outcome);
     }
 
     fn handle_externally_triggered_scan(
@@ -937,10 +1576,11 @@ impl Accountant {
             comma_joined_stringifiable(fingerprints_data, |(hash, _)| format!("{:?}", hash))
         }
 
-        match self
-            .pending_payable_dao
-            .insert_new_fingerprints(&msg.hashes_and_balances, msg.batch_wide_timestamp)
-        {
+        match self.pending_payable_dao.insert_new_fingerprints(
+            &msg.hashes_and_balances,
+            &msg.resubmission_data,
+            msg.batch_wide_timestamp,
+        ) {
             Ok(_) => debug!(
                 self.logger,
                 "Saved new pending payable fingerprints for: {}",
@@ -1017,6 +1657,13 @@ pub fn checked_conversion<T: Copy + Display, S: TryFrom<T>>(num: T) -> S {
     politely_checked_conversion(num).unwrap_or_else(|msg| panic!("{}", msg))
 }
 
+fn describe_runway(runway_hours_opt: Option<u64>) -> String {
+    match runway_hours_opt {
+        Some(hours) => format!("about {} hours", hours),
+        None => "indefinitely".to_string(),
+    }
+}
+
 pub fn gwei_to_wei<T: Mul<Output = T> + From<u32> + From<S>, S>(gwei: S) -> T {
     (T::from(gwei)).mul(T::from(WEIS_IN_GWEI as u32))
 }
@@ -1028,6 +1675,7 @@ pub fn wei_to_gwei<T: TryFrom<S>, S: Display + Copy + Div<Output = S> + From<u32
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::accountant::db_access_objects::banned_dao::BannedAccount;
     use crate::accountant::db_access_objects::payable_dao::{
         PayableAccount, PayableDaoError, PayableDaoFactory,
     };
@@ -1035,8 +1683,9 @@ mod tests {
         PendingPayable, PendingPayableDaoError, TransactionHashes,
     };
     use crate::accountant::db_access_objects::receivable_dao::ReceivableAccount;
+    use crate::accountant::db_access_objects::service_receipt_dao::StoredServiceReceipt;
     use crate::accountant::db_access_objects::utils::{from_time_t, to_time_t, CustomQuery};
-    use crate::accountant::payment_adjuster::Adjustment;
+    use crate::accountant::payment_adjuster::{Adjustment, AnalysisError};
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::test_utils::BlockchainAgentMock;
     use crate::accountant::scanners::test_utils::protect_payables_in_test;
     use crate::accountant::scanners::BeginScanError;
@@ -1045,14 +1694,17 @@ mod tests {
     };
     use crate::accountant::test_utils::{
         bc_from_earning_wallet, bc_from_wallets, make_payable_account, make_payables,
-        BannedDaoFactoryMock, ConfigDaoFactoryMock, MessageIdGeneratorMock, NullScanner,
-        PayableDaoFactoryMock, PayableDaoMock, PayableScannerBuilder, PaymentAdjusterMock,
-        PendingPayableDaoFactoryMock, PendingPayableDaoMock, ReceivableDaoFactoryMock,
-        ReceivableDaoMock, ScannerMock,
+        make_receivable_account, make_resubmission_data, BannedDaoFactoryMock,
+        ConfigDaoFactoryMock, LedgerDaoFactoryMock, LedgerDaoMock, MessageIdGeneratorMock,
+        NullScanner, PayableDaoFactoryMock, PayableDaoMock, PayableScannerBuilder,
+        PaymentAdjusterMock, PendingPayableDaoFactoryMock, PendingPayableDaoMock,
+        ReceivableDaoFactoryMock, ReceivableDaoMock, ScannerMock, ServiceReceiptDaoFactoryMock,
+        ServiceReceiptDaoMock,
     };
     use crate::accountant::test_utils::{AccountantBuilder, BannedDaoMock};
     use crate::accountant::Accountant;
     use crate::blockchain::blockchain_bridge::BlockchainBridge;
+    use crate::blockchain::blockchain_interface::data_structures::errors::BlockchainAgentBuildError;
     use crate::blockchain::test_utils::{make_tx_hash, BlockchainInterfaceMock};
     use crate::database::rusqlite_wrappers::TransactionSafeWrapper;
     use crate::database::test_utils::transaction_wrapper_mock::TransactionInnerWrapperMockBuilder;
@@ -1062,9 +1714,13 @@ mod tests {
         ExitServiceConsumed, PaymentThresholds, RoutingServiceConsumed, ScanIntervals,
         DEFAULT_EARNING_WALLET, DEFAULT_PAYMENT_THRESHOLDS,
     };
-    use crate::sub_lib::blockchain_bridge::OutboundPaymentsInstructions;
+    use crate::sub_lib::blockchain_bridge::{
+        ConsumingWalletBalances, OutboundPaymentsInstructions,
+    };
+    use crate::sub_lib::cryptde::{CryptData, PublicKey};
     use crate::sub_lib::neighborhood::ConfigChange;
     use crate::sub_lib::neighborhood::{Hops, WalletPair};
+    use crate::sub_lib::route::Route;
     use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
     use crate::test_utils::recorder::make_recorder;
     use crate::test_utils::recorder::peer_actors_builder;
@@ -1079,7 +1735,7 @@ mod tests {
     };
     use crate::test_utils::{make_paying_wallet, make_wallet};
     use actix::{Arbiter, System};
-    use ethereum_types::U64;
+    use ethereum_types::{U256, U64};
     use ethsign_crypto::Keccak256;
     use itertools::Itertools;
     use log::Level;
@@ -1089,8 +1745,10 @@ mod tests {
     };
     use masq_lib::messages::TopRecordsOrdering::{Age, Balance};
     use masq_lib::messages::{
-        CustomQueries, RangeQuery, ScanType, TopRecordsConfig, UiFinancialStatistics,
-        UiMessageError, UiPayableAccount, UiReceivableAccount, UiScanRequest, UiScanResponse,
+        CustomQueries, FinancialsPage, RangeQuery, ScanType, TopRecordsConfig, UiBannedWallet,
+        UiFinancialStatistics, UiLedgerEntry, UiMessageError, UiPayableAccount,
+        UiPaymentsAdjustedBroadcast, UiReceivableAccount, UiReducedPayment, UiScanRequest,
+        UiScanResponse, UiSimulatedAccount, UiThresholdEvent, UiThresholdEventType, UiWalletRunway,
     };
     use masq_lib::test_utils::logging::init_test_logging;
     use masq_lib::test_utils::logging::TestLogHandler;
@@ -1098,6 +1756,7 @@ mod tests {
     use masq_lib::ui_gateway::MessagePath::Conversation;
     use masq_lib::ui_gateway::{MessageBody, MessagePath, NodeFromUiMessage, NodeToUiMessage};
     use std::any::TypeId;
+    use std::net::SocketAddr;
     use std::ops::{Add, Sub};
     use std::sync::Arc;
     use std::sync::Mutex;
@@ -1121,6 +1780,11 @@ mod tests {
     fn constants_have_correct_values() {
         assert_eq!(CRASH_KEY, "ACCOUNTANT");
         assert_eq!(DEFAULT_PENDING_TOO_LONG_SEC, 21_600);
+        assert_eq!(DEFAULT_PENDING_STUCK_SEC, 1_800);
+        assert_eq!(DEFAULT_CONFIRMATION_DEPTH, 12);
+        assert_eq!(DEFAULT_LEDGER_ROLLUP_DAYS, 30);
+        assert_eq!(DEFAULT_BALANCE_WARNING_DAYS, 3);
+        assert_eq!(SECONDS_IN_DAY, 86_400);
     }
 
     #[test]
@@ -1130,6 +1794,8 @@ mod tests {
         let pending_payable_dao_factory_params_arc = Arc::new(Mutex::new(vec![]));
         let receivable_dao_factory_params_arc = Arc::new(Mutex::new(vec![]));
         let banned_dao_factory_params_arc = Arc::new(Mutex::new(vec![]));
+        let ledger_dao_factory_params_arc = Arc::new(Mutex::new(vec![]));
+        let service_receipt_dao_factory_params_arc = Arc::new(Mutex::new(vec![]));
         let config_dao_factory_params_arc = Arc::new(Mutex::new(vec![]));
         let payable_dao_factory = PayableDaoFactoryMock::new()
             .make_params(&payable_dao_factory_params_arc)
@@ -1147,7 +1813,16 @@ mod tests {
             .make_result(ReceivableDaoMock::new()); // For Receivable Scanner
         let banned_dao_factory = BannedDaoFactoryMock::new()
             .make_params(&banned_dao_factory_params_arc)
+            .make_result(BannedDaoMock::new()) // For Accountant
             .make_result(BannedDaoMock::new()); // For Receivable Scanner
+        let ledger_dao_factory = LedgerDaoFactoryMock::new()
+            .make_params(&ledger_dao_factory_params_arc)
+            .make_result(LedgerDaoMock::new()) // For Accountant
+            .make_result(LedgerDaoMock::new()) // For PendingPayable Scanner
+            .make_result(LedgerDaoMock::new()); // For Receivable Scanner
+        let service_receipt_dao_factory = ServiceReceiptDaoFactoryMock::new()
+            .make_params(&service_receipt_dao_factory_params_arc)
+            .make_result(ServiceReceiptDaoMock::new()); // For Accountant
         let config_dao_factory = ConfigDaoFactoryMock::new()
             .make_params(&config_dao_factory_params_arc)
             .make_result(ConfigDaoMock::new()); // For receivable scanner
@@ -1159,6 +1834,8 @@ mod tests {
                 pending_payable_dao_factory: Box::new(pending_payable_dao_factory),
                 receivable_dao_factory: Box::new(receivable_dao_factory),
                 banned_dao_factory: Box::new(banned_dao_factory),
+                ledger_dao_factory: Box::new(ledger_dao_factory),
+                service_receipt_dao_factory: Box::new(service_receipt_dao_factory),
                 config_dao_factory: Box::new(config_dao_factory),
             },
         );
@@ -1175,7 +1852,15 @@ mod tests {
             *receivable_dao_factory_params_arc.lock().unwrap(),
             vec![(), ()]
         );
-        assert_eq!(*banned_dao_factory_params_arc.lock().unwrap(), vec![()]);
+        assert_eq!(*banned_dao_factory_params_arc.lock().unwrap(), vec![(), ()]);
+        assert_eq!(
+            *ledger_dao_factory_params_arc.lock().unwrap(),
+            vec![(), (), ()]
+        );
+        assert_eq!(
+            *service_receipt_dao_factory_params_arc.lock().unwrap(),
+            vec![()]
+        );
         assert_eq!(*config_dao_factory_params_arc.lock().unwrap(), vec![()]);
     }
 
@@ -1199,8 +1884,19 @@ mod tests {
                 .make_result(ReceivableDaoMock::new()) // For Accountant
                 .make_result(ReceivableDaoMock::new()), // For Scanner
         );
-        let banned_dao_factory =
-            Box::new(BannedDaoFactoryMock::new().make_result(BannedDaoMock::new()));
+        let banned_dao_factory = Box::new(
+            BannedDaoFactoryMock::new()
+                .make_result(BannedDaoMock::new()) // For Accountant
+                .make_result(BannedDaoMock::new()), // For Receivable Scanner
+        );
+        let ledger_dao_factory = Box::new(
+            LedgerDaoFactoryMock::new()
+                .make_result(LedgerDaoMock::new()) // For Accountant
+                .make_result(LedgerDaoMock::new()) // For PendingPayable Scanner
+                .make_result(LedgerDaoMock::new()), // For Receivable Scanner
+        );
+        let service_receipt_dao_factory =
+            Box::new(ServiceReceiptDaoFactoryMock::new().make_result(ServiceReceiptDaoMock::new()));
         let config_dao_factory =
             Box::new(ConfigDaoFactoryMock::new().make_result(ConfigDaoMock::new()));
 
@@ -1211,6 +1907,8 @@ mod tests {
                 pending_payable_dao_factory,
                 receivable_dao_factory,
                 banned_dao_factory,
+                ledger_dao_factory,
+                service_receipt_dao_factory,
                 config_dao_factory,
             },
         );
@@ -1354,6 +2052,7 @@ mod tests {
             blockchain_bridge_recording.get_record::<RetrieveTransactions>(0),
             &RetrieveTransactions {
                 recipient: make_wallet("earning_wallet"),
+                confirmation_depth: DEFAULT_CONFIRMATION_DEPTH,
                 response_skeleton_opt: Some(ResponseSkeleton {
                     client_id: 1234,
                     context_id: 4321,
@@ -1362,6 +2061,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn receivable_scan_broadcasts_ban_changes_made_by_the_scanner() {
+        let delinquent = make_receivable_account(1234, true);
+        let reformed = make_receivable_account(3456, false);
+        let receivable_dao = ReceivableDaoMock::new()
+            .new_delinquencies_result(vec![delinquent.clone()])
+            .paid_delinquencies_result(vec![reformed.clone()]);
+        let banned_dao = BannedDaoMock::new().ban_result(true).unban_result(true);
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(bc_from_earning_wallet(make_wallet("earning_wallet")))
+            .receivable_daos(vec![ForReceivableScanner(receivable_dao)])
+            .banned_daos(vec![ForReceivableScanner(banned_dao)])
+            .build();
+        let (blockchain_bridge, _, _) = make_recorder();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let subject_addr = subject.start();
+        let system = System::new("test");
+        let peer_actors = peer_actors_builder()
+            .blockchain_bridge(blockchain_bridge)
+            .ui_gateway(ui_gateway)
+            .build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(ScanForReceivables {
+                response_skeleton_opt: None,
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(ui_gateway_recording.len(), 2);
+        let ban_message = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
+        assert_eq!(ban_message.target, AllClients);
+        let (ban_broadcast, _) = UiBanChangedBroadcast::fmb(ban_message.body.clone()).unwrap();
+        assert_eq!(ban_broadcast.wallet, delinquent.wallet.to_string());
+        assert_eq!(ban_broadcast.banned, true);
+        assert!(ban_broadcast
+            .reason_opt
+            .unwrap()
+            .starts_with("Delinquent with balance 1,234 gwei at age "));
+        let unban_message = ui_gateway_recording.get_record::<NodeToUiMessage>(1);
+        assert_eq!(
+            unban_message,
+            &NodeToUiMessage {
+                target: AllClients,
+                body: UiBanChangedBroadcast {
+                    wallet: reformed.wallet.to_string(),
+                    banned: false,
+                    reason_opt: None,
+                }
+                .tmb(0)
+            }
+        );
+    }
+
     #[test]
     fn received_payments_with_response_skeleton_sends_response_to_ui_gateway() {
         let mut config = bc_from_earning_wallet(make_wallet("earning_wallet"));
@@ -1371,8 +2127,10 @@ mod tests {
             pending_payable_scan_interval: Duration::from_secs(100),
         });
         config.suppress_initial_scans = true;
+        let receivable_dao = ReceivableDaoMock::new().credited_transactions_since_result(vec![]);
         let subject = AccountantBuilder::default()
             .bootstrapper_config(config)
+            .receivable_daos(vec![ForReceivableScanner(receivable_dao)])
             .config_dao(ConfigDaoMock::new().set_result(Ok(())))
             .build();
         let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
@@ -1383,6 +2141,7 @@ mod tests {
         let received_payments = ReceivedPayments {
             timestamp: SystemTime::now(),
             payments: vec![],
+            scanned_from_block: 1234555,
             new_start_block: 1234567,
             response_skeleton_opt: Some(ResponseSkeleton {
                 client_id: 1234,
@@ -1544,25 +2303,10 @@ mod tests {
 
         system.run();
         let mut is_adjustment_required_params = is_adjustment_required_params_arc.lock().unwrap();
-        let (blockchain_agent_with_context_msg_actual, logger_clone) =
+        let (qualified_payables_actual, agent_id_stamp_actual, logger_clone) =
             is_adjustment_required_params.remove(0);
-        assert_eq!(
-            blockchain_agent_with_context_msg_actual.protected_qualified_payables,
-            protect_payables_in_test(accounts.clone())
-        );
-        assert_eq!(
-            blockchain_agent_with_context_msg_actual.response_skeleton_opt,
-            Some(ResponseSkeleton {
-                client_id: 1234,
-                context_id: 4321,
-            })
-        );
-        assert_eq!(
-            blockchain_agent_with_context_msg_actual
-                .agent
-                .arbitrary_id_stamp(),
-            agent_id_stamp
-        );
+        assert_eq!(qualified_payables_actual, accounts);
+        assert_eq!(agent_id_stamp_actual, agent_id_stamp);
         assert!(is_adjustment_required_params.is_empty());
         let blockchain_bridge_recording = blockchain_bridge_recording_arc.lock().unwrap();
         let payments_instructions =
@@ -1585,46 +2329,124 @@ mod tests {
         // because otherwise this test would've panicked
     }
 
-    fn test_use_of_the_same_logger(logger_clone: &Logger, test_name: &str) {
-        let experiment_msg = format!("DEBUG: {test_name}: hello world");
-        let log_handler = TestLogHandler::default();
-        log_handler.exists_no_log_containing(&experiment_msg);
-        debug!(logger_clone, "hello world");
-        log_handler.exists_log_containing(&experiment_msg);
-    }
-
     #[test]
-    fn received_qualified_payables_exceeding_our_masq_balance_are_adjusted_before_forwarded_to_blockchain_bridge(
-    ) {
-        // the numbers for balances don't do real math, they need not to match either the condition for
-        // the payment adjustment or the actual values that come from the payable size reducing algorithm;
-        // all that is mocked in this test
+    fn payable_scan_is_aborted_and_reported_if_no_payment_can_be_afforded() {
         init_test_logging();
-        let test_name = "received_qualified_payables_exceeding_our_masq_balance_are_adjusted_before_forwarded_to_blockchain_bridge";
-        let adjust_payments_params_arc = Arc::new(Mutex::new(vec![]));
+        let test_name = "payable_scan_is_aborted_and_reported_if_no_payment_can_be_afforded";
         let (blockchain_bridge, _, blockchain_bridge_recording_arc) = make_recorder();
-        let report_recipient = blockchain_bridge
-            .system_stop_conditions(match_every_type_id!(OutboundPaymentsInstructions))
-            .start()
-            .recipient();
-        let mut subject = AccountantBuilder::default().build();
-        let unadjusted_account_1 = make_payable_account(111_111);
-        let unadjusted_account_2 = make_payable_account(222_222);
-        let adjusted_account_1 = PayableAccount {
-            balance_wei: gwei_to_wei(55_550_u64),
-            ..unadjusted_account_1.clone()
-        };
-        let adjusted_account_2 = PayableAccount {
-            balance_wei: gwei_to_wei(100_000_u64),
-            ..unadjusted_account_2.clone()
-        };
-        let response_skeleton = ResponseSkeleton {
-            client_id: 12,
-            context_id: 55,
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let analysis_error = AnalysisError::NotEnoughMasqBalanceForAnyAccount {
+            number_of_accounts: 1,
+            total_amount_demanded_minor: 1_000_000,
+            cw_masq_token_balance_minor: 1_000,
         };
-        let agent_id_stamp_first_phase = ArbitraryIdStamp::new();
-        let agent =
-            BlockchainAgentMock::default().set_arbitrary_id_stamp(agent_id_stamp_first_phase);
+        let payment_adjuster = PaymentAdjusterMock::default().is_adjustment_required_result(Err(
+            AnalysisError::NotEnoughMasqBalanceForAnyAccount {
+                number_of_accounts: 1,
+                total_amount_demanded_minor: 1_000_000,
+                cw_masq_token_balance_minor: 1_000,
+            },
+        ));
+        let payable_scanner = PayableScannerBuilder::new()
+            .payment_adjuster(payment_adjuster)
+            .build();
+        let mut subject = AccountantBuilder::default()
+            .logger(Logger::new(test_name))
+            .build();
+        subject.scanners.payable = Box::new(payable_scanner);
+        subject.scanners.payable.mark_as_started(SystemTime::now());
+        subject.outbound_payments_instructions_sub_opt =
+            Some(blockchain_bridge.start().recipient());
+        let subject_addr = subject.start();
+        let system = System::new(test_name);
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let response_skeleton = ResponseSkeleton {
+            client_id: 22,
+            context_id: 33,
+        };
+        let msg = BlockchainAgentWithContextMessage {
+            protected_qualified_payables: protect_payables_in_test(vec![make_payable_account(
+                1_000,
+            )]),
+            agent: Box::new(BlockchainAgentMock::default()),
+            response_skeleton_opt: Some(response_skeleton),
+        };
+
+        subject_addr.try_send(msg).unwrap();
+
+        subject_addr
+            .try_send(AssertionsMessage {
+                assertions: Box::new(|actor: &mut Accountant| {
+                    assert_eq!(actor.scanners.payable.scan_started_at(), None)
+                }),
+            })
+            .unwrap();
+        System::current().stop();
+        system.run();
+        let blockchain_bridge_recording = blockchain_bridge_recording_arc.lock().unwrap();
+        assert_eq!(blockchain_bridge_recording.len(), 0);
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: ClientId(22),
+                body: MessageBody {
+                    opcode: "scan".to_string(),
+                    path: MessagePath::Conversation(33),
+                    payload: Err((
+                        SCAN_ERROR,
+                        format!("Payables scan failed: '{}'", analysis_error),
+                    )),
+                },
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "ERROR: {}: Received ScanError: ScanError {{ scan_type: Payables",
+            test_name
+        ));
+    }
+
+    fn test_use_of_the_same_logger(logger_clone: &Logger, test_name: &str) {
+        let experiment_msg = format!("DEBUG: {test_name}: hello world");
+        let log_handler = TestLogHandler::default();
+        log_handler.exists_no_log_containing(&experiment_msg);
+        debug!(logger_clone, "hello world");
+        log_handler.exists_log_containing(&experiment_msg);
+    }
+
+    #[test]
+    fn received_qualified_payables_exceeding_our_masq_balance_are_adjusted_before_forwarded_to_blockchain_bridge(
+    ) {
+        // the numbers for balances don't do real math, they need not to match either the condition for
+        // the payment adjustment or the actual values that come from the payable size reducing algorithm;
+        // all that is mocked in this test
+        init_test_logging();
+        let test_name = "received_qualified_payables_exceeding_our_masq_balance_are_adjusted_before_forwarded_to_blockchain_bridge";
+        let adjust_payments_params_arc = Arc::new(Mutex::new(vec![]));
+        let (blockchain_bridge, _, blockchain_bridge_recording_arc) = make_recorder();
+        let report_recipient = blockchain_bridge
+            .system_stop_conditions(match_every_type_id!(OutboundPaymentsInstructions))
+            .start()
+            .recipient();
+        let mut subject = AccountantBuilder::default().build();
+        let unadjusted_account_1 = make_payable_account(111_111);
+        let unadjusted_account_2 = make_payable_account(222_222);
+        let adjusted_account_1 = PayableAccount {
+            balance_wei: gwei_to_wei(55_550_u64),
+            ..unadjusted_account_1.clone()
+        };
+        let adjusted_account_2 = PayableAccount {
+            balance_wei: gwei_to_wei(100_000_u64),
+            ..unadjusted_account_2.clone()
+        };
+        let response_skeleton = ResponseSkeleton {
+            client_id: 12,
+            context_id: 55,
+        };
+        let agent_id_stamp_first_phase = ArbitraryIdStamp::new();
+        let agent =
+            BlockchainAgentMock::default().set_arbitrary_id_stamp(agent_id_stamp_first_phase);
         let initial_unadjusted_accounts = protect_payables_in_test(vec![
             unadjusted_account_1.clone(),
             unadjusted_account_2.clone(),
@@ -1645,15 +2467,26 @@ mod tests {
             agent: Box::new(agent),
             response_skeleton_opt: Some(response_skeleton),
         };
+        let summary = UiPaymentsAdjustedBroadcast {
+            paid_in_full_count: 0,
+            reduced: vec![UiReducedPayment {
+                wallet: unadjusted_account_1.wallet.to_string(),
+                original_balance_wei: unadjusted_account_1.balance_wei,
+                adjusted_balance_wei: adjusted_account_1.balance_wei,
+            }],
+            unpaid: vec![],
+        };
         let payment_adjuster = PaymentAdjusterMock::default()
             .is_adjustment_required_result(Ok(Some(Adjustment::MasqToken)))
             .adjust_payments_params(&adjust_payments_params_arc)
-            .adjust_payments_result(payments_instructions);
+            .adjust_payments_result((payments_instructions, summary.clone()));
         let payable_scanner = PayableScannerBuilder::new()
             .payment_adjuster(payment_adjuster)
             .build();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
         subject.scanners.payable = Box::new(payable_scanner);
         subject.outbound_payments_instructions_sub_opt = Some(report_recipient);
+        subject.ui_message_sub_opt = Some(ui_gateway.start().recipient());
         subject.logger = Logger::new(test_name);
         let subject_addr = subject.start();
         let system = System::new("test");
@@ -1704,6 +2537,15 @@ mod tests {
             agent_id_stamp_second_phase
         );
         assert_eq!(blockchain_bridge_recording.len(), 1);
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: AllClients,
+                body: summary.tmb(0),
+            }
+        );
+        assert_eq!(ui_gateway_recording.len(), 1);
         test_use_of_the_same_logger(&logger_clone, test_name)
     }
 
@@ -1723,6 +2565,8 @@ mod tests {
             attempt: 1,
             amount: 1_000_000,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let pending_payable_dao = PendingPayableDaoMock::default()
             .return_all_errorless_fingerprints_result(vec![fingerprint.clone()]);
@@ -1759,6 +2603,7 @@ mod tests {
                     client_id: 1234,
                     context_id: 4321,
                 }),
+                stuck_payables_opt: None,
             }
         );
     }
@@ -1781,6 +2626,8 @@ mod tests {
             attempt: 1,
             amount: 1_000_000,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let pending_payable_dao = PendingPayableDaoMock::default()
             .return_all_errorless_fingerprints_result(vec![fingerprint]);
@@ -1841,6 +2688,7 @@ mod tests {
                 client_id: 1234,
                 context_id: 4321,
             }),
+            replacements: vec![],
         };
 
         subject_addr.try_send(report_transaction_receipts).unwrap();
@@ -1934,16 +2782,21 @@ mod tests {
         System::current().stop();
         system.run();
         let blockchain_bridge_recorder = blockchain_bridge_recording_arc.lock().unwrap();
-        assert_eq!(blockchain_bridge_recorder.len(), 1);
+        assert_eq!(blockchain_bridge_recorder.len(), 2);
         let message = blockchain_bridge_recorder.get_record::<QualifiedPayablesMessage>(0);
         assert_eq!(
             message,
             &QualifiedPayablesMessage {
                 protected_qualified_payables: protect_payables_in_test(qualified_payables),
-                consuming_wallet,
+                consuming_wallet: consuming_wallet.clone(),
                 response_skeleton_opt: None,
             }
         );
+        let message = blockchain_bridge_recorder.get_record::<RequestConsumingWalletBalances>(1);
+        assert_eq!(
+            message,
+            &RequestConsumingWalletBalances { consuming_wallet }
+        );
     }
 
     #[test]
@@ -1981,6 +2834,7 @@ mod tests {
             retrieve_transactions_msg,
             &RetrieveTransactions {
                 recipient: earning_wallet.clone(),
+                confirmation_depth: DEFAULT_CONFIRMATION_DEPTH,
                 response_skeleton_opt: None,
             }
         );
@@ -1996,11 +2850,15 @@ mod tests {
         let earning_wallet = make_wallet("earner3000");
         let expected_receivable_1 = BlockchainTransaction {
             block_number: 7,
+            block_hash: make_tx_hash(1001),
+            transaction_hash: make_tx_hash(1),
             from: make_wallet("wallet0"),
             wei_amount: 456,
         };
         let expected_receivable_2 = BlockchainTransaction {
             block_number: 13,
+            block_hash: make_tx_hash(1002),
+            transaction_hash: make_tx_hash(2),
             from: make_wallet("wallet1"),
             wei_amount: 10000,
         };
@@ -2011,6 +2869,7 @@ mod tests {
             .set_arbitrary_id_stamp(transaction_id);
         let wrapped_transaction = TransactionSafeWrapper::new_with_builder(txn_inner_builder);
         let receivable_dao = ReceivableDaoMock::new()
+            .credited_transactions_since_result(vec![])
             .more_money_received_params(&more_money_received_params_arc)
             .more_money_received_result(wrapped_transaction);
         let config_dao = ConfigDaoMock::new()
@@ -2028,6 +2887,7 @@ mod tests {
             .try_send(ReceivedPayments {
                 timestamp: now,
                 payments: vec![expected_receivable_1.clone(), expected_receivable_2.clone()],
+                scanned_from_block: 123456777,
                 new_start_block: 123456789,
                 response_skeleton_opt: None,
             })
@@ -2038,7 +2898,11 @@ mod tests {
         let more_money_received_params = more_money_received_params_arc.lock().unwrap();
         assert_eq!(
             *more_money_received_params,
-            vec![(now, vec![expected_receivable_1, expected_receivable_2])]
+            vec![(
+                now,
+                vec![expected_receivable_1, expected_receivable_2],
+                vec![]
+            )]
         );
         let commit_params = commit_params_arc.lock().unwrap();
         assert_eq!(*commit_params, vec![()]);
@@ -2118,6 +2982,33 @@ mod tests {
         tlh.exists_log_containing("INFO: Accountant: Scanning for delinquencies");
     }
 
+    #[test]
+    fn scan_for_receivables_reports_outcome_and_ledger_totals_to_metrics_if_metrics_are_served() {
+        let payable_dao = PayableDaoMock::new().total_result(123_456_789);
+        let receivable_dao = ReceivableDaoMock::new().total_result(-987_654);
+        let mut config = make_bc_with_defaults();
+        config.metrics_port_opt = Some(5999);
+        let metrics = config.metrics.clone();
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(config)
+            .payable_daos(vec![ForAccountantBody(payable_dao)])
+            .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .build();
+        subject.scanners.receivable = Box::new(
+            ScannerMock::new()
+                .begin_scan_result(Err(BeginScanError::ScanAlreadyRunning(SystemTime::now()))),
+        );
+
+        subject.handle_request_of_scan_for_receivable(None);
+
+        let rendered = metrics.render();
+        assert!(rendered.contains(
+            "\nmasq_node_scans_total{scan=\"receivables\",outcome=\"already_running\"} 1\n"
+        ));
+        assert!(rendered.contains("\nmasq_node_payable_total_wei 123456789\n"));
+        assert!(rendered.contains("\nmasq_node_receivable_total_wei -987654\n"));
+    }
+
     #[test]
     fn periodical_scanning_for_receivables_and_delinquencies_works() {
         init_test_logging();
@@ -2131,6 +3022,7 @@ mod tests {
             .begin_scan_result(Err(BeginScanError::NothingToProcess))
             .begin_scan_result(Ok(RetrieveTransactions {
                 recipient: make_wallet("some_recipient"),
+                confirmation_depth: DEFAULT_CONFIRMATION_DEPTH,
                 response_skeleton_opt: None,
             }))
             .stop_the_system_after_last_msg();
@@ -2230,6 +3122,7 @@ mod tests {
             .begin_scan_result(Ok(RequestTransactionReceipts {
                 pending_payable: vec![],
                 response_skeleton_opt: None,
+                stuck_payables_opt: None,
             }))
             .stop_the_system_after_last_msg();
         let mut config = make_bc_with_defaults();
@@ -2680,6 +3573,8 @@ mod tests {
             }),
         };
         subject.qualified_payables_sub_opt = Some(pps_for_blockchain_bridge_sub);
+        subject.request_consuming_wallet_balances_sub_opt =
+            Some(blockchain_bridge_addr.clone().recipient());
         let addr = subject.start();
         addr.try_send(message_before.clone()).unwrap();
 
@@ -2704,13 +3599,15 @@ mod tests {
         system.run();
         let recording = blockchain_bridge_recording.lock().unwrap();
         let messages_received = recording.len();
-        assert_eq!(messages_received, 2);
+        // The scheduled scan was refused, but it still asked for the balances
+        assert_eq!(messages_received, 3);
         let first_message: &QualifiedPayablesMessage = recording.get_record(0);
         assert_eq!(
             first_message.response_skeleton_opt,
             message_before.response_skeleton_opt
         );
-        let second_message: &QualifiedPayablesMessage = recording.get_record(1);
+        let _: &RequestConsumingWalletBalances = recording.get_record(1);
+        let second_message: &QualifiedPayablesMessage = recording.get_record(2);
         assert_eq!(
             second_message.response_skeleton_opt,
             message_after.response_skeleton_opt
@@ -2735,6 +3632,8 @@ mod tests {
             attempt: 1,
             amount: 4444,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let payable_fingerprint_2 = PendingPayableFingerprint {
             rowid: 550,
@@ -2743,6 +3642,8 @@ mod tests {
             attempt: 2,
             amount: 7999,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let pending_payable_dao = PendingPayableDaoMock::default()
             .return_all_errorless_fingerprints_result(vec![
@@ -2775,6 +3676,7 @@ mod tests {
             &RequestTransactionReceipts {
                 pending_payable: vec![payable_fingerprint_1, payable_fingerprint_2],
                 response_skeleton_opt: None,
+                stuck_payables_opt: None,
             }
         );
         let log_handler = TestLogHandler::new();
@@ -2791,10 +3693,13 @@ mod tests {
         let receivable_dao_mock = ReceivableDaoMock::new()
             .more_money_receivable_parameters(&more_money_receivable_parameters_arc)
             .more_money_receivable_result(Ok(()));
+        let record_params_arc = Arc::new(Mutex::new(vec![]));
+        let ledger_dao = LedgerDaoMock::new().record_params(&record_params_arc);
         let subject = AccountantBuilder::default()
             .bootstrapper_config(bootstrapper_config)
             .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
             .receivable_daos(vec![ForAccountantBody(receivable_dao_mock)])
+            .ledger_daos(vec![ForAccountantBody(ledger_dao)])
             .build();
         let system = System::new("report_routing_service_message_is_received");
         let subject_addr: Addr<Accountant> = subject.start();
@@ -2822,6 +3727,17 @@ mod tests {
             more_money_receivable_parameters[0],
             (now, make_wallet("booga"), (1 * 42) + (1234 * 24))
         );
+        let record_params = record_params_arc.lock().unwrap();
+        assert_eq!(
+            *record_params,
+            vec![vec![LedgerEntry::service(
+                now,
+                make_wallet("booga"),
+                LedgerEntryType::RoutingProvided,
+                1234,
+                (1 * 42) + (1234 * 24)
+            )]]
+        );
         TestLogHandler::new().exists_log_containing(&format!(
             "DEBUG: Accountant: Charging routing of 1234 bytes to wallet {}",
             paying_wallet
@@ -2928,10 +3844,13 @@ mod tests {
         let receivable_dao_mock = ReceivableDaoMock::new()
             .more_money_receivable_parameters(&more_money_receivable_parameters_arc)
             .more_money_receivable_result(Ok(()));
+        let record_params_arc = Arc::new(Mutex::new(vec![]));
+        let ledger_dao = LedgerDaoMock::new().record_params(&record_params_arc);
         let subject = AccountantBuilder::default()
             .bootstrapper_config(config)
             .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
             .receivable_daos(vec![ForAccountantBody(receivable_dao_mock)])
+            .ledger_daos(vec![ForAccountantBody(ledger_dao)])
             .build();
         let system = System::new("report_exit_service_provided_message_is_received");
         let subject_addr: Addr<Accountant> = subject.start();
@@ -2959,6 +3878,17 @@ mod tests {
             more_money_receivable_parameters[0],
             (now, make_wallet("booga"), (1 * 42) + (1234 * 24))
         );
+        let record_params = record_params_arc.lock().unwrap();
+        assert_eq!(
+            *record_params,
+            vec![vec![LedgerEntry::service(
+                now,
+                make_wallet("booga"),
+                LedgerEntryType::ExitProvided,
+                1234,
+                (1 * 42) + (1234 * 24)
+            )]]
+        );
         TestLogHandler::new().exists_log_containing(&format!(
             "DEBUG: Accountant: Charging exit service for 1234 bytes to wallet {}",
             paying_wallet
@@ -3065,9 +3995,12 @@ mod tests {
             .more_money_payable_result(Ok(()))
             .more_money_payable_result(Ok(()))
             .more_money_payable_result(Ok(()));
+        let record_params_arc = Arc::new(Mutex::new(vec![]));
+        let ledger_dao = LedgerDaoMock::new().record_params(&record_params_arc);
         let mut subject = AccountantBuilder::default()
             .bootstrapper_config(config)
             .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
+            .ledger_daos(vec![ForAccountantBody(ledger_dao)])
             .build();
         subject.message_id_generator = Box::new(MessageIdGeneratorMock::default().id_result(123));
         let system = System::new("report_services_consumed_message_is_received");
@@ -3129,6 +4062,33 @@ mod tests {
                 )
             ]
         );
+        let record_params = record_params_arc.lock().unwrap();
+        assert_eq!(
+            *record_params,
+            vec![
+                vec![LedgerEntry::service(
+                    timestamp,
+                    earning_wallet_exit.clone(),
+                    LedgerEntryType::ExitConsumed,
+                    1200,
+                    (1 * 120) + (1200 * 30)
+                )],
+                vec![LedgerEntry::service(
+                    timestamp,
+                    earning_wallet_routing_1.clone(),
+                    LedgerEntryType::RoutingConsumed,
+                    3456,
+                    (1 * 42) + (3456 * 24)
+                )],
+                vec![LedgerEntry::service(
+                    timestamp,
+                    earning_wallet_routing_2.clone(),
+                    LedgerEntryType::RoutingConsumed,
+                    3456,
+                    (1 * 52) + (3456 * 33)
+                )]
+            ]
+        );
         let test_log_handler = TestLogHandler::new();
 
         test_log_handler.exists_log_containing(&format!(
@@ -3334,7 +4294,14 @@ mod tests {
             .receivable_daos(vec![ForAccountantBody(receivable_dao)])
             .build();
 
-        let _ = subject.record_service_provided(i64::MAX as u64, 1, SystemTime::now(), 2, &wallet);
+        let _ = subject.record_service_provided(
+            i64::MAX as u64,
+            1,
+            SystemTime::now(),
+            2,
+            &wallet,
+            LedgerEntryType::RoutingProvided,
+        );
     }
 
     #[test]
@@ -3343,12 +4310,23 @@ mod tests {
         let wallet = make_wallet("booga");
         let receivable_dao = ReceivableDaoMock::new()
             .more_money_receivable_result(Err(ReceivableDaoError::SignConversion(1234)));
+        let record_params_arc = Arc::new(Mutex::new(vec![]));
+        let ledger_dao = LedgerDaoMock::new().record_params(&record_params_arc);
         let subject = AccountantBuilder::default()
             .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .ledger_daos(vec![ForAccountantBody(ledger_dao)])
             .build();
 
-        subject.record_service_provided(i64::MAX as u64, 1, SystemTime::now(), 2, &wallet);
+        subject.record_service_provided(
+            i64::MAX as u64,
+            1,
+            SystemTime::now(),
+            2,
+            &wallet,
+            LedgerEntryType::RoutingProvided,
+        );
 
+        assert!(record_params_arc.lock().unwrap().is_empty());
         TestLogHandler::new().exists_log_containing(&format!(
             "ERROR: Accountant: Overflow error recording service provided for {}: service rate {}, byte rate 1, payload size 2. Skipping",
             wallet,
@@ -3362,12 +4340,19 @@ mod tests {
         let wallet = make_wallet("booga");
         let payable_dao = PayableDaoMock::new()
             .more_money_payable_result(Err(PayableDaoError::SignConversion(1234)));
-        let subject = AccountantBuilder::default()
+        let mut subject = AccountantBuilder::default()
             .payable_daos(vec![ForAccountantBody(payable_dao)])
             .build();
         let service_rate = i64::MAX as u64;
 
-        subject.record_service_consumed(service_rate, 1, SystemTime::now(), 2, &wallet);
+        subject.record_service_consumed(
+            service_rate,
+            1,
+            SystemTime::now(),
+            2,
+            &wallet,
+            LedgerEntryType::RoutingConsumed,
+        );
 
         TestLogHandler::new().exists_log_containing(&format!(
             "ERROR: Accountant: Overflow error recording consumed services from {}: total charge {}, service rate {}, byte rate 1, payload size 2. Skipping",
@@ -3388,11 +4373,18 @@ mod tests {
         let payable_dao = PayableDaoMock::new().more_money_payable_result(Err(
             PayableDaoError::RusqliteError("we cannot help ourselves; this is baaad".to_string()),
         ));
-        let subject = AccountantBuilder::default()
+        let mut subject = AccountantBuilder::default()
             .payable_daos(vec![ForAccountantBody(payable_dao)])
             .build();
 
-        let _ = subject.record_service_consumed(i64::MAX as u64, 1, SystemTime::now(), 2, &wallet);
+        let _ = subject.record_service_consumed(
+            i64::MAX as u64,
+            1,
+            SystemTime::now(),
+            2,
+            &wallet,
+            LedgerEntryType::RoutingConsumed,
+        );
     }
 
     #[test]
@@ -3451,10 +4443,19 @@ mod tests {
         let transaction_receipt_tx_2_third_round = TransactionReceipt::default();
         let mut transaction_receipt_tx_2_fourth_round = TransactionReceipt::default();
         transaction_receipt_tx_2_fourth_round.status = Some(U64::from(1)); // confirmed
-        let agent = BlockchainAgentMock::default();
+        let agent = BlockchainAgentMock::default()
+            .consuming_wallet_balances_result(ConsumingWalletBalances::new(
+                U256::from(u128::MAX),
+                U256::from(u128::MAX),
+            ))
+            .estimated_transaction_fee_total_result(1_000_000);
         let blockchain_interface = BlockchainInterfaceMock::default()
             .build_blockchain_agent_params(&build_blockchain_agent_params)
             .build_blockchain_agent_result(Ok(Box::new(agent)))
+            // the balances aren't forecast in this test
+            .build_blockchain_agent_result(Err(
+                BlockchainAgentBuildError::UninitializedBlockchainInterface,
+            ))
             // because we cannot have both, resolution on the high level and also of what's inside blockchain interface,
             // there is one component missing in this wholesome test - the part where we send a request for
             // a fingerprint of that payable in the DB - this happens inside send_raw_transaction()
@@ -3520,6 +4521,8 @@ mod tests {
             attempt: 1,
             amount: payable_account_balance_1,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let fingerprint_2_first_round = PendingPayableFingerprint {
             rowid: rowid_for_account_2,
@@ -3528,6 +4531,8 @@ mod tests {
             attempt: 1,
             amount: payable_account_balance_2,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let fingerprint_1_second_round = PendingPayableFingerprint {
             attempt: 2,
@@ -3667,7 +4672,10 @@ mod tests {
         let build_blockchain_agent_params = build_blockchain_agent_params.lock().unwrap();
         assert_eq!(
             *build_blockchain_agent_params,
-            vec![(consuming_wallet, persistent_config_id_stamp)]
+            vec![
+                (consuming_wallet.clone(), persistent_config_id_stamp),
+                (consuming_wallet, persistent_config_id_stamp)
+            ]
         );
         let update_fingerprints_params = update_fingerprint_params_arc.lock().unwrap();
         assert_eq!(
@@ -3750,6 +4758,8 @@ mod tests {
             attempt: 2,
             amount: 444,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let transaction_hash_2 = make_tx_hash(3333333);
         let mut transaction_receipt_2 = TransactionReceipt::default();
@@ -3762,6 +4772,8 @@ mod tests {
             attempt: 15,
             amount: 1212,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let msg = ReportTransactionReceipts {
             fingerprints_with_receipts: vec![
@@ -3769,6 +4781,7 @@ mod tests {
                 (Some(transaction_receipt_2), fingerprint_2.clone()),
             ],
             response_skeleton_opt: None,
+            replacements: vec![],
         };
 
         subject_addr.try_send(msg).unwrap();
@@ -3801,9 +4814,11 @@ mod tests {
         let hash_2 = make_tx_hash(0x1b207);
         let amount_2 = 87654;
         let init_params = vec![(hash_1, amount_1), (hash_2, amount_2)];
+        let resubmission_data = vec![make_resubmission_data(4), make_resubmission_data(5)];
         let init_fingerprints_msg = PendingPayableFingerprintSeeds {
             batch_wide_timestamp: timestamp,
             hashes_and_balances: init_params.clone(),
+            resubmission_data: resubmission_data.clone(),
         };
 
         let _ = accountant_subs
@@ -3817,7 +4832,11 @@ mod tests {
         let insert_fingerprint_params = insert_fingerprint_params_arc.lock().unwrap();
         assert_eq!(
             *insert_fingerprint_params,
-            vec![(vec![(hash_1, amount_1), (hash_2, amount_2)], timestamp)]
+            vec![(
+                vec![(hash_1, amount_1), (hash_2, amount_2)],
+                resubmission_data,
+                timestamp
+            )]
         );
         TestLogHandler::new().exists_log_containing(
             "DEBUG: Accountant: Saved new pending payable fingerprints for: \
@@ -3844,6 +4863,7 @@ mod tests {
         let report_new_fingerprints = PendingPayableFingerprintSeeds {
             batch_wide_timestamp: timestamp,
             hashes_and_balances: vec![(transaction_hash, amount)],
+            resubmission_data: vec![make_resubmission_data(6)],
         };
 
         let _ = subject.handle_new_pending_payable_fingerprints(report_new_fingerprints);
@@ -3851,7 +4871,11 @@ mod tests {
         let insert_fingerprint_params = insert_fingerprint_params_arc.lock().unwrap();
         assert_eq!(
             *insert_fingerprint_params,
-            vec![(vec![(transaction_hash, amount)], timestamp)]
+            vec![(
+                vec![(transaction_hash, amount)],
+                vec![make_resubmission_data(6)],
+                timestamp
+            )]
         );
         TestLogHandler::new().exists_log_containing("ERROR: Accountant: Failed to process \
          new pending payable fingerprints due to 'InsertionFailed(\"Crashed\")', disabling the automated \
@@ -3953,6 +4977,7 @@ mod tests {
                 stats_required: false,
                 top_records_opt: None,
                 custom_queries_opt: None,
+                page_opt: None,
             }
             .tmb(2222),
         };
@@ -3998,6 +5023,7 @@ mod tests {
                 }),
                 receivable_opt: None,
             }),
+            page_opt: None,
         };
 
         let result = subject.compute_financials(&request, 4567);
@@ -4037,6 +5063,7 @@ mod tests {
                 stats_required: true,
                 top_records_opt: None,
                 custom_queries_opt: None,
+                page_opt: None,
             }
             .tmb(2222),
         };
@@ -4058,105 +5085,1280 @@ mod tests {
                     total_paid_payable_gwei: 0,
                     total_unpaid_receivable_gwei: 987,
                     total_paid_receivable_gwei: 0,
+                    runway_opt: None,
                 }),
                 query_results_opt: None,
             }
         )
     }
 
+    fn make_report_of_consuming_wallet_balances(
+        consuming_wallet: &Wallet,
+        service_fee_balance_wei: u128,
+        transaction_fee_balance_wei: u128,
+    ) -> ReportConsumingWalletBalances {
+        ReportConsumingWalletBalances {
+            consuming_wallet: consuming_wallet.clone(),
+            balances: ConsumingWalletBalances::new(
+                U256::from(transaction_fee_balance_wei),
+                U256::from(service_fee_balance_wei),
+            ),
+            transaction_fee_per_payment_wei: 1_000_000,
+        }
+    }
+
+    fn forecast_with_a_day_of_spending(spent_wei: u128) -> BalanceForecast {
+        let mut forecast = BalanceForecast::new(
+            SystemTime::now() - Duration::from_secs(24 * 3600),
+            gwei_to_wei(DEFAULT_PAYMENT_THRESHOLDS.debt_threshold_gwei),
+        );
+        forecast.record_spending(SystemTime::now(), spent_wei);
+        forecast
+    }
+
     #[test]
-    fn compute_financials_processes_defaulted_request() {
-        let payable_dao = PayableDaoMock::new().total_result(u64::MAX as u128 + 123456);
-        let receivable_dao = ReceivableDaoMock::new().total_result((i64::MAX as i128) * 3);
-        let subject = AccountantBuilder::default()
-            .bootstrapper_config(bc_from_earning_wallet(make_wallet("some_wallet_address")))
+    fn short_runway_of_consuming_wallet_is_broadcast_only_once() {
+        init_test_logging();
+        let test_name = "short_runway_of_consuming_wallet_is_broadcast_only_once";
+        let masq: u128 = 1_000_000_000_000_000_000;
+        let consuming_wallet = make_wallet("consuming");
+        let payable_dao = PayableDaoMock::new()
+            .total_result(24 * masq)
+            .total_result(24 * masq);
+        let system = System::new(test_name);
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(make_bc_with_defaults())
             .payable_daos(vec![ForAccountantBody(payable_dao)])
-            .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .logger(Logger::new(test_name))
             .build();
-        subject
-            .financial_statistics
-            .borrow_mut()
-            .total_paid_payable_wei = 172_345_602_235_454_454;
-        subject
-            .financial_statistics
-            .borrow_mut()
-            .total_paid_receivable_wei = 4_455_656_989_415_777_555;
-        let context_id = 1234;
-        let request = UiFinancialsRequest {
-            stats_required: true,
-            top_records_opt: None,
-            custom_queries_opt: None,
-        };
+        subject.balance_forecast = forecast_with_a_day_of_spending(24 * masq);
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        subject.ui_message_sub_opt = Some(ui_gateway.start().recipient());
 
-        let result = subject.compute_financials(&request, context_id);
+        subject.handle_report_consuming_wallet_balances(make_report_of_consuming_wallet_balances(
+            &consuming_wallet,
+            48 * masq,
+            48_000_000,
+        ));
+        subject.handle_report_consuming_wallet_balances(make_report_of_consuming_wallet_balances(
+            &consuming_wallet,
+            48 * masq,
+            48_000_000,
+        ));
 
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(ui_gateway_recording.len(), 1);
+        let message = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
+        assert_eq!(message.target, AllClients);
+        let (body, _) = UiWalletBalanceWarningBroadcast::fmb(message.body.clone()).unwrap();
         assert_eq!(
-            result,
-            UiFinancialsResponse {
-                stats_opt: Some(UiFinancialStatistics {
-                    total_unpaid_and_pending_payable_gwei: 18446744073,
-                    total_paid_payable_gwei: 172345602,
-                    total_unpaid_receivable_gwei: 27670116110,
-                    total_paid_receivable_gwei: 4455656989
-                }),
-                query_results_opt: None
+            body,
+            UiWalletBalanceWarningBroadcast {
+                consuming_wallet: consuming_wallet.to_string(),
+                service_fee_balance_wei: 48 * masq,
+                transaction_fee_balance_wei: 48_000_000,
+                runway: UiWalletRunway {
+                    service_fee_runway_hours_opt: Some(24),
+                    transaction_fee_runway_hours_opt: Some(48),
+                },
+                warning_days: DEFAULT_BALANCE_WARNING_DAYS,
             }
-            .tmb(context_id)
-        )
+        );
+        TestLogHandler::new().assert_logs_contain_in_order(vec![&format!(
+            "WARN: {test_name}: Consuming wallet {} is running low: at the recent rate of spending, \
+            its MASQ will last about 24 hours and its gas about 48 hours. Fund it to keep paying for services",
+            consuming_wallet
+        )]);
     }
 
-    macro_rules! extract_ages_from_accounts {
-        ($main_structure: expr, $account_specific_field_opt: ident) => {{
-            let accounts_collection = &$main_structure
-                .query_results_opt
-                .as_ref()
-                .unwrap()
-                .$account_specific_field_opt
-                .as_ref()
-                .unwrap();
-            accounts_collection
-                .iter()
-                .map(|account| account.age_s)
-                .collect::<Vec<u64>>()
-        }};
+    #[test]
+    fn ample_runway_of_consuming_wallet_is_not_broadcast() {
+        init_test_logging();
+        let test_name = "ample_runway_of_consuming_wallet_is_not_broadcast";
+        let masq: u128 = 1_000_000_000_000_000_000;
+        let payable_dao = PayableDaoMock::new().total_result(0);
+        let system = System::new(test_name);
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(make_bc_with_defaults())
+            .payable_daos(vec![ForAccountantBody(payable_dao)])
+            .logger(Logger::new(test_name))
+            .build();
+        subject.balance_forecast = forecast_with_a_day_of_spending(masq);
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        subject.ui_message_sub_opt = Some(ui_gateway.start().recipient());
+
+        subject.handle_report_consuming_wallet_balances(make_report_of_consuming_wallet_balances(
+            &make_wallet("consuming"),
+            30 * masq,
+            1_000_000_000,
+        ));
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(ui_gateway_recording.len(), 0);
+        TestLogHandler::new().exists_no_log_containing(&format!("WARN: {test_name}"));
     }
 
     #[test]
-    fn compute_financials_processes_request_with_top_records_only_and_balance_ordering() {
-        //take that the tested logic doesn't contain anything about an actual process of ordering,
-        //that part is in the responsibility of the database manager, answering the specific SQL query
-        let payable_custom_query_params_arc = Arc::new(Mutex::new(vec![]));
-        let receivable_custom_query_params_arc = Arc::new(Mutex::new(vec![]));
-        let payable_accounts_retrieved = vec![PayableAccount {
-            wallet: make_wallet("abcd123"),
-            balance_wei: 58_568_686_005,
-            last_paid_timestamp: SystemTime::now().sub(Duration::from_secs(5000)),
-            pending_payable_opt: None,
-        }];
+    fn financials_show_the_runway_once_the_balances_are_known() {
+        let masq: u128 = 1_000_000_000_000_000_000;
         let payable_dao = PayableDaoMock::new()
-            .custom_query_params(&payable_custom_query_params_arc)
-            .custom_query_result(Some(payable_accounts_retrieved));
-        let receivable_accounts_retrieved = vec![ReceivableAccount {
-            wallet: make_wallet("efe4848"),
-            balance_wei: 3_788_455_600_556_898,
-            last_received_timestamp: SystemTime::now().sub(Duration::from_secs(6500)),
-        }];
-        let receivable_dao = ReceivableDaoMock::new()
-            .custom_query_params(&receivable_custom_query_params_arc)
-            .custom_query_result(Some(receivable_accounts_retrieved));
-        let subject = AccountantBuilder::default()
-            .bootstrapper_config(bc_from_earning_wallet(make_wallet("some_wallet_address")))
+            .total_result(2 * masq)
+            .total_result(2 * masq);
+        let receivable_dao = ReceivableDaoMock::new().total_result(0);
+        let mut bootstrapper_config = make_bc_with_defaults();
+        bootstrapper_config.balance_warning_days = 0;
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(bootstrapper_config)
             .payable_daos(vec![ForAccountantBody(payable_dao)])
             .receivable_daos(vec![ForAccountantBody(receivable_dao)])
             .build();
-        let context_id_expected = 1234;
+        subject.balance_forecast = forecast_with_a_day_of_spending(4 * masq);
+        subject.handle_report_consuming_wallet_balances(make_report_of_consuming_wallet_balances(
+            &make_wallet("consuming"),
+            10 * masq,
+            10_000_000,
+        ));
         let request = UiFinancialsRequest {
-            stats_required: false,
-            top_records_opt: Some(TopRecordsConfig {
+            stats_required: true,
+            top_records_opt: None,
+            custom_queries_opt: None,
+            page_opt: None,
+        };
+
+        let result = subject.compute_financials(&request, 1234);
+
+        let (body, _) = UiFinancialsResponse::fmb(result).unwrap();
+        assert_eq!(
+            body.stats_opt.unwrap().runway_opt,
+            Some(UiWalletRunway {
+                service_fee_runway_hours_opt: Some(48),
+                transaction_fee_runway_hours_opt: Some(60),
+            })
+        );
+    }
+
+    #[test]
+    fn consumed_services_are_counted_into_the_spending_forecast() {
+        let payable_dao = PayableDaoMock::new().more_money_payable_result(Ok(()));
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(make_bc_with_defaults())
+            .payable_daos(vec![ForAccountantBody(payable_dao)])
+            .build();
+        subject.balance_forecast = forecast_with_a_day_of_spending(1_000);
+
+        subject.record_service_consumed(
+            500,
+            10,
+            SystemTime::now(),
+            100,
+            &make_wallet("exit"),
+            LedgerEntryType::ExitConsumed,
+        );
+
+        assert_eq!(
+            subject
+                .balance_forecast
+                .daily_spending_wei(SystemTime::now()),
+            1_000 + 500 + 10 * 100
+        );
+    }
+
+    fn send_ban_request_and_collect_ui_messages(
+        banned_dao: BannedDaoMock,
+        request: MessageBody,
+    ) -> Vec<NodeToUiMessage> {
+        let system = System::new("test");
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(make_bc_with_defaults())
+            .banned_daos(vec![ForAccountantBody(banned_dao)])
+            .build();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let subject_addr = subject.start();
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: request,
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        (0..ui_gateway_recording.len())
+            .map(|idx| {
+                ui_gateway_recording
+                    .get_record::<NodeToUiMessage>(idx)
+                    .clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ban_list_request_produces_ban_list_response() {
+        let ban_list_params_arc = Arc::new(Mutex::new(vec![]));
+        let banned_dao = BannedDaoMock::new()
+            .ban_list_parameters(&ban_list_params_arc)
+            .ban_list_result(vec![
+                BannedAccount {
+                    wallet: make_wallet("manual"),
+                    reason_opt: Some("Spammer".to_string()),
+                    timestamp_opt: Some(from_time_t(1_700_000_000)),
+                    manual: true,
+                },
+                BannedAccount {
+                    wallet: make_wallet("legacy"),
+                    reason_opt: None,
+                    timestamp_opt: None,
+                    manual: false,
+                },
+            ]);
+
+        let result =
+            send_ban_request_and_collect_ui_messages(banned_dao, UiBanListRequest {}.tmb(3333));
+
+        assert_eq!(*ban_list_params_arc.lock().unwrap(), vec![()]);
+        assert_eq!(
+            result,
+            vec![NodeToUiMessage {
+                target: ClientId(1234),
+                body: UiBanListResponse {
+                    banned: vec![
+                        UiBannedWallet {
+                            wallet: make_wallet("manual").to_string(),
+                            reason_opt: Some("Spammer".to_string()),
+                            timestamp_s_opt: Some(1_700_000_000),
+                            manual: true,
+                        },
+                        UiBannedWallet {
+                            wallet: make_wallet("legacy").to_string(),
+                            reason_opt: None,
+                            timestamp_s_opt: None,
+                            manual: false,
+                        }
+                    ]
+                }
+                .tmb(3333)
+            }]
+        );
+    }
+
+    #[test]
+    fn ban_request_bans_manually_and_tells_the_other_clients() {
+        let ban_params_arc = Arc::new(Mutex::new(vec![]));
+        let banned_dao = BannedDaoMock::new()
+            .ban_parameters(&ban_params_arc)
+            .ban_result(true);
+        let wallet = make_wallet("spammer");
+        let before = SystemTime::now();
+
+        let result = send_ban_request_and_collect_ui_messages(
+            banned_dao,
+            UiBanRequest {
+                wallet: wallet.to_string(),
+                reason_opt: Some("Spammer".to_string()),
+            }
+            .tmb(4444),
+        );
+
+        let after = SystemTime::now();
+        let ban_params = ban_params_arc.lock().unwrap();
+        let (ban_wallet, timestamp, reason_opt, origin) = &ban_params[0];
+        assert_eq!(ban_wallet, &wallet);
+        assert!(before <= *timestamp && *timestamp <= after);
+        assert_eq!(reason_opt, &Some("Spammer".to_string()));
+        assert_eq!(origin, &BanOrigin::Manual);
+        assert_eq!(ban_params.len(), 1);
+        assert_eq!(
+            result,
+            vec![
+                NodeToUiMessage {
+                    target: AllExcept(1234),
+                    body: UiBanChangedBroadcast {
+                        wallet: wallet.to_string(),
+                        banned: true,
+                        reason_opt: Some("Spammer".to_string()),
+                    }
+                    .tmb(0)
+                },
+                NodeToUiMessage {
+                    target: ClientId(1234),
+                    body: UiBanResponse {
+                        already_banned: false
+                    }
+                    .tmb(4444)
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn ban_request_for_a_banned_wallet_broadcasts_nothing() {
+        let banned_dao = BannedDaoMock::new().ban_result(false);
+
+        let result = send_ban_request_and_collect_ui_messages(
+            banned_dao,
+            UiBanRequest {
+                wallet: make_wallet("spammer").to_string(),
+                reason_opt: None,
+            }
+            .tmb(4444),
+        );
+
+        assert_eq!(
+            result,
+            vec![NodeToUiMessage {
+                target: ClientId(1234),
+                body: UiBanResponse {
+                    already_banned: true
+                }
+                .tmb(4444)
+            }]
+        );
+    }
+
+    #[test]
+    fn unban_request_lifts_the_ban_and_tells_the_other_clients() {
+        let unban_params_arc = Arc::new(Mutex::new(vec![]));
+        let banned_dao = BannedDaoMock::new()
+            .unban_parameters(&unban_params_arc)
+            .unban_result(true);
+        let wallet = make_wallet("reformed");
+
+        let result = send_ban_request_and_collect_ui_messages(
+            banned_dao,
+            UiUnbanRequest {
+                wallet: wallet.to_string(),
+            }
+            .tmb(5555),
+        );
+
+        assert_eq!(*unban_params_arc.lock().unwrap(), vec![wallet.clone()]);
+        assert_eq!(
+            result,
+            vec![
+                NodeToUiMessage {
+                    target: AllExcept(1234),
+                    body: UiBanChangedBroadcast {
+                        wallet: wallet.to_string(),
+                        banned: false,
+                        reason_opt: None,
+                    }
+                    .tmb(0)
+                },
+                NodeToUiMessage {
+                    target: ClientId(1234),
+                    body: UiUnbanResponse { was_banned: true }.tmb(5555)
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn unban_request_for_a_wallet_that_was_not_banned_broadcasts_nothing() {
+        let banned_dao = BannedDaoMock::new().unban_result(false);
+
+        let result = send_ban_request_and_collect_ui_messages(
+            banned_dao,
+            UiUnbanRequest {
+                wallet: make_wallet("innocent").to_string(),
+            }
+            .tmb(5555),
+        );
+
+        assert_eq!(
+            result,
+            vec![NodeToUiMessage {
+                target: ClientId(1234),
+                body: UiUnbanResponse { was_banned: false }.tmb(5555)
+            }]
+        );
+    }
+
+    #[test]
+    fn ban_requests_with_invalid_wallets_are_rejected() {
+        let ban_result = send_ban_request_and_collect_ui_messages(
+            BannedDaoMock::new(),
+            UiBanRequest {
+                wallet: "booga".to_string(),
+                reason_opt: None,
+            }
+            .tmb(6666),
+        );
+        let unban_result = send_ban_request_and_collect_ui_messages(
+            BannedDaoMock::new(),
+            UiUnbanRequest {
+                wallet: "0xbooga".to_string(),
+            }
+            .tmb(7777),
+        );
+
+        let assert_rejection = |result: Vec<NodeToUiMessage>, opcode: &str, context_id, wallet| {
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].target, ClientId(1234));
+            assert_eq!(result[0].body.opcode, opcode);
+            assert_eq!(result[0].body.path, MessagePath::Conversation(context_id));
+            let (code, message) = result[0].body.payload.clone().unwrap_err();
+            assert_eq!(code, BAN_REQUEST_ERROR);
+            assert!(
+                message.starts_with(&format!("Invalid wallet '{}': ", wallet)),
+                "{}",
+                message
+            );
+        };
+        assert_rejection(ban_result, "ban", 6666, "booga");
+        assert_rejection(unban_result, "unban", 7777, "0xbooga");
+    }
+
+    #[test]
+    fn ledger_request_produces_ledger_response() {
+        let entries_params_arc = Arc::new(Mutex::new(vec![]));
+        let wallet = make_wallet("creditor");
+        let ledger_dao = LedgerDaoMock::new()
+            .entries_params(&entries_params_arc)
+            .entries_result(vec![
+                LedgerEntry::payment(
+                    from_time_t(1_700_000_500),
+                    wallet.clone(),
+                    LedgerEntryType::PaymentSent,
+                    u64::MAX as u128 + 1,
+                    make_tx_hash(456),
+                ),
+                LedgerEntry {
+                    entry_count_opt: Some(17),
+                    ..LedgerEntry::service(
+                        from_time_t(1_700_000_000),
+                        wallet.clone(),
+                        LedgerEntryType::RoutingConsumed,
+                        5000,
+                        123_456,
+                    )
+                },
+            ]);
+        let system = System::new("test");
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(make_bc_with_defaults())
+            .ledger_daos(vec![ForAccountantBody(ledger_dao)])
+            .build();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let subject_addr = subject.start();
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let ui_message = NodeFromUiMessage {
+            client_id: 1234,
+            body: UiLedgerRequest {
+                wallet_opt: Some(wallet.to_string()),
+                from_timestamp_s_opt: Some(1_699_000_000),
+                to_timestamp_s_opt: Some(1_701_000_000),
+                max_entries_opt: Some(50),
+            }
+            .tmb(2222),
+        };
+
+        subject_addr.try_send(ui_message).unwrap();
+
+        System::current().stop();
+        system.run();
+        let entries_params = entries_params_arc.lock().unwrap();
+        assert_eq!(
+            *entries_params,
+            vec![LedgerQuery {
+                wallet_opt: Some(wallet.clone()),
+                from_opt: Some(from_time_t(1_699_000_000)),
+                to_opt: Some(from_time_t(1_701_000_000)),
+                max_entries_opt: Some(50),
+            }]
+        );
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let response = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
+        assert_eq!(response.target, ClientId(1234));
+        let (body, context_id) = UiLedgerResponse::fmb(response.body.clone()).unwrap();
+        assert_eq!(context_id, 2222);
+        assert_eq!(
+            body,
+            UiLedgerResponse {
+                entries: vec![
+                    UiLedgerEntry {
+                        timestamp_s: 1_700_000_500,
+                        wallet: wallet.to_string(),
+                        entry_type: "paymentSent".to_string(),
+                        bytes: 0,
+                        amount_wei: u64::MAX as u128 + 1,
+                        transaction_hash_opt: Some(format!("{:?}", make_tx_hash(456))),
+                        entry_count_opt: None,
+                    },
+                    UiLedgerEntry {
+                        timestamp_s: 1_700_000_000,
+                        wallet: wallet.to_string(),
+                        entry_type: "routingConsumed".to_string(),
+                        bytes: 5000,
+                        amount_wei: 123_456,
+                        transaction_hash_opt: None,
+                        entry_count_opt: Some(17),
+                    }
+                ]
+            }
+        )
+    }
+
+    #[test]
+    fn compute_ledger_without_filters_asks_for_everything() {
+        let entries_params_arc = Arc::new(Mutex::new(vec![]));
+        let ledger_dao = LedgerDaoMock::new()
+            .entries_params(&entries_params_arc)
+            .entries_result(vec![]);
+        let subject = AccountantBuilder::default()
+            .ledger_daos(vec![ForAccountantBody(ledger_dao)])
+            .build();
+        let request = UiLedgerRequest {
+            wallet_opt: None,
+            from_timestamp_s_opt: None,
+            to_timestamp_s_opt: None,
+            max_entries_opt: None,
+        };
+
+        let result = subject.compute_ledger(&request, 4321);
+
+        assert_eq!(result, UiLedgerResponse { entries: vec![] }.tmb(4321));
+        let entries_params = entries_params_arc.lock().unwrap();
+        assert_eq!(*entries_params, vec![LedgerQuery::default()]);
+    }
+
+    #[test]
+    fn compute_ledger_rejects_requests_it_cannot_answer() {
+        let subject = AccountantBuilder::default().build();
+        let request = |wallet_opt: Option<&str>, from_opt: Option<u64>, to_opt: Option<u64>| {
+            UiLedgerRequest {
+                wallet_opt: wallet_opt.map(|wallet| wallet.to_string()),
+                from_timestamp_s_opt: from_opt,
+                to_timestamp_s_opt: to_opt,
+                max_entries_opt: None,
+            }
+        };
+        let error_text = |result: MessageBody| {
+            assert_eq!(result.opcode, "ledger");
+            assert_eq!(result.path, MessagePath::Conversation(1234));
+            let (code, text) = result.payload.unwrap_err();
+            assert_eq!(code, INVALID_LEDGER_QUERY);
+            text
+        };
+
+        let bad_wallet = subject.compute_ledger(&request(Some("booga"), None, None), 1234);
+        let backwards_range =
+            subject.compute_ledger(&request(None, Some(2_000), Some(1_000)), 1234);
+        let out_of_range = subject.compute_ledger(&request(None, None, Some(u64::MAX)), 1234);
+
+        assert!(error_text(bad_wallet).starts_with("Invalid wallet in ledger request: "));
+        assert_eq!(
+            error_text(backwards_range),
+            "Ledger request asks for entries from 2000 to 1000, which is no time at all"
+        );
+        assert_eq!(
+            error_text(out_of_range),
+            format!("Timestamp {} in ledger request is out of range", u64::MAX)
+        );
+    }
+
+    #[test]
+    fn simulate_thresholds_request_reports_events_for_synthetic_accounts() {
+        let system = System::new("test");
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(make_bc_with_defaults())
+            .build();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let subject_addr = subject.start();
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let account = |name: &str, age_s: u64| UiSimulatedAccount {
+            wallet: make_wallet(name).to_string(),
+            balance_gwei: 1_001,
+            age_s,
+            banned: false,
+        };
+        let ui_message = NodeFromUiMessage {
+            client_id: 1234,
+            body: UiSimulateThresholdsRequest {
+                payment_thresholds: "1000|100|50|200|800|200".to_string(),
+                window_s: 100,
+                step_s: 10,
+                payables_opt: Some(vec![account("creditor", 50)]),
+                receivables_opt: Some(vec![account("debtor", 100)]),
+            }
+            .tmb(2222),
+        };
+
+        subject_addr.try_send(ui_message).unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let response = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
+        assert_eq!(response.target, ClientId(1234));
+        let (body, context_id) = UiSimulateThresholdsResponse::fmb(response.body.clone()).unwrap();
+        assert_eq!(context_id, 2222);
+        assert_eq!(
+            body,
+            UiSimulateThresholdsResponse {
+                events: vec![
+                    UiThresholdEvent {
+                        offset_s: 60,
+                        wallet: make_wallet("creditor").to_string(),
+                        event: UiThresholdEventType::Paid,
+                        balance_gwei: 1_001,
+                        age_s: 110,
+                    },
+                    UiThresholdEvent {
+                        offset_s: 60,
+                        wallet: make_wallet("debtor").to_string(),
+                        event: UiThresholdEventType::Banned,
+                        balance_gwei: 1_001,
+                        age_s: 160,
+                    }
+                ]
+            }
+        )
+    }
+
+    #[test]
+    fn compute_threshold_simulation_uses_the_books_when_no_accounts_are_given() {
+        let receivable_custom_query_params_arc = Arc::new(Mutex::new(vec![]));
+        let now = SystemTime::now();
+        let payable_dao = PayableDaoMock::new().non_pending_payables_result(vec![PayableAccount {
+            wallet: make_wallet("creditor"),
+            balance_wei: gwei_to_wei(5_000_u64),
+            last_paid_timestamp: now - Duration::from_secs(20_000),
+            pending_payable_opt: None,
+        }]);
+        let receivable_dao = ReceivableDaoMock::new()
+            .custom_query_params(&receivable_custom_query_params_arc)
+            .custom_query_result(Some(vec![ReceivableAccount {
+                wallet: make_wallet("debtor"),
+                balance_wei: gwei_to_wei(100_u64),
+                last_received_timestamp: now - Duration::from_secs(20_000),
+            }]));
+        let banned_dao = BannedDaoMock::new().ban_list_result(vec![BannedAccount {
+            wallet: make_wallet("debtor"),
+            reason_opt: None,
+            timestamp_opt: None,
+            manual: false,
+        }]);
+        let subject = AccountantBuilder::default()
+            .payable_daos(vec![ForAccountantBody(payable_dao)])
+            .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .banned_daos(vec![ForAccountantBody(banned_dao)])
+            .build();
+        let request = UiSimulateThresholdsRequest {
+            payment_thresholds: "1000|100|50|200|800|200".to_string(),
+            window_s: 0,
+            step_s: 1,
+            payables_opt: None,
+            receivables_opt: None,
+        };
+
+        let result = subject.compute_threshold_simulation(&request, 4321);
+
+        let (body, context_id) = UiSimulateThresholdsResponse::fmb(result).unwrap();
+        assert_eq!(context_id, 4321);
+        let events = body
+            .events
+            .into_iter()
+            .map(|event| (event.wallet, event.event))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            vec![
+                (
+                    make_wallet("creditor").to_string(),
+                    UiThresholdEventType::Paid
+                ),
+                (
+                    make_wallet("debtor").to_string(),
+                    UiThresholdEventType::Unbanned
+                )
+            ]
+        );
+        let receivable_custom_query_params = receivable_custom_query_params_arc.lock().unwrap();
+        assert_eq!(
+            *receivable_custom_query_params,
+            vec![CustomQuery::Page {
+                offset: 0,
+                count: u16::MAX
+            }]
+        )
+    }
+
+    #[test]
+    fn compute_threshold_simulation_rejects_requests_it_cannot_answer() {
+        let subject = AccountantBuilder::default().build();
+        let request =
+            |payment_thresholds: &str, wallet: &str, step_s: u64| UiSimulateThresholdsRequest {
+                payment_thresholds: payment_thresholds.to_string(),
+                window_s: 100,
+                step_s,
+                payables_opt: None,
+                receivables_opt: Some(vec![UiSimulatedAccount {
+                    wallet: wallet.to_string(),
+                    balance_gwei: 1,
+                    age_s: 0,
+                    banned: false,
+                }]),
+            };
+        let error_text = |result: MessageBody| {
+            assert_eq!(result.opcode, "simulateThresholds");
+            assert_eq!(result.path, MessagePath::Conversation(1234));
+            let (code, text) = result.payload.unwrap_err();
+            assert_eq!(code, INVALID_THRESHOLDS_SIMULATION);
+            text
+        };
+        let wallet = make_wallet("debtor").to_string();
+
+        let bad_thresholds =
+            subject.compute_threshold_simulation(&request("1000|booga", &wallet, 10), 1234);
+        let bad_wallet = subject
+            .compute_threshold_simulation(&request("1000|100|50|200|800|200", "booga", 10), 1234);
+        let bad_step = subject
+            .compute_threshold_simulation(&request("1000|100|50|200|800|200", &wallet, 0), 1234);
+
+        assert!(error_text(bad_thresholds).starts_with("Invalid payment thresholds: "));
+        assert!(error_text(bad_wallet).starts_with("Invalid wallet 'booga': "));
+        assert_eq!(
+            error_text(bad_step),
+            "Simulation step must be at least 1 s".to_string()
+        );
+    }
+
+    #[test]
+    fn maintain_ledger_rolls_up_and_trims_by_configured_days() {
+        let roll_up_params_arc = Arc::new(Mutex::new(vec![]));
+        let delete_older_than_params_arc = Arc::new(Mutex::new(vec![]));
+        let ledger_dao = LedgerDaoMock::new()
+            .roll_up_params(&roll_up_params_arc)
+            .delete_older_than_params(&delete_older_than_params_arc);
+        let mut config = make_bc_with_defaults();
+        config.ledger_rollup_days = 7;
+        config.ledger_retention_days_opt = Some(365);
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(config)
+            .ledger_daos(vec![ForAccountantBody(ledger_dao)])
+            .build();
+        let now = SystemTime::now();
+
+        subject.maintain_ledger(now);
+
+        let roll_up_params = roll_up_params_arc.lock().unwrap();
+        assert_eq!(*roll_up_params, vec![now - Duration::from_secs(7 * 86_400)]);
+        let delete_older_than_params = delete_older_than_params_arc.lock().unwrap();
+        assert_eq!(
+            *delete_older_than_params,
+            vec![now - Duration::from_secs(365 * 86_400)]
+        );
+    }
+
+    #[test]
+    fn maintain_ledger_keeps_everything_without_retention_days() {
+        let roll_up_params_arc = Arc::new(Mutex::new(vec![]));
+        let delete_older_than_params_arc = Arc::new(Mutex::new(vec![]));
+        let ledger_dao = LedgerDaoMock::new()
+            .roll_up_params(&roll_up_params_arc)
+            .delete_older_than_params(&delete_older_than_params_arc);
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(make_bc_with_defaults())
+            .ledger_daos(vec![ForAccountantBody(ledger_dao)])
+            .build();
+        let now = SystemTime::now();
+
+        subject.maintain_ledger(now);
+
+        let roll_up_params = roll_up_params_arc.lock().unwrap();
+        assert_eq!(
+            *roll_up_params,
+            vec![now - Duration::from_secs(DEFAULT_LEDGER_ROLLUP_DAYS * 86_400)]
+        );
+        assert!(delete_older_than_params_arc.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn maintain_ledger_trims_service_receipts_along_with_the_ledger() {
+        let delete_older_than_params_arc = Arc::new(Mutex::new(vec![]));
+        let service_receipt_dao =
+            ServiceReceiptDaoMock::new().delete_older_than_params(&delete_older_than_params_arc);
+        let mut config = make_bc_with_defaults();
+        config.ledger_retention_days_opt = Some(90);
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(config)
+            .service_receipt_dao(service_receipt_dao)
+            .build();
+        let now = SystemTime::now();
+
+        subject.maintain_ledger(now);
+
+        let delete_older_than_params = delete_older_than_params_arc.lock().unwrap();
+        assert_eq!(
+            *delete_older_than_params,
+            vec![now - Duration::from_secs(90 * 86_400)]
+        );
+    }
+
+    #[test]
+    fn maintain_ledger_trims_service_receipts_by_default_without_retention_days() {
+        let delete_older_than_params_arc = Arc::new(Mutex::new(vec![]));
+        let service_receipt_dao =
+            ServiceReceiptDaoMock::new().delete_older_than_params(&delete_older_than_params_arc);
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(make_bc_with_defaults())
+            .service_receipt_dao(service_receipt_dao)
+            .build();
+        let now = SystemTime::now();
+
+        subject.maintain_ledger(now);
+
+        let delete_older_than_params = delete_older_than_params_arc.lock().unwrap();
+        assert_eq!(
+            *delete_older_than_params,
+            vec![now - Duration::from_secs(DEFAULT_SERVICE_RECEIPT_RETENTION_DAYS * 86_400)]
+        );
+    }
+
+    fn make_service_receipt(
+        consuming_wallet: &Wallet,
+        provider_wallet: &Wallet,
+        route_id: u32,
+        first_service_s: u64,
+        amount_wei: u128,
+    ) -> ServiceReceipt_0v1 {
+        ServiceReceipt_0v1 {
+            consumer_key: PublicKey::new(b"consumer"),
+            consuming_wallet: consuming_wallet.clone(),
+            provider_wallet: provider_wallet.clone(),
+            route_id,
+            first_service_s,
+            services: 10,
+            bytes: 10_000,
+            amount_wei,
+            signature: CryptData::new(b"signature"),
+        }
+    }
+
+    fn paid_for(receipt: ServiceReceipt_0v1) -> ExpiredCoresPackage<ServiceReceipt_0v1> {
+        ExpiredCoresPackage::new(
+            SocketAddr::from_str("1.2.3.4:5678").unwrap(),
+            Some(receipt.consuming_wallet.clone()),
+            Route { hops: vec![] },
+            receipt,
+            0,
+        )
+    }
+
+    #[test]
+    fn service_receipt_for_our_earning_wallet_is_stored() {
+        let store_params_arc = Arc::new(Mutex::new(vec![]));
+        let earning_wallet = make_wallet("earning");
+        let receipt =
+            make_service_receipt(&make_wallet("consumer"), &earning_wallet, 4, 1_000, 5_000);
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(bc_from_earning_wallet(earning_wallet))
+            .service_receipt_dao(ServiceReceiptDaoMock::new().store_params(&store_params_arc))
+            .build();
+        let system = System::new("service_receipt_for_our_earning_wallet_is_stored");
+        let subject_addr = subject.start();
+        let before = SystemTime::now();
+
+        subject_addr
+            .try_send(ExpiredCoresPackage::new(
+                SocketAddr::from_str("1.2.3.4:5678").unwrap(),
+                Some(make_wallet("consumer")),
+                Route { hops: vec![] },
+                receipt.clone(),
+                0,
+            ))
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let after = SystemTime::now();
+        let store_params = store_params_arc.lock().unwrap();
+        assert_eq!(store_params.len(), 1);
+        assert_eq!(store_params[0].0, receipt);
+        assert!(before <= store_params[0].1 && store_params[0].1 <= after);
+    }
+
+    #[test]
+    fn service_receipt_for_someone_elses_wallet_is_ignored() {
+        init_test_logging();
+        let store_params_arc = Arc::new(Mutex::new(vec![]));
+        let earning_wallet = make_wallet("earning");
+        let receipt = make_service_receipt(
+            &make_wallet("consumer"),
+            &make_wallet("stranger"),
+            4,
+            1_000,
+            5_000,
+        );
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(bc_from_earning_wallet(earning_wallet.clone()))
+            .service_receipt_dao(ServiceReceiptDaoMock::new().store_params(&store_params_arc))
+            .build();
+        subject.logger = Logger::new("service_receipt_for_someone_elses_wallet_is_ignored");
+
+        subject.handle_service_receipt(paid_for(receipt), SystemTime::now());
+
+        assert!(store_params_arc.lock().unwrap().is_empty());
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: service_receipt_for_someone_elses_wallet_is_ignored: Ignoring service receipt \
+            for route 4 from {}: it acknowledges services to {}, not to {}",
+            make_wallet("consumer"),
+            make_wallet("stranger"),
+            earning_wallet
+        ));
+    }
+
+    #[test]
+    fn service_receipt_that_did_not_come_from_its_consuming_wallet_is_ignored() {
+        init_test_logging();
+        let store_params_arc = Arc::new(Mutex::new(vec![]));
+        let earning_wallet = make_wallet("earning");
+        let consumer = make_wallet("consumer");
+        let receipt = make_service_receipt(&consumer, &earning_wallet, 4, 1_000, 5_000);
+        let mut from_someone_else = paid_for(receipt.clone());
+        from_someone_else.paying_wallet = Some(make_wallet("someone else"));
+        let mut from_no_one = paid_for(receipt);
+        from_no_one.paying_wallet = None;
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(bc_from_earning_wallet(earning_wallet))
+            .service_receipt_dao(ServiceReceiptDaoMock::new().store_params(&store_params_arc))
+            .build();
+        subject.logger =
+            Logger::new("service_receipt_that_did_not_come_from_its_consuming_wallet_is_ignored");
+
+        subject.handle_service_receipt(from_someone_else, SystemTime::now());
+        subject.handle_service_receipt(from_no_one, SystemTime::now());
+
+        assert!(store_params_arc.lock().unwrap().is_empty());
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: service_receipt_that_did_not_come_from_its_consuming_wallet_is_ignored: \
+            Ignoring service receipt for route 4 from {}: it didn't come from that wallet",
+            consumer
+        ));
+    }
+
+    #[test]
+    fn service_receipt_with_figures_out_of_range_is_ignored() {
+        init_test_logging();
+        let store_params_arc = Arc::new(Mutex::new(vec![]));
+        let earning_wallet = make_wallet("earning");
+        let consumer = make_wallet("consumer");
+        let huge_amount =
+            make_service_receipt(&consumer, &earning_wallet, 4, 1_000, i128::MAX as u128 + 1);
+        let mut huge_services = make_service_receipt(&consumer, &earning_wallet, 5, 1_000, 5_000);
+        huge_services.services = i64::MAX as u64 + 1;
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(bc_from_earning_wallet(earning_wallet))
+            .service_receipt_dao(ServiceReceiptDaoMock::new().store_params(&store_params_arc))
+            .build();
+        subject.logger = Logger::new("service_receipt_with_figures_out_of_range_is_ignored");
+
+        subject.handle_service_receipt(paid_for(huge_amount), SystemTime::now());
+        subject.handle_service_receipt(paid_for(huge_services), SystemTime::now());
+
+        assert!(store_params_arc.lock().unwrap().is_empty());
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(&format!(
+            "WARN: service_receipt_with_figures_out_of_range_is_ignored: Ignoring service receipt \
+            for route 4 from {}: amount {} wei is out of range",
+            consumer,
+            i128::MAX as u128 + 1
+        ));
+        tlh.exists_log_containing(&format!(
+            "WARN: service_receipt_with_figures_out_of_range_is_ignored: Ignoring service receipt \
+            for route 5 from {}: its figures are out of range",
+            consumer
+        ));
+    }
+
+    #[test]
+    fn reconciliation_saturates_rather_than_overflowing() {
+        let earning_wallet = make_wallet("earning");
+        let consumer = make_wallet("consumer");
+        let stored = |route_id: u32| StoredServiceReceipt {
+            receipt: make_service_receipt(
+                &consumer,
+                &earning_wallet,
+                route_id,
+                1_700_000_000,
+                i128::MAX as u128,
+            ),
+            received: from_time_t(1_700_100_000),
+        };
+        let service_receipt_dao =
+            ServiceReceiptDaoMock::new().receipts_result(vec![stored(1), stored(2), stored(3)]);
+        let ledger_dao = LedgerDaoMock::new().entries_result(vec![LedgerEntry::service(
+            from_time_t(1_700_050_000),
+            consumer.clone(),
+            LedgerEntryType::RoutingProvided,
+            100,
+            1_000,
+        )]);
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(bc_from_earning_wallet(earning_wallet.clone()))
+            .ledger_daos(vec![ForAccountantBody(ledger_dao)])
+            .service_receipt_dao(service_receipt_dao)
+            .build();
+
+        let result = subject.compute_reconciliation();
+
+        assert_eq!(
+            result,
+            vec![UiReconciliationAccount {
+                wallet: consumer.to_string(),
+                since_s: 1_700_000_000,
+                receipt_count: 3,
+                billed_wei: 1_000,
+                acknowledged_wei: u128::MAX,
+                discrepancy_wei: 1_000 - i128::MAX,
+            }]
+        );
+    }
+
+    #[test]
+    fn reconciliation_compares_billed_services_with_acknowledged_ones_per_wallet() {
+        let entries_params_arc = Arc::new(Mutex::new(vec![]));
+        let earning_wallet = make_wallet("earning");
+        let honest = make_wallet("honest");
+        let stingy = make_wallet("stingy");
+        let stored = |receipt: ServiceReceipt_0v1| StoredServiceReceipt {
+            receipt,
+            received: from_time_t(1_700_100_000),
+        };
+        let service_receipt_dao = ServiceReceiptDaoMock::new().receipts_result(vec![
+            stored(make_service_receipt(
+                &stingy,
+                &earning_wallet,
+                1,
+                1_700_000_000,
+                u64::MAX as u128,
+            )),
+            stored(make_service_receipt(
+                &honest,
+                &earning_wallet,
+                2,
+                1_700_000_100,
+                3_000,
+            )),
+            stored(make_service_receipt(
+                &stingy,
+                &earning_wallet,
+                3,
+                1_699_999_000,
+                1_000,
+            )),
+        ]);
+        let service = |wallet: &Wallet, entry_type, amount_wei| {
+            LedgerEntry::service(
+                from_time_t(1_700_050_000),
+                wallet.clone(),
+                entry_type,
+                100,
+                amount_wei,
+            )
+        };
+        let ledger_dao = LedgerDaoMock::new()
+            .entries_params(&entries_params_arc)
+            .entries_result(vec![
+                service(&honest, LedgerEntryType::RoutingProvided, 1_000),
+                service(&honest, LedgerEntryType::ExitProvided, 2_000),
+                service(&honest, LedgerEntryType::RoutingConsumed, 7_000),
+            ])
+            .entries_result(vec![
+                service(&stingy, LedgerEntryType::ExitProvided, u64::MAX as u128),
+                service(&stingy, LedgerEntryType::RoutingProvided, 5_000),
+                LedgerEntry::payment(
+                    from_time_t(1_700_060_000),
+                    stingy.clone(),
+                    LedgerEntryType::PaymentReceived,
+                    1_000_000,
+                    make_tx_hash(789),
+                ),
+            ]);
+        let system = System::new("test");
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(bc_from_earning_wallet(earning_wallet))
+            .ledger_daos(vec![ForAccountantBody(ledger_dao)])
+            .service_receipt_dao(service_receipt_dao)
+            .build();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let subject_addr = subject.start();
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let ui_message = NodeFromUiMessage {
+            client_id: 1234,
+            body: UiReconciliationRequest {}.tmb(3333),
+        };
+
+        subject_addr.try_send(ui_message).unwrap();
+
+        System::current().stop();
+        system.run();
+        let (first, second) = if honest.to_string() < stingy.to_string() {
+            (honest.clone(), stingy.clone())
+        } else {
+            (stingy.clone(), honest.clone())
+        };
+        let since = |wallet: &Wallet| {
+            if wallet == &honest {
+                1_700_000_100
+            } else {
+                1_699_999_000
+            }
+        };
+        let entries_params = entries_params_arc.lock().unwrap();
+        assert_eq!(
+            *entries_params,
+            vec![first.clone(), second.clone()]
+                .into_iter()
+                .map(|wallet| LedgerQuery {
+                    from_opt: Some(from_time_t(since(&wallet))),
+                    wallet_opt: Some(wallet),
+                    to_opt: None,
+                    max_entries_opt: None,
+                })
+                .collect::<Vec<_>>()
+        );
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let response = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
+        assert_eq!(response.target, ClientId(1234));
+        let (body, context_id) = UiReconciliationResponse::fmb(response.body.clone()).unwrap();
+        assert_eq!(context_id, 3333);
+        let honest_account = UiReconciliationAccount {
+            wallet: honest.to_string(),
+            since_s: 1_700_000_100,
+            receipt_count: 1,
+            billed_wei: 3_000,
+            acknowledged_wei: 3_000,
+            discrepancy_wei: 0,
+        };
+        let stingy_account = UiReconciliationAccount {
+            wallet: stingy.to_string(),
+            since_s: 1_699_999_000,
+            receipt_count: 2,
+            billed_wei: u64::MAX as u128 + 5_000,
+            acknowledged_wei: u64::MAX as u128 + 1_000,
+            discrepancy_wei: 4_000,
+        };
+        let expected_accounts = if first == honest {
+            vec![honest_account, stingy_account]
+        } else {
+            vec![stingy_account, honest_account]
+        };
+        assert_eq!(
+            body,
+            UiReconciliationResponse {
+                accounts: expected_accounts
+            }
+        );
+    }
+
+    #[test]
+    fn compute_financials_processes_defaulted_request() {
+        let payable_dao = PayableDaoMock::new().total_result(u64::MAX as u128 + 123456);
+        let receivable_dao = ReceivableDaoMock::new().total_result((i64::MAX as i128) * 3);
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(bc_from_earning_wallet(make_wallet("some_wallet_address")))
+            .payable_daos(vec![ForAccountantBody(payable_dao)])
+            .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .build();
+        subject
+            .financial_statistics
+            .borrow_mut()
+            .total_paid_payable_wei = 172_345_602_235_454_454;
+        subject
+            .financial_statistics
+            .borrow_mut()
+            .total_paid_receivable_wei = 4_455_656_989_415_777_555;
+        let context_id = 1234;
+        let request = UiFinancialsRequest {
+            stats_required: true,
+            top_records_opt: None,
+            custom_queries_opt: None,
+            page_opt: None,
+        };
+
+        let result = subject.compute_financials(&request, context_id);
+
+        assert_eq!(
+            result,
+            UiFinancialsResponse {
+                stats_opt: Some(UiFinancialStatistics {
+                    total_unpaid_and_pending_payable_gwei: 18446744073,
+                    total_paid_payable_gwei: 172345602,
+                    total_unpaid_receivable_gwei: 27670116110,
+                    total_paid_receivable_gwei: 4455656989,
+                    runway_opt: None,
+                }),
+                query_results_opt: None
+            }
+            .tmb(context_id)
+        )
+    }
+
+    macro_rules! extract_ages_from_accounts {
+        ($main_structure: expr, $account_specific_field_opt: ident) => {{
+            let accounts_collection = &$main_structure
+                .query_results_opt
+                .as_ref()
+                .unwrap()
+                .$account_specific_field_opt
+                .as_ref()
+                .unwrap();
+            accounts_collection
+                .iter()
+                .map(|account| account.age_s)
+                .collect::<Vec<u64>>()
+        }};
+    }
+
+    #[test]
+    fn compute_financials_processes_request_with_top_records_only_and_balance_ordering() {
+        //take that the tested logic doesn't contain anything about an actual process of ordering,
+        //that part is in the responsibility of the database manager, answering the specific SQL query
+        let payable_custom_query_params_arc = Arc::new(Mutex::new(vec![]));
+        let receivable_custom_query_params_arc = Arc::new(Mutex::new(vec![]));
+        let payable_accounts_retrieved = vec![PayableAccount {
+            wallet: make_wallet("abcd123"),
+            balance_wei: 58_568_686_005,
+            last_paid_timestamp: SystemTime::now().sub(Duration::from_secs(5000)),
+            pending_payable_opt: None,
+        }];
+        let payable_dao = PayableDaoMock::new()
+            .custom_query_params(&payable_custom_query_params_arc)
+            .custom_query_result(Some(payable_accounts_retrieved));
+        let receivable_accounts_retrieved = vec![ReceivableAccount {
+            wallet: make_wallet("efe4848"),
+            balance_wei: 3_788_455_600_556_898,
+            last_received_timestamp: SystemTime::now().sub(Duration::from_secs(6500)),
+        }];
+        let receivable_dao = ReceivableDaoMock::new()
+            .custom_query_params(&receivable_custom_query_params_arc)
+            .custom_query_result(Some(receivable_accounts_retrieved));
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(bc_from_earning_wallet(make_wallet("some_wallet_address")))
+            .payable_daos(vec![ForAccountantBody(payable_dao)])
+            .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .build();
+        let context_id_expected = 1234;
+        let request = UiFinancialsRequest {
+            stats_required: false,
+            top_records_opt: Some(TopRecordsConfig {
                 count: 6,
                 ordered_by: Balance,
             }),
             custom_queries_opt: None,
+            page_opt: None,
         };
         let before = SystemTime::now();
 
@@ -4177,12 +6379,14 @@ mod tests {
                         wallet: make_wallet("abcd123").to_string(),
                         age_s: extracted_payable_ages[0],
                         balance_gwei: 58,
-                        pending_payable_hash_opt: None
+                        pending_payable_hash_opt: None,
+                        balance_wei_opt: None,
                     },]),
                     receivable_opt: Some(vec![UiReceivableAccount {
                         wallet: make_wallet("efe4848").to_string(),
                         age_s: extracted_receivable_ages[0],
-                        balance_gwei: 3_788_455
+                        balance_gwei: 3_788_455,
+                        balance_wei_opt: None,
                     },])
                 }),
             }
@@ -4238,6 +6442,7 @@ mod tests {
                 ordered_by: Age,
             }),
             custom_queries_opt: None,
+            page_opt: None,
         };
 
         let result = subject.compute_financials(&request, context_id_expected);
@@ -4272,6 +6477,102 @@ mod tests {
         )
     }
 
+    #[test]
+    fn compute_financials_processes_request_for_a_page_of_records() {
+        let payable_custom_query_params_arc = Arc::new(Mutex::new(vec![]));
+        let receivable_custom_query_params_arc = Arc::new(Mutex::new(vec![]));
+        let now = SystemTime::now();
+        let payable_dao = PayableDaoMock::new()
+            .custom_query_params(&payable_custom_query_params_arc)
+            .custom_query_result(Some(vec![PayableAccount {
+                wallet: make_wallet("abcd123"),
+                balance_wei: 58_568_686_005,
+                last_paid_timestamp: now.checked_sub(Duration::from_secs(5_000)).unwrap(),
+                pending_payable_opt: None,
+            }]));
+        let receivable_dao = ReceivableDaoMock::new()
+            .custom_query_params(&receivable_custom_query_params_arc)
+            .custom_query_result(None);
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(bc_from_earning_wallet(make_wallet("some_wallet_address")))
+            .payable_daos(vec![ForAccountantBody(payable_dao)])
+            .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .build();
+        let request = UiFinancialsRequest {
+            stats_required: false,
+            top_records_opt: None,
+            custom_queries_opt: None,
+            page_opt: Some(FinancialsPage {
+                offset: 500,
+                count: 250,
+            }),
+        };
+
+        let result = subject.compute_financials(&request, 1234);
+
+        let (response, context_id) = UiFinancialsResponse::fmb(result).unwrap();
+        assert_eq!(context_id, 1234);
+        let query_results = response.query_results_opt.unwrap();
+        assert_eq!(response.stats_opt, None);
+        let payable = query_results.payable_opt.unwrap();
+        assert_eq!(payable.len(), 1);
+        assert_eq!(payable[0].wallet, make_wallet("abcd123").to_string());
+        assert_eq!(payable[0].balance_gwei, 58);
+        assert!(payable[0].age_s >= 5_000);
+        assert_eq!(query_results.receivable_opt, Some(vec![]));
+        let payable_custom_query_params = payable_custom_query_params_arc.lock().unwrap();
+        assert_eq!(
+            *payable_custom_query_params,
+            vec![CustomQuery::Page {
+                offset: 500,
+                count: 250
+            }]
+        );
+        let receivable_custom_query_params = receivable_custom_query_params_arc.lock().unwrap();
+        assert_eq!(
+            *receivable_custom_query_params,
+            vec![CustomQuery::Page {
+                offset: 500,
+                count: 250
+            }]
+        )
+    }
+
+    #[test]
+    fn financials_request_does_not_mix_a_page_with_other_views_into_books() {
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(bc_from_earning_wallet(make_wallet("some_wallet_address")))
+            .build();
+        let request = UiFinancialsRequest {
+            stats_required: true,
+            top_records_opt: Some(TopRecordsConfig {
+                count: 13,
+                ordered_by: Age,
+            }),
+            custom_queries_opt: None,
+            page_opt: Some(FinancialsPage {
+                offset: 0,
+                count: 100,
+            }),
+        };
+
+        let result = subject.compute_financials(&request, 4567);
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "financials".to_string(),
+                path: Conversation(4567),
+                payload: Err((
+                    REQUEST_WITH_MUTUALLY_EXCLUSIVE_PARAMS,
+                    "Requesting a page of all records together with top records or the more \
+                    customized subset of records is not allowed"
+                        .to_string()
+                ))
+            }
+        );
+    }
+
     #[test]
     fn compute_financials_processes_request_with_range_queries_only() {
         let payable_custom_query_params_arc = Arc::new(Mutex::new(vec![]));
@@ -4323,6 +6624,7 @@ mod tests {
                     max_amount_gwei: 60_000_000,
                 }),
             }),
+            page_opt: None,
         };
         let before = SystemTime::now();
 
@@ -4343,18 +6645,21 @@ mod tests {
                         wallet: make_wallet("abcd123").to_string(),
                         age_s: extracted_payable_ages[0],
                         balance_gwei: 5,
-                        pending_payable_hash_opt: None
+                        pending_payable_hash_opt: None,
+                        balance_wei_opt: None,
                     },]),
                     receivable_opt: Some(vec![
                         UiReceivableAccount {
                             wallet: make_wallet("efe4848").to_string(),
                             age_s: extracted_receivable_ages[0],
-                            balance_gwei: 20_456_056
+                            balance_gwei: 20_456_056,
+                            balance_wei_opt: None,
                         },
                         UiReceivableAccount {
                             wallet: make_wallet("bb123aa").to_string(),
                             age_s: extracted_receivable_ages[1],
                             balance_gwei: 550,
+                            balance_wei_opt: None,
                         }
                     ])
                 })
@@ -4451,6 +6756,7 @@ mod tests {
                     max_amount_gwei: 150000000000,
                 }),
             }),
+            page_opt: None,
         };
 
         let result = subject.compute_financials(&request, context_id_expected);
@@ -4496,6 +6802,7 @@ mod tests {
                 }),
                 receivable_opt: None,
             }),
+            page_opt: None,
         };
 
         assert_compute_financials_tests_range_query_on_too_big_values_in_input(
@@ -4519,6 +6826,7 @@ mod tests {
                     max_amount_gwei: 6666,
                 }),
             }),
+            page_opt: None,
         };
 
         assert_compute_financials_tests_range_query_on_too_big_values_in_input(
@@ -4560,6 +6868,7 @@ mod tests {
                 }),
                 receivable_opt: None,
             }),
+            page_opt: None,
         };
 
         subject.compute_financials(&request, context_id_expected);
@@ -4596,6 +6905,7 @@ mod tests {
                     max_amount_gwei: 150000000000,
                 }),
             }),
+            page_opt: None,
         };
 
         subject.compute_financials(&request, context_id_expected);
@@ -1,15 +1,25 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::BlockchainAgentWithContextMessage;
+use crate::accountant::db_access_objects::payable_dao::PayableAccount;
+use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::PreparedAdjustment;
 use crate::sub_lib::blockchain_bridge::OutboundPaymentsInstructions;
+use itertools::Itertools;
 use masq_lib::logger::Logger;
+use masq_lib::messages::{UiPaymentsAdjustedBroadcast, UiReducedPayment, UiUnpaidPayment};
+use std::fmt::{Display, Formatter};
 use std::time::SystemTime;
+use thousands::Separable;
+use web3::types::U256;
+
+// Every period a debt stays unpaid adds its own size once more to its weight
+pub const AGE_WEIGHT_PERIOD_SEC: u128 = 86_400;
 
 pub trait PaymentAdjuster {
     fn search_for_indispensable_adjustment(
         &self,
-        msg: &BlockchainAgentWithContextMessage,
+        qualified_payables: &[PayableAccount],
+        agent: &dyn BlockchainAgent,
         logger: &Logger,
     ) -> Result<Option<Adjustment>, AnalysisError>;
 
@@ -18,7 +28,7 @@ pub trait PaymentAdjuster {
         setup: PreparedAdjustment,
         now: SystemTime,
         logger: &Logger,
-    ) -> OutboundPaymentsInstructions;
+    ) -> (OutboundPaymentsInstructions, UiPaymentsAdjustedBroadcast);
 
     as_any_ref_in_trait!();
 }
@@ -28,19 +38,75 @@ pub struct PaymentAdjusterReal {}
 impl PaymentAdjuster for PaymentAdjusterReal {
     fn search_for_indispensable_adjustment(
         &self,
-        _msg: &BlockchainAgentWithContextMessage,
-        _logger: &Logger,
+        qualified_payables: &[PayableAccount],
+        agent: &dyn BlockchainAgent,
+        logger: &Logger,
     ) -> Result<Option<Adjustment>, AnalysisError> {
-        Ok(None)
+        let cw_balances = agent.consuming_wallet_balances();
+
+        let limiting_count_opt = Self::determine_transaction_count_limit_by_transaction_fee(
+            agent,
+            qualified_payables.len(),
+            saturate_u256(cw_balances.transaction_fee_balance_in_minor_units),
+            logger,
+        )?;
+        let is_masq_token_short = Self::check_masq_token_sufficiency(
+            qualified_payables,
+            saturate_u256(cw_balances.masq_token_balance_in_minor_units),
+            logger,
+        )?;
+
+        Ok(match (limiting_count_opt, is_masq_token_short) {
+            (Some(limiting_count), true) => Some(Adjustment::Both { limiting_count }),
+            (Some(limiting_count), false) => {
+                Some(Adjustment::TransactionFeeCurrency { limiting_count })
+            }
+            (None, true) => Some(Adjustment::MasqToken),
+            (None, false) => None,
+        })
     }
 
     fn adjust_payments(
         &self,
-        _setup: PreparedAdjustment,
-        _now: SystemTime,
-        _logger: &Logger,
-    ) -> OutboundPaymentsInstructions {
-        todo!("this function is dead until the card GH-711 is played")
+        setup: PreparedAdjustment,
+        now: SystemTime,
+        logger: &Logger,
+    ) -> (OutboundPaymentsInstructions, UiPaymentsAdjustedBroadcast) {
+        let msg = setup.original_setup_msg;
+        let qualified_payables: Vec<PayableAccount> =
+            msg.protected_qualified_payables.expose_vector();
+        let cw_masq_token_balance = saturate_u256(
+            msg.agent
+                .consuming_wallet_balances()
+                .masq_token_balance_in_minor_units,
+        );
+        let weighted_payables = Self::weigh_payables(qualified_payables, now);
+
+        let (survivors, mut eliminated) = match setup.adjustment {
+            Adjustment::MasqToken => (weighted_payables, vec![]),
+            Adjustment::TransactionFeeCurrency { limiting_count }
+            | Adjustment::Both { limiting_count } => {
+                Self::cut_down_by_count(weighted_payables, limiting_count)
+            }
+        };
+        let (adjusted, eliminated_for_masq) =
+            Self::prorate_by_masq_token_balance(survivors, cw_masq_token_balance);
+        eliminated.extend(eliminated_for_masq);
+
+        let summary = Self::summarize_adjustment(&adjusted, &eliminated, logger);
+        let affordable_accounts = adjusted
+            .into_iter()
+            .map(|adjusted| PayableAccount {
+                balance_wei: adjusted.adjusted_balance_wei,
+                ..adjusted.original.account
+            })
+            .collect();
+        let instructions = OutboundPaymentsInstructions::new(
+            affordable_accounts,
+            msg.agent,
+            msg.response_skeleton_opt,
+        );
+        (instructions, summary)
     }
 
     as_any_ref_in_trait_impl!();
@@ -50,6 +116,266 @@ impl PaymentAdjusterReal {
     pub fn new() -> Self {
         Self {}
     }
+
+    fn determine_transaction_count_limit_by_transaction_fee(
+        agent: &dyn BlockchainAgent,
+        number_of_accounts: usize,
+        cw_transaction_fee_balance_minor: u128,
+        logger: &Logger,
+    ) -> Result<Option<u16>, AnalysisError> {
+        if number_of_accounts == 0 {
+            return Ok(None);
+        }
+        let required_fee_total = agent.estimated_transaction_fee_total(number_of_accounts);
+        if cw_transaction_fee_balance_minor >= required_fee_total {
+            return Ok(None);
+        }
+        // Fees grow linearly with the number of transactions
+        let per_transaction_requirement_minor = required_fee_total / number_of_accounts as u128;
+        let affordable_count = cw_transaction_fee_balance_minor / per_transaction_requirement_minor;
+        if affordable_count == 0 {
+            return Err(AnalysisError::NotEnoughTransactionFeeBalanceForSingleTx {
+                number_of_accounts,
+                per_transaction_requirement_minor,
+                cw_transaction_fee_balance_minor,
+            });
+        }
+        warning!(
+            logger,
+            "Transaction fee balance of {} wei cannot cover the anticipated fees of {} wei for {} \
+            transactions; only {} of them can be paid",
+            cw_transaction_fee_balance_minor.separate_with_commas(),
+            required_fee_total.separate_with_commas(),
+            number_of_accounts,
+            affordable_count
+        );
+        Ok(Some(u16::try_from(affordable_count).unwrap_or(u16::MAX)))
+    }
+
+    fn check_masq_token_sufficiency(
+        qualified_payables: &[PayableAccount],
+        cw_masq_token_balance_minor: u128,
+        logger: &Logger,
+    ) -> Result<bool, AnalysisError> {
+        let total_amount_demanded_minor = sum_balances(qualified_payables.iter());
+        if cw_masq_token_balance_minor >= total_amount_demanded_minor {
+            return Ok(false);
+        }
+        let smallest_acceptable_payment = qualified_payables
+            .iter()
+            .map(|payable| disqualification_limit(payable.balance_wei))
+            .min()
+            .unwrap_or(0);
+        if cw_masq_token_balance_minor == 0
+            || cw_masq_token_balance_minor < smallest_acceptable_payment
+        {
+            return Err(AnalysisError::NotEnoughMasqBalanceForAnyAccount {
+                number_of_accounts: qualified_payables.len(),
+                total_amount_demanded_minor,
+                cw_masq_token_balance_minor,
+            });
+        }
+        warning!(
+            logger,
+            "Total of {} wei in MASQ was ordered while the consuming wallet held only {} wei of \
+            MASQ token; the payments will be reduced",
+            total_amount_demanded_minor.separate_with_commas(),
+            cw_masq_token_balance_minor.separate_with_commas()
+        );
+        Ok(true)
+    }
+
+    fn weigh_payables(payables: Vec<PayableAccount>, now: SystemTime) -> Vec<WeightedPayable> {
+        payables
+            .into_iter()
+            .map(|account| {
+                let age_sec = now
+                    .duration_since(account.last_paid_timestamp)
+                    .unwrap_or_default()
+                    .as_secs() as u128;
+                let weight = account.balance_wei.saturating_add(
+                    account.balance_wei.saturating_mul(age_sec) / AGE_WEIGHT_PERIOD_SEC,
+                );
+                WeightedPayable { account, weight }
+            })
+            .sorted_by(|a, b| b.weight.cmp(&a.weight))
+            .collect()
+    }
+
+    fn cut_down_by_count(
+        weighted_payables: Vec<WeightedPayable>,
+        limiting_count: u16,
+    ) -> (Vec<WeightedPayable>, Vec<EliminatedPayable>) {
+        let mut survivors = weighted_payables;
+        let eliminated = if survivors.len() > limiting_count as usize {
+            survivors.split_off(limiting_count as usize)
+        } else {
+            vec![]
+        };
+        (
+            survivors,
+            eliminated
+                .into_iter()
+                .map(|weighted| EliminatedPayable {
+                    account: weighted.account,
+                    reason: EliminationReason::InsufficientTransactionFeeBalance,
+                })
+                .collect(),
+        )
+    }
+
+    // Shares the MASQ balance out in proportion to the weights. Accounts whose share would exceed
+    // their debt are paid in full and the rest is shared out again; if somebody's share is too
+    // small to be worth a transaction, the lightest such account is dropped and we start over.
+    fn prorate_by_masq_token_balance(
+        weighted_payables: Vec<WeightedPayable>,
+        cw_masq_token_balance_minor: u128,
+    ) -> (Vec<AdjustedPayable>, Vec<EliminatedPayable>) {
+        let mut remaining_balance = cw_masq_token_balance_minor;
+        let mut undecided = weighted_payables;
+        let mut adjusted: Vec<AdjustedPayable> = vec![];
+        let mut eliminated: Vec<EliminatedPayable> = vec![];
+        loop {
+            if sum_balances(undecided.iter().map(|weighted| &weighted.account)) <= remaining_balance
+            {
+                adjusted.extend(undecided.into_iter().map(AdjustedPayable::paid_in_full));
+                break;
+            }
+            let shares = Self::propose_shares(&undecided, remaining_balance);
+            let (satisfied, unsatisfied): (Vec<_>, Vec<_>) = undecided
+                .into_iter()
+                .zip(shares.into_iter())
+                .partition(|(weighted, share)| *share >= weighted.account.balance_wei);
+            if !satisfied.is_empty() {
+                satisfied.into_iter().for_each(|(weighted, _)| {
+                    remaining_balance -= weighted.account.balance_wei;
+                    adjusted.push(AdjustedPayable::paid_in_full(weighted))
+                });
+                undecided = unsatisfied
+                    .into_iter()
+                    .map(|(weighted, _)| weighted)
+                    .collect();
+                continue;
+            }
+            let disqualified_idx_opt = if unsatisfied.len() > 1 {
+                unsatisfied.iter().rposition(|(weighted, share)| {
+                    *share < disqualification_limit(weighted.account.balance_wei)
+                })
+            } else {
+                None
+            };
+            match disqualified_idx_opt {
+                Some(idx) => {
+                    let mut unsatisfied = unsatisfied;
+                    let (disqualified, _) = unsatisfied.remove(idx);
+                    eliminated.push(EliminatedPayable {
+                        account: disqualified.account,
+                        reason: EliminationReason::InsufficientMasqBalance,
+                    });
+                    undecided = unsatisfied
+                        .into_iter()
+                        .map(|(weighted, _)| weighted)
+                        .collect();
+                }
+                None => {
+                    unsatisfied.into_iter().for_each(|(weighted, share)| {
+                        if share == 0 {
+                            eliminated.push(EliminatedPayable {
+                                account: weighted.account,
+                                reason: EliminationReason::InsufficientMasqBalance,
+                            })
+                        } else {
+                            adjusted.push(AdjustedPayable {
+                                original: weighted,
+                                adjusted_balance_wei: share,
+                            })
+                        }
+                    });
+                    break;
+                }
+            }
+        }
+        adjusted.sort_by(|a, b| b.original.weight.cmp(&a.original.weight));
+        (adjusted, eliminated)
+    }
+
+    fn propose_shares(weighted_payables: &[WeightedPayable], balance_to_share: u128) -> Vec<u128> {
+        let total_weight = weighted_payables
+            .iter()
+            .fold(U256::zero(), |sum, weighted| {
+                sum + U256::from(weighted.weight)
+            });
+        weighted_payables
+            .iter()
+            .map(|weighted| {
+                if total_weight.is_zero() {
+                    0
+                } else {
+                    (U256::from(balance_to_share) * U256::from(weighted.weight) / total_weight)
+                        .as_u128()
+                }
+            })
+            .collect()
+    }
+
+    fn summarize_adjustment(
+        adjusted: &[AdjustedPayable],
+        eliminated: &[EliminatedPayable],
+        logger: &Logger,
+    ) -> UiPaymentsAdjustedBroadcast {
+        let paid_in_full_count = adjusted
+            .iter()
+            .filter(|adjusted| {
+                adjusted.adjusted_balance_wei == adjusted.original.account.balance_wei
+            })
+            .count();
+        let reduced = adjusted
+            .iter()
+            .filter(|adjusted| {
+                adjusted.adjusted_balance_wei != adjusted.original.account.balance_wei
+            })
+            .map(|adjusted| UiReducedPayment {
+                wallet: adjusted.original.account.wallet.to_string(),
+                original_balance_wei: adjusted.original.account.balance_wei,
+                adjusted_balance_wei: adjusted.adjusted_balance_wei,
+            })
+            .collect::<Vec<_>>();
+        let unpaid = eliminated
+            .iter()
+            .map(|eliminated| UiUnpaidPayment {
+                wallet: eliminated.account.wallet.to_string(),
+                balance_wei: eliminated.account.balance_wei,
+                reason: eliminated.reason.to_string(),
+            })
+            .collect::<Vec<_>>();
+        let reduced_lines = reduced.iter().map(|reduced| {
+            format!(
+                "{} wei owed to {} reduced to {} wei",
+                reduced.original_balance_wei.separate_with_commas(),
+                reduced.wallet,
+                reduced.adjusted_balance_wei.separate_with_commas()
+            )
+        });
+        let unpaid_lines = unpaid.iter().map(|unpaid| {
+            format!(
+                "{} wei owed to {} left unpaid: {}",
+                unpaid.balance_wei.separate_with_commas(),
+                unpaid.wallet,
+                unpaid.reason
+            )
+        });
+        warning!(
+            logger,
+            "Payments adjusted to the consuming wallet balances; {} paid in full:\n{}",
+            paid_in_full_count,
+            reduced_lines.chain(unpaid_lines).join("\n")
+        );
+        UiPaymentsAdjustedBroadcast {
+            paid_in_full_count: paid_in_full_count as u64,
+            reduced,
+            unpaid,
+        }
+    }
 }
 
 impl Default for PaymentAdjusterReal {
@@ -62,44 +388,509 @@ impl Default for PaymentAdjusterReal {
 pub enum Adjustment {
     MasqToken,
     TransactionFeeCurrency { limiting_count: u16 },
-    Both,
+    Both { limiting_count: u16 },
 }
 
 #[derive(Debug, PartialEq, Eq)]
-pub enum AnalysisError {}
+pub enum AnalysisError {
+    NotEnoughTransactionFeeBalanceForSingleTx {
+        number_of_accounts: usize,
+        per_transaction_requirement_minor: u128,
+        cw_transaction_fee_balance_minor: u128,
+    },
+    NotEnoughMasqBalanceForAnyAccount {
+        number_of_accounts: usize,
+        total_amount_demanded_minor: u128,
+        cw_masq_token_balance_minor: u128,
+    },
+}
+
+impl Display for AnalysisError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnalysisError::NotEnoughTransactionFeeBalanceForSingleTx {
+                number_of_accounts,
+                per_transaction_requirement_minor,
+                cw_transaction_fee_balance_minor,
+            } => write!(
+                f,
+                "Transaction fee balance of {} wei is not enough for a single one of {} payments \
+                anticipated to cost {} wei each",
+                cw_transaction_fee_balance_minor.separate_with_commas(),
+                number_of_accounts,
+                per_transaction_requirement_minor.separate_with_commas()
+            ),
+            AnalysisError::NotEnoughMasqBalanceForAnyAccount {
+                number_of_accounts,
+                total_amount_demanded_minor,
+                cw_masq_token_balance_minor,
+            } => write!(
+                f,
+                "MASQ balance of {} wei is too small to pay a reasonable part of any of {} \
+                payables demanding {} wei in total",
+                cw_masq_token_balance_minor.separate_with_commas(),
+                number_of_accounts,
+                total_amount_demanded_minor.separate_with_commas()
+            ),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct WeightedPayable {
+    account: PayableAccount,
+    weight: u128,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct AdjustedPayable {
+    original: WeightedPayable,
+    adjusted_balance_wei: u128,
+}
+
+impl AdjustedPayable {
+    fn paid_in_full(original: WeightedPayable) -> Self {
+        let adjusted_balance_wei = original.account.balance_wei;
+        Self {
+            original,
+            adjusted_balance_wei,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct EliminatedPayable {
+    account: PayableAccount,
+    reason: EliminationReason,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum EliminationReason {
+    InsufficientTransactionFeeBalance,
+    InsufficientMasqBalance,
+}
+
+impl Display for EliminationReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EliminationReason::InsufficientTransactionFeeBalance => {
+                write!(f, "transaction fee balance too low")
+            }
+            EliminationReason::InsufficientMasqBalance => write!(f, "MASQ balance too low"),
+        }
+    }
+}
+
+// Paying off less than half of a debt isn't worth the transaction fee
+fn disqualification_limit(balance_wei: u128) -> u128 {
+    balance_wei / 2
+}
+
+fn sum_balances<'a>(payables: impl Iterator<Item = &'a PayableAccount>) -> u128 {
+    payables.fold(0, |sum, payable| sum.saturating_add(payable.balance_wei))
+}
+
+pub(in crate::accountant) fn saturate_u256(value: U256) -> u128 {
+    if value > U256::from(u128::MAX) {
+        u128::MAX
+    } else {
+        value.as_u128()
+    }
+}
 
 #[cfg(test)]
 mod tests {
-    use crate::accountant::payment_adjuster::{PaymentAdjuster, PaymentAdjusterReal};
+    use crate::accountant::db_access_objects::payable_dao::PayableAccount;
+    use crate::accountant::payment_adjuster::{
+        Adjustment, AnalysisError, PaymentAdjuster, PaymentAdjusterReal, AGE_WEIGHT_PERIOD_SEC,
+    };
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::BlockchainAgentWithContextMessage;
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::test_utils::BlockchainAgentMock;
+    use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::PreparedAdjustment;
     use crate::accountant::scanners::test_utils::protect_payables_in_test;
-    use crate::accountant::test_utils::make_payable_account;
+    use crate::accountant::ResponseSkeleton;
+    use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
+    use crate::test_utils::make_wallet;
+    use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::ArbitraryIdStamp;
     use masq_lib::logger::Logger;
+    use masq_lib::messages::{UiPaymentsAdjustedBroadcast, UiReducedPayment, UiUnpaidPayment};
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use std::time::{Duration, SystemTime};
+    use web3::types::U256;
 
-    #[test]
-    fn search_for_indispensable_adjustment_always_returns_none() {
-        init_test_logging();
-        let test_name = "is_adjustment_required_always_returns_none";
-        let mut payable = make_payable_account(111);
-        payable.balance_wei = 100_000_000;
-        let agent = BlockchainAgentMock::default();
-        let setup_msg = BlockchainAgentWithContextMessage {
-            protected_qualified_payables: protect_payables_in_test(vec![payable]),
+    const DAY: u64 = AGE_WEIGHT_PERIOD_SEC as u64;
+
+    fn make_payable(
+        name: &str,
+        balance_wei: u128,
+        age_sec: u64,
+        now: SystemTime,
+    ) -> PayableAccount {
+        PayableAccount {
+            wallet: make_wallet(name),
+            balance_wei,
+            last_paid_timestamp: now - Duration::from_secs(age_sec),
+            pending_payable_opt: None,
+        }
+    }
+
+    fn make_setup_msg(
+        payables: Vec<PayableAccount>,
+        agent: BlockchainAgentMock,
+    ) -> BlockchainAgentWithContextMessage {
+        BlockchainAgentWithContextMessage {
+            protected_qualified_payables: protect_payables_in_test(payables),
             agent: Box::new(agent),
             response_skeleton_opt: None,
-        };
-        let logger = Logger::new(test_name);
+        }
+    }
+
+    fn make_agent(transaction_fee_balance: u128, masq_balance: u128) -> BlockchainAgentMock {
+        BlockchainAgentMock::default().consuming_wallet_balances_result(
+            ConsumingWalletBalances::new(
+                U256::from(transaction_fee_balance),
+                U256::from(masq_balance),
+            ),
+        )
+    }
+
+    #[test]
+    fn search_for_indispensable_adjustment_returns_none_if_balances_cover_everything() {
+        init_test_logging();
+        let test_name =
+            "search_for_indispensable_adjustment_returns_none_if_balances_cover_everything";
+        let now = SystemTime::now();
+        let payables = vec![
+            make_payable("abc", 4_000_000_000, 0, now),
+            make_payable("def", 6_000_000_000, 0, now),
+        ];
+        let agent = make_agent(200, 10_000_000_000).estimated_transaction_fee_total_result(200);
         let subject = PaymentAdjusterReal::new();
 
-        let result = subject.search_for_indispensable_adjustment(&setup_msg, &logger);
+        let result =
+            subject.search_for_indispensable_adjustment(&payables, &agent, &Logger::new(test_name));
 
         assert_eq!(result, Ok(None));
         TestLogHandler::default().exists_no_log_containing(test_name);
-        // Nobody in this test asked about the wallet balances and the transaction fee
-        // requirement, yet we got through with the final None.
-        // How do we know? The mock agent didn't blow up while missing these
-        // results
+    }
+
+    #[test]
+    fn search_for_indispensable_adjustment_limits_count_by_transaction_fee_balance() {
+        init_test_logging();
+        let test_name =
+            "search_for_indispensable_adjustment_limits_count_by_transaction_fee_balance";
+        let now = SystemTime::now();
+        let payables = vec![
+            make_payable("abc", 1_000, 0, now),
+            make_payable("def", 1_000, 0, now),
+            make_payable("ghi", 1_000, 0, now),
+        ];
+        let agent = make_agent(25_000, 3_000).estimated_transaction_fee_total_result(30_000);
+        let subject = PaymentAdjusterReal::new();
+
+        let result =
+            subject.search_for_indispensable_adjustment(&payables, &agent, &Logger::new(test_name));
+
+        assert_eq!(
+            result,
+            Ok(Some(Adjustment::TransactionFeeCurrency {
+                limiting_count: 2
+            }))
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Transaction fee balance of 25,000 wei cannot cover the anticipated fees of \
+            30,000 wei for 3 transactions; only 2 of them can be paid",
+            test_name
+        ));
+    }
+
+    #[test]
+    fn search_for_indispensable_adjustment_detects_masq_token_shortage() {
+        init_test_logging();
+        let test_name = "search_for_indispensable_adjustment_detects_masq_token_shortage";
+        let now = SystemTime::now();
+        let payables = vec![
+            make_payable("abc", 2_000_000, 0, now),
+            make_payable("def", 4_000_000, 0, now),
+        ];
+        let agent = make_agent(1_000, 4_000_000).estimated_transaction_fee_total_result(1_000);
+        let subject = PaymentAdjusterReal::new();
+
+        let result =
+            subject.search_for_indispensable_adjustment(&payables, &agent, &Logger::new(test_name));
+
+        assert_eq!(result, Ok(Some(Adjustment::MasqToken)));
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Total of 6,000,000 wei in MASQ was ordered while the consuming wallet held \
+            only 4,000,000 wei of MASQ token; the payments will be reduced",
+            test_name
+        ));
+    }
+
+    #[test]
+    fn search_for_indispensable_adjustment_detects_both_shortages() {
+        let now = SystemTime::now();
+        let payables = vec![
+            make_payable("abc", 2_000_000, 0, now),
+            make_payable("def", 4_000_000, 0, now),
+        ];
+        let agent = make_agent(999, 4_000_000).estimated_transaction_fee_total_result(1_000);
+        let subject = PaymentAdjusterReal::new();
+
+        let result =
+            subject.search_for_indispensable_adjustment(&payables, &agent, &Logger::new("test"));
+
+        assert_eq!(result, Ok(Some(Adjustment::Both { limiting_count: 1 })));
+    }
+
+    #[test]
+    fn search_for_indispensable_adjustment_fails_if_not_even_one_transaction_fee_is_affordable() {
+        let now = SystemTime::now();
+        let payables = vec![
+            make_payable("abc", 1_000, 0, now),
+            make_payable("def", 1_000, 0, now),
+        ];
+        let agent = make_agent(499, 2_000).estimated_transaction_fee_total_result(1_000);
+        let subject = PaymentAdjusterReal::new();
+
+        let result =
+            subject.search_for_indispensable_adjustment(&payables, &agent, &Logger::new("test"));
+
+        assert_eq!(
+            result,
+            Err(AnalysisError::NotEnoughTransactionFeeBalanceForSingleTx {
+                number_of_accounts: 2,
+                per_transaction_requirement_minor: 500,
+                cw_transaction_fee_balance_minor: 499,
+            })
+        );
+    }
+
+    #[test]
+    fn search_for_indispensable_adjustment_fails_if_masq_balance_is_too_small_for_anybody() {
+        let now = SystemTime::now();
+        let payables = vec![
+            make_payable("abc", 2_000_000_000, 0, now),
+            make_payable("def", 3_000_000_000, 0, now),
+        ];
+        let agent = make_agent(1_000, 999_999_999).estimated_transaction_fee_total_result(1_000);
+        let subject = PaymentAdjusterReal::new();
+
+        let result =
+            subject.search_for_indispensable_adjustment(&payables, &agent, &Logger::new("test"));
+
+        assert_eq!(
+            result,
+            Err(AnalysisError::NotEnoughMasqBalanceForAnyAccount {
+                number_of_accounts: 2,
+                total_amount_demanded_minor: 5_000_000_000,
+                cw_masq_token_balance_minor: 999_999_999,
+            })
+        );
+    }
+
+    #[test]
+    fn analysis_errors_are_displayed_readably() {
+        assert_eq!(
+            AnalysisError::NotEnoughTransactionFeeBalanceForSingleTx {
+                number_of_accounts: 3,
+                per_transaction_requirement_minor: 12_000,
+                cw_transaction_fee_balance_minor: 11_999,
+            }
+            .to_string(),
+            "Transaction fee balance of 11,999 wei is not enough for a single one of 3 payments \
+            anticipated to cost 12,000 wei each"
+        );
+        assert_eq!(
+            AnalysisError::NotEnoughMasqBalanceForAnyAccount {
+                number_of_accounts: 2,
+                total_amount_demanded_minor: 5_000_000,
+                cw_masq_token_balance_minor: 1_000,
+            }
+            .to_string(),
+            "MASQ balance of 1,000 wei is too small to pay a reasonable part of any of 2 \
+            payables demanding 5,000,000 wei in total"
+        );
+    }
+
+    fn run_adjustment(
+        payables: Vec<PayableAccount>,
+        masq_balance: u128,
+        adjustment: Adjustment,
+        now: SystemTime,
+        test_name: &str,
+    ) -> (Vec<PayableAccount>, UiPaymentsAdjustedBroadcast) {
+        let agent_id_stamp = ArbitraryIdStamp::new();
+        let agent = make_agent(u128::MAX, masq_balance).set_arbitrary_id_stamp(agent_id_stamp);
+        let response_skeleton = ResponseSkeleton {
+            client_id: 123,
+            context_id: 321,
+        };
+        let setup_msg = BlockchainAgentWithContextMessage {
+            response_skeleton_opt: Some(response_skeleton),
+            ..make_setup_msg(payables, agent)
+        };
+        let subject = PaymentAdjusterReal::new();
+
+        let (instructions, summary) = subject.adjust_payments(
+            PreparedAdjustment::new(setup_msg, adjustment),
+            now,
+            &Logger::new(test_name),
+        );
+
+        assert_eq!(instructions.agent.arbitrary_id_stamp(), agent_id_stamp);
+        assert_eq!(instructions.response_skeleton_opt, Some(response_skeleton));
+        (instructions.affordable_accounts, summary)
+    }
+
+    #[test]
+    fn adjust_payments_keeps_the_weightiest_payables_when_transaction_count_is_limited() {
+        init_test_logging();
+        let test_name =
+            "adjust_payments_keeps_the_weightiest_payables_when_transaction_count_is_limited";
+        let now = SystemTime::now();
+        let big_young = make_payable("big_young", 5_000_000_000, 0, now);
+        let small_old = make_payable("small_old", 1_000_000_000, 10 * DAY, now);
+        let medium_young = make_payable("medium_young", 3_000_000_000, 0, now);
+        let payables = vec![big_young.clone(), small_old.clone(), medium_young.clone()];
+
+        let (result, summary) = run_adjustment(
+            payables,
+            u128::MAX,
+            Adjustment::TransactionFeeCurrency { limiting_count: 2 },
+            now,
+            test_name,
+        );
+
+        assert_eq!(result, vec![small_old, big_young]);
+        assert_eq!(
+            summary,
+            UiPaymentsAdjustedBroadcast {
+                paid_in_full_count: 2,
+                reduced: vec![],
+                unpaid: vec![UiUnpaidPayment {
+                    wallet: medium_young.wallet.to_string(),
+                    balance_wei: 3_000_000_000,
+                    reason: "transaction fee balance too low".to_string(),
+                }],
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Payments adjusted to the consuming wallet balances; 2 paid in full:\n\
+            3,000,000,000 wei owed to {} left unpaid: transaction fee balance too low",
+            test_name, medium_young.wallet
+        ));
+    }
+
+    #[test]
+    fn adjust_payments_prorates_masq_by_age_and_size_paying_off_whoever_needs_less_than_a_share() {
+        init_test_logging();
+        let test_name = "adjust_payments_prorates_masq_by_age_and_size_paying_off_whoever_needs_less_than_a_share";
+        let now = SystemTime::now();
+        let small_young = make_payable("small_young", 1_000_000_000, 0, now);
+        let medium_old = make_payable("medium_old", 2_000_000_000, DAY, now);
+        let big_young = make_payable("big_young", 3_000_000_000, 0, now);
+        let payables = vec![small_young.clone(), medium_old.clone(), big_young.clone()];
+
+        let (result, summary) = run_adjustment(
+            payables,
+            4_000_000_000,
+            Adjustment::MasqToken,
+            now,
+            test_name,
+        );
+
+        // Weights 1, 4 and 3: medium_old would get half the balance, more than it needs
+        assert_eq!(
+            result,
+            vec![
+                medium_old,
+                PayableAccount {
+                    balance_wei: 1_500_000_000,
+                    ..big_young.clone()
+                },
+                PayableAccount {
+                    balance_wei: 500_000_000,
+                    ..small_young.clone()
+                },
+            ]
+        );
+        assert_eq!(
+            summary,
+            UiPaymentsAdjustedBroadcast {
+                paid_in_full_count: 1,
+                reduced: vec![
+                    UiReducedPayment {
+                        wallet: big_young.wallet.to_string(),
+                        original_balance_wei: 3_000_000_000,
+                        adjusted_balance_wei: 1_500_000_000,
+                    },
+                    UiReducedPayment {
+                        wallet: small_young.wallet.to_string(),
+                        original_balance_wei: 1_000_000_000,
+                        adjusted_balance_wei: 500_000_000,
+                    },
+                ],
+                unpaid: vec![],
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Payments adjusted to the consuming wallet balances; 1 paid in full:\n\
+            3,000,000,000 wei owed to {} reduced to 1,500,000,000 wei\n\
+            1,000,000,000 wei owed to {} reduced to 500,000,000 wei",
+            test_name, big_young.wallet, small_young.wallet
+        ));
+    }
+
+    #[test]
+    fn adjust_payments_drops_the_lightest_payable_whose_share_is_not_worth_a_transaction() {
+        let now = SystemTime::now();
+        let small_young = make_payable("small_young", 1_000_000_000, 0, now);
+        let big_old = make_payable("big_old", 9_000_000_000, 2 * DAY, now);
+        let payables = vec![small_young, big_old.clone()];
+
+        let (result, _) = run_adjustment(
+            payables,
+            6_000_000_000,
+            Adjustment::MasqToken,
+            now,
+            "adjust_payments_drops_the_lightest_payable_whose_share_is_not_worth_a_transaction",
+        );
+
+        assert_eq!(
+            result,
+            vec![PayableAccount {
+                balance_wei: 6_000_000_000,
+                ..big_old
+            }]
+        );
+    }
+
+    #[test]
+    fn adjust_payments_limits_count_first_and_then_prorates_masq_for_both() {
+        let now = SystemTime::now();
+        let big_young = make_payable("big_young", 5_000_000_000, 0, now);
+        let small_old = make_payable("small_old", 1_000_000_000, 10 * DAY, now);
+        let medium_young = make_payable("medium_young", 3_000_000_000, 0, now);
+        let payables = vec![big_young.clone(), small_old.clone(), medium_young];
+
+        let (result, _) = run_adjustment(
+            payables,
+            5_000_000_000,
+            Adjustment::Both { limiting_count: 2 },
+            now,
+            "adjust_payments_limits_count_first_and_then_prorates_masq_for_both",
+        );
+
+        assert_eq!(
+            result,
+            vec![
+                small_old,
+                PayableAccount {
+                    balance_wei: 4_000_000_000,
+                    ..big_young
+                }
+            ]
+        );
     }
 }
@@ -1,6 +1,8 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
+use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::{
+    BlockchainAgent, TransactionFeeModel,
+};
 
 use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
 use crate::sub_lib::wallet::Wallet;
@@ -27,9 +29,9 @@ impl BlockchainAgent for BlockchainAgentNull {
         }
     }
 
-    fn agreed_fee_per_computation_unit(&self) -> u64 {
-        self.log_function_call("agreed_fee_per_computation_unit()");
-        0
+    fn agreed_transaction_fee_model(&self) -> TransactionFeeModel {
+        self.log_function_call("agreed_transaction_fee_model()");
+        TransactionFeeModel::Legacy { gas_price_wei: 0 }
     }
 
     fn consuming_wallet(&self) -> &Wallet {
@@ -76,7 +78,9 @@ impl Default for BlockchainAgentNull {
 #[cfg(test)]
 mod tests {
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::agent_null::BlockchainAgentNull;
-    use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
+    use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::{
+        BlockchainAgent, TransactionFeeModel,
+    };
 
     use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
     use crate::sub_lib::wallet::Wallet;
@@ -152,16 +156,16 @@ mod tests {
     }
 
     #[test]
-    fn null_agent_agreed_fee_per_computation_unit() {
+    fn null_agent_agreed_transaction_fee_model() {
         init_test_logging();
-        let test_name = "null_agent_agreed_fee_per_computation_unit";
+        let test_name = "null_agent_agreed_transaction_fee_model";
         let mut subject = BlockchainAgentNull::new();
         subject.logger = Logger::new(test_name);
 
-        let result = subject.agreed_fee_per_computation_unit();
+        let result = subject.agreed_transaction_fee_model();
 
-        assert_eq!(result, 0);
-        assert_error_log(test_name, "agreed_fee_per_computation_unit")
+        assert_eq!(result, TransactionFeeModel::Legacy { gas_price_wei: 0 });
+        assert_error_log(test_name, "agreed_transaction_fee_model")
     }
 
     #[test]
@@ -1,6 +1,8 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
+use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::{
+    BlockchainAgent, TransactionFeeModel,
+};
 
 use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
 use crate::sub_lib::wallet::Wallet;
@@ -9,7 +11,7 @@ use web3::types::U256;
 
 #[derive(Debug, Clone)]
 pub struct BlockchainAgentWeb3 {
-    gas_price_gwei: u64,
+    transaction_fee_model: TransactionFeeModel,
     gas_limit_const_part: u64,
     maximum_added_gas_margin: u64,
     consuming_wallet: Wallet,
@@ -19,17 +21,17 @@ pub struct BlockchainAgentWeb3 {
 
 impl BlockchainAgent for BlockchainAgentWeb3 {
     fn estimated_transaction_fee_total(&self, number_of_transactions: usize) -> u128 {
-        let gas_price = self.gas_price_gwei as u128;
+        let max_fee_per_gas = self.transaction_fee_model.max_fee_per_gas_wei();
         let max_gas_limit = (self.maximum_added_gas_margin + self.gas_limit_const_part) as u128;
-        number_of_transactions as u128 * gas_price * max_gas_limit
+        number_of_transactions as u128 * max_fee_per_gas * max_gas_limit
     }
 
     fn consuming_wallet_balances(&self) -> ConsumingWalletBalances {
         self.consuming_wallet_balances
     }
 
-    fn agreed_fee_per_computation_unit(&self) -> u64 {
-        self.gas_price_gwei
+    fn agreed_transaction_fee_model(&self) -> TransactionFeeModel {
+        self.transaction_fee_model
     }
 
     fn consuming_wallet(&self) -> &Wallet {
@@ -47,14 +49,14 @@ pub const WEB3_MAXIMAL_GAS_LIMIT_MARGIN: u64 = 3328;
 
 impl BlockchainAgentWeb3 {
     pub fn new(
-        gas_price_gwei: u64,
+        transaction_fee_model: TransactionFeeModel,
         gas_limit_const_part: u64,
         consuming_wallet: Wallet,
         consuming_wallet_balances: ConsumingWalletBalances,
         pending_transaction_id: U256,
     ) -> Self {
         Self {
-            gas_price_gwei,
+            transaction_fee_model,
             gas_limit_const_part,
             consuming_wallet,
             maximum_added_gas_margin: WEB3_MAXIMAL_GAS_LIMIT_MARGIN,
@@ -69,7 +71,9 @@ mod tests {
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::agent_web3::{
         BlockchainAgentWeb3, WEB3_MAXIMAL_GAS_LIMIT_MARGIN,
     };
-    use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
+    use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::{
+        BlockchainAgent, TransactionFeeModel,
+    };
 
     use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
     use crate::test_utils::make_wallet;
@@ -83,7 +87,10 @@ mod tests {
 
     #[test]
     fn blockchain_agent_can_return_non_computed_input_values() {
-        let gas_price_gwei = 123;
+        let transaction_fee_model = TransactionFeeModel::Eip1559 {
+            max_fee_per_gas_wei: 123_000_000_000,
+            max_priority_fee_per_gas_wei: 2_000_000_000,
+        };
         let gas_limit_const_part = 44_000;
         let consuming_wallet = make_wallet("abcde");
         let consuming_wallet_balances = ConsumingWalletBalances {
@@ -93,14 +100,17 @@ mod tests {
         let pending_transaction_id = U256::from(777);
 
         let subject = BlockchainAgentWeb3::new(
-            gas_price_gwei,
+            transaction_fee_model,
             gas_limit_const_part,
             consuming_wallet.clone(),
             consuming_wallet_balances,
             pending_transaction_id,
         );
 
-        assert_eq!(subject.agreed_fee_per_computation_unit(), gas_price_gwei);
+        assert_eq!(
+            subject.agreed_transaction_fee_model(),
+            transaction_fee_model
+        );
         assert_eq!(subject.consuming_wallet(), &consuming_wallet);
         assert_eq!(
             subject.consuming_wallet_balances(),
@@ -110,20 +120,10 @@ mod tests {
     }
 
     #[test]
-    fn estimated_transaction_fee_works() {
-        let consuming_wallet = make_wallet("efg");
-        let consuming_wallet_balances = ConsumingWalletBalances {
-            transaction_fee_balance_in_minor_units: Default::default(),
-            masq_token_balance_in_minor_units: Default::default(),
-        };
-        let nonce = U256::from(55);
-        let agent = BlockchainAgentWeb3::new(
-            444,
-            77_777,
-            consuming_wallet,
-            consuming_wallet_balances,
-            nonce,
-        );
+    fn estimated_transaction_fee_works_for_legacy_transactions() {
+        let agent = make_agent(TransactionFeeModel::Legacy {
+            gas_price_wei: 444_000_000_000,
+        });
 
         let result = agent.estimated_transaction_fee_total(3);
 
@@ -134,7 +134,38 @@ mod tests {
         );
         assert_eq!(
             result,
-            (3 * (77_777 + WEB3_MAXIMAL_GAS_LIMIT_MARGIN)) as u128 * 444
+            (3 * (77_777 + WEB3_MAXIMAL_GAS_LIMIT_MARGIN)) as u128 * 444_000_000_000
+        );
+    }
+
+    #[test]
+    fn estimated_transaction_fee_for_eip1559_transactions_counts_with_the_max_fee_per_gas() {
+        let agent = make_agent(TransactionFeeModel::Eip1559 {
+            max_fee_per_gas_wei: 55_500_000_000,
+            max_priority_fee_per_gas_wei: 1_500_000_000,
+        });
+
+        let result = agent.estimated_transaction_fee_total(3);
+
+        assert_eq!(
+            result,
+            (3 * (77_777 + WEB3_MAXIMAL_GAS_LIMIT_MARGIN)) as u128 * 55_500_000_000
         );
     }
+
+    fn make_agent(transaction_fee_model: TransactionFeeModel) -> BlockchainAgentWeb3 {
+        let consuming_wallet = make_wallet("efg");
+        let consuming_wallet_balances = ConsumingWalletBalances {
+            transaction_fee_balance_in_minor_units: Default::default(),
+            masq_token_balance_in_minor_units: Default::default(),
+        };
+        let nonce = U256::from(55);
+        BlockchainAgentWeb3::new(
+            transaction_fee_model,
+            77_777,
+            consuming_wallet,
+            consuming_wallet_balances,
+            nonce,
+        )
+    }
 }
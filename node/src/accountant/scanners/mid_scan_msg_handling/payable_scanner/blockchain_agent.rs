@@ -24,7 +24,7 @@ use web3::types::U256;
 pub trait BlockchainAgent: Send {
     fn estimated_transaction_fee_total(&self, number_of_transactions: usize) -> u128;
     fn consuming_wallet_balances(&self) -> ConsumingWalletBalances;
-    fn agreed_fee_per_computation_unit(&self) -> u64;
+    fn agreed_transaction_fee_model(&self) -> TransactionFeeModel;
     fn consuming_wallet(&self) -> &Wallet;
     fn pending_transaction_id(&self) -> U256;
 
@@ -35,3 +35,46 @@ pub trait BlockchainAgent: Send {
     as_any_ref_in_trait!();
     arbitrary_id_stamp_in_trait!();
 }
+
+// All prices are in wei per a unit of gas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionFeeModel {
+    Legacy {
+        gas_price_wei: u128,
+    },
+    Eip1559 {
+        max_fee_per_gas_wei: u128,
+        max_priority_fee_per_gas_wei: u128,
+    },
+}
+
+impl TransactionFeeModel {
+    pub fn max_fee_per_gas_wei(&self) -> u128 {
+        match self {
+            TransactionFeeModel::Legacy { gas_price_wei } => *gas_price_wei,
+            TransactionFeeModel::Eip1559 {
+                max_fee_per_gas_wei,
+                ..
+            } => *max_fee_per_gas_wei,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::TransactionFeeModel;
+
+    #[test]
+    fn max_fee_per_gas_is_the_most_the_model_can_charge_per_unit_of_gas() {
+        let legacy = TransactionFeeModel::Legacy {
+            gas_price_wei: 123_456,
+        };
+        let eip1559 = TransactionFeeModel::Eip1559 {
+            max_fee_per_gas_wei: 654_321,
+            max_priority_fee_per_gas_wei: 1_000,
+        };
+
+        assert_eq!(legacy.max_fee_per_gas_wei(), 123_456);
+        assert_eq!(eip1559.max_fee_per_gas_wei(), 654_321)
+    }
+}
@@ -13,6 +13,7 @@ use crate::sub_lib::blockchain_bridge::OutboundPaymentsInstructions;
 use actix::Message;
 use itertools::Either;
 use masq_lib::logger::Logger;
+use masq_lib::messages::UiPaymentsAdjustedBroadcast;
 
 pub trait MultistagePayableScanner<BeginMessage, EndMessage>:
     Scanner<BeginMessage, EndMessage> + SolvencySensitivePaymentInstructor
@@ -33,7 +34,7 @@ pub trait SolvencySensitivePaymentInstructor {
         &self,
         setup: PreparedAdjustment,
         logger: &Logger,
-    ) -> OutboundPaymentsInstructions;
+    ) -> (OutboundPaymentsInstructions, UiPaymentsAdjustedBroadcast);
 }
 
 pub struct PreparedAdjustment {
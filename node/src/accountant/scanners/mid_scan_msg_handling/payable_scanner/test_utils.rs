@@ -2,7 +2,9 @@
 
 #![cfg(test)]
 
-use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
+use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::{
+    BlockchainAgent, TransactionFeeModel,
+};
 use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
 use crate::sub_lib::wallet::Wallet;
 use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::ArbitraryIdStamp;
@@ -12,8 +14,9 @@ use std::cell::RefCell;
 
 #[derive(Default)]
 pub struct BlockchainAgentMock {
+    estimated_transaction_fee_total_results: RefCell<Vec<u128>>,
     consuming_wallet_balances_results: RefCell<Vec<ConsumingWalletBalances>>,
-    agreed_fee_per_computation_unit_results: RefCell<Vec<u64>>,
+    agreed_transaction_fee_model_results: RefCell<Vec<TransactionFeeModel>>,
     consuming_wallet_result_opt: Option<Wallet>,
     pending_transaction_id_results: RefCell<Vec<U256>>,
     arbitrary_id_stamp_opt: Option<ArbitraryIdStamp>,
@@ -21,15 +24,19 @@ pub struct BlockchainAgentMock {
 
 impl BlockchainAgent for BlockchainAgentMock {
     fn estimated_transaction_fee_total(&self, _number_of_transactions: usize) -> u128 {
-        todo!("to be implemented by GH-711")
+        self.estimated_transaction_fee_total_results
+            .borrow_mut()
+            .remove(0)
     }
 
     fn consuming_wallet_balances(&self) -> ConsumingWalletBalances {
-        todo!("to be implemented by GH-711")
+        self.consuming_wallet_balances_results
+            .borrow_mut()
+            .remove(0)
     }
 
-    fn agreed_fee_per_computation_unit(&self) -> u64 {
-        self.agreed_fee_per_computation_unit_results
+    fn agreed_transaction_fee_model(&self) -> TransactionFeeModel {
+        self.agreed_transaction_fee_model_results
             .borrow_mut()
             .remove(0)
     }
@@ -50,6 +57,13 @@ impl BlockchainAgent for BlockchainAgentMock {
 }
 
 impl BlockchainAgentMock {
+    pub fn estimated_transaction_fee_total_result(self, result: u128) -> Self {
+        self.estimated_transaction_fee_total_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
     pub fn consuming_wallet_balances_result(self, result: ConsumingWalletBalances) -> Self {
         self.consuming_wallet_balances_results
             .borrow_mut()
@@ -57,8 +71,8 @@ impl BlockchainAgentMock {
         self
     }
 
-    pub fn agreed_fee_per_computation_unit_result(self, result: u64) -> Self {
-        self.agreed_fee_per_computation_unit_results
+    pub fn agreed_transaction_fee_model_result(self, result: TransactionFeeModel) -> Self {
+        self.agreed_transaction_fee_model_results
             .borrow_mut()
             .push(result);
         self
@@ -4,8 +4,11 @@ pub mod mid_scan_msg_handling;
 pub mod scanners_utils;
 pub mod test_utils;
 
+use crate::accountant::db_access_objects::ledger_dao::{LedgerDao, LedgerEntry, LedgerEntryType};
 use crate::accountant::db_access_objects::payable_dao::{PayableAccount, PayableDao};
-use crate::accountant::db_access_objects::pending_payable_dao::{PendingPayable, PendingPayableDao};
+use crate::accountant::db_access_objects::pending_payable_dao::{
+    PendingPayable, PendingPayableDao, PendingPayableReplacement,
+};
 use crate::accountant::db_access_objects::receivable_dao::ReceivableDao;
 use crate::accountant::payment_adjuster::{PaymentAdjuster, PaymentAdjusterReal};
 use crate::accountant::scanners::scanners_utils::payable_scanner_utils::PayableTransactingErrorEnum::{
@@ -26,10 +29,11 @@ use crate::accountant::PendingPayableId;
 use crate::accountant::{
     comma_joined_stringifiable, gwei_to_wei, Accountant, ReceivedPayments,
     ReportTransactionReceipts, RequestTransactionReceipts, ResponseSkeleton, ScanForPayables,
-    ScanForPendingPayables, ScanForReceivables, SentPayables,
+    ScanForPendingPayables, ScanForReceivables, SentPayables, StuckPayables,
 };
-use crate::accountant::db_access_objects::banned_dao::BannedDao;
+use crate::accountant::db_access_objects::banned_dao::{BanOrigin, BannedDao};
 use crate::blockchain::blockchain_bridge::{PendingPayableFingerprint, RetrieveTransactions};
+use crate::metrics::NodeMetrics;
 use crate::sub_lib::accountant::{
     DaoFactories, FinancialStatistics, PaymentThresholds, ScanIntervals,
 };
@@ -42,12 +46,15 @@ use actix::{Context, Message};
 use itertools::{Either, Itertools};
 use masq_lib::logger::Logger;
 use masq_lib::logger::TIME_FORMATTING_STRING;
-use masq_lib::messages::{ScanType, ToMessageBody, UiScanResponse};
+use masq_lib::messages::{
+    ScanType, ToMessageBody, UiBanChangedBroadcast, UiPaymentsAdjustedBroadcast, UiScanResponse,
+};
 use masq_lib::ui_gateway::{MessageTarget, NodeToUiMessage};
 use masq_lib::utils::ExpectValue;
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::rc::Rc;
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use time::format_description::parse;
 use time::OffsetDateTime;
@@ -56,6 +63,8 @@ use masq_lib::type_obfuscation::Obfuscated;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::{PreparedAdjustment, MultistagePayableScanner, SolvencySensitivePaymentInstructor};
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::{BlockchainAgentWithContextMessage, QualifiedPayablesMessage};
 use crate::blockchain::blockchain_interface::data_structures::errors::PayableTransactionError;
+use crate::blockchain::blockchain_interface::data_structures::BlockchainTransaction;
+use thousands::Separable;
 use crate::db_config::persistent_configuration::{PersistentConfiguration, PersistentConfigurationReal};
 
 pub struct Scanners {
@@ -65,25 +74,34 @@ pub struct Scanners {
 }
 
 impl Scanners {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         dao_factories: DaoFactories,
         payment_thresholds: Rc<PaymentThresholds>,
         when_pending_too_long_sec: u64,
+        when_pending_stuck_sec: u64,
+        confirmation_depth: u64,
         financial_statistics: Rc<RefCell<FinancialStatistics>>,
+        ban_changes: Rc<RefCell<Vec<UiBanChangedBroadcast>>>,
+        metrics: Arc<NodeMetrics>,
     ) -> Self {
         let payable = Box::new(PayableScanner::new(
             dao_factories.payable_dao_factory.make(),
             dao_factories.pending_payable_dao_factory.make(),
             Rc::clone(&payment_thresholds),
             Box::new(PaymentAdjusterReal::new()),
+            metrics.clone(),
         ));
 
         let pending_payable = Box::new(PendingPayableScanner::new(
             dao_factories.payable_dao_factory.make(),
             dao_factories.pending_payable_dao_factory.make(),
+            dao_factories.ledger_dao_factory.make(),
             Rc::clone(&payment_thresholds),
             when_pending_too_long_sec,
+            when_pending_stuck_sec,
             Rc::clone(&financial_statistics),
+            metrics.clone(),
         ));
 
         let persistent_configuration =
@@ -91,9 +109,13 @@ impl Scanners {
         let receivable = Box::new(ReceivableScanner::new(
             dao_factories.receivable_dao_factory.make(),
             dao_factories.banned_dao_factory.make(),
+            dao_factories.ledger_dao_factory.make(),
             Box::new(persistent_configuration),
             Rc::clone(&payment_thresholds),
+            confirmation_depth,
             financial_statistics,
+            ban_changes,
+            metrics,
         ));
 
         Scanners {
@@ -128,13 +150,15 @@ where
 pub struct ScannerCommon {
     initiated_at_opt: Option<SystemTime>,
     pub payment_thresholds: Rc<PaymentThresholds>,
+    metrics: Arc<NodeMetrics>,
 }
 
 impl ScannerCommon {
-    fn new(payment_thresholds: Rc<PaymentThresholds>) -> Self {
+    fn new(payment_thresholds: Rc<PaymentThresholds>, metrics: Arc<NodeMetrics>) -> Self {
         Self {
             initiated_at_opt: None,
             payment_thresholds,
+            metrics,
         }
     }
 
@@ -143,12 +167,14 @@ impl ScannerCommon {
             Some(timestamp) => {
                 let elapsed_time = now
                     .duration_since(timestamp)
-                    .expect("Unable to calculate elapsed time for the scan.")
-                    .as_millis();
+                    .expect("Unable to calculate elapsed time for the scan.");
                 info!(
                     logger,
-                    "The {:?} scan ended in {}ms.", scan_type, elapsed_time
+                    "The {:?} scan ended in {}ms.",
+                    scan_type,
+                    elapsed_time.as_millis()
                 );
+                self.metrics.record_scan_duration(scan_type, elapsed_time);
             }
             None => {
                 error!(
@@ -269,21 +295,28 @@ impl SolvencySensitivePaymentInstructor for PayableScanner {
         msg: BlockchainAgentWithContextMessage,
         logger: &Logger,
     ) -> Result<Either<OutboundPaymentsInstructions, PreparedAdjustment>, String> {
-        match self
-            .payment_adjuster
-            .search_for_indispensable_adjustment(&msg, logger)
-        {
-            Ok(None) => {
-                let protected = msg.protected_qualified_payables;
-                let unprotected = self.expose_payables(protected);
-                Ok(Either::Left(OutboundPaymentsInstructions::new(
-                    unprotected,
+        let unprotected = self.expose_payables(msg.protected_qualified_payables);
+        match self.payment_adjuster.search_for_indispensable_adjustment(
+            &unprotected,
+            msg.agent.as_ref(),
+            logger,
+        ) {
+            Ok(None) => Ok(Either::Left(OutboundPaymentsInstructions::new(
+                unprotected,
+                msg.agent,
+                msg.response_skeleton_opt,
+            ))),
+            Ok(Some(adjustment)) => {
+                let setup_msg = BlockchainAgentWithContextMessage::new(
+                    self.protect_payables(unprotected),
                     msg.agent,
                     msg.response_skeleton_opt,
+                );
+                Ok(Either::Right(PreparedAdjustment::new(
+                    setup_msg, adjustment,
                 )))
             }
-            Ok(Some(adjustment)) => Ok(Either::Right(PreparedAdjustment::new(msg, adjustment))),
-            Err(_e) => todo!("be implemented with GH-711"),
+            Err(e) => Err(e.to_string()),
         }
     }
 
@@ -291,7 +324,7 @@ impl SolvencySensitivePaymentInstructor for PayableScanner {
         &self,
         setup: PreparedAdjustment,
         logger: &Logger,
-    ) -> OutboundPaymentsInstructions {
+    ) -> (OutboundPaymentsInstructions, UiPaymentsAdjustedBroadcast) {
         let now = SystemTime::now();
         self.payment_adjuster.adjust_payments(setup, now, logger)
     }
@@ -305,9 +338,10 @@ impl PayableScanner {
         pending_payable_dao: Box<dyn PendingPayableDao>,
         payment_thresholds: Rc<PaymentThresholds>,
         payment_adjuster: Box<dyn PaymentAdjuster>,
+        metrics: Arc<NodeMetrics>,
     ) -> Self {
         Self {
-            common: ScannerCommon::new(payment_thresholds),
+            common: ScannerCommon::new(payment_thresholds, metrics),
             payable_dao,
             pending_payable_dao,
             payable_threshold_gauge: Box::new(PayableThresholdsGaugeReal::default()),
@@ -385,10 +419,17 @@ impl PayableScanner {
             .iter()
             .map(|pending_payable| pending_payable.hash)
             .collect::<Vec<H256>>();
-        let mut sent_payables_hashmap = sent_payables
-            .iter()
-            .map(|payable| (payable.hash, &payable.recipient_wallet))
-            .collect::<HashMap<H256, &Wallet>>();
+        // A multisend transaction shares its hash among several payables; their fingerprints
+        // were inserted in the order of the payables and come back ordered by rowid
+        let mut sent_payables_hashmap = sent_payables.iter().fold(
+            HashMap::<H256, VecDeque<&Wallet>>::new(),
+            |mut acc, payable| {
+                acc.entry(payable.hash)
+                    .or_default()
+                    .push_back(&payable.recipient_wallet);
+                acc
+            },
+        );
 
         let transaction_hashes = self.pending_payable_dao.fingerprints_rowids(&hashes);
         let mut hashes_from_db = transaction_hashes
@@ -414,7 +455,8 @@ impl PayableScanner {
             .into_iter()
             .map(|(rowid, hash)| {
                 let wallet = sent_payables_hashmap
-                    .remove(&hash)
+                    .get_mut(&hash)
+                    .and_then(|wallets| wallets.pop_front())
                     .expect("expect transaction hash, but it disappear");
                 PendingPayableMetadata::new(wallet, hash, Some(rowid))
             })
@@ -424,7 +466,8 @@ impl PayableScanner {
             .into_iter()
             .map(|hash| {
                 let wallet = sent_payables_hashmap
-                    .remove(&hash)
+                    .get_mut(&hash)
+                    .and_then(|wallets| wallets.pop_front())
                     .expect("expect transaction hash, but it disappear");
                 PendingPayableMetadata::new(wallet, hash, None)
             })
@@ -563,14 +606,16 @@ pub struct PendingPayableScanner {
     pub common: ScannerCommon,
     pub payable_dao: Box<dyn PayableDao>,
     pub pending_payable_dao: Box<dyn PendingPayableDao>,
+    pub ledger_dao: Box<dyn LedgerDao>,
     pub when_pending_too_long_sec: u64,
+    pub when_pending_stuck_sec: u64,
     pub financial_statistics: Rc<RefCell<FinancialStatistics>>,
 }
 
 impl Scanner<RequestTransactionReceipts, ReportTransactionReceipts> for PendingPayableScanner {
     fn begin_scan(
         &mut self,
-        _irrelevant_wallet: Wallet,
+        consuming_wallet: Wallet,
         timestamp: SystemTime,
         response_skeleton_opt: Option<ResponseSkeleton>,
         logger: &Logger,
@@ -592,8 +637,11 @@ impl Scanner<RequestTransactionReceipts, ReportTransactionReceipts> for PendingP
                     "Found {} pending payables to process",
                     filtered_pending_payable.len()
                 );
+                let stuck_payables_opt =
+                    self.stuck_payables(&filtered_pending_payable, consuming_wallet, logger);
                 Ok(RequestTransactionReceipts {
                     pending_payable: filtered_pending_payable,
+                    stuck_payables_opt,
                     response_skeleton_opt,
                 })
             }
@@ -615,8 +663,10 @@ impl Scanner<RequestTransactionReceipts, ReportTransactionReceipts> for PendingP
                     "Processing receipts for {} transactions",
                     message.fingerprints_with_receipts.len()
                 );
+                let replacements = message.replacements.clone();
                 let scan_report = self.handle_receipts_for_pending_transactions(message, logger);
                 self.process_transactions_by_reported_state(scan_report, logger);
+                self.record_replacements(replacements, logger);
             }
         }
 
@@ -633,22 +683,62 @@ impl Scanner<RequestTransactionReceipts, ReportTransactionReceipts> for PendingP
 }
 
 impl PendingPayableScanner {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         payable_dao: Box<dyn PayableDao>,
         pending_payable_dao: Box<dyn PendingPayableDao>,
+        ledger_dao: Box<dyn LedgerDao>,
         payment_thresholds: Rc<PaymentThresholds>,
         when_pending_too_long_sec: u64,
+        when_pending_stuck_sec: u64,
         financial_statistics: Rc<RefCell<FinancialStatistics>>,
+        metrics: Arc<NodeMetrics>,
     ) -> Self {
         Self {
-            common: ScannerCommon::new(payment_thresholds),
+            common: ScannerCommon::new(payment_thresholds, metrics),
             payable_dao,
             pending_payable_dao,
+            ledger_dao,
             when_pending_too_long_sec,
+            when_pending_stuck_sec,
             financial_statistics,
         }
     }
 
+    fn stuck_payables(
+        &self,
+        fingerprints: &[PendingPayableFingerprint],
+        consuming_wallet: Wallet,
+        logger: &Logger,
+    ) -> Option<StuckPayables> {
+        let rowids = fingerprints
+            .iter()
+            .filter(|fingerprint| self.is_stuck(fingerprint))
+            .map(|fingerprint| fingerprint.rowid)
+            .collect::<Vec<u64>>();
+        if rowids.is_empty() {
+            None
+        } else {
+            debug!(
+                logger,
+                "Pending payables at rowids {} are going to be replaced unless mined by now",
+                comma_joined_stringifiable(&rowids, |id| id.to_string())
+            );
+            Some(StuckPayables {
+                consuming_wallet,
+                rowids,
+            })
+        }
+    }
+
+    // Each replacement is given the same time to be mined as the original transaction
+    fn is_stuck(&self, fingerprint: &PendingPayableFingerprint) -> bool {
+        let stuck_after_sec =
+            self.when_pending_stuck_sec * (fingerprint.replaced_hashes.len() as u64 + 1);
+        fingerprint.resubmission_data_opt.is_some()
+            && elapsed_in_ms(fingerprint.timestamp) > u128::from(stuck_after_sec) * 1000
+    }
+
     fn handle_receipts_for_pending_transactions(
         &self,
         msg: ReportTransactionReceipts,
@@ -677,7 +767,7 @@ impl PendingPayableScanner {
                 Some(receipt) => self.interpret_transaction_receipt(
                     scan_report_so_far,
                     &receipt,
-                    fingerprint,
+                    Self::reconcile_hash(fingerprint, &receipt, logger),
                     logger,
                 ),
                 None => handle_none_receipt(scan_report_so_far, fingerprint, logger),
@@ -685,6 +775,29 @@ impl PendingPayableScanner {
         )
     }
 
+    // The receipt may belong to any of the transactions the payable has been sent by, as they share
+    // the nonce, and only one of them can ever be mined
+    fn reconcile_hash(
+        mut fingerprint: PendingPayableFingerprint,
+        receipt: &TransactionReceipt,
+        logger: &Logger,
+    ) -> PendingPayableFingerprint {
+        if receipt.transaction_hash != fingerprint.hash
+            && fingerprint
+                .replaced_hashes
+                .contains(&receipt.transaction_hash)
+        {
+            info!(
+                logger,
+                "Receipt for pending payable {:?} belongs to its replaced transaction {:?}",
+                fingerprint.hash,
+                receipt.transaction_hash
+            );
+            fingerprint.hash = receipt.transaction_hash
+        }
+        fingerprint
+    }
+
     fn interpret_transaction_receipt(
         &self,
         scan_report: PendingPayableScanReport,
@@ -795,6 +908,7 @@ impl PendingPayableScanner {
                     panic!("Unable to delete payable fingerprints {} of verified transactions due to {:?}",
                            serialize_hashes(&fingerprints), e)
                 } else {
+                    self.record_payments_in_ledger(&fingerprints, logger);
                     info!(
                         logger,
                         "Transactions {} completed their confirmation process succeeding",
@@ -805,6 +919,67 @@ impl PendingPayableScanner {
         }
     }
 
+    fn record_replacements(&self, replacements: Vec<PendingPayableReplacement>, logger: &Logger) {
+        if !replacements.is_empty() {
+            match self.pending_payable_dao.record_replacements(&replacements) {
+                Ok(_) => replacements.iter().for_each(|replacement| {
+                    info!(
+                        logger,
+                        "Stuck transaction {:?} replaced by {:?} with fees {:?}",
+                        replacement
+                            .replaced_hashes
+                            .last()
+                            .expectv("replaced transaction hash"),
+                        replacement.hash,
+                        replacement.fee_model
+                    )
+                }),
+                Err(e) => panic!(
+                    "Unable to record replacements of stuck transactions by {} due to {:?}",
+                    comma_joined_stringifiable(&replacements, |replacement| format!(
+                        "{:?}",
+                        replacement.hash
+                    )),
+                    e
+                ),
+            }
+        }
+    }
+
+    fn record_payments_in_ledger(
+        &self,
+        fingerprints: &[PendingPayableFingerprint],
+        logger: &Logger,
+    ) {
+        let now = SystemTime::now();
+        let entries = fingerprints
+            .iter()
+            .flat_map(
+                |fingerprint| match fingerprint.resubmission_data_opt.as_ref() {
+                    Some(resubmission_data) => Some(LedgerEntry::payment(
+                        now,
+                        resubmission_data.recipient_wallet.clone(),
+                        LedgerEntryType::PaymentSent,
+                        fingerprint.amount,
+                        fingerprint.hash,
+                    )),
+                    None => {
+                        debug!(
+                            logger,
+                            "Payment {:?} stays out of the ledger; its record predates keeping \
+                            the recipient",
+                            fingerprint.hash
+                        );
+                        None
+                    }
+                },
+            )
+            .collect::<Vec<_>>();
+        if !entries.is_empty() {
+            self.ledger_dao.record(&entries)
+        }
+    }
+
     fn add_to_the_total_of_paid_payable(
         &mut self,
         fingerprints: &[PendingPayableFingerprint],
@@ -828,8 +1003,12 @@ pub struct ReceivableScanner {
     pub common: ScannerCommon,
     pub receivable_dao: Box<dyn ReceivableDao>,
     pub banned_dao: Box<dyn BannedDao>,
+    pub ledger_dao: Box<dyn LedgerDao>,
     pub persistent_configuration: Box<dyn PersistentConfiguration>,
+    pub confirmation_depth: u64,
     pub financial_statistics: Rc<RefCell<FinancialStatistics>>,
+    // Bans and unbans made by the scanner, waiting for the Accountant to broadcast them
+    pub ban_changes: Rc<RefCell<Vec<UiBanChangedBroadcast>>>,
 }
 
 impl Scanner<RetrieveTransactions, ReceivedPayments> for ReceivableScanner {
@@ -849,12 +1028,14 @@ impl Scanner<RetrieveTransactions, ReceivedPayments> for ReceivableScanner {
 
         Ok(RetrieveTransactions {
             recipient: earning_wallet,
+            confirmation_depth: self.confirmation_depth,
             response_skeleton_opt,
         })
     }
 
     fn finish_scan(&mut self, msg: ReceivedPayments, logger: &Logger) -> Option<NodeToUiMessage> {
-        if msg.payments.is_empty() {
+        let (new_payments, orphaned_payments) = self.sort_out_received_payments(&msg, logger);
+        if new_payments.is_empty() && orphaned_payments.is_empty() {
             info!(
                 logger,
                 "No newly received payments were detected during the scanning process."
@@ -871,8 +1052,11 @@ impl Scanner<RetrieveTransactions, ReceivedPayments> for ReceivableScanner {
                 ),
             }
         } else {
-            self.handle_new_received_payments(&msg, logger)
+            self.handle_new_received_payments(&msg, &new_payments, &orphaned_payments, logger)
         }
+        self.receivable_dao.forget_credited_transactions_before(
+            msg.new_start_block.saturating_sub(self.confirmation_depth),
+        );
 
         self.mark_as_ended(logger);
         msg.response_skeleton_opt
@@ -889,27 +1073,87 @@ impl Scanner<RetrieveTransactions, ReceivedPayments> for ReceivableScanner {
 }
 
 impl ReceivableScanner {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         receivable_dao: Box<dyn ReceivableDao>,
         banned_dao: Box<dyn BannedDao>,
+        ledger_dao: Box<dyn LedgerDao>,
         persistent_configuration: Box<dyn PersistentConfiguration>,
         payment_thresholds: Rc<PaymentThresholds>,
+        confirmation_depth: u64,
         financial_statistics: Rc<RefCell<FinancialStatistics>>,
+        ban_changes: Rc<RefCell<Vec<UiBanChangedBroadcast>>>,
+        metrics: Arc<NodeMetrics>,
     ) -> Self {
         Self {
-            common: ScannerCommon::new(payment_thresholds),
+            common: ScannerCommon::new(payment_thresholds, metrics),
             receivable_dao,
             banned_dao,
+            ledger_dao,
             persistent_configuration,
+            confirmation_depth,
             financial_statistics,
+            ban_changes,
         }
     }
 
-    fn handle_new_received_payments(&mut self, msg: &ReceivedPayments, logger: &Logger) {
-        let mut txn = self
+    fn sort_out_received_payments(
+        &self,
+        msg: &ReceivedPayments,
+        logger: &Logger,
+    ) -> (Vec<BlockchainTransaction>, Vec<BlockchainTransaction>) {
+        let same_transaction = |a: &BlockchainTransaction, b: &BlockchainTransaction| {
+            a.transaction_hash == b.transaction_hash && a.block_hash == b.block_hash
+        };
+        let credited_payments = self
             .receivable_dao
-            .as_mut()
-            .more_money_received(msg.timestamp, &msg.payments);
+            .credited_transactions_since(msg.scanned_from_block);
+        let orphaned_payments = credited_payments
+            .iter()
+            .filter(|credited| {
+                !msg.payments
+                    .iter()
+                    .any(|received| same_transaction(credited, received))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        orphaned_payments.iter().for_each(|orphaned| {
+            warning!(
+                logger,
+                "Payment {:?} of {} wei from {} in block {} ({:?}) is no longer on the canonical \
+                chain; its credit is being reversed",
+                orphaned.transaction_hash,
+                orphaned.wei_amount.separate_with_commas(),
+                orphaned.from,
+                orphaned.block_number,
+                orphaned.block_hash
+            )
+        });
+        let new_payments = msg
+            .payments
+            .iter()
+            .filter(|received| {
+                !credited_payments
+                    .iter()
+                    .any(|credited| same_transaction(credited, received))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        (new_payments, orphaned_payments)
+    }
+
+    fn handle_new_received_payments(
+        &mut self,
+        msg: &ReceivedPayments,
+        new_payments: &[BlockchainTransaction],
+        orphaned_payments: &[BlockchainTransaction],
+        logger: &Logger,
+    ) {
+        let mut txn = self.receivable_dao.as_mut().more_money_received(
+            msg.timestamp,
+            new_payments,
+            orphaned_payments,
+        );
 
         let new_start_block = msg.new_start_block;
         match self
@@ -930,14 +1174,39 @@ impl ReceivableScanner {
             Err(e) => panic!("Commit of received transactions failed: {:?}", e),
         }
 
-        let total_newly_paid_receivable = msg
-            .payments
-            .iter()
-            .fold(0, |so_far, now| so_far + now.wei_amount);
+        let ledger_entries = |payments: &[BlockchainTransaction], entry_type| {
+            payments
+                .iter()
+                .map(|payment| {
+                    LedgerEntry::payment(
+                        msg.timestamp,
+                        payment.from.clone(),
+                        entry_type,
+                        payment.wei_amount,
+                        payment.transaction_hash,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        self.ledger_dao.record(
+            &ledger_entries(new_payments, LedgerEntryType::PaymentReceived)
+                .into_iter()
+                .chain(ledger_entries(
+                    orphaned_payments,
+                    LedgerEntryType::PaymentReversed,
+                ))
+                .collect::<Vec<_>>(),
+        );
 
-        self.financial_statistics
-            .borrow_mut()
-            .total_paid_receivable_wei += total_newly_paid_receivable;
+        let sum_up = |payments: &[BlockchainTransaction]| {
+            payments
+                .iter()
+                .fold(0, |so_far, now| so_far + now.wei_amount)
+        };
+        let mut financial_statistics = self.financial_statistics.borrow_mut();
+        financial_statistics.total_paid_receivable_wei =
+            (financial_statistics.total_paid_receivable_wei + sum_up(new_payments))
+                .saturating_sub(sum_up(orphaned_payments));
     }
 
     pub fn scan_for_delinquencies(&self, timestamp: SystemTime, logger: &Logger) {
@@ -951,8 +1220,24 @@ impl ReceivableScanner {
             .new_delinquencies(timestamp, self.common.payment_thresholds.as_ref())
             .into_iter()
             .for_each(|account| {
-                self.banned_dao.ban(&account.wallet);
                 let (balance_str_wei, age) = balance_and_age(timestamp, &account);
+                let reason = format!(
+                    "Delinquent with balance {} gwei at age {} sec",
+                    balance_str_wei,
+                    age.as_secs()
+                );
+                if self.banned_dao.ban(
+                    &account.wallet,
+                    timestamp,
+                    Some(reason.clone()),
+                    BanOrigin::Automatic,
+                ) {
+                    self.ban_changes.borrow_mut().push(UiBanChangedBroadcast {
+                        wallet: account.wallet.to_string(),
+                        banned: true,
+                        reason_opt: Some(reason),
+                    })
+                }
                 info!(
                     logger,
                     "Wallet {} (balance: {} gwei, age: {} sec) banned for delinquency",
@@ -968,7 +1253,13 @@ impl ReceivableScanner {
             .paid_delinquencies(self.common.payment_thresholds.as_ref())
             .into_iter()
             .for_each(|account| {
-                self.banned_dao.unban(&account.wallet);
+                if self.banned_dao.unban(&account.wallet) {
+                    self.ban_changes.borrow_mut().push(UiBanChangedBroadcast {
+                        wallet: account.wallet.to_string(),
+                        banned: false,
+                        reason_opt: None,
+                    })
+                }
                 let (balance_str_wei, age) = balance_and_age(timestamp, &account);
                 info!(
                     logger,
@@ -1099,11 +1390,14 @@ impl<T: Default + 'static> ScanScheduler for PeriodicalScanScheduler<T> {
 }
 #[cfg(test)]
 mod tests {
+    use crate::accountant::db_access_objects::banned_dao::BanOrigin;
+    use crate::accountant::db_access_objects::ledger_dao::{LedgerEntry, LedgerEntryType};
     use crate::accountant::db_access_objects::payable_dao::{PayableAccount, PayableDaoError};
     use crate::accountant::db_access_objects::pending_payable_dao::{
-        PendingPayable, PendingPayableDaoError, TransactionHashes,
+        PendingPayable, PendingPayableDaoError, PendingPayableReplacement, TransactionHashes,
     };
     use crate::accountant::db_access_objects::utils::{from_time_t, to_time_t};
+    use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::TransactionFeeModel;
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::QualifiedPayablesMessage;
     use crate::accountant::scanners::scanners_utils::payable_scanner_utils::PendingPayableMetadata;
     use crate::accountant::scanners::scanners_utils::pending_payable_scanner_utils::PendingPayableScanReport;
@@ -1114,15 +1408,17 @@ mod tests {
     };
     use crate::accountant::test_utils::{
         make_custom_payment_thresholds, make_payable_account, make_payables,
-        make_pending_payable_fingerprint, make_receivable_account, BannedDaoFactoryMock,
-        BannedDaoMock, ConfigDaoFactoryMock, PayableDaoFactoryMock, PayableDaoMock,
-        PayableScannerBuilder, PayableThresholdsGaugeMock, PendingPayableDaoFactoryMock,
-        PendingPayableDaoMock, PendingPayableScannerBuilder, ReceivableDaoFactoryMock,
-        ReceivableDaoMock, ReceivableScannerBuilder,
+        make_pending_payable_fingerprint, make_receivable_account, make_resubmission_data,
+        BannedDaoFactoryMock, BannedDaoMock, ConfigDaoFactoryMock, LedgerDaoFactoryMock,
+        LedgerDaoMock, PayableDaoFactoryMock, PayableDaoMock, PayableScannerBuilder,
+        PayableThresholdsGaugeMock, PendingPayableDaoFactoryMock, PendingPayableDaoMock,
+        PendingPayableScannerBuilder, ReceivableDaoFactoryMock, ReceivableDaoMock,
+        ReceivableScannerBuilder, ServiceReceiptDaoFactoryMock,
     };
     use crate::accountant::{
         gwei_to_wei, PendingPayableId, ReceivedPayments, ReportTransactionReceipts,
-        RequestTransactionReceipts, SentPayables, DEFAULT_PENDING_TOO_LONG_SEC,
+        RequestTransactionReceipts, SentPayables, StuckPayables, DEFAULT_CONFIRMATION_DEPTH,
+        DEFAULT_PENDING_TOO_LONG_SEC,
     };
     use crate::blockchain::blockchain_bridge::{PendingPayableFingerprint, RetrieveTransactions};
     use crate::blockchain::blockchain_interface::data_structures::errors::PayableTransactionError;
@@ -1134,6 +1430,7 @@ mod tests {
     use crate::database::test_utils::transaction_wrapper_mock::TransactionInnerWrapperMockBuilder;
     use crate::db_config::mocks::ConfigDaoMock;
     use crate::db_config::persistent_configuration::PersistentConfigError;
+    use crate::metrics::NodeMetrics;
     use crate::sub_lib::accountant::{
         DaoFactories, FinancialStatistics, PaymentThresholds, ScanIntervals,
         DEFAULT_PAYMENT_THRESHOLDS,
@@ -1169,12 +1466,16 @@ mod tests {
         let receivable_dao = ReceivableDaoMock::new();
         let receivable_dao_factory = ReceivableDaoFactoryMock::new().make_result(receivable_dao);
         let banned_dao_factory = BannedDaoFactoryMock::new().make_result(BannedDaoMock::new());
+        let ledger_dao_factory = LedgerDaoFactoryMock::new()
+            .make_result(LedgerDaoMock::new())
+            .make_result(LedgerDaoMock::new());
         let set_params_arc = Arc::new(Mutex::new(vec![]));
         let config_dao_mock = ConfigDaoMock::new()
             .set_params(&set_params_arc)
             .set_result(Ok(()));
         let config_dao_factory = ConfigDaoFactoryMock::new().make_result(config_dao_mock);
         let when_pending_too_long_sec = 1234;
+        let when_pending_stuck_sec = 321;
         let financial_statistics = FinancialStatistics {
             total_paid_payable_wei: 1,
             total_paid_receivable_wei: 2,
@@ -1189,11 +1490,17 @@ mod tests {
                 pending_payable_dao_factory: Box::new(pending_payable_dao_factory),
                 receivable_dao_factory: Box::new(receivable_dao_factory),
                 banned_dao_factory: Box::new(banned_dao_factory),
+                ledger_dao_factory: Box::new(ledger_dao_factory),
+                service_receipt_dao_factory: Box::new(ServiceReceiptDaoFactoryMock::new()),
                 config_dao_factory: Box::new(config_dao_factory),
             },
             Rc::clone(&payment_thresholds_rc),
             when_pending_too_long_sec,
+            when_pending_stuck_sec,
+            24,
             Rc::new(RefCell::new(financial_statistics.clone())),
+            Rc::new(RefCell::new(vec![])),
+            Arc::new(NodeMetrics::default()),
         );
 
         let payable_scanner = scanners
@@ -1220,6 +1527,10 @@ mod tests {
             pending_payable_scanner.when_pending_too_long_sec,
             when_pending_too_long_sec
         );
+        assert_eq!(
+            pending_payable_scanner.when_pending_stuck_sec,
+            when_pending_stuck_sec
+        );
         assert_eq!(
             *pending_payable_scanner.financial_statistics.borrow(),
             financial_statistics
@@ -1232,6 +1543,7 @@ mod tests {
             pending_payable_scanner.common.initiated_at_opt.is_some(),
             false
         );
+        assert_eq!(receivable_scanner.confirmation_depth, 24);
         assert_eq!(
             *receivable_scanner.financial_statistics.borrow(),
             financial_statistics
@@ -1504,6 +1816,44 @@ mod tests {
         assert!(nonexistent.is_empty())
     }
 
+    #[test]
+    fn payables_of_a_multisend_transaction_are_paired_with_their_fingerprints_in_order() {
+        let wallet_1 = make_wallet("abc");
+        let wallet_2 = make_wallet("def");
+        let wallet_3 = make_wallet("ghi");
+        let multisend_hash = make_tx_hash(123);
+        let lone_hash = make_tx_hash(345);
+        let pending_payables_owned = vec![
+            PendingPayable::new(wallet_1.clone(), multisend_hash),
+            PendingPayable::new(wallet_2.clone(), lone_hash),
+            PendingPayable::new(wallet_3.clone(), multisend_hash),
+        ];
+        let pending_payables_ref = pending_payables_owned
+            .iter()
+            .collect::<Vec<&PendingPayable>>();
+        let pending_payable_dao =
+            PendingPayableDaoMock::new().fingerprints_rowids_result(TransactionHashes {
+                rowid_results: vec![(4, multisend_hash), (5, lone_hash), (6, multisend_hash)],
+                no_rowid_results: vec![],
+            });
+        let subject = PayableScannerBuilder::new()
+            .pending_payable_dao(pending_payable_dao)
+            .build();
+
+        let (existent, nonexistent) =
+            subject.separate_existent_and_nonexistent_fingerprints(&pending_payables_ref);
+
+        assert_eq!(
+            existent,
+            vec![
+                PendingPayableMetadata::new(&wallet_1, multisend_hash, Some(4)),
+                PendingPayableMetadata::new(&wallet_2, lone_hash, Some(5)),
+                PendingPayableMetadata::new(&wallet_3, multisend_hash, Some(6)),
+            ]
+        );
+        assert!(nonexistent.is_empty())
+    }
+
     struct TestingMismatchedDataAboutPendingPayables {
         pending_payables: Vec<PendingPayable>,
         common_hash_1: H256,
@@ -2227,6 +2577,8 @@ mod tests {
             attempt: 1,
             amount: 4444,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let payable_fingerprint_2 = PendingPayableFingerprint {
             rowid: 550,
@@ -2235,6 +2587,8 @@ mod tests {
             attempt: 1,
             amount: 7999,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let fingerprints = vec![payable_fingerprint_1, payable_fingerprint_2];
         let pending_payable_dao = PendingPayableDaoMock::new()
@@ -2257,7 +2611,8 @@ mod tests {
             result,
             Ok(RequestTransactionReceipts {
                 pending_payable: fingerprints,
-                response_skeleton_opt: None
+                response_skeleton_opt: None,
+                stuck_payables_opt: None,
             })
         );
         TestLogHandler::new().assert_logs_match_in_order(vec![
@@ -2280,6 +2635,8 @@ mod tests {
                 attempt: 1,
                 amount: 1_000_000,
                 process_error: None,
+                replaced_hashes: vec![],
+                resubmission_data_opt: None,
             }]);
         let mut subject = PendingPayableScannerBuilder::new()
             .pending_payable_dao(pending_payable_dao)
@@ -2312,6 +2669,64 @@ mod tests {
         assert_eq!(is_scan_running, false);
     }
 
+    #[test]
+    fn pending_payable_scanner_flags_stuck_payables_for_replacement() {
+        init_test_logging();
+        let test_name = "pending_payable_scanner_flags_stuck_payables_for_replacement";
+        let consuming_wallet = make_paying_wallet(b"consuming wallet");
+        let now = SystemTime::now();
+        let when_pending_stuck_sec = 600;
+        let sent_sec_ago = |sec: u64| now.checked_sub(Duration::from_secs(sec)).unwrap();
+        let mut without_resubmission_data = make_pending_payable_fingerprint();
+        without_resubmission_data.rowid = 1;
+        without_resubmission_data.timestamp = sent_sec_ago(5_000);
+        let mut stuck = make_pending_payable_fingerprint();
+        stuck.rowid = 2;
+        stuck.timestamp = sent_sec_ago(601);
+        stuck.resubmission_data_opt = Some(make_resubmission_data(2));
+        let mut replaced_recently = make_pending_payable_fingerprint();
+        replaced_recently.rowid = 3;
+        replaced_recently.timestamp = sent_sec_ago(1_199);
+        replaced_recently.replaced_hashes = vec![make_tx_hash(333)];
+        replaced_recently.resubmission_data_opt = Some(make_resubmission_data(3));
+        let mut stuck_after_replacement = make_pending_payable_fingerprint();
+        stuck_after_replacement.rowid = 4;
+        stuck_after_replacement.timestamp = sent_sec_ago(1_201);
+        stuck_after_replacement.replaced_hashes = vec![make_tx_hash(444)];
+        stuck_after_replacement.resubmission_data_opt = Some(make_resubmission_data(4));
+        let fingerprints = vec![
+            without_resubmission_data,
+            stuck,
+            replaced_recently,
+            stuck_after_replacement,
+        ];
+        let pending_payable_dao = PendingPayableDaoMock::new()
+            .return_all_errorless_fingerprints_result(fingerprints.clone());
+        let mut subject = PendingPayableScannerBuilder::new()
+            .pending_payable_dao(pending_payable_dao)
+            .when_pending_stuck_sec(when_pending_stuck_sec)
+            .build();
+
+        let result =
+            subject.begin_scan(consuming_wallet.clone(), now, None, &Logger::new(test_name));
+
+        assert_eq!(
+            result,
+            Ok(RequestTransactionReceipts {
+                pending_payable: fingerprints,
+                stuck_payables_opt: Some(StuckPayables {
+                    consuming_wallet,
+                    rowids: vec![2, 4]
+                }),
+                response_skeleton_opt: None,
+            })
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "DEBUG: {test_name}: Pending payables at rowids 2, 4 are going to be replaced \
+            unless mined by now"
+        ));
+    }
+
     fn assert_interpreting_none_status_for_pending_payable(
         test_name: &str,
         when_pending_too_long_sec: u64,
@@ -2332,6 +2747,8 @@ mod tests {
             attempt: 1,
             amount: 123,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let logger = Logger::new(test_name);
         let scan_report = PendingPayableScanReport::default();
@@ -2497,6 +2914,8 @@ mod tests {
             attempt: 5,
             amount: 2222,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let logger = Logger::new(test_name);
         let scan_report = PendingPayableScanReport::default();
@@ -2534,10 +2953,13 @@ mod tests {
             attempt: 3,
             amount: 111,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let msg = ReportTransactionReceipts {
             fingerprints_with_receipts: vec![(tx_receipt_opt, fingerprint.clone())],
             response_skeleton_opt: None,
+            replacements: vec![],
         };
 
         let result = subject.handle_receipts_for_pending_transactions(msg, &Logger::new(test_name));
@@ -2734,6 +3156,8 @@ mod tests {
             attempt: 1,
             amount: 4567,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let pending_payable_fingerprint_2 = PendingPayableFingerprint {
             rowid: rowid_2,
@@ -2742,6 +3166,8 @@ mod tests {
             attempt: 1,
             amount: 5555,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
 
         subject.confirm_transactions(
@@ -2779,6 +3205,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn confirm_transactions_records_payments_sent_in_the_ledger() {
+        init_test_logging();
+        let test_name = "confirm_transactions_records_payments_sent_in_the_ledger";
+        let record_params_arc = Arc::new(Mutex::new(vec![]));
+        let payable_dao = PayableDaoMock::default().transactions_confirmed_result(Ok(()));
+        let pending_payable_dao =
+            PendingPayableDaoMock::default().delete_fingerprints_result(Ok(()));
+        let ledger_dao = LedgerDaoMock::new().record_params(&record_params_arc);
+        let mut subject = PendingPayableScannerBuilder::new()
+            .payable_dao(payable_dao)
+            .pending_payable_dao(pending_payable_dao)
+            .ledger_dao(ledger_dao)
+            .build();
+        let mut remembered = make_pending_payable_fingerprint();
+        remembered.hash = make_tx_hash(0x123);
+        remembered.amount = 4567;
+        remembered.resubmission_data_opt = Some(make_resubmission_data(5));
+        let mut legacy = make_pending_payable_fingerprint();
+        legacy.hash = make_tx_hash(0x567);
+        legacy.resubmission_data_opt = None;
+        let before = SystemTime::now();
+
+        subject.confirm_transactions(vec![remembered, legacy], &Logger::new(test_name));
+
+        let after = SystemTime::now();
+        let mut record_params = record_params_arc.lock().unwrap();
+        let mut entries = record_params.remove(0);
+        assert!(record_params.is_empty());
+        let entry = entries.remove(0);
+        assert!(entries.is_empty());
+        assert!(before <= entry.timestamp && entry.timestamp <= after);
+        assert_eq!(
+            entry,
+            LedgerEntry::payment(
+                entry.timestamp,
+                make_wallet("recipient_5"),
+                LedgerEntryType::PaymentSent,
+                4567,
+                make_tx_hash(0x123)
+            )
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "DEBUG: {test_name}: Payment \
+            0x0000000000000000000000000000000000000000000000000000000000000567 stays out of the \
+            ledger; its record predates keeping the recipient"
+        ));
+    }
+
     #[test]
     #[should_panic(
         expected = "Unable to cast confirmed pending payables 0x0000000000000000000000000000000000000000000\
@@ -2811,6 +3286,8 @@ mod tests {
             attempt: 1,
             amount: 5478,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let fingerprint_2 = PendingPayableFingerprint {
             rowid: 6,
@@ -2819,6 +3296,8 @@ mod tests {
             attempt: 1,
             amount: 6543,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let payable_dao = PayableDaoMock::default().transactions_confirmed_result(Ok(()));
         let pending_payable_dao =
@@ -2864,6 +3343,8 @@ mod tests {
             attempt: 2,
             amount: 444,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let transaction_hash_2 = make_tx_hash(1234);
         let mut transaction_receipt_2 = TransactionReceipt::default();
@@ -2876,6 +3357,8 @@ mod tests {
             attempt: 15,
             amount: 1212,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let msg = ReportTransactionReceipts {
             fingerprints_with_receipts: vec![
@@ -2883,6 +3366,7 @@ mod tests {
                 (Some(transaction_receipt_2), fingerprint_2.clone()),
             ],
             response_skeleton_opt: None,
+            replacements: vec![],
         };
         subject.mark_as_started(SystemTime::now());
 
@@ -2913,6 +3397,7 @@ mod tests {
         let msg = ReportTransactionReceipts {
             fingerprints_with_receipts: vec![],
             response_skeleton_opt: None,
+            replacements: vec![],
         };
         subject.mark_as_started(SystemTime::now());
 
@@ -2930,6 +3415,129 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn pending_payable_scanner_records_replacements_of_stuck_payables() {
+        init_test_logging();
+        let test_name = "pending_payable_scanner_records_replacements_of_stuck_payables";
+        let record_replacements_params_arc = Arc::new(Mutex::new(vec![]));
+        let pending_payable_dao = PendingPayableDaoMock::new()
+            .increment_scan_attempts_result(Ok(()))
+            .record_replacements_params(&record_replacements_params_arc)
+            .record_replacements_result(Ok(()));
+        let mut subject = PendingPayableScannerBuilder::new()
+            .pending_payable_dao(pending_payable_dao)
+            .build();
+        let mut fingerprint = make_pending_payable_fingerprint();
+        fingerprint.timestamp = SystemTime::now();
+        fingerprint.hash = make_tx_hash(0x1c8);
+        let replacement = PendingPayableReplacement {
+            rowid: fingerprint.rowid,
+            hash: make_tx_hash(0x2d9),
+            replaced_hashes: vec![make_tx_hash(0x1c8)],
+            fee_model: TransactionFeeModel::Legacy {
+                gas_price_wei: 150_000_000_000,
+            },
+        };
+        let msg = ReportTransactionReceipts {
+            fingerprints_with_receipts: vec![(None, fingerprint)],
+            replacements: vec![replacement.clone()],
+            response_skeleton_opt: None,
+        };
+        subject.mark_as_started(SystemTime::now());
+
+        let message_opt = subject.finish_scan(msg, &Logger::new(test_name));
+
+        assert_eq!(message_opt, None);
+        let record_replacements_params = record_replacements_params_arc.lock().unwrap();
+        assert_eq!(*record_replacements_params, vec![vec![replacement]]);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {test_name}: Stuck transaction 0x00000000000000000000000000000000000000000000\
+            000000000000000001c8 replaced by 0x0000000000000000000000000000000000000000000000000000\
+            0000000002d9 with fees Legacy {{ gas_price_wei: 150000000000 }}"
+        ));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Unable to record replacements of stuck transactions by 0x000000000000000000000000\
+        00000000000000000000000000000000000002d9 due to UpdateFailed(\"Oh no\")"
+    )]
+    fn pending_payable_scanner_panics_on_failure_to_record_replacements() {
+        let pending_payable_dao = PendingPayableDaoMock::new()
+            .increment_scan_attempts_result(Ok(()))
+            .record_replacements_result(Err(PendingPayableDaoError::UpdateFailed(
+                "Oh no".to_string(),
+            )));
+        let mut subject = PendingPayableScannerBuilder::new()
+            .pending_payable_dao(pending_payable_dao)
+            .build();
+        let mut fingerprint = make_pending_payable_fingerprint();
+        fingerprint.timestamp = SystemTime::now();
+        let replacement = PendingPayableReplacement {
+            rowid: fingerprint.rowid,
+            hash: make_tx_hash(0x2d9),
+            replaced_hashes: vec![fingerprint.hash],
+            fee_model: TransactionFeeModel::Legacy {
+                gas_price_wei: 150_000_000_000,
+            },
+        };
+        let msg = ReportTransactionReceipts {
+            fingerprints_with_receipts: vec![(None, fingerprint)],
+            replacements: vec![replacement],
+            response_skeleton_opt: None,
+        };
+        subject.mark_as_started(SystemTime::now());
+
+        let _ = subject.finish_scan(msg, &Logger::new("test"));
+    }
+
+    #[test]
+    fn pending_payable_scanner_confirms_payable_mined_by_its_replaced_transaction() {
+        init_test_logging();
+        let test_name =
+            "pending_payable_scanner_confirms_payable_mined_by_its_replaced_transaction";
+        let transactions_confirmed_params_arc = Arc::new(Mutex::new(vec![]));
+        let payable_dao = PayableDaoMock::new()
+            .transactions_confirmed_params(&transactions_confirmed_params_arc)
+            .transactions_confirmed_result(Ok(()));
+        let pending_payable_dao = PendingPayableDaoMock::new().delete_fingerprints_result(Ok(()));
+        let mut subject = PendingPayableScannerBuilder::new()
+            .payable_dao(payable_dao)
+            .pending_payable_dao(pending_payable_dao)
+            .build();
+        let original_hash = make_tx_hash(0x4b1);
+        let latest_hash = make_tx_hash(0x4b3);
+        let mut receipt = TransactionReceipt::default();
+        receipt.transaction_hash = original_hash;
+        receipt.status = Some(U64::from(1));
+        let mut fingerprint = make_pending_payable_fingerprint();
+        fingerprint.hash = latest_hash;
+        fingerprint.replaced_hashes = vec![original_hash, make_tx_hash(0x4b2)];
+        let msg = ReportTransactionReceipts {
+            fingerprints_with_receipts: vec![(Some(receipt), fingerprint.clone())],
+            replacements: vec![],
+            response_skeleton_opt: None,
+        };
+        subject.mark_as_started(SystemTime::now());
+
+        let _ = subject.finish_scan(msg, &Logger::new(test_name));
+
+        let transactions_confirmed_params = transactions_confirmed_params_arc.lock().unwrap();
+        let expected_fingerprint = PendingPayableFingerprint {
+            hash: original_hash,
+            ..fingerprint
+        };
+        assert_eq!(
+            *transactions_confirmed_params,
+            vec![vec![expected_fingerprint]]
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {test_name}: Receipt for pending payable {:?} belongs to its replaced \
+            transaction {:?}",
+            latest_hash, original_hash
+        ));
+    }
+
     #[test]
     fn receivable_scanner_can_initiate_a_scan() {
         init_test_logging();
@@ -2956,6 +3564,7 @@ mod tests {
             result,
             Ok(RetrieveTransactions {
                 recipient: earning_wallet.clone(),
+                confirmation_depth: DEFAULT_CONFIRMATION_DEPTH,
                 response_skeleton_opt: None
             })
         );
@@ -3008,9 +3617,12 @@ mod tests {
         let payment_thresholds = make_custom_payment_thresholds();
         let earning_wallet = make_wallet("earning");
         let banned_dao = BannedDaoMock::new()
-            .ban_list_result(vec![])
             .ban_parameters(&ban_parameters_arc)
-            .unban_parameters(&unban_parameters_arc);
+            .ban_result(true)
+            .ban_result(true)
+            .unban_parameters(&unban_parameters_arc)
+            .unban_result(true)
+            .unban_result(true);
         let mut receivable_scanner = ReceivableScannerBuilder::new()
             .receivable_dao(receivable_dao)
             .banned_dao(banned_dao)
@@ -3025,6 +3637,7 @@ mod tests {
             result,
             Ok(RetrieveTransactions {
                 recipient: earning_wallet,
+                confirmation_depth: DEFAULT_CONFIRMATION_DEPTH,
                 response_skeleton_opt: None
             })
         );
@@ -3037,13 +3650,40 @@ mod tests {
         assert_eq!(paid_delinquencies_parameters.len(), 1);
         assert_eq!(payment_thresholds, paid_delinquencies_parameters[0]);
         let ban_parameters = ban_parameters_arc.lock().unwrap();
-        assert!(ban_parameters.contains(&newly_banned_1.wallet));
-        assert!(ban_parameters.contains(&newly_banned_2.wallet));
-        assert_eq!(2, ban_parameters.len());
+        let banned_wallets = ban_parameters
+            .iter()
+            .map(|(wallet, timestamp, _, origin)| {
+                assert_eq!(*timestamp, now);
+                assert_eq!(*origin, BanOrigin::Automatic);
+                wallet.clone()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            banned_wallets,
+            vec![newly_banned_1.wallet.clone(), newly_banned_2.wallet.clone()]
+        );
+        let reason_regex =
+            Regex::new("^Delinquent with balance 1,234 gwei at age \\d+ sec$").unwrap();
+        assert!(reason_regex.is_match(ban_parameters[0].2.as_ref().unwrap()));
         let unban_parameters = unban_parameters_arc.lock().unwrap();
         assert!(unban_parameters.contains(&newly_unbanned_1.wallet));
         assert!(unban_parameters.contains(&newly_unbanned_2.wallet));
         assert_eq!(2, unban_parameters.len());
+        let ban_changes = receivable_scanner.ban_changes.borrow();
+        assert_eq!(
+            ban_changes
+                .iter()
+                .map(|change| (change.wallet.clone(), change.banned))
+                .collect::<Vec<_>>(),
+            vec![
+                (newly_banned_1.wallet.to_string(), true),
+                (newly_banned_2.wallet.to_string(), true),
+                (newly_unbanned_1.wallet.to_string(), false),
+                (newly_unbanned_2.wallet.to_string(), false),
+            ]
+        );
+        assert_eq!(ban_changes[0].reason_opt, ban_parameters[0].2);
+        assert_eq!(ban_changes[2].reason_opt, None);
         let tlh = TestLogHandler::new();
         tlh.exists_log_matching(
             "INFO: DELINQUENCY_TEST: Wallet 0x00000000000000000077616c6c65743132333464 \
@@ -3063,21 +3703,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn receivable_scanner_broadcasts_only_bans_that_actually_changed() {
+        let already_banned = make_receivable_account(1234, true);
+        let never_banned = make_receivable_account(3456, false);
+        let receivable_dao = ReceivableDaoMock::new()
+            .new_delinquencies_result(vec![already_banned])
+            .paid_delinquencies_result(vec![never_banned]);
+        let banned_dao = BannedDaoMock::new().ban_result(false).unban_result(false);
+        let mut receivable_scanner = ReceivableScannerBuilder::new()
+            .receivable_dao(receivable_dao)
+            .banned_dao(banned_dao)
+            .build();
+
+        let _ = receivable_scanner.begin_scan(
+            make_wallet("earning"),
+            SystemTime::now(),
+            None,
+            &Logger::new("test"),
+        );
+
+        assert!(receivable_scanner.ban_changes.borrow().is_empty());
+    }
+
     #[test]
     fn receivable_scanner_handles_no_new_payments_found() {
         init_test_logging();
         let test_name = "receivable_scanner_aborts_scan_if_no_payments_were_supplied";
         let set_start_block_params_arc = Arc::new(Mutex::new(vec![]));
+        let forget_credited_transactions_before_params_arc = Arc::new(Mutex::new(vec![]));
         let new_start_block = 4321;
         let persistent_config = PersistentConfigurationMock::new()
             .set_start_block_params(&set_start_block_params_arc)
             .set_start_block_result(Ok(()));
+        let receivable_dao = ReceivableDaoMock::new()
+            .credited_transactions_since_result(vec![])
+            .forget_credited_transactions_before_params(
+                &forget_credited_transactions_before_params_arc,
+            );
         let mut subject = ReceivableScannerBuilder::new()
+            .receivable_dao(receivable_dao)
             .persistent_configuration(persistent_config)
+            .confirmation_depth(12)
             .build();
         let msg = ReceivedPayments {
             timestamp: SystemTime::now(),
             payments: vec![],
+            scanned_from_block: new_start_block - 12,
             new_start_block,
             response_skeleton_opt: None,
         };
@@ -3087,6 +3759,11 @@ mod tests {
         assert_eq!(message_opt, None);
         let set_start_block_params = set_start_block_params_arc.lock().unwrap();
         assert_eq!(*set_start_block_params, vec![4321]);
+        let forget_credited_transactions_before_params =
+            forget_credited_transactions_before_params_arc
+                .lock()
+                .unwrap();
+        assert_eq!(*forget_credited_transactions_before_params, vec![4309]);
         TestLogHandler::new().exists_log_containing(&format!(
             "INFO: {test_name}: No newly received payments were detected during the scanning process."
         ));
@@ -3102,12 +3779,15 @@ mod tests {
         let persistent_config = PersistentConfigurationMock::new().set_start_block_result(Err(
             PersistentConfigError::UninterpretableValue("Illiterate database manager".to_string()),
         ));
+        let receivable_dao = ReceivableDaoMock::new().credited_transactions_since_result(vec![]);
         let mut subject = ReceivableScannerBuilder::new()
+            .receivable_dao(receivable_dao)
             .persistent_configuration(persistent_config)
             .build();
         let msg = ReceivedPayments {
             timestamp: now,
             payments: vec![],
+            scanned_from_block: 6697,
             new_start_block: 6709,
             response_skeleton_opt: None,
         };
@@ -3135,6 +3815,7 @@ mod tests {
             .set_start_block_from_txn_params(&set_start_block_from_txn_params_arc)
             .set_start_block_from_txn_result(Ok(()));
         let receivable_dao = ReceivableDaoMock::new()
+            .credited_transactions_since_result(vec![])
             .more_money_received_params(&more_money_received_params_arc)
             .more_money_received_result(transaction);
         let mut subject = ReceivableScannerBuilder::new()
@@ -3147,11 +3828,15 @@ mod tests {
         let receivables = vec![
             BlockchainTransaction {
                 block_number: 4578910,
+                block_hash: make_tx_hash(1001),
+                transaction_hash: make_tx_hash(1),
                 from: make_wallet("wallet_1"),
                 wei_amount: 45_780,
             },
             BlockchainTransaction {
                 block_number: 4569898,
+                block_hash: make_tx_hash(1002),
+                transaction_hash: make_tx_hash(2),
                 from: make_wallet("wallet_2"),
                 wei_amount: 3_333_345,
             },
@@ -3159,6 +3844,7 @@ mod tests {
         let msg = ReceivedPayments {
             timestamp: now,
             payments: receivables.clone(),
+            scanned_from_block: 7890111,
             new_start_block: 7890123,
             response_skeleton_opt: None,
         };
@@ -3174,7 +3860,10 @@ mod tests {
         assert_eq!(subject.scan_started_at(), None);
         assert_eq!(total_paid_receivable, 2_222_123_123 + 45_780 + 3_333_345);
         let more_money_received_params = more_money_received_params_arc.lock().unwrap();
-        assert_eq!(*more_money_received_params, vec![(now, receivables)]);
+        assert_eq!(
+            *more_money_received_params,
+            vec![(now, receivables, vec![])]
+        );
         let set_by_guest_transaction_params = set_start_block_from_txn_params_arc.lock().unwrap();
         assert_eq!(
             *set_by_guest_transaction_params,
@@ -3187,6 +3876,191 @@ mod tests {
         );
     }
 
+    #[test]
+    fn receivable_scanner_reverses_orphaned_payments_and_credits_only_new_ones() {
+        init_test_logging();
+        let test_name = "receivable_scanner_reverses_orphaned_payments_and_credits_only_new_ones";
+        let now = SystemTime::now();
+        let credited_transactions_since_params_arc = Arc::new(Mutex::new(vec![]));
+        let more_money_received_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_start_block_from_txn_params_arc = Arc::new(Mutex::new(vec![]));
+        let transaction_id = ArbitraryIdStamp::new();
+        let txn_inner_builder = TransactionInnerWrapperMockBuilder::default()
+            .commit_result(Ok(()))
+            .set_arbitrary_id_stamp(transaction_id);
+        let transaction = TransactionSafeWrapper::new_with_builder(txn_inner_builder);
+        let still_canonical = BlockchainTransaction {
+            block_number: 7890100,
+            block_hash: make_tx_hash(1100),
+            transaction_hash: make_tx_hash(100),
+            from: make_wallet("wallet_1"),
+            wei_amount: 1_000,
+        };
+        let orphaned = BlockchainTransaction {
+            block_number: 7890105,
+            block_hash: make_tx_hash(1105),
+            transaction_hash: make_tx_hash(105),
+            from: make_wallet("wallet_2"),
+            wei_amount: 20_000,
+        };
+        let remined_elsewhere_before = BlockchainTransaction {
+            block_number: 7890106,
+            block_hash: make_tx_hash(1106),
+            transaction_hash: make_tx_hash(106),
+            from: make_wallet("wallet_3"),
+            wei_amount: 300_000,
+        };
+        let remined_elsewhere_after = BlockchainTransaction {
+            block_number: 7890107,
+            block_hash: make_tx_hash(2107),
+            ..remined_elsewhere_before.clone()
+        };
+        let brand_new = BlockchainTransaction {
+            block_number: 7890110,
+            block_hash: make_tx_hash(1110),
+            transaction_hash: make_tx_hash(110),
+            from: make_wallet("wallet_4"),
+            wei_amount: 4_000_000,
+        };
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_start_block_from_txn_params(&set_start_block_from_txn_params_arc)
+            .set_start_block_from_txn_result(Ok(()));
+        let receivable_dao = ReceivableDaoMock::new()
+            .credited_transactions_since_params(&credited_transactions_since_params_arc)
+            .credited_transactions_since_result(vec![
+                still_canonical.clone(),
+                orphaned.clone(),
+                remined_elsewhere_before.clone(),
+            ])
+            .more_money_received_params(&more_money_received_params_arc)
+            .more_money_received_result(transaction);
+        let record_params_arc = Arc::new(Mutex::new(vec![]));
+        let ledger_dao = LedgerDaoMock::new().record_params(&record_params_arc);
+        let mut subject = ReceivableScannerBuilder::new()
+            .receivable_dao(receivable_dao)
+            .ledger_dao(ledger_dao)
+            .persistent_configuration(persistent_config)
+            .build();
+        subject
+            .financial_statistics
+            .borrow_mut()
+            .total_paid_receivable_wei = 5_000_000;
+        let msg = ReceivedPayments {
+            timestamp: now,
+            payments: vec![
+                still_canonical,
+                remined_elsewhere_after.clone(),
+                brand_new.clone(),
+            ],
+            scanned_from_block: 7890099,
+            new_start_block: 7890111,
+            response_skeleton_opt: None,
+        };
+        subject.mark_as_started(SystemTime::now());
+
+        let message_opt = subject.finish_scan(msg, &Logger::new(test_name));
+
+        assert_eq!(message_opt, None);
+        let credited_transactions_since_params =
+            credited_transactions_since_params_arc.lock().unwrap();
+        assert_eq!(*credited_transactions_since_params, vec![7890099]);
+        let more_money_received_params = more_money_received_params_arc.lock().unwrap();
+        assert_eq!(
+            *more_money_received_params,
+            vec![(
+                now,
+                vec![remined_elsewhere_after.clone(), brand_new.clone()],
+                vec![orphaned.clone(), remined_elsewhere_before.clone()]
+            )]
+        );
+        let set_start_block_from_txn_params = set_start_block_from_txn_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_start_block_from_txn_params,
+            vec![(7890111, transaction_id)]
+        );
+        assert_eq!(
+            subject
+                .financial_statistics
+                .borrow()
+                .total_paid_receivable_wei,
+            5_000_000 + 300_000 + 4_000_000 - 20_000 - 300_000
+        );
+        let record_params = record_params_arc.lock().unwrap();
+        let ledger_entry = |payment: &BlockchainTransaction, entry_type| {
+            LedgerEntry::payment(
+                now,
+                payment.from.clone(),
+                entry_type,
+                payment.wei_amount,
+                payment.transaction_hash,
+            )
+        };
+        assert_eq!(
+            *record_params,
+            vec![vec![
+                ledger_entry(&remined_elsewhere_after, LedgerEntryType::PaymentReceived),
+                ledger_entry(&brand_new, LedgerEntryType::PaymentReceived),
+                ledger_entry(&orphaned, LedgerEntryType::PaymentReversed),
+                ledger_entry(&remined_elsewhere_before, LedgerEntryType::PaymentReversed),
+            ]]
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Payment 0x0000000000000000000000000000000000000000000000000000000000000069 \
+            of 20,000 wei from {} in block 7890105 \
+            (0x0000000000000000000000000000000000000000000000000000000000000451) is no longer on \
+            the canonical chain; its credit is being reversed",
+            make_wallet("wallet_2")
+        ));
+    }
+
+    #[test]
+    fn receivable_scanner_does_not_credit_again_payments_that_are_still_canonical() {
+        init_test_logging();
+        let test_name =
+            "receivable_scanner_does_not_credit_again_payments_that_are_still_canonical";
+        let set_start_block_params_arc = Arc::new(Mutex::new(vec![]));
+        let credited_payment = BlockchainTransaction {
+            block_number: 4500,
+            block_hash: make_tx_hash(1500),
+            transaction_hash: make_tx_hash(500),
+            from: make_wallet("wallet"),
+            wei_amount: 1_000,
+        };
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_start_block_params(&set_start_block_params_arc)
+            .set_start_block_result(Ok(()));
+        // No more_money_received_result: more_money_received() must not be called
+        let receivable_dao = ReceivableDaoMock::new()
+            .credited_transactions_since_result(vec![credited_payment.clone()]);
+        let mut subject = ReceivableScannerBuilder::new()
+            .receivable_dao(receivable_dao)
+            .persistent_configuration(persistent_config)
+            .build();
+        let msg = ReceivedPayments {
+            timestamp: SystemTime::now(),
+            payments: vec![credited_payment],
+            scanned_from_block: 4490,
+            new_start_block: 4502,
+            response_skeleton_opt: None,
+        };
+
+        let message_opt = subject.finish_scan(msg, &Logger::new(test_name));
+
+        assert_eq!(message_opt, None);
+        let set_start_block_params = set_start_block_params_arc.lock().unwrap();
+        assert_eq!(*set_start_block_params, vec![4502]);
+        assert_eq!(
+            subject
+                .financial_statistics
+                .borrow()
+                .total_paid_receivable_wei,
+            0
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {test_name}: No newly received payments were detected during the scanning process."
+        ));
+    }
+
     #[test]
     #[should_panic(expected = "Attempt to set new start block to 7890123 failed due to: \
     DatabaseError(\"Fatigue\")")]
@@ -3199,19 +4073,24 @@ mod tests {
         let persistent_config = PersistentConfigurationMock::new().set_start_block_from_txn_result(
             Err(PersistentConfigError::DatabaseError("Fatigue".to_string())),
         );
-        let receivable_dao = ReceivableDaoMock::new().more_money_received_result(transaction);
+        let receivable_dao = ReceivableDaoMock::new()
+            .credited_transactions_since_result(vec![])
+            .more_money_received_result(transaction);
         let mut subject = ReceivableScannerBuilder::new()
             .receivable_dao(receivable_dao)
             .persistent_configuration(persistent_config)
             .build();
         let receivables = vec![BlockchainTransaction {
             block_number: 4578910,
+            block_hash: make_tx_hash(1003),
+            transaction_hash: make_tx_hash(3),
             from: make_wallet("abc"),
             wei_amount: 45_780,
         }];
         let msg = ReceivedPayments {
             timestamp: now,
             payments: receivables,
+            scanned_from_block: 7890111,
             new_start_block: 7890123,
             response_skeleton_opt: None,
         };
@@ -3242,19 +4121,24 @@ mod tests {
         let transaction = TransactionSafeWrapper::new_with_builder(txn_inner_builder);
         let persistent_config =
             PersistentConfigurationMock::new().set_start_block_from_txn_result(Ok(()));
-        let receivable_dao = ReceivableDaoMock::new().more_money_received_result(transaction);
+        let receivable_dao = ReceivableDaoMock::new()
+            .credited_transactions_since_result(vec![])
+            .more_money_received_result(transaction);
         let mut subject = ReceivableScannerBuilder::new()
             .receivable_dao(receivable_dao)
             .persistent_configuration(persistent_config)
             .build();
         let receivables = vec![BlockchainTransaction {
             block_number: 4578910,
+            block_hash: make_tx_hash(1004),
+            transaction_hash: make_tx_hash(4),
             from: make_wallet("abc"),
             wei_amount: 45_780,
         }];
         let msg = ReceivedPayments {
             timestamp: now,
             payments: receivables,
+            scanned_from_block: 7890111,
             new_start_block: 7890123,
             response_skeleton_opt: None,
         };
@@ -3269,7 +4153,9 @@ mod tests {
         init_test_logging();
         let test_name = "signal_scanner_completion_and_log_if_timestamp_is_correct";
         let logger = Logger::new(test_name);
-        let mut subject = ScannerCommon::new(Rc::new(make_custom_payment_thresholds()));
+        let metrics = Arc::new(NodeMetrics::default());
+        let mut subject =
+            ScannerCommon::new(Rc::new(make_custom_payment_thresholds()), metrics.clone());
         let start = from_time_t(1_000_000_000);
         let end = start.checked_add(Duration::from_millis(145)).unwrap();
         subject.initiated_at_opt = Some(start);
@@ -3279,6 +4165,11 @@ mod tests {
         TestLogHandler::new().exists_log_containing(&format!(
             "INFO: {test_name}: The Payables scan ended in 145ms."
         ));
+        let rendered = metrics.render();
+        assert!(rendered
+            .contains("\nmasq_node_scan_duration_seconds_sum{scan=\"payables\"} 0.145000\n"));
+        assert!(rendered
+            .contains("\nmasq_node_scans_total{scan=\"payables\",outcome=\"completed\"} 1\n"));
     }
 
     #[test]
@@ -3286,7 +4177,10 @@ mod tests {
         init_test_logging();
         let test_name = "signal_scanner_completion_and_log_if_timestamp_is_not_found";
         let logger = Logger::new(test_name);
-        let mut subject = ScannerCommon::new(Rc::new(make_custom_payment_thresholds()));
+        let mut subject = ScannerCommon::new(
+            Rc::new(make_custom_payment_thresholds()),
+            Arc::new(NodeMetrics::default()),
+        );
         subject.initiated_at_opt = None;
 
         subject.signal_scanner_completion(ScanType::Receivables, SystemTime::now(), &logger);
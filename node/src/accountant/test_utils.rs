@@ -2,18 +2,30 @@
 
 #![cfg(test)]
 
-use crate::accountant::db_access_objects::banned_dao::{BannedDao, BannedDaoFactory};
+use crate::accountant::db_access_objects::banned_dao::{
+    BanOrigin, BannedAccount, BannedDao, BannedDaoFactory,
+};
+use crate::accountant::db_access_objects::ledger_dao::{
+    LedgerDao, LedgerDaoFactory, LedgerEntry, LedgerQuery,
+};
 use crate::accountant::db_access_objects::payable_dao::{
     PayableAccount, PayableDao, PayableDaoError, PayableDaoFactory,
 };
 use crate::accountant::db_access_objects::pending_payable_dao::{
-    PendingPayableDao, PendingPayableDaoError, PendingPayableDaoFactory, TransactionHashes,
+    PendingPayableDao, PendingPayableDaoError, PendingPayableDaoFactory, PendingPayableReplacement,
+    TransactionHashes,
 };
 use crate::accountant::db_access_objects::receivable_dao::{
     ReceivableAccount, ReceivableDao, ReceivableDaoError, ReceivableDaoFactory,
 };
+use crate::accountant::db_access_objects::service_receipt_dao::{
+    ServiceReceiptDao, ServiceReceiptDaoFactory, StoredServiceReceipt,
+};
 use crate::accountant::db_access_objects::utils::{from_time_t, to_time_t, CustomQuery};
 use crate::accountant::payment_adjuster::{Adjustment, AnalysisError, PaymentAdjuster};
+use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::{
+    BlockchainAgent, TransactionFeeModel,
+};
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::{
     BlockchainAgentWithContextMessage, QualifiedPayablesMessage,
 };
@@ -26,28 +38,31 @@ use crate::accountant::scanners::{
     ReceivableScanner, ScanSchedulers, Scanner,
 };
 use crate::accountant::{
-    gwei_to_wei, Accountant, ResponseSkeleton, SentPayables, DEFAULT_PENDING_TOO_LONG_SEC,
+    gwei_to_wei, Accountant, ResponseSkeleton, SentPayables, DEFAULT_CONFIRMATION_DEPTH,
+    DEFAULT_PENDING_STUCK_SEC, DEFAULT_PENDING_TOO_LONG_SEC,
 };
-use crate::blockchain::blockchain_bridge::PendingPayableFingerprint;
+use crate::blockchain::blockchain_bridge::{PendingPayableFingerprint, ResubmissionData};
 use crate::blockchain::blockchain_interface::data_structures::BlockchainTransaction;
 use crate::blockchain::test_utils::make_tx_hash;
 use crate::bootstrapper::BootstrapperConfig;
 use crate::database::rusqlite_wrappers::TransactionSafeWrapper;
 use crate::db_config::config_dao::{ConfigDao, ConfigDaoFactory};
 use crate::db_config::mocks::ConfigDaoMock;
-use crate::sub_lib::accountant::{DaoFactories, FinancialStatistics};
+use crate::metrics::NodeMetrics;
+use crate::sub_lib::accountant::{DaoFactories, FinancialStatistics, ServiceReceipt_0v1};
 use crate::sub_lib::accountant::{MessageIdGenerator, PaymentThresholds};
 use crate::sub_lib::blockchain_bridge::OutboundPaymentsInstructions;
 use crate::sub_lib::utils::NotifyLaterHandle;
 use crate::sub_lib::wallet::Wallet;
 use crate::test_utils::make_wallet;
 use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
+use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::ArbitraryIdStamp;
 use crate::test_utils::unshared_test_utils::make_bc_with_defaults;
 use actix::{Message, System};
 use ethereum_types::H256;
 use itertools::Either;
 use masq_lib::logger::Logger;
-use masq_lib::messages::ScanType;
+use masq_lib::messages::{ScanType, UiPaymentsAdjustedBroadcast};
 use masq_lib::ui_gateway::NodeToUiMessage;
 use rusqlite::{Connection, OpenFlags, Row};
 use std::any::type_name;
@@ -102,6 +117,8 @@ pub struct AccountantBuilder {
     receivable_dao_factory_opt: Option<ReceivableDaoFactoryMock>,
     pending_payable_dao_factory_opt: Option<PendingPayableDaoFactoryMock>,
     banned_dao_factory_opt: Option<BannedDaoFactoryMock>,
+    ledger_dao_factory_opt: Option<LedgerDaoFactoryMock>,
+    service_receipt_dao_factory_opt: Option<ServiceReceiptDaoFactoryMock>,
     config_dao_factory_opt: Option<ConfigDaoFactoryMock>,
 }
 
@@ -115,6 +132,8 @@ impl Default for AccountantBuilder {
             receivable_dao_factory_opt: None,
             pending_payable_dao_factory_opt: None,
             banned_dao_factory_opt: None,
+            ledger_dao_factory_opt: None,
+            service_receipt_dao_factory_opt: None,
             config_dao_factory_opt: None,
         }
     }
@@ -262,6 +281,17 @@ const RECEIVABLE_DAOS_ACCOUNTANT_INITIALIZATION_ORDER: [DestinationMarker; 2] =
     DestinationMarker::ReceivableScanner,
 ];
 
+const BANNED_DAOS_ACCOUNTANT_INITIALIZATION_ORDER: [DestinationMarker; 2] = [
+    DestinationMarker::AccountantBody,
+    DestinationMarker::ReceivableScanner,
+];
+
+const LEDGER_DAOS_ACCOUNTANT_INITIALIZATION_ORDER: [DestinationMarker; 3] = [
+    DestinationMarker::AccountantBody,
+    DestinationMarker::PendingPayableScanner,
+    DestinationMarker::ReceivableScanner,
+];
+
 impl AccountantBuilder {
     pub fn bootstrapper_config(mut self, config: BootstrapperConfig) -> Self {
         self.config_opt = Some(config);
@@ -320,17 +350,37 @@ impl AccountantBuilder {
         )
     }
 
-    //TODO this method seems to be never used?
-    pub fn banned_dao(mut self, banned_dao: BannedDaoMock) -> Self {
-        match self.banned_dao_factory_opt {
-            None => {
-                self.banned_dao_factory_opt =
-                    Some(BannedDaoFactoryMock::new().make_result(banned_dao))
-            }
-            Some(banned_dao_factory) => {
-                self.banned_dao_factory_opt = Some(banned_dao_factory.make_result(banned_dao))
-            }
-        }
+    pub fn ledger_daos(
+        mut self,
+        specially_configured_daos: Vec<DaoWithDestination<LedgerDaoMock>>,
+    ) -> Self {
+        create_or_update_factory!(
+            specially_configured_daos,
+            LEDGER_DAOS_ACCOUNTANT_INITIALIZATION_ORDER,
+            ledger_dao_factory_opt,
+            LedgerDaoFactoryMock,
+            LedgerDao,
+            self
+        )
+    }
+
+    pub fn banned_daos(
+        mut self,
+        specially_configured_daos: Vec<DaoWithDestination<BannedDaoMock>>,
+    ) -> Self {
+        create_or_update_factory!(
+            specially_configured_daos,
+            BANNED_DAOS_ACCOUNTANT_INITIALIZATION_ORDER,
+            banned_dao_factory_opt,
+            BannedDaoFactoryMock,
+            BannedDao,
+            self
+        )
+    }
+
+    pub fn service_receipt_dao(mut self, service_receipt_dao: ServiceReceiptDaoMock) -> Self {
+        self.service_receipt_dao_factory_opt =
+            Some(ServiceReceiptDaoFactoryMock::new().make_result(service_receipt_dao));
         self
     }
 
@@ -358,9 +408,20 @@ impl AccountantBuilder {
                 .make_result(PendingPayableDaoMock::new())
                 .make_result(PendingPayableDaoMock::new()),
         );
-        let banned_dao_factory = self
-            .banned_dao_factory_opt
-            .unwrap_or(BannedDaoFactoryMock::new().make_result(BannedDaoMock::new()));
+        let banned_dao_factory = self.banned_dao_factory_opt.unwrap_or(
+            BannedDaoFactoryMock::new()
+                .make_result(BannedDaoMock::new())
+                .make_result(BannedDaoMock::new()),
+        );
+        let ledger_dao_factory = self.ledger_dao_factory_opt.unwrap_or(
+            LedgerDaoFactoryMock::new()
+                .make_result(LedgerDaoMock::new())
+                .make_result(LedgerDaoMock::new())
+                .make_result(LedgerDaoMock::new()),
+        );
+        let service_receipt_dao_factory = self.service_receipt_dao_factory_opt.unwrap_or(
+            ServiceReceiptDaoFactoryMock::new().make_result(ServiceReceiptDaoMock::new()),
+        );
         let config_dao_factory = self
             .config_dao_factory_opt
             .unwrap_or(ConfigDaoFactoryMock::new().make_result(ConfigDaoMock::new()));
@@ -371,6 +432,8 @@ impl AccountantBuilder {
                 pending_payable_dao_factory: Box::new(pending_payable_dao_factory),
                 receivable_dao_factory: Box::new(receivable_dao_factory),
                 banned_dao_factory: Box::new(banned_dao_factory),
+                ledger_dao_factory: Box::new(ledger_dao_factory),
+                service_receipt_dao_factory: Box::new(service_receipt_dao_factory),
                 config_dao_factory: Box::new(config_dao_factory),
             },
         );
@@ -491,6 +554,74 @@ impl BannedDaoFactoryMock {
     }
 }
 
+pub struct LedgerDaoFactoryMock {
+    make_params: Arc<Mutex<Vec<()>>>,
+    make_results: RefCell<Vec<Box<dyn LedgerDao>>>,
+}
+
+impl LedgerDaoFactory for LedgerDaoFactoryMock {
+    fn make(&self) -> Box<dyn LedgerDao> {
+        if self.make_results.borrow().len() == 0 {
+            panic!("LedgerDao Missing.")
+        };
+        self.make_params.lock().unwrap().push(());
+        self.make_results.borrow_mut().remove(0)
+    }
+}
+
+impl LedgerDaoFactoryMock {
+    pub fn new() -> Self {
+        Self {
+            make_params: Arc::new(Mutex::new(vec![])),
+            make_results: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn make_params(mut self, params: &Arc<Mutex<Vec<()>>>) -> Self {
+        self.make_params = params.clone();
+        self
+    }
+
+    pub fn make_result(self, result: LedgerDaoMock) -> Self {
+        self.make_results.borrow_mut().push(Box::new(result));
+        self
+    }
+}
+
+pub struct ServiceReceiptDaoFactoryMock {
+    make_params: Arc<Mutex<Vec<()>>>,
+    make_results: RefCell<Vec<Box<dyn ServiceReceiptDao>>>,
+}
+
+impl ServiceReceiptDaoFactory for ServiceReceiptDaoFactoryMock {
+    fn make(&self) -> Box<dyn ServiceReceiptDao> {
+        if self.make_results.borrow().len() == 0 {
+            panic!("ServiceReceiptDao Missing.")
+        };
+        self.make_params.lock().unwrap().push(());
+        self.make_results.borrow_mut().remove(0)
+    }
+}
+
+impl ServiceReceiptDaoFactoryMock {
+    pub fn new() -> Self {
+        Self {
+            make_params: Arc::new(Mutex::new(vec![])),
+            make_results: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn make_params(mut self, params: &Arc<Mutex<Vec<()>>>) -> Self {
+        self.make_params = params.clone();
+        self
+    }
+
+    pub fn make_result(self, result: ServiceReceiptDaoMock) -> Self {
+        self.make_results.borrow_mut().push(Box::new(result));
+        self
+    }
+}
+
 pub struct ConfigDaoFactoryMock {
     make_params: Arc<Mutex<Vec<()>>>,
     make_results: RefCell<Vec<Box<dyn ConfigDao>>>,
@@ -674,12 +805,21 @@ impl PayableDaoMock {
     }
 }
 
+type MoreMoneyReceivedParams = (
+    SystemTime,
+    Vec<BlockchainTransaction>,
+    Vec<BlockchainTransaction>,
+);
+
 #[derive(Debug, Default)]
 pub struct ReceivableDaoMock {
     more_money_receivable_parameters: Arc<Mutex<Vec<(SystemTime, Wallet, u128)>>>,
     more_money_receivable_results: RefCell<Vec<Result<(), ReceivableDaoError>>>,
-    more_money_received_parameters: Arc<Mutex<Vec<(SystemTime, Vec<BlockchainTransaction>)>>>,
+    more_money_received_parameters: Arc<Mutex<Vec<MoreMoneyReceivedParams>>>,
     more_money_received_results: RefCell<Vec<TransactionSafeWrapper<'static>>>,
+    credited_transactions_since_params: Arc<Mutex<Vec<u64>>>,
+    credited_transactions_since_results: RefCell<Vec<Vec<BlockchainTransaction>>>,
+    forget_credited_transactions_before_params: Arc<Mutex<Vec<u64>>>,
     new_delinquencies_parameters: Arc<Mutex<Vec<(SystemTime, PaymentThresholds)>>>,
     new_delinquencies_results: RefCell<Vec<Vec<ReceivableAccount>>>,
     paid_delinquencies_parameters: Arc<Mutex<Vec<PaymentThresholds>>>,
@@ -707,12 +847,31 @@ impl ReceivableDao for ReceivableDaoMock {
         &mut self,
         now: SystemTime,
         transactions: &[BlockchainTransaction],
+        orphaned_transactions: &[BlockchainTransaction],
     ) -> TransactionSafeWrapper {
-        self.more_money_received_parameters
+        self.more_money_received_parameters.lock().unwrap().push((
+            now,
+            transactions.to_vec(),
+            orphaned_transactions.to_vec(),
+        ));
+        self.more_money_received_results.borrow_mut().remove(0)
+    }
+
+    fn credited_transactions_since(&self, block_number: u64) -> Vec<BlockchainTransaction> {
+        self.credited_transactions_since_params
             .lock()
             .unwrap()
-            .push((now, transactions.to_vec()));
-        self.more_money_received_results.borrow_mut().remove(0)
+            .push(block_number);
+        self.credited_transactions_since_results
+            .borrow_mut()
+            .remove(0)
+    }
+
+    fn forget_credited_transactions_before(&self, block_number: u64) {
+        self.forget_credited_transactions_before_params
+            .lock()
+            .unwrap()
+            .push(block_number);
     }
 
     fn new_delinquencies(
@@ -770,7 +929,7 @@ impl ReceivableDaoMock {
 
     pub fn more_money_received_params(
         mut self,
-        parameters: &Arc<Mutex<Vec<(SystemTime, Vec<BlockchainTransaction>)>>>,
+        parameters: &Arc<Mutex<Vec<MoreMoneyReceivedParams>>>,
     ) -> Self {
         self.more_money_received_parameters = parameters.clone();
         self
@@ -781,6 +940,26 @@ impl ReceivableDaoMock {
         self
     }
 
+    pub fn credited_transactions_since_params(mut self, params: &Arc<Mutex<Vec<u64>>>) -> Self {
+        self.credited_transactions_since_params = params.clone();
+        self
+    }
+
+    pub fn credited_transactions_since_result(self, result: Vec<BlockchainTransaction>) -> Self {
+        self.credited_transactions_since_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
+    pub fn forget_credited_transactions_before_params(
+        mut self,
+        params: &Arc<Mutex<Vec<u64>>>,
+    ) -> Self {
+        self.forget_credited_transactions_before_params = params.clone();
+        self
+    }
+
     pub fn new_delinquencies_parameters(
         mut self,
         parameters: &Arc<Mutex<Vec<(SystemTime, PaymentThresholds)>>>,
@@ -826,23 +1005,36 @@ impl ReceivableDaoMock {
 #[derive(Debug, Default)]
 pub struct BannedDaoMock {
     ban_list_parameters: Arc<Mutex<Vec<()>>>,
-    ban_list_results: RefCell<Vec<Vec<Wallet>>>,
-    ban_parameters: Arc<Mutex<Vec<Wallet>>>,
+    ban_list_results: RefCell<Vec<Vec<BannedAccount>>>,
+    ban_parameters: Arc<Mutex<Vec<(Wallet, SystemTime, Option<String>, BanOrigin)>>>,
+    ban_results: RefCell<Vec<bool>>,
     unban_parameters: Arc<Mutex<Vec<Wallet>>>,
+    unban_results: RefCell<Vec<bool>>,
 }
 
 impl BannedDao for BannedDaoMock {
-    fn ban_list(&self) -> Vec<Wallet> {
+    fn ban_list(&self) -> Vec<BannedAccount> {
         self.ban_list_parameters.lock().unwrap().push(());
         self.ban_list_results.borrow_mut().remove(0)
     }
 
-    fn ban(&self, wallet: &Wallet) {
-        self.ban_parameters.lock().unwrap().push(wallet.clone());
+    fn ban(
+        &self,
+        wallet: &Wallet,
+        timestamp: SystemTime,
+        reason_opt: Option<String>,
+        origin: BanOrigin,
+    ) -> bool {
+        self.ban_parameters
+            .lock()
+            .unwrap()
+            .push((wallet.clone(), timestamp, reason_opt, origin));
+        self.ban_results.borrow_mut().remove(0)
     }
 
-    fn unban(&self, wallet: &Wallet) {
+    fn unban(&self, wallet: &Wallet) -> bool {
         self.unban_parameters.lock().unwrap().push(wallet.clone());
+        self.unban_results.borrow_mut().remove(0)
     }
 }
 
@@ -852,24 +1044,151 @@ impl BannedDaoMock {
             ban_list_parameters: Arc::new(Mutex::new(vec![])),
             ban_list_results: RefCell::new(vec![]),
             ban_parameters: Arc::new(Mutex::new(vec![])),
+            ban_results: RefCell::new(vec![]),
             unban_parameters: Arc::new(Mutex::new(vec![])),
+            unban_results: RefCell::new(vec![]),
         }
     }
 
-    pub fn ban_list_result(self, result: Vec<Wallet>) -> Self {
+    pub fn ban_list_parameters(mut self, parameters: &Arc<Mutex<Vec<()>>>) -> Self {
+        self.ban_list_parameters = parameters.clone();
+        self
+    }
+
+    pub fn ban_list_result(self, result: Vec<BannedAccount>) -> Self {
         self.ban_list_results.borrow_mut().push(result);
         self
     }
 
-    pub fn ban_parameters(mut self, parameters: &Arc<Mutex<Vec<Wallet>>>) -> Self {
+    pub fn ban_parameters(
+        mut self,
+        parameters: &Arc<Mutex<Vec<(Wallet, SystemTime, Option<String>, BanOrigin)>>>,
+    ) -> Self {
         self.ban_parameters = parameters.clone();
         self
     }
 
+    pub fn ban_result(self, result: bool) -> Self {
+        self.ban_results.borrow_mut().push(result);
+        self
+    }
+
     pub fn unban_parameters(mut self, parameters: &Arc<Mutex<Vec<Wallet>>>) -> Self {
         self.unban_parameters = parameters.clone();
         self
     }
+
+    pub fn unban_result(self, result: bool) -> Self {
+        self.unban_results.borrow_mut().push(result);
+        self
+    }
+}
+
+#[derive(Default)]
+pub struct LedgerDaoMock {
+    record_params: Arc<Mutex<Vec<Vec<LedgerEntry>>>>,
+    entries_params: Arc<Mutex<Vec<LedgerQuery>>>,
+    entries_results: RefCell<Vec<Vec<LedgerEntry>>>,
+    roll_up_params: Arc<Mutex<Vec<SystemTime>>>,
+    delete_older_than_params: Arc<Mutex<Vec<SystemTime>>>,
+}
+
+impl LedgerDao for LedgerDaoMock {
+    fn record(&self, entries: &[LedgerEntry]) {
+        self.record_params.lock().unwrap().push(entries.to_vec());
+    }
+
+    fn entries(&self, query: &LedgerQuery) -> Vec<LedgerEntry> {
+        self.entries_params.lock().unwrap().push(query.clone());
+        self.entries_results.borrow_mut().remove(0)
+    }
+
+    fn roll_up(&mut self, before: SystemTime) {
+        self.roll_up_params.lock().unwrap().push(before);
+    }
+
+    fn delete_older_than(&self, cutoff: SystemTime) {
+        self.delete_older_than_params.lock().unwrap().push(cutoff);
+    }
+}
+
+impl LedgerDaoMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_params(mut self, params: &Arc<Mutex<Vec<Vec<LedgerEntry>>>>) -> Self {
+        self.record_params = params.clone();
+        self
+    }
+
+    pub fn entries_params(mut self, params: &Arc<Mutex<Vec<LedgerQuery>>>) -> Self {
+        self.entries_params = params.clone();
+        self
+    }
+
+    pub fn entries_result(self, result: Vec<LedgerEntry>) -> Self {
+        self.entries_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn roll_up_params(mut self, params: &Arc<Mutex<Vec<SystemTime>>>) -> Self {
+        self.roll_up_params = params.clone();
+        self
+    }
+
+    pub fn delete_older_than_params(mut self, params: &Arc<Mutex<Vec<SystemTime>>>) -> Self {
+        self.delete_older_than_params = params.clone();
+        self
+    }
+}
+
+#[derive(Default)]
+pub struct ServiceReceiptDaoMock {
+    store_params: Arc<Mutex<Vec<(ServiceReceipt_0v1, SystemTime)>>>,
+    receipts_results: RefCell<Vec<Vec<StoredServiceReceipt>>>,
+    delete_older_than_params: Arc<Mutex<Vec<SystemTime>>>,
+}
+
+impl ServiceReceiptDao for ServiceReceiptDaoMock {
+    fn store(&self, receipt: &ServiceReceipt_0v1, received: SystemTime) {
+        self.store_params
+            .lock()
+            .unwrap()
+            .push((receipt.clone(), received));
+    }
+
+    fn receipts(&self) -> Vec<StoredServiceReceipt> {
+        self.receipts_results.borrow_mut().remove(0)
+    }
+
+    fn delete_older_than(&self, cutoff: SystemTime) {
+        self.delete_older_than_params.lock().unwrap().push(cutoff);
+    }
+}
+
+impl ServiceReceiptDaoMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn store_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(ServiceReceipt_0v1, SystemTime)>>>,
+    ) -> Self {
+        self.store_params = params.clone();
+        self
+    }
+
+    pub fn receipts_result(self, result: Vec<StoredServiceReceipt>) -> Self {
+        self.receipts_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn delete_older_than_params(mut self, params: &Arc<Mutex<Vec<SystemTime>>>) -> Self {
+        self.delete_older_than_params = params.clone();
+        self
+    }
 }
 
 pub fn bc_from_earning_wallet(earning_wallet: Wallet) -> BootstrapperConfig {
@@ -891,12 +1210,15 @@ pub struct PendingPayableDaoMock {
     fingerprints_rowids_results: RefCell<Vec<TransactionHashes>>,
     delete_fingerprints_params: Arc<Mutex<Vec<Vec<u64>>>>,
     delete_fingerprints_results: RefCell<Vec<Result<(), PendingPayableDaoError>>>,
-    insert_new_fingerprints_params: Arc<Mutex<Vec<(Vec<(H256, u128)>, SystemTime)>>>,
+    insert_new_fingerprints_params:
+        Arc<Mutex<Vec<(Vec<(H256, u128)>, Vec<ResubmissionData>, SystemTime)>>>,
     insert_new_fingerprints_results: RefCell<Vec<Result<(), PendingPayableDaoError>>>,
     increment_scan_attempts_params: Arc<Mutex<Vec<Vec<u64>>>>,
     increment_scan_attempts_result: RefCell<Vec<Result<(), PendingPayableDaoError>>>,
     mark_failures_params: Arc<Mutex<Vec<Vec<u64>>>>,
     mark_failures_results: RefCell<Vec<Result<(), PendingPayableDaoError>>>,
+    record_replacements_params: Arc<Mutex<Vec<Vec<PendingPayableReplacement>>>>,
+    record_replacements_results: RefCell<Vec<Result<(), PendingPayableDaoError>>>,
     return_all_errorless_fingerprints_params: Arc<Mutex<Vec<()>>>,
     return_all_errorless_fingerprints_results: RefCell<Vec<Vec<PendingPayableFingerprint>>>,
     pub have_return_all_errorless_fingerprints_shut_down_the_system: bool,
@@ -933,12 +1255,14 @@ impl PendingPayableDao for PendingPayableDaoMock {
     fn insert_new_fingerprints(
         &self,
         hashes_and_amounts: &[(H256, u128)],
+        resubmission_data: &[ResubmissionData],
         batch_wide_timestamp: SystemTime,
     ) -> Result<(), PendingPayableDaoError> {
-        self.insert_new_fingerprints_params
-            .lock()
-            .unwrap()
-            .push((hashes_and_amounts.to_vec(), batch_wide_timestamp));
+        self.insert_new_fingerprints_params.lock().unwrap().push((
+            hashes_and_amounts.to_vec(),
+            resubmission_data.to_vec(),
+            batch_wide_timestamp,
+        ));
         self.insert_new_fingerprints_results.borrow_mut().remove(0)
     }
 
@@ -962,6 +1286,17 @@ impl PendingPayableDao for PendingPayableDaoMock {
         self.mark_failures_params.lock().unwrap().push(ids.to_vec());
         self.mark_failures_results.borrow_mut().remove(0)
     }
+
+    fn record_replacements(
+        &self,
+        replacements: &[PendingPayableReplacement],
+    ) -> Result<(), PendingPayableDaoError> {
+        self.record_replacements_params
+            .lock()
+            .unwrap()
+            .push(replacements.to_vec());
+        self.record_replacements_results.borrow_mut().remove(0)
+    }
 }
 
 impl PendingPayableDaoMock {
@@ -981,7 +1316,7 @@ impl PendingPayableDaoMock {
 
     pub fn insert_fingerprints_params(
         mut self,
-        params: &Arc<Mutex<Vec<(Vec<(H256, u128)>, SystemTime)>>>,
+        params: &Arc<Mutex<Vec<(Vec<(H256, u128)>, Vec<ResubmissionData>, SystemTime)>>>,
     ) -> Self {
         self.insert_new_fingerprints_params = params.clone();
         self
@@ -1032,6 +1367,19 @@ impl PendingPayableDaoMock {
         self
     }
 
+    pub fn record_replacements_params(
+        mut self,
+        params: &Arc<Mutex<Vec<Vec<PendingPayableReplacement>>>>,
+    ) -> Self {
+        self.record_replacements_params = params.clone();
+        self
+    }
+
+    pub fn record_replacements_result(self, result: Result<(), PendingPayableDaoError>) -> Self {
+        self.record_replacements_results.borrow_mut().push(result);
+        self
+    }
+
     pub fn increment_scan_attempts_params(mut self, params: &Arc<Mutex<Vec<Vec<u64>>>>) -> Self {
         self.increment_scan_attempts_params = params.clone();
         self
@@ -1133,6 +1481,7 @@ impl PayableScannerBuilder {
             Box::new(self.pending_payable_dao),
             Rc::new(self.payment_thresholds),
             Box::new(self.payment_adjuster),
+            Arc::new(NodeMetrics::default()),
         )
     }
 }
@@ -1140,8 +1489,10 @@ impl PayableScannerBuilder {
 pub struct PendingPayableScannerBuilder {
     payable_dao: PayableDaoMock,
     pending_payable_dao: PendingPayableDaoMock,
+    ledger_dao: LedgerDaoMock,
     payment_thresholds: PaymentThresholds,
     when_pending_too_long_sec: u64,
+    when_pending_stuck_sec: u64,
     financial_statistics: FinancialStatistics,
 }
 
@@ -1150,8 +1501,10 @@ impl PendingPayableScannerBuilder {
         Self {
             payable_dao: PayableDaoMock::new(),
             pending_payable_dao: PendingPayableDaoMock::new(),
+            ledger_dao: LedgerDaoMock::new(),
             payment_thresholds: PaymentThresholds::default(),
             when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            when_pending_stuck_sec: DEFAULT_PENDING_STUCK_SEC,
             financial_statistics: FinancialStatistics::default(),
         }
     }
@@ -1166,18 +1519,31 @@ impl PendingPayableScannerBuilder {
         self
     }
 
+    pub fn ledger_dao(mut self, ledger_dao: LedgerDaoMock) -> Self {
+        self.ledger_dao = ledger_dao;
+        self
+    }
+
     pub fn when_pending_too_long_sec(mut self, interval: u64) -> Self {
         self.when_pending_too_long_sec = interval;
         self
     }
 
+    pub fn when_pending_stuck_sec(mut self, interval: u64) -> Self {
+        self.when_pending_stuck_sec = interval;
+        self
+    }
+
     pub fn build(self) -> PendingPayableScanner {
         PendingPayableScanner::new(
             Box::new(self.payable_dao),
             Box::new(self.pending_payable_dao),
+            Box::new(self.ledger_dao),
             Rc::new(self.payment_thresholds),
             self.when_pending_too_long_sec,
+            self.when_pending_stuck_sec,
             Rc::new(RefCell::new(self.financial_statistics)),
+            Arc::new(NodeMetrics::default()),
         )
     }
 }
@@ -1185,8 +1551,10 @@ impl PendingPayableScannerBuilder {
 pub struct ReceivableScannerBuilder {
     receivable_dao: ReceivableDaoMock,
     banned_dao: BannedDaoMock,
+    ledger_dao: LedgerDaoMock,
     persistent_configuration: PersistentConfigurationMock,
     payment_thresholds: PaymentThresholds,
+    confirmation_depth: u64,
     financial_statistics: FinancialStatistics,
 }
 
@@ -1195,8 +1563,10 @@ impl ReceivableScannerBuilder {
         Self {
             receivable_dao: ReceivableDaoMock::new(),
             banned_dao: BannedDaoMock::new(),
+            ledger_dao: LedgerDaoMock::new(),
             persistent_configuration: PersistentConfigurationMock::new(),
             payment_thresholds: PaymentThresholds::default(),
+            confirmation_depth: DEFAULT_CONFIRMATION_DEPTH,
             financial_statistics: FinancialStatistics::default(),
         }
     }
@@ -1219,18 +1589,32 @@ impl ReceivableScannerBuilder {
         self
     }
 
+    pub fn ledger_dao(mut self, ledger_dao: LedgerDaoMock) -> Self {
+        self.ledger_dao = ledger_dao;
+        self
+    }
+
     pub fn payment_thresholds(mut self, payment_thresholds: PaymentThresholds) -> Self {
         self.payment_thresholds = payment_thresholds;
         self
     }
 
+    pub fn confirmation_depth(mut self, confirmation_depth: u64) -> Self {
+        self.confirmation_depth = confirmation_depth;
+        self
+    }
+
     pub fn build(self) -> ReceivableScanner {
         ReceivableScanner::new(
             Box::new(self.receivable_dao),
             Box::new(self.banned_dao),
+            Box::new(self.ledger_dao),
             Box::new(self.persistent_configuration),
             Rc::new(self.payment_thresholds),
+            self.confirmation_depth,
             Rc::new(RefCell::new(self.financial_statistics)),
+            Rc::new(RefCell::new(vec![])),
+            Arc::new(NodeMetrics::default()),
         )
     }
 }
@@ -1254,6 +1638,18 @@ pub fn make_pending_payable_fingerprint() -> PendingPayableFingerprint {
         attempt: 1,
         amount: 12345,
         process_error: None,
+        replaced_hashes: vec![],
+        resubmission_data_opt: None,
+    }
+}
+
+pub fn make_resubmission_data(nonce: u64) -> ResubmissionData {
+    ResubmissionData {
+        nonce,
+        recipient_wallet: make_wallet(&format!("recipient_{}", nonce)),
+        fee_model: TransactionFeeModel::Legacy {
+            gas_price_wei: gwei_to_wei(123_u64),
+        },
     }
 }
 
@@ -1439,23 +1835,29 @@ pub fn trick_rusqlite_with_read_only_conn(
 #[derive(Default)]
 pub struct PaymentAdjusterMock {
     search_for_indispensable_adjustment_params:
-        Arc<Mutex<Vec<(BlockchainAgentWithContextMessage, Logger)>>>,
+        Arc<Mutex<Vec<(Vec<PayableAccount>, ArbitraryIdStamp, Logger)>>>,
     search_for_indispensable_adjustment_results:
         RefCell<Vec<Result<Option<Adjustment>, AnalysisError>>>,
     adjust_payments_params: Arc<Mutex<Vec<(PreparedAdjustment, SystemTime, Logger)>>>,
-    adjust_payments_results: RefCell<Vec<OutboundPaymentsInstructions>>,
+    adjust_payments_results:
+        RefCell<Vec<(OutboundPaymentsInstructions, UiPaymentsAdjustedBroadcast)>>,
 }
 
 impl PaymentAdjuster for PaymentAdjusterMock {
     fn search_for_indispensable_adjustment(
         &self,
-        msg: &BlockchainAgentWithContextMessage,
+        qualified_payables: &[PayableAccount],
+        agent: &dyn BlockchainAgent,
         logger: &Logger,
     ) -> Result<Option<Adjustment>, AnalysisError> {
         self.search_for_indispensable_adjustment_params
             .lock()
             .unwrap()
-            .push((msg.clone(), logger.clone()));
+            .push((
+                qualified_payables.to_vec(),
+                agent.arbitrary_id_stamp(),
+                logger.clone(),
+            ));
         self.search_for_indispensable_adjustment_results
             .borrow_mut()
             .remove(0)
@@ -1466,7 +1868,7 @@ impl PaymentAdjuster for PaymentAdjusterMock {
         setup: PreparedAdjustment,
         now: SystemTime,
         logger: &Logger,
-    ) -> OutboundPaymentsInstructions {
+    ) -> (OutboundPaymentsInstructions, UiPaymentsAdjustedBroadcast) {
         self.adjust_payments_params
             .lock()
             .unwrap()
@@ -1478,7 +1880,7 @@ impl PaymentAdjuster for PaymentAdjusterMock {
 impl PaymentAdjusterMock {
     pub fn is_adjustment_required_params(
         mut self,
-        params: &Arc<Mutex<Vec<(BlockchainAgentWithContextMessage, Logger)>>>,
+        params: &Arc<Mutex<Vec<(Vec<PayableAccount>, ArbitraryIdStamp, Logger)>>>,
     ) -> Self {
         self.search_for_indispensable_adjustment_params = params.clone();
         self
@@ -1502,7 +1904,10 @@ impl PaymentAdjusterMock {
         self
     }
 
-    pub fn adjust_payments_result(self, result: OutboundPaymentsInstructions) -> Self {
+    pub fn adjust_payments_result(
+        self,
+        result: (OutboundPaymentsInstructions, UiPaymentsAdjustedBroadcast),
+    ) -> Self {
         self.adjust_payments_results.borrow_mut().push(result);
         self
     }
@@ -1525,7 +1930,7 @@ macro_rules! formal_traits_for_payable_mid_scan_msg_handling {
                 &self,
                 _setup: PreparedAdjustment,
                 _logger: &Logger,
-            ) -> OutboundPaymentsInstructions {
+            ) -> (OutboundPaymentsInstructions, UiPaymentsAdjustedBroadcast) {
                 intentionally_blank!()
             }
         }
@@ -0,0 +1,607 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::accountant::db_access_objects::banned_dao::BannedAccount;
+use crate::accountant::db_access_objects::payable_dao::PayableAccount;
+use crate::accountant::db_access_objects::receivable_dao::ReceivableAccount;
+use crate::accountant::db_access_objects::utils::ThresholdUtils;
+use crate::accountant::scanners::scanners_utils::payable_scanner_utils::{
+    PayableThresholdsGauge, PayableThresholdsGaugeReal,
+};
+use crate::accountant::{gwei_to_wei, wei_to_gwei};
+use crate::sub_lib::accountant::PaymentThresholds;
+use crate::sub_lib::wallet::Wallet;
+use masq_lib::constants::WEIS_IN_GWEI;
+use masq_lib::messages::{UiSimulatedAccount, UiThresholdEvent, UiThresholdEventType};
+use std::str::FromStr;
+use std::time::SystemTime;
+
+// Keeps a careless request from making the Accountant spin for ages
+pub const MAX_SIMULATION_STEPS: u64 = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulatedPayable {
+    pub wallet: Wallet,
+    pub balance_wei: u128,
+    pub age_s: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulatedBanStatus {
+    NotBanned,
+    Banned,
+    ManuallyBanned,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulatedReceivable {
+    pub wallet: Wallet,
+    pub balance_wei: i128,
+    pub age_s: u64,
+    pub ban_status: SimulatedBanStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThresholdEventKind {
+    Paid,
+    Banned,
+    Unbanned,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThresholdEvent {
+    pub offset_s: u64,
+    pub wallet: Wallet,
+    pub kind: ThresholdEventKind,
+    pub balance_wei: i128,
+    pub age_s: u64,
+}
+
+// Replays the rules of the payable and receivable scanners against accounts whose balances stay
+// put while they age, reporting every payment, ban and unban the scanners would decide on if they
+// ran once per step over the window. A paid payable drops out of the simulation; a receivable may
+// be banned and unbanned repeatedly, which is exactly the kind of surprise this is meant to reveal.
+pub fn simulate_thresholds(
+    payment_thresholds: &PaymentThresholds,
+    payables: &[SimulatedPayable],
+    receivables: &[SimulatedReceivable],
+    window_s: u64,
+    step_s: u64,
+) -> Result<Vec<ThresholdEvent>, String> {
+    validate_simulation(payment_thresholds, window_s, step_s)?;
+    let gauge = PayableThresholdsGaugeReal::default();
+    let mut unpaid: Vec<&SimulatedPayable> = payables.iter().collect();
+    let mut ban_statuses: Vec<SimulatedBanStatus> =
+        receivables.iter().map(|r| r.ban_status).collect();
+    let mut events = vec![];
+    for offset_s in (0..=window_s).step_by(step_s as usize) {
+        unpaid.retain(|payable| {
+            let age_s = payable.age_s.saturating_add(offset_s);
+            if !payable_qualifies(&gauge, payment_thresholds, payable.balance_wei, age_s) {
+                return true;
+            }
+            events.push(ThresholdEvent {
+                offset_s,
+                wallet: payable.wallet.clone(),
+                kind: ThresholdEventKind::Paid,
+                balance_wei: payable.balance_wei as i128,
+                age_s,
+            });
+            false
+        });
+        receivables
+            .iter()
+            .zip(ban_statuses.iter_mut())
+            .for_each(|(receivable, ban_status)| {
+                let age_s = receivable.age_s.saturating_add(offset_s);
+                let kind_opt = match ban_status {
+                    SimulatedBanStatus::NotBanned
+                        if is_delinquent(payment_thresholds, receivable.balance_wei, age_s) =>
+                    {
+                        *ban_status = SimulatedBanStatus::Banned;
+                        Some(ThresholdEventKind::Banned)
+                    }
+                    SimulatedBanStatus::Banned
+                        if is_paid_up(payment_thresholds, receivable.balance_wei) =>
+                    {
+                        *ban_status = SimulatedBanStatus::NotBanned;
+                        Some(ThresholdEventKind::Unbanned)
+                    }
+                    _ => None,
+                };
+                if let Some(kind) = kind_opt {
+                    events.push(ThresholdEvent {
+                        offset_s,
+                        wallet: receivable.wallet.clone(),
+                        kind,
+                        balance_wei: receivable.balance_wei,
+                        age_s,
+                    })
+                }
+            });
+    }
+    Ok(events)
+}
+
+fn validate_simulation(
+    payment_thresholds: &PaymentThresholds,
+    window_s: u64,
+    step_s: u64,
+) -> Result<(), String> {
+    if payment_thresholds.debt_threshold_gwei <= payment_thresholds.permanent_debt_allowed_gwei {
+        return Err(format!(
+            "Value of DebtThresholdGwei ({}) must be bigger than PermanentDebtAllowedGwei ({})",
+            payment_thresholds.debt_threshold_gwei, payment_thresholds.permanent_debt_allowed_gwei
+        ));
+    }
+    if payment_thresholds.threshold_interval_sec == 0
+        || payment_thresholds.threshold_interval_sec > 10_u64.pow(9)
+    {
+        return Err(
+            "Value of ThresholdIntervalSec must be between 1 and 1,000,000,000 s".to_string(),
+        );
+    }
+    if step_s == 0 {
+        return Err("Simulation step must be at least 1 s".to_string());
+    }
+    if window_s / step_s >= MAX_SIMULATION_STEPS {
+        return Err(format!(
+            "Simulation of {} s in steps of {} s would take more than {} steps",
+            window_s, step_s, MAX_SIMULATION_STEPS
+        ));
+    }
+    Ok(())
+}
+
+fn payable_qualifies(
+    gauge: &dyn PayableThresholdsGauge,
+    payment_thresholds: &PaymentThresholds,
+    balance_wei: u128,
+    age_s: u64,
+) -> bool {
+    if gauge.is_innocent_age(age_s, payment_thresholds.maturity_threshold_sec) {
+        return false;
+    }
+    if gauge.is_innocent_balance(
+        balance_wei,
+        gwei_to_wei(payment_thresholds.permanent_debt_allowed_gwei),
+    ) {
+        return false;
+    }
+    balance_wei > gauge.calculate_payout_threshold_in_gwei(payment_thresholds, age_s)
+}
+
+// Mirrors the query behind ReceivableDao::new_delinquencies()
+fn is_delinquent(payment_thresholds: &PaymentThresholds, balance_wei: i128, age_s: u64) -> bool {
+    let overdue_s = age_s as i128
+        - payment_thresholds.maturity_threshold_sec as i128
+        - payment_thresholds.payment_grace_period_sec as i128;
+    if overdue_s <= 0 {
+        return false;
+    }
+    let limit_wei = gwei_to_wei::<i128, u64>(payment_thresholds.debt_threshold_gwei)
+        + ThresholdUtils::slope(payment_thresholds) * overdue_s;
+    balance_wei > limit_wei
+        && balance_wei > gwei_to_wei::<i128, u64>(payment_thresholds.permanent_debt_allowed_gwei)
+}
+
+// Mirrors the query behind ReceivableDao::paid_delinquencies()
+fn is_paid_up(payment_thresholds: &PaymentThresholds, balance_wei: i128) -> bool {
+    balance_wei <= payment_thresholds.unban_below_gwei as i128 * WEIS_IN_GWEI
+}
+
+pub fn payables_from_ui(accounts: &[UiSimulatedAccount]) -> Result<Vec<SimulatedPayable>, String> {
+    accounts
+        .iter()
+        .map(|account| {
+            let wallet = wallet_from_ui(&account.wallet)?;
+            if account.balance_gwei < 0 {
+                return Err(format!(
+                    "Payable to {} cannot have a negative balance ({} gwei)",
+                    account.wallet, account.balance_gwei
+                ));
+            }
+            Ok(SimulatedPayable {
+                wallet,
+                balance_wei: gwei_to_wei(account.balance_gwei as u64),
+                age_s: account.age_s,
+            })
+        })
+        .collect()
+}
+
+// An operator can only ask about automatic bans here; a manual one would never change anyway
+pub fn receivables_from_ui(
+    accounts: &[UiSimulatedAccount],
+) -> Result<Vec<SimulatedReceivable>, String> {
+    accounts
+        .iter()
+        .map(|account| {
+            Ok(SimulatedReceivable {
+                wallet: wallet_from_ui(&account.wallet)?,
+                balance_wei: gwei_to_wei(account.balance_gwei),
+                age_s: account.age_s,
+                ban_status: if account.banned {
+                    SimulatedBanStatus::Banned
+                } else {
+                    SimulatedBanStatus::NotBanned
+                },
+            })
+        })
+        .collect()
+}
+
+pub fn payables_from_books(
+    accounts: Vec<PayableAccount>,
+    now: SystemTime,
+) -> Vec<SimulatedPayable> {
+    accounts
+        .into_iter()
+        .map(|account| SimulatedPayable {
+            age_s: age_at(now, account.last_paid_timestamp),
+            wallet: account.wallet,
+            balance_wei: account.balance_wei,
+        })
+        .collect()
+}
+
+pub fn receivables_from_books(
+    accounts: Vec<ReceivableAccount>,
+    ban_list: &[BannedAccount],
+    now: SystemTime,
+) -> Vec<SimulatedReceivable> {
+    accounts
+        .into_iter()
+        .map(|account| {
+            let ban_status = match ban_list.iter().find(|ban| ban.wallet == account.wallet) {
+                None => SimulatedBanStatus::NotBanned,
+                Some(ban) if ban.manual => SimulatedBanStatus::ManuallyBanned,
+                Some(_) => SimulatedBanStatus::Banned,
+            };
+            SimulatedReceivable {
+                age_s: age_at(now, account.last_received_timestamp),
+                wallet: account.wallet,
+                balance_wei: account.balance_wei,
+                ban_status,
+            }
+        })
+        .collect()
+}
+
+pub fn events_to_ui(events: Vec<ThresholdEvent>) -> Vec<UiThresholdEvent> {
+    events
+        .into_iter()
+        .map(|event| UiThresholdEvent {
+            offset_s: event.offset_s,
+            wallet: event.wallet.to_string(),
+            event: match event.kind {
+                ThresholdEventKind::Paid => UiThresholdEventType::Paid,
+                ThresholdEventKind::Banned => UiThresholdEventType::Banned,
+                ThresholdEventKind::Unbanned => UiThresholdEventType::Unbanned,
+            },
+            balance_gwei: wei_to_gwei(event.balance_wei),
+            age_s: event.age_s,
+        })
+        .collect()
+}
+
+fn wallet_from_ui(wallet: &str) -> Result<Wallet, String> {
+    Wallet::from_str(wallet).map_err(|e| format!("Invalid wallet '{}': {:?}", wallet, e))
+}
+
+fn age_at(now: SystemTime, timestamp: SystemTime) -> u64 {
+    now.duration_since(timestamp)
+        .map(|age| age.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::make_wallet;
+    use std::time::Duration;
+
+    fn make_thresholds() -> PaymentThresholds {
+        PaymentThresholds {
+            debt_threshold_gwei: 1_000,
+            maturity_threshold_sec: 100,
+            payment_grace_period_sec: 50,
+            permanent_debt_allowed_gwei: 200,
+            threshold_interval_sec: 800,
+            unban_below_gwei: 200,
+        }
+    }
+
+    fn make_receivable(
+        name: &str,
+        balance_gwei: i128,
+        age_s: u64,
+        ban_status: SimulatedBanStatus,
+    ) -> SimulatedReceivable {
+        SimulatedReceivable {
+            wallet: make_wallet(name),
+            balance_wei: balance_gwei * WEIS_IN_GWEI,
+            age_s,
+            ban_status,
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(MAX_SIMULATION_STEPS, 10_000);
+    }
+
+    #[test]
+    fn payable_is_paid_once_it_matures_and_not_again() {
+        let payable = SimulatedPayable {
+            wallet: make_wallet("creditor"),
+            balance_wei: gwei_to_wei(1_001_u64),
+            age_s: 50,
+        };
+
+        let result = simulate_thresholds(&make_thresholds(), &[payable], &[], 200, 10).unwrap();
+
+        assert_eq!(
+            result,
+            vec![ThresholdEvent {
+                offset_s: 60,
+                wallet: make_wallet("creditor"),
+                kind: ThresholdEventKind::Paid,
+                balance_wei: gwei_to_wei(1_001_u64),
+                age_s: 110,
+            }]
+        )
+    }
+
+    #[test]
+    fn payable_below_the_threshold_is_paid_when_the_declining_threshold_reaches_it() {
+        // The threshold falls by one gwei per second after maturity
+        let payable = SimulatedPayable {
+            wallet: make_wallet("creditor"),
+            balance_wei: gwei_to_wei(600_u64),
+            age_s: 0,
+        };
+
+        let result = simulate_thresholds(&make_thresholds(), &[payable], &[], 1_000, 100).unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].offset_s, 600);
+        assert_eq!(result[0].kind, ThresholdEventKind::Paid);
+    }
+
+    #[test]
+    fn payable_within_permanent_debt_allowance_is_never_paid() {
+        let payable = SimulatedPayable {
+            wallet: make_wallet("creditor"),
+            balance_wei: gwei_to_wei(200_u64),
+            age_s: 1_000_000,
+        };
+
+        let result = simulate_thresholds(&make_thresholds(), &[payable], &[], 1_000, 10).unwrap();
+
+        assert_eq!(result, vec![])
+    }
+
+    #[test]
+    fn debtor_is_banned_only_after_grace_period_runs_out() {
+        let receivable = make_receivable("debtor", 1_001, 100, SimulatedBanStatus::NotBanned);
+
+        let result = simulate_thresholds(&make_thresholds(), &[], &[receivable], 100, 10).unwrap();
+
+        assert_eq!(
+            result,
+            vec![ThresholdEvent {
+                offset_s: 60,
+                wallet: make_wallet("debtor"),
+                kind: ThresholdEventKind::Banned,
+                balance_wei: 1_001 * WEIS_IN_GWEI,
+                age_s: 160,
+            }]
+        )
+    }
+
+    #[test]
+    fn debtor_within_permanent_debt_allowance_is_never_banned() {
+        let receivable = make_receivable("debtor", 200, 1_000_000, SimulatedBanStatus::NotBanned);
+
+        let result = simulate_thresholds(&make_thresholds(), &[], &[receivable], 100, 10).unwrap();
+
+        assert_eq!(result, vec![])
+    }
+
+    #[test]
+    fn automatically_banned_debtor_who_paid_up_is_unbanned_but_manual_ban_stays() {
+        let receivables = [
+            make_receivable("auto", 150, 1_000_000, SimulatedBanStatus::Banned),
+            make_receivable("manual", 150, 1_000_000, SimulatedBanStatus::ManuallyBanned),
+            make_receivable("still owing", 900, 1_000_000, SimulatedBanStatus::Banned),
+        ];
+
+        let result = simulate_thresholds(&make_thresholds(), &[], &receivables, 100, 10).unwrap();
+
+        assert_eq!(
+            result,
+            vec![ThresholdEvent {
+                offset_s: 0,
+                wallet: make_wallet("auto"),
+                kind: ThresholdEventKind::Unbanned,
+                balance_wei: 150 * WEIS_IN_GWEI,
+                age_s: 1_000_000,
+            }]
+        )
+    }
+
+    #[test]
+    fn debtor_flaps_between_ban_and_unban_if_unban_level_sits_above_permanent_debt() {
+        let mut thresholds = make_thresholds();
+        thresholds.unban_below_gwei = 500;
+        let receivable = make_receivable("debtor", 400, 1_000_000, SimulatedBanStatus::Banned);
+
+        let result = simulate_thresholds(&thresholds, &[], &[receivable], 20, 10).unwrap();
+
+        let kinds = result
+            .iter()
+            .map(|event| (event.offset_s, event.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (0, ThresholdEventKind::Unbanned),
+                (10, ThresholdEventKind::Banned),
+                (20, ThresholdEventKind::Unbanned)
+            ]
+        )
+    }
+
+    #[test]
+    fn accounts_from_ui_are_converted_to_wei_and_checked() {
+        let wallet = make_wallet("abc").to_string();
+        let account = |balance_gwei: i64, banned: bool| UiSimulatedAccount {
+            wallet: wallet.clone(),
+            balance_gwei,
+            age_s: 1234,
+            banned,
+        };
+
+        assert_eq!(
+            payables_from_ui(&[account(5, false)]),
+            Ok(vec![SimulatedPayable {
+                wallet: make_wallet("abc"),
+                balance_wei: 5 * WEIS_IN_GWEI as u128,
+                age_s: 1234,
+            }])
+        );
+        assert_eq!(
+            payables_from_ui(&[account(-5, false)]),
+            Err(format!(
+                "Payable to {} cannot have a negative balance (-5 gwei)",
+                wallet
+            ))
+        );
+        assert_eq!(
+            receivables_from_ui(&[account(-5, true)]),
+            Ok(vec![make_receivable(
+                "abc",
+                -5,
+                1234,
+                SimulatedBanStatus::Banned
+            )])
+        );
+        let result = receivables_from_ui(&[UiSimulatedAccount {
+            wallet: "booga".to_string(),
+            balance_gwei: 5,
+            age_s: 0,
+            banned: false,
+        }]);
+        assert!(result.unwrap_err().starts_with("Invalid wallet 'booga': "));
+    }
+
+    #[test]
+    fn accounts_from_books_carry_their_age_and_ban_status() {
+        let now = SystemTime::now();
+        let receivable = |name: &str| ReceivableAccount {
+            wallet: make_wallet(name),
+            balance_wei: 7_000_000_000,
+            last_received_timestamp: now - Duration::from_secs(300),
+        };
+        let ban = |name: &str, manual: bool| BannedAccount {
+            wallet: make_wallet(name),
+            reason_opt: None,
+            timestamp_opt: None,
+            manual,
+        };
+        let payable = PayableAccount {
+            wallet: make_wallet("creditor"),
+            balance_wei: 9_000_000_000,
+            last_paid_timestamp: now + Duration::from_secs(10),
+            pending_payable_opt: None,
+        };
+
+        let receivables = receivables_from_books(
+            vec![receivable("free"), receivable("auto"), receivable("manual")],
+            &[ban("auto", false), ban("manual", true)],
+            now,
+        );
+        let payables = payables_from_books(vec![payable], now);
+
+        assert_eq!(
+            receivables,
+            vec![
+                make_receivable("free", 7, 300, SimulatedBanStatus::NotBanned),
+                make_receivable("auto", 7, 300, SimulatedBanStatus::Banned),
+                make_receivable("manual", 7, 300, SimulatedBanStatus::ManuallyBanned),
+            ]
+        );
+        assert_eq!(
+            payables,
+            vec![SimulatedPayable {
+                wallet: make_wallet("creditor"),
+                balance_wei: 9_000_000_000,
+                age_s: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn events_are_reported_to_ui_in_gwei() {
+        let events = vec![ThresholdEvent {
+            offset_s: 600,
+            wallet: make_wallet("debtor"),
+            kind: ThresholdEventKind::Unbanned,
+            balance_wei: 123_456_789_012,
+            age_s: 4_000,
+        }];
+
+        let result = events_to_ui(events);
+
+        assert_eq!(
+            result,
+            vec![UiThresholdEvent {
+                offset_s: 600,
+                wallet: make_wallet("debtor").to_string(),
+                event: UiThresholdEventType::Unbanned,
+                balance_gwei: 123,
+                age_s: 4_000,
+            }]
+        )
+    }
+
+    #[test]
+    fn simulation_rejects_unusable_parameters() {
+        let mut equal_debts = make_thresholds();
+        equal_debts.permanent_debt_allowed_gwei = equal_debts.debt_threshold_gwei;
+        let mut zero_interval = make_thresholds();
+        zero_interval.threshold_interval_sec = 0;
+        let mut long_interval = make_thresholds();
+        long_interval.threshold_interval_sec = 1_000_000_001;
+
+        assert_eq!(
+            simulate_thresholds(&equal_debts, &[], &[], 100, 10),
+            Err(
+                "Value of DebtThresholdGwei (1000) must be bigger than PermanentDebtAllowedGwei (1000)"
+                    .to_string()
+            )
+        );
+        let interval_msg =
+            "Value of ThresholdIntervalSec must be between 1 and 1,000,000,000 s".to_string();
+        assert_eq!(
+            simulate_thresholds(&zero_interval, &[], &[], 100, 10),
+            Err(interval_msg.clone())
+        );
+        assert_eq!(
+            simulate_thresholds(&long_interval, &[], &[], 100, 10),
+            Err(interval_msg)
+        );
+        assert_eq!(
+            simulate_thresholds(&make_thresholds(), &[], &[], 100, 0),
+            Err("Simulation step must be at least 1 s".to_string())
+        );
+        assert_eq!(
+            simulate_thresholds(&make_thresholds(), &[], &[], 100_000, 10),
+            Err(
+                "Simulation of 100000 s in steps of 10 s would take more than 10000 steps"
+                    .to_string()
+            )
+        );
+        assert!(simulate_thresholds(&make_thresholds(), &[], &[], 99_999, 10).is_ok());
+    }
+}
@@ -13,9 +13,11 @@ use super::ui_gateway::UiGateway;
 use crate::accountant::db_access_objects::banned_dao::{BannedCacheLoader, BannedCacheLoaderReal};
 use crate::blockchain::blockchain_bridge::{BlockchainBridge, BlockchainBridgeSubsFactoryReal};
 use crate::bootstrapper::CryptDEPair;
+use crate::clandestine_port_hopper::ClandestinePortHopper;
 use crate::database::db_initializer::DbInitializationConfig;
 use crate::database::db_initializer::{connection_or_panic, DbInitializer, DbInitializerReal};
 use crate::db_config::persistent_configuration::PersistentConfiguration;
+use crate::metrics::metrics_server::start_metrics_server;
 use crate::node_configurator::configurator::Configurator;
 use crate::sub_lib::accountant::{AccountantSubs, AccountantSubsFactoryReal, DaoFactories};
 use crate::sub_lib::blockchain_bridge::BlockchainBridgeSubs;
@@ -26,12 +28,13 @@ use crate::sub_lib::hopper::HopperConfig;
 use crate::sub_lib::hopper::HopperSubs;
 use crate::sub_lib::neighborhood::{NeighborhoodMode, NeighborhoodSubs};
 use crate::sub_lib::peer_actors::{BindMessage, StartMessage};
-use crate::sub_lib::peer_actors::{NewPublicIp, PeerActors};
+use crate::sub_lib::peer_actors::{NewClandestinePorts, NewPublicIp, PeerActors};
 use crate::sub_lib::proxy_client::ProxyClientConfig;
 use crate::sub_lib::proxy_client::ProxyClientSubs;
 use crate::sub_lib::proxy_server::ProxyServerSubs;
 use crate::sub_lib::ui_gateway::UiGatewaySubs;
 use actix::Recipient;
+use actix::{Actor, Context, Handler};
 use actix::{Addr, Arbiter};
 use automap_lib::comm_layer::AutomapError;
 use automap_lib::control_layer::automap_control::{
@@ -127,6 +130,7 @@ impl ActorSystemFactoryTools for ActorSystemFactoryToolsReal {
                         .exit_service_rate,
                     exit_byte_rate: config.neighborhood_config.mode.rate_pack().exit_byte_rate,
                     is_decentralized: config.neighborhood_config.mode.is_decentralized(),
+                    metrics: config.metrics.clone(),
                     crashable: is_crashable(&config),
                 }),
             )
@@ -146,6 +150,8 @@ impl ActorSystemFactoryTools for ActorSystemFactoryToolsReal {
                 .rate_pack()
                 .routing_byte_rate,
             is_decentralized: config.neighborhood_config.mode.is_decentralized(),
+            relay_batch_latency_opt: config.relay_batch_latency_opt,
+            metrics: config.metrics.clone(),
             crashable: is_crashable(&config),
         });
         let blockchain_bridge_subs = actor_factory
@@ -186,26 +192,22 @@ impl ActorSystemFactoryTools for ActorSystemFactoryToolsReal {
         if let Some(subs) = proxy_client_subs_opt {
             send_bind_message!(subs, peer_actors);
         }
-        stream_handler_pool_subs
-            .bind
+
+        pool_bind_sub
             .try_send(PoolBindMessage {
                 dispatcher_subs: dispatcher_subs.clone(),
                 stream_handler_pool_subs: stream_handler_pool_subs.clone(),
                 neighborhood_subs: neighborhood_subs.clone(),
-            })
-            .expect("Stream Handler Pool is dead");
-        pool_bind_sub
-            .try_send(PoolBindMessage {
-                dispatcher_subs,
-                stream_handler_pool_subs: stream_handler_pool_subs.clone(),
-                neighborhood_subs,
+                new_ports_subs: vec![],
             })
             .expect("Dispatcher is dead");
 
         self.log_recipient_setter
             .prepare_log_recipient(ui_gateway_subs.node_to_ui_message_sub);
 
-        self.start_automap(
+        Self::maybe_start_metrics_server(&config);
+
+        let automap_port_mapper_opt = self.start_automap(
             &config,
             persistent_config,
             vec![
@@ -213,6 +215,22 @@ impl ActorSystemFactoryTools for ActorSystemFactoryToolsReal {
                 peer_actors.dispatcher.new_ip_sub.clone(),
             ],
         );
+        let mut new_ports_subs = vec![
+            peer_actors.neighborhood.new_clandestine_ports.clone(),
+            peer_actors.dispatcher.new_ports_sub.clone(),
+        ];
+        new_ports_subs.extend(automap_port_mapper_opt);
+        // The StreamHandlerPool opens the extra clandestine ports when it's bound, so it's bound
+        // last, once Automap is ready to map them.
+        stream_handler_pool_subs
+            .bind
+            .try_send(PoolBindMessage {
+                dispatcher_subs,
+                stream_handler_pool_subs: stream_handler_pool_subs.clone(),
+                neighborhood_subs,
+                new_ports_subs,
+            })
+            .expect("Stream Handler Pool is dead");
 
         //after we've bound all the actors, send start messages to any actors that need it
         send_start_message!(peer_actors.neighborhood);
@@ -268,6 +286,21 @@ impl ActorSystemFactoryToolsReal {
         exit_process(1, &format!("Automap failure: {}{:?}", prefix, error));
     }
 
+    fn maybe_start_metrics_server(config: &BootstrapperConfig) {
+        if let Some(port) = config.metrics_port_opt {
+            if let Err(e) = start_metrics_server(
+                port,
+                config.metrics.clone(),
+                config.ui_gateway_config.ui_token_file_opt.clone(),
+            ) {
+                exit_process(
+                    1,
+                    &format!("Couldn't serve metrics on port {}: {}", port, e),
+                );
+            }
+        }
+    }
+
     fn maybe_save_usual_protocol(
         automap_control: &dyn AutomapControl,
         persistent_config: &mut dyn PersistentConfiguration,
@@ -298,11 +331,11 @@ impl ActorSystemFactoryToolsReal {
         config: &BootstrapperConfig,
         mut persistent_config: Box<dyn PersistentConfiguration>,
         new_ip_recipients: Vec<Recipient<NewPublicIp>>,
-    ) {
+    ) -> Option<Recipient<NewClandestinePorts>> {
         if let NeighborhoodMode::Standard(node_addr, _, _) = &config.neighborhood_config.mode {
             // If we already know the IP address, no need for Automap
             if node_addr.ip_addr() != IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)) {
-                return;
+                return None;
             }
             let change_handler = move |change: AutomapChange| match change {
                 AutomapChange::NewIp(new_public_ip) => {
@@ -320,7 +353,7 @@ impl ActorSystemFactoryToolsReal {
                 Ok(ip) => ip,
                 Err(e) => {
                     Self::handle_automap_error("Can't get public IP - ", e);
-                    return; // never happens; handle_automap_error doesn't return.
+                    return None; // never happens; handle_automap_error doesn't return.
                 }
             };
             Self::maybe_save_usual_protocol(
@@ -337,6 +370,54 @@ impl ActorSystemFactoryToolsReal {
                     );
                 }
             });
+            if config.clandestine_port_rotation_opt.is_some() {
+                let mapper = AutomapPortMapper::new(automap_control, &node_addr.ports());
+                return Some(mapper.start().recipient::<NewClandestinePorts>());
+            }
+        }
+        None
+    }
+}
+
+// Keeps the AutomapControl alive after startup so that hopped clandestine ports can be mapped
+// through the router too. Automap can't remove individual mappings, so the mappings for ports
+// that have been hopped away from are left to expire.
+pub struct AutomapPortMapper {
+    automap_control: Box<dyn AutomapControl>,
+    mapped_ports: Vec<u16>,
+    logger: Logger,
+}
+
+impl Actor for AutomapPortMapper {
+    type Context = Context<Self>;
+}
+
+impl Handler<NewClandestinePorts> for AutomapPortMapper {
+    type Result = ();
+
+    fn handle(&mut self, msg: NewClandestinePorts, _ctx: &mut Self::Context) -> Self::Result {
+        msg.ports
+            .iter()
+            .filter(|port| !self.mapped_ports.contains(port))
+            .for_each(|port| match self.automap_control.add_mapping(*port) {
+                Ok(_) => debug!(self.logger, "Mapped port {} through the router", port),
+                Err(e) => warning!(
+                    self.logger,
+                    "Can't map port {} through the router: {:?}",
+                    port,
+                    e
+                ),
+            });
+        self.mapped_ports = msg.ports;
+    }
+}
+
+impl AutomapPortMapper {
+    pub fn new(automap_control: Box<dyn AutomapControl>, mapped_ports: &[u16]) -> Self {
+        Self {
+            automap_control,
+            mapped_ports: mapped_ports.to_vec(),
+            logger: Logger::new("AutomapPortMapper"),
         }
     }
 }
@@ -407,16 +488,27 @@ impl ActorFactory for ActorFactoryReal {
         } else {
             None
         };
+        let receipts_wallet_opt = config
+            .consuming_wallet_opt
+            .clone()
+            .filter(|wallet| is_decentralized && wallet.can_sign());
+        let contract_address = config.blockchain_bridge_config.chain.rec().contract;
         let crashable = is_crashable(config);
         let arbiter = Arbiter::builder().stop_system_on_panic(true);
         let addr: Addr<ProxyServer> = arbiter.start(move |_| {
-            ProxyServer::new(
+            let proxy_server = ProxyServer::new(
                 cryptdes.main,
                 cryptdes.alias,
                 is_decentralized,
                 consuming_wallet_balance,
                 crashable,
-            )
+            );
+            match receipts_wallet_opt {
+                Some(consuming_wallet) => {
+                    proxy_server.with_service_receipts(consuming_wallet, contract_address)
+                }
+                None => proxy_server,
+            }
         });
         ProxyServer::make_subs_from(&addr)
     }
@@ -451,6 +543,8 @@ impl ActorFactory for ActorFactoryReal {
         let pending_payable_dao_factory = Box::new(Accountant::dao_factory(data_directory));
         let receivable_dao_factory = Box::new(Accountant::dao_factory(data_directory));
         let banned_dao_factory = Box::new(Accountant::dao_factory(data_directory));
+        let ledger_dao_factory = Box::new(Accountant::dao_factory(data_directory));
+        let service_receipt_dao_factory = Box::new(Accountant::dao_factory(data_directory));
         let config_dao_factory = Box::new(Accountant::dao_factory(data_directory));
         Self::load_banned_cache(db_initializer, banned_cache_loader, data_directory);
         let arbiter = Arbiter::builder().stop_system_on_panic(true);
@@ -462,6 +556,8 @@ impl ActorFactory for ActorFactoryReal {
                     pending_payable_dao_factory,
                     receivable_dao_factory,
                     banned_dao_factory,
+                    ledger_dao_factory,
+                    service_receipt_dao_factory,
                     config_dao_factory,
                 },
             )
@@ -483,10 +579,20 @@ impl ActorFactory for ActorFactoryReal {
     ) -> StreamHandlerPoolSubs {
         let clandestine_discriminator_factories =
             config.clandestine_discriminator_factories.clone();
+        let clandestine_websocket_mode = config.clandestine_websocket_mode;
+        let clandestine_port_hopper_opt = ClandestinePortHopper::from_config(config);
+        let metrics = config.metrics.clone();
         let crashable = is_crashable(config);
         let arbiter = Arbiter::builder().stop_system_on_panic(true);
-        let addr: Addr<StreamHandlerPool> = arbiter
-            .start(move |_| StreamHandlerPool::new(clandestine_discriminator_factories, crashable));
+        let addr: Addr<StreamHandlerPool> = arbiter.start(move |_| {
+            StreamHandlerPool::new(
+                clandestine_discriminator_factories,
+                clandestine_websocket_mode,
+                clandestine_port_hopper_opt,
+                metrics,
+                crashable,
+            )
+        });
         StreamHandlerPool::make_subs_from(&addr)
     }
 
@@ -505,6 +611,13 @@ impl ActorFactory for ActorFactoryReal {
             .blockchain_bridge_config
             .blockchain_service_url_opt
             .clone();
+        let blockchain_service_quorum = config.blockchain_bridge_config.blockchain_service_quorum;
+        let external_signer_url_opt = config
+            .blockchain_bridge_config
+            .external_signer_url_opt
+            .clone();
+        let multisend_contract_opt = config.blockchain_bridge_config.multisend_contract_opt;
+        let multisend_max_creditors = config.blockchain_bridge_config.multisend_max_creditors;
         let crashable = is_crashable(config);
         let data_directory = config.data_directory.clone();
         let chain = config.blockchain_bridge_config.chain;
@@ -512,6 +625,10 @@ impl ActorFactory for ActorFactoryReal {
         let addr: Addr<BlockchainBridge> = arbiter.start(move |_| {
             let blockchain_interface = BlockchainBridge::initialize_blockchain_interface(
                 blockchain_service_url_opt,
+                blockchain_service_quorum,
+                external_signer_url_opt,
+                multisend_contract_opt,
+                multisend_max_creditors,
                 chain,
             );
             let persistent_config =
@@ -623,16 +740,23 @@ where
 mod tests {
     use super::*;
     use crate::accountant::exportable_test_parts::test_accountant_is_constructed_with_upgraded_db_connection_recognizing_our_extra_sqlite_functions;
-    use crate::accountant::DEFAULT_PENDING_TOO_LONG_SEC;
+    use crate::accountant::{
+        DEFAULT_BALANCE_WARNING_DAYS, DEFAULT_CONFIRMATION_DEPTH, DEFAULT_LEDGER_ROLLUP_DAYS,
+        DEFAULT_PENDING_STUCK_SEC, DEFAULT_PENDING_TOO_LONG_SEC,
+    };
     use crate::blockchain::blockchain_bridge::exportable_test_parts::test_blockchain_bridge_is_constructed_with_correctly_functioning_connections;
-    use crate::bootstrapper::{Bootstrapper, RealUser};
+    use crate::bootstrapper::{Bootstrapper, ClandestineWebSocketMode, RealUser};
+    use crate::metrics::NodeMetrics;
+    use crate::neighborhood::pricing::PricingConfig;
     use crate::node_test_utils::{
         make_stream_handler_pool_subs_from_recorder, start_recorder_refcell_opt,
     };
+    use crate::server_initializer::LogFileConfig;
     use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
     use crate::sub_lib::blockchain_bridge::BlockchainBridgeConfig;
     use crate::sub_lib::cryptde::{PlainData, PublicKey};
     use crate::sub_lib::cryptde_null::CryptDENull;
+    use crate::sub_lib::crypto_suite::CryptoSuite;
     use crate::sub_lib::dispatcher::{InboundClientData, StreamShutdownMsg};
     use crate::sub_lib::neighborhood::NeighborhoodMode;
     use crate::sub_lib::neighborhood::NodeDescriptor;
@@ -669,19 +793,23 @@ mod tests {
     };
     use crossbeam_channel::unbounded;
     use log::LevelFilter;
-    use masq_lib::constants::DEFAULT_CHAIN;
+    use masq_lib::constants::{DEFAULT_CHAIN, DEFAULT_MULTISEND_MAX_CREDITORS};
     use masq_lib::crash_point::CrashPoint;
     #[cfg(feature = "log_recipient_test")]
     use masq_lib::logger::INITIALIZATION_COUNTER;
     use masq_lib::messages::{ToMessageBody, UiCrashRequest, UiDescriptorRequest};
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
     use masq_lib::test_utils::utils::{ensure_node_home_directory_exists, TEST_DEFAULT_CHAIN};
     use masq_lib::ui_gateway::NodeFromUiMessage;
     use masq_lib::utils::running_test;
     use masq_lib::utils::AutomapProtocol::Igdp;
+    use masq_lib::utils::{find_free_port, localhost};
     use std::cell::RefCell;
     use std::collections::HashMap;
     use std::convert::TryFrom;
+    use std::io::{Read, Write};
     use std::net::Ipv4Addr;
+    use std::net::TcpStream;
     use std::net::{IpAddr, SocketAddr, SocketAddrV4};
     use std::path::PathBuf;
     use std::ptr::addr_of;
@@ -827,6 +955,7 @@ mod tests {
                 stream_shutdown_sub: recipient!(addr, StreamShutdownMsg),
                 ui_sub: recipient!(addr, NodeFromUiMessage),
                 new_ip_sub: recipient!(addr, NewPublicIp),
+                new_ports_sub: recipient!(addr, NewClandestinePorts),
             };
             (dispatcher_subs, addr.recipient::<PoolBindMessage>())
         }
@@ -1145,13 +1274,28 @@ mod tests {
         let config = BootstrapperConfig {
             log_level: LevelFilter::Off,
             crash_point: CrashPoint::None,
+            clandestine_websocket_mode: ClandestineWebSocketMode::Off,
+            clandestine_port_count: 1,
+            clandestine_port_rotation_opt: None,
+            relay_batch_latency_opt: None,
+            metrics_port_opt: None,
+            metrics: Arc::new(NodeMetrics::default()),
+            log_file_config: LogFileConfig::default(),
+            crypto_suite: CryptoSuite::Curve25519,
             dns_servers: vec![],
             scan_intervals_opt: Some(ScanIntervals::default()),
             suppress_initial_scans: false,
             clandestine_discriminator_factories: Vec::new(),
-            ui_gateway_config: UiGatewayConfig { ui_port: 5335 },
+            ui_gateway_config: UiGatewayConfig {
+                ui_port: 5335,
+                ui_token_file_opt: None,
+            },
             blockchain_bridge_config: BlockchainBridgeConfig {
                 blockchain_service_url_opt: None,
+                blockchain_service_quorum: 1,
+                external_signer_url_opt: None,
+                multisend_contract_opt: None,
+                multisend_max_creditors: DEFAULT_MULTISEND_MAX_CREDITORS,
                 chain: TEST_DEFAULT_CHAIN,
                 gas_price: 1,
             },
@@ -1174,8 +1318,14 @@ mod tests {
                 ),
                 min_hops: MIN_HOPS_FOR_TEST,
             },
+            pricing_config: PricingConfig::default(),
             payment_thresholds_opt: Some(PaymentThresholds::default()),
             when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            when_pending_stuck_sec: DEFAULT_PENDING_STUCK_SEC,
+            confirmation_depth: DEFAULT_CONFIRMATION_DEPTH,
+            ledger_rollup_days: DEFAULT_LEDGER_ROLLUP_DAYS,
+            balance_warning_days: DEFAULT_BALANCE_WARNING_DAYS,
+            ledger_retention_days_opt: None,
         };
         let persistent_config = PersistentConfigurationMock::default()
             .chain_name_result("eth-ropsten".to_string())
@@ -1220,13 +1370,28 @@ mod tests {
         let config = BootstrapperConfig {
             log_level: LevelFilter::Off,
             crash_point: CrashPoint::None,
+            clandestine_websocket_mode: ClandestineWebSocketMode::Off,
+            clandestine_port_count: 1,
+            clandestine_port_rotation_opt: None,
+            relay_batch_latency_opt: None,
+            metrics_port_opt: None,
+            metrics: Arc::new(NodeMetrics::default()),
+            log_file_config: LogFileConfig::default(),
+            crypto_suite: CryptoSuite::Curve25519,
             dns_servers: vec![],
             scan_intervals_opt: None,
             suppress_initial_scans: false,
             clandestine_discriminator_factories: Vec::new(),
-            ui_gateway_config: UiGatewayConfig { ui_port: 5335 },
+            ui_gateway_config: UiGatewayConfig {
+                ui_port: 5335,
+                ui_token_file_opt: None,
+            },
             blockchain_bridge_config: BlockchainBridgeConfig {
                 blockchain_service_url_opt: None,
+                blockchain_service_quorum: 1,
+                external_signer_url_opt: None,
+                multisend_contract_opt: None,
+                multisend_max_creditors: DEFAULT_MULTISEND_MAX_CREDITORS,
                 chain: TEST_DEFAULT_CHAIN,
                 gas_price: 1,
             },
@@ -1249,8 +1414,14 @@ mod tests {
                 ),
                 min_hops: MIN_HOPS_FOR_TEST,
             },
+            pricing_config: PricingConfig::default(),
             payment_thresholds_opt: Default::default(),
-            when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC
+            when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            when_pending_stuck_sec: DEFAULT_PENDING_STUCK_SEC,
+            confirmation_depth: DEFAULT_CONFIRMATION_DEPTH,
+            ledger_rollup_days: DEFAULT_LEDGER_ROLLUP_DAYS,
+            balance_warning_days: DEFAULT_BALANCE_WARNING_DAYS,
+            ledger_retention_days_opt: None
         };
         let add_mapping_params_arc = Arc::new(Mutex::new(vec![]));
         let mut subject = make_subject_with_null_setter();
@@ -1297,12 +1468,14 @@ mod tests {
         check_cryptde(hopper_config.cryptdes.main);
         assert_eq!(hopper_config.per_routing_service, 300);
         assert_eq!(hopper_config.per_routing_byte, 101);
+        assert!(Arc::ptr_eq(&hopper_config.metrics, &config.metrics));
         let proxy_client_config = Parameters::get(parameters.proxy_client_params);
         check_cryptde(proxy_client_config.cryptde);
         assert_eq!(proxy_client_config.exit_service_rate, 500);
         assert_eq!(proxy_client_config.exit_byte_rate, 103);
         assert_eq!(proxy_client_config.dns_servers, config.dns_servers);
         assert_eq!(proxy_client_config.is_decentralized, true);
+        assert!(Arc::ptr_eq(&proxy_client_config.metrics, &config.metrics));
         let (actual_cryptde_pair, bootstrapper_config) =
             Parameters::get(parameters.proxy_server_params);
         check_cryptde(actual_cryptde_pair.main);
@@ -1344,6 +1517,10 @@ mod tests {
             blockchain_bridge_param.blockchain_bridge_config,
             BlockchainBridgeConfig {
                 blockchain_service_url_opt: None,
+                blockchain_service_quorum: 1,
+                external_signer_url_opt: None,
+                multisend_contract_opt: None,
+                multisend_max_creditors: DEFAULT_MULTISEND_MAX_CREDITORS,
                 chain: TEST_DEFAULT_CHAIN,
                 gas_price: 1
             }
@@ -1466,6 +1643,48 @@ mod tests {
         )
     }
 
+    #[test]
+    fn metrics_server_is_started_if_metrics_port_is_configured() {
+        let port = find_free_port();
+        let mut config = BootstrapperConfig::new();
+        config.metrics_port_opt = Some(port);
+
+        ActorSystemFactoryToolsReal::maybe_start_metrics_server(&config);
+
+        let mut stream = TcpStream::connect(SocketAddr::new(localhost(), port)).unwrap();
+        stream.write_all(b"GET /metrics HTTP/1.1\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.contains("masq_node_relayed_bytes_total"));
+    }
+
+    #[test]
+    fn metrics_server_is_guarded_by_the_ui_token_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "actor_system_factory",
+            "metrics_server_is_guarded_by_the_ui_token_file",
+        );
+        let ui_token_file = home_dir.join("ui_tokens");
+        std::fs::write(&ui_token_file, "admin=booga\nread-only=wooga\n").unwrap();
+        let port = find_free_port();
+        let mut config = BootstrapperConfig::new();
+        config.metrics_port_opt = Some(port);
+        config.ui_gateway_config.ui_token_file_opt = Some(ui_token_file);
+
+        ActorSystemFactoryToolsReal::maybe_start_metrics_server(&config);
+
+        let mut stream = TcpStream::connect(SocketAddr::new(localhost(), port)).unwrap();
+        stream.write_all(b"GET /metrics HTTP/1.1\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(
+            response.starts_with("HTTP/1.1 401 Unauthorized\r\n"),
+            "{}",
+            response
+        );
+    }
+
     #[test]
     fn automap_protocol_is_not_saved_if_indifferent_from_last_time() {
         let config_entry = Some(AutomapProtocol::Igdp);
@@ -1523,13 +1742,28 @@ mod tests {
         let config = BootstrapperConfig {
             log_level: LevelFilter::Off,
             crash_point: CrashPoint::None,
+            clandestine_websocket_mode: ClandestineWebSocketMode::Off,
+            clandestine_port_count: 1,
+            clandestine_port_rotation_opt: None,
+            relay_batch_latency_opt: None,
+            metrics_port_opt: None,
+            metrics: Arc::new(NodeMetrics::default()),
+            log_file_config: LogFileConfig::default(),
+            crypto_suite: CryptoSuite::Curve25519,
             dns_servers: vec![],
             scan_intervals_opt: None,
             suppress_initial_scans: false,
             clandestine_discriminator_factories: Vec::new(),
-            ui_gateway_config: UiGatewayConfig { ui_port: 5335 },
+            ui_gateway_config: UiGatewayConfig {
+                ui_port: 5335,
+                ui_token_file_opt: None,
+            },
             blockchain_bridge_config: BlockchainBridgeConfig {
                 blockchain_service_url_opt: None,
+                blockchain_service_quorum: 1,
+                external_signer_url_opt: None,
+                multisend_contract_opt: None,
+                multisend_max_creditors: DEFAULT_MULTISEND_MAX_CREDITORS,
                 chain: TEST_DEFAULT_CHAIN,
                 gas_price: 1,
             },
@@ -1548,8 +1782,14 @@ mod tests {
                 mode: NeighborhoodMode::ConsumeOnly(vec![]),
                 min_hops: MIN_HOPS_FOR_TEST,
             },
+            pricing_config: PricingConfig::default(),
             payment_thresholds_opt: Default::default(),
-            when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC
+            when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            when_pending_stuck_sec: DEFAULT_PENDING_STUCK_SEC,
+            confirmation_depth: DEFAULT_CONFIRMATION_DEPTH,
+            ledger_rollup_days: DEFAULT_LEDGER_ROLLUP_DAYS,
+            balance_warning_days: DEFAULT_BALANCE_WARNING_DAYS,
+            ledger_retention_days_opt: None
         };
         let system = System::new("MASQNode");
         let mut subject = make_subject_with_null_setter();
@@ -1599,6 +1839,102 @@ mod tests {
         // no not-enough-results-provided error: test passes
     }
 
+    #[test]
+    fn start_automap_hands_automap_over_to_a_port_mapper_if_clandestine_ports_are_rotated() {
+        let add_mapping_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = ActorSystemFactoryToolsReal::new();
+        let automap_control = Box::new(
+            AutomapControlMock::new()
+                .get_public_ip_result(Ok(IpAddr::from_str("1.2.3.4").unwrap()))
+                .get_mapping_protocol_result(Some(AutomapProtocol::Pmp))
+                .add_mapping_params(&add_mapping_params_arc)
+                .add_mapping_result(Ok(()))
+                .add_mapping_result(Ok(()))
+                .add_mapping_result(Ok(())),
+        );
+        subject.automap_control_factory =
+            Box::new(AutomapControlFactoryMock::new().make_result(automap_control));
+        let mut config = BootstrapperConfig::default();
+        config.mapping_protocol_opt = Some(AutomapProtocol::Pmp);
+        config.clandestine_port_rotation_opt = Some(Duration::from_secs(600));
+        config.neighborhood_config.mode = NeighborhoodMode::Standard(
+            NodeAddr::new(&IpAddr::from_str("0.0.0.0").unwrap(), &[1234, 2345]),
+            vec![],
+            DEFAULT_RATE_PACK,
+        );
+        let system = System::new("test");
+
+        let result = subject.start_automap(
+            &config,
+            Box::new(PersistentConfigurationMock::new()),
+            vec![],
+        );
+
+        result
+            .unwrap()
+            .try_send(NewClandestinePorts {
+                ports: vec![1234, 3456],
+            })
+            .unwrap();
+        System::current().stop();
+        system.run();
+        let add_mapping_params = add_mapping_params_arc.lock().unwrap();
+        assert_eq!(*add_mapping_params, vec![1234, 2345, 3456]);
+    }
+
+    #[test]
+    fn start_automap_keeps_no_port_mapper_if_clandestine_ports_are_not_rotated() {
+        let mut subject = ActorSystemFactoryToolsReal::new();
+        let automap_control = Box::new(
+            AutomapControlMock::new()
+                .get_public_ip_result(Ok(IpAddr::from_str("1.2.3.4").unwrap()))
+                .get_mapping_protocol_result(Some(AutomapProtocol::Pmp))
+                .add_mapping_result(Ok(())),
+        );
+        subject.automap_control_factory =
+            Box::new(AutomapControlFactoryMock::new().make_result(automap_control));
+        let mut config = BootstrapperConfig::default();
+        config.mapping_protocol_opt = Some(AutomapProtocol::Pmp);
+        config.neighborhood_config.mode = NeighborhoodMode::Standard(
+            NodeAddr::new(&IpAddr::from_str("0.0.0.0").unwrap(), &[1234]),
+            vec![],
+            DEFAULT_RATE_PACK,
+        );
+
+        let result = subject.start_automap(
+            &config,
+            Box::new(PersistentConfigurationMock::new()),
+            vec![],
+        );
+
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn automap_port_mapper_complains_about_ports_it_cannot_map() {
+        init_test_logging();
+        let add_mapping_params_arc = Arc::new(Mutex::new(vec![]));
+        let automap_control = AutomapControlMock::new()
+            .add_mapping_params(&add_mapping_params_arc)
+            .add_mapping_result(Err(AutomapError::PermanentLeasesOnly));
+        let subject = AutomapPortMapper::new(Box::new(automap_control), &[1234, 2345]);
+        let system = System::new("test");
+        let addr = subject.start();
+
+        addr.try_send(NewClandestinePorts {
+            ports: vec![1234, 3456],
+        })
+        .unwrap();
+
+        System::current().stop();
+        system.run();
+        let add_mapping_params = add_mapping_params_arc.lock().unwrap();
+        assert_eq!(*add_mapping_params, vec![3456]);
+        TestLogHandler::new().exists_log_containing(
+            "WARN: AutomapPortMapper: Can't map port 3456 through the router: PermanentLeasesOnly",
+        );
+    }
+
     #[test]
     #[should_panic(expected = "1: Automap failure: AllProtocolsFailed")]
     fn start_automap_change_handler_handles_remapping_errors_properly() {
@@ -1709,13 +2045,28 @@ mod tests {
         let config = BootstrapperConfig {
             log_level: LevelFilter::Off,
             crash_point: CrashPoint::None,
+            clandestine_websocket_mode: ClandestineWebSocketMode::Off,
+            clandestine_port_count: 1,
+            clandestine_port_rotation_opt: None,
+            relay_batch_latency_opt: None,
+            metrics_port_opt: None,
+            metrics: Arc::new(NodeMetrics::default()),
+            log_file_config: LogFileConfig::default(),
+            crypto_suite: CryptoSuite::Curve25519,
             dns_servers: vec![],
             scan_intervals_opt: None,
             suppress_initial_scans: false,
             clandestine_discriminator_factories: Vec::new(),
-            ui_gateway_config: UiGatewayConfig { ui_port: 5335 },
+            ui_gateway_config: UiGatewayConfig {
+                ui_port: 5335,
+                ui_token_file_opt: None,
+            },
             blockchain_bridge_config: BlockchainBridgeConfig {
                 blockchain_service_url_opt: None,
+                blockchain_service_quorum: 1,
+                external_signer_url_opt: None,
+                multisend_contract_opt: None,
+                multisend_max_creditors: DEFAULT_MULTISEND_MAX_CREDITORS,
                 chain: TEST_DEFAULT_CHAIN,
                 gas_price: 1,
             },
@@ -1737,9 +2088,15 @@ mod tests {
                 ),
                 min_hops: MIN_HOPS_FOR_TEST,
             },
+            pricing_config: PricingConfig::default(),
             node_descriptor: Default::default(),
             payment_thresholds_opt: Default::default(),
             when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            when_pending_stuck_sec: DEFAULT_PENDING_STUCK_SEC,
+            confirmation_depth: DEFAULT_CONFIRMATION_DEPTH,
+            ledger_rollup_days: DEFAULT_LEDGER_ROLLUP_DAYS,
+            balance_warning_days: DEFAULT_BALANCE_WARNING_DAYS,
+            ledger_retention_days_opt: None,
         };
         let subject = make_subject_with_null_setter();
         let system = System::new("MASQNode");
@@ -1780,6 +2137,7 @@ mod tests {
                 )],
                 exit_service_rate: 50,
                 is_decentralized: true,
+                metrics: Arc::new(NodeMetrics::default()),
                 crashable: true,
                 exit_byte_rate: 50,
             };
@@ -1798,6 +2156,8 @@ mod tests {
                 per_routing_service: 100,
                 per_routing_byte: 50,
                 is_decentralized: false,
+                relay_batch_latency_opt: None,
+                metrics: Arc::new(NodeMetrics::default()),
                 crashable: true,
             };
             let subscribers = ActorFactoryReal {}.make_and_start_hopper(hopper_config);
@@ -1,12 +1,14 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use crate::daemon::restart_supervisor::validate_restart_policy;
 use clap::{crate_description, crate_version, App, AppSettings, Arg};
 use indoc::indoc;
 use lazy_static::lazy_static;
 use masq_lib::constants::{HIGHEST_USABLE_PORT, LOWEST_USABLE_INSECURE_PORT};
 use masq_lib::shared_schema::{
-    chain_arg, data_directory_arg, db_password_arg, real_user_arg, shared_app, ui_port_arg,
-    DB_PASSWORD_HELP,
+    chain_arg, data_directory_arg, db_password_arg, real_user_arg, shared_app,
+    ui_authentication_arg, ui_port_arg, ui_token_file_arg, DB_PASSWORD_HELP,
+    UI_AUTHENTICATION_HELP, UI_TOKEN_FILE_HELP,
 };
 use masq_lib::utils::DATA_DIRECTORY_DAEMON_HELP;
 
@@ -33,10 +35,24 @@ pub fn app_daemon() -> App<'static, 'static> {
                 .help("Directs MASQ to start the Daemon that controls the Node, rather than the Node itself"),
         )
         .arg(ui_port_arg(&DAEMON_UI_PORT_HELP))
+        .arg(ui_token_file_arg(UI_TOKEN_FILE_HELP))
+        .arg(ui_authentication_arg(UI_AUTHENTICATION_HELP))
+        .arg(
+            Arg::with_name("restart-policy")
+                .long("restart-policy")
+                .value_name("RESTART-POLICY")
+                .required(false)
+                .takes_value(true)
+                .validator(validate_restart_policy)
+                .help(RESTART_POLICY_HELP),
+        )
 }
 
 pub fn app_node() -> App<'static, 'static> {
-    shared_app(app_head().after_help(NODE_HELP_TEXT)).arg(ui_port_arg(&DAEMON_UI_PORT_HELP))
+    shared_app(app_head().after_help(NODE_HELP_TEXT))
+        .arg(ui_port_arg(&DAEMON_UI_PORT_HELP))
+        .arg(ui_token_file_arg(UI_TOKEN_FILE_HELP))
+        .arg(ui_authentication_arg(UI_AUTHENTICATION_HELP))
 }
 
 pub fn app_config_dumper() -> App<'static, 'static> {
@@ -66,6 +82,12 @@ lazy_static! {
 const DUMP_CONFIG_HELP: &str =
     "Dump the configuration of MASQ Node to stdout in JSON. Used chiefly by UIs.";
 
+const RESTART_POLICY_HELP: &str = "If the Node crashes, the Daemon will relaunch it with the last setup according \
+    to this policy, written as MaxRestarts|InitialBackoffSec|MaxBackoffSec|CrashLoopWindowSec: for example, \
+    5|10|600|3600. Each restart waits twice as long as the one before, starting at InitialBackoffSec and never \
+    longer than MaxBackoffSec; once MaxRestarts restarts have happened within CrashLoopWindowSec, the Daemon \
+    decides the Node is crash-looping and leaves it down. Without this parameter, a crashed Node stays down.";
+
 const NODE_HELP_TEXT: &str = indoc!(
     r"ADDITIONAL HELP:
     If you want to start the MASQ Daemon to manage the MASQ Node and the MASQ UIs, try:
@@ -132,6 +154,14 @@ mod tests {
             home_dir.to_string_lossy().to_string().as_str()
             )
         );
+        assert_eq!(
+            RESTART_POLICY_HELP,
+            "If the Node crashes, the Daemon will relaunch it with the last setup according \
+            to this policy, written as MaxRestarts|InitialBackoffSec|MaxBackoffSec|CrashLoopWindowSec: for example, \
+            5|10|600|3600. Each restart waits twice as long as the one before, starting at InitialBackoffSec and never \
+            longer than MaxBackoffSec; once MaxRestarts restarts have happened within CrashLoopWindowSec, the Daemon \
+            decides the Node is crash-looping and leaves it down. Without this parameter, a crashed Node stays down."
+        );
         assert_eq!(
             DUMP_CONFIG_HELP,
             "Dump the configuration of MASQ Node to stdout in JSON. Used chiefly by UIs."
@@ -1,20 +1,29 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::accountant::db_access_objects::payable_dao::PayableAccount;
-use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
+use crate::accountant::db_access_objects::pending_payable_dao::PendingPayableReplacement;
+use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::{
+    BlockchainAgent, TransactionFeeModel,
+};
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::{
     BlockchainAgentWithContextMessage, QualifiedPayablesMessage,
 };
 use crate::accountant::{
-    ReceivedPayments, ResponseSkeleton, ScanError, SentPayables, SkeletonOptHolder,
+    comma_joined_stringifiable, ReportTransactionReceipts, RequestTransactionReceipts,
+    StuckPayables,
+};
+use crate::accountant::{
+    ReceivedPayments, ReportConsumingWalletBalances, ResponseSkeleton, ScanError, SentPayables,
+    SkeletonOptHolder,
 };
-use crate::accountant::{ReportTransactionReceipts, RequestTransactionReceipts};
 use crate::actor_system_factory::SubsFactory;
 use crate::blockchain::blockchain_interface::blockchain_interface_null::BlockchainInterfaceNull;
 use crate::blockchain::blockchain_interface::data_structures::errors::{
     BlockchainError, PayableTransactionError,
 };
-use crate::blockchain::blockchain_interface::data_structures::ProcessedPayableFallible;
+use crate::blockchain::blockchain_interface::data_structures::{
+    BlockchainTransaction, ProcessedPayableFallible,
+};
 use crate::blockchain::blockchain_interface::BlockchainInterface;
 use crate::blockchain::blockchain_interface_initializer::BlockchainInterfaceInitializer;
 use crate::database::db_initializer::{DbInitializationConfig, DbInitializer, DbInitializerReal};
@@ -38,9 +47,10 @@ use masq_lib::messages::ScanType;
 use masq_lib::ui_gateway::NodeFromUiMessage;
 use masq_lib::utils::to_string;
 use regex::Regex;
+use std::iter::once;
 use std::path::Path;
 use std::time::SystemTime;
-use web3::types::{BlockNumber, TransactionReceipt, H256};
+use web3::types::{Address, BlockNumber, TransactionReceipt, H256};
 
 pub const CRASH_KEY: &str = "BLOCKCHAINBRIDGE";
 
@@ -52,6 +62,7 @@ pub struct BlockchainBridge {
     payable_payments_setup_subs_opt: Option<Recipient<BlockchainAgentWithContextMessage>>,
     received_payments_subs_opt: Option<Recipient<ReceivedPayments>>,
     scan_error_subs_opt: Option<Recipient<ScanError>>,
+    consuming_wallet_balances_subs_opt: Option<Recipient<ReportConsumingWalletBalances>>,
     crashable: bool,
     pending_payable_confirmation: TransactionConfirmationTools,
 }
@@ -80,6 +91,8 @@ impl Handler<BindMessage> for BlockchainBridge {
         self.sent_payable_subs_opt = Some(msg.peer_actors.accountant.report_sent_payments);
         self.received_payments_subs_opt = Some(msg.peer_actors.accountant.report_inbound_payments);
         self.scan_error_subs_opt = Some(msg.peer_actors.accountant.scan_errors);
+        self.consuming_wallet_balances_subs_opt =
+            Some(msg.peer_actors.accountant.report_consuming_wallet_balances);
         // There's a multinode integration test looking for this message
         debug!(self.logger, "Received BindMessage");
     }
@@ -88,6 +101,7 @@ impl Handler<BindMessage> for BlockchainBridge {
 #[derive(Debug, PartialEq, Eq, Message, Clone)]
 pub struct RetrieveTransactions {
     pub recipient: Wallet,
+    pub confirmation_depth: u64,
     pub response_skeleton_opt: Option<ResponseSkeleton>,
 }
 
@@ -145,10 +159,32 @@ impl Handler<OutboundPaymentsInstructions> for BlockchainBridge {
     }
 }
 
+// Asks for what the Accountant needs to forecast how long the consuming wallet will last
+#[derive(Debug, PartialEq, Eq, Message, Clone)]
+pub struct RequestConsumingWalletBalances {
+    pub consuming_wallet: Wallet,
+}
+
+impl Handler<RequestConsumingWalletBalances> for BlockchainBridge {
+    type Result = ();
+
+    fn handle(&mut self, msg: RequestConsumingWalletBalances, _ctx: &mut Self::Context) {
+        if let Err(e) = self.handle_request_consuming_wallet_balances(msg) {
+            warning!(
+                self.logger,
+                "Cannot forecast the balances of the consuming wallet: {}",
+                e
+            )
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Message)]
 pub struct PendingPayableFingerprintSeeds {
     pub batch_wide_timestamp: SystemTime,
     pub hashes_and_balances: Vec<(H256, u128)>,
+    // One for each hash, in the same order
+    pub resubmission_data: Vec<ResubmissionData>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -161,6 +197,18 @@ pub struct PendingPayableFingerprint {
     pub attempt: u16,
     pub amount: u128,
     pub process_error: Option<String>,
+    // Hashes of the transactions this one has replaced, the oldest first
+    pub replaced_hashes: Vec<H256>,
+    // Missing at records from before the transactions could be replaced
+    pub resubmission_data_opt: Option<ResubmissionData>,
+}
+
+// What it takes to replace a stuck transaction by another one with the same nonce
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ResubmissionData {
+    pub nonce: u64,
+    pub recipient_wallet: Wallet,
+    pub fee_model: TransactionFeeModel,
 }
 
 impl Handler<NodeFromUiMessage> for BlockchainBridge {
@@ -184,6 +232,7 @@ impl BlockchainBridge {
             payable_payments_setup_subs_opt: None,
             received_payments_subs_opt: None,
             scan_error_subs_opt: None,
+            consuming_wallet_balances_subs_opt: None,
             crashable,
             logger: Logger::new("BlockchainBridge"),
             pending_payable_confirmation: TransactionConfirmationTools {
@@ -206,13 +255,24 @@ impl BlockchainBridge {
 
     pub fn initialize_blockchain_interface(
         blockchain_service_url_opt: Option<String>,
+        blockchain_service_quorum: u16,
+        external_signer_url_opt: Option<String>,
+        multisend_contract_opt: Option<Address>,
+        multisend_max_creditors: u16,
         chain: Chain,
     ) -> Box<dyn BlockchainInterface> {
         match blockchain_service_url_opt {
             Some(url) => {
                 // TODO if we decided to have interchangeably runtime switchable or simultaneously usable interfaces we will
                 // probably want to make BlockchainInterfaceInitializer a collaborator that's a part of the actor
-                BlockchainInterfaceInitializer {}.initialize_interface(&url, chain)
+                BlockchainInterfaceInitializer {}.initialize_interface(
+                    &url,
+                    blockchain_service_quorum,
+                    external_signer_url_opt.as_deref(),
+                    multisend_contract_opt,
+                    multisend_max_creditors,
+                    chain,
+                )
             }
             None => Box::new(BlockchainInterfaceNull::default()),
         }
@@ -226,6 +286,7 @@ impl BlockchainBridge {
             retrieve_transactions: recipient!(addr, RetrieveTransactions),
             ui_sub: recipient!(addr, NodeFromUiMessage),
             request_transaction_receipts: recipient!(addr, RequestTransactionReceipts),
+            request_consuming_wallet_balances: recipient!(addr, RequestConsumingWalletBalances),
         }
     }
 
@@ -253,6 +314,29 @@ impl BlockchainBridge {
         Ok(())
     }
 
+    fn handle_request_consuming_wallet_balances(
+        &mut self,
+        msg: RequestConsumingWalletBalances,
+    ) -> Result<(), String> {
+        // The agent knows the balances as well as what a payment costs in fees on this chain
+        let agent = self
+            .blockchain_interface
+            .build_blockchain_agent(&msg.consuming_wallet, &*self.persistent_config)
+            .map_err(to_string)?;
+
+        self.consuming_wallet_balances_subs_opt
+            .as_ref()
+            .expect("Accountant is unbound")
+            .try_send(ReportConsumingWalletBalances {
+                consuming_wallet: msg.consuming_wallet,
+                balances: agent.consuming_wallet_balances(),
+                transaction_fee_per_payment_wei: agent.estimated_transaction_fee_total(1),
+            })
+            .expect("Accountant is dead");
+
+        Ok(())
+    }
+
     fn handle_outbound_payments_instructions(
         &mut self,
         msg: OutboundPaymentsInstructions,
@@ -288,31 +372,60 @@ impl BlockchainBridge {
             Ok(Some(mbc)) => mbc,
             _ => u64::MAX,
         };
+        // Payments credited in the latest blocks below the start block are retrieved once more, so
+        // that the Accountant can tell whether a chain reorganization has orphaned any of them. Half
+        // of the block count limit at most, so that every scan still gets somewhere.
+        let scanned_from_block_nbr =
+            start_block_nbr.saturating_sub(msg.confirmation_depth.min(max_block_count / 2));
         let end_block = match self
             .blockchain_interface
             .lower_interface()
             .get_block_number()
         {
             Ok(eb) => {
+                let confirmed_block_nbr = eb.as_u64().saturating_sub(msg.confirmation_depth);
+                if confirmed_block_nbr < start_block_nbr {
+                    debug!(
+                        self.logger,
+                        "No block past {} has {} confirmations yet",
+                        start_block_nbr,
+                        msg.confirmation_depth
+                    );
+                    return self.send_received_payments(
+                        vec![],
+                        start_block_nbr,
+                        start_block_nbr,
+                        msg.response_skeleton_opt,
+                    );
+                }
                 if u64::MAX == max_block_count {
-                    BlockNumber::Number(eb)
+                    BlockNumber::Number(confirmed_block_nbr.into())
                 } else {
-                    BlockNumber::Number(eb.as_u64().min(start_block_nbr + max_block_count).into())
+                    BlockNumber::Number(
+                        confirmed_block_nbr
+                            .min(scanned_from_block_nbr + max_block_count)
+                            .into(),
+                    )
                 }
             }
             Err(e) => {
-                info!(
+                // Without the latest block number, there's no telling which blocks are confirmed
+                warning!(
                     self.logger,
-                    "Using 'latest' block number instead of a literal number. {:?}", e
+                    "Skipping the scan for received payments: couldn't tell how many confirmations \
+                    blocks past {} have. {:?}",
+                    start_block_nbr,
+                    e
+                );
+                return self.send_received_payments(
+                    vec![],
+                    start_block_nbr,
+                    start_block_nbr,
+                    msg.response_skeleton_opt,
                 );
-                if max_block_count == u64::MAX {
-                    BlockNumber::Latest
-                } else {
-                    BlockNumber::Number((start_block_nbr + max_block_count).into())
-                }
             }
         };
-        let start_block = BlockNumber::Number(start_block_nbr.into());
+        let start_block = BlockNumber::Number(scanned_from_block_nbr.into());
         let retrieved_transactions =
             self.blockchain_interface
                 .retrieve_transactions(start_block, end_block, &msg.recipient);
@@ -321,17 +434,12 @@ impl BlockchainBridge {
                 if transactions.transactions.is_empty() {
                     debug!(self.logger, "No new receivable detected");
                 }
-                self.received_payments_subs_opt
-                    .as_ref()
-                    .expect("Accountant is unbound")
-                    .try_send(ReceivedPayments {
-                        timestamp: SystemTime::now(),
-                        payments: transactions.transactions,
-                        new_start_block: transactions.new_start_block,
-                        response_skeleton_opt: msg.response_skeleton_opt,
-                    })
-                    .expect("Accountant is dead.");
-                Ok(())
+                self.send_received_payments(
+                    transactions.transactions,
+                    scanned_from_block_nbr,
+                    transactions.new_start_block,
+                    msg.response_skeleton_opt,
+                )
             }
             Err(e) => {
                 if let Some(max_block_count) = self.extract_max_block_count(e.clone()) {
@@ -363,6 +471,27 @@ impl BlockchainBridge {
         }
     }
 
+    fn send_received_payments(
+        &self,
+        payments: Vec<BlockchainTransaction>,
+        scanned_from_block: u64,
+        new_start_block: u64,
+        response_skeleton_opt: Option<ResponseSkeleton>,
+    ) -> Result<(), String> {
+        self.received_payments_subs_opt
+            .as_ref()
+            .expect("Accountant is unbound")
+            .try_send(ReceivedPayments {
+                timestamp: SystemTime::now(),
+                payments,
+                scanned_from_block,
+                new_start_block,
+                response_skeleton_opt,
+            })
+            .expect("Accountant is dead.");
+        Ok(())
+    }
+
     fn handle_request_transaction_receipts(
         &mut self,
         msg: RequestTransactionReceipts,
@@ -374,15 +503,12 @@ impl BlockchainBridge {
         let (vector_of_results, error_opt) = msg.pending_payable.iter().fold(
             init,
             |(mut ok_receipts, err_opt), current_fingerprint| match err_opt {
-                None => match self
-                    .blockchain_interface
-                    .get_transaction_receipt(current_fingerprint.hash)
-                {
+                None => match self.receipt_of_any_transaction(current_fingerprint) {
                     Ok(receipt_opt) => {
                         ok_receipts.push(receipt_opt);
                         (ok_receipts, None)
                     }
-                    Err(e) => (ok_receipts, Some((e, current_fingerprint.hash))),
+                    Err(e) => (ok_receipts, Some(e)),
                 },
                 _ => (ok_receipts, err_opt),
             },
@@ -391,12 +517,19 @@ impl BlockchainBridge {
             .into_iter()
             .zip(msg.pending_payable.into_iter())
             .collect_vec();
+        let replacements = match msg.stuck_payables_opt {
+            Some(stuck_payables) if error_opt.is_none() => {
+                self.replace_stuck_payables(&pairs, stuck_payables)
+            }
+            _ => vec![],
+        };
         self.pending_payable_confirmation
             .report_transaction_receipts_sub_opt
             .as_ref()
             .expect("Accountant is unbound")
             .try_send(ReportTransactionReceipts {
                 fingerprints_with_receipts: pairs,
+                replacements,
                 response_skeleton_opt: msg.response_skeleton_opt,
             })
             .expect("Accountant is dead");
@@ -410,6 +543,57 @@ impl BlockchainBridge {
         Ok(())
     }
 
+    // Only one of the transactions sharing the nonce can be mined, the latest being the likeliest
+    fn receipt_of_any_transaction(
+        &self,
+        fingerprint: &PendingPayableFingerprint,
+    ) -> Result<Option<TransactionReceipt>, (BlockchainError, H256)> {
+        for hash in once(&fingerprint.hash).chain(fingerprint.replaced_hashes.iter().rev()) {
+            match self.blockchain_interface.get_transaction_receipt(*hash) {
+                Ok(Some(receipt)) => return Ok(Some(receipt)),
+                Ok(None) => (),
+                Err(e) => return Err((e, *hash)),
+            }
+        }
+        Ok(None)
+    }
+
+    fn replace_stuck_payables(
+        &self,
+        fingerprints_with_receipts: &[(Option<TransactionReceipt>, PendingPayableFingerprint)],
+        stuck_payables: StuckPayables,
+    ) -> Vec<PendingPayableReplacement> {
+        let still_stuck = fingerprints_with_receipts
+            .iter()
+            .filter(|(receipt_opt, fingerprint)| {
+                receipt_opt.is_none() && stuck_payables.rowids.contains(&fingerprint.rowid)
+            })
+            .map(|(_, fingerprint)| fingerprint.clone())
+            .collect::<Vec<PendingPayableFingerprint>>();
+        if still_stuck.is_empty() {
+            return vec![];
+        }
+        match self.blockchain_interface.replace_stuck_payables(
+            &stuck_payables.consuming_wallet,
+            &*self.persistent_config,
+            &still_stuck,
+        ) {
+            Ok(replacements) => replacements,
+            Err(e) => {
+                warning!(
+                    self.logger,
+                    "Failed to replace stuck pending payables {}: {}",
+                    comma_joined_stringifiable(&still_stuck, |fingerprint| format!(
+                        "{:?}",
+                        fingerprint.hash
+                    )),
+                    e
+                );
+                vec![]
+            }
+        }
+    }
+
     fn handle_scan<M, F>(&mut self, handler: F, scan_type: ScanType, msg: M)
     where
         F: FnOnce(&mut BlockchainBridge, M) -> Result<(), String>,
@@ -500,7 +684,7 @@ mod tests {
     use crate::accountant::db_access_objects::utils::from_time_t;
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::test_utils::BlockchainAgentMock;
     use crate::accountant::scanners::test_utils::protect_payables_in_test;
-    use crate::accountant::test_utils::make_pending_payable_fingerprint;
+    use crate::accountant::test_utils::{make_pending_payable_fingerprint, make_resubmission_data};
     use crate::blockchain::blockchain_interface::blockchain_interface_null::BlockchainInterfaceNull;
     use crate::blockchain::blockchain_interface::data_structures::errors::{
         BlockchainAgentBuildError, PayableTransactionError,
@@ -514,6 +698,7 @@ mod tests {
     use crate::db_config::persistent_configuration::PersistentConfigError;
     use crate::match_every_type_id;
     use crate::node_test_utils::check_timestamp;
+    use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
     use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
     use crate::test_utils::recorder::{make_recorder, peer_actors_builder};
     use crate::test_utils::recorder_stop_conditions::StopCondition;
@@ -525,8 +710,9 @@ mod tests {
     };
     use crate::test_utils::{make_paying_wallet, make_wallet};
     use actix::System;
-    use ethereum_types::U64;
+    use ethereum_types::{U256, U64};
     use ethsign_crypto::Keccak256;
+    use masq_lib::constants::DEFAULT_MULTISEND_MAX_CREDITORS;
     use masq_lib::messages::ScanType;
     use masq_lib::test_utils::logging::init_test_logging;
     use masq_lib::test_utils::logging::TestLogHandler;
@@ -556,7 +742,14 @@ mod tests {
 
     #[test]
     fn blockchain_interface_null_as_result_of_missing_blockchain_service_url() {
-        let result = BlockchainBridge::initialize_blockchain_interface(None, TEST_DEFAULT_CHAIN);
+        let result = BlockchainBridge::initialize_blockchain_interface(
+            None,
+            1,
+            None,
+            None,
+            DEFAULT_MULTISEND_MAX_CREDITORS,
+            TEST_DEFAULT_CHAIN,
+        );
 
         result
             .as_any()
@@ -710,6 +903,92 @@ mod tests {
             .exists_log_containing(&format!("WARN: {test_name}: {expected_error_msg}"));
     }
 
+    #[test]
+    fn request_for_consuming_wallet_balances_is_answered_from_the_blockchain_agent() {
+        let system = System::new(
+            "request_for_consuming_wallet_balances_is_answered_from_the_blockchain_agent",
+        );
+        let build_blockchain_agent_params_arc = Arc::new(Mutex::new(vec![]));
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let balances = ConsumingWalletBalances::new(U256::from(123_456), U256::from(987_654_321));
+        let agent = BlockchainAgentMock::default()
+            .consuming_wallet_balances_result(balances)
+            .estimated_transaction_fee_total_result(55_000);
+        let blockchain_interface = BlockchainInterfaceMock::default()
+            .build_blockchain_agent_params(&build_blockchain_agent_params_arc)
+            .build_blockchain_agent_result(Ok(Box::new(agent)));
+        let persistent_config_id_stamp = ArbitraryIdStamp::new();
+        let persistent_configuration = PersistentConfigurationMock::default()
+            .set_arbitrary_id_stamp(persistent_config_id_stamp);
+        let consuming_wallet = make_paying_wallet(b"consuming");
+        let subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
+            Box::new(persistent_configuration),
+            false,
+        );
+        let addr = subject.start();
+        let subject_subs = BlockchainBridge::make_subs_from(&addr);
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        send_bind_message!(subject_subs, peer_actors);
+
+        addr.try_send(RequestConsumingWalletBalances {
+            consuming_wallet: consuming_wallet.clone(),
+        })
+        .unwrap();
+
+        System::current().stop();
+        system.run();
+        let build_blockchain_agent_params = build_blockchain_agent_params_arc.lock().unwrap();
+        assert_eq!(
+            *build_blockchain_agent_params,
+            vec![(consuming_wallet.clone(), persistent_config_id_stamp)]
+        );
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(
+            accountant_recording.get_record::<ReportConsumingWalletBalances>(0),
+            &ReportConsumingWalletBalances {
+                consuming_wallet,
+                balances,
+                transaction_fee_per_payment_wei: 55_000,
+            }
+        );
+        assert_eq!(accountant_recording.len(), 1);
+    }
+
+    #[test]
+    fn failed_request_for_consuming_wallet_balances_is_logged() {
+        init_test_logging();
+        let test_name = "failed_request_for_consuming_wallet_balances_is_logged";
+        let system = System::new(test_name);
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let blockchain_interface = BlockchainInterfaceMock::default()
+            .build_blockchain_agent_result(Err(BlockchainAgentBuildError::GasPrice(
+                PersistentConfigError::NotPresent,
+            )));
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
+            Box::new(PersistentConfigurationMock::default()),
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+        subject.consuming_wallet_balances_subs_opt = Some(accountant.start().recipient());
+        let addr = subject.start();
+
+        addr.try_send(RequestConsumingWalletBalances {
+            consuming_wallet: make_wallet("consuming"),
+        })
+        .unwrap();
+
+        System::current().stop();
+        system.run();
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(accountant_recording.len(), 0);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Cannot forecast the balances of the consuming wallet: \
+            Blockchain agent construction failed at fetching gas price from the database: NotPresent"
+        ));
+    }
+
     #[test]
     fn handle_outbound_payments_instructions_sees_payments_happen_and_sends_payment_results_back_to_accountant(
     ) {
@@ -935,6 +1214,8 @@ mod tests {
             attempt: 3,
             amount: 4565,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let blockchain_interface_mock = BlockchainInterfaceMock::default()
             .get_transaction_receipt_params(&get_transaction_receipt_params_arc)
@@ -958,6 +1239,7 @@ mod tests {
                 client_id: 1234,
                 context_id: 4321,
             }),
+            stuck_payables_opt: None,
         };
 
         let _ = addr.try_send(msg).unwrap();
@@ -982,12 +1264,213 @@ mod tests {
                     client_id: 1234,
                     context_id: 4321
                 }),
+                replacements: vec![],
             }
         );
         let get_transaction_receipt_params = get_transaction_receipt_params_arc.lock().unwrap();
         assert_eq!(*get_transaction_receipt_params, vec![hash_1, hash_2])
     }
 
+    #[test]
+    fn blockchain_bridge_looks_for_receipts_of_replaced_transactions_starting_with_the_latest() {
+        let get_transaction_receipt_params_arc = Arc::new(Mutex::new(vec![]));
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let report_transaction_receipts_recipient: Recipient<ReportTransactionReceipts> =
+            accountant
+                .system_stop_conditions(match_every_type_id!(ReportTransactionReceipts))
+                .start()
+                .recipient();
+        let hash_1 = make_tx_hash(111);
+        let hash_2 = make_tx_hash(222);
+        let hash_3 = make_tx_hash(333);
+        let mut fingerprint = make_pending_payable_fingerprint();
+        fingerprint.hash = hash_3;
+        fingerprint.replaced_hashes = vec![hash_1, hash_2];
+        let mut receipt = TransactionReceipt::default();
+        receipt.transaction_hash = hash_1;
+        let blockchain_interface_mock = BlockchainInterfaceMock::default()
+            .get_transaction_receipt_params(&get_transaction_receipt_params_arc)
+            .get_transaction_receipt_result(Ok(None))
+            .get_transaction_receipt_result(Ok(None))
+            .get_transaction_receipt_result(Ok(Some(receipt.clone())));
+        let system = System::new("test");
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface_mock),
+            Box::new(PersistentConfigurationMock::default()),
+            false,
+        );
+        subject
+            .pending_payable_confirmation
+            .report_transaction_receipts_sub_opt = Some(report_transaction_receipts_recipient);
+        let addr = subject.start();
+        let msg = RequestTransactionReceipts {
+            pending_payable: vec![fingerprint.clone()],
+            stuck_payables_opt: None,
+            response_skeleton_opt: None,
+        };
+
+        let _ = addr.try_send(msg).unwrap();
+
+        assert_eq!(system.run(), 0);
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        let received_message = accountant_recording.get_record::<ReportTransactionReceipts>(0);
+        assert_eq!(
+            received_message,
+            &ReportTransactionReceipts {
+                fingerprints_with_receipts: vec![(Some(receipt), fingerprint)],
+                replacements: vec![],
+                response_skeleton_opt: None,
+            }
+        );
+        let get_transaction_receipt_params = get_transaction_receipt_params_arc.lock().unwrap();
+        assert_eq!(
+            *get_transaction_receipt_params,
+            vec![hash_3, hash_2, hash_1]
+        )
+    }
+
+    #[test]
+    fn blockchain_bridge_replaces_stuck_payables_still_without_receipt() {
+        let replace_stuck_payables_params_arc = Arc::new(Mutex::new(vec![]));
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let report_transaction_receipts_recipient: Recipient<ReportTransactionReceipts> =
+            accountant
+                .system_stop_conditions(match_every_type_id!(ReportTransactionReceipts))
+                .start()
+                .recipient();
+        let consuming_wallet = make_paying_wallet(b"consuming");
+        let mut mined_meanwhile = make_pending_payable_fingerprint();
+        mined_meanwhile.rowid = 1;
+        mined_meanwhile.resubmission_data_opt = Some(make_resubmission_data(1));
+        let mut stuck = make_pending_payable_fingerprint();
+        stuck.rowid = 2;
+        stuck.hash = make_tx_hash(222);
+        stuck.resubmission_data_opt = Some(make_resubmission_data(2));
+        let mut young = make_pending_payable_fingerprint();
+        young.rowid = 3;
+        young.hash = make_tx_hash(333);
+        let replacement = PendingPayableReplacement {
+            rowid: 2,
+            hash: make_tx_hash(444),
+            replaced_hashes: vec![make_tx_hash(222)],
+            fee_model: TransactionFeeModel::Legacy {
+                gas_price_wei: 140_000_000_000,
+            },
+        };
+        let blockchain_interface_mock = BlockchainInterfaceMock::default()
+            .get_transaction_receipt_result(Ok(Some(TransactionReceipt::default())))
+            .get_transaction_receipt_result(Ok(None))
+            .get_transaction_receipt_result(Ok(None))
+            .replace_stuck_payables_params(&replace_stuck_payables_params_arc)
+            .replace_stuck_payables_result(Ok(vec![replacement.clone()]));
+        let persistent_config_id_stamp = ArbitraryIdStamp::new();
+        let persistent_config = PersistentConfigurationMock::default()
+            .set_arbitrary_id_stamp(persistent_config_id_stamp);
+        let system = System::new("test");
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface_mock),
+            Box::new(persistent_config),
+            false,
+        );
+        subject
+            .pending_payable_confirmation
+            .report_transaction_receipts_sub_opt = Some(report_transaction_receipts_recipient);
+        let addr = subject.start();
+        let msg = RequestTransactionReceipts {
+            pending_payable: vec![mined_meanwhile.clone(), stuck.clone(), young.clone()],
+            stuck_payables_opt: Some(StuckPayables {
+                consuming_wallet: consuming_wallet.clone(),
+                rowids: vec![1, 2],
+            }),
+            response_skeleton_opt: None,
+        };
+
+        let _ = addr.try_send(msg).unwrap();
+
+        assert_eq!(system.run(), 0);
+        let replace_stuck_payables_params = replace_stuck_payables_params_arc.lock().unwrap();
+        assert_eq!(
+            *replace_stuck_payables_params,
+            vec![(
+                consuming_wallet,
+                persistent_config_id_stamp,
+                vec![stuck.clone()]
+            )]
+        );
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        let received_message = accountant_recording.get_record::<ReportTransactionReceipts>(0);
+        assert_eq!(
+            received_message,
+            &ReportTransactionReceipts {
+                fingerprints_with_receipts: vec![
+                    (Some(TransactionReceipt::default()), mined_meanwhile),
+                    (None, stuck),
+                    (None, young)
+                ],
+                replacements: vec![replacement],
+                response_skeleton_opt: None,
+            }
+        );
+    }
+
+    #[test]
+    fn blockchain_bridge_reports_no_replacements_if_replacing_stuck_payables_failed() {
+        init_test_logging();
+        let test_name =
+            "blockchain_bridge_reports_no_replacements_if_replacing_stuck_payables_failed";
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let report_transaction_receipts_recipient: Recipient<ReportTransactionReceipts> =
+            accountant
+                .system_stop_conditions(match_every_type_id!(ReportTransactionReceipts))
+                .start()
+                .recipient();
+        let mut stuck = make_pending_payable_fingerprint();
+        stuck.hash = make_tx_hash(222);
+        stuck.resubmission_data_opt = Some(make_resubmission_data(2));
+        let blockchain_interface_mock = BlockchainInterfaceMock::default()
+            .get_transaction_receipt_result(Ok(None))
+            .replace_stuck_payables_result(Err(PayableTransactionError::GasPriceQueryFailed(
+                "Database busy".to_string(),
+            )));
+        let system = System::new("test");
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface_mock),
+            Box::new(PersistentConfigurationMock::default()),
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+        subject
+            .pending_payable_confirmation
+            .report_transaction_receipts_sub_opt = Some(report_transaction_receipts_recipient);
+        let addr = subject.start();
+        let msg = RequestTransactionReceipts {
+            pending_payable: vec![stuck.clone()],
+            stuck_payables_opt: Some(StuckPayables {
+                consuming_wallet: make_paying_wallet(b"consuming"),
+                rowids: vec![stuck.rowid],
+            }),
+            response_skeleton_opt: None,
+        };
+
+        let _ = addr.try_send(msg).unwrap();
+
+        assert_eq!(system.run(), 0);
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        let received_message = accountant_recording.get_record::<ReportTransactionReceipts>(0);
+        assert_eq!(
+            received_message,
+            &ReportTransactionReceipts {
+                fingerprints_with_receipts: vec![(None, stuck)],
+                replacements: vec![],
+                response_skeleton_opt: None,
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Failed to replace stuck pending payables 0x0000000000000000000000\
+            0000000000000000000000000000000000000000de: Unsuccessful gas price query: \"Database busy\""
+        ));
+    }
+
     #[test]
     fn blockchain_bridge_logs_error_from_retrieving_received_payments() {
         init_test_logging();
@@ -1014,6 +1497,7 @@ mod tests {
         subject.scan_error_subs_opt = Some(scan_error_recipient);
         let msg = RetrieveTransactions {
             recipient: make_wallet("blah"),
+            confirmation_depth: 4,
             response_skeleton_opt: None,
         };
         let subject_addr = subject.start();
@@ -1064,6 +1548,8 @@ mod tests {
             attempt: 3,
             amount: 3333,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let fingerprint_3 = PendingPayableFingerprint {
             rowid: 456,
@@ -1072,6 +1558,8 @@ mod tests {
             attempt: 3,
             amount: 4565,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let fingerprint_4 = PendingPayableFingerprint {
             rowid: 450,
@@ -1080,6 +1568,8 @@ mod tests {
             attempt: 1,
             amount: 7879,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let mut transaction_receipt = TransactionReceipt::default();
         transaction_receipt.block_number = Some(U64::from(4545454));
@@ -1112,6 +1602,7 @@ mod tests {
                 client_id: 1234,
                 context_id: 4321,
             }),
+            stuck_payables_opt: None,
         };
         let subject_addr = subject.start();
 
@@ -1137,6 +1628,7 @@ mod tests {
                     client_id: 1234,
                     context_id: 4321
                 }),
+                replacements: vec![],
             }
         );
         let scan_error_msg = accountant_recording.get_record::<ScanError>(1);
@@ -1165,6 +1657,7 @@ mod tests {
         let msg = RequestTransactionReceipts {
             pending_payable: vec![],
             response_skeleton_opt: None,
+            stuck_payables_opt: None,
         };
         let system = System::new(
             "blockchain_bridge_can_return_report_transaction_receipts_with_an_empty_vector",
@@ -1179,7 +1672,8 @@ mod tests {
             recording.get_record::<ReportTransactionReceipts>(0),
             &ReportTransactionReceipts {
                 fingerprints_with_receipts: vec![],
-                response_skeleton_opt: None
+                response_skeleton_opt: None,
+                replacements: vec![],
             }
         )
     }
@@ -1202,6 +1696,8 @@ mod tests {
             attempt: 3,
             amount: 3333,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let fingerprint_2 = PendingPayableFingerprint {
             rowid: 456,
@@ -1210,6 +1706,8 @@ mod tests {
             attempt: 3,
             amount: 4565,
             process_error: None,
+            replaced_hashes: vec![],
+            resubmission_data_opt: None,
         };
         let blockchain_interface_mock = BlockchainInterfaceMock::default()
             .get_transaction_receipt_params(&get_transaction_receipt_params_arc)
@@ -1227,6 +1725,7 @@ mod tests {
         let msg = RequestTransactionReceipts {
             pending_payable: vec![fingerprint_1, fingerprint_2],
             response_skeleton_opt: None,
+            stuck_payables_opt: None,
         };
         let system = System::new("test");
 
@@ -1245,7 +1744,8 @@ mod tests {
             recording.get_record::<ReportTransactionReceipts>(0),
             &ReportTransactionReceipts {
                 fingerprints_with_receipts: vec![],
-                response_skeleton_opt: None
+                response_skeleton_opt: None,
+                replacements: vec![],
             }
         );
         assert_eq!(
@@ -1262,38 +1762,19 @@ mod tests {
     }
 
     #[test]
-    fn handle_retrieve_transactions_uses_latest_block_number_upon_get_block_number_error() {
+    fn handle_retrieve_transactions_skips_the_scan_upon_get_block_number_error() {
         init_test_logging();
         let retrieve_transactions_params_arc = Arc::new(Mutex::new(vec![]));
-        let system = System::new(
-            "handle_retrieve_transactions_uses_latest_block_number_upon_get_block_number_error",
-        );
+        let system =
+            System::new("handle_retrieve_transactions_skips_the_scan_upon_get_block_number_error");
         let (accountant, _, accountant_recording_arc) = make_recorder();
         let earning_wallet = make_wallet("somewallet");
-        let amount = 42;
-        let amount2 = 55;
-        let expected_transactions = RetrievedBlockchainTransactions {
-            new_start_block: 8675309u64,
-            transactions: vec![
-                BlockchainTransaction {
-                    block_number: 7,
-                    from: earning_wallet.clone(),
-                    wei_amount: amount,
-                },
-                BlockchainTransaction {
-                    block_number: 9,
-                    from: earning_wallet.clone(),
-                    wei_amount: amount2,
-                },
-            ],
-        };
         let lower_interface =
             LowBlockchainIntMock::default().get_block_number_result(LatestBlockNumber::Err(
                 BlockchainError::QueryFailed("Failed to read the latest block number".to_string()),
             ));
         let blockchain_interface_mock = BlockchainInterfaceMock::default()
             .retrieve_transactions_params(&retrieve_transactions_params_arc)
-            .retrieve_transactions_result(Ok(expected_transactions.clone()))
             .lower_interface_results(Box::new(lower_interface));
         let persistent_config = PersistentConfigurationMock::new()
             .max_block_count_result(Ok(Some(10000u64)))
@@ -1308,7 +1789,8 @@ mod tests {
         let peer_actors = peer_actors_builder().accountant(accountant).build();
         send_bind_message!(subject_subs, peer_actors);
         let retrieve_transactions = RetrieveTransactions {
-            recipient: earning_wallet.clone(),
+            recipient: earning_wallet,
+            confirmation_depth: 4,
             response_skeleton_opt: Some(ResponseSkeleton {
                 client_id: 1234,
                 context_id: 4321,
@@ -1322,14 +1804,7 @@ mod tests {
         system.run();
         let after = SystemTime::now();
         let retrieve_transactions_params = retrieve_transactions_params_arc.lock().unwrap();
-        assert_eq!(
-            *retrieve_transactions_params,
-            vec![(
-                BlockNumber::Number(6u64.into()),
-                BlockNumber::Number(10006u64.into()),
-                earning_wallet
-            )]
-        );
+        assert!(retrieve_transactions_params.is_empty());
         let accountant_received_payment = accountant_recording_arc.lock().unwrap();
         assert_eq!(accountant_received_payment.len(), 1);
         let received_payments = accountant_received_payment.get_record::<ReceivedPayments>(0);
@@ -1338,15 +1813,19 @@ mod tests {
             received_payments,
             &ReceivedPayments {
                 timestamp: received_payments.timestamp,
-                payments: expected_transactions.transactions,
-                new_start_block: 8675309u64,
+                payments: vec![],
+                scanned_from_block: 6,
+                new_start_block: 6,
                 response_skeleton_opt: Some(ResponseSkeleton {
                     client_id: 1234,
                     context_id: 4321
                 }),
             }
         );
-        TestLogHandler::new().exists_log_containing("INFO: BlockchainBridge: Using 'latest' block number instead of a literal number. QueryFailed(\"Failed to read the latest block number\")");
+        TestLogHandler::new().exists_log_containing(
+            "WARN: BlockchainBridge: Skipping the scan for received payments: couldn't tell how \
+            many confirmations blocks past 6 have. QueryFailed(\"Failed to read the latest block number\")",
+        );
     }
 
     #[test]
@@ -1363,11 +1842,15 @@ mod tests {
             transactions: vec![
                 BlockchainTransaction {
                     block_number: 7,
+                    block_hash: make_tx_hash(1003),
+                    transaction_hash: make_tx_hash(3),
                     from: earning_wallet.clone(),
                     wei_amount: amount,
                 },
                 BlockchainTransaction {
                     block_number: 9,
+                    block_hash: make_tx_hash(1004),
+                    transaction_hash: make_tx_hash(4),
                     from: earning_wallet.clone(),
                     wei_amount: amount2,
                 },
@@ -1394,6 +1877,7 @@ mod tests {
         send_bind_message!(subject_subs, peer_actors);
         let retrieve_transactions = RetrieveTransactions {
             recipient: earning_wallet.clone(),
+            confirmation_depth: 4,
             response_skeleton_opt: Some(ResponseSkeleton {
                 client_id: 1234,
                 context_id: 4321,
@@ -1410,8 +1894,8 @@ mod tests {
         assert_eq!(
             *retrieve_transactions_params,
             vec![(
-                BlockNumber::Number(6u64.into()),
-                BlockNumber::Number(1024u64.into()),
+                BlockNumber::Number(2u64.into()),
+                BlockNumber::Number(1020u64.into()),
                 earning_wallet
             )]
         );
@@ -1424,6 +1908,7 @@ mod tests {
             &ReceivedPayments {
                 timestamp: received_payments.timestamp,
                 payments: expected_transactions.transactions,
+                scanned_from_block: 2,
                 new_start_block: 9876,
                 response_skeleton_opt: Some(ResponseSkeleton {
                     client_id: 1234,
@@ -1433,11 +1918,124 @@ mod tests {
         );
     }
 
+    #[test]
+    fn handle_retrieve_transactions_rechecks_no_more_than_half_of_max_block_count_below_start_block(
+    ) {
+        let retrieve_transactions_params_arc = Arc::new(Mutex::new(vec![]));
+        let system = System::new("test");
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let lower_interface =
+            LowBlockchainIntMock::default().get_block_number_result(Ok(5000u64.into()));
+        let blockchain_interface_mock = BlockchainInterfaceMock::default()
+            .retrieve_transactions_params(&retrieve_transactions_params_arc)
+            .retrieve_transactions_result(Ok(RetrievedBlockchainTransactions {
+                new_start_block: 1111,
+                transactions: vec![],
+            }))
+            .lower_interface_results(Box::new(lower_interface));
+        let persistent_config = PersistentConfigurationMock::new()
+            .max_block_count_result(Ok(Some(20)))
+            .start_block_result(Ok(1000));
+        let subject = BlockchainBridge::new(
+            Box::new(blockchain_interface_mock),
+            Box::new(persistent_config),
+            false,
+        );
+        let addr = subject.start();
+        let subject_subs = BlockchainBridge::make_subs_from(&addr);
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        send_bind_message!(subject_subs, peer_actors);
+        let retrieve_transactions = RetrieveTransactions {
+            recipient: make_wallet("somewallet"),
+            confirmation_depth: 64,
+            response_skeleton_opt: None,
+        };
+
+        let _ = addr.try_send(retrieve_transactions).unwrap();
+
+        System::current().stop();
+        system.run();
+        let retrieve_transactions_params = retrieve_transactions_params_arc.lock().unwrap();
+        assert_eq!(
+            *retrieve_transactions_params,
+            vec![(
+                BlockNumber::Number(990u64.into()),
+                BlockNumber::Number(1010u64.into()),
+                make_wallet("somewallet")
+            )]
+        );
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        let received_payments = accountant_recording.get_record::<ReceivedPayments>(0);
+        assert_eq!(received_payments.scanned_from_block, 990);
+        assert_eq!(received_payments.new_start_block, 1111);
+    }
+
+    #[test]
+    fn handle_retrieve_transactions_skips_retrieval_if_no_new_block_is_deep_enough() {
+        init_test_logging();
+        let test_name =
+            "handle_retrieve_transactions_skips_retrieval_if_no_new_block_is_deep_enough";
+        let system = System::new(test_name);
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let lower_interface =
+            LowBlockchainIntMock::default().get_block_number_result(Ok(1011u64.into()));
+        // No retrieve_transactions_result: retrieve_transactions() must not be called
+        let blockchain_interface_mock =
+            BlockchainInterfaceMock::default().lower_interface_results(Box::new(lower_interface));
+        let persistent_config = PersistentConfigurationMock::new()
+            .max_block_count_result(Ok(None))
+            .start_block_result(Ok(1000));
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface_mock),
+            Box::new(persistent_config),
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+        let addr = subject.start();
+        let subject_subs = BlockchainBridge::make_subs_from(&addr);
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        send_bind_message!(subject_subs, peer_actors);
+        let retrieve_transactions = RetrieveTransactions {
+            recipient: make_wallet("somewallet"),
+            confirmation_depth: 12,
+            response_skeleton_opt: Some(ResponseSkeleton {
+                client_id: 1234,
+                context_id: 4321,
+            }),
+        };
+        let before = SystemTime::now();
+
+        let _ = addr.try_send(retrieve_transactions).unwrap();
+
+        System::current().stop();
+        system.run();
+        let after = SystemTime::now();
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        let received_payments = accountant_recording.get_record::<ReceivedPayments>(0);
+        check_timestamp(before, received_payments.timestamp, after);
+        assert_eq!(
+            received_payments,
+            &ReceivedPayments {
+                timestamp: received_payments.timestamp,
+                payments: vec![],
+                scanned_from_block: 1000,
+                new_start_block: 1000,
+                response_skeleton_opt: Some(ResponseSkeleton {
+                    client_id: 1234,
+                    context_id: 4321
+                }),
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "DEBUG: {test_name}: No block past 1000 has 12 confirmations yet"
+        ));
+    }
+
     #[test]
     fn processing_of_received_payments_continues_even_if_no_payments_are_detected() {
         init_test_logging();
         let lower_interface =
-            LowBlockchainIntMock::default().get_block_number_result(Ok(0u64.into()));
+            LowBlockchainIntMock::default().get_block_number_result(Ok(100u64.into()));
         let blockchain_interface_mock = BlockchainInterfaceMock::default()
             .retrieve_transactions_result(Ok(RetrievedBlockchainTransactions {
                 new_start_block: 7,
@@ -1462,6 +2060,7 @@ mod tests {
         send_bind_message!(subject_subs, peer_actors);
         let retrieve_transactions = RetrieveTransactions {
             recipient: make_wallet("somewallet"),
+            confirmation_depth: 4,
             response_skeleton_opt: Some(ResponseSkeleton {
                 client_id: 1234,
                 context_id: 4321,
@@ -1482,6 +2081,7 @@ mod tests {
             &ReceivedPayments {
                 timestamp: received_payments.timestamp,
                 payments: vec![],
+                scanned_from_block: 2,
                 new_start_block: 7,
                 response_skeleton_opt: Some(ResponseSkeleton {
                     client_id: 1234,
@@ -1511,6 +2111,7 @@ mod tests {
         );
         let retrieve_transactions = RetrieveTransactions {
             recipient: make_wallet("somewallet"),
+            confirmation_depth: 4,
             response_skeleton_opt: None,
         };
 
@@ -1543,6 +2144,7 @@ mod tests {
         subject.scan_error_subs_opt = Some(accountant.start().recipient());
         let retrieve_transactions = RetrieveTransactions {
             recipient: make_wallet("somewallet"),
+            confirmation_depth: 4,
             response_skeleton_opt: Some(ResponseSkeleton {
                 client_id: 1234,
                 context_id: 4321,
@@ -1574,6 +2176,7 @@ mod tests {
         subject.scan_error_subs_opt = Some(accountant.start().recipient());
         let retrieve_transactions = RetrieveTransactions {
             recipient: make_wallet("somewallet"),
+            confirmation_depth: 4,
             response_skeleton_opt: None,
         };
 
@@ -1612,6 +2215,7 @@ mod tests {
         subject.scan_error_subs_opt = Some(accountant.start().recipient());
         let retrieve_transactions = RetrieveTransactions {
             recipient: make_wallet("somewallet"),
+            confirmation_depth: 4,
             response_skeleton_opt: Some(ResponseSkeleton {
                 client_id: 1234,
                 context_id: 4321,
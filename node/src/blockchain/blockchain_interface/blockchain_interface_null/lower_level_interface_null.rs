@@ -2,10 +2,11 @@
 
 use crate::blockchain::blockchain_interface::data_structures::errors::BlockchainError;
 use crate::blockchain::blockchain_interface::lower_level_interface::{
-    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForNonce,
+    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForFeeHistory, ResultForNonce,
 };
 use crate::sub_lib::wallet::Wallet;
 use masq_lib::logger::Logger;
+use web3::types::Address;
 
 pub struct LowBlockChainIntNull {
     logger: Logger,
@@ -20,6 +21,10 @@ impl LowBlockchainInt for LowBlockChainIntNull {
         Err(self.handle_null_call("masq balance"))
     }
 
+    fn get_service_fee_allowance(&self, _owner: &Wallet, _spender: Address) -> ResultForBalance {
+        Err(self.handle_null_call("masq allowance"))
+    }
+
     fn get_block_number(&self) -> LatestBlockNumber {
         Err(self.handle_null_call("block number"))
     }
@@ -27,6 +32,10 @@ impl LowBlockchainInt for LowBlockChainIntNull {
     fn get_transaction_id(&self, _wallet: &Wallet) -> ResultForNonce {
         Err(self.handle_null_call("transaction id"))
     }
+
+    fn get_fee_history(&self, _block_count: u64, _reward_percentile: f64) -> ResultForFeeHistory {
+        Err(self.handle_null_call("fee history"))
+    }
 }
 
 impl LowBlockChainIntNull {
@@ -73,6 +82,16 @@ mod tests {
         test_null_method(test_name, act, "masq balance");
     }
 
+    #[test]
+    fn low_bci_null_gets_no_masq_allowance() {
+        let test_name = "low_bci_null_gets_no_masq_allowance";
+        let act = |subject: &LowBlockChainIntNull, wallet: &Wallet| {
+            subject.get_service_fee_allowance(wallet, wallet.address())
+        };
+
+        test_null_method(test_name, act, "masq allowance");
+    }
+
     #[test]
     fn low_bci_null_gets_no_block_number() {
         let test_name = "low_bci_null_gets_no_block_number";
@@ -90,6 +109,15 @@ mod tests {
         test_null_method(test_name, act, "transaction id");
     }
 
+    #[test]
+    fn low_bci_null_gets_no_fee_history() {
+        let test_name = "low_bci_null_gets_no_fee_history";
+        let act =
+            |subject: &LowBlockChainIntNull, _wallet: &Wallet| subject.get_fee_history(10, 50.0);
+
+        test_null_method(test_name, act, "fee history");
+    }
+
     fn test_null_method<T: Debug + PartialEq>(
         test_name: &str,
         act: fn(&LowBlockChainIntNull, &Wallet) -> Result<T, BlockchainError>,
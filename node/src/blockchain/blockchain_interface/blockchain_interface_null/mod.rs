@@ -3,8 +3,9 @@
 pub mod lower_level_interface_null;
 
 use crate::accountant::db_access_objects::payable_dao::PayableAccount;
+use crate::accountant::db_access_objects::pending_payable_dao::PendingPayableReplacement;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
-use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
+use crate::blockchain::blockchain_bridge::{PendingPayableFingerprint, PendingPayableFingerprintSeeds};
 use crate::blockchain::blockchain_interface::blockchain_interface_null::lower_level_interface_null::LowBlockChainIntNull;
 use crate::blockchain::blockchain_interface::lower_level_interface::LowBlockchainInt;
 use crate::db_config::persistent_configuration::PersistentConfiguration;
@@ -53,6 +54,15 @@ impl BlockchainInterface for BlockchainInterfaceNull {
         self.handle_uninitialized_interface("pay for payables")
     }
 
+    fn replace_stuck_payables(
+        &self,
+        _consuming_wallet: &Wallet,
+        _persistent_config: &dyn PersistentConfiguration,
+        _stuck_payables: &[PendingPayableFingerprint],
+    ) -> Result<Vec<PendingPayableReplacement>, PayableTransactionError> {
+        self.handle_uninitialized_interface("replace stuck payables")
+    }
+
     fn get_transaction_receipt(&self, _hash: H256) -> ResultForReceipt {
         self.handle_uninitialized_interface("get transaction receipt")
     }
@@ -131,7 +141,7 @@ impl BlockchainInterfaceNull {
 #[cfg(test)]
 mod tests {
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::agent_null::BlockchainAgentNull;
-    use crate::accountant::test_utils::make_payable_account;
+    use crate::accountant::test_utils::{make_payable_account, make_pending_payable_fingerprint};
     use crate::blockchain::blockchain_interface::blockchain_interface_null::lower_level_interface_null::LowBlockChainIntNull;
     use crate::blockchain::blockchain_interface::blockchain_interface_null::{
         BlockchainInterfaceNull, BlockchainInterfaceUninitializedError,
@@ -233,6 +243,31 @@ mod tests {
         TestLogHandler::new().exists_log_containing(expected_log_msg.as_str());
     }
 
+    #[test]
+    fn blockchain_interface_null_cannot_replace_stuck_payables() {
+        init_test_logging();
+        let test_name = "blockchain_interface_null_cannot_replace_stuck_payables";
+        let consuming_wallet = make_wallet("consuming");
+        let persistent_config = PersistentConfigurationMock::new();
+        let stuck_payables = vec![make_pending_payable_fingerprint()];
+
+        let result = make_subject(test_name).replace_stuck_payables(
+            &consuming_wallet,
+            &persistent_config,
+            &stuck_payables,
+        );
+
+        assert_eq!(
+            result,
+            Err(PayableTransactionError::UninitializedBlockchainInterface)
+        );
+        let expected_log_msg = format!(
+            "ERROR: {test_name}: Failed to replace stuck payables with uninitialized blockchain \
+            interface. Parameter blockchain-service-url is missing."
+        );
+        TestLogHandler::new().exists_log_containing(expected_log_msg.as_str());
+    }
+
     #[test]
     fn blockchain_interface_null_gets_no_transaction_receipt() {
         init_test_logging();
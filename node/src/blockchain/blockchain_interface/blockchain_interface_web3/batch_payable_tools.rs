@@ -1,6 +1,7 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
+use crate::blockchain::blockchain_bridge::{PendingPayableFingerprintSeeds, ResubmissionData};
+use crate::blockchain::blockchain_interface::blockchain_interface_web3::eip1559_transaction::Eip1559TransactionParameters;
 use actix::Recipient;
 use futures::Future;
 use serde_json::Value;
@@ -21,6 +22,11 @@ where
         web3: &Web3<Batch<T>>,
         key: &secp256k1secrets::key::SecretKey,
     ) -> Result<SignedTransaction, Web3Error>;
+    fn sign_eip1559_transaction(
+        &self,
+        transaction_params: Eip1559TransactionParameters,
+        key: &secp256k1secrets::key::SecretKey,
+    ) -> SignedTransaction;
     fn append_transaction_to_batch(&self, signed_transaction: Bytes, web3: &Web3<Batch<T>>);
     fn batch_wide_timestamp(&self) -> SystemTime;
     fn send_new_payable_fingerprints_seeds(
@@ -28,6 +34,7 @@ where
         batch_wide_timestamp: SystemTime,
         new_pp_fingerprints_sub: &Recipient<PendingPayableFingerprintSeeds>,
         hashes_and_balances: &[(H256, u128)],
+        resubmission_data: &[ResubmissionData],
     );
     fn submit_batch(
         &self,
@@ -60,6 +67,14 @@ impl<T: BatchTransport + Debug> BatchPayableTools<T> for BatchPayableToolsReal<T
             .wait()
     }
 
+    fn sign_eip1559_transaction(
+        &self,
+        transaction_params: Eip1559TransactionParameters,
+        key: &secp256k1secrets::key::SecretKey,
+    ) -> SignedTransaction {
+        transaction_params.sign(key)
+    }
+
     fn append_transaction_to_batch(&self, signed_transaction: Bytes, web3: &Web3<Batch<T>>) {
         let _ = web3.eth().send_raw_transaction(signed_transaction);
     }
@@ -73,11 +88,13 @@ impl<T: BatchTransport + Debug> BatchPayableTools<T> for BatchPayableToolsReal<T
         batch_wide_timestamp: SystemTime,
         pp_fingerprint_sub: &Recipient<PendingPayableFingerprintSeeds>,
         hashes_and_balances: &[(H256, u128)],
+        resubmission_data: &[ResubmissionData],
     ) {
         pp_fingerprint_sub
             .try_send(PendingPayableFingerprintSeeds {
                 batch_wide_timestamp,
                 hashes_and_balances: hashes_and_balances.to_vec(),
+                resubmission_data: resubmission_data.to_vec(),
             })
             .expect("Accountant is dead");
     }
@@ -92,6 +109,7 @@ impl<T: BatchTransport + Debug> BatchPayableTools<T> for BatchPayableToolsReal<T
 
 #[cfg(test)]
 mod tests {
+    use crate::accountant::test_utils::make_resubmission_data;
     use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
     use crate::blockchain::blockchain_interface::blockchain_interface_web3::batch_payable_tools::{
         BatchPayableTools, BatchPayableToolsReal,
@@ -107,9 +125,15 @@ mod tests {
         let recipient = accountant.start().recipient();
         let timestamp = SystemTime::now();
         let hashes_and_balances = vec![(make_tx_hash(123), 5), (make_tx_hash(45466), 444444)];
+        let resubmission_data = vec![make_resubmission_data(7), make_resubmission_data(8)];
 
         let _ = BatchPayableToolsReal::<TestTransport>::default()
-            .send_new_payable_fingerprints_seeds(timestamp, &recipient, &hashes_and_balances);
+            .send_new_payable_fingerprints_seeds(
+                timestamp,
+                &recipient,
+                &hashes_and_balances,
+                &resubmission_data,
+            );
 
         let system = System::new("new fingerprints");
         System::current().stop();
@@ -120,7 +144,8 @@ mod tests {
             message,
             &PendingPayableFingerprintSeeds {
                 batch_wide_timestamp: timestamp,
-                hashes_and_balances
+                hashes_and_balances,
+                resubmission_data
             }
         )
     }
@@ -0,0 +1,200 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use ethsign_crypto::Keccak256;
+use rlp::RlpStream;
+use secp256k1secrets::key::SecretKey;
+use secp256k1secrets::{Message, Secp256k1};
+use web3::types::{Address, Bytes, SignedTransaction, H256, U256};
+
+// The envelope type byte of EIP-2718 typed transactions that denotes EIP-1559
+pub const EIP1559_TRANSACTION_TYPE: u8 = 0x02;
+
+// web3 of our version knows only legacy transactions; this fills the gap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Eip1559TransactionParameters {
+    pub chain_id: u64,
+    pub nonce: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas: U256,
+    pub to: Address,
+    pub value: U256,
+    pub data: Bytes,
+}
+
+impl Eip1559TransactionParameters {
+    pub fn sign(&self, key: &SecretKey) -> SignedTransaction {
+        let mut rlp = RlpStream::new();
+        self.rlp_append_fields(&mut rlp, 9);
+        let message_hash = H256::from(Self::typed_payload(rlp.as_raw()).keccak256());
+
+        let message = Message::from_slice(message_hash.as_bytes())
+            .expect("hash of 32 bytes is a valid message");
+        let (recovery_id, signature) = Secp256k1::signing_only()
+            .sign_recoverable(&message, key)
+            .serialize_compact();
+        let y_parity = recovery_id.to_i32() as u64;
+        let r = H256::from_slice(&signature[..32]);
+        let s = H256::from_slice(&signature[32..]);
+
+        let mut rlp = RlpStream::new();
+        self.rlp_append_fields(&mut rlp, 12);
+        rlp.append(&y_parity);
+        rlp.append(&U256::from_big_endian(r.as_bytes()));
+        rlp.append(&U256::from_big_endian(s.as_bytes()));
+        let raw_transaction = Self::typed_payload(rlp.as_raw());
+        let transaction_hash = H256::from(raw_transaction.keccak256());
+
+        SignedTransaction {
+            message_hash,
+            v: y_parity,
+            r,
+            s,
+            raw_transaction: Bytes(raw_transaction),
+            transaction_hash,
+        }
+    }
+
+    fn rlp_append_fields(&self, rlp: &mut RlpStream, list_len: usize) {
+        rlp.begin_list(list_len);
+        rlp.append(&self.chain_id);
+        rlp.append(&self.nonce);
+        rlp.append(&self.max_priority_fee_per_gas);
+        rlp.append(&self.max_fee_per_gas);
+        rlp.append(&self.gas);
+        rlp.append(&self.to);
+        rlp.append(&self.value);
+        rlp.append(&self.data.0);
+        // we never make use of access lists
+        rlp.begin_list(0);
+    }
+
+    fn typed_payload(rlp_bytes: &[u8]) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(rlp_bytes.len() + 1);
+        payload.push(EIP1559_TRANSACTION_TYPE);
+        payload.extend_from_slice(rlp_bytes);
+        payload
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::bip32::Bip32EncryptionKeyProvider;
+    use crate::sub_lib::wallet::Wallet;
+    use rlp::Rlp;
+    use secp256k1secrets::key::PublicKey;
+    use secp256k1secrets::recovery::{RecoverableSignature, RecoveryId};
+
+    #[test]
+    fn constants_are_correct() {
+        assert_eq!(EIP1559_TRANSACTION_TYPE, 0x02)
+    }
+
+    #[test]
+    fn signed_eip1559_transaction_carries_all_parameters_in_a_type_2_envelope() {
+        let subject = make_subject();
+        let key = make_secret_key();
+
+        let result = subject.sign(&key);
+
+        let raw = result.raw_transaction.0.as_slice();
+        assert_eq!(raw[0], EIP1559_TRANSACTION_TYPE);
+        let rlp = Rlp::new(&raw[1..]);
+        assert_eq!(rlp.item_count().unwrap(), 12);
+        assert_eq!(rlp.val_at::<u64>(0).unwrap(), 137);
+        assert_eq!(rlp.val_at::<U256>(1).unwrap(), U256::from(5));
+        assert_eq!(rlp.val_at::<U256>(2).unwrap(), U256::from(1_500_000_000));
+        assert_eq!(
+            rlp.val_at::<U256>(3).unwrap(),
+            U256::from(61_500_000_000_u64)
+        );
+        assert_eq!(rlp.val_at::<U256>(4).unwrap(), U256::from(55_000));
+        assert_eq!(rlp.val_at::<Address>(5).unwrap(), subject.to);
+        assert_eq!(rlp.val_at::<U256>(6).unwrap(), U256::zero());
+        assert_eq!(rlp.val_at::<Vec<u8>>(7).unwrap(), subject.data.0);
+        assert_eq!(rlp.at(8).unwrap().item_count().unwrap(), 0);
+        assert_eq!(rlp.val_at::<u64>(9).unwrap(), result.v);
+        assert_eq!(
+            rlp.val_at::<U256>(10).unwrap(),
+            U256::from_big_endian(result.r.as_bytes())
+        );
+        assert_eq!(
+            rlp.val_at::<U256>(11).unwrap(),
+            U256::from_big_endian(result.s.as_bytes())
+        );
+        assert_eq!(
+            result.transaction_hash,
+            H256::from(result.raw_transaction.0.keccak256())
+        );
+    }
+
+    #[test]
+    fn signature_of_eip1559_transaction_recovers_to_the_signing_wallet() {
+        let subject = make_subject();
+        let wallet =
+            Wallet::from(Bip32EncryptionKeyProvider::from_raw_secret(&[0x42; 32]).unwrap());
+        let key = wallet.prepare_secp256k1_secret().unwrap();
+
+        let result = subject.sign(&key);
+
+        assert!(result.v == 0 || result.v == 1, "{}", result.v);
+        let raw = result.raw_transaction.0.as_slice();
+        let rlp = Rlp::new(&raw[1..]);
+        let mut unsigned = RlpStream::new_list(9);
+        (0..9).for_each(|idx| {
+            unsigned.append_raw(rlp.at(idx).unwrap().as_raw(), 1);
+        });
+        let mut preimage = vec![EIP1559_TRANSACTION_TYPE];
+        preimage.extend_from_slice(unsigned.as_raw());
+        let message_hash = H256::from(preimage.keccak256());
+        assert_eq!(message_hash, result.message_hash);
+        let mut compact_signature = [0u8; 64];
+        compact_signature[..32].copy_from_slice(result.r.as_bytes());
+        compact_signature[32..].copy_from_slice(result.s.as_bytes());
+        let signature = RecoverableSignature::from_compact(
+            &compact_signature,
+            RecoveryId::from_i32(result.v as i32).unwrap(),
+        )
+        .unwrap();
+        let public_key: PublicKey = Secp256k1::verification_only()
+            .recover(
+                &Message::from_slice(message_hash.as_bytes()).unwrap(),
+                &signature,
+            )
+            .unwrap();
+        let public_key_hash = public_key.serialize_uncompressed()[1..].keccak256();
+        assert_eq!(
+            Address::from_slice(&public_key_hash[12..]),
+            wallet.address()
+        );
+    }
+
+    #[test]
+    fn eip1559_signing_is_deterministic() {
+        let subject = make_subject();
+        let key = make_secret_key();
+
+        let first = subject.sign(&key);
+        let second = subject.sign(&key);
+
+        assert_eq!(first, second)
+    }
+
+    fn make_subject() -> Eip1559TransactionParameters {
+        Eip1559TransactionParameters {
+            chain_id: 137,
+            nonce: U256::from(5),
+            max_priority_fee_per_gas: U256::from(1_500_000_000),
+            max_fee_per_gas: U256::from(61_500_000_000_u64),
+            gas: U256::from(55_000),
+            to: Address::from_slice(&[0x33; 20]),
+            value: U256::zero(),
+            data: Bytes(vec![0xa9, 0x05, 0x9c, 0xbb, 0x00, 0x01, 0x02]),
+        }
+    }
+
+    fn make_secret_key() -> SecretKey {
+        SecretKey::from_slice(&[0x42; 32]).unwrap()
+    }
+}
@@ -2,14 +2,16 @@
 
 use crate::blockchain::blockchain_interface::data_structures::errors::BlockchainError;
 use crate::blockchain::blockchain_interface::lower_level_interface::{
-    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForNonce,
+    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForFeeHistory, ResultForNonce,
 };
 use crate::sub_lib::wallet::Wallet;
+use ethereum_types::U64;
 use futures::Future;
 use std::rc::Rc;
 use web3::contract::{Contract, Options};
+use web3::helpers::{serialize, CallFuture};
 use web3::transports::Batch;
-use web3::types::BlockNumber;
+use web3::types::{Address, BlockNumber};
 use web3::{BatchTransport, Web3};
 
 pub struct LowBlockchainIntWeb3<T>
@@ -47,6 +49,24 @@ where
             .wait()
     }
 
+    fn get_service_fee_allowance(&self, owner: &Wallet, spender: Address) -> ResultForBalance {
+        self.contract
+            .query(
+                "allowance",
+                (owner.address(), spender),
+                None,
+                Options::default(),
+                None,
+            )
+            .map_err(|e| {
+                BlockchainError::QueryFailed(format!(
+                    "{} for wallet {} and spender {:?}",
+                    e, owner, spender
+                ))
+            })
+            .wait()
+    }
+
     fn get_block_number(&self) -> LatestBlockNumber {
         self.web3
             .eth()
@@ -62,6 +82,18 @@ where
             .map_err(|e| BlockchainError::QueryFailed(format!("{} for wallet {}", e, wallet)))
             .wait()
     }
+
+    // The web3 library predates EIP-1559, hence the raw call
+    fn get_fee_history(&self, block_count: u64, reward_percentile: f64) -> ResultForFeeHistory {
+        let params = vec![
+            serialize(&U64::from(block_count)),
+            serialize(&BlockNumber::Latest),
+            serialize(&vec![reward_percentile]),
+        ];
+        CallFuture::new(self.web3.transport().execute("eth_feeHistory", params))
+            .map_err(|e| BlockchainError::QueryFailed(format!("Fee history: {}", e)))
+            .wait()
+    }
 }
 
 impl<T> LowBlockchainIntWeb3<T>
@@ -84,6 +116,7 @@ mod tests {
         CONTRACT_ABI, REQUESTS_IN_PARALLEL,
     };
     use crate::blockchain::blockchain_interface::lower_level_interface::{LowBlockchainInt, ResultForBalance};
+    use crate::blockchain::blockchain_interface::data_structures::FeeHistory;
     use crate::blockchain::blockchain_interface::BlockchainError;
     use crate::sub_lib::wallet::Wallet;
     use crate::test_utils::http_test_server::TestServer;
@@ -128,7 +161,7 @@ mod tests {
         let requests = test_server.requests_so_far();
         let bodies: Vec<Value> = requests
             .into_iter()
-            .map(|request| serde_json::from_slice(&request.body()).unwrap())
+            .map(|request| serde_json::from_slice(request.body()).unwrap())
             .collect();
         assert_eq!(bodies[0]["method"].to_string(), "\"eth_getBalance\"",);
         assert_eq!(
@@ -225,6 +258,55 @@ mod tests {
         assert_error_from_unintelligible_response(act, "Invalid hex");
     }
 
+    #[test]
+    fn low_interface_web3_get_service_fee_allowance_works() {
+        let port = find_free_port();
+        let test_server = TestServer::start (port, vec![
+            br#"{"jsonrpc":"2.0","id":0,"result":"0x00000000000000000000000000000000000000000000000000000000DEADBEEF"}"#.to_vec()
+        ]);
+        let (_event_loop_handle, transport) = Http::with_max_parallel(
+            &format!("http://{}:{}", &Ipv4Addr::LOCALHOST.to_string(), port),
+            REQUESTS_IN_PARALLEL,
+        )
+        .unwrap();
+        let chain = TEST_DEFAULT_CHAIN;
+        let subject = make_subject(transport, chain);
+        let owner = Wallet::from_str("0x3f69f9efd4f2592fd70be8c32ecd9dce71c472fc").unwrap();
+        let spender = Wallet::from_str("0x00000000000000000000000000000000000000aa")
+            .unwrap()
+            .address();
+
+        let result = subject.get_service_fee_allowance(&owner, spender).unwrap();
+
+        assert_eq!(result, U256::from(0xDEADBEEF_u64));
+        let requests = test_server.requests_so_far();
+        let bodies: Vec<Value> = requests
+            .into_iter()
+            .map(|request| serde_json::from_slice(request.body()).unwrap())
+            .collect();
+        assert_eq!(bodies[0]["method"].to_string(), "\"eth_call\"",);
+        assert_eq!(
+            bodies[0]["params"][0]["to"].to_string(),
+            format!("\"{:?}\"", chain.rec().contract),
+        );
+        assert_eq!(
+            bodies[0]["params"][0]["data"].to_string(),
+            "\"0xdd62ed3e\
+            0000000000000000000000003f69f9efd4f2592fd70be8c32ecd9dce71c472fc\
+            00000000000000000000000000000000000000000000000000000000000000aa\""
+        );
+        assert_eq!(bodies.len(), 1)
+    }
+
+    #[test]
+    fn low_interface_web3_get_service_fee_allowance_returns_err_for_unintelligible_response() {
+        let act = |subject: &LowBlockchainIntWeb3<Http>, wallet: &Wallet| {
+            subject.get_service_fee_allowance(wallet, wallet.address())
+        };
+
+        assert_error_from_unintelligible_response(act, "Invalid hex");
+    }
+
     #[test]
     fn low_interface_web3_can_fetch_latest_block_number_successfully() {
         let prepare_params_arc = Arc::new(Mutex::new(vec![]));
@@ -336,6 +418,84 @@ mod tests {
         assert_eq!(*send_params, vec![(1, expected_request)])
     }
 
+    #[test]
+    fn low_interface_web3_get_fee_history_works() {
+        let port = find_free_port();
+        let test_server = TestServer::start (port, vec![
+            br#"{"jsonrpc":"2.0","id":0,"result":{"oldestBlock":"0x1e3705d","baseFeePerGas":["0x6fc23ac00","0x77359400","0x826299e00"],"gasUsedRatio":[0.61,0.96],"reward":[["0x3b9aca00"],["0x59682f00"]]}}"#.to_vec()
+        ]);
+        let (_event_loop_handle, transport) = Http::with_max_parallel(
+            &format!("http://{}:{}", &Ipv4Addr::LOCALHOST.to_string(), port),
+            REQUESTS_IN_PARALLEL,
+        )
+        .unwrap();
+        let subject = make_subject(transport, TEST_DEFAULT_CHAIN);
+
+        let result = subject.get_fee_history(2, 50.0);
+
+        assert_eq!(
+            result,
+            Ok(FeeHistory {
+                oldest_block: U256::from(0x1e3705d),
+                base_fee_per_gas: vec![
+                    U256::from(30_000_000_000_u64),
+                    U256::from(2_000_000_000_u64),
+                    U256::from(35_000_000_000_u64)
+                ],
+                reward: vec![
+                    vec![U256::from(1_000_000_000)],
+                    vec![U256::from(1_500_000_000)]
+                ]
+            })
+        );
+        let requests = test_server.requests_so_far();
+        let bodies: Vec<Value> = requests
+            .into_iter()
+            .map(|request| serde_json::from_slice(&request.body()).unwrap())
+            .collect();
+        assert_eq!(bodies[0]["method"].to_string(), "\"eth_feeHistory\"");
+        assert_eq!(bodies[0]["params"], json!(["0x2", "latest", [50.0]]));
+        assert_eq!(bodies.len(), 1)
+    }
+
+    #[test]
+    fn low_interface_web3_get_fee_history_tolerates_missing_rewards() {
+        let transport = TestTransport::default().send_result(json!({
+            "oldestBlock": "0x10",
+            "baseFeePerGas": ["0x0", "0x0"],
+            "gasUsedRatio": [0.0]
+        }));
+        let subject = make_subject(transport, TEST_DEFAULT_CHAIN);
+
+        let result = subject.get_fee_history(1, 50.0);
+
+        assert_eq!(
+            result,
+            Ok(FeeHistory {
+                oldest_block: U256::from(16),
+                base_fee_per_gas: vec![U256::zero(), U256::zero()],
+                reward: vec![]
+            })
+        )
+    }
+
+    #[test]
+    fn low_interface_web3_get_fee_history_handles_err() {
+        let transport = TestTransport::default().send_result(json!("not a fee history"));
+        let subject = make_subject(transport, TEST_DEFAULT_CHAIN);
+
+        let result = subject.get_fee_history(10, 50.0);
+
+        assert_eq!(
+            result,
+            Err(BlockchainError::QueryFailed(
+                "Fee history: Decoder error: Error(\"invalid type: string \\\"not a fee history\\\", \
+                expected struct FeeHistory\", line: 0, column: 0)"
+                    .to_string()
+            ))
+        )
+    }
+
     #[test]
     fn low_interface_web3_get_transaction_id_handles_err() {
         let act = |subject: &LowBlockchainIntWeb3<Http>, wallet: &Wallet| {
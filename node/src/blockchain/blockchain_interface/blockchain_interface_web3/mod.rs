@@ -1,18 +1,23 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 mod batch_payable_tools;
+mod eip1559_transaction;
 pub mod lower_level_interface_web3;
+pub mod multi_endpoint_transport;
 mod test_utils;
+pub mod transaction_signer;
 
 use crate::accountant::db_access_objects::payable_dao::{PayableAccount};
 use crate::accountant::{gwei_to_wei};
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::agent_web3::BlockchainAgentWeb3;
-use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
-use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
+use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::{BlockchainAgent, TransactionFeeModel};
+use crate::blockchain::blockchain_bridge::{PendingPayableFingerprint, PendingPayableFingerprintSeeds, ResubmissionData};
 use crate::blockchain::blockchain_interface::blockchain_interface_web3::batch_payable_tools::{
     BatchPayableTools, BatchPayableToolsReal,
 };
+use crate::blockchain::blockchain_interface::blockchain_interface_web3::eip1559_transaction::Eip1559TransactionParameters;
 use crate::blockchain::blockchain_interface::blockchain_interface_web3::lower_level_interface_web3::LowBlockchainIntWeb3;
+use crate::blockchain::blockchain_interface::blockchain_interface_web3::transaction_signer::{TransactionSigner, UnsignedTransaction};
 use crate::blockchain::blockchain_interface::lower_level_interface::LowBlockchainInt;
 use crate::blockchain::blockchain_interface::{BlockchainAgentBuildError, BlockchainError, BlockchainInterface, PayableTransactionError, ResultForReceipt, RetrievedBlockchainTransactions};
 use crate::db_config::persistent_configuration::PersistentConfiguration;
@@ -20,13 +25,15 @@ use crate::masq_lib::utils::ExpectValue;
 use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
 use crate::sub_lib::wallet::Wallet;
 use actix::Recipient;
+use ethabi::Token;
 use futures::Future;
 use indoc::indoc;
 use masq_lib::blockchains::chains::Chain;
+use masq_lib::constants::DEFAULT_MULTISEND_MAX_CREDITORS;
 use masq_lib::logger::Logger;
 use serde_json::Value;
 use std::fmt::Debug;
-use std::iter::once;
+use std::iter::{once, repeat};
 use std::rc::Rc;
 use thousands::Separable;
 use web3::contract::Contract;
@@ -36,8 +43,8 @@ use web3::types::{
     H160, H256, U256,
 };
 use web3::{BatchTransport, Error, Web3};
-use crate::accountant::db_access_objects::pending_payable_dao::PendingPayable;
-use crate::blockchain::blockchain_interface::data_structures::{BlockchainTransaction, ProcessedPayableFallible, RpcPayablesFailure};
+use crate::accountant::db_access_objects::pending_payable_dao::{PendingPayable, PendingPayableReplacement};
+use crate::blockchain::blockchain_interface::data_structures::{BlockchainTransaction, FeeHistory, ProcessedPayableFallible, RpcPayablesFailure};
 
 const CONTRACT_ABI: &str = indoc!(
     r#"[{
@@ -56,6 +63,14 @@ const CONTRACT_ABI: &str = indoc!(
     "payable":false,
     "stateMutability":"nonpayable",
     "type":"function"
+    },{
+    "constant":true,
+    "inputs":[{"name":"owner","type":"address"},{"name":"spender","type":"address"}],
+    "name":"allowance",
+    "outputs":[{"name":"","type":"uint256"}],
+    "payable":false,
+    "stateMutability":"view",
+    "type":"function"
     }]"#
 );
 
@@ -66,8 +81,18 @@ const TRANSACTION_LITERAL: H256 = H256([
 
 const TRANSFER_METHOD_ID: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
 
+// disperseTokenSimple(address,address[],uint256[]) of the widely deployed Disperse contract
+const MULTISEND_METHOD_ID: [u8; 4] = [0x51, 0xba, 0x16, 0x2c];
+
+// A generous gas limit for one transferFrom made by the multisend contract; unused gas isn't paid
+pub const MULTISEND_GAS_PER_CREDITOR: u64 = 50_000;
+
 pub const REQUESTS_IN_PARALLEL: usize = 1;
 
+pub const FEE_HISTORY_BLOCK_COUNT: u64 = 10;
+
+pub const FEE_HISTORY_REWARD_PERCENTILE: f64 = 50.0;
+
 pub struct BlockchainInterfaceWeb3<T>
 where
     T: 'static + BatchTransport + Debug,
@@ -81,6 +106,12 @@ where
     web3_batch: Rc<Web3<Batch<T>>>,
     batch_payable_tools: Box<dyn BatchPayableTools<T>>,
     lower_interface: Box<dyn LowBlockchainInt>,
+    // When present, transactions are signed outside the Node and the consuming wallet needs no key
+    transaction_signer_opt: Option<Box<dyn TransactionSigner>>,
+    // When present, payables sent together are paid through this contract, in transactions of up to
+    // multisend_max_creditors payables each
+    multisend_contract_opt: Option<Address>,
+    multisend_max_creditors: usize,
 }
 
 impl<T> BlockchainInterface for BlockchainInterfaceWeb3<T>
@@ -173,12 +204,18 @@ where
                                     transactions.len()
                                 )
                             }
+                            // Blocks past a literal end block weren't scanned, perhaps because they
+                            // aren't confirmed yet, and must not be skipped next time
+                            let last_scanned_block_number = match end_block {
+                                BlockNumber::Number(eb) => eb.as_u64(),
+                                _ => response_block_number,
+                            };
                             // Get the largest transaction block number, unless there are no
                             // transactions, in which case use end_block, unless get_latest_block()
                             // was not successful.
                             let transaction_max_block_number = self
                                 .find_largest_transaction_block_number(
-                                    response_block_number,
+                                    last_scanned_block_number,
                                     &transactions,
                                 );
                             debug!(
@@ -249,6 +286,8 @@ where
             }
         };
 
+        let transaction_fee_model = self.transaction_fee_model(gas_price_gwei);
+
         let consuming_wallet_balances = ConsumingWalletBalances {
             transaction_fee_balance_in_minor_units: transaction_fee_balance,
             masq_token_balance_in_minor_units: masq_token_balance,
@@ -256,7 +295,7 @@ where
         let consuming_wallet = consuming_wallet.clone();
 
         Ok(Box::new(BlockchainAgentWeb3::new(
-            gas_price_gwei,
+            transaction_fee_model,
             self.gas_limit_const_part,
             consuming_wallet,
             consuming_wallet_balances,
@@ -271,48 +310,181 @@ where
         accounts: &[PayableAccount],
     ) -> Result<Vec<ProcessedPayableFallible>, PayableTransactionError> {
         let consuming_wallet = agent.consuming_wallet();
-        let gas_price = agent.agreed_fee_per_computation_unit();
+        let fee_model = agent.agreed_transaction_fee_model();
         let pending_nonce = agent.pending_transaction_id();
 
         debug!(
             self.logger,
-            "Common attributes of payables to be transacted: sender wallet: {}, contract: {:?}, chain_id: {}, gas_price: {}",
+            "Common attributes of payables to be transacted: sender wallet: {}, contract: {:?}, chain_id: {}, fees: {:?}",
             consuming_wallet,
             self.chain.rec().contract,
             self.chain.rec().num_chain_id,
-            gas_price
+            fee_model
         );
 
-        let hashes_and_paid_amounts = self.sign_and_append_multiple_payments(
-            consuming_wallet,
-            gas_price,
+        let multisend_contract_opt = self.multisend_contract_for(consuming_wallet, accounts);
+        let hashes_and_paid_amounts = match multisend_contract_opt {
+            Some(multisend_contract) => self.sign_and_append_multisend_payments(
+                multisend_contract,
+                consuming_wallet,
+                fee_model,
+                pending_nonce,
+                accounts,
+            )?,
+            None => self.sign_and_append_multiple_payments(
+                consuming_wallet,
+                fee_model,
+                pending_nonce,
+                accounts,
+            )?,
+        };
+        let creditors_per_transaction = self.creditors_per_transaction(multisend_contract_opt);
+        let resubmission_data = Self::resubmission_data(
+            fee_model,
             pending_nonce,
             accounts,
-        )?;
+            creditors_per_transaction,
+        );
         let timestamp = self.batch_payable_tools.batch_wide_timestamp();
         self.batch_payable_tools
             .send_new_payable_fingerprints_seeds(
                 timestamp,
                 new_fingerprints_recipient,
                 &hashes_and_paid_amounts,
+                &resubmission_data,
             );
 
         info!(
             self.logger,
             "{}",
-            self.transmission_log(accounts, gas_price)
+            self.transmission_log(accounts, fee_model, multisend_contract_opt)
         );
 
         match self.batch_payable_tools.submit_batch(&self.web3_batch) {
-            Ok(responses) => Ok(Self::merged_output_data(
-                responses,
-                hashes_and_paid_amounts,
-                accounts,
-            )),
+            Ok(responses) => {
+                // The response to each transaction tells the fate of every payable in it
+                let responses = responses
+                    .into_iter()
+                    .zip(accounts.chunks(creditors_per_transaction))
+                    .flat_map(|(response, payables)| repeat(response).take(payables.len()))
+                    .collect();
+                Ok(Self::merged_output_data(
+                    responses,
+                    hashes_and_paid_amounts,
+                    accounts,
+                ))
+            }
             Err(e) => Err(Self::error_with_hashes(e, hashes_and_paid_amounts)),
         }
     }
 
+    fn replace_stuck_payables(
+        &self,
+        consuming_wallet: &Wallet,
+        persistent_config: &dyn PersistentConfiguration,
+        stuck_payables: &[PendingPayableFingerprint],
+    ) -> Result<Vec<PendingPayableReplacement>, PayableTransactionError> {
+        let gas_price_ceiling_gwei = persistent_config
+            .gas_price()
+            .map_err(|e| PayableTransactionError::GasPriceQueryFailed(format!("{:?}", e)))?;
+        let market_fee_model = self.transaction_fee_model(gas_price_ceiling_gwei);
+        let mut signed_replacements = vec![];
+        for fingerprints in Self::group_by_transaction(stuck_payables) {
+            let (stuck_hash, resubmission_data) = (fingerprints[0].0.hash, fingerprints[0].1);
+            let fee_model = Self::bumped_fee_model(resubmission_data.fee_model, market_fee_model);
+            if fee_model.max_fee_per_gas_wei() > gwei_to_wei(gas_price_ceiling_gwei) {
+                warning!(
+                    self.logger,
+                    "Cannot replace stuck transaction {:?} as its fees would have to exceed the \
+                    ceiling of {} gwei; consider raising gas-price",
+                    stuck_hash,
+                    gas_price_ceiling_gwei
+                );
+                continue;
+            }
+            let nonce = U256::from(resubmission_data.nonce);
+            let new_hash = match (fingerprints.as_slice(), self.multisend_contract_opt) {
+                ([(fingerprint, resubmission_data)], _) => self.handle_new_transaction(
+                    &resubmission_data.recipient_wallet,
+                    consuming_wallet,
+                    fingerprint.amount,
+                    nonce,
+                    fee_model,
+                )?,
+                (_, Some(multisend_contract)) => self.handle_new_multisend_transaction(
+                    multisend_contract,
+                    consuming_wallet,
+                    &fingerprints
+                        .iter()
+                        .map(|(fingerprint, resubmission_data)| {
+                            (
+                                resubmission_data.recipient_wallet.address(),
+                                fingerprint.amount,
+                            )
+                        })
+                        .collect::<Vec<_>>(),
+                    nonce,
+                    fee_model,
+                )?,
+                (_, None) => {
+                    warning!(
+                        self.logger,
+                        "Cannot replace stuck transaction {:?} paying {} creditors as no \
+                        multisend-contract is configured",
+                        stuck_hash,
+                        fingerprints.len()
+                    );
+                    continue;
+                }
+            };
+            signed_replacements.push((fingerprints, new_hash, fee_model));
+        }
+        if signed_replacements.is_empty() {
+            return Ok(vec![]);
+        }
+        match self.batch_payable_tools.submit_batch(&self.web3_batch) {
+            Ok(responses) => Ok(responses
+                .into_iter()
+                .zip(signed_replacements.into_iter())
+                .flat_map(
+                    |(rpc_result, (fingerprints, hash, fee_model))| match rpc_result {
+                        Ok(_) => fingerprints
+                            .into_iter()
+                            .map(|(fingerprint, _)| PendingPayableReplacement {
+                                rowid: fingerprint.rowid,
+                                hash,
+                                replaced_hashes: fingerprint
+                                    .replaced_hashes
+                                    .iter()
+                                    .copied()
+                                    .chain(once(fingerprint.hash))
+                                    .collect(),
+                                fee_model,
+                            })
+                            .collect(),
+                        Err(e) => {
+                            warning!(
+                                self.logger,
+                                "Replacement {:?} of stuck transaction {:?} was rejected: {}",
+                                hash,
+                                fingerprints[0].0.hash,
+                                e
+                            );
+                            vec![]
+                        }
+                    },
+                )
+                .collect()),
+            Err(e) => Err(PayableTransactionError::Sending {
+                msg: e.to_string(),
+                hashes: signed_replacements
+                    .into_iter()
+                    .map(|(_, hash, _)| hash)
+                    .collect(),
+            }),
+        }
+    }
+
     fn get_transaction_receipt(&self, hash: H256) -> ResultForReceipt {
         self.web3
             .eth()
@@ -353,13 +525,73 @@ where
             web3_batch,
             lower_interface: lower_level_blockchain_interface,
             batch_payable_tools,
+            transaction_signer_opt: None,
+            multisend_contract_opt: None,
+            multisend_max_creditors: DEFAULT_MULTISEND_MAX_CREDITORS as usize,
+        }
+    }
+
+    pub fn set_transaction_signer(&mut self, transaction_signer: Box<dyn TransactionSigner>) {
+        self.transaction_signer_opt = Some(transaction_signer);
+    }
+
+    pub fn set_multisend_contract(&mut self, multisend_contract: Address, max_creditors: u16) {
+        self.multisend_contract_opt = Some(multisend_contract);
+        self.multisend_max_creditors = max_creditors as usize;
+    }
+
+    fn creditors_per_transaction(&self, multisend_contract_opt: Option<Address>) -> usize {
+        match multisend_contract_opt {
+            Some(_) => self.multisend_max_creditors,
+            None => 1,
+        }
+    }
+
+    // A single payable is cheaper to pay by a plain transfer, and the multisend contract can't pay
+    // more than the consuming wallet has allowed it to spend
+    fn multisend_contract_for(
+        &self,
+        consuming_wallet: &Wallet,
+        accounts: &[PayableAccount],
+    ) -> Option<Address> {
+        let multisend_contract = self.multisend_contract_opt.filter(|_| accounts.len() > 1)?;
+        let total_wei = accounts.iter().fold(U256::zero(), |sum, account| {
+            sum.saturating_add(U256::from(account.balance_wei))
+        });
+        match self
+            .lower_interface
+            .get_service_fee_allowance(consuming_wallet, multisend_contract)
+        {
+            Ok(allowance) if allowance >= total_wei => Some(multisend_contract),
+            Ok(allowance) => {
+                warning!(
+                    self.logger,
+                    "Multisend contract {:?} may spend only {} wei of {}, but the payables need {} wei; \
+                    paying every creditor by a transfer of its own",
+                    multisend_contract,
+                    allowance.separate_with_commas(),
+                    consuming_wallet,
+                    total_wei.separate_with_commas()
+                );
+                None
+            }
+            Err(e) => {
+                warning!(
+                    self.logger,
+                    "Couldn't check what multisend contract {:?} may spend: {:?}; paying every \
+                    creditor by a transfer of its own",
+                    multisend_contract,
+                    e
+                );
+                None
+            }
         }
     }
 
     fn sign_and_append_multiple_payments(
         &self,
         consuming_wallet: &Wallet,
-        gas_price: u64,
+        fee_model: TransactionFeeModel,
         pending_nonce: U256,
         accounts: &[PayableAccount],
     ) -> HashAndAmountResult {
@@ -374,7 +606,7 @@ where
                         pending_nonce_opt,
                         hashes_and_amounts,
                         consuming_wallet,
-                        gas_price,
+                        fee_model,
                         account,
                     )
                 } else {
@@ -390,7 +622,7 @@ where
         pending_nonce_opt: Option<U256>,
         hashes_and_amounts: Vec<(H256, u128)>,
         consuming_wallet: &Wallet,
-        gas_price: u64,
+        fee_model: TransactionFeeModel,
         account: &PayableAccount,
     ) -> (HashAndAmountResult, Option<U256>) {
         let nonce = pending_nonce_opt.expectv("pending nonce");
@@ -398,7 +630,7 @@ where
             hashes_and_amounts,
             consuming_wallet,
             nonce,
-            gas_price,
+            fee_model,
             account,
         );
         let advanced_nonce = Self::advance_used_nonce(nonce);
@@ -413,7 +645,7 @@ where
         mut hashes_and_amounts: Vec<(H256, u128)>,
         consuming_wallet: &Wallet,
         nonce: U256,
-        gas_price: u64,
+        fee_model: TransactionFeeModel,
         account: &PayableAccount,
     ) -> HashAndAmountResult {
         debug!(
@@ -429,7 +661,7 @@ where
             consuming_wallet,
             account.balance_wei,
             nonce,
-            gas_price,
+            fee_model,
         ) {
             Ok(new_hash) => {
                 hashes_and_amounts.push((new_hash, account.balance_wei));
@@ -439,6 +671,153 @@ where
         }
     }
 
+    // A payment cycle with more creditors than one transaction may pay is paid in several, with
+    // consecutive nonces; a creditor left alone in the last one is paid by a plain transfer
+    fn sign_and_append_multisend_payments(
+        &self,
+        multisend_contract: Address,
+        consuming_wallet: &Wallet,
+        fee_model: TransactionFeeModel,
+        pending_nonce: U256,
+        accounts: &[PayableAccount],
+    ) -> HashAndAmountResult {
+        accounts
+            .chunks(self.multisend_max_creditors)
+            .enumerate()
+            .try_fold(
+                Vec::with_capacity(accounts.len()),
+                |mut hashes_and_amounts, (idx, payables)| {
+                    let nonce = pending_nonce + U256::from(idx);
+                    match payables {
+                        [account] => self.sign_and_append_payment(
+                            hashes_and_amounts,
+                            consuming_wallet,
+                            nonce,
+                            fee_model,
+                            account,
+                        ),
+                        _ => {
+                            hashes_and_amounts.extend(self.sign_and_append_multisend_payment(
+                                multisend_contract,
+                                consuming_wallet,
+                                fee_model,
+                                nonce,
+                                payables,
+                            )?);
+                            Ok(hashes_and_amounts)
+                        }
+                    }
+                },
+            )
+    }
+
+    fn sign_and_append_multisend_payment(
+        &self,
+        multisend_contract: Address,
+        consuming_wallet: &Wallet,
+        fee_model: TransactionFeeModel,
+        nonce: U256,
+        accounts: &[PayableAccount],
+    ) -> HashAndAmountResult {
+        debug!(
+            self.logger,
+            "Preparing payment to {} creditors through multisend contract {:?} with nonce {}",
+            accounts.len(),
+            multisend_contract,
+            nonce
+        );
+        let recipients_and_amounts = accounts
+            .iter()
+            .map(|account| (account.wallet.address(), account.balance_wei))
+            .collect::<Vec<_>>();
+        let new_hash = self.handle_new_multisend_transaction(
+            multisend_contract,
+            consuming_wallet,
+            &recipients_and_amounts,
+            nonce,
+            fee_model,
+        )?;
+        Ok(accounts
+            .iter()
+            .map(|account| (new_hash, account.balance_wei))
+            .collect())
+    }
+
+    fn resubmission_data(
+        fee_model: TransactionFeeModel,
+        pending_nonce: U256,
+        accounts: &[PayableAccount],
+        creditors_per_transaction: usize,
+    ) -> Vec<ResubmissionData> {
+        accounts
+            .iter()
+            .enumerate()
+            .map(|(idx, account)| ResubmissionData {
+                nonce: pending_nonce.as_u64() + (idx / creditors_per_transaction) as u64,
+                recipient_wallet: account.wallet.clone(),
+                fee_model,
+            })
+            .collect()
+    }
+
+    // The fingerprints of a multisend transaction share its hash, so it gets replaced as a whole
+    fn group_by_transaction(
+        stuck_payables: &[PendingPayableFingerprint],
+    ) -> Vec<Vec<(&PendingPayableFingerprint, &ResubmissionData)>> {
+        stuck_payables
+            .iter()
+            .filter_map(|fingerprint| {
+                fingerprint
+                    .resubmission_data_opt
+                    .as_ref()
+                    .map(|resubmission_data| (fingerprint, resubmission_data))
+            })
+            .fold(vec![], |mut groups: Vec<Vec<_>>, member| {
+                match groups
+                    .iter_mut()
+                    .find(|group| group[0].0.hash == member.0.hash)
+                {
+                    Some(group) => group.push(member),
+                    None => groups.push(vec![member]),
+                }
+                groups
+            })
+    }
+
+    // A replacement is only accepted by the nodes if it pays at least 10 % more than its
+    // predecessor; it also shouldn't pay less than the market currently asks for
+    fn bumped_fee_model(
+        previous: TransactionFeeModel,
+        market: TransactionFeeModel,
+    ) -> TransactionFeeModel {
+        fn bump(fee_wei: u128) -> u128 {
+            fee_wei.saturating_add((fee_wei + 9) / 10)
+        }
+        match previous {
+            TransactionFeeModel::Legacy { gas_price_wei } => TransactionFeeModel::Legacy {
+                gas_price_wei: bump(gas_price_wei).max(market.max_fee_per_gas_wei()),
+            },
+            TransactionFeeModel::Eip1559 {
+                max_fee_per_gas_wei,
+                max_priority_fee_per_gas_wei,
+            } => {
+                let market_priority_fee_wei = match market {
+                    TransactionFeeModel::Eip1559 {
+                        max_priority_fee_per_gas_wei,
+                        ..
+                    } => max_priority_fee_per_gas_wei,
+                    TransactionFeeModel::Legacy { .. } => 0,
+                };
+                TransactionFeeModel::Eip1559 {
+                    max_fee_per_gas_wei: bump(max_fee_per_gas_wei)
+                        .max(market.max_fee_per_gas_wei()),
+                    max_priority_fee_per_gas_wei: bump(max_priority_fee_per_gas_wei)
+                        .max(market_priority_fee_wei),
+                }
+            }
+        }
+    }
+
     fn advance_used_nonce(current_nonce: U256) -> U256 {
         current_nonce
             .checked_add(U256::one())
@@ -489,10 +868,34 @@ where
         consuming_wallet: &'a Wallet,
         amount: u128,
         nonce: U256,
-        gas_price: u64,
+        fee_model: TransactionFeeModel,
     ) -> Result<H256, PayableTransactionError> {
         let signed_tx =
-            self.sign_transaction(recipient, consuming_wallet, amount, nonce, gas_price)?;
+            self.sign_transaction(recipient, consuming_wallet, amount, nonce, fee_model)?;
+        self.batch_payable_tools
+            .append_transaction_to_batch(signed_tx.raw_transaction, &self.web3_batch);
+        Ok(signed_tx.transaction_hash)
+    }
+
+    fn handle_new_multisend_transaction(
+        &self,
+        multisend_contract: Address,
+        consuming_wallet: &Wallet,
+        recipients_and_amounts: &[(Address, u128)],
+        nonce: U256,
+        fee_model: TransactionFeeModel,
+    ) -> Result<H256, PayableTransactionError> {
+        let data = self.multisend_transaction_data(recipients_and_amounts);
+        let gas_limit = self.compute_gas_limit(data.as_slice())
+            + U256::from(MULTISEND_GAS_PER_CREDITOR * recipients_and_amounts.len() as u64);
+        let signed_tx = self.sign_contract_call(
+            multisend_contract,
+            data,
+            gas_limit,
+            consuming_wallet,
+            nonce,
+            fee_model,
+        )?;
         self.batch_payable_tools
             .append_transaction_to_batch(signed_tx.raw_transaction, &self.web3_batch);
         Ok(signed_tx.transaction_hash)
@@ -504,32 +907,165 @@ where
         consuming_wallet: &'a Wallet,
         amount: u128,
         nonce: U256,
-        gas_price: u64,
+        fee_model: TransactionFeeModel,
     ) -> Result<SignedTransaction, PayableTransactionError> {
         let data = Self::transaction_data(recipient, amount);
         let gas_limit = self.compute_gas_limit(data.as_slice());
-        let gas_price = gwei_to_wei::<U256, _>(gas_price);
-        let transaction_parameters = TransactionParameters {
-            nonce: Some(nonce),
-            to: Some(H160(self.contract_address().0)),
-            gas: gas_limit,
-            gas_price: Some(gas_price),
-            value: ethereum_types::U256::zero(),
-            data: Bytes(data.to_vec()),
-            chain_id: Some(self.chain.rec().num_chain_id),
+        self.sign_contract_call(
+            self.contract_address(),
+            data.to_vec(),
+            gas_limit,
+            consuming_wallet,
+            nonce,
+            fee_model,
+        )
+    }
+
+    fn sign_contract_call(
+        &self,
+        contract: Address,
+        data: Vec<u8>,
+        gas_limit: U256,
+        consuming_wallet: &Wallet,
+        nonce: U256,
+        fee_model: TransactionFeeModel,
+    ) -> Result<SignedTransaction, PayableTransactionError> {
+        let transaction = match fee_model {
+            TransactionFeeModel::Legacy { gas_price_wei } => {
+                UnsignedTransaction::Legacy(TransactionParameters {
+                    nonce: Some(nonce),
+                    to: Some(H160(contract.0)),
+                    gas: gas_limit,
+                    gas_price: Some(U256::from(gas_price_wei)),
+                    value: ethereum_types::U256::zero(),
+                    data: Bytes(data),
+                    chain_id: Some(self.chain.rec().num_chain_id),
+                })
+            }
+            TransactionFeeModel::Eip1559 {
+                max_fee_per_gas_wei,
+                max_priority_fee_per_gas_wei,
+            } => UnsignedTransaction::Eip1559(Eip1559TransactionParameters {
+                chain_id: self.chain.rec().num_chain_id,
+                nonce,
+                max_priority_fee_per_gas: U256::from(max_priority_fee_per_gas_wei),
+                max_fee_per_gas: U256::from(max_fee_per_gas_wei),
+                gas: gas_limit,
+                to: H160(contract.0),
+                value: ethereum_types::U256::zero(),
+                data: Bytes(data),
+            }),
         };
 
+        match &self.transaction_signer_opt {
+            Some(signer) => signer
+                .sign_transaction(consuming_wallet.address(), transaction)
+                .map_err(PayableTransactionError::Signing),
+            None => self.sign_transaction_locally(consuming_wallet, transaction),
+        }
+    }
+
+    fn sign_transaction_locally(
+        &self,
+        consuming_wallet: &Wallet,
+        transaction: UnsignedTransaction,
+    ) -> Result<SignedTransaction, PayableTransactionError> {
         let key = match consuming_wallet.prepare_secp256k1_secret() {
             Ok(secret) => secret,
             Err(e) => return Err(PayableTransactionError::UnusableWallet(e.to_string())),
         };
 
-        self.batch_payable_tools
-            .sign_transaction(transaction_parameters, &self.web3_batch, &key)
-            .map_err(|e| PayableTransactionError::Signing(e.to_string()))
+        match transaction {
+            UnsignedTransaction::Legacy(transaction_parameters) => self
+                .batch_payable_tools
+                .sign_transaction(transaction_parameters, &self.web3_batch, &key)
+                .map_err(|e| PayableTransactionError::Signing(e.to_string())),
+            UnsignedTransaction::Eip1559(transaction_parameters) => Ok(self
+                .batch_payable_tools
+                .sign_eip1559_transaction(transaction_parameters, &key)),
+        }
+    }
+
+    fn transaction_fee_model(&self, gas_price_ceiling_gwei: u64) -> TransactionFeeModel {
+        let ceiling_wei = gwei_to_wei::<u128, _>(gas_price_ceiling_gwei);
+        let fee_history = match self
+            .lower_interface
+            .get_fee_history(FEE_HISTORY_BLOCK_COUNT, FEE_HISTORY_REWARD_PERCENTILE)
+        {
+            Ok(fee_history) => fee_history,
+            Err(e) => {
+                warning!(
+                    self.logger,
+                    "Falling back to legacy transactions at {} gwei per gas: {:?}",
+                    gas_price_ceiling_gwei,
+                    e
+                );
+                return TransactionFeeModel::Legacy {
+                    gas_price_wei: ceiling_wei,
+                };
+            }
+        };
+        let (next_base_fee, priority_fee) = match Self::fee_market_estimates(&fee_history) {
+            Some(estimates) => estimates,
+            None => {
+                debug!(
+                    self.logger,
+                    "Blockchain service reports no base fees; falling back to legacy transactions"
+                );
+                return TransactionFeeModel::Legacy {
+                    gas_price_wei: ceiling_wei,
+                };
+            }
+        };
+        // The doubled base fee keeps the transaction includable through six full blocks in a row
+        let max_fee = next_base_fee.saturating_mul(2).saturating_add(priority_fee);
+        if max_fee > ceiling_wei {
+            warning!(
+                self.logger,
+                "Max fee per gas of {} wei, as derived from recent blocks, exceeds the ceiling \
+                of {} gwei given by gas-price; using the ceiling instead",
+                max_fee.separate_with_commas(),
+                gas_price_ceiling_gwei
+            );
+        }
+        TransactionFeeModel::Eip1559 {
+            max_fee_per_gas_wei: max_fee.min(ceiling_wei),
+            max_priority_fee_per_gas_wei: priority_fee.min(ceiling_wei),
+        }
+    }
+
+    fn fee_market_estimates(fee_history: &FeeHistory) -> Option<(u128, u128)> {
+        let next_base_fee = fee_history.base_fee_per_gas.last()?;
+        if next_base_fee.is_zero() {
+            return None;
+        }
+        let mut rewards: Vec<U256> = fee_history
+            .reward
+            .iter()
+            .filter_map(|block_rewards| block_rewards.first().copied())
+            .collect();
+        rewards.sort();
+        let median_reward = rewards.get(rewards.len() / 2).copied().unwrap_or_default();
+        Some((
+            Self::u256_to_u128_saturating(*next_base_fee),
+            Self::u256_to_u128_saturating(median_reward),
+        ))
+    }
+
+    fn u256_to_u128_saturating(num: U256) -> u128 {
+        if num > U256::from(u128::MAX) {
+            u128::MAX
+        } else {
+            num.as_u128()
+        }
     }
 
-    fn transmission_log(&self, accounts: &[PayableAccount], gas_price: u64) -> String {
+    fn transmission_log(
+        &self,
+        accounts: &[PayableAccount],
+        fee_model: TransactionFeeModel,
+        multisend_contract_opt: Option<Address>,
+    ) -> String {
         let chain_name = self
             .chain
             .rec()
@@ -538,16 +1074,40 @@ where
             .skip_while(|char| char != &'-')
             .skip(1)
             .collect::<String>();
+        let fees = match fee_model {
+            TransactionFeeModel::Legacy { gas_price_wei } => format!(
+                "{:<45}{} wei\n",
+                "gas price:",
+                gas_price_wei.separate_with_commas()
+            ),
+            TransactionFeeModel::Eip1559 {
+                max_fee_per_gas_wei,
+                max_priority_fee_per_gas_wei,
+            } => format!(
+                "{:<45}{} wei\n{:<45}{} wei\n",
+                "max fee per gas:",
+                max_fee_per_gas_wei.separate_with_commas(),
+                "max priority fee per gas:",
+                max_priority_fee_per_gas_wei.separate_with_commas()
+            ),
+        };
+        let multisend = match multisend_contract_opt {
+            Some(multisend_contract) => {
+                format!("{:<45}{:?}\n", "multisend contract:", multisend_contract)
+            }
+            None => String::new(),
+        };
         let introduction = once(format!(
             "\
         Paying to creditors...\n\
         Transactions in the batch:\n\
         \n\
-        gas price:                                   {} gwei\n\
+        {}\
         chain:                                       {}\n\
+        {}\
         \n\
         [wallet address]                             [payment in wei]\n",
-            gas_price, chain_name
+            fees, chain_name, multisend
         ));
         let body = accounts.iter().map(|account| {
             format!(
@@ -569,6 +1129,25 @@ where
         data
     }
 
+    fn multisend_transaction_data(&self, recipients_and_amounts: &[(Address, u128)]) -> Vec<u8> {
+        let (recipients, amounts): (Vec<Token>, Vec<Token>) = recipients_and_amounts
+            .iter()
+            .map(|(recipient, amount)| {
+                (Token::Address(*recipient), Token::Uint(U256::from(*amount)))
+            })
+            .unzip();
+        let arguments = ethabi::encode(&[
+            Token::Address(self.contract_address()),
+            Token::Array(recipients),
+            Token::Array(amounts),
+        ]);
+        MULTISEND_METHOD_ID
+            .iter()
+            .copied()
+            .chain(arguments)
+            .collect()
+    }
+
     fn compute_gas_limit(&self, data: &[u8]) -> U256 {
         ethereum_types::U256::try_from(data.iter().fold(self.gas_limit_const_part, |acc, v| {
             acc + if v == &0u8 { 4 } else { 68 }
@@ -577,23 +1156,26 @@ where
     }
 
     fn web3_gas_limit_const_part(chain: Chain) -> u64 {
-        match chain {
-            Chain::EthMainnet | Chain::EthRopsten | Chain::Dev => 55_000,
-            Chain::PolyMainnet | Chain::PolyAmoy => 70_000,
-        }
+        chain.rec().gas_limit_const_part
     }
 
     fn extract_transactions_from_logs(&self, logs: Vec<Log>) -> Vec<BlockchainTransaction> {
         logs.iter()
-            .filter_map(|log: &Log| match log.block_number {
-                None => None,
-                Some(block_number) => {
-                    let wei_amount = U256::from(log.data.0.as_slice()).as_u128();
-                    Some(BlockchainTransaction {
-                        block_number: block_number.as_u64(),
-                        from: Wallet::from(log.topics[1]),
-                        wei_amount,
-                    })
+            // A log marked as removed belonged to a block that a chain reorganization has orphaned
+            .filter(|log: &&Log| !log.is_removed())
+            .filter_map(|log: &Log| {
+                match (log.block_number, log.block_hash, log.transaction_hash) {
+                    (Some(block_number), Some(block_hash), Some(transaction_hash)) => {
+                        let wei_amount = U256::from(log.data.0.as_slice()).as_u128();
+                        Some(BlockchainTransaction {
+                            block_number: block_number.as_u64(),
+                            block_hash,
+                            transaction_hash,
+                            from: Wallet::from(log.topics[1]),
+                            wei_amount,
+                        })
+                    }
+                    _ => None,
                 }
             })
             .collect()
@@ -621,17 +1203,25 @@ mod tests {
     use crate::accountant::db_access_objects::utils::from_time_t;
     use crate::accountant::gwei_to_wei;
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::agent_web3::WEB3_MAXIMAL_GAS_LIMIT_MARGIN;
-    use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
+    use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::{
+        BlockchainAgent, TransactionFeeModel,
+    };
     use crate::accountant::test_utils::{
         make_payable_account, make_payable_account_with_wallet_and_balance_and_timestamp_opt,
+        make_pending_payable_fingerprint, make_resubmission_data,
     };
     use crate::blockchain::bip32::Bip32EncryptionKeyProvider;
-    use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
+    use crate::blockchain::blockchain_bridge::{
+        PendingPayableFingerprint, PendingPayableFingerprintSeeds, ResubmissionData,
+    };
 
+    use crate::blockchain::blockchain_interface::blockchain_interface_web3::eip1559_transaction::Eip1559TransactionParameters;
     use crate::blockchain::blockchain_interface::blockchain_interface_web3::{
-        BlockchainInterfaceWeb3, CONTRACT_ABI, REQUESTS_IN_PARALLEL, TRANSACTION_LITERAL,
-        TRANSFER_METHOD_ID,
+        BlockchainInterfaceWeb3, CONTRACT_ABI, FEE_HISTORY_BLOCK_COUNT,
+        FEE_HISTORY_REWARD_PERCENTILE, MULTISEND_GAS_PER_CREDITOR, MULTISEND_METHOD_ID,
+        REQUESTS_IN_PARALLEL, TRANSACTION_LITERAL, TRANSFER_METHOD_ID,
     };
+    use crate::blockchain::blockchain_interface::lower_level_interface::ResultForFeeHistory;
     use crate::blockchain::blockchain_interface::test_utils::{
         test_blockchain_interface_is_connected_and_functioning, LowBlockchainIntMock,
     };
@@ -658,6 +1248,7 @@ mod tests {
     use jsonrpc_core::Version::V2;
     use jsonrpc_core::{Call, Error as RPCError, ErrorCode, Id, MethodCall, Params};
     use masq_lib::blockchains::chains::Chain;
+    use masq_lib::constants::DEFAULT_MULTISEND_MAX_CREDITORS;
     use masq_lib::logger::Logger;
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
     use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
@@ -667,18 +1258,22 @@ mod tests {
     use std::net::Ipv4Addr;
 
     use crate::accountant::db_access_objects::payable_dao::PayableAccount;
-    use crate::accountant::db_access_objects::pending_payable_dao::PendingPayable;
+    use crate::accountant::db_access_objects::pending_payable_dao::{
+        PendingPayable, PendingPayableReplacement,
+    };
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::test_utils::BlockchainAgentMock;
     use crate::blockchain::blockchain_interface::blockchain_interface_web3::test_utils::{
-        make_default_signed_transaction, BatchPayableToolsMock,
+        make_default_signed_transaction, BatchPayableToolsMock, TransactionSignerMock,
     };
+    use crate::blockchain::blockchain_interface::blockchain_interface_web3::transaction_signer::UnsignedTransaction;
     use crate::blockchain::blockchain_interface::data_structures::{
-        BlockchainTransaction, RpcPayablesFailure,
+        BlockchainTransaction, FeeHistory, RpcPayablesFailure,
     };
     use indoc::indoc;
     use std::str::FromStr;
     use std::sync::{Arc, Mutex};
     use std::time::SystemTime;
+    use thousands::Separable;
     use web3::transports::{Batch, Http};
     use web3::types::{
         Address, BlockNumber, Bytes, TransactionParameters, TransactionReceipt, H2048, H256, U256,
@@ -705,6 +1300,14 @@ mod tests {
             "payable":false,
             "stateMutability":"nonpayable",
             "type":"function"
+            },{
+            "constant":true,
+            "inputs":[{"name":"owner","type":"address"},{"name":"spender","type":"address"}],
+            "name":"allowance",
+            "outputs":[{"name":"","type":"uint256"}],
+            "payable":false,
+            "stateMutability":"view",
+            "type":"function"
             }]"#
         );
         let transaction_literal_expected: H256 = H256 {
@@ -717,6 +1320,8 @@ mod tests {
         assert_eq!(CONTRACT_ABI, contract_abi_expected);
         assert_eq!(TRANSACTION_LITERAL, transaction_literal_expected);
         assert_eq!(TRANSFER_METHOD_ID, [0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(MULTISEND_METHOD_ID, [0x51, 0xba, 0x16, 0x2c]);
+        assert_eq!(MULTISEND_GAS_PER_CREDITOR, 50_000);
         assert_eq!(REQUESTS_IN_PARALLEL, 1);
     }
 
@@ -752,6 +1357,86 @@ mod tests {
         test_blockchain_interface_is_connected_and_functioning(subject_factory)
     }
 
+    #[test]
+    fn blockchain_interface_web3_retrieve_transactions_ignores_logs_removed_by_reorg() {
+        let to = "0x3f69f9efd4f2592fd70be8c32ecd9dce71c472fc";
+        let port = find_free_port();
+        #[rustfmt::skip]
+        let _test_server = TestServer::start (port, vec![
+            br#"[{"jsonrpc":"2.0","id":2,"result":"0x400"},{
+                "jsonrpc":"2.0",
+                "id":3,
+                "result":[
+                    {
+                        "address":"0xcd6c588e005032dd882cd43bf53a32129be81302",
+                        "blockHash":"0x1a24b9169cbaec3f6effa1f600b70c7ab9e8e86db44062b49132a4415d26732a",
+                        "blockNumber":"0x3e8",
+                        "data":"0x0000000000000000000000000000000000000000000000000010000000000000",
+                        "logIndex":"0x0",
+                        "removed":false,
+                        "topics":[
+                            "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+                            "0x0000000000000000000000003ab28ecedea6cdb6feed398e93ae8c7b316b1182",
+                            "0x000000000000000000000000adc1853c7859369639eb414b6342b36288fe6092"
+                        ],
+                        "transactionHash":"0x955cec6ac4f832911ab894ce16aa22c3003f46deff3f7165b32700d2f5ff0681",
+                        "transactionIndex":"0x0"
+                    },
+                    {
+                        "address":"0xcd6c588e005032dd882cd43bf53a32129be81302",
+                        "blockHash":"0x1a24b9169cbaec3f6effa1f600b70c7ab9e8e86db44062b49132a4415d26732b",
+                        "blockNumber":"0x3f0",
+                        "data":"0x0000000000000000000000000000000000000000000000000010000000000000",
+                        "logIndex":"0x0",
+                        "removed":true,
+                        "topics":[
+                            "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+                            "0x0000000000000000000000003f69f9efd4f2592fd70be8c32ecd9dce71c472fc",
+                            "0x000000000000000000000000adc1853c7859369639eb414b6342b36288fe6092"
+                        ],
+                        "transactionHash":"0x955cec6ac4f832911ab894ce16aa22c3003f46deff3f7165b32700d2f5ff0680",
+                        "transactionIndex":"0x0"
+                    }
+                ]
+            }]"#.to_vec(),
+        ]);
+        let (event_loop_handle, transport) = Http::with_max_parallel(
+            &format!("http://{}:{}", &Ipv4Addr::LOCALHOST, port),
+            REQUESTS_IN_PARALLEL,
+        )
+        .unwrap();
+        let subject =
+            BlockchainInterfaceWeb3::new(transport, event_loop_handle, TEST_DEFAULT_CHAIN);
+
+        let result = subject
+            .retrieve_transactions(
+                BlockNumber::Number(42u64.into()),
+                BlockNumber::Number(1024u64.into()),
+                &Wallet::from_str(&to).unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            result,
+            RetrievedBlockchainTransactions {
+                new_start_block: 1025,
+                transactions: vec![BlockchainTransaction {
+                    block_number: 1000,
+                    block_hash: H256::from_str(
+                        "1a24b9169cbaec3f6effa1f600b70c7ab9e8e86db44062b49132a4415d26732a"
+                    )
+                    .unwrap(),
+                    transaction_hash: H256::from_str(
+                        "955cec6ac4f832911ab894ce16aa22c3003f46deff3f7165b32700d2f5ff0681"
+                    )
+                    .unwrap(),
+                    from: Wallet::from_str("0x3ab28ecedea6cdb6feed398e93ae8c7b316b1182").unwrap(),
+                    wei_amount: 4_503_599_627_370_496u128,
+                }]
+            }
+        )
+    }
+
     #[test]
     fn blockchain_interface_web3_retrieves_transactions() {
         let to = "0x3f69f9efd4f2592fd70be8c32ecd9dce71c472fc";
@@ -834,12 +1519,28 @@ mod tests {
                 transactions: vec![
                     BlockchainTransaction {
                         block_number: 0x4be663,
+                        block_hash: H256::from_str(
+                            "1a24b9169cbaec3f6effa1f600b70c7ab9e8e86db44062b49132a4415d26732a"
+                        )
+                        .unwrap(),
+                        transaction_hash: H256::from_str(
+                            "955cec6ac4f832911ab894ce16aa22c3003f46deff3f7165b32700d2f5ff0681"
+                        )
+                        .unwrap(),
                         from: Wallet::from_str("0x3ab28ecedea6cdb6feed398e93ae8c7b316b1182")
                             .unwrap(),
                         wei_amount: 4_503_599_627_370_496u128,
                     },
                     BlockchainTransaction {
                         block_number: 0x4be662,
+                        block_hash: H256::from_str(
+                            "1a24b9169cbaec3f6effa1f600b70c7ab9e8e86db44062b49132a4415d26732b"
+                        )
+                        .unwrap(),
+                        transaction_hash: H256::from_str(
+                            "955cec6ac4f832911ab894ce16aa22c3003f46deff3f7165b32700d2f5ff0680"
+                        )
+                        .unwrap(),
                         from: Wallet::from_str("0x3f69f9efd4f2592fd70be8c32ecd9dce71c472fc")
                             .unwrap(),
                         wei_amount: 4_503_599_627_370_496u128,
@@ -1054,6 +1755,7 @@ mod tests {
         let get_transaction_fee_balance_params_arc = Arc::new(Mutex::new(vec![]));
         let get_masq_balance_params_arc = Arc::new(Mutex::new(vec![]));
         let get_transactions_id_params_arc = Arc::new(Mutex::new(vec![]));
+        let get_fee_history_params_arc = Arc::new(Mutex::new(vec![]));
         let chain = Chain::PolyMainnet;
         let wallet = make_wallet("abc");
         let persistent_config = PersistentConfigurationMock::new().gas_price_result(Ok(50));
@@ -1071,7 +1773,12 @@ mod tests {
             .get_masq_balance_params(&get_masq_balance_params_arc)
             .get_masq_balance_result(Ok(masq_balance))
             .get_transaction_id_params(&get_transactions_id_params_arc)
-            .get_transaction_id_result(Ok(transaction_id));
+            .get_transaction_id_result(Ok(transaction_id))
+            .get_fee_history_params(&get_fee_history_params_arc)
+            .get_fee_history_result(Ok(make_fee_history(
+                &[18, 19, 20],
+                &[&[1_000_000_000], &[3_000_000_000], &[2_000_000_000]],
+            )));
         subject.lower_interface = Box::new(lower_blockchain_interface);
 
         let result = subject
@@ -1085,6 +1792,11 @@ mod tests {
         assert_eq!(*get_masq_balance_params, vec![wallet.clone()]);
         let get_transaction_id_params = get_transactions_id_params_arc.lock().unwrap();
         assert_eq!(*get_transaction_id_params, vec![wallet.clone()]);
+        let get_fee_history_params = get_fee_history_params_arc.lock().unwrap();
+        assert_eq!(
+            *get_fee_history_params,
+            vec![(FEE_HISTORY_BLOCK_COUNT, FEE_HISTORY_REWARD_PERCENTILE)]
+        );
         assert_eq!(result.consuming_wallet(), &wallet);
         assert_eq!(result.pending_transaction_id(), transaction_id);
         assert_eq!(
@@ -1094,17 +1806,171 @@ mod tests {
                 masq_token_balance_in_minor_units: masq_balance
             }
         );
-        assert_eq!(result.agreed_fee_per_computation_unit(), 50);
+        assert_eq!(
+            result.agreed_transaction_fee_model(),
+            TransactionFeeModel::Eip1559 {
+                max_fee_per_gas_wei: 42_000_000_000,
+                max_priority_fee_per_gas_wei: 2_000_000_000
+            }
+        );
         let expected_fee_estimation = (3
             * (BlockchainInterfaceWeb3::<Http>::web3_gas_limit_const_part(chain)
-                + WEB3_MAXIMAL_GAS_LIMIT_MARGIN)
-            * 50) as u128;
+                + WEB3_MAXIMAL_GAS_LIMIT_MARGIN)) as u128
+            * 42_000_000_000;
         assert_eq!(
             result.estimated_transaction_fee_total(3),
             expected_fee_estimation
         )
     }
 
+    #[test]
+    fn constants_for_fee_history_are_correct() {
+        assert_eq!(FEE_HISTORY_BLOCK_COUNT, 10);
+        assert_eq!(FEE_HISTORY_REWARD_PERCENTILE, 50.0)
+    }
+
+    #[test]
+    fn transaction_fee_model_takes_median_of_rewards_and_tolerates_blocks_without_them() {
+        let subject = make_subject_with_fee_history(Ok(make_fee_history(
+            &[30, 31, 32, 33],
+            &[&[4_000_000_000], &[], &[1_000_000_000], &[30_000_000_000]],
+        )));
+
+        let result = subject.transaction_fee_model(100);
+
+        assert_eq!(
+            result,
+            TransactionFeeModel::Eip1559 {
+                max_fee_per_gas_wei: 70_000_000_000,
+                max_priority_fee_per_gas_wei: 4_000_000_000
+            }
+        )
+    }
+
+    #[test]
+    fn transaction_fee_model_with_no_rewards_at_all_offers_no_priority_fee() {
+        let subject = make_subject_with_fee_history(Ok(make_fee_history(&[7, 8], &[])));
+
+        let result = subject.transaction_fee_model(100);
+
+        assert_eq!(
+            result,
+            TransactionFeeModel::Eip1559 {
+                max_fee_per_gas_wei: 16_000_000_000,
+                max_priority_fee_per_gas_wei: 0
+            }
+        )
+    }
+
+    #[test]
+    fn transaction_fee_model_is_capped_by_gas_price_ceiling() {
+        init_test_logging();
+        let test_name = "transaction_fee_model_is_capped_by_gas_price_ceiling";
+        let mut subject =
+            make_subject_with_fee_history(Ok(make_fee_history(&[90, 100], &[&[70_000_000_000]])));
+        subject.logger = Logger::new(test_name);
+
+        let result = subject.transaction_fee_model(60);
+
+        assert_eq!(
+            result,
+            TransactionFeeModel::Eip1559 {
+                max_fee_per_gas_wei: 60_000_000_000,
+                max_priority_fee_per_gas_wei: 60_000_000_000
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Max fee per gas of 270,000,000,000 wei, as derived from recent \
+            blocks, exceeds the ceiling of 60 gwei given by gas-price; using the ceiling instead"
+        ));
+    }
+
+    #[test]
+    fn transaction_fee_model_falls_back_to_legacy_if_fee_history_is_unavailable() {
+        init_test_logging();
+        let test_name = "transaction_fee_model_falls_back_to_legacy_if_fee_history_is_unavailable";
+        let mut subject = make_subject_with_fee_history(Err(BlockchainError::QueryFailed(
+            "Method not found".to_string(),
+        )));
+        subject.logger = Logger::new(test_name);
+
+        let result = subject.transaction_fee_model(25);
+
+        assert_eq!(
+            result,
+            TransactionFeeModel::Legacy {
+                gas_price_wei: 25_000_000_000
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Falling back to legacy transactions at 25 gwei per gas: \
+            QueryFailed(\"Method not found\")"
+        ));
+    }
+
+    #[test]
+    fn transaction_fee_model_falls_back_to_legacy_for_chains_without_base_fee() {
+        init_test_logging();
+        let test_name = "transaction_fee_model_falls_back_to_legacy_for_chains_without_base_fee";
+        let mut subject =
+            make_subject_with_fee_history(Ok(make_fee_history(&[0, 0], &[&[1_000_000_000]])));
+        subject.logger = Logger::new(test_name);
+
+        let result = subject.transaction_fee_model(25);
+
+        assert_eq!(
+            result,
+            TransactionFeeModel::Legacy {
+                gas_price_wei: 25_000_000_000
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "DEBUG: {test_name}: Blockchain service reports no base fees; falling back to legacy \
+            transactions"
+        ));
+    }
+
+    #[test]
+    fn transaction_fee_model_falls_back_to_legacy_for_empty_fee_history() {
+        let subject = make_subject_with_fee_history(Ok(FeeHistory::default()));
+
+        let result = subject.transaction_fee_model(25);
+
+        assert_eq!(
+            result,
+            TransactionFeeModel::Legacy {
+                gas_price_wei: 25_000_000_000
+            }
+        )
+    }
+
+    fn make_subject_with_fee_history(
+        result: ResultForFeeHistory,
+    ) -> BlockchainInterfaceWeb3<TestTransport> {
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            TEST_DEFAULT_CHAIN,
+        );
+        subject.lower_interface =
+            Box::new(LowBlockchainIntMock::default().get_fee_history_result(result));
+        subject
+    }
+
+    fn make_fee_history(base_fees_gwei: &[u64], rewards_wei: &[&[u64]]) -> FeeHistory {
+        FeeHistory {
+            oldest_block: U256::from(1000),
+            base_fee_per_gas: base_fees_gwei
+                .iter()
+                .map(|gwei| gwei_to_wei(*gwei))
+                .collect(),
+            reward: rewards_wei
+                .iter()
+                .map(|block_rewards| block_rewards.iter().map(|wei| U256::from(*wei)).collect())
+                .collect(),
+        }
+    }
+
     #[test]
     fn build_of_the_blockchain_agent_fails_on_fetching_gas_price() {
         let chain = Chain::PolyAmoy;
@@ -1261,7 +2127,7 @@ mod tests {
         );
         let accounts_to_process = vec![account_1, account_2, account_3];
         let consuming_wallet = make_paying_wallet(b"gdasgsa");
-        let agent = make_initialized_agent(120, consuming_wallet, U256::from(6));
+        let agent = make_initialized_agent(legacy_fees(120), consuming_wallet, U256::from(6));
         let test_timestamp_before = SystemTime::now();
 
         let result = subject
@@ -1390,13 +2256,30 @@ mod tests {
                     (expected_hash_1, gwei_to_wei(900_000_000_u64)),
                     (expected_hash_2, 123_456_789),
                     (expected_hash_3, gwei_to_wei(33_355_666_u64))
-                ]
+                ],
+                resubmission_data: vec![
+                    ResubmissionData {
+                        nonce: 6,
+                        recipient_wallet: make_wallet("w123"),
+                        fee_model: legacy_fees(120)
+                    },
+                    ResubmissionData {
+                        nonce: 7,
+                        recipient_wallet: make_wallet("w555"),
+                        fee_model: legacy_fees(120)
+                    },
+                    ResubmissionData {
+                        nonce: 8,
+                        recipient_wallet: make_wallet("w987"),
+                        fee_model: legacy_fees(120)
+                    }
+                ],
             }
         );
         let log_handler = TestLogHandler::new();
         log_handler.exists_log_containing("DEBUG: sending_batch_payments: \
         Common attributes of payables to be transacted: sender wallet: 0x5c361ba8d82fcf0e5538b2a823e9d457a2296725, contract: \
-          0x384dec25e03f94931767ce4c3556168468ba24c3, chain_id: 3, gas_price: 120");
+          0x384dec25e03f94931767ce4c3556168468ba24c3, chain_id: 3, fees: Legacy { gas_price_wei: 120000000000 }");
         log_handler.exists_log_containing(
             "DEBUG: sending_batch_payments: Preparing payment of 900,000,000,000,000,000 wei \
         to 0x0000000000000000000000000000000077313233 with nonce 6",
@@ -1413,7 +2296,7 @@ mod tests {
             "INFO: sending_batch_payments: Paying to creditors...\n\
         Transactions in the batch:\n\
         \n\
-        gas price:                                   120 gwei\n\
+        gas price:                                   120,000,000,000 wei\n\
         chain:                                       ropsten\n\
         \n\
         [wallet address]                             [payment in wei]\n\
@@ -1511,7 +2394,7 @@ mod tests {
             second_payment_amount,
             None,
         );
-        let agent = make_initialized_agent(123, consuming_wallet, U256::from(4));
+        let agent = make_initialized_agent(legacy_fees(123), consuming_wallet, U256::from(4));
 
         let result = subject.send_batch_of_payables(
             agent,
@@ -1568,7 +2451,7 @@ mod tests {
         );
         assert!(sign_transaction_params.is_empty());
         let new_payable_fingerprint_params = new_payable_fingerprint_params_arc.lock().unwrap();
-        let (batch_wide_timestamp, recipient, actual_pending_payables) =
+        let (batch_wide_timestamp, recipient, actual_pending_payables, resubmission_data) =
             &new_payable_fingerprint_params[0];
         assert_eq!(batch_wide_timestamp, &batch_wide_timestamp_expected);
         assert_eq!(
@@ -1578,6 +2461,21 @@ mod tests {
                 (second_hash, second_payment_amount)
             ]
         );
+        assert_eq!(
+            resubmission_data,
+            &vec![
+                ResubmissionData {
+                    nonce: 4,
+                    recipient_wallet: first_creditor_wallet,
+                    fee_model: legacy_fees(123)
+                },
+                ResubmissionData {
+                    nonce: 5,
+                    recipient_wallet: second_creditor_wallet,
+                    fee_model: legacy_fees(123)
+                }
+            ]
+        );
         let mut append_transaction_to_batch_params =
             append_transaction_to_batch_params_arc.lock().unwrap();
         let (bytes_first_payment, web3_from_ertb_call_1) =
@@ -1605,6 +2503,7 @@ mod tests {
         let probe_message = PendingPayableFingerprintSeeds {
             batch_wide_timestamp: SystemTime::now(),
             hashes_and_balances: vec![],
+            resubmission_data: vec![],
         };
         recipient.try_send(probe_message).unwrap();
         System::current().stop();
@@ -1613,6 +2512,453 @@ mod tests {
         assert_eq!(accountant_recording.len(), 1)
     }
 
+    #[test]
+    fn send_batch_of_payables_pays_all_creditors_in_one_multisend_transaction() {
+        init_test_logging();
+        let test_name = "send_batch_of_payables_pays_all_creditors_in_one_multisend_transaction";
+        let get_service_fee_allowance_params_arc = Arc::new(Mutex::new(vec![]));
+        let sign_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let append_transaction_to_batch_params_arc = Arc::new(Mutex::new(vec![]));
+        let new_payable_fingerprint_params_arc = Arc::new(Mutex::new(vec![]));
+        let chain = Chain::EthMainnet;
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            chain,
+        );
+        subject.logger = Logger::new(test_name);
+        let multisend_contract = make_wallet("multisend").address();
+        subject.set_multisend_contract(multisend_contract, DEFAULT_MULTISEND_MAX_CREDITORS);
+        subject.lower_interface = Box::new(
+            LowBlockchainIntMock::default()
+                .get_service_fee_allowance_params(&get_service_fee_allowance_params_arc)
+                .get_service_fee_allowance_result(Ok(U256::from(
+                    333_222_111_000_u64 + 11_222_333_444,
+                ))),
+        );
+        let mut signed_transaction = make_default_signed_transaction();
+        signed_transaction.transaction_hash = make_tx_hash(0xabc);
+        signed_transaction.raw_transaction = Bytes(vec![7, 8, 9]);
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_transaction_params(&sign_transaction_params_arc)
+            .sign_transaction_result(Ok(signed_transaction))
+            .batch_wide_timestamp_result(SystemTime::now())
+            .send_new_payable_fingerprint_credentials_params(&new_payable_fingerprint_params_arc)
+            .append_transaction_to_batch_params(&append_transaction_to_batch_params_arc)
+            .submit_batch_result(Ok(vec![Ok(json!("...hash..."))]));
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        let first_creditor_wallet = make_wallet("creditor321");
+        let second_creditor_wallet = make_wallet("creditor123");
+        let accounts = vec![
+            make_payable_account_with_wallet_and_balance_and_timestamp_opt(
+                first_creditor_wallet.clone(),
+                333_222_111_000,
+                None,
+            ),
+            make_payable_account_with_wallet_and_balance_and_timestamp_opt(
+                second_creditor_wallet.clone(),
+                11_222_333_444,
+                None,
+            ),
+        ];
+        let agent = make_initialized_agent(
+            legacy_fees(123),
+            make_paying_wallet(b"consuming"),
+            U256::from(4),
+        );
+        let (accountant, _, _) = make_recorder();
+        let fingerprints_recipient = accountant.start().recipient();
+
+        let result = subject.send_batch_of_payables(agent, &fingerprints_recipient, &accounts);
+
+        assert_eq!(
+            result,
+            Ok(vec![
+                Ok(PendingPayable {
+                    recipient_wallet: first_creditor_wallet.clone(),
+                    hash: make_tx_hash(0xabc),
+                }),
+                Ok(PendingPayable {
+                    recipient_wallet: second_creditor_wallet.clone(),
+                    hash: make_tx_hash(0xabc),
+                })
+            ])
+        );
+        assert_eq!(
+            *get_service_fee_allowance_params_arc.lock().unwrap(),
+            vec![(make_paying_wallet(b"consuming"), multisend_contract)]
+        );
+        let expected_data = decode_hex(concat!(
+            "51ba162c",
+            "00000000000000000000000006f3c323f0238c72bf35011071f2b5b7f43a054c",
+            "0000000000000000000000000000000000000000000000000000000000000060",
+            "00000000000000000000000000000000000000000000000000000000000000c0",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000006372656469746f72333231",
+            "0000000000000000000000000000000000000000006372656469746f72313233",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000004d9595e718",
+            "000000000000000000000000000000000000000000000000000000029ce73804",
+        ))
+        .unwrap();
+        let sign_transaction_params = sign_transaction_params_arc.lock().unwrap();
+        let (transaction_params, _, _) = &sign_transaction_params[0];
+        assert_eq!(
+            transaction_params,
+            &TransactionParameters {
+                nonce: Some(U256::from(4)),
+                to: Some(multisend_contract),
+                gas: U256::from(60_008 + 2 * MULTISEND_GAS_PER_CREDITOR),
+                gas_price: Some(U256::from(123_000_000_000_u64)),
+                value: U256::zero(),
+                data: Bytes(expected_data),
+                chain_id: Some(chain.rec().num_chain_id),
+            }
+        );
+        assert_eq!(sign_transaction_params.len(), 1);
+        let new_payable_fingerprint_params = new_payable_fingerprint_params_arc.lock().unwrap();
+        let (_, _, hashes_and_amounts, resubmission_data) = &new_payable_fingerprint_params[0];
+        assert_eq!(
+            hashes_and_amounts,
+            &vec![
+                (make_tx_hash(0xabc), 333_222_111_000),
+                (make_tx_hash(0xabc), 11_222_333_444)
+            ]
+        );
+        assert_eq!(
+            resubmission_data,
+            &vec![
+                ResubmissionData {
+                    nonce: 4,
+                    recipient_wallet: first_creditor_wallet,
+                    fee_model: legacy_fees(123)
+                },
+                ResubmissionData {
+                    nonce: 4,
+                    recipient_wallet: second_creditor_wallet,
+                    fee_model: legacy_fees(123)
+                }
+            ]
+        );
+        let append_transaction_to_batch_params =
+            append_transaction_to_batch_params_arc.lock().unwrap();
+        assert_eq!(append_transaction_to_batch_params.len(), 1);
+        assert_eq!(
+            append_transaction_to_batch_params[0].0,
+            Bytes(vec![7, 8, 9])
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {test_name}: Paying to creditors...\n\
+        Transactions in the batch:\n\
+        \n\
+        gas price:                                   123,000,000,000 wei\n\
+        chain:                                       mainnet\n\
+        multisend contract:                          {:?}\n\
+        \n\
+        [wallet address]                             [payment in wei]\n\
+        0x0000000000000000006372656469746f72333231   333,222,111,000\n",
+            multisend_contract
+        ));
+    }
+
+    #[test]
+    fn send_batch_of_payables_reports_a_rejected_multisend_transaction_for_every_payable() {
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            TEST_DEFAULT_CHAIN,
+        );
+        subject.set_multisend_contract(
+            make_wallet("multisend").address(),
+            DEFAULT_MULTISEND_MAX_CREDITORS,
+        );
+        subject.lower_interface = Box::new(
+            LowBlockchainIntMock::default().get_service_fee_allowance_result(Ok(U256::MAX)),
+        );
+        let mut signed_transaction = make_default_signed_transaction();
+        signed_transaction.transaction_hash = make_tx_hash(0xabc);
+        let rpc_error = web3::Error::Rpc(RPCError {
+            code: ErrorCode::ServerError(-32000),
+            message: "insufficient funds for gas * price + value".to_string(),
+            data: None,
+        });
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_transaction_result(Ok(signed_transaction))
+            .batch_wide_timestamp_result(SystemTime::now())
+            .submit_batch_result(Ok(vec![Err(rpc_error.clone())]));
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        let accounts = vec![make_payable_account(111), make_payable_account(222)];
+        let agent = make_initialized_agent(
+            legacy_fees(123),
+            make_paying_wallet(b"consuming"),
+            U256::from(4),
+        );
+        let (accountant, _, _) = make_recorder();
+        let fingerprints_recipient = accountant.start().recipient();
+
+        let result = subject.send_batch_of_payables(agent, &fingerprints_recipient, &accounts);
+
+        assert_eq!(
+            result,
+            Ok(vec![
+                Err(RpcPayablesFailure {
+                    rpc_error: rpc_error.clone(),
+                    recipient_wallet: accounts[0].wallet.clone(),
+                    hash: make_tx_hash(0xabc),
+                }),
+                Err(RpcPayablesFailure {
+                    rpc_error,
+                    recipient_wallet: accounts[1].wallet.clone(),
+                    hash: make_tx_hash(0xabc),
+                })
+            ])
+        );
+    }
+
+    #[test]
+    fn send_batch_of_payables_splits_creditors_over_the_limit_into_several_multisend_transactions()
+    {
+        let sign_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let new_payable_fingerprint_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            TEST_DEFAULT_CHAIN,
+        );
+        let multisend_contract = make_wallet("multisend").address();
+        subject.set_multisend_contract(multisend_contract, 2);
+        subject.lower_interface = Box::new(
+            LowBlockchainIntMock::default().get_service_fee_allowance_result(Ok(U256::MAX)),
+        );
+        let signed_transactions = [0xaaa, 0xbbb, 0xccc].map(|hash| {
+            let mut signed_transaction = make_default_signed_transaction();
+            signed_transaction.transaction_hash = make_tx_hash(hash);
+            signed_transaction
+        });
+        let rpc_error = web3::Error::Rpc(RPCError {
+            code: ErrorCode::ServerError(-32000),
+            message: "nonce too low".to_string(),
+            data: None,
+        });
+        let [first_signed, second_signed, third_signed] = signed_transactions;
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_transaction_params(&sign_transaction_params_arc)
+            .sign_transaction_result(Ok(first_signed))
+            .sign_transaction_result(Ok(second_signed))
+            .sign_transaction_result(Ok(third_signed))
+            .batch_wide_timestamp_result(SystemTime::now())
+            .send_new_payable_fingerprint_credentials_params(&new_payable_fingerprint_params_arc)
+            .submit_batch_result(Ok(vec![
+                Ok(json!("...hash...")),
+                Err(rpc_error.clone()),
+                Ok(json!("...hash...")),
+            ]));
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        let accounts = (1..=5)
+            .map(|n| make_payable_account(n * 111))
+            .collect::<Vec<_>>();
+        let agent = make_initialized_agent(
+            legacy_fees(123),
+            make_paying_wallet(b"consuming"),
+            U256::from(4),
+        );
+        let (accountant, _, _) = make_recorder();
+        let fingerprints_recipient = accountant.start().recipient();
+
+        let result = subject.send_batch_of_payables(agent, &fingerprints_recipient, &accounts);
+
+        let pending_payable = |idx: usize, hash: u32| {
+            Ok(PendingPayable {
+                recipient_wallet: accounts[idx].wallet.clone(),
+                hash: make_tx_hash(hash),
+            })
+        };
+        let failure = |idx: usize| {
+            Err(RpcPayablesFailure {
+                rpc_error: rpc_error.clone(),
+                recipient_wallet: accounts[idx].wallet.clone(),
+                hash: make_tx_hash(0xbbb),
+            })
+        };
+        assert_eq!(
+            result,
+            Ok(vec![
+                pending_payable(0, 0xaaa),
+                pending_payable(1, 0xaaa),
+                failure(2),
+                failure(3),
+                pending_payable(4, 0xccc),
+            ])
+        );
+        let sign_transaction_params = sign_transaction_params_arc.lock().unwrap();
+        let nonces_and_recipients = sign_transaction_params
+            .iter()
+            .map(|(transaction_params, _, _)| (transaction_params.nonce, transaction_params.to))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            nonces_and_recipients,
+            vec![
+                (Some(U256::from(4)), Some(multisend_contract)),
+                (Some(U256::from(5)), Some(multisend_contract)),
+                (Some(U256::from(6)), Some(subject.contract_address())),
+            ]
+        );
+        let new_payable_fingerprint_params = new_payable_fingerprint_params_arc.lock().unwrap();
+        let (_, _, _, resubmission_data) = &new_payable_fingerprint_params[0];
+        assert_eq!(
+            resubmission_data
+                .iter()
+                .map(|resubmission_data| resubmission_data.nonce)
+                .collect::<Vec<_>>(),
+            vec![4, 4, 5, 5, 6]
+        );
+    }
+
+    #[test]
+    fn send_batch_of_payables_pays_a_lone_creditor_by_plain_transfer_despite_multisend_contract() {
+        let sign_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            TEST_DEFAULT_CHAIN,
+        );
+        subject.set_multisend_contract(
+            make_wallet("multisend").address(),
+            DEFAULT_MULTISEND_MAX_CREDITORS,
+        );
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_transaction_params(&sign_transaction_params_arc)
+            .sign_transaction_result(Ok(make_default_signed_transaction()))
+            .batch_wide_timestamp_result(SystemTime::now())
+            .submit_batch_result(Ok(vec![Ok(json!("...hash..."))]));
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        let account = make_payable_account(111);
+        let agent = make_initialized_agent(
+            legacy_fees(123),
+            make_paying_wallet(b"consuming"),
+            U256::from(4),
+        );
+        let (accountant, _, _) = make_recorder();
+        let fingerprints_recipient = accountant.start().recipient();
+
+        let result = subject.send_batch_of_payables(agent, &fingerprints_recipient, &[account]);
+
+        assert_eq!(result.unwrap().len(), 1);
+        let sign_transaction_params = sign_transaction_params_arc.lock().unwrap();
+        let (transaction_params, _, _) = &sign_transaction_params[0];
+        assert_eq!(transaction_params.to, Some(subject.contract_address()));
+        assert_eq!(transaction_params.data.0[0..4], TRANSFER_METHOD_ID);
+    }
+
+    #[test]
+    fn send_batch_of_payables_pays_by_plain_transfers_if_the_multisend_allowance_is_too_low() {
+        init_test_logging();
+        let test_name =
+            "send_batch_of_payables_pays_by_plain_transfers_if_the_multisend_allowance_is_too_low";
+        let sign_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            TEST_DEFAULT_CHAIN,
+        );
+        subject.logger = Logger::new(test_name);
+        let multisend_contract = make_wallet("multisend").address();
+        subject.set_multisend_contract(multisend_contract, DEFAULT_MULTISEND_MAX_CREDITORS);
+        let accounts = vec![make_payable_account(111), make_payable_account(222)];
+        let total_wei = accounts[0].balance_wei + accounts[1].balance_wei;
+        subject.lower_interface = Box::new(
+            LowBlockchainIntMock::default()
+                .get_service_fee_allowance_result(Ok(U256::from(total_wei - 1))),
+        );
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_transaction_params(&sign_transaction_params_arc)
+            .sign_transaction_result(Ok(make_default_signed_transaction()))
+            .sign_transaction_result(Ok(make_default_signed_transaction()))
+            .batch_wide_timestamp_result(SystemTime::now())
+            .submit_batch_result(Ok(vec![Ok(json!("...hash...")), Ok(json!("...hash..."))]));
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        let consuming_wallet = make_paying_wallet(b"consuming");
+        let agent =
+            make_initialized_agent(legacy_fees(123), consuming_wallet.clone(), U256::from(4));
+        let (accountant, _, _) = make_recorder();
+        let fingerprints_recipient = accountant.start().recipient();
+
+        let result = subject.send_batch_of_payables(agent, &fingerprints_recipient, &accounts);
+
+        assert_eq!(result.unwrap().len(), 2);
+        let sign_transaction_params = sign_transaction_params_arc.lock().unwrap();
+        let nonces_and_recipients = sign_transaction_params
+            .iter()
+            .map(|(transaction_params, _, _)| (transaction_params.nonce, transaction_params.to))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            nonces_and_recipients,
+            vec![
+                (Some(U256::from(4)), Some(subject.contract_address())),
+                (Some(U256::from(5)), Some(subject.contract_address())),
+            ]
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Multisend contract {:?} may spend only {} wei of {}, but the \
+            payables need {} wei; paying every creditor by a transfer of its own",
+            multisend_contract,
+            (total_wei - 1).separate_with_commas(),
+            consuming_wallet,
+            total_wei.separate_with_commas()
+        ));
+    }
+
+    #[test]
+    fn send_batch_of_payables_pays_by_plain_transfers_if_the_multisend_allowance_is_unknown() {
+        init_test_logging();
+        let test_name =
+            "send_batch_of_payables_pays_by_plain_transfers_if_the_multisend_allowance_is_unknown";
+        let sign_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            TEST_DEFAULT_CHAIN,
+        );
+        subject.logger = Logger::new(test_name);
+        let multisend_contract = make_wallet("multisend").address();
+        subject.set_multisend_contract(multisend_contract, DEFAULT_MULTISEND_MAX_CREDITORS);
+        subject.lower_interface = Box::new(
+            LowBlockchainIntMock::default().get_service_fee_allowance_result(Err(
+                BlockchainError::QueryFailed("booga".to_string()),
+            )),
+        );
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_transaction_params(&sign_transaction_params_arc)
+            .sign_transaction_result(Ok(make_default_signed_transaction()))
+            .sign_transaction_result(Ok(make_default_signed_transaction()))
+            .batch_wide_timestamp_result(SystemTime::now())
+            .submit_batch_result(Ok(vec![Ok(json!("...hash...")), Ok(json!("...hash..."))]));
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        let accounts = vec![make_payable_account(111), make_payable_account(222)];
+        let agent = make_initialized_agent(
+            legacy_fees(123),
+            make_paying_wallet(b"consuming"),
+            U256::from(4),
+        );
+        let (accountant, _, _) = make_recorder();
+        let fingerprints_recipient = accountant.start().recipient();
+
+        let result = subject.send_batch_of_payables(agent, &fingerprints_recipient, &accounts);
+
+        assert_eq!(result.unwrap().len(), 2);
+        let sign_transaction_params = sign_transaction_params_arc.lock().unwrap();
+        assert_eq!(sign_transaction_params.len(), 2);
+        assert!(sign_transaction_params
+            .iter()
+            .all(|(transaction_params, _, _)| transaction_params.to
+                == Some(subject.contract_address())));
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Couldn't check what multisend contract {:?} may spend: \
+            QueryFailed(\"booga\"); paying every creditor by a transfer of its own",
+            multisend_contract
+        ));
+    }
+
     #[test]
     fn web3_gas_limit_const_part_returns_reasonable_values() {
         type Subject = BlockchainInterfaceWeb3<Http>;
@@ -1656,7 +3002,7 @@ mod tests {
             .sign_transaction_result(Ok(make_default_signed_transaction()));
         subject.batch_payable_tools = Box::new(batch_payable_tools);
         let consuming_wallet = make_paying_wallet(consuming_wallet_secret_raw_bytes);
-        let gas_price = 123;
+        let fee_model = legacy_fees(123);
         let nonce = U256::from(5);
 
         let _ = subject.sign_transaction(
@@ -1664,7 +3010,7 @@ mod tests {
             &consuming_wallet,
             1_000_000_000,
             nonce,
-            gas_price,
+            fee_model,
         );
 
         let mut sign_transaction_params = sign_transaction_params_arc.lock().unwrap();
@@ -1692,6 +3038,132 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sign_transaction_makes_eip1559_transaction_for_eip1559_fee_model() {
+        let sign_eip1559_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let chain = Chain::PolyMainnet;
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            chain,
+        );
+        let signed_transaction = make_default_signed_transaction();
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_eip1559_transaction_params(&sign_eip1559_transaction_params_arc)
+            .sign_eip1559_transaction_result(signed_transaction.clone());
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        let consuming_wallet_secret_raw_bytes = b"my-wallet";
+        let consuming_wallet = make_paying_wallet(consuming_wallet_secret_raw_bytes);
+        let recipient = make_wallet("wallet1");
+        let fee_model = TransactionFeeModel::Eip1559 {
+            max_fee_per_gas_wei: 61_500_000_000,
+            max_priority_fee_per_gas_wei: 1_500_000_000,
+        };
+
+        let result = subject.sign_transaction(
+            &recipient,
+            &consuming_wallet,
+            1_000_000_000,
+            U256::from(5),
+            fee_model,
+        );
+
+        assert_eq!(result, Ok(signed_transaction));
+        let mut sign_eip1559_transaction_params =
+            sign_eip1559_transaction_params_arc.lock().unwrap();
+        let (transaction_params, secret) = sign_eip1559_transaction_params.remove(0);
+        assert!(sign_eip1559_transaction_params.is_empty());
+        let data =
+            BlockchainInterfaceWeb3::<TestTransport>::transaction_data(&recipient, 1_000_000_000);
+        assert_eq!(
+            transaction_params,
+            Eip1559TransactionParameters {
+                chain_id: chain.rec().num_chain_id,
+                nonce: U256::from(5),
+                max_priority_fee_per_gas: U256::from(1_500_000_000),
+                max_fee_per_gas: U256::from(61_500_000_000_u64),
+                gas: subject.compute_gas_limit(&data),
+                to: subject.contract_address(),
+                value: U256::zero(),
+                data: Bytes(data.to_vec()),
+            }
+        );
+        assert_eq!(
+            secret,
+            (&Bip32EncryptionKeyProvider::from_raw_secret(
+                &consuming_wallet_secret_raw_bytes.keccak256()
+            )
+            .unwrap())
+                .into()
+        );
+    }
+
+    #[test]
+    fn blockchain_interface_web3_can_transfer_tokens_in_batch_as_eip1559_transactions() {
+        init_test_logging();
+        let test_name =
+            "blockchain_interface_web3_can_transfer_tokens_in_batch_as_eip1559_transactions";
+        let send_batch_params_arc = Arc::new(Mutex::new(vec![]));
+        let transport = TestTransport::default()
+            .send_batch_params(&send_batch_params_arc)
+            .send_batch_result(vec![Ok(json!("...hash..."))]);
+        let (accountant, _, _) = make_recorder();
+        let fingerprint_recipient = accountant.start().recipient();
+        let chain = Chain::PolyMainnet;
+        let mut subject =
+            BlockchainInterfaceWeb3::new(transport, make_fake_event_loop_handle(), chain);
+        subject.logger = Logger::new(test_name);
+        let account = make_payable_account_with_wallet_and_balance_and_timestamp_opt(
+            make_wallet("w123"),
+            gwei_to_wei(900_000_000_u64),
+            None,
+        );
+        let fee_model = TransactionFeeModel::Eip1559 {
+            max_fee_per_gas_wei: 61_500_000_000,
+            max_priority_fee_per_gas_wei: 30_000_000_000,
+        };
+        let agent =
+            make_initialized_agent(fee_model, make_paying_wallet(b"gdasgsa"), U256::from(6));
+
+        let result = subject
+            .send_batch_of_payables(agent, &fingerprint_recipient, &[account])
+            .unwrap();
+
+        let send_batch_params = send_batch_params_arc.lock().unwrap();
+        let raw_transaction = match &send_batch_params[0][0].1 {
+            Call::MethodCall(MethodCall { method, params, .. }) => {
+                assert_eq!(method, "eth_sendRawTransaction");
+                match params {
+                    Params::Array(values) => values[0].as_str().unwrap().to_string(),
+                    x => panic!("unexpected params {:?}", x),
+                }
+            }
+            x => panic!("unexpected call {:?}", x),
+        };
+        assert!(
+            raw_transaction.starts_with("0x02"),
+            "not a type-2 transaction: {}",
+            raw_transaction
+        );
+        let raw_transaction_bytes = decode_hex(&raw_transaction[2..]).unwrap();
+        let pending_payable = result[0].as_ref().unwrap();
+        assert_eq!(
+            pending_payable.hash,
+            H256::from(raw_transaction_bytes.keccak256())
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {test_name}: Paying to creditors...\n\
+        Transactions in the batch:\n\
+        \n\
+        max fee per gas:                             61,500,000,000 wei\n\
+        max priority fee per gas:                    30,000,000,000 wei\n\
+        chain:                                       mainnet\n\
+        \n\
+        [wallet address]                             [payment in wei]\n\
+        0x0000000000000000000000000000000077313233   900,000,000,000,000,000\n"
+        ));
+    }
+
     #[test]
     fn signing_error_terminates_iteration_over_accounts_and_propagates_it_all_way_up_and_out() {
         let transport = TestTransport::default();
@@ -1708,7 +3180,7 @@ mod tests {
         let recipient = Recorder::new().start().recipient();
         let consuming_wallet = make_paying_wallet(&b"consume, you greedy fool!"[..]);
         let accounts = vec![make_payable_account(5555), make_payable_account(6666)];
-        let agent = make_initialized_agent(123, consuming_wallet, U256::from(4));
+        let agent = make_initialized_agent(legacy_fees(123), consuming_wallet, U256::from(4));
 
         let result = subject.send_batch_of_payables(agent, &recipient, &accounts);
 
@@ -1737,7 +3209,8 @@ mod tests {
             9000,
             None,
         );
-        let agent = make_initialized_agent(123, incomplete_consuming_wallet, U256::from(1));
+        let agent =
+            make_initialized_agent(legacy_fees(123), incomplete_consuming_wallet, U256::from(1));
 
         let result = subject.send_batch_of_payables(agent, &recipient, &vec![account]);
 
@@ -1750,6 +3223,90 @@ mod tests {
         assert_eq!(accountant_recording.len(), 0)
     }
 
+    #[test]
+    fn sign_transaction_hands_the_transaction_to_the_transaction_signer_when_there_is_one() {
+        let sign_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let chain = Chain::PolyMainnet;
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            chain,
+        );
+        let signed_transaction = make_default_signed_transaction();
+        subject.set_transaction_signer(Box::new(
+            TransactionSignerMock::default()
+                .sign_transaction_params(&sign_transaction_params_arc)
+                .sign_transaction_result(Ok(signed_transaction.clone())),
+        ));
+        // No private key: the signer holds it
+        let consuming_wallet =
+            Wallet::from_str("0x3f69f9efd4f2592fd70be8c32ecd9dce71c472fc").unwrap();
+        let recipient = make_wallet("wallet1");
+        let fee_model = TransactionFeeModel::Eip1559 {
+            max_fee_per_gas_wei: 61_500_000_000,
+            max_priority_fee_per_gas_wei: 1_500_000_000,
+        };
+
+        let result = subject.sign_transaction(
+            &recipient,
+            &consuming_wallet,
+            1_000_000_000,
+            U256::from(5),
+            fee_model,
+        );
+
+        assert_eq!(result, Ok(signed_transaction));
+        let sign_transaction_params = sign_transaction_params_arc.lock().unwrap();
+        let data =
+            BlockchainInterfaceWeb3::<TestTransport>::transaction_data(&recipient, 1_000_000_000);
+        assert_eq!(
+            *sign_transaction_params,
+            vec![(
+                consuming_wallet.address(),
+                UnsignedTransaction::Eip1559(Eip1559TransactionParameters {
+                    chain_id: chain.rec().num_chain_id,
+                    nonce: U256::from(5),
+                    max_priority_fee_per_gas: U256::from(1_500_000_000),
+                    max_fee_per_gas: U256::from(61_500_000_000u64),
+                    gas: subject.compute_gas_limit(data.as_slice()),
+                    to: chain.rec().contract,
+                    value: U256::zero(),
+                    data: Bytes(data.to_vec()),
+                })
+            )]
+        );
+    }
+
+    #[test]
+    fn sign_transaction_reports_failure_of_the_transaction_signer() {
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            TEST_DEFAULT_CHAIN,
+        );
+        subject.set_transaction_signer(Box::new(
+            TransactionSignerMock::default()
+                .sign_transaction_result(Err("External signer failed: Unreachable".to_string())),
+        ));
+        let consuming_wallet =
+            Wallet::from_str("0x3f69f9efd4f2592fd70be8c32ecd9dce71c472fc").unwrap();
+
+        let result = subject.sign_transaction(
+            &make_wallet("wallet1"),
+            &consuming_wallet,
+            1_000_000_000,
+            U256::from(5),
+            legacy_fees(123),
+        );
+
+        assert_eq!(
+            result,
+            Err(PayableTransactionError::Signing(
+                "External signer failed: Unreachable".to_string()
+            ))
+        );
+    }
+
     #[test]
     fn send_batch_of_payables_fails_on_sending() {
         let transport = TestTransport::default();
@@ -1772,7 +3329,7 @@ mod tests {
             None,
         );
         let consuming_wallet = make_paying_wallet(consuming_wallet_secret_raw_bytes);
-        let agent = make_initialized_agent(120, consuming_wallet, U256::from(6));
+        let agent = make_initialized_agent(legacy_fees(120), consuming_wallet, U256::from(6));
 
         let result = subject.send_batch_of_payables(agent, &unimportant_recipient, &vec![account]);
 
@@ -1785,6 +3342,366 @@ mod tests {
         );
     }
 
+    #[test]
+    fn bumped_fee_model_raises_legacy_gas_price_by_ten_percent_rounded_up() {
+        let result = BlockchainInterfaceWeb3::<TestTransport>::bumped_fee_model(
+            TransactionFeeModel::Legacy {
+                gas_price_wei: 1_000_000_001,
+            },
+            legacy_fees(1),
+        );
+
+        assert_eq!(
+            result,
+            TransactionFeeModel::Legacy {
+                gas_price_wei: 1_100_000_002
+            }
+        )
+    }
+
+    #[test]
+    fn bumped_fee_model_does_not_go_below_the_market() {
+        let result = BlockchainInterfaceWeb3::<TestTransport>::bumped_fee_model(
+            TransactionFeeModel::Eip1559 {
+                max_fee_per_gas_wei: 40_000_000_000,
+                max_priority_fee_per_gas_wei: 2_000_000_000,
+            },
+            TransactionFeeModel::Eip1559 {
+                max_fee_per_gas_wei: 61_000_000_000,
+                max_priority_fee_per_gas_wei: 1_000_000_000,
+            },
+        );
+
+        assert_eq!(
+            result,
+            TransactionFeeModel::Eip1559 {
+                max_fee_per_gas_wei: 61_000_000_000,
+                max_priority_fee_per_gas_wei: 2_200_000_000
+            }
+        )
+    }
+
+    #[test]
+    fn bumped_fee_model_keeps_eip1559_transaction_even_if_the_market_fell_back_to_legacy() {
+        let result = BlockchainInterfaceWeb3::<TestTransport>::bumped_fee_model(
+            TransactionFeeModel::Eip1559 {
+                max_fee_per_gas_wei: 40_000_000_000,
+                max_priority_fee_per_gas_wei: 2_000_000_000,
+            },
+            legacy_fees(50),
+        );
+
+        assert_eq!(
+            result,
+            TransactionFeeModel::Eip1559 {
+                max_fee_per_gas_wei: 50_000_000_000,
+                max_priority_fee_per_gas_wei: 2_200_000_000
+            }
+        )
+    }
+
+    #[test]
+    fn replace_stuck_payables_resends_them_with_bumped_fees_and_the_same_nonce() {
+        init_test_logging();
+        let test_name = "replace_stuck_payables_resends_them_with_bumped_fees_and_the_same_nonce";
+        let sign_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let sign_eip1559_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let append_transaction_to_batch_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject =
+            make_subject_with_fee_history(Ok(make_fee_history(&[30], &[&[1_000_000_000]])));
+        subject.logger = Logger::new(test_name);
+        let mut eip1559_signed_transaction = make_default_signed_transaction();
+        eip1559_signed_transaction.transaction_hash = make_tx_hash(0xaaa);
+        eip1559_signed_transaction.raw_transaction = Bytes(vec![1, 2, 3]);
+        let mut legacy_signed_transaction = make_default_signed_transaction();
+        legacy_signed_transaction.transaction_hash = make_tx_hash(0xbbb);
+        legacy_signed_transaction.raw_transaction = Bytes(vec![4, 5, 6]);
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_eip1559_transaction_params(&sign_eip1559_transaction_params_arc)
+            .sign_eip1559_transaction_result(eip1559_signed_transaction)
+            .sign_transaction_params(&sign_transaction_params_arc)
+            .sign_transaction_result(Ok(legacy_signed_transaction))
+            .append_transaction_to_batch_params(&append_transaction_to_batch_params_arc)
+            .submit_batch_result(Ok(vec![
+                Ok(json!("...hash...")),
+                Err(web3::Error::Rpc(RPCError {
+                    code: ErrorCode::ServerError(-32000),
+                    message: "replacement transaction underpriced".to_string(),
+                    data: None,
+                })),
+            ]));
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        let persistent_config = PersistentConfigurationMock::new().gas_price_result(Ok(100));
+        let consuming_wallet = make_paying_wallet(b"consuming");
+        let eip1559_fingerprint = make_stuck_fingerprint(
+            1,
+            4,
+            TransactionFeeModel::Eip1559 {
+                max_fee_per_gas_wei: 70_000_000_000,
+                max_priority_fee_per_gas_wei: 2_000_000_000,
+            },
+        );
+        let legacy_fingerprint = make_stuck_fingerprint(2, 5, legacy_fees(50));
+        let too_expensive_fingerprint = make_stuck_fingerprint(3, 6, legacy_fees(95));
+        let mut fingerprint_without_resubmission_data =
+            make_stuck_fingerprint(4, 7, legacy_fees(1));
+        fingerprint_without_resubmission_data.resubmission_data_opt = None;
+
+        let result = subject.replace_stuck_payables(
+            &consuming_wallet,
+            &persistent_config,
+            &[
+                eip1559_fingerprint.clone(),
+                legacy_fingerprint.clone(),
+                too_expensive_fingerprint.clone(),
+                fingerprint_without_resubmission_data,
+            ],
+        );
+
+        assert_eq!(
+            result,
+            Ok(vec![PendingPayableReplacement {
+                rowid: 1,
+                hash: make_tx_hash(0xaaa),
+                replaced_hashes: vec![make_tx_hash(0x100), eip1559_fingerprint.hash],
+                fee_model: TransactionFeeModel::Eip1559 {
+                    max_fee_per_gas_wei: 77_000_000_000,
+                    max_priority_fee_per_gas_wei: 2_200_000_000
+                }
+            }])
+        );
+        let sign_eip1559_transaction_params = sign_eip1559_transaction_params_arc.lock().unwrap();
+        let (eip1559_params, _) = &sign_eip1559_transaction_params[0];
+        assert_eq!(eip1559_params.nonce, U256::from(4));
+        assert_eq!(
+            eip1559_params.max_fee_per_gas,
+            U256::from(77_000_000_000_u64)
+        );
+        assert_eq!(
+            eip1559_params.max_priority_fee_per_gas,
+            U256::from(2_200_000_000_u64)
+        );
+        assert_eq!(
+            eip1559_params.data,
+            Bytes(
+                BlockchainInterfaceWeb3::<TestTransport>::transaction_data(
+                    &make_wallet("recipient_4"),
+                    eip1559_fingerprint.amount
+                )
+                .to_vec()
+            )
+        );
+        let sign_transaction_params = sign_transaction_params_arc.lock().unwrap();
+        let (legacy_params, _, _) = &sign_transaction_params[0];
+        assert_eq!(legacy_params.nonce, Some(U256::from(5)));
+        assert_eq!(
+            legacy_params.gas_price,
+            Some(U256::from(61_000_000_000_u64))
+        );
+        assert_eq!(sign_transaction_params.len(), 1);
+        let append_transaction_to_batch_params =
+            append_transaction_to_batch_params_arc.lock().unwrap();
+        let appended_transactions = append_transaction_to_batch_params
+            .iter()
+            .map(|(bytes, _)| bytes.clone())
+            .collect::<Vec<Bytes>>();
+        assert_eq!(
+            appended_transactions,
+            vec![Bytes(vec![1, 2, 3]), Bytes(vec![4, 5, 6])]
+        );
+        let log_handler = TestLogHandler::new();
+        log_handler.exists_log_containing(&format!(
+            "WARN: {test_name}: Cannot replace stuck transaction {:?} as its fees would have to \
+            exceed the ceiling of 100 gwei; consider raising gas-price",
+            too_expensive_fingerprint.hash
+        ));
+        log_handler.exists_log_containing(&format!(
+            "WARN: {test_name}: Replacement {:?} of stuck transaction {:?} was rejected: RPC error",
+            make_tx_hash(0xbbb),
+            legacy_fingerprint.hash
+        ));
+    }
+
+    #[test]
+    fn replace_stuck_payables_sends_nothing_if_no_payable_can_be_replaced() {
+        // The mock would panic at an unexpected submission of the batch
+        let mut subject = make_subject_with_fee_history(Err(BlockchainError::InvalidResponse));
+        subject.batch_payable_tools = Box::new(BatchPayableToolsMock::<TestTransport>::default());
+        let persistent_config = PersistentConfigurationMock::new().gas_price_result(Ok(100));
+
+        let result = subject.replace_stuck_payables(
+            &make_paying_wallet(b"consuming"),
+            &persistent_config,
+            &[make_stuck_fingerprint(1, 4, legacy_fees(100))],
+        );
+
+        assert_eq!(result, Ok(vec![]))
+    }
+
+    #[test]
+    fn replace_stuck_payables_fails_on_gas_price_query() {
+        let subject = make_subject_with_fee_history(Err(BlockchainError::InvalidResponse));
+        let persistent_config = PersistentConfigurationMock::new()
+            .gas_price_result(Err(PersistentConfigError::TransactionError));
+
+        let result = subject.replace_stuck_payables(
+            &make_paying_wallet(b"consuming"),
+            &persistent_config,
+            &[make_stuck_fingerprint(1, 4, legacy_fees(10))],
+        );
+
+        assert_eq!(
+            result,
+            Err(PayableTransactionError::GasPriceQueryFailed(
+                "TransactionError".to_string()
+            ))
+        )
+    }
+
+    #[test]
+    fn replace_stuck_payables_fails_on_sending() {
+        let mut subject = make_subject_with_fee_history(Err(BlockchainError::InvalidResponse));
+        let mut signed_transaction = make_default_signed_transaction();
+        signed_transaction.transaction_hash = make_tx_hash(0xccc);
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_transaction_result(Ok(signed_transaction))
+            .submit_batch_result(Err(Web3Error::Transport("Transaction crashed".to_string())));
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        let persistent_config = PersistentConfigurationMock::new().gas_price_result(Ok(100));
+
+        let result = subject.replace_stuck_payables(
+            &make_paying_wallet(b"consuming"),
+            &persistent_config,
+            &[make_stuck_fingerprint(1, 4, legacy_fees(10))],
+        );
+
+        assert_eq!(
+            result,
+            Err(PayableTransactionError::Sending {
+                msg: "Transport error: Transaction crashed".to_string(),
+                hashes: vec![make_tx_hash(0xccc)]
+            })
+        )
+    }
+
+    #[test]
+    fn replace_stuck_payables_replaces_a_multisend_transaction_as_a_whole() {
+        let sign_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = make_subject_with_fee_history(Err(BlockchainError::InvalidResponse));
+        let multisend_contract = make_wallet("multisend").address();
+        subject.set_multisend_contract(multisend_contract, DEFAULT_MULTISEND_MAX_CREDITORS);
+        let mut multisend_signed_transaction = make_default_signed_transaction();
+        multisend_signed_transaction.transaction_hash = make_tx_hash(0xaaa);
+        let mut plain_signed_transaction = make_default_signed_transaction();
+        plain_signed_transaction.transaction_hash = make_tx_hash(0xbbb);
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_transaction_params(&sign_transaction_params_arc)
+            .sign_transaction_result(Ok(multisend_signed_transaction))
+            .sign_transaction_result(Ok(plain_signed_transaction))
+            .submit_batch_result(Ok(vec![Ok(json!("...hash...")), Ok(json!("...hash..."))]));
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        let persistent_config = PersistentConfigurationMock::new().gas_price_result(Ok(100));
+        let first_multisend_fingerprint = make_stuck_fingerprint(1, 4, legacy_fees(10));
+        let mut second_multisend_fingerprint = make_stuck_fingerprint(2, 5, legacy_fees(10));
+        second_multisend_fingerprint.hash = first_multisend_fingerprint.hash;
+        second_multisend_fingerprint
+            .resubmission_data_opt
+            .as_mut()
+            .unwrap()
+            .nonce = 4;
+        let plain_fingerprint = make_stuck_fingerprint(3, 6, legacy_fees(10));
+
+        let result = subject.replace_stuck_payables(
+            &make_paying_wallet(b"consuming"),
+            &persistent_config,
+            &[
+                first_multisend_fingerprint.clone(),
+                plain_fingerprint.clone(),
+                second_multisend_fingerprint.clone(),
+            ],
+        );
+
+        let replacement = |rowid: u64, hash: H256, replaced_hash: H256| PendingPayableReplacement {
+            rowid,
+            hash,
+            replaced_hashes: vec![make_tx_hash(0x100), replaced_hash],
+            fee_model: legacy_fees(100),
+        };
+        assert_eq!(
+            result,
+            Ok(vec![
+                replacement(1, make_tx_hash(0xaaa), first_multisend_fingerprint.hash),
+                replacement(2, make_tx_hash(0xaaa), first_multisend_fingerprint.hash),
+                replacement(3, make_tx_hash(0xbbb), plain_fingerprint.hash),
+            ])
+        );
+        let sign_transaction_params = sign_transaction_params_arc.lock().unwrap();
+        let (multisend_params, _, _) = &sign_transaction_params[0];
+        assert_eq!(multisend_params.to, Some(multisend_contract));
+        assert_eq!(multisend_params.nonce, Some(U256::from(4)));
+        assert_eq!(
+            multisend_params.data,
+            Bytes(subject.multisend_transaction_data(&[
+                (
+                    make_wallet("recipient_4").address(),
+                    first_multisend_fingerprint.amount
+                ),
+                (
+                    make_wallet("recipient_5").address(),
+                    second_multisend_fingerprint.amount
+                )
+            ]))
+        );
+        let (plain_params, _, _) = &sign_transaction_params[1];
+        assert_eq!(plain_params.to, Some(subject.contract_address()));
+        assert_eq!(plain_params.nonce, Some(U256::from(6)));
+        assert_eq!(sign_transaction_params.len(), 2);
+    }
+
+    #[test]
+    fn replace_stuck_payables_cannot_replace_a_multisend_transaction_without_multisend_contract() {
+        init_test_logging();
+        let test_name =
+            "replace_stuck_payables_cannot_replace_a_multisend_transaction_without_multisend_contract";
+        // The mock would panic at an unexpected submission of the batch
+        let mut subject = make_subject_with_fee_history(Err(BlockchainError::InvalidResponse));
+        subject.logger = Logger::new(test_name);
+        subject.batch_payable_tools = Box::new(BatchPayableToolsMock::<TestTransport>::default());
+        let persistent_config = PersistentConfigurationMock::new().gas_price_result(Ok(100));
+        let first_fingerprint = make_stuck_fingerprint(1, 4, legacy_fees(10));
+        let mut second_fingerprint = make_stuck_fingerprint(2, 4, legacy_fees(10));
+        second_fingerprint.hash = first_fingerprint.hash;
+
+        let result = subject.replace_stuck_payables(
+            &make_paying_wallet(b"consuming"),
+            &persistent_config,
+            &[first_fingerprint.clone(), second_fingerprint],
+        );
+
+        assert_eq!(result, Ok(vec![]));
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Cannot replace stuck transaction {:?} paying 2 creditors as no \
+            multisend-contract is configured",
+            first_fingerprint.hash
+        ));
+    }
+
+    fn make_stuck_fingerprint(
+        rowid: u64,
+        nonce: u64,
+        fee_model: TransactionFeeModel,
+    ) -> PendingPayableFingerprint {
+        let mut fingerprint = make_pending_payable_fingerprint();
+        fingerprint.rowid = rowid;
+        fingerprint.hash = make_tx_hash(0x200 + rowid as u32);
+        fingerprint.replaced_hashes = vec![make_tx_hash(0x100)];
+        fingerprint.resubmission_data_opt = Some(ResubmissionData {
+            fee_model,
+            ..make_resubmission_data(nonce)
+        });
+        fingerprint
+    }
+
     #[test]
     fn sign_transaction_fails_on_signing_itself() {
         let transport = TestTransport::default();
@@ -1799,11 +3716,11 @@ mod tests {
         subject.batch_payable_tools = Box::new(batch_payable_tools);
         let recipient = make_wallet("unlucky man");
         let consuming_wallet = make_paying_wallet(consuming_wallet_secret_raw_bytes);
-        let gas_price = 123;
+        let fee_model = legacy_fees(123);
         let nonce = U256::from(1);
 
         let result =
-            subject.sign_transaction(&recipient, &consuming_wallet, 444444, nonce, gas_price);
+            subject.sign_transaction(&recipient, &consuming_wallet, 444444, nonce, fee_model);
 
         assert_eq!(
             result,
@@ -1839,9 +3756,11 @@ mod tests {
         let consuming_wallet = test_consuming_wallet_with_secret();
         let recipient_wallet = test_recipient_wallet();
         let nonce_correct_type = U256::from(nonce);
-        let gas_price = match chain {
-            Chain::EthMainnet | Chain::EthRopsten | Chain::Dev => 110,
-            Chain::PolyMainnet | Chain::PolyAmoy => 55,
+        let fee_model = match chain {
+            Chain::EthMainnet | Chain::EthRopsten | Chain::Dev | Chain::Custom(_) => {
+                legacy_fees(110)
+            }
+            Chain::PolyMainnet | Chain::PolyAmoy => legacy_fees(55),
         };
         let payment_size_wei = 1_000_000_000_000;
         let payable_account = make_payable_account_with_wallet_and_balance_and_timestamp_opt(
@@ -1856,7 +3775,7 @@ mod tests {
                 &consuming_wallet,
                 payable_account.balance_wei,
                 nonce_correct_type,
-                gas_price,
+                fee_model,
             )
             .unwrap();
 
@@ -2222,18 +4141,32 @@ mod tests {
     }
 
     fn make_initialized_agent(
-        gas_price_gwei: u64,
+        fee_model: TransactionFeeModel,
         consuming_wallet: Wallet,
         nonce: U256,
     ) -> Box<dyn BlockchainAgent> {
         Box::new(
             BlockchainAgentMock::default()
                 .consuming_wallet_result(consuming_wallet)
-                .agreed_fee_per_computation_unit_result(gas_price_gwei)
+                .agreed_transaction_fee_model_result(fee_model)
                 .pending_transaction_id_result(nonce),
         )
     }
 
+    fn legacy_fees(gas_price_gwei: u64) -> TransactionFeeModel {
+        TransactionFeeModel::Legacy {
+            gas_price_wei: gwei_to_wei(gas_price_gwei),
+        }
+    }
+
+    #[test]
+    fn hash_the_multisend_contract_function_signature() {
+        assert_eq!(
+            "disperseTokenSimple(address,address[],uint256[])".keccak256()[0..4],
+            MULTISEND_METHOD_ID,
+        );
+    }
+
     #[test]
     fn hash_the_smart_contract_transfer_function_signature() {
         assert_eq!(
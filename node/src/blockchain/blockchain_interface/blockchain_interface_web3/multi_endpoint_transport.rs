@@ -0,0 +1,943 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use futures::future::join_all;
+use futures::Future;
+use jsonrpc_core as rpc;
+use masq_lib::logger::Logger;
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+use web3::{BatchTransport, Error, RequestId, Transport};
+
+// Responses to these methods are only accepted if enough endpoints agree on them
+pub const QUORUM_METHODS: [&str; 2] = ["eth_getLogs", "eth_getTransactionReceipt"];
+pub const MAX_HEALTH_SCORE: i32 = 10;
+pub const MIN_HEALTH_SCORE: i32 = -10;
+pub const FAILURE_PENALTY: i32 = 5;
+// Only these fields of the answers are voted on, because they're the only ones the Node reads
+pub const LOG_QUORUM_FIELDS: [&str; 6] = [
+    "blockHash",
+    "blockNumber",
+    "data",
+    "removed",
+    "topics",
+    "transactionHash",
+];
+pub const RECEIPT_QUORUM_FIELDS: [&str; 2] = ["status", "transactionHash"];
+const QUANTITY_FIELDS: [&str; 2] = ["blockNumber", "status"];
+
+type BoxedFuture<I> = Box<dyn Future<Item = I, Error = Error>>;
+
+#[derive(Debug)]
+pub struct RpcEndpoint<T> {
+    pub url: String,
+    pub transport: T,
+}
+
+// Spreads the requests over several blockchain services. Endpoints are tried in the order of their
+// health score, which rises with every answer and drops sharply with every transport failure.
+// The first endpoint that answers wins, unless the request asks for data listed in QUORUM_METHODS;
+// such a request goes to all endpoints and at least 'quorum' of them must return the same data.
+// Errors don't count as votes.
+#[derive(Clone)]
+pub struct MultiEndpointTransport<T> {
+    endpoints: Rc<Vec<RpcEndpoint<T>>>,
+    health_scores: Rc<RefCell<Vec<i32>>>,
+    quorum: usize,
+    logger: Logger,
+}
+
+impl<T> Debug for MultiEndpointTransport<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let urls = self
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.url.as_str())
+            .collect::<Vec<_>>();
+        write!(
+            f,
+            "MultiEndpointTransport {{ urls: {:?}, health_scores: {:?}, quorum: {} }}",
+            urls,
+            self.health_scores.borrow(),
+            self.quorum
+        )
+    }
+}
+
+impl<T> Transport for MultiEndpointTransport<T>
+where
+    T: 'static + BatchTransport,
+{
+    type Out = BoxedFuture<rpc::Value>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        self.endpoints[0].transport.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+        if self.requires_quorum(&[&request]) {
+            self.send_with_quorum(id, request)
+        } else {
+            self.send_with_failover(self.endpoints_by_health(), move |transport: &T| {
+                transport.send(id, request.clone())
+            })
+        }
+    }
+}
+
+impl<T> BatchTransport for MultiEndpointTransport<T>
+where
+    T: 'static + BatchTransport,
+{
+    type Batch = BoxedFuture<Vec<Result<rpc::Value, Error>>>;
+
+    fn send_batch<I>(&self, requests: I) -> Self::Batch
+    where
+        I: IntoIterator<Item = (RequestId, rpc::Call)>,
+    {
+        let requests = requests.into_iter().collect::<Vec<_>>();
+        let calls = requests.iter().map(|(_, call)| call).collect::<Vec<_>>();
+        if self.requires_quorum(&calls) {
+            self.send_batch_with_quorum(requests)
+        } else {
+            self.send_with_failover(self.endpoints_by_health(), move |transport: &T| {
+                transport.send_batch(requests.clone())
+            })
+        }
+    }
+}
+
+impl<T> MultiEndpointTransport<T>
+where
+    T: 'static + BatchTransport,
+{
+    pub fn new(endpoints: Vec<RpcEndpoint<T>>, quorum: usize) -> Self {
+        if endpoints.is_empty() {
+            panic!("MultiEndpointTransport needs at least one endpoint")
+        }
+        let health_scores = vec![0; endpoints.len()];
+        Self {
+            endpoints: Rc::new(endpoints),
+            health_scores: Rc::new(RefCell::new(health_scores)),
+            quorum,
+            logger: Logger::new("MultiEndpointTransport"),
+        }
+    }
+
+    pub fn health_scores(&self) -> Vec<i32> {
+        self.health_scores.borrow().clone()
+    }
+
+    fn requires_quorum(&self, calls: &[&rpc::Call]) -> bool {
+        self.quorum > 1
+            && calls
+                .iter()
+                .any(|call| QUORUM_METHODS.contains(&Self::method_of(call)))
+    }
+
+    fn method_of(call: &rpc::Call) -> &str {
+        match call {
+            rpc::Call::MethodCall(method_call) => &method_call.method,
+            rpc::Call::Notification(notification) => &notification.method,
+            rpc::Call::Invalid { .. } => "",
+        }
+    }
+
+    fn endpoints_by_health(&self) -> VecDeque<usize> {
+        let health_scores = self.health_scores.borrow();
+        let mut order = (0..health_scores.len()).collect::<Vec<usize>>();
+        // The sort is stable, so endpoints equally healthy keep the configured order
+        order.sort_by_key(|idx| Reverse(health_scores[*idx]));
+        order.into()
+    }
+
+    fn send_with_failover<R, F, O>(&self, mut order: VecDeque<usize>, request: F) -> BoxedFuture<R>
+    where
+        R: 'static,
+        F: Fn(&T) -> O + 'static,
+        O: Future<Item = R, Error = Error> + 'static,
+    {
+        let idx = order.pop_front().expect("Failover with no endpoint to try");
+        let future = request(&self.endpoints[idx].transport);
+        let this = self.clone();
+        Box::new(future.then(move |result| -> BoxedFuture<R> {
+            match result {
+                Err(e) if Self::is_transport_failure(&e) => {
+                    this.record_failure(idx);
+                    match order.front() {
+                        Some(next_idx) => {
+                            warning!(
+                                this.logger,
+                                "Blockchain service at {} failed: {}; trying {} instead",
+                                this.endpoints[idx].url,
+                                e,
+                                this.endpoints[*next_idx].url
+                            );
+                            this.send_with_failover(order, request)
+                        }
+                        None => Box::new(futures::failed(e)),
+                    }
+                }
+                result => {
+                    this.record_success(idx);
+                    Box::new(futures::done(result))
+                }
+            }
+        }))
+    }
+
+    fn send_with_quorum(&self, id: RequestId, request: rpc::Call) -> BoxedFuture<rpc::Value> {
+        let method = Self::method_of(&request).to_string();
+        let responses = self
+            .endpoints
+            .iter()
+            .enumerate()
+            .map(|(idx, endpoint)| {
+                endpoint
+                    .transport
+                    .send(id, request.clone())
+                    .then(move |result| Ok::<_, Error>((idx, result)))
+            })
+            .collect::<Vec<_>>();
+        let this = self.clone();
+        Box::new(join_all(responses).and_then(move |responses| {
+            responses.iter().for_each(|(idx, result)| match result {
+                Err(e) if Self::is_transport_failure(e) => this.record_failure(*idx),
+                _ => this.record_success(*idx),
+            });
+            this.agreed_response(&method, responses)
+        }))
+    }
+
+    fn send_batch_with_quorum(
+        &self,
+        requests: Vec<(RequestId, rpc::Call)>,
+    ) -> BoxedFuture<Vec<Result<rpc::Value, Error>>> {
+        let methods = requests
+            .iter()
+            .map(|(_, call)| Self::method_of(call).to_string())
+            .collect::<Vec<_>>();
+        let responses = self
+            .endpoints
+            .iter()
+            .enumerate()
+            .map(|(idx, endpoint)| {
+                endpoint
+                    .transport
+                    .send_batch(requests.clone())
+                    .then(move |result| Ok::<_, Error>((idx, result)))
+            })
+            .collect::<Vec<_>>();
+        let this = self.clone();
+        Box::new(join_all(responses).and_then(move |responses| {
+            let mut first_error_opt = None;
+            let mut answers = vec![];
+            responses
+                .into_iter()
+                .for_each(|(idx, result)| match result {
+                    Ok(items) => {
+                        this.record_success(idx);
+                        answers.push((idx, items))
+                    }
+                    Err(e) => {
+                        this.record_failure(idx);
+                        first_error_opt.get_or_insert(e);
+                    }
+                });
+            if answers.is_empty() {
+                return Err(first_error_opt.expect("No endpoint produced any result"));
+            }
+            Ok(this.agreed_batch_response(&methods, answers))
+        }))
+    }
+
+    fn agreed_batch_response(
+        &self,
+        methods: &[String],
+        mut answers: Vec<(usize, Vec<Result<rpc::Value, Error>>)>,
+    ) -> Vec<Result<rpc::Value, Error>> {
+        let order = self.endpoints_by_health();
+        answers.sort_by_key(|(idx, _)| order.iter().position(|ordered| ordered == idx));
+        methods
+            .iter()
+            .enumerate()
+            .map(|(position, method)| {
+                let item_responses = answers
+                    .iter()
+                    .map(|(idx, items)| {
+                        let item = items.get(position).cloned().unwrap_or_else(|| {
+                            Err(Error::InvalidResponse(format!(
+                                "Batch response from {} is missing item {}",
+                                self.endpoints[*idx].url, position
+                            )))
+                        });
+                        (*idx, item)
+                    })
+                    .collect::<Vec<_>>();
+                if QUORUM_METHODS.contains(&method.as_str()) {
+                    self.agreed_response(method, item_responses)
+                } else {
+                    item_responses
+                        .into_iter()
+                        .next()
+                        .expect("Batch answers disappeared")
+                        .1
+                }
+            })
+            .collect()
+    }
+
+    fn agreed_response(
+        &self,
+        method: &str,
+        responses: Vec<(usize, Result<rpc::Value, Error>)>,
+    ) -> Result<rpc::Value, Error> {
+        let votes = responses
+            .iter()
+            .filter_map(|(idx, result)| {
+                result
+                    .as_ref()
+                    .ok()
+                    .map(|value| (*idx, value, Self::comparable(method, value)))
+            })
+            .collect::<Vec<_>>();
+        let mut tally: Vec<(&rpc::Value, usize)> = vec![];
+        votes.iter().for_each(|(_, _, comparable)| {
+            match tally.iter_mut().find(|(tallied, _)| *tallied == comparable) {
+                Some((_, count)) => *count += 1,
+                None => tally.push((comparable, 1)),
+            }
+        });
+        match tally.iter().find(|(_, count)| *count >= self.quorum) {
+            Some((agreed, _)) => {
+                votes
+                    .iter()
+                    .filter(|(_, _, comparable)| comparable != *agreed)
+                    .for_each(|(idx, _, _)| {
+                        warning!(
+                            self.logger,
+                            "Blockchain service at {} disagrees with the quorum on {}",
+                            self.endpoints[*idx].url,
+                            method
+                        );
+                        self.record_failure(*idx)
+                    });
+                let (_, value, _) = votes
+                    .iter()
+                    .find(|(_, _, comparable)| comparable == *agreed)
+                    .expect("Agreed answer disappeared");
+                Ok((*value).clone())
+            }
+            None if votes.is_empty() => Err(responses
+                .into_iter()
+                .find_map(|(_, result)| result.err())
+                .expect("No blockchain service produced any result")),
+            None => {
+                let best = tally.iter().map(|(_, count)| *count).max().unwrap_or(0);
+                Err(Error::Transport(format!(
+                    "Only {} of {} blockchain services agreed on {}; {} required",
+                    best,
+                    self.endpoints.len(),
+                    method,
+                    self.quorum
+                )))
+            }
+        }
+    }
+
+    // Reduces an answer to the fields the Node reads, spelled uniformly, so that services that add
+    // extra fields or write hex in another case or with leading zeros still agree
+    fn comparable(method: &str, value: &rpc::Value) -> rpc::Value {
+        let fields: &[&str] = match method {
+            "eth_getLogs" => &LOG_QUORUM_FIELDS,
+            "eth_getTransactionReceipt" => &RECEIPT_QUORUM_FIELDS,
+            _ => return value.clone(),
+        };
+        match value {
+            rpc::Value::Array(items) => rpc::Value::Array(
+                items
+                    .iter()
+                    .map(|item| Self::comparable_object(fields, item))
+                    .collect(),
+            ),
+            _ => Self::comparable_object(fields, value),
+        }
+    }
+
+    fn comparable_object(fields: &[&str], value: &rpc::Value) -> rpc::Value {
+        match value {
+            rpc::Value::Object(object) => rpc::Value::Object(
+                fields
+                    .iter()
+                    .map(|field| {
+                        (
+                            field.to_string(),
+                            Self::normalized(field, object.get(*field)),
+                        )
+                    })
+                    .collect(),
+            ),
+            _ => value.clone(),
+        }
+    }
+
+    fn normalized(field: &str, value_opt: Option<&rpc::Value>) -> rpc::Value {
+        match value_opt {
+            None | Some(rpc::Value::Null) if field == "removed" => rpc::Value::Bool(false),
+            None => rpc::Value::Null,
+            Some(rpc::Value::String(string)) => {
+                let lowercase = string.to_lowercase();
+                match lowercase.strip_prefix("0x") {
+                    Some(digits) if QUANTITY_FIELDS.contains(&field) => {
+                        match digits.trim_start_matches('0') {
+                            "" => rpc::Value::String("0x0".to_string()),
+                            significant => rpc::Value::String(format!("0x{}", significant)),
+                        }
+                    }
+                    _ => rpc::Value::String(lowercase),
+                }
+            }
+            Some(rpc::Value::Array(items)) => rpc::Value::Array(
+                items
+                    .iter()
+                    .map(|item| Self::normalized(field, Some(item)))
+                    .collect(),
+            ),
+            Some(other) => other.clone(),
+        }
+    }
+
+    fn is_transport_failure(error: &Error) -> bool {
+        // An RPC error means the service is alive and has answered; asking elsewhere won't help
+        !matches!(error, Error::Rpc(_))
+    }
+
+    fn record_success(&self, idx: usize) {
+        let mut health_scores = self.health_scores.borrow_mut();
+        health_scores[idx] = (health_scores[idx] + 1).min(MAX_HEALTH_SCORE)
+    }
+
+    fn record_failure(&self, idx: usize) {
+        let mut health_scores = self.health_scores.borrow_mut();
+        health_scores[idx] = (health_scores[idx] - FAILURE_PENALTY).max(MIN_HEALTH_SCORE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    type BatchResult = Result<Vec<Result<rpc::Value, Error>>, Error>;
+    type BatchParams = Arc<Mutex<Vec<Vec<(RequestId, rpc::Call)>>>>;
+
+    #[derive(Debug, Default, Clone)]
+    struct EndpointTransportMock {
+        send_params: Arc<Mutex<Vec<(RequestId, rpc::Call)>>>,
+        send_results: Rc<RefCell<VecDeque<Result<rpc::Value, Error>>>>,
+        send_batch_params: BatchParams,
+        send_batch_results: Rc<RefCell<VecDeque<BatchResult>>>,
+    }
+
+    impl Transport for EndpointTransportMock {
+        type Out = BoxedFuture<rpc::Value>;
+
+        fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+            (7, web3::helpers::build_request(7, method, params))
+        }
+
+        fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+            self.send_params.lock().unwrap().push((id, request));
+            Box::new(futures::done(
+                self.send_results.borrow_mut().pop_front().unwrap(),
+            ))
+        }
+    }
+
+    impl BatchTransport for EndpointTransportMock {
+        type Batch = BoxedFuture<Vec<Result<rpc::Value, Error>>>;
+
+        fn send_batch<I>(&self, requests: I) -> Self::Batch
+        where
+            I: IntoIterator<Item = (RequestId, rpc::Call)>,
+        {
+            self.send_batch_params
+                .lock()
+                .unwrap()
+                .push(requests.into_iter().collect());
+            Box::new(futures::done(
+                self.send_batch_results.borrow_mut().pop_front().unwrap(),
+            ))
+        }
+    }
+
+    impl EndpointTransportMock {
+        fn send_params(mut self, params: &Arc<Mutex<Vec<(RequestId, rpc::Call)>>>) -> Self {
+            self.send_params = params.clone();
+            self
+        }
+
+        fn send_result(self, result: Result<rpc::Value, Error>) -> Self {
+            self.send_results.borrow_mut().push_back(result);
+            self
+        }
+
+        fn send_batch_params(mut self, params: &BatchParams) -> Self {
+            self.send_batch_params = params.clone();
+            self
+        }
+
+        fn send_batch_result(self, result: BatchResult) -> Self {
+            self.send_batch_results.borrow_mut().push_back(result);
+            self
+        }
+    }
+
+    fn make_subject(
+        transports: Vec<EndpointTransportMock>,
+        quorum: usize,
+    ) -> MultiEndpointTransport<EndpointTransportMock> {
+        let endpoints = transports
+            .into_iter()
+            .enumerate()
+            .map(|(idx, transport)| RpcEndpoint {
+                url: format!("http://endpoint-{}", idx),
+                transport,
+            })
+            .collect();
+        MultiEndpointTransport::new(endpoints, quorum)
+    }
+
+    fn call(method: &str) -> rpc::Call {
+        web3::helpers::build_request(1, method, vec![])
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(QUORUM_METHODS, ["eth_getLogs", "eth_getTransactionReceipt"]);
+        assert_eq!(MAX_HEALTH_SCORE, 10);
+        assert_eq!(MIN_HEALTH_SCORE, -10);
+        assert_eq!(FAILURE_PENALTY, 5);
+        assert_eq!(
+            LOG_QUORUM_FIELDS,
+            [
+                "blockHash",
+                "blockNumber",
+                "data",
+                "removed",
+                "topics",
+                "transactionHash"
+            ]
+        );
+        assert_eq!(RECEIPT_QUORUM_FIELDS, ["status", "transactionHash"]);
+        assert_eq!(QUANTITY_FIELDS, ["blockNumber", "status"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "MultiEndpointTransport needs at least one endpoint")]
+    fn new_panics_without_endpoints() {
+        let _ = MultiEndpointTransport::<EndpointTransportMock>::new(vec![], 1);
+    }
+
+    #[test]
+    fn send_uses_the_first_endpoint_while_it_is_healthy() {
+        let send_params_0 = Arc::new(Mutex::new(vec![]));
+        let send_params_1 = Arc::new(Mutex::new(vec![]));
+        let subject = make_subject(
+            vec![
+                EndpointTransportMock::default()
+                    .send_params(&send_params_0)
+                    .send_result(Ok(json!("0x1"))),
+                EndpointTransportMock::default().send_params(&send_params_1),
+            ],
+            1,
+        );
+
+        let result = subject.send(1, call("eth_blockNumber")).wait();
+
+        assert_eq!(result, Ok(json!("0x1")));
+        assert_eq!(
+            *send_params_0.lock().unwrap(),
+            vec![(1, call("eth_blockNumber"))]
+        );
+        assert!(send_params_1.lock().unwrap().is_empty());
+        assert_eq!(subject.health_scores(), vec![1, 0]);
+    }
+
+    #[test]
+    fn send_fails_over_to_the_next_endpoint_and_demotes_the_failing_one() {
+        init_test_logging();
+        let send_params_0 = Arc::new(Mutex::new(vec![]));
+        let send_params_1 = Arc::new(Mutex::new(vec![]));
+        let subject = make_subject(
+            vec![
+                EndpointTransportMock::default()
+                    .send_params(&send_params_0)
+                    .send_result(Err(Error::Transport(
+                        "Unexpected response status code: 429".to_string(),
+                    ))),
+                EndpointTransportMock::default()
+                    .send_params(&send_params_1)
+                    .send_result(Ok(json!("0x2")))
+                    .send_result(Ok(json!("0x3"))),
+            ],
+            1,
+        );
+
+        let first_result = subject.send(1, call("eth_blockNumber")).wait();
+        let second_result = subject.send(2, call("eth_blockNumber")).wait();
+
+        assert_eq!(first_result, Ok(json!("0x2")));
+        assert_eq!(second_result, Ok(json!("0x3")));
+        assert_eq!(send_params_0.lock().unwrap().len(), 1);
+        assert_eq!(send_params_1.lock().unwrap().len(), 2);
+        assert_eq!(subject.health_scores(), vec![-5, 2]);
+        TestLogHandler::new().exists_log_containing(
+            "WARN: MultiEndpointTransport: Blockchain service at http://endpoint-0 failed: \
+            Transport error: Unexpected response status code: 429; trying http://endpoint-1 instead",
+        );
+    }
+
+    #[test]
+    fn send_does_not_fail_over_on_rpc_error() {
+        let send_params_1 = Arc::new(Mutex::new(vec![]));
+        let rpc_error = Error::Rpc(rpc::Error::invalid_params("nonce too low"));
+        let subject = make_subject(
+            vec![
+                EndpointTransportMock::default().send_result(Err(rpc_error.clone())),
+                EndpointTransportMock::default().send_params(&send_params_1),
+            ],
+            1,
+        );
+
+        let result = subject.send(1, call("eth_sendRawTransaction")).wait();
+
+        assert_eq!(result, Err(rpc_error));
+        assert!(send_params_1.lock().unwrap().is_empty());
+        assert_eq!(subject.health_scores(), vec![1, 0]);
+    }
+
+    #[test]
+    fn send_returns_the_last_error_if_all_endpoints_fail() {
+        let subject = make_subject(
+            vec![
+                EndpointTransportMock::default().send_result(Err(Error::Unreachable)),
+                EndpointTransportMock::default()
+                    .send_result(Err(Error::Transport("timeout".to_string()))),
+            ],
+            1,
+        );
+
+        let result = subject.send(1, call("eth_blockNumber")).wait();
+
+        assert_eq!(result, Err(Error::Transport("timeout".to_string())));
+        assert_eq!(subject.health_scores(), vec![-5, -5]);
+    }
+
+    #[test]
+    fn health_scores_stay_within_limits() {
+        let mut failing = EndpointTransportMock::default();
+        let mut healthy = EndpointTransportMock::default();
+        for _ in 0..12 {
+            failing = failing.send_result(Err(Error::Unreachable));
+            healthy = healthy.send_result(Ok(json!("0x1")));
+        }
+        let failing_subject = make_subject(vec![failing], 1);
+        let healthy_subject = make_subject(vec![healthy], 1);
+
+        (0..12).for_each(|_| {
+            let _ = failing_subject.send(1, call("eth_blockNumber")).wait();
+            let _ = healthy_subject.send(1, call("eth_blockNumber")).wait();
+        });
+
+        assert_eq!(failing_subject.health_scores(), vec![MIN_HEALTH_SCORE]);
+        assert_eq!(healthy_subject.health_scores(), vec![MAX_HEALTH_SCORE]);
+    }
+
+    #[test]
+    fn send_of_quorum_method_accepts_response_agreed_by_enough_endpoints() {
+        init_test_logging();
+        let receipt = json!({"transactionHash": "0xabc", "status": "0x1"});
+        let subject = make_subject(
+            vec![
+                EndpointTransportMock::default().send_result(Ok(receipt.clone())),
+                EndpointTransportMock::default().send_result(Ok(json!(null))),
+                EndpointTransportMock::default().send_result(Ok(receipt.clone())),
+            ],
+            2,
+        );
+
+        let result = subject.send(1, call("eth_getTransactionReceipt")).wait();
+
+        assert_eq!(result, Ok(receipt));
+        assert_eq!(subject.health_scores(), vec![1, -4, 1]);
+        TestLogHandler::new().exists_log_containing(
+            "WARN: MultiEndpointTransport: Blockchain service at http://endpoint-1 \
+            disagrees with the quorum on eth_getTransactionReceipt",
+        );
+    }
+
+    #[test]
+    fn send_of_quorum_method_fails_without_agreement() {
+        let subject = make_subject(
+            vec![
+                EndpointTransportMock::default().send_result(Ok(json!("0x1"))),
+                EndpointTransportMock::default().send_result(Err(Error::Unreachable)),
+                EndpointTransportMock::default().send_result(Ok(json!("0x2"))),
+            ],
+            2,
+        );
+
+        let result = subject.send(1, call("eth_getTransactionReceipt")).wait();
+
+        assert_eq!(
+            result,
+            Err(Error::Transport(
+                "Only 1 of 3 blockchain services agreed on eth_getTransactionReceipt; 2 required"
+                    .to_string()
+            ))
+        );
+        assert_eq!(subject.health_scores(), vec![1, -5, 1]);
+    }
+
+    #[test]
+    fn send_of_quorum_method_votes_only_on_the_fields_in_use_and_normalizes_them() {
+        let receipt_0 = json!({"transactionHash": "0xABC", "status": "0x01", "gasUsed": "0x5208"});
+        let receipt_1 =
+            json!({"transactionHash": "0xabc", "status": "0x1", "effectiveGasPrice": "0x7"});
+        let receipt_2 = json!({"transactionHash": "0xAbc", "status": "0x1", "gasUsed": "0x5209"});
+        let subject = make_subject(
+            vec![
+                EndpointTransportMock::default().send_result(Ok(receipt_0.clone())),
+                EndpointTransportMock::default().send_result(Ok(receipt_1)),
+                EndpointTransportMock::default().send_result(Ok(receipt_2)),
+            ],
+            3,
+        );
+
+        let result = subject.send(1, call("eth_getTransactionReceipt")).wait();
+
+        assert_eq!(result, Ok(receipt_0));
+        assert_eq!(subject.health_scores(), vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn send_of_quorum_method_notices_disagreement_on_a_field_in_use() {
+        init_test_logging();
+        let log = json!({
+            "blockHash": "0x1234",
+            "blockNumber": "0x10",
+            "data": "0x00ff",
+            "topics": ["0xDDF2", "0x0001"],
+            "transactionHash": "0xabcd",
+            "logIndex": "0x0"
+        });
+        let same_log = json!({
+            "blockHash": "0x1234",
+            "blockNumber": "0x010",
+            "data": "0x00FF",
+            "removed": false,
+            "topics": ["0xddf2", "0x0001"],
+            "transactionHash": "0xABCD",
+            "logIndex": "0x3"
+        });
+        let other_log = json!({
+            "blockHash": "0x1234",
+            "blockNumber": "0x10",
+            "data": "0x00ff",
+            "topics": ["0xddf2", "0x0002"],
+            "transactionHash": "0xabcd"
+        });
+        let subject = make_subject(
+            vec![
+                EndpointTransportMock::default().send_result(Ok(json!([other_log]))),
+                EndpointTransportMock::default().send_result(Ok(json!([log]))),
+                EndpointTransportMock::default().send_result(Ok(json!([same_log]))),
+            ],
+            2,
+        );
+
+        let result = subject.send(1, call("eth_getLogs")).wait();
+
+        assert_eq!(result, Ok(json!([log])));
+        assert_eq!(subject.health_scores(), vec![-4, 1, 1]);
+        TestLogHandler::new().exists_log_containing(
+            "WARN: MultiEndpointTransport: Blockchain service at http://endpoint-0 \
+            disagrees with the quorum on eth_getLogs",
+        );
+    }
+
+    #[test]
+    fn send_of_quorum_method_does_not_count_errors_as_votes() {
+        let rpc_error = Error::Rpc(rpc::Error::invalid_params("booga"));
+        let subject = make_subject(
+            vec![
+                EndpointTransportMock::default().send_result(Err(rpc_error.clone())),
+                EndpointTransportMock::default().send_result(Err(rpc_error)),
+                EndpointTransportMock::default().send_result(Ok(json!(null))),
+            ],
+            2,
+        );
+
+        let result = subject.send(1, call("eth_getTransactionReceipt")).wait();
+
+        assert_eq!(
+            result,
+            Err(Error::Transport(
+                "Only 1 of 3 blockchain services agreed on eth_getTransactionReceipt; 2 required"
+                    .to_string()
+            ))
+        );
+        assert_eq!(subject.health_scores(), vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn send_of_quorum_method_passes_on_the_first_error_if_nobody_answers() {
+        let subject = make_subject(
+            vec![
+                EndpointTransportMock::default()
+                    .send_result(Err(Error::Rpc(rpc::Error::invalid_params("booga")))),
+                EndpointTransportMock::default().send_result(Err(Error::Unreachable)),
+            ],
+            2,
+        );
+
+        let result = subject.send(1, call("eth_getLogs")).wait();
+
+        assert_eq!(result, Err(Error::Rpc(rpc::Error::invalid_params("booga"))));
+        assert_eq!(subject.health_scores(), vec![1, -5]);
+    }
+
+    #[test]
+    fn quorum_of_one_means_plain_failover_even_for_quorum_methods() {
+        let send_params_1 = Arc::new(Mutex::new(vec![]));
+        let subject = make_subject(
+            vec![
+                EndpointTransportMock::default().send_result(Ok(json!([]))),
+                EndpointTransportMock::default().send_params(&send_params_1),
+            ],
+            1,
+        );
+
+        let result = subject.send(1, call("eth_getLogs")).wait();
+
+        assert_eq!(result, Ok(json!([])));
+        assert!(send_params_1.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn send_batch_fails_over_to_the_next_endpoint() {
+        let send_batch_params_0 = Arc::new(Mutex::new(vec![]));
+        let send_batch_params_1 = Arc::new(Mutex::new(vec![]));
+        let requests = vec![
+            (1, call("eth_sendRawTransaction")),
+            (2, call("eth_sendRawTransaction")),
+        ];
+        let subject = make_subject(
+            vec![
+                EndpointTransportMock::default()
+                    .send_batch_params(&send_batch_params_0)
+                    .send_batch_result(Err(Error::Unreachable)),
+                EndpointTransportMock::default()
+                    .send_batch_params(&send_batch_params_1)
+                    .send_batch_result(Ok(vec![Ok(json!("0x1")), Ok(json!("0x2"))])),
+            ],
+            2,
+        );
+
+        let result = subject.send_batch(requests.clone()).wait();
+
+        assert_eq!(result, Ok(vec![Ok(json!("0x1")), Ok(json!("0x2"))]));
+        assert_eq!(*send_batch_params_0.lock().unwrap(), vec![requests.clone()]);
+        assert_eq!(*send_batch_params_1.lock().unwrap(), vec![requests]);
+        assert_eq!(subject.health_scores(), vec![-5, 1]);
+    }
+
+    #[test]
+    fn send_batch_with_quorum_method_checks_agreement_item_by_item() {
+        let logs = json!([{"blockNumber": "0x10"}]);
+        let requests = vec![(1, call("eth_blockNumber")), (2, call("eth_getLogs"))];
+        let subject = make_subject(
+            vec![
+                EndpointTransportMock::default().send_batch_result(Err(Error::Unreachable)),
+                EndpointTransportMock::default()
+                    .send_batch_result(Ok(vec![Ok(json!("0x21")), Ok(logs.clone())])),
+                EndpointTransportMock::default()
+                    .send_batch_result(Ok(vec![Ok(json!("0x20")), Ok(logs.clone())])),
+            ],
+            2,
+        );
+
+        let result = subject.send_batch(requests).wait();
+
+        assert_eq!(result, Ok(vec![Ok(json!("0x21")), Ok(logs)]));
+        assert_eq!(subject.health_scores(), vec![-5, 1, 1]);
+    }
+
+    #[test]
+    fn send_batch_with_quorum_method_reports_missing_agreement_on_the_item() {
+        let requests = vec![(1, call("eth_blockNumber")), (2, call("eth_getLogs"))];
+        let subject = make_subject(
+            vec![
+                EndpointTransportMock::default()
+                    .send_batch_result(Ok(vec![Ok(json!("0x21")), Ok(json!([]))])),
+                EndpointTransportMock::default()
+                    .send_batch_result(Ok(vec![Ok(json!("0x21")), Ok(json!([{}]))])),
+            ],
+            2,
+        );
+
+        let result = subject.send_batch(requests).wait();
+
+        assert_eq!(
+            result,
+            Ok(vec![
+                Ok(json!("0x21")),
+                Err(Error::Transport(
+                    "Only 1 of 2 blockchain services agreed on eth_getLogs; 2 required".to_string()
+                ))
+            ])
+        );
+    }
+
+    #[test]
+    fn send_batch_with_quorum_method_fails_if_no_endpoint_answers() {
+        let requests = vec![(1, call("eth_getLogs"))];
+        let subject = make_subject(
+            vec![
+                EndpointTransportMock::default().send_batch_result(Err(Error::Unreachable)),
+                EndpointTransportMock::default()
+                    .send_batch_result(Err(Error::Transport("timeout".to_string()))),
+            ],
+            2,
+        );
+
+        let result = subject.send_batch(requests).wait();
+
+        assert_eq!(result, Err(Error::Unreachable));
+        assert_eq!(subject.health_scores(), vec![-5, -5]);
+    }
+
+    #[test]
+    fn debug_shows_urls_health_and_quorum() {
+        let subject = make_subject(
+            vec![
+                EndpointTransportMock::default(),
+                EndpointTransportMock::default(),
+            ],
+            2,
+        );
+
+        let result = format!("{:?}", subject);
+
+        assert_eq!(
+            result,
+            "MultiEndpointTransport { urls: [\"http://endpoint-0\", \"http://endpoint-1\"], \
+            health_scores: [0, 0], quorum: 2 }"
+        );
+    }
+}
@@ -0,0 +1,898 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::blockchain::blockchain_interface::blockchain_interface_web3::eip1559_transaction::{
+    Eip1559TransactionParameters, EIP1559_TRANSACTION_TYPE,
+};
+use ethsign::Signature;
+use ethsign_crypto::Keccak256;
+use futures::Future;
+use rlp::{Decodable, Rlp, RlpStream};
+use rustc_hex::{FromHex, ToHex};
+use serde_json::{json, Value};
+use std::fmt::Debug;
+use web3::transports::{EventLoopHandle, Http};
+use web3::types::{Address, Bytes, SignedTransaction, TransactionParameters, H256, U256};
+use web3::Transport;
+
+pub const SIGN_TRANSACTION_METHOD: &str = "account_signTransaction";
+pub const SIGN_DATA_METHOD: &str = "account_signData";
+// Clef signs text/plain data as an EIP-191 personal message
+pub const PERSONAL_MESSAGE_CONTENT_TYPE: &str = "text/plain";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnsignedTransaction {
+    Legacy(TransactionParameters),
+    Eip1559(Eip1559TransactionParameters),
+}
+
+// Signs on behalf of a consuming wallet whose private key the Node doesn't hold
+pub trait TransactionSigner {
+    fn sign_transaction(
+        &self,
+        from: Address,
+        transaction: UnsignedTransaction,
+    ) -> Result<SignedTransaction, String>;
+}
+
+// Proves, for a consuming wallet whose private key the Node doesn't hold, that the wallet will pay
+// the Node with the given digest for routing
+pub trait PayerProofSigner {
+    fn sign_payer_proof(&self, from: Address, digest: &[u8]) -> Result<Signature, String>;
+}
+
+// Talks to a Clef-compatible signer over JSON-RPC; the signer decides whether to sign and with which key
+pub struct ExternalTransactionSigner<T: Transport> {
+    transport: T,
+}
+
+// The Neighborhood's signer, which can't share the event loop of the blockchain services
+pub struct ExternalPayerProofSigner {
+    _event_loop_handle: EventLoopHandle,
+    signer: ExternalTransactionSigner<Http>,
+}
+
+impl PayerProofSigner for ExternalPayerProofSigner {
+    fn sign_payer_proof(&self, from: Address, digest: &[u8]) -> Result<Signature, String> {
+        self.signer.sign_payer_proof(from, digest)
+    }
+}
+
+impl ExternalPayerProofSigner {
+    pub fn new(signer_url: &str) -> Result<Self, String> {
+        let (event_loop_handle, transport) =
+            Http::new(signer_url).map_err(|e| format!("{:?}", e))?;
+        Ok(Self {
+            _event_loop_handle: event_loop_handle,
+            signer: ExternalTransactionSigner::new(transport),
+        })
+    }
+}
+
+impl<T: Transport> TransactionSigner for ExternalTransactionSigner<T> {
+    fn sign_transaction(
+        &self,
+        from: Address,
+        transaction: UnsignedTransaction,
+    ) -> Result<SignedTransaction, String> {
+        let request = Self::make_request(from, &transaction);
+        let response = self
+            .transport
+            .execute(SIGN_TRANSACTION_METHOD, vec![request])
+            .wait()
+            .map_err(|e| format!("External signer failed: {:?}", e))?;
+        Self::interpret_response(&response, from, &transaction)
+    }
+}
+
+impl<T: Transport> PayerProofSigner for ExternalTransactionSigner<T> {
+    fn sign_payer_proof(&self, from: Address, digest: &[u8]) -> Result<Signature, String> {
+        let params = vec![
+            json!(PERSONAL_MESSAGE_CONTENT_TYPE),
+            json!(from),
+            json!(format!("0x{}", digest.to_hex::<String>())),
+        ];
+        let response = self
+            .transport
+            .execute(SIGN_DATA_METHOD, params)
+            .wait()
+            .map_err(|e| format!("External signer failed: {:?}", e))?;
+        Self::interpret_signature(&response)
+    }
+}
+
+impl<T: Transport> ExternalTransactionSigner<T> {
+    pub fn new(transport: T) -> Self {
+        Self { transport }
+    }
+
+    fn make_request(from: Address, transaction: &UnsignedTransaction) -> Value {
+        match transaction {
+            UnsignedTransaction::Legacy(params) => json!({
+                "from": from,
+                "to": params.to,
+                "gas": params.gas,
+                "gasPrice": params.gas_price,
+                "value": params.value,
+                "nonce": params.nonce,
+                "data": params.data,
+                "chainId": params.chain_id.map(U256::from),
+            }),
+            UnsignedTransaction::Eip1559(params) => json!({
+                "from": from,
+                "to": params.to,
+                "gas": params.gas,
+                "maxFeePerGas": params.max_fee_per_gas,
+                "maxPriorityFeePerGas": params.max_priority_fee_per_gas,
+                "value": params.value,
+                "nonce": params.nonce,
+                "data": params.data,
+                "chainId": U256::from(params.chain_id),
+            }),
+        }
+    }
+
+    fn interpret_response(
+        response: &Value,
+        from: Address,
+        transaction: &UnsignedTransaction,
+    ) -> Result<SignedTransaction, String> {
+        let raw_transaction = match response.get("raw").and_then(Value::as_str) {
+            Some(raw) => Self::decode_hex(raw)?,
+            None => {
+                return Err(format!(
+                    "External signer returned no raw transaction: {}",
+                    response
+                ))
+            }
+        };
+        if raw_transaction.is_empty() {
+            return Err("External signer returned an empty raw transaction".to_string());
+        }
+        Self::verify_raw_transaction(&raw_transaction, from, transaction)?;
+        let transaction_hash = H256::from(raw_transaction.keccak256());
+        let tx = response.get("tx").cloned().unwrap_or(Value::Null);
+        if let Some(reported_hash) = tx.get("hash").and_then(Value::as_str) {
+            if Self::decode_hex(reported_hash)? != transaction_hash.as_bytes() {
+                return Err(format!(
+                    "External signer reported hash {} for a transaction hashing to {:?}",
+                    reported_hash, transaction_hash
+                ));
+            }
+        }
+        let field = |name: &str| -> Result<U256, String> {
+            match tx.get(name) {
+                Some(value) => serde_json::from_value::<U256>(value.clone())
+                    .map_err(|e| format!("Bad '{}' from external signer: {:?}", name, e)),
+                None => Ok(U256::zero()),
+            }
+        };
+        let as_h256 = |value: U256| {
+            let mut bytes = [0u8; 32];
+            value.to_big_endian(&mut bytes);
+            H256::from(bytes)
+        };
+        Ok(SignedTransaction {
+            message_hash: H256::zero(),
+            v: field("v")?.low_u64(),
+            r: as_h256(field("r")?),
+            s: as_h256(field("s")?),
+            raw_transaction: Bytes(raw_transaction),
+            transaction_hash,
+        })
+    }
+
+    fn interpret_signature(response: &Value) -> Result<Signature, String> {
+        let signature = match response.as_str() {
+            Some(signature) => Self::decode_hex(signature)?,
+            None => {
+                return Err(format!(
+                    "External signer returned no signature: {}",
+                    response
+                ))
+            }
+        };
+        if signature.len() != 65 {
+            return Err(format!(
+                "External signer returned a {}-byte signature instead of a 65-byte one",
+                signature.len()
+            ));
+        }
+        // Signers put 27 or 28 in v, where recovery wants 0 or 1
+        let v = match signature[64] {
+            v @ 0..=1 => v,
+            v @ 27..=28 => v - 27,
+            v => {
+                return Err(format!(
+                    "External signer returned a signature with v = {}",
+                    v
+                ))
+            }
+        };
+        let mut r = [0u8; 32];
+        r.copy_from_slice(&signature[0..32]);
+        let mut s = [0u8; 32];
+        s.copy_from_slice(&signature[32..64]);
+        Ok(Signature { v, r, s })
+    }
+
+    // The signer signs whatever it pleases, with whichever key it pleases; make sure that's what we
+    // asked for before we send it
+    fn verify_raw_transaction(
+        raw: &[u8],
+        from: Address,
+        transaction: &UnsignedTransaction,
+    ) -> Result<(), String> {
+        let sender = match transaction {
+            UnsignedTransaction::Eip1559(params) => {
+                if raw[0] != EIP1559_TRANSACTION_TYPE {
+                    return Err(format!(
+                        "External signer returned a transaction of type {:#04x} instead of an EIP-1559 one",
+                        raw[0]
+                    ));
+                }
+                let rlp = Rlp::new(&raw[1..]);
+                Self::verify_field(&rlp, 0, "chain id", params.chain_id)?;
+                Self::verify_field(&rlp, 1, "nonce", params.nonce)?;
+                Self::verify_field(&rlp, 2, "max priority fee", params.max_priority_fee_per_gas)?;
+                Self::verify_field(&rlp, 3, "max fee", params.max_fee_per_gas)?;
+                Self::verify_field(&rlp, 4, "gas limit", params.gas)?;
+                Self::verify_field(&rlp, 5, "recipient", params.to)?;
+                Self::verify_field(&rlp, 6, "value", params.value)?;
+                Self::verify_field(&rlp, 7, "data", params.data.0.clone())?;
+                let y_parity = Self::decode_field::<u64>(&rlp, 9, "y parity")?;
+                let unsigned = Self::unsigned_transaction(&rlp, 9, &[])?;
+                let message = [&[EIP1559_TRANSACTION_TYPE][..], &unsigned].concat();
+                Self::recover_sender(&rlp, 10, y_parity, &message)?
+            }
+            UnsignedTransaction::Legacy(params) => {
+                let rlp = Rlp::new(raw);
+                if let Some(nonce) = params.nonce {
+                    Self::verify_field(&rlp, 0, "nonce", nonce)?;
+                }
+                if let Some(gas_price) = params.gas_price {
+                    Self::verify_field(&rlp, 1, "gas price", gas_price)?;
+                }
+                Self::verify_field(&rlp, 2, "gas limit", params.gas)?;
+                if let Some(to) = params.to {
+                    Self::verify_field(&rlp, 3, "recipient", to)?;
+                }
+                Self::verify_field(&rlp, 4, "value", params.value)?;
+                Self::verify_field(&rlp, 5, "data", params.data.0.clone())?;
+                // EIP-155 folds the chain id into v; older signatures have a v of 27 or 28
+                let v = Self::decode_field::<u64>(&rlp, 6, "v")?;
+                let chain_id_opt = (v >= 35).then(|| (v - 35) / 2);
+                if chain_id_opt != params.chain_id {
+                    return Err(format!(
+                        "External signer changed the chain id of the transaction from {:?} to {:?}",
+                        params.chain_id, chain_id_opt
+                    ));
+                }
+                match chain_id_opt {
+                    Some(chain_id) => {
+                        let unsigned = Self::unsigned_transaction(&rlp, 6, &[chain_id, 0, 0])?;
+                        Self::recover_sender(&rlp, 7, (v - 35) % 2, &unsigned)?
+                    }
+                    None => {
+                        let unsigned = Self::unsigned_transaction(&rlp, 6, &[])?;
+                        Self::recover_sender(&rlp, 7, v.saturating_sub(27), &unsigned)?
+                    }
+                }
+            }
+        };
+        if sender == from {
+            Ok(())
+        } else {
+            Err(format!(
+                "External signer signed the transaction as {:?} instead of {:?}",
+                sender, from
+            ))
+        }
+    }
+
+    // What was signed: the leading fields of the signed transaction, plus any the signature replaced
+    fn unsigned_transaction(
+        rlp: &Rlp,
+        signed_field_count: usize,
+        replaced_fields: &[u64],
+    ) -> Result<Vec<u8>, String> {
+        let mut stream = RlpStream::new_list(signed_field_count + replaced_fields.len());
+        for index in 0..signed_field_count {
+            let field = rlp.at(index).map_err(|e| {
+                format!("Can't decode the transaction from external signer: {:?}", e)
+            })?;
+            stream.append_raw(field.as_raw(), 1);
+        }
+        replaced_fields.iter().for_each(|field| {
+            stream.append(field);
+        });
+        Ok(stream.as_raw().to_vec())
+    }
+
+    fn recover_sender(
+        rlp: &Rlp,
+        r_index: usize,
+        recovery_id: u64,
+        unsigned: &[u8],
+    ) -> Result<Address, String> {
+        if recovery_id > 1 {
+            return Err(format!(
+                "External signer returned a signature with recovery id {}",
+                recovery_id
+            ));
+        }
+        let as_bytes = |value: U256| {
+            let mut bytes = [0u8; 32];
+            value.to_big_endian(&mut bytes);
+            bytes
+        };
+        let signature = Signature {
+            v: recovery_id as u8,
+            r: as_bytes(Self::decode_field::<U256>(rlp, r_index, "signature")?),
+            s: as_bytes(Self::decode_field::<U256>(rlp, r_index + 1, "signature")?),
+        };
+        let public_key = signature.recover(&unsigned.keccak256()).map_err(|e| {
+            format!(
+                "Can't recover the sender of the transaction from external signer: {:?}",
+                e
+            )
+        })?;
+        Ok(Address::from_slice(public_key.address()))
+    }
+
+    fn verify_field<V: Decodable + PartialEq + Debug>(
+        rlp: &Rlp,
+        index: usize,
+        name: &str,
+        expected: V,
+    ) -> Result<(), String> {
+        let actual = Self::decode_field::<V>(rlp, index, name)?;
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(format!(
+                "External signer changed the {} of the transaction from {:?} to {:?}",
+                name, expected, actual
+            ))
+        }
+    }
+
+    fn decode_field<V: Decodable>(rlp: &Rlp, index: usize, name: &str) -> Result<V, String> {
+        rlp.val_at::<V>(index).map_err(|e| {
+            format!(
+                "Can't decode the {} of the transaction from external signer: {:?}",
+                name, e
+            )
+        })
+    }
+
+    fn decode_hex(value: &str) -> Result<Vec<u8>, String> {
+        value
+            .trim_start_matches("0x")
+            .from_hex::<Vec<u8>>()
+            .map_err(|e| format!("Bad hex from external signer '{}': {:?}", value, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::blockchain_interface::blockchain_interface_web3::REQUESTS_IN_PARALLEL;
+    use crate::blockchain::payer::personal_message_digest;
+    use crate::blockchain::test_utils::TestTransport;
+    use crate::sub_lib::wallet::Wallet;
+    use crate::test_utils::http_test_server::TestServer;
+    use crate::test_utils::{assert_string_contains, make_paying_wallet};
+    use masq_lib::utils::find_free_port;
+    use rlp::RlpStream;
+    use std::net::Ipv4Addr;
+    use std::sync::{Arc, Mutex};
+    use web3::transports::Http;
+
+    fn make_eip1559_transaction() -> Eip1559TransactionParameters {
+        Eip1559TransactionParameters {
+            chain_id: 137,
+            nonce: U256::from(5),
+            max_priority_fee_per_gas: U256::from(1_500_000_000u64),
+            max_fee_per_gas: U256::from(61_500_000_000u64),
+            gas: U256::from(55_000),
+            to: Address::from_low_u64_be(0x1234),
+            value: U256::zero(),
+            data: Bytes(vec![0xa9, 0x05, 0x9c, 0xbb]),
+        }
+    }
+
+    fn signer_wallet() -> Wallet {
+        make_paying_wallet(b"externally held")
+    }
+
+    fn signer_address() -> Address {
+        signer_wallet().address()
+    }
+
+    fn sign_eip1559_transaction(transaction: &Eip1559TransactionParameters) -> SignedTransaction {
+        transaction.sign(&signer_wallet().prepare_secp256k1_secret().unwrap())
+    }
+
+    fn make_legacy_transaction() -> TransactionParameters {
+        TransactionParameters {
+            nonce: Some(U256::from(3)),
+            to: Some(Address::from_low_u64_be(0x1234)),
+            gas: U256::from(55_000),
+            gas_price: Some(U256::from(1_000_000_000)),
+            value: U256::zero(),
+            data: Bytes(vec![0xa9]),
+            chain_id: Some(3),
+        }
+    }
+
+    // Signed for the given chain the way EIP-155 says
+    fn make_legacy_raw(transaction: &TransactionParameters, chain_id: u64) -> Vec<u8> {
+        let append_fields = |rlp: &mut RlpStream| {
+            rlp.append(&transaction.nonce.unwrap());
+            rlp.append(&transaction.gas_price.unwrap());
+            rlp.append(&transaction.gas);
+            rlp.append(&transaction.to.unwrap());
+            rlp.append(&transaction.value);
+            rlp.append(&transaction.data.0);
+        };
+        let mut unsigned = RlpStream::new_list(9);
+        append_fields(&mut unsigned);
+        unsigned.append(&chain_id);
+        unsigned.append(&0u64);
+        unsigned.append(&0u64);
+        let signature = signer_wallet()
+            .sign(&unsigned.as_raw().keccak256())
+            .unwrap();
+        let mut rlp = RlpStream::new_list(9);
+        append_fields(&mut rlp);
+        rlp.append(&(signature.v as u64 + chain_id * 2 + 35));
+        rlp.append(&U256::from_big_endian(&signature.r));
+        rlp.append(&U256::from_big_endian(&signature.s));
+        rlp.out().to_vec()
+    }
+
+    fn to_hex(bytes: &[u8]) -> String {
+        format!("0x{}", rustc_hex::ToHex::to_hex::<String>(bytes))
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(SIGN_TRANSACTION_METHOD, "account_signTransaction");
+        assert_eq!(SIGN_DATA_METHOD, "account_signData");
+        assert_eq!(PERSONAL_MESSAGE_CONTENT_TYPE, "text/plain");
+    }
+
+    #[test]
+    fn external_signer_signs_through_a_stand_in_signer() {
+        let port = find_free_port();
+        let wallet = make_paying_wallet(b"externally held");
+        let transaction = make_eip1559_transaction();
+        // The stand-in signer holds the key the Node doesn't have
+        let expected = transaction.sign(&wallet.prepare_secp256k1_secret().unwrap());
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 0,
+            "result": {
+                "raw": to_hex(&expected.raw_transaction.0),
+                "tx": {
+                    "hash": format!("{:?}", expected.transaction_hash),
+                    "v": format!("{:#x}", expected.v),
+                    "r": format!("{:?}", expected.r),
+                    "s": format!("{:?}", expected.s),
+                }
+            }
+        });
+        let test_server = TestServer::start(port, vec![body.to_string().into_bytes()]);
+        let (_event_loop_handle, transport) = Http::with_max_parallel(
+            &format!("http://{}:{}", Ipv4Addr::LOCALHOST, port),
+            REQUESTS_IN_PARALLEL,
+        )
+        .unwrap();
+        let subject = ExternalTransactionSigner::new(transport);
+
+        let result =
+            subject.sign_transaction(wallet.address(), UnsignedTransaction::Eip1559(transaction));
+
+        let signed = result.unwrap();
+        assert_eq!(signed.raw_transaction, expected.raw_transaction);
+        assert_eq!(signed.transaction_hash, expected.transaction_hash);
+        assert_eq!(signed.v, expected.v);
+        assert_eq!(signed.r, expected.r);
+        assert_eq!(signed.s, expected.s);
+        let requests = test_server.requests_so_far();
+        let request: Value = serde_json::from_slice(requests[0].body()).unwrap();
+        assert_eq!(request["method"], json!("account_signTransaction"));
+        assert_eq!(
+            request["params"],
+            json!([{
+                "from": format!("{:?}", wallet.address()),
+                "to": "0x0000000000000000000000000000000000001234",
+                "gas": "0xd6d8",
+                "maxFeePerGas": "0xe51af8700",
+                "maxPriorityFeePerGas": "0x59682f00",
+                "value": "0x0",
+                "nonce": "0x5",
+                "data": "0xa9059cbb",
+                "chainId": "0x89",
+            }])
+        );
+    }
+
+    #[test]
+    fn external_signer_describes_legacy_transactions() {
+        let prepare_params_arc = Arc::new(Mutex::new(vec![]));
+        let transaction = make_legacy_transaction();
+        let raw = make_legacy_raw(&transaction, 3);
+        let transport = TestTransport::default()
+            .prepare_params(&prepare_params_arc)
+            .send_result(json!({ "raw": to_hex(&raw) }));
+        let subject = ExternalTransactionSigner::new(transport);
+        let from = signer_address();
+
+        let result = subject.sign_transaction(from, UnsignedTransaction::Legacy(transaction));
+
+        assert_eq!(
+            result,
+            Ok(SignedTransaction {
+                message_hash: H256::zero(),
+                v: 0,
+                r: H256::zero(),
+                s: H256::zero(),
+                raw_transaction: Bytes(raw.clone()),
+                transaction_hash: H256::from(raw.keccak256()),
+            })
+        );
+        let prepare_params = prepare_params_arc.lock().unwrap();
+        assert_eq!(
+            *prepare_params,
+            vec![(
+                "account_signTransaction".to_string(),
+                vec![json!({
+                    "from": format!("{:?}", from),
+                    "to": "0x0000000000000000000000000000000000001234",
+                    "gas": "0xd6d8",
+                    "gasPrice": "0x3b9aca00",
+                    "value": "0x0",
+                    "nonce": "0x3",
+                    "data": "0xa9",
+                    "chainId": "0x3",
+                })]
+            )]
+        );
+    }
+
+    #[test]
+    fn external_signer_signs_payer_proofs_as_personal_messages() {
+        let prepare_params_arc = Arc::new(Mutex::new(vec![]));
+        let wallet = make_paying_wallet(b"externally held");
+        let digest = [0xabu8; 32];
+        let expected = wallet.sign(&personal_message_digest(&digest)).unwrap();
+        let signature = [&expected.r[..], &expected.s[..], &[expected.v + 27]].concat();
+        let transport = TestTransport::default()
+            .prepare_params(&prepare_params_arc)
+            .send_result(json!(to_hex(&signature)));
+        let subject = ExternalTransactionSigner::new(transport);
+
+        let result = subject.sign_payer_proof(wallet.address(), &digest);
+
+        assert_eq!(result, Ok(expected));
+        let prepare_params = prepare_params_arc.lock().unwrap();
+        assert_eq!(
+            *prepare_params,
+            vec![(
+                "account_signData".to_string(),
+                vec![
+                    json!("text/plain"),
+                    json!(format!("{:?}", wallet.address())),
+                    json!(to_hex(&digest)),
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn external_signer_rejects_a_payer_proof_of_the_wrong_length() {
+        let transport = TestTransport::default().send_result(json!(to_hex(&[1u8; 64])));
+        let subject = ExternalTransactionSigner::new(transport);
+
+        let result = subject.sign_payer_proof(Address::zero(), &[0u8; 32]);
+
+        assert_eq!(
+            result,
+            Err(
+                "External signer returned a 64-byte signature instead of a 65-byte one".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn external_signer_rejects_a_payer_proof_with_a_strange_v() {
+        let signature = [&[1u8; 64][..], &[35]].concat();
+        let transport = TestTransport::default().send_result(json!(to_hex(&signature)));
+        let subject = ExternalTransactionSigner::new(transport);
+
+        let result = subject.sign_payer_proof(Address::zero(), &[0u8; 32]);
+
+        assert_eq!(
+            result,
+            Err("External signer returned a signature with v = 35".to_string())
+        );
+    }
+
+    #[test]
+    fn external_signer_reports_a_failing_signer() {
+        let subject = ExternalTransactionSigner::new(TestTransport::default());
+
+        let result = subject.sign_transaction(
+            signer_address(),
+            UnsignedTransaction::Eip1559(make_eip1559_transaction()),
+        );
+
+        assert_eq!(
+            result,
+            Err("External signer failed: Unreachable".to_string())
+        );
+    }
+
+    #[test]
+    fn external_signer_rejects_a_response_without_raw_transaction() {
+        let transport = TestTransport::default().send_result(json!({ "tx": {} }));
+        let subject = ExternalTransactionSigner::new(transport);
+
+        let result = subject.sign_transaction(
+            signer_address(),
+            UnsignedTransaction::Eip1559(make_eip1559_transaction()),
+        );
+
+        assert_eq!(
+            result,
+            Err("External signer returned no raw transaction: {\"tx\":{}}".to_string())
+        );
+    }
+
+    #[test]
+    fn external_signer_rejects_an_empty_raw_transaction() {
+        let transport = TestTransport::default().send_result(json!({ "raw": "0x" }));
+        let subject = ExternalTransactionSigner::new(transport);
+
+        let result = subject.sign_transaction(
+            signer_address(),
+            UnsignedTransaction::Eip1559(make_eip1559_transaction()),
+        );
+
+        assert_eq!(
+            result,
+            Err("External signer returned an empty raw transaction".to_string())
+        );
+    }
+
+    #[test]
+    fn external_signer_rejects_a_hash_that_does_not_match_the_raw_transaction() {
+        let raw = sign_eip1559_transaction(&make_eip1559_transaction())
+            .raw_transaction
+            .0;
+        let transport = TestTransport::default().send_result(json!({
+            "raw": to_hex(&raw),
+            "tx": { "hash": format!("{:?}", H256::repeat_byte(0x11)) }
+        }));
+        let subject = ExternalTransactionSigner::new(transport);
+
+        let result = subject.sign_transaction(
+            signer_address(),
+            UnsignedTransaction::Eip1559(make_eip1559_transaction()),
+        );
+
+        assert_eq!(
+            result,
+            Err(format!(
+                "External signer reported hash {:?} for a transaction hashing to {:?}",
+                H256::repeat_byte(0x11),
+                H256::from(raw.keccak256())
+            ))
+        );
+    }
+
+    #[test]
+    fn external_signer_rejects_a_transaction_to_another_recipient() {
+        let mut tampered = make_eip1559_transaction();
+        tampered.to = Address::from_low_u64_be(0x6666);
+        let raw = sign_eip1559_transaction(&tampered).raw_transaction.0;
+        let transport = TestTransport::default().send_result(json!({ "raw": to_hex(&raw) }));
+        let subject = ExternalTransactionSigner::new(transport);
+
+        let result = subject.sign_transaction(
+            signer_address(),
+            UnsignedTransaction::Eip1559(make_eip1559_transaction()),
+        );
+
+        assert_eq!(
+            result,
+            Err(format!(
+                "External signer changed the recipient of the transaction from {:?} to {:?}",
+                Address::from_low_u64_be(0x1234),
+                Address::from_low_u64_be(0x6666)
+            ))
+        );
+    }
+
+    #[test]
+    fn external_signer_rejects_a_transaction_with_other_value_nonce_data_or_chain_id() {
+        let expected = make_eip1559_transaction();
+        let tamper = |tampering: &dyn Fn(&mut Eip1559TransactionParameters)| {
+            let mut tampered = expected.clone();
+            tampering(&mut tampered);
+            let raw = sign_eip1559_transaction(&tampered).raw_transaction.0;
+            let transport = TestTransport::default().send_result(json!({ "raw": to_hex(&raw) }));
+            ExternalTransactionSigner::new(transport)
+                .sign_transaction(
+                    signer_address(),
+                    UnsignedTransaction::Eip1559(expected.clone()),
+                )
+                .unwrap_err()
+        };
+
+        let value = tamper(&|tx| tx.value = U256::from(1_000_000));
+        let nonce = tamper(&|tx| tx.nonce = U256::from(6));
+        let data = tamper(&|tx| tx.data = Bytes(vec![0x01]));
+        let chain_id = tamper(&|tx| tx.chain_id = 1);
+
+        assert_eq!(
+            value,
+            "External signer changed the value of the transaction from 0 to 1000000"
+        );
+        assert_eq!(
+            nonce,
+            "External signer changed the nonce of the transaction from 5 to 6"
+        );
+        assert_eq!(
+            data,
+            "External signer changed the data of the transaction from [169, 5, 156, 187] to [1]"
+        );
+        assert_eq!(
+            chain_id,
+            "External signer changed the chain id of the transaction from 137 to 1"
+        );
+    }
+
+    #[test]
+    fn external_signer_rejects_a_transaction_with_other_fees_or_gas_limit() {
+        let expected = make_eip1559_transaction();
+        let tamper = |tampering: &dyn Fn(&mut Eip1559TransactionParameters)| {
+            let mut tampered = expected.clone();
+            tampering(&mut tampered);
+            let raw = sign_eip1559_transaction(&tampered).raw_transaction.0;
+            let transport = TestTransport::default().send_result(json!({ "raw": to_hex(&raw) }));
+            ExternalTransactionSigner::new(transport)
+                .sign_transaction(
+                    signer_address(),
+                    UnsignedTransaction::Eip1559(expected.clone()),
+                )
+                .unwrap_err()
+        };
+
+        let priority_fee = tamper(&|tx| tx.max_priority_fee_per_gas = U256::from(2));
+        let max_fee = tamper(&|tx| tx.max_fee_per_gas = U256::from(3));
+        let gas_limit = tamper(&|tx| tx.gas = U256::from(4));
+
+        assert_eq!(
+            priority_fee,
+            "External signer changed the max priority fee of the transaction from 1500000000 to 2"
+        );
+        assert_eq!(
+            max_fee,
+            "External signer changed the max fee of the transaction from 61500000000 to 3"
+        );
+        assert_eq!(
+            gas_limit,
+            "External signer changed the gas limit of the transaction from 55000 to 4"
+        );
+    }
+
+    #[test]
+    fn external_signer_rejects_a_transaction_signed_by_another_wallet() {
+        let transaction = make_eip1559_transaction();
+        let raw = sign_eip1559_transaction(&transaction).raw_transaction.0;
+        let transport = TestTransport::default().send_result(json!({ "raw": to_hex(&raw) }));
+        let subject = ExternalTransactionSigner::new(transport);
+        let consuming_wallet_address = make_paying_wallet(b"consuming").address();
+
+        let result = subject.sign_transaction(
+            consuming_wallet_address,
+            UnsignedTransaction::Eip1559(transaction),
+        );
+
+        assert_eq!(
+            result,
+            Err(format!(
+                "External signer signed the transaction as {:?} instead of {:?}",
+                signer_address(),
+                consuming_wallet_address
+            ))
+        );
+    }
+
+    #[test]
+    fn external_signer_rejects_a_legacy_transaction_with_another_gas_price_or_signer() {
+        let expected = make_legacy_transaction();
+        let mut tampered = expected.clone();
+        tampered.gas_price = Some(U256::from(2_000_000_000u64));
+        let sign_legacy = |from: Address, raw: Vec<u8>| {
+            let transport = TestTransport::default().send_result(json!({ "raw": to_hex(&raw) }));
+            ExternalTransactionSigner::new(transport)
+                .sign_transaction(from, UnsignedTransaction::Legacy(expected.clone()))
+                .unwrap_err()
+        };
+        let consuming_wallet_address = make_paying_wallet(b"consuming").address();
+
+        let gas_price = sign_legacy(signer_address(), make_legacy_raw(&tampered, 3));
+        let sender = sign_legacy(consuming_wallet_address, make_legacy_raw(&expected, 3));
+
+        assert_eq!(
+            gas_price,
+            "External signer changed the gas price of the transaction from 1000000000 to 2000000000"
+        );
+        assert_eq!(
+            sender,
+            format!(
+                "External signer signed the transaction as {:?} instead of {:?}",
+                signer_address(),
+                consuming_wallet_address
+            )
+        );
+    }
+
+    #[test]
+    fn external_signer_rejects_a_legacy_transaction_in_place_of_an_eip1559_one() {
+        let raw = make_legacy_raw(&make_legacy_transaction(), 3);
+        let transport = TestTransport::default().send_result(json!({ "raw": to_hex(&raw) }));
+        let subject = ExternalTransactionSigner::new(transport);
+
+        let result = subject.sign_transaction(
+            signer_address(),
+            UnsignedTransaction::Eip1559(make_eip1559_transaction()),
+        );
+
+        assert_eq!(
+            result,
+            Err(format!(
+                "External signer returned a transaction of type {:#04x} instead of an EIP-1559 one",
+                raw[0]
+            ))
+        );
+    }
+
+    #[test]
+    fn external_signer_rejects_a_legacy_transaction_for_another_chain() {
+        let transaction = make_legacy_transaction();
+        let raw = make_legacy_raw(&transaction, 1);
+        let transport = TestTransport::default().send_result(json!({ "raw": to_hex(&raw) }));
+        let subject = ExternalTransactionSigner::new(transport);
+
+        let result =
+            subject.sign_transaction(signer_address(), UnsignedTransaction::Legacy(transaction));
+
+        assert_eq!(
+            result,
+            Err(
+                "External signer changed the chain id of the transaction from Some(3) to Some(1)"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn external_signer_rejects_bad_hex() {
+        let transport = TestTransport::default().send_result(json!({ "raw": "0xbooga" }));
+        let subject = ExternalTransactionSigner::new(transport);
+
+        let result = subject.sign_transaction(
+            signer_address(),
+            UnsignedTransaction::Eip1559(make_eip1559_transaction()),
+        );
+
+        assert_string_contains(
+            &result.unwrap_err(),
+            "Bad hex from external signer '0xbooga'",
+        );
+    }
+}
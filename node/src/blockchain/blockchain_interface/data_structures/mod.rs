@@ -3,12 +3,15 @@
 pub mod errors;
 use crate::accountant::db_access_objects::pending_payable_dao::PendingPayable;
 use crate::sub_lib::wallet::Wallet;
-use web3::types::H256;
+use serde_derive::Deserialize;
+use web3::types::{H256, U256};
 use web3::Error;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BlockchainTransaction {
     pub block_number: u64,
+    pub block_hash: H256,
+    pub transaction_hash: H256,
     pub from: Wallet,
     pub wei_amount: u128,
 }
@@ -19,6 +22,18 @@ pub struct RetrievedBlockchainTransactions {
     pub transactions: Vec<BlockchainTransaction>,
 }
 
+// Answer to eth_feeHistory; 'base_fee_per_gas' includes one extra item, the base fee
+// of the block that is yet to come
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeHistory {
+    pub oldest_block: U256,
+    #[serde(default)]
+    pub base_fee_per_gas: Vec<U256>,
+    #[serde(default)]
+    pub reward: Vec<Vec<U256>>,
+}
+
 pub type ProcessedPayableFallible = Result<PendingPayable, RpcPayablesFailure>;
 
 #[derive(Debug, PartialEq, Clone)]
@@ -7,8 +7,11 @@ pub mod lower_level_interface;
 pub mod test_utils;
 
 use crate::accountant::db_access_objects::payable_dao::PayableAccount;
+use crate::accountant::db_access_objects::pending_payable_dao::PendingPayableReplacement;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
-use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
+use crate::blockchain::blockchain_bridge::{
+    PendingPayableFingerprint, PendingPayableFingerprintSeeds,
+};
 use crate::blockchain::blockchain_interface::data_structures::errors::{
     BlockchainAgentBuildError, BlockchainError, PayableTransactionError, ResultForReceipt,
 };
@@ -44,6 +47,13 @@ pub trait BlockchainInterface {
         accounts: &[PayableAccount],
     ) -> Result<Vec<ProcessedPayableFallible>, PayableTransactionError>;
 
+    fn replace_stuck_payables(
+        &self,
+        consuming_wallet: &Wallet,
+        persistent_config: &dyn PersistentConfiguration,
+        stuck_payables: &[PendingPayableFingerprint],
+    ) -> Result<Vec<PendingPayableReplacement>, PayableTransactionError>;
+
     fn get_transaction_receipt(&self, hash: H256) -> ResultForReceipt;
 
     fn lower_interface(&self) -> &dyn LowBlockchainInt;
@@ -3,7 +3,7 @@
 #![cfg(test)]
 
 use crate::blockchain::blockchain_interface::lower_level_interface::{
-    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForNonce,
+    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForFeeHistory, ResultForNonce,
 };
 use crate::blockchain::blockchain_interface::BlockchainInterface;
 use crate::sub_lib::wallet::Wallet;
@@ -14,6 +14,7 @@ use masq_lib::utils::find_free_port;
 use serde_json::Value;
 use std::cell::RefCell;
 use std::sync::{Arc, Mutex};
+use web3::types::Address;
 
 #[derive(Default)]
 pub struct LowBlockchainIntMock {
@@ -21,9 +22,13 @@ pub struct LowBlockchainIntMock {
     get_transaction_fee_balance_results: RefCell<Vec<ResultForBalance>>,
     get_masq_balance_params: Arc<Mutex<Vec<Wallet>>>,
     get_masq_balance_results: RefCell<Vec<ResultForBalance>>,
+    get_service_fee_allowance_params: Arc<Mutex<Vec<(Wallet, Address)>>>,
+    get_service_fee_allowance_results: RefCell<Vec<ResultForBalance>>,
     get_block_number_results: RefCell<Vec<LatestBlockNumber>>,
     get_transaction_id_params: Arc<Mutex<Vec<Wallet>>>,
     get_transaction_id_results: RefCell<Vec<ResultForNonce>>,
+    get_fee_history_params: Arc<Mutex<Vec<(u64, f64)>>>,
+    get_fee_history_results: RefCell<Vec<ResultForFeeHistory>>,
 }
 
 impl LowBlockchainInt for LowBlockchainIntMock {
@@ -45,6 +50,16 @@ impl LowBlockchainInt for LowBlockchainIntMock {
         self.get_masq_balance_results.borrow_mut().remove(0)
     }
 
+    fn get_service_fee_allowance(&self, owner: &Wallet, spender: Address) -> ResultForBalance {
+        self.get_service_fee_allowance_params
+            .lock()
+            .unwrap()
+            .push((owner.clone(), spender));
+        self.get_service_fee_allowance_results
+            .borrow_mut()
+            .remove(0)
+    }
+
     fn get_block_number(&self) -> LatestBlockNumber {
         self.get_block_number_results.borrow_mut().remove(0)
     }
@@ -56,6 +71,14 @@ impl LowBlockchainInt for LowBlockchainIntMock {
             .push(address.clone());
         self.get_transaction_id_results.borrow_mut().remove(0)
     }
+
+    fn get_fee_history(&self, block_count: u64, reward_percentile: f64) -> ResultForFeeHistory {
+        self.get_fee_history_params
+            .lock()
+            .unwrap()
+            .push((block_count, reward_percentile));
+        self.get_fee_history_results.borrow_mut().remove(0)
+    }
 }
 
 impl LowBlockchainIntMock {
@@ -81,6 +104,21 @@ impl LowBlockchainIntMock {
         self
     }
 
+    pub fn get_service_fee_allowance_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(Wallet, Address)>>>,
+    ) -> Self {
+        self.get_service_fee_allowance_params = params.clone();
+        self
+    }
+
+    pub fn get_service_fee_allowance_result(self, result: ResultForBalance) -> Self {
+        self.get_service_fee_allowance_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
     pub fn get_block_number_result(self, result: LatestBlockNumber) -> Self {
         self.get_block_number_results.borrow_mut().push(result);
         self
@@ -95,6 +133,16 @@ impl LowBlockchainIntMock {
         self.get_transaction_id_results.borrow_mut().push(result);
         self
     }
+
+    pub fn get_fee_history_params(mut self, params: &Arc<Mutex<Vec<(u64, f64)>>>) -> Self {
+        self.get_fee_history_params = params.clone();
+        self
+    }
+
+    pub fn get_fee_history_result(self, result: ResultForFeeHistory) -> Self {
+        self.get_fee_history_results.borrow_mut().push(result);
+        self
+    }
 }
 
 pub fn test_blockchain_interface_is_connected_and_functioning<F>(subject_factory: F)
@@ -1,11 +1,17 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use crate::blockchain::blockchain_interface::blockchain_interface_web3::multi_endpoint_transport::{
+    MultiEndpointTransport, RpcEndpoint,
+};
+use crate::blockchain::blockchain_interface::blockchain_interface_web3::transaction_signer::ExternalTransactionSigner;
 use crate::blockchain::blockchain_interface::blockchain_interface_web3::{
     BlockchainInterfaceWeb3, REQUESTS_IN_PARALLEL,
 };
 use crate::blockchain::blockchain_interface::BlockchainInterface;
+use crate::sub_lib::blockchain_bridge::blockchain_service_urls;
 use masq_lib::blockchains::chains::Chain;
-use web3::transports::Http;
+use web3::transports::{EventLoopHandle, Http};
+use web3::types::Address;
 
 pub(in crate::blockchain) struct BlockchainInterfaceInitializer {}
 
@@ -15,29 +21,79 @@ impl BlockchainInterfaceInitializer {
     pub fn initialize_interface(
         &self,
         blockchain_service_url: &str,
+        blockchain_service_quorum: u16,
+        external_signer_url_opt: Option<&str>,
+        multisend_contract_opt: Option<Address>,
+        multisend_max_creditors: u16,
         chain: Chain,
     ) -> Box<dyn BlockchainInterface> {
-        self.initialize_web3_interface(blockchain_service_url, chain)
+        self.initialize_web3_interface(
+            blockchain_service_url,
+            blockchain_service_quorum,
+            external_signer_url_opt,
+            multisend_contract_opt,
+            multisend_max_creditors,
+            chain,
+        )
     }
 
     fn initialize_web3_interface(
         &self,
         blockchain_service_url: &str,
+        blockchain_service_quorum: u16,
+        external_signer_url_opt: Option<&str>,
+        multisend_contract_opt: Option<Address>,
+        multisend_max_creditors: u16,
         chain: Chain,
     ) -> Box<dyn BlockchainInterface> {
-        match Http::with_max_parallel(blockchain_service_url, REQUESTS_IN_PARALLEL) {
-            Ok((event_loop_handle, transport)) => Box::new(BlockchainInterfaceWeb3::new(
-                transport,
-                event_loop_handle,
-                chain,
-            )),
-            Err(e) => panic!(
-                "Invalid blockchain service URL \"{}\". Error: {:?}. Chain: {}",
-                blockchain_service_url,
-                e,
-                chain.rec().literal_identifier
-            ),
+        let urls = blockchain_service_urls(blockchain_service_url)
+            .into_iter()
+            .map(|url| url.to_string())
+            .collect::<Vec<_>>();
+        let signer_url_opt = external_signer_url_opt.map(|url| url.to_string());
+        // All the services, and the external signer if any, share a single event loop
+        let (event_loop_handle, (transports, signer_transport_opt)) =
+            EventLoopHandle::spawn(move |handle| {
+                let transports = urls
+                    .into_iter()
+                    .map(|url| {
+                        let transport_result =
+                            Http::with_event_loop(&url, handle, REQUESTS_IN_PARALLEL);
+                        (url, transport_result)
+                    })
+                    .collect::<Vec<_>>();
+                let signer_transport_opt = signer_url_opt.map(|url| {
+                    let transport_result = Http::with_event_loop(&url, handle, 1);
+                    (url, transport_result)
+                });
+                Ok((transports, signer_transport_opt))
+            })
+            .expect("Event loop for blockchain services failed to start");
+        let endpoints = transports
+            .into_iter()
+            .map(|(url, transport_result)| match transport_result {
+                Ok(transport) => RpcEndpoint { url, transport },
+                Err(e) => panic!(
+                    "Invalid blockchain service URL \"{}\". Error: {:?}. Chain: {}",
+                    url,
+                    e,
+                    chain.rec().literal_identifier
+                ),
+            })
+            .collect();
+        let transport = MultiEndpointTransport::new(endpoints, blockchain_service_quorum as usize);
+        let mut interface = BlockchainInterfaceWeb3::new(transport, event_loop_handle, chain);
+        if let Some((url, transport_result)) = signer_transport_opt {
+            match transport_result {
+                Ok(transport) => interface
+                    .set_transaction_signer(Box::new(ExternalTransactionSigner::new(transport))),
+                Err(e) => panic!("Invalid external signer URL \"{}\". Error: {:?}", url, e),
+            }
+        }
+        if let Some(multisend_contract) = multisend_contract_opt {
+            interface.set_multisend_contract(multisend_contract, multisend_max_creditors)
         }
+        Box::new(interface)
     }
 }
 
@@ -50,14 +106,47 @@ mod tests {
 
     use crate::blockchain::blockchain_interface::test_utils::test_blockchain_interface_is_connected_and_functioning;
 
-    use masq_lib::constants::DEFAULT_CHAIN;
+    use masq_lib::constants::{DEFAULT_CHAIN, DEFAULT_MULTISEND_MAX_CREDITORS};
+    use masq_lib::utils::find_free_port;
 
     #[test]
     fn initialize_web3_interface_works() {
         let subject_factory = |port: u16, chain: Chain| {
             let subject = BlockchainInterfaceInitializer {};
             let server_url = &format!("http://{}:{}", &Ipv4Addr::LOCALHOST.to_string(), port);
-            subject.initialize_web3_interface(server_url, chain)
+            subject.initialize_web3_interface(
+                server_url,
+                1,
+                None,
+                None,
+                DEFAULT_MULTISEND_MAX_CREDITORS,
+                chain,
+            )
+        };
+
+        test_blockchain_interface_is_connected_and_functioning(subject_factory)
+    }
+
+    #[test]
+    fn initialize_web3_interface_with_several_urls_fails_over_to_a_working_one() {
+        let dead_port = find_free_port();
+        let subject_factory = |port: u16, chain: Chain| {
+            let subject = BlockchainInterfaceInitializer {};
+            let server_urls = &format!(
+                "http://{}:{},http://{}:{}",
+                Ipv4Addr::LOCALHOST,
+                dead_port,
+                Ipv4Addr::LOCALHOST,
+                port
+            );
+            subject.initialize_web3_interface(
+                server_urls,
+                1,
+                None,
+                None,
+                DEFAULT_MULTISEND_MAX_CREDITORS,
+                chain,
+            )
         };
 
         test_blockchain_interface_is_connected_and_functioning(subject_factory)
@@ -70,6 +159,66 @@ mod tests {
         let blockchain_service_url = "http://λ:8545";
         let subject = BlockchainInterfaceInitializer {};
 
-        subject.initialize_web3_interface(blockchain_service_url, DEFAULT_CHAIN);
+        subject.initialize_web3_interface(
+            blockchain_service_url,
+            1,
+            None,
+            None,
+            DEFAULT_MULTISEND_MAX_CREDITORS,
+            DEFAULT_CHAIN,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid blockchain service URL \"http://λ:8545\". \
+    Error: Transport(\"InvalidUri(InvalidUriChar)\"). Chain: polygon-mainnet")]
+    fn invalid_blockchain_url_among_several_produces_panic_for_web3_interface() {
+        let blockchain_service_urls = "http://localhost:8545,http://λ:8545";
+        let subject = BlockchainInterfaceInitializer {};
+
+        subject.initialize_web3_interface(
+            blockchain_service_urls,
+            1,
+            None,
+            None,
+            DEFAULT_MULTISEND_MAX_CREDITORS,
+            DEFAULT_CHAIN,
+        );
+    }
+
+    #[test]
+    fn initialize_web3_interface_with_external_signer_works() {
+        let signer_port = find_free_port();
+        let subject_factory = |port: u16, chain: Chain| {
+            let subject = BlockchainInterfaceInitializer {};
+            let server_url = &format!("http://{}:{}", Ipv4Addr::LOCALHOST, port);
+            let signer_url = format!("http://{}:{}", Ipv4Addr::LOCALHOST, signer_port);
+            subject.initialize_web3_interface(
+                server_url,
+                1,
+                Some(&signer_url),
+                None,
+                DEFAULT_MULTISEND_MAX_CREDITORS,
+                chain,
+            )
+        };
+
+        test_blockchain_interface_is_connected_and_functioning(subject_factory)
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid external signer URL \"http://λ:8550\". \
+    Error: Transport(\"InvalidUri(InvalidUriChar)\")")]
+    fn invalid_external_signer_url_produces_panic_for_web3_interface() {
+        let subject = BlockchainInterfaceInitializer {};
+
+        subject.initialize_web3_interface(
+            "http://localhost:8545",
+            1,
+            Some("http://λ:8550"),
+            None,
+            DEFAULT_MULTISEND_MAX_CREDITORS,
+            DEFAULT_CHAIN,
+        );
     }
 }
@@ -3,6 +3,7 @@ use crate::accountant::db_access_objects::banned_dao::BAN_CACHE;
 use crate::blockchain::signature::SerializableSignature;
 use crate::sub_lib::wallet::Wallet;
 use ethsign::Signature;
+use ethsign_crypto::Keccak256;
 use serde_derive::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
@@ -29,19 +30,34 @@ impl Payer {
         (self.wallet.congruent(&other.wallet)) && (self.proof == other.proof)
     }
 
+    // An external signer won't sign a bare digest, so its proofs sign the digest as a personal message
     pub fn owns_secret_key(&self, digest: &dyn AsRef<[u8]>) -> bool {
-        match &self.proof.recover(digest.as_ref()) {
-            Ok(payer_public_key) => match payer_public_key.verify(&self.proof, digest.as_ref()) {
+        self.proves(digest.as_ref()) || self.proves(&personal_message_digest(digest.as_ref()))
+    }
+
+    pub fn is_delinquent(&self) -> bool {
+        BAN_CACHE.is_banned(&self.wallet)
+    }
+
+    fn proves(&self, digest: &[u8]) -> bool {
+        match &self.proof.recover(digest) {
+            Ok(payer_public_key) => match payer_public_key.verify(&self.proof, digest) {
                 Ok(result) => result && payer_public_key.address() == &self.wallet.address().0,
                 Err(_) => false,
             },
             Err(_) => false,
         }
     }
+}
 
-    pub fn is_delinquent(&self) -> bool {
-        BAN_CACHE.is_banned(&self.wallet)
-    }
+// What an EIP-191 (version 0x45) signer actually signs when asked to sign a message
+pub fn personal_message_digest(message: &[u8]) -> [u8; 32] {
+    [
+        format!("\x19Ethereum Signed Message:\n{}", message.len()).as_bytes(),
+        message,
+    ]
+    .concat()
+    .keccak256()
 }
 
 impl Clone for Payer {
@@ -62,7 +78,7 @@ mod tests {
     use super::*;
     use crate::sub_lib::cryptde;
     use crate::sub_lib::cryptde::PublicKey as SubPublicKey;
-    use crate::test_utils::make_payer;
+    use crate::test_utils::{make_payer, make_paying_wallet};
     use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
     use rustc_hex::FromHex;
 
@@ -93,6 +109,34 @@ mod tests {
         assert_eq!(payer.owns_secret_key(&digest), false);
     }
 
+    #[test]
+    fn can_pay_validates_payer_proof_signed_as_a_personal_message() {
+        let wallet = make_paying_wallet(b"held by an external signer");
+        let public_key = SubPublicKey::new(&b"sign these bytessign these bytes"[..]);
+        let digest = cryptde::create_digest(&public_key, &TEST_DEFAULT_CHAIN.rec().contract);
+        let proof = wallet.sign(&personal_message_digest(&digest)).unwrap();
+        let payer = Payer::new(&wallet, &proof);
+
+        assert!(payer.owns_secret_key(&digest));
+        let other_digest = cryptde::create_digest(
+            &SubPublicKey::new(&b"wrong key"[..]),
+            &TEST_DEFAULT_CHAIN.rec().contract,
+        );
+        assert!(!payer.owns_secret_key(&other_digest));
+    }
+
+    #[test]
+    fn personal_message_digest_is_the_one_ethereum_signers_use() {
+        let result = personal_message_digest(b"hello");
+
+        assert_eq!(
+            result.to_vec(),
+            "50b2c43fd39106bafbba0da34fc430e1f91e3c96ea2acee2bc34119f92b37750"
+                .from_hex::<Vec<u8>>()
+                .unwrap()
+        );
+    }
+
     #[test]
     fn is_delinquent_says_no_for_non_delinquent_payer() {
         let secret = "812deadbeefcafefeedbabefaceea7abacadaba0deadbeefcafefeedbabeface"
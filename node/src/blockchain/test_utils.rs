@@ -3,8 +3,12 @@
 #![cfg(test)]
 
 use crate::accountant::db_access_objects::payable_dao::PayableAccount;
+use crate::accountant::db_access_objects::pending_payable_dao::PendingPayableReplacement;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
-use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
+use crate::blockchain::blockchain_bridge::{
+    PendingPayableFingerprint, PendingPayableFingerprintSeeds,
+};
+use crate::blockchain::blockchain_interface::blockchain_interface_web3::transaction_signer::PayerProofSigner;
 use crate::blockchain::blockchain_interface::blockchain_interface_web3::REQUESTS_IN_PARALLEL;
 use crate::blockchain::blockchain_interface::data_structures::errors::{
     BlockchainAgentBuildError, BlockchainError, PayableTransactionError, ResultForReceipt,
@@ -22,6 +26,7 @@ use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::ArbitraryIdStamp
 use actix::Recipient;
 use bip39::{Language, Mnemonic, Seed};
 use ethereum_types::{BigEndianHash, H256};
+use ethsign::Signature;
 use jsonrpc_core as rpc;
 use lazy_static::lazy_static;
 use masq_lib::blockchains::chains::Chain;
@@ -75,6 +80,10 @@ pub struct BlockchainInterfaceMock {
     >,
     send_batch_of_payables_results:
         RefCell<Vec<Result<Vec<ProcessedPayableFallible>, PayableTransactionError>>>,
+    replace_stuck_payables_params:
+        Arc<Mutex<Vec<(Wallet, ArbitraryIdStamp, Vec<PendingPayableFingerprint>)>>>,
+    replace_stuck_payables_results:
+        RefCell<Vec<Result<Vec<PendingPayableReplacement>, PayableTransactionError>>>,
     get_transaction_receipt_params: Arc<Mutex<Vec<H256>>>,
     get_transaction_receipt_results: RefCell<Vec<ResultForReceipt>>,
     lower_interface_result: Option<Box<LowBlockchainIntMock>>,
@@ -126,6 +135,20 @@ impl BlockchainInterface for BlockchainInterfaceMock {
         self.send_batch_of_payables_results.borrow_mut().remove(0)
     }
 
+    fn replace_stuck_payables(
+        &self,
+        consuming_wallet: &Wallet,
+        persistent_config: &dyn PersistentConfiguration,
+        stuck_payables: &[PendingPayableFingerprint],
+    ) -> Result<Vec<PendingPayableReplacement>, PayableTransactionError> {
+        self.replace_stuck_payables_params.lock().unwrap().push((
+            consuming_wallet.clone(),
+            persistent_config.arbitrary_id_stamp(),
+            stuck_payables.to_vec(),
+        ));
+        self.replace_stuck_payables_results.borrow_mut().remove(0)
+    }
+
     fn get_transaction_receipt(&self, hash: H256) -> ResultForReceipt {
         self.get_transaction_receipt_params
             .lock()
@@ -200,6 +223,24 @@ impl BlockchainInterfaceMock {
         self
     }
 
+    pub fn replace_stuck_payables_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(Wallet, ArbitraryIdStamp, Vec<PendingPayableFingerprint>)>>>,
+    ) -> Self {
+        self.replace_stuck_payables_params = params.clone();
+        self
+    }
+
+    pub fn replace_stuck_payables_result(
+        self,
+        result: Result<Vec<PendingPayableReplacement>, PayableTransactionError>,
+    ) -> Self {
+        self.replace_stuck_payables_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
     pub fn get_transaction_receipt_params(mut self, params: &Arc<Mutex<Vec<H256>>>) -> Self {
         self.get_transaction_receipt_params = params.clone();
         self
@@ -317,6 +358,34 @@ impl TestTransport {
     }
 }
 
+#[derive(Default)]
+pub struct PayerProofSignerMock {
+    sign_payer_proof_params: Arc<Mutex<Vec<(Address, H256)>>>,
+    sign_payer_proof_results: RefCell<Vec<Result<Signature, String>>>,
+}
+
+impl PayerProofSigner for PayerProofSignerMock {
+    fn sign_payer_proof(&self, from: Address, digest: &[u8]) -> Result<Signature, String> {
+        self.sign_payer_proof_params
+            .lock()
+            .unwrap()
+            .push((from, H256::from_slice(digest)));
+        self.sign_payer_proof_results.borrow_mut().remove(0)
+    }
+}
+
+impl PayerProofSignerMock {
+    pub fn sign_payer_proof_params(mut self, params: &Arc<Mutex<Vec<(Address, H256)>>>) -> Self {
+        self.sign_payer_proof_params = params.clone();
+        self
+    }
+
+    pub fn sign_payer_proof_result(self, result: Result<Signature, String>) -> Self {
+        self.sign_payer_proof_results.borrow_mut().push(result);
+        self
+    }
+}
+
 pub fn make_fake_event_loop_handle() -> EventLoopHandle {
     Http::with_max_parallel("http://86.75.30.9", REQUESTS_IN_PARALLEL)
         .unwrap()
@@ -1,8 +1,12 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
-use crate::accountant::DEFAULT_PENDING_TOO_LONG_SEC;
+use crate::accountant::{
+    DEFAULT_BALANCE_WARNING_DAYS, DEFAULT_CONFIRMATION_DEPTH, DEFAULT_LEDGER_ROLLUP_DAYS,
+    DEFAULT_PENDING_STUCK_SEC, DEFAULT_PENDING_TOO_LONG_SEC,
+};
 use crate::actor_system_factory::ActorSystemFactory;
 use crate::actor_system_factory::ActorSystemFactoryReal;
 use crate::actor_system_factory::{ActorFactoryReal, ActorSystemFactoryToolsReal};
+use crate::clandestine_port_hopper::choose_extra_clandestine_ports;
 use crate::crash_test_dummy::CrashTestDummy;
 use crate::database::db_initializer::DbInitializationConfig;
 use crate::database::db_initializer::{DbInitializer, DbInitializerReal};
@@ -15,20 +19,22 @@ use crate::json_discriminator_factory::JsonDiscriminatorFactory;
 use crate::listener_handler::ListenerHandler;
 use crate::listener_handler::ListenerHandlerFactory;
 use crate::listener_handler::ListenerHandlerFactoryReal;
+use crate::metrics::NodeMetrics;
+use crate::neighborhood::pricing::PricingConfig;
 use crate::neighborhood::DEFAULT_MIN_HOPS;
 use crate::node_configurator::node_configurator_standard::{
     NodeConfiguratorStandardPrivileged, NodeConfiguratorStandardUnprivileged,
 };
 use crate::node_configurator::{initialize_database, DirsWrapper, NodeConfigurator};
 use crate::privilege_drop::{IdWrapper, IdWrapperReal};
-use crate::server_initializer::LoggerInitializerWrapper;
+use crate::server_initializer::{LogFileConfig, LoggerInitializerWrapper};
 use crate::stream_handler_pool::StreamHandlerPoolSubs;
 use crate::sub_lib::accountant;
 use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
 use crate::sub_lib::blockchain_bridge::BlockchainBridgeConfig;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::cryptde_null::CryptDENull;
-use crate::sub_lib::cryptde_real::CryptDEReal;
+use crate::sub_lib::crypto_suite::CryptoSuite;
 use crate::sub_lib::neighborhood::NodeDescriptor;
 use crate::sub_lib::neighborhood::{NeighborhoodConfig, NeighborhoodMode};
 use crate::sub_lib::node_addr::NodeAddr;
@@ -36,12 +42,16 @@ use crate::sub_lib::socket_server::ConfiguredByPrivilege;
 use crate::sub_lib::ui_gateway::UiGatewayConfig;
 use crate::sub_lib::utils::db_connection_launch_panic;
 use crate::sub_lib::wallet::Wallet;
+use crate::websocket_discriminator_factory::WebSocketDiscriminatorFactory;
+use crate::websocket_masquerader::WebSocketRole;
 use futures::try_ready;
 use itertools::Itertools;
 use log::LevelFilter;
 use masq_lib::blockchains::chains::Chain;
 use masq_lib::command::StdStreams;
-use masq_lib::constants::DEFAULT_UI_PORT;
+use masq_lib::constants::{
+    DEFAULT_BLOCKCHAIN_SERVICE_QUORUM, DEFAULT_MULTISEND_MAX_CREDITORS, DEFAULT_UI_PORT,
+};
 use masq_lib::crash_point::CrashPoint;
 use masq_lib::logger::Logger;
 use masq_lib::multi_config::MultiConfig;
@@ -55,6 +65,8 @@ use std::fmt::{Debug, Display, Error, Formatter};
 use std::net::{Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 use std::vec::Vec;
 use tokio::prelude::stream::futures_unordered::FuturesUnordered;
 use tokio::prelude::Async;
@@ -115,6 +127,9 @@ impl Default for CryptDEPair {
 pub struct PortConfiguration {
     pub discriminator_factories: Vec<Box<dyn DiscriminatorFactory>>,
     pub is_clandestine: bool,
+    // For a listening port, Some(Server) means WebSocket upgrades are accepted alongside plain
+    // connections; for a single stream, it means the stream speaks WebSocket in that role.
+    pub websocket_role_opt: Option<WebSocketRole>,
 }
 
 impl PortConfiguration {
@@ -125,10 +140,41 @@ impl PortConfiguration {
         PortConfiguration {
             discriminator_factories,
             is_clandestine,
+            websocket_role_opt: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClandestineWebSocketMode {
+    Off,
+    Accept,
+    On,
+}
+
+impl FromStr for ClandestineWebSocketMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(ClandestineWebSocketMode::Off),
+            "accept" => Ok(ClandestineWebSocketMode::Accept),
+            "on" => Ok(ClandestineWebSocketMode::On),
+            _ => Err(format!("Unrecognized clandestine WebSocket mode: '{}'", s)),
         }
     }
 }
 
+impl ClandestineWebSocketMode {
+    pub fn accepts_websocket(&self) -> bool {
+        *self != ClandestineWebSocketMode::Off
+    }
+
+    pub fn connects_via_websocket(&self) -> bool {
+        *self == ClandestineWebSocketMode::On
+    }
+}
+
 pub trait EnvironmentWrapper: Send {
     fn var(&self, key: &str) -> Option<String>;
 }
@@ -333,11 +379,25 @@ impl Display for RealUser {
 pub struct BootstrapperConfig {
     // These fields can be set while privileged without penalty
     pub log_level: LevelFilter,
+    pub log_file_config: LogFileConfig,
     pub dns_servers: Vec<SocketAddr>,
     pub scan_intervals_opt: Option<ScanIntervals>,
     pub suppress_initial_scans: bool,
     pub when_pending_too_long_sec: u64,
+    pub when_pending_stuck_sec: u64,
+    pub confirmation_depth: u64,
+    pub ledger_rollup_days: u64,
+    pub ledger_retention_days_opt: Option<u64>,
+    pub balance_warning_days: u64,
     pub crash_point: CrashPoint,
+    pub clandestine_websocket_mode: ClandestineWebSocketMode,
+    pub clandestine_port_count: usize,
+    pub clandestine_port_rotation_opt: Option<Duration>,
+    pub relay_batch_latency_opt: Option<Duration>,
+    pub metrics_port_opt: Option<u16>,
+    // Every actor reports to this; it's only exposed to the outside world if --metrics-port is given
+    pub metrics: Arc<NodeMetrics>,
+    pub crypto_suite: CryptoSuite,
     pub clandestine_discriminator_factories: Vec<Box<dyn DiscriminatorFactory>>,
     pub ui_gateway_config: UiGatewayConfig,
     pub blockchain_bridge_config: BlockchainBridgeConfig,
@@ -356,6 +416,7 @@ pub struct BootstrapperConfig {
     pub consuming_wallet_opt: Option<Wallet>,
     pub earning_wallet: Wallet,
     pub neighborhood_config: NeighborhoodConfig,
+    pub pricing_config: PricingConfig,
 }
 
 impl Default for BootstrapperConfig {
@@ -369,16 +430,29 @@ impl BootstrapperConfig {
         BootstrapperConfig {
             // These fields can be set while privileged without penalty
             log_level: LevelFilter::Off,
+            log_file_config: LogFileConfig::default(),
             dns_servers: vec![],
             scan_intervals_opt: None,
             suppress_initial_scans: false,
             crash_point: CrashPoint::None,
+            clandestine_websocket_mode: ClandestineWebSocketMode::Off,
+            clandestine_port_count: 1,
+            clandestine_port_rotation_opt: None,
+            relay_batch_latency_opt: None,
+            metrics_port_opt: None,
+            metrics: Arc::new(NodeMetrics::default()),
+            crypto_suite: CryptoSuite::Curve25519,
             clandestine_discriminator_factories: vec![],
             ui_gateway_config: UiGatewayConfig {
                 ui_port: DEFAULT_UI_PORT,
+                ui_token_file_opt: None,
             },
             blockchain_bridge_config: BlockchainBridgeConfig {
                 blockchain_service_url_opt: None,
+                blockchain_service_quorum: DEFAULT_BLOCKCHAIN_SERVICE_QUORUM,
+                external_signer_url_opt: None,
+                multisend_contract_opt: None,
+                multisend_max_creditors: DEFAULT_MULTISEND_MAX_CREDITORS,
                 chain: TEST_DEFAULT_CHAIN,
                 gas_price: 1,
             },
@@ -400,7 +474,13 @@ impl BootstrapperConfig {
                 mode: NeighborhoodMode::ZeroHop,
                 min_hops: DEFAULT_MIN_HOPS,
             },
+            pricing_config: PricingConfig::default(),
             when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            when_pending_stuck_sec: DEFAULT_PENDING_STUCK_SEC,
+            confirmation_depth: DEFAULT_CONFIRMATION_DEPTH,
+            ledger_rollup_days: DEFAULT_LEDGER_ROLLUP_DAYS,
+            ledger_retention_days_opt: None,
+            balance_warning_days: DEFAULT_BALANCE_WARNING_DAYS,
         }
     }
 
@@ -409,8 +489,12 @@ impl BootstrapperConfig {
         self.blockchain_bridge_config.blockchain_service_url_opt = unprivileged
             .blockchain_bridge_config
             .blockchain_service_url_opt;
+        self.blockchain_bridge_config.blockchain_service_quorum = unprivileged
+            .blockchain_bridge_config
+            .blockchain_service_quorum;
         self.clandestine_port_opt = unprivileged.clandestine_port_opt;
         self.neighborhood_config = unprivileged.neighborhood_config;
+        self.pricing_config = unprivileged.pricing_config;
         self.earning_wallet = unprivileged.earning_wallet;
         self.consuming_wallet_opt = unprivileged.consuming_wallet_opt;
         self.db_password_opt = unprivileged.db_password_opt;
@@ -418,6 +502,11 @@ impl BootstrapperConfig {
         self.suppress_initial_scans = unprivileged.suppress_initial_scans;
         self.payment_thresholds_opt = unprivileged.payment_thresholds_opt;
         self.when_pending_too_long_sec = unprivileged.when_pending_too_long_sec;
+        self.when_pending_stuck_sec = unprivileged.when_pending_stuck_sec;
+        self.confirmation_depth = unprivileged.confirmation_depth;
+        self.ledger_rollup_days = unprivileged.ledger_rollup_days;
+        self.ledger_retention_days_opt = unprivileged.ledger_retention_days_opt;
+        self.balance_warning_days = unprivileged.balance_warning_days;
     }
 
     pub fn exit_service_rate(&self) -> u64 {
@@ -438,6 +527,18 @@ impl BootstrapperConfig {
     pub fn routing_byte_rate(&self) -> u64 {
         self.neighborhood_config.mode.rate_pack().routing_byte_rate
     }
+
+    pub fn clandestine_port_configuration(&self) -> PortConfiguration {
+        PortConfiguration {
+            discriminator_factories: vec![Box::new(JsonDiscriminatorFactory::new())],
+            is_clandestine: true,
+            websocket_role_opt: if self.clandestine_websocket_mode.accepts_websocket() {
+                Some(WebSocketRole::Server)
+            } else {
+                None
+            },
+        }
+    }
 }
 
 pub struct Bootstrapper {
@@ -469,6 +570,7 @@ impl ConfiguredByPrivilege for Bootstrapper {
             self.config.data_directory.clone(),
             &self.config.real_user,
             self.config.log_level,
+            &self.config.log_file_config,
             None,
         );
         self.listener_handlers =
@@ -503,6 +605,7 @@ impl ConfiguredByPrivilege for Bootstrapper {
         let cryptdes = Bootstrapper::initialize_cryptdes(
             &main_cryptde_null_opt,
             &alias_cryptde_null_opt,
+            self.config.crypto_suite,
             self.config.blockchain_bridge_config.chain,
         );
         let node_descriptor = Bootstrapper::make_local_descriptor(
@@ -549,6 +652,7 @@ impl Bootstrapper {
         Self::initialize_cryptdes(
             main_cryptde_null_opt,
             alias_cryptde_null_opt,
+            CryptoSuite::Curve25519,
             masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN,
         )
     }
@@ -556,15 +660,23 @@ impl Bootstrapper {
     fn initialize_cryptdes(
         main_cryptde_null_opt: &Option<&dyn CryptDE>,
         alias_cryptde_null_opt: &Option<&dyn CryptDE>,
+        crypto_suite: CryptoSuite,
         chain: Chain,
     ) -> CryptDEPair {
         unsafe {
-            Self::initialize_single_cryptde(main_cryptde_null_opt, &mut MAIN_CRYPTDE_BOX_OPT, chain)
+            Self::initialize_single_cryptde(
+                main_cryptde_null_opt,
+                &mut MAIN_CRYPTDE_BOX_OPT,
+                crypto_suite,
+                chain,
+            )
         };
+        // The alias key never leaves this Node, so nothing is gained by making it post-quantum
         unsafe {
             Self::initialize_single_cryptde(
                 alias_cryptde_null_opt,
                 &mut ALIAS_CRYPTDE_BOX_OPT,
+                CryptoSuite::Curve25519,
                 chain,
             )
         }
@@ -574,6 +686,7 @@ impl Bootstrapper {
     fn initialize_single_cryptde(
         cryptde_null_opt: &Option<&dyn CryptDE>,
         boxed_cryptde: &mut Option<Box<dyn CryptDE>>,
+        crypto_suite: CryptoSuite,
         chain: Chain,
     ) {
         match cryptde_null_opt {
@@ -581,7 +694,7 @@ impl Bootstrapper {
                 let _ = boxed_cryptde.replace(Box::new(<&CryptDENull>::from(*cryptde).clone()));
             }
             None => {
-                let _ = boxed_cryptde.replace(Box::new(CryptDEReal::new(chain)));
+                let _ = boxed_cryptde.replace(crypto_suite.make_cryptde(chain));
             }
         }
     }
@@ -647,17 +760,18 @@ impl Bootstrapper {
                 listener_handler
                     .bind_port_and_configuration(
                         clandestine_port,
-                        PortConfiguration {
-                            discriminator_factories: vec![
-                                Box::new(JsonDiscriminatorFactory::new()),
-                            ],
-                            is_clandestine: true,
-                        },
+                        self.config.clandestine_port_configuration(),
                     )
                     .expect("Failed to bind ListenerHandler to clandestine port");
                 self.listener_handlers.push(listener_handler);
+                // The StreamHandlerPool will listen on the extra ports once it's running
+                let mut clandestine_ports = vec![clandestine_port];
+                clandestine_ports.extend(choose_extra_clandestine_ports(
+                    &[clandestine_port],
+                    self.config.clandestine_port_count.saturating_sub(1),
+                ));
                 self.config.neighborhood_config.mode = NeighborhoodMode::Standard(
-                    NodeAddr::new(&node_addr.ip_addr(), &[clandestine_port]),
+                    NodeAddr::new(&node_addr.ip_addr(), &clandestine_ports),
                     neighbor_configs.clone(),
                     *rate_pack,
                 );
@@ -665,9 +779,19 @@ impl Bootstrapper {
             } else {
                 None
             };
-        self.config
-            .clandestine_discriminator_factories
-            .push(Box::new(JsonDiscriminatorFactory::new()));
+        if self
+            .config
+            .clandestine_websocket_mode
+            .connects_via_websocket()
+        {
+            self.config
+                .clandestine_discriminator_factories
+                .push(Box::new(WebSocketDiscriminatorFactory::new()));
+        } else {
+            self.config
+                .clandestine_discriminator_factories
+                .push(Box::new(JsonDiscriminatorFactory::new()));
+        }
         clandestine_port_opt
     }
 
@@ -712,8 +836,8 @@ mod tests {
     use crate::accountant::DEFAULT_PENDING_TOO_LONG_SEC;
     use crate::actor_system_factory::{ActorFactory, ActorSystemFactory};
     use crate::bootstrapper::{
-        main_cryptde_ref, Bootstrapper, BootstrapperConfig, EnvironmentWrapper, PortConfiguration,
-        RealUser,
+        main_cryptde_ref, Bootstrapper, BootstrapperConfig, ClandestineWebSocketMode,
+        EnvironmentWrapper, PortConfiguration, RealUser,
     };
     use crate::database::db_initializer::DbInitializationConfig;
     use crate::database::db_initializer::{DbInitializer, DbInitializerReal};
@@ -724,16 +848,18 @@ mod tests {
     use crate::discriminator::Discriminator;
     use crate::discriminator::UnmaskedChunk;
     use crate::listener_handler::{ListenerHandler, ListenerHandlerFactory};
+    use crate::neighborhood::pricing::PricingConfig;
     use crate::node_test_utils::{extract_log, DirsWrapperMock, IdWrapperMock};
     use crate::node_test_utils::{make_stream_handler_pool_subs_from_recorder, TestLogOwner};
     use crate::server_initializer::test_utils::LoggerInitializerWrapperMock;
-    use crate::server_initializer::LoggerInitializerWrapper;
+    use crate::server_initializer::{LogFileConfig, LoggerInitializerWrapper};
     use crate::stream_handler_pool::StreamHandlerPoolSubs;
     use crate::stream_messages::AddStreamMsg;
     use crate::sub_lib::accountant::ScanIntervals;
     use crate::sub_lib::cryptde::PublicKey;
     use crate::sub_lib::cryptde::{CryptDE, PlainData};
     use crate::sub_lib::cryptde_null::CryptDENull;
+    use crate::sub_lib::crypto_suite::CryptoSuite;
     use crate::sub_lib::neighborhood::{
         NeighborhoodConfig, NeighborhoodMode, NodeDescriptor, DEFAULT_RATE_PACK,
     };
@@ -769,6 +895,7 @@ mod tests {
     use masq_lib::utils::{find_free_port, to_string};
     use std::cell::RefCell;
     use std::collections::HashMap;
+    use std::convert::TryFrom;
     use std::io;
     use std::io::ErrorKind;
     use std::marker::Sync;
@@ -837,8 +964,8 @@ mod tests {
             discriminator_factories: PortConfiguration,
         ) -> io::Result<()> {
             self.log.lock().unwrap().log(format!(
-                "bind_port_and_configuration ({}, PortConfiguration {{is_clandestine: {}, ...}})",
-                port, discriminator_factories.is_clandestine
+                "bind_port_and_configuration ({}, PortConfiguration {{is_clandestine: {}, websocket_role_opt: {:?}, ...}})",
+                port, discriminator_factories.is_clandestine, discriminator_factories.websocket_role_opt
             ));
             self.port_configuration_parameter = Some(discriminator_factories);
             self.bind_port_and_discriminator_factories_result
@@ -1082,14 +1209,14 @@ mod tests {
         all_calls.extend(third_handler_log.lock().unwrap().dump());
         assert!(
             all_calls.contains(&String::from(
-                "bind_port_and_configuration (80, PortConfiguration {is_clandestine: false, ...})"
+                "bind_port_and_configuration (80, PortConfiguration {is_clandestine: false, websocket_role_opt: None, ...})"
             )),
             "{:?}",
             all_calls
         );
         assert!(
             all_calls.contains(&String::from(
-                "bind_port_and_configuration (443, PortConfiguration {is_clandestine: false, ...})"
+                "bind_port_and_configuration (443, PortConfiguration {is_clandestine: false, websocket_role_opt: None, ...})"
             )),
             "{:?}",
             all_calls
@@ -1163,6 +1290,7 @@ mod tests {
                 data_dir,
                 RealUser::new(Some(123), Some(456), Some("/home/booga".into())),
                 LevelFilter::Warn,
+                LogFileConfig::default(),
                 None,
             )]
         )
@@ -1222,6 +1350,7 @@ mod tests {
             PortConfiguration {
                 discriminator_factories: vec![],
                 is_clandestine: true,
+                websocket_role_opt: None,
             },
         );
         privileged_config.port_configurations = port_configuration;
@@ -1244,14 +1373,23 @@ mod tests {
         unprivileged_config
             .blockchain_bridge_config
             .blockchain_service_url_opt = blockchain_url_opt.clone();
+        unprivileged_config
+            .blockchain_bridge_config
+            .blockchain_service_quorum = 2;
         unprivileged_config.clandestine_port_opt = clandestine_port_opt;
         unprivileged_config.neighborhood_config = neighborhood_config.clone();
+        unprivileged_config.pricing_config = PricingConfig::try_from("market|80|150").unwrap();
         unprivileged_config.earning_wallet = earning_wallet.clone();
         unprivileged_config.consuming_wallet_opt = consuming_wallet_opt.clone();
         unprivileged_config.db_password_opt = db_password_opt.clone();
         unprivileged_config.scan_intervals_opt = Some(ScanIntervals::default());
         unprivileged_config.suppress_initial_scans = false;
         unprivileged_config.when_pending_too_long_sec = DEFAULT_PENDING_TOO_LONG_SEC;
+        unprivileged_config.when_pending_stuck_sec = 1234;
+        unprivileged_config.confirmation_depth = 24;
+        unprivileged_config.ledger_rollup_days = 7;
+        unprivileged_config.ledger_retention_days_opt = Some(365);
+        unprivileged_config.balance_warning_days = 10;
 
         privileged_config.merge_unprivileged(unprivileged_config);
 
@@ -1266,8 +1404,18 @@ mod tests {
                 .blockchain_service_url_opt,
             blockchain_url_opt
         );
+        assert_eq!(
+            privileged_config
+                .blockchain_bridge_config
+                .blockchain_service_quorum,
+            2
+        );
         assert_eq!(privileged_config.clandestine_port_opt, clandestine_port_opt);
         assert_eq!(privileged_config.neighborhood_config, neighborhood_config);
+        assert_eq!(
+            privileged_config.pricing_config,
+            PricingConfig::try_from("market|80|150").unwrap()
+        );
         assert_eq!(privileged_config.earning_wallet, earning_wallet);
         assert_eq!(privileged_config.consuming_wallet_opt, consuming_wallet_opt);
         assert_eq!(privileged_config.db_password_opt, db_password_opt);
@@ -1280,6 +1428,11 @@ mod tests {
             privileged_config.when_pending_too_long_sec,
             DEFAULT_PENDING_TOO_LONG_SEC
         );
+        assert_eq!(privileged_config.when_pending_stuck_sec, 1234);
+        assert_eq!(privileged_config.confirmation_depth, 24);
+        assert_eq!(privileged_config.ledger_rollup_days, 7);
+        assert_eq!(privileged_config.ledger_retention_days_opt, Some(365));
+        assert_eq!(privileged_config.balance_warning_days, 10);
         //some values from the privileged config
         assert_eq!(privileged_config.log_level, Off);
         assert_eq!(
@@ -1530,7 +1683,12 @@ mod tests {
     #[test]
     fn initialize_cryptde_without_cryptde_null_uses_cryptde_real() {
         let _lock = INITIALIZATION.lock();
-        let cryptdes = Bootstrapper::initialize_cryptdes(&None, &None, TEST_DEFAULT_CHAIN);
+        let cryptdes = Bootstrapper::initialize_cryptdes(
+            &None,
+            &None,
+            CryptoSuite::Curve25519,
+            TEST_DEFAULT_CHAIN,
+        );
 
         assert_eq!(main_cryptde_ref().public_key(), cryptdes.main.public_key());
         // Brittle assertion: this may not be true forever
@@ -1544,13 +1702,36 @@ mod tests {
         let cryptde_null = main_cryptde().clone();
         let cryptde_null_public_key = cryptde_null.public_key().clone();
 
-        let cryptdes =
-            Bootstrapper::initialize_cryptdes(&Some(cryptde_null), &None, TEST_DEFAULT_CHAIN);
+        let cryptdes = Bootstrapper::initialize_cryptdes(
+            &Some(cryptde_null),
+            &None,
+            CryptoSuite::Curve25519,
+            TEST_DEFAULT_CHAIN,
+        );
 
         assert_eq!(cryptdes.main.public_key(), &cryptde_null_public_key);
         assert_eq!(main_cryptde_ref().public_key(), cryptdes.main.public_key());
     }
 
+    #[test]
+    fn initialize_cryptde_with_hybrid_crypto_suite_makes_only_main_cryptde_hybrid() {
+        let _lock = INITIALIZATION.lock();
+
+        let cryptdes = Bootstrapper::initialize_cryptdes(
+            &None,
+            &None,
+            CryptoSuite::X25519MlKem768,
+            TEST_DEFAULT_CHAIN,
+        );
+
+        assert_eq!(cryptdes.main.crypto_suite(), CryptoSuite::X25519MlKem768);
+        assert_eq!(
+            cryptdes.main.public_key().len(),
+            CryptoSuite::X25519MlKem768.rec().public_key_len
+        );
+        assert_eq!(cryptdes.alias.crypto_suite(), CryptoSuite::Curve25519);
+    }
+
     #[test]
     fn initialize_cryptde_and_report_local_descriptor_with_ip_address() {
         let _lock = INITIALIZATION.lock();
@@ -1560,7 +1741,12 @@ mod tests {
             &[3456u16, 4567u16],
         );
         let cryptde_ref = {
-            let cryptdes = Bootstrapper::initialize_cryptdes(&None, &None, TEST_DEFAULT_CHAIN);
+            let cryptdes = Bootstrapper::initialize_cryptdes(
+                &None,
+                &None,
+                CryptoSuite::Curve25519,
+                TEST_DEFAULT_CHAIN,
+            );
             let descriptor = Bootstrapper::make_local_descriptor(
                 cryptdes.main,
                 Some(node_addr),
@@ -1603,7 +1789,12 @@ mod tests {
         let _lock = INITIALIZATION.lock();
         init_test_logging();
         let cryptdes = {
-            let cryptdes = Bootstrapper::initialize_cryptdes(&None, &None, TEST_DEFAULT_CHAIN);
+            let cryptdes = Bootstrapper::initialize_cryptdes(
+                &None,
+                &None,
+                CryptoSuite::Curve25519,
+                TEST_DEFAULT_CHAIN,
+            );
             let descriptor =
                 Bootstrapper::make_local_descriptor(cryptdes.main, None, TEST_DEFAULT_CHAIN);
             Bootstrapper::report_local_descriptor(cryptdes.main, &descriptor);
@@ -1688,7 +1879,7 @@ mod tests {
         assert_eq!(
             calls,
             vec![
-                "bind_port_and_configuration (1234, PortConfiguration {is_clandestine: true, ...})"
+                "bind_port_and_configuration (1234, PortConfiguration {is_clandestine: true, websocket_role_opt: None, ...})"
                     .to_string()
             ],
         );
@@ -1902,6 +2093,84 @@ mod tests {
         assert_eq!(0, clandestine_discriminators.len()); // Used to be 1, now 0 after removal
     }
 
+    #[test]
+    fn set_up_clandestine_port_accepts_and_connects_via_websocket_when_configured() {
+        let port = find_free_port();
+        let data_dir = ensure_node_home_directory_exists(
+            "bootstrapper",
+            "set_up_clandestine_port_accepts_and_connects_via_websocket_when_configured",
+        );
+        DbInitializerReal::default()
+            .initialize(&data_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let mut config = BootstrapperConfig::new();
+        config.neighborhood_config = NeighborhoodConfig {
+            mode: NeighborhoodMode::Standard(
+                NodeAddr::new(&IpAddr::from_str("1.2.3.4").unwrap(), &[4321]),
+                vec![],
+                rate_pack(100),
+            ),
+            min_hops: MIN_HOPS_FOR_TEST,
+        };
+        config.data_directory = data_dir;
+        config.clandestine_port_opt = Some(port);
+        config.clandestine_websocket_mode = ClandestineWebSocketMode::On;
+        let (listener_handler, listener_handler_log_arc) =
+            extract_log(ListenerHandlerNull::new(vec![]).bind_port_result(Ok(())));
+        let mut subject = BootstrapperBuilder::new()
+            .add_listener_handler(Box::new(listener_handler))
+            .config(config)
+            .build();
+
+        let result = subject.set_up_clandestine_port();
+
+        assert_eq!(result, Some(port));
+        assert_eq!(
+            listener_handler_log_arc.lock().unwrap().dump(),
+            vec![format!(
+                "bind_port_and_configuration ({}, PortConfiguration {{is_clandestine: true, websocket_role_opt: Some(Server), ...}})",
+                port
+            )]
+        );
+        let mut clandestine_discriminators = subject
+            .config
+            .clandestine_discriminator_factories
+            .into_iter()
+            .map(|factory| factory.make())
+            .collect::<Vec<Discriminator>>();
+        assert_eq!(clandestine_discriminators.len(), 1);
+        let mut discriminator = clandestine_discriminators.remove(0);
+        discriminator.add_data(&[0x82, 0x05, b'B', b'o', b'o', b'g', b'a']);
+        assert_eq!(
+            discriminator.take_chunk(),
+            Some(UnmaskedChunk {
+                chunk: b"Booga".to_vec(),
+                last_chunk: true,
+                sequenced: false,
+            }),
+        );
+    }
+
+    #[test]
+    fn clandestine_websocket_mode_can_be_parsed_and_interrogated() {
+        let off = ClandestineWebSocketMode::from_str("off").unwrap();
+        let accept = ClandestineWebSocketMode::from_str("Accept").unwrap();
+        let on = ClandestineWebSocketMode::from_str("ON").unwrap();
+        let bad = ClandestineWebSocketMode::from_str("booga");
+
+        assert_eq!(off, ClandestineWebSocketMode::Off);
+        assert_eq!(accept, ClandestineWebSocketMode::Accept);
+        assert_eq!(on, ClandestineWebSocketMode::On);
+        assert_eq!(
+            bad,
+            Err("Unrecognized clandestine WebSocket mode: 'booga'".to_string())
+        );
+        assert_eq!(
+            [off, accept, on].map(|mode| (mode.accepts_websocket(), mode.connects_via_websocket())),
+            [(false, false), (true, false), (true, true)]
+        );
+    }
+
     #[test]
     fn set_up_clandestine_port_handles_unspecified_port_in_standard_mode() {
         let cryptde_actual = CryptDENull::from(&PublicKey::new(&[1, 2, 3, 4]), TEST_DEFAULT_CHAIN);
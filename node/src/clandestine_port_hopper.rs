@@ -0,0 +1,442 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::bootstrapper::{BootstrapperConfig, PortConfiguration};
+use crate::database::db_initializer::DbInitializerReal;
+use crate::listener_handler::{ListenerHandlerFactory, ListenerHandlerFactoryReal};
+use crate::stream_messages::AddStreamMsg;
+use crate::sub_lib::neighborhood::NeighborhoodMode;
+use actix::Recipient;
+use futures::sync::oneshot;
+use masq_lib::logger::Logger;
+use std::io;
+use std::time::Duration;
+use tokio::prelude::Future;
+
+pub const EXTRA_CLANDESTINE_PORT_BIND_ATTEMPTS: usize = 10;
+
+pub fn choose_extra_clandestine_ports(ports_in_use: &[u16], count: usize) -> Vec<u16> {
+    let mut chosen: Vec<u16> = vec![];
+    while chosen.len() < count {
+        let candidate = DbInitializerReal::choose_clandestine_port();
+        if !ports_in_use.contains(&candidate) && !chosen.contains(&candidate) {
+            chosen.push(candidate);
+        }
+    }
+    chosen
+}
+
+struct ExtraClandestinePort {
+    port: u16,
+    // Dropping this closes the listener.
+    _closer: oneshot::Sender<()>,
+}
+
+// The primary clandestine port belongs to the Bootstrapper and never changes. The extra ones are
+// opened here, once the StreamHandlerPool can accept their streams, and may be hopped periodically.
+pub struct ClandestinePortHopper {
+    primary_port: u16,
+    initial_extra_ports: Vec<u16>,
+    extra_ports: Vec<ExtraClandestinePort>,
+    port_configuration: PortConfiguration,
+    rotation_interval_opt: Option<Duration>,
+    listener_handler_factory: Box<dyn ListenerHandlerFactory>,
+    choose_ports: fn(&[u16], usize) -> Vec<u16>,
+    logger: Logger,
+}
+
+impl ClandestinePortHopper {
+    pub fn new(
+        ports: &[u16],
+        port_configuration: PortConfiguration,
+        rotation_interval_opt: Option<Duration>,
+    ) -> ClandestinePortHopper {
+        ClandestinePortHopper {
+            primary_port: ports[0],
+            initial_extra_ports: ports[1..].to_vec(),
+            extra_ports: vec![],
+            port_configuration,
+            rotation_interval_opt,
+            listener_handler_factory: Box::new(ListenerHandlerFactoryReal::new()),
+            choose_ports: choose_extra_clandestine_ports,
+            logger: Logger::new("ClandestinePortHopper"),
+        }
+    }
+
+    pub fn from_config(config: &BootstrapperConfig) -> Option<ClandestinePortHopper> {
+        match &config.neighborhood_config.mode {
+            NeighborhoodMode::Standard(node_addr, _, _) if node_addr.ports().len() > 1 => {
+                Some(ClandestinePortHopper::new(
+                    &node_addr.ports(),
+                    config.clandestine_port_configuration(),
+                    config.clandestine_port_rotation_opt,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn rotation_interval_opt(&self) -> Option<Duration> {
+        self.rotation_interval_opt
+    }
+
+    pub fn ports(&self) -> Vec<u16> {
+        let mut ports = vec![self.primary_port];
+        ports.extend(self.extra_ports.iter().map(|extra_port| extra_port.port));
+        ports
+    }
+
+    pub fn advertised_ports(&self) -> Vec<u16> {
+        let mut ports = vec![self.primary_port];
+        ports.extend(&self.initial_extra_ports);
+        ports
+    }
+
+    pub fn open(&mut self, add_stream_sub: &Recipient<AddStreamMsg>) -> Vec<u16> {
+        let initial_extra_ports = std::mem::take(&mut self.initial_extra_ports);
+        self.extra_ports = initial_extra_ports
+            .into_iter()
+            .flat_map(|port| self.open_extra_port(port, add_stream_sub))
+            .collect();
+        self.ports()
+    }
+
+    pub fn rotate(&mut self, add_stream_sub: &Recipient<AddStreamMsg>) -> Vec<u16> {
+        let old_ports = self.ports();
+        let fresh_extra_ports = (self.choose_ports)(&old_ports, old_ports.len() - 1)
+            .into_iter()
+            .flat_map(|port| self.open_extra_port(port, add_stream_sub))
+            .collect();
+        self.extra_ports = fresh_extra_ports;
+        let new_ports = self.ports();
+        info!(
+            self.logger,
+            "Hopped clandestine ports from {:?} to {:?}", old_ports, new_ports
+        );
+        new_ports
+    }
+
+    fn open_extra_port(
+        &self,
+        port: u16,
+        add_stream_sub: &Recipient<AddStreamMsg>,
+    ) -> Option<ExtraClandestinePort> {
+        let mut candidate = port;
+        for _ in 0..EXTRA_CLANDESTINE_PORT_BIND_ATTEMPTS {
+            match self.listen(candidate, add_stream_sub) {
+                Ok(closer) => {
+                    debug!(
+                        self.logger,
+                        "Listening for clandestine traffic on extra port {}", candidate
+                    );
+                    return Some(ExtraClandestinePort {
+                        port: candidate,
+                        _closer: closer,
+                    });
+                }
+                Err(e) => {
+                    warning!(
+                        self.logger,
+                        "Could not listen on extra clandestine port {}: {}",
+                        candidate,
+                        e
+                    );
+                    candidate = (self.choose_ports)(&self.ports_in_use(candidate), 1)[0];
+                }
+            }
+        }
+        error!(
+            self.logger,
+            "Giving up on extra clandestine port {} after {} attempts",
+            port,
+            EXTRA_CLANDESTINE_PORT_BIND_ATTEMPTS
+        );
+        None
+    }
+
+    fn listen(
+        &self,
+        port: u16,
+        add_stream_sub: &Recipient<AddStreamMsg>,
+    ) -> io::Result<oneshot::Sender<()>> {
+        let mut listener_handler = self.listener_handler_factory.make();
+        listener_handler.bind_port_and_configuration(port, self.port_configuration.clone())?;
+        listener_handler.bind_subs(add_stream_sub.clone());
+        let (closer, closed) = oneshot::channel::<()>();
+        tokio::spawn(
+            listener_handler
+                .select(closed.then(|_| Ok::<(), ()>(())))
+                .then(|_| Ok(())),
+        );
+        Ok(closer)
+    }
+
+    fn ports_in_use(&self, failed_port: u16) -> Vec<u16> {
+        let mut ports = self.ports();
+        ports.extend(&self.initial_extra_ports);
+        ports.push(failed_port);
+        ports
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bootstrapper::PortConfiguration;
+    use crate::json_discriminator_factory::JsonDiscriminatorFactory;
+    use crate::listener_handler::ListenerHandler;
+    use crate::sub_lib::neighborhood::{NeighborhoodConfig, DEFAULT_RATE_PACK};
+    use crate::sub_lib::node_addr::NodeAddr;
+    use crate::test_utils::neighborhood_test_utils::MIN_HOPS_FOR_TEST;
+    use crate::test_utils::recorder::make_recorder;
+    use actix::{Actor, System};
+    use futures::{future, task};
+    use masq_lib::constants::{HIGHEST_RANDOM_CLANDESTINE_PORT, LOWEST_USABLE_INSECURE_PORT};
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use std::net::IpAddr;
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+    use tokio::prelude::Async;
+
+    struct ListenerHandlerMock {
+        port: u16,
+        bind_results: Arc<Mutex<Vec<io::Result<()>>>>,
+        bound_ports: Arc<Mutex<Vec<u16>>>,
+        closed_ports: Arc<Mutex<Vec<u16>>>,
+    }
+
+    impl ListenerHandler for ListenerHandlerMock {
+        fn bind_port_and_configuration(
+            &mut self,
+            port: u16,
+            _port_configuration: PortConfiguration,
+        ) -> io::Result<()> {
+            self.port = port;
+            let mut bind_results = self.bind_results.lock().unwrap();
+            let result = if bind_results.is_empty() {
+                Ok(())
+            } else {
+                bind_results.remove(0)
+            };
+            if result.is_ok() {
+                self.bound_ports.lock().unwrap().push(port);
+            }
+            result
+        }
+
+        fn bind_subs(&mut self, _add_stream_sub: Recipient<AddStreamMsg>) {}
+    }
+
+    impl Future for ListenerHandlerMock {
+        type Item = ();
+        type Error = ();
+
+        fn poll(&mut self) -> Result<Async<()>, ()> {
+            Ok(Async::NotReady)
+        }
+    }
+
+    impl Drop for ListenerHandlerMock {
+        fn drop(&mut self) {
+            if self.bound_ports.lock().unwrap().contains(&self.port) {
+                self.closed_ports.lock().unwrap().push(self.port);
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct ListenerHandlerFactoryMock {
+        bind_results: Arc<Mutex<Vec<io::Result<()>>>>,
+        bound_ports: Arc<Mutex<Vec<u16>>>,
+        closed_ports: Arc<Mutex<Vec<u16>>>,
+    }
+
+    impl ListenerHandlerFactory for ListenerHandlerFactoryMock {
+        fn make(&self) -> Box<dyn ListenerHandler<Item = (), Error = ()>> {
+            Box::new(ListenerHandlerMock {
+                port: 0,
+                bind_results: self.bind_results.clone(),
+                bound_ports: self.bound_ports.clone(),
+                closed_ports: self.closed_ports.clone(),
+            })
+        }
+    }
+
+    impl ListenerHandlerFactoryMock {
+        fn bind_result(self, result: io::Result<()>) -> Self {
+            self.bind_results.lock().unwrap().push(result);
+            self
+        }
+    }
+
+    fn choose_ports_from_6000(ports_in_use: &[u16], count: usize) -> Vec<u16> {
+        (6000..)
+            .filter(|port| !ports_in_use.contains(port))
+            .take(count)
+            .collect()
+    }
+
+    fn make_subject(ports: &[u16], factory: ListenerHandlerFactoryMock) -> ClandestinePortHopper {
+        let mut subject = ClandestinePortHopper::new(
+            ports,
+            PortConfiguration::new(vec![Box::new(JsonDiscriminatorFactory::new())], true),
+            None,
+        );
+        subject.listener_handler_factory = Box::new(factory);
+        subject.choose_ports = choose_ports_from_6000;
+        subject
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(EXTRA_CLANDESTINE_PORT_BIND_ATTEMPTS, 10);
+    }
+
+    #[test]
+    fn choose_extra_clandestine_ports_chooses_distinct_unused_ports() {
+        let result = choose_extra_clandestine_ports(&[1234, 2345], 5);
+
+        assert_eq!(result.len(), 5);
+        result.iter().enumerate().for_each(|(idx, port)| {
+            assert!((LOWEST_USABLE_INSECURE_PORT..HIGHEST_RANDOM_CLANDESTINE_PORT).contains(port));
+            assert!(*port != 1234 && *port != 2345);
+            assert!(!result[(idx + 1)..].contains(port));
+        });
+    }
+
+    #[test]
+    fn from_config_makes_nothing_for_a_single_port() {
+        let mut config = BootstrapperConfig::new();
+        config.neighborhood_config = NeighborhoodConfig {
+            mode: NeighborhoodMode::Standard(
+                NodeAddr::new(&IpAddr::from_str("1.2.3.4").unwrap(), &[1234]),
+                vec![],
+                DEFAULT_RATE_PACK,
+            ),
+            min_hops: MIN_HOPS_FOR_TEST,
+        };
+
+        let result = ClandestinePortHopper::from_config(&config);
+
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn from_config_takes_ports_and_rotation_from_config() {
+        let mut config = BootstrapperConfig::new();
+        config.clandestine_port_rotation_opt = Some(Duration::from_secs(600));
+        config.neighborhood_config = NeighborhoodConfig {
+            mode: NeighborhoodMode::Standard(
+                NodeAddr::new(&IpAddr::from_str("1.2.3.4").unwrap(), &[1234, 2345, 3456]),
+                vec![],
+                DEFAULT_RATE_PACK,
+            ),
+            min_hops: MIN_HOPS_FOR_TEST,
+        };
+
+        let result = ClandestinePortHopper::from_config(&config).unwrap();
+
+        assert_eq!(result.primary_port, 1234);
+        assert_eq!(result.initial_extra_ports, vec![2345, 3456]);
+        assert_eq!(
+            result.rotation_interval_opt(),
+            Some(Duration::from_secs(600))
+        );
+        assert!(result.port_configuration.is_clandestine);
+    }
+
+    #[test]
+    fn open_listens_on_initial_extra_ports() {
+        let mut system = System::new("open_listens_on_initial_extra_ports");
+        let factory = ListenerHandlerFactoryMock::default();
+        let bound_ports_arc = factory.bound_ports.clone();
+        let mut subject = make_subject(&[1234, 2345, 3456], factory);
+        let add_stream_sub = make_recorder().0.start().recipient::<AddStreamMsg>();
+
+        let result = system
+            .block_on(future::lazy(|| Ok::<_, ()>(subject.open(&add_stream_sub))))
+            .unwrap();
+
+        assert_eq!(result, vec![1234, 2345, 3456]);
+        assert_eq!(subject.ports(), vec![1234, 2345, 3456]);
+        assert_eq!(*bound_ports_arc.lock().unwrap(), vec![2345, 3456]);
+    }
+
+    #[test]
+    fn open_replaces_an_extra_port_that_cannot_be_bound() {
+        init_test_logging();
+        let mut system = System::new("open_replaces_an_extra_port_that_cannot_be_bound");
+        let factory = ListenerHandlerFactoryMock::default()
+            .bind_result(Err(io::Error::from(io::ErrorKind::AddrInUse)))
+            .bind_result(Ok(()));
+        let mut subject = make_subject(&[1234, 2345], factory);
+        let add_stream_sub = make_recorder().0.start().recipient::<AddStreamMsg>();
+
+        let result = system
+            .block_on(future::lazy(|| Ok::<_, ()>(subject.open(&add_stream_sub))))
+            .unwrap();
+
+        assert_eq!(result, vec![1234, 6000]);
+        TestLogHandler::new().exists_log_containing(
+            "WARN: ClandestinePortHopper: Could not listen on extra clandestine port 2345",
+        );
+    }
+
+    #[test]
+    fn open_gives_up_on_an_extra_port_after_too_many_failures() {
+        init_test_logging();
+        let mut system = System::new("open_gives_up_on_an_extra_port_after_too_many_failures");
+        let factory = (0..EXTRA_CLANDESTINE_PORT_BIND_ATTEMPTS)
+            .fold(ListenerHandlerFactoryMock::default(), |factory, _| {
+                factory.bind_result(Err(io::Error::from(io::ErrorKind::AddrInUse)))
+            });
+        let mut subject = make_subject(&[1234, 2345, 3456], factory);
+        let add_stream_sub = make_recorder().0.start().recipient::<AddStreamMsg>();
+
+        let result = system
+            .block_on(future::lazy(|| Ok::<_, ()>(subject.open(&add_stream_sub))))
+            .unwrap();
+
+        assert_eq!(result, vec![1234, 3456]);
+        TestLogHandler::new().exists_log_containing(
+            "ERROR: ClandestinePortHopper: Giving up on extra clandestine port 2345 after 10 attempts",
+        );
+    }
+
+    #[test]
+    fn rotate_opens_fresh_extra_ports_and_closes_old_ones() {
+        init_test_logging();
+        let mut system = System::new("rotate_opens_fresh_extra_ports_and_closes_old_ones");
+        let factory = ListenerHandlerFactoryMock::default();
+        let bound_ports_arc = factory.bound_ports.clone();
+        let closed_ports_arc = factory.closed_ports.clone();
+        let mut subject = make_subject(&[1234, 2345, 3456], factory);
+        let add_stream_sub = make_recorder().0.start().recipient::<AddStreamMsg>();
+        let result = system
+            .block_on(future::lazy(|| {
+                subject.open(&add_stream_sub);
+                Ok::<_, ()>(subject.rotate(&add_stream_sub))
+            }))
+            .unwrap();
+
+        let mut closed_ports = system
+            .block_on(future::poll_fn(|| {
+                let closed_ports = closed_ports_arc.lock().unwrap().clone();
+                if closed_ports.len() < 2 {
+                    task::current().notify();
+                    Ok::<_, ()>(Async::NotReady)
+                } else {
+                    Ok(Async::Ready(closed_ports))
+                }
+            }))
+            .unwrap();
+        assert_eq!(result, vec![1234, 6000, 6001]);
+        assert_eq!(
+            *bound_ports_arc.lock().unwrap(),
+            vec![2345, 3456, 6000, 6001]
+        );
+        closed_ports.sort_unstable();
+        assert_eq!(closed_ports, vec![2345, 3456]);
+        TestLogHandler::new().exists_log_containing(
+            "INFO: ClandestinePortHopper: Hopped clandestine ports from [1234, 2345, 3456] to [1234, 6000, 6001]",
+        );
+    }
+}
@@ -2,10 +2,12 @@
 
 use actix::Message;
 use lazy_static::lazy_static;
-use masq_lib::messages::CrashReason;
+use masq_lib::messages::{CrashReason, UiCrashReport};
 
 lazy_static! {
     static ref RECOGNIZERS: Vec<Box<dyn Recognizer>> = vec![
+        Box::new(ActorPanicRecognizer {}),
+        Box::new(NodePanicRecognizer {}),
         Box::new(ChildWaitFailureRecognizer {}),
         Box::new(NoInformationRecognizer {}),
         Box::new(UnrecognizedRecognizer {}),
@@ -13,7 +15,12 @@ lazy_static! {
 }
 
 trait Recognizer: Sync {
-    fn try_convert(&self, exit_code: Option<i32>, stderr: &Option<String>) -> Option<CrashReason>;
+    fn try_convert(
+        &self,
+        exit_code: Option<i32>,
+        stderr: &Option<String>,
+        crash_report_opt: Option<&UiCrashReport>,
+    ) -> Option<CrashReason>;
 }
 
 #[derive(Message, Clone, Debug, PartialEq, Eq)]
@@ -24,18 +31,56 @@ pub struct CrashNotification {
 }
 
 impl CrashNotification {
-    pub fn analyze(&self) -> CrashReason {
+    // The crash report, if any, must be the one the crashed process wrote about itself
+    pub fn analyze(&self, crash_report_opt: Option<&UiCrashReport>) -> CrashReason {
         let init: Option<CrashReason> = None;
         RECOGNIZERS
             .iter()
             .fold(init, |sofar, recognizer| match sofar {
                 Some(_) => sofar,
-                None => recognizer.try_convert(self.exit_code, &self.stderr),
+                None => recognizer.try_convert(self.exit_code, &self.stderr, crash_report_opt),
             })
             .expect("RECOGNIZERS isn't exhaustive")
     }
 }
 
+struct ActorPanicRecognizer {}
+
+impl Recognizer for ActorPanicRecognizer {
+    fn try_convert(
+        &self,
+        _exit_code: Option<i32>,
+        _stderr: &Option<String>,
+        crash_report_opt: Option<&UiCrashReport>,
+    ) -> Option<CrashReason> {
+        let report = crash_report_opt?;
+        report
+            .actor_opt
+            .as_ref()
+            .map(|actor| CrashReason::ActorPanic {
+                actor: actor.clone(),
+                message: report.panic_message.clone(),
+                location: report.location.clone(),
+            })
+    }
+}
+
+struct NodePanicRecognizer {}
+
+impl Recognizer for NodePanicRecognizer {
+    fn try_convert(
+        &self,
+        _exit_code: Option<i32>,
+        _stderr: &Option<String>,
+        crash_report_opt: Option<&UiCrashReport>,
+    ) -> Option<CrashReason> {
+        crash_report_opt.map(|report| CrashReason::NodePanic {
+            message: report.panic_message.clone(),
+            location: report.location.clone(),
+        })
+    }
+}
+
 struct ChildWaitFailureRecognizer {}
 
 const CHILD_WAIT_FAILURE_PREFIX: &str = "Child wait failure: ";
@@ -45,6 +90,7 @@ impl Recognizer for ChildWaitFailureRecognizer {
         &self,
         exit_code_opt: Option<i32>,
         stderr_opt: &Option<String>,
+        _crash_report_opt: Option<&UiCrashReport>,
     ) -> Option<CrashReason> {
         if exit_code_opt.is_some() {
             return None;
@@ -62,7 +108,12 @@ impl Recognizer for ChildWaitFailureRecognizer {
 struct NoInformationRecognizer {}
 
 impl Recognizer for NoInformationRecognizer {
-    fn try_convert(&self, exit_code: Option<i32>, stderr: &Option<String>) -> Option<CrashReason> {
+    fn try_convert(
+        &self,
+        exit_code: Option<i32>,
+        stderr: &Option<String>,
+        _crash_report_opt: Option<&UiCrashReport>,
+    ) -> Option<CrashReason> {
         if exit_code.is_none()
             && (stderr.is_none()
                 || stderr
@@ -82,7 +133,12 @@ impl Recognizer for NoInformationRecognizer {
 struct UnrecognizedRecognizer {}
 
 impl Recognizer for UnrecognizedRecognizer {
-    fn try_convert(&self, _exit_code: Option<i32>, stderr: &Option<String>) -> Option<CrashReason> {
+    fn try_convert(
+        &self,
+        _exit_code: Option<i32>,
+        stderr: &Option<String>,
+        _crash_report_opt: Option<&UiCrashReport>,
+    ) -> Option<CrashReason> {
         match stderr {
             Some(stderr) => Some(CrashReason::Unrecognized(stderr.clone())),
             None => Some(CrashReason::Unrecognized(String::new())),
@@ -107,7 +163,7 @@ mod tests {
             stderr: Some("Child wait failure: booga booga".to_string()),
         };
 
-        let result = subject.analyze();
+        let result = subject.analyze(None);
 
         assert_eq!(
             result,
@@ -126,7 +182,7 @@ mod tests {
                     stderr: stderr.clone(),
                 };
 
-                let result = subject.analyze();
+                let result = subject.analyze(None);
 
                 assert_eq!(
                     result,
@@ -145,11 +201,65 @@ mod tests {
             stderr: Some("unrecognizable".to_string()),
         };
 
-        let result = subject.analyze();
+        let result = subject.analyze(None);
 
         assert_eq!(
             result,
             CrashReason::Unrecognized("unrecognizable".to_string())
         )
     }
+
+    fn make_crash_report(actor_opt: Option<&str>) -> UiCrashReport {
+        UiCrashReport {
+            timestamp_s: 1_700_000_000,
+            process_id: 4321,
+            version: "0.9.0".to_string(),
+            panic_message: "Booga".to_string(),
+            location: "src/accountant/mod.rs:12:34".to_string(),
+            actor_opt: actor_opt.map(|actor| actor.to_string()),
+            backtrace: "0: rust_begin_unwind".to_string(),
+            log_lines: vec![],
+        }
+    }
+
+    #[test]
+    fn recognizes_actor_panic_from_crash_report() {
+        let subject = CrashNotification {
+            process_id: 4321,
+            exit_code: Some(101),
+            stderr: Some("ERROR: PanicHandler: Booga".to_string()),
+        };
+        let crash_report = make_crash_report(Some("ACCOUNTANT"));
+
+        let result = subject.analyze(Some(&crash_report));
+
+        assert_eq!(
+            result,
+            CrashReason::ActorPanic {
+                actor: "ACCOUNTANT".to_string(),
+                message: "Booga".to_string(),
+                location: "src/accountant/mod.rs:12:34".to_string(),
+            }
+        )
+    }
+
+    #[test]
+    fn recognizes_node_panic_from_crash_report() {
+        let subject = CrashNotification {
+            process_id: 4321,
+            exit_code: None,
+            stderr: None,
+        };
+        let crash_report = make_crash_report(None);
+
+        let result = subject.analyze(Some(&crash_report));
+
+        assert_eq!(
+            result,
+            CrashReason::NodePanic {
+                message: "Booga".to_string(),
+                location: "src/accountant/mod.rs:12:34".to_string(),
+            }
+        )
+    }
 }
@@ -2,14 +2,17 @@
 
 use crate::bootstrapper::RealUser;
 use crate::daemon::launcher::LauncherReal;
+use crate::daemon::restart_supervisor::{RestartPolicy, RestartSupervisor};
 use crate::daemon::{
     ChannelFactory, ChannelFactoryReal, Daemon, DaemonBindMessage, Launcher, Recipients,
 };
 use crate::node_configurator::node_configurator_initialization::InitializationConfig;
 use crate::node_configurator::port_is_busy;
+use crate::privilege_drop::{PrivilegeDropper, PrivilegeDropperReal};
 use crate::run_modes_factories::{DIClusteredParams, DaemonInitializer, RunModeResult};
+use crate::server_initializer::LogFileConfig;
 use crate::sub_lib::main_tools::main_with_args;
-use crate::sub_lib::ui_gateway::UiGatewayConfig;
+use crate::sub_lib::ui_gateway::{load_or_create_ui_tokens, UiGatewayConfig};
 use crate::ui_gateway::UiGateway;
 use actix::{Actor, System, SystemRunner};
 use crossbeam_channel::{unbounded, Receiver, Sender};
@@ -17,6 +20,7 @@ use flexi_logger::LevelFilter;
 use itertools::Itertools;
 use masq_lib::command::StdStreams;
 use masq_lib::shared_schema::ConfiguratorError;
+use masq_lib::ui_auth::ui_token_file_in;
 use std::collections::HashMap;
 
 use masq_lib::utils::ExpectValue;
@@ -24,16 +28,34 @@ use std::path::PathBuf;
 use std::str::FromStr;
 
 pub trait RecipientsFactory {
-    fn make(&self, launcher: Box<dyn Launcher>, ui_port: u16) -> Recipients;
+    fn make(
+        &self,
+        launcher: Box<dyn Launcher>,
+        ui_port: u16,
+        ui_token_file_opt: Option<PathBuf>,
+        restart_policy_opt: Option<RestartPolicy>,
+    ) -> Recipients;
 }
 
 #[derive(Default)]
 pub struct RecipientsFactoryReal {}
 
 impl RecipientsFactory for RecipientsFactoryReal {
-    fn make(&self, launcher: Box<dyn Launcher>, ui_port: u16) -> Recipients {
-        let ui_gateway_addr = UiGateway::new(&UiGatewayConfig { ui_port }, false).start();
-        let daemon_addr = Daemon::new(launcher).start();
+    fn make(
+        &self,
+        launcher: Box<dyn Launcher>,
+        ui_port: u16,
+        ui_token_file_opt: Option<PathBuf>,
+        restart_policy_opt: Option<RestartPolicy>,
+    ) -> Recipients {
+        let ui_gateway_config = UiGatewayConfig {
+            ui_port,
+            ui_token_file_opt,
+        };
+        let ui_gateway_addr = UiGateway::new(&ui_gateway_config, false).start();
+        let mut daemon = Daemon::new(launcher);
+        daemon.restart_supervisor = RestartSupervisor::new(restart_policy_opt);
+        let daemon_addr = daemon.start();
         Recipients {
             ui_gateway_from_sub: ui_gateway_addr.clone().recipient(),
             ui_gateway_to_sub: ui_gateway_addr.clone().recipient(),
@@ -64,6 +86,8 @@ impl ChannelFactory for ChannelFactoryReal {
 
 pub struct DaemonInitializerReal {
     config: InitializationConfig,
+    real_user: RealUser,
+    privilege_dropper: Box<dyn PrivilegeDropper>,
     channel_factory: Box<dyn ChannelFactory>,
     recipients_factory: Box<dyn RecipientsFactory>,
     rerunner: Box<dyn Rerunner>,
@@ -75,6 +99,7 @@ impl DaemonInitializer for DaemonInitializerReal {
             let message = format!("There appears to be a process already listening on port {}; are you sure there's not a Daemon already running?", self.config.ui_port);
             return Err(ConfiguratorError::required("ui-port", message.as_str()));
         }
+        self.prepare_ui_token_file()?;
         let system = System::new("daemon");
         let (sender, receiver) = self.channel_factory.make();
 
@@ -110,7 +135,7 @@ impl RerunnerReal {
 
 impl DaemonInitializerReal {
     pub fn new(
-        config: InitializationConfig,
+        mut config: InitializationConfig,
         mut params: DIClusteredParams,
     ) -> DaemonInitializerReal {
         let real_user = RealUser::new(None, None, None).populate(params.dirs_wrapper.as_ref());
@@ -136,25 +161,52 @@ impl DaemonInitializerReal {
         let real_data_dir = PathBuf::from_str(real_home_dir)
             .expectv("path string")
             .join(relative_data_dir);
+        let masq_directory = real_data_dir.join("MASQ");
+        config.ui_token_file_opt = if config.ui_authentication_off {
+            None
+        } else {
+            Some(
+                config
+                    .ui_token_file_opt
+                    .unwrap_or_else(|| ui_token_file_in(&masq_directory)),
+            )
+        };
         params.logger_initializer_wrapper.init(
-            real_data_dir.join("MASQ"),
+            masq_directory,
             &real_user,
             LevelFilter::Trace,
+            &LogFileConfig::default(),
             Some("daemon"),
         );
         DaemonInitializerReal {
             config,
+            real_user,
+            privilege_dropper: Box::new(PrivilegeDropperReal::new()),
             channel_factory: params.channel_factory,
             recipients_factory: params.recipients_factory,
             rerunner: params.rerunner,
         }
     }
 
+    // The Daemon may run as root, but the Node it launches runs as the real user and must be able
+    // to read the same tokens, so the Daemon creates the file before anyone else can.
+    fn prepare_ui_token_file(&self) -> Result<(), ConfiguratorError> {
+        if let Some(ui_token_file) = &self.config.ui_token_file_opt {
+            load_or_create_ui_tokens(ui_token_file)
+                .map_err(|e| ConfiguratorError::required("ui-token-file", &e))?;
+            self.privilege_dropper.chown(ui_token_file, &self.real_user);
+        }
+        Ok(())
+    }
+
     fn bind(&mut self, sender: Sender<HashMap<String, String>>) {
-        let launcher = LauncherReal::new(sender);
-        let recipients = self
-            .recipients_factory
-            .make(Box::new(launcher), self.config.ui_port);
+        let launcher = LauncherReal::new(sender, self.config.ui_token_file_opt.clone());
+        let recipients = self.recipients_factory.make(
+            Box::new(launcher),
+            self.config.ui_port,
+            self.config.ui_token_file_opt.clone(),
+            self.config.restart_policy_opt,
+        );
         let bind_message = DaemonBindMessage {
             to_ui_message_recipient: recipients.ui_gateway_to_sub,
             from_ui_message_recipient: recipients.ui_gateway_from_sub,
@@ -189,7 +241,10 @@ mod tests {
     use crate::node_test_utils::DirsWrapperMock;
     use crate::run_modes_factories::mocks::test_clustered_params;
     use crate::run_modes_factories::{DaemonInitializerFactory, DaemonInitializerFactoryReal};
-    use crate::server_initializer::test_utils::LoggerInitializerWrapperMock;
+    use crate::server_initializer::test_utils::{
+        LoggerInitializerWrapperMock, PrivilegeDropperMock,
+    };
+    use crate::sub_lib::ui_gateway::write_private_file;
     use crate::test_utils::recorder::{make_recorder, Recorder};
     use crate::test_utils::unshared_test_utils::ChannelFactoryMock;
     use actix::System;
@@ -197,23 +252,46 @@ mod tests {
     use masq_lib::test_utils::environment_guard::EnvironmentGuard;
     use masq_lib::test_utils::fake_stream_holder::FakeStreamHolder;
     use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use masq_lib::ui_auth::UiTokens;
     use masq_lib::utils::{find_free_port, localhost, slice_of_strs_to_vec_of_strings};
     use std::cell::RefCell;
     use std::iter::FromIterator;
     use std::net::{SocketAddr, TcpListener};
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
     use std::ptr::addr_of;
     use std::str::FromStr;
     use std::sync::{Arc, Mutex};
 
+    type MakeParamsArc = Arc<
+        Mutex<
+            Vec<(
+                Box<dyn Launcher>,
+                u16,
+                Option<PathBuf>,
+                Option<RestartPolicy>,
+            )>,
+        >,
+    >;
+
     struct RecipientsFactoryMock {
-        make_params: Arc<Mutex<Vec<(Box<dyn Launcher>, u16)>>>,
+        make_params: MakeParamsArc,
         make_results: RefCell<Vec<Recipients>>,
     }
 
     impl RecipientsFactory for RecipientsFactoryMock {
-        fn make(&self, launcher: Box<dyn Launcher>, ui_port: u16) -> Recipients {
-            self.make_params.lock().unwrap().push((launcher, ui_port));
+        fn make(
+            &self,
+            launcher: Box<dyn Launcher>,
+            ui_port: u16,
+            ui_token_file_opt: Option<PathBuf>,
+            restart_policy_opt: Option<RestartPolicy>,
+        ) -> Recipients {
+            self.make_params.lock().unwrap().push((
+                launcher,
+                ui_port,
+                ui_token_file_opt,
+                restart_policy_opt,
+            ));
             self.make_results.borrow_mut().remove(0)
         }
     }
@@ -226,6 +304,11 @@ mod tests {
             }
         }
 
+        fn make_params(mut self, params: &MakeParamsArc) -> Self {
+            self.make_params = params.clone();
+            self
+        }
+
         fn make_result(self, result: Recipients) -> Self {
             self.make_results.borrow_mut().push(result);
             self
@@ -328,9 +411,19 @@ mod tests {
             .data_dir_result(Some(home_dir.join("data")));
         let logger_initializer_wrapper = LoggerInitializerWrapperMock::new();
         let port = find_free_port();
-        let config = InitializationConfig { ui_port: port };
+        let restart_policy = RestartPolicy::try_from("5|10|600|3600").unwrap();
+        let ui_token_file = home_dir.join("ui_tokens");
+        let config = InitializationConfig {
+            ui_port: port,
+            ui_token_file_opt: Some(ui_token_file.clone()),
+            ui_authentication_off: false,
+            restart_policy_opt: Some(restart_policy),
+        };
         let channel_factory = ChannelFactoryMock::new();
-        let addr_factory = RecipientsFactoryMock::new().make_result(recipients);
+        let make_params_arc = Arc::new(Mutex::new(vec![]));
+        let addr_factory = RecipientsFactoryMock::new()
+            .make_params(&make_params_arc)
+            .make_result(recipients);
         let rerunner = RerunnerMock::new();
         let clustered_params = DIClusteredParams {
             dirs_wrapper: Box::new(dirs_wrapper),
@@ -351,6 +444,127 @@ mod tests {
         let _ = daemon_recording.get_record::<DaemonBindMessage>(0);
         assert_eq!(ui_gateway_recording.len(), 1);
         assert_eq!(daemon_recording.len(), 1);
+        let make_params = make_params_arc.lock().unwrap();
+        assert_eq!(make_params.len(), 1);
+        assert_eq!(make_params[0].1, port);
+        assert_eq!(make_params[0].2, Some(ui_token_file));
+        assert_eq!(make_params[0].3, Some(restart_policy));
+    }
+
+    fn make_subject_for_ui_token_file(
+        home_dir: &Path,
+        ui_token_file_opt: Option<PathBuf>,
+        ui_authentication_off: bool,
+    ) -> DaemonInitializerReal {
+        let dirs_wrapper = DirsWrapperMock::new()
+            .home_dir_result(Some(home_dir.to_path_buf()))
+            .data_dir_result(Some(home_dir.join("data")));
+        let clustered_params = DIClusteredParams {
+            dirs_wrapper: Box::new(dirs_wrapper),
+            logger_initializer_wrapper: Box::new(LoggerInitializerWrapperMock::new()),
+            channel_factory: Box::new(ChannelFactoryMock::new()),
+            recipients_factory: Box::new(RecipientsFactoryMock::new()),
+            rerunner: Box::new(RerunnerMock::new()),
+        };
+        DaemonInitializerReal::new(
+            InitializationConfig {
+                ui_port: find_free_port(),
+                ui_token_file_opt,
+                ui_authentication_off,
+                restart_policy_opt: None,
+            },
+            clustered_params,
+        )
+    }
+
+    #[test]
+    fn new_puts_the_ui_token_file_in_the_masq_directory_by_default() {
+        let home_dir = ensure_node_home_directory_exists(
+            "daemon_initializer",
+            "new_puts_the_ui_token_file_in_the_masq_directory_by_default",
+        );
+
+        let subject = make_subject_for_ui_token_file(&home_dir, None, false);
+
+        assert_eq!(
+            subject.config.ui_token_file_opt,
+            Some(home_dir.join("data").join("MASQ").join("ui_tokens"))
+        );
+    }
+
+    #[test]
+    fn new_drops_the_ui_token_file_when_ui_authentication_is_off() {
+        let home_dir = ensure_node_home_directory_exists(
+            "daemon_initializer",
+            "new_drops_the_ui_token_file_when_ui_authentication_is_off",
+        );
+
+        let subject =
+            make_subject_for_ui_token_file(&home_dir, Some(home_dir.join("ui_tokens")), true);
+
+        assert_eq!(subject.config.ui_token_file_opt, None);
+    }
+
+    #[test]
+    fn prepare_ui_token_file_creates_the_file_and_hands_it_to_the_real_user() {
+        let home_dir = ensure_node_home_directory_exists(
+            "daemon_initializer",
+            "prepare_ui_token_file_creates_the_file_and_hands_it_to_the_real_user",
+        );
+        let ui_token_file = home_dir.join("ui_tokens");
+        let chown_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject =
+            make_subject_for_ui_token_file(&home_dir, Some(ui_token_file.clone()), false);
+        subject.privilege_dropper =
+            Box::new(PrivilegeDropperMock::new().chown_params(&chown_params_arc));
+
+        let result = subject.prepare_ui_token_file();
+
+        assert_eq!(result, Ok(()));
+        assert!(UiTokens::read_from(&ui_token_file).is_ok());
+        let chown_params = chown_params_arc.lock().unwrap();
+        assert_eq!(*chown_params, vec![(ui_token_file, subject.real_user)]);
+    }
+
+    #[test]
+    fn prepare_ui_token_file_does_nothing_when_ui_authentication_is_off() {
+        let home_dir = ensure_node_home_directory_exists(
+            "daemon_initializer",
+            "prepare_ui_token_file_does_nothing_when_ui_authentication_is_off",
+        );
+        let chown_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = make_subject_for_ui_token_file(&home_dir, None, true);
+        subject.privilege_dropper =
+            Box::new(PrivilegeDropperMock::new().chown_params(&chown_params_arc));
+
+        let result = subject.prepare_ui_token_file();
+
+        assert_eq!(result, Ok(()));
+        assert!(chown_params_arc.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn go_complains_about_a_bad_ui_token_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "daemon_initializer",
+            "go_complains_about_a_bad_ui_token_file",
+        );
+        let ui_token_file = home_dir.join("ui_tokens");
+        write_private_file(&ui_token_file, "admin=booga\n").unwrap();
+        let mut subject =
+            make_subject_for_ui_token_file(&home_dir, Some(ui_token_file.clone()), false);
+        subject.privilege_dropper = Box::new(PrivilegeDropperMock::new());
+        let mut holder = FakeStreamHolder::new();
+
+        let result = subject.go(&mut holder.streams(), &[]);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "ui-token-file",
+                &format!("UI token file {:?} has no 'read-only' token", ui_token_file)
+            ))
+        );
     }
 
     #[test]
@@ -366,7 +580,12 @@ mod tests {
             .data_dir_result(Some(home_dir.join("data")));
         let logger_initializer_wrapper = LoggerInitializerWrapperMock::new();
         let port = find_free_port();
-        let config = InitializationConfig { ui_port: port };
+        let config = InitializationConfig {
+            ui_port: port,
+            ui_token_file_opt: None,
+            ui_authentication_off: true,
+            restart_policy_opt: None,
+        };
         let (sender, receiver) = unbounded();
         let channel_factory = ChannelFactoryMock::new();
         let addr_factory = RecipientsFactoryMock::new();
@@ -421,8 +640,15 @@ mod tests {
             recipients_factory: Box::new(RecipientsFactoryMock::new()),
             rerunner: Box::new(RerunnerMock::new()),
         };
-        let mut subject =
-            DaemonInitializerReal::new(InitializationConfig { ui_port: port }, clustered_params);
+        let mut subject = DaemonInitializerReal::new(
+            InitializationConfig {
+                ui_port: port,
+                ui_token_file_opt: None,
+                ui_authentication_off: true,
+                restart_policy_opt: None,
+            },
+            clustered_params,
+        );
         let mut holder = FakeStreamHolder::new();
 
         let result = subject.go(&mut holder.streams(), &[]);
@@ -5,9 +5,11 @@ use crate::daemon::launch_verifier::LaunchVerification::{
 };
 use masq_lib::logger::Logger;
 use masq_lib::messages::NODE_UI_PROTOCOL;
+use masq_lib::ui_auth::{ui_token_headers, UiTokens};
 use masq_lib::utils::ExpectValue;
 use std::cell::RefCell;
 use std::net::TcpStream;
+use std::path::PathBuf;
 use std::thread;
 use std::time::Duration;
 use sysinfo::{ProcessExt, ProcessStatus, Signal, SystemExt};
@@ -31,6 +33,7 @@ pub trait VerifierTools {
 
 pub struct VerifierToolsReal {
     client_builder: RefCell<Box<dyn ClientBuilderWrapper>>,
+    ui_token_file_opt: Option<PathBuf>,
     logger: Logger,
 }
 
@@ -42,6 +45,16 @@ impl VerifierTools for VerifierToolsReal {
             panic!("client builder: {:?}", e)
         }
         client_builder_ref.add_protocol(NODE_UI_PROTOCOL);
+        if let Some(ui_token_file) = &self.ui_token_file_opt {
+            // Read on every attempt, so that tokens rotated by hand are picked up
+            match UiTokens::read_from(ui_token_file) {
+                Ok(ui_tokens) => client_builder_ref.add_ui_token(&ui_tokens.admin),
+                Err(e) => {
+                    error!(self.logger, "Can't verify launch: {}", e);
+                    return false;
+                }
+            }
+        }
         match client_builder_ref.connect_insecure() {
             Ok(mut client) => client.send_message(OwnedMessage::Close(None)).is_ok(),
             Err(_) => false,
@@ -86,10 +99,16 @@ impl VerifierToolsReal {
     pub fn new() -> Self {
         Self {
             client_builder: RefCell::new(Box::new(ClientBuilderWrapperReal::default())),
+            ui_token_file_opt: None,
             logger: Logger::new("VerifierTools"),
         }
     }
 
+    pub fn with_ui_token_file(mut self, ui_token_file_opt: Option<PathBuf>) -> Self {
+        self.ui_token_file_opt = ui_token_file_opt;
+        self
+    }
+
     fn system() -> sysinfo::System {
         let mut system: sysinfo::System = sysinfo::System::new_all();
         system.refresh_processes();
@@ -167,8 +186,12 @@ impl LaunchVerifier for LaunchVerifierReal {
 }
 
 impl LaunchVerifierReal {
-    pub fn new() -> Self {
-        Self::default()
+    pub fn new(ui_token_file_opt: Option<PathBuf>) -> Self {
+        Self {
+            verifier_tools: Box::new(
+                VerifierToolsReal::new().with_ui_token_file(ui_token_file_opt),
+            ),
+        }
     }
 
     fn await_ui_connection(&self, ui_port: u16) -> bool {
@@ -217,6 +240,7 @@ impl ClientWrapper for ClientWrapperReal {
 pub trait ClientBuilderWrapper {
     fn initiate_client_builder(&mut self, address: &str) -> Result<(), ParseError>;
     fn add_protocol(&self, protocol: &str);
+    fn add_ui_token(&self, token: &str);
     fn connect_insecure(&mut self) -> WebSocketResult<Box<dyn ClientWrapper>>;
 }
 
@@ -241,6 +265,16 @@ impl ClientBuilderWrapper for ClientBuilderWrapperReal<'_> {
         self.builder_opt.replace(Some(updated_builder));
     }
 
+    fn add_ui_token(&self, token: &str) {
+        let updated_builder = self
+            .builder_opt
+            .borrow_mut()
+            .take()
+            .expectv("client builder")
+            .custom_headers(&ui_token_headers(token));
+        self.builder_opt.replace(Some(updated_builder));
+    }
+
     fn connect_insecure(&mut self) -> WebSocketResult<Box<dyn ClientWrapper>> {
         self.builder_opt
             .borrow_mut()
@@ -258,6 +292,8 @@ mod tests {
         CleanFailure, InterventionRequired, Launched,
     };
     use crate::daemon::mocks::{ClientBuilderWrapperMock, ClientWrapperMock, VerifierToolsMock};
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
     use masq_lib::utils::find_free_port;
     use std::process::{Child, Command};
     use std::sync::{Arc, Mutex};
@@ -283,7 +319,7 @@ mod tests {
             .can_connect_to_ui_gateway_result(false)
             .can_connect_to_ui_gateway_result(false)
             .can_connect_to_ui_gateway_result(true);
-        let mut subject = LaunchVerifierReal::new();
+        let mut subject = LaunchVerifierReal::new(None);
         subject.verifier_tools = Box::new(tools);
 
         let result = subject.verify_launch(1234, 4321);
@@ -311,7 +347,7 @@ mod tests {
             tools = tools.can_connect_to_ui_gateway_result(false);
         }
         tools = tools.process_is_running_result(false);
-        let mut subject = LaunchVerifierReal::new();
+        let mut subject = LaunchVerifierReal::new(None);
         subject.verifier_tools = Box::new(tools);
 
         let result = subject.verify_launch(1234, 4321);
@@ -344,7 +380,7 @@ mod tests {
             .process_is_running_result(true)
             .process_is_running_result(true)
             .process_is_running_result(false);
-        let mut subject = LaunchVerifierReal::new();
+        let mut subject = LaunchVerifierReal::new(None);
         subject.verifier_tools = Box::new(tools);
 
         let result = subject.verify_launch(1234, 4321);
@@ -379,7 +415,7 @@ mod tests {
         for _ in 0..death_check_count {
             tools = tools.process_is_running_result(true);
         }
-        let mut subject = LaunchVerifierReal::new();
+        let mut subject = LaunchVerifierReal::new(None);
         subject.verifier_tools = Box::new(tools);
 
         let result = subject.verify_launch(1234, 4321);
@@ -471,6 +507,56 @@ mod tests {
         assert_eq!(result, false);
     }
 
+    #[test]
+    fn can_connect_to_ui_gateway_presents_the_admin_token() {
+        let home_dir = ensure_node_home_directory_exists(
+            "launch_verifier",
+            "can_connect_to_ui_gateway_presents_the_admin_token",
+        );
+        let ui_token_file = home_dir.join("ui_tokens");
+        std::fs::write(&ui_token_file, "admin=booga\nread-only=wooga\n").unwrap();
+        let add_ui_token_params_arc = Arc::new(Mutex::new(vec![]));
+        let subject = VerifierToolsReal::new().with_ui_token_file(Some(ui_token_file));
+        let client = ClientWrapperMock::default().send_message_result(Ok(()));
+        let client_builder = ClientBuilderWrapperMock::default()
+            .initiate_client_builder_result(Ok(()))
+            .add_ui_token_params(&add_ui_token_params_arc)
+            .connect_insecure_result(Ok(Box::new(client)));
+        subject.client_builder.replace(Box::new(client_builder));
+
+        let result = subject.can_connect_to_ui_gateway(5678);
+
+        assert!(result);
+        let add_ui_token_params = add_ui_token_params_arc.lock().unwrap();
+        assert_eq!(*add_ui_token_params, vec!["booga".to_string()]);
+    }
+
+    #[test]
+    fn can_connect_to_ui_gateway_fails_without_a_readable_token_file() {
+        init_test_logging();
+        let home_dir = ensure_node_home_directory_exists(
+            "launch_verifier",
+            "can_connect_to_ui_gateway_fails_without_a_readable_token_file",
+        );
+        let ui_token_file = home_dir.join("ui_tokens");
+        let add_ui_token_params_arc = Arc::new(Mutex::new(vec![]));
+        let subject = VerifierToolsReal::new().with_ui_token_file(Some(ui_token_file.clone()));
+        let client_builder = ClientBuilderWrapperMock::default()
+            .initiate_client_builder_result(Ok(()))
+            .add_ui_token_params(&add_ui_token_params_arc);
+        subject.client_builder.replace(Box::new(client_builder));
+
+        let result = subject.can_connect_to_ui_gateway(5678);
+
+        assert!(!result);
+        let add_ui_token_params = add_ui_token_params_arc.lock().unwrap();
+        assert!(add_ui_token_params.is_empty());
+        TestLogHandler::new().exists_log_containing(&format!(
+            "ERROR: VerifierTools: Can't verify launch: Couldn't read UI token file {:?}",
+            ui_token_file
+        ));
+    }
+
     #[test]
     fn client_builder_handles_initialization_error() {
         let url_address = "foolish";
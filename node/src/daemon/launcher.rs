@@ -150,6 +150,7 @@ impl ExecerReal {
 pub struct LauncherReal {
     execer: Box<dyn Execer>,
     verifier: Box<dyn LaunchVerifier>,
+    ui_token_file_opt: Option<PathBuf>,
 }
 
 impl Launcher for LauncherReal {
@@ -160,6 +161,20 @@ impl Launcher for LauncherReal {
     ) -> Result<Option<LaunchSuccess>, String> {
         let redirect_ui_port = find_free_port();
         params.insert("ui-port".to_string(), format!("{}", redirect_ui_port));
+        // The Node must demand the same tokens as the Daemon, or none if the Daemon demands none
+        match &self.ui_token_file_opt {
+            Some(ui_token_file) => {
+                params.remove("ui-authentication");
+                params.insert(
+                    "ui-token-file".to_string(),
+                    ui_token_file.to_string_lossy().to_string(),
+                );
+            }
+            None => {
+                params.remove("ui-token-file");
+                params.insert("ui-authentication".to_string(), "off".to_string());
+            }
+        }
         let params_vec = params
             .into_iter()
             .sorted_by_key(|(n, _)| n.clone())
@@ -181,10 +196,14 @@ impl Launcher for LauncherReal {
 
 impl LauncherReal {
     // _sender is needed for the not-Windows side; it's not used here
-    pub fn new(_sender: Sender<HashMap<String, String>>) -> Self {
+    pub fn new(
+        _sender: Sender<HashMap<String, String>>,
+        ui_token_file_opt: Option<PathBuf>,
+    ) -> Self {
         Self {
             execer: Box::new(ExecerReal::new()),
-            verifier: Box::new(LaunchVerifierReal::new()),
+            verifier: Box::new(LaunchVerifierReal::new(ui_token_file_opt.clone())),
+            ui_token_file_opt,
         }
     }
 }
@@ -442,7 +461,7 @@ mod tests {
         let verifier = LaunchVerifierMock::new()
             .verify_launch_params(&verify_launch_params_arc)
             .verify_launch_result(Launched);
-        let mut subject = LauncherReal::new(unbounded().0);
+        let mut subject = LauncherReal::new(unbounded().0, None);
         subject.execer = Box::new(execer);
         subject.verifier = Box::new(verifier);
         let params = HashMap::from_iter(
@@ -469,6 +488,8 @@ mod tests {
             vec![&vec![
                 "--name".to_string(),
                 "value".to_string(),
+                "--ui-authentication".to_string(),
+                "off".to_string(),
                 "--ui-port".to_string(),
                 format!("{}", result.redirect_ui_port),
             ]]
@@ -490,6 +511,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn launch_hands_the_ui_token_file_to_the_node() {
+        let (ui_gateway, _, _) = make_recorder();
+        let crashed_recipient = ui_gateway.start().recipient();
+        let exec_params_arc = Arc::new(Mutex::new(vec![]));
+        let execer = ExecerMock::new()
+            .exec_params(&exec_params_arc)
+            .exec_result(Ok(1234));
+        let verifier = LaunchVerifierMock::new().verify_launch_result(Launched);
+        let mut subject =
+            LauncherReal::new(unbounded().0, Some(PathBuf::from("/home/booga/ui_tokens")));
+        subject.execer = Box::new(execer);
+        subject.verifier = Box::new(verifier);
+
+        let params = HashMap::from_iter(vec![("ui-authentication".to_string(), "off".to_string())]);
+
+        let result = subject.launch(params, crashed_recipient).unwrap().unwrap();
+
+        let exec_params = exec_params_arc.lock().unwrap();
+        assert_eq!(
+            (*exec_params)[0].0,
+            vec![
+                "--ui-port".to_string(),
+                format!("{}", result.redirect_ui_port),
+                "--ui-token-file".to_string(),
+                "/home/booga/ui_tokens".to_string(),
+            ]
+        );
+    }
+
     #[test]
     fn launch_calls_execer_and_returns_failure() {
         let (ui_gateway, _, _) = make_recorder();
@@ -499,7 +550,7 @@ mod tests {
             .exec_params(&exec_params_arc)
             .exec_result(Err("Booga!".to_string()));
         let verifier = LaunchVerifierMock::new();
-        let mut subject = LauncherReal::new(unbounded().0);
+        let mut subject = LauncherReal::new(unbounded().0, None);
         subject.execer = Box::new(execer);
         subject.verifier = Box::new(verifier);
         let params = HashMap::from_iter(
@@ -524,7 +575,7 @@ mod tests {
         let crashed_recipient = ui_gateway.start().recipient();
         let execer = ExecerMock::new().exec_result(Ok(1234));
         let verifier = LaunchVerifierMock::new().verify_launch_result(CleanFailure);
-        let mut subject = LauncherReal::new(unbounded().0);
+        let mut subject = LauncherReal::new(unbounded().0, None);
         subject.execer = Box::new(execer);
         subject.verifier = Box::new(verifier);
 
@@ -545,7 +596,7 @@ mod tests {
         let crashed_recipient = ui_gateway.start().recipient();
         let execer = ExecerMock::new().exec_result(Ok(1234));
         let verifier = LaunchVerifierMock::new().verify_launch_result(DirtyFailure);
-        let mut subject = LauncherReal::new(unbounded().0);
+        let mut subject = LauncherReal::new(unbounded().0, None);
         subject.execer = Box::new(execer);
         subject.verifier = Box::new(verifier);
 
@@ -568,7 +619,7 @@ mod tests {
         let crashed_recipient = ui_gateway.start().recipient();
         let execer = ExecerMock::new().exec_result(Ok(1234));
         let verifier = LaunchVerifierMock::new().verify_launch_result(InterventionRequired);
-        let mut subject = LauncherReal::new(unbounded().0);
+        let mut subject = LauncherReal::new(unbounded().0, None);
         subject.execer = Box::new(execer);
         subject.verifier = Box::new(verifier);
 
@@ -156,6 +156,7 @@ pub struct ClientBuilderWrapperMock {
     initiate_client_builder_params: Arc<Mutex<Vec<String>>>,
     initiate_client_builder_result: RefCell<Vec<Result<(), ParseError>>>,
     add_protocol_params: Arc<Mutex<Vec<String>>>,
+    add_ui_token_params: Arc<Mutex<Vec<String>>>,
     connect_insecure_result: RefCell<Vec<Result<Box<dyn ClientWrapper>, WebSocketError>>>,
 }
 
@@ -175,6 +176,13 @@ impl ClientBuilderWrapper for ClientBuilderWrapperMock {
             .push(protocol.to_string())
     }
 
+    fn add_ui_token(&self, token: &str) {
+        self.add_ui_token_params
+            .lock()
+            .unwrap()
+            .push(token.to_string())
+    }
+
     fn connect_insecure(&mut self) -> WebSocketResult<Box<dyn ClientWrapper>> {
         self.connect_insecure_result.borrow_mut().remove(0)
     }
@@ -197,6 +205,11 @@ impl ClientBuilderWrapperMock {
         self
     }
 
+    pub fn add_ui_token_params(mut self, params: &Arc<Mutex<Vec<String>>>) -> Self {
+        self.add_ui_token_params = params.clone();
+        self
+    }
+
     pub fn connect_insecure_result(
         self,
         result: Result<Box<dyn ClientWrapper>, WebSocketError>,
@@ -5,6 +5,7 @@ pub mod daemon_initializer;
 pub mod dns_inspector;
 pub mod launch_verifier;
 mod launcher;
+pub mod restart_supervisor;
 mod setup_reporter;
 
 #[cfg(test)]
@@ -12,11 +13,13 @@ mod mocks;
 
 use crate::daemon::crash_notification::CrashNotification;
 use crate::daemon::launch_verifier::{VerifierTools, VerifierToolsReal};
+use crate::daemon::restart_supervisor::{RestartDecision, RestartSupervisor};
 use crate::daemon::setup_reporter::{SetupCluster, SetupReporter, SetupReporterReal};
 use crate::node_configurator::DirsWrapperReal;
+use crate::sub_lib::crash_report::read_crash_report;
 use crate::sub_lib::utils::NODE_MAILBOX_CAPACITY;
 use actix::Recipient;
-use actix::{Actor, Context, Handler, Message};
+use actix::{Actor, AsyncContext, Context, Handler, Message};
 use crossbeam_channel::{Receiver, Sender};
 use itertools::Itertools;
 use lazy_static::lazy_static;
@@ -24,9 +27,10 @@ use masq_lib::constants::{NODE_ALREADY_RUNNING_ERROR, NODE_LAUNCH_ERROR, NODE_NO
 use masq_lib::logger::Logger;
 use masq_lib::messages::UiSetupResponseValueStatus::{Configured, Set};
 use masq_lib::messages::{
-    FromMessageBody, ToMessageBody, UiNodeCrashedBroadcast, UiRedirect, UiSetupBroadcast,
-    UiSetupRequest, UiSetupResponse, UiSetupResponseValue, UiStartOrder, UiStartResponse,
-    UiUndeliveredFireAndForget,
+    CrashReason, FromMessageBody, ToMessageBody, UiCrashHistoryRequest, UiCrashHistoryResponse,
+    UiCrashReport, UiCrashReportRequest, UiCrashReportResponse, UiNodeCrashedBroadcast,
+    UiNodeRestartedBroadcast, UiRedirect, UiSetupBroadcast, UiSetupRequest, UiSetupResponse,
+    UiSetupResponseValue, UiStartOrder, UiStartResponse, UiUndeliveredFireAndForget,
 };
 use masq_lib::shared_schema::ConfiguratorError;
 use masq_lib::ui_gateway::MessagePath::{Conversation, FireAndForget};
@@ -35,6 +39,8 @@ use masq_lib::ui_gateway::{
     MessageBody, MessagePath, MessageTarget, NodeFromUiMessage, NodeToUiMessage,
 };
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
 
 pub struct Recipients {
     ui_gateway_from_sub: Recipient<NodeFromUiMessage>,
@@ -96,6 +102,10 @@ pub struct DaemonBindMessage {
     pub crash_notification_recipient: Recipient<CrashNotification>, // the Daemon itself, for crash notifications
 }
 
+// Sent by the Daemon to itself once the backoff after a crash has run out
+#[derive(Message, Debug, PartialEq, Eq, Clone)]
+pub struct RestartNode {}
+
 pub struct Daemon {
     launcher: Box<dyn Launcher>,
     params: SetupCluster,
@@ -105,6 +115,7 @@ pub struct Daemon {
     node_ui_port: Option<u16>,
     verifier_tools: Box<dyn VerifierTools>,
     setup_reporter: Box<dyn SetupReporter>,
+    restart_supervisor: RestartSupervisor,
     logger: Logger,
 }
 
@@ -134,6 +145,10 @@ impl Handler<NodeFromUiMessage> for Daemon {
             self.handle_setup(client_id, context_id, setup_request);
         } else if let Ok((_, context_id)) = UiStartOrder::fmb(msg.body.clone()) {
             self.handle_start_order(client_id, context_id);
+        } else if let Ok((_, context_id)) = UiCrashHistoryRequest::fmb(msg.body.clone()) {
+            self.handle_crash_history(client_id, context_id);
+        } else if let Ok((_, context_id)) = UiCrashReportRequest::fmb(msg.body.clone()) {
+            self.handle_crash_report(client_id, context_id);
         } else {
             self.handle_unexpected_message(client_id, msg.body);
         }
@@ -144,13 +159,27 @@ impl Handler<NodeFromUiMessage> for Daemon {
 impl Handler<CrashNotification> for Daemon {
     type Result = ();
 
-    fn handle(&mut self, msg: CrashNotification, _ctx: &mut Self::Context) -> Self::Result {
+    fn handle(&mut self, msg: CrashNotification, ctx: &mut Self::Context) -> Self::Result {
         debug!(&self.logger, "Handling CrashNotification");
-        self.handle_crash_notification(msg);
+        if let Some(delay) = self.handle_crash_notification(msg) {
+            ctx.notify_later(RestartNode {}, delay);
+        }
         debug!(&self.logger, "CrashNotification handled");
     }
 }
 
+impl Handler<RestartNode> for Daemon {
+    type Result = ();
+
+    fn handle(&mut self, _msg: RestartNode, ctx: &mut Self::Context) -> Self::Result {
+        debug!(&self.logger, "Handling RestartNode");
+        if let Some(delay) = self.handle_restart_node() {
+            ctx.notify_later(RestartNode {}, delay);
+        }
+        debug!(&self.logger, "RestartNode handled");
+    }
+}
+
 impl Daemon {
     pub fn new(launcher: Box<dyn Launcher>) -> Daemon {
         Daemon {
@@ -162,6 +191,7 @@ impl Daemon {
             node_ui_port: None,
             verifier_tools: Box::new(VerifierToolsReal::new()),
             setup_reporter: Box::new(SetupReporterReal::new(Box::new(DirsWrapperReal::default()))),
+            restart_supervisor: RestartSupervisor::new(None),
             logger: Logger::new("Daemon"),
         }
     }
@@ -207,16 +237,13 @@ impl Daemon {
                 },
             ),
             None => match self.launcher.launch(
-                self.params
-                    .iter()
-                    .filter(|(_, v)| v.status == Set || v.status == Configured)
-                    .map(|(k, v)| (k.to_string(), v.value.to_string()))
-                    .collect(),
+                self.launch_params(),
                 self.crash_notification_sub.clone().expect("Daemon unbound"),
             ) {
                 Ok(Some(success)) => {
                     self.node_process_id = Some(success.new_process_id);
                     self.node_ui_port = Some(success.redirect_ui_port);
+                    self.restart_supervisor.reset();
                     self.respond_to_ui(
                         client_id,
                         UiStartResponse {
@@ -270,21 +297,147 @@ impl Daemon {
         }
     }
 
-    fn handle_crash_notification(&mut self, msg: CrashNotification) {
+    fn handle_crash_history(&self, client_id: u64, context_id: u64) {
+        self.respond_to_ui(
+            client_id,
+            UiCrashHistoryResponse {
+                restart_policy_opt: self
+                    .restart_supervisor
+                    .policy_opt()
+                    .map(|policy| policy.to_string()),
+                crashes: self.restart_supervisor.history(),
+            }
+            .tmb(context_id),
+        )
+    }
+
+    fn handle_crash_report(&self, client_id: u64, context_id: u64) {
+        self.respond_to_ui(
+            client_id,
+            UiCrashReportResponse {
+                report_opt: self.read_crash_report(),
+            }
+            .tmb(context_id),
+        )
+    }
+
+    fn handle_crash_notification(&mut self, msg: CrashNotification) -> Option<Duration> {
         if self.node_ui_port.is_some() || self.node_process_id.is_some() {
             self.node_process_id = None;
             self.node_ui_port = None;
+            let crash_report_opt = self
+                .read_crash_report()
+                .filter(|report| report.process_id == msg.process_id);
+            let crash_reason = msg.analyze(crash_report_opt.as_ref());
             self.send_ui_message(
                 UiNodeCrashedBroadcast {
                     process_id: msg.process_id,
-                    crash_reason: msg.analyze(),
+                    crash_reason: crash_reason.clone(),
                 }
                 .tmb(0),
                 MessageTarget::AllClients,
             );
+            let decision = self.restart_supervisor.on_crash(
+                SystemTime::now(),
+                msg.process_id,
+                msg.exit_code,
+                crash_reason,
+            );
+            self.delay_from_decision(decision)
+        } else {
+            None
+        }
+    }
+
+    fn handle_restart_node(&mut self) -> Option<Duration> {
+        if self.port_if_node_is_running().is_some() {
+            info!(
+                &self.logger,
+                "Node was started by hand before its scheduled restart; not restarting"
+            );
+            return None;
+        }
+        let now = SystemTime::now();
+        let restart_count = self.restart_supervisor.on_restart(now);
+        let crashed_process_id = self
+            .restart_supervisor
+            .history()
+            .last()
+            .map(|record| record.process_id)
+            .unwrap_or(0);
+        match self.launcher.launch(
+            self.launch_params(),
+            self.crash_notification_sub.clone().expect("Daemon unbound"),
+        ) {
+            Ok(Some(success)) => {
+                info!(
+                    &self.logger,
+                    "Restarted crashed Node as process {} (restart {} within the crash-loop window)",
+                    success.new_process_id,
+                    restart_count
+                );
+                self.node_process_id = Some(success.new_process_id);
+                self.node_ui_port = Some(success.redirect_ui_port);
+                self.send_ui_message(
+                    UiNodeRestartedBroadcast {
+                        crashed_process_id,
+                        new_process_id: success.new_process_id,
+                        redirect_ui_port: success.redirect_ui_port,
+                        restart_count,
+                    }
+                    .tmb(0),
+                    MessageTarget::AllClients,
+                );
+                None
+            }
+            Ok(None) => None,
+            Err(e) => {
+                error!(&self.logger, "Could not restart Node: {}", e);
+                let decision = self.restart_supervisor.on_crash(
+                    now,
+                    crashed_process_id,
+                    None,
+                    CrashReason::Unrecognized(format!("Could not restart Node: {}", e)),
+                );
+                self.delay_from_decision(decision)
+            }
         }
     }
 
+    fn delay_from_decision(&self, decision: RestartDecision) -> Option<Duration> {
+        match decision {
+            RestartDecision::RestartAfter(delay) => {
+                info!(
+                    &self.logger,
+                    "Node will be restarted in {} seconds",
+                    delay.as_secs()
+                );
+                Some(delay)
+            }
+            RestartDecision::CrashLoop => {
+                error!(
+                    &self.logger,
+                    "Node is crash-looping; giving up on restarting it until it is started by hand"
+                );
+                None
+            }
+            RestartDecision::NoRestartPolicy | RestartDecision::CleanExit => None,
+        }
+    }
+
+    fn read_crash_report(&self) -> Option<UiCrashReport> {
+        let data_directory = self.params.get("data-directory")?;
+        read_crash_report(&PathBuf::from(&data_directory.value))
+    }
+
+    fn launch_params(&self) -> HashMap<String, String> {
+        self.params
+            .iter()
+            .filter(|(_, v)| v.status == Set || v.status == Configured)
+            .map(|(k, v)| (k.to_string(), v.value.to_string()))
+            .collect()
+    }
+
     fn port_if_node_is_running(&mut self) -> Option<u16> {
         if let Some(process_id) = self.node_process_id {
             if self.verifier_tools.process_is_running(process_id) {
@@ -431,9 +584,12 @@ mod tests {
     use super::*;
     use crate::daemon::crash_notification::CrashNotification;
     use crate::daemon::mocks::VerifierToolsMock;
+    use crate::daemon::restart_supervisor::RestartPolicy;
     use crate::daemon::setup_reporter::{setup_cluster_from, SetupCluster};
     use crate::daemon::LaunchSuccess;
+    use crate::sub_lib::crash_report::write_crash_report;
     use crate::test_utils::recorder::make_recorder;
+    use crate::test_utils::recorder_stop_conditions::{StopCondition, StopConditions};
     use crate::test_utils::unshared_test_utils::make_daemon_bind_message;
     use actix::System;
     use masq_lib::constants::{
@@ -441,9 +597,10 @@ mod tests {
     };
     use masq_lib::messages::UiSetupResponseValueStatus::{Blank, Required, Set};
     use masq_lib::messages::{
-        CrashReason, UiFinancialsRequest, UiNodeCrashedBroadcast, UiRedirect, UiSetupBroadcast,
-        UiSetupRequest, UiSetupRequestValue, UiSetupResponse, UiSetupResponseValue,
-        UiSetupResponseValueStatus, UiShutdownRequest, UiStartOrder, UiStartResponse,
+        CrashReason, UiFinancialsRequest, UiNodeCrashedBroadcast, UiRedirect, UiRestartDecision,
+        UiSetupBroadcast, UiSetupRequest, UiSetupRequestValue, UiSetupResponse,
+        UiSetupResponseValue, UiSetupResponseValueStatus, UiShutdownRequest, UiStartOrder,
+        UiStartResponse,
     };
     use masq_lib::shared_schema::ConfiguratorError;
     use masq_lib::test_utils::environment_guard::{ClapGuard, EnvironmentGuard};
@@ -453,6 +610,7 @@ mod tests {
     use std::cell::RefCell;
     use std::collections::HashSet;
     use std::iter::FromIterator;
+    use std::path::Path;
     use std::sync::{Arc, Mutex};
 
     #[test]
@@ -1544,6 +1702,7 @@ mod tests {
             stats_required: true,
             top_records_opt: None,
             custom_queries_opt: None,
+            page_opt: None,
         }
         .tmb(4321);
 
@@ -1653,4 +1812,411 @@ mod tests {
         let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
         assert_eq!(ui_gateway_recording.len(), 0);
     }
+
+    #[test]
+    fn crashed_node_is_restarted_under_restart_policy_and_restart_is_broadcast() {
+        let restarted_broadcast = NodeToUiMessage {
+            target: MessageTarget::AllClients,
+            body: UiNodeRestartedBroadcast {
+                crashed_process_id: 54321,
+                new_process_id: 2345,
+                redirect_ui_port: 5432,
+                restart_count: 1,
+            }
+            .tmb(0),
+        };
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let ui_gateway = ui_gateway.system_stop_conditions(StopConditions::All(vec![
+            StopCondition::StopOnMatch {
+                exemplar: Box::new(restarted_broadcast.clone()),
+            },
+        ]));
+        let launch_params_arc = Arc::new(Mutex::new(vec![]));
+        let launcher = LauncherMock::new()
+            .launch_params(&launch_params_arc)
+            .launch_result(Ok(Some(LaunchSuccess {
+                new_process_id: 2345,
+                redirect_ui_port: 5432,
+            })));
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(launcher));
+        subject.params.insert(
+            "db-password".to_string(),
+            UiSetupResponseValue::new("db-password", "goober", Set),
+        );
+        subject.node_ui_port = Some(1234);
+        subject.node_process_id = Some(54321);
+        subject.verifier_tools = Box::new(VerifierToolsMock::new());
+        subject.restart_supervisor =
+            RestartSupervisor::new(Some(RestartPolicy::try_from("3|0|0|600").unwrap()));
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+
+        subject_addr
+            .try_send(CrashNotification {
+                process_id: 54321,
+                exit_code: Some(123),
+                stderr: Some("Standard error".to_string()),
+            })
+            .unwrap();
+
+        system.run();
+        let launch_params = launch_params_arc.lock().unwrap();
+        assert_eq!(
+            (*launch_params)
+                .iter()
+                .map(|x| &x.0)
+                .collect::<Vec<&HashMap<String, String>>>(),
+            vec![&HashMap::from_iter(
+                vec![("db-password", "goober")]
+                    .into_iter()
+                    .map(|(n, v)| (n.to_string(), v.to_string()))
+            )]
+        );
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0).body,
+            UiNodeCrashedBroadcast {
+                process_id: 54321,
+                crash_reason: CrashReason::Unrecognized("Standard error".to_string()),
+            }
+            .tmb(0)
+        );
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(1),
+            &restarted_broadcast
+        );
+        assert_eq!(ui_gateway_recording.len(), 2);
+    }
+
+    #[test]
+    fn crash_notification_does_not_restart_crash_looping_node() {
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        let (ui_gateway, _, _) = make_recorder();
+        let system = System::new("test");
+        subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
+        subject.node_ui_port = Some(1234);
+        subject.node_process_id = Some(54321);
+        subject.restart_supervisor =
+            RestartSupervisor::new(Some(RestartPolicy::try_from("1|10|10|600").unwrap()));
+        subject.restart_supervisor.on_restart(SystemTime::now());
+
+        let result = subject.handle_crash_notification(CrashNotification {
+            process_id: 54321,
+            exit_code: Some(123),
+            stderr: None,
+        });
+
+        System::current().stop();
+        system.run();
+        assert_eq!(result, None);
+        let history = subject.restart_supervisor.history();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].decision, UiRestartDecision::CrashLoop);
+    }
+
+    #[test]
+    fn crash_notification_does_not_restart_node_that_exited_cleanly() {
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        let (ui_gateway, _, _) = make_recorder();
+        let system = System::new("test");
+        subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
+        subject.node_ui_port = Some(1234);
+        subject.node_process_id = Some(54321);
+        subject.restart_supervisor =
+            RestartSupervisor::new(Some(RestartPolicy::try_from("3|10|10|600").unwrap()));
+
+        let result = subject.handle_crash_notification(CrashNotification {
+            process_id: 54321,
+            exit_code: Some(0),
+            stderr: None,
+        });
+
+        System::current().stop();
+        system.run();
+        assert_eq!(result, None);
+        assert_eq!(
+            subject.restart_supervisor.history()[0].decision,
+            UiRestartDecision::CleanExit
+        );
+    }
+
+    #[test]
+    fn failed_restart_is_recorded_and_tried_again_later() {
+        let launcher = LauncherMock::new().launch_result(Err("Booga".to_string()));
+        let mut subject = Daemon::new(Box::new(launcher));
+        let (crash_notifier, _, _) = make_recorder();
+        let system = System::new("test");
+        subject.crash_notification_sub = Some(crash_notifier.start().recipient());
+        subject.restart_supervisor =
+            RestartSupervisor::new(Some(RestartPolicy::try_from("3|10|60|600").unwrap()));
+        subject.restart_supervisor.on_crash(
+            SystemTime::now(),
+            54321,
+            Some(123),
+            CrashReason::NoInformation,
+        );
+
+        let result = subject.handle_restart_node();
+
+        System::current().stop();
+        system.run();
+        assert_eq!(result, Some(Duration::from_secs(20)));
+        let history = subject.restart_supervisor.history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].process_id, 54321);
+        assert_eq!(
+            history[1].crash_reason,
+            CrashReason::Unrecognized("Could not restart Node: Booga".to_string())
+        );
+        assert_eq!(history[1].restart_delay_s_opt, Some(20));
+        assert_eq!(subject.node_process_id, None);
+    }
+
+    #[test]
+    fn scheduled_restart_is_skipped_if_node_was_started_by_hand() {
+        let launch_params_arc = Arc::new(Mutex::new(vec![]));
+        let launcher = LauncherMock::new().launch_params(&launch_params_arc);
+        let verifier_tools = VerifierToolsMock::new().process_is_running_result(true);
+        let mut subject = Daemon::new(Box::new(launcher));
+        subject.verifier_tools = Box::new(verifier_tools);
+        subject.node_ui_port = Some(1234);
+        subject.node_process_id = Some(2345);
+        subject.restart_supervisor =
+            RestartSupervisor::new(Some(RestartPolicy::try_from("3|10|60|600").unwrap()));
+
+        let result = subject.handle_restart_node();
+
+        assert_eq!(result, None);
+        assert!(launch_params_arc.lock().unwrap().is_empty());
+        assert_eq!(subject.node_process_id, Some(2345));
+    }
+
+    #[test]
+    fn start_order_gives_restart_supervisor_a_fresh_start() {
+        let launcher = LauncherMock::new().launch_result(Ok(Some(LaunchSuccess {
+            new_process_id: 2345,
+            redirect_ui_port: 5432,
+        })));
+        let mut subject = Daemon::new(Box::new(launcher));
+        let (ui_gateway, _, _) = make_recorder();
+        let (crash_notifier, _, _) = make_recorder();
+        let system = System::new("test");
+        subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
+        subject.crash_notification_sub = Some(crash_notifier.start().recipient());
+        subject.restart_supervisor =
+            RestartSupervisor::new(Some(RestartPolicy::try_from("1|10|10|600").unwrap()));
+        subject.restart_supervisor.on_restart(SystemTime::now());
+
+        subject.handle_start_order(1234, 4321);
+
+        let result = subject.handle_crash_notification(CrashNotification {
+            process_id: 2345,
+            exit_code: Some(123),
+            stderr: None,
+        });
+        System::current().stop();
+        system.run();
+        assert_eq!(result, Some(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn accepts_crash_history_request_and_returns_crash_history() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.restart_supervisor =
+            RestartSupervisor::new(Some(RestartPolicy::try_from("3|10|60|600").unwrap()));
+        subject.restart_supervisor.on_crash(
+            SystemTime::now(),
+            54321,
+            Some(123),
+            CrashReason::NoInformation,
+        );
+        let expected_crashes = subject.restart_supervisor.history();
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiCrashHistoryRequest {}.tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(0)
+            .clone();
+        assert_eq!(record.target, ClientId(1234));
+        let (payload, context_id) = UiCrashHistoryResponse::fmb(record.body).unwrap();
+        assert_eq!(context_id, 4321);
+        assert_eq!(
+            payload,
+            UiCrashHistoryResponse {
+                restart_policy_opt: Some("3|10|60|600".to_string()),
+                crashes: expected_crashes,
+            }
+        );
+    }
+
+    fn make_crash_report(process_id: u32) -> UiCrashReport {
+        UiCrashReport {
+            timestamp_s: 1_700_000_000,
+            process_id,
+            version: "0.9.0".to_string(),
+            panic_message: "Booga".to_string(),
+            location: "src/accountant/mod.rs:12:34".to_string(),
+            actor_opt: Some("ACCOUNTANT".to_string()),
+            backtrace: "0: rust_begin_unwind".to_string(),
+            log_lines: vec!["last line".to_string()],
+        }
+    }
+
+    fn make_daemon_with_data_directory(data_directory: &Path) -> Daemon {
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.params.insert(
+            "data-directory".to_string(),
+            UiSetupResponseValue::new(
+                "data-directory",
+                data_directory.to_str().unwrap(),
+                UiSetupResponseValueStatus::Default,
+            ),
+        );
+        subject
+    }
+
+    #[test]
+    fn crash_notification_is_analyzed_with_the_crash_report_of_the_crashed_node() {
+        let data_directory = ensure_node_home_directory_exists(
+            "daemon",
+            "crash_notification_is_analyzed_with_the_crash_report_of_the_crashed_node",
+        );
+        write_crash_report(&data_directory, &make_crash_report(54321)).unwrap();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let system = System::new("test");
+        let mut subject = make_daemon_with_data_directory(&data_directory);
+        subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
+        subject.node_ui_port = Some(1234);
+        subject.node_process_id = Some(54321);
+
+        subject.handle_crash_notification(CrashNotification {
+            process_id: 54321,
+            exit_code: Some(101),
+            stderr: Some("Standard error".to_string()),
+        });
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0).body,
+            UiNodeCrashedBroadcast {
+                process_id: 54321,
+                crash_reason: CrashReason::ActorPanic {
+                    actor: "ACCOUNTANT".to_string(),
+                    message: "Booga".to_string(),
+                    location: "src/accountant/mod.rs:12:34".to_string(),
+                },
+            }
+            .tmb(0)
+        );
+    }
+
+    #[test]
+    fn crash_notification_ignores_crash_report_left_by_another_process() {
+        let data_directory = ensure_node_home_directory_exists(
+            "daemon",
+            "crash_notification_ignores_crash_report_left_by_another_process",
+        );
+        write_crash_report(&data_directory, &make_crash_report(11111)).unwrap();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let system = System::new("test");
+        let mut subject = make_daemon_with_data_directory(&data_directory);
+        subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
+        subject.node_ui_port = Some(1234);
+        subject.node_process_id = Some(54321);
+
+        subject.handle_crash_notification(CrashNotification {
+            process_id: 54321,
+            exit_code: Some(101),
+            stderr: Some("Standard error".to_string()),
+        });
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0).body,
+            UiNodeCrashedBroadcast {
+                process_id: 54321,
+                crash_reason: CrashReason::Unrecognized("Standard error".to_string()),
+            }
+            .tmb(0)
+        );
+    }
+
+    #[test]
+    fn accepts_crash_report_request_and_returns_latest_crash_report() {
+        let data_directory = ensure_node_home_directory_exists(
+            "daemon",
+            "accepts_crash_report_request_and_returns_latest_crash_report",
+        );
+        write_crash_report(&data_directory, &make_crash_report(54321)).unwrap();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let system = System::new("test");
+        let subject = make_daemon_with_data_directory(&data_directory);
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiCrashReportRequest {}.tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(0)
+            .clone();
+        assert_eq!(record.target, ClientId(1234));
+        let (payload, context_id) = UiCrashReportResponse::fmb(record.body).unwrap();
+        assert_eq!(context_id, 4321);
+        assert_eq!(
+            payload,
+            UiCrashReportResponse {
+                report_opt: Some(make_crash_report(54321))
+            }
+        );
+    }
+
+    #[test]
+    fn crash_report_request_without_data_directory_finds_no_report() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
+
+        subject.handle_crash_report(1234, 4321);
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let record = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
+        assert_eq!(
+            record.body,
+            UiCrashReportResponse { report_opt: None }.tmb(4321)
+        );
+    }
 }
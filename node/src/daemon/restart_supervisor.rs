@@ -0,0 +1,359 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use masq_lib::messages::{CrashReason, UiCrashRecord, UiRestartDecision};
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Older crashes are forgotten so that a Node crashing for weeks can't exhaust the Daemon's memory
+pub const CRASH_HISTORY_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartPolicy {
+    pub max_restarts: u32,
+    pub initial_backoff_s: u64,
+    pub max_backoff_s: u64,
+    pub crash_loop_window_s: u64,
+}
+
+impl TryFrom<&str> for RestartPolicy {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let pieces = value.split('|').collect::<Vec<&str>>();
+        if pieces.len() != 4 {
+            return Err(format!(
+                "Restart policy '{}' must look like MaxRestarts|InitialBackoffSec|MaxBackoffSec|CrashLoopWindowSec",
+                value
+            ));
+        }
+        let number = |name: &str, piece: &str| {
+            piece
+                .parse::<u64>()
+                .map_err(|_| format!("{} '{}' is not a whole number", name, piece))
+        };
+        let max_restarts = number("MaxRestarts", pieces[0])?;
+        let initial_backoff_s = number("InitialBackoffSec", pieces[1])?;
+        let max_backoff_s = number("MaxBackoffSec", pieces[2])?;
+        let crash_loop_window_s = number("CrashLoopWindowSec", pieces[3])?;
+        if max_restarts == 0 || max_restarts > u32::MAX as u64 {
+            return Err(format!(
+                "MaxRestarts must be between 1 and {}, not {}",
+                u32::MAX,
+                max_restarts
+            ));
+        }
+        if max_backoff_s < initial_backoff_s {
+            return Err(format!(
+                "MaxBackoffSec ({}) must not be less than InitialBackoffSec ({})",
+                max_backoff_s, initial_backoff_s
+            ));
+        }
+        if crash_loop_window_s == 0 {
+            return Err("CrashLoopWindowSec must be at least 1".to_string());
+        }
+        Ok(RestartPolicy {
+            max_restarts: max_restarts as u32,
+            initial_backoff_s,
+            max_backoff_s,
+            crash_loop_window_s,
+        })
+    }
+}
+
+impl Display for RestartPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}|{}",
+            self.max_restarts, self.initial_backoff_s, self.max_backoff_s, self.crash_loop_window_s
+        )
+    }
+}
+
+pub fn validate_restart_policy(value: String) -> Result<(), String> {
+    RestartPolicy::try_from(value.as_str()).map(|_| ())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartDecision {
+    NoRestartPolicy,
+    CleanExit,
+    RestartAfter(Duration),
+    CrashLoop,
+}
+
+// Decides whether a crashed Node deserves another try, backing off exponentially with every
+// restart made within the crash-loop window and giving up once the window holds too many of them
+pub struct RestartSupervisor {
+    policy_opt: Option<RestartPolicy>,
+    restart_times: Vec<SystemTime>,
+    history: VecDeque<UiCrashRecord>,
+}
+
+impl RestartSupervisor {
+    pub fn new(policy_opt: Option<RestartPolicy>) -> Self {
+        Self {
+            policy_opt,
+            restart_times: vec![],
+            history: VecDeque::new(),
+        }
+    }
+
+    pub fn policy_opt(&self) -> Option<RestartPolicy> {
+        self.policy_opt
+    }
+
+    pub fn on_crash(
+        &mut self,
+        now: SystemTime,
+        process_id: u32,
+        exit_code_opt: Option<i32>,
+        crash_reason: CrashReason,
+    ) -> RestartDecision {
+        let decision = match (self.policy_opt, exit_code_opt) {
+            (_, Some(0)) => RestartDecision::CleanExit,
+            (None, _) => RestartDecision::NoRestartPolicy,
+            (Some(policy), _) => {
+                let recent_restarts = self.recent_restarts(now, &policy);
+                if recent_restarts >= policy.max_restarts {
+                    RestartDecision::CrashLoop
+                } else {
+                    let backoff_s = policy
+                        .initial_backoff_s
+                        .saturating_mul(2_u64.saturating_pow(recent_restarts))
+                        .min(policy.max_backoff_s);
+                    RestartDecision::RestartAfter(Duration::from_secs(backoff_s))
+                }
+            }
+        };
+        if self.history.len() >= CRASH_HISTORY_LIMIT {
+            self.history.pop_front();
+        }
+        self.history.push_back(UiCrashRecord {
+            timestamp_s: now
+                .duration_since(UNIX_EPOCH)
+                .map(|since_epoch| since_epoch.as_secs())
+                .unwrap_or(0),
+            process_id,
+            crash_reason,
+            decision: match decision {
+                RestartDecision::NoRestartPolicy => UiRestartDecision::NoRestartPolicy,
+                RestartDecision::CleanExit => UiRestartDecision::CleanExit,
+                RestartDecision::RestartAfter(_) => UiRestartDecision::Restart,
+                RestartDecision::CrashLoop => UiRestartDecision::CrashLoop,
+            },
+            restart_delay_s_opt: match decision {
+                RestartDecision::RestartAfter(delay) => Some(delay.as_secs()),
+                _ => None,
+            },
+        });
+        decision
+    }
+
+    // Returns how many restarts, this one included, fall within the crash-loop window
+    pub fn on_restart(&mut self, now: SystemTime) -> u32 {
+        self.restart_times.push(now);
+        match self.policy_opt {
+            Some(policy) => self.recent_restarts(now, &policy),
+            None => self.restart_times.len() as u32,
+        }
+    }
+
+    // A Node started by hand deserves a fresh count of restarts
+    pub fn reset(&mut self) {
+        self.restart_times.clear()
+    }
+
+    pub fn history(&self) -> Vec<UiCrashRecord> {
+        self.history.iter().cloned().collect()
+    }
+
+    fn recent_restarts(&mut self, now: SystemTime, policy: &RestartPolicy) -> u32 {
+        let window = Duration::from_secs(policy.crash_loop_window_s);
+        self.restart_times.retain(|restart_time| {
+            now.duration_since(*restart_time)
+                .map(|elapsed| elapsed < window)
+                .unwrap_or(true)
+        });
+        self.restart_times.len() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_policy() -> RestartPolicy {
+        RestartPolicy::try_from("3|10|25|600").unwrap()
+    }
+
+    fn crash(subject: &mut RestartSupervisor, now: SystemTime) -> RestartDecision {
+        subject.on_crash(now, 1234, Some(1), CrashReason::NoInformation)
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(CRASH_HISTORY_LIMIT, 100);
+    }
+
+    #[test]
+    fn restart_policy_parses_and_displays() {
+        let result = RestartPolicy::try_from("5|10|600|3600").unwrap();
+
+        assert_eq!(
+            result,
+            RestartPolicy {
+                max_restarts: 5,
+                initial_backoff_s: 10,
+                max_backoff_s: 600,
+                crash_loop_window_s: 3600,
+            }
+        );
+        assert_eq!(result.to_string(), "5|10|600|3600".to_string());
+    }
+
+    #[test]
+    fn restart_policy_rejects_nonsense() {
+        let shape_msg = |value: &str| {
+            format!(
+                "Restart policy '{}' must look like MaxRestarts|InitialBackoffSec|MaxBackoffSec|CrashLoopWindowSec",
+                value
+            )
+        };
+
+        assert_eq!(
+            RestartPolicy::try_from("5|10|600"),
+            Err(shape_msg("5|10|600"))
+        );
+        assert_eq!(
+            RestartPolicy::try_from("5|10|600|3600|1"),
+            Err(shape_msg("5|10|600|3600|1"))
+        );
+        assert_eq!(
+            RestartPolicy::try_from("5|ten|600|3600"),
+            Err("InitialBackoffSec 'ten' is not a whole number".to_string())
+        );
+        assert_eq!(
+            RestartPolicy::try_from("0|10|600|3600"),
+            Err(format!(
+                "MaxRestarts must be between 1 and {}, not 0",
+                u32::MAX
+            ))
+        );
+        assert_eq!(
+            RestartPolicy::try_from("5|10|9|3600"),
+            Err("MaxBackoffSec (9) must not be less than InitialBackoffSec (10)".to_string())
+        );
+        assert_eq!(
+            RestartPolicy::try_from("5|10|600|0"),
+            Err("CrashLoopWindowSec must be at least 1".to_string())
+        );
+        assert_eq!(validate_restart_policy("5|10|600|3600".to_string()), Ok(()));
+    }
+
+    #[test]
+    fn backoff_doubles_with_each_restart_until_the_maximum_then_gives_up() {
+        let mut subject = RestartSupervisor::new(Some(make_policy()));
+        let now = SystemTime::now();
+        let mut decisions = vec![];
+        for restart in 0..4 {
+            let at = now + Duration::from_secs(restart * 30);
+            decisions.push(crash(&mut subject, at));
+            if let RestartDecision::RestartAfter(_) = decisions.last().unwrap() {
+                subject.on_restart(at);
+            }
+        }
+
+        assert_eq!(
+            decisions,
+            vec![
+                RestartDecision::RestartAfter(Duration::from_secs(10)),
+                RestartDecision::RestartAfter(Duration::from_secs(20)),
+                RestartDecision::RestartAfter(Duration::from_secs(25)),
+                RestartDecision::CrashLoop,
+            ]
+        );
+    }
+
+    #[test]
+    fn restarts_older_than_the_window_no_longer_count() {
+        let mut subject = RestartSupervisor::new(Some(make_policy()));
+        let now = SystemTime::now();
+        subject.on_restart(now);
+        subject.on_restart(now + Duration::from_secs(100));
+        subject.on_restart(now + Duration::from_secs(200));
+
+        let inside_window = crash(&mut subject, now + Duration::from_secs(599));
+        let after_oldest_expired = crash(&mut subject, now + Duration::from_secs(600));
+        let restart_count = subject.on_restart(now + Duration::from_secs(801));
+
+        assert_eq!(inside_window, RestartDecision::CrashLoop);
+        assert_eq!(
+            after_oldest_expired,
+            RestartDecision::RestartAfter(Duration::from_secs(25))
+        );
+        assert_eq!(restart_count, 1);
+    }
+
+    #[test]
+    fn reset_forgets_restarts_but_not_history() {
+        let mut subject = RestartSupervisor::new(Some(make_policy()));
+        let now = SystemTime::now();
+        crash(&mut subject, now);
+        subject.on_restart(now);
+        subject.on_restart(now);
+        subject.on_restart(now);
+
+        subject.reset();
+
+        assert_eq!(
+            crash(&mut subject, now),
+            RestartDecision::RestartAfter(Duration::from_secs(10))
+        );
+        assert_eq!(subject.history().len(), 2);
+    }
+
+    #[test]
+    fn clean_exit_and_missing_policy_mean_no_restart() {
+        let mut with_policy = RestartSupervisor::new(Some(make_policy()));
+        let mut without_policy = RestartSupervisor::new(None);
+        let now = SystemTime::now();
+
+        let clean_exit = with_policy.on_crash(now, 1234, Some(0), CrashReason::NoInformation);
+        let no_policy = crash(&mut without_policy, now);
+
+        assert_eq!(clean_exit, RestartDecision::CleanExit);
+        assert_eq!(no_policy, RestartDecision::NoRestartPolicy);
+        assert_eq!(without_policy.policy_opt(), None);
+    }
+
+    #[test]
+    fn history_records_decisions_and_forgets_the_oldest_crashes() {
+        let mut subject = RestartSupervisor::new(Some(make_policy()));
+        let now = SystemTime::now();
+        subject.on_crash(
+            now,
+            1111,
+            Some(101),
+            CrashReason::Unrecognized("Booga".to_string()),
+        );
+        (0..CRASH_HISTORY_LIMIT).for_each(|_| {
+            let _ = crash(&mut subject, now);
+        });
+
+        let history = subject.history();
+
+        assert_eq!(history.len(), CRASH_HISTORY_LIMIT);
+        assert_eq!(
+            history[0],
+            UiCrashRecord {
+                timestamp_s: now.duration_since(UNIX_EPOCH).unwrap().as_secs(),
+                process_id: 1234,
+                crash_reason: CrashReason::NoInformation,
+                decision: UiRestartDecision::Restart,
+                restart_delay_s_opt: Some(10),
+            }
+        );
+    }
+}
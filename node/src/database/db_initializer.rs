@@ -9,8 +9,8 @@ use crate::sub_lib::neighborhood::DEFAULT_RATE_PACK;
 use crate::sub_lib::utils::db_connection_launch_panic;
 use masq_lib::blockchains::chains::Chain;
 use masq_lib::constants::{
-    CURRENT_SCHEMA_VERSION, DEFAULT_GAS_PRICE, HIGHEST_RANDOM_CLANDESTINE_PORT,
-    LOWEST_USABLE_INSECURE_PORT,
+    CURRENT_SCHEMA_VERSION, DEFAULT_BLOCKCHAIN_SERVICE_QUORUM, DEFAULT_GAS_PRICE,
+    HIGHEST_RANDOM_CLANDESTINE_PORT, LOWEST_USABLE_INSECURE_PORT,
 };
 use masq_lib::logger::Logger;
 use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
@@ -137,7 +137,10 @@ impl DbInitializerReal {
         Self::create_payable_table(conn);
         Self::create_pending_payable_table(conn);
         Self::create_receivable_table(conn);
+        Self::create_received_payment_table(conn);
+        Self::create_ledger_table(conn);
         Self::create_banned_table(conn);
+        Self::create_service_receipt_table(conn);
     }
 
     pub fn create_config_table(conn: &Connection) {
@@ -160,6 +163,13 @@ impl DbInitializerReal {
             false,
             "blockchain service url to interact with the blockchain",
         );
+        Self::set_config_value(
+            conn,
+            "blockchain_service_quorum",
+            Some(&DEFAULT_BLOCKCHAIN_SERVICE_QUORUM.to_string()),
+            false,
+            "number of blockchain services that must agree on data read from the blockchain",
+        );
         Self::set_config_value(
             conn,
             "chain_name",
@@ -273,13 +283,20 @@ impl DbInitializerReal {
                     amount_low_b integer not null,
                     payable_timestamp integer not null,
                     attempt integer not null,
-                    process_error text null
+                    process_error text null,
+                    nonce integer null,
+                    recipient_address text null,
+                    max_fee_per_gas_wei_high_b integer null,
+                    max_fee_per_gas_wei_low_b integer null,
+                    max_priority_fee_per_gas_wei_high_b integer null,
+                    max_priority_fee_per_gas_wei_low_b integer null,
+                    replaced_hashes text not null default ''
             )",
             [],
         )
         .expect("Can't create pending_payable table");
         conn.execute(
-            "CREATE UNIQUE INDEX pending_payable_hash_idx ON pending_payable (transaction_hash)",
+            "CREATE UNIQUE INDEX pending_payable_hash_idx ON pending_payable (transaction_hash, recipient_address)",
             [],
         )
         .expect("Can't create transaction hash index in pending payments");
@@ -312,14 +329,91 @@ impl DbInitializerReal {
         .expect("Can't create receivable table");
     }
 
+    pub fn create_received_payment_table(conn: &Connection) {
+        conn.execute(
+            "create table if not exists received_payment (
+                    transaction_hash text primary key,
+                    block_number integer not null,
+                    block_hash text not null,
+                    wallet_address text not null,
+                    amount_high_b integer not null,
+                    amount_low_b integer not null
+            ) strict",
+            [],
+        )
+        .expect("Can't create received_payment table");
+        conn.execute(
+            "CREATE INDEX received_payment_block_number_idx ON received_payment (block_number)",
+            [],
+        )
+        .expect("Can't create block number index in received payments");
+    }
+
+    pub fn create_ledger_table(conn: &Connection) {
+        conn.execute(
+            "create table if not exists ledger (
+                    timestamp integer not null,
+                    wallet_address text not null,
+                    entry_type text not null,
+                    bytes integer not null,
+                    amount_high_b integer not null,
+                    amount_low_b integer not null,
+                    transaction_hash text null,
+                    entry_count integer null
+            ) strict",
+            [],
+        )
+        .expect("Can't create ledger table");
+        conn.execute(
+            "CREATE INDEX ledger_timestamp_idx ON ledger (timestamp)",
+            [],
+        )
+        .expect("Can't create timestamp index in ledger");
+        conn.execute(
+            "CREATE INDEX ledger_wallet_address_idx ON ledger (wallet_address)",
+            [],
+        )
+        .expect("Can't create wallet address index in ledger");
+    }
+
     pub fn create_banned_table(conn: &Connection) {
         conn.execute(
-            "create table banned ( wallet_address text primary key )",
+            "create table banned (
+                    wallet_address text primary key,
+                    reason text null,
+                    timestamp integer null,
+                    manual integer not null default 0
+            )",
             [],
         )
         .expect("Can't create banned table");
     }
 
+    pub fn create_service_receipt_table(conn: &Connection) {
+        conn.execute(
+            "create table if not exists service_receipt (
+                    consumer_key blob not null,
+                    consuming_wallet_address text not null,
+                    provider_wallet_address text not null,
+                    route_id integer not null,
+                    first_service integer not null,
+                    services integer not null,
+                    bytes integer not null,
+                    amount_high_b integer not null,
+                    amount_low_b integer not null,
+                    signature blob not null,
+                    received integer not null
+            ) strict",
+            [],
+        )
+        .expect("Can't create service_receipt table");
+        conn.execute(
+            "CREATE UNIQUE INDEX service_receipt_route_idx ON service_receipt (consumer_key, route_id, provider_wallet_address, first_service)",
+            [],
+        )
+        .expect("Can't create route index in service_receipt");
+    }
+
     fn extra_configuration(
         conn: &Connection,
         init_config: &DbInitializationConfig,
@@ -658,7 +752,7 @@ mod tests {
     #[test]
     fn constants_have_correct_values() {
         assert_eq!(DATABASE_FILE, "node-data.db");
-        assert_eq!(CURRENT_SCHEMA_VERSION, 9);
+        assert_eq!(CURRENT_SCHEMA_VERSION, 16);
     }
 
     #[test]
@@ -698,7 +792,9 @@ mod tests {
             .initialize(&home_dir, DbInitializationConfig::test_default())
             .unwrap();
 
-        let mut stmt = conn.prepare("select rowid, transaction_hash, amount_high_b, amount_low_b, payable_timestamp, attempt, process_error from pending_payable").unwrap();
+        let mut stmt = conn.prepare("select rowid, transaction_hash, amount_high_b, amount_low_b, payable_timestamp, attempt, process_error, \
+            nonce, recipient_address, max_fee_per_gas_wei_high_b, max_fee_per_gas_wei_low_b, max_priority_fee_per_gas_wei_high_b, \
+            max_priority_fee_per_gas_wei_low_b, replaced_hashes from pending_payable").unwrap();
         let mut payable_contents = stmt.query_map([], |_| Ok(42)).unwrap();
         assert!(payable_contents.next().is_none());
         let expected_key_words: &[&[&str]] = &[
@@ -709,9 +805,16 @@ mod tests {
             &["payable_timestamp", "integer", "not", "null"],
             &["attempt", "integer", "not", "null"],
             &["process_error", "text", "null"],
+            &["nonce", "integer", "null"],
+            &["recipient_address", "text", "null"],
+            &["max_fee_per_gas_wei_high_b", "integer", "null"],
+            &["max_fee_per_gas_wei_low_b", "integer", "null"],
+            &["max_priority_fee_per_gas_wei_high_b", "integer", "null"],
+            &["max_priority_fee_per_gas_wei_low_b", "integer", "null"],
+            &["replaced_hashes", "text", "not", "null", "default", "''"],
         ];
         assert_create_table_stm_contains_all_parts(&*conn, "pending_payable", expected_key_words);
-        let expected_key_words: &[&[&str]] = &[&["transaction_hash"]];
+        let expected_key_words: &[&[&str]] = &[&["transaction_hash"], &["recipient_address"]];
         assert_index_stm_is_coupled_with_right_parameter(
             conn.as_ref(),
             "pending_payable_hash_idx",
@@ -774,6 +877,88 @@ mod tests {
         assert_no_index_exists_for_table(conn.as_ref(), "receivable")
     }
 
+    #[test]
+    fn db_initialize_creates_received_payment_table() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "db_initializer",
+            "db_initialize_creates_received_payment_table",
+        );
+        let subject = DbInitializerReal::default();
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+
+        let mut stmt = conn
+            .prepare("select transaction_hash, block_number, block_hash, wallet_address, amount_high_b, amount_low_b from received_payment")
+            .unwrap();
+        let mut received_payment_contents = stmt.query_map([], |_| Ok(())).unwrap();
+        assert!(received_payment_contents.next().is_none());
+        assert_table_created_as_strict(&*conn, "received_payment");
+        let expected_key_words: &[&[&str]] = &[
+            &["transaction_hash", "text", "primary", "key"],
+            &["block_number", "integer", "not", "null"],
+            &["block_hash", "text", "not", "null"],
+            &["wallet_address", "text", "not", "null"],
+            &["amount_high_b", "integer", "not", "null"],
+            &["amount_low_b", "integer", "not", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            conn.as_ref(),
+            "received_payment",
+            expected_key_words,
+        );
+        let expected_key_words: &[&[&str]] = &[&["block_number"]];
+        assert_index_stm_is_coupled_with_right_parameter(
+            conn.as_ref(),
+            "received_payment_block_number_idx",
+            expected_key_words,
+        )
+    }
+
+    #[test]
+    fn db_initialize_creates_ledger_table() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "db_initializer",
+            "db_initialize_creates_ledger_table",
+        );
+        let subject = DbInitializerReal::default();
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+
+        let mut stmt = conn
+            .prepare("select timestamp, wallet_address, entry_type, bytes, amount_high_b, amount_low_b, transaction_hash, entry_count from ledger")
+            .unwrap();
+        let mut ledger_contents = stmt.query_map([], |_| Ok(())).unwrap();
+        assert!(ledger_contents.next().is_none());
+        assert_table_created_as_strict(&*conn, "ledger");
+        let expected_key_words: &[&[&str]] = &[
+            &["timestamp", "integer", "not", "null"],
+            &["wallet_address", "text", "not", "null"],
+            &["entry_type", "text", "not", "null"],
+            &["bytes", "integer", "not", "null"],
+            &["amount_high_b", "integer", "not", "null"],
+            &["amount_low_b", "integer", "not", "null"],
+            &["transaction_hash", "text", "null"],
+            &["entry_count", "integer", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(conn.as_ref(), "ledger", expected_key_words);
+        let expected_key_words: &[&[&str]] = &[&["timestamp"]];
+        assert_index_stm_is_coupled_with_right_parameter(
+            conn.as_ref(),
+            "ledger_timestamp_idx",
+            expected_key_words,
+        );
+        let expected_key_words: &[&[&str]] = &[&["wallet_address"]];
+        assert_index_stm_is_coupled_with_right_parameter(
+            conn.as_ref(),
+            "ledger_wallet_address_idx",
+            expected_key_words,
+        )
+    }
+
     #[test]
     fn db_initialize_creates_banned_table() {
         init_test_logging();
@@ -787,14 +972,70 @@ mod tests {
             .initialize(&home_dir, DbInitializationConfig::test_default())
             .unwrap();
 
-        let mut stmt = conn.prepare("select wallet_address from banned").unwrap();
+        let mut stmt = conn
+            .prepare("select wallet_address, reason, timestamp, manual from banned")
+            .unwrap();
         let mut banned_contents = stmt.query_map([], |_| Ok(42)).unwrap();
         assert!(banned_contents.next().is_none());
-        let expected_key_words: &[&[&str]] = &[&["wallet_address", "text", "primary", "key"]];
+        let expected_key_words: &[&[&str]] = &[
+            &["wallet_address", "text", "primary", "key"],
+            &["reason", "text", "null"],
+            &["timestamp", "integer", "null"],
+            &["manual", "integer", "not", "null", "default", "0"],
+        ];
         assert_create_table_stm_contains_all_parts(conn.as_ref(), "banned", expected_key_words);
         assert_no_index_exists_for_table(conn.as_ref(), "banned")
     }
 
+    #[test]
+    fn db_initialize_creates_service_receipt_table() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "db_initializer",
+            "db_initialize_creates_service_receipt_table",
+        );
+        let subject = DbInitializerReal::default();
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+
+        let mut stmt = conn
+            .prepare("select consumer_key, consuming_wallet_address, provider_wallet_address, route_id, first_service, services, bytes, amount_high_b, amount_low_b, signature, received from service_receipt")
+            .unwrap();
+        let mut receipt_contents = stmt.query_map([], |_| Ok(())).unwrap();
+        assert!(receipt_contents.next().is_none());
+        assert_table_created_as_strict(&*conn, "service_receipt");
+        let expected_key_words: &[&[&str]] = &[
+            &["consumer_key", "blob", "not", "null"],
+            &["consuming_wallet_address", "text", "not", "null"],
+            &["provider_wallet_address", "text", "not", "null"],
+            &["route_id", "integer", "not", "null"],
+            &["first_service", "integer", "not", "null"],
+            &["services", "integer", "not", "null"],
+            &["bytes", "integer", "not", "null"],
+            &["amount_high_b", "integer", "not", "null"],
+            &["amount_low_b", "integer", "not", "null"],
+            &["signature", "blob", "not", "null"],
+            &["received", "integer", "not", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            conn.as_ref(),
+            "service_receipt",
+            expected_key_words,
+        );
+        let expected_key_words: &[&[&str]] = &[
+            &["consumer_key"],
+            &["route_id"],
+            &["provider_wallet_address"],
+            &["first_service"],
+        ];
+        assert_index_stm_is_coupled_with_right_parameter(
+            conn.as_ref(),
+            "service_receipt_route_idx",
+            expected_key_words,
+        )
+    }
+
     #[test]
     #[should_panic(expected = "The database undoubtedly exists, but: unable to open database file")]
     fn double_check_the_result_of_db_migration_panics_if_cannot_reestablish_the_connection_to_the_database(
@@ -912,6 +1153,12 @@ mod tests {
             assert_eq!(actual_encrypted, expected_encrypted);
             value
         };
+        verify(
+            &mut config_vec,
+            "blockchain_service_quorum",
+            Some(&DEFAULT_BLOCKCHAIN_SERVICE_QUORUM.to_string()),
+            false,
+        );
         verify(&mut config_vec, "blockchain_service_url", None, false);
         verify(
             &mut config_vec,
@@ -0,0 +1,83 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::database::db_migrations::db_migrator::DatabaseMigration;
+use crate::database::db_migrations::migrator_utils::DBMigDeclarator;
+use masq_lib::constants::DEFAULT_BLOCKCHAIN_SERVICE_QUORUM;
+
+#[allow(non_camel_case_types)]
+pub struct Migrate_10_to_11;
+
+impl DatabaseMigration for Migrate_10_to_11 {
+    fn migrate<'a>(
+        &self,
+        declaration_utils: Box<dyn DBMigDeclarator + 'a>,
+    ) -> rusqlite::Result<()> {
+        let statement = format!(
+            "INSERT INTO config (name, value, encrypted) VALUES ('blockchain_service_quorum', '{DEFAULT_BLOCKCHAIN_SERVICE_QUORUM}', 0)",
+        );
+        declaration_utils.execute_upon_transaction(&[&statement])
+    }
+
+    fn old_version(&self) -> usize {
+        10
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal, DATABASE_FILE,
+    };
+    use crate::database::db_migrations::db_migrator::DatabaseMigration;
+    use crate::database::db_migrations::migrations::migration_10_to_11::Migrate_10_to_11;
+    use crate::test_utils::database_utils::{
+        bring_db_0_back_to_life_and_return_connection, make_external_data, retrieve_config_row,
+    };
+    use masq_lib::constants::DEFAULT_BLOCKCHAIN_SERVICE_QUORUM;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+
+    #[test]
+    fn old_version_says_10() {
+        let subject = Migrate_10_to_11 {};
+
+        let result = subject.old_version();
+
+        assert_eq!(result, 10);
+    }
+
+    #[test]
+    fn migration_from_10_to_11_is_properly_set() {
+        let start_at = Migrate_10_to_11 {}.old_version();
+        let dir_path = ensure_node_home_directory_exists(
+            "db_migrations",
+            "migration_from_10_to_11_is_properly_set",
+        );
+        let db_path = dir_path.join(DATABASE_FILE);
+        let _ = bring_db_0_back_to_life_and_return_connection(&db_path);
+        let subject = DbInitializerReal::default();
+        {
+            subject
+                .initialize_to_version(&dir_path, start_at, DbInitializationConfig::test_default())
+                .unwrap();
+        }
+
+        let result = subject.initialize_to_version(
+            &dir_path,
+            start_at + 1,
+            DbInitializationConfig::create_or_migrate(make_external_data()),
+        );
+
+        let connection = result.unwrap();
+        let (bsq_value, bsq_encrypted) =
+            retrieve_config_row(connection.as_ref(), "blockchain_service_quorum");
+        assert_eq!(
+            bsq_value,
+            Some(DEFAULT_BLOCKCHAIN_SERVICE_QUORUM.to_string())
+        );
+        assert_eq!(bsq_encrypted, false);
+        let (schv_value, schv_encrypted) =
+            retrieve_config_row(connection.as_ref(), "schema_version");
+        assert_eq!(schv_value, Some("11".to_string()));
+        assert_eq!(schv_encrypted, false);
+    }
+}
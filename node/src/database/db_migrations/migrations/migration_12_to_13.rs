@@ -0,0 +1,115 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::database::db_migrations::db_migrator::DatabaseMigration;
+use crate::database::db_migrations::migrator_utils::DBMigDeclarator;
+
+#[allow(non_camel_case_types)]
+pub struct Migrate_12_to_13;
+
+impl DatabaseMigration for Migrate_12_to_13 {
+    fn migrate<'a>(
+        &self,
+        declaration_utils: Box<dyn DBMigDeclarator + 'a>,
+    ) -> rusqlite::Result<()> {
+        // The ledger starts empty; history from before this migration lives only in the balances
+        declaration_utils.execute_upon_transaction(&[
+            &"create table if not exists ledger (
+                    timestamp integer not null,
+                    wallet_address text not null,
+                    entry_type text not null,
+                    bytes integer not null,
+                    amount_high_b integer not null,
+                    amount_low_b integer not null,
+                    transaction_hash text null,
+                    entry_count integer null
+            ) strict",
+            &"CREATE INDEX ledger_timestamp_idx ON ledger (timestamp)",
+            &"CREATE INDEX ledger_wallet_address_idx ON ledger (wallet_address)",
+        ])
+    }
+
+    fn old_version(&self) -> usize {
+        12
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal, DATABASE_FILE,
+    };
+    use crate::database::db_migrations::db_migrator::DatabaseMigration;
+    use crate::database::db_migrations::migrations::migration_12_to_13::Migrate_12_to_13;
+    use crate::test_utils::database_utils::{
+        assert_create_table_stm_contains_all_parts,
+        assert_index_stm_is_coupled_with_right_parameter, assert_table_created_as_strict,
+        bring_db_0_back_to_life_and_return_connection, make_external_data, retrieve_config_row,
+    };
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+
+    #[test]
+    fn old_version_says_12() {
+        let subject = Migrate_12_to_13 {};
+
+        let result = subject.old_version();
+
+        assert_eq!(result, 12);
+    }
+
+    #[test]
+    fn migration_from_12_to_13_is_properly_set() {
+        let start_at = Migrate_12_to_13 {}.old_version();
+        let dir_path = ensure_node_home_directory_exists(
+            "db_migrations",
+            "migration_from_12_to_13_is_properly_set",
+        );
+        let db_path = dir_path.join(DATABASE_FILE);
+        let _ = bring_db_0_back_to_life_and_return_connection(&db_path);
+        let subject = DbInitializerReal::default();
+        {
+            subject
+                .initialize_to_version(&dir_path, start_at, DbInitializationConfig::test_default())
+                .unwrap();
+        }
+
+        let result = subject.initialize_to_version(
+            &dir_path,
+            start_at + 1,
+            DbInitializationConfig::create_or_migrate(make_external_data()),
+        );
+
+        let connection = result.unwrap();
+        assert_table_created_as_strict(connection.as_ref(), "ledger");
+        let expected_key_words: &[&[&str]] = &[
+            &["timestamp", "integer", "not", "null"],
+            &["wallet_address", "text", "not", "null"],
+            &["entry_type", "text", "not", "null"],
+            &["bytes", "integer", "not", "null"],
+            &["amount_high_b", "integer", "not", "null"],
+            &["amount_low_b", "integer", "not", "null"],
+            &["transaction_hash", "text", "null"],
+            &["entry_count", "integer", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            connection.as_ref(),
+            "ledger",
+            expected_key_words,
+        );
+        let expected_key_words: &[&[&str]] = &[&["timestamp"]];
+        assert_index_stm_is_coupled_with_right_parameter(
+            connection.as_ref(),
+            "ledger_timestamp_idx",
+            expected_key_words,
+        );
+        let expected_key_words: &[&[&str]] = &[&["wallet_address"]];
+        assert_index_stm_is_coupled_with_right_parameter(
+            connection.as_ref(),
+            "ledger_wallet_address_idx",
+            expected_key_words,
+        );
+        let (schv_value, schv_encrypted) =
+            retrieve_config_row(connection.as_ref(), "schema_version");
+        assert_eq!(schv_value, Some("13".to_string()));
+        assert_eq!(schv_encrypted, false);
+    }
+}
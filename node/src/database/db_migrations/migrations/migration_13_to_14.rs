@@ -0,0 +1,114 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::database::db_migrations::db_migrator::DatabaseMigration;
+use crate::database::db_migrations::migrator_utils::DBMigDeclarator;
+
+#[allow(non_camel_case_types)]
+pub struct Migrate_13_to_14;
+
+impl DatabaseMigration for Migrate_13_to_14 {
+    fn migrate<'a>(
+        &self,
+        declaration_utils: Box<dyn DBMigDeclarator + 'a>,
+    ) -> rusqlite::Result<()> {
+        // Bans made before this migration were all automatic and stay without a reason or time
+        declaration_utils.execute_upon_transaction(&[
+            &"alter table banned add reason text null",
+            &"alter table banned add timestamp integer null",
+            &"alter table banned add manual integer not null default 0",
+        ])
+    }
+
+    fn old_version(&self) -> usize {
+        13
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal, DATABASE_FILE,
+    };
+    use crate::database::db_migrations::db_migrator::DatabaseMigration;
+    use crate::database::db_migrations::migrations::migration_13_to_14::Migrate_13_to_14;
+    use crate::test_utils::database_utils::{
+        assert_create_table_stm_contains_all_parts, bring_db_0_back_to_life_and_return_connection,
+        make_external_data, retrieve_config_row,
+    };
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use rusqlite::types::Value::Null;
+
+    #[test]
+    fn old_version_says_13() {
+        let subject = Migrate_13_to_14 {};
+
+        let result = subject.old_version();
+
+        assert_eq!(result, 13);
+    }
+
+    #[test]
+    fn migration_from_13_to_14_is_properly_set() {
+        let start_at = Migrate_13_to_14 {}.old_version();
+        let dir_path = ensure_node_home_directory_exists(
+            "db_migrations",
+            "migration_from_13_to_14_is_properly_set",
+        );
+        let db_path = dir_path.join(DATABASE_FILE);
+        let _ = bring_db_0_back_to_life_and_return_connection(&db_path);
+        let subject = DbInitializerReal::default();
+        {
+            let conn = subject
+                .initialize_to_version(&dir_path, start_at, DbInitializationConfig::test_default())
+                .unwrap();
+            conn.prepare("insert into banned (wallet_address) values ('0x000000000000000000000000000000626f6f6761')")
+                .unwrap()
+                .execute([])
+                .unwrap();
+        }
+
+        let result = subject.initialize_to_version(
+            &dir_path,
+            start_at + 1,
+            DbInitializationConfig::create_or_migrate(make_external_data()),
+        );
+
+        let connection = result.unwrap();
+        let expected_key_words: &[&[&str]] = &[
+            &["wallet_address", "text", "primary", "key"],
+            &["reason", "text", "null"],
+            &["timestamp", "integer", "null"],
+            &["manual", "integer", "not", "null", "default", "0"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            connection.as_ref(),
+            "banned",
+            expected_key_words,
+        );
+        let old_record = connection
+            .prepare("select wallet_address, reason, timestamp, manual from banned")
+            .unwrap()
+            .query_row([], |row| {
+                Ok((
+                    row.get::<usize, String>(0).unwrap(),
+                    row.get::<usize, rusqlite::types::Value>(1).unwrap(),
+                    row.get::<usize, rusqlite::types::Value>(2).unwrap(),
+                    row.get::<usize, i64>(3).unwrap(),
+                ))
+            })
+            .unwrap();
+        assert_eq!(
+            old_record,
+            (
+                "0x000000000000000000000000000000626f6f6761".to_string(),
+                Null,
+                Null,
+                0
+            )
+        );
+        let (schv_value, schv_encrypted) =
+            retrieve_config_row(connection.as_ref(), "schema_version");
+        assert_eq!(schv_value, Some("14".to_string()));
+        assert_eq!(schv_encrypted, false);
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::database::db_migrations::db_migrator::DatabaseMigration;
+use crate::database::db_migrations::migrator_utils::DBMigDeclarator;
+
+#[allow(non_camel_case_types)]
+pub struct Migrate_14_to_15;
+
+impl DatabaseMigration for Migrate_14_to_15 {
+    fn migrate<'a>(
+        &self,
+        declaration_utils: Box<dyn DBMigDeclarator + 'a>,
+    ) -> rusqlite::Result<()> {
+        declaration_utils.execute_upon_transaction(&[
+            &"create table if not exists service_receipt (
+                    consumer_key blob not null,
+                    consuming_wallet_address text not null,
+                    provider_wallet_address text not null,
+                    route_id integer not null,
+                    first_service integer not null,
+                    services integer not null,
+                    bytes integer not null,
+                    amount_high_b integer not null,
+                    amount_low_b integer not null,
+                    signature blob not null,
+                    received integer not null
+            ) strict",
+            &"CREATE UNIQUE INDEX service_receipt_route_idx ON service_receipt (consumer_key, route_id, provider_wallet_address, first_service)",
+        ])
+    }
+
+    fn old_version(&self) -> usize {
+        14
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal, DATABASE_FILE,
+    };
+    use crate::database::db_migrations::db_migrator::DatabaseMigration;
+    use crate::database::db_migrations::migrations::migration_14_to_15::Migrate_14_to_15;
+    use crate::test_utils::database_utils::{
+        assert_create_table_stm_contains_all_parts,
+        assert_index_stm_is_coupled_with_right_parameter, assert_table_created_as_strict,
+        bring_db_0_back_to_life_and_return_connection, make_external_data, retrieve_config_row,
+    };
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+
+    #[test]
+    fn old_version_says_14() {
+        let subject = Migrate_14_to_15 {};
+
+        let result = subject.old_version();
+
+        assert_eq!(result, 14);
+    }
+
+    #[test]
+    fn migration_from_14_to_15_is_properly_set() {
+        let start_at = Migrate_14_to_15 {}.old_version();
+        let dir_path = ensure_node_home_directory_exists(
+            "db_migrations",
+            "migration_from_14_to_15_is_properly_set",
+        );
+        let db_path = dir_path.join(DATABASE_FILE);
+        let _ = bring_db_0_back_to_life_and_return_connection(&db_path);
+        let subject = DbInitializerReal::default();
+        {
+            subject
+                .initialize_to_version(&dir_path, start_at, DbInitializationConfig::test_default())
+                .unwrap();
+        }
+
+        let result = subject.initialize_to_version(
+            &dir_path,
+            start_at + 1,
+            DbInitializationConfig::create_or_migrate(make_external_data()),
+        );
+
+        let connection = result.unwrap();
+        assert_table_created_as_strict(connection.as_ref(), "service_receipt");
+        let expected_key_words: &[&[&str]] = &[
+            &["consumer_key", "blob", "not", "null"],
+            &["consuming_wallet_address", "text", "not", "null"],
+            &["provider_wallet_address", "text", "not", "null"],
+            &["route_id", "integer", "not", "null"],
+            &["first_service", "integer", "not", "null"],
+            &["services", "integer", "not", "null"],
+            &["bytes", "integer", "not", "null"],
+            &["amount_high_b", "integer", "not", "null"],
+            &["amount_low_b", "integer", "not", "null"],
+            &["signature", "blob", "not", "null"],
+            &["received", "integer", "not", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            connection.as_ref(),
+            "service_receipt",
+            expected_key_words,
+        );
+        let expected_key_words: &[&[&str]] = &[
+            &["consumer_key"],
+            &["route_id"],
+            &["provider_wallet_address"],
+            &["first_service"],
+        ];
+        assert_index_stm_is_coupled_with_right_parameter(
+            connection.as_ref(),
+            "service_receipt_route_idx",
+            expected_key_words,
+        );
+        let (schv_value, schv_encrypted) =
+            retrieve_config_row(connection.as_ref(), "schema_version");
+        assert_eq!(schv_value, Some("15".to_string()));
+        assert_eq!(schv_encrypted, false);
+    }
+}
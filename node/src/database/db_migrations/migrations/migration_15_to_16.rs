@@ -0,0 +1,96 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::database::db_migrations::db_migrator::DatabaseMigration;
+use crate::database::db_migrations::migrator_utils::DBMigDeclarator;
+
+#[allow(non_camel_case_types)]
+pub struct Migrate_15_to_16;
+
+impl DatabaseMigration for Migrate_15_to_16 {
+    fn migrate<'a>(
+        &self,
+        declaration_utils: Box<dyn DBMigDeclarator + 'a>,
+    ) -> rusqlite::Result<()> {
+        // A multisend transaction pays several creditors, each of whom gets a fingerprint of its own;
+        // databases that went through the rebuild of the table in 6_to_7 lost the index altogether
+        declaration_utils.execute_upon_transaction(&[
+            &"drop index if exists pending_payable_hash_idx",
+            &"create unique index pending_payable_hash_idx ON pending_payable (transaction_hash, recipient_address)",
+        ])
+    }
+
+    fn old_version(&self) -> usize {
+        15
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal, DATABASE_FILE,
+    };
+    use crate::database::db_migrations::db_migrator::DatabaseMigration;
+    use crate::database::db_migrations::migrations::migration_15_to_16::Migrate_15_to_16;
+    use crate::test_utils::database_utils::{
+        assert_index_stm_is_coupled_with_right_parameter,
+        bring_db_0_back_to_life_and_return_connection, make_external_data, retrieve_config_row,
+    };
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+
+    #[test]
+    fn old_version_says_15() {
+        let subject = Migrate_15_to_16 {};
+
+        let result = subject.old_version();
+
+        assert_eq!(result, 15);
+    }
+
+    #[test]
+    fn migration_from_15_to_16_is_properly_set() {
+        let start_at = Migrate_15_to_16 {}.old_version();
+        let dir_path = ensure_node_home_directory_exists(
+            "db_migrations",
+            "migration_from_15_to_16_is_properly_set",
+        );
+        let db_path = dir_path.join(DATABASE_FILE);
+        let _ = bring_db_0_back_to_life_and_return_connection(&db_path);
+        let subject = DbInitializerReal::default();
+        {
+            let conn = subject
+                .initialize_to_version(&dir_path, start_at, DbInitializationConfig::test_default())
+                .unwrap();
+            conn.prepare(
+                "insert into pending_payable (rowid, transaction_hash, amount_high_b, amount_low_b, \
+                payable_timestamp, attempt, process_error) values (1, '0xabc', 0, 1234, 1500000000, 1, null)",
+            )
+            .unwrap()
+            .execute([])
+            .unwrap();
+        }
+
+        let result = subject.initialize_to_version(
+            &dir_path,
+            start_at + 1,
+            DbInitializationConfig::create_or_migrate(make_external_data()),
+        );
+
+        let connection = result.unwrap();
+        let expected_key_words: &[&[&str]] = &[&["transaction_hash"], &["recipient_address"]];
+        assert_index_stm_is_coupled_with_right_parameter(
+            connection.as_ref(),
+            "pending_payable_hash_idx",
+            expected_key_words,
+        );
+        let count: i64 = connection
+            .prepare("select count(*) from pending_payable where transaction_hash = '0xabc'")
+            .unwrap()
+            .query_row([], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+        let (schv_value, schv_encrypted) =
+            retrieve_config_row(connection.as_ref(), "schema_version");
+        assert_eq!(schv_value, Some("16".to_string()));
+        assert_eq!(schv_encrypted, false);
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::database::db_migrations::db_migrator::DatabaseMigration;
+use crate::database::db_migrations::migrator_utils::DBMigDeclarator;
+
+#[allow(non_camel_case_types)]
+pub struct Migrate_9_to_10;
+
+impl DatabaseMigration for Migrate_9_to_10 {
+    fn migrate<'a>(
+        &self,
+        declaration_utils: Box<dyn DBMigDeclarator + 'a>,
+    ) -> rusqlite::Result<()> {
+        // Records from before this migration lack the details needed to replace their transaction
+        // and therefore keep nulls in the new columns
+        declaration_utils.execute_upon_transaction(&[
+            &"alter table pending_payable add nonce integer null",
+            &"alter table pending_payable add recipient_address text null",
+            &"alter table pending_payable add max_fee_per_gas_wei_high_b integer null",
+            &"alter table pending_payable add max_fee_per_gas_wei_low_b integer null",
+            &"alter table pending_payable add max_priority_fee_per_gas_wei_high_b integer null",
+            &"alter table pending_payable add max_priority_fee_per_gas_wei_low_b integer null",
+            &"alter table pending_payable add replaced_hashes text not null default ''",
+        ])
+    }
+
+    fn old_version(&self) -> usize {
+        9
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal, DATABASE_FILE,
+    };
+    use crate::database::db_migrations::db_migrator::DatabaseMigration;
+    use crate::database::db_migrations::migrations::migration_9_to_10::Migrate_9_to_10;
+    use crate::test_utils::database_utils::{
+        assert_create_table_stm_contains_all_parts, bring_db_0_back_to_life_and_return_connection,
+        make_external_data, retrieve_config_row,
+    };
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use rusqlite::types::Value::Null;
+
+    #[test]
+    fn old_version_says_9() {
+        let subject = Migrate_9_to_10 {};
+
+        let result = subject.old_version();
+
+        assert_eq!(result, 9);
+    }
+
+    #[test]
+    fn migration_from_9_to_10_is_properly_set() {
+        let start_at = Migrate_9_to_10 {}.old_version();
+        let dir_path = ensure_node_home_directory_exists(
+            "db_migrations",
+            "migration_from_9_to_10_is_properly_set",
+        );
+        let db_path = dir_path.join(DATABASE_FILE);
+        let _ = bring_db_0_back_to_life_and_return_connection(&db_path);
+        let subject = DbInitializerReal::default();
+        {
+            let conn = subject
+                .initialize_to_version(&dir_path, start_at, DbInitializationConfig::test_default())
+                .unwrap();
+            conn.prepare(
+                "insert into pending_payable (transaction_hash, amount_high_b, amount_low_b, \
+                payable_timestamp, attempt, process_error) values ('0x1234', 0, 456, 1000, 3, null)",
+            )
+            .unwrap()
+            .execute([])
+            .unwrap();
+        }
+
+        let result = subject.initialize_to_version(
+            &dir_path,
+            start_at + 1,
+            DbInitializationConfig::create_or_migrate(make_external_data()),
+        );
+
+        let connection = result.unwrap();
+        let expected_key_words: &[&[&str]] = &[
+            &["rowid", "integer", "primary", "key"],
+            &["transaction_hash", "text", "not", "null"],
+            &["amount_high_b", "integer", "not", "null"],
+            &["amount_low_b", "integer", "not", "null"],
+            &["payable_timestamp", "integer", "not", "null"],
+            &["attempt", "integer", "not", "null"],
+            &["process_error", "text", "null"],
+            &["nonce", "integer", "null"],
+            &["recipient_address", "text", "null"],
+            &["max_fee_per_gas_wei_high_b", "integer", "null"],
+            &["max_fee_per_gas_wei_low_b", "integer", "null"],
+            &["max_priority_fee_per_gas_wei_high_b", "integer", "null"],
+            &["max_priority_fee_per_gas_wei_low_b", "integer", "null"],
+            &["replaced_hashes", "text", "not", "null", "default", "''"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            connection.as_ref(),
+            "pending_payable",
+            expected_key_words,
+        );
+        let old_record = connection
+            .prepare(
+                "select transaction_hash, attempt, nonce, recipient_address, \
+                max_fee_per_gas_wei_high_b, max_fee_per_gas_wei_low_b, \
+                max_priority_fee_per_gas_wei_high_b, max_priority_fee_per_gas_wei_low_b, \
+                replaced_hashes from pending_payable",
+            )
+            .unwrap()
+            .query_row([], |row| {
+                Ok((
+                    row.get::<usize, String>(0).unwrap(),
+                    row.get::<usize, i64>(1).unwrap(),
+                    row.get::<usize, rusqlite::types::Value>(2).unwrap(),
+                    row.get::<usize, rusqlite::types::Value>(3).unwrap(),
+                    row.get::<usize, rusqlite::types::Value>(4).unwrap(),
+                    row.get::<usize, rusqlite::types::Value>(5).unwrap(),
+                    row.get::<usize, rusqlite::types::Value>(6).unwrap(),
+                    row.get::<usize, rusqlite::types::Value>(7).unwrap(),
+                    row.get::<usize, String>(8).unwrap(),
+                ))
+            })
+            .unwrap();
+        assert_eq!(
+            old_record,
+            (
+                "0x1234".to_string(),
+                3,
+                Null,
+                Null,
+                Null,
+                Null,
+                Null,
+                Null,
+                String::new()
+            )
+        );
+        let (schv_value, schv_encrypted) =
+            retrieve_config_row(connection.as_ref(), "schema_version");
+        assert_eq!(schv_value, Some("10".to_string()));
+        assert_eq!(schv_encrypted, false);
+    }
+}
@@ -8,7 +8,9 @@ use crate::sub_lib::accountant::{DEFAULT_PAYMENT_THRESHOLDS, DEFAULT_SCAN_INTERV
 use crate::sub_lib::neighborhood::DEFAULT_RATE_PACK;
 use itertools::Itertools;
 use masq_lib::blockchains::chains::Chain;
-use masq_lib::constants::{CURRENT_SCHEMA_VERSION, DEFAULT_GAS_PRICE};
+use masq_lib::constants::{
+    CURRENT_SCHEMA_VERSION, DEFAULT_BLOCKCHAIN_SERVICE_QUORUM, DEFAULT_GAS_PRICE,
+};
 use std::collections::HashMap;
 
 /*
@@ -118,6 +120,10 @@ impl Default for ConfigDaoNull {
             (Some("standard".to_string()), false),
         );
         data.insert("blockchain_service_url".to_string(), (None, false));
+        data.insert(
+            "blockchain_service_quorum".to_string(),
+            (Some(DEFAULT_BLOCKCHAIN_SERVICE_QUORUM.to_string()), false),
+        );
         data.insert("past_neighbors".to_string(), (None, true));
         data.insert("mapping_protocol".to_string(), (None, false));
         data.insert(
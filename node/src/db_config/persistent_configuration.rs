@@ -11,6 +11,7 @@ use crate::db_config::typed_config_layer::{
     TypedConfigLayerError,
 };
 use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
+use crate::sub_lib::blockchain_bridge::blockchain_service_urls;
 use crate::sub_lib::cryptde::PlainData;
 use crate::sub_lib::neighborhood::{Hops, NodeDescriptor, RatePack};
 use crate::sub_lib::wallet::Wallet;
@@ -84,6 +85,8 @@ impl PersistentConfigError {
 pub trait PersistentConfiguration {
     fn blockchain_service_url(&self) -> Result<Option<String>, PersistentConfigError>;
     fn set_blockchain_service_url(&mut self, url: &str) -> Result<(), PersistentConfigError>;
+    fn blockchain_service_quorum(&self) -> Result<u16, PersistentConfigError>;
+    fn set_blockchain_service_quorum(&mut self, quorum: u16) -> Result<(), PersistentConfigError>;
     fn current_schema_version(&self) -> String;
     fn chain_name(&self) -> String;
     fn check_password(
@@ -170,12 +173,36 @@ impl PersistentConfiguration for PersistentConfigurationReal {
     }
 
     fn set_blockchain_service_url(&mut self, url: &str) -> Result<(), PersistentConfigError> {
-        Url::parse(url).map_err(|e| PersistentConfigError::InvalidUrl(e.to_string()))?;
+        let urls = blockchain_service_urls(url);
+        if urls.is_empty() {
+            return Err(PersistentConfigError::InvalidUrl(
+                "No URL given".to_string(),
+            ));
+        }
+        urls.into_iter().try_for_each(|url| {
+            Url::parse(url)
+                .map(|_| ())
+                .map_err(|e| PersistentConfigError::InvalidUrl(e.to_string()))
+        })?;
         Ok(self
             .dao
             .set("blockchain_service_url", Some(url.to_string()))?)
     }
 
+    fn blockchain_service_quorum(&self) -> Result<u16, PersistentConfigError> {
+        let quorum = self.simple_get_method(decode_u64, "blockchain_service_quorum")?;
+        u16::try_from(quorum).map_err(|_| {
+            PersistentConfigError::UninterpretableValue(format!(
+                "Blockchain service quorum {} is out of range",
+                quorum
+            ))
+        })
+    }
+
+    fn set_blockchain_service_quorum(&mut self, quorum: u16) -> Result<(), PersistentConfigError> {
+        self.simple_set_method("blockchain_service_quorum", quorum)
+    }
+
     fn current_schema_version(&self) -> String {
         match self.get("schema_version") {
             Ok(record_opt) => match record_opt {
@@ -923,6 +950,123 @@ mod tests {
         );
     }
 
+    #[test]
+    fn set_blockchain_service_url_accepts_several_urls() {
+        let set_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = Box::new(
+            ConfigDaoMock::new()
+                .set_params(&set_params_arc)
+                .set_result(Ok(())),
+        );
+        let mut subject = PersistentConfigurationReal::new(config_dao);
+
+        let result =
+            subject.set_blockchain_service_url("https://ifura.io/ID,http://localhost:8545");
+
+        assert_eq!(result, Ok(()));
+        let set_params = set_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_params,
+            vec![(
+                "blockchain_service_url".to_string(),
+                Some("https://ifura.io/ID,http://localhost:8545".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn set_blockchain_service_url_complains_if_any_of_several_urls_is_invalid() {
+        let config_dao = Box::new(ConfigDaoMock::new().set_result(Ok(())));
+        let mut subject = PersistentConfigurationReal::new(config_dao);
+
+        let result = subject.set_blockchain_service_url("https://ifura.io/ID,https.ifura.io");
+
+        assert_eq!(
+            result,
+            Err(PersistentConfigError::InvalidUrl(
+                "relative URL without a base".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn set_blockchain_service_url_complains_if_no_url_is_given() {
+        let config_dao = Box::new(ConfigDaoMock::new().set_result(Ok(())));
+        let mut subject = PersistentConfigurationReal::new(config_dao);
+
+        let result = subject.set_blockchain_service_url(" , ");
+
+        assert_eq!(
+            result,
+            Err(PersistentConfigError::InvalidUrl(
+                "No URL given".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn blockchain_service_quorum() {
+        let get_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = Box::new(
+            ConfigDaoMock::new()
+                .get_params(&get_params_arc)
+                .get_result(Ok(ConfigDaoRecord::new(
+                    "blockchain_service_quorum",
+                    Some("2"),
+                    false,
+                ))),
+        );
+        let subject = PersistentConfigurationReal::new(config_dao);
+
+        let result = subject.blockchain_service_quorum();
+
+        assert_eq!(result, Ok(2));
+        let get_params = get_params_arc.lock().unwrap();
+        assert_eq!(*get_params, vec!["blockchain_service_quorum".to_string()]);
+    }
+
+    #[test]
+    fn blockchain_service_quorum_complains_about_value_out_of_range() {
+        let config_dao = Box::new(ConfigDaoMock::new().get_result(Ok(ConfigDaoRecord::new(
+            "blockchain_service_quorum",
+            Some("65536"),
+            false,
+        ))));
+        let subject = PersistentConfigurationReal::new(config_dao);
+
+        let result = subject.blockchain_service_quorum();
+
+        assert_eq!(
+            result,
+            Err(PersistentConfigError::UninterpretableValue(
+                "Blockchain service quorum 65536 is out of range".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn set_blockchain_service_quorum() {
+        let set_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = Box::new(
+            ConfigDaoMock::new()
+                .set_params(&set_params_arc)
+                .set_result(Ok(())),
+        );
+        let mut subject = PersistentConfigurationReal::new(config_dao);
+
+        let result = subject.set_blockchain_service_quorum(3);
+
+        assert_eq!(result, Ok(()));
+        let set_params = set_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_params,
+            vec![(
+                "blockchain_service_quorum".to_string(),
+                Some("3".to_string())
+            )]
+        );
+    }
+
     #[test]
     fn clandestine_port_success() {
         let get_params_arc = Arc::new(Mutex::new(vec![]));
@@ -35,6 +35,7 @@ impl Clone for Box<dyn DiscriminatorFactory> {
 pub struct Discriminator {
     framer: Box<dyn Framer>,
     masqueraders: Vec<Box<dyn Masquerader>>,
+    peer_closing: bool,
     logger: Logger,
 }
 
@@ -46,6 +47,7 @@ impl Discriminator {
         Discriminator {
             framer,
             masqueraders,
+            peer_closing: false,
             logger: Logger::new("Discriminator"),
         }
     }
@@ -55,20 +57,41 @@ impl Discriminator {
     }
 
     pub fn take_chunk(&mut self) -> Option<UnmaskedChunk> {
-        let frame = match self.framer.take_frame() {
-            Some(frame) => frame,
-            None => return None,
-        };
-        for masquerader in &self.masqueraders {
-            match masquerader.try_unmask(&frame.chunk[..]) {
+        loop {
+            let frame = match self.framer.take_frame() {
+                Some(frame) => frame,
+                None => return None,
+            };
+            match self.unmask(&frame.chunk[..]) {
                 Ok(chunk) => return Some(chunk),
+                Err(MasqueradeError::NothingToUnmask) => (),
+                Err(MasqueradeError::PeerClosing) => {
+                    self.peer_closing = true;
+                    return None;
+                }
+                Err(_) => return None,
+            }
+        }
+    }
+
+    pub fn peer_is_closing(&self) -> bool {
+        self.peer_closing
+    }
+
+    fn unmask(&self, frame: &[u8]) -> Result<UnmaskedChunk, MasqueradeError> {
+        for masquerader in &self.masqueraders {
+            match masquerader.try_unmask(frame) {
+                Ok(chunk) => return Ok(chunk),
                 Err(MasqueradeError::NotThisMasquerader) => (),
+                Err(e @ (MasqueradeError::NothingToUnmask | MasqueradeError::PeerClosing)) => {
+                    return Err(e)
+                }
                 Err(e) => {
                     warning!(self.logger, "{}", e);
                 }
             }
         }
-        None
+        Err(MasqueradeError::NotThisMasquerader)
     }
 }
 
@@ -247,6 +270,56 @@ mod tests {
         assert_eq!(second_try_unmask_parameters_guard.len(), 0);
     }
 
+    #[test]
+    fn skips_frames_with_nothing_to_unmask() {
+        let mut framer = FramerMock::new();
+        framer.add_data(&b"ping"[..]);
+        framer.add_data(&b"booga"[..]);
+        let mut try_unmask_parameters: Arc<Mutex<Vec<Vec<u8>>>> = Arc::new(Mutex::new(vec![]));
+        let masquerader = MasqueraderMock::new()
+            .try_unmask_result(Err(MasqueradeError::NothingToUnmask))
+            .try_unmask_result(Ok(UnmaskedChunk::new(
+                Vec::from(&b"booga"[..]),
+                true,
+                false,
+            )))
+            .try_unmask_parameters(&mut try_unmask_parameters);
+        let mut subject = Discriminator::new(Box::new(framer), vec![Box::new(masquerader)]);
+
+        let result = subject.take_chunk();
+
+        assert_eq!(
+            result,
+            Some(UnmaskedChunk::new(Vec::from(&b"booga"[..]), true, false))
+        );
+        assert!(!subject.peer_is_closing());
+        assert_eq!(
+            *try_unmask_parameters.lock().unwrap(),
+            vec![Vec::from(&b"ping"[..]), Vec::from(&b"booga"[..])]
+        );
+    }
+
+    #[test]
+    fn remembers_that_the_peer_is_closing() {
+        let mut framer = FramerMock::new();
+        framer.add_data(&b"close"[..]);
+        framer.add_data(&b"booga"[..]);
+        let first_masquerader =
+            MasqueraderMock::new().try_unmask_result(Err(MasqueradeError::PeerClosing));
+        let second_masquerader = MasqueraderMock::new();
+        let mut subject = Discriminator::new(
+            Box::new(framer),
+            vec![Box::new(first_masquerader), Box::new(second_masquerader)],
+        );
+        let before = subject.peer_is_closing();
+
+        let result = subject.take_chunk();
+
+        assert!(!before);
+        assert_eq!(result, None);
+        assert!(subject.peer_is_closing());
+    }
+
     #[test]
     fn returns_third_data_if_first_masquerader_says_no_and_second_blows_up() {
         init_test_logging();
@@ -43,6 +43,7 @@ impl ConsumingService {
         let target_node_addr = incipient_cores_package.node_addr.clone();
         match LiveCoresPackage::from_no_lookup_incipient(incipient_cores_package, self.cryptde) {
             Ok((live_package, _)) => {
+                let live_package = live_package.stamped(SystemTime::now());
                 let encrypted_package = match encodex(self.cryptde, &target_key, &live_package) {
                     Ok(p) => p,
                     Err(e) => {
@@ -74,6 +75,7 @@ impl ConsumingService {
         );
         match LiveCoresPackage::from_incipient(incipient_cores_package, self.cryptde.borrow()) {
             Ok((live_package, next_hop)) => {
+                let live_package = live_package.stamped(SystemTime::now());
                 let encrypted_package =
                     match encodex(self.cryptde, &next_hop.public_key, &live_package) {
                         Ok(p) => p,
@@ -132,8 +134,9 @@ impl ConsumingService {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::node_test_utils::check_timestamp;
-    use crate::sub_lib::cryptde::PublicKey;
+    use crate::node_test_utils::{check_stamp_and_remove, check_timestamp};
+    use crate::sub_lib::cryptde::{decodex, PublicKey};
+    use crate::sub_lib::cryptde_null::CryptDENull;
     use crate::sub_lib::dispatcher::{Component, InboundClientData};
     use crate::sub_lib::node_addr::NodeAddr;
     use crate::sub_lib::route::Route;
@@ -168,23 +171,31 @@ mod tests {
             peer_actors.dispatcher.from_dispatcher_client,
             peer_actors.hopper.from_dispatcher,
         );
+        let before = SystemTime::now();
 
         subject.consume_no_lookup(package.clone());
 
         System::current().stop();
         system.run();
+        let after = SystemTime::now();
         let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
         let transmit_data_msg = dispatcher_recording.get_record::<TransmitDataMsg>(0);
-        let (lcp, _) = LiveCoresPackage::from_no_lookup_incipient(package, main_cryptde()).unwrap();
+        let target_cryptde = CryptDENull::from(&target_key, TEST_DEFAULT_CHAIN);
+        let actual_lcp =
+            decodex::<LiveCoresPackage>(&target_cryptde, &CryptData::new(&transmit_data_msg.data))
+                .unwrap();
+        let (expected_lcp, _) =
+            LiveCoresPackage::from_no_lookup_incipient(package, main_cryptde()).unwrap();
         assert_eq!(
-            &TransmitDataMsg {
-                endpoint: Endpoint::Socket(SocketAddr::from_str("1.2.1.2:1212").unwrap()),
-                last_data: false,
-                sequence_number: None,
-                data: encodex(main_cryptde(), &target_key, &lcp).unwrap().into(),
-            },
-            transmit_data_msg
+            check_stamp_and_remove(before, actual_lcp, after),
+            expected_lcp
+        );
+        assert_eq!(
+            transmit_data_msg.endpoint,
+            Endpoint::Socket(SocketAddr::from_str("1.2.1.2:1212").unwrap())
         );
+        assert!(!transmit_data_msg.last_data);
+        assert_eq!(transmit_data_msg.sequence_number, None);
     }
 
     #[test]
@@ -238,25 +249,28 @@ mod tests {
             peer_actors.dispatcher.from_dispatcher_client,
             peer_actors.hopper.from_dispatcher,
         );
+        let before = SystemTime::now();
 
         subject.consume(incipient_cores_package.clone());
 
         System::current().stop();
         system.run();
+        let after = SystemTime::now();
         let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
         let record = dispatcher_recording.get_record::<TransmitDataMsg>(0);
+        let destination_cryptde = CryptDENull::from(&destination_key, TEST_DEFAULT_CHAIN);
+        let actual_lcp =
+            decodex::<LiveCoresPackage>(&destination_cryptde, &CryptData::new(&record.data))
+                .unwrap();
         let (expected_lcp, _) =
             LiveCoresPackage::from_incipient(incipient_cores_package, cryptde).unwrap();
-        let expected_lcp_enc = encodex(cryptde, &destination_key, &expected_lcp).unwrap();
         assert_eq!(
-            TransmitDataMsg {
-                endpoint: Endpoint::Key(destination_key.clone()),
-                last_data: false,
-                sequence_number: None,
-                data: expected_lcp_enc.into(),
-            },
-            *record,
+            check_stamp_and_remove(before, actual_lcp, after),
+            expected_lcp
         );
+        assert_eq!(record.endpoint, Endpoint::Key(destination_key.clone()));
+        assert!(!record.last_data);
+        assert_eq!(record.sequence_number, None);
     }
 
     #[test]
@@ -295,11 +309,19 @@ mod tests {
         let hopper_recording = hopper_recording_arc.lock().unwrap();
         let record = hopper_recording.get_record::<InboundClientData>(0);
         check_timestamp(before, record.timestamp, after);
+        let actual_lcp =
+            decodex::<LiveCoresPackage>(cryptde, &CryptData::new(&record.data)).unwrap();
         let (expected_lcp, _) =
             LiveCoresPackage::from_incipient(incipient_cores_package, cryptde).unwrap();
-        let expected_lcp_enc = encodex(cryptde, &destination_key, &expected_lcp).unwrap();
         assert_eq!(
-            *record,
+            check_stamp_and_remove(before, actual_lcp, after),
+            expected_lcp
+        );
+        assert_eq!(
+            InboundClientData {
+                data: vec![],
+                ..record.clone()
+            },
             InboundClientData {
                 timestamp: record.timestamp,
                 peer_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 0),
@@ -307,7 +329,7 @@ mod tests {
                 last_data: false,
                 is_clandestine: true,
                 sequence_number: None,
-                data: expected_lcp_enc.into(),
+                data: vec![],
             },
         );
     }
@@ -10,12 +10,17 @@ use crate::sub_lib::hopper::{ExpiredCoresPackage, MessageType, NoLookupIncipient
 use crate::sub_lib::route::Route;
 use serde_derive::{Deserialize, Serialize};
 use std::net::SocketAddr;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LiveCoresPackage {
     pub version: DataVersion,
     pub route: Route,
     pub payload: CryptData,
+    // Set anew by every Node that sends the package on; only the next hop can read it, since the
+    // whole package travels encrypted to that hop's key. Packages from older Nodes come without it.
+    #[serde(default)]
+    pub sent_at_s: u64,
 }
 
 impl LiveCoresPackage {
@@ -23,14 +28,35 @@ impl LiveCoresPackage {
         DataVersion::new(0, 0).expect("Internal Error")
     }
 
+    // Packages at this version or later always carry the time they were sent
+    pub fn stamped_version() -> DataVersion {
+        DataVersion::new(0, 1).expect("Internal Error")
+    }
+
     pub fn new(route: Route, payload: CryptData) -> LiveCoresPackage {
         Self {
             version: Self::version(),
             route,
             payload,
+            sent_at_s: 0,
         }
     }
 
+    pub fn stamped(mut self, now: SystemTime) -> LiveCoresPackage {
+        self.version = Self::stamped_version();
+        self.sent_at_s = now
+            .duration_since(UNIX_EPOCH)
+            .expect("System clock is before the epoch")
+            .as_secs();
+        self
+    }
+
+    pub fn is_stamped(&self) -> bool {
+        let stamped_version = Self::stamped_version();
+        (self.version.major(), self.version.minor())
+            >= (stamped_version.major(), stamped_version.minor())
+    }
+
     pub fn into_next_live(
         mut self,
         cryptde: &dyn CryptDE, // must be the main CryptDE of the Node to which the top hop is encrypted
@@ -180,6 +206,44 @@ mod tests {
         ); // garbage
     }
 
+    #[test]
+    fn stamping_sets_the_send_time_and_the_stamped_version() {
+        let subject = LiveCoresPackage::new(Route { hops: vec![] }, CryptData::new(&[1, 2]));
+
+        let result = subject
+            .clone()
+            .stamped(UNIX_EPOCH + std::time::Duration::from_secs(1_234_567));
+
+        assert!(!subject.is_stamped());
+        assert!(result.is_stamped());
+        assert_eq!(result.version, LiveCoresPackage::stamped_version());
+        assert_eq!(result.sent_at_s, 1_234_567);
+    }
+
+    #[test]
+    fn packages_from_older_nodes_arrive_unstamped() {
+        #[derive(Serialize)]
+        struct OlderLiveCoresPackage {
+            version: DataVersion,
+            route: Route,
+            payload: CryptData,
+        }
+        let older_package = OlderLiveCoresPackage {
+            version: LiveCoresPackage::version(),
+            route: Route { hops: vec![] },
+            payload: CryptData::new(&[1, 2]),
+        };
+        let serialized = serde_cbor::ser::to_vec(&older_package).unwrap();
+
+        let result = serde_cbor::de::from_slice::<LiveCoresPackage>(&serialized).unwrap();
+
+        assert_eq!(
+            result,
+            LiveCoresPackage::new(Route { hops: vec![] }, CryptData::new(&[1, 2]))
+        );
+        assert!(!result.is_stamped());
+    }
+
     #[test]
     fn to_next_live_complains_about_bad_input() {
         let subject = LiveCoresPackage::new(Route { hops: vec![] }, CryptData::new(&[]));
@@ -258,7 +322,8 @@ mod tests {
             LiveHop {
                 public_key: key34.clone(),
                 payer: Some(paying_wallet.as_payer(&key12, &contract_address)),
-                component: Component::Hopper
+                component: Component::Hopper,
+                originator_key_opt: None,
             },
             next_stop
         );
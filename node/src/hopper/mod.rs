@@ -2,24 +2,31 @@
 
 mod consuming_service;
 pub mod live_cores_package;
+mod relay_mixer;
+mod replay_cache;
 mod routing_service;
 
 use crate::bootstrapper::CryptDEPair;
 use crate::hopper::routing_service::RoutingServiceSubs;
+use crate::metrics::NodeMetrics;
 use crate::sub_lib::dispatcher::InboundClientData;
 use crate::sub_lib::hopper::HopperSubs;
 use crate::sub_lib::hopper::IncipientCoresPackage;
 use crate::sub_lib::hopper::{HopperConfig, NoLookupIncipientCoresPackage};
+use crate::sub_lib::neighborhood::{ConfigChange, ConfigChangeMsg};
 use crate::sub_lib::peer_actors::BindMessage;
 use crate::sub_lib::utils::{handle_ui_crash_request, NODE_MAILBOX_CAPACITY};
 use actix::Actor;
 use actix::Addr;
+use actix::AsyncContext;
 use actix::Context;
 use actix::Handler;
 use consuming_service::ConsumingService;
 use masq_lib::logger::Logger;
 use masq_lib::ui_gateway::NodeFromUiMessage;
 use routing_service::RoutingService;
+use std::sync::Arc;
+use std::time::Duration;
 
 pub const CRASH_KEY: &str = "HOPPER";
 
@@ -30,6 +37,8 @@ pub struct Hopper {
     per_routing_service: u64,
     per_routing_byte: u64,
     is_decentralized: bool,
+    relay_batch_latency_opt: Option<Duration>,
+    metrics: Arc<NodeMetrics>,
     logger: Logger,
     crashable: bool,
 }
@@ -57,11 +66,27 @@ impl Handler<BindMessage> for Hopper {
                 hopper_subs: msg.peer_actors.hopper,
                 to_dispatcher: msg.peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: msg.peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: msg.peer_actors.accountant.service_receipt,
             },
             self.per_routing_service,
             self.per_routing_byte,
             self.is_decentralized,
+            self.relay_batch_latency_opt,
+            self.metrics.clone(),
         ));
+        if let Some(flush_interval) = self
+            .routing_service
+            .as_ref()
+            .and_then(|routing_service| routing_service.relay_flush_interval_opt())
+        {
+            ctx.run_interval(flush_interval, |hopper, _ctx| {
+                hopper
+                    .routing_service
+                    .as_mut()
+                    .expect("Hopper unbound: no RoutingService")
+                    .flush_relays()
+            });
+        }
     }
 }
 
@@ -100,7 +125,7 @@ impl Handler<InboundClientData> for Hopper {
 
     fn handle(&mut self, msg: InboundClientData, _ctx: &mut Self::Context) -> Self::Result {
         self.routing_service
-            .as_ref()
+            .as_mut()
             .expect("Hopper unbound: no RoutingService")
             .route(msg);
     }
@@ -114,6 +139,14 @@ impl Handler<NodeFromUiMessage> for Hopper {
     }
 }
 
+impl Handler<ConfigChangeMsg> for Hopper {
+    type Result = ();
+
+    fn handle(&mut self, msg: ConfigChangeMsg, _ctx: &mut Self::Context) -> Self::Result {
+        self.handle_config_change_msg(msg)
+    }
+}
+
 impl Hopper {
     pub fn new(config: HopperConfig) -> Hopper {
         Hopper {
@@ -124,6 +157,8 @@ impl Hopper {
             per_routing_service: config.per_routing_service,
             per_routing_byte: config.per_routing_byte,
             is_decentralized: config.is_decentralized,
+            relay_batch_latency_opt: config.relay_batch_latency_opt,
+            metrics: config.metrics,
             logger: Logger::new("Hopper"),
         }
     }
@@ -135,6 +170,26 @@ impl Hopper {
             from_hopper_client_no_lookup: recipient!(addr, NoLookupIncipientCoresPackage),
             from_dispatcher: recipient!(addr, InboundClientData),
             node_from_ui: recipient!(addr, NodeFromUiMessage),
+            config_change_msg_sub: recipient!(addr, ConfigChangeMsg),
+        }
+    }
+
+    fn handle_config_change_msg(&mut self, msg: ConfigChangeMsg) {
+        if let ConfigChange::UpdateRatePack(rate_pack) = msg.change {
+            debug!(
+                self.logger,
+                "Routing rates changed to {} per CORES package and {} per byte",
+                rate_pack.routing_service_rate,
+                rate_pack.routing_byte_rate
+            );
+            self.per_routing_service = rate_pack.routing_service_rate;
+            self.per_routing_byte = rate_pack.routing_byte_rate;
+            if let Some(routing_service) = self.routing_service.as_mut() {
+                routing_service
+                    .set_routing_rates(rate_pack.routing_service_rate, rate_pack.routing_byte_rate)
+            }
+        } else {
+            trace!(self.logger, "Ignored irrelevant message: {:?}", msg);
         }
     }
 }
@@ -147,6 +202,7 @@ mod tests {
     use crate::sub_lib::cryptde::PublicKey;
     use crate::sub_lib::dispatcher::Component;
     use crate::sub_lib::hopper::IncipientCoresPackage;
+    use crate::sub_lib::neighborhood::RatePack;
     use crate::sub_lib::route::Route;
     use crate::sub_lib::route::RouteSegment;
     use crate::test_utils::unshared_test_utils::prove_that_crash_request_handler_is_hooked_up;
@@ -156,6 +212,7 @@ mod tests {
     };
     use actix::Actor;
     use actix::System;
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
     use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
     use std::net::SocketAddr;
     use std::str::FromStr;
@@ -166,6 +223,38 @@ mod tests {
         assert_eq!(CRASH_KEY, "HOPPER");
     }
 
+    #[test]
+    fn hopper_takes_routing_rates_from_a_new_rate_pack() {
+        init_test_logging();
+        let mut subject = Hopper::new(HopperConfig {
+            cryptdes: make_cryptde_pair(),
+            per_routing_service: 100,
+            per_routing_byte: 200,
+            is_decentralized: true,
+            relay_batch_latency_opt: None,
+            metrics: Arc::new(NodeMetrics::default()),
+            crashable: false,
+        });
+
+        subject.handle_config_change_msg(ConfigChangeMsg {
+            change: ConfigChange::UpdateRatePack(RatePack {
+                routing_byte_rate: 201,
+                routing_service_rate: 101,
+                exit_byte_rate: 401,
+                exit_service_rate: 301,
+            }),
+        });
+        subject.handle_config_change_msg(ConfigChangeMsg {
+            change: ConfigChange::UpdatePassword("booga".to_string()),
+        });
+
+        assert_eq!(subject.per_routing_service, 101);
+        assert_eq!(subject.per_routing_byte, 201);
+        TestLogHandler::new().exists_log_containing(
+            "DEBUG: Hopper: Routing rates changed to 101 per CORES package and 201 per byte",
+        );
+    }
+
     #[test]
     #[should_panic(expected = "Hopper unbound: no RoutingService")]
     fn panics_if_routing_service_is_unbound() {
@@ -205,6 +294,8 @@ mod tests {
             per_routing_service: 100,
             per_routing_byte: 200,
             is_decentralized: false,
+            relay_batch_latency_opt: None,
+            metrics: Arc::new(NodeMetrics::default()),
             crashable: false,
         });
         let subject_addr = subject.start();
@@ -248,6 +339,8 @@ mod tests {
             per_routing_service: 100,
             per_routing_byte: 200,
             is_decentralized: false,
+            relay_batch_latency_opt: None,
+            metrics: Arc::new(NodeMetrics::default()),
             crashable: false,
         });
         let subject_addr = subject.start();
@@ -268,6 +361,8 @@ mod tests {
             per_routing_service: 100,
             per_routing_byte: 200,
             is_decentralized: false,
+            relay_batch_latency_opt: None,
+            metrics: Arc::new(NodeMetrics::default()),
             crashable: true,
         });
 
@@ -0,0 +1,263 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::stream_handler_pool::TransmitDataMsg;
+use masq_lib::logger::Logger;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+pub const RELAY_MIXER_FLUSHES_PER_LATENCY_BUDGET: u32 = 4;
+pub const RELAY_MIXING_STATISTICS_INTERVAL: Duration = Duration::from_secs(300);
+
+struct HeldRelay {
+    msg: TransmitDataMsg,
+    held_at: Instant,
+    release_at: Instant,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RelayMixingStatistics {
+    pub packages: u64,
+    pub batches: u64,
+    pub total_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RelayMixingStatistics {
+    pub fn mean_delay(&self) -> Duration {
+        if self.packages == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_nanos((self.total_delay.as_nanos() / self.packages as u128) as u64)
+        }
+    }
+}
+
+// A relay that forwards every package the moment it arrives lets anybody watching its traffic
+// pair up incoming and outgoing packages by timing alone. Holding each package for a random
+// interval and releasing whatever is due in shuffled batches breaks that pairing, at the cost of
+// added latency that never exceeds the latency budget.
+pub struct RelayMixer {
+    latency_budget: Duration,
+    held: Vec<HeldRelay>,
+    statistics: RelayMixingStatistics,
+    statistics_since: Instant,
+    logger: Logger,
+}
+
+impl RelayMixer {
+    pub fn new(latency_budget: Duration) -> RelayMixer {
+        RelayMixer {
+            latency_budget,
+            held: vec![],
+            statistics: RelayMixingStatistics::default(),
+            statistics_since: Instant::now(),
+            logger: Logger::new("RelayMixer"),
+        }
+    }
+
+    pub fn flush_interval(&self) -> Duration {
+        self.latency_budget / RELAY_MIXER_FLUSHES_PER_LATENCY_BUDGET
+    }
+
+    #[cfg(test)]
+    pub fn statistics(&self) -> &RelayMixingStatistics {
+        &self.statistics
+    }
+
+    #[cfg(test)]
+    pub fn held_count(&self) -> usize {
+        self.held.len()
+    }
+
+    pub fn hold(&mut self, msg: TransmitDataMsg, now: Instant) {
+        // Packages are released on the first flush after they're due, so leave room for one
+        // flush interval inside the budget.
+        let longest_hold = self.latency_budget - self.flush_interval();
+        let mut release_at = now + rand::thread_rng().gen_range(Duration::ZERO..=longest_hold);
+        if msg.last_data {
+            // The last package for a stream must not overtake the ones before it.
+            release_at = self
+                .held
+                .iter()
+                .filter(|held| held.msg.endpoint == msg.endpoint)
+                .map(|held| held.release_at)
+                .fold(release_at, |latest, candidate| latest.max(candidate));
+        }
+        self.held.push(HeldRelay {
+            msg,
+            held_at: now,
+            release_at,
+        });
+    }
+
+    pub fn flush(&mut self, now: Instant) -> Vec<TransmitDataMsg> {
+        let (due, still_held): (Vec<HeldRelay>, Vec<HeldRelay>) = std::mem::take(&mut self.held)
+            .into_iter()
+            .partition(|held| held.release_at <= now);
+        self.held = still_held;
+        if !due.is_empty() {
+            self.record_batch(&due, now);
+        }
+        self.maybe_report_statistics(now);
+        let mut batch = due.into_iter().map(|held| held.msg).collect::<Vec<_>>();
+        batch.shuffle(&mut rand::thread_rng());
+        batch.sort_by_key(|msg| msg.last_data);
+        batch
+    }
+
+    fn record_batch(&mut self, due: &[HeldRelay], now: Instant) {
+        due.iter().for_each(|held| {
+            let delay = now.duration_since(held.held_at);
+            self.statistics.total_delay += delay;
+            self.statistics.max_delay = self.statistics.max_delay.max(delay);
+        });
+        self.statistics.packages += due.len() as u64;
+        self.statistics.batches += 1;
+        debug!(
+            self.logger,
+            "Releasing batch of {} relayed packages; {} still held",
+            due.len(),
+            self.held.len()
+        );
+    }
+
+    fn maybe_report_statistics(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.statistics_since);
+        if elapsed < RELAY_MIXING_STATISTICS_INTERVAL {
+            return;
+        }
+        info!(
+            self.logger,
+            "Relayed {} packages in {} batches over the last {}s, adding {}ms of delay on average and {}ms at most",
+            self.statistics.packages,
+            self.statistics.batches,
+            elapsed.as_secs(),
+            self.statistics.mean_delay().as_millis(),
+            self.statistics.max_delay.as_millis()
+        );
+        self.statistics = RelayMixingStatistics::default();
+        self.statistics_since = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sub_lib::dispatcher::Endpoint;
+    use crate::test_utils::make_meaningless_public_key;
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+
+    fn make_msg(data: u8, last_data: bool) -> TransmitDataMsg {
+        TransmitDataMsg {
+            endpoint: Endpoint::Key(make_meaningless_public_key()),
+            last_data,
+            sequence_number: None,
+            data: vec![data],
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(RELAY_MIXER_FLUSHES_PER_LATENCY_BUDGET, 4);
+        assert_eq!(RELAY_MIXING_STATISTICS_INTERVAL, Duration::from_secs(300));
+    }
+
+    #[test]
+    fn flush_interval_is_a_fraction_of_the_latency_budget() {
+        let subject = RelayMixer::new(Duration::from_millis(400));
+
+        let result = subject.flush_interval();
+
+        assert_eq!(result, Duration::from_millis(100));
+    }
+
+    #[test]
+    fn nothing_is_released_before_it_could_be_due() {
+        let mut subject = RelayMixer::new(Duration::from_millis(400));
+        let now = Instant::now();
+        (0..10).for_each(|n| subject.hold(make_msg(n, false), now));
+
+        let result = subject.flush(now - Duration::from_millis(1));
+
+        assert!(result.is_empty());
+        assert_eq!(subject.held_count(), 10);
+        assert_eq!(subject.statistics(), &RelayMixingStatistics::default());
+    }
+
+    #[test]
+    fn everything_is_released_within_the_latency_budget() {
+        let mut subject = RelayMixer::new(Duration::from_millis(400));
+        let now = Instant::now();
+        (0..50).for_each(|n| subject.hold(make_msg(n, false), now));
+
+        let result = subject.flush(now + Duration::from_millis(300));
+
+        let mut data = result
+            .into_iter()
+            .map(|msg| msg.data[0])
+            .collect::<Vec<u8>>();
+        data.sort_unstable();
+        assert_eq!(data, (0..50).collect::<Vec<u8>>());
+        assert_eq!(subject.held_count(), 0);
+        let statistics = subject.statistics();
+        assert_eq!(statistics.packages, 50);
+        assert_eq!(statistics.batches, 1);
+        assert_eq!(statistics.max_delay, Duration::from_millis(300));
+        assert_eq!(statistics.mean_delay(), Duration::from_millis(300));
+    }
+
+    #[test]
+    fn last_data_is_released_no_earlier_than_the_rest_of_its_stream() {
+        let mut subject = RelayMixer::new(Duration::from_millis(400));
+        let now = Instant::now();
+        (0..20).for_each(|n| subject.hold(make_msg(n, false), now));
+        subject.hold(make_msg(20, true), now);
+        let latest_release = subject
+            .held
+            .iter()
+            .map(|held| held.release_at)
+            .max()
+            .unwrap();
+
+        let batch = subject.flush(latest_release);
+
+        assert_eq!(batch.len(), 21);
+        assert_eq!(batch.last().unwrap().data, vec![20]);
+        assert!(batch.last().unwrap().last_data);
+    }
+
+    #[test]
+    fn mean_delay_is_zero_without_packages() {
+        let subject = RelayMixingStatistics::default();
+
+        let result = subject.mean_delay();
+
+        assert_eq!(result, Duration::ZERO);
+    }
+
+    #[test]
+    fn statistics_are_logged_and_reset_periodically() {
+        init_test_logging();
+        let mut subject = RelayMixer::new(Duration::from_millis(400));
+        subject.logger = Logger::new("statistics_are_logged_and_reset_periodically");
+        let start = subject.statistics_since;
+        subject.hold(make_msg(1, false), start);
+        subject.hold(make_msg(2, false), start);
+        subject.flush(start + Duration::from_millis(300));
+        subject.hold(make_msg(3, false), start + Duration::from_millis(300));
+
+        subject.flush(start + RELAY_MIXING_STATISTICS_INTERVAL);
+
+        assert_eq!(subject.statistics(), &RelayMixingStatistics::default());
+        assert_eq!(
+            subject.statistics_since,
+            start + RELAY_MIXING_STATISTICS_INTERVAL
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: statistics_are_logged_and_reset_periodically: Relayed 3 packages in 2 batches over the last 300s, \
+             adding {}ms of delay on average and {}ms at most",
+            (300 + 300 + (RELAY_MIXING_STATISTICS_INTERVAL.as_millis() - 300)) / 3,
+            RELAY_MIXING_STATISTICS_INTERVAL.as_millis() - 300
+        ));
+    }
+}
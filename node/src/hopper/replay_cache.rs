@@ -0,0 +1,208 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::hopper::live_cores_package::LiveCoresPackage;
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+// Every Node stamps a CORES package when it sends it on. Packages stamped further in the past or
+// the future than these are refused outright, so the cache never has to remember anything longer
+// than the two together.
+pub const MAX_PACKAGE_AGE: Duration = Duration::from_secs(10 * 60);
+pub const MAX_CLOCK_SKEW: Duration = Duration::from_secs(10 * 60);
+pub const REPLAY_CACHE_TTL: Duration = Duration::from_secs(20 * 60);
+pub const REPLAY_CACHE_CAPACITY: usize = 500_000;
+
+type PackageDigest = [u8; sha1::DIGEST_LENGTH];
+
+// Routes are encrypted once and then reused for the life of a stream, so the top hop alone can't
+// tell one package from the next. Every legitimate package carries a freshly-encrypted payload,
+// though, so the top hop and the payload together identify a package; a byte-for-byte copy of a
+// package we've already seen is a replay. Sightings are kept in the order they happened, so expiry
+// takes them off the front. A sighting is never forgotten before it expires: if the cache fills up
+// with live sightings, new packages are refused until room opens up, since forgetting a sighting
+// early would let its package be replayed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sighting {
+    First,
+    Replay,
+    Overflow,
+}
+
+pub struct ReplayCache {
+    seen: HashSet<PackageDigest>,
+    sightings: VecDeque<(Instant, PackageDigest)>,
+    ttl: Duration,
+    capacity: usize,
+}
+
+impl ReplayCache {
+    pub fn new(ttl: Duration, capacity: usize) -> ReplayCache {
+        ReplayCache {
+            seen: HashSet::new(),
+            sightings: VecDeque::new(),
+            ttl,
+            capacity,
+        }
+    }
+
+    pub fn sight(&mut self, live_package: &LiveCoresPackage) -> Sighting {
+        let now = Instant::now();
+        self.forget_expired_sightings(now);
+        let digest = Self::digest(live_package);
+        if self.seen.contains(&digest) {
+            return Sighting::Replay;
+        }
+        if self.sightings.len() >= self.capacity {
+            return Sighting::Overflow;
+        }
+        self.seen.insert(digest);
+        self.sightings.push_back((now, digest));
+        Sighting::First
+    }
+
+    fn forget_expired_sightings(&mut self, now: Instant) {
+        while let Some((seen_at, digest)) = self.sightings.front() {
+            if now.duration_since(*seen_at) < self.ttl {
+                break;
+            }
+            self.seen.remove(digest);
+            self.sightings.pop_front();
+        }
+    }
+
+    // The stamp is left out on purpose: a copy of a package is still a copy with a new stamp on it
+    fn digest(live_package: &LiveCoresPackage) -> PackageDigest {
+        let mut hash = sha1::Sha1::new();
+        if let Some(top_hop) = live_package.route.hops.first() {
+            hash.update(top_hop.as_slice());
+        }
+        hash.update(live_package.payload.as_slice());
+        hash.digest().bytes()
+    }
+}
+
+impl Default for ReplayCache {
+    fn default() -> Self {
+        ReplayCache::new(REPLAY_CACHE_TTL, REPLAY_CACHE_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sub_lib::cryptde::CryptData;
+    use crate::sub_lib::route::Route;
+    use std::thread;
+
+    fn make_package(hops: Vec<&[u8]>, payload: &[u8]) -> LiveCoresPackage {
+        LiveCoresPackage::new(
+            Route {
+                hops: hops.into_iter().map(CryptData::new).collect(),
+            },
+            CryptData::new(payload),
+        )
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(MAX_PACKAGE_AGE, Duration::from_secs(600));
+        assert_eq!(MAX_CLOCK_SKEW, Duration::from_secs(600));
+        assert_eq!(REPLAY_CACHE_TTL, MAX_PACKAGE_AGE + MAX_CLOCK_SKEW);
+        assert_eq!(REPLAY_CACHE_CAPACITY, 500_000);
+    }
+
+    #[test]
+    fn first_sighting_is_not_a_replay_but_second_is() {
+        let mut subject = ReplayCache::default();
+        let package = make_package(vec![&[1, 2, 3], &[4, 5, 6]], &[7, 8, 9]);
+
+        let first = subject.sight(&package);
+        let second = subject.sight(&package.clone());
+
+        assert_eq!(first, Sighting::First);
+        assert_eq!(second, Sighting::Replay);
+    }
+
+    #[test]
+    fn packages_on_the_same_route_with_different_payloads_are_not_replays() {
+        let mut subject = ReplayCache::default();
+        let first_package = make_package(vec![&[1, 2, 3], &[4, 5, 6]], &[7, 8, 9]);
+        let second_package = make_package(vec![&[1, 2, 3], &[4, 5, 6]], &[9, 8, 7]);
+
+        let first = subject.sight(&first_package);
+        let second = subject.sight(&second_package);
+
+        assert_eq!(first, Sighting::First);
+        assert_eq!(second, Sighting::First);
+    }
+
+    #[test]
+    fn packages_with_the_same_payload_on_different_hops_are_not_replays() {
+        let mut subject = ReplayCache::default();
+        let first_package = make_package(vec![&[1, 2, 3], &[4, 5, 6]], &[7, 8, 9]);
+        let second_package = make_package(vec![&[4, 5, 6], &[1, 2, 3]], &[7, 8, 9]);
+
+        let first = subject.sight(&first_package);
+        let second = subject.sight(&second_package);
+
+        assert_eq!(first, Sighting::First);
+        assert_eq!(second, Sighting::First);
+    }
+
+    #[test]
+    fn replays_are_forgotten_after_ttl() {
+        let mut subject = ReplayCache::new(Duration::from_millis(10), REPLAY_CACHE_CAPACITY);
+        let package = make_package(vec![&[1, 2, 3]], &[7, 8, 9]);
+        subject.sight(&package);
+
+        thread::sleep(Duration::from_millis(20));
+        let result = subject.sight(&package);
+
+        assert_eq!(result, Sighting::First);
+    }
+
+    #[test]
+    fn new_packages_are_refused_rather_than_live_sightings_forgotten_when_capacity_is_reached() {
+        let mut subject = ReplayCache::new(REPLAY_CACHE_TTL, 2);
+        let first_package = make_package(vec![&[1]], &[1]);
+        let second_package = make_package(vec![&[2]], &[2]);
+        let third_package = make_package(vec![&[3]], &[3]);
+        subject.sight(&first_package);
+        subject.sight(&second_package);
+
+        let third_result = subject.sight(&third_package);
+        let first_result = subject.sight(&first_package);
+        let second_result = subject.sight(&second_package);
+
+        assert_eq!(third_result, Sighting::Overflow);
+        assert_eq!(first_result, Sighting::Replay);
+        assert_eq!(second_result, Sighting::Replay);
+        assert_eq!(subject.seen.len(), 2);
+        assert_eq!(subject.sightings.len(), 2);
+    }
+
+    #[test]
+    fn new_packages_are_accepted_again_once_sightings_expire() {
+        let mut subject = ReplayCache::new(Duration::from_millis(10), 1);
+        subject.sight(&make_package(vec![&[1]], &[1]));
+        let refused = subject.sight(&make_package(vec![&[2]], &[2]));
+
+        thread::sleep(Duration::from_millis(20));
+        let accepted = subject.sight(&make_package(vec![&[2]], &[2]));
+
+        assert_eq!(refused, Sighting::Overflow);
+        assert_eq!(accepted, Sighting::First);
+    }
+
+    #[test]
+    fn expired_sightings_are_dropped_from_both_collections() {
+        let mut subject = ReplayCache::new(Duration::from_millis(10), REPLAY_CACHE_CAPACITY);
+        subject.sight(&make_package(vec![&[1]], &[1]));
+        subject.sight(&make_package(vec![&[2]], &[2]));
+
+        thread::sleep(Duration::from_millis(20));
+        subject.sight(&make_package(vec![&[3]], &[3]));
+
+        assert_eq!(subject.seen.len(), 1);
+        assert_eq!(subject.sightings.len(), 1);
+    }
+}
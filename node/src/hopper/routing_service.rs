@@ -1,10 +1,13 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 use super::live_cores_package::LiveCoresPackage;
+use super::relay_mixer::RelayMixer;
+use super::replay_cache::{ReplayCache, Sighting, MAX_CLOCK_SKEW, MAX_PACKAGE_AGE};
 use crate::blockchain::payer::Payer;
 use crate::bootstrapper::CryptDEPair;
+use crate::metrics::NodeMetrics;
 use crate::neighborhood::gossip::Gossip_0v1;
-use crate::sub_lib::accountant::ReportRoutingServiceProvidedMessage;
-use crate::sub_lib::cryptde::{decodex, encodex, CryptData, CryptdecError};
+use crate::sub_lib::accountant::{ReportRoutingServiceProvidedMessage, ServiceReceipt_0v1};
+use crate::sub_lib::cryptde::{decodex, encodex, CryptData, CryptdecError, PublicKey};
 use crate::sub_lib::dispatcher::{Component, Endpoint, InboundClientData};
 use crate::sub_lib::hop::LiveHop;
 use crate::sub_lib::hopper::{ExpiredCoresPackage, HopperSubs, MessageType};
@@ -14,12 +17,14 @@ use crate::sub_lib::proxy_client::{
 };
 use crate::sub_lib::proxy_server::{ClientRequestPayload_0v1, ProxyServerSubs};
 use crate::sub_lib::stream_handler_pool::TransmitDataMsg;
+use crate::sub_lib::wallet::Wallet;
 use actix::Recipient;
 use masq_lib::logger::Logger;
 use std::borrow::Borrow;
 use std::convert::TryFrom;
 use std::net::SocketAddr;
-use std::time::SystemTime;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 pub struct RoutingServiceSubs {
     pub proxy_client_subs_opt: Option<ProxyClientSubs>,
@@ -28,6 +33,7 @@ pub struct RoutingServiceSubs {
     pub hopper_subs: HopperSubs,
     pub to_dispatcher: Recipient<TransmitDataMsg>,
     pub to_accountant_routing: Recipient<ReportRoutingServiceProvidedMessage>,
+    pub to_accountant_receipts: Recipient<ExpiredCoresPackage<ServiceReceipt_0v1>>,
 }
 
 pub struct RoutingService {
@@ -37,6 +43,9 @@ pub struct RoutingService {
     per_routing_byte: u64,
     logger: Logger,
     is_decentralized: bool,
+    replay_cache: ReplayCache,
+    relay_mixer_opt: Option<RelayMixer>,
+    metrics: Arc<NodeMetrics>,
 }
 
 impl RoutingService {
@@ -46,6 +55,8 @@ impl RoutingService {
         per_routing_service: u64,
         per_routing_byte: u64,
         is_decentralized: bool,
+        relay_batch_latency_opt: Option<Duration>,
+        metrics: Arc<NodeMetrics>,
     ) -> RoutingService {
         RoutingService {
             cryptdes,
@@ -54,10 +65,40 @@ impl RoutingService {
             per_routing_byte,
             logger: Logger::new("RoutingService"),
             is_decentralized,
+            replay_cache: ReplayCache::default(),
+            relay_mixer_opt: relay_batch_latency_opt.map(RelayMixer::new),
+            metrics,
         }
     }
 
-    pub fn route(&self, ibcd: InboundClientData) {
+    pub fn set_routing_rates(&mut self, per_routing_service: u64, per_routing_byte: u64) {
+        self.per_routing_service = per_routing_service;
+        self.per_routing_byte = per_routing_byte;
+    }
+
+    pub fn relay_flush_interval_opt(&self) -> Option<Duration> {
+        self.relay_mixer_opt
+            .as_ref()
+            .map(|relay_mixer| relay_mixer.flush_interval())
+    }
+
+    pub fn flush_relays(&mut self) {
+        let relay_mixer = match self.relay_mixer_opt.as_mut() {
+            Some(relay_mixer) => relay_mixer,
+            None => return,
+        };
+        relay_mixer
+            .flush(Instant::now())
+            .into_iter()
+            .for_each(|transmit_msg| {
+                self.routing_service_subs
+                    .to_dispatcher
+                    .try_send(transmit_msg)
+                    .expect("Dispatcher is dead")
+            });
+    }
+
+    pub fn route(&mut self, ibcd: InboundClientData) {
         let data_size = ibcd.data.len();
         debug!(
             self.logger,
@@ -97,11 +138,72 @@ impl RoutingService {
             }
         };
 
+        if let Some(complaint) = Self::complaint_about_package_age(&live_package, SystemTime::now())
+        {
+            warning!(
+                self.logger,
+                "Discarding CORES package with {}-byte payload from {}: it {}",
+                live_package.payload.len(),
+                peer_addr,
+                complaint
+            );
+            return;
+        }
+
+        match self.replay_cache.sight(&live_package) {
+            Sighting::First => (),
+            Sighting::Replay => {
+                warning!(
+                    self.logger,
+                    "Discarding replayed CORES package with {}-byte payload from {}",
+                    live_package.payload.len(),
+                    peer_addr
+                );
+                return;
+            }
+            Sighting::Overflow => {
+                warning!(
+                    self.logger,
+                    "Discarding CORES package with {}-byte payload from {}: too many recent packages to check it for replay",
+                    live_package.payload.len(),
+                    peer_addr
+                );
+                return;
+            }
+        }
+
         self.route_data(peer_addr, next_hop, live_package, last_data, &ibcd_but_data);
     }
 
+    // Packages from older Nodes come at the unstamped version; those can only be caught by the
+    // replay cache until no such Nodes are left. A package at the stamped version always has its
+    // stamp checked, so one that comes without a stamp looks decades old and is refused.
+    fn complaint_about_package_age(
+        live_package: &LiveCoresPackage,
+        now: SystemTime,
+    ) -> Option<String> {
+        if !live_package.is_stamped() {
+            return None;
+        }
+        let now_s = now
+            .duration_since(UNIX_EPOCH)
+            .expect("System clock is before the epoch")
+            .as_secs();
+        let sent_at_s = live_package.sent_at_s;
+        if sent_at_s > now_s + MAX_CLOCK_SKEW.as_secs() {
+            Some(format!(
+                "was stamped {} seconds in the future",
+                sent_at_s - now_s
+            ))
+        } else if now_s > sent_at_s + MAX_PACKAGE_AGE.as_secs() {
+            Some(format!("was sent {} seconds ago", now_s - sent_at_s))
+        } else {
+            None
+        }
+    }
+
     fn route_data(
-        &self,
+        &mut self,
         sender_addr: SocketAddr,
         next_hop: LiveHop,
         live_package: LiveCoresPackage,
@@ -180,7 +282,7 @@ impl RoutingService {
         let payload = encodex(
             self.cryptdes.main,
             self.cryptdes.main.public_key(),
-            &next_lcp,
+            &next_lcp.stamped(SystemTime::now()),
         )
         .expect("Encryption of LiveCoresPackage failed");
         let inbound_client_data = InboundClientData {
@@ -387,6 +489,45 @@ impl RoutingService {
                     ))
                     .expect("Neighborhood is dead")
             }
+            (Component::Accountant, MessageType::ServiceReceipt(vd)) => {
+                let receipt = match ServiceReceipt_0v1::try_from(vd) {
+                    Ok(r) => r,
+                    Err(e) => {
+                        error!(self.logger, "Received unmigratable ServiceReceipt: {:?}", e);
+                        return;
+                    }
+                };
+                let originator_key_opt = expired_package
+                    .remaining_route
+                    .next_hop(self.cryptdes.main)
+                    .ok()
+                    .and_then(|hop| hop.originator_key_opt);
+                if let Some(complaint) = self.complaint_about_receipt(
+                    &receipt,
+                    &originator_key_opt,
+                    &expired_package.paying_wallet,
+                    payer_owns_secret_key,
+                ) {
+                    warning!(
+                        self.logger,
+                        "Discarding service receipt for route {} from {}: {}",
+                        receipt.route_id,
+                        immediate_neighbor,
+                        complaint
+                    );
+                    return;
+                }
+                self.routing_service_subs
+                    .to_accountant_receipts
+                    .try_send(ExpiredCoresPackage::new(
+                        expired_package.immediate_neighbor,
+                        expired_package.paying_wallet,
+                        expired_package.remaining_route,
+                        receipt,
+                        expired_package.payload_len,
+                    ))
+                    .expect("Accountant is dead")
+            }
             (destination, payload) => error!(
                 self.logger,
                 "Attempt to send invalid combination {:?} to {:?}", payload, destination
@@ -394,8 +535,64 @@ impl RoutingService {
         };
     }
 
-    fn route_data_externally(
+    // A receipt must be signed by the Node its route started from, and must speak for the wallet
+    // that Node proved it owns by paying for the route
+    fn complaint_about_receipt(
         &self,
+        receipt: &ServiceReceipt_0v1,
+        originator_key_opt: &Option<PublicKey>,
+        paying_wallet_opt: &Option<Wallet>,
+        payer_owns_secret_key: bool,
+    ) -> Option<String> {
+        if !receipt.verify(self.cryptdes.main) {
+            return Some("its signature doesn't match its contents".to_string());
+        }
+        if let Some(complaint) = Self::complaint_about_receipt_figures(receipt) {
+            return Some(complaint);
+        }
+        match originator_key_opt {
+            Some(key) if key == &receipt.consumer_key => (),
+            Some(key) => {
+                return Some(format!(
+                    "it was signed by {} but came from {}",
+                    receipt.consumer_key, key
+                ))
+            }
+            None => return Some("its route doesn't say where it came from".to_string()),
+        }
+        match paying_wallet_opt {
+            Some(wallet) if !payer_owns_secret_key => {
+                Some(format!("no proof of paying wallet {} ownership", wallet))
+            }
+            Some(wallet) if wallet.address() == receipt.consuming_wallet.address() => None,
+            Some(wallet) => Some(format!(
+                "it speaks for wallet {} but came from wallet {}",
+                receipt.consuming_wallet, wallet
+            )),
+            None => Some("no paying wallet".to_string()),
+        }
+    }
+
+    // The database keeps these figures in signed integers
+    fn complaint_about_receipt_figures(receipt: &ServiceReceipt_0v1) -> Option<String> {
+        if receipt.amount_wei > i128::MAX as u128 {
+            return Some(format!(
+                "its amount of {} wei is too large",
+                receipt.amount_wei
+            ));
+        }
+        [
+            ("first service timestamp", receipt.first_service_s),
+            ("number of services", receipt.services),
+            ("number of bytes", receipt.bytes),
+        ]
+        .iter()
+        .find(|(_, figure)| *figure > i64::MAX as u64)
+        .map(|(name, figure)| format!("its {} of {} is too large", name, figure))
+    }
+
+    fn route_data_externally(
+        &mut self,
         live_package: LiveCoresPackage,
         payer: Option<Payer>,
         last_data: bool,
@@ -450,7 +647,17 @@ impl RoutingService {
                 return;
             }
         };
+        self.metrics.add_relayed_bytes(payload_size);
 
+        if let Some(relay_mixer) = self.relay_mixer_opt.as_mut() {
+            debug!(
+                self.logger,
+                "Holding {}-byte LiveCoresPackage for a batched relay",
+                transmit_msg.data.len()
+            );
+            relay_mixer.hold(transmit_msg, Instant::now());
+            return;
+        }
         debug!(
             self.logger,
             "Relaying {}-byte LiveCoresPackage to Dispatcher inside a TransmitDataMsg",
@@ -479,6 +686,7 @@ impl RoutingService {
                 }
                 Ok(p) => p,
             };
+        let next_live_package = next_live_package.stamped(SystemTime::now());
         let next_live_package_enc =
             match encodex(self.cryptdes.main, &next_hop.public_key, &next_live_package) {
                 Ok(nlpe) => nlpe,
@@ -502,12 +710,14 @@ mod tests {
     use super::*;
     use crate::accountant::db_access_objects::banned_dao::BAN_CACHE;
     use crate::bootstrapper::Bootstrapper;
+    use crate::hopper::replay_cache::REPLAY_CACHE_TTL;
     use crate::neighborhood::gossip::{GossipBuilder, Gossip_0v1};
-    use crate::node_test_utils::check_timestamp;
+    use crate::node_test_utils::{check_stamp_and_remove, check_timestamp};
     use crate::sub_lib::accountant::ReportRoutingServiceProvidedMessage;
-    use crate::sub_lib::cryptde::{encodex, CryptDE, PlainData, PublicKey};
+    use crate::sub_lib::cryptde::{encodex, CryptDE, CryptData, PlainData, PublicKey};
     use crate::sub_lib::cryptde_null::CryptDENull;
     use crate::sub_lib::cryptde_real::CryptDEReal;
+    use crate::sub_lib::data_version::DataVersion;
     use crate::sub_lib::hopper::{IncipientCoresPackage, MessageType, MessageType::ClientRequest};
     use crate::sub_lib::neighborhood::GossipFailure_0v1;
     use crate::sub_lib::peer_actors::PeerActors;
@@ -517,12 +727,11 @@ mod tests {
     use crate::sub_lib::sequence_buffer::SequencedPacket;
     use crate::sub_lib::stream_key::StreamKey;
     use crate::sub_lib::versioned_data::VersionedData;
-    use crate::sub_lib::wallet::Wallet;
     use crate::test_utils::recorder::{make_recorder, peer_actors_builder};
     use crate::test_utils::{
         alias_cryptde, main_cryptde, make_cryptde_pair, make_meaningless_message_type,
-        make_paying_wallet, make_request_payload, make_response_payload, rate_pack_routing,
-        rate_pack_routing_byte, route_from_proxy_client, route_to_proxy_client,
+        make_paying_wallet, make_request_payload, make_response_payload, make_wallet,
+        rate_pack_routing, rate_pack_routing_byte, route_from_proxy_client, route_to_proxy_client,
         route_to_proxy_server,
     };
     use actix::System;
@@ -531,6 +740,7 @@ mod tests {
     use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
     use std::net::SocketAddr;
     use std::str::FromStr;
+    use std::thread;
     use std::time::SystemTime;
 
     #[test]
@@ -565,7 +775,7 @@ mod tests {
 
         let system = System::new("dns_resolution_failures_are_reported_to_the_proxy_server");
         let peer_actors = peer_actors_builder().proxy_server(proxy_server).build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             cryptdes,
             RoutingServiceSubs {
                 proxy_client_subs_opt: peer_actors.proxy_client_opt,
@@ -574,10 +784,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             100,
             200,
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
 
         subject.route(inbound_client_data);
@@ -610,7 +823,7 @@ mod tests {
             data: data_enc.into(),
         };
         let peer_actors = peer_actors_builder().build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             cryptdes,
             RoutingServiceSubs {
                 proxy_client_subs_opt: peer_actors.proxy_client_opt,
@@ -619,10 +832,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             100,
             200,
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
 
         subject.route(inbound_client_data);
@@ -657,7 +873,7 @@ mod tests {
             data: data_enc.into(),
         };
         let peer_actors = peer_actors_builder().build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -669,10 +885,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             100,
             200,
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
 
         subject.route(inbound_client_data);
@@ -709,7 +928,7 @@ mod tests {
             data: data_enc.into(),
         };
         let peer_actors = peer_actors_builder().build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -721,10 +940,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             100,
             200,
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
         subject.route(inbound_client_data);
         TestLogHandler::new().exists_log_matching("Attempt to send invalid combination .* to .*");
@@ -765,7 +987,7 @@ mod tests {
 
         let system = System::new("converts_live_message_to_expired_for_proxy_client");
         let peer_actors = peer_actors_builder().proxy_client(component).build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -777,10 +999,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             0,
             0,
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
 
         subject.route(inbound_client_data);
@@ -838,7 +1063,7 @@ mod tests {
 
         let system = System::new("converts_live_message_to_expired_for_proxy_client");
         let peer_actors = peer_actors_builder().build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -850,10 +1075,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             0,
             0,
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
 
         subject.route(inbound_client_data);
@@ -897,7 +1125,7 @@ mod tests {
 
         let system = System::new("converts_live_message_to_expired_for_proxy_server");
         let peer_actors = peer_actors_builder().proxy_server(proxy_server).build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -909,10 +1137,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             0,
             0,
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
 
         subject.route(inbound_client_data);
@@ -978,7 +1209,7 @@ mod tests {
 
         let system = System::new("converts_live_gossip_message_to_expired_for_neighborhood");
         let peer_actors = peer_actors_builder().neighborhood(component).build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -990,10 +1221,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             0,
             0,
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
 
         subject.route(inbound_client_data);
@@ -1055,7 +1289,7 @@ mod tests {
         let system =
             System::new("converts_live_gossip_failure_message_to_expired_for_neighborhood");
         let peer_actors = peer_actors_builder().neighborhood(component).build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: cryptde,
                 alias: alias_cryptde(),
@@ -1067,10 +1301,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             0,
             0,
             true,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
 
         subject.route(inbound_client_data);
@@ -1137,7 +1374,7 @@ mod tests {
             .dispatcher(dispatcher)
             .accountant(accountant)
             .build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -1149,10 +1386,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             rate_pack_routing(103),
             rate_pack_routing_byte(103),
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
         let before = SystemTime::now();
 
@@ -1163,18 +1403,17 @@ mod tests {
         let after = SystemTime::now();
         let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
         let record = dispatcher_recording.get_record::<TransmitDataMsg>(0);
+        let next_cryptde = CryptDENull::from(&next_key, TEST_DEFAULT_CHAIN);
+        let actual_lcp =
+            decodex::<LiveCoresPackage>(&next_cryptde, &CryptData::new(&record.data)).unwrap();
         let expected_lcp = lcp_a.into_next_live(main_cryptde).unwrap().1;
-        let expected_lcp_ser = PlainData::new(&serde_cbor::ser::to_vec(&expected_lcp).unwrap());
-        let expected_lcp_enc = main_cryptde.encode(&next_key, &expected_lcp_ser).unwrap();
         assert_eq!(
-            *record,
-            TransmitDataMsg {
-                endpoint: Endpoint::Key(next_key.clone()),
-                last_data: true,
-                sequence_number: None,
-                data: expected_lcp_enc.into(),
-            }
+            check_stamp_and_remove(before, actual_lcp, after),
+            expected_lcp
         );
+        assert_eq!(record.endpoint, Endpoint::Key(next_key.clone()));
+        assert!(record.last_data);
+        assert_eq!(record.sequence_number, None);
         let accountant_recording = accountant_recording_arc.lock().unwrap();
         let message = accountant_recording.get_record::<ReportRoutingServiceProvidedMessage>(0);
         check_timestamp(before, message.timestamp, after);
@@ -1191,6 +1430,440 @@ mod tests {
         )
     }
 
+    #[test]
+    fn discards_and_logs_replayed_inbound_client_data() {
+        init_test_logging();
+        let _eg = EnvironmentGuard::new();
+        BAN_CACHE.clear();
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let paying_wallet = make_paying_wallet(b"wallet");
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let next_key = PublicKey::new(&[65, 65, 65]);
+        let route = Route::one_way(
+            RouteSegment::new(
+                vec![main_cryptde.public_key(), &next_key],
+                Component::Neighborhood,
+            ),
+            main_cryptde,
+            Some(paying_wallet),
+            Some(TEST_DEFAULT_CHAIN.rec().contract),
+        )
+        .unwrap();
+        let make_ibcd = |payload: &[u8]| {
+            let lcp = LiveCoresPackage::new(
+                route.clone(),
+                main_cryptde
+                    .encode(&next_key, &PlainData::new(payload))
+                    .unwrap(),
+            );
+            let data_ser = PlainData::new(&serde_cbor::ser::to_vec(&lcp).unwrap()[..]);
+            let data_enc = main_cryptde
+                .encode(main_cryptde.public_key(), &data_ser)
+                .unwrap();
+            InboundClientData {
+                timestamp: SystemTime::now(),
+                peer_addr: SocketAddr::from_str("1.2.3.4:5678").unwrap(),
+                reception_port: None,
+                last_data: false,
+                is_clandestine: true,
+                sequence_number: None,
+                data: data_enc.into(),
+            }
+        };
+        let original = make_ibcd(b"abcd");
+        let replay = make_ibcd(b"abcd");
+        let same_route_new_payload = make_ibcd(b"efgh");
+        let system = System::new("discards_and_logs_replayed_inbound_client_data");
+        let peer_actors = peer_actors_builder()
+            .dispatcher(dispatcher)
+            .accountant(accountant)
+            .build();
+        let mut subject = RoutingService::new(
+            CryptDEPair {
+                main: main_cryptde,
+                alias: alias_cryptde,
+            },
+            make_routing_service_subs(peer_actors),
+            rate_pack_routing(103),
+            rate_pack_routing_byte(103),
+            false,
+            None,
+            Arc::new(NodeMetrics::default()),
+        );
+
+        subject.route(original);
+        subject.route(replay);
+        subject.route(same_route_new_payload);
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(dispatcher_recording.len(), 2);
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(accountant_recording.len(), 2);
+        TestLogHandler::new().exists_log_containing(
+            "WARN: RoutingService: Discarding replayed CORES package with 7-byte payload from 1.2.3.4:5678",
+        );
+    }
+
+    fn make_stamped_ibcd(
+        main_cryptde: &dyn CryptDE,
+        next_key: &PublicKey,
+        payload: &[u8],
+        version: DataVersion,
+        sent_at_s: u64,
+    ) -> InboundClientData {
+        let route = Route::one_way(
+            RouteSegment::new(
+                vec![main_cryptde.public_key(), next_key],
+                Component::Neighborhood,
+            ),
+            main_cryptde,
+            Some(make_paying_wallet(b"wallet")),
+            Some(TEST_DEFAULT_CHAIN.rec().contract),
+        )
+        .unwrap();
+        let mut lcp = LiveCoresPackage::new(
+            route,
+            main_cryptde
+                .encode(next_key, &PlainData::new(payload))
+                .unwrap(),
+        );
+        lcp.version = version;
+        lcp.sent_at_s = sent_at_s;
+        let data_enc = encodex(main_cryptde, main_cryptde.public_key(), &lcp).unwrap();
+        InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: SocketAddr::from_str("1.2.3.4:5678").unwrap(),
+            reception_port: None,
+            last_data: false,
+            is_clandestine: true,
+            sequence_number: None,
+            data: data_enc.into(),
+        }
+    }
+
+    #[test]
+    fn discards_and_logs_inbound_client_data_that_is_stale_or_from_the_future_or_unstamped() {
+        init_test_logging();
+        let _eg = EnvironmentGuard::new();
+        BAN_CACHE.clear();
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let next_key = PublicKey::new(&[65, 65, 65]);
+        let now_s = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let stamped_version = LiveCoresPackage::stamped_version();
+        let stale = make_stamped_ibcd(
+            main_cryptde,
+            &next_key,
+            b"abcd",
+            stamped_version,
+            now_s - MAX_PACKAGE_AGE.as_secs() - 100,
+        );
+        let from_the_future = make_stamped_ibcd(
+            main_cryptde,
+            &next_key,
+            b"efghi",
+            stamped_version,
+            now_s + MAX_CLOCK_SKEW.as_secs() + 100,
+        );
+        let stamp_missing =
+            make_stamped_ibcd(main_cryptde, &next_key, b"jklmno", stamped_version, 0);
+        let system = System::new(
+            "discards_and_logs_inbound_client_data_that_is_stale_or_from_the_future_or_unstamped",
+        );
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher).build();
+        let mut subject = RoutingService::new(
+            CryptDEPair {
+                main: main_cryptde,
+                alias: alias_cryptde,
+            },
+            make_routing_service_subs(peer_actors),
+            rate_pack_routing(103),
+            rate_pack_routing_byte(103),
+            false,
+            None,
+            Arc::new(NodeMetrics::default()),
+        );
+
+        subject.route(stale);
+        subject.route(from_the_future);
+        subject.route(stamp_missing);
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(dispatcher_recording.len(), 0);
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_matching(
+            "WARN: RoutingService: Discarding CORES package with 7-byte payload from \
+            1\\.2\\.3\\.4:5678: it was sent 7\\d\\d seconds ago",
+        );
+        tlh.exists_log_matching(
+            "WARN: RoutingService: Discarding CORES package with 8-byte payload from \
+            1\\.2\\.3\\.4:5678: it was stamped \\d+ seconds in the future",
+        );
+        tlh.exists_log_matching(
+            "WARN: RoutingService: Discarding CORES package with 9-byte payload from \
+            1\\.2\\.3\\.4:5678: it was sent \\d+ seconds ago",
+        );
+    }
+
+    #[test]
+    fn discards_and_logs_inbound_client_data_when_the_replay_cache_is_full() {
+        init_test_logging();
+        let _eg = EnvironmentGuard::new();
+        BAN_CACHE.clear();
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let next_key = PublicKey::new(&[65, 65, 65]);
+        let now_s = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let stamped_version = LiveCoresPackage::stamped_version();
+        let first = make_stamped_ibcd(main_cryptde, &next_key, b"abcd", stamped_version, now_s);
+        let second = make_stamped_ibcd(main_cryptde, &next_key, b"efghi", stamped_version, now_s);
+        let system =
+            System::new("discards_and_logs_inbound_client_data_when_the_replay_cache_is_full");
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher).build();
+        let mut subject = RoutingService::new(
+            CryptDEPair {
+                main: main_cryptde,
+                alias: alias_cryptde,
+            },
+            make_routing_service_subs(peer_actors),
+            rate_pack_routing(103),
+            rate_pack_routing_byte(103),
+            false,
+            None,
+            Arc::new(NodeMetrics::default()),
+        );
+        subject.replay_cache = ReplayCache::new(REPLAY_CACHE_TTL, 1);
+
+        subject.route(first);
+        subject.route(second);
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(dispatcher_recording.len(), 1);
+        TestLogHandler::new().exists_log_containing(
+            "WARN: RoutingService: Discarding CORES package with 8-byte payload from 1.2.3.4:5678: \
+            too many recent packages to check it for replay",
+        );
+    }
+
+    #[test]
+    fn complaint_about_package_age_accepts_fresh_and_unstamped_packages() {
+        let now = SystemTime::now();
+        let now_s = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let package = LiveCoresPackage::new(Route { hops: vec![] }, CryptData::new(&[1, 2, 3]));
+        let stamped_at = |sent_at_s: u64| {
+            package
+                .clone()
+                .stamped(UNIX_EPOCH + Duration::from_secs(sent_at_s))
+        };
+
+        let unstamped = RoutingService::complaint_about_package_age(&package, now);
+        let fresh = RoutingService::complaint_about_package_age(&stamped_at(now_s), now);
+        let oldest = RoutingService::complaint_about_package_age(
+            &stamped_at(now_s - MAX_PACKAGE_AGE.as_secs()),
+            now,
+        );
+        let most_skewed = RoutingService::complaint_about_package_age(
+            &stamped_at(now_s + MAX_CLOCK_SKEW.as_secs()),
+            now,
+        );
+
+        assert_eq!(unstamped, None);
+        assert_eq!(fresh, None);
+        assert_eq!(oldest, None);
+        assert_eq!(most_skewed, None);
+    }
+
+    #[test]
+    fn complaint_about_package_age_rejects_stale_packages_and_packages_from_the_future() {
+        let now = SystemTime::now();
+        let now_s = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let package = LiveCoresPackage::new(Route { hops: vec![] }, CryptData::new(&[1, 2, 3]));
+        let stale = package
+            .clone()
+            .stamped(UNIX_EPOCH + Duration::from_secs(now_s - MAX_PACKAGE_AGE.as_secs() - 1));
+        let from_the_future =
+            package.stamped(UNIX_EPOCH + Duration::from_secs(now_s + MAX_CLOCK_SKEW.as_secs() + 1));
+
+        let stale_result = RoutingService::complaint_about_package_age(&stale, now);
+        let from_the_future_result =
+            RoutingService::complaint_about_package_age(&from_the_future, now);
+
+        assert_eq!(
+            stale_result,
+            Some(format!(
+                "was sent {} seconds ago",
+                MAX_PACKAGE_AGE.as_secs() + 1
+            ))
+        );
+        assert_eq!(
+            from_the_future_result,
+            Some(format!(
+                "was stamped {} seconds in the future",
+                MAX_CLOCK_SKEW.as_secs() + 1
+            ))
+        );
+    }
+
+    #[test]
+    fn packages_on_a_stream_that_outlives_max_package_age_are_accepted_as_long_as_each_is_fresh() {
+        let main_cryptde = main_cryptde();
+        let next_key = PublicKey::new(&[65, 65, 65]);
+        let route = Route::one_way(
+            RouteSegment::new(
+                vec![main_cryptde.public_key(), &next_key],
+                Component::Neighborhood,
+            ),
+            main_cryptde,
+            Some(make_paying_wallet(b"wallet")),
+            Some(TEST_DEFAULT_CHAIN.rec().contract),
+        )
+        .unwrap();
+        let stream_start = SystemTime::now();
+        let much_later = stream_start + MAX_PACKAGE_AGE * 10;
+        let first_package =
+            LiveCoresPackage::new(route.clone(), CryptData::new(b"first")).stamped(stream_start);
+        let late_package =
+            LiveCoresPackage::new(route, CryptData::new(b"late")).stamped(much_later);
+        let late_copy_of_first_package = first_package.clone();
+
+        let first_result =
+            RoutingService::complaint_about_package_age(&first_package, stream_start);
+        let late_result = RoutingService::complaint_about_package_age(&late_package, much_later);
+        let late_copy_result =
+            RoutingService::complaint_about_package_age(&late_copy_of_first_package, much_later);
+
+        assert_eq!(first_result, None);
+        assert_eq!(late_result, None);
+        assert!(late_copy_result.is_some());
+    }
+
+    #[test]
+    fn holds_relayed_packages_until_they_are_flushed_when_batching() {
+        let _eg = EnvironmentGuard::new();
+        BAN_CACHE.clear();
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let paying_wallet = make_paying_wallet(b"wallet");
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let next_key = PublicKey::new(&[65, 65, 65]);
+        let route = Route::one_way(
+            RouteSegment::new(
+                vec![main_cryptde.public_key(), &next_key],
+                Component::Neighborhood,
+            ),
+            main_cryptde,
+            Some(paying_wallet),
+            Some(TEST_DEFAULT_CHAIN.rec().contract),
+        )
+        .unwrap();
+        let make_ibcd = |payload: &[u8]| {
+            let lcp = LiveCoresPackage::new(
+                route.clone(),
+                main_cryptde
+                    .encode(&next_key, &PlainData::new(payload))
+                    .unwrap(),
+            );
+            let data_ser = PlainData::new(&serde_cbor::ser::to_vec(&lcp).unwrap()[..]);
+            let data_enc = main_cryptde
+                .encode(main_cryptde.public_key(), &data_ser)
+                .unwrap();
+            InboundClientData {
+                timestamp: SystemTime::now(),
+                peer_addr: SocketAddr::from_str("1.2.3.4:5678").unwrap(),
+                reception_port: None,
+                last_data: false,
+                is_clandestine: true,
+                sequence_number: None,
+                data: data_enc.into(),
+            }
+        };
+        let system = System::new("holds_relayed_packages_until_they_are_flushed_when_batching");
+        let peer_actors = peer_actors_builder()
+            .dispatcher(dispatcher)
+            .accountant(accountant)
+            .build();
+        let metrics = Arc::new(NodeMetrics::default());
+        let mut subject = RoutingService::new(
+            CryptDEPair {
+                main: main_cryptde,
+                alias: alias_cryptde,
+            },
+            make_routing_service_subs(peer_actors),
+            rate_pack_routing(103),
+            rate_pack_routing_byte(103),
+            false,
+            Some(Duration::from_millis(20)),
+            metrics.clone(),
+        );
+        subject.route(make_ibcd(b"abcd"));
+        subject.route(make_ibcd(b"efgh"));
+        subject.route(make_ibcd(b"ijkl"));
+        let held_before_flush = subject.relay_mixer_opt.as_ref().unwrap().held_count();
+        thread::sleep(Duration::from_millis(20));
+
+        subject.flush_relays();
+
+        System::current().stop();
+        system.run();
+        assert_eq!(held_before_flush, 3);
+        assert_eq!(subject.relay_mixer_opt.as_ref().unwrap().held_count(), 0);
+        assert_eq!(
+            subject.relay_flush_interval_opt(),
+            Some(Duration::from_millis(5))
+        );
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(dispatcher_recording.len(), 3);
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(accountant_recording.len(), 3);
+        let relayed_bytes: usize = (0..3)
+            .map(|idx| {
+                accountant_recording
+                    .get_record::<ReportRoutingServiceProvidedMessage>(idx)
+                    .payload_size
+            })
+            .sum();
+        assert!(metrics.render().contains(&format!(
+            "\nmasq_node_relayed_bytes_total {}\n",
+            relayed_bytes
+        )));
+    }
+
+    #[test]
+    fn set_routing_rates_changes_the_rates_charged_for_routing() {
+        let peer_actors = peer_actors_builder().build();
+        let mut subject = RoutingService::new(
+            make_cryptde_pair(),
+            make_routing_service_subs(peer_actors),
+            100,
+            200,
+            false,
+            None,
+            Arc::new(NodeMetrics::default()),
+        );
+
+        subject.set_routing_rates(300, 400);
+
+        assert_eq!(subject.per_routing_service, 300);
+        assert_eq!(subject.per_routing_byte, 400);
+    }
+
     #[test]
     fn reprocesses_inbound_client_data_meant_for_this_node_and_destined_for_hopper() {
         let _eg = EnvironmentGuard::new();
@@ -1232,7 +1905,7 @@ mod tests {
             "reprocesses_inbound_client_data_meant_for_this_node_and_destined_for_hopper",
         );
         let peer_actors = peer_actors_builder().hopper(hopper).build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -1244,10 +1917,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             rate_pack_routing(103),
             rate_pack_routing_byte(103),
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
         let before = SystemTime::now();
 
@@ -1259,11 +1935,18 @@ mod tests {
         let hopper_recording = hopper_recording_arc.lock().unwrap();
         let record = hopper_recording.get_record::<InboundClientData>(0);
         check_timestamp(before, record.timestamp, after);
+        let actual_lcp =
+            decodex::<LiveCoresPackage>(main_cryptde, &CryptData::new(&record.data)).unwrap();
         let expected_lcp = lcp_a.into_next_live(main_cryptde).unwrap().1;
-        let expected_lcp_enc =
-            encodex(main_cryptde, &main_cryptde.public_key(), &expected_lcp).unwrap();
         assert_eq!(
-            *record,
+            check_stamp_and_remove(before, actual_lcp, after),
+            expected_lcp
+        );
+        assert_eq!(
+            InboundClientData {
+                data: vec![],
+                ..record.clone()
+            },
             InboundClientData {
                 timestamp: record.timestamp,
                 peer_addr: SocketAddr::from_str("1.2.3.4:5678").unwrap(),
@@ -1271,7 +1954,7 @@ mod tests {
                 last_data: true,
                 is_clandestine: true,
                 sequence_number: None,
-                data: expected_lcp_enc.into()
+                data: vec![]
             }
         );
     }
@@ -1328,7 +2011,7 @@ mod tests {
             .dispatcher(dispatcher)
             .accountant(accountant)
             .build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -1340,10 +2023,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             100,
             200,
             true,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
 
         subject.route(inbound_client_data);
@@ -1429,7 +2115,7 @@ mod tests {
             .dispatcher(dispatcher)
             .accountant(accountant)
             .build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -1441,10 +2127,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             100,
             200,
             true,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
 
         subject.route(inbound_client_data);
@@ -1527,7 +2216,7 @@ mod tests {
             .dispatcher(dispatcher)
             .accountant(accountant)
             .build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -1539,10 +2228,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             100,
             200,
             true,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
 
         subject.route_data_externally(
@@ -1603,7 +2295,7 @@ mod tests {
             .dispatcher(dispatcher)
             .accountant(accountant)
             .build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -1615,10 +2307,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             rate_pack_routing(103),
             rate_pack_routing_byte(103),
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
 
         subject.route(inbound_client_data);
@@ -1677,7 +2372,7 @@ mod tests {
             .dispatcher(dispatcher)
             .accountant(accountant)
             .build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -1689,10 +2384,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             rate_pack_routing(103),
             rate_pack_routing_byte(103),
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
 
         subject.route(inbound_client_data);
@@ -1730,7 +2428,7 @@ mod tests {
             .neighborhood(neighborhood)
             .dispatcher(dispatcher)
             .build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             make_cryptde_pair(),
             RoutingServiceSubs {
                 proxy_client_subs_opt: peer_actors.proxy_client_opt,
@@ -1739,10 +2437,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             100,
             200,
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
 
         subject.route(inbound_client_data);
@@ -1788,7 +2489,7 @@ mod tests {
             .neighborhood(neighborhood)
             .dispatcher(dispatcher)
             .build();
-        let subject = RoutingService::new(
+        let mut subject = RoutingService::new(
             CryptDEPair {
                 main: main_cryptde,
                 alias: alias_cryptde,
@@ -1800,10 +2501,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             100,
             200,
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
 
         subject.route(inbound_client_data);
@@ -1811,7 +2515,7 @@ mod tests {
         System::current().stop_with_code(0);
         system.run();
         TestLogHandler::new().exists_log_containing(
-            "ERROR: RoutingService: Invalid 67-byte CORES package: RoutingError(EmptyRoute)",
+            "ERROR: RoutingService: Invalid 78-byte CORES package: RoutingError(EmptyRoute)",
         );
         assert_eq!(proxy_client_recording_arc.lock().unwrap().len(), 0);
         assert_eq!(proxy_server_recording_arc.lock().unwrap().len(), 0);
@@ -1832,10 +2536,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             100,
             200,
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
         let lcp = LiveCoresPackage::new(Route { hops: vec![] }, CryptData::new(&[]));
         let ibcd = InboundClientData {
@@ -1863,6 +2570,7 @@ mod tests {
             hopper_subs: peer_actors.hopper,
             to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
             to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+            to_accountant_receipts: peer_actors.accountant.service_receipt,
         }
     }
 
@@ -1879,6 +2587,8 @@ mod tests {
             100,
             200,
             true,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
         let route = Route::single_hop(&PublicKey::new(b"1234"), subject.cryptdes.main).unwrap();
         let payload = payload_factory(&subject.cryptdes);
@@ -1996,10 +2706,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             100,
             200,
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
         let expired_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -2035,10 +2748,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             100,
             200,
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
         let expired_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -2074,10 +2790,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             100,
             200,
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
         let expired_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -2113,10 +2832,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             100,
             200,
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
         let expired_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -2152,10 +2874,13 @@ mod tests {
                 hopper_subs: peer_actors.hopper,
                 to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
                 to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+                to_accountant_receipts: peer_actors.accountant.service_receipt,
             },
             100,
             200,
             false,
+            None,
+            Arc::new(NodeMetrics::default()),
         );
         let expired_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -2176,4 +2901,332 @@ mod tests {
             "ERROR: RoutingService: Received unmigratable GossipFailure: MigrationNotFound(DataVersion { major: 0, minor: 0 }, DataVersion { major: 0, minor: 1 })",
         );
     }
+
+    fn consumer_cryptde() -> CryptDENull {
+        CryptDENull::from(&PublicKey::new(b"consumer"), TEST_DEFAULT_CHAIN)
+    }
+
+    fn make_signed_receipt(consuming_wallet: &Wallet) -> ServiceReceipt_0v1 {
+        ServiceReceipt_0v1 {
+            consumer_key: PublicKey::new(&[]),
+            consuming_wallet: Wallet::from(consuming_wallet.address()),
+            provider_wallet: make_wallet("provider"),
+            route_id: 42,
+            first_service_s: 1_700_000_000,
+            services: 3,
+            bytes: 3_000,
+            amount_wei: 123_456,
+            signature: CryptData::new(&[]),
+        }
+        .signed(&consumer_cryptde())
+    }
+
+    // The route as it arrives here, with this Node's hop on top
+    fn make_receipt_route(originator_key_opt: Option<&PublicKey>) -> Route {
+        let sender_key = originator_key_opt
+            .cloned()
+            .unwrap_or_else(|| consumer_cryptde().public_key().clone());
+        let route_segment = RouteSegment::new(
+            vec![&sender_key, main_cryptde().public_key()],
+            Component::Accountant,
+        );
+        let paying_wallet = Some(make_paying_wallet(b"consumer"));
+        let contract_address = Some(TEST_DEFAULT_CHAIN.rec().contract);
+        let mut route = match originator_key_opt {
+            Some(_) => Route::one_way_from_originator(
+                route_segment,
+                main_cryptde(),
+                paying_wallet,
+                contract_address,
+            ),
+            None => Route::one_way(
+                route_segment,
+                main_cryptde(),
+                paying_wallet,
+                contract_address,
+            ),
+        }
+        .unwrap();
+        route
+            .shift(&CryptDENull::from(&sender_key, TEST_DEFAULT_CHAIN))
+            .unwrap();
+        route
+    }
+
+    fn route_service_receipt(
+        test_name: &str,
+        receipt: ServiceReceipt_0v1,
+        remaining_route: Route,
+        paying_wallet_opt: Option<Wallet>,
+        payer_owns_secret_key: bool,
+    ) -> Vec<ExpiredCoresPackage<ServiceReceipt_0v1>> {
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        let subject = RoutingService::new(
+            make_cryptde_pair(),
+            make_routing_service_subs(peer_actors),
+            100,
+            200,
+            true,
+            None,
+            Arc::new(NodeMetrics::default()),
+        );
+        let expired_package = ExpiredCoresPackage::new(
+            SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+            paying_wallet_opt,
+            remaining_route,
+            MessageType::from(receipt),
+            0,
+        );
+        let system = System::new(test_name);
+
+        subject.route_expired_package(
+            Component::Accountant,
+            expired_package,
+            payer_owns_secret_key,
+        );
+
+        System::current().stop_with_code(0);
+        system.run();
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        (0..accountant_recording.len())
+            .map(|index| {
+                accountant_recording
+                    .get_record::<ExpiredCoresPackage<ServiceReceipt_0v1>>(index)
+                    .clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn route_expired_package_forwards_service_receipt_from_its_paying_wallet_to_accountant() {
+        let paying_wallet = make_paying_wallet(b"consumer");
+        let receipt = make_signed_receipt(&paying_wallet);
+        let route = make_receipt_route(Some(consumer_cryptde().public_key()));
+
+        let result = route_service_receipt(
+            "route_expired_package_forwards_service_receipt_from_its_paying_wallet_to_accountant",
+            receipt.clone(),
+            route.clone(),
+            Some(paying_wallet.clone()),
+            true,
+        );
+
+        assert_eq!(
+            result,
+            vec![ExpiredCoresPackage::new(
+                SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+                Some(paying_wallet),
+                route,
+                receipt,
+                0,
+            )]
+        );
+    }
+
+    #[test]
+    fn route_expired_package_discards_service_receipt_signed_by_another_node() {
+        init_test_logging();
+        let paying_wallet = make_paying_wallet(b"consumer");
+        let receipt = make_signed_receipt(&paying_wallet);
+        let sender_key = PublicKey::new(b"sender");
+
+        let result = route_service_receipt(
+            "route_expired_package_discards_service_receipt_signed_by_another_node",
+            receipt,
+            make_receipt_route(Some(&sender_key)),
+            Some(paying_wallet),
+            true,
+        );
+
+        assert!(result.is_empty());
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: RoutingService: Discarding service receipt for route 42 from 1.2.3.4:1234: it was signed by {} but came from {}",
+            consumer_cryptde().public_key(),
+            sender_key
+        ));
+    }
+
+    #[test]
+    fn route_expired_package_discards_service_receipt_whose_route_does_not_name_its_originator() {
+        init_test_logging();
+        let paying_wallet = make_paying_wallet(b"consumer");
+        let receipt = make_signed_receipt(&paying_wallet);
+
+        let result = route_service_receipt(
+            "route_expired_package_discards_service_receipt_whose_route_does_not_name_its_originator",
+            receipt,
+            make_receipt_route(None),
+            Some(paying_wallet),
+            true,
+        );
+
+        assert!(result.is_empty());
+        TestLogHandler::new().exists_log_containing(
+            "WARN: RoutingService: Discarding service receipt for route 42 from 1.2.3.4:1234: its route doesn't say where it came from",
+        );
+    }
+
+    #[test]
+    fn route_expired_package_discards_service_receipt_with_bad_signature() {
+        init_test_logging();
+        let paying_wallet = make_paying_wallet(b"consumer");
+        let mut receipt = make_signed_receipt(&paying_wallet);
+        receipt.amount_wei = 1;
+
+        let result = route_service_receipt(
+            "route_expired_package_discards_service_receipt_with_bad_signature",
+            receipt,
+            make_receipt_route(Some(consumer_cryptde().public_key())),
+            Some(paying_wallet),
+            true,
+        );
+
+        assert!(result.is_empty());
+        TestLogHandler::new().exists_log_containing(
+            "WARN: RoutingService: Discarding service receipt for route 42 from 1.2.3.4:1234: its signature doesn't match its contents",
+        );
+    }
+
+    #[test]
+    fn route_expired_package_discards_service_receipt_with_amount_too_large_to_record() {
+        init_test_logging();
+        let paying_wallet = make_paying_wallet(b"consumer");
+        let mut receipt = make_signed_receipt(&paying_wallet);
+        receipt.amount_wei = i128::MAX as u128 + 1;
+        let receipt = receipt.signed(&consumer_cryptde());
+
+        let result = route_service_receipt(
+            "route_expired_package_discards_service_receipt_with_amount_too_large_to_record",
+            receipt,
+            make_receipt_route(Some(consumer_cryptde().public_key())),
+            Some(paying_wallet),
+            true,
+        );
+
+        assert!(result.is_empty());
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: RoutingService: Discarding service receipt for route 42 from 1.2.3.4:1234: its amount of {} wei is too large",
+            i128::MAX as u128 + 1
+        ));
+    }
+
+    #[test]
+    fn route_expired_package_discards_service_receipt_with_counts_too_large_to_record() {
+        init_test_logging();
+        let paying_wallet = make_paying_wallet(b"consumer");
+        let mut receipt = make_signed_receipt(&paying_wallet);
+        receipt.bytes = u64::MAX;
+        let receipt = receipt.signed(&consumer_cryptde());
+
+        let result = route_service_receipt(
+            "route_expired_package_discards_service_receipt_with_counts_too_large_to_record",
+            receipt,
+            make_receipt_route(Some(consumer_cryptde().public_key())),
+            Some(paying_wallet),
+            true,
+        );
+
+        assert!(result.is_empty());
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: RoutingService: Discarding service receipt for route 42 from 1.2.3.4:1234: its number of bytes of {} is too large",
+            u64::MAX
+        ));
+    }
+
+    #[test]
+    fn route_expired_package_discards_service_receipt_speaking_for_another_wallet() {
+        init_test_logging();
+        let paying_wallet = make_paying_wallet(b"consumer");
+        let receipt = make_signed_receipt(&make_wallet("impostor"));
+
+        let result = route_service_receipt(
+            "route_expired_package_discards_service_receipt_speaking_for_another_wallet",
+            receipt,
+            make_receipt_route(Some(consumer_cryptde().public_key())),
+            Some(paying_wallet.clone()),
+            true,
+        );
+
+        assert!(result.is_empty());
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: RoutingService: Discarding service receipt for route 42 from 1.2.3.4:1234: it speaks for wallet {} but came from wallet {}",
+            make_wallet("impostor"),
+            paying_wallet
+        ));
+    }
+
+    #[test]
+    fn route_expired_package_discards_service_receipt_without_proof_of_paying_wallet() {
+        init_test_logging();
+        let paying_wallet = make_paying_wallet(b"unproven");
+        let receipt = make_signed_receipt(&paying_wallet);
+
+        let result = route_service_receipt(
+            "route_expired_package_discards_service_receipt_without_proof_of_paying_wallet",
+            receipt,
+            make_receipt_route(Some(consumer_cryptde().public_key())),
+            Some(paying_wallet.clone()),
+            false,
+        );
+
+        assert!(result.is_empty());
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: RoutingService: Discarding service receipt for route 42 from 1.2.3.4:1234: no proof of paying wallet {} ownership",
+            paying_wallet
+        ));
+    }
+
+    #[test]
+    fn route_expired_package_discards_service_receipt_without_paying_wallet() {
+        init_test_logging();
+        let receipt = make_signed_receipt(&make_paying_wallet(b"absent"));
+
+        let result = route_service_receipt(
+            "route_expired_package_discards_service_receipt_without_paying_wallet",
+            receipt,
+            make_receipt_route(Some(consumer_cryptde().public_key())),
+            None,
+            false,
+        );
+
+        assert!(result.is_empty());
+        TestLogHandler::new().exists_log_containing(
+            "WARN: RoutingService: Discarding service receipt for route 42 from 1.2.3.4:1234: no paying wallet",
+        );
+    }
+
+    #[test]
+    fn route_expired_package_handles_unmigratable_service_receipt() {
+        init_test_logging();
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        let subject = RoutingService::new(
+            make_cryptde_pair(),
+            make_routing_service_subs(peer_actors),
+            100,
+            200,
+            true,
+            None,
+            Arc::new(NodeMetrics::default()),
+        );
+        let expired_package = ExpiredCoresPackage::new(
+            SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+            None,
+            Route { hops: vec![] },
+            MessageType::ServiceReceipt(VersionedData::test_new(dv!(0, 0), vec![])),
+            0,
+        );
+        let system = System::new("route_expired_package_handles_unmigratable_service_receipt");
+
+        subject.route_expired_package(Component::Accountant, expired_package, true);
+
+        System::current().stop_with_code(0);
+        system.run();
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(accountant_recording.len(), 0);
+        TestLogHandler::new().exists_log_containing(
+            "ERROR: RoutingService: Received unmigratable ServiceReceipt: MigrationNotFound(DataVersion { major: 0, minor: 0 }, DataVersion { major: 0, minor: 1 })",
+        );
+    }
 }
@@ -15,6 +15,7 @@ mod actor_system_factory;
 pub mod apps;
 pub mod blockchain;
 mod bootstrapper;
+mod clandestine_port_hopper;
 mod crash_test_dummy;
 pub mod daemon;
 pub mod database;
@@ -29,6 +30,7 @@ pub mod json_framer;
 pub mod json_masquerader;
 mod listener_handler;
 pub mod masquerader;
+pub mod metrics;
 pub mod neighborhood;
 pub mod node_configurator;
 mod null_masquerader;
@@ -46,3 +48,6 @@ mod stream_writer_unsorted;
 pub mod test_utils; //TODO we should make some effort for collections of testing utils to be really test conditioned.
 pub mod tls_discriminator_factory;
 pub mod ui_gateway;
+pub mod websocket_discriminator_factory;
+pub mod websocket_framer;
+pub mod websocket_masquerader;
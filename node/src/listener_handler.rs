@@ -5,6 +5,11 @@ use crate::sub_lib::stream_connector::StreamConnector;
 use crate::sub_lib::stream_connector::StreamConnectorReal;
 use crate::sub_lib::tokio_wrappers::TokioListenerWrapper;
 use crate::sub_lib::tokio_wrappers::TokioListenerWrapperReal;
+use crate::sub_lib::websocket_handshake::{
+    ServerHandshakeOutcome, SniffedReadHalf, WebSocketServerHandshake, SERVER_HANDSHAKE_TIMEOUT_MS,
+};
+use crate::websocket_discriminator_factory::WebSocketDiscriminatorFactory;
+use crate::websocket_masquerader::WebSocketRole;
 use actix::Recipient;
 use masq_lib::logger::Logger;
 use std::io;
@@ -12,8 +17,11 @@ use std::marker::Send;
 use std::net::IpAddr;
 use std::net::Ipv4Addr;
 use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::TcpStream;
 use tokio::prelude::Async;
 use tokio::prelude::Future;
+use tokio::timer::Timeout;
 
 pub trait ListenerHandler: Send + Future {
     fn bind_port_and_configuration(
@@ -34,6 +42,7 @@ pub struct ListenerHandlerReal {
     listener: Box<dyn TokioListenerWrapper>,
     add_stream_sub: Option<Recipient<AddStreamMsg>>,
     stream_connector: Box<dyn StreamConnector>,
+    websocket_handshake_timeout: Duration,
     logger: Logger,
 }
 
@@ -69,6 +78,10 @@ impl Future for ListenerHandlerReal {
             let result = self.listener.poll_accept();
             match result {
                 Ok(Async::Ready((stream, socket_addr))) => {
+                    if self.accepts_websocket() {
+                        self.accept_possible_websocket(stream, socket_addr);
+                        continue;
+                    }
                     let connection_info =
                         match self.stream_connector.split_stream(stream, &self.logger) {
                             Some(ci) => ci,
@@ -113,9 +126,93 @@ impl ListenerHandlerReal {
             listener: Box::new(TokioListenerWrapperReal::new()),
             add_stream_sub: None,
             stream_connector: Box::new(StreamConnectorReal {}),
+            websocket_handshake_timeout: Duration::from_millis(SERVER_HANDSHAKE_TIMEOUT_MS),
             logger: Logger::new("Uninitialized Listener"),
         }
     }
+
+    fn accepts_websocket(&self) -> bool {
+        self.port_configuration
+            .as_ref()
+            .expect("Internal error: port_configuration is None")
+            .websocket_role_opt
+            .is_some()
+    }
+
+    fn accept_possible_websocket(&self, stream: TcpStream, socket_addr: SocketAddr) {
+        let add_stream_sub = self
+            .add_stream_sub
+            .as_ref()
+            .expect("Internal error: StreamHandlerPool unbound")
+            .clone();
+        let port = self.port;
+        let port_configuration = self
+            .port_configuration
+            .as_ref()
+            .expect("Internal error: port_configuration is None")
+            .clone();
+        let logger = self.logger.clone();
+        let handshake = Timeout::new(
+            WebSocketServerHandshake::new(stream),
+            self.websocket_handshake_timeout,
+        )
+        .map_err(|wrapped_error| match wrapped_error.into_inner() {
+            Some(error) => error,
+            None => io::Error::from(io::ErrorKind::TimedOut),
+        });
+        tokio::spawn(handshake.then(move |result| {
+            let (stream, sniffed_opt) = match result {
+                Ok(ServerHandshakeOutcome::Upgraded(stream)) => (stream, None),
+                Ok(ServerHandshakeOutcome::Plain(stream, sniffed)) => (stream, Some(sniffed)),
+                Err(e) => {
+                    warning!(
+                        logger,
+                        "Dropping connection from {}: WebSocket handshake failed: {}",
+                        socket_addr,
+                        e
+                    );
+                    return Ok(());
+                }
+            };
+            let mut connection_info = match (StreamConnectorReal {}).split_stream(stream, &logger) {
+                Some(ci) => ci,
+                None => {
+                    error!(
+                        logger,
+                        "Connection from {} was closed before it could be accepted", socket_addr
+                    );
+                    return Ok(());
+                }
+            };
+            let port_configuration = match sniffed_opt {
+                None => {
+                    debug!(
+                        logger,
+                        "Connection from {} upgraded to WebSocket", socket_addr
+                    );
+                    PortConfiguration {
+                        discriminator_factories: vec![Box::new(
+                            WebSocketDiscriminatorFactory::new(),
+                        )],
+                        is_clandestine: port_configuration.is_clandestine,
+                        websocket_role_opt: Some(WebSocketRole::Server),
+                    }
+                }
+                Some(sniffed) => {
+                    connection_info.reader =
+                        Box::new(SniffedReadHalf::new(sniffed, connection_info.reader));
+                    PortConfiguration {
+                        websocket_role_opt: None,
+                        ..port_configuration
+                    }
+                }
+            };
+            add_stream_sub
+                .try_send(AddStreamMsg::new(connection_info, port, port_configuration))
+                .expect("Internal error: StreamHandlerPool is dead");
+            Ok(())
+        }));
+    }
 }
 
 pub struct ListenerHandlerFactoryReal {}
@@ -136,6 +233,7 @@ impl ListenerHandlerFactoryReal {
 mod tests {
     use super::*;
     use crate::node_test_utils::NullDiscriminatorFactory;
+    use crate::sub_lib::websocket_handshake::{make_upgrade_request, verify_upgrade_response};
     use crate::test_utils::little_tcp_server::LittleTcpServer;
     use crate::test_utils::recorder::make_recorder;
     use crate::test_utils::recorder::Recorder;
@@ -151,6 +249,7 @@ mod tests {
     use std::cell::RefCell;
     use std::io::Error;
     use std::io::ErrorKind;
+    use std::io::{Read, Write};
     use std::net;
     use std::net::Shutdown;
     use std::net::TcpStream as StdTcpStream;
@@ -432,6 +531,126 @@ mod tests {
         assert_eq!(recording.len(), 3);
     }
 
+    #[test]
+    fn upgrades_websocket_connections_and_passes_plain_ones_through_on_websocket_port() {
+        let (stream_handler_pool, awaiter, recording_arc) = make_recorder();
+        let (tx, rx) = unbounded();
+        thread::spawn(move || {
+            let system = System::new(
+                "upgrades_websocket_connections_and_passes_plain_ones_through_on_websocket_port",
+            );
+            let add_stream_sub = start_recorder(stream_handler_pool);
+            tx.send(add_stream_sub).expect("Internal Error");
+            system.run();
+        });
+        let port = find_free_port();
+        thread::spawn(move || {
+            let add_stream_sub = rx.recv().unwrap();
+            let mut subject = ListenerHandlerReal::new();
+            subject.bind_subs(add_stream_sub);
+            let mut port_configuration =
+                PortConfiguration::new(vec![Box::new(NullDiscriminatorFactory::new())], true);
+            port_configuration.websocket_role_opt = Some(WebSocketRole::Server);
+            subject
+                .bind_port_and_configuration(port, port_configuration)
+                .unwrap();
+            tokio::run(subject)
+        });
+        thread::sleep(Duration::from_millis(500));
+        let socket_addr = SocketAddr::new(localhost(), port);
+        let mut plain = net::TcpStream::connect(socket_addr).unwrap();
+        plain.write_all(b"{\"bodyText\": \"booga\"}").unwrap();
+        awaiter.await_message_count(1);
+        let mut websocket = net::TcpStream::connect(socket_addr).unwrap();
+        websocket
+            .write_all(&make_upgrade_request(
+                "localhost",
+                "dGhlIHNhbXBsZSBub25jZQ==",
+            ))
+            .unwrap();
+        let mut response = vec![];
+        let mut buf = [0u8; 1];
+        while !response.ends_with(b"\r\n\r\n") {
+            websocket.read_exact(&mut buf).unwrap();
+            response.push(buf[0]);
+        }
+
+        awaiter.await_message_count(2);
+        assert_eq!(
+            verify_upgrade_response(&response, "dGhlIHNhbXBsZSBub25jZQ=="),
+            Ok(())
+        );
+        let recording = recording_arc.lock().unwrap();
+        let plain_msg = recording.get_record::<AddStreamMsg>(0);
+        assert_eq!(
+            plain_msg.connection_info.peer_addr,
+            plain.local_addr().unwrap()
+        );
+        assert_eq!(plain_msg.port_configuration.websocket_role_opt, None);
+        assert!(plain_msg.port_configuration.is_clandestine);
+        let websocket_msg = recording.get_record::<AddStreamMsg>(1);
+        assert_eq!(
+            websocket_msg.connection_info.peer_addr,
+            websocket.local_addr().unwrap()
+        );
+        assert_eq!(
+            websocket_msg.port_configuration.websocket_role_opt,
+            Some(WebSocketRole::Server)
+        );
+        assert!(websocket_msg.port_configuration.is_clandestine);
+        let mut discriminator = websocket_msg.port_configuration.discriminator_factories[0].make();
+        discriminator.add_data(&[0x82, 0x01, 0x42]);
+        assert_eq!(discriminator.take_chunk().unwrap().chunk, vec![0x42]);
+    }
+
+    #[test]
+    fn drops_connections_that_do_not_finish_the_websocket_handshake_in_time() {
+        init_test_logging();
+        let (stream_handler_pool, _, recording_arc) = make_recorder();
+        let (tx, rx) = unbounded();
+        thread::spawn(move || {
+            let system =
+                System::new("drops_connections_that_do_not_finish_the_websocket_handshake_in_time");
+            let add_stream_sub = start_recorder(stream_handler_pool);
+            tx.send(add_stream_sub).expect("Internal Error");
+            system.run();
+        });
+        let port = find_free_port();
+        thread::spawn(move || {
+            let add_stream_sub = rx.recv().unwrap();
+            let mut subject = ListenerHandlerReal::new();
+            subject.websocket_handshake_timeout = Duration::from_millis(100);
+            subject.bind_subs(add_stream_sub);
+            let mut port_configuration =
+                PortConfiguration::new(vec![Box::new(NullDiscriminatorFactory::new())], true);
+            port_configuration.websocket_role_opt = Some(WebSocketRole::Server);
+            subject
+                .bind_port_and_configuration(port, port_configuration)
+                .unwrap();
+            tokio::run(subject)
+        });
+        thread::sleep(Duration::from_millis(500));
+        let mut dawdler = net::TcpStream::connect(SocketAddr::new(localhost(), port)).unwrap();
+        dawdler
+            .set_read_timeout(Some(Duration::from_millis(2000)))
+            .unwrap();
+
+        dawdler.write_all(b"GE").unwrap();
+
+        let mut buf = [0u8; 1];
+        assert_eq!(dawdler.read(&mut buf).unwrap(), 0);
+        TestLogHandler::new().await_log_containing(
+            &format!(
+                "WARN: ListenerHandler {}: Dropping connection from {}: WebSocket handshake \
+                failed: timed out",
+                port,
+                dawdler.local_addr().unwrap()
+            ),
+            1000,
+        );
+        assert_eq!(recording_arc.lock().unwrap().len(), 0);
+    }
+
     fn start_recorder(recorder: Recorder) -> Recipient<AddStreamMsg> {
         let recorder_addr: Addr<Recorder> = recorder.start();
         recorder_addr.recipient::<AddStreamMsg>()
@@ -8,6 +8,8 @@ use std::marker::Send;
 #[derive(Debug, PartialEq, Eq)]
 pub enum MasqueradeError {
     NotThisMasquerader, // This masquerader can't unmask this data. Try another one.
+    NothingToUnmask,    // This data is protocol housekeeping without a payload. Drop it.
+    PeerClosing,        // This data announces that the peer is closing the stream.
     LowLevelDataError(String), // Error below the level of the masquerade protocol.
     MidLevelDataError(String), // Error in the syntax or semantics of the masquerade protocol.
     HighLevelDataError(String), // Error extracting a LiveCoresPackage from the masquerade.
@@ -20,6 +22,8 @@ impl Display for MasqueradeError {
             MasqueradeError::MidLevelDataError(ref s) => write!(f, "Mid-level data error: {}", s),
             MasqueradeError::HighLevelDataError(ref s) => write!(f, "High-level data error: {}", s),
             MasqueradeError::NotThisMasquerader => write!(f, "Data not for this masquerader"),
+            MasqueradeError::NothingToUnmask => write!(f, "Data carries no payload"),
+            MasqueradeError::PeerClosing => write!(f, "Peer is closing the stream"),
         }
     }
 }
@@ -60,5 +64,13 @@ mod tests {
             &format!("{}", MasqueradeError::NotThisMasquerader),
             "Data not for this masquerader"
         );
+        assert_eq!(
+            &format!("{}", MasqueradeError::NothingToUnmask),
+            "Data carries no payload"
+        );
+        assert_eq!(
+            &format!("{}", MasqueradeError::PeerClosing),
+            "Peer is closing the stream"
+        );
     }
 }
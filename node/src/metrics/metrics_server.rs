@@ -0,0 +1,267 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::metrics::NodeMetrics;
+use masq_lib::logger::Logger;
+use masq_lib::ui_auth::{UiTokens, UI_TOKEN_HEADER};
+use masq_lib::utils::localhost;
+use std::io;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const MAX_REQUEST_HEADER_SIZE: usize = 8192;
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Serves the metrics on its own thread, so that a slow scraper can never hold up an actor. The
+// metrics include what the Node owes and is owed, so a scraper must present a UI token, just as a
+// UI must, unless UI authentication is off.
+pub fn start_metrics_server(
+    port: u16,
+    metrics: Arc<NodeMetrics>,
+    ui_token_file_opt: Option<PathBuf>,
+) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind(SocketAddr::new(localhost(), port))?;
+    let local_addr = listener.local_addr()?;
+    let logger = Logger::new("MetricsServer");
+    info!(logger, "Serving metrics at http://{}/metrics", local_addr);
+    thread::spawn(move || {
+        for stream_result in listener.incoming() {
+            match stream_result {
+                Ok(stream) => {
+                    if let Err(e) = serve(stream, &metrics, ui_token_file_opt.as_deref(), &logger) {
+                        debug!(logger, "Couldn't answer metrics request: {}", e)
+                    }
+                }
+                Err(e) => warning!(logger, "Couldn't accept metrics connection: {}", e),
+            }
+        }
+    });
+    Ok(local_addr)
+}
+
+fn serve(
+    mut stream: TcpStream,
+    metrics: &NodeMetrics,
+    ui_token_file_opt: Option<&Path>,
+    logger: &Logger,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    let request_head = read_request_head(&mut stream)?;
+    let request_line = request_head.first().map(String::as_str).unwrap_or("");
+    let response = match request_line
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["GET", "/metrics", _] if !is_authorized(&request_head[1..], ui_token_file_opt, logger) => {
+            make_response(
+                "401 Unauthorized",
+                "text/plain; charset=utf-8",
+                "Unauthorized\n",
+            )
+        }
+        ["GET", "/metrics", _] => make_response(
+            "200 OK",
+            "text/plain; version=0.0.4; charset=utf-8",
+            &metrics.render(),
+        ),
+        _ => make_response("404 Not Found", "text/plain; charset=utf-8", "Not Found\n"),
+    };
+    stream.write_all(response.as_bytes())?;
+    stream.flush()
+}
+
+// The token file is read for every request rather than once, so that the server neither races the
+// UiGateway to create it nor goes on accepting tokens that have been replaced
+fn is_authorized(headers: &[String], ui_token_file_opt: Option<&Path>, logger: &Logger) -> bool {
+    let ui_token_file = match ui_token_file_opt {
+        Some(ui_token_file) => ui_token_file,
+        None => return true,
+    };
+    let tokens = match UiTokens::read_from(ui_token_file) {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            warning!(logger, "Refusing metrics request: {}", e);
+            return false;
+        }
+    };
+    headers
+        .iter()
+        .filter_map(|header| presented_token_opt(header))
+        .any(|token| tokens.permission_of(token).is_some())
+}
+
+fn presented_token_opt(header: &str) -> Option<&str> {
+    let (name, value) = header.split_once(':')?;
+    let value = value.trim();
+    if name.trim().eq_ignore_ascii_case(UI_TOKEN_HEADER) {
+        Some(value)
+    } else if name.trim().eq_ignore_ascii_case("Authorization") {
+        let (scheme, credentials) = value.split_once(' ')?;
+        scheme
+            .eq_ignore_ascii_case("Bearer")
+            .then(|| credentials.trim())
+    } else {
+        None
+    }
+}
+
+fn read_request_head(stream: &mut TcpStream) -> io::Result<Vec<String>> {
+    let mut request = vec![];
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        if request.len() > MAX_REQUEST_HEADER_SIZE {
+            break;
+        }
+        let len = stream.read(&mut buf)?;
+        if len == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..len]);
+    }
+    let request = String::from_utf8_lossy(&request);
+    Ok(request
+        .lines()
+        .take_while(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect())
+}
+
+fn make_response(status: &str, content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use masq_lib::utils::find_free_port;
+
+    fn request(addr: SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn metrics_server_serves_rendered_metrics() {
+        let metrics = Arc::new(NodeMetrics::default());
+        metrics.add_relayed_bytes(4321);
+        let port = find_free_port();
+
+        let addr = start_metrics_server(port, metrics, None).unwrap();
+
+        assert_eq!(addr, SocketAddr::new(localhost(), port));
+        let response = request(addr, "GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4; charset=utf-8\r\n"));
+        assert!(response.contains("\r\n\r\n# HELP masq_node_streams_active "));
+        assert!(response.contains("\nmasq_node_relayed_bytes_total 4321\n"));
+    }
+
+    #[test]
+    fn metrics_server_answers_other_requests_with_not_found() {
+        let port = find_free_port();
+        let addr = start_metrics_server(port, Arc::new(NodeMetrics::default()), None).unwrap();
+
+        let wrong_path = request(addr, "GET /status HTTP/1.1\r\n\r\n");
+        let wrong_method = request(addr, "POST /metrics HTTP/1.1\r\n\r\n");
+
+        assert!(wrong_path.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(wrong_method.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+
+    #[test]
+    fn metrics_server_demands_a_ui_token_if_there_is_a_token_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "metrics_server",
+            "metrics_server_demands_a_ui_token_if_there_is_a_token_file",
+        );
+        let ui_token_file = home_dir.join("ui_tokens");
+        std::fs::write(&ui_token_file, "admin=booga\nread-only=wooga\n").unwrap();
+        let port = find_free_port();
+        let addr =
+            start_metrics_server(port, Arc::new(NodeMetrics::default()), Some(ui_token_file))
+                .unwrap();
+
+        let no_token = request(addr, "GET /metrics HTTP/1.1\r\n\r\n");
+        let wrong_token = request(
+            addr,
+            "GET /metrics HTTP/1.1\r\nX-MASQ-UI-Token: agoob\r\n\r\n",
+        );
+        let read_only_token = request(
+            addr,
+            "GET /metrics HTTP/1.1\r\nx-masq-ui-token: wooga\r\n\r\n",
+        );
+        let bearer_token = request(
+            addr,
+            "GET /metrics HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer booga\r\n\r\n",
+        );
+
+        assert!(
+            no_token.starts_with("HTTP/1.1 401 Unauthorized\r\n"),
+            "{}",
+            no_token
+        );
+        assert!(!no_token.contains("masq_node_"));
+        assert!(wrong_token.starts_with("HTTP/1.1 401 Unauthorized\r\n"));
+        assert!(read_only_token.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(bearer_token.starts_with("HTTP/1.1 200 OK\r\n"));
+    }
+
+    #[test]
+    fn metrics_server_refuses_everyone_if_the_token_file_cannot_be_read() {
+        let home_dir = ensure_node_home_directory_exists(
+            "metrics_server",
+            "metrics_server_refuses_everyone_if_the_token_file_cannot_be_read",
+        );
+        let port = find_free_port();
+        let addr = start_metrics_server(
+            port,
+            Arc::new(NodeMetrics::default()),
+            Some(home_dir.join("nonexistent")),
+        )
+        .unwrap();
+
+        let response = request(
+            addr,
+            "GET /metrics HTTP/1.1\r\nX-MASQ-UI-Token: booga\r\n\r\n",
+        );
+
+        assert!(response.starts_with("HTTP/1.1 401 Unauthorized\r\n"));
+    }
+
+    #[test]
+    fn presented_token_opt_recognizes_both_headers() {
+        assert_eq!(presented_token_opt("X-MASQ-UI-Token: booga"), Some("booga"));
+        assert_eq!(
+            presented_token_opt("authorization: bearer  booga "),
+            Some("booga")
+        );
+        assert_eq!(presented_token_opt("Authorization: Basic Ym9vZ2E="), None);
+        assert_eq!(presented_token_opt("Host: localhost"), None);
+        assert_eq!(presented_token_opt("no colon"), None);
+    }
+
+    #[test]
+    fn metrics_server_complains_if_port_is_taken() {
+        let port = find_free_port();
+        let _listener = TcpListener::bind(SocketAddr::new(localhost(), port)).unwrap();
+
+        let result = start_metrics_server(port, Arc::new(NodeMetrics::default()), None);
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::AddrInUse);
+    }
+}
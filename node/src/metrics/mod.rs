@@ -0,0 +1,418 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+pub mod metrics_server;
+
+use crate::neighborhood::overall_connection_status::OverallConnectionStage;
+use masq_lib::messages::ScanType;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub const STREAM_HANDLER_POOL_LABEL: &str = "stream_handler_pool";
+pub const PROXY_CLIENT_POOL_LABEL: &str = "proxy_client";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GossipOutcome {
+    Accepted,
+    Rejected,
+    Ignored,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ScanOutcome {
+    Started,
+    NothingToProcess,
+    AlreadyRunning,
+    NoConsumingWallet,
+    Completed,
+}
+
+impl ScanOutcome {
+    fn label(&self) -> &'static str {
+        match self {
+            ScanOutcome::Started => "started",
+            ScanOutcome::NothingToProcess => "nothing_to_process",
+            ScanOutcome::AlreadyRunning => "already_running",
+            ScanOutcome::NoConsumingWallet => "no_consuming_wallet",
+            ScanOutcome::Completed => "completed",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct GossipCounts {
+    received: u64,
+    accepted: u64,
+    rejected: u64,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct DurationSummary {
+    sum_micros: u64,
+    count: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct NodeMetrics {
+    streams_active: Mutex<BTreeMap<&'static str, usize>>,
+    relayed_bytes: AtomicU64,
+    exited_bytes: AtomicU64,
+    gossip: Mutex<BTreeMap<&'static str, GossipCounts>>,
+    route_queries: Mutex<DurationSummary>,
+    route_query_failures: AtomicU64,
+    scan_durations: Mutex<BTreeMap<&'static str, DurationSummary>>,
+    scan_outcomes: Mutex<BTreeMap<(&'static str, ScanOutcome), u64>>,
+    ledger_totals_opt: Mutex<Option<(u128, i128)>>,
+    connection_stage_opt: Mutex<Option<OverallConnectionStage>>,
+}
+
+impl NodeMetrics {
+    pub fn set_active_streams(&self, pool: &'static str, count: usize) {
+        self.streams_active
+            .lock()
+            .expect("Metrics poisoned")
+            .insert(pool, count);
+    }
+
+    pub fn add_relayed_bytes(&self, bytes: usize) {
+        self.relayed_bytes
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub fn add_exited_bytes(&self, bytes: usize) {
+        self.exited_bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub fn record_gossip(&self, handler: &'static str, outcome: GossipOutcome) {
+        let mut gossip = self.gossip.lock().expect("Metrics poisoned");
+        let counts = gossip.entry(handler).or_default();
+        counts.received += 1;
+        match outcome {
+            GossipOutcome::Accepted => counts.accepted += 1,
+            GossipOutcome::Rejected => counts.rejected += 1,
+            GossipOutcome::Ignored => (),
+        }
+    }
+
+    pub fn record_route_query(&self, duration: Duration, succeeded: bool) {
+        Self::add_to_summary(
+            &mut self.route_queries.lock().expect("Metrics poisoned"),
+            duration,
+        );
+        if !succeeded {
+            self.route_query_failures.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn record_scan_outcome(&self, scan_type: ScanType, outcome: ScanOutcome) {
+        *self
+            .scan_outcomes
+            .lock()
+            .expect("Metrics poisoned")
+            .entry((scan_label(scan_type), outcome))
+            .or_default() += 1;
+    }
+
+    pub fn record_scan_duration(&self, scan_type: ScanType, duration: Duration) {
+        let mut scan_durations = self.scan_durations.lock().expect("Metrics poisoned");
+        Self::add_to_summary(
+            scan_durations.entry(scan_label(scan_type)).or_default(),
+            duration,
+        );
+        drop(scan_durations);
+        self.record_scan_outcome(scan_type, ScanOutcome::Completed);
+    }
+
+    pub fn set_ledger_totals(&self, payable_total_wei: u128, receivable_total_wei: i128) {
+        self.ledger_totals_opt
+            .lock()
+            .expect("Metrics poisoned")
+            .replace((payable_total_wei, receivable_total_wei));
+    }
+
+    pub fn set_connection_stage(&self, stage: OverallConnectionStage) {
+        self.connection_stage_opt
+            .lock()
+            .expect("Metrics poisoned")
+            .replace(stage);
+    }
+
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+        self.render_streams(&mut text);
+        Self::render_counter(
+            &mut text,
+            "masq_node_relayed_bytes_total",
+            "Payload bytes this Node has routed onward for other Nodes.",
+            self.relayed_bytes.load(Ordering::Relaxed),
+        );
+        Self::render_counter(
+            &mut text,
+            "masq_node_exited_bytes_total",
+            "Payload bytes this Node has written to servers on the Internet as an exit Node.",
+            self.exited_bytes.load(Ordering::Relaxed),
+        );
+        self.render_gossip(&mut text);
+        self.render_route_queries(&mut text);
+        self.render_scans(&mut text);
+        self.render_ledger_totals(&mut text);
+        self.render_connection_stage(&mut text);
+        text
+    }
+
+    fn add_to_summary(summary: &mut DurationSummary, duration: Duration) {
+        summary.sum_micros += duration.as_micros() as u64;
+        summary.count += 1;
+    }
+
+    fn render_header(text: &mut String, name: &str, help: &str, metric_type: &str) {
+        writeln!(text, "# HELP {} {}", name, help).expect("Write failed");
+        writeln!(text, "# TYPE {} {}", name, metric_type).expect("Write failed");
+    }
+
+    fn render_counter(text: &mut String, name: &str, help: &str, value: u64) {
+        Self::render_header(text, name, help, "counter");
+        writeln!(text, "{} {}", name, value).expect("Write failed");
+    }
+
+    fn render_streams(&self, text: &mut String) {
+        let name = "masq_node_streams_active";
+        Self::render_header(text, name, "Streams currently open, by pool.", "gauge");
+        for (pool, count) in self.streams_active.lock().expect("Metrics poisoned").iter() {
+            writeln!(text, "{}{{pool=\"{}\"}} {}", name, pool, count).expect("Write failed");
+        }
+    }
+
+    fn render_gossip(&self, text: &mut String) {
+        let gossip = self.gossip.lock().expect("Metrics poisoned");
+        type Extractor = fn(&GossipCounts) -> u64;
+        let families: [(&str, &str, Extractor); 3] = [
+            (
+                "masq_node_gossip_received_total",
+                "Gossip messages received, by the handler that took charge of them.",
+                |counts| counts.received,
+            ),
+            (
+                "masq_node_gossip_accepted_total",
+                "Gossip messages that changed the neighborhood database or drew a reply.",
+                |counts| counts.accepted,
+            ),
+            (
+                "masq_node_gossip_rejected_total",
+                "Gossip messages that failed or got their sender banned.",
+                |counts| counts.rejected,
+            ),
+        ];
+        for (name, help, extract) in families.iter() {
+            Self::render_header(text, name, help, "counter");
+            for (handler, counts) in gossip.iter() {
+                writeln!(
+                    text,
+                    "{}{{handler=\"{}\"}} {}",
+                    name,
+                    handler,
+                    extract(counts)
+                )
+                .expect("Write failed");
+            }
+        }
+    }
+
+    fn render_route_queries(&self, text: &mut String) {
+        let name = "masq_node_route_query_duration_seconds";
+        let summary = *self.route_queries.lock().expect("Metrics poisoned");
+        Self::render_header(
+            text,
+            name,
+            "Time the Neighborhood spent answering route queries.",
+            "summary",
+        );
+        writeln!(text, "{}_sum {}", name, seconds(summary.sum_micros)).expect("Write failed");
+        writeln!(text, "{}_count {}", name, summary.count).expect("Write failed");
+        Self::render_counter(
+            text,
+            "masq_node_route_query_failures_total",
+            "Route queries the Neighborhood could not satisfy.",
+            self.route_query_failures.load(Ordering::Relaxed),
+        );
+    }
+
+    fn render_scans(&self, text: &mut String) {
+        let name = "masq_node_scan_duration_seconds";
+        Self::render_header(
+            text,
+            name,
+            "Time from the start to the end of completed Accountant scans.",
+            "summary",
+        );
+        for (scan, summary) in self.scan_durations.lock().expect("Metrics poisoned").iter() {
+            writeln!(
+                text,
+                "{}_sum{{scan=\"{}\"}} {}",
+                name,
+                scan,
+                seconds(summary.sum_micros)
+            )
+            .expect("Write failed");
+            writeln!(
+                text,
+                "{}_count{{scan=\"{}\"}} {}",
+                name, scan, summary.count
+            )
+            .expect("Write failed");
+        }
+        let name = "masq_node_scans_total";
+        Self::render_header(
+            text,
+            name,
+            "Accountant scan requests, by scan and outcome.",
+            "counter",
+        );
+        for ((scan, outcome), count) in self.scan_outcomes.lock().expect("Metrics poisoned").iter()
+        {
+            writeln!(
+                text,
+                "{}{{scan=\"{}\",outcome=\"{}\"}} {}",
+                name,
+                scan,
+                outcome.label(),
+                count
+            )
+            .expect("Write failed");
+        }
+    }
+
+    fn render_ledger_totals(&self, text: &mut String) {
+        if let Some((payable, receivable)) =
+            *self.ledger_totals_opt.lock().expect("Metrics poisoned")
+        {
+            let name = "masq_node_payable_total_wei";
+            Self::render_header(text, name, "Total owed by this Node to others.", "gauge");
+            writeln!(text, "{} {}", name, payable).expect("Write failed");
+            let name = "masq_node_receivable_total_wei";
+            Self::render_header(text, name, "Total owed to this Node by others.", "gauge");
+            writeln!(text, "{} {}", name, receivable).expect("Write failed");
+        }
+    }
+
+    fn render_connection_stage(&self, text: &mut String) {
+        if let Some(current) = *self.connection_stage_opt.lock().expect("Metrics poisoned") {
+            let name = "masq_node_connection_stage";
+            Self::render_header(
+                text,
+                name,
+                "1 for the overall connection stage the Node is in, 0 for the others.",
+                "gauge",
+            );
+            for (stage, label) in [
+                (OverallConnectionStage::NotConnected, "not_connected"),
+                (
+                    OverallConnectionStage::ConnectedToNeighbor,
+                    "connected_to_neighbor",
+                ),
+                (OverallConnectionStage::RouteFound, "route_found"),
+            ] {
+                writeln!(
+                    text,
+                    "{}{{stage=\"{}\"}} {}",
+                    name,
+                    label,
+                    u8::from(stage == current)
+                )
+                .expect("Write failed");
+            }
+        }
+    }
+}
+
+fn scan_label(scan_type: ScanType) -> &'static str {
+    match scan_type {
+        ScanType::Payables => "payables",
+        ScanType::PendingPayables => "pending_payables",
+        ScanType::Receivables => "receivables",
+    }
+}
+
+fn seconds(micros: u64) -> String {
+    format!("{}.{:06}", micros / 1_000_000, micros % 1_000_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_starts_with_empty_counters_and_omits_what_has_never_been_reported() {
+        let subject = NodeMetrics::default();
+
+        let result = subject.render();
+
+        assert!(result.contains("# TYPE masq_node_streams_active gauge\n"));
+        assert!(result.contains("\nmasq_node_relayed_bytes_total 0\n"));
+        assert!(result.contains("\nmasq_node_exited_bytes_total 0\n"));
+        assert!(result.contains("\nmasq_node_route_query_duration_seconds_sum 0.000000\n"));
+        assert!(result.contains("\nmasq_node_route_query_duration_seconds_count 0\n"));
+        assert!(result.contains("\nmasq_node_route_query_failures_total 0\n"));
+        assert!(!result.contains("masq_node_payable_total_wei"));
+        assert!(!result.contains("masq_node_connection_stage"));
+    }
+
+    #[test]
+    fn render_reports_everything_that_has_been_recorded() {
+        let subject = NodeMetrics::default();
+        subject.set_active_streams(STREAM_HANDLER_POOL_LABEL, 3);
+        subject.set_active_streams(PROXY_CLIENT_POOL_LABEL, 1);
+        subject.set_active_streams(STREAM_HANDLER_POOL_LABEL, 2);
+        subject.add_relayed_bytes(1000);
+        subject.add_relayed_bytes(24);
+        subject.add_exited_bytes(512);
+        subject.record_gossip("DebutHandler", GossipOutcome::Accepted);
+        subject.record_gossip("DebutHandler", GossipOutcome::Rejected);
+        subject.record_gossip("StandardGossipHandler", GossipOutcome::Ignored);
+        subject.record_route_query(Duration::from_micros(1_500_000), true);
+        subject.record_route_query(Duration::from_micros(250), false);
+        subject.record_scan_outcome(ScanType::Payables, ScanOutcome::Started);
+        subject.record_scan_outcome(ScanType::Receivables, ScanOutcome::AlreadyRunning);
+        subject.record_scan_duration(ScanType::Payables, Duration::from_millis(145));
+        subject.set_ledger_totals(123_456_789_000, -42);
+        subject.set_connection_stage(OverallConnectionStage::ConnectedToNeighbor);
+
+        let result = subject.render();
+
+        [
+            "masq_node_streams_active{pool=\"proxy_client\"} 1",
+            "masq_node_streams_active{pool=\"stream_handler_pool\"} 2",
+            "masq_node_relayed_bytes_total 1024",
+            "masq_node_exited_bytes_total 512",
+            "masq_node_gossip_received_total{handler=\"DebutHandler\"} 2",
+            "masq_node_gossip_received_total{handler=\"StandardGossipHandler\"} 1",
+            "masq_node_gossip_accepted_total{handler=\"DebutHandler\"} 1",
+            "masq_node_gossip_accepted_total{handler=\"StandardGossipHandler\"} 0",
+            "masq_node_gossip_rejected_total{handler=\"DebutHandler\"} 1",
+            "masq_node_route_query_duration_seconds_sum 1.500250",
+            "masq_node_route_query_duration_seconds_count 2",
+            "masq_node_route_query_failures_total 1",
+            "masq_node_scan_duration_seconds_sum{scan=\"payables\"} 0.145000",
+            "masq_node_scan_duration_seconds_count{scan=\"payables\"} 1",
+            "masq_node_scans_total{scan=\"payables\",outcome=\"started\"} 1",
+            "masq_node_scans_total{scan=\"payables\",outcome=\"completed\"} 1",
+            "masq_node_scans_total{scan=\"receivables\",outcome=\"already_running\"} 1",
+            "masq_node_payable_total_wei 123456789000",
+            "masq_node_receivable_total_wei -42",
+            "masq_node_connection_stage{stage=\"not_connected\"} 0",
+            "masq_node_connection_stage{stage=\"connected_to_neighbor\"} 1",
+            "masq_node_connection_stage{stage=\"route_found\"} 0",
+        ]
+        .iter()
+        .for_each(|line| {
+            assert!(
+                result.contains(&format!("\n{}\n", line)),
+                "Missing '{}' in:\n{}",
+                line,
+                result
+            )
+        });
+    }
+}
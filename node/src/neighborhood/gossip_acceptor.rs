@@ -1,5 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use crate::metrics::{GossipOutcome, NodeMetrics};
 use crate::neighborhood::gossip::{GossipBuilder, Gossip_0v1};
 use crate::neighborhood::neighborhood_database::{NeighborhoodDatabase, NeighborhoodDatabaseError};
 use crate::neighborhood::node_record::NodeRecord;
@@ -13,6 +14,7 @@ use masq_lib::logger::Logger;
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
 /// Note: if you decide to change this, make sure you test thoroughly. Values less than 5 may lead
@@ -1235,6 +1237,7 @@ pub struct GossipAcceptorReal<'a> {
     cryptde: &'a dyn CryptDE,
     gossip_handlers: Vec<Box<dyn GossipHandler>>,
     logger: Logger,
+    metrics: Arc<NodeMetrics>,
 }
 
 impl<'a> GossipAcceptor for GossipAcceptorReal<'a> {
@@ -1251,7 +1254,7 @@ impl<'a> GossipAcceptor for GossipAcceptorReal<'a> {
             .map(|h| (h.qualifies(database, &agrs, gossip_source), h.as_ref()))
             .find(|pair| !matches!(pair, (Qualification::Unmatched, _)))
             .expect("gossip_handlers should intercept everything");
-        match qualification {
+        let result = match qualification {
             Qualification::Matched => {
                 debug!(
                     self.logger,
@@ -1270,12 +1273,15 @@ impl<'a> GossipAcceptor for GossipAcceptorReal<'a> {
                 panic!("Nothing in gossip_handlers returned Matched or Malformed")
             }
             Qualification::Malformed(reason) => GossipAcceptanceResult::Ban(reason),
-        }
+        };
+        self.metrics
+            .record_gossip(handler_ref.type_name(), Self::gossip_outcome(&result));
+        result
     }
 }
 
 impl<'a> GossipAcceptorReal<'a> {
-    pub fn new(cryptde: &'a dyn CryptDE) -> GossipAcceptorReal {
+    pub fn new(cryptde: &'a dyn CryptDE, metrics: Arc<NodeMetrics>) -> GossipAcceptorReal {
         let logger = Logger::new("GossipAcceptor");
         GossipAcceptorReal {
             gossip_handlers: vec![
@@ -1287,6 +1293,19 @@ impl<'a> GossipAcceptorReal<'a> {
             ],
             cryptde,
             logger,
+            metrics,
+        }
+    }
+
+    fn gossip_outcome(result: &GossipAcceptanceResult) -> GossipOutcome {
+        match result {
+            GossipAcceptanceResult::Accepted | GossipAcceptanceResult::Reply(..) => {
+                GossipOutcome::Accepted
+            }
+            GossipAcceptanceResult::Failed(..) | GossipAcceptanceResult::Ban(_) => {
+                GossipOutcome::Rejected
+            }
+            GossipAcceptanceResult::Ignored => GossipOutcome::Ignored,
         }
     }
 
@@ -2868,7 +2887,9 @@ mod tests {
             .node(node_a.public_key(), false)
             .node(node_b.public_key(), false)
             .build();
-        let subject = make_subject(main_cryptde());
+        let mut subject = make_subject(main_cryptde());
+        let metrics = Arc::new(NodeMetrics::default());
+        subject.metrics = metrics.clone();
 
         let result = subject.handle(
             &mut dest_db,
@@ -2878,6 +2899,11 @@ mod tests {
         );
 
         assert_eq!(GossipAcceptanceResult::Ignored, result);
+        let rendered = metrics.render();
+        assert!(rendered
+            .contains("\nmasq_node_gossip_received_total{handler=\"StandardGossipHandler\"} 1\n"));
+        assert!(rendered
+            .contains("\nmasq_node_gossip_accepted_total{handler=\"StandardGossipHandler\"} 0\n"));
     }
 
     #[test]
@@ -2886,7 +2912,9 @@ mod tests {
         let root_node_cryptde = CryptDENull::from(&root_node.public_key(), TEST_DEFAULT_CHAIN);
         let mut dest_db = db_from_node(&root_node);
         let (gossip, debut_node, gossip_source) = make_debut(2345, Mode::Standard);
-        let subject = make_subject(&root_node_cryptde);
+        let mut subject = make_subject(&root_node_cryptde);
+        let metrics = Arc::new(NodeMetrics::default());
+        subject.metrics = metrics.clone();
         let before = time_t_timestamp();
 
         let result = subject.handle(
@@ -2898,6 +2926,9 @@ mod tests {
 
         let after = time_t_timestamp();
         assert_eq!(GossipAcceptanceResult::Accepted, result);
+        assert!(metrics
+            .render()
+            .contains("\nmasq_node_gossip_accepted_total{handler=\"DebutHandler\"} 1\n"));
         root_node
             .add_half_neighbor_key(debut_node.public_key().clone())
             .unwrap();
@@ -4257,7 +4288,7 @@ mod tests {
     }
 
     fn make_subject(crypt_de: &dyn CryptDE) -> GossipAcceptorReal {
-        GossipAcceptorReal::new(crypt_de)
+        GossipAcceptorReal::new(crypt_de, Arc::new(NodeMetrics::default()))
     }
 
     fn assert_node_records_eq(
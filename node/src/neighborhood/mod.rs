@@ -7,11 +7,14 @@ pub mod gossip_producer;
 pub mod neighborhood_database;
 pub mod node_record;
 pub mod overall_connection_status;
+pub mod pricing;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
 
 use actix::Context;
 use actix::Handler;
@@ -27,20 +30,26 @@ use masq_lib::messages::{UiConnectionStatusResponse, UiShutdownRequest};
 use masq_lib::ui_gateway::{MessageTarget, NodeFromUiMessage, NodeToUiMessage};
 use masq_lib::utils::{exit_process, ExpectValue, NeighborhoodModeLight};
 
+use crate::blockchain::blockchain_interface::blockchain_interface_web3::transaction_signer::{
+    ExternalPayerProofSigner, PayerProofSigner,
+};
+use crate::blockchain::payer::Payer;
 use crate::bootstrapper::BootstrapperConfig;
 use crate::database::db_initializer::DbInitializationConfig;
 use crate::database::db_initializer::{DbInitializer, DbInitializerReal};
 use crate::db_config::persistent_configuration::{
     PersistentConfigError, PersistentConfiguration, PersistentConfigurationReal,
 };
+use crate::metrics::NodeMetrics;
 use crate::neighborhood::gossip::{DotGossipEndpoint, GossipNodeRecord, Gossip_0v1};
 use crate::neighborhood::gossip_acceptor::GossipAcceptanceResult;
 use crate::neighborhood::node_record::NodeRecordInner_0v1;
 use crate::neighborhood::overall_connection_status::{
     OverallConnectionStage, OverallConnectionStatus,
 };
+use crate::neighborhood::pricing::{PricingInputs, RatePackPricer};
 use crate::stream_messages::RemovedStreamType;
-use crate::sub_lib::cryptde::PublicKey;
+use crate::sub_lib::cryptde::{create_digest, PublicKey};
 use crate::sub_lib::cryptde::{CryptDE, CryptData, PlainData};
 use crate::sub_lib::dispatcher::{Component, StreamShutdownMsg};
 use crate::sub_lib::hopper::{ExpiredCoresPackage, NoLookupIncipientCoresPackage};
@@ -56,8 +65,9 @@ use crate::sub_lib::neighborhood::{DispatcherNodeQueryMessage, GossipFailure_0v1
 use crate::sub_lib::neighborhood::{Hops, NeighborhoodMetadata, NodeQueryResponseMetadata};
 use crate::sub_lib::neighborhood::{NRMetadataChange, NodeQueryMessage};
 use crate::sub_lib::neighborhood::{NeighborhoodSubs, NeighborhoodTools};
+use crate::sub_lib::neighborhood::{RepriceMessage, ServedStreamCountMessage};
 use crate::sub_lib::node_addr::NodeAddr;
-use crate::sub_lib::peer_actors::{BindMessage, NewPublicIp, StartMessage};
+use crate::sub_lib::peer_actors::{BindMessage, NewClandestinePorts, NewPublicIp, StartMessage};
 use crate::sub_lib::route::Route;
 use crate::sub_lib::route::RouteSegment;
 use crate::sub_lib::stream_handler_pool::DispatcherNodeQueryResponse;
@@ -87,13 +97,18 @@ pub struct Neighborhood {
     hopper_no_lookup_opt: Option<Recipient<NoLookupIncipientCoresPackage>>,
     connected_signal_opt: Option<Recipient<StartMessage>>,
     node_to_ui_recipient_opt: Option<Recipient<NodeToUiMessage>>,
+    rate_pack_recipients_opt: Option<Vec<Recipient<ConfigChangeMsg>>>,
     gossip_acceptor: Box<dyn GossipAcceptor>,
     gossip_producer: Box<dyn GossipProducer>,
     neighborhood_database: NeighborhoodDatabase,
     consuming_wallet_opt: Option<Wallet>,
+    payer_proof_signer_opt: Option<Box<dyn PayerProofSigner>>,
+    payer_proofs: HashMap<PublicKey, Payer>,
     mode: NeighborhoodModeLight,
     min_hops: Hops,
     db_patch_size: u8,
+    rate_pack_pricer_opt: Option<RatePackPricer>,
+    served_stream_count: usize,
     next_return_route_id: u32,
     overall_connection_status: OverallConnectionStatus,
     chain: Chain,
@@ -103,6 +118,7 @@ pub struct Neighborhood {
     db_password_opt: Option<String>,
     logger: Logger,
     tools: NeighborhoodTools,
+    metrics: Arc<NodeMetrics>,
 }
 
 impl Actor for Neighborhood {
@@ -118,14 +134,45 @@ impl Handler<BindMessage> for Neighborhood {
         self.hopper_no_lookup_opt = Some(msg.peer_actors.hopper.from_hopper_client_no_lookup);
         self.connected_signal_opt = Some(msg.peer_actors.accountant.start);
         self.node_to_ui_recipient_opt = Some(msg.peer_actors.ui_gateway.node_to_ui_message_sub);
+        let mut rate_pack_recipients = vec![msg.peer_actors.hopper.config_change_msg_sub];
+        if let Some(proxy_client) = msg.peer_actors.proxy_client_opt {
+            rate_pack_recipients.push(proxy_client.config_change_msg_sub);
+        }
+        self.rate_pack_recipients_opt = Some(rate_pack_recipients);
     }
 }
 
 impl Handler<StartMessage> for Neighborhood {
     type Result = ();
 
-    fn handle(&mut self, _msg: StartMessage, _ctx: &mut Self::Context) -> Self::Result {
+    fn handle(&mut self, _msg: StartMessage, ctx: &mut Self::Context) -> Self::Result {
         self.handle_start_message();
+        if self.rate_pack_pricer_opt.is_some() {
+            self.tools.notify_later_reprice.notify_later(
+                RepriceMessage {},
+                self.tools.reprice_interval,
+                ctx,
+            );
+        }
+    }
+}
+
+impl Handler<RepriceMessage> for Neighborhood {
+    type Result = ();
+
+    fn handle(&mut self, msg: RepriceMessage, ctx: &mut Self::Context) -> Self::Result {
+        self.handle_reprice();
+        self.tools
+            .notify_later_reprice
+            .notify_later(msg, self.tools.reprice_interval, ctx);
+    }
+}
+
+impl Handler<ServedStreamCountMessage> for Neighborhood {
+    type Result = ();
+
+    fn handle(&mut self, msg: ServedStreamCountMessage, _ctx: &mut Self::Context) -> Self::Result {
+        self.served_stream_count = msg.stream_count;
     }
 }
 
@@ -137,6 +184,14 @@ impl Handler<NewPublicIp> for Neighborhood {
     }
 }
 
+impl Handler<NewClandestinePorts> for Neighborhood {
+    type Result = ();
+
+    fn handle(&mut self, msg: NewClandestinePorts, _ctx: &mut Self::Context) -> Self::Result {
+        self.handle_new_clandestine_ports(msg);
+    }
+}
+
 impl Handler<ConfigChangeMsg> for Neighborhood {
     type Result = ();
 
@@ -265,6 +320,7 @@ impl Handler<ConnectionProgressMessage> for Neighborhood {
                                     .expect("UI Gateway is unbound"),
                                 &self.logger,
                             );
+                        self.report_connection_stage();
                     }
                     _ => (),
                 }
@@ -436,6 +492,22 @@ impl Neighborhood {
             .collect_vec();
 
         let overall_connection_status = OverallConnectionStatus::new(initial_neighbors);
+        let logger = Logger::new("Neighborhood");
+        // The private key of a consuming wallet with an external signer stays with the signer, so
+        // the signer has to make the payer proofs on our routes
+        let payer_proof_signer_opt = config
+            .blockchain_bridge_config
+            .external_signer_url_opt
+            .as_ref()
+            .map(|url| {
+                let signer = ExternalPayerProofSigner::new(url).unwrap_or_else(|e| {
+                    panic!("Invalid external signer URL \"{}\". Error: {}", url, e)
+                });
+                Box::new(signer) as Box<dyn PayerProofSigner>
+            });
+        let rate_pack_pricer_opt = neighborhood_mode
+            .is_standard()
+            .then(|| RatePackPricer::new(*neighborhood_mode.rate_pack(), config.pricing_config));
 
         Neighborhood {
             cryptde,
@@ -443,13 +515,18 @@ impl Neighborhood {
             hopper_no_lookup_opt: None,
             connected_signal_opt: None,
             node_to_ui_recipient_opt: None,
-            gossip_acceptor: Box::new(GossipAcceptorReal::new(cryptde)),
+            rate_pack_recipients_opt: None,
+            gossip_acceptor: Box::new(GossipAcceptorReal::new(cryptde, config.metrics.clone())),
             gossip_producer: Box::new(GossipProducerReal::new()),
             neighborhood_database,
             consuming_wallet_opt: config.consuming_wallet_opt.clone(),
+            payer_proof_signer_opt,
+            payer_proofs: HashMap::new(),
             mode,
             min_hops,
             db_patch_size,
+            rate_pack_pricer_opt,
+            served_stream_count: 0,
             next_return_route_id: 0,
             overall_connection_status,
             chain: config.blockchain_bridge_config.chain,
@@ -457,8 +534,9 @@ impl Neighborhood {
             data_directory: config.data_directory.clone(),
             persistent_config_opt: None,
             db_password_opt: config.db_password_opt.clone(),
-            logger: Logger::new("Neighborhood"),
+            logger,
             tools: NeighborhoodTools::default(),
+            metrics: config.metrics.clone(),
         }
     }
 
@@ -467,6 +545,7 @@ impl Neighborhood {
             bind: addr.clone().recipient::<BindMessage>(),
             start: addr.clone().recipient::<StartMessage>(),
             new_public_ip: addr.clone().recipient::<NewPublicIp>(),
+            new_clandestine_ports: addr.clone().recipient::<NewClandestinePorts>(),
             route_query: addr.clone().recipient::<RouteQueryMessage>(),
             update_node_record_metadata: addr
                 .clone()
@@ -481,6 +560,7 @@ impl Neighborhood {
             stream_shutdown_sub: addr.clone().recipient::<StreamShutdownMsg>(),
             from_ui_message_sub: addr.clone().recipient::<NodeFromUiMessage>(),
             connection_progress_sub: addr.clone().recipient::<ConnectionProgressMessage>(),
+            served_stream_count_sub: addr.clone().recipient::<ServedStreamCountMessage>(),
         }
     }
 
@@ -489,6 +569,12 @@ impl Neighborhood {
         self.connect_database();
         self.validate_or_replace_min_hops_value();
         self.send_debut_gossip_to_all_initial_descriptors();
+        self.report_connection_stage();
+    }
+
+    fn report_connection_stage(&self) {
+        self.metrics
+            .set_connection_stage(self.overall_connection_status.stage);
     }
 
     fn handle_new_public_ip(&mut self, msg: NewPublicIp) {
@@ -506,13 +592,32 @@ impl Neighborhood {
         );
     }
 
+    fn handle_new_clandestine_ports(&mut self, msg: NewClandestinePorts) {
+        let old_ports = self
+            .neighborhood_database
+            .root()
+            .node_addr_opt()
+            .expectv("Root node")
+            .ports();
+        self.neighborhood_database.new_clandestine_ports(&msg.ports);
+        info!(
+            self.logger,
+            "Changed clandestine ports from {:?} to {:?}", old_ports, msg.ports
+        );
+        self.neighborhood_database.root_mut().increment_version();
+        self.gossip_to_neighbors();
+    }
+
     fn handle_route_query_message(&mut self, msg: RouteQueryMessage) -> Option<RouteQueryResponse> {
         let debug_msg_opt = self.logger.debug_enabled().then(|| format!("{:?}", msg));
+        let started_at = Instant::now();
         let route_result = if self.mode == NeighborhoodModeLight::ZeroHop {
             Ok(self.zero_hop_route_response())
         } else {
             self.make_round_trip_route(msg)
         };
+        self.metrics
+            .record_route_query(started_at.elapsed(), route_result.is_ok());
         match route_result {
             Ok(response) => {
                 debug!(
@@ -552,6 +657,7 @@ impl Neighborhood {
                         "Consuming Wallet has been updated: {}", wallet_pair.consuming_wallet
                     );
                     self.consuming_wallet_opt = Some(wallet_pair.consuming_wallet);
+                    self.payer_proofs.clear();
                 }
             }
             ConfigChange::UpdateMinHops(new_min_hops) => {
@@ -567,6 +673,7 @@ impl Neighborhood {
                             node_to_ui_recipient,
                             &self.logger,
                         );
+                    self.report_connection_stage();
                 }
                 self.search_for_a_new_route();
             }
@@ -574,9 +681,50 @@ impl Neighborhood {
                 info!(self.logger, "DB Password has been updated.");
                 self.db_password_opt = Some(new_password);
             }
+            ConfigChange::UpdateRatePack(_) => {
+                trace!(self.logger, "Ignored irrelevant message: {:?}", msg)
+            }
         }
     }
 
+    fn handle_reprice(&mut self) {
+        let pricer = match self.rate_pack_pricer_opt.as_ref() {
+            Some(pricer) => pricer,
+            None => return,
+        };
+        let inputs = PricingInputs {
+            served_stream_count: self.served_stream_count,
+            neighborhood_database: &self.neighborhood_database,
+        };
+        let current = *self.neighborhood_database.root().rate_pack();
+        let new_rate_pack = match pricer.reprice(&current, &inputs) {
+            Some(rate_pack) => rate_pack,
+            None => {
+                debug!(self.logger, "Rate pack {} needs no repricing", current);
+                return;
+            }
+        };
+        let root = self.neighborhood_database.root_mut();
+        root.set_rate_pack(new_rate_pack);
+        root.increment_version();
+        self.rate_pack_recipients_opt
+            .as_ref()
+            .expect("Hopper is unbound")
+            .iter()
+            .for_each(|recipient| {
+                recipient
+                    .try_send(ConfigChangeMsg {
+                        change: ConfigChange::UpdateRatePack(new_rate_pack),
+                    })
+                    .expect("Rate pack recipient is dead")
+            });
+        info!(
+            self.logger,
+            "Repriced rate pack from {} to {}", current, new_rate_pack
+        );
+        self.gossip_to_neighbors();
+    }
+
     fn validate_or_replace_min_hops_value(&mut self) {
         if let Some(persistent_config) = self.persistent_config_opt.as_ref() {
             let value_in_db = persistent_config
@@ -697,6 +845,21 @@ impl Neighborhood {
             return;
         }
 
+        let crypto_suite_inconsistent = |agr: &AccessibleGossipRecord| {
+            !agr.inner
+                .crypto_suite
+                .matches_public_key_len(agr.inner.public_key.len())
+        };
+        if agrs.iter().any(crypto_suite_inconsistent) {
+            error!(
+                self.logger,
+                "Received Gossip advertising a crypto suite that doesn't fit its public key from {}",
+                gossip_source
+            );
+            self.announce_gossip_handling_completion(record_count);
+            return;
+        }
+
         self.handle_gossip_agrs(agrs, gossip_source, cpm_recipient);
         self.announce_gossip_handling_completion(record_count);
     }
@@ -888,6 +1051,7 @@ impl Neighborhood {
                         .expect("UI was not bound."),
                     &self.logger,
                 );
+            self.report_connection_stage();
             self.connected_signal_opt
                 .as_ref()
                 .expect("Accountant was not bound")
@@ -1057,17 +1221,27 @@ impl Neighborhood {
             Err(e) => return Err(e),
         };
 
+        let payers_opt = match self.consuming_wallet_opt.clone() {
+            Some(wallet) if !wallet.can_sign() => Some(self.prove_payment(&wallet, &segments)?),
+            _ => None,
+        };
+
         let return_route_id = self.advance_return_route_id();
-        Ok(RouteQueryResponse {
-            route: Route::round_trip(
+        let route = match payers_opt {
+            Some(payers) => {
+                Route::round_trip_with_payers(over, back, self.cryptde, &payers, return_route_id)
+            }
+            None => Route::round_trip(
                 over,
                 back,
                 self.cryptde,
                 self.consuming_wallet_opt.clone(),
                 return_route_id,
                 Some(self.chain.rec().contract),
-            )
-            .expect("Internal error: bad route"),
+            ),
+        };
+        Ok(RouteQueryResponse {
+            route: route.expect("Internal error: bad route"),
             expected_services: ExpectedServices::RoundTrip(
                 expected_request_services,
                 expected_response_services,
@@ -1076,6 +1250,51 @@ impl Neighborhood {
         })
     }
 
+    // A proof only depends on the Node it's for, and the external signer may be slow to make one
+    // (or wait for its owner to approve it), so each Node's proof is kept for the next Route.
+    fn prove_payment(
+        &mut self,
+        wallet: &Wallet,
+        segments: &[&RouteSegment],
+    ) -> Result<HashMap<PublicKey, Payer>, String> {
+        let signer = match &self.payer_proof_signer_opt {
+            Some(signer) => signer,
+            None => {
+                return Err(format!(
+                    "Cannot make route without a signer for consuming wallet {}",
+                    wallet
+                ))
+            }
+        };
+        let contract_address = self.chain.rec().contract;
+        let mut payers = HashMap::new();
+        for key in segments.iter().flat_map(|segment| segment.keys.iter()) {
+            if payers.contains_key(key) {
+                continue;
+            }
+            let payer = match self.payer_proofs.get(key) {
+                Some(payer) => payer.clone(),
+                None => {
+                    let digest = create_digest(key, &contract_address);
+                    let proof = signer
+                        .sign_payer_proof(wallet.address(), &digest)
+                        .map_err(|e| format!("Cannot prove payment to {}: {}", key, e))?;
+                    let payer = Payer::new(wallet, &proof);
+                    if !payer.owns_secret_key(&digest) {
+                        return Err(format!(
+                            "Cannot prove payment to {}: the external signer didn't sign for {}",
+                            key, wallet
+                        ));
+                    }
+                    self.payer_proofs.insert(key.clone(), payer.clone());
+                    payer
+                }
+            };
+            payers.insert(key.clone(), payer);
+        }
+        Ok(payers)
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn make_route_segment(
         &self,
@@ -1658,12 +1877,14 @@ mod tests {
     use masq_lib::utils::running_test;
 
     use crate::db_config::persistent_configuration::PersistentConfigError;
+    use crate::match_every_type_id;
     use crate::neighborhood::gossip::GossipBuilder;
     use crate::neighborhood::gossip::Gossip_0v1;
     use crate::neighborhood::node_record::NodeRecordInner_0v1;
     use crate::stream_messages::{NonClandestineAttributes, RemovedStreamType};
     use crate::sub_lib::cryptde::{decodex, encodex, CryptData};
     use crate::sub_lib::cryptde_null::CryptDENull;
+    use crate::sub_lib::crypto_suite::CryptoSuite;
     use crate::sub_lib::dispatcher::Endpoint;
     use crate::sub_lib::hop::LiveHop;
     use crate::sub_lib::hopper::MessageType;
@@ -1691,6 +1912,7 @@ mod tests {
     use crate::test_utils::recorder::peer_actors_builder;
     use crate::test_utils::recorder::Recorder;
     use crate::test_utils::recorder::Recording;
+    use crate::test_utils::recorder_stop_conditions::{StopCondition, StopConditions};
     use crate::test_utils::unshared_test_utils::{
         assert_on_initialization_with_panic_on_migration, make_cpm_recipient,
         make_node_to_ui_recipient, make_recipient_and_recording_arc,
@@ -1701,13 +1923,17 @@ mod tests {
 
     use super::*;
     use crate::accountant::test_utils::bc_from_earning_wallet;
+    use crate::blockchain::payer::personal_message_digest;
+    use crate::blockchain::test_utils::PayerProofSignerMock;
     use crate::neighborhood::overall_connection_status::ConnectionStageErrors::{
         NoGossipResponseReceived, PassLoopFound, TcpConnectionFailed,
     };
     use crate::neighborhood::overall_connection_status::{
         ConnectionProgress, ConnectionStage, OverallConnectionStage,
     };
+    use crate::neighborhood::pricing::{PricingConfig, FULL_LOAD_STREAM_COUNT};
     use crate::test_utils::unshared_test_utils::notify_handlers::NotifyLaterHandleMock;
+    use ethereum_types::H256;
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
 
     impl Handler<AssertionsMessage<Neighborhood>> for Neighborhood {
@@ -1859,6 +2085,28 @@ mod tests {
         assert_eq!(root_node_record_ref.half_neighbor_keys().len(), 0);
     }
 
+    #[test]
+    fn neighborhood_has_the_external_signer_prove_payment_for_its_consuming_wallet() {
+        let consuming_wallet =
+            Wallet::from_str("0x3f69f9efd4f2592fd70be8c32ecd9dce71c472fc").unwrap();
+        let mut config = bc_from_nc_plus(
+            NeighborhoodConfig {
+                mode: NeighborhoodMode::ZeroHop,
+                min_hops: MIN_HOPS_FOR_TEST,
+            },
+            make_wallet("earning"),
+            Some(consuming_wallet.clone()),
+            "neighborhood_has_the_external_signer_prove_payment_for_its_consuming_wallet",
+        );
+        config.blockchain_bridge_config.external_signer_url_opt =
+            Some("http://127.0.0.1:8550".to_string());
+
+        let subject = Neighborhood::new(main_cryptde(), &config);
+
+        assert_eq!(subject.consuming_wallet_opt, Some(consuming_wallet));
+        assert!(subject.payer_proof_signer_opt.is_some());
+    }
+
     #[test]
     fn node_with_zero_hop_config_ignores_start_message() {
         init_test_logging();
@@ -2599,7 +2847,9 @@ mod tests {
     fn route_query_responds_with_none_when_asked_for_route_with_too_many_hops() {
         let system =
             System::new("route_query_responds_with_none_when_asked_for_route_with_too_many_hops");
-        let subject = make_standard_subject();
+        let mut subject = make_standard_subject();
+        let metrics = Arc::new(NodeMetrics::default());
+        subject.metrics = metrics.clone();
         let addr: Addr<Neighborhood> = subject.start();
         let sub: Recipient<RouteQueryMessage> = addr.recipient::<RouteQueryMessage>();
 
@@ -2609,6 +2859,9 @@ mod tests {
         system.run();
         let result = future.wait().unwrap();
         assert_eq!(result, None);
+        let rendered = metrics.render();
+        assert!(rendered.contains("\nmasq_node_route_query_duration_seconds_count 1\n"));
+        assert!(rendered.contains("\nmasq_node_route_query_failures_total 1\n"));
     }
 
     #[test]
@@ -2885,6 +3138,134 @@ mod tests {
         assert_eq!(expected_response, result);
     }
 
+    #[test]
+    fn route_query_has_the_external_signer_prove_payment_once_for_each_node() {
+        let cryptde = main_cryptde();
+        let mut subject = make_standard_subject();
+        subject.min_hops = Hops::TwoHops;
+        let held_wallet = make_paying_wallet(b"held by an external signer");
+        let consuming_wallet = held_wallet.as_address_wallet();
+        subject.consuming_wallet_opt = Some(consuming_wallet.clone());
+        let p = &subject.neighborhood_database.root().clone();
+        let q = &make_node_record(3456, true);
+        let r = &make_node_record(4567, false);
+        {
+            let db = &mut subject.neighborhood_database;
+            db.add_node(q.clone()).unwrap();
+            db.add_node(r.clone()).unwrap();
+            db.add_arbitrary_full_neighbor(p.public_key(), q.public_key());
+            db.add_arbitrary_full_neighbor(q.public_key(), r.public_key());
+        }
+        let contract_address = TEST_DEFAULT_CHAIN.rec().contract;
+        let digest = |node: &NodeRecord| create_digest(node.public_key(), &contract_address);
+        // The way Clef signs text/plain data
+        let prove = |node: &NodeRecord| {
+            held_wallet
+                .sign(&personal_message_digest(&digest(node)))
+                .unwrap()
+        };
+        let sign_payer_proof_params_arc = Arc::new(Mutex::new(vec![]));
+        subject.payer_proof_signer_opt = Some(Box::new(
+            PayerProofSignerMock::default()
+                .sign_payer_proof_params(&sign_payer_proof_params_arc)
+                .sign_payer_proof_result(Ok(prove(p)))
+                .sign_payer_proof_result(Ok(prove(q)))
+                .sign_payer_proof_result(Ok(prove(r))),
+        ));
+
+        let first_result = subject
+            .handle_route_query_message(RouteQueryMessage::data_indefinite_route_request(
+                None, 5000,
+            ))
+            .unwrap();
+        let second_result = subject
+            .handle_route_query_message(RouteQueryMessage::data_indefinite_route_request(
+                None, 5000,
+            ))
+            .unwrap();
+
+        let payers = vec![p, q, r]
+            .into_iter()
+            .map(|node| {
+                (
+                    node.public_key().clone(),
+                    Payer::new(&consuming_wallet, &prove(node)),
+                )
+            })
+            .collect::<HashMap<PublicKey, Payer>>();
+        let expected_route = |return_route_id: u32| {
+            Route::round_trip_with_payers(
+                segment(&[p, q, r], &Component::ProxyClient),
+                segment(&[r, q, p], &Component::ProxyServer),
+                cryptde,
+                &payers,
+                return_route_id,
+            )
+            .unwrap()
+        };
+        assert_eq!(first_result.route, expected_route(0));
+        assert_eq!(second_result.route, expected_route(1));
+        let sign_payer_proof_params = sign_payer_proof_params_arc.lock().unwrap();
+        assert_eq!(
+            *sign_payer_proof_params,
+            vec![
+                (consuming_wallet.address(), H256::from(digest(p))),
+                (consuming_wallet.address(), H256::from(digest(q))),
+                (consuming_wallet.address(), H256::from(digest(r))),
+            ]
+        );
+    }
+
+    #[test]
+    fn prove_payment_reports_a_failing_external_signer() {
+        let mut subject = make_standard_subject();
+        let consuming_wallet = make_wallet("consuming");
+        let key = PublicKey::new(&[1, 2, 3, 4]);
+        subject.payer_proof_signer_opt = Some(Box::new(
+            PayerProofSignerMock::default()
+                .sign_payer_proof_result(Err("External signer failed: Unreachable".to_string())),
+        ));
+
+        let result = subject.prove_payment(
+            &consuming_wallet,
+            &[&RouteSegment::new(vec![&key], Component::ProxyClient)],
+        );
+
+        assert_eq!(
+            result,
+            Err("Cannot prove payment to AQIDBA: External signer failed: Unreachable".to_string())
+        );
+        assert!(subject.payer_proofs.is_empty());
+    }
+
+    #[test]
+    fn prove_payment_refuses_a_proof_signed_for_another_wallet() {
+        let mut subject = make_standard_subject();
+        let consuming_wallet = make_paying_wallet(b"consuming").as_address_wallet();
+        let key = PublicKey::new(&[1, 2, 3, 4]);
+        let digest = create_digest(&key, &TEST_DEFAULT_CHAIN.rec().contract);
+        let proof = make_paying_wallet(b"someone else")
+            .sign(&personal_message_digest(&digest))
+            .unwrap();
+        subject.payer_proof_signer_opt = Some(Box::new(
+            PayerProofSignerMock::default().sign_payer_proof_result(Ok(proof)),
+        ));
+
+        let result = subject.prove_payment(
+            &consuming_wallet,
+            &[&RouteSegment::new(vec![&key], Component::ProxyClient)],
+        );
+
+        assert_eq!(
+            result,
+            Err(format!(
+                "Cannot prove payment to AQIDBA: the external signer didn't sign for {}",
+                consuming_wallet
+            ))
+        );
+        assert!(subject.payer_proofs.is_empty());
+    }
+
     #[test]
     fn compose_route_query_response_returns_an_error_when_route_segment_is_empty() {
         let mut subject = make_standard_subject();
@@ -3009,6 +3390,118 @@ mod tests {
         assertions(&subject);
     }
 
+    #[test]
+    fn only_a_standard_neighborhood_gets_a_rate_pack_pricer() {
+        let standard_subject = make_standard_subject();
+        let zero_hop_subject =
+            neighborhood_from_nodes(&make_global_cryptde_node_record(9999, false), None);
+
+        assert_eq!(standard_subject.rate_pack_pricer_opt.is_some(), true);
+        assert_eq!(zero_hop_subject.rate_pack_pricer_opt.is_some(), false);
+    }
+
+    #[test]
+    fn reprice_message_publishes_and_gossips_a_new_rate_pack() {
+        init_test_logging();
+        let test_name = "reprice_message_publishes_and_gossips_a_new_rate_pack";
+        let subject_node = make_global_cryptde_node_record(1234, true);
+        let neighbor = make_node_record(1050, true);
+        let mut subject = neighborhood_from_nodes(&subject_node, Some(&neighbor));
+        subject.logger = Logger::new(test_name);
+        subject
+            .neighborhood_database
+            .add_node(neighbor.clone())
+            .unwrap();
+        subject
+            .neighborhood_database
+            .add_arbitrary_full_neighbor(subject_node.public_key(), neighbor.public_key());
+        let old_version = subject.neighborhood_database.root().version();
+        let base = *subject_node.rate_pack();
+        subject.rate_pack_pricer_opt = Some(RatePackPricer::new(
+            base,
+            PricingConfig::try_from("load|50|200").unwrap(),
+        ));
+        subject.gossip_producer =
+            Box::new(GossipProducerMock::new().produce_result(Some(Gossip_0v1::new(vec![]))));
+        let notify_later_reprice_params_arc = Arc::new(Mutex::new(vec![]));
+        subject.tools.notify_later_reprice = Box::new(
+            NotifyLaterHandleMock::default().notify_later_params(&notify_later_reprice_params_arc),
+        );
+        subject.tools.reprice_interval = Duration::from_millis(10);
+        let (hopper, _, hopper_recording_arc) = make_recorder();
+        let hopper = hopper
+            .system_stop_conditions(match_every_type_id!(ConfigChangeMsg, IncipientCoresPackage));
+        let (proxy_client, _, proxy_client_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder()
+            .hopper(hopper)
+            .proxy_client(proxy_client)
+            .build();
+        let system = System::new(test_name);
+        let addr = subject.start();
+        addr.try_send(BindMessage { peer_actors }).unwrap();
+        addr.try_send(ServedStreamCountMessage {
+            stream_count: FULL_LOAD_STREAM_COUNT,
+        })
+        .unwrap();
+        let expected_rate_pack = RatePack {
+            routing_byte_rate: base.routing_byte_rate * 2,
+            routing_service_rate: base.routing_service_rate * 2,
+            exit_byte_rate: base.exit_byte_rate * 2,
+            exit_service_rate: base.exit_service_rate * 2,
+        };
+
+        addr.try_send(RepriceMessage {}).unwrap();
+
+        let assertions = Box::new(move |actor: &mut Neighborhood| {
+            let root = actor.neighborhood_database.root();
+            assert_eq!(*root.rate_pack(), expected_rate_pack);
+            assert_eq!(root.version(), old_version + 1);
+        });
+        addr.try_send(AssertionsMessage { assertions }).unwrap();
+        assert_eq!(system.run(), 0);
+        let expected_msg = ConfigChangeMsg {
+            change: ConfigChange::UpdateRatePack(expected_rate_pack),
+        };
+        let hopper_recording = hopper_recording_arc.lock().unwrap();
+        assert_eq!(
+            hopper_recording.get_record::<ConfigChangeMsg>(0),
+            &expected_msg
+        );
+        let _ = hopper_recording.get_record::<IncipientCoresPackage>(1);
+        let proxy_client_recording = proxy_client_recording_arc.lock().unwrap();
+        assert_eq!(
+            proxy_client_recording.get_record::<ConfigChangeMsg>(0),
+            &expected_msg
+        );
+        let notify_later_reprice_params = notify_later_reprice_params_arc.lock().unwrap();
+        assert_eq!(
+            *notify_later_reprice_params,
+            vec![(RepriceMessage {}, Duration::from_millis(10))]
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {}: Repriced rate pack from {} to {}",
+            test_name, base, expected_rate_pack
+        ));
+    }
+
+    #[test]
+    fn reprice_leaves_an_unmoved_rate_pack_alone() {
+        init_test_logging();
+        let test_name = "reprice_leaves_an_unmoved_rate_pack_alone";
+        let mut subject = make_standard_subject();
+        subject.logger = Logger::new(test_name);
+        let old_root = subject.neighborhood_database.root().clone();
+
+        subject.handle_reprice();
+
+        assert_eq!(subject.neighborhood_database.root(), &old_root);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "DEBUG: {}: Rate pack {} needs no repricing",
+            test_name,
+            old_root.rate_pack()
+        ));
+    }
+
     #[test]
     fn can_calculate_db_patch_size_from_min_hops() {
         assert_eq!(Neighborhood::calculate_db_patch_size(Hops::OneHop), 3);
@@ -3050,6 +3543,8 @@ mod tests {
         subject.min_hops = Hops::TwoHops;
         subject.logger = Logger::new(test_name);
         subject.overall_connection_status.stage = OverallConnectionStage::RouteFound;
+        let metrics = Arc::new(NodeMetrics::default());
+        subject.metrics = metrics.clone();
         let subject_addr = subject.start();
         let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
         subject_addr.try_send(BindMessage { peer_actors }).unwrap();
@@ -3095,6 +3590,9 @@ mod tests {
             ),
             &format!("DEBUG: {test_name}: Searching for a 4-hop route..."),
         ]);
+        assert!(metrics
+            .render()
+            .contains("\nmasq_node_connection_stage{stage=\"connected_to_neighbor\"} 1\n"));
     }
 
     #[test]
@@ -4330,6 +4828,49 @@ mod tests {
             .exists_log_containing("INFO: Neighborhood: Changed public IP from 1.2.3.4 to 4.3.2.1");
     }
 
+    #[test]
+    fn handle_new_clandestine_ports_changes_ports_and_gossips_to_neighbors() {
+        init_test_logging();
+        let subject_node = make_global_cryptde_node_record(1234, true);
+        let neighbor = make_node_record(1050, true);
+        let mut subject: Neighborhood = neighborhood_from_nodes(&subject_node, Some(&neighbor));
+        subject
+            .neighborhood_database
+            .add_node(neighbor.clone())
+            .unwrap();
+        subject
+            .neighborhood_database
+            .add_arbitrary_full_neighbor(subject_node.public_key(), neighbor.public_key());
+        let old_version = subject.neighborhood_database.root().version();
+        let gossip = Gossip_0v1::new(vec![]);
+        let produce_params_arc = Arc::new(Mutex::new(vec![]));
+        let gossip_producer = GossipProducerMock::new()
+            .produce_params(&produce_params_arc)
+            .produce_result(Some(gossip));
+        subject.gossip_producer = Box::new(gossip_producer);
+        let (hopper, _, hopper_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder().hopper(hopper).build();
+        let system = System::new("");
+        subject.hopper_opt = Some(peer_actors.hopper.from_hopper_client);
+
+        subject.handle_new_clandestine_ports(NewClandestinePorts {
+            ports: vec![1234, 5678],
+        });
+
+        System::current().stop();
+        system.run();
+        let root = subject.neighborhood_database.root();
+        assert_eq!(root.node_addr_opt().unwrap().ports(), vec![1234, 5678]);
+        assert_eq!(root.version(), old_version + 1);
+        let produce_params = produce_params_arc.lock().unwrap();
+        assert_eq!(produce_params[0].1, neighbor.public_key().clone());
+        let hopper_recording = hopper_recording_arc.lock().unwrap();
+        assert_eq!(hopper_recording.len(), 1);
+        TestLogHandler::new().exists_log_containing(
+            "INFO: Neighborhood: Changed clandestine ports from [1234] to [1234, 5678]",
+        );
+    }
+
     #[test]
     fn neighborhood_sends_from_gossip_producer_when_acceptance_introductions_are_not_provided() {
         init_test_logging();
@@ -4624,6 +5165,31 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn neighborhood_does_not_accept_gossip_if_a_record_crypto_suite_does_not_fit_its_key() {
+        init_test_logging();
+        let mut subject = make_standard_subject();
+        let db = &mut subject.neighborhood_database;
+        let one_node_key = &db.add_node(make_node_record(2222, true)).unwrap();
+        let mut another_node = make_node_record(3333, true);
+        another_node.inner.crypto_suite = CryptoSuite::X25519MlKem768; // but its key is four bytes
+        another_node.resign();
+        let another_node_key = &db.add_node(another_node).unwrap();
+        let gossip = GossipBuilder::new(db)
+            .node(one_node_key, true)
+            .node(another_node_key, false)
+            .build();
+        let gossip_source = SocketAddr::from_str("1.2.3.4:1234").unwrap();
+
+        subject.handle_gossip(gossip, gossip_source, make_cpm_recipient().0);
+
+        // No panic means that subject didn't try to invoke the GossipAcceptorMock: test passes!
+        TestLogHandler::new().exists_log_containing(&format!(
+            "ERROR: Neighborhood: Received Gossip advertising a crypto suite that doesn't fit its public key from {}",
+            gossip_source
+        ));
+    }
+
     #[test]
     fn neighborhood_logs_received_gossip_in_dot_graph_format() {
         init_test_logging();
@@ -266,6 +266,12 @@ impl NeighborhoodDatabase {
         self.by_ip_addr.insert(public_ip, public_key);
     }
 
+    pub fn new_clandestine_ports(&mut self, ports: &[u16]) {
+        let record = self.root_mut();
+        let old_node_addr = record.metadata.node_addr_opt.clone().expectv("Root node");
+        record.metadata.node_addr_opt = Some(NodeAddr::new(&old_node_addr.ip_addr(), ports));
+    }
+
     fn to_dot_renderables(&self) -> Vec<Box<dyn DotRenderable>> {
         let mut mentioned: HashSet<PublicKey> = HashSet::new();
         let mut present: HashSet<PublicKey> = HashSet::new();
@@ -805,6 +811,30 @@ mod tests {
         assert_eq!(new_node, old_node); // now they should be identical
     }
 
+    #[test]
+    fn new_clandestine_ports_replaces_ports_and_nothing_else() {
+        let this_node = make_node_record(1234, true);
+        let old_node = this_node.clone();
+        let mut subject = NeighborhoodDatabase::new(
+            this_node.public_key(),
+            (&this_node).into(),
+            this_node.earning_wallet(),
+            &CryptDENull::from(this_node.public_key(), DEFAULT_CHAIN),
+        );
+
+        subject.new_clandestine_ports(&[1234, 5678]);
+
+        let mut new_node = subject.root().clone();
+        let old_ip_addr = old_node.node_addr_opt().unwrap().ip_addr();
+        assert_eq!(subject.node_by_ip(&old_ip_addr), Some(&new_node));
+        assert_eq!(
+            new_node.node_addr_opt(),
+            Some(NodeAddr::new(&old_ip_addr, &[1234, 5678]))
+        );
+        new_node.metadata.node_addr_opt = old_node.metadata.node_addr_opt.clone(); // undo the only change
+        assert_eq!(new_node, old_node); // now they should be identical
+    }
+
     #[test]
     fn remove_neighbor_returns_error_when_given_nonexistent_node_key() {
         let this_node = make_node_record(123, true);
@@ -4,6 +4,7 @@ use crate::neighborhood::gossip::GossipNodeRecord;
 use crate::neighborhood::neighborhood_database::{NeighborhoodDatabase, NeighborhoodDatabaseError};
 use crate::neighborhood::{regenerate_signed_gossip, AccessibleGossipRecord};
 use crate::sub_lib::cryptde::{CryptDE, CryptData, PlainData, PublicKey};
+use crate::sub_lib::crypto_suite::CryptoSuite;
 use crate::sub_lib::neighborhood::{NodeDescriptor, RatePack};
 use crate::sub_lib::node_addr::NodeAddr;
 use crate::sub_lib::utils::time_t_timestamp;
@@ -24,6 +25,10 @@ pub struct NodeRecordInner_0v1 {
     pub accepts_connections: bool,
     pub routes_data: bool,
     pub version: u32,
+    // Records from Nodes that predate crypto suites don't mention one; those Nodes are all classic,
+    // and classic records keep leaving it out so that they look the same to those Nodes
+    #[serde(default, skip_serializing_if = "CryptoSuite::is_default")]
+    pub crypto_suite: CryptoSuite,
 }
 
 impl TryFrom<GossipNodeRecord> for NodeRecordInner_0v1 {
@@ -78,6 +83,7 @@ impl NodeRecord {
                 routes_data,
                 neighbors: BTreeSet::new(),
                 version,
+                crypto_suite: cryptde.crypto_suite(),
             },
             signed_gossip: PlainData::new(&[]),
             signature: CryptData::new(&[]),
@@ -90,6 +96,10 @@ impl NodeRecord {
         &self.inner.public_key
     }
 
+    pub fn crypto_suite(&self) -> CryptoSuite {
+        self.inner.crypto_suite
+    }
+
     pub fn node_addr_opt(&self) -> Option<NodeAddr> {
         self.metadata.node_addr_opt.clone()
     }
@@ -244,6 +254,10 @@ impl NodeRecord {
         &self.inner.rate_pack
     }
 
+    pub fn set_rate_pack(&mut self, rate_pack: RatePack) {
+        self.inner.rate_pack = rate_pack;
+    }
+
     pub fn update(&mut self, agr: AccessibleGossipRecord) -> Result<(), String> {
         if &agr.inner.public_key != self.public_key() {
             return Err(format!(
@@ -263,13 +277,14 @@ impl NodeRecord {
             (None, None) => (),
             (None, Some(na)) => self.metadata.node_addr_opt = Some(na.clone()),
             (Some(_), None) => (),
-            (Some(existing), Some(incoming)) if existing != incoming => {
+            (Some(existing), Some(incoming)) if existing.ip_addr() != incoming.ip_addr() => {
                 return Err(format!(
                     "Updating a NodeRecord must not change its node_addr_opt: {} -> {}",
                     existing, incoming
                 ))
             }
-            _ => (),
+            // Nodes that hop their clandestine ports advertise the new ones at the same IP
+            (Some(_), Some(incoming)) => self.metadata.node_addr_opt = Some(incoming.clone()),
         }
         self.metadata.last_update = time_t_timestamp();
         self.signed_gossip = agr.signed_gossip;
@@ -797,6 +812,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn set_rate_pack_sets_the_rate_pack() {
+        let mut subject = make_node_record(1234, true);
+
+        subject.set_rate_pack(rate_pack(4321));
+
+        assert_eq!(subject.rate_pack(), &rate_pack(4321));
+    }
+
     #[test]
     fn update_works_when_immutable_characteristics_dont_change() {
         let mut subject = make_node_record(1234, true);
@@ -854,6 +878,24 @@ mod tests {
         assert_eq!(subject.node_addr_opt(), existing_node_addr_opt);
     }
 
+    #[test]
+    fn update_adopts_new_ports_at_the_same_ip_address() {
+        let mut subject = make_node_record(1234, true);
+        let new_node_addr_opt = Some(NodeAddr::new(
+            &IpAddr::from_str("1.2.3.4").unwrap(),
+            &[1234, 5678],
+        ));
+        let mut modified = subject.clone();
+        modified.metadata.node_addr_opt = new_node_addr_opt.clone();
+        modified.resign();
+        let agr = AccessibleGossipRecord::from(&modified);
+
+        let result = subject.update(agr);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(subject.node_addr_opt(), new_node_addr_opt);
+    }
+
     #[test]
     fn update_adopts_new_node_addr_when_current_version_has_none() {
         let mut subject = make_node_record(1234, false);
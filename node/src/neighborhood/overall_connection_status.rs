@@ -327,6 +327,7 @@ mod tests {
         PassLoopFound, TcpConnectionFailed,
     };
     use crate::neighborhood::PublicKey;
+    use crate::sub_lib::crypto_suite::CryptoSuite;
     use crate::test_utils::neighborhood_test_utils::{make_ip, make_node, make_node_descriptor};
     use crate::test_utils::unshared_test_utils::make_node_to_ui_recipient;
     use actix::System;
@@ -337,13 +338,14 @@ mod tests {
 
     #[test]
     #[should_panic(
-        expected = "Unable to receive node addr for the descriptor NodeDescriptor { blockchain: EthRopsten, encryption_public_key: 0x000000, node_addr_opt: None }"
+        expected = "Unable to receive node addr for the descriptor NodeDescriptor { blockchain: EthRopsten, encryption_public_key: 0x000000, node_addr_opt: None, crypto_suite: Curve25519 }"
     )]
     fn can_not_create_a_new_connection_without_node_addr() {
         let descriptor_with_no_ip_address = NodeDescriptor {
             blockchain: Chain::EthRopsten,
             encryption_public_key: PublicKey::from(vec![0, 0, 0]),
             node_addr_opt: None,
+            crypto_suite: CryptoSuite::Curve25519,
         };
         let _connection_progress = ConnectionProgress::new(descriptor_with_no_ip_address);
     }
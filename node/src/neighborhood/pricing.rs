@@ -0,0 +1,455 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::neighborhood::neighborhood_database::NeighborhoodDatabase;
+use crate::sub_lib::neighborhood::RatePack;
+use std::convert::TryFrom;
+
+pub const DEFAULT_LOWER_BOUND_PERCENT: u64 = 50;
+pub const DEFAULT_UPPER_BOUND_PERCENT: u64 = 200;
+// A load-priced Node charges its upper bound from this many served streams on
+pub const FULL_LOAD_STREAM_COUNT: usize = 100;
+// Smaller moves aren't worth a new round of Gossip
+pub const REPRICE_THRESHOLD_PERCENT: u64 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PricingStrategyKind {
+    Fixed,
+    LoadBased,
+    MarketRelative,
+}
+
+// The bounds are percentages of the rate pack the operator configured
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PricingConfig {
+    pub kind: PricingStrategyKind,
+    pub lower_bound_percent: u64,
+    pub upper_bound_percent: u64,
+}
+
+impl Default for PricingConfig {
+    fn default() -> Self {
+        PricingConfig {
+            kind: PricingStrategyKind::Fixed,
+            lower_bound_percent: DEFAULT_LOWER_BOUND_PERCENT,
+            upper_bound_percent: DEFAULT_UPPER_BOUND_PERCENT,
+        }
+    }
+}
+
+impl TryFrom<&str> for PricingConfig {
+    type Error = String;
+
+    fn try_from(parameters: &str) -> Result<Self, String> {
+        let mut pieces = parameters.split('|');
+        let kind = match pieces.next().map(str::trim) {
+            Some("fixed") => PricingStrategyKind::Fixed,
+            Some("load") => PricingStrategyKind::LoadBased,
+            Some("market") => PricingStrategyKind::MarketRelative,
+            _ => {
+                return Err(format!(
+                    "Pricing strategy must be fixed, load or market, not '{}'",
+                    parameters
+                ))
+            }
+        };
+        let bounds = pieces
+            .map(|piece| piece.trim().parse::<u64>())
+            .collect::<Result<Vec<u64>, _>>()
+            .map_err(|e| format!("Bounds of pricing strategy '{}': {}", parameters, e))?;
+        let (lower_bound_percent, upper_bound_percent) = match bounds.as_slice() {
+            [] => (DEFAULT_LOWER_BOUND_PERCENT, DEFAULT_UPPER_BOUND_PERCENT),
+            [lower, upper] => (*lower, *upper),
+            _ => {
+                return Err(format!(
+                    "Pricing strategy '{}' must have both bounds or neither",
+                    parameters
+                ))
+            }
+        };
+        if lower_bound_percent > 100 || upper_bound_percent < 100 {
+            return Err(format!(
+                "Bounds of pricing strategy '{}' must enclose 100 percent of the rate pack",
+                parameters
+            ));
+        }
+        Ok(PricingConfig {
+            kind,
+            lower_bound_percent,
+            upper_bound_percent,
+        })
+    }
+}
+
+pub struct PricingInputs<'a> {
+    pub served_stream_count: usize,
+    pub neighborhood_database: &'a NeighborhoodDatabase,
+}
+
+pub trait PricingStrategy {
+    fn price(
+        &self,
+        floor: &RatePack,
+        base: &RatePack,
+        ceiling: &RatePack,
+        inputs: &PricingInputs,
+    ) -> RatePack;
+}
+
+pub struct FixedPricing {}
+
+impl PricingStrategy for FixedPricing {
+    fn price(&self, _: &RatePack, base: &RatePack, _: &RatePack, _: &PricingInputs) -> RatePack {
+        *base
+    }
+}
+
+// Cheap while idle to draw traffic in, dear while busy to hold it off
+pub struct LoadBasedPricing {}
+
+impl PricingStrategy for LoadBasedPricing {
+    fn price(
+        &self,
+        floor: &RatePack,
+        _base: &RatePack,
+        ceiling: &RatePack,
+        inputs: &PricingInputs,
+    ) -> RatePack {
+        let load = inputs.served_stream_count.min(FULL_LOAD_STREAM_COUNT) as u128;
+        let interpolate = |low: u64, high: u64| {
+            let span = high.saturating_sub(low) as u128;
+            low + (span * load / FULL_LOAD_STREAM_COUNT as u128) as u64
+        };
+        RatePack {
+            routing_byte_rate: interpolate(floor.routing_byte_rate, ceiling.routing_byte_rate),
+            routing_service_rate: interpolate(
+                floor.routing_service_rate,
+                ceiling.routing_service_rate,
+            ),
+            exit_byte_rate: interpolate(floor.exit_byte_rate, ceiling.exit_byte_rate),
+            exit_service_rate: interpolate(floor.exit_service_rate, ceiling.exit_service_rate),
+        }
+    }
+}
+
+// Follows the median of the prices the other Nodes we know about are asking
+pub struct MarketRelativePricing {}
+
+impl PricingStrategy for MarketRelativePricing {
+    fn price(
+        &self,
+        _floor: &RatePack,
+        base: &RatePack,
+        _ceiling: &RatePack,
+        inputs: &PricingInputs,
+    ) -> RatePack {
+        let database = inputs.neighborhood_database;
+        let root_key = database.root().public_key();
+        let rate_packs = database
+            .keys()
+            .into_iter()
+            .filter(|key| *key != root_key)
+            .filter_map(|key| database.node_by_key(key))
+            .map(|node| *node.rate_pack())
+            .collect::<Vec<RatePack>>();
+        if rate_packs.is_empty() {
+            return *base;
+        }
+        let median_of = |field: fn(&RatePack) -> u64| {
+            let mut values = rate_packs.iter().map(field).collect::<Vec<u64>>();
+            values.sort_unstable();
+            let middle = values.len() / 2;
+            if values.len() % 2 == 0 {
+                ((values[middle - 1] as u128 + values[middle] as u128) / 2) as u64
+            } else {
+                values[middle]
+            }
+        };
+        RatePack {
+            routing_byte_rate: median_of(|rate_pack| rate_pack.routing_byte_rate),
+            routing_service_rate: median_of(|rate_pack| rate_pack.routing_service_rate),
+            exit_byte_rate: median_of(|rate_pack| rate_pack.exit_byte_rate),
+            exit_service_rate: median_of(|rate_pack| rate_pack.exit_service_rate),
+        }
+    }
+}
+
+pub struct RatePackPricer {
+    floor: RatePack,
+    base: RatePack,
+    ceiling: RatePack,
+    strategy: Box<dyn PricingStrategy>,
+}
+
+impl RatePackPricer {
+    pub fn new(base: RatePack, config: PricingConfig) -> Self {
+        let strategy: Box<dyn PricingStrategy> = match config.kind {
+            PricingStrategyKind::Fixed => Box::new(FixedPricing {}),
+            PricingStrategyKind::LoadBased => Box::new(LoadBasedPricing {}),
+            PricingStrategyKind::MarketRelative => Box::new(MarketRelativePricing {}),
+        };
+        RatePackPricer {
+            floor: scale(&base, config.lower_bound_percent),
+            base,
+            ceiling: scale(&base, config.upper_bound_percent),
+            strategy,
+        }
+    }
+
+    // None unless the new price has moved far enough from the current one to be worth gossiping
+    pub fn reprice(&self, current: &RatePack, inputs: &PricingInputs) -> Option<RatePack> {
+        let proposed = self
+            .strategy
+            .price(&self.floor, &self.base, &self.ceiling, inputs);
+        let bounded = RatePack {
+            routing_byte_rate: proposed
+                .routing_byte_rate
+                .clamp(self.floor.routing_byte_rate, self.ceiling.routing_byte_rate),
+            routing_service_rate: proposed.routing_service_rate.clamp(
+                self.floor.routing_service_rate,
+                self.ceiling.routing_service_rate,
+            ),
+            exit_byte_rate: proposed
+                .exit_byte_rate
+                .clamp(self.floor.exit_byte_rate, self.ceiling.exit_byte_rate),
+            exit_service_rate: proposed
+                .exit_service_rate
+                .clamp(self.floor.exit_service_rate, self.ceiling.exit_service_rate),
+        };
+        moved_significantly(current, &bounded).then(|| bounded)
+    }
+}
+
+fn scale(rate_pack: &RatePack, percent: u64) -> RatePack {
+    let scale_rate =
+        |rate: u64| u64::try_from(rate as u128 * percent as u128 / 100).unwrap_or(u64::MAX);
+    RatePack {
+        routing_byte_rate: scale_rate(rate_pack.routing_byte_rate),
+        routing_service_rate: scale_rate(rate_pack.routing_service_rate),
+        exit_byte_rate: scale_rate(rate_pack.exit_byte_rate),
+        exit_service_rate: scale_rate(rate_pack.exit_service_rate),
+    }
+}
+
+fn moved_significantly(current: &RatePack, proposed: &RatePack) -> bool {
+    [
+        (current.routing_byte_rate, proposed.routing_byte_rate),
+        (current.routing_service_rate, proposed.routing_service_rate),
+        (current.exit_byte_rate, proposed.exit_byte_rate),
+        (current.exit_service_rate, proposed.exit_service_rate),
+    ]
+    .iter()
+    .any(|(current, proposed)| {
+        let difference = (*current as i128 - *proposed as i128).unsigned_abs();
+        difference * 100 >= *current as u128 * REPRICE_THRESHOLD_PERCENT as u128 && difference > 0
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::neighborhood_test_utils::{db_from_node, make_node_record};
+
+    fn flat_rate_pack(rate: u64) -> RatePack {
+        RatePack {
+            routing_byte_rate: rate,
+            routing_service_rate: rate,
+            exit_byte_rate: rate,
+            exit_service_rate: rate,
+        }
+    }
+
+    fn inputs(
+        served_stream_count: usize,
+        neighborhood_database: &NeighborhoodDatabase,
+    ) -> PricingInputs {
+        PricingInputs {
+            served_stream_count,
+            neighborhood_database,
+        }
+    }
+
+    fn database_with_neighbor_rates(rates: &[u64]) -> NeighborhoodDatabase {
+        let mut root = make_node_record(1000, true);
+        root.set_rate_pack(flat_rate_pack(1_000_000));
+        let mut database = db_from_node(&root);
+        rates.iter().enumerate().for_each(|(index, rate)| {
+            let mut node = make_node_record(2000 + index as u16, true);
+            node.set_rate_pack(flat_rate_pack(*rate));
+            database.add_node(node).unwrap();
+        });
+        database
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(DEFAULT_LOWER_BOUND_PERCENT, 50);
+        assert_eq!(DEFAULT_UPPER_BOUND_PERCENT, 200);
+        assert_eq!(FULL_LOAD_STREAM_COUNT, 100);
+        assert_eq!(REPRICE_THRESHOLD_PERCENT, 10);
+    }
+
+    #[test]
+    fn pricing_config_is_parsed_from_strategy_and_optional_bounds() {
+        let results = [
+            "fixed",
+            "load",
+            "market|80|150",
+            "load|100|100",
+            "auction",
+            "load|50",
+            "market|50|booga",
+            "load|110|200",
+            "market|50|90",
+        ]
+        .iter()
+        .map(|parameters| PricingConfig::try_from(*parameters))
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            results,
+            vec![
+                Ok(PricingConfig::default()),
+                Ok(PricingConfig {
+                    kind: PricingStrategyKind::LoadBased,
+                    lower_bound_percent: 50,
+                    upper_bound_percent: 200
+                }),
+                Ok(PricingConfig {
+                    kind: PricingStrategyKind::MarketRelative,
+                    lower_bound_percent: 80,
+                    upper_bound_percent: 150
+                }),
+                Ok(PricingConfig {
+                    kind: PricingStrategyKind::LoadBased,
+                    lower_bound_percent: 100,
+                    upper_bound_percent: 100
+                }),
+                Err("Pricing strategy must be fixed, load or market, not 'auction'".to_string()),
+                Err("Pricing strategy 'load|50' must have both bounds or neither".to_string()),
+                Err(
+                    "Bounds of pricing strategy 'market|50|booga': invalid digit found in string"
+                        .to_string()
+                ),
+                Err(
+                    "Bounds of pricing strategy 'load|110|200' must enclose 100 percent of the rate pack"
+                        .to_string()
+                ),
+                Err(
+                    "Bounds of pricing strategy 'market|50|90' must enclose 100 percent of the rate pack"
+                        .to_string()
+                ),
+            ]
+        )
+    }
+
+    #[test]
+    fn fixed_pricing_returns_to_the_configured_rate_pack() {
+        let database = database_with_neighbor_rates(&[]);
+        let subject = RatePackPricer::new(flat_rate_pack(1_000_000), PricingConfig::default());
+
+        let result = subject.reprice(&flat_rate_pack(1_500_000), &inputs(100, &database));
+
+        assert_eq!(result, Some(flat_rate_pack(1_000_000)));
+    }
+
+    #[test]
+    fn load_based_pricing_moves_from_lower_to_upper_bound_with_served_streams() {
+        let database = database_with_neighbor_rates(&[]);
+        let subject = RatePackPricer::new(
+            flat_rate_pack(1_000_000),
+            PricingConfig::try_from("load|50|200").unwrap(),
+        );
+        let current = flat_rate_pack(10_000_000);
+
+        let results = [0, 50, 100, 1_000]
+            .iter()
+            .map(|count| subject.reprice(&current, &inputs(*count, &database)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            results,
+            vec![
+                Some(flat_rate_pack(500_000)),
+                Some(flat_rate_pack(1_250_000)),
+                Some(flat_rate_pack(2_000_000)),
+                Some(flat_rate_pack(2_000_000)),
+            ]
+        )
+    }
+
+    #[test]
+    fn market_relative_pricing_follows_the_median_of_other_nodes() {
+        let odd_database = database_with_neighbor_rates(&[900_000, 1_400_000, 1_200_000]);
+        let even_database =
+            database_with_neighbor_rates(&[900_000, 1_300_000, 1_200_000, 1_700_000]);
+        let subject = RatePackPricer::new(
+            flat_rate_pack(1_000_000),
+            PricingConfig::try_from("market|50|200").unwrap(),
+        );
+        let current = flat_rate_pack(1_000_000);
+
+        let odd_result = subject.reprice(&current, &inputs(0, &odd_database));
+        let even_result = subject.reprice(&current, &inputs(0, &even_database));
+
+        assert_eq!(odd_result, Some(flat_rate_pack(1_200_000)));
+        assert_eq!(even_result, Some(flat_rate_pack(1_250_000)));
+    }
+
+    #[test]
+    fn market_relative_pricing_is_kept_within_bounds() {
+        let expensive_database = database_with_neighbor_rates(&[9_000_000, 8_000_000]);
+        let cheap_database = database_with_neighbor_rates(&[1, 2, 3]);
+        let subject = RatePackPricer::new(
+            flat_rate_pack(1_000_000),
+            PricingConfig::try_from("market|80|150").unwrap(),
+        );
+        let current = flat_rate_pack(1_000_000);
+
+        let expensive_result = subject.reprice(&current, &inputs(0, &expensive_database));
+        let cheap_result = subject.reprice(&current, &inputs(0, &cheap_database));
+
+        assert_eq!(expensive_result, Some(flat_rate_pack(1_500_000)));
+        assert_eq!(cheap_result, Some(flat_rate_pack(800_000)));
+    }
+
+    #[test]
+    fn market_relative_pricing_keeps_the_configured_rate_pack_without_other_nodes() {
+        let database = database_with_neighbor_rates(&[]);
+        let subject = RatePackPricer::new(
+            flat_rate_pack(1_000_000),
+            PricingConfig::try_from("market").unwrap(),
+        );
+
+        let result = subject.reprice(&flat_rate_pack(1_500_000), &inputs(0, &database));
+
+        assert_eq!(result, Some(flat_rate_pack(1_000_000)));
+    }
+
+    #[test]
+    fn small_price_moves_are_not_worth_repricing() {
+        let database = database_with_neighbor_rates(&[]);
+        let subject = RatePackPricer::new(
+            flat_rate_pack(1_000_000),
+            PricingConfig::try_from("load|50|200").unwrap(),
+        );
+        let mut current = flat_rate_pack(1_250_000);
+        current.exit_service_rate = 1_138_000;
+
+        let small_move = subject.reprice(&current, &inputs(50, &database));
+        current.exit_service_rate = 1_136_000;
+        let big_enough_move = subject.reprice(&current, &inputs(50, &database));
+
+        assert_eq!(small_move, None);
+        assert_eq!(big_enough_move, Some(flat_rate_pack(1_250_000)));
+    }
+
+    #[test]
+    fn unchanged_price_is_not_repriced() {
+        let database = database_with_neighbor_rates(&[]);
+        let subject = RatePackPricer::new(flat_rate_pack(0), PricingConfig::default());
+
+        let result = subject.reprice(&flat_rate_pack(0), &inputs(0, &database));
+
+        assert_eq!(result, None);
+    }
+}
@@ -26,6 +26,7 @@ use crate::db_config::config_dao::ConfigDaoReal;
 use crate::db_config::persistent_configuration::{
     PersistentConfigError, PersistentConfiguration, PersistentConfigurationReal,
 };
+use crate::sub_lib::blockchain_bridge::blockchain_service_urls;
 use crate::sub_lib::neighborhood::{ConfigChange, ConfigChangeMsg, Hops, WalletPair};
 use crate::sub_lib::peer_actors::{BindMessage, ConfigChangeSubs};
 use crate::sub_lib::utils::{db_connection_launch_panic, handle_ui_crash_request};
@@ -733,6 +734,8 @@ impl Configurator {
 
         match password {
             None => match msg.name.as_str() {
+                "blockchain-service-quorum" => self.set_blockchain_service_quorum(msg.value)?,
+                "blockchain-service-url" => self.set_blockchain_service_url(msg.value)?,
                 "gas-price" => self.set_gas_price(msg.value)?,
                 "min-hops" => self.set_min_hops(msg.value)?,
                 "start-block" => self.set_start_block(msg.value)?,
@@ -751,6 +754,102 @@ impl Configurator {
         Ok(UiSetConfigurationResponse {}.tmb(context_id))
     }
 
+    fn set_blockchain_service_quorum(
+        &mut self,
+        string_quorum: String,
+    ) -> Result<(), (u64, String)> {
+        let quorum = match string_quorum.parse::<u16>() {
+            Ok(0) => {
+                return Err((
+                    NON_PARSABLE_VALUE,
+                    "blockchain service quorum: must be at least 1".to_string(),
+                ))
+            }
+            Ok(num) => num,
+            Err(e) => {
+                return Err((
+                    NON_PARSABLE_VALUE,
+                    format!("blockchain service quorum: {:?}", e),
+                ))
+            }
+        };
+        let service_count = match self.persistent_config.blockchain_service_url() {
+            Ok(urls_opt) => urls_opt
+                .map(|urls| blockchain_service_urls(&urls).len())
+                .unwrap_or(0),
+            Err(e) => {
+                return Err((
+                    CONFIGURATOR_READ_ERROR,
+                    format!("blockchain service url: {:?}", e),
+                ))
+            }
+        };
+        Self::check_blockchain_service_quorum("blockchain service quorum", quorum, service_count)?;
+        match self.persistent_config.set_blockchain_service_quorum(quorum) {
+            Ok(_) => {
+                info!(
+                    self.logger,
+                    "The blockchain service quorum has been changed to {}; it will apply from the next start of the Node",
+                    quorum
+                );
+                Ok(())
+            }
+            Err(e) => Err((
+                CONFIGURATOR_WRITE_ERROR,
+                format!("blockchain service quorum: {:?}", e),
+            )),
+        }
+    }
+
+    fn set_blockchain_service_url(&mut self, urls: String) -> Result<(), (u64, String)> {
+        let quorum = match self.persistent_config.blockchain_service_quorum() {
+            Ok(quorum) => quorum,
+            Err(e) => {
+                return Err((
+                    CONFIGURATOR_READ_ERROR,
+                    format!("blockchain service quorum: {:?}", e),
+                ))
+            }
+        };
+        let service_count = blockchain_service_urls(&urls).len();
+        Self::check_blockchain_service_quorum("blockchain service url", quorum, service_count)?;
+        match self.persistent_config.set_blockchain_service_url(&urls) {
+            Ok(_) => {
+                info!(
+                    self.logger,
+                    "The blockchain service URL has been changed to {}; it will apply from the next start of the Node",
+                    urls
+                );
+                Ok(())
+            }
+            Err(PersistentConfigError::InvalidUrl(e)) => {
+                Err((NON_PARSABLE_VALUE, format!("blockchain service url: {}", e)))
+            }
+            Err(e) => Err((
+                CONFIGURATOR_WRITE_ERROR,
+                format!("blockchain service url: {:?}", e),
+            )),
+        }
+    }
+
+    fn check_blockchain_service_quorum(
+        parameter: &str,
+        quorum: u16,
+        service_count: usize,
+    ) -> Result<(), (u64, String)> {
+        if service_count > 0 && usize::from(quorum) > service_count {
+            Err((
+                NON_PARSABLE_VALUE,
+                format!(
+                    "{}: quorum {} is greater than the number of blockchain services, which is {}",
+                    parameter, quorum, service_count
+                ),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
     fn set_gas_price(&mut self, string_price: String) -> Result<(), (u64, String)> {
         let price_number = match string_price.parse::<u64>() {
             Ok(num) => num,
@@ -2125,6 +2224,265 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn handle_set_configuration_works_for_blockchain_service_url() {
+        init_test_logging();
+        let test_name = "handle_set_configuration_works_for_blockchain_service_url";
+        let set_blockchain_service_url_params_arc = Arc::new(Mutex::new(vec![]));
+        let persistent_config = PersistentConfigurationMock::new()
+            .blockchain_service_quorum_result(Ok(2))
+            .set_blockchain_service_url_params(&set_blockchain_service_url_params_arc)
+            .set_blockchain_service_url_result(Ok(()));
+        let mut subject = make_subject(Some(persistent_config));
+        subject.logger = Logger::new(test_name);
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "blockchain-service-url".to_string(),
+                value: "https://infura.io/ID,http://localhost:8545".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Ok(r#"{}"#.to_string())
+            }
+        );
+        let set_blockchain_service_url_params =
+            set_blockchain_service_url_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_blockchain_service_url_params,
+            vec!["https://infura.io/ID,http://localhost:8545".to_string()]
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {test_name}: The blockchain service URL has been changed to \
+            https://infura.io/ID,http://localhost:8545; it will apply from the next start of the Node"
+        ));
+    }
+
+    #[test]
+    fn handle_set_configuration_rejects_blockchain_service_url_with_too_few_services_for_quorum() {
+        let set_blockchain_service_url_params_arc = Arc::new(Mutex::new(vec![]));
+        let persistent_config = PersistentConfigurationMock::new()
+            .blockchain_service_quorum_result(Ok(2))
+            .set_blockchain_service_url_params(&set_blockchain_service_url_params_arc);
+        let mut subject = make_subject(Some(persistent_config));
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "blockchain-service-url".to_string(),
+                value: "https://infura.io/ID".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Err((
+                    NON_PARSABLE_VALUE,
+                    "blockchain service url: quorum 2 is greater than the number of \
+                    blockchain services, which is 1"
+                        .to_string()
+                ))
+            }
+        );
+        assert!(set_blockchain_service_url_params_arc
+            .lock()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn handle_set_configuration_rejects_invalid_blockchain_service_url() {
+        let persistent_config = PersistentConfigurationMock::new()
+            .blockchain_service_quorum_result(Ok(1))
+            .set_blockchain_service_url_result(Err(PersistentConfigError::InvalidUrl(
+                "relative URL without a base".to_string(),
+            )));
+        let mut subject = make_subject(Some(persistent_config));
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "blockchain-service-url".to_string(),
+                value: "infura.io".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Err((
+                    NON_PARSABLE_VALUE,
+                    "blockchain service url: relative URL without a base".to_string()
+                ))
+            }
+        );
+    }
+
+    #[test]
+    fn handle_set_configuration_handles_failure_on_blockchain_service_url_database_issue() {
+        let persistent_config = PersistentConfigurationMock::new()
+            .blockchain_service_quorum_result(Ok(1))
+            .set_blockchain_service_url_result(Err(PersistentConfigError::TransactionError));
+        let mut subject = make_subject(Some(persistent_config));
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "blockchain-service-url".to_string(),
+                value: "https://infura.io/ID".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Err((
+                    CONFIGURATOR_WRITE_ERROR,
+                    "blockchain service url: TransactionError".to_string()
+                ))
+            }
+        );
+    }
+
+    #[test]
+    fn handle_set_configuration_works_for_blockchain_service_quorum() {
+        init_test_logging();
+        let test_name = "handle_set_configuration_works_for_blockchain_service_quorum";
+        let set_blockchain_service_quorum_params_arc = Arc::new(Mutex::new(vec![]));
+        let persistent_config = PersistentConfigurationMock::new()
+            .blockchain_service_url_result(Ok(Some(
+                "https://infura.io/ID,https://alchemy.com/ID,http://localhost:8545".to_string(),
+            )))
+            .set_blockchain_service_quorum_params(&set_blockchain_service_quorum_params_arc)
+            .set_blockchain_service_quorum_result(Ok(()));
+        let mut subject = make_subject(Some(persistent_config));
+        subject.logger = Logger::new(test_name);
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "blockchain-service-quorum".to_string(),
+                value: "2".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Ok(r#"{}"#.to_string())
+            }
+        );
+        let set_blockchain_service_quorum_params =
+            set_blockchain_service_quorum_params_arc.lock().unwrap();
+        assert_eq!(*set_blockchain_service_quorum_params, vec![2]);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {test_name}: The blockchain service quorum has been changed to 2; \
+            it will apply from the next start of the Node"
+        ));
+    }
+
+    #[test]
+    fn handle_set_configuration_rejects_blockchain_service_quorum_above_number_of_services() {
+        let persistent_config = PersistentConfigurationMock::new()
+            .blockchain_service_url_result(Ok(Some("https://infura.io/ID".to_string())));
+        let mut subject = make_subject(Some(persistent_config));
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "blockchain-service-quorum".to_string(),
+                value: "2".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Err((
+                    NON_PARSABLE_VALUE,
+                    "blockchain service quorum: quorum 2 is greater than the number of \
+                    blockchain services, which is 1"
+                        .to_string()
+                ))
+            }
+        );
+    }
+
+    #[test]
+    fn handle_set_configuration_rejects_zero_or_non_parsable_blockchain_service_quorum() {
+        let mut subject = make_subject(None);
+
+        let zero_result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "blockchain-service-quorum".to_string(),
+                value: "0".to_string(),
+            },
+            4000,
+        );
+        let garbage_result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "blockchain-service-quorum".to_string(),
+                value: "booga".to_string(),
+            },
+            4001,
+        );
+
+        assert_eq!(
+            zero_result.payload,
+            Err((
+                NON_PARSABLE_VALUE,
+                "blockchain service quorum: must be at least 1".to_string()
+            ))
+        );
+        assert_eq!(
+            garbage_result.payload,
+            Err((
+                NON_PARSABLE_VALUE,
+                "blockchain service quorum: ParseIntError { kind: InvalidDigit }".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn handle_set_configuration_handles_failure_on_blockchain_service_quorum_database_issue() {
+        let persistent_config = PersistentConfigurationMock::new()
+            .blockchain_service_url_result(Err(PersistentConfigError::NotPresent));
+        let mut subject = make_subject(Some(persistent_config));
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "blockchain-service-quorum".to_string(),
+                value: "1".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result.payload,
+            Err((
+                CONFIGURATOR_READ_ERROR,
+                "blockchain service url: NotPresent".to_string()
+            ))
+        );
+    }
+
     #[test]
     fn handle_set_configuration_works_for_gas_price() {
         let set_gas_price_params_arc = Arc::new(Mutex::new(vec![]));
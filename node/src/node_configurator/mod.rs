@@ -15,13 +15,14 @@ use crate::sub_lib::utils::{db_connection_launch_panic, make_new_multi_config};
 use clap::{value_t, App};
 use core::option::Option;
 use dirs::{data_local_dir, home_dir};
+use masq_lib::blockchains::chain_registry::load_chain_registry;
 use masq_lib::blockchains::chains::Chain;
 use masq_lib::constants::DEFAULT_CHAIN;
 use masq_lib::multi_config::{
     merge, CommandLineVcl, EnvironmentVcl, MultiConfig, VirtualCommandLine,
 };
 use masq_lib::shared_schema::ConfiguratorError;
-use masq_lib::utils::{add_masq_and_chain_directories, localhost};
+use masq_lib::utils::{add_chain_specific_directory, localhost};
 use std::env::current_dir;
 use std::net::{SocketAddr, TcpListener};
 use std::path::{Path, PathBuf};
@@ -280,6 +281,14 @@ pub fn data_directory_from_context(
     dirs_wrapper: &dyn DirsWrapper,
     real_user: &RealUser,
     chain: Chain,
+) -> PathBuf {
+    add_chain_specific_directory(chain, &masq_directory_from_context(dirs_wrapper, real_user))
+}
+
+// The directory holding the data directories of all the chains, as well as the chain registry file
+pub fn masq_directory_from_context(
+    dirs_wrapper: &dyn DirsWrapper,
+    real_user: &RealUser,
 ) -> PathBuf {
     let right_home_dir = real_user
         .home_dir_opt
@@ -294,8 +303,22 @@ pub fn data_directory_from_context(
     let adjusted_local_data_dir: &Path = wrong_local_data_dir
         .strip_prefix(wrong_home_dir)
         .expect("std lib failed");
-    let homedir = right_home_dir.join(adjusted_local_data_dir);
-    add_masq_and_chain_directories(chain, &homedir)
+    right_home_dir.join(adjusted_local_data_dir).join("MASQ")
+}
+
+// Must happen before any command line is parsed, because the chain definitions decide which values
+// of --chain are valid.
+pub fn load_chain_registry_from_context(
+    dirs_wrapper: &dyn DirsWrapper,
+) -> Result<(), ConfiguratorError> {
+    if dirs_wrapper.home_dir().is_none() || dirs_wrapper.data_dir().is_none() {
+        return Ok(());
+    }
+    let real_user = RealUser::new(None, None, None).populate(dirs_wrapper);
+    let masq_directory = masq_directory_from_context(dirs_wrapper, &real_user);
+    load_chain_registry(&masq_directory)
+        .map(|_| ())
+        .map_err(|e| ConfiguratorError::required("chain", &e.to_string()))
 }
 
 pub fn port_is_busy(port: u16) -> bool {
@@ -376,6 +399,38 @@ mod tests {
         )
     }
 
+    #[test]
+    fn masq_directory_from_context_is_the_parent_of_data_directories() {
+        let dirs_wrapper = DirsWrapperMock::new()
+            .home_dir_result(Some(PathBuf::from("/nonexistent_home/root".to_string())))
+            .data_dir_result(Some(PathBuf::from("/nonexistent_home/root/.local/share")));
+        let real_user = RealUser::new(
+            None,
+            None,
+            Some(PathBuf::from(
+                "/nonexistent_home/nonexistent_alice".to_string(),
+            )),
+        );
+
+        let result = masq_directory_from_context(&dirs_wrapper, &real_user);
+
+        assert_eq!(
+            result,
+            PathBuf::from("/nonexistent_home/nonexistent_alice/.local/share/MASQ".to_string())
+        )
+    }
+
+    #[test]
+    fn load_chain_registry_from_context_does_without_home_directory() {
+        let dirs_wrapper = DirsWrapperMock::new()
+            .home_dir_result(None)
+            .data_dir_result(None);
+
+        let result = load_chain_registry_from_context(&dirs_wrapper);
+
+        assert_eq!(result, Ok(()))
+    }
+
     #[test]
     fn determine_config_file_path_finds_path_in_args() {
         let data_directory = ensure_node_home_directory_exists(
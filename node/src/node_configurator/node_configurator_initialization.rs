@@ -1,14 +1,19 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::apps::app_daemon;
+use crate::daemon::restart_supervisor::RestartPolicy;
 use crate::node_configurator::NodeConfigurator;
 use crate::sub_lib::utils::make_new_multi_config;
 use masq_lib::multi_config::{CommandLineVcl, MultiConfig};
 use masq_lib::shared_schema::ConfiguratorError;
+use std::path::PathBuf;
 
 #[derive(Default, Clone, PartialEq, Eq, Debug)]
 pub struct InitializationConfig {
     pub ui_port: u16,
+    pub ui_token_file_opt: Option<PathBuf>,
+    pub ui_authentication_off: bool,
+    pub restart_policy_opt: Option<RestartPolicy>,
 }
 
 pub struct NodeConfiguratorInitializationReal;
@@ -43,6 +48,14 @@ mod initialization {
 
     pub fn parse_args(multi_config: &MultiConfig, config: &mut InitializationConfig) {
         config.ui_port = value_m!(multi_config, "ui-port", u16).unwrap_or(DEFAULT_UI_PORT);
+        config.ui_token_file_opt = value_m!(multi_config, "ui-token-file", PathBuf);
+        config.ui_authentication_off = value_m!(multi_config, "ui-authentication", String)
+            .map(|value| value.eq_ignore_ascii_case("off"))
+            .unwrap_or(false);
+        config.restart_policy_opt =
+            value_m!(multi_config, "restart-policy", String).map(|policy| {
+                RestartPolicy::try_from(policy.as_str()).expect("restart-policy validator failed")
+            });
     }
 }
 
@@ -64,13 +77,19 @@ mod tests {
         initialization::parse_args(&multi_config, &mut config);
 
         assert_eq!(config.ui_port, DEFAULT_UI_PORT);
+        assert_eq!(config.ui_token_file_opt, None);
+        assert!(!config.ui_authentication_off);
+        assert_eq!(config.restart_policy_opt, None);
     }
 
     #[test]
     fn parse_args_creates_configuration_with_values() {
         let args = ArgsBuilder::new()
             .opt("--initialization")
-            .param("--ui-port", "4321");
+            .param("--ui-port", "4321")
+            .param("--ui-token-file", "/home/booga/ui_tokens")
+            .param("--ui-authentication", "Off")
+            .param("--restart-policy", "5|10|600|3600");
         let mut config = InitializationConfig::default();
         let vcls: Vec<Box<dyn VirtualCommandLine>> =
             vec![Box::new(CommandLineVcl::new(args.into()))];
@@ -79,5 +98,19 @@ mod tests {
         initialization::parse_args(&multi_config, &mut config);
 
         assert_eq!(config.ui_port, 4321);
+        assert_eq!(
+            config.ui_token_file_opt,
+            Some(PathBuf::from("/home/booga/ui_tokens"))
+        );
+        assert!(config.ui_authentication_off);
+        assert_eq!(
+            config.restart_policy_opt,
+            Some(RestartPolicy {
+                max_restarts: 5,
+                initial_backoff_s: 10,
+                max_backoff_s: 600,
+                crash_loop_window_s: 3600,
+            })
+        );
     }
 }
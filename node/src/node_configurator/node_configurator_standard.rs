@@ -1,8 +1,9 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use crate::bootstrapper::BootstrapperConfig;
+use crate::bootstrapper::{BootstrapperConfig, ClandestineWebSocketMode};
 use crate::node_configurator::{initialize_database, DirsWrapper, FieldPair, NodeConfigurator};
 use crate::node_configurator::{ConfigInitializationData, DirsWrapperReal};
+use crate::server_initializer::{LogFileConfig, LogFormat, LogRotationAge};
 use masq_lib::crash_point::CrashPoint;
 use masq_lib::logger::Logger;
 use masq_lib::multi_config::{MultiConfig, VirtualCommandLine};
@@ -10,6 +11,7 @@ use masq_lib::shared_schema::ConfiguratorError;
 use masq_lib::utils::NeighborhoodModeLight;
 use std::net::SocketAddr;
 use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
 
 use clap::value_t;
 use log::LevelFilter;
@@ -23,16 +25,19 @@ use crate::node_configurator::unprivileged_parse_args_configuration::{
     UnprivilegedParseArgsConfiguration, UnprivilegedParseArgsConfigurationDaoReal,
 };
 use crate::node_configurator::{
-    data_directory_from_context, determine_user_specific_data,
+    data_directory_from_context, determine_user_specific_data, masq_directory_from_context,
     real_user_data_directory_path_and_chain,
 };
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::cryptde_null::CryptDENull;
+use crate::sub_lib::crypto_suite::CryptoSuite;
 use crate::sub_lib::utils::make_new_multi_config;
 use crate::tls_discriminator_factory::TlsDiscriminatorFactory;
 use masq_lib::constants::{DEFAULT_UI_PORT, HTTP_PORT, TLS_PORT};
 use masq_lib::multi_config::{CommandLineVcl, ConfigFileVcl, EnvironmentVcl};
+use masq_lib::ui_auth::ui_token_file_in;
 use std::str::FromStr;
+use std::time::Duration;
 
 pub struct NodeConfiguratorStandardPrivileged {
     dirs_wrapper: Box<dyn DirsWrapper>,
@@ -290,13 +295,60 @@ pub fn privileged_parse_args(
 
     privileged_config.log_level =
         value_m!(multi_config, "log-level", LevelFilter).unwrap_or(LevelFilter::Warn);
+    let default_log_file_config = LogFileConfig::default();
+    privileged_config.log_file_config = LogFileConfig {
+        format: value_m!(multi_config, "log-format", LogFormat)
+            .unwrap_or(default_log_file_config.format),
+        rotation_size_mb: value_m!(multi_config, "log-rotation-size", u64)
+            .unwrap_or(default_log_file_config.rotation_size_mb),
+        rotation_age: value_m!(multi_config, "log-rotation-age", LogRotationAge)
+            .unwrap_or(default_log_file_config.rotation_age),
+        retention_files: value_m!(multi_config, "log-retention", usize)
+            .unwrap_or(default_log_file_config.retention_files),
+        compression: value_m!(multi_config, "log-compression", String)
+            .map(|compression| !compression.eq_ignore_ascii_case("off"))
+            .unwrap_or(default_log_file_config.compression),
+    };
 
     privileged_config.ui_gateway_config.ui_port =
         value_m!(multi_config, "ui-port", u16).unwrap_or(DEFAULT_UI_PORT);
+    privileged_config.ui_gateway_config.ui_token_file_opt =
+        match value_m!(multi_config, "ui-authentication", String) {
+            Some(value) if value.eq_ignore_ascii_case("off") => None,
+            _ => Some(
+                value_m!(multi_config, "ui-token-file", PathBuf).unwrap_or_else(|| {
+                    ui_token_file_in(&masq_directory_from_context(
+                        dirs_wrapper,
+                        &privileged_config.real_user,
+                    ))
+                }),
+            ),
+        };
 
     privileged_config.crash_point =
         value_m!(multi_config, "crash-point", CrashPoint).unwrap_or(CrashPoint::None);
 
+    privileged_config.clandestine_websocket_mode = value_m!(
+        multi_config,
+        "clandestine-websocket",
+        ClandestineWebSocketMode
+    )
+    .unwrap_or(ClandestineWebSocketMode::Off);
+
+    privileged_config.clandestine_port_count =
+        value_m!(multi_config, "clandestine-port-count", usize).unwrap_or(1);
+
+    privileged_config.clandestine_port_rotation_opt =
+        value_m!(multi_config, "clandestine-port-rotation", u64).map(Duration::from_secs);
+
+    privileged_config.relay_batch_latency_opt =
+        value_m!(multi_config, "relay-batch-latency", u64).map(Duration::from_millis);
+
+    privileged_config.metrics_port_opt = value_m!(multi_config, "metrics-port", u16);
+
+    privileged_config.crypto_suite =
+        value_m!(multi_config, "crypto-suite", CryptoSuite).unwrap_or_default();
+
     if let Some(public_key_str) = value_m!(multi_config, "fake-public-key", String) {
         let (main_public_key, alias_public_key) = match base64::decode(&public_key_str) {
             Ok(mut key) => {
@@ -348,6 +400,11 @@ fn configure_database(
             return Err(pce.into_configurator_error("blockchain-service-url"));
         }
     }
+    if let Err(pce) = persistent_config
+        .set_blockchain_service_quorum(config.blockchain_bridge_config.blockchain_service_quorum)
+    {
+        return Err(pce.into_configurator_error("blockchain-service-quorum"));
+    }
     if let Err(pce) = persistent_config.set_gas_price(config.blockchain_bridge_config.gas_price) {
         return Err(pce.into_configurator_error("gas-price"));
     }
@@ -465,6 +522,7 @@ mod tests {
         let mut persistent_config = PersistentConfigurationMock::new()
             .set_neighborhood_mode_result(Ok(()))
             .set_min_hops_result(Ok(()))
+            .set_blockchain_service_quorum_result(Ok(()))
             .set_gas_price_result(Err(PersistentConfigError::TransactionError));
 
         let result = configure_database(&config, &mut persistent_config);
@@ -494,6 +552,24 @@ mod tests {
         )
     }
 
+    #[test]
+    fn configure_database_handles_error_during_setting_blockchain_service_quorum() {
+        let mut config = BootstrapperConfig::new();
+        config.blockchain_bridge_config.blockchain_service_quorum = 2;
+        let mut persistent_config = PersistentConfigurationMock::new()
+            .set_neighborhood_mode_result(Ok(()))
+            .set_min_hops_result(Ok(()))
+            .set_blockchain_service_quorum_result(Err(PersistentConfigError::TransactionError));
+
+        let result = configure_database(&config, &mut persistent_config);
+
+        assert_eq!(
+            result,
+            Err(PersistentConfigError::TransactionError
+                .into_configurator_error("blockchain-service-quorum"))
+        )
+    }
+
     #[test]
     fn configure_database_handles_error_during_setting_neighborhood_mode() {
         let mut config = BootstrapperConfig::new();
@@ -649,6 +725,7 @@ mod tests {
             .param("--ip", "34.56.78.90")
             .param("--clandestine-port", "1234")
             .param("--ui-port", "5335")
+            .param("--ui-token-file", "/home/booga/ui_tokens")
             .param("--data-directory", home_dir.to_str().unwrap())
             .param("--blockchain-service-url", "http://127.0.0.1:8545")
             .param("--log-level", "trace")
@@ -683,6 +760,10 @@ mod tests {
             ),
         );
         assert_eq!(config.ui_gateway_config.ui_port, 5335);
+        assert_eq!(
+            config.ui_gateway_config.ui_token_file_opt,
+            Some(PathBuf::from("/home/booga/ui_tokens"))
+        );
         assert_eq!(
             config.neighborhood_config,
             NeighborhoodConfig {
@@ -750,6 +831,11 @@ mod tests {
             PathBuf::from("/home/booga/.local/share/MASQ")
                 .join(DEFAULT_CHAIN.rec().literal_identifier)
         );
+        #[cfg(target_os = "linux")]
+        assert_eq!(
+            config.ui_gateway_config.ui_token_file_opt,
+            Some(PathBuf::from("/home/booga/.local/share/MASQ/ui_tokens"))
+        );
 
         #[cfg(target_os = "macos")]
         assert_eq!(
@@ -759,6 +845,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn privileged_parse_args_drops_the_ui_token_file_when_ui_authentication_is_off() {
+        running_test();
+        let args = ArgsBuilder::new()
+            .param("--ip", "1.2.3.4")
+            .param("--ui-token-file", "/home/booga/ui_tokens")
+            .param("--ui-authentication", "off");
+        let mut config = BootstrapperConfig::new();
+        let vcls: Vec<Box<dyn VirtualCommandLine>> =
+            vec![Box::new(CommandLineVcl::new(args.into()))];
+        let multi_config = make_new_multi_config(&app_node(), vcls).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(config.ui_gateway_config.ui_token_file_opt, None);
+    }
+
     #[test]
     fn privileged_parse_args_with_no_command_line_params() {
         running_test();
@@ -810,6 +913,144 @@ mod tests {
         assert_eq!(config.crash_point, CrashPoint::Panic);
     }
 
+    #[test]
+    fn no_parameters_produces_configuration_without_clandestine_websocket() {
+        running_test();
+        let args = make_default_cli_params();
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.clandestine_websocket_mode,
+            ClandestineWebSocketMode::Off
+        );
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_clandestine_websocket() {
+        running_test();
+        let args = make_default_cli_params().param("--clandestine-websocket", "Accept");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.clandestine_websocket_mode,
+            ClandestineWebSocketMode::Accept
+        );
+    }
+
+    #[test]
+    fn no_parameters_produces_configuration_with_single_unrotated_clandestine_port() {
+        running_test();
+        let args = make_default_cli_params();
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(config.clandestine_port_count, 1);
+        assert_eq!(config.clandestine_port_rotation_opt, None);
+        assert_eq!(config.relay_batch_latency_opt, None);
+        assert_eq!(config.metrics_port_opt, None);
+        assert_eq!(config.crypto_suite, CryptoSuite::Curve25519);
+        assert_eq!(config.log_file_config, LogFileConfig::default());
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_multiple_rotated_clandestine_ports() {
+        running_test();
+        let args = make_default_cli_params()
+            .param("--clandestine-port-count", "3")
+            .param("--clandestine-port-rotation", "900");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(config.clandestine_port_count, 3);
+        assert_eq!(
+            config.clandestine_port_rotation_opt,
+            Some(Duration::from_secs(900))
+        );
+    }
+
+    #[test]
+    fn relay_batch_latency_is_parsed_into_a_duration() {
+        running_test();
+        let args = make_default_cli_params().param("--relay-batch-latency", "250");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.relay_batch_latency_opt,
+            Some(Duration::from_millis(250))
+        );
+    }
+
+    #[test]
+    fn metrics_port_is_parsed() {
+        running_test();
+        let args = make_default_cli_params().param("--metrics-port", "5999");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(config.metrics_port_opt, Some(5999));
+    }
+
+    #[test]
+    fn log_file_parameters_are_parsed() {
+        running_test();
+        let args = make_default_cli_params()
+            .param("--log-format", "json")
+            .param("--log-rotation-size", "25")
+            .param("--log-rotation-age", "daily")
+            .param("--log-retention", "12")
+            .param("--log-compression", "off");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.log_file_config,
+            LogFileConfig {
+                format: LogFormat::Json,
+                rotation_size_mb: 25,
+                rotation_age: LogRotationAge::Daily,
+                retention_files: 12,
+                compression: false,
+            }
+        );
+    }
+
+    #[test]
+    fn crypto_suite_is_parsed_case_insensitively() {
+        running_test();
+        let args = make_default_cli_params().param("--crypto-suite", "X25519-MLKEM768");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(config.crypto_suite, CryptoSuite::X25519MlKem768);
+    }
+
     fn fill_up_config_file(mut config_file: File) {
         {
             config_file
@@ -1459,8 +1700,10 @@ mod tests {
             .unwrap()]);
         config.neighborhood_config.min_hops = Hops::FourHops;
         config.blockchain_bridge_config.blockchain_service_url_opt =
-            Some("https://infura.io/ID".to_string());
+            Some("https://infura.io/ID,http://localhost:8545".to_string());
+        config.blockchain_bridge_config.blockchain_service_quorum = 2;
         let set_blockchain_service_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_blockchain_service_quorum_params_arc = Arc::new(Mutex::new(vec![]));
         let set_clandestine_port_params_arc = Arc::new(Mutex::new(vec![]));
         let set_gas_price_params_arc = Arc::new(Mutex::new(vec![]));
         let set_neighborhood_mode_params_arc = Arc::new(Mutex::new(vec![]));
@@ -1470,6 +1713,8 @@ mod tests {
             .set_clandestine_port_result(Ok(()))
             .set_blockchain_service_url_params(&set_blockchain_service_params_arc)
             .set_blockchain_service_url_result(Ok(()))
+            .set_blockchain_service_quorum_params(&set_blockchain_service_quorum_params_arc)
+            .set_blockchain_service_quorum_result(Ok(()))
             .set_neighborhood_mode_params(&set_neighborhood_mode_params_arc)
             .set_neighborhood_mode_result(Ok(()))
             .set_gas_price_params(&set_gas_price_params_arc)
@@ -1483,8 +1728,11 @@ mod tests {
         let set_blockchain_service_url = set_blockchain_service_params_arc.lock().unwrap();
         assert_eq!(
             *set_blockchain_service_url,
-            vec!["https://infura.io/ID".to_string()]
+            vec!["https://infura.io/ID,http://localhost:8545".to_string()]
         );
+        let set_blockchain_service_quorum_params =
+            set_blockchain_service_quorum_params_arc.lock().unwrap();
+        assert_eq!(*set_blockchain_service_quorum_params, vec![2]);
         let set_neighborhood_mode_params = set_neighborhood_mode_params_arc.lock().unwrap();
         assert_eq!(
             *set_neighborhood_mode_params,
@@ -1511,6 +1759,7 @@ mod tests {
             .set_neighborhood_mode_params(&set_neighborhood_mode_params_arc)
             .set_neighborhood_mode_result(Ok(()))
             .set_min_hops_result(Ok(()))
+            .set_blockchain_service_quorum_result(Ok(()))
             .set_gas_price_result(Ok(()));
 
         let result = configure_database(&config, &mut persistent_config);
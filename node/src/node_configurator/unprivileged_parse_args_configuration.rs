@@ -1,10 +1,15 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use crate::accountant::DEFAULT_PENDING_TOO_LONG_SEC;
+use crate::accountant::{
+    DEFAULT_BALANCE_WARNING_DAYS, DEFAULT_CONFIRMATION_DEPTH, DEFAULT_LEDGER_ROLLUP_DAYS,
+    DEFAULT_PENDING_STUCK_SEC, DEFAULT_PENDING_TOO_LONG_SEC,
+};
 use crate::blockchain::bip32::Bip32EncryptionKeyProvider;
 use crate::bootstrapper::BootstrapperConfig;
 use crate::db_config::persistent_configuration::{PersistentConfigError, PersistentConfiguration};
+use crate::neighborhood::pricing::PricingConfig;
 use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals, DEFAULT_EARNING_WALLET};
+use crate::sub_lib::blockchain_bridge::{blockchain_service_urls, BlockchainBridgeConfig};
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::cryptde_null::CryptDENull;
 use crate::sub_lib::cryptde_real::CryptDEReal;
@@ -16,7 +21,7 @@ use crate::sub_lib::wallet::Wallet;
 use clap::value_t;
 use itertools::Itertools;
 use masq_lib::blockchains::chains::Chain;
-use masq_lib::constants::{DEFAULT_CHAIN, MASQ_URL_PREFIX};
+use masq_lib::constants::{DEFAULT_CHAIN, DEFAULT_MULTISEND_MAX_CREDITORS, MASQ_URL_PREFIX};
 use masq_lib::logger::Logger;
 use masq_lib::multi_config::MultiConfig;
 use masq_lib::shared_schema::{ConfiguratorError, ParamError};
@@ -46,6 +51,21 @@ pub trait UnprivilegedParseArgsConfiguration {
                     Err(pce) => return Err(pce.into_configurator_error("gas-price")),
                 }
             };
+        unprivileged_config
+            .blockchain_bridge_config
+            .blockchain_service_quorum =
+            if is_user_specified(multi_config, "blockchain-service-quorum") {
+                value_m!(multi_config, "blockchain-service-quorum", u16)
+                    .expectv("blockchain service quorum")
+            } else {
+                match persistent_config.blockchain_service_quorum() {
+                    Ok(quorum) => quorum,
+                    Err(pce) => {
+                        return Err(pce.into_configurator_error("blockchain-service-quorum"))
+                    }
+                }
+            };
+        check_blockchain_service_quorum(&unprivileged_config.blockchain_bridge_config)?;
         unprivileged_config.clandestine_port_opt = value_m!(multi_config, "clandestine-port", u16);
         unprivileged_config.blockchain_bridge_config.gas_price =
             if is_user_specified(multi_config, "gas-price") {
@@ -56,7 +76,27 @@ pub trait UnprivilegedParseArgsConfiguration {
                     Err(pce) => return Err(pce.into_configurator_error("gas-price")),
                 }
             };
+        unprivileged_config
+            .blockchain_bridge_config
+            .multisend_contract_opt =
+            value_m!(multi_config, "multisend-contract", String).map(|address| {
+                Wallet::from_str(&address)
+                    .unwrap_or_else(|_| {
+                        panic!("Validator let through a bad contract address: {}", address)
+                    })
+                    .address()
+            });
+        unprivileged_config
+            .blockchain_bridge_config
+            .multisend_max_creditors = value_m!(multi_config, "multisend-max-creditors", u16)
+            .unwrap_or(DEFAULT_MULTISEND_MAX_CREDITORS);
         unprivileged_config.db_password_opt = value_m!(multi_config, "db-password", String);
+        unprivileged_config.pricing_config =
+            match value_m!(multi_config, "pricing-strategy", String) {
+                Some(parameters) => PricingConfig::try_from(parameters.as_str())
+                    .map_err(|e| ConfiguratorError::required("pricing-strategy", &e))?,
+                None => PricingConfig::default(),
+            };
         configure_accountant_config(multi_config, unprivileged_config, persistent_config)?;
         unprivileged_config.mapping_protocol_opt =
             compute_mapping_protocol_opt(multi_config, persistent_config, logger);
@@ -190,11 +230,46 @@ pub fn get_wallets(
             )
         })
     });
-    config.consuming_wallet_opt = consuming_wallet_opt;
+    let externally_signed_wallet_opt = get_externally_signed_consuming_wallet(
+        multi_config,
+        consuming_wallet_opt.is_some(),
+        config,
+    )?;
+    config.consuming_wallet_opt = externally_signed_wallet_opt.or(consuming_wallet_opt);
     config.earning_wallet = earning_wallet_opt.unwrap_or_else(|| DEFAULT_EARNING_WALLET.clone());
     Ok(())
 }
 
+fn get_externally_signed_consuming_wallet(
+    multi_config: &MultiConfig,
+    has_consuming_private_key: bool,
+    config: &mut BootstrapperConfig,
+) -> Result<Option<Wallet>, ConfiguratorError> {
+    let address_opt = value_m!(multi_config, "consuming-wallet-address", String);
+    let signer_url_opt = value_m!(multi_config, "external-signer-url", String);
+    match (address_opt, signer_url_opt) {
+        (None, None) => Ok(None),
+        (Some(_), None) => Err(ConfiguratorError::required(
+            "external-signer-url",
+            "Must be supplied along with --consuming-wallet-address",
+        )),
+        (None, Some(_)) => Err(ConfiguratorError::required(
+            "consuming-wallet-address",
+            "Must be supplied along with --external-signer-url",
+        )),
+        (Some(_), Some(_)) if has_consuming_private_key => Err(ConfiguratorError::required(
+            "consuming-wallet-address",
+            "Cannot use an external signer while the Node holds a consuming private key",
+        )),
+        (Some(address), Some(signer_url)) => {
+            config.blockchain_bridge_config.external_signer_url_opt = Some(signer_url);
+            Ok(Some(Wallet::from_str(&address).unwrap_or_else(|_| {
+                panic!("Validator let through a bad wallet address: {}", address)
+            })))
+        }
+    }
+}
+
 fn wallet_params_are_equal(a: &str, b: &str) -> bool {
     a.to_uppercase() == b.to_uppercase()
 }
@@ -220,7 +295,6 @@ pub fn make_neighborhood_config<T: UnprivilegedParseArgsConfiguration + ?Sized>(
             Err(e) => panic!("Unable to find min_hops value in database: {:?}", e),
         },
     };
-
     match make_neighborhood_mode(multi_config, neighbor_configs, persistent_config) {
         Ok(mode) => Ok(NeighborhoodConfig { mode, min_hops }),
         Err(e) => Err(e),
@@ -480,6 +554,27 @@ fn compute_mapping_protocol_opt(
     computed_mapping_protocol_opt
 }
 
+fn check_blockchain_service_quorum(
+    config: &BlockchainBridgeConfig,
+) -> Result<(), ConfiguratorError> {
+    let service_count = config
+        .blockchain_service_url_opt
+        .as_ref()
+        .map(|urls| blockchain_service_urls(urls).len())
+        .unwrap_or(0);
+    if service_count > 0 && usize::from(config.blockchain_service_quorum) > service_count {
+        Err(ConfiguratorError::required(
+            "blockchain-service-quorum",
+            &format!(
+                "Value {} is greater than the number of blockchain services, which is {}",
+                config.blockchain_service_quorum, service_count
+            ),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
 fn configure_accountant_config(
     multi_config: &MultiConfig,
     config: &mut BootstrapperConfig,
@@ -511,6 +606,15 @@ fn configure_accountant_config(
     config.scan_intervals_opt = Some(scan_intervals);
     config.suppress_initial_scans = suppress_initial_scans;
     config.when_pending_too_long_sec = DEFAULT_PENDING_TOO_LONG_SEC;
+    config.when_pending_stuck_sec =
+        value_m!(multi_config, "stuck-payable-sec", u64).unwrap_or(DEFAULT_PENDING_STUCK_SEC);
+    config.confirmation_depth =
+        value_m!(multi_config, "confirmation-depth", u64).unwrap_or(DEFAULT_CONFIRMATION_DEPTH);
+    config.ledger_rollup_days =
+        value_m!(multi_config, "ledger-rollup-days", u64).unwrap_or(DEFAULT_LEDGER_ROLLUP_DAYS);
+    config.ledger_retention_days_opt = value_m!(multi_config, "ledger-retention-days", u64);
+    config.balance_warning_days =
+        value_m!(multi_config, "balance-warning-days", u64).unwrap_or(DEFAULT_BALANCE_WARNING_DAYS);
     Ok(())
 }
 
@@ -629,6 +733,7 @@ mod tests {
     use crate::db_config::config_dao::{ConfigDao, ConfigDaoReal};
     use crate::db_config::persistent_configuration::PersistentConfigError::NotPresent;
     use crate::db_config::persistent_configuration::PersistentConfigurationReal;
+    use crate::neighborhood::pricing::PricingStrategyKind;
     use crate::sub_lib::accountant::DEFAULT_PAYMENT_THRESHOLDS;
     use crate::sub_lib::cryptde::{PlainData, PublicKey};
     use crate::sub_lib::neighborhood::{Hops, DEFAULT_RATE_PACK};
@@ -638,11 +743,11 @@ mod tests {
     use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
     use crate::test_utils::unshared_test_utils::{
         configure_default_persistent_config, default_persistent_config_just_accountant_config,
-        make_persistent_config_real_with_config_dao_null, make_simplified_multi_config,
-        ACCOUNTANT_CONFIG_PARAMS, MAPPING_PROTOCOL, RATE_PACK, ZERO,
+        default_persistent_config_just_base, make_persistent_config_real_with_config_dao_null,
+        make_simplified_multi_config, ACCOUNTANT_CONFIG_PARAMS, MAPPING_PROTOCOL, RATE_PACK, ZERO,
     };
     use crate::test_utils::{main_cryptde, ArgsBuilder};
-    use masq_lib::constants::DEFAULT_GAS_PRICE;
+    use masq_lib::constants::{DEFAULT_BLOCKCHAIN_SERVICE_QUORUM, DEFAULT_GAS_PRICE};
     use masq_lib::multi_config::{CommandLineVcl, NameValueVclArg, VclArg, VirtualCommandLine};
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
     use masq_lib::test_utils::utils::{ensure_node_home_directory_exists, TEST_DEFAULT_CHAIN};
@@ -824,6 +929,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn make_neighborhood_config_allows_more_than_one_hop_with_an_external_signer() {
+        running_test();
+        let multi_config = make_new_multi_config(
+            &app_node(),
+            vec![Box::new(CommandLineVcl::new(
+                ArgsBuilder::new()
+                    .param("--neighborhood-mode", "zero-hop")
+                    .param("--min-hops", "3")
+                    .into(),
+            ))],
+        )
+        .unwrap();
+        let mut bootstrapper_config = BootstrapperConfig::new();
+        bootstrapper_config
+            .blockchain_bridge_config
+            .external_signer_url_opt = Some("http://127.0.0.1:8550".to_string());
+
+        let result = make_neighborhood_config(
+            &UnprivilegedParseArgsConfigurationDaoReal {},
+            &multi_config,
+            &mut configure_default_persistent_config(RATE_PACK),
+            &mut bootstrapper_config,
+        );
+
+        assert_eq!(result.unwrap().min_hops, Hops::ThreeHops);
+    }
+
     #[test]
     fn make_neighborhood_config_standard_missing_ip() {
         running_test();
@@ -1632,6 +1765,11 @@ mod tests {
         assert_eq!(config.earning_wallet, DEFAULT_EARNING_WALLET.clone(),);
         assert_eq!(config.consuming_wallet_opt, None);
         assert_eq!(config.mapping_protocol_opt, None);
+        assert_eq!(config.blockchain_bridge_config.multisend_contract_opt, None);
+        assert_eq!(
+            config.blockchain_bridge_config.multisend_max_creditors,
+            DEFAULT_MULTISEND_MAX_CREDITORS
+        );
     }
 
     #[test]
@@ -1730,6 +1868,220 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unprivileged_parse_args_with_blockchain_service_quorum_on_command_line() {
+        running_test();
+        let args = ArgsBuilder::new()
+            .param("--neighborhood-mode", "zero-hop")
+            .param(
+                "--blockchain-service-url",
+                "https://infura.io/ID,https://alchemy.com/ID,http://localhost:8545",
+            )
+            .param("--blockchain-service-quorum", "2");
+        let mut config = BootstrapperConfig::new();
+        let vcls: Vec<Box<dyn VirtualCommandLine>> =
+            vec![Box::new(CommandLineVcl::new(args.into()))];
+        let multi_config = make_new_multi_config(&app_node(), vcls).unwrap();
+        let mut persistent_configuration = {
+            let config = make_persistent_config(None, None, None, None, None, None, None);
+            default_persistent_config_just_accountant_config(config)
+        };
+        let subject = UnprivilegedParseArgsConfigurationDaoReal {};
+
+        subject
+            .unprivileged_parse_args(
+                &multi_config,
+                &mut config,
+                &mut persistent_configuration,
+                &Logger::new("test"),
+            )
+            .unwrap();
+
+        assert_eq!(
+            config.blockchain_bridge_config.blockchain_service_url_opt,
+            Some("https://infura.io/ID,https://alchemy.com/ID,http://localhost:8545".to_string())
+        );
+        assert_eq!(config.blockchain_bridge_config.blockchain_service_quorum, 2);
+    }
+
+    #[test]
+    fn unprivileged_parse_args_with_multisend_contract_on_command_line() {
+        running_test();
+        let args = ArgsBuilder::new()
+            .param("--neighborhood-mode", "zero-hop")
+            .param(
+                "--multisend-contract",
+                "0xD152f549545093347A162Dce210e7293f1452150",
+            )
+            .param("--multisend-max-creditors", "25");
+        let mut config = BootstrapperConfig::new();
+        let vcls: Vec<Box<dyn VirtualCommandLine>> =
+            vec![Box::new(CommandLineVcl::new(args.into()))];
+        let multi_config = make_new_multi_config(&app_node(), vcls).unwrap();
+        let mut persistent_configuration = {
+            let config = make_persistent_config(None, None, None, None, None, None, None)
+                .blockchain_service_url_result(Ok(None))
+                .blockchain_service_quorum_result(Ok(1));
+            default_persistent_config_just_accountant_config(config)
+        };
+        let subject = UnprivilegedParseArgsConfigurationDaoReal {};
+
+        subject
+            .unprivileged_parse_args(
+                &multi_config,
+                &mut config,
+                &mut persistent_configuration,
+                &Logger::new("test"),
+            )
+            .unwrap();
+
+        assert_eq!(
+            config.blockchain_bridge_config.multisend_contract_opt,
+            Some(
+                Wallet::from_str("0xD152f549545093347A162Dce210e7293f1452150")
+                    .unwrap()
+                    .address()
+            )
+        );
+        assert_eq!(config.blockchain_bridge_config.multisend_max_creditors, 25);
+    }
+
+    #[test]
+    fn unprivileged_parse_args_with_pricing_strategy_on_command_line() {
+        running_test();
+        let args = ArgsBuilder::new()
+            .param("--neighborhood-mode", "zero-hop")
+            .param("--pricing-strategy", "market|80|150");
+        let mut config = BootstrapperConfig::new();
+        let vcls: Vec<Box<dyn VirtualCommandLine>> =
+            vec![Box::new(CommandLineVcl::new(args.into()))];
+        let multi_config = make_new_multi_config(&app_node(), vcls).unwrap();
+        let mut persistent_configuration = {
+            let config = make_persistent_config(None, None, None, None, None, None, None)
+                .blockchain_service_url_result(Ok(None))
+                .blockchain_service_quorum_result(Ok(1));
+            default_persistent_config_just_accountant_config(config)
+        };
+        let subject = UnprivilegedParseArgsConfigurationDaoReal {};
+
+        subject
+            .unprivileged_parse_args(
+                &multi_config,
+                &mut config,
+                &mut persistent_configuration,
+                &Logger::new("test"),
+            )
+            .unwrap();
+
+        assert_eq!(
+            config.pricing_config,
+            PricingConfig {
+                kind: PricingStrategyKind::MarketRelative,
+                lower_bound_percent: 80,
+                upper_bound_percent: 150,
+            }
+        );
+    }
+
+    #[test]
+    fn unprivileged_parse_args_rejects_a_bad_pricing_strategy() {
+        running_test();
+        let args = ArgsBuilder::new()
+            .param("--neighborhood-mode", "zero-hop")
+            .param("--pricing-strategy", "auction");
+        let mut config = BootstrapperConfig::new();
+        let vcls: Vec<Box<dyn VirtualCommandLine>> =
+            vec![Box::new(CommandLineVcl::new(args.into()))];
+        let multi_config = make_new_multi_config(&app_node(), vcls).unwrap();
+        let mut persistent_configuration =
+            make_persistent_config(None, None, None, None, None, None, None)
+                .blockchain_service_url_result(Ok(None))
+                .blockchain_service_quorum_result(Ok(1));
+        let subject = UnprivilegedParseArgsConfigurationDaoReal {};
+
+        let result = subject.unprivileged_parse_args(
+            &multi_config,
+            &mut config,
+            &mut persistent_configuration,
+            &Logger::new("test"),
+        );
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "pricing-strategy",
+                "Pricing strategy must be fixed, load or market, not 'auction'"
+            ))
+        );
+    }
+
+    #[test]
+    fn unprivileged_parse_args_with_blockchain_service_quorum_in_database_but_not_command_line() {
+        running_test();
+        let args = ArgsBuilder::new().param("--neighborhood-mode", "zero-hop");
+        let mut config = BootstrapperConfig::new();
+        let vcls: Vec<Box<dyn VirtualCommandLine>> =
+            vec![Box::new(CommandLineVcl::new(args.into()))];
+        let multi_config = make_new_multi_config(&app_node(), vcls).unwrap();
+        let mut persistent_configuration = default_persistent_config_just_accountant_config(
+            default_persistent_config_just_base(
+                PersistentConfigurationMock::new()
+                    .blockchain_service_url_result(Ok(Some(
+                        "https://infura.io/ID,https://alchemy.com/ID".to_string(),
+                    )))
+                    .blockchain_service_quorum_result(Ok(2)),
+            )
+            .mapping_protocol_result(Ok(None))
+            .rate_pack_result(Ok(DEFAULT_RATE_PACK)),
+        );
+        let subject = UnprivilegedParseArgsConfigurationDaoReal {};
+
+        subject
+            .unprivileged_parse_args(
+                &multi_config,
+                &mut config,
+                &mut persistent_configuration,
+                &Logger::new("test"),
+            )
+            .unwrap();
+
+        assert_eq!(config.blockchain_bridge_config.blockchain_service_quorum, 2);
+    }
+
+    #[test]
+    fn unprivileged_parse_args_rejects_blockchain_service_quorum_above_number_of_services() {
+        running_test();
+        let args = ArgsBuilder::new()
+            .param("--neighborhood-mode", "zero-hop")
+            .param(
+                "--blockchain-service-url",
+                "https://infura.io/ID,https://alchemy.com/ID",
+            )
+            .param("--blockchain-service-quorum", "3");
+        let mut config = BootstrapperConfig::new();
+        let vcls: Vec<Box<dyn VirtualCommandLine>> =
+            vec![Box::new(CommandLineVcl::new(args.into()))];
+        let multi_config = make_new_multi_config(&app_node(), vcls).unwrap();
+        let mut persistent_configuration =
+            make_persistent_config(None, None, None, None, None, None, None);
+        let subject = UnprivilegedParseArgsConfigurationDaoReal {};
+
+        let result = subject.unprivileged_parse_args(
+            &multi_config,
+            &mut config,
+            &mut persistent_configuration,
+            &Logger::new("test"),
+        );
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "blockchain-service-quorum",
+                "Value 3 is greater than the number of blockchain services, which is 2"
+            ))
+        );
+    }
+
     #[test]
     fn unprivileged_parse_args_with_mapping_protocol_both_on_command_line_and_in_database() {
         running_test();
@@ -1877,6 +2229,11 @@ mod tests {
             config.when_pending_too_long_sec,
             DEFAULT_PENDING_TOO_LONG_SEC
         );
+        assert_eq!(config.when_pending_stuck_sec, DEFAULT_PENDING_STUCK_SEC);
+        assert_eq!(config.confirmation_depth, DEFAULT_CONFIRMATION_DEPTH);
+        assert_eq!(config.ledger_rollup_days, DEFAULT_LEDGER_ROLLUP_DAYS);
+        assert_eq!(config.ledger_retention_days_opt, None);
+        assert_eq!(config.balance_warning_days, DEFAULT_BALANCE_WARNING_DAYS);
         let set_scan_intervals_params = set_scan_intervals_params_arc.lock().unwrap();
         assert_eq!(*set_scan_intervals_params, vec!["180|150|130".to_string()]);
         let set_payment_thresholds_params = set_payment_thresholds_params_arc.lock().unwrap();
@@ -1897,6 +2254,16 @@ mod tests {
             "180|150|130",
             "--payment-thresholds",
             "100000|1000|1000|20000|1000|20000",
+            "--stuck-payable-sec",
+            "900",
+            "--confirmation-depth",
+            "24",
+            "--ledger-rollup-days",
+            "7",
+            "--ledger-retention-days",
+            "365",
+            "--balance-warning-days",
+            "10",
         ];
         let mut config = BootstrapperConfig::new();
         let multi_config = make_simplified_multi_config(args);
@@ -1954,6 +2321,11 @@ mod tests {
             config.when_pending_too_long_sec,
             expected_when_pending_too_long_sec
         );
+        assert_eq!(config.when_pending_stuck_sec, 900);
+        assert_eq!(config.confirmation_depth, 24);
+        assert_eq!(config.ledger_rollup_days, 7);
+        assert_eq!(config.ledger_retention_days_opt, Some(365));
+        assert_eq!(config.balance_warning_days, 10);
         //no prepared results for the setter methods, that is they were uncalled
     }
 
@@ -2414,6 +2786,112 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_wallets_makes_address_only_consuming_wallet_for_external_signer() {
+        running_test();
+        let args = [
+            "--consuming-wallet-address",
+            "0xcafedeadbeefbabefacecafedeadbeefbabeface",
+            "--external-signer-url",
+            "http://127.0.0.1:8550",
+        ];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config =
+            make_persistent_config(None, None, None, None, None, None, None);
+        let mut config = BootstrapperConfig::new();
+
+        get_wallets(&multi_config, &mut persistent_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.consuming_wallet_opt,
+            Some(Wallet::from_str("0xcafedeadbeefbabefacecafedeadbeefbabeface").unwrap())
+        );
+        assert_eq!(
+            config.blockchain_bridge_config.external_signer_url_opt,
+            Some("http://127.0.0.1:8550".to_string())
+        );
+    }
+
+    #[test]
+    fn get_wallets_requires_external_signer_url_with_consuming_wallet_address() {
+        running_test();
+        let args = [
+            "--consuming-wallet-address",
+            "0xcafedeadbeefbabefacecafedeadbeefbabeface",
+        ];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config =
+            make_persistent_config(None, None, None, None, None, None, None);
+        let mut config = BootstrapperConfig::new();
+
+        let result = get_wallets(&multi_config, &mut persistent_config, &mut config);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "external-signer-url",
+                "Must be supplied along with --consuming-wallet-address"
+            ))
+        );
+    }
+
+    #[test]
+    fn get_wallets_requires_consuming_wallet_address_with_external_signer_url() {
+        running_test();
+        let args = ["--external-signer-url", "http://127.0.0.1:8550"];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config =
+            make_persistent_config(None, None, None, None, None, None, None);
+        let mut config = BootstrapperConfig::new();
+
+        let result = get_wallets(&multi_config, &mut persistent_config, &mut config);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "consuming-wallet-address",
+                "Must be supplied along with --external-signer-url"
+            ))
+        );
+    }
+
+    #[test]
+    fn get_wallets_rejects_external_signer_when_consuming_private_key_is_present() {
+        running_test();
+        let args = [
+            "--consuming-wallet-address",
+            "0xcafedeadbeefbabefacecafedeadbeefbabeface",
+            "--external-signer-url",
+            "http://127.0.0.1:8550",
+        ];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config = make_persistent_config(
+            Some("password"),
+            Some("0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF"),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let mut config = BootstrapperConfig::new();
+        config.db_password_opt = Some("password".to_string());
+
+        let result = get_wallets(&multi_config, &mut persistent_config, &mut config);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "consuming-wallet-address",
+                "Cannot use an external signer while the Node holds a consuming private key"
+            ))
+        );
+        assert_eq!(
+            config.blockchain_bridge_config.external_signer_url_opt,
+            None
+        );
+    }
+
     #[test]
     fn configure_rate_pack_command_line_absent_config_dao_null_so_all_defaults() {
         running_test();
@@ -2658,5 +3136,6 @@ mod tests {
             .mapping_protocol_result(Ok(Some(AutomapProtocol::Pcp)))
             .rate_pack_result(Ok(rate_pack))
             .min_hops_result(Ok(min_hops))
+            .blockchain_service_quorum_result(Ok(DEFAULT_BLOCKCHAIN_SERVICE_QUORUM))
     }
 }
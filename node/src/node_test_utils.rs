@@ -4,6 +4,7 @@
 use crate::discriminator::Discriminator;
 use crate::discriminator::DiscriminatorFactory;
 use crate::discriminator::UnmaskedChunk;
+use crate::hopper::live_cores_package::LiveCoresPackage;
 use crate::masquerader::MasqueradeError;
 use crate::masquerader::Masquerader;
 use crate::node_configurator::DirsWrapper;
@@ -27,7 +28,7 @@ use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
-use std::time::SystemTime;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub trait TestLogOwner {
     fn get_test_log(&self) -> Arc<Mutex<TestLog>>;
@@ -226,6 +227,30 @@ pub fn check_timestamp(before: SystemTime, timestamp: SystemTime, after: SystemT
     });
 }
 
+// Checks that the package was stamped between the two times, then takes the stamp off, so that the
+// package can be compared with one that was never stamped
+pub fn check_stamp_and_remove(
+    before: SystemTime,
+    mut live_package: LiveCoresPackage,
+    after: SystemTime,
+) -> LiveCoresPackage {
+    assert!(
+        live_package.is_stamped(),
+        "Package should have been stamped, but wasn't"
+    );
+    let secs = |time: SystemTime| time.duration_since(UNIX_EPOCH).unwrap().as_secs();
+    assert!(
+        secs(before) <= live_package.sent_at_s && live_package.sent_at_s <= secs(after),
+        "Stamp should have been between {:?} and {:?}, but was {}",
+        before,
+        after,
+        live_package.sent_at_s
+    );
+    live_package.version = LiveCoresPackage::version();
+    live_package.sent_at_s = 0;
+    live_package
+}
+
 pub struct NullFramer {
     data: Vec<Vec<u8>>,
 }
@@ -8,6 +8,7 @@ mod stream_handler_pool;
 mod stream_reader;
 mod stream_writer;
 
+use crate::metrics::NodeMetrics;
 use crate::proxy_client::resolver_wrapper::ResolverWrapperFactory;
 use crate::proxy_client::resolver_wrapper::ResolverWrapperFactoryReal;
 use crate::proxy_client::stream_handler_pool::StreamHandlerPool;
@@ -18,6 +19,7 @@ use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::hopper::MessageType;
 use crate::sub_lib::hopper::{ExpiredCoresPackage, IncipientCoresPackage};
+use crate::sub_lib::neighborhood::{ConfigChange, ConfigChangeMsg, ServedStreamCountMessage};
 use crate::sub_lib::peer_actors::BindMessage;
 use crate::sub_lib::proxy_client::InboundServerData;
 use crate::sub_lib::proxy_client::ProxyClientConfig;
@@ -40,6 +42,7 @@ use masq_lib::ui_gateway::NodeFromUiMessage;
 use pretty_hex::PrettyHex;
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::SystemTime;
 use trust_dns_resolver::config::NameServerConfig;
 use trust_dns_resolver::config::Protocol;
@@ -55,11 +58,13 @@ pub struct ProxyClient {
     cryptde: &'static dyn CryptDE,
     to_hopper: Option<Recipient<IncipientCoresPackage>>,
     to_accountant: Option<Recipient<ReportExitServiceProvidedMessage>>,
+    served_stream_count_sub_opt: Option<Recipient<ServedStreamCountMessage>>,
     pool: Option<Box<dyn StreamHandlerPool>>,
     stream_contexts: HashMap<StreamKey, StreamContext>,
     exit_service_rate: u64,
     exit_byte_rate: u64,
     is_decentralized: bool,
+    metrics: Arc<NodeMetrics>,
     crashable: bool,
     logger: Logger,
 }
@@ -76,6 +81,8 @@ impl Handler<BindMessage> for ProxyClient {
         ctx.set_mailbox_capacity(NODE_MAILBOX_CAPACITY);
         self.to_hopper = Some(msg.peer_actors.hopper.from_hopper_client);
         self.to_accountant = Some(msg.peer_actors.accountant.report_exit_service_provided);
+        self.served_stream_count_sub_opt =
+            Some(msg.peer_actors.neighborhood.served_stream_count_sub);
         let mut config = ResolverConfig::new();
         for dns_server_ref in &self.dns_servers {
             info!(self.logger, "Adding DNS server: {}", dns_server_ref.ip());
@@ -94,6 +101,7 @@ impl Handler<BindMessage> for ProxyClient {
             msg.peer_actors.proxy_client_opt.unwrap(),
             self.exit_service_rate,
             self.exit_byte_rate,
+            self.metrics.clone(),
         ));
     }
 }
@@ -123,9 +131,14 @@ impl Handler<ExpiredCoresPackage<ClientRequestPayload_0v1>> for ProxyClient {
                 payload.sequenced_packet.sequence_number,
                 payload.sequenced_packet.data.len()
             );
-            self.stream_contexts
-                .insert(payload.stream_key, latest_stream_context);
+            let is_new_stream = self
+                .stream_contexts
+                .insert(payload.stream_key, latest_stream_context)
+                .is_none();
             pool.process_package(payload, paying_wallet);
+            if is_new_stream {
+                self.report_served_stream_count();
+            }
         } else {
             warning!(self.logger, "Refusing to provide exit services for CORES package with {}-byte payload without paying wallet", payload.sequenced_packet.data.len());
         }
@@ -167,6 +180,7 @@ impl Handler<InboundServerData> for ProxyClient {
                 "Retiring stream key {}: no more data", msg_stream_key
             );
             self.stream_contexts.remove(&msg_stream_key);
+            self.report_served_stream_count();
         }
     }
 }
@@ -199,6 +213,7 @@ impl Handler<DnsResolveFailure_0v1> for ProxyClient {
                     "Removing stream key {} for DnsResolveFailure", stream_key
                 );
                 self.stream_contexts.remove(&stream_key);
+                self.report_served_stream_count();
             }
             None => error!(
                 self.logger,
@@ -216,6 +231,14 @@ impl Handler<NodeFromUiMessage> for ProxyClient {
     }
 }
 
+impl Handler<ConfigChangeMsg> for ProxyClient {
+    type Result = ();
+
+    fn handle(&mut self, msg: ConfigChangeMsg, _ctx: &mut Self::Context) -> Self::Result {
+        self.handle_config_change_msg(msg)
+    }
+}
+
 impl ProxyClient {
     pub fn new(config: ProxyClientConfig) -> ProxyClient {
         if config.dns_servers.is_empty() {
@@ -228,11 +251,13 @@ impl ProxyClient {
             cryptde: config.cryptde,
             to_hopper: None,
             to_accountant: None,
+            served_stream_count_sub_opt: None,
             pool: None,
             stream_contexts: HashMap::new(),
             exit_service_rate: config.exit_service_rate,
             exit_byte_rate: config.exit_byte_rate,
             is_decentralized: config.is_decentralized,
+            metrics: config.metrics,
             crashable: config.crashable,
             logger: Logger::new("ProxyClient"),
         }
@@ -245,9 +270,38 @@ impl ProxyClient {
             inbound_server_data: recipient!(addr, InboundServerData),
             dns_resolve_failed: recipient!(addr, DnsResolveFailure_0v1),
             node_from_ui: recipient!(addr, NodeFromUiMessage),
+            config_change_msg_sub: recipient!(addr, ConfigChangeMsg),
         }
     }
 
+    fn handle_config_change_msg(&mut self, msg: ConfigChangeMsg) {
+        if let ConfigChange::UpdateRatePack(rate_pack) = msg.change {
+            debug!(
+                self.logger,
+                "Exit rates changed to {} per CORES package and {} per byte",
+                rate_pack.exit_service_rate,
+                rate_pack.exit_byte_rate
+            );
+            self.exit_service_rate = rate_pack.exit_service_rate;
+            self.exit_byte_rate = rate_pack.exit_byte_rate;
+            if let Some(pool) = self.pool.as_ref() {
+                pool.update_exit_rates(rate_pack.exit_service_rate, rate_pack.exit_byte_rate)
+            }
+        } else {
+            trace!(self.logger, "Ignored irrelevant message: {:?}", msg);
+        }
+    }
+
+    fn report_served_stream_count(&self) {
+        self.served_stream_count_sub_opt
+            .as_ref()
+            .expect("Neighborhood is unbound")
+            .try_send(ServedStreamCountMessage {
+                stream_count: self.stream_contexts.len(),
+            })
+            .expect("Neighborhood is dead");
+    }
+
     fn send_response_to_hopper(
         &self,
         msg: InboundServerData,
@@ -331,6 +385,7 @@ struct StreamContext {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::match_every_type_id;
     use crate::node_test_utils::check_timestamp;
     use crate::proxy_client::local_test_utils::ResolverWrapperFactoryMock;
     use crate::proxy_client::local_test_utils::ResolverWrapperMock;
@@ -342,6 +397,7 @@ mod tests {
     use crate::sub_lib::cryptde::PublicKey;
     use crate::sub_lib::dispatcher::Component;
     use crate::sub_lib::hopper::MessageType;
+    use crate::sub_lib::neighborhood::RatePack;
     use crate::sub_lib::proxy_client::ClientResponsePayload_0v1;
     use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
     use crate::sub_lib::proxy_server::ProxyProtocol;
@@ -353,16 +409,17 @@ mod tests {
     use crate::test_utils::recorder::make_recorder;
     use crate::test_utils::recorder::peer_actors_builder;
     use crate::test_utils::recorder::Recorder;
+    use crate::test_utils::recorder_stop_conditions::{StopCondition, StopConditions};
     use crate::test_utils::unshared_test_utils::prove_that_crash_request_handler_is_hooked_up;
     use crate::test_utils::*;
     use actix::System;
     use masq_lib::blockchains::chains::Chain;
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use std::any::TypeId;
     use std::cell::RefCell;
     use std::net::SocketAddr;
     use std::net::{IpAddr, SocketAddrV4};
     use std::str::FromStr;
-    use std::sync::Arc;
     use std::sync::Mutex;
     use std::thread;
     use std::time::SystemTime;
@@ -378,6 +435,7 @@ mod tests {
 
     pub struct StreamHandlerPoolMock {
         process_package_parameters: Arc<Mutex<Vec<(ClientRequestPayload_0v1, Option<Wallet>)>>>,
+        update_exit_rates_params: Arc<Mutex<Vec<(u64, u64)>>>,
     }
 
     impl StreamHandlerPool for StreamHandlerPoolMock {
@@ -391,15 +449,28 @@ mod tests {
                 .unwrap()
                 .push((payload, paying_wallet));
         }
+
+        fn update_exit_rates(&self, exit_service_rate: u64, exit_byte_rate: u64) {
+            self.update_exit_rates_params
+                .lock()
+                .unwrap()
+                .push((exit_service_rate, exit_byte_rate));
+        }
     }
 
     impl StreamHandlerPoolMock {
         pub fn new() -> StreamHandlerPoolMock {
             StreamHandlerPoolMock {
                 process_package_parameters: Arc::new(Mutex::new(vec![])),
+                update_exit_rates_params: Arc::new(Mutex::new(vec![])),
             }
         }
 
+        pub fn update_exit_rates_params(mut self, params: &Arc<Mutex<Vec<(u64, u64)>>>) -> Self {
+            self.update_exit_rates_params = params.clone();
+            self
+        }
+
         pub fn process_package_parameters(
             self,
             parameters: &mut Arc<Mutex<Vec<(ClientRequestPayload_0v1, Option<Wallet>)>>>,
@@ -434,6 +505,7 @@ mod tests {
             proxy_client_subs: ProxyClientSubs,
             exit_service_rate: u64,
             exit_byte_rate: u64,
+            _metrics: Arc<NodeMetrics>,
         ) -> Box<dyn StreamHandlerPool> {
             self.make_parameters.lock().unwrap().push((
                 resolver,
@@ -493,6 +565,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized,
+            metrics: Arc::new(NodeMetrics::default()),
             crashable: false,
         };
 
@@ -516,6 +589,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            metrics: Arc::new(NodeMetrics::default()),
             crashable: true,
         });
 
@@ -533,6 +607,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            metrics: Arc::new(NodeMetrics::default()),
             crashable: false,
         });
     }
@@ -562,6 +637,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            metrics: Arc::new(NodeMetrics::default()),
             crashable: false,
         });
         subject.resolver_wrapper_factory = Box::new(resolver_wrapper_factory);
@@ -627,6 +703,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            metrics: Arc::new(NodeMetrics::default()),
             crashable: false,
         });
         let subject_addr: Addr<ProxyClient> = subject.start();
@@ -651,6 +728,7 @@ mod tests {
                 exit_service_rate: 0,
                 exit_byte_rate: 0,
                 is_decentralized: true,
+                metrics: Arc::new(NodeMetrics::default()),
                 crashable: false,
             });
             let subject_addr = subject.start();
@@ -692,6 +770,7 @@ mod tests {
                 exit_service_rate: 0,
                 exit_byte_rate: 0,
                 is_decentralized: true,
+                metrics: Arc::new(NodeMetrics::default()),
                 crashable: false,
             });
             subject.stream_contexts.insert(
@@ -784,6 +863,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            metrics: Arc::new(NodeMetrics::default()),
             crashable: false,
         });
         subject.resolver_wrapper_factory = Box::new(resolver_factory);
@@ -799,6 +879,149 @@ mod tests {
         assert_eq!(parameter, (request, Some(make_wallet("consuming")),));
     }
 
+    #[test]
+    fn served_stream_count_is_reported_to_neighborhood_as_streams_come_and_go() {
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let request = ClientRequestPayload_0v1 {
+            stream_key,
+            sequenced_packet: SequencedPacket {
+                data: b"inbound data".to_vec(),
+                sequence_number: 0,
+                last_data: false,
+            },
+            target_hostname: None,
+            target_port: 0,
+            protocol: ProxyProtocol::HTTP,
+            originator_public_key: PublicKey::new(&b"originator"[..]),
+        };
+        let key = make_meaningless_public_key();
+        let route = make_one_way_route_to_proxy_client(vec![&key, &key]);
+        let package = ExpiredCoresPackage::new(
+            SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+            Some(make_wallet("consuming")),
+            route,
+            request,
+            0,
+        );
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let neighborhood = neighborhood.system_stop_conditions(match_every_type_id!(
+            ServedStreamCountMessage,
+            ServedStreamCountMessage
+        ));
+        let system =
+            System::new("served_stream_count_is_reported_to_neighborhood_as_streams_come_and_go");
+        let peer_actors = peer_actors_builder().neighborhood(neighborhood).build();
+        let pool_factory =
+            StreamHandlerPoolFactoryMock::new().make_result(Box::new(StreamHandlerPoolMock::new()));
+        let resolver_factory =
+            ResolverWrapperFactoryMock::new().new_result(Box::new(ResolverWrapperMock::new()));
+        let mut subject = ProxyClient::new(ProxyClientConfig {
+            cryptde: main_cryptde(),
+            dns_servers: dnss(),
+            exit_service_rate: 100,
+            exit_byte_rate: 200,
+            is_decentralized: true,
+            metrics: Arc::new(NodeMetrics::default()),
+            crashable: false,
+        });
+        subject.resolver_wrapper_factory = Box::new(resolver_factory);
+        subject.stream_handler_pool_factory = Box::new(pool_factory);
+        let subject_addr: Addr<ProxyClient> = subject.start();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr.try_send(package.clone()).unwrap();
+        subject_addr.try_send(package).unwrap();
+        subject_addr
+            .try_send(InboundServerData {
+                stream_key,
+                last_data: true,
+                sequence_number: 0,
+                source: SocketAddr::from_str("4.3.2.1:80").unwrap(),
+                data: b"outbound data".to_vec(),
+            })
+            .unwrap();
+
+        system.run();
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(
+            neighborhood_recording.get_record::<ServedStreamCountMessage>(0),
+            &ServedStreamCountMessage { stream_count: 1 }
+        );
+        assert_eq!(
+            neighborhood_recording.get_record::<ServedStreamCountMessage>(1),
+            &ServedStreamCountMessage { stream_count: 0 }
+        );
+        assert_eq!(neighborhood_recording.len(), 2);
+    }
+
+    #[test]
+    fn new_rate_pack_changes_the_exit_rates_charged() {
+        init_test_logging();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let update_exit_rates_params_arc = Arc::new(Mutex::new(vec![]));
+        let pool =
+            StreamHandlerPoolMock::new().update_exit_rates_params(&update_exit_rates_params_arc);
+        let pool_factory = StreamHandlerPoolFactoryMock::new().make_result(Box::new(pool));
+        let resolver_factory =
+            ResolverWrapperFactoryMock::new().new_result(Box::new(ResolverWrapperMock::new()));
+        let system = System::new("new_rate_pack_changes_the_exit_rates_charged");
+        let mut subject = ProxyClient::new(ProxyClientConfig {
+            cryptde: main_cryptde(),
+            dns_servers: dnss(),
+            exit_service_rate: 100,
+            exit_byte_rate: 200,
+            is_decentralized: true,
+            metrics: Arc::new(NodeMetrics::default()),
+            crashable: false,
+        });
+        subject.resolver_wrapper_factory = Box::new(resolver_factory);
+        subject.stream_handler_pool_factory = Box::new(pool_factory);
+        subject.stream_contexts.insert(
+            stream_key.clone(),
+            StreamContext {
+                return_route: make_meaningless_route(),
+                payload_destination_key: PublicKey::new(&b"abcd"[..]),
+                paying_wallet: Some(make_wallet("paying")),
+            },
+        );
+        let subject_addr: Addr<ProxyClient> = subject.start();
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(ConfigChangeMsg {
+                change: ConfigChange::UpdateRatePack(RatePack {
+                    routing_byte_rate: 1,
+                    routing_service_rate: 2,
+                    exit_byte_rate: 301,
+                    exit_service_rate: 401,
+                }),
+            })
+            .unwrap();
+        subject_addr
+            .try_send(InboundServerData {
+                stream_key,
+                last_data: false,
+                sequence_number: 0,
+                source: SocketAddr::from_str("4.3.2.1:80").unwrap(),
+                data: b"data".to_vec(),
+            })
+            .unwrap();
+
+        System::current().stop_with_code(0);
+        system.run();
+        let update_exit_rates_params = update_exit_rates_params_arc.lock().unwrap();
+        assert_eq!(*update_exit_rates_params, vec![(401, 301)]);
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        let exit_report = accountant_recording.get_record::<ReportExitServiceProvidedMessage>(0);
+        assert_eq!(exit_report.service_rate, 401);
+        assert_eq!(exit_report.byte_rate, 301);
+        TestLogHandler::new().exists_log_containing(
+            "DEBUG: ProxyClient: Exit rates changed to 401 per CORES package and 301 per byte",
+        );
+    }
+
     #[test]
     fn refuse_to_provide_exit_services_with_no_paying_wallet() {
         init_test_logging();
@@ -841,6 +1064,7 @@ mod tests {
             exit_service_rate: rate_pack_exit(100),
             exit_byte_rate: rate_pack_exit_byte(100),
             is_decentralized: true,
+            metrics: Arc::new(NodeMetrics::default()),
             crashable: false,
         });
         subject.resolver_wrapper_factory = Box::new(resolver_factory);
@@ -908,6 +1132,7 @@ mod tests {
             exit_service_rate: rate_pack_exit(100),
             exit_byte_rate: rate_pack_exit_byte(100),
             is_decentralized: false,
+            metrics: Arc::new(NodeMetrics::default()),
             crashable: false,
         });
         subject.resolver_wrapper_factory = Box::new(resolver_factory);
@@ -939,6 +1164,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            metrics: Arc::new(NodeMetrics::default()),
             crashable: false,
         });
         subject.stream_contexts.insert(
@@ -1088,6 +1314,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            metrics: Arc::new(NodeMetrics::default()),
             crashable: false,
         });
         subject.stream_contexts.insert(
@@ -1140,6 +1367,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            metrics: Arc::new(NodeMetrics::default()),
             crashable: false,
         });
         subject.stream_contexts.insert(
@@ -1190,6 +1418,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            metrics: Arc::new(NodeMetrics::default()),
             crashable: false,
         });
         let mut process_package_params_arc = Arc::new(Mutex::new(vec![]));
@@ -1,6 +1,7 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 #![allow(proc_macro_derive_resolution_fallback)]
 
+use crate::metrics::{NodeMetrics, PROXY_CLIENT_POOL_LABEL};
 use crate::proxy_client::resolver_wrapper::ResolverWrapper;
 use crate::proxy_client::stream_establisher::StreamEstablisherFactoryReal;
 use crate::proxy_client::stream_establisher::{StreamEstablisher, StreamEstablisherFactory};
@@ -31,6 +32,7 @@ use trust_dns_resolver::lookup_ip::LookupIp;
 
 pub trait StreamHandlerPool {
     fn process_package(&self, payload: ClientRequestPayload_0v1, paying_wallet_opt: Option<Wallet>);
+    fn update_exit_rates(&self, exit_service_rate: u64, exit_byte_rate: u64);
 }
 
 pub struct StreamHandlerPoolReal {
@@ -48,6 +50,14 @@ struct StreamHandlerPoolRealInner {
     establisher_factory: Box<dyn StreamEstablisherFactory>,
     exit_service_rate: u64,
     exit_byte_rate: u64,
+    metrics: Arc<NodeMetrics>,
+}
+
+impl StreamHandlerPoolRealInner {
+    fn report_active_streams(&self) {
+        self.metrics
+            .set_active_streams(PROXY_CLIENT_POOL_LABEL, self.stream_writer_channels.len());
+    }
 }
 
 impl StreamHandlerPool for StreamHandlerPoolReal {
@@ -59,6 +69,12 @@ impl StreamHandlerPool for StreamHandlerPoolReal {
         self.do_housekeeping();
         Self::process_package(payload, paying_wallet_opt, self.inner.clone())
     }
+
+    fn update_exit_rates(&self, exit_service_rate: u64, exit_byte_rate: u64) {
+        let mut inner = self.inner.lock().expect("Stream handler pool is poisoned");
+        inner.exit_service_rate = exit_service_rate;
+        inner.exit_byte_rate = exit_byte_rate;
+    }
 }
 
 type StreamEstablisherResult =
@@ -72,6 +88,7 @@ impl StreamHandlerPoolReal {
         proxy_client_subs: ProxyClientSubs,
         exit_service_rate: u64,
         exit_byte_rate: u64,
+        metrics: Arc<NodeMetrics>,
     ) -> StreamHandlerPoolReal {
         let (stream_killer_tx, stream_killer_rx) = unbounded();
         let (stream_adder_tx, stream_adder_rx) = unbounded();
@@ -91,6 +108,7 @@ impl StreamHandlerPoolReal {
                 logger: Logger::new("ProxyClient"),
                 exit_service_rate,
                 exit_byte_rate,
+                metrics,
             })),
             stream_adder_rx,
             stream_killer_rx,
@@ -165,6 +183,7 @@ impl StreamHandlerPoolReal {
                 "Removing stream writer for {}",
                 sender_wrapper.peer_addr()
             );
+            inner.report_active_streams();
         }
         Self::send_terminating_package(
             stream_key,
@@ -198,8 +217,10 @@ impl StreamHandlerPoolReal {
                         "Trying to remove StreamWriter {:?}, but it's already gone", stream_key
                     ),
                 }
+                inner.report_active_streams();
             }
             if payload_size > 0 {
+                inner.metrics.add_exited_bytes(payload_size);
                 match paying_wallet_opt {
                     Some(wallet) => inner
                         .accountant_sub
@@ -454,6 +475,7 @@ impl StreamHandlerPoolReal {
                 ),
             }
         }
+        inner.report_active_streams();
     }
 
     fn add_new_streams(&self) {
@@ -474,10 +496,12 @@ impl StreamHandlerPoolReal {
                 }
             };
         }
+        inner.report_active_streams();
     }
 }
 
 pub trait StreamHandlerPoolFactory {
+    #[allow(clippy::too_many_arguments)]
     fn make(
         &self,
         resolver: Box<dyn ResolverWrapper>,
@@ -486,6 +510,7 @@ pub trait StreamHandlerPoolFactory {
         proxy_client_subs: ProxyClientSubs,
         exit_service_rate: u64,
         exit_byte_rate: u64,
+        metrics: Arc<NodeMetrics>,
     ) -> Box<dyn StreamHandlerPool>;
 }
 
@@ -500,6 +525,7 @@ impl StreamHandlerPoolFactory for StreamHandlerPoolFactoryReal {
         proxy_client_subs: ProxyClientSubs,
         exit_service_rate: u64,
         exit_byte_rate: u64,
+        metrics: Arc<NodeMetrics>,
     ) -> Box<dyn StreamHandlerPool> {
         Box::new(StreamHandlerPoolReal::new(
             resolver,
@@ -508,6 +534,7 @@ impl StreamHandlerPoolFactory for StreamHandlerPoolFactoryReal {
             proxy_client_subs,
             exit_service_rate,
             exit_byte_rate,
+            metrics,
         ))
     }
 }
@@ -615,6 +642,7 @@ mod tests {
                 }),
                 exit_service_rate: Default::default(),
                 exit_byte_rate: Default::default(),
+                metrics: Arc::new(NodeMetrics::default()),
             };
             let payload = ClientRequestPayload_0v1 {
                 stream_key,
@@ -681,6 +709,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                Arc::new(NodeMetrics::default()),
             );
             subject
                 .inner
@@ -742,6 +771,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                Arc::new(NodeMetrics::default()),
             );
             subject
                 .inner
@@ -824,6 +854,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                Arc::new(NodeMetrics::default()),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -933,6 +964,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                Arc::new(NodeMetrics::default()),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -1023,6 +1055,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                Arc::new(NodeMetrics::default()),
             );
 
             run_process_package_in_actix(subject, package);
@@ -1058,6 +1091,8 @@ mod tests {
         let expected_write_parameters = write_parameters.clone();
         let (proxy_client, proxy_client_awaiter, proxy_client_recording_arc) = make_recorder();
         let (accountant, accountant_awaiter, accountant_recording_arc) = make_recorder();
+        let metrics = Arc::new(NodeMetrics::default());
+        let metrics_inner = metrics.clone();
         let before = SystemTime::now();
         thread::spawn(move || {
             let peer_actors = peer_actors_builder()
@@ -1112,6 +1147,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                Arc::new(NodeMetrics::default()),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -1136,6 +1172,7 @@ mod tests {
                 inner.establisher_factory = Box::new(StreamEstablisherFactoryMock {
                     make_results: RefCell::new(vec![establisher]),
                 });
+                inner.metrics = metrics_inner;
             }
 
             run_process_package_in_actix(subject, package);
@@ -1143,6 +1180,9 @@ mod tests {
 
         proxy_client_awaiter.await_message_count(1);
         accountant_awaiter.await_message_count(1);
+        assert!(metrics
+            .render()
+            .contains("\nmasq_node_exited_bytes_total 19\n"));
         let after = SystemTime::now();
         assert_eq!(
             expected_lookup_ip_parameters.lock().unwrap().deref(),
@@ -1214,6 +1254,7 @@ mod tests {
                 peer_actors.proxy_client_opt.clone().unwrap(),
                 100,
                 200,
+                Arc::new(NodeMetrics::default()),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -1288,6 +1329,27 @@ mod tests {
         assert!(remaining_ips_3.is_empty());
     }
 
+    #[test]
+    fn update_exit_rates_changes_the_rates_charged_for_new_requests() {
+        let _system = System::new("update_exit_rates_changes_the_rates_charged_for_new_requests");
+        let peer_actors = peer_actors_builder().build();
+        let subject = StreamHandlerPoolReal::new(
+            Box::new(ResolverWrapperMock::new()),
+            main_cryptde(),
+            peer_actors.accountant.report_exit_service_provided.clone(),
+            peer_actors.proxy_client_opt.unwrap(),
+            100,
+            200,
+            Arc::new(NodeMetrics::default()),
+        );
+
+        subject.update_exit_rates(300, 400);
+
+        let inner = subject.inner.lock().unwrap();
+        assert_eq!(inner.exit_service_rate, 300);
+        assert_eq!(inner.exit_byte_rate, 400);
+    }
+
     #[test]
     fn wildcard_ip_resolves_in_dns_failure() {
         init_test_logging();
@@ -1333,6 +1395,7 @@ mod tests {
                 peer_actors.proxy_client_opt.clone().unwrap(),
                 100,
                 200,
+                Arc::new(NodeMetrics::default()),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -1443,6 +1506,7 @@ mod tests {
                 peer_actors.proxy_client_opt.clone().unwrap(),
                 100,
                 200,
+                Arc::new(NodeMetrics::default()),
             );
 
             let peer_addr = SocketAddr::from_str("3.4.5.6:80").unwrap();
@@ -1540,6 +1604,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                Arc::new(NodeMetrics::default()),
             );
             subject.inner.lock().unwrap().logger =
                 Logger::new("bad_dns_lookup_produces_log_and_sends_error_response");
@@ -1609,6 +1674,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                Arc::new(NodeMetrics::default()),
             );
             subject
                 .inner
@@ -1668,6 +1734,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                Arc::new(NodeMetrics::default()),
             );
 
             subject.inner.lock().unwrap().establisher_factory =
@@ -1704,6 +1771,7 @@ mod tests {
             peer_actors.proxy_client_opt.unwrap(),
             0,
             0,
+            Arc::new(NodeMetrics::default()),
         );
         let (stream_killer_tx, stream_killer_rx) = unbounded();
         subject.stream_killer_rx = stream_killer_rx;
@@ -1735,6 +1803,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn housekeeping_reports_active_streams_to_metrics() {
+        let system = System::new("test");
+        let peer_actors = peer_actors_builder().build();
+        let mut subject = StreamHandlerPoolReal::new(
+            Box::new(ResolverWrapperMock::new()),
+            main_cryptde(),
+            peer_actors.accountant.report_exit_service_provided,
+            peer_actors.proxy_client_opt.unwrap(),
+            0,
+            0,
+            Arc::new(NodeMetrics::default()),
+        );
+        let metrics = Arc::new(NodeMetrics::default());
+        let (stream_killer_tx, stream_killer_rx) = unbounded();
+        subject.stream_killer_rx = stream_killer_rx;
+        let (stream_adder_tx, stream_adder_rx) = unbounded();
+        subject.stream_adder_rx = stream_adder_rx;
+        let doomed_stream_key = StreamKey::make_meaningful_stream_key("doomed");
+        let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        {
+            let mut inner = subject.inner.lock().unwrap();
+            inner.metrics = metrics.clone();
+            inner.stream_writer_channels.insert(
+                doomed_stream_key,
+                Box::new(SenderWrapperMock::new(peer_addr)),
+            );
+        }
+        vec!["first", "second"].into_iter().for_each(|phrase| {
+            stream_adder_tx
+                .send((
+                    StreamKey::make_meaningful_stream_key(phrase),
+                    Box::new(SenderWrapperMock::new(peer_addr)),
+                ))
+                .unwrap()
+        });
+        stream_killer_tx.send((doomed_stream_key, 0)).unwrap();
+
+        subject.do_housekeeping();
+
+        System::current().stop_with_code(0);
+        system.run();
+        assert!(metrics
+            .render()
+            .contains("\nmasq_node_streams_active{pool=\"proxy_client\"} 2\n"));
+    }
+
     #[test]
     fn clean_up_dead_streams_does_not_send_server_drop_report_if_dead_stream_is_gone_already() {
         let system = System::new("test");
@@ -1747,6 +1862,7 @@ mod tests {
             peer_actors.proxy_client_opt.unwrap(),
             0,
             0,
+            Arc::new(NodeMetrics::default()),
         );
         let (stream_killer_tx, stream_killer_rx) = unbounded();
         subject.stream_killer_rx = stream_killer_rx;
@@ -5,6 +5,7 @@ pub mod http_protocol_pack;
 pub mod protocol_pack;
 pub mod server_impersonator_http;
 pub mod server_impersonator_tls;
+pub mod service_receipts;
 pub mod tls_protocol_pack;
 
 use crate::proxy_server::client_request_payload_factory::{
@@ -12,6 +13,7 @@ use crate::proxy_server::client_request_payload_factory::{
 };
 use crate::proxy_server::http_protocol_pack::HttpProtocolPack;
 use crate::proxy_server::protocol_pack::{from_ibcd, from_protocol, ProtocolPack};
+use crate::proxy_server::service_receipts::{ServiceReceipts, SERVICE_RECEIPT_INTERVAL};
 use crate::proxy_server::ExitServiceSearch::{Definite, ZeroHop};
 use crate::stream_messages::NonClandestineAttributes;
 use crate::stream_messages::RemovedStreamType;
@@ -32,7 +34,7 @@ use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_
 use crate::sub_lib::proxy_server::AddReturnRouteMessage;
 use crate::sub_lib::proxy_server::ProxyServerSubs;
 use crate::sub_lib::proxy_server::{
-    AddRouteResultMessage, ClientRequestPayload_0v1, ProxyProtocol,
+    AddRouteResultMessage, ClientRequestPayload_0v1, ProxyProtocol, TallyServicesMessage,
 };
 use crate::sub_lib::route::Route;
 use crate::sub_lib::stream_handler_pool::TransmitDataMsg;
@@ -41,6 +43,7 @@ use crate::sub_lib::ttl_hashmap::TtlHashMap;
 use crate::sub_lib::utils::{handle_ui_crash_request, NODE_MAILBOX_CAPACITY};
 use crate::sub_lib::wallet::Wallet;
 use actix::Addr;
+use actix::AsyncContext;
 use actix::Context;
 use actix::Handler;
 use actix::Recipient;
@@ -54,6 +57,7 @@ use std::net::SocketAddr;
 use std::rc::Rc;
 use std::time::{Duration, SystemTime};
 use tokio::prelude::Future;
+use web3::types::Address;
 
 pub const CRASH_KEY: &str = "PROXYSERVER";
 pub const RETURN_ROUTE_TTL: Duration = Duration::from_secs(120);
@@ -67,6 +71,7 @@ struct ProxyServerOutSubs {
     add_return_route: Recipient<AddReturnRouteMessage>,
     stream_shutdown_sub: Recipient<StreamShutdownMsg>,
     route_result_sub: Recipient<AddRouteResultMessage>,
+    tally_services: Recipient<TallyServicesMessage>,
 }
 
 pub struct ProxyServer {
@@ -86,6 +91,8 @@ pub struct ProxyServer {
     route_ids_to_return_routes: TtlHashMap<u32, AddReturnRouteMessage>,
     browser_proxy_sequence_offset: bool,
     inbound_client_data_helper_opt: Option<Box<dyn IBCDHelper>>,
+    service_receipts_opt: Option<ServiceReceipts>,
+    service_receipt_interval: Duration,
 }
 
 impl Actor for ProxyServer {
@@ -106,8 +113,14 @@ impl Handler<BindMessage> for ProxyServer {
             add_return_route: msg.peer_actors.proxy_server.add_return_route,
             stream_shutdown_sub: msg.peer_actors.proxy_server.stream_shutdown_sub,
             route_result_sub: msg.peer_actors.proxy_server.route_result_sub,
+            tally_services: msg.peer_actors.proxy_server.tally_services,
         };
         self.subs = Some(subs);
+        if self.service_receipts_opt.is_some() {
+            ctx.run_interval(self.service_receipt_interval, |act, _ctx| {
+                act.send_service_receipts(SystemTime::now())
+            });
+        }
     }
 }
 
@@ -212,6 +225,23 @@ impl Handler<StreamShutdownMsg> for ProxyServer {
     }
 }
 
+impl Handler<TallyServicesMessage> for ProxyServer {
+    type Result = ();
+
+    fn handle(&mut self, msg: TallyServicesMessage, _ctx: &mut Self::Context) -> Self::Result {
+        if let Some(service_receipts) = self.service_receipts_opt.as_mut() {
+            service_receipts.tally_over(
+                msg.return_route_id,
+                self.main_cryptde.public_key(),
+                &msg.expected_services,
+                msg.timestamp,
+                msg.exit_payload_size,
+                msg.routing_payload_size,
+            )
+        }
+    }
+}
+
 impl Handler<NodeFromUiMessage> for ProxyServer {
     type Result = ();
 
@@ -245,9 +275,20 @@ impl ProxyServer {
             route_ids_to_return_routes: TtlHashMap::new(RETURN_ROUTE_TTL),
             browser_proxy_sequence_offset: false,
             inbound_client_data_helper_opt: Some(Box::new(IBCDHelperReal::new())),
+            service_receipts_opt: None,
+            service_receipt_interval: SERVICE_RECEIPT_INTERVAL,
         }
     }
 
+    pub fn with_service_receipts(
+        mut self,
+        consuming_wallet: Wallet,
+        contract_address: Address,
+    ) -> Self {
+        self.service_receipts_opt = Some(ServiceReceipts::new(consuming_wallet, contract_address));
+        self
+    }
+
     pub fn make_subs_from(addr: &Addr<ProxyServer>) -> ProxyServerSubs {
         ProxyServerSubs {
             bind: recipient!(addr, BindMessage),
@@ -258,7 +299,24 @@ impl ProxyServer {
             stream_shutdown_sub: recipient!(addr, StreamShutdownMsg),
             node_from_ui: recipient!(addr, NodeFromUiMessage),
             route_result_sub: recipient!(addr, AddRouteResultMessage),
+            tally_services: recipient!(addr, TallyServicesMessage),
+        }
+    }
+
+    // Receipts are signed with the main CryptDE, since providers check that they were signed by
+    // the Node the route started from
+    fn send_service_receipts(&mut self, now: SystemTime) {
+        let packages = match self.service_receipts_opt.as_mut() {
+            Some(service_receipts) => service_receipts.make_packages(self.main_cryptde, now),
+            None => return,
+        };
+        if !packages.is_empty() {
+            debug!(self.logger, "Sending {} service receipts", packages.len());
         }
+        let hopper = &self.out_subs("Hopper").hopper;
+        packages
+            .into_iter()
+            .for_each(|package| hopper.try_send(package).expect("Hopper is dead"))
     }
 
     fn remove_dns_failure_retry(
@@ -670,6 +728,9 @@ impl ProxyServer {
                     &args.accountant_sub,
                     args.retire_stream_key_sub_opt.as_ref(),
                     args.is_decentralized,
+                    args.tally_services_sub_opt
+                        .as_ref()
+                        .map(|sub| (return_route_id, sub)),
                 )
             }
             _ => panic!("Expected RoundTrip ExpectedServices but got OneWay"),
@@ -752,6 +813,7 @@ impl ProxyServer {
         accountant_sub: &Recipient<ReportServicesConsumedMessage>,
         retire_stream_key_via: Option<&Recipient<StreamShutdownMsg>>,
         is_decentralized: bool,
+        tally_services_via: Option<(u32, &Recipient<TallyServicesMessage>)>,
     ) -> Result<(), String> {
         let destination_key_opt = if is_decentralized {
             expected_services.iter().find_map(|service| match service {
@@ -786,6 +848,17 @@ impl ProxyServer {
                     &payload_destination_key,
                 )
                 .expect("Key magically disappeared");
+                if let Some((return_route_id, tally_services_sub)) = tally_services_via {
+                    tally_services_sub
+                        .try_send(TallyServicesMessage {
+                            return_route_id,
+                            timestamp,
+                            expected_services: expected_services.clone(),
+                            exit_payload_size: payload_size,
+                            routing_payload_size: pkg.payload.len(),
+                        })
+                        .expect("ProxyServer is dead");
+                }
                 if is_decentralized {
                     let exit =
                         ProxyServer::report_on_exit_service(&expected_services, payload_size);
@@ -889,7 +962,7 @@ impl ProxyServer {
     }
 
     fn report_response_services_consumed(
-        &self,
+        &mut self,
         return_route_info: &AddReturnRouteMessage,
         exit_size: usize,
         routing_size: usize,
@@ -933,8 +1006,19 @@ impl ProxyServer {
                 _ => None,
             })
             .collect::<Vec<_>>();
+        let timestamp = SystemTime::now();
+        if let Some(service_receipts) = self.service_receipts_opt.as_mut() {
+            service_receipts.tally_back(
+                return_route_info.return_route_id,
+                self.main_cryptde.public_key(),
+                &return_route_info.expected_services,
+                timestamp,
+                exit_size,
+                routing_size,
+            )
+        }
         let report_message = ReportServicesConsumedMessage {
-            timestamp: SystemTime::now(),
+            timestamp,
             exit: exit_service_report,
             routing_payload_size: routing_size,
             routing: routing_service_reports,
@@ -1145,6 +1229,7 @@ pub struct TryTransmitToHopperArgs {
     pub dispatcher_sub: Recipient<TransmitDataMsg>,
     pub accountant_sub: Recipient<ReportServicesConsumedMessage>,
     pub add_return_route_sub: Recipient<AddReturnRouteMessage>,
+    pub tally_services_sub_opt: Option<Recipient<TallyServicesMessage>>,
 }
 
 impl TryTransmitToHopperArgs {
@@ -1180,6 +1265,10 @@ impl TryTransmitToHopperArgs {
                 .add_return_route
                 .clone(),
             is_decentralized: proxy_server.is_decentralized,
+            tally_services_sub_opt: proxy_server
+                .service_receipts_opt
+                .as_ref()
+                .map(|_| proxy_server.out_subs("ProxyServer").tally_services.clone()),
         }
     }
 }
@@ -1238,7 +1327,7 @@ mod tests {
     use crate::proxy_server::server_impersonator_http::ServerImpersonatorHttp;
     use crate::proxy_server::server_impersonator_tls::ServerImpersonatorTls;
     use crate::stream_messages::{NonClandestineAttributes, RemovedStreamType};
-    use crate::sub_lib::accountant::RoutingServiceConsumed;
+    use crate::sub_lib::accountant::{RoutingServiceConsumed, ServiceReceipt_0v1};
     use crate::sub_lib::cryptde::{decodex, CryptData};
     use crate::sub_lib::cryptde::{encodex, PlainData};
     use crate::sub_lib::cryptde_null::CryptDENull;
@@ -1379,6 +1468,7 @@ mod tests {
             add_return_route: recipient!(addr, AddReturnRouteMessage),
             stream_shutdown_sub: recipient!(addr, StreamShutdownMsg),
             route_result_sub: recipient!(addr, AddRouteResultMessage),
+            tally_services: recipient!(addr, TallyServicesMessage),
         }
     }
 
@@ -1434,6 +1524,7 @@ mod tests {
                 public_key: cryptde.public_key().clone(),
                 payer: None,
                 component: Component::ProxyServer,
+                originator_key_opt: None,
             },
         )
         .unwrap()
@@ -2699,6 +2790,7 @@ mod tests {
             accountant_sub: peer_actors.accountant.report_services_consumed,
             add_return_route_sub: peer_actors.proxy_server.add_return_route,
             retire_stream_key_sub_opt: None,
+            tally_services_sub_opt: None,
         };
 
         let result = ProxyServer::try_transmit_to_hopper(tth_args, route_query_response);
@@ -2742,6 +2834,149 @@ mod tests {
         assert_eq!(result, Ok(()));
     }
 
+    #[test]
+    fn try_transmit_to_hopper_tallies_services_over_if_directed_to_do_so() {
+        let cryptde = main_cryptde();
+        let now = SystemTime::now();
+        let (proxy_server_mock, _, proxy_server_recording_arc) = make_recorder();
+        let (hopper_mock, _, hopper_recording_arc) = make_recorder();
+        let over = vec![
+            ExpectedService::Nothing,
+            ExpectedService::Exit(
+                PublicKey::new(&[3]),
+                make_wallet("exit earning wallet"),
+                rate_pack(103),
+            ),
+        ];
+        let route_query_response = RouteQueryResponse {
+            route: make_meaningless_route(),
+            expected_services: ExpectedServices::RoundTrip(
+                over.clone(),
+                vec![
+                    ExpectedService::Exit(
+                        PublicKey::new(&[3]),
+                        make_wallet("exit earning wallet"),
+                        rate_pack(103),
+                    ),
+                    ExpectedService::Nothing,
+                ],
+                7,
+            ),
+        };
+        let expected_data = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n".to_vec();
+        let exit_payload_size = expected_data.len();
+        let system =
+            System::new("try_transmit_to_hopper_tallies_services_over_if_directed_to_do_so");
+        let peer_actors = peer_actors_builder()
+            .hopper(hopper_mock)
+            .proxy_server(proxy_server_mock)
+            .build();
+        let payload = ClientRequestPayload_0v1 {
+            stream_key: StreamKey::make_meaningless_stream_key(),
+            sequenced_packet: SequencedPacket::new(expected_data, 0, false),
+            target_hostname: Some("nowhere.com".to_string()),
+            target_port: HTTP_PORT,
+            protocol: ProxyProtocol::HTTP,
+            originator_public_key: PublicKey::new(b"originator_public_key"),
+        };
+        let tth_args = TryTransmitToHopperArgs {
+            main_cryptde: cryptde,
+            payload,
+            client_addr: SocketAddr::from_str("1.2.3.4:5678").unwrap(),
+            timestamp: now,
+            is_decentralized: true,
+            logger: Logger::new("test"),
+            hopper_sub: peer_actors.hopper.from_hopper_client,
+            dispatcher_sub: peer_actors.dispatcher.from_dispatcher_client,
+            accountant_sub: peer_actors.accountant.report_services_consumed,
+            add_return_route_sub: peer_actors.proxy_server.add_return_route,
+            retire_stream_key_sub_opt: None,
+            tally_services_sub_opt: Some(peer_actors.proxy_server.tally_services),
+        };
+
+        let result = ProxyServer::try_transmit_to_hopper(tth_args, route_query_response);
+
+        System::current().stop();
+        system.run();
+        assert_eq!(result, Ok(()));
+        let recording = hopper_recording_arc.lock().unwrap();
+        let routing_payload_size = recording
+            .get_record::<IncipientCoresPackage>(0)
+            .payload
+            .len();
+        let recording = proxy_server_recording_arc.lock().unwrap();
+        let _ = recording.get_record::<AddReturnRouteMessage>(0);
+        assert_eq!(
+            recording.get_record::<TallyServicesMessage>(1),
+            &TallyServicesMessage {
+                return_route_id: 7,
+                timestamp: now,
+                expected_services: over,
+                exit_payload_size,
+                routing_payload_size,
+            }
+        );
+        assert_eq!(recording.len(), 2);
+    }
+
+    #[test]
+    fn proxy_server_with_service_receipts_sends_tallied_receipts_periodically() {
+        let cryptde = main_cryptde();
+        let consuming_wallet = make_paying_wallet(b"consuming wallet");
+        let exit_wallet = make_wallet("exit earning wallet");
+        let exit_key = PublicKey::new(b"exit");
+        let (hopper_mock, _, hopper_recording_arc) = make_recorder();
+        let hopper_mock =
+            hopper_mock.system_stop_conditions(match_every_type_id!(IncipientCoresPackage));
+        let system =
+            System::new("proxy_server_with_service_receipts_sends_tallied_receipts_periodically");
+        let mut subject = ProxyServer::new(
+            cryptde,
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            false,
+        )
+        .with_service_receipts(consuming_wallet.clone(), TEST_DEFAULT_CHAIN.rec().contract);
+        subject.service_receipt_interval = Duration::from_millis(10);
+        let subject_addr = subject.start();
+        let peer_actors = peer_actors_builder().hopper(hopper_mock).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(TallyServicesMessage {
+                return_route_id: 5,
+                timestamp: SystemTime::now(),
+                expected_services: vec![
+                    ExpectedService::Nothing,
+                    ExpectedService::Exit(exit_key.clone(), exit_wallet.clone(), rate_pack(10)),
+                ],
+                exit_payload_size: 100,
+                routing_payload_size: 150,
+            })
+            .unwrap();
+
+        system.run();
+        let recording = hopper_recording_arc.lock().unwrap();
+        let package = recording.get_record::<IncipientCoresPackage>(0);
+        let exit_cryptde = CryptDENull::from(&exit_key, TEST_DEFAULT_CHAIN);
+        let receipt = match decodex::<MessageType>(&exit_cryptde, &package.payload).unwrap() {
+            MessageType::ServiceReceipt(vd) => ServiceReceipt_0v1::try_from(vd).unwrap(),
+            x => panic!("Expected ServiceReceipt, got {:?}", x),
+        };
+        assert_eq!(receipt.consumer_key, cryptde.public_key().clone());
+        assert_eq!(
+            receipt.consuming_wallet,
+            Wallet::from(consuming_wallet.address())
+        );
+        assert_eq!(receipt.provider_wallet, exit_wallet);
+        assert_eq!(receipt.route_id, 5);
+        assert_eq!(receipt.services, 1);
+        assert_eq!(receipt.bytes, 100);
+        assert_eq!(receipt.amount_wei, 410 + 13 * 100);
+        assert!(receipt.verify(cryptde));
+    }
+
     #[test]
     fn try_transmit_to_hopper_orders_stream_shutdown_if_directed_to_do_so() {
         let cryptde = main_cryptde();
@@ -2784,6 +3019,7 @@ mod tests {
             accountant_sub: peer_actors.accountant.report_services_consumed,
             add_return_route_sub: peer_actors.proxy_server.add_return_route,
             retire_stream_key_sub_opt: Some(peer_actors.proxy_server.stream_shutdown_sub),
+            tally_services_sub_opt: None,
         };
 
         let result = ProxyServer::try_transmit_to_hopper(tth_args, route_query_response);
@@ -3067,6 +3303,7 @@ mod tests {
             accountant_sub: peer_actors.accountant.report_services_consumed,
             add_return_route_sub: peer_actors.proxy_server.add_return_route,
             retire_stream_key_sub_opt: None,
+            tally_services_sub_opt: None,
         };
 
         let _result = ProxyServer::try_transmit_to_hopper(tth_args, route_result);
@@ -3078,7 +3315,7 @@ mod tests {
     ) {
         let main_cryptde = main_cryptde();
         let alias_cryptde = alias_cryptde();
-        let subject = ProxyServer::new(
+        let mut subject = ProxyServer::new(
             main_cryptde,
             alias_cryptde,
             true,
@@ -5683,6 +5920,7 @@ mod tests {
             accountant_sub: recipient!(&addr, ReportServicesConsumedMessage),
             add_return_route_sub: recipient!(&addr, AddReturnRouteMessage),
             retire_stream_key_sub_opt: None,
+            tally_services_sub_opt: None,
         };
         let subject = RouteQueryResponseResolverReal {};
         let system = System::new("resolve_message_handles_mailbox_error_from_neighborhood");
@@ -0,0 +1,380 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::accountant::ServiceReceipt_0v1;
+use crate::sub_lib::cryptde::{CryptDE, CryptData, PublicKey};
+use crate::sub_lib::dispatcher::Component;
+use crate::sub_lib::hopper::IncipientCoresPackage;
+use crate::sub_lib::neighborhood::ExpectedService;
+use crate::sub_lib::route::{Route, RouteSegment};
+use crate::sub_lib::wallet::Wallet;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+use web3::types::Address;
+
+pub const SERVICE_RECEIPT_INTERVAL: Duration = Duration::from_secs(60);
+// Tallies whose last receipt went out and that have seen no service for this long are dropped
+pub const IDLE_TALLY_RETENTION: Duration = Duration::from_secs(3_600);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Tally {
+    path: Vec<PublicKey>,
+    first_service: SystemTime,
+    last_service: SystemTime,
+    services: u64,
+    bytes: u64,
+    amount_wei: u128,
+    unreported: bool,
+}
+
+// What this Node has consumed from each provider, per route, for the receipts it signs
+pub struct ServiceReceipts {
+    consuming_wallet: Wallet,
+    contract_address: Address,
+    tallies: HashMap<(u32, Wallet), Tally>,
+}
+
+impl ServiceReceipts {
+    pub fn new(consuming_wallet: Wallet, contract_address: Address) -> Self {
+        Self {
+            consuming_wallet,
+            contract_address,
+            tallies: HashMap::new(),
+        }
+    }
+
+    // On the way out, the expected services start with this Node's
+    pub fn tally_over(
+        &mut self,
+        route_id: u32,
+        our_key: &PublicKey,
+        expected_services: &[ExpectedService],
+        timestamp: SystemTime,
+        exit_size: usize,
+        routing_size: usize,
+    ) {
+        expected_services
+            .iter()
+            .enumerate()
+            .for_each(|(index, service)| {
+                let path = expected_services[..=index]
+                    .iter()
+                    .map(|service| key_of(service, our_key))
+                    .collect::<Vec<_>>();
+                self.tally_service(route_id, &path, service, timestamp, exit_size, routing_size)
+            })
+    }
+
+    // On the way back, they end with it
+    pub fn tally_back(
+        &mut self,
+        route_id: u32,
+        our_key: &PublicKey,
+        expected_services: &[ExpectedService],
+        timestamp: SystemTime,
+        exit_size: usize,
+        routing_size: usize,
+    ) {
+        expected_services
+            .iter()
+            .enumerate()
+            .for_each(|(index, service)| {
+                let path = expected_services[index..]
+                    .iter()
+                    .rev()
+                    .map(|service| key_of(service, our_key))
+                    .collect::<Vec<_>>();
+                self.tally_service(route_id, &path, service, timestamp, exit_size, routing_size)
+            })
+    }
+
+    fn tally_service(
+        &mut self,
+        route_id: u32,
+        path: &[PublicKey],
+        service: &ExpectedService,
+        timestamp: SystemTime,
+        exit_size: usize,
+        routing_size: usize,
+    ) {
+        let charge = |service_rate: u64, byte_rate: u64, payload_size: usize| {
+            service_rate as u128 + byte_rate as u128 * payload_size as u128
+        };
+        match service {
+            ExpectedService::Routing(_, wallet, rate_pack) => self.tally(
+                route_id,
+                wallet,
+                path,
+                timestamp,
+                routing_size,
+                charge(
+                    rate_pack.routing_service_rate,
+                    rate_pack.routing_byte_rate,
+                    routing_size,
+                ),
+            ),
+            ExpectedService::Exit(_, wallet, rate_pack) => self.tally(
+                route_id,
+                wallet,
+                path,
+                timestamp,
+                exit_size,
+                charge(
+                    rate_pack.exit_service_rate,
+                    rate_pack.exit_byte_rate,
+                    exit_size,
+                ),
+            ),
+            ExpectedService::Nothing => (),
+        }
+    }
+
+    // The path leads from this Node to the provider; it's where the receipts will travel
+    fn tally(
+        &mut self,
+        route_id: u32,
+        provider_wallet: &Wallet,
+        path: &[PublicKey],
+        timestamp: SystemTime,
+        payload_size: usize,
+        amount_wei: u128,
+    ) {
+        let tally = self
+            .tallies
+            .entry((route_id, provider_wallet.clone()))
+            .or_insert_with(|| Tally {
+                path: path.to_vec(),
+                first_service: timestamp,
+                last_service: timestamp,
+                services: 0,
+                bytes: 0,
+                amount_wei: 0,
+                unreported: false,
+            });
+        tally.first_service = tally.first_service.min(timestamp);
+        tally.last_service = tally.last_service.max(timestamp);
+        tally.services += 1;
+        tally.bytes += payload_size as u64;
+        tally.amount_wei += amount_wei;
+        tally.unreported = true;
+    }
+
+    pub fn make_packages(
+        &mut self,
+        cryptde: &dyn CryptDE,
+        now: SystemTime,
+    ) -> Vec<IncipientCoresPackage> {
+        self.tallies.retain(|_, tally| {
+            tally.unreported
+                || now.duration_since(tally.last_service).unwrap_or_default() < IDLE_TALLY_RETENTION
+        });
+        let consuming_wallet = &self.consuming_wallet;
+        let contract_address = self.contract_address;
+        self.tallies
+            .iter_mut()
+            .filter(|(_, tally)| tally.unreported)
+            .map(|((route_id, provider_wallet), tally)| {
+                tally.unreported = false;
+                let receipt = ServiceReceipt_0v1 {
+                    consumer_key: cryptde.public_key().clone(),
+                    consuming_wallet: Wallet::from(consuming_wallet.address()),
+                    provider_wallet: provider_wallet.clone(),
+                    route_id: *route_id,
+                    first_service_s: tally
+                        .first_service
+                        .duration_since(SystemTime::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs(),
+                    services: tally.services,
+                    bytes: tally.bytes,
+                    amount_wei: tally.amount_wei,
+                    signature: CryptData::new(&[]),
+                }
+                .signed(cryptde);
+                let route = Route::one_way_from_originator(
+                    RouteSegment::new(tally.path.iter().collect(), Component::Accountant),
+                    cryptde,
+                    Some(consuming_wallet.clone()),
+                    Some(contract_address),
+                )
+                .expect("Couldn't make route for service receipt");
+                IncipientCoresPackage::new(
+                    cryptde,
+                    route,
+                    receipt.into(),
+                    tally.path.last().expect("Service receipt path is empty"),
+                )
+                .expect("Couldn't package service receipt")
+            })
+            .collect()
+    }
+}
+
+fn key_of(service: &ExpectedService, our_key: &PublicKey) -> PublicKey {
+    match service {
+        ExpectedService::Routing(key, _, _) | ExpectedService::Exit(key, _, _) => key.clone(),
+        ExpectedService::Nothing => our_key.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sub_lib::cryptde::decodex;
+    use crate::sub_lib::cryptde_null::CryptDENull;
+    use crate::sub_lib::hopper::MessageType;
+    use crate::test_utils::{main_cryptde, make_paying_wallet, make_wallet, rate_pack};
+    use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
+    use std::convert::TryFrom;
+
+    fn at(seconds: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    fn extract_receipt(package: &IncipientCoresPackage) -> ServiceReceipt_0v1 {
+        let provider_cryptde = CryptDENull::from(&PublicKey::new(b"provider"), TEST_DEFAULT_CHAIN);
+        let message_type: MessageType = decodex(&provider_cryptde, &package.payload).unwrap();
+        match message_type {
+            MessageType::ServiceReceipt(vd) => ServiceReceipt_0v1::try_from(vd).unwrap(),
+            x => panic!("Expected ServiceReceipt, got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(SERVICE_RECEIPT_INTERVAL, Duration::from_secs(60));
+        assert_eq!(IDLE_TALLY_RETENTION, Duration::from_secs(3_600));
+    }
+
+    #[test]
+    fn services_are_tallied_with_paths_from_this_node_in_both_directions() {
+        let our_key = PublicKey::new(b"us");
+        let router_key = PublicKey::new(b"router");
+        let exit_key = PublicKey::new(b"exit");
+        let router_wallet = make_wallet("router");
+        let exit_wallet = make_wallet("exit");
+        let over = vec![
+            ExpectedService::Nothing,
+            ExpectedService::Routing(router_key.clone(), router_wallet.clone(), rate_pack(10)),
+            ExpectedService::Exit(exit_key.clone(), exit_wallet.clone(), rate_pack(20)),
+        ];
+        let back = vec![
+            ExpectedService::Exit(exit_key.clone(), exit_wallet.clone(), rate_pack(20)),
+            ExpectedService::Routing(router_key.clone(), router_wallet.clone(), rate_pack(10)),
+            ExpectedService::Nothing,
+        ];
+        let mut subject = ServiceReceipts::new(
+            make_paying_wallet(b"consumer"),
+            TEST_DEFAULT_CHAIN.rec().contract,
+        );
+
+        subject.tally_over(3, &our_key, &over, at(1_000), 100, 150);
+        subject.tally_back(3, &our_key, &back, at(1_001), 1_000, 1_050);
+
+        let router_tally = subject.tallies.get(&(3, router_wallet)).unwrap();
+        assert_eq!(router_tally.path, vec![our_key.clone(), router_key.clone()]);
+        assert_eq!(router_tally.services, 2);
+        assert_eq!(router_tally.bytes, 150 + 1_050);
+        assert_eq!(
+            router_tally.amount_wei,
+            2 * 210 + 11 * (150 + 1_050) as u128
+        );
+        let exit_tally = subject.tallies.get(&(3, exit_wallet)).unwrap();
+        assert_eq!(exit_tally.path, vec![our_key, router_key, exit_key]);
+        assert_eq!(exit_tally.services, 2);
+        assert_eq!(exit_tally.bytes, 100 + 1_000);
+        assert_eq!(exit_tally.amount_wei, 2 * 420 + 23 * (100 + 1_000) as u128);
+        assert_eq!(exit_tally.first_service, at(1_000));
+        assert_eq!(exit_tally.last_service, at(1_001));
+        assert_eq!(subject.tallies.len(), 2);
+    }
+
+    #[test]
+    fn tallies_are_cumulative_and_signed_into_receipts_along_their_path() {
+        let cryptde = main_cryptde();
+        let consuming_wallet = make_paying_wallet(b"consumer");
+        let provider_wallet = make_wallet("provider");
+        let contract_address = TEST_DEFAULT_CHAIN.rec().contract;
+        let path = vec![
+            cryptde.public_key().clone(),
+            PublicKey::new(b"router"),
+            PublicKey::new(b"provider"),
+        ];
+        let mut subject = ServiceReceipts::new(consuming_wallet.clone(), contract_address);
+        subject.tally(7, &provider_wallet, &path, at(1_000), 100, 1_234);
+        subject.tally(7, &provider_wallet, &path, at(900), 200, u64::MAX as u128);
+
+        let result = subject.make_packages(cryptde, at(1_100));
+
+        assert_eq!(result.len(), 1);
+        let receipt = extract_receipt(&result[0]);
+        assert_eq!(
+            receipt,
+            ServiceReceipt_0v1 {
+                consumer_key: cryptde.public_key().clone(),
+                consuming_wallet: Wallet::from(consuming_wallet.address()),
+                provider_wallet,
+                route_id: 7,
+                first_service_s: 900,
+                services: 2,
+                bytes: 300,
+                amount_wei: u64::MAX as u128 + 1_234,
+                signature: receipt.signature.clone(),
+            }
+        );
+        assert!(receipt.verify(cryptde));
+        let expected_route = Route::one_way_from_originator(
+            RouteSegment::new(path.iter().collect(), Component::Accountant),
+            cryptde,
+            Some(consuming_wallet),
+            Some(contract_address),
+        )
+        .unwrap();
+        assert_eq!(result[0].route, expected_route);
+    }
+
+    #[test]
+    fn only_tallies_with_new_services_are_reported_again() {
+        let cryptde = main_cryptde();
+        let first_wallet = make_wallet("first");
+        let second_wallet = make_wallet("second");
+        let path = vec![cryptde.public_key().clone(), PublicKey::new(b"provider")];
+        let mut subject = ServiceReceipts::new(
+            make_paying_wallet(b"consumer"),
+            TEST_DEFAULT_CHAIN.rec().contract,
+        );
+        subject.tally(1, &first_wallet, &path, at(1_000), 10, 100);
+        subject.tally(1, &second_wallet, &path, at(1_000), 10, 100);
+        let first_round = subject.make_packages(cryptde, at(1_010));
+        subject.tally(1, &second_wallet, &path, at(1_020), 10, 100);
+
+        let second_round = subject.make_packages(cryptde, at(1_030));
+        let third_round = subject.make_packages(cryptde, at(1_040));
+
+        assert_eq!(first_round.len(), 2);
+        assert_eq!(second_round.len(), 1);
+        let receipt = extract_receipt(&second_round[0]);
+        assert_eq!(receipt.provider_wallet, second_wallet);
+        assert_eq!(receipt.services, 2);
+        assert_eq!(receipt.amount_wei, 200);
+        assert!(third_round.is_empty());
+    }
+
+    #[test]
+    fn idle_tallies_are_forgotten_once_reported() {
+        let cryptde = main_cryptde();
+        let wallet = make_wallet("provider");
+        let path = vec![cryptde.public_key().clone(), PublicKey::new(b"provider")];
+        let mut subject = ServiceReceipts::new(
+            make_paying_wallet(b"consumer"),
+            TEST_DEFAULT_CHAIN.rec().contract,
+        );
+        subject.tally(1, &wallet, &path, at(1_000), 10, 100);
+        let late = at(1_000) + IDLE_TALLY_RETENTION;
+        let _ = subject.make_packages(cryptde, late);
+        assert_eq!(subject.tallies.len(), 1);
+
+        let _ = subject.make_packages(cryptde, late + Duration::from_secs(1));
+
+        assert!(subject.tallies.is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::apps::{app_config_dumper, app_daemon, app_node};
+use crate::node_configurator::{load_chain_registry_from_context, DirsWrapper, DirsWrapperReal};
 use crate::privilege_drop::{PrivilegeDropper, PrivilegeDropperReal};
 use crate::run_modes::Leaving::{ExitCode, Not};
 use crate::run_modes_factories::{
@@ -24,6 +25,7 @@ enum Mode {
 
 pub struct RunModes {
     privilege_dropper: Box<dyn PrivilegeDropper>,
+    dirs_wrapper: Box<dyn DirsWrapper>,
     runner: Box<dyn Runner>,
 }
 
@@ -37,6 +39,7 @@ impl RunModes {
     pub fn new() -> Self {
         Self {
             privilege_dropper: Box::new(PrivilegeDropperReal::new()),
+            dirs_wrapper: Box::new(DirsWrapperReal::default()),
             runner: Box::new(RunnerReal::new()),
         }
     }
@@ -46,6 +49,10 @@ impl RunModes {
             Enter(mode) => mode,
             Leave(exit_code) => return exit_code,
         };
+        if let Err(e) = load_chain_registry_from_context(self.dirs_wrapper.as_ref()) {
+            Self::process_gathered_errors(e, streams);
+            return 1;
+        }
 
         match match mode {
             Mode::DumpConfig => self.runner.dump_config(args, streams),
@@ -283,15 +290,18 @@ impl From<ConfiguratorError> for RunnerError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::node_test_utils::DirsWrapperMock;
     use crate::run_modes_factories::mocks::{
         DaemonInitializerFactoryMock, DaemonInitializerMock, DumpConfigRunnerFactoryMock,
         DumpConfigRunnerMock, ServerInitializerFactoryMock, ServerInitializerMock,
     };
     use crate::server_initializer::test_utils::PrivilegeDropperMock;
     use masq_lib::test_utils::fake_stream_holder::FakeStreamHolder;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
     use masq_lib::utils::slice_of_strs_to_vec_of_strings;
     use regex::Regex;
     use std::cell::RefCell;
+    use std::fs;
     use std::ops::{Deref, Not};
     use std::sync::{Arc, Mutex};
     use time::OffsetDateTime;
@@ -482,6 +492,39 @@ parm2 - msg2\n"
         )
     }
 
+    #[test]
+    fn go_refuses_to_run_with_malformed_chain_definitions() {
+        let home_dir = ensure_node_home_directory_exists(
+            "run_modes",
+            "go_refuses_to_run_with_malformed_chain_definitions",
+        );
+        let data_dir = home_dir.join(".local").join("share");
+        let masq_dir = data_dir.join("MASQ");
+        fs::create_dir_all(&masq_dir).unwrap();
+        fs::write(masq_dir.join("chains.json"), "[{\"chainId\": 5}]").unwrap();
+        let dump_config_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = RunModes::new();
+        subject.runner = Box::new(RunnerMock::new().dump_config_params(&dump_config_params_arc));
+        subject.privilege_dropper =
+            Box::new(PrivilegeDropperMock::new().expect_privilege_result(true));
+        subject.dirs_wrapper = Box::new(
+            DirsWrapperMock::new()
+                .home_dir_result(Some(home_dir))
+                .data_dir_result(Some(data_dir)),
+        );
+        let mut holder = FakeStreamHolder::new();
+
+        let result = subject.go(&["--dump-config".to_string()], &mut holder.streams());
+
+        assert_eq!(result, 1);
+        assert_eq!(
+            &holder.stderr.get_string(),
+            "Configuration error\n\
+chain - Chain definitions are malformed: missing field `identifier` at line 1 column 15\n"
+        );
+        assert!(dump_config_params_arc.lock().unwrap().is_empty());
+    }
+
     #[test]
     fn run_node_hands_in_an_error_from_go() {
         let go_params_arc = Arc::new(Mutex::new(vec![]));
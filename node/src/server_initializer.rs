@@ -6,24 +6,35 @@ use crate::entry_dns::dns_socket_server::DnsSocketServer;
 use crate::node_configurator::node_configurator_standard::server_initializer_collected_params;
 use crate::node_configurator::{DirsWrapper, DirsWrapperReal};
 use crate::run_modes_factories::{RunModeResult, ServerInitializer};
+use crate::sub_lib::crash_report::{
+    crash_already_reported, last_log_lines, take_crashing_actor, write_crash_report,
+    CRASH_REPORT_LOG_LINES,
+};
 use crate::sub_lib::socket_server::ConfiguredByPrivilege;
 use backtrace::Backtrace;
-use clap::value_t;
+use clap::{crate_version, value_t};
 use flexi_logger::{
-    Cleanup, Criterion, DeferredNow, Duplicate, LevelFilter, LogSpecBuilder, Logger, Naming, Record,
+    Age, Cleanup, Criterion, DeferredNow, Duplicate, LevelFilter, LogSpecBuilder, Logger, Naming,
+    Record,
 };
 use futures::try_ready;
 use lazy_static::lazy_static;
 use log::{log, Level};
 use masq_lib::command::StdStreams;
+use masq_lib::constants::{DEFAULT_LOG_RETENTION_FILES, DEFAULT_LOG_ROTATION_SIZE_MB};
 use masq_lib::logger;
-use masq_lib::logger::{real_format_function, POINTER_TO_FORMAT_FUNCTION};
+use masq_lib::logger::{
+    json_format_function, real_format_function, FormatFunction, POINTER_TO_FORMAT_FUNCTION,
+};
+use masq_lib::messages::UiCrashReport;
 use masq_lib::shared_schema::ConfiguratorError;
 use std::any::Any;
 use std::io;
 use std::panic::{Location, PanicInfo};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
 use time::OffsetDateTime;
 use tokio::prelude::{Async, Future};
 
@@ -123,12 +134,100 @@ lazy_static! {
     pub static ref LOGFILE_NAME: Mutex<PathBuf> = Mutex::new(PathBuf::from("uninitialized"));
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("Unrecognized log format: '{}'", s)),
+        }
+    }
+}
+
+impl LogFormat {
+    pub fn format_function(&self) -> FormatFunction {
+        match self {
+            LogFormat::Text => real_format_function,
+            LogFormat::Json => json_format_function,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogRotationAge {
+    Never,
+    Hourly,
+    Daily,
+}
+
+impl FromStr for LogRotationAge {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "never" => Ok(LogRotationAge::Never),
+            "hourly" => Ok(LogRotationAge::Hourly),
+            "daily" => Ok(LogRotationAge::Daily),
+            _ => Err(format!("Unrecognized log rotation age: '{}'", s)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LogFileConfig {
+    pub format: LogFormat,
+    pub rotation_size_mb: u64,
+    pub rotation_age: LogRotationAge,
+    pub retention_files: usize,
+    pub compression: bool,
+}
+
+impl Default for LogFileConfig {
+    fn default() -> Self {
+        LogFileConfig {
+            format: LogFormat::Text,
+            rotation_size_mb: DEFAULT_LOG_ROTATION_SIZE_MB,
+            rotation_age: LogRotationAge::Never,
+            retention_files: DEFAULT_LOG_RETENTION_FILES as usize,
+            compression: true,
+        }
+    }
+}
+
+impl LogFileConfig {
+    fn criterion(&self) -> Criterion {
+        let size = self.rotation_size_mb * 1_000_000;
+        match self.rotation_age {
+            LogRotationAge::Never => Criterion::Size(size),
+            LogRotationAge::Hourly => Criterion::AgeOrSize(Age::Hour, size),
+            LogRotationAge::Daily => Criterion::AgeOrSize(Age::Day, size),
+        }
+    }
+
+    fn cleanup(&self) -> Cleanup {
+        if self.compression {
+            Cleanup::KeepZipFiles(self.retention_files)
+        } else {
+            Cleanup::KeepLogFiles(self.retention_files)
+        }
+    }
+}
+
 pub trait LoggerInitializerWrapper {
     fn init(
         &mut self,
         file_path: PathBuf,
         real_user: &RealUser,
         log_level: LevelFilter,
+        log_file_config: &LogFileConfig,
         discriminant_opt: Option<&str>,
     );
 }
@@ -141,6 +240,7 @@ impl LoggerInitializerWrapper for LoggerInitializerWrapperReal {
         file_path: PathBuf,
         real_user: &RealUser,
         log_level: LevelFilter,
+        log_file_config: &LogFileConfig,
         discriminant_opt: Option<&str>,
     ) {
         let mut logger = Logger::with(
@@ -157,9 +257,9 @@ impl LoggerInitializerWrapper for LoggerInitializerWrapperReal {
         .suppress_timestamp()
         .format(format_function)
         .rotate(
-            Criterion::Size(100_000_000),
+            log_file_config.criterion(),
             Naming::Numbers,
-            Cleanup::KeepZipFiles(50),
+            log_file_config.cleanup(),
         );
         if let Some(discriminant) = discriminant_opt {
             logger = logger.discriminant(discriminant);
@@ -179,12 +279,19 @@ impl LoggerInitializerWrapper for LoggerInitializerWrapperReal {
             panic_hook(AltPanicInfo::from(panic_info))
         }));
 
+        if log_file_config.format == LogFormat::Json {
+            // A raw heading would be the only line in the logfile that isn't a JSON object
+            unsafe {
+                POINTER_TO_FORMAT_FUNCTION = json_format_function;
+            }
+        }
+
         // Info level is not shown within the log
         log!(Level::Info, "{}", logger::Logger::log_file_heading());
 
         unsafe {
             // This resets the format function after specialized formatting for the log heading is used.
-            POINTER_TO_FORMAT_FUNCTION = real_format_function;
+            POINTER_TO_FORMAT_FUNCTION = log_file_config.format.format_function();
         }
     }
 }
@@ -256,6 +363,34 @@ fn panic_hook(panic_info: AltPanicInfo) {
     error!(logger, "{} - {}", location, message);
     let backtrace = Backtrace::new();
     error!(logger, "{:?}", backtrace);
+    let logfile = LoggerInitializerWrapperReal::get_logfile_name();
+    if let Some(data_directory) = logfile
+        .parent()
+        .filter(|_| logfile.is_file())
+        .filter(|data_directory| !crash_already_reported(data_directory))
+    {
+        let report = UiCrashReport {
+            timestamp_s: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|since_epoch| since_epoch.as_secs())
+                .unwrap_or(0),
+            process_id: std::process::id(),
+            version: crate_version!().to_string(),
+            panic_message: message,
+            location,
+            actor_opt: take_crashing_actor(),
+            backtrace: format!("{:?}", backtrace),
+            log_lines: last_log_lines(&logfile, CRASH_REPORT_LOG_LINES),
+        };
+        if let Err(e) = write_crash_report(data_directory, &report) {
+            error!(
+                logger,
+                "Could not write crash report into {}: {}",
+                data_directory.display(),
+                e
+            );
+        }
+    }
 }
 
 // DeferredNow can't be constructed in a test; therefore this function is untestable.
@@ -272,7 +407,7 @@ fn format_function(
 pub mod test_utils {
     use crate::bootstrapper::RealUser;
     use crate::privilege_drop::PrivilegeDropper;
-    use crate::server_initializer::LoggerInitializerWrapper;
+    use crate::server_initializer::{LogFileConfig, LoggerInitializerWrapper};
     use log::LevelFilter;
     use std::cell::RefCell;
     use std::path::{Path, PathBuf};
@@ -340,8 +475,16 @@ pub mod test_utils {
         }
     }
 
+    pub type LoggerInitParams = (
+        PathBuf,
+        RealUser,
+        LevelFilter,
+        LogFileConfig,
+        Option<String>,
+    );
+
     pub struct LoggerInitializerWrapperMock {
-        init_parameters: Arc<Mutex<Vec<(PathBuf, RealUser, LevelFilter, Option<String>)>>>,
+        init_parameters: Arc<Mutex<Vec<LoggerInitParams>>>,
     }
 
     impl LoggerInitializerWrapper for LoggerInitializerWrapperMock {
@@ -350,12 +493,14 @@ pub mod test_utils {
             file_path: PathBuf,
             real_user: &RealUser,
             log_level: LevelFilter,
+            log_file_config: &LogFileConfig,
             name_segment: Option<&str>,
         ) {
             self.init_parameters.lock().unwrap().push((
                 file_path,
                 real_user.clone(),
                 log_level,
+                *log_file_config,
                 match name_segment {
                     Some(s) => Some(s.to_string()),
                     None => None,
@@ -371,10 +516,7 @@ pub mod test_utils {
             }
         }
 
-        pub fn init_parameters(
-            mut self,
-            parameters: &Arc<Mutex<Vec<(PathBuf, RealUser, LevelFilter, Option<String>)>>>,
-        ) -> Self {
+        pub fn init_parameters(mut self, parameters: &Arc<Mutex<Vec<LoggerInitParams>>>) -> Self {
             self.init_parameters = parameters.clone();
             self
         }
@@ -388,6 +530,7 @@ pub mod tests {
     use crate::crash_test_dummy::CrashTestDummy;
     use crate::node_test_utils::DirsWrapperMock;
     use crate::server_initializer::test_utils::PrivilegeDropperMock;
+    use crate::sub_lib::crash_report::read_crash_report;
     use crate::test_utils::logfile_name_guard::LogfileNameGuard;
     use crate::test_utils::unshared_test_utils::make_pre_populated_mocked_directory_wrapper;
     use masq_lib::constants::DEFAULT_CHAIN;
@@ -398,6 +541,7 @@ pub mod tests {
         ByteArrayReader, ByteArrayWriter, FakeStreamHolder,
     };
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
     use masq_lib::utils::slice_of_strs_to_vec_of_strings;
     use std::cell::RefCell;
     use std::ops::Not;
@@ -550,6 +694,85 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn log_format_is_parsed_case_insensitively() {
+        assert_eq!(LogFormat::from_str("text"), Ok(LogFormat::Text));
+        assert_eq!(LogFormat::from_str("JSON"), Ok(LogFormat::Json));
+        assert_eq!(
+            LogFormat::from_str("xml"),
+            Err("Unrecognized log format: 'xml'".to_string())
+        );
+    }
+
+    #[test]
+    fn log_format_selects_format_function() {
+        assert_eq!(
+            LogFormat::Text.format_function() as usize,
+            real_format_function as usize
+        );
+        assert_eq!(
+            LogFormat::Json.format_function() as usize,
+            json_format_function as usize
+        );
+    }
+
+    #[test]
+    fn log_rotation_age_is_parsed_case_insensitively() {
+        assert_eq!(LogRotationAge::from_str("never"), Ok(LogRotationAge::Never));
+        assert_eq!(
+            LogRotationAge::from_str("Hourly"),
+            Ok(LogRotationAge::Hourly)
+        );
+        assert_eq!(LogRotationAge::from_str("daily"), Ok(LogRotationAge::Daily));
+        assert_eq!(
+            LogRotationAge::from_str("weekly"),
+            Err("Unrecognized log rotation age: 'weekly'".to_string())
+        );
+    }
+
+    #[test]
+    fn log_file_config_defaults_to_former_fixed_behavior() {
+        let subject = LogFileConfig::default();
+
+        assert_eq!(
+            subject,
+            LogFileConfig {
+                format: LogFormat::Text,
+                rotation_size_mb: 100,
+                rotation_age: LogRotationAge::Never,
+                retention_files: 50,
+                compression: true,
+            }
+        );
+        assert_eq!(format!("{:?}", subject.criterion()), "Size(100000000)");
+        assert_eq!(format!("{:?}", subject.cleanup()), "KeepZipFiles(50)");
+    }
+
+    #[test]
+    fn log_file_config_rotates_by_age_or_size_and_keeps_plain_files_without_compression() {
+        let hourly = LogFileConfig {
+            format: LogFormat::Json,
+            rotation_size_mb: 5,
+            rotation_age: LogRotationAge::Hourly,
+            retention_files: 7,
+            compression: false,
+        };
+        let daily = LogFileConfig {
+            rotation_age: LogRotationAge::Daily,
+            ..hourly
+        };
+
+        assert_eq!(
+            format!("{:?}", hourly.criterion()),
+            "AgeOrSize(Hour, 5000000)"
+        );
+        assert_eq!(
+            format!("{:?}", daily.criterion()),
+            "AgeOrSize(Day, 5000000)"
+        );
+        assert_eq!(format!("{:?}", hourly.cleanup()), "KeepLogFiles(7)");
+    }
+
     #[test]
     fn combine_results_combines_success_and_success() {
         let initial_success: RunModeResult = Ok(());
@@ -678,6 +901,80 @@ pub mod tests {
         tlh.exists_log_containing("ERROR: PanicHandler: file.txt:24:42 - I'm just a string slice");
     }
 
+    #[test]
+    fn panic_hook_writes_crash_report_next_to_the_logfile() {
+        let data_directory = ensure_node_home_directory_exists(
+            "server_initializer",
+            "panic_hook_writes_crash_report_next_to_the_logfile",
+        );
+        let logfile = data_directory.join("MASQNode_rCURRENT.log");
+        std::fs::write(&logfile, "first line\nlast line\n").unwrap();
+        let _guard = LogfileNameGuard::new(&logfile);
+        let panic_info = AltPanicInfo {
+            payload: &"Something broke",
+            location: Some(AltLocation {
+                file: "file.txt".to_string(),
+                line: 24,
+                col: 42,
+            }),
+        };
+
+        panic_hook(panic_info);
+
+        let report = read_crash_report(&data_directory).unwrap();
+        assert_eq!(report.process_id, std::process::id());
+        assert_eq!(report.version, crate_version!().to_string());
+        assert_eq!(report.panic_message, "Something broke".to_string());
+        assert_eq!(report.location, "file.txt:24:42".to_string());
+        assert!(report.backtrace.contains("panic_hook"));
+        assert_eq!(
+            report.log_lines[0..2].to_vec(),
+            vec!["first line".to_string(), "last line".to_string()]
+        );
+    }
+
+    #[test]
+    fn panic_hook_keeps_the_crash_report_about_the_first_panic_of_the_process() {
+        let data_directory = ensure_node_home_directory_exists(
+            "server_initializer",
+            "panic_hook_keeps_the_crash_report_about_the_first_panic_of_the_process",
+        );
+        let logfile = data_directory.join("MASQNode_rCURRENT.log");
+        std::fs::write(&logfile, "only line\n").unwrap();
+        let _guard = LogfileNameGuard::new(&logfile);
+        let first_panic_info = AltPanicInfo {
+            payload: &"First thing broke",
+            location: None,
+        };
+        let second_panic_info = AltPanicInfo {
+            payload: &"Second thing broke in consequence",
+            location: None,
+        };
+
+        panic_hook(first_panic_info);
+        panic_hook(second_panic_info);
+
+        let report = read_crash_report(&data_directory).unwrap();
+        assert_eq!(report.panic_message, "First thing broke".to_string());
+    }
+
+    #[test]
+    fn panic_hook_writes_no_crash_report_before_logging_starts() {
+        let data_directory = ensure_node_home_directory_exists(
+            "server_initializer",
+            "panic_hook_writes_no_crash_report_before_logging_starts",
+        );
+        let _guard = LogfileNameGuard::new(&data_directory.join("MASQNode_rCURRENT.log"));
+        let panic_info = AltPanicInfo {
+            payload: &"Something broke",
+            location: None,
+        };
+
+        panic_hook(panic_info);
+
+        assert_eq!(read_crash_report(&data_directory), None);
+    }
+
     #[test]
     fn exits_after_all_socket_servers_exit() {
         let _ = LogfileNameGuard::new(&PathBuf::from("uninitialized"));
@@ -1,9 +1,11 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use crate::bootstrapper::PortConfiguration;
+use crate::bootstrapper::{ClandestineWebSocketMode, PortConfiguration};
+use crate::clandestine_port_hopper::ClandestinePortHopper;
 use crate::discriminator::DiscriminatorFactory;
 use crate::json_masquerader::JsonMasquerader;
 use crate::masquerader::Masquerader;
+use crate::metrics::{NodeMetrics, STREAM_HANDLER_POOL_LABEL};
 use crate::stream_messages::*;
 use crate::stream_reader::StreamReaderReal;
 use crate::stream_writer_sorted::StreamWriterSorted;
@@ -22,6 +24,7 @@ use crate::sub_lib::neighborhood::{
 };
 use crate::sub_lib::neighborhood::{DispatcherNodeQueryMessage, ZERO_RATE_PACK};
 use crate::sub_lib::node_addr::NodeAddr;
+use crate::sub_lib::peer_actors::NewClandestinePorts;
 use crate::sub_lib::sequence_buffer::SequencedPacket;
 use crate::sub_lib::stream_connector::ConnectionInfo;
 use crate::sub_lib::stream_connector::StreamConnector;
@@ -31,6 +34,7 @@ use crate::sub_lib::stream_handler_pool::TransmitDataMsg;
 use crate::sub_lib::tokio_wrappers::ReadHalfWrapper;
 use crate::sub_lib::tokio_wrappers::WriteHalfWrapper;
 use crate::sub_lib::utils::{handle_ui_crash_request, MessageScheduler, NODE_MAILBOX_CAPACITY};
+use crate::websocket_masquerader::{WebSocketMasquerader, WebSocketRole};
 use actix::Addr;
 use actix::Context;
 use actix::Handler;
@@ -43,6 +47,7 @@ use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::io;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::prelude::Future;
 
@@ -108,6 +113,7 @@ impl Display for StreamWriterKey {
 // It is used to store streams for both neighbors and browser.
 pub struct StreamHandlerPool {
     stream_writers: HashMap<StreamWriterKey, Option<Box<dyn SenderWrapper<SequencedPacket>>>>,
+    websocket_roles: HashMap<StreamWriterKey, WebSocketRole>,
     dispatcher_subs_opt: Option<DispatcherSubs>,
     self_subs_opt: Option<StreamHandlerPoolSubs>,
     ask_neighborhood_opt: Option<Recipient<DispatcherNodeQueryMessage>>,
@@ -118,7 +124,11 @@ pub struct StreamHandlerPool {
     stream_connector: Box<dyn StreamConnector>,
     channel_factory: Box<dyn FuturesChannelFactory<SequencedPacket>>,
     clandestine_discriminator_factories: Vec<Box<dyn DiscriminatorFactory>>,
+    clandestine_websocket_mode: ClandestineWebSocketMode,
     traffic_analyzer: Box<dyn TrafficAnalyzer>,
+    clandestine_port_hopper_opt: Option<ClandestinePortHopper>,
+    new_ports_subs: Vec<Recipient<NewClandestinePorts>>,
+    metrics: Arc<NodeMetrics>,
 }
 
 impl Actor for StreamHandlerPool {
@@ -178,6 +188,8 @@ impl Handler<PoolBindMessage> for StreamHandlerPool {
         self.ask_neighborhood_opt = Some(msg.neighborhood_subs.dispatcher_node_query);
         self.remove_neighbor_sub_opt = Some(msg.neighborhood_subs.remove_neighbor);
         self.connection_progress_sub_opt = Some(msg.neighborhood_subs.connection_progress_sub);
+        self.new_ports_subs = msg.new_ports_subs;
+        self.open_clandestine_ports(ctx);
     }
 }
 
@@ -192,10 +204,14 @@ impl Handler<NodeFromUiMessage> for StreamHandlerPool {
 impl StreamHandlerPool {
     pub fn new(
         clandestine_discriminator_factories: Vec<Box<dyn DiscriminatorFactory>>,
+        clandestine_websocket_mode: ClandestineWebSocketMode,
+        clandestine_port_hopper_opt: Option<ClandestinePortHopper>,
+        metrics: Arc<NodeMetrics>,
         crashable: bool,
     ) -> StreamHandlerPool {
         StreamHandlerPool {
             stream_writers: HashMap::new(),
+            websocket_roles: HashMap::new(),
             dispatcher_subs_opt: None,
             self_subs_opt: None,
             ask_neighborhood_opt: None,
@@ -206,7 +222,11 @@ impl StreamHandlerPool {
             stream_connector: Box::new(StreamConnectorReal {}),
             channel_factory: Box::new(FuturesChannelFactoryReal {}),
             clandestine_discriminator_factories,
+            clandestine_websocket_mode,
             traffic_analyzer: Box::new(TrafficAnalyzerReal {}),
+            clandestine_port_hopper_opt,
+            new_ports_subs: vec![],
+            metrics,
         }
     }
 
@@ -225,6 +245,43 @@ impl StreamHandlerPool {
         }
     }
 
+    fn open_clandestine_ports(&mut self, ctx: &mut Context<Self>) {
+        let add_stream_sub = self
+            .self_subs_opt
+            .as_ref()
+            .expect("StreamHandlerPool is unbound")
+            .add_sub
+            .clone();
+        let hopper = match self.clandestine_port_hopper_opt.as_mut() {
+            Some(hopper) => hopper,
+            None => return,
+        };
+        let advertised_ports = hopper.advertised_ports();
+        let opened_ports = hopper.open(&add_stream_sub);
+        let rotation_interval_opt = hopper.rotation_interval_opt();
+        if opened_ports != advertised_ports {
+            // Some extra ports couldn't be opened as advertised; tell everyone what we really have
+            self.publish_clandestine_ports(opened_ports);
+        }
+        if let Some(interval) = rotation_interval_opt {
+            ctx.run_interval(interval, move |pool, _ctx| {
+                if let Some(hopper) = pool.clandestine_port_hopper_opt.as_mut() {
+                    let new_ports = hopper.rotate(&add_stream_sub);
+                    pool.publish_clandestine_ports(new_ports);
+                }
+            });
+        }
+    }
+
+    fn publish_clandestine_ports(&self, ports: Vec<u16>) {
+        self.new_ports_subs.iter().for_each(|sub| {
+            sub.try_send(NewClandestinePorts {
+                ports: ports.clone(),
+            })
+            .expect("NewClandestinePorts recipient is dead")
+        });
+    }
+
     fn set_up_stream_reader(
         &mut self,
         read_stream: Box<dyn ReadHalfWrapper>,
@@ -286,6 +343,7 @@ impl StreamHandlerPool {
         let (tx, rx) = self.channel_factory.make(peer_addr);
         self.stream_writers
             .insert(StreamWriterKey::from(peer_addr), Some(tx));
+        self.report_active_streams();
 
         if is_clandestine {
             tokio::spawn(StreamWriterUnsorted::new(write_stream, peer_addr, rx));
@@ -350,6 +408,11 @@ impl StreamHandlerPool {
         msg: AddStreamMsg,
     ) -> <Self as Handler<AddStreamMsg>>::Result {
         let port_config = msg.port_configuration.clone();
+        let sw_key = StreamWriterKey::from(msg.connection_info.peer_addr);
+        match port_config.websocket_role_opt {
+            Some(role) => self.websocket_roles.insert(sw_key, role),
+            None => self.websocket_roles.remove(&sw_key),
+        };
         self.set_up_stream_writer(
             msg.connection_info.writer,
             msg.connection_info.peer_addr,
@@ -373,10 +436,12 @@ impl StreamHandlerPool {
             msg.peer_addr,
             stream_writer_key
         );
+        self.websocket_roles.remove(&stream_writer_key);
         let report_to_counterpart = match self.stream_writers.remove(&stream_writer_key) {
             None | Some(None) => false,
             Some(Some(_sender_wrapper)) => true,
         };
+        self.report_active_streams();
         let stream_shutdown_msg = StreamShutdownMsg {
             peer_addr: msg.peer_addr,
             stream_type: msg.stream_type,
@@ -416,7 +481,7 @@ impl StreamHandlerPool {
             panic!("Neighborhood has returned a NodeDescriptor with no ports. This indicates an unrecoverable error.")
         }
 
-        // TODO: Picking the first port is a temporary hack. This problem should go away with clandestine ports.
+        // If the first port can't be connected, the StreamStartFailureHandler will try the others.
         let peer_addr = SocketAddr::new(node_addr.ip_addr(), node_addr.ports()[0]);
 
         let sw_key = StreamWriterKey::from(peer_addr);
@@ -442,6 +507,16 @@ impl StreamHandlerPool {
         }
     }
 
+    fn report_active_streams(&self) {
+        let open_writer_count = self
+            .stream_writers
+            .values()
+            .filter(|writer_opt| writer_opt.is_some())
+            .count();
+        self.metrics
+            .set_active_streams(STREAM_HANDLER_POOL_LABEL, open_writer_count);
+    }
+
     fn send_or_queue_packet(
         &mut self,
         msg: DispatcherNodeQueryResponse,
@@ -456,6 +531,9 @@ impl StreamHandlerPool {
                 if remove_stream_writer {
                     self.stream_writers
                         .remove(&StreamWriterKey::from(peer_addr));
+                    self.websocket_roles
+                        .remove(&StreamWriterKey::from(peer_addr));
+                    self.report_active_streams();
                 }
             }
             Some(None) => self.delay_packet_for_opening_stream(msg, peer_addr, sw_key),
@@ -493,7 +571,9 @@ impl StreamHandlerPool {
         );
         debug!(self.logger, "Masking {} bytes", msg.context.data.len());
         let packet = if msg.context.sequence_number.is_none() {
-            let masquerader = self.traffic_analyzer.get_masquerader();
+            let masquerader = self
+                .traffic_analyzer
+                .get_masquerader(self.websocket_roles.get(&sw_key).copied());
             match masquerader.mask(msg.context.data.as_slice()) {
                 Ok(masked_data) => SequencedPacket::new(masked_data, 0, false),
                 Err(e) => {
@@ -585,9 +665,13 @@ impl StreamHandlerPool {
         let failure_handler = StreamStartFailureHandler::new(self, &msg, peer_addr);
         let success_handler = StreamStartSuccessHandler::new(self, msg, peer_addr);
 
-        let connect_future = self
-            .stream_connector
-            .connect(peer_addr, &self.logger)
+        let connection_future = if self.clandestine_websocket_mode.connects_via_websocket() {
+            self.stream_connector
+                .connect_websocket(peer_addr, &self.logger)
+        } else {
+            self.stream_connector.connect(peer_addr, &self.logger)
+        };
+        let connect_future = connection_future
             .map(move |connection_info| success_handler.handle(connection_info))
             .map_err(move |err| {
                 // connection was unsuccessful
@@ -608,6 +692,8 @@ struct StreamStartFailureHandler {
     pub logger: Logger,
     pub peer_addr: SocketAddr,
     pub dispatcher_sub: Recipient<StreamShutdownMsg>,
+    pub retry_msg_opt: Option<DispatcherNodeQueryResponse>,
+    pub node_query_response_sub: Recipient<DispatcherNodeQueryResponse>,
 }
 
 impl StreamStartFailureHandler {
@@ -644,17 +730,12 @@ impl StreamStartFailureHandler {
                 .expect("Dispatcher is dead")
                 .stream_shutdown_sub
                 .clone(),
+            retry_msg_opt: Self::make_retry_msg(msg, peer_addr),
+            node_query_response_sub: subs.node_query_response,
         }
     }
 
     pub fn handle(self, err: io::Error) {
-        error!(
-            self.logger,
-            "Stream to {} does not exist and could not be connected; discarding {} bytes: {}",
-            self.peer_addr,
-            self.msg_data_len,
-            err
-        );
         self.remove_sub
             .try_send(RemoveStreamMsg {
                 peer_addr: self.peer_addr,
@@ -663,6 +744,25 @@ impl StreamStartFailureHandler {
                 dispatcher_sub: self.dispatcher_sub,
             })
             .expect("StreamHandlerPool is dead");
+        if let Some(retry_msg) = self.retry_msg_opt {
+            warning!(
+                self.logger,
+                "Stream to {} could not be connected; trying the neighbor's other clandestine ports: {}",
+                self.peer_addr,
+                err
+            );
+            self.node_query_response_sub
+                .try_send(retry_msg)
+                .expect("StreamHandlerPool is dead");
+            return;
+        }
+        error!(
+            self.logger,
+            "Stream to {} does not exist and could not be connected; discarding {} bytes: {}",
+            self.peer_addr,
+            self.msg_data_len,
+            err
+        );
         let remove_node_message = RemoveNeighborMessage {
             public_key: self.key.clone(),
         };
@@ -677,6 +777,29 @@ impl StreamStartFailureHandler {
             .try_send(connection_progress_message)
             .expect("Neighborhood is dead");
     }
+
+    // A Node with several clandestine ports may still be reachable on one we haven't tried yet.
+    fn make_retry_msg(
+        msg: &DispatcherNodeQueryResponse,
+        peer_addr: SocketAddr,
+    ) -> Option<DispatcherNodeQueryResponse> {
+        let metadata = msg.result.as_ref()?;
+        let node_addr = metadata.node_addr_opt.as_ref()?;
+        let remaining_ports = node_addr
+            .ports()
+            .into_iter()
+            .filter(|port| *port != peer_addr.port())
+            .collect::<Vec<u16>>();
+        if remaining_ports.is_empty() {
+            return None;
+        }
+        let mut retry_msg = msg.clone();
+        retry_msg.result = Some(NodeQueryResponseMetadata {
+            node_addr_opt: Some(NodeAddr::new(&node_addr.ip_addr(), &remaining_ports)),
+            ..metadata.clone()
+        });
+        Some(retry_msg)
+    }
 }
 
 struct StreamStartSuccessHandler {
@@ -686,6 +809,7 @@ struct StreamStartSuccessHandler {
     pub connection_progress_sub_ok: Recipient<ConnectionProgressMessage>,
     pub logger: Logger,
     pub clandestine_discriminator_factories: Vec<Box<dyn DiscriminatorFactory>>,
+    pub websocket_role_opt: Option<WebSocketRole>,
     pub peer_addr: SocketAddr,
 }
 
@@ -709,6 +833,11 @@ impl StreamStartSuccessHandler {
                 .expect("Neighborhood Unbound"),
             logger: pool.logger.clone(),
             clandestine_discriminator_factories: pool.clandestine_discriminator_factories.clone(),
+            websocket_role_opt: if pool.clandestine_websocket_mode.connects_via_websocket() {
+                Some(WebSocketRole::Client)
+            } else {
+                None
+            },
             peer_addr,
         }
     }
@@ -723,10 +852,11 @@ impl StreamStartSuccessHandler {
             .try_send(AddStreamMsg {
                 connection_info,
                 origin_port: Some(origin_port),
-                port_configuration: PortConfiguration::new(
-                    self.clandestine_discriminator_factories,
-                    true,
-                ),
+                port_configuration: PortConfiguration {
+                    discriminator_factories: self.clandestine_discriminator_factories,
+                    is_clandestine: true,
+                    websocket_role_opt: self.websocket_role_opt,
+                },
             })
             .expect("StreamHandlerPool is dead");
         self.node_query_response_sub
@@ -743,14 +873,17 @@ impl StreamStartSuccessHandler {
 }
 
 trait TrafficAnalyzer {
-    fn get_masquerader(&self) -> Box<dyn Masquerader>;
+    fn get_masquerader(&self, websocket_role_opt: Option<WebSocketRole>) -> Box<dyn Masquerader>;
 }
 
 struct TrafficAnalyzerReal {}
 
 impl TrafficAnalyzer for TrafficAnalyzerReal {
-    fn get_masquerader(&self) -> Box<dyn Masquerader> {
-        Box::new(JsonMasquerader::new())
+    fn get_masquerader(&self, websocket_role_opt: Option<WebSocketRole>) -> Box<dyn Masquerader> {
+        match websocket_role_opt {
+            Some(role) => Box::new(WebSocketMasquerader::new(role)),
+            None => Box::new(JsonMasquerader::new()),
+        }
     }
 }
 
@@ -781,6 +914,7 @@ mod tests {
     use crate::test_utils::tokio_wrapper_mocks::WriteHalfWrapperMock;
     use crate::test_utils::unshared_test_utils::prove_that_crash_request_handler_is_hooked_up;
     use crate::test_utils::{await_messages, make_send_error};
+    use crate::websocket_discriminator_factory::WebSocketDiscriminatorFactory;
     use actix::Actor;
     use actix::Addr;
     use actix::System;
@@ -808,7 +942,10 @@ mod tests {
     struct TrafficAnalyzerMock {}
 
     impl TrafficAnalyzer for TrafficAnalyzerMock {
-        fn get_masquerader(&self) -> Box<dyn Masquerader> {
+        fn get_masquerader(
+            &self,
+            _websocket_role_opt: Option<WebSocketRole>,
+        ) -> Box<dyn Masquerader> {
             Box::new(FailingMasquerader {})
         }
     }
@@ -837,7 +974,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                ClandestineWebSocketMode::Off,
+                None,
+                Arc::new(NodeMetrics::default()),
+                false,
+            );
             subject.stream_connector = Box::new(StreamConnectorMock::new());
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -849,6 +992,7 @@ mod tests {
                     dispatcher_subs: peer_actors.dispatcher,
                     stream_handler_pool_subs: subject_subs.clone(),
                     neighborhood_subs: peer_actors.neighborhood,
+                    new_ports_subs: vec![],
                 })
                 .unwrap();
 
@@ -958,7 +1102,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(
+                vec![],
+                ClandestineWebSocketMode::Off,
+                None,
+                Arc::new(NodeMetrics::default()),
+                false,
+            );
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -969,6 +1119,7 @@ mod tests {
                     dispatcher_subs: peer_actors.dispatcher,
                     stream_handler_pool_subs: subject_subs.clone(),
                     neighborhood_subs: peer_actors.neighborhood,
+                    new_ports_subs: vec![],
                 })
                 .unwrap();
 
@@ -1022,7 +1173,13 @@ mod tests {
         thread::spawn(move || {
             let system = System::new("test");
 
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                ClandestineWebSocketMode::Off,
+                None,
+                Arc::new(NodeMetrics::default()),
+                false,
+            );
             subject.stream_connector = Box::new(
                 StreamConnectorMock::new()
                     .connect_pair_result(Err(Error::from(ErrorKind::ConnectionRefused))),
@@ -1036,6 +1193,7 @@ mod tests {
                     dispatcher_subs: peer_actors.dispatcher,
                     stream_handler_pool_subs: subject_subs.clone(),
                     neighborhood_subs: peer_actors.neighborhood,
+                    new_ports_subs: vec![],
                 })
                 .unwrap();
 
@@ -1126,8 +1284,13 @@ mod tests {
         thread::spawn(move || {
             let system = System::new("test");
 
-            let mut subject =
-                StreamHandlerPool::new(vec![Box::new(JsonDiscriminatorFactory {})], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![Box::new(JsonDiscriminatorFactory {})],
+                ClandestineWebSocketMode::Off,
+                None,
+                Arc::new(NodeMetrics::default()),
+                false,
+            );
             subject.stream_connector = Box::new(StreamConnectorMock::new().connection(
                 local_addr,
                 peer_addr,
@@ -1143,6 +1306,7 @@ mod tests {
                     dispatcher_subs: peer_actors.dispatcher.clone(),
                     stream_handler_pool_subs: subject_subs.clone(),
                     neighborhood_subs: peer_actors.neighborhood,
+                    new_ports_subs: vec![],
                 })
                 .unwrap();
 
@@ -1199,7 +1363,13 @@ mod tests {
         let (recorder, _, recording_arc) = make_recorder();
         let system = System::new("test");
         let sub = recorder.start().recipient::<StreamShutdownMsg>();
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            ClandestineWebSocketMode::Off,
+            None,
+            Arc::new(NodeMetrics::default()),
+            false,
+        );
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let local_addr = SocketAddr::from_str("127.0.0.1:0").unwrap();
         let sw_key = StreamWriterKey::from(peer_addr);
@@ -1230,12 +1400,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn handle_remove_stream_msg_reports_remaining_open_streams_to_metrics() {
+        let (recorder, _, _) = make_recorder();
+        let system = System::new("test");
+        let sub = recorder.start().recipient::<StreamShutdownMsg>();
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            ClandestineWebSocketMode::Off,
+            None,
+            Arc::new(NodeMetrics::default()),
+            false,
+        );
+        let metrics = Arc::new(NodeMetrics::default());
+        subject.metrics = metrics.clone();
+        let local_addr = SocketAddr::from_str("127.0.0.1:0").unwrap();
+        let removed_peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        vec!["1.2.3.4:5678", "2.3.4.5:6789"]
+            .into_iter()
+            .for_each(|peer_addr| {
+                subject.stream_writers.insert(
+                    StreamWriterKey::from(SocketAddr::from_str(peer_addr).unwrap()),
+                    Some(Box::new(SenderWrapperMock::new(local_addr))),
+                );
+            });
+        subject.stream_writers.insert(
+            StreamWriterKey::from(SocketAddr::from_str("3.4.5.6:7890").unwrap()),
+            None,
+        );
+
+        subject.handle_remove_stream_msg(RemoveStreamMsg {
+            peer_addr: removed_peer_addr,
+            local_addr,
+            stream_type: RemovedStreamType::Clandestine,
+            dispatcher_sub: sub,
+        });
+
+        System::current().stop_with_code(0);
+        system.run();
+        assert!(metrics
+            .render()
+            .contains("\nmasq_node_streams_active{pool=\"stream_handler_pool\"} 1\n"));
+    }
+
     #[test]
     fn handle_remove_stream_msg_handles_no_report_to_counterpart_scenario() {
         let (recorder, _, recording_arc) = make_recorder();
         let system = System::new("test");
         let sub = recorder.start().recipient::<StreamShutdownMsg>();
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            ClandestineWebSocketMode::Off,
+            None,
+            Arc::new(NodeMetrics::default()),
+            false,
+        );
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let local_addr = SocketAddr::from_str("127.0.0.1:0").unwrap();
         let sw_key = StreamWriterKey::from(peer_addr);
@@ -1273,7 +1492,13 @@ mod tests {
         let (recorder, _, recording_arc) = make_recorder();
         let system = System::new("test");
         let sub = recorder.start().recipient::<StreamShutdownMsg>();
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            ClandestineWebSocketMode::Off,
+            None,
+            Arc::new(NodeMetrics::default()),
+            false,
+        );
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let local_addr = SocketAddr::from_str("127.0.0.1:0").unwrap();
         let sw_key = StreamWriterKey::from(peer_addr);
@@ -1312,7 +1537,13 @@ mod tests {
         let (neighborhood, neighborhood_awaiter, neighborhood_recording_arc) = make_recorder();
         thread::spawn(move || {
             let system = System::new("when_stream_handler_pool_fails_to_create_nonexistent_stream_for_write_then_it_logs_and_notifies_neighborhood");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                ClandestineWebSocketMode::Off,
+                None,
+                Arc::new(NodeMetrics::default()),
+                false,
+            );
             subject.stream_connector = Box::new(
                 StreamConnectorMock::new()
                     .connect_pair_result(Err(Error::from(ErrorKind::Other)))
@@ -1327,6 +1558,7 @@ mod tests {
                     dispatcher_subs: peer_actors.dispatcher,
                     stream_handler_pool_subs: subject_subs.clone(),
                     neighborhood_subs: peer_actors.neighborhood,
+                    new_ports_subs: vec![],
                 })
                 .unwrap();
 
@@ -1387,7 +1619,13 @@ mod tests {
                 "stream_handler_pool_creates_nonexistent_stream_for_reading_and_writing",
             );
             let discriminator_factory = JsonDiscriminatorFactory::new();
-            let mut subject = StreamHandlerPool::new(vec![Box::new(discriminator_factory)], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![Box::new(discriminator_factory)],
+                ClandestineWebSocketMode::Off,
+                None,
+                Arc::new(NodeMetrics::default()),
+                false,
+            );
             subject.stream_connector = Box::new(
                 StreamConnectorMock::new().connect_pair_result(Ok(ConnectionInfo {
                     reader: Box::new(
@@ -1417,6 +1655,7 @@ mod tests {
                     dispatcher_subs: peer_actors.dispatcher,
                     stream_handler_pool_subs: subject_subs.clone(),
                     neighborhood_subs: peer_actors.neighborhood,
+                    new_ports_subs: vec![],
                 })
                 .unwrap();
 
@@ -1488,6 +1727,120 @@ mod tests {
         );
     }
 
+    #[test]
+    fn stream_handler_pool_creates_nonexistent_websocket_stream_when_so_configured() {
+        use crossbeam_channel::unbounded;
+        let public_key = PublicKey::from(vec![0, 1, 2, 3]);
+        let server_masquerader = WebSocketMasquerader::new(WebSocketRole::Server);
+        let incoming_unmasked = b"Incoming data".to_vec();
+        let incoming_masked = server_masquerader.mask(&incoming_unmasked).unwrap();
+        let outgoing_unmasked = b"Outgoing data".to_vec();
+        let outgoing_masked_len = outgoing_unmasked.len() + 6;
+        let (dispatcher, dispatcher_awaiter, dispatcher_recording_arc) = make_recorder();
+        let (neighborhood, neighborhood_awaiter, neighborhood_recording_arc) = make_recorder();
+        let poll_write_params_arc = Arc::new(Mutex::new(vec![]));
+        let poll_write_params_arc_a = poll_write_params_arc.clone();
+        let connect_params_arc = Arc::new(Mutex::new(vec![]));
+        let connect_params_arc_a = connect_params_arc.clone();
+        let connect_websocket_params_arc = Arc::new(Mutex::new(vec![]));
+        let connect_websocket_params_arc_a = connect_websocket_params_arc.clone();
+        let (tx, rx) = unbounded();
+        thread::spawn(move || {
+            let system = System::new(
+                "stream_handler_pool_creates_nonexistent_websocket_stream_when_so_configured",
+            );
+            let mut subject = StreamHandlerPool::new(
+                vec![Box::new(WebSocketDiscriminatorFactory::new())],
+                ClandestineWebSocketMode::On,
+                None,
+                Arc::new(NodeMetrics::default()),
+                false,
+            );
+            subject.stream_connector = Box::new(
+                StreamConnectorMock::new()
+                    .connect_pair_params(&connect_params_arc)
+                    .connect_websocket_params(&connect_websocket_params_arc)
+                    .connect_pair_result(Ok(ConnectionInfo {
+                        reader: Box::new(
+                            ReadHalfWrapperMock::new()
+                                .poll_read_ok(incoming_masked)
+                                .poll_read_result(vec![], Ok(Async::NotReady)),
+                        ),
+                        writer: Box::new(
+                            WriteHalfWrapperMock::new()
+                                .poll_write_ok(outgoing_masked_len)
+                                .poll_write_result(Ok(Async::NotReady))
+                                .poll_write_params(&poll_write_params_arc),
+                        ),
+                        local_addr: SocketAddr::from_str("127.0.0.1:54321").unwrap(),
+                        peer_addr: SocketAddr::from_str("1.2.3.5:7000").unwrap(),
+                    })),
+            );
+            let subject_addr: Addr<StreamHandlerPool> = subject.start();
+            let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
+            let peer_actors = peer_actors_builder()
+                .dispatcher(dispatcher)
+                .neighborhood(neighborhood)
+                .build();
+            subject_subs
+                .bind
+                .try_send(PoolBindMessage {
+                    dispatcher_subs: peer_actors.dispatcher,
+                    stream_handler_pool_subs: subject_subs.clone(),
+                    neighborhood_subs: peer_actors.neighborhood,
+                    new_ports_subs: vec![],
+                })
+                .unwrap();
+            tx.send(subject_subs).unwrap();
+            system.run();
+        });
+        let subject_subs = rx.recv().unwrap();
+
+        subject_subs
+            .transmit_sub
+            .try_send(TransmitDataMsg {
+                endpoint: Endpoint::Key(public_key.clone()),
+                last_data: false,
+                sequence_number: None,
+                data: outgoing_unmasked.clone(),
+            })
+            .unwrap();
+
+        neighborhood_awaiter.await_message_count(1);
+        let target_ip_addr = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 5));
+        let node_query_msg =
+            Recording::get::<DispatcherNodeQueryMessage>(&neighborhood_recording_arc, 0);
+        subject_subs
+            .node_query_response
+            .try_send(DispatcherNodeQueryResponse {
+                result: Some(NodeQueryResponseMetadata::new(
+                    public_key,
+                    Some(NodeAddr::new(&target_ip_addr, &[7000])),
+                    rate_pack(100),
+                )),
+                context: node_query_msg.context,
+            })
+            .unwrap();
+        await_messages(1, &poll_write_params_arc_a);
+        let poll_write_params = poll_write_params_arc_a.lock().unwrap();
+        let outgoing_frame = &poll_write_params[0];
+        assert_eq!(&outgoing_frame[0..2], &[0x82, 0x80 | 13]);
+        assert_eq!(
+            server_masquerader.try_unmask(outgoing_frame).unwrap().chunk,
+            outgoing_unmasked
+        );
+        assert_eq!(
+            *connect_websocket_params_arc_a.lock().unwrap(),
+            vec![SocketAddr::from_str("1.2.3.5:7000").unwrap()]
+        );
+        assert!(connect_params_arc_a.lock().unwrap().is_empty());
+        dispatcher_awaiter.await_message_count(1);
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        let ibcd = dispatcher_recording.get_record::<InboundClientData>(0);
+        assert_eq!(ibcd.data, incoming_unmasked);
+        assert!(ibcd.is_clandestine);
+    }
+
     #[test]
     fn transmit_data_msg_handler_finds_ip_from_neighborhood_and_transmits_message() {
         init_test_logging();
@@ -1507,7 +1860,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(
+                vec![],
+                ClandestineWebSocketMode::Off,
+                None,
+                Arc::new(NodeMetrics::default()),
+                false,
+            );
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -1518,6 +1877,7 @@ mod tests {
                     dispatcher_subs: peer_actors.dispatcher,
                     stream_handler_pool_subs: subject_subs.clone(),
                     neighborhood_subs: peer_actors.neighborhood,
+                    new_ports_subs: vec![],
                 })
                 .unwrap();
 
@@ -1588,7 +1948,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(
+                vec![],
+                ClandestineWebSocketMode::Off,
+                None,
+                Arc::new(NodeMetrics::default()),
+                false,
+            );
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -1599,6 +1965,7 @@ mod tests {
                     dispatcher_subs: peer_actors.dispatcher,
                     stream_handler_pool_subs: subject_subs.clone(),
                     neighborhood_subs: peer_actors.neighborhood,
+                    new_ports_subs: vec![],
                 })
                 .unwrap();
 
@@ -1636,7 +2003,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(
+                vec![],
+                ClandestineWebSocketMode::Off,
+                None,
+                Arc::new(NodeMetrics::default()),
+                false,
+            );
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -1647,6 +2020,7 @@ mod tests {
                     dispatcher_subs: peer_actors.dispatcher,
                     stream_handler_pool_subs: subject_subs.clone(),
                     neighborhood_subs: peer_actors.neighborhood,
+                    new_ports_subs: vec![],
                 })
                 .unwrap();
 
@@ -1700,7 +2074,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                ClandestineWebSocketMode::Off,
+                None,
+                Arc::new(NodeMetrics::default()),
+                false,
+            );
             subject
                 .stream_writers
                 .insert(StreamWriterKey::from(peer_addr), None);
@@ -1713,6 +2093,7 @@ mod tests {
                     dispatcher_subs: peer_actors.dispatcher,
                     stream_handler_pool_subs: subject_subs.clone(),
                     neighborhood_subs: peer_actors.neighborhood,
+                    new_ports_subs: vec![],
                 })
                 .unwrap();
 
@@ -1790,7 +2171,13 @@ mod tests {
         let sender_wrapper = SenderWrapperMock::new(peer_addr)
             .unbounded_send_params(&sender_wrapper_unbounded_send_params_arc)
             .unbounded_send_result(Err(send_error));
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            ClandestineWebSocketMode::Off,
+            None,
+            Arc::new(NodeMetrics::default()),
+            false,
+        );
         subject
             .stream_writers
             .insert(sw_key, Some(Box::new(sender_wrapper)));
@@ -1863,7 +2250,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                ClandestineWebSocketMode::Off,
+                None,
+                Arc::new(NodeMetrics::default()),
+                false,
+            );
             subject.stream_connector = Box::new(
                 StreamConnectorMock::new()
                     .connect_pair_result(Err(Error::from(ErrorKind::Other)))
@@ -1880,6 +2273,7 @@ mod tests {
                     dispatcher_subs: peer_actors.dispatcher,
                     stream_handler_pool_subs: subject_subs.clone(),
                     neighborhood_subs: peer_actors.neighborhood,
+                    new_ports_subs: vec![],
                 })
                 .unwrap();
 
@@ -1932,6 +2326,107 @@ mod tests {
         );
     }
 
+    #[test]
+    fn when_a_connection_fails_the_other_clandestine_ports_of_the_neighbor_are_tried() {
+        init_test_logging();
+        let cryptde = main_cryptde();
+        let key = cryptde.public_key().clone();
+        let peer_addr = SocketAddr::from_str("5.4.3.2:8000").unwrap();
+        let msg = TransmitDataMsg {
+            endpoint: Endpoint::Key(key.clone()),
+            last_data: false,
+            sequence_number: None,
+            data: b"hello".to_vec(),
+        };
+        let expected_data = JsonMasquerader::new().mask(&msg.data).unwrap();
+        let poll_write_params_arc = Arc::new(Mutex::new(Vec::new()));
+        let connection_info = ConnectionInfo {
+            reader: Box::new(
+                ReadHalfWrapperMock::new().poll_read_result(vec![], Ok(Async::NotReady)),
+            ),
+            writer: Box::new(
+                WriteHalfWrapperMock::new()
+                    .poll_write_params(&poll_write_params_arc)
+                    .poll_write_result(Ok(Async::Ready(expected_data.len()))),
+            ),
+            local_addr: SocketAddr::from_str("1.2.3.4:80").unwrap(),
+            peer_addr: SocketAddr::from_str("5.4.3.2:9000").unwrap(),
+        };
+        let connect_pair_params_arc = Arc::new(Mutex::new(vec![]));
+        let connect_pair_params_arc_a = connect_pair_params_arc.clone();
+        let (neighborhood, neighborhood_awaiter, neighborhood_recording_arc) = make_recorder();
+
+        thread::spawn(move || {
+            let system = System::new("test");
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                ClandestineWebSocketMode::Off,
+                None,
+                Arc::new(NodeMetrics::default()),
+                false,
+            );
+            subject.stream_connector = Box::new(
+                StreamConnectorMock::new()
+                    .connect_pair_params(&connect_pair_params_arc)
+                    .connect_pair_result(Err(Error::from(ErrorKind::ConnectionRefused)))
+                    .connect_pair_result(Ok(connection_info)),
+            );
+            subject.clandestine_discriminator_factories =
+                vec![Box::new(HttpRequestDiscriminatorFactory::new())];
+            let subject_addr: Addr<StreamHandlerPool> = subject.start();
+            let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
+            let peer_actors = peer_actors_builder().neighborhood(neighborhood).build();
+            subject_subs
+                .bind
+                .try_send(PoolBindMessage {
+                    dispatcher_subs: peer_actors.dispatcher,
+                    stream_handler_pool_subs: subject_subs.clone(),
+                    neighborhood_subs: peer_actors.neighborhood,
+                    new_ports_subs: vec![],
+                })
+                .unwrap();
+
+            subject_subs
+                .node_query_response
+                .try_send(DispatcherNodeQueryResponse {
+                    result: Some(NodeQueryResponseMetadata::new(
+                        key,
+                        Some(NodeAddr::new(&peer_addr.ip(), &[8000, 9000])),
+                        rate_pack(100),
+                    )),
+                    context: msg,
+                })
+                .unwrap();
+
+            system.run();
+        });
+
+        await_messages(1, &poll_write_params_arc);
+        let poll_write_params = poll_write_params_arc.lock().unwrap();
+        assert_eq!(poll_write_params[0], expected_data);
+        let connect_pair_params = connect_pair_params_arc_a.lock().unwrap();
+        assert_eq!(
+            *connect_pair_params,
+            vec![
+                SocketAddr::from_str("5.4.3.2:8000").unwrap(),
+                SocketAddr::from_str("5.4.3.2:9000").unwrap(),
+            ]
+        );
+        neighborhood_awaiter.await_message_count(1);
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(
+            neighborhood_recording.get_record::<ConnectionProgressMessage>(0),
+            &ConnectionProgressMessage {
+                peer_addr: peer_addr.ip(),
+                event: ConnectionProgressEvent::TcpConnectionSuccessful
+            }
+        );
+        assert_eq!(neighborhood_recording.len(), 1);
+        TestLogHandler::new().exists_log_containing(
+            "WARN: Dispatcher: Stream to 5.4.3.2:8000 could not be connected; trying the neighbor's other clandestine ports: connection refused",
+        );
+    }
+
     #[test]
     fn node_query_response_handler_sets_counterpart_flag_and_removes_stream_writer_if_last_data_is_true(
     ) {
@@ -1943,7 +2438,13 @@ mod tests {
         let sender_wrapper = SenderWrapperMock::new(peer_addr)
             .unbounded_send_params(&sender_wrapper_unbounded_send_params_arc)
             .unbounded_send_result(Ok(()));
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            ClandestineWebSocketMode::Off,
+            None,
+            Arc::new(NodeMetrics::default()),
+            false,
+        );
         subject.stream_writers.insert(
             StreamWriterKey::from(peer_addr),
             Some(Box::new(sender_wrapper)),
@@ -2003,7 +2504,13 @@ mod tests {
         };
 
         let system = System::new("test");
-        let subject = StreamHandlerPool::new(vec![], false);
+        let subject = StreamHandlerPool::new(
+            vec![],
+            ClandestineWebSocketMode::Off,
+            None,
+            Arc::new(NodeMetrics::default()),
+            false,
+        );
         let subject_addr: Addr<StreamHandlerPool> = subject.start();
         let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
         let peer_actors = peer_actors_builder().build();
@@ -2013,6 +2520,7 @@ mod tests {
                 dispatcher_subs: peer_actors.dispatcher,
                 stream_handler_pool_subs: subject_subs.clone(),
                 neighborhood_subs: peer_actors.neighborhood,
+                new_ports_subs: vec![],
             })
             .unwrap();
 
@@ -2051,7 +2559,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(
+                vec![],
+                ClandestineWebSocketMode::Off,
+                None,
+                Arc::new(NodeMetrics::default()),
+                false,
+            );
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -2062,6 +2576,7 @@ mod tests {
                     dispatcher_subs: peer_actors.dispatcher,
                     stream_handler_pool_subs: subject_subs.clone(),
                     neighborhood_subs: peer_actors.neighborhood,
+                    new_ports_subs: vec![],
                 })
                 .unwrap();
 
@@ -2124,7 +2639,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                ClandestineWebSocketMode::Off,
+                None,
+                Arc::new(NodeMetrics::default()),
+                false,
+            );
             subject.traffic_analyzer = Box::new(TrafficAnalyzerMock {});
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
@@ -2136,6 +2657,7 @@ mod tests {
                     dispatcher_subs: peer_actors.dispatcher,
                     stream_handler_pool_subs: subject_subs.clone(),
                     neighborhood_subs: peer_actors.neighborhood,
+                    new_ports_subs: vec![],
                 })
                 .unwrap();
 
@@ -2185,7 +2707,13 @@ mod tests {
                 "stream_handler_pool_creates_nonexistent_stream_for_reading_and_writing",
             );
             let discriminator_factory = JsonDiscriminatorFactory::new();
-            let mut subject = StreamHandlerPool::new(vec![Box::new(discriminator_factory)], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![Box::new(discriminator_factory)],
+                ClandestineWebSocketMode::Off,
+                None,
+                Arc::new(NodeMetrics::default()),
+                false,
+            );
             subject.stream_connector = Box::new(StreamConnectorMock::new()); // this will panic if a connection is attempted
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -2196,6 +2724,7 @@ mod tests {
                     dispatcher_subs: peer_actors.dispatcher,
                     stream_handler_pool_subs: subject_subs.clone(),
                     neighborhood_subs: peer_actors.neighborhood,
+                    new_ports_subs: vec![],
                 })
                 .unwrap();
 
@@ -2232,7 +2761,13 @@ mod tests {
         expected = "panic message (processed with: node_lib::sub_lib::utils::crash_request_analyzer)"
     )]
     fn stream_handler_can_be_crashed_properly_but_not_improperly() {
-        let stream_handler_pool = StreamHandlerPool::new(vec![], true);
+        let stream_handler_pool = StreamHandlerPool::new(
+            vec![],
+            ClandestineWebSocketMode::Off,
+            None,
+            Arc::new(NodeMetrics::default()),
+            true,
+        );
 
         prove_that_crash_request_handler_is_hooked_up(stream_handler_pool, CRASH_KEY);
     }
@@ -3,6 +3,7 @@ use crate::bootstrapper::PortConfiguration;
 use crate::stream_handler_pool::StreamHandlerPoolSubs;
 use crate::sub_lib::dispatcher::{DispatcherSubs, StreamShutdownMsg};
 use crate::sub_lib::neighborhood::NeighborhoodSubs;
+use crate::sub_lib::peer_actors::NewClandestinePorts;
 use crate::sub_lib::stream_connector::ConnectionInfo;
 use actix::{Message, Recipient};
 use std::fmt;
@@ -66,6 +67,7 @@ pub struct PoolBindMessage {
     pub dispatcher_subs: DispatcherSubs,
     pub stream_handler_pool_subs: StreamHandlerPoolSubs,
     pub neighborhood_subs: NeighborhoodSubs,
+    pub new_ports_subs: Vec<Recipient<NewClandestinePorts>>,
 }
 
 impl Debug for PoolBindMessage {
@@ -96,6 +98,7 @@ mod tests {
             dispatcher_subs,
             stream_handler_pool_subs,
             neighborhood_subs,
+            new_ports_subs: vec![],
         };
 
         let result = format!("{:?}", subject);
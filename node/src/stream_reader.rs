@@ -55,7 +55,15 @@ impl Future for StreamReaderReal {
                         length,
                         Self::stringify(self.local_addr, self.peer_addr)
                     );
-                    self.wrangle_discriminators(&buf, length)
+                    if self.wrangle_discriminators(&buf, length) {
+                        debug!(
+                            self.logger,
+                            "Stream {} has shut down (peer closed the masquerade)",
+                            Self::stringify(self.local_addr, self.peer_addr)
+                        );
+                        self.shutdown();
+                        return Ok(Async::Ready(()));
+                    }
                 }
                 Err(e) => {
                     if indicates_dead_stream(e.kind()) {
@@ -118,7 +126,8 @@ impl StreamReaderReal {
         }
     }
 
-    fn wrangle_discriminators(&mut self, buf: &[u8], length: usize) {
+    // Returns true if the peer has announced that it's closing the stream
+    fn wrangle_discriminators(&mut self, buf: &[u8], length: usize) -> bool {
         // Although discriminators is a vec, it was never really designed to have more than one.
         let is_connect = HttpProtocolPack::is_connect(buf);
         let chosen_discriminator = if self.discriminators.len() > 1 && is_connect {
@@ -178,6 +187,7 @@ impl StreamReaderReal {
                 }
             }
         }
+        chosen_discriminator.peer_is_closing()
     }
 
     fn shutdown(&mut self) {
@@ -223,6 +233,8 @@ mod tests {
     use crate::test_utils::recorder::Recording;
     use crate::test_utils::tokio_wrapper_mocks::ReadHalfWrapperMock;
     use crate::tls_discriminator_factory::TlsDiscriminatorFactory;
+    use crate::websocket_discriminator_factory::WebSocketDiscriminatorFactory;
+    use crate::websocket_masquerader::{WebSocketMasquerader, WebSocketRole};
     use actix::Actor;
     use actix::Addr;
     use actix::System;
@@ -527,6 +539,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn stream_reader_shuts_down_cleanly_when_the_peer_closes_the_websocket() {
+        init_test_logging();
+        let system = System::new("test");
+        let (shp_recording_arc, stream_handler_pool_subs) = stream_handler_pool_stuff();
+        let (d_recording_arc, dispatcher_subs) = dispatcher_stuff();
+        let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let local_addr = SocketAddr::from_str("1.2.3.5:6789").unwrap();
+        let discriminator_factories: Vec<Box<dyn DiscriminatorFactory>> =
+            vec![Box::new(WebSocketDiscriminatorFactory::new())];
+        let mut frames = WebSocketMasquerader::new(WebSocketRole::Client)
+            .mask(b"booga")
+            .unwrap();
+        frames.extend(vec![0x89, 0x80, 0x01, 0x02, 0x03, 0x04]);
+        frames.extend(vec![0x88, 0x82, 0x01, 0x02, 0x03, 0x04, 0x02, 0xEA]);
+        let reader = ReadHalfWrapperMock {
+            poll_read_results: vec![(frames.clone(), Ok(Async::Ready(frames.len())))],
+        };
+        let mut subject = StreamReaderReal::new(
+            Box::new(reader),
+            None,
+            dispatcher_subs.ibcd_sub,
+            stream_handler_pool_subs.remove_sub,
+            dispatcher_subs.stream_shutdown_sub.clone(),
+            discriminator_factories,
+            true,
+            peer_addr,
+            local_addr,
+        );
+
+        let result = subject.poll();
+
+        System::current().stop_with_code(0);
+        system.run();
+        assert_eq!(result, Ok(Async::Ready(())));
+        let d_recording = d_recording_arc.lock().unwrap();
+        assert_eq!(d_recording.len(), 1);
+        let d_record = d_recording.get_record::<dispatcher::InboundClientData>(0);
+        assert_eq!(d_record.data, b"booga".to_vec());
+        let shp_recording = shp_recording_arc.lock().unwrap();
+        assert_eq!(
+            shp_recording.get_record::<RemoveStreamMsg>(0),
+            &RemoveStreamMsg {
+                peer_addr,
+                local_addr,
+                stream_type: RemovedStreamType::Clandestine,
+                dispatcher_sub: dispatcher_subs.stream_shutdown_sub,
+            }
+        );
+        TestLogHandler::new().exists_log_containing(
+            "DEBUG: StreamReader for 1.2.3.4:5678: Stream between local 1.2.3.5:6789 and peer 1.2.3.4:5678 has shut down (peer closed the masquerade)",
+        );
+    }
+
     #[test]
     fn stream_reader_sends_two_correct_sequenced_messages_when_sent_a_http_connect() {
         let system = System::new("test");
@@ -1,23 +1,29 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 use crate::accountant::db_access_objects::banned_dao::BannedDaoFactory;
+use crate::accountant::db_access_objects::ledger_dao::LedgerDaoFactory;
 use crate::accountant::db_access_objects::payable_dao::PayableDaoFactory;
 use crate::accountant::db_access_objects::pending_payable_dao::PendingPayableDaoFactory;
 use crate::accountant::db_access_objects::receivable_dao::ReceivableDaoFactory;
+use crate::accountant::db_access_objects::service_receipt_dao::ServiceReceiptDaoFactory;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::BlockchainAgentWithContextMessage;
 use crate::accountant::{
-    checked_conversion, Accountant, ReceivedPayments, ReportTransactionReceipts, ScanError,
-    SentPayables,
+    checked_conversion, Accountant, ReceivedPayments, ReportConsumingWalletBalances,
+    ReportTransactionReceipts, ScanError, SentPayables,
 };
 use crate::actor_system_factory::SubsFactory;
 use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
 use crate::db_config::config_dao::ConfigDaoFactory;
+use crate::sub_lib::cryptde::{CryptDE, CryptData, PlainData, PublicKey};
+use crate::sub_lib::hopper::{ExpiredCoresPackage, MessageType};
 use crate::sub_lib::neighborhood::ConfigChangeMsg;
 use crate::sub_lib::peer_actors::{BindMessage, StartMessage};
+use crate::sub_lib::versioned_data::VersionedData;
 use crate::sub_lib::wallet::Wallet;
 use actix::Recipient;
 use actix::{Addr, Message};
 use lazy_static::lazy_static;
 use masq_lib::ui_gateway::NodeFromUiMessage;
+use serde_derive::{Deserialize, Serialize};
 use std::fmt::{Debug, Formatter};
 use std::str::FromStr;
 use std::sync::atomic::{AtomicU32, Ordering};
@@ -74,6 +80,8 @@ pub struct DaoFactories {
     pub pending_payable_dao_factory: Box<dyn PendingPayableDaoFactory>,
     pub receivable_dao_factory: Box<dyn ReceivableDaoFactory>,
     pub banned_dao_factory: Box<dyn BannedDaoFactory>,
+    pub ledger_dao_factory: Box<dyn LedgerDaoFactory>,
+    pub service_receipt_dao_factory: Box<dyn ServiceReceiptDaoFactory>,
     pub config_dao_factory: Box<dyn ConfigDaoFactory>,
 }
 
@@ -98,11 +106,13 @@ pub struct AccountantSubs {
     pub report_routing_service_provided: Recipient<ReportRoutingServiceProvidedMessage>,
     pub report_exit_service_provided: Recipient<ReportExitServiceProvidedMessage>,
     pub report_services_consumed: Recipient<ReportServicesConsumedMessage>,
+    pub service_receipt: Recipient<ExpiredCoresPackage<ServiceReceipt_0v1>>,
     pub report_payable_payments_setup: Recipient<BlockchainAgentWithContextMessage>,
     pub report_inbound_payments: Recipient<ReceivedPayments>,
     pub init_pending_payable_fingerprints: Recipient<PendingPayableFingerprintSeeds>,
     pub report_transaction_receipts: Recipient<ReportTransactionReceipts>,
     pub report_sent_payments: Recipient<SentPayables>,
+    pub report_consuming_wallet_balances: Recipient<ReportConsumingWalletBalances>,
     pub scan_errors: Recipient<ScanError>,
     pub ui_message_sub: Recipient<NodeFromUiMessage>,
 }
@@ -163,6 +173,73 @@ pub struct ExitServiceConsumed {
     pub byte_rate: u64,
 }
 
+// A consumer's statement, cumulative over one route, of what it owes a single provider. It's signed
+// with the CryptDE whose public key it carries, so the provider can hold on to it as evidence.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[allow(non_camel_case_types)]
+pub struct ServiceReceipt_0v1 {
+    pub consumer_key: PublicKey,
+    pub consuming_wallet: Wallet,
+    pub provider_wallet: Wallet,
+    pub route_id: u32,
+    pub first_service_s: u64,
+    pub services: u64,
+    pub bytes: u64,
+    #[serde(with = "u128_as_bytes")]
+    pub amount_wei: u128,
+    pub signature: CryptData,
+}
+
+impl From<ServiceReceipt_0v1> for MessageType {
+    fn from(receipt: ServiceReceipt_0v1) -> Self {
+        MessageType::ServiceReceipt(VersionedData::new(
+            &crate::sub_lib::migrations::service_receipt::MIGRATIONS,
+            &receipt,
+        ))
+    }
+}
+
+impl ServiceReceipt_0v1 {
+    pub fn signed(mut self, cryptde: &dyn CryptDE) -> Self {
+        self.consumer_key = cryptde.public_key().clone();
+        self.signature = cryptde
+            .sign(&self.signed_data())
+            .expect("Couldn't sign service receipt");
+        self
+    }
+
+    pub fn verify(&self, cryptde: &dyn CryptDE) -> bool {
+        cryptde.verify_signature(&self.signed_data(), &self.signature, &self.consumer_key)
+    }
+
+    fn signed_data(&self) -> PlainData {
+        let fields = (
+            &self.consumer_key,
+            &self.consuming_wallet,
+            &self.provider_wallet,
+            self.route_id,
+            self.first_service_s,
+            self.services,
+            self.bytes,
+            self.amount_wei.to_be_bytes(),
+        );
+        PlainData::from(serde_cbor::ser::to_vec(&fields).expect("Serialization failure"))
+    }
+}
+
+// CBOR can't carry integers wider than 64 bits, and a cumulative amount may well outgrow them
+pub(crate) mod u128_as_bytes {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+        value.to_be_bytes().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+        <[u8; 16]>::deserialize(deserializer).map(u128::from_be_bytes)
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Default)]
 pub struct FinancialStatistics {
     pub total_paid_payable_wei: u128,
@@ -195,14 +272,19 @@ impl MessageIdGenerator for MessageIdGeneratorReal {
 mod tests {
     use crate::accountant::test_utils::AccountantBuilder;
     use crate::accountant::{checked_conversion, Accountant};
+    use crate::sub_lib::accountant::ServiceReceipt_0v1;
     use crate::sub_lib::accountant::{
         AccountantSubsFactoryReal, MessageIdGenerator, MessageIdGeneratorReal, PaymentThresholds,
         ScanIntervals, SubsFactory, DEFAULT_EARNING_WALLET, DEFAULT_PAYMENT_THRESHOLDS,
         DEFAULT_SCAN_INTERVALS, MSG_ID_INCREMENTER, TEMPORARY_CONSUMING_WALLET,
     };
+    use crate::sub_lib::cryptde::{CryptData, PublicKey};
+    use crate::sub_lib::cryptde_null::CryptDENull;
     use crate::sub_lib::wallet::Wallet;
     use crate::test_utils::recorder::{make_accountant_subs_from_recorder, Recorder};
+    use crate::test_utils::{main_cryptde, make_wallet};
     use actix::Actor;
+    use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
     use std::str::FromStr;
     use std::sync::atomic::Ordering;
     use std::sync::Mutex;
@@ -288,4 +370,63 @@ mod tests {
 
         assert_eq!(id, 0)
     }
+
+    fn make_receipt() -> ServiceReceipt_0v1 {
+        ServiceReceipt_0v1 {
+            consumer_key: PublicKey::new(&[]),
+            consuming_wallet: make_wallet("consumer"),
+            provider_wallet: make_wallet("provider"),
+            route_id: 12,
+            first_service_s: 1_700_000_000,
+            services: 5,
+            bytes: 5_000,
+            amount_wei: u64::MAX as u128 * 3,
+            signature: CryptData::new(&[]),
+        }
+    }
+
+    #[test]
+    fn signed_service_receipt_carries_the_signers_key_and_verifies() {
+        let cryptde = main_cryptde();
+
+        let result = make_receipt().signed(cryptde);
+
+        assert_eq!(&result.consumer_key, cryptde.public_key());
+        assert_ne!(result.signature, CryptData::new(&[]));
+        assert_eq!(result.verify(cryptde), true);
+    }
+
+    #[test]
+    fn service_receipt_does_not_verify_once_tampered_with() {
+        let cryptde = main_cryptde();
+        let receipt = make_receipt().signed(cryptde);
+        let mut inflated = receipt.clone();
+        inflated.amount_wei += 1;
+        let mut rerouted = receipt.clone();
+        rerouted.route_id += 1;
+        let mut disowned = receipt;
+        disowned.consumer_key = PublicKey::new(b"somebody else");
+
+        assert_eq!(inflated.verify(cryptde), false);
+        assert_eq!(rerouted.verify(cryptde), false);
+        assert_eq!(disowned.verify(cryptde), false);
+    }
+
+    #[test]
+    fn service_receipt_verifies_with_any_cryptde() {
+        let receipt = make_receipt().signed(main_cryptde());
+        let other_cryptde = CryptDENull::from(&PublicKey::new(b"other"), TEST_DEFAULT_CHAIN);
+
+        assert_eq!(receipt.verify(&other_cryptde), true);
+    }
+
+    #[test]
+    fn service_receipt_survives_serialization_with_a_wide_amount() {
+        let receipt = make_receipt().signed(main_cryptde());
+
+        let serialized = serde_cbor::ser::to_vec(&receipt).unwrap();
+        let result: ServiceReceipt_0v1 = serde_cbor::de::from_slice(&serialized).unwrap();
+
+        assert_eq!(result, receipt);
+    }
 }
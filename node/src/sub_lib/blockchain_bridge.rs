@@ -4,7 +4,7 @@ use crate::accountant::db_access_objects::payable_dao::PayableAccount;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::QualifiedPayablesMessage;
 use crate::accountant::{RequestTransactionReceipts, ResponseSkeleton, SkeletonOptHolder};
-use crate::blockchain::blockchain_bridge::RetrieveTransactions;
+use crate::blockchain::blockchain_bridge::{RequestConsumingWalletBalances, RetrieveTransactions};
 use crate::sub_lib::peer_actors::BindMessage;
 use actix::Message;
 use actix::Recipient;
@@ -12,17 +12,33 @@ use masq_lib::blockchains::chains::Chain;
 use masq_lib::ui_gateway::NodeFromUiMessage;
 use std::fmt;
 use std::fmt::{Debug, Formatter};
-use web3::types::U256;
+use web3::types::{Address, U256};
 
 #[derive(Clone, PartialEq, Eq, Debug, Default)]
 pub struct BlockchainBridgeConfig {
     pub blockchain_service_url_opt: Option<String>,
+    pub blockchain_service_quorum: u16,
+    // The consuming wallet's transactions are signed by this service rather than by the Node
+    pub external_signer_url_opt: Option<String>,
+    // Payables sent together are paid in as few transactions through this contract as
+    // multisend_max_creditors allows
+    pub multisend_contract_opt: Option<Address>,
+    pub multisend_max_creditors: u16,
     pub chain: Chain,
     // TODO: totally ignored during the setup of the BlockchainBridge actor!
     // Use it in the body or delete this field
     pub gas_price: u64,
 }
 
+// The blockchain service URL may name several services, separated by commas
+pub fn blockchain_service_urls(blockchain_service_url: &str) -> Vec<&str> {
+    blockchain_service_url
+        .split(',')
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .collect()
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct BlockchainBridgeSubs {
     pub bind: Recipient<BindMessage>,
@@ -31,6 +47,7 @@ pub struct BlockchainBridgeSubs {
     pub retrieve_transactions: Recipient<RetrieveTransactions>,
     pub ui_sub: Recipient<NodeFromUiMessage>,
     pub request_transaction_receipts: Recipient<RequestTransactionReceipts>,
+    pub request_consuming_wallet_balances: Recipient<RequestConsumingWalletBalances>,
 }
 
 impl Debug for BlockchainBridgeSubs {
@@ -86,10 +103,18 @@ mod tests {
     use crate::actor_system_factory::SubsFactory;
     use crate::blockchain::blockchain_bridge::{BlockchainBridge, BlockchainBridgeSubsFactoryReal};
     use crate::blockchain::test_utils::BlockchainInterfaceMock;
+    use crate::sub_lib::blockchain_bridge::blockchain_service_urls;
     use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
     use crate::test_utils::recorder::{make_blockchain_bridge_subs_from_recorder, Recorder};
     use actix::Actor;
 
+    #[test]
+    fn blockchain_service_urls_splits_on_commas_and_ignores_blanks() {
+        let result = blockchain_service_urls(" https://one.org/ID,http://two.com:8545 ,, ");
+
+        assert_eq!(result, vec!["https://one.org/ID", "http://two.com:8545"]);
+    }
+
     #[test]
     fn blockchain_bridge_subs_debug() {
         let recorder = Recorder::new().start();
@@ -0,0 +1,203 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use lazy_static::lazy_static;
+use masq_lib::messages::UiCrashReport;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+pub const CRASH_REPORT_FILE_NAME: &str = "crash_report.json";
+pub const CRASH_REPORT_LOG_LINES: usize = 50;
+const LOG_TAIL_CHUNK_SIZE: u64 = 8192;
+
+lazy_static! {
+    static ref CRASHING_ACTOR: Mutex<Option<String>> = Mutex::new(None);
+}
+
+// An actor told to crash at a crash point names itself here just before it panics, because
+// the panic hook can't otherwise tell which actor's thread it is running on
+pub fn announce_crashing_actor(actor: &str) {
+    *crashing_actor_guard() = Some(actor.to_string());
+}
+
+pub fn take_crashing_actor() -> Option<String> {
+    crashing_actor_guard().take()
+}
+
+pub fn last_log_lines(logfile: &Path, count: usize) -> Vec<String> {
+    match read_log_tail(logfile, count) {
+        Ok(bytes) => {
+            let text = String::from_utf8_lossy(&bytes);
+            let lines = text.lines().collect::<Vec<&str>>();
+            lines[lines.len().saturating_sub(count)..]
+                .iter()
+                .map(|line| line.to_string())
+                .collect()
+        }
+        Err(_) => vec![],
+    }
+}
+
+// A panic in one actor usually brings down others, and their panics would overwrite the report
+// about the one that started it
+pub fn crash_already_reported(data_directory: &Path) -> bool {
+    read_crash_report(data_directory)
+        .map(|report| report.process_id == std::process::id())
+        .unwrap_or(false)
+}
+
+pub fn write_crash_report(data_directory: &Path, report: &UiCrashReport) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(report).expect("Serialization problem");
+    fs::write(data_directory.join(CRASH_REPORT_FILE_NAME), json)
+}
+
+pub fn read_crash_report(data_directory: &Path) -> Option<UiCrashReport> {
+    let json = fs::read_to_string(data_directory.join(CRASH_REPORT_FILE_NAME)).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+// Reads the logfile backwards chunk by chunk until the chunks hold more line breaks than there are
+// lines wanted, so that the first of those lines is complete
+fn read_log_tail(logfile: &Path, count: usize) -> io::Result<Vec<u8>> {
+    let mut file = File::open(logfile)?;
+    let mut position = file.seek(SeekFrom::End(0))?;
+    let mut tail: Vec<u8> = vec![];
+    while position > 0 && count_line_breaks(&tail) <= count {
+        let chunk_size = position.min(LOG_TAIL_CHUNK_SIZE);
+        position -= chunk_size;
+        file.seek(SeekFrom::Start(position))?;
+        let mut chunk = vec![0; chunk_size as usize];
+        file.read_exact(&mut chunk)?;
+        chunk.extend(tail);
+        tail = chunk;
+    }
+    Ok(tail)
+}
+
+fn count_line_breaks(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|byte| **byte == b'\n').count()
+}
+
+fn crashing_actor_guard<'a>() -> MutexGuard<'a, Option<String>> {
+    match CRASHING_ACTOR.lock() {
+        Ok(guard) => guard,
+        Err(poison_err) => poison_err.into_inner(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+
+    fn make_report() -> UiCrashReport {
+        UiCrashReport {
+            timestamp_s: 1_700_000_000,
+            process_id: 4321,
+            version: "0.9.0".to_string(),
+            panic_message: "Booga".to_string(),
+            location: "src/accountant/mod.rs:12:34".to_string(),
+            actor_opt: Some("ACCOUNTANT".to_string()),
+            backtrace: "0: rust_begin_unwind".to_string(),
+            log_lines: vec!["first line".to_string(), "last line".to_string()],
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(CRASH_REPORT_FILE_NAME, "crash_report.json");
+        assert_eq!(CRASH_REPORT_LOG_LINES, 50);
+    }
+
+    #[test]
+    fn crash_report_survives_a_round_trip_through_the_data_directory() {
+        let data_directory =
+            ensure_node_home_directory_exists("crash_report", "crash_report_survives_a_round_trip");
+        let report = make_report();
+
+        write_crash_report(&data_directory, &report).unwrap();
+        let result = read_crash_report(&data_directory);
+
+        assert_eq!(result, Some(report));
+    }
+
+    #[test]
+    fn read_crash_report_tolerates_missing_and_garbled_reports() {
+        let data_directory = ensure_node_home_directory_exists(
+            "crash_report",
+            "read_crash_report_tolerates_missing_and_garbled_reports",
+        );
+
+        let missing = read_crash_report(&data_directory);
+        fs::write(data_directory.join(CRASH_REPORT_FILE_NAME), "{booga").unwrap();
+        let garbled = read_crash_report(&data_directory);
+
+        assert_eq!(missing, None);
+        assert_eq!(garbled, None);
+    }
+
+    #[test]
+    fn last_log_lines_keeps_only_the_tail() {
+        let directory =
+            ensure_node_home_directory_exists("crash_report", "last_log_lines_keeps_only_the_tail");
+        let logfile = directory.join("MASQNode_rCURRENT.log");
+        fs::write(&logfile, "one\ntwo\nthree\nfour\n").unwrap();
+
+        let two = last_log_lines(&logfile, 2);
+        let all = last_log_lines(&logfile, 10);
+        let none = last_log_lines(&directory.join("nonexistent.log"), 2);
+
+        assert_eq!(two, vec!["three".to_string(), "four".to_string()]);
+        assert_eq!(all.len(), 4);
+        assert_eq!(none, Vec::<String>::new());
+    }
+
+    #[test]
+    fn last_log_lines_reads_the_tail_of_a_logfile_longer_than_a_chunk() {
+        let directory = ensure_node_home_directory_exists(
+            "crash_report",
+            "last_log_lines_reads_the_tail_of_a_logfile_longer_than_a_chunk",
+        );
+        let logfile = directory.join("MASQNode_rCURRENT.log");
+        let content = (0..10_000)
+            .map(|idx| format!("Line number {}\n", idx))
+            .collect::<String>();
+        fs::write(&logfile, content).unwrap();
+
+        let result = last_log_lines(&logfile, 3);
+
+        assert_eq!(
+            result,
+            vec![
+                "Line number 9997".to_string(),
+                "Line number 9998".to_string(),
+                "Line number 9999".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn crash_already_reported_recognizes_reports_of_this_process_only() {
+        let data_directory = ensure_node_home_directory_exists(
+            "crash_report",
+            "crash_already_reported_recognizes_reports_of_this_process_only",
+        );
+        let none_yet = crash_already_reported(&data_directory);
+        write_crash_report(&data_directory, &make_report()).unwrap();
+        let other_process = crash_already_reported(&data_directory);
+        let report = UiCrashReport {
+            process_id: std::process::id(),
+            ..make_report()
+        };
+        write_crash_report(&data_directory, &report).unwrap();
+
+        let this_process = crash_already_reported(&data_directory);
+
+        assert!(!none_yet);
+        assert!(!other_process);
+        assert!(this_process);
+    }
+}
@@ -1,4 +1,5 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::crypto_suite::CryptoSuite;
 use crate::sub_lib::route::RouteError;
 use ethsign_crypto::Keccak256;
 use rustc_hex::{FromHex, ToHex};
@@ -556,6 +557,7 @@ pub trait CryptDE: Send + Sync {
     ) -> Result<PublicKey, String>;
     fn digest(&self) -> [u8; 32];
     fn as_any(&self) -> &dyn Any;
+    fn crypto_suite(&self) -> CryptoSuite;
 }
 
 pub struct SerdeCborError {
@@ -0,0 +1,393 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::cryptde;
+use crate::sub_lib::cryptde::{
+    CryptDE, CryptData, CryptdecError, PlainData, PrivateKey, PublicKey, SymmetricKey,
+};
+use crate::sub_lib::cryptde_real::{CryptDEReal, CLASSIC_PUBLIC_KEY_LEN};
+use crate::sub_lib::crypto_suite::CryptoSuite;
+use crate::sub_lib::ml_kem;
+use masq_lib::blockchains::chains::Chain;
+use sodiumoxide::crypto::box_ as encryption;
+use sodiumoxide::crypto::box_::curve25519xsalsa20poly1305 as cxsp;
+use sodiumoxide::crypto::secretbox;
+use sodiumoxide::randombytes::randombytes_into;
+use std::any::Any;
+use tiny_keccak::{Hasher, Sha3};
+
+pub const HYBRID_PUBLIC_KEY_LEN: usize = CLASSIC_PUBLIC_KEY_LEN + ml_kem::ENCAPSULATION_KEY_LEN;
+const HYBRID_PACKAGE_VERSION: u8 = 1;
+const HYBRID_HEADER_LEN: usize = 1 + cxsp::PUBLICKEYBYTES + ml_kem::CIPHERTEXT_LEN;
+const HYBRID_OVERHEAD: usize = HYBRID_HEADER_LEN + secretbox::NONCEBYTES + secretbox::MACBYTES;
+const KEY_COMBINER_LABEL: &[u8] = b"MASQ x25519-mlkem768";
+
+// A CryptDEHybrid's public key is a classic public key with an ML-KEM-768 encapsulation key
+// appended. Data for another hybrid Node is protected by a secret derived from both an X25519
+// exchange and an ML-KEM encapsulation, so it stays safe unless both are broken; data for a classic
+// Node falls back to the classic suite, and classic Nodes reach this one through the classic part
+// of its key. Hybrid data starts with a version byte so that decoding can tell it from classic data
+// without paying for a decapsulation. Signatures remain classic Ed25519.
+#[allow(clippy::upper_case_acronyms)]
+pub struct CryptDEHybrid {
+    classic: CryptDEReal,
+    public_key: PublicKey,
+    decapsulation_key: Vec<u8>,
+    digest: [u8; 32],
+}
+
+impl CryptDE for CryptDEHybrid {
+    fn encode(&self, key: &PublicKey, data: &PlainData) -> Result<CryptData, CryptdecError> {
+        if key.len() != HYBRID_PUBLIC_KEY_LEN {
+            return self.classic.encode(key, data);
+        }
+        let (ephemeral_public_key, ephemeral_secret_key) = encryption::gen_keypair();
+        let recipient_x25519_key = &key.as_slice()[..cxsp::PUBLICKEYBYTES];
+        let x25519_secret =
+            CryptDEReal::key_agreement_with_secret(&ephemeral_secret_key, recipient_x25519_key)?;
+        let mut encapsulation_seed = [0u8; ml_kem::ENCAPSULATION_SEED_LEN];
+        randombytes_into(&mut encapsulation_seed);
+        let (ml_kem_secret, ml_kem_ciphertext) = ml_kem::encapsulate(
+            &key.as_slice()[CLASSIC_PUBLIC_KEY_LEN..],
+            &encapsulation_seed,
+        )
+        .map_err(|e| CryptdecError::InvalidKey(format!("{:?}", e)))?;
+        let symmetric_key = Self::combine_secrets(
+            &ml_kem_secret,
+            &x25519_secret,
+            &ephemeral_public_key.0,
+            recipient_x25519_key,
+        );
+        let sealed = self.classic.encode_sym(&symmetric_key, data)?;
+        let mut result = Vec::with_capacity(HYBRID_OVERHEAD + data.len());
+        result.push(HYBRID_PACKAGE_VERSION);
+        result.extend_from_slice(&ephemeral_public_key.0);
+        result.extend(ml_kem_ciphertext);
+        result.extend_from_slice(sealed.as_slice());
+        Ok(CryptData::from(result))
+    }
+
+    fn decode(&self, data: &CryptData) -> Result<PlainData, CryptdecError> {
+        // Classic data begins with a random ephemeral key, so about one classic package in 256
+        // carries our version byte by chance; the secretbox authenticator rejects it as hybrid.
+        if data.len() >= HYBRID_OVERHEAD && data.as_slice()[0] == HYBRID_PACKAGE_VERSION {
+            if let Ok(plain_data) = self.decode_hybrid(data) {
+                return Ok(plain_data);
+            }
+        }
+        self.classic.decode(data)
+    }
+
+    fn encode_sym(&self, key: &SymmetricKey, data: &PlainData) -> Result<CryptData, CryptdecError> {
+        self.classic.encode_sym(key, data)
+    }
+
+    fn decode_sym(&self, key: &SymmetricKey, data: &CryptData) -> Result<PlainData, CryptdecError> {
+        self.classic.decode_sym(key, data)
+    }
+
+    fn gen_key_sym(&self) -> SymmetricKey {
+        self.classic.gen_key_sym()
+    }
+
+    fn random(&self, dest: &mut [u8]) {
+        self.classic.random(dest)
+    }
+
+    fn private_key(&self) -> &PrivateKey {
+        self.classic.private_key()
+    }
+
+    fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    fn dup(&self) -> Box<dyn CryptDE> {
+        Box::new(CryptDEHybrid {
+            classic: self.classic.duplicate(),
+            public_key: self.public_key.clone(),
+            decapsulation_key: self.decapsulation_key.clone(),
+            digest: self.digest,
+        })
+    }
+
+    fn sign(&self, data: &PlainData) -> Result<CryptData, CryptdecError> {
+        self.classic.sign(data)
+    }
+
+    fn verify_signature(
+        &self,
+        data: &PlainData,
+        signature: &CryptData,
+        public_key: &PublicKey,
+    ) -> bool {
+        self.classic.verify_signature(data, signature, public_key)
+    }
+
+    fn hash(&self, data: &PlainData) -> CryptData {
+        self.classic.hash(data)
+    }
+
+    fn public_key_to_descriptor_fragment(&self, public_key: &PublicKey) -> String {
+        self.classic.public_key_to_descriptor_fragment(public_key)
+    }
+
+    fn descriptor_fragment_to_first_contact_public_key(
+        &self,
+        descriptor_fragment: &str,
+    ) -> Result<PublicKey, String> {
+        self.classic
+            .descriptor_fragment_to_first_contact_public_key(descriptor_fragment)
+    }
+
+    fn digest(&self) -> [u8; 32] {
+        self.digest
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn crypto_suite(&self) -> CryptoSuite {
+        CryptoSuite::X25519MlKem768
+    }
+}
+
+impl CryptDEHybrid {
+    pub fn new(chain: Chain) -> Self {
+        let classic = CryptDEReal::new(chain);
+        let mut keypair_seed = [0u8; ml_kem::KEYPAIR_SEED_LEN];
+        randombytes_into(&mut keypair_seed);
+        let (encapsulation_key, decapsulation_key) = ml_kem::keypair_from_seed(&keypair_seed);
+        let mut public_key_data = classic.public_key().as_slice().to_vec();
+        public_key_data.extend(encapsulation_key);
+        let public_key = PublicKey::from(public_key_data);
+        let digest = cryptde::create_digest(&public_key, &chain.rec().contract);
+        Self {
+            classic,
+            public_key,
+            decapsulation_key,
+            digest,
+        }
+    }
+
+    fn decode_hybrid(&self, data: &CryptData) -> Result<PlainData, CryptdecError> {
+        let (header, sealed) = data.as_slice()[1..].split_at(HYBRID_HEADER_LEN - 1);
+        let (ephemeral_public_key, ml_kem_ciphertext) = header.split_at(cxsp::PUBLICKEYBYTES);
+        let x25519_secret = self.classic.key_agreement(ephemeral_public_key)?;
+        let ml_kem_secret = ml_kem::decapsulate(&self.decapsulation_key, ml_kem_ciphertext)
+            .map_err(|e| CryptdecError::OtherError(format!("{:?}", e)))?;
+        let symmetric_key = Self::combine_secrets(
+            &ml_kem_secret,
+            &x25519_secret,
+            ephemeral_public_key,
+            &self.public_key.as_slice()[..cxsp::PUBLICKEYBYTES],
+        );
+        self.classic
+            .decode_sym(&symmetric_key, &CryptData::new(sealed))
+    }
+
+    // Same shape as the X-Wing combiner: the X25519 transcript goes in alongside both secrets,
+    // while ML-KEM's ciphertext needn't because ML-KEM already binds its secret to it.
+    fn combine_secrets(
+        ml_kem_secret: &[u8],
+        x25519_secret: &[u8],
+        ephemeral_public_key: &[u8],
+        recipient_x25519_key: &[u8],
+    ) -> SymmetricKey {
+        let mut sha3 = Sha3::v256();
+        sha3.update(KEY_COMBINER_LABEL);
+        sha3.update(ml_kem_secret);
+        sha3.update(x25519_secret);
+        sha3.update(ephemeral_public_key);
+        sha3.update(recipient_x25519_key);
+        let mut output = [0u8; secretbox::KEYBYTES];
+        sha3.finalize(&mut output);
+        SymmetricKey::new(&output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(HYBRID_PUBLIC_KEY_LEN, 64 + 1184);
+        assert_eq!(HYBRID_PACKAGE_VERSION, 1);
+        assert_eq!(HYBRID_HEADER_LEN, 1 + 32 + 1088);
+        assert_eq!(HYBRID_OVERHEAD, 1 + 32 + 1088 + 24 + 16);
+        assert_eq!(KEY_COMBINER_LABEL, b"MASQ x25519-mlkem768");
+    }
+
+    #[test]
+    fn public_key_is_a_classic_key_with_an_encapsulation_key_appended() {
+        let subject = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+
+        let result = subject.public_key();
+
+        assert_eq!(result.len(), HYBRID_PUBLIC_KEY_LEN);
+        assert_eq!(
+            &result.as_slice()[..CLASSIC_PUBLIC_KEY_LEN],
+            subject.classic.public_key().as_slice()
+        );
+        assert_eq!(subject.crypto_suite(), CryptoSuite::X25519MlKem768);
+    }
+
+    #[test]
+    fn hybrid_encode_decode_round_trip_works() {
+        let sender = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+        let recipient = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+        let data = PlainData::new(b"The quick brown fox jumps over the lazy dog");
+
+        let encoded = sender.encode(recipient.public_key(), &data).unwrap();
+        let result = recipient.decode(&encoded);
+
+        assert_eq!(encoded.len(), HYBRID_OVERHEAD + data.len());
+        assert_eq!(encoded.as_slice()[0], HYBRID_PACKAGE_VERSION);
+        assert_eq!(result, Ok(data));
+    }
+
+    #[test]
+    fn hybrid_data_with_another_version_byte_is_not_opened_as_hybrid() {
+        let sender = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+        let recipient = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+        let encoded = sender
+            .encode(recipient.public_key(), &PlainData::new(b"secret"))
+            .unwrap();
+        let mut relabeled = encoded.as_slice().to_vec();
+        relabeled[0] = HYBRID_PACKAGE_VERSION + 1;
+
+        let result = recipient.decode(&CryptData::from(relabeled));
+
+        assert_eq!(result, Err(CryptdecError::OpeningFailed));
+    }
+
+    #[test]
+    fn as_any_exposes_the_hybrid_cryptde() {
+        let subject = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+
+        let result = subject.as_any().downcast_ref::<CryptDEHybrid>();
+
+        assert_eq!(result.unwrap().public_key(), subject.public_key());
+    }
+
+    #[test]
+    fn hybrid_data_cannot_be_decoded_by_someone_else() {
+        let sender = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+        let recipient = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+        let eavesdropper = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+        let encoded = sender
+            .encode(recipient.public_key(), &PlainData::new(b"secret"))
+            .unwrap();
+
+        let result = eavesdropper.decode(&encoded);
+
+        assert_eq!(result, Err(CryptdecError::OpeningFailed));
+    }
+
+    #[test]
+    fn tampered_hybrid_data_is_rejected() {
+        let sender = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+        let recipient = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+        let encoded = sender
+            .encode(recipient.public_key(), &PlainData::new(b"secret"))
+            .unwrap();
+        let mut tampered = encoded.as_slice().to_vec();
+        tampered[1 + cxsp::PUBLICKEYBYTES + 10] ^= 1;
+
+        let result = recipient.decode(&CryptData::from(tampered));
+
+        assert_eq!(result, Err(CryptdecError::OpeningFailed));
+    }
+
+    #[test]
+    fn hybrid_node_falls_back_to_the_classic_suite_for_classic_recipients() {
+        let sender = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+        let recipient = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let data = PlainData::new(b"for a classic Node");
+
+        let encoded = sender.encode(recipient.public_key(), &data).unwrap();
+        let result = recipient.decode(&encoded);
+
+        assert!(encoded.len() < HYBRID_OVERHEAD);
+        assert_eq!(result, Ok(data));
+    }
+
+    #[test]
+    fn classic_node_reaches_a_hybrid_node_through_the_classic_suite() {
+        let sender = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let recipient = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+        let short_data = PlainData::new(b"short");
+        let long_data = PlainData::new(&[42u8; 2000]);
+
+        let short_result =
+            recipient.decode(&sender.encode(recipient.public_key(), &short_data).unwrap());
+        let long_result =
+            recipient.decode(&sender.encode(recipient.public_key(), &long_data).unwrap());
+
+        assert_eq!(short_result, Ok(short_data));
+        assert_eq!(long_result, Ok(long_data));
+    }
+
+    #[test]
+    fn signatures_are_verifiable_by_classic_and_hybrid_nodes_alike() {
+        let signer = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+        let classic_verifier = CryptDEReal::new(TEST_DEFAULT_CHAIN);
+        let hybrid_verifier = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+        let data = PlainData::new(b"signed");
+
+        let signature = signer.sign(&data).unwrap();
+
+        assert!(classic_verifier.verify_signature(&data, &signature, signer.public_key()));
+        assert!(hybrid_verifier.verify_signature(&data, &signature, signer.public_key()));
+        assert!(!hybrid_verifier.verify_signature(
+            &PlainData::new(b"forged"),
+            &signature,
+            signer.public_key()
+        ));
+    }
+
+    #[test]
+    fn digest_covers_the_whole_public_key() {
+        let subject = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+
+        let result = subject.digest();
+
+        assert_eq!(
+            result,
+            cryptde::create_digest(subject.public_key(), &TEST_DEFAULT_CHAIN.rec().contract)
+        );
+        assert_ne!(result, subject.classic.digest());
+    }
+
+    #[test]
+    fn dup_works() {
+        let subject = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+        let sender = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+        let data = PlainData::new(b"for the duplicate");
+
+        let result = subject.dup();
+
+        assert_eq!(result.public_key(), subject.public_key());
+        assert_eq!(result.digest(), subject.digest());
+        assert_eq!(result.crypto_suite(), CryptoSuite::X25519MlKem768);
+        let encoded = sender.encode(subject.public_key(), &data).unwrap();
+        assert_eq!(result.decode(&encoded), Ok(data));
+    }
+
+    #[test]
+    fn descriptor_fragment_carries_only_the_classic_encryption_key() {
+        let subject = CryptDEHybrid::new(TEST_DEFAULT_CHAIN);
+
+        let fragment = subject.public_key_to_descriptor_fragment(subject.public_key());
+        let result = subject
+            .descriptor_fragment_to_first_contact_public_key(&fragment)
+            .unwrap();
+
+        assert_eq!(result.len(), CLASSIC_PUBLIC_KEY_LEN);
+        assert_eq!(
+            &result.as_slice()[..cxsp::PUBLICKEYBYTES],
+            &subject.public_key().as_slice()[..cxsp::PUBLICKEYBYTES]
+        );
+    }
+}
@@ -6,6 +6,7 @@ use crate::sub_lib::cryptde::PlainData;
 use crate::sub_lib::cryptde::PrivateKey;
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::cryptde::{CryptDE, SymmetricKey};
+use crate::sub_lib::crypto_suite::CryptoSuite;
 use masq_lib::blockchains::chains::Chain;
 use masq_lib::utils::ExpectValue;
 use rand::prelude::*;
@@ -151,6 +152,10 @@ impl CryptDE for CryptDENull {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn crypto_suite(&self) -> CryptoSuite {
+        CryptoSuite::Curve25519
+    }
 }
 
 impl<'a> From<&'a dyn CryptDE> for &'a CryptDENull {
@@ -3,9 +3,11 @@ use crate::sub_lib::cryptde;
 use crate::sub_lib::cryptde::{
     CryptDE, CryptData, CryptdecError, PlainData, PrivateKey, PublicKey, SymmetricKey,
 };
+use crate::sub_lib::crypto_suite::CryptoSuite;
 use lazy_static::lazy_static;
 use masq_lib::blockchains::chains::Chain;
 use sodiumoxide::crypto::box_::curve25519xsalsa20poly1305 as cxsp;
+use sodiumoxide::crypto::scalarmult::curve25519 as scalarmult;
 use sodiumoxide::crypto::sealedbox::curve25519blake2bxsalsa20poly1305::SEALBYTES;
 use sodiumoxide::crypto::sealedbox::{open, seal};
 use sodiumoxide::crypto::secretbox;
@@ -23,6 +25,8 @@ lazy_static! {
     };
 }
 
+pub const CLASSIC_PUBLIC_KEY_LEN: usize = cxsp::PUBLICKEYBYTES + signing::PUBLICKEYBYTES;
+
 #[allow(clippy::upper_case_acronyms)]
 pub struct CryptDEReal {
     public_key: PublicKey,
@@ -34,7 +38,9 @@ pub struct CryptDEReal {
 
 impl CryptDE for CryptDEReal {
     fn encode(&self, key: &PublicKey, data: &PlainData) -> Result<CryptData, CryptdecError> {
-        if key.len() != cxsp::PUBLICKEYBYTES + signing::PUBLICKEYBYTES {
+        // Keys from richer crypto suites begin with a classic key, so the classic suite can still
+        // reach the Nodes that use them.
+        if key.len() < CLASSIC_PUBLIC_KEY_LEN {
             return Err(CryptdecError::InvalidKey(format!("{:?}", key.as_slice())));
         }
         let remote_public_key = Self::encryption_public_key_from(key);
@@ -107,13 +113,7 @@ impl CryptDE for CryptDEReal {
     }
 
     fn dup(&self) -> Box<dyn CryptDE> {
-        Box::new(CryptDEReal {
-            public_key: self.public_key.clone(),
-            encryption_secret_key: encryption::SecretKey(self.encryption_secret_key.0),
-            signing_secret_key: signing::SecretKey(self.signing_secret_key.0),
-            digest: self.digest,
-            pre_shared_data: self.pre_shared_data,
-        })
+        Box::new(self.duplicate())
     }
 
     fn sign(&self, data: &PlainData) -> Result<CryptData, CryptdecError> {
@@ -187,6 +187,10 @@ impl CryptDE for CryptDEReal {
         //created for a special case of manipulation with cryptDENull where I know I'm not going to meet cryptDEReal there
         unimplemented!()
     }
+
+    fn crypto_suite(&self) -> CryptoSuite {
+        CryptoSuite::Curve25519
+    }
 }
 
 impl CryptDEReal {
@@ -206,6 +210,36 @@ impl CryptDEReal {
         }
     }
 
+    pub fn duplicate(&self) -> CryptDEReal {
+        CryptDEReal {
+            public_key: self.public_key.clone(),
+            encryption_secret_key: encryption::SecretKey(self.encryption_secret_key.0),
+            signing_secret_key: signing::SecretKey(self.signing_secret_key.0),
+            digest: self.digest,
+            pre_shared_data: self.pre_shared_data,
+        }
+    }
+
+    // X25519 key agreement between this CryptDE's encryption key and someone else's, for crypto
+    // suites that build on the classic one.
+    pub fn key_agreement(&self, other_public_key: &[u8]) -> Result<[u8; 32], CryptdecError> {
+        Self::key_agreement_with_secret(&self.encryption_secret_key, other_public_key)
+    }
+
+    pub fn key_agreement_with_secret(
+        secret_key: &encryption::SecretKey,
+        other_public_key: &[u8],
+    ) -> Result<[u8; 32], CryptdecError> {
+        let other_point = match scalarmult::GroupElement::from_slice(other_public_key) {
+            Some(point) => point,
+            None => return Err(CryptdecError::InvalidKey(format!("{:?}", other_public_key))),
+        };
+        match scalarmult::scalarmult(&scalarmult::Scalar(secret_key.0), &other_point) {
+            Ok(shared_secret) => Ok(shared_secret.0),
+            Err(()) => Err(CryptdecError::InvalidKey(format!("{:?}", other_public_key))),
+        }
+    }
+
     fn local_public_key_from(
         encryption_public_key: &encryption::PublicKey,
         signing_public_key: &signing::PublicKey,
@@ -227,7 +261,9 @@ impl CryptDEReal {
 
     fn signing_public_key_from(local_public_key: &PublicKey) -> signing::PublicKey {
         let mut data = [0u8; signing::PUBLICKEYBYTES];
-        data.copy_from_slice(&local_public_key.as_slice()[cxsp::PUBLICKEYBYTES..]);
+        data.copy_from_slice(
+            &local_public_key.as_slice()[cxsp::PUBLICKEYBYTES..CLASSIC_PUBLIC_KEY_LEN],
+        );
         signing::PublicKey(data)
     }
 }
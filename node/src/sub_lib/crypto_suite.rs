@@ -0,0 +1,185 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::cryptde::CryptDE;
+use crate::sub_lib::cryptde_hybrid::{CryptDEHybrid, HYBRID_PUBLIC_KEY_LEN};
+use crate::sub_lib::cryptde_real::{CryptDEReal, CLASSIC_PUBLIC_KEY_LEN};
+use masq_lib::blockchains::chains::Chain;
+use masq_lib::constants::{
+    CURVE25519_CRYPTO_SUITE_IDENTIFIER, X25519_MLKEM768_CRYPTO_SUITE_IDENTIFIER,
+};
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CryptoSuite {
+    Curve25519,
+    X25519MlKem768,
+}
+
+pub struct CryptoSuiteRecord {
+    pub self_id: CryptoSuite,
+    pub literal_identifier: &'static str,
+    pub public_key_len: usize,
+    pub post_quantum: bool,
+    make_cryptde: fn(Chain) -> Box<dyn CryptDE>,
+}
+
+pub const CRYPTO_SUITES: [CryptoSuiteRecord; 2] = [
+    CryptoSuiteRecord {
+        self_id: CryptoSuite::Curve25519,
+        literal_identifier: CURVE25519_CRYPTO_SUITE_IDENTIFIER,
+        public_key_len: CLASSIC_PUBLIC_KEY_LEN,
+        post_quantum: false,
+        make_cryptde: make_cryptde_real,
+    },
+    CryptoSuiteRecord {
+        self_id: CryptoSuite::X25519MlKem768,
+        literal_identifier: X25519_MLKEM768_CRYPTO_SUITE_IDENTIFIER,
+        public_key_len: HYBRID_PUBLIC_KEY_LEN,
+        post_quantum: true,
+        make_cryptde: make_cryptde_hybrid,
+    },
+];
+
+impl Default for CryptoSuite {
+    fn default() -> Self {
+        CryptoSuite::Curve25519
+    }
+}
+
+impl Display for CryptoSuite {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.rec().literal_identifier)
+    }
+}
+
+impl FromStr for CryptoSuite {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crypto_suite_from_identifier_opt(&s.to_lowercase())
+            .ok_or_else(|| format!("Unknown crypto suite: '{}'", s))
+    }
+}
+
+impl CryptoSuite {
+    pub fn rec(&self) -> &'static CryptoSuiteRecord {
+        CRYPTO_SUITES
+            .iter()
+            .find(|record| &record.self_id == self)
+            .unwrap_or_else(|| panic!("CryptoSuiteRecord for '{:?}' doesn't exist", self))
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == CryptoSuite::default()
+    }
+
+    pub fn make_cryptde(&self, chain: Chain) -> Box<dyn CryptDE> {
+        (self.rec().make_cryptde)(chain)
+    }
+
+    // A public key can be longer than its suite's own keys only by belonging to a richer suite,
+    // so the suite a Node advertises must agree with the length of the key it advertises.
+    pub fn matches_public_key_len(&self, public_key_len: usize) -> bool {
+        match CRYPTO_SUITES
+            .iter()
+            .find(|record| record.public_key_len == public_key_len)
+        {
+            Some(record) => record.self_id == *self,
+            None => *self == CryptoSuite::Curve25519,
+        }
+    }
+}
+
+pub fn crypto_suite_from_identifier_opt(identifier: &str) -> Option<CryptoSuite> {
+    CRYPTO_SUITES
+        .iter()
+        .find(|record| record.literal_identifier == identifier)
+        .map(|record| record.self_id)
+}
+
+fn make_cryptde_real(chain: Chain) -> Box<dyn CryptDE> {
+    Box::new(CryptDEReal::new(chain))
+}
+
+fn make_cryptde_hybrid(chain: Chain) -> Box<dyn CryptDE> {
+    Box::new(CryptDEHybrid::new(chain))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
+
+    #[test]
+    fn crypto_suite_records_are_correct() {
+        assert_eq!(CRYPTO_SUITES.len(), 2);
+        let curve25519 = CryptoSuite::Curve25519.rec();
+        assert_eq!(curve25519.self_id, CryptoSuite::Curve25519);
+        assert_eq!(curve25519.literal_identifier, "curve25519");
+        assert_eq!(curve25519.public_key_len, 64);
+        assert_eq!(curve25519.post_quantum, false);
+        let hybrid = CryptoSuite::X25519MlKem768.rec();
+        assert_eq!(hybrid.self_id, CryptoSuite::X25519MlKem768);
+        assert_eq!(hybrid.literal_identifier, "x25519-mlkem768");
+        assert_eq!(hybrid.public_key_len, 64 + 1184);
+        assert_eq!(hybrid.post_quantum, true);
+    }
+
+    #[test]
+    fn default_crypto_suite_is_curve25519() {
+        let result = CryptoSuite::default();
+
+        assert_eq!(result, CryptoSuite::Curve25519);
+        assert_eq!(CryptoSuite::Curve25519.is_default(), true);
+        assert_eq!(CryptoSuite::X25519MlKem768.is_default(), false);
+    }
+
+    #[test]
+    fn crypto_suites_round_trip_through_their_identifiers() {
+        CRYPTO_SUITES.iter().for_each(|record| {
+            let identifier = record.self_id.to_string();
+
+            let result = CryptoSuite::from_str(&identifier.to_uppercase());
+
+            assert_eq!(identifier, record.literal_identifier);
+            assert_eq!(result, Ok(record.self_id));
+        })
+    }
+
+    #[test]
+    fn unknown_crypto_suite_identifiers_are_rejected() {
+        let result = CryptoSuite::from_str("rot13");
+
+        assert_eq!(result, Err("Unknown crypto suite: 'rot13'".to_string()));
+        assert_eq!(crypto_suite_from_identifier_opt("rot13"), None);
+    }
+
+    #[test]
+    fn each_crypto_suite_makes_a_cryptde_of_its_own_kind() {
+        CRYPTO_SUITES.iter().for_each(|record| {
+            let result = record.self_id.make_cryptde(TEST_DEFAULT_CHAIN);
+
+            assert_eq!(result.crypto_suite(), record.self_id);
+            assert_eq!(result.public_key().len(), record.public_key_len);
+        })
+    }
+
+    #[test]
+    fn crypto_suites_match_only_the_key_lengths_they_produce() {
+        assert_eq!(CryptoSuite::Curve25519.matches_public_key_len(64), true);
+        assert_eq!(CryptoSuite::Curve25519.matches_public_key_len(1248), false);
+        assert_eq!(
+            CryptoSuite::X25519MlKem768.matches_public_key_len(1248),
+            true
+        );
+        assert_eq!(
+            CryptoSuite::X25519MlKem768.matches_public_key_len(64),
+            false
+        );
+        // Test keys come in all sorts of lengths; they can only be classic
+        assert_eq!(CryptoSuite::Curve25519.matches_public_key_len(4), true);
+        assert_eq!(CryptoSuite::X25519MlKem768.matches_public_key_len(4), false);
+    }
+}
@@ -2,7 +2,7 @@
 use crate::proxy_server::http_protocol_pack::HttpProtocolPack;
 use crate::stream_messages::RemovedStreamType;
 use crate::sub_lib::cryptde::PublicKey;
-use crate::sub_lib::peer_actors::{BindMessage, NewPublicIp};
+use crate::sub_lib::peer_actors::{BindMessage, NewClandestinePorts, NewPublicIp};
 use crate::sub_lib::stream_handler_pool::TransmitDataMsg;
 use actix::Message;
 use actix::Recipient;
@@ -25,6 +25,7 @@ pub enum Component {
     Hopper,
     ProxyServer,
     ProxyClient,
+    Accountant,
 }
 
 impl Serialize for Component {
@@ -37,6 +38,7 @@ impl Serialize for Component {
             Component::Hopper => 1,
             Component::ProxyServer => 2,
             Component::ProxyClient => 3,
+            Component::Accountant => 4,
         };
         serializer.serialize_u8(index)
     }
@@ -69,6 +71,7 @@ impl<'a> Visitor<'a> for ComponentVisitor {
             1 => Ok(Component::Hopper),
             2 => Ok(Component::ProxyServer),
             3 => Ok(Component::ProxyClient),
+            4 => Ok(Component::Accountant),
             _ => Err(serde::de::Error::invalid_value(
                 serde::de::Unexpected::Unsigned(u64::from(v)),
                 &self,
@@ -99,6 +102,7 @@ impl Component {
             Component::Hopper,
             Component::ProxyServer,
             Component::ProxyClient,
+            Component::Accountant,
         ]
     }
 }
@@ -167,6 +171,7 @@ pub struct DispatcherSubs {
     pub stream_shutdown_sub: Recipient<StreamShutdownMsg>,
     pub ui_sub: Recipient<NodeFromUiMessage>,
     pub new_ip_sub: Recipient<NewPublicIp>,
+    pub new_ports_sub: Recipient<NewClandestinePorts>,
 }
 
 impl Debug for DispatcherSubs {
@@ -184,6 +189,7 @@ impl Clone for DispatcherSubs {
             stream_shutdown_sub: self.stream_shutdown_sub.clone(),
             ui_sub: self.ui_sub.clone(),
             new_ip_sub: self.new_ip_sub.clone(),
+            new_ports_sub: self.new_ports_sub.clone(),
         }
     }
 }
@@ -207,6 +213,7 @@ mod tests {
             stream_shutdown_sub: recipient!(addr, StreamShutdownMsg),
             ui_sub: recipient!(addr, NodeFromUiMessage),
             new_ip_sub: recipient!(addr, NewPublicIp),
+            new_ports_sub: recipient!(addr, NewClandestinePorts),
         };
 
         assert_eq!(format!("{:?}", subject), "DispatcherSubs");
@@ -245,6 +252,7 @@ mod tests {
         let hopper_data = serde_cbor::ser::to_vec(&Component::Hopper).unwrap();
         let proxy_server_data = serde_cbor::ser::to_vec(&Component::ProxyServer).unwrap();
         let proxy_client_data = serde_cbor::ser::to_vec(&Component::ProxyClient).unwrap();
+        let accountant_data = serde_cbor::ser::to_vec(&Component::Accountant).unwrap();
 
         let neighborhood_result =
             serde_cbor::de::from_slice::<Component>(&neighborhood_data[..]).unwrap();
@@ -253,20 +261,23 @@ mod tests {
             serde_cbor::de::from_slice::<Component>(&proxy_server_data[..]).unwrap();
         let proxy_client_result =
             serde_cbor::de::from_slice::<Component>(&proxy_client_data[..]).unwrap();
+        let accountant_result =
+            serde_cbor::de::from_slice::<Component>(&accountant_data[..]).unwrap();
 
         assert_eq!(neighborhood_result, Component::Neighborhood);
         assert_eq!(hopper_result, Component::Hopper);
         assert_eq!(proxy_server_result, Component::ProxyServer);
         assert_eq!(proxy_client_result, Component::ProxyClient);
+        assert_eq!(accountant_result, Component::Accountant);
     }
 
     #[test]
     fn component_deserializer_handles_unrecognized_component() {
-        let unrecognized_data: &[u8] = &[4];
+        let unrecognized_data: &[u8] = &[5];
 
         let unrecognized_result = serde_cbor::de::from_slice::<Component>(unrecognized_data);
 
-        assert_eq!(format!("{:?}", unrecognized_result), String::from("Err(ErrorImpl { code: Message(\"invalid value: integer `4`, expected a Component enum\"), offset: 0 })"))
+        assert_eq!(format!("{:?}", unrecognized_result), String::from("Err(ErrorImpl { code: Message(\"invalid value: integer `5`, expected a Component enum\"), offset: 0 })"))
     }
 
     #[test]
@@ -16,6 +16,10 @@ pub struct LiveHop {
     pub public_key: PublicKey,
     pub payer: Option<Payer>,
     pub component: Component,
+    // Names the Node that built the Route, so it must never appear on ordinary Routes; see
+    // Route::one_way_from_originator()
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub originator_key_opt: Option<PublicKey>,
 }
 
 impl LiveHop {
@@ -24,6 +28,7 @@ impl LiveHop {
             public_key: key.clone(),
             payer,
             component,
+            originator_key_opt: None,
         }
     }
 
@@ -1,12 +1,14 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 use crate::bootstrapper::CryptDEPair;
+use crate::metrics::NodeMetrics;
 use crate::neighborhood::gossip::Gossip_0v1;
+use crate::sub_lib::accountant::ServiceReceipt_0v1;
 use crate::sub_lib::cryptde::encodex;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::cryptde::CryptData;
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::dispatcher::InboundClientData;
-use crate::sub_lib::neighborhood::GossipFailure_0v1;
+use crate::sub_lib::neighborhood::{ConfigChangeMsg, GossipFailure_0v1};
 use crate::sub_lib::node_addr::NodeAddr;
 use crate::sub_lib::peer_actors::BindMessage;
 use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
@@ -20,6 +22,8 @@ use masq_lib::ui_gateway::NodeFromUiMessage;
 use serde_derive::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Special-case hack to avoid extending a Card From Hell. I'm not sure what the right way to do
 /// this is, but this doesn't feel like it. The intent here is to provide a way to send a CORES
@@ -72,6 +76,7 @@ pub enum MessageType {
     Gossip(VersionedData<Gossip_0v1>),
     GossipFailure(VersionedData<GossipFailure_0v1>),
     DnsResolveFailed(VersionedData<DnsResolveFailure_0v1>),
+    ServiceReceipt(VersionedData<ServiceReceipt_0v1>),
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -81,6 +86,7 @@ pub enum MessageTypeLite {
     Gossip,
     GossipFailure,
     DnsResolveFailed,
+    ServiceReceipt,
 }
 
 #[allow(clippy::from_over_into)]
@@ -92,6 +98,7 @@ impl Into<MessageTypeLite> for MessageType {
             MessageType::Gossip(_) => MessageTypeLite::Gossip,
             MessageType::GossipFailure(_) => MessageTypeLite::GossipFailure,
             MessageType::DnsResolveFailed(_) => MessageTypeLite::DnsResolveFailed,
+            MessageType::ServiceReceipt(_) => MessageTypeLite::ServiceReceipt,
         }
     }
 }
@@ -149,6 +156,8 @@ pub struct HopperConfig {
     pub per_routing_service: u64,
     pub per_routing_byte: u64,
     pub is_decentralized: bool,
+    pub relay_batch_latency_opt: Option<Duration>,
+    pub metrics: Arc<NodeMetrics>,
     pub crashable: bool,
 }
 
@@ -159,6 +168,7 @@ pub struct HopperSubs {
     pub from_hopper_client_no_lookup: Recipient<NoLookupIncipientCoresPackage>,
     pub from_dispatcher: Recipient<InboundClientData>,
     pub node_from_ui: Recipient<NodeFromUiMessage>,
+    pub config_change_msg_sub: Recipient<ConfigChangeMsg>,
 }
 
 impl Debug for HopperSubs {
@@ -190,6 +200,7 @@ mod tests {
             from_hopper_client_no_lookup: recipient!(recorder, NoLookupIncipientCoresPackage),
             from_dispatcher: recipient!(recorder, InboundClientData),
             node_from_ui: recipient!(recorder, NodeFromUiMessage),
+            config_change_msg_sub: recipient!(recorder, ConfigChangeMsg),
         };
 
         assert_eq!(format!("{:?}", subject), "HopperSubs");
@@ -325,17 +336,21 @@ mod tests {
         let client_request = MessageType::ClientRequest(VersionedData::test_new(dv!(0, 0), vec![]));
         let gossip_failure = MessageType::GossipFailure(VersionedData::test_new(dv!(0, 0), vec![]));
         let gossip = MessageType::Gossip(VersionedData::test_new(dv!(0, 0), vec![]));
+        let service_receipt =
+            MessageType::ServiceReceipt(VersionedData::test_new(dv!(0, 0), vec![]));
 
         let dns_resolve_failed_result: MessageTypeLite = dns_resolve_failed.into();
         let client_response_result: MessageTypeLite = client_response.into();
         let client_request_result: MessageTypeLite = client_request.into();
         let gossip_failure_result: MessageTypeLite = gossip_failure.into();
         let gossip_result: MessageTypeLite = gossip.into();
+        let service_receipt_result: MessageTypeLite = service_receipt.into();
 
         assert_eq!(dns_resolve_failed_result, MessageTypeLite::DnsResolveFailed);
         assert_eq!(client_response_result, MessageTypeLite::ClientResponse);
         assert_eq!(client_request_result, MessageTypeLite::ClientRequest);
         assert_eq!(gossip_failure_result, MessageTypeLite::GossipFailure);
         assert_eq!(gossip_result, MessageTypeLite::Gossip);
+        assert_eq!(service_receipt_result, MessageTypeLite::ServiceReceipt);
     }
 }
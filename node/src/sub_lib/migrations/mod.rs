@@ -6,4 +6,5 @@ pub mod dns_resolve_failure;
 pub mod gossip;
 pub mod gossip_failure;
 pub mod node_record_inner;
+pub mod service_receipt;
 pub mod utils;
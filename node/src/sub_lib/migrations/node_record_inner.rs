@@ -2,6 +2,7 @@
 
 use crate::neighborhood::node_record::NodeRecordInner_0v1;
 use crate::sub_lib::cryptde::PublicKey;
+use crate::sub_lib::crypto_suite::CryptoSuite;
 use crate::sub_lib::migrations::utils::value_to_type;
 use crate::sub_lib::neighborhood::RatePack;
 use crate::sub_lib::versioned_data::{MigrationError, Migrations, StepError, VersionedData};
@@ -55,6 +56,7 @@ impl TryFrom<&Value> for NodeRecordInner_0v1 {
                 let mut accepts_connections_opt: Option<bool> = None;
                 let mut routes_data_opt: Option<bool> = None;
                 let mut version_opt: Option<u32> = None;
+                let mut crypto_suite_opt: Option<CryptoSuite> = None;
                 map.keys().for_each(|k| {
                     let v = map.get(k).expect("Disappeared");
                     match (k, v) {
@@ -86,6 +88,10 @@ impl TryFrom<&Value> for NodeRecordInner_0v1 {
                                 _ => (),
                             }
                         }
+                        (Value::Text(field_name), Value::Text(_)) => match field_name.as_str() {
+                            "crypto_suite" => crypto_suite_opt = value_to_type::<CryptoSuite>(v),
+                            _ => (),
+                        },
                         (Value::Text(field_name), Value::Integer(field_value)) => {
                             match field_name.as_str() {
                                 "version" => match field_value {
@@ -131,6 +137,7 @@ impl TryFrom<&Value> for NodeRecordInner_0v1 {
                     accepts_connections: accepts_connections_opt.expect("public_key disappeared"),
                     routes_data: routes_data_opt.expect("public_key disappeared"),
                     version: version_opt.expect("public_key disappeared"),
+                    crypto_suite: crypto_suite_opt.unwrap_or_default(),
                 })
             }
             _ => Err(StepError::SemanticError(format!(
@@ -173,6 +180,7 @@ mod tests {
             pub accepts_connections: bool,
             pub routes_data: bool,
             pub version: u32,
+            pub crypto_suite: CryptoSuite,
             pub another_field: String,
             pub yet_another_field: u64,
         }
@@ -186,6 +194,7 @@ mod tests {
             accepts_connections: false,
             routes_data: true,
             version: 42,
+            crypto_suite: CryptoSuite::X25519MlKem768,
         };
         let future_nri = ExampleFutureNRI {
             public_key: expected_nri.public_key.clone(),
@@ -195,6 +204,7 @@ mod tests {
             accepts_connections: expected_nri.accepts_connections,
             routes_data: expected_nri.routes_data,
             version: expected_nri.version,
+            crypto_suite: expected_nri.crypto_suite,
             another_field: "These are the times that try men's souls".to_string(),
             yet_another_field: 1234567890,
         };
@@ -209,6 +219,35 @@ mod tests {
         assert_eq!(actual_nri, expected_nri);
     }
 
+    #[test]
+    fn records_that_mention_no_crypto_suite_are_migrated_as_curve25519() {
+        #[derive(Serialize, Deserialize)]
+        struct ExamplePastNRI {
+            pub public_key: PublicKey,
+            pub earning_wallet: Wallet,
+            pub rate_pack: RatePack,
+            pub neighbors: BTreeSet<PublicKey>,
+            pub accepts_connections: bool,
+            pub routes_data: bool,
+            pub version: u32,
+        }
+        let past_nri = ExamplePastNRI {
+            public_key: PublicKey::new(&[1, 2, 3, 4]),
+            earning_wallet: Wallet::new("0x0123456789012345678901234567890123456789"),
+            rate_pack: DEFAULT_RATE_PACK,
+            neighbors: BTreeSet::new(),
+            accepts_connections: true,
+            routes_data: true,
+            version: 42,
+        };
+        let value = serde_cbor::value::to_value(&past_nri).unwrap();
+
+        let result = NodeRecordInner_0v1::try_from(&value).unwrap();
+
+        assert_eq!(result.crypto_suite, CryptoSuite::Curve25519);
+        assert_eq!(result.version, 42);
+    }
+
     #[test]
     fn cannot_migrate_from_value_other_than_map() {
         let value = Value::Bool(true);
@@ -0,0 +1,226 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::accountant::ServiceReceipt_0v1;
+use crate::sub_lib::cryptde::{CryptData, PublicKey};
+use crate::sub_lib::migrations::utils::value_to_type;
+use crate::sub_lib::versioned_data::Migrations;
+use crate::sub_lib::versioned_data::{MigrationError, StepError, VersionedData};
+use crate::sub_lib::wallet::Wallet;
+use lazy_static::lazy_static;
+use serde_cbor::Value;
+use std::convert::TryFrom;
+
+lazy_static! {
+    pub static ref MIGRATIONS: Migrations = {
+        let current_version = masq_lib::constants::SERVICE_RECEIPT_CURRENT_VERSION;
+        let mut migrations = Migrations::new(current_version);
+
+        migrate_value!(dv!(0, 1), ServiceReceipt_0v1, ServiceReceiptMF_0v1, {|value: serde_cbor::Value| {
+            ServiceReceipt_0v1::try_from (&value)
+        }});
+        migrations.add_step (masq_lib::data_version::FUTURE_VERSION, dv!(0, 1), Box::new (ServiceReceiptMF_0v1{}));
+
+        // add more steps here
+
+        migrations
+    };
+}
+
+impl From<ServiceReceipt_0v1> for VersionedData<ServiceReceipt_0v1> {
+    fn from(data: ServiceReceipt_0v1) -> Self {
+        VersionedData::new(&MIGRATIONS, &data)
+    }
+}
+
+impl TryFrom<VersionedData<ServiceReceipt_0v1>> for ServiceReceipt_0v1 {
+    type Error = MigrationError;
+
+    fn try_from(vd: VersionedData<ServiceReceipt_0v1>) -> Result<Self, Self::Error> {
+        vd.extract(&MIGRATIONS)
+    }
+}
+
+impl TryFrom<&Value> for ServiceReceipt_0v1 {
+    type Error = StepError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Map(map) => {
+                let mut consumer_key_opt: Option<PublicKey> = None;
+                let mut consuming_wallet_opt: Option<Wallet> = None;
+                let mut provider_wallet_opt: Option<Wallet> = None;
+                let mut route_id_opt: Option<u32> = None;
+                let mut first_service_s_opt: Option<u64> = None;
+                let mut services_opt: Option<u64> = None;
+                let mut bytes_opt: Option<u64> = None;
+                let mut amount_wei_opt: Option<u128> = None;
+                let mut signature_opt: Option<CryptData> = None;
+                map.keys().for_each(|k| {
+                    let v = map.get(k).expect("Disappeared");
+                    if let Value::Text(field_name) = k {
+                        match field_name.as_str() {
+                            "consumer_key" => consumer_key_opt = value_to_type::<PublicKey>(v),
+                            "consuming_wallet" => consuming_wallet_opt = value_to_type::<Wallet>(v),
+                            "provider_wallet" => provider_wallet_opt = value_to_type::<Wallet>(v),
+                            "route_id" => route_id_opt = value_to_type::<u32>(v),
+                            "first_service_s" => first_service_s_opt = value_to_type::<u64>(v),
+                            "services" => services_opt = value_to_type::<u64>(v),
+                            "bytes" => bytes_opt = value_to_type::<u64>(v),
+                            "amount_wei" => {
+                                amount_wei_opt =
+                                    value_to_type::<[u8; 16]>(v).map(u128::from_be_bytes)
+                            }
+                            "signature" => signature_opt = value_to_type::<CryptData>(v),
+                            _ => (),
+                        }
+                    }
+                });
+                let mut missing_fields: Vec<&str> = vec![];
+                fn check_field<'a, T>(
+                    missing_fields: &mut Vec<&'a str>,
+                    name: &'a str,
+                    field: &Option<T>,
+                ) {
+                    if field.is_none() {
+                        missing_fields.push(name)
+                    }
+                }
+                check_field(&mut missing_fields, "consumer_key", &consumer_key_opt);
+                check_field(
+                    &mut missing_fields,
+                    "consuming_wallet",
+                    &consuming_wallet_opt,
+                );
+                check_field(&mut missing_fields, "provider_wallet", &provider_wallet_opt);
+                check_field(&mut missing_fields, "route_id", &route_id_opt);
+                check_field(&mut missing_fields, "first_service_s", &first_service_s_opt);
+                check_field(&mut missing_fields, "services", &services_opt);
+                check_field(&mut missing_fields, "bytes", &bytes_opt);
+                check_field(&mut missing_fields, "amount_wei", &amount_wei_opt);
+                check_field(&mut missing_fields, "signature", &signature_opt);
+                if !missing_fields.is_empty() {
+                    return Err(StepError::SemanticError(format!(
+                        "Missing fields in ServiceReceipt: {:?}",
+                        missing_fields
+                    )));
+                }
+                Ok(ServiceReceipt_0v1 {
+                    consumer_key: consumer_key_opt.expect("consumer_key disappeared"),
+                    consuming_wallet: consuming_wallet_opt.expect("consuming_wallet disappeared"),
+                    provider_wallet: provider_wallet_opt.expect("provider_wallet disappeared"),
+                    route_id: route_id_opt.expect("route_id disappeared"),
+                    first_service_s: first_service_s_opt.expect("first_service_s disappeared"),
+                    services: services_opt.expect("services disappeared"),
+                    bytes: bytes_opt.expect("bytes disappeared"),
+                    amount_wei: amount_wei_opt.expect("amount_wei disappeared"),
+                    signature: signature_opt.expect("signature disappeared"),
+                })
+            }
+            _ => Err(StepError::SemanticError(format!(
+                "Expected Value::Map; found {:?}",
+                value
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{main_cryptde, make_wallet};
+    use masq_lib::data_version::DataVersion;
+    use serde_derive::{Deserialize, Serialize};
+
+    fn make_receipt() -> ServiceReceipt_0v1 {
+        ServiceReceipt_0v1 {
+            consumer_key: PublicKey::new(&[]),
+            consuming_wallet: make_wallet("consumer"),
+            provider_wallet: make_wallet("provider"),
+            route_id: 1234,
+            first_service_s: 1_700_000_000,
+            services: 56,
+            bytes: 78_901,
+            amount_wei: 123_456_789_012_345_678_901_234,
+            signature: CryptData::new(&[]),
+        }
+        .signed(main_cryptde())
+    }
+
+    #[test]
+    fn can_migrate_from_the_future() {
+        #[derive(Serialize, Deserialize)]
+        struct ExampleFutureSR {
+            pub consumer_key: PublicKey,
+            pub consuming_wallet: Wallet,
+            pub provider_wallet: Wallet,
+            pub route_id: u32,
+            pub first_service_s: u64,
+            pub services: u64,
+            pub bytes: u64,
+            #[serde(with = "crate::sub_lib::accountant::u128_as_bytes")]
+            pub amount_wei: u128,
+            pub signature: CryptData,
+            pub another_field: String,
+            pub yet_another_field: u64,
+        }
+        let expected_sr = make_receipt();
+        let future_sr = ExampleFutureSR {
+            consumer_key: expected_sr.consumer_key.clone(),
+            consuming_wallet: expected_sr.consuming_wallet.clone(),
+            provider_wallet: expected_sr.provider_wallet.clone(),
+            route_id: expected_sr.route_id,
+            first_service_s: expected_sr.first_service_s,
+            services: expected_sr.services,
+            bytes: expected_sr.bytes,
+            amount_wei: expected_sr.amount_wei,
+            signature: expected_sr.signature.clone(),
+            another_field: "These are the times that try men's souls".to_string(),
+            yet_another_field: 1234567890,
+        };
+        let future_migrations = Migrations::new(DataVersion::new(4095, 4095));
+        let serialized =
+            serde_cbor::ser::to_vec(&VersionedData::new(&future_migrations, &future_sr)).unwrap();
+        let future_vd =
+            serde_cbor::de::from_slice::<VersionedData<ServiceReceipt_0v1>>(&serialized).unwrap();
+
+        let actual_sr = ServiceReceipt_0v1::try_from(future_vd).unwrap();
+
+        assert_eq!(actual_sr, expected_sr);
+        assert_eq!(actual_sr.verify(main_cryptde()), true);
+    }
+
+    #[test]
+    fn cannot_migrate_from_value_other_than_map() {
+        let value = Value::Bool(true);
+
+        let result = ServiceReceipt_0v1::try_from(&value);
+
+        assert_eq!(
+            result,
+            Err(StepError::SemanticError(
+                "Expected Value::Map; found Bool(true)".to_string()
+            ))
+        )
+    }
+
+    #[test]
+    fn cannot_migrate_from_map_with_missing_fields() {
+        let value = Value::Map(
+            vec![(Value::Text("route_id".to_string()), Value::Integer(1234))]
+                .into_iter()
+                .collect(),
+        );
+
+        let result = ServiceReceipt_0v1::try_from(&value);
+
+        assert_eq!(
+            result,
+            Err(StepError::SemanticError(
+                "Missing fields in ServiceReceipt: [\"consumer_key\", \"consuming_wallet\", \
+                \"provider_wallet\", \"first_service_s\", \"services\", \"bytes\", \
+                \"amount_wei\", \"signature\"]"
+                    .to_string()
+            ))
+        )
+    }
+}
@@ -0,0 +1,596 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+// ML-KEM-768 as specified in FIPS 203. A hybrid Node encapsulates for every CORES package it encodes
+// for another hybrid Node and decapsulates every one it receives, so this runs once per package, not
+// once per Route. The code favors clarity over speed, except that arithmetic on secret coefficients
+// avoids divisions and data-dependent branches. Randomness comes in from the caller, so everything
+// here is deterministic.
+
+use tiny_keccak::{Hasher, Sha3, Shake, Xof};
+
+const N: usize = 256;
+const Q: u16 = 3329;
+const K: usize = 3;
+const ETA1: usize = 2;
+const ETA2: usize = 2;
+const DU: usize = 10;
+const DV: usize = 4;
+// 128^-1 mod q, applied at the end of the inverse NTT
+const INVERSE_NTT_SCALE: u16 = 3303;
+// floor(2^26 / q), for Barrett reduction of products of two coefficients
+const BARRETT_SHIFT: u32 = 26;
+const BARRETT_MULTIPLIER: u64 = (1 << BARRETT_SHIFT) / Q as u64;
+
+pub const ENCAPSULATION_KEY_LEN: usize = 384 * K + 32;
+pub const DECAPSULATION_KEY_LEN: usize = 768 * K + 96;
+pub const CIPHERTEXT_LEN: usize = 32 * (DU * K + DV);
+pub const SHARED_SECRET_LEN: usize = 32;
+pub const KEYPAIR_SEED_LEN: usize = 64;
+pub const ENCAPSULATION_SEED_LEN: usize = 32;
+
+type Poly = [u16; N];
+type PolyVec = [Poly; K];
+
+const ZETAS: [u16; 128] = powers_of_zeta(0);
+const GAMMAS: [u16; 128] = powers_of_zeta(1);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MlKemError {
+    InvalidEncapsulationKey,
+    InvalidDecapsulationKey,
+    InvalidCiphertext,
+}
+
+pub fn keypair_from_seed(seed: &[u8; KEYPAIR_SEED_LEN]) -> (Vec<u8>, Vec<u8>) {
+    let (d, z) = seed.split_at(32);
+    let (encapsulation_key, pke_decryption_key) = pke_keypair(d);
+    let mut decapsulation_key = pke_decryption_key;
+    decapsulation_key.extend_from_slice(&encapsulation_key);
+    decapsulation_key.extend_from_slice(&h(&encapsulation_key));
+    decapsulation_key.extend_from_slice(z);
+    (encapsulation_key, decapsulation_key)
+}
+
+pub fn encapsulate(
+    encapsulation_key: &[u8],
+    seed: &[u8; ENCAPSULATION_SEED_LEN],
+) -> Result<([u8; SHARED_SECRET_LEN], Vec<u8>), MlKemError> {
+    if !is_valid_encapsulation_key(encapsulation_key) {
+        return Err(MlKemError::InvalidEncapsulationKey);
+    }
+    let (shared_secret, r) = g(&[seed, &h(encapsulation_key)]);
+    let ciphertext = pke_encrypt(encapsulation_key, seed, &r);
+    Ok((shared_secret, ciphertext))
+}
+
+pub fn decapsulate(
+    decapsulation_key: &[u8],
+    ciphertext: &[u8],
+) -> Result<[u8; SHARED_SECRET_LEN], MlKemError> {
+    if decapsulation_key.len() != DECAPSULATION_KEY_LEN {
+        return Err(MlKemError::InvalidDecapsulationKey);
+    }
+    if ciphertext.len() != CIPHERTEXT_LEN {
+        return Err(MlKemError::InvalidCiphertext);
+    }
+    let (pke_decryption_key, rest) = decapsulation_key.split_at(384 * K);
+    let (encapsulation_key, rest) = rest.split_at(ENCAPSULATION_KEY_LEN);
+    let (ek_hash, z) = rest.split_at(32);
+    if h(encapsulation_key) != ek_hash {
+        return Err(MlKemError::InvalidDecapsulationKey);
+    }
+    let message = pke_decrypt(pke_decryption_key, ciphertext);
+    let (shared_secret, r) = g(&[&message, ek_hash]);
+    let rejection_secret = j(&[z, ciphertext]);
+    let reencrypted = pke_encrypt(encapsulation_key, &message, &r);
+    // Implicit rejection: a tampered ciphertext yields a pseudorandom secret rather than an
+    // error, and the choice between the two mustn't depend on a data-dependent branch.
+    let difference = reencrypted
+        .iter()
+        .zip(ciphertext.iter())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+    let keep_mask = ((difference as u16).wrapping_sub(1) >> 8) as u8;
+    let mut result = [0u8; SHARED_SECRET_LEN];
+    result.iter_mut().enumerate().for_each(|(idx, byte)| {
+        *byte = (shared_secret[idx] & keep_mask) | (rejection_secret[idx] & !keep_mask)
+    });
+    Ok(result)
+}
+
+fn is_valid_encapsulation_key(encapsulation_key: &[u8]) -> bool {
+    if encapsulation_key.len() != ENCAPSULATION_KEY_LEN {
+        return false;
+    }
+    // Every coefficient must already be reduced mod q, or decoding would silently change the key
+    encapsulation_key[..384 * K]
+        .chunks(384)
+        .all(|chunk| encode(12, &decode(12, chunk)) == chunk)
+}
+
+fn pke_keypair(d: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let (rho, sigma) = g(&[d, &[K as u8]]);
+    let a_hat = expand_matrix(&rho);
+    let mut nonce = 0u8;
+    let mut s_hat = sample_poly_vec(ETA1, &sigma, &mut nonce);
+    let mut e_hat = sample_poly_vec(ETA1, &sigma, &mut nonce);
+    s_hat.iter_mut().for_each(ntt);
+    e_hat.iter_mut().for_each(ntt);
+    let mut encapsulation_key = Vec::with_capacity(ENCAPSULATION_KEY_LEN);
+    (0..K).for_each(|i| {
+        let t_hat = add(&inner_product(&a_hat[i], &s_hat), &e_hat[i]);
+        encapsulation_key.extend(encode(12, &t_hat));
+    });
+    encapsulation_key.extend_from_slice(&rho);
+    let decryption_key = s_hat.iter().flat_map(|poly| encode(12, poly)).collect();
+    (encapsulation_key, decryption_key)
+}
+
+fn pke_encrypt(encapsulation_key: &[u8], message: &[u8], r: &[u8; 32]) -> Vec<u8> {
+    let (t_hat_bytes, rho) = encapsulation_key.split_at(384 * K);
+    let t_hat = decode_poly_vec(12, t_hat_bytes);
+    let mut rho_array = [0u8; 32];
+    rho_array.copy_from_slice(rho);
+    let a_hat = expand_matrix(&rho_array);
+    let mut nonce = 0u8;
+    let mut y_hat = sample_poly_vec(ETA1, r, &mut nonce);
+    let e1 = sample_poly_vec(ETA2, r, &mut nonce);
+    let e2 = sample_poly_cbd(ETA2, &prf(ETA2, r, nonce));
+    y_hat.iter_mut().for_each(ntt);
+    let mut ciphertext = Vec::with_capacity(CIPHERTEXT_LEN);
+    (0..K).for_each(|i| {
+        let column = [a_hat[0][i], a_hat[1][i], a_hat[2][i]];
+        let mut u = inner_product(&column, &y_hat);
+        inverse_ntt(&mut u);
+        ciphertext.extend(encode(DU, &compress(DU, &add(&u, &e1[i]))));
+    });
+    let mu = decompress(1, &decode(1, message));
+    let mut v = inner_product(&t_hat, &y_hat);
+    inverse_ntt(&mut v);
+    let v = add(&add(&v, &e2), &mu);
+    ciphertext.extend(encode(DV, &compress(DV, &v)));
+    ciphertext
+}
+
+fn pke_decrypt(decryption_key: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let (u_bytes, v_bytes) = ciphertext.split_at(32 * DU * K);
+    let mut u_hat = [[0u16; N]; K];
+    u_bytes
+        .chunks(32 * DU)
+        .zip(u_hat.iter_mut())
+        .for_each(|(chunk, poly)| {
+            *poly = decompress(DU, &decode(DU, chunk));
+            ntt(poly);
+        });
+    let v = decompress(DV, &decode(DV, v_bytes));
+    let s_hat = decode_poly_vec(12, decryption_key);
+    let mut s_dot_u = inner_product(&s_hat, &u_hat);
+    inverse_ntt(&mut s_dot_u);
+    encode(1, &compress(1, &subtract(&v, &s_dot_u)))
+}
+
+fn expand_matrix(rho: &[u8; 32]) -> [PolyVec; K] {
+    let mut a_hat = [[[0u16; N]; K]; K];
+    (0..K).for_each(|i| (0..K).for_each(|j| a_hat[i][j] = sample_ntt(rho, j as u8, i as u8)));
+    a_hat
+}
+
+fn sample_poly_vec(eta: usize, seed: &[u8; 32], nonce: &mut u8) -> PolyVec {
+    let mut result = [[0u16; N]; K];
+    result.iter_mut().for_each(|poly| {
+        *poly = sample_poly_cbd(eta, &prf(eta, seed, *nonce));
+        *nonce += 1;
+    });
+    result
+}
+
+fn sample_ntt(rho: &[u8; 32], j: u8, i: u8) -> Poly {
+    let mut xof = Shake::v128();
+    xof.update(rho);
+    xof.update(&[j, i]);
+    let mut result = [0u16; N];
+    let mut count = 0;
+    let mut block = [0u8; 168];
+    while count < N {
+        xof.squeeze(&mut block);
+        for chunk in block.chunks(3) {
+            let d1 = chunk[0] as u16 | ((chunk[1] as u16 & 0x0F) << 8);
+            let d2 = (chunk[1] as u16 >> 4) | ((chunk[2] as u16) << 4);
+            for candidate in [d1, d2] {
+                if candidate < Q && count < N {
+                    result[count] = candidate;
+                    count += 1;
+                }
+            }
+        }
+    }
+    result
+}
+
+fn sample_poly_cbd(eta: usize, bytes: &[u8]) -> Poly {
+    let bit = |idx: usize| ((bytes[idx / 8] >> (idx % 8)) & 1) as u16;
+    let mut result = [0u16; N];
+    result.iter_mut().enumerate().for_each(|(i, coefficient)| {
+        let x: u16 = (0..eta).map(|j| bit(2 * i * eta + j)).sum();
+        let y: u16 = (0..eta).map(|j| bit(2 * i * eta + eta + j)).sum();
+        *coefficient = reduce_once(x + Q - y);
+    });
+    result
+}
+
+fn ntt(f: &mut Poly) {
+    let mut zeta_idx = 1;
+    let mut len = 128;
+    while len >= 2 {
+        for start in (0..N).step_by(2 * len) {
+            let zeta = ZETAS[zeta_idx];
+            zeta_idx += 1;
+            for j in start..(start + len) {
+                let t = mul(zeta, f[j + len]);
+                f[j + len] = reduce_once(f[j] + Q - t);
+                f[j] = reduce_once(f[j] + t);
+            }
+        }
+        len /= 2;
+    }
+}
+
+fn inverse_ntt(f: &mut Poly) {
+    let mut zeta_idx = 127;
+    let mut len = 2;
+    while len <= 128 {
+        for start in (0..N).step_by(2 * len) {
+            let zeta = ZETAS[zeta_idx];
+            zeta_idx -= 1;
+            for j in start..(start + len) {
+                let t = f[j];
+                f[j] = reduce_once(t + f[j + len]);
+                f[j + len] = mul(zeta, reduce_once(f[j + len] + Q - t));
+            }
+        }
+        len *= 2;
+    }
+    f.iter_mut()
+        .for_each(|coefficient| *coefficient = mul(*coefficient, INVERSE_NTT_SCALE));
+}
+
+fn multiply_ntts(f: &Poly, g: &Poly) -> Poly {
+    let mut result = [0u16; N];
+    (0..N / 2).for_each(|i| {
+        let (a0, a1, b0, b1) = (f[2 * i], f[2 * i + 1], g[2 * i], g[2 * i + 1]);
+        result[2 * i] = reduce_once(mul(a0, b0) + mul(mul(a1, b1), GAMMAS[i]));
+        result[2 * i + 1] = reduce_once(mul(a0, b1) + mul(a1, b0));
+    });
+    result
+}
+
+fn inner_product(left: &PolyVec, right: &PolyVec) -> Poly {
+    left.iter()
+        .zip(right.iter())
+        .fold([0u16; N], |acc, (l, r)| add(&acc, &multiply_ntts(l, r)))
+}
+
+fn add(left: &Poly, right: &Poly) -> Poly {
+    let mut result = [0u16; N];
+    (0..N).for_each(|i| result[i] = reduce_once(left[i] + right[i]));
+    result
+}
+
+fn subtract(left: &Poly, right: &Poly) -> Poly {
+    let mut result = [0u16; N];
+    (0..N).for_each(|i| result[i] = reduce_once(left[i] + Q - right[i]));
+    result
+}
+
+fn mul(a: u16, b: u16) -> u16 {
+    barrett_reduce(a as u32 * b as u32)
+}
+
+// For x < q^2: the quotient estimate is at most one short, which reduce_once makes up for
+fn barrett_reduce(x: u32) -> u16 {
+    let quotient = ((x as u64 * BARRETT_MULTIPLIER) >> BARRETT_SHIFT) as u32;
+    reduce_once((x - quotient * Q as u32) as u16)
+}
+
+// For x < 2q: subtracts q unless that would go below zero, using a mask rather than a branch
+fn reduce_once(x: u16) -> u16 {
+    let difference = x.wrapping_sub(Q);
+    let borrow_mask = 0u16.wrapping_sub(difference >> 15);
+    difference.wrapping_add(Q & borrow_mask)
+}
+
+fn compress(d: usize, f: &Poly) -> Poly {
+    // round(2^d * x / q), dividing by q with a multiply and shift: a hardware divide on secret
+    // data can take a data-dependent number of cycles.
+    const SHIFT: u32 = 36;
+    const RECIPROCAL: u64 = ((1u64 << SHIFT) + Q as u64 - 1) / Q as u64;
+    let mut result = [0u16; N];
+    (0..N).for_each(|i| {
+        let numerator = ((f[i] as u64) << d) + (Q as u64 / 2);
+        result[i] = (((numerator * RECIPROCAL) >> SHIFT) as u16) & ((1u16 << d) - 1);
+    });
+    result
+}
+
+fn decompress(d: usize, f: &Poly) -> Poly {
+    let mut result = [0u16; N];
+    (0..N).for_each(|i| {
+        result[i] = ((f[i] as u32 * Q as u32 + (1u32 << (d - 1))) >> d) as u16;
+    });
+    result
+}
+
+fn encode(d: usize, f: &Poly) -> Vec<u8> {
+    let mut result = Vec::with_capacity(32 * d);
+    let mut accumulator = 0u32;
+    let mut bits = 0;
+    f.iter().for_each(|coefficient| {
+        accumulator |= (*coefficient as u32) << bits;
+        bits += d;
+        while bits >= 8 {
+            result.push(accumulator as u8);
+            accumulator >>= 8;
+            bits -= 8;
+        }
+    });
+    result
+}
+
+fn decode(d: usize, bytes: &[u8]) -> Poly {
+    let mask = (1u32 << d) - 1;
+    let mut result = [0u16; N];
+    let mut accumulator = 0u32;
+    let mut bits = 0;
+    let mut count = 0;
+    for byte in &bytes[..32 * d] {
+        accumulator |= (*byte as u32) << bits;
+        bits += 8;
+        while bits >= d && count < N {
+            let value = (accumulator & mask) as u16;
+            result[count] = if d == 12 { reduce_once(value) } else { value };
+            accumulator >>= d;
+            bits -= d;
+            count += 1;
+        }
+    }
+    result
+}
+
+fn decode_poly_vec(d: usize, bytes: &[u8]) -> PolyVec {
+    let mut result = [[0u16; N]; K];
+    bytes
+        .chunks(32 * d)
+        .zip(result.iter_mut())
+        .for_each(|(chunk, poly)| *poly = decode(d, chunk));
+    result
+}
+
+fn prf(eta: usize, seed: &[u8; 32], nonce: u8) -> Vec<u8> {
+    let mut shake = Shake::v256();
+    shake.update(seed);
+    shake.update(&[nonce]);
+    let mut result = vec![0u8; 64 * eta];
+    shake.finalize(&mut result);
+    result
+}
+
+fn g(inputs: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+    let mut sha3 = Sha3::v512();
+    inputs.iter().for_each(|input| sha3.update(input));
+    let mut output = [0u8; 64];
+    sha3.finalize(&mut output);
+    let mut first = [0u8; 32];
+    let mut second = [0u8; 32];
+    first.copy_from_slice(&output[..32]);
+    second.copy_from_slice(&output[32..]);
+    (first, second)
+}
+
+fn h(input: &[u8]) -> [u8; 32] {
+    let mut sha3 = Sha3::v256();
+    sha3.update(input);
+    let mut output = [0u8; 32];
+    sha3.finalize(&mut output);
+    output
+}
+
+fn j(inputs: &[&[u8]]) -> [u8; 32] {
+    let mut shake = Shake::v256();
+    inputs.iter().for_each(|input| shake.update(input));
+    let mut output = [0u8; 32];
+    shake.finalize(&mut output);
+    output
+}
+
+// 17 is a primitive 256th root of unity mod q. ZETAS holds 17^BitRev7(i) and GAMMAS holds
+// 17^(2 * BitRev7(i) + 1).
+const fn powers_of_zeta(gamma_offset: usize) -> [u16; 128] {
+    let mut result = [0u16; 128];
+    let mut i = 0;
+    while i < 128 {
+        let mut reversed = 0;
+        let mut bit = 0;
+        while bit < 7 {
+            reversed |= ((i >> bit) & 1) << (6 - bit);
+            bit += 1;
+        }
+        let exponent = if gamma_offset == 0 {
+            reversed
+        } else {
+            2 * reversed + gamma_offset
+        };
+        let mut power = 1u32;
+        let mut step = 0;
+        while step < exponent {
+            power = power * 17 % Q as u32;
+            step += 1;
+        }
+        result[i] = power as u16;
+        i += 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex_of(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    fn seed(first_byte: u8) -> [u8; KEYPAIR_SEED_LEN] {
+        let mut result = [0u8; KEYPAIR_SEED_LEN];
+        result
+            .iter_mut()
+            .enumerate()
+            .for_each(|(idx, byte)| *byte = first_byte.wrapping_add(idx as u8));
+        result
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(ENCAPSULATION_KEY_LEN, 1184);
+        assert_eq!(DECAPSULATION_KEY_LEN, 2400);
+        assert_eq!(CIPHERTEXT_LEN, 1088);
+        assert_eq!(SHARED_SECRET_LEN, 32);
+        assert_eq!(KEYPAIR_SEED_LEN, 64);
+        assert_eq!(ENCAPSULATION_SEED_LEN, 32);
+        assert_eq!(&ZETAS[..4], &[1, 1729, 2580, 3289]);
+        assert_eq!(&GAMMAS[..4], &[17, 3312, 2761, 568]);
+        assert_eq!(BARRETT_SHIFT, 26);
+        assert_eq!(BARRETT_MULTIPLIER, 20158);
+    }
+
+    // The expected values below come from OpenSSL's independent ML-KEM-768 implementation, run
+    // on the same seeds.
+    #[test]
+    fn keypair_from_seed_matches_reference_implementation() {
+        let (encapsulation_key, decapsulation_key) = keypair_from_seed(&seed(0));
+
+        assert_eq!(encapsulation_key.len(), ENCAPSULATION_KEY_LEN);
+        assert_eq!(decapsulation_key.len(), DECAPSULATION_KEY_LEN);
+        assert_eq!(
+            hex_of(&h(&encapsulation_key)),
+            "a24e16d8f8f9383a95b77050f4d9fd2f5733eec1d63ef3c23ebf9918173669a7"
+        );
+        assert_eq!(
+            hex_of(&h(&decapsulation_key)),
+            "1149f17c3c4ac6ab1e3e2d9d8bd0171355ac0fa31bb8855c48ceade874c0864b"
+        );
+    }
+
+    #[test]
+    fn encapsulation_matches_reference_implementation_and_decapsulates() {
+        let (encapsulation_key, decapsulation_key) = keypair_from_seed(&seed(0));
+
+        let (shared_secret, ciphertext) = encapsulate(&encapsulation_key, &[7u8; 32]).unwrap();
+
+        assert_eq!(ciphertext.len(), CIPHERTEXT_LEN);
+        assert_eq!(
+            hex_of(&h(&ciphertext)),
+            "a2ccffc801ffd1202ecf6b9a7fb3235a6efa4d4963cc84dc6c69239223b54a5c"
+        );
+        assert_eq!(
+            hex_of(&shared_secret),
+            "f3409cb545c0757aab3d7c7b9e8be4225b4aac1107f6663f1f19dc676a69de60"
+        );
+        assert_eq!(
+            decapsulate(&decapsulation_key, &ciphertext),
+            Ok(shared_secret)
+        );
+    }
+
+    #[test]
+    fn tampered_ciphertext_decapsulates_to_the_implicit_rejection_secret() {
+        let (encapsulation_key, decapsulation_key) = keypair_from_seed(&seed(0));
+        let (_, mut ciphertext) = encapsulate(&encapsulation_key, &[7u8; 32]).unwrap();
+        ciphertext[0] ^= 1;
+
+        let result = decapsulate(&decapsulation_key, &ciphertext).unwrap();
+
+        assert_eq!(
+            hex_of(&result),
+            "206be478d442255385855fd6c36ab087508efd27b920ff6213282b5e2fc3401d"
+        );
+    }
+
+    #[test]
+    fn different_keypairs_produce_different_secrets() {
+        let (first_key, _) = keypair_from_seed(&seed(0));
+        let (second_key, second_decapsulation_key) = keypair_from_seed(&seed(1));
+        let (first_secret, _) = encapsulate(&first_key, &[7u8; 32]).unwrap();
+        let (second_secret, ciphertext) = encapsulate(&second_key, &[7u8; 32]).unwrap();
+
+        let result = decapsulate(&second_decapsulation_key, &ciphertext).unwrap();
+
+        assert_ne!(first_key, second_key);
+        assert_ne!(first_secret, second_secret);
+        assert_eq!(result, second_secret);
+    }
+
+    #[test]
+    fn encapsulation_keys_of_the_wrong_length_are_rejected() {
+        let (encapsulation_key, _) = keypair_from_seed(&seed(0));
+
+        let result = encapsulate(&encapsulation_key[1..], &[7u8; 32]);
+
+        assert_eq!(result, Err(MlKemError::InvalidEncapsulationKey));
+    }
+
+    #[test]
+    fn encapsulation_keys_with_unreduced_coefficients_are_rejected() {
+        let (mut encapsulation_key, _) = keypair_from_seed(&seed(0));
+        encapsulation_key[0] = 0xFF;
+        encapsulation_key[1] |= 0x0F;
+
+        let result = encapsulate(&encapsulation_key, &[7u8; 32]);
+
+        assert_eq!(result, Err(MlKemError::InvalidEncapsulationKey));
+    }
+
+    #[test]
+    fn ciphertexts_of_the_wrong_length_are_rejected() {
+        let (_, decapsulation_key) = keypair_from_seed(&seed(0));
+
+        let result = decapsulate(&decapsulation_key, &[0u8; CIPHERTEXT_LEN - 1]);
+
+        assert_eq!(result, Err(MlKemError::InvalidCiphertext));
+    }
+
+    #[test]
+    fn decapsulation_keys_that_fail_their_hash_check_are_rejected() {
+        let (_, mut decapsulation_key) = keypair_from_seed(&seed(0));
+        decapsulation_key[384 * K] ^= 1;
+
+        let result = decapsulate(&decapsulation_key, &[0u8; CIPHERTEXT_LEN]);
+
+        assert_eq!(result, Err(MlKemError::InvalidDecapsulationKey));
+    }
+
+    #[test]
+    fn barrett_reduce_agrees_with_remainder_for_all_products_of_coefficients() {
+        (0..(Q as u32 - 1) * (Q as u32 - 1) + 1).for_each(|x| {
+            assert_eq!(barrett_reduce(x) as u32, x % Q as u32, "x = {}", x);
+        });
+    }
+
+    #[test]
+    fn reduce_once_agrees_with_remainder_below_twice_q() {
+        (0..2 * Q).for_each(|x| assert_eq!(reduce_once(x), x % Q, "x = {}", x));
+    }
+
+    #[test]
+    fn compress_rounds_exactly_like_division() {
+        [1, DU, DV].iter().for_each(|d| {
+            let mut poly = [0u16; N];
+            (0..Q).collect::<Vec<u16>>().chunks(N).for_each(|chunk| {
+                poly[..chunk.len()].copy_from_slice(chunk);
+                let result = compress(*d, &poly);
+                chunk.iter().enumerate().for_each(|(idx, x)| {
+                    let expected = ((((*x as u32) << d) + Q as u32 / 2) / Q as u32) % (1 << d);
+                    assert_eq!(result[idx] as u32, expected, "x = {}, d = {}", x, d);
+                });
+            });
+        });
+    }
+}
@@ -12,9 +12,12 @@ pub mod blockchain_bridge;
 pub mod channel_wrappers;
 pub mod combined_parameters;
 pub mod configurator;
+pub mod crash_report;
 pub mod cryptde;
+pub mod cryptde_hybrid;
 pub mod cryptde_null;
 pub mod cryptde_real;
+pub mod crypto_suite;
 pub mod data_version;
 pub mod dispatcher;
 pub mod framer;
@@ -26,6 +29,7 @@ pub mod http_response_start_finder;
 pub mod limiter;
 pub mod main_tools;
 pub mod migrations;
+pub mod ml_kem;
 pub mod neighborhood;
 pub mod node_addr;
 pub mod peer_actors;
@@ -45,3 +49,4 @@ pub mod ttl_hashmap;
 pub mod udp_socket_wrapper;
 pub mod ui_gateway;
 pub mod wallet;
+pub mod websocket_handshake;
@@ -63,6 +63,11 @@ pub struct NewPublicIp {
     pub new_ip: IpAddr,
 }
 
+#[derive(Message, Clone, PartialEq, Eq, Debug)]
+pub struct NewClandestinePorts {
+    pub ports: Vec<u16>,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::test_utils::recorder::peer_actors_builder;
@@ -1,6 +1,8 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::metrics::NodeMetrics;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::hopper::{ExpiredCoresPackage, MessageType};
+use crate::sub_lib::neighborhood::ConfigChangeMsg;
 use crate::sub_lib::peer_actors::BindMessage;
 use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
 use crate::sub_lib::sequence_buffer::SequencedPacket;
@@ -14,6 +16,7 @@ use std::fmt::{Debug, Formatter};
 use std::net::Ipv4Addr;
 use std::net::SocketAddr;
 use std::net::SocketAddrV4;
+use std::sync::Arc;
 
 pub fn error_socket_addr() -> SocketAddr {
     SocketAddr::from(SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), 0))
@@ -26,6 +29,7 @@ pub struct ProxyClientConfig {
     pub exit_service_rate: u64,
     pub exit_byte_rate: u64,
     pub is_decentralized: bool,
+    pub metrics: Arc<NodeMetrics>,
     pub crashable: bool,
 }
 
@@ -73,6 +77,7 @@ pub struct ProxyClientSubs {
     pub inbound_server_data: Recipient<InboundServerData>,
     pub dns_resolve_failed: Recipient<DnsResolveFailure_0v1>,
     pub node_from_ui: Recipient<NodeFromUiMessage>,
+    pub config_change_msg_sub: Recipient<ConfigChangeMsg>,
 }
 
 impl Debug for ProxyClientSubs {
@@ -139,6 +144,7 @@ mod tests {
             inbound_server_data: recipient!(recorder, InboundServerData),
             dns_resolve_failed: recipient!(recorder, DnsResolveFailure_0v1),
             node_from_ui: recipient!(recorder, NodeFromUiMessage),
+            config_change_msg_sub: recipient!(recorder, ConfigChangeMsg),
         };
 
         assert_eq!(format!("{:?}", subject), "ProxyClientSubs");
@@ -15,6 +15,7 @@ use actix::Recipient;
 use masq_lib::ui_gateway::NodeFromUiMessage;
 use serde_derive::{Deserialize, Serialize};
 use std::fmt::Debug;
+use std::time::SystemTime;
 
 pub const DEFAULT_MINIMUM_HOP_COUNT: usize = 3;
 
@@ -68,6 +69,16 @@ pub struct AddRouteResultMessage {
     pub result: Result<RouteQueryResponse, String>,
 }
 
+// Services consumed on the way out to the exit Node, to be tallied into service receipts
+#[derive(Message, Debug, PartialEq, Eq)]
+pub struct TallyServicesMessage {
+    pub return_route_id: u32,
+    pub timestamp: SystemTime,
+    pub expected_services: Vec<ExpectedService>,
+    pub exit_payload_size: usize,
+    pub routing_payload_size: usize,
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct ProxyServerSubs {
     // ProxyServer will handle these messages:
@@ -79,6 +90,7 @@ pub struct ProxyServerSubs {
     pub stream_shutdown_sub: Recipient<StreamShutdownMsg>,
     pub node_from_ui: Recipient<NodeFromUiMessage>,
     pub route_result_sub: Recipient<AddRouteResultMessage>,
+    pub tally_services: Recipient<TallyServicesMessage>,
 }
 
 impl Debug for ProxyServerSubs {
@@ -110,6 +122,7 @@ mod tests {
             stream_shutdown_sub: recipient!(recorder, StreamShutdownMsg),
             node_from_ui: recipient!(recorder, NodeFromUiMessage),
             route_result_sub: recipient!(recorder, AddRouteResultMessage),
+            tally_services: recipient!(recorder, TallyServicesMessage),
         };
 
         assert_eq!(format!("{:?}", subject), "ProxyServerSubs");
@@ -1,4 +1,5 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::blockchain::payer::Payer;
 use crate::sub_lib::cryptde::encodex;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::cryptde::CryptData;
@@ -11,6 +12,7 @@ use ethereum_types::Address;
 use itertools::Itertools;
 use serde_derive::{Deserialize, Serialize};
 use std::cmp::min;
+use std::collections::HashMap;
 use std::iter;
 
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -33,6 +35,7 @@ impl Route {
             None,
             None,
             None,
+            false,
         )
     }
 
@@ -49,6 +52,27 @@ impl Route {
             consuming_wallet,
             None,
             contract_address,
+            false,
+        )
+    }
+
+    // Like one_way(), except that the recipient learns the key of the Node at the start of the
+    // Route segment. That defeats the purpose of a multi-hop Route, so it's only for packages
+    // that name their originator anyway, such as service receipts.
+    pub fn one_way_from_originator(
+        route_segment: RouteSegment,
+        cryptde: &dyn CryptDE, // Any CryptDE can go here; it's only used to encrypt to public keys.
+        consuming_wallet: Option<Wallet>,
+        contract_address: Option<Address>,
+    ) -> Result<Route, CodexError> {
+        Self::construct(
+            route_segment,
+            None,
+            cryptde,
+            consuming_wallet,
+            None,
+            contract_address,
+            true,
         )
     }
 
@@ -67,6 +91,33 @@ impl Route {
             consuming_wallet,
             Some(return_route_id),
             contract_address,
+            false,
+        )
+    }
+
+    // Like round_trip(), but for a consuming wallet whose payer proofs were made elsewhere; there
+    // must be one for each key on the Route.
+    pub fn round_trip_with_payers(
+        route_segment_over: RouteSegment,
+        route_segment_back: RouteSegment,
+        cryptde: &dyn CryptDE, // Must be the CryptDE of the originating Node: used to encrypt return_route_id.
+        payers: &HashMap<PublicKey, Payer>,
+        return_route_id: u32,
+    ) -> Result<Route, CodexError> {
+        Self::construct_with_payers(
+            route_segment_over,
+            Some(route_segment_back),
+            cryptde,
+            &|key| {
+                Some(
+                    payers
+                        .get(key)
+                        .unwrap_or_else(|| panic!("No payer proof for {}", key))
+                        .clone(),
+                )
+            },
+            Some(return_route_id),
+            false,
         )
     }
 
@@ -150,6 +201,30 @@ impl Route {
         consuming_wallet: Option<Wallet>,
         return_route_id_opt: Option<u32>,
         contract_address: Option<Address>,
+        reveal_originator: bool,
+    ) -> Result<Route, CodexError> {
+        let contract_address = contract_address.unwrap_or_else(Address::zero);
+        Self::construct_with_payers(
+            over,
+            back,
+            cryptde,
+            &|key| {
+                consuming_wallet
+                    .as_ref()
+                    .map(|wallet| wallet.as_payer(key, &contract_address))
+            },
+            return_route_id_opt,
+            reveal_originator,
+        )
+    }
+
+    fn construct_with_payers(
+        over: RouteSegment,
+        back: Option<RouteSegment>,
+        cryptde: &dyn CryptDE,
+        payer_for: &dyn Fn(&PublicKey) -> Option<Payer>,
+        return_route_id_opt: Option<u32>,
+        reveal_originator: bool,
     ) -> Result<Route, CodexError> {
         if let Some(error) = Route::validate_route_segments(&over, &back) {
             return Err(CodexError::RoutingError(error));
@@ -157,21 +232,14 @@ impl Route {
         let over_component = over.recipient;
         let over_keys = over.keys.iter();
 
-        let mut hops = Route::over_segment(
-            back.is_none(),
-            consuming_wallet.clone(),
-            over_keys,
-            over_component,
-            contract_address,
-        );
+        let mut hops = Route::over_segment(back.is_none(), payer_for, over_keys, over_component);
 
-        Route::back_segment(
-            &back,
-            consuming_wallet,
-            over_component,
-            &mut hops,
-            contract_address,
-        );
+        Route::back_segment(&back, payer_for, over_component, &mut hops);
+
+        if reveal_originator {
+            // The hop that expires the package follows the hops through the rest of the keys
+            hops[over.keys.len() - 1].originator_key_opt = Some(over.keys[0].clone());
+        }
 
         Route::hops_to_route(
             hops[0..].to_vec(),
@@ -183,42 +251,23 @@ impl Route {
 
     fn over_segment<'a>(
         one_way: bool,
-        consuming_wallet_opt: Option<Wallet>,
+        payer_for: &dyn Fn(&PublicKey) -> Option<Payer>,
         over_keys: impl Iterator<Item = &'a PublicKey>,
         over_component: Component,
-        contract_address_opt: Option<Address>,
     ) -> Vec<LiveHop> {
         let mut last_key: Option<PublicKey> = None;
         let mut hops: Vec<LiveHop> = over_keys
             .tuple_windows()
             .map(|(current_key, next_key)| {
                 last_key = Some(next_key.clone());
-                LiveHop::new(
-                    next_key,
-                    consuming_wallet_opt.as_ref().map(|w| {
-                        w.as_payer(
-                            &current_key,
-                            &contract_address_opt.unwrap_or_else(Address::zero),
-                        )
-                    }),
-                    Component::Hopper,
-                )
+                LiveHop::new(next_key, payer_for(current_key), Component::Hopper)
             })
             .collect();
         if one_way {
             let key = PublicKey::new(b"");
             match last_key {
                 Some(last_hop_key) => {
-                    hops.push(LiveHop::new(
-                        &key,
-                        consuming_wallet_opt.map(|w| {
-                            w.as_payer(
-                                &last_hop_key,
-                                &contract_address_opt.unwrap_or_else(Address::zero),
-                            )
-                        }),
-                        over_component,
-                    ));
+                    hops.push(LiveHop::new(&key, payer_for(&last_hop_key), over_component));
                 }
                 None => hops.push(LiveHop::new(&key, None, over_component)),
             }
@@ -228,10 +277,9 @@ impl Route {
 
     fn back_segment(
         back_option: &Option<RouteSegment>,
-        consuming_wallet: Option<Wallet>,
+        payer_for: &dyn Fn(&PublicKey) -> Option<Payer>,
         over_component: Component,
         hops: &mut Vec<LiveHop>,
-        contract_address: Option<Address>,
     ) {
         if let Some(back) = back_option {
             let back_component = back.recipient;
@@ -244,25 +292,14 @@ impl Route {
                     Component::Hopper
                 };
 
-                hops.push(LiveHop::new(
-                    next_key,
-                    consuming_wallet.clone().map(|w| {
-                        w.as_payer(
-                            &current_key,
-                            &contract_address.unwrap_or_else(Address::zero),
-                        )
-                    }),
-                    component,
-                ))
+                hops.push(LiveHop::new(next_key, payer_for(current_key), component))
             }
             let next_key = PublicKey::new(b"");
             match back_keys.last() {
                 Some(current_key) => {
                     hops.push(LiveHop::new(
                         &next_key,
-                        consuming_wallet.map(|w| {
-                            w.as_payer(current_key, &contract_address.unwrap_or_else(Address::zero))
-                        }),
+                        payer_for(current_key),
                         back_component,
                     ));
                 }
@@ -607,6 +644,86 @@ mod tests {
         );
     }
 
+    #[test]
+    fn round_trip_with_payers_puts_each_payer_on_the_hop_it_pays_for() {
+        let a_key = PublicKey::new(&[65, 65, 65]);
+        let b_key = PublicKey::new(&[66, 66, 66]);
+        let c_key = PublicKey::new(&[67, 67, 67]);
+        let cryptde = main_cryptde();
+        let paying_wallet = make_paying_wallet(b"wallet");
+        let contract_address = TEST_DEFAULT_CHAIN.rec().contract;
+        let payers = vec![&a_key, &b_key, &c_key]
+            .into_iter()
+            .map(|key| (key.clone(), paying_wallet.as_payer(key, &contract_address)))
+            .collect::<HashMap<PublicKey, Payer>>();
+
+        let subject = Route::round_trip_with_payers(
+            RouteSegment::new(vec![&a_key, &b_key, &c_key], Component::ProxyClient),
+            RouteSegment::new(vec![&c_key, &b_key, &a_key], Component::ProxyServer),
+            cryptde,
+            &payers,
+            1234,
+        )
+        .unwrap();
+
+        let expected = Route::round_trip(
+            RouteSegment::new(vec![&a_key, &b_key, &c_key], Component::ProxyClient),
+            RouteSegment::new(vec![&c_key, &b_key, &a_key], Component::ProxyServer),
+            cryptde,
+            Some(paying_wallet),
+            1234,
+            Some(contract_address),
+        )
+        .unwrap();
+        assert_eq!(subject, expected);
+    }
+
+    #[test]
+    fn one_way_from_originator_names_the_originator_only_to_the_recipient() {
+        let a_key = PublicKey::new(&[65, 65, 65]);
+        let b_key = PublicKey::new(&[66, 66, 66]);
+        let c_key = PublicKey::new(&[67, 67, 67]);
+        let cryptde = main_cryptde();
+        let paying_wallet = make_paying_wallet(b"wallet");
+        let contract_address = TEST_DEFAULT_CHAIN.rec().contract;
+
+        let subject = Route::one_way_from_originator(
+            RouteSegment::new(vec![&a_key, &b_key, &c_key], Component::Accountant),
+            cryptde,
+            Some(paying_wallet.clone()),
+            Some(contract_address),
+        )
+        .unwrap();
+
+        let empty_public_key = PublicKey::new(b"");
+        let mut recipient_hop = LiveHop::new(
+            &empty_public_key,
+            Some(paying_wallet.as_payer(&c_key, &contract_address)),
+            Component::Accountant,
+        );
+        recipient_hop.originator_key_opt = Some(a_key.clone());
+        assert_eq!(
+            vec!(
+                LiveHop::new(
+                    &b_key,
+                    Some(paying_wallet.as_payer(&a_key, &contract_address)),
+                    Component::Hopper
+                )
+                .encode(&a_key, cryptde)
+                .unwrap(),
+                LiveHop::new(
+                    &c_key,
+                    Some(paying_wallet.as_payer(&b_key, &contract_address)),
+                    Component::Hopper
+                )
+                .encode(&b_key, cryptde)
+                .unwrap(),
+                recipient_hop.encode(&c_key, cryptde).unwrap(),
+            ),
+            subject.hops,
+        );
+    }
+
     #[test]
     fn next_hop_decodes_top_hop() {
         let cryptde = main_cryptde();
@@ -781,9 +898,9 @@ mod tests {
             result,
             String::from(
                 r#"
-Encrypted with 0x01020304: LiveHop { public_key: 0x02030405, payer: Some(Payer { wallet: Wallet { kind: Address(0x71d0fc7d1c570b1ed786382b551a09391c91e33d) }, proof: Signature { v: 0, r: "8b663e5a10f40c3307e6fb5340482a5e11df78dafc619ceff97f11fa79fea320", s: "8b663e5a10f40c3307e6fb5340482a5e11df78dafc619ceff97f11fa79fea320" } }), component: Hopper }
-Encrypted with 0x02030405: LiveHop { public_key: 0x03040506, payer: Some(Payer { wallet: Wallet { kind: Address(0x71d0fc7d1c570b1ed786382b551a09391c91e33d) }, proof: Signature { v: 1, r: "63be72962f19dda7802220ed48c0d8199d510b45608a3789c50f61912b98a15b", s: "63be72962f19dda7802220ed48c0d8199d510b45608a3789c50f61912b98a15b" } }), component: Hopper }
-Encrypted with 0x03040506: LiveHop { public_key: 0x, payer: Some(Payer { wallet: Wallet { kind: Address(0x71d0fc7d1c570b1ed786382b551a09391c91e33d) }, proof: Signature { v: 1, r: "9ca23557adf96d7aed407a06ce96851a4184e947a7b29b6c3872eef902fcba1e", s: "9ca23557adf96d7aed407a06ce96851a4184e947a7b29b6c3872eef902fcba1e" } }), component: Neighborhood }
+Encrypted with 0x01020304: LiveHop { public_key: 0x02030405, payer: Some(Payer { wallet: Wallet { kind: Address(0x71d0fc7d1c570b1ed786382b551a09391c91e33d) }, proof: Signature { v: 0, r: "8b663e5a10f40c3307e6fb5340482a5e11df78dafc619ceff97f11fa79fea320", s: "8b663e5a10f40c3307e6fb5340482a5e11df78dafc619ceff97f11fa79fea320" } }), component: Hopper, originator_key_opt: None }
+Encrypted with 0x02030405: LiveHop { public_key: 0x03040506, payer: Some(Payer { wallet: Wallet { kind: Address(0x71d0fc7d1c570b1ed786382b551a09391c91e33d) }, proof: Signature { v: 1, r: "63be72962f19dda7802220ed48c0d8199d510b45608a3789c50f61912b98a15b", s: "63be72962f19dda7802220ed48c0d8199d510b45608a3789c50f61912b98a15b" } }), component: Hopper, originator_key_opt: None }
+Encrypted with 0x03040506: LiveHop { public_key: 0x, payer: Some(Payer { wallet: Wallet { kind: Address(0x71d0fc7d1c570b1ed786382b551a09391c91e33d) }, proof: Signature { v: 1, r: "9ca23557adf96d7aed407a06ce96851a4184e947a7b29b6c3872eef902fcba1e", s: "9ca23557adf96d7aed407a06ce96851a4184e947a7b29b6c3872eef902fcba1e" } }), component: Neighborhood, originator_key_opt: None }
 "#
             )
         );
@@ -818,11 +935,11 @@ Encrypted with 0x03040506: LiveHop { public_key: 0x, payer: Some(Payer { wallet:
             result,
             String::from(
                 r#"
-Encrypted with 0x01020304: LiveHop { public_key: 0x02030405, payer: Some(Payer { wallet: Wallet { kind: Address(0x71d0fc7d1c570b1ed786382b551a09391c91e33d) }, proof: Signature { v: 0, r: "8b663e5a10f40c3307e6fb5340482a5e11df78dafc619ceff97f11fa79fea320", s: "8b663e5a10f40c3307e6fb5340482a5e11df78dafc619ceff97f11fa79fea320" } }), component: Hopper }
-Encrypted with 0x02030405: LiveHop { public_key: 0x03040506, payer: Some(Payer { wallet: Wallet { kind: Address(0x71d0fc7d1c570b1ed786382b551a09391c91e33d) }, proof: Signature { v: 1, r: "63be72962f19dda7802220ed48c0d8199d510b45608a3789c50f61912b98a15b", s: "63be72962f19dda7802220ed48c0d8199d510b45608a3789c50f61912b98a15b" } }), component: Hopper }
-Encrypted with 0x03040506: LiveHop { public_key: 0x02030405, payer: Some(Payer { wallet: Wallet { kind: Address(0x71d0fc7d1c570b1ed786382b551a09391c91e33d) }, proof: Signature { v: 1, r: "9ca23557adf96d7aed407a06ce96851a4184e947a7b29b6c3872eef902fcba1e", s: "9ca23557adf96d7aed407a06ce96851a4184e947a7b29b6c3872eef902fcba1e" } }), component: ProxyClient }
-Encrypted with 0x02030405: LiveHop { public_key: 0x01020304, payer: Some(Payer { wallet: Wallet { kind: Address(0x71d0fc7d1c570b1ed786382b551a09391c91e33d) }, proof: Signature { v: 1, r: "63be72962f19dda7802220ed48c0d8199d510b45608a3789c50f61912b98a15b", s: "63be72962f19dda7802220ed48c0d8199d510b45608a3789c50f61912b98a15b" } }), component: Hopper }
-Encrypted with 0x01020304: LiveHop { public_key: 0x, payer: Some(Payer { wallet: Wallet { kind: Address(0x71d0fc7d1c570b1ed786382b551a09391c91e33d) }, proof: Signature { v: 0, r: "8b663e5a10f40c3307e6fb5340482a5e11df78dafc619ceff97f11fa79fea320", s: "8b663e5a10f40c3307e6fb5340482a5e11df78dafc619ceff97f11fa79fea320" } }), component: ProxyServer }
+Encrypted with 0x01020304: LiveHop { public_key: 0x02030405, payer: Some(Payer { wallet: Wallet { kind: Address(0x71d0fc7d1c570b1ed786382b551a09391c91e33d) }, proof: Signature { v: 0, r: "8b663e5a10f40c3307e6fb5340482a5e11df78dafc619ceff97f11fa79fea320", s: "8b663e5a10f40c3307e6fb5340482a5e11df78dafc619ceff97f11fa79fea320" } }), component: Hopper, originator_key_opt: None }
+Encrypted with 0x02030405: LiveHop { public_key: 0x03040506, payer: Some(Payer { wallet: Wallet { kind: Address(0x71d0fc7d1c570b1ed786382b551a09391c91e33d) }, proof: Signature { v: 1, r: "63be72962f19dda7802220ed48c0d8199d510b45608a3789c50f61912b98a15b", s: "63be72962f19dda7802220ed48c0d8199d510b45608a3789c50f61912b98a15b" } }), component: Hopper, originator_key_opt: None }
+Encrypted with 0x03040506: LiveHop { public_key: 0x02030405, payer: Some(Payer { wallet: Wallet { kind: Address(0x71d0fc7d1c570b1ed786382b551a09391c91e33d) }, proof: Signature { v: 1, r: "9ca23557adf96d7aed407a06ce96851a4184e947a7b29b6c3872eef902fcba1e", s: "9ca23557adf96d7aed407a06ce96851a4184e947a7b29b6c3872eef902fcba1e" } }), component: ProxyClient, originator_key_opt: None }
+Encrypted with 0x02030405: LiveHop { public_key: 0x01020304, payer: Some(Payer { wallet: Wallet { kind: Address(0x71d0fc7d1c570b1ed786382b551a09391c91e33d) }, proof: Signature { v: 1, r: "63be72962f19dda7802220ed48c0d8199d510b45608a3789c50f61912b98a15b", s: "63be72962f19dda7802220ed48c0d8199d510b45608a3789c50f61912b98a15b" } }), component: Hopper, originator_key_opt: None }
+Encrypted with 0x01020304: LiveHop { public_key: 0x, payer: Some(Payer { wallet: Wallet { kind: Address(0x71d0fc7d1c570b1ed786382b551a09391c91e33d) }, proof: Signature { v: 0, r: "8b663e5a10f40c3307e6fb5340482a5e11df78dafc619ceff97f11fa79fea320", s: "8b663e5a10f40c3307e6fb5340482a5e11df78dafc619ceff97f11fa79fea320" } }), component: ProxyServer, originator_key_opt: None }
 Encrypted with 0x01020304: Return Route ID: 1234
 "#
             )
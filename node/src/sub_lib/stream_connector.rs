@@ -3,6 +3,7 @@ use crate::sub_lib::tokio_wrappers::ReadHalfWrapper;
 use crate::sub_lib::tokio_wrappers::ReadHalfWrapperReal;
 use crate::sub_lib::tokio_wrappers::WriteHalfWrapper;
 use crate::sub_lib::tokio_wrappers::WriteHalfWrapperReal;
+use crate::sub_lib::websocket_handshake::WebSocketClientHandshake;
 use masq_lib::logger::Logger;
 use std::io::ErrorKind;
 use std::net::IpAddr;
@@ -28,6 +29,7 @@ pub struct ConnectionInfo {
 
 pub trait StreamConnector: Send {
     fn connect(&self, socket_addr: SocketAddr, logger: &Logger) -> ConnectionInfoFuture;
+    fn connect_websocket(&self, socket_addr: SocketAddr, logger: &Logger) -> ConnectionInfoFuture;
     fn connect_one(
         &self,
         ip_addrs: Vec<IpAddr>,
@@ -90,6 +92,39 @@ impl StreamConnector for StreamConnectorReal {
         )
     }
 
+    fn connect_websocket(&self, socket_addr: SocketAddr, logger: &Logger) -> ConnectionInfoFuture {
+        let future_logger = logger.clone();
+        let host = socket_addr.to_string();
+        Box::new(
+            Timeout::new(
+                TcpStream::connect(&socket_addr)
+                    .and_then(move |stream| WebSocketClientHandshake::new(stream, &host))
+                    .then(move |result| match result {
+                        Ok(stream) => {
+                            match (StreamConnectorReal {}).split_stream(stream, &future_logger) {
+                                Some(connection_info) => Ok(connection_info),
+                                None => Err(io::Error::from(ErrorKind::NotConnected)),
+                            }
+                        }
+                        Err(e) => {
+                            error!(
+                                future_logger,
+                                "Could not establish WebSocket connection to {}: {}",
+                                socket_addr,
+                                e
+                            );
+                            Err(e)
+                        }
+                    }),
+                Duration::from_millis(CONNECT_TIMEOUT_MS),
+            )
+            .map_err(|wrapped_error| match wrapped_error.into_inner() {
+                Some(error) => error,
+                None => io::Error::from(ErrorKind::TimedOut),
+            }),
+        )
+    }
+
     fn connect_one(
         &self,
         ip_addrs: Vec<IpAddr>,
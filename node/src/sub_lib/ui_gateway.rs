@@ -2,12 +2,82 @@
 
 use crate::sub_lib::peer_actors::BindMessage;
 use actix::Recipient;
+use masq_lib::ui_auth::UiTokens;
 use masq_lib::ui_gateway::{NodeFromUiMessage, NodeToUiMessage};
+use rand::Rng;
+use rustc_hex::ToHex;
 use std::fmt::{Debug, Formatter};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const UI_TOKEN_BYTES: usize = 32;
 
 #[derive(Clone, Debug)]
 pub struct UiGatewayConfig {
     pub ui_port: u16,
+    pub ui_token_file_opt: Option<PathBuf>,
+}
+
+pub fn load_or_create_ui_tokens(path: &Path) -> Result<UiTokens, String> {
+    if path.exists() {
+        ensure_private_file(path)?;
+        return UiTokens::read_from(path);
+    }
+    let tokens = UiTokens::new(&random_ui_token(), &random_ui_token());
+    path.parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| write_private_file(path, &tokens.file_contents()))
+        .map_err(|e| format!("Couldn't create UI token file {:?}: {}", path, e))?;
+    Ok(tokens)
+}
+
+fn random_ui_token() -> String {
+    let mut bytes = [0u8; UI_TOKEN_BYTES];
+    rand::thread_rng().fill(&mut bytes[..]);
+    bytes.to_hex()
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn write_private_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    use std::os::unix::fs::OpenOptionsExt;
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(contents.as_bytes())
+}
+
+// Whoever can read the tokens can act as the user's UI, so a file others can get at is refused
+// rather than used, the way ssh treats its private keys
+#[cfg(not(target_os = "windows"))]
+fn ensure_private_file(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = std::fs::metadata(path)
+        .map_err(|e| format!("Couldn't examine UI token file {:?}: {}", path, e))?
+        .permissions()
+        .mode();
+    if mode & 0o077 != 0 {
+        return Err(format!(
+            "UI token file {:?} is accessible to other users (mode {:03o}); restrict it with 'chmod 600'",
+            path,
+            mode & 0o777
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn ensure_private_file(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub fn write_private_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    // Files under the user's profile directory aren't readable by other users by default
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    file.write_all(contents.as_bytes())
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -30,6 +100,112 @@ mod tests {
     use crate::sub_lib::ui_gateway::UiGatewaySubs;
     use crate::test_utils::recorder::Recorder;
     use actix::Actor;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use std::fs;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(UI_TOKEN_BYTES, 32);
+    }
+
+    #[test]
+    fn load_or_create_ui_tokens_creates_a_private_file_with_random_tokens() {
+        let home_dir = ensure_node_home_directory_exists(
+            "ui_gateway",
+            "load_or_create_ui_tokens_creates_a_private_file_with_random_tokens",
+        );
+        let path = home_dir.join("ui_tokens");
+
+        let result = load_or_create_ui_tokens(&path).unwrap();
+
+        assert_eq!(result.admin.len(), UI_TOKEN_BYTES * 2);
+        assert_eq!(result.read_only.len(), UI_TOKEN_BYTES * 2);
+        assert_ne!(result.admin, result.read_only);
+        assert_eq!(UiTokens::read_from(&path), Ok(result.clone()));
+        #[cfg(not(target_os = "windows"))]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let second_home_dir = ensure_node_home_directory_exists(
+            "ui_gateway",
+            "load_or_create_ui_tokens_creates_a_private_file_with_random_tokens_again",
+        );
+        let other = load_or_create_ui_tokens(&second_home_dir.join("ui_tokens")).unwrap();
+        assert_ne!(other, result);
+    }
+
+    #[test]
+    fn load_or_create_ui_tokens_uses_an_existing_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "ui_gateway",
+            "load_or_create_ui_tokens_uses_an_existing_file",
+        );
+        let path = home_dir.join("ui_tokens");
+        write_private_file(&path, "admin=my own secret\nread-only=my other secret\n").unwrap();
+
+        let result = load_or_create_ui_tokens(&path);
+
+        assert_eq!(
+            result,
+            Ok(UiTokens::new("my own secret", "my other secret"))
+        );
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn load_or_create_ui_tokens_refuses_a_file_other_users_can_access() {
+        use std::os::unix::fs::PermissionsExt;
+        let home_dir = ensure_node_home_directory_exists(
+            "ui_gateway",
+            "load_or_create_ui_tokens_refuses_a_file_other_users_can_access",
+        );
+        let path = home_dir.join("ui_tokens");
+        fs::write(&path, "admin=my own secret\nread-only=my other secret\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+
+        let result = load_or_create_ui_tokens(&path);
+
+        assert_eq!(
+            result,
+            Err(format!(
+                "UI token file {:?} is accessible to other users (mode 640); restrict it with 'chmod 600'",
+                path
+            ))
+        );
+    }
+
+    #[test]
+    fn load_or_create_ui_tokens_creates_a_missing_directory() {
+        let home_dir = ensure_node_home_directory_exists(
+            "ui_gateway",
+            "load_or_create_ui_tokens_creates_a_missing_directory",
+        );
+        let path = home_dir.join("nonexistent").join("ui_tokens");
+
+        let result = load_or_create_ui_tokens(&path).unwrap();
+
+        assert_eq!(UiTokens::read_from(&path), Ok(result));
+    }
+
+    #[test]
+    fn load_or_create_ui_tokens_complains_if_the_file_cannot_be_created() {
+        let home_dir = ensure_node_home_directory_exists(
+            "ui_gateway",
+            "load_or_create_ui_tokens_complains_if_the_file_cannot_be_created",
+        );
+        fs::write(home_dir.join("not_a_directory"), "").unwrap();
+        let path = home_dir.join("not_a_directory").join("ui_tokens");
+
+        let result = load_or_create_ui_tokens(&path).err().unwrap();
+
+        assert!(
+            result.starts_with(&format!("Couldn't create UI token file {:?}: ", path)),
+            "{}",
+            result
+        );
+    }
 
     #[test]
     fn ui_gateway_subs_debug() {
@@ -1,6 +1,7 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::database::db_initializer::{InitializationError, DATABASE_FILE};
+use crate::sub_lib::crash_report::announce_crashing_actor;
 use actix::{Actor, AsyncContext, Context, Handler, Message, SpawnHandle};
 use clap::App;
 use masq_lib::logger::Logger;
@@ -106,6 +107,7 @@ pub fn handle_ui_crash_request(
     let crash_analyzer = crash_request_analyzer;
     if let Some(cr) = crash_analyzer(msg, logger, crashable, crash_key) {
         let processed_with = type_name_of(crash_analyzer);
+        announce_crashing_actor(crash_key);
         panic!("{} (processed with: {})", cr.panic_message, processed_with)
     }
 }
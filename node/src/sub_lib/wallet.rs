@@ -154,6 +154,10 @@ impl Wallet {
         }
     }
 
+    pub fn can_sign(&self) -> bool {
+        matches!(self.kind, WalletKind::SecretKey(_))
+    }
+
     pub fn prepare_secp256k1_secret(
         &self,
     ) -> Result<secp256k1secrets::key::SecretKey, WalletError> {
@@ -683,6 +687,24 @@ mod tests {
         assert_eq!(result, expected_keypair);
     }
 
+    #[test]
+    fn only_keypair_wallets_can_sign() {
+        let secret_key_text = "0000000000000000000000003f69f9efd4f2592fd70be8c32ecd9dce71c472fc";
+        let keypair = Bip32EncryptionKeyProvider::from_raw_secret(
+            &secret_key_text.from_hex::<Vec<u8>>().unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(Wallet::from(keypair).can_sign(), true);
+        assert_eq!(
+            Wallet::from_str("0x3f69f9efd4f2592fd70be8c32ecd9dce71c472fc")
+                .unwrap()
+                .can_sign(),
+            false
+        );
+        assert_eq!(Wallet::null().can_sign(), false);
+    }
+
     #[test]
     fn cant_convert_to_keypair_if_didnt_come_from_keypair() {
         let subject = Wallet::from_str("0x3f69f9efd4f2592fd70be8c32ecd9dce71c472fc").unwrap();
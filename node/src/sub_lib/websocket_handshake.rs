@@ -0,0 +1,584 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::tokio_wrappers::ReadHalfWrapper;
+use futures::try_ready;
+use std::io;
+use std::io::{ErrorKind, Read};
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::net::TcpStream;
+use tokio::prelude::Async;
+use tokio::prelude::Future;
+use tokio::prelude::Poll;
+
+pub const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+pub const MAX_HANDSHAKE_HEADER_LEN: usize = 8192;
+pub const SERVER_HANDSHAKE_TIMEOUT_MS: u64 = 5000;
+const UPGRADE_REQUEST_PREFIX: &[u8] = b"GET ";
+const HEADER_TERMINATOR: &[u8] = b"\r\n\r\n";
+
+pub fn accept_key_for(client_key: &str) -> String {
+    let mut hash = sha1::Sha1::new();
+    hash.update(client_key.as_bytes());
+    hash.update(WEBSOCKET_GUID.as_bytes());
+    base64::encode(&hash.digest().bytes())
+}
+
+pub fn make_client_key() -> String {
+    let nonce: [u8; 16] = rand::random();
+    base64::encode(&nonce)
+}
+
+pub fn make_upgrade_request(host: &str, client_key: &str) -> Vec<u8> {
+    format!(
+        "GET / HTTP/1.1\r\n\
+         Host: {}\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Key: {}\r\n\
+         Sec-WebSocket-Version: 13\r\n\
+         \r\n",
+        host, client_key
+    )
+    .into_bytes()
+}
+
+pub fn make_upgrade_response(client_key: &str) -> Vec<u8> {
+    format!(
+        "HTTP/1.1 101 Switching Protocols\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\
+         \r\n",
+        accept_key_for(client_key)
+    )
+    .into_bytes()
+}
+
+// Returns the client's Sec-WebSocket-Key if the header is an acceptable upgrade request.
+pub fn parse_upgrade_request(header: &[u8]) -> Result<String, String> {
+    let lines = header_lines(header)?;
+    let request_line = lines.first().copied().unwrap_or("");
+    if !request_line.starts_with("GET ") || !request_line.ends_with(" HTTP/1.1") {
+        return Err(format!(
+            "Expected a WebSocket upgrade request, not '{}'",
+            request_line
+        ));
+    }
+    if !header_contains_token(&lines, "Upgrade", "websocket") {
+        return Err("Request does not ask to upgrade to websocket".to_string());
+    }
+    if !header_contains_token(&lines, "Connection", "upgrade") {
+        return Err("Request does not ask for a connection upgrade".to_string());
+    }
+    match header_value(&lines, "Sec-WebSocket-Version") {
+        Some("13") => (),
+        Some(version) => return Err(format!("Unsupported WebSocket version {}", version)),
+        None => return Err("Request has no Sec-WebSocket-Version".to_string()),
+    }
+    match header_value(&lines, "Sec-WebSocket-Key") {
+        Some(key) if !key.is_empty() => Ok(key.to_string()),
+        _ => Err("Request has no Sec-WebSocket-Key".to_string()),
+    }
+}
+
+pub fn verify_upgrade_response(header: &[u8], client_key: &str) -> Result<(), String> {
+    let lines = header_lines(header)?;
+    let status_line = lines.first().copied().unwrap_or("");
+    if !status_line.starts_with("HTTP/1.1 101") {
+        return Err(format!(
+            "Server refused WebSocket upgrade: '{}'",
+            status_line
+        ));
+    }
+    let expected_accept = accept_key_for(client_key);
+    match header_value(&lines, "Sec-WebSocket-Accept") {
+        Some(accept) if accept == expected_accept => Ok(()),
+        Some(accept) => Err(format!(
+            "Server sent Sec-WebSocket-Accept '{}' instead of '{}'",
+            accept, expected_accept
+        )),
+        None => Err("Server sent no Sec-WebSocket-Accept".to_string()),
+    }
+}
+
+fn header_lines(header: &[u8]) -> Result<Vec<&str>, String> {
+    let text = std::str::from_utf8(header)
+        .map_err(|_| "WebSocket handshake header is not UTF-8".to_string())?;
+    Ok(text.split("\r\n").filter(|line| !line.is_empty()).collect())
+}
+
+fn header_value<'a>(lines: &[&'a str], name: &str) -> Option<&'a str> {
+    lines.iter().skip(1).find_map(|line| {
+        let mut pieces = line.splitn(2, ':');
+        match (pieces.next(), pieces.next()) {
+            (Some(key), Some(value)) if key.trim().eq_ignore_ascii_case(name) => Some(value.trim()),
+            _ => None,
+        }
+    })
+}
+
+fn header_contains_token(lines: &[&str], name: &str, token: &str) -> bool {
+    match header_value(lines, name) {
+        Some(value) => value
+            .split(',')
+            .any(|candidate| candidate.trim().eq_ignore_ascii_case(token)),
+        None => false,
+    }
+}
+
+// Reads one byte at a time so that nothing after the end of the header is consumed.
+fn poll_read_header<S: AsyncRead>(stream: &mut S, header: &mut Vec<u8>) -> Poll<(), io::Error> {
+    while !header.ends_with(HEADER_TERMINATOR) {
+        if header.len() >= MAX_HANDSHAKE_HEADER_LEN {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "WebSocket handshake header exceeds {} bytes",
+                    MAX_HANDSHAKE_HEADER_LEN
+                ),
+            ));
+        }
+        let mut buf = [0u8; 1];
+        match try_ready!(stream.poll_read(&mut buf)) {
+            0 => return Err(io::Error::from(ErrorKind::UnexpectedEof)),
+            _ => header.push(buf[0]),
+        }
+    }
+    Ok(Async::Ready(()))
+}
+
+fn poll_write_all<S: AsyncWrite>(
+    stream: &mut S,
+    data: &[u8],
+    written: &mut usize,
+) -> Poll<(), io::Error> {
+    while *written < data.len() {
+        match try_ready!(stream.poll_write(&data[*written..])) {
+            0 => return Err(io::Error::from(ErrorKind::WriteZero)),
+            len => *written += len,
+        }
+    }
+    stream.poll_flush()
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum ServerHandshakeState {
+    Sniffing,
+    ReadingRequest,
+    WritingResponse,
+}
+
+#[derive(Debug)]
+pub enum ServerHandshakeOutcome {
+    Upgraded(TcpStream),
+    // Not an upgrade request; carries the bytes that were read while finding that out
+    Plain(TcpStream, Vec<u8>),
+}
+
+// Connections that don't begin with an upgrade request are handed back along with whatever was
+// read from them, so plain CORES traffic can share the port.
+pub struct WebSocketServerHandshake {
+    stream_opt: Option<TcpStream>,
+    state: ServerHandshakeState,
+    request_header: Vec<u8>,
+    response: Vec<u8>,
+    written: usize,
+}
+
+impl Future for WebSocketServerHandshake {
+    type Item = ServerHandshakeOutcome;
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        loop {
+            let stream = self
+                .stream_opt
+                .as_mut()
+                .expect("WebSocketServerHandshake polled after completion");
+            match self.state {
+                ServerHandshakeState::Sniffing => {
+                    // Reading rather than peeking, so that we're only woken up when more arrives
+                    let mut buf = [0u8; 1];
+                    match try_ready!(stream.poll_read(&mut buf)) {
+                        0 => return Err(io::Error::from(ErrorKind::UnexpectedEof)),
+                        _ => self.request_header.push(buf[0]),
+                    }
+                    let sniffed = &self.request_header;
+                    if sniffed[..] != UPGRADE_REQUEST_PREFIX[..sniffed.len()] {
+                        let stream = self.stream_opt.take().expect("stream disappeared");
+                        let sniffed = std::mem::take(&mut self.request_header);
+                        return Ok(Async::Ready(ServerHandshakeOutcome::Plain(stream, sniffed)));
+                    }
+                    if sniffed.len() == UPGRADE_REQUEST_PREFIX.len() {
+                        self.state = ServerHandshakeState::ReadingRequest;
+                    }
+                }
+                ServerHandshakeState::ReadingRequest => {
+                    try_ready!(poll_read_header(stream, &mut self.request_header));
+                    let client_key = parse_upgrade_request(&self.request_header)
+                        .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+                    self.response = make_upgrade_response(&client_key);
+                    self.state = ServerHandshakeState::WritingResponse;
+                }
+                ServerHandshakeState::WritingResponse => {
+                    try_ready!(poll_write_all(stream, &self.response, &mut self.written));
+                    let stream = self.stream_opt.take().expect("stream disappeared");
+                    return Ok(Async::Ready(ServerHandshakeOutcome::Upgraded(stream)));
+                }
+            }
+        }
+    }
+}
+
+impl WebSocketServerHandshake {
+    pub fn new(stream: TcpStream) -> Self {
+        Self {
+            stream_opt: Some(stream),
+            state: ServerHandshakeState::Sniffing,
+            request_header: vec![],
+            response: vec![],
+            written: 0,
+        }
+    }
+}
+
+// Gives back the bytes the server handshake read from a plain connection before the rest of it
+pub struct SniffedReadHalf {
+    sniffed: Vec<u8>,
+    delegate: Box<dyn ReadHalfWrapper>,
+}
+
+impl Read for SniffedReadHalf {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        if self.sniffed.is_empty() {
+            return self.delegate.read(buf);
+        }
+        let len = self.sniffed.len().min(buf.len());
+        buf[..len].copy_from_slice(&self.sniffed[..len]);
+        self.sniffed.drain(..len);
+        Ok(len)
+    }
+}
+
+impl AsyncRead for SniffedReadHalf {
+    fn poll_read(&mut self, buf: &mut [u8]) -> Poll<usize, io::Error> {
+        if self.sniffed.is_empty() {
+            return self.delegate.as_mut().poll_read(buf);
+        }
+        Ok(Async::Ready(self.read(buf)?))
+    }
+}
+
+impl ReadHalfWrapper for SniffedReadHalf {}
+
+impl SniffedReadHalf {
+    pub fn new(sniffed: Vec<u8>, delegate: Box<dyn ReadHalfWrapper>) -> Self {
+        Self { sniffed, delegate }
+    }
+}
+
+pub struct WebSocketClientHandshake {
+    stream_opt: Option<TcpStream>,
+    client_key: String,
+    request: Vec<u8>,
+    written: usize,
+    response_header: Vec<u8>,
+}
+
+impl Future for WebSocketClientHandshake {
+    type Item = TcpStream;
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let stream = self
+            .stream_opt
+            .as_mut()
+            .expect("WebSocketClientHandshake polled after completion");
+        try_ready!(poll_write_all(stream, &self.request, &mut self.written));
+        try_ready!(poll_read_header(stream, &mut self.response_header));
+        verify_upgrade_response(&self.response_header, &self.client_key)
+            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+        Ok(Async::Ready(
+            self.stream_opt.take().expect("stream disappeared"),
+        ))
+    }
+}
+
+impl WebSocketClientHandshake {
+    pub fn new(stream: TcpStream, host: &str) -> Self {
+        let client_key = make_client_key();
+        Self {
+            stream_opt: Some(stream),
+            request: make_upgrade_request(host, &client_key),
+            client_key,
+            written: 0,
+            response_header: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sub_lib::tokio_wrappers::ReadHalfWrapperReal;
+    use crate::test_utils::tokio_wrapper_mocks::ReadHalfWrapperMock;
+    use masq_lib::utils::{find_free_port, localhost};
+    use std::io::{Read, Write};
+    use std::net::SocketAddr;
+    use std::net::TcpListener as StdTcpListener;
+    use std::net::TcpStream as StdTcpStream;
+    use std::thread;
+    use std::time::Duration;
+    use tokio::reactor::Handle;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(WEBSOCKET_GUID, "258EAFA5-E914-47DA-95CA-C5AB0DC85B11");
+        assert_eq!(MAX_HANDSHAKE_HEADER_LEN, 8192);
+        assert_eq!(SERVER_HANDSHAKE_TIMEOUT_MS, 5000);
+    }
+
+    #[test]
+    fn accept_key_matches_rfc_6455_example() {
+        let result = accept_key_for("dGhlIHNhbXBsZSBub25jZQ==");
+
+        assert_eq!(result, "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+    }
+
+    #[test]
+    fn client_keys_are_sixteen_random_bytes() {
+        let first = make_client_key();
+        let second = make_client_key();
+
+        assert_eq!(base64::decode(&first).unwrap().len(), 16);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn upgrade_request_can_be_parsed() {
+        let request = make_upgrade_request("1.2.3.4:5678", "dGhlIHNhbXBsZSBub25jZQ==");
+
+        let result = parse_upgrade_request(&request);
+
+        assert_eq!(result, Ok("dGhlIHNhbXBsZSBub25jZQ==".to_string()));
+    }
+
+    #[test]
+    fn upgrade_request_headers_are_case_insensitive() {
+        let request = b"GET /cores HTTP/1.1\r\nhost: example.com\r\nupgrade: WebSocket\r\n\
+            connection: keep-alive, Upgrade\r\nsec-websocket-key: booga\r\n\
+            sec-websocket-version: 13\r\n\r\n";
+
+        let result = parse_upgrade_request(request);
+
+        assert_eq!(result, Ok("booga".to_string()));
+    }
+
+    #[test]
+    fn upgrade_request_parsing_detects_problems() {
+        let check = |request: &str, expected: &str| {
+            assert_eq!(
+                parse_upgrade_request(request.as_bytes()),
+                Err(expected.to_string()),
+                "{}",
+                request
+            )
+        };
+
+        check(
+            "POST / HTTP/1.1\r\n\r\n",
+            "Expected a WebSocket upgrade request, not 'POST / HTTP/1.1'",
+        );
+        check(
+            "GET / HTTP/1.1\r\nConnection: Upgrade\r\n\r\n",
+            "Request does not ask to upgrade to websocket",
+        );
+        check(
+            "GET / HTTP/1.1\r\nUpgrade: websocket\r\n\r\n",
+            "Request does not ask for a connection upgrade",
+        );
+        check(
+            "GET / HTTP/1.1\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\r\n",
+            "Request has no Sec-WebSocket-Version",
+        );
+        check(
+            "GET / HTTP/1.1\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Version: 8\r\n\r\n",
+            "Unsupported WebSocket version 8",
+        );
+        check(
+            "GET / HTTP/1.1\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Version: 13\r\n\r\n",
+            "Request has no Sec-WebSocket-Key",
+        );
+        assert_eq!(
+            parse_upgrade_request(&[0x47, 0x45, 0x54, 0x20, 0xC0, 0x0D, 0x0A]),
+            Err("WebSocket handshake header is not UTF-8".to_string())
+        );
+    }
+
+    #[test]
+    fn upgrade_response_can_be_verified() {
+        let response = make_upgrade_response("booga");
+
+        let result = verify_upgrade_response(&response, "booga");
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn upgrade_response_verification_detects_problems() {
+        let wrong_key_response = make_upgrade_response("wrong");
+
+        assert_eq!(
+            verify_upgrade_response(b"HTTP/1.1 403 Forbidden\r\n\r\n", "booga"),
+            Err("Server refused WebSocket upgrade: 'HTTP/1.1 403 Forbidden'".to_string())
+        );
+        assert_eq!(
+            verify_upgrade_response(b"HTTP/1.1 101 Switching Protocols\r\n\r\n", "booga"),
+            Err("Server sent no Sec-WebSocket-Accept".to_string())
+        );
+        assert_eq!(
+            verify_upgrade_response(&wrong_key_response, "booga"),
+            Err(format!(
+                "Server sent Sec-WebSocket-Accept '{}' instead of '{}'",
+                accept_key_for("wrong"),
+                accept_key_for("booga")
+            ))
+        );
+    }
+
+    fn accept_one_with_server_handshake(
+        port: u16,
+    ) -> thread::JoinHandle<io::Result<ServerHandshakeOutcome>> {
+        let listener = StdTcpListener::bind(SocketAddr::new(localhost(), port)).unwrap();
+        thread::spawn(move || {
+            let (std_stream, _) = listener.accept().unwrap();
+            let stream = TcpStream::from_std(std_stream, &Handle::default()).unwrap();
+            WebSocketServerHandshake::new(stream).wait()
+        })
+    }
+
+    #[test]
+    fn client_and_server_handshakes_agree_and_leave_frames_unread() {
+        let port = find_free_port();
+        let server_handle = accept_one_with_server_handshake(port);
+        let address = SocketAddr::new(localhost(), port);
+
+        let client_stream = TcpStream::connect(&address)
+            .and_then(|stream| WebSocketClientHandshake::new(stream, "localhost"))
+            .wait()
+            .unwrap();
+
+        let server_stream = match server_handle.join().unwrap().unwrap() {
+            ServerHandshakeOutcome::Upgraded(stream) => stream,
+            outcome => panic!("Expected an upgrade, not {:?}", outcome),
+        };
+        tokio::io::write_all(client_stream, [0x82, 0x01, 0x42])
+            .wait()
+            .unwrap();
+        let (_, buf) = tokio::io::read_exact(server_stream, [0u8; 3])
+            .wait()
+            .unwrap();
+        assert_eq!(buf, [0x82, 0x01, 0x42]);
+    }
+
+    #[test]
+    fn server_handshake_passes_through_connections_that_dont_upgrade() {
+        let port = find_free_port();
+        let server_handle = accept_one_with_server_handshake(port);
+        let mut client = StdTcpStream::connect(SocketAddr::new(localhost(), port)).unwrap();
+
+        client.write_all(b"{\"bodyText\": \"booga\"}").unwrap();
+
+        let (server_stream, sniffed) = match server_handle.join().unwrap().unwrap() {
+            ServerHandshakeOutcome::Plain(stream, sniffed) => (stream, sniffed),
+            outcome => panic!("Expected a plain connection, not {:?}", outcome),
+        };
+        assert_eq!(sniffed, b"{".to_vec());
+        let (read_half, _) = server_stream.split();
+        let reader = SniffedReadHalf::new(sniffed, Box::new(ReadHalfWrapperReal::new(read_half)));
+        let (_, buf) = tokio::io::read_exact(reader, [0u8; 5]).wait().unwrap();
+        assert_eq!(&buf, b"{\"bod");
+    }
+
+    #[test]
+    fn server_handshake_waits_for_an_upgrade_request_that_arrives_piecemeal() {
+        let port = find_free_port();
+        let server_handle = accept_one_with_server_handshake(port);
+        let mut client = StdTcpStream::connect(SocketAddr::new(localhost(), port)).unwrap();
+        let request = make_upgrade_request("localhost", "dGhlIHNhbXBsZSBub25jZQ==");
+
+        client.write_all(&request[..2]).unwrap();
+        thread::sleep(Duration::from_millis(100));
+        client.write_all(&request[2..]).unwrap();
+
+        match server_handle.join().unwrap().unwrap() {
+            ServerHandshakeOutcome::Upgraded(_) => (),
+            outcome => panic!("Expected an upgrade, not {:?}", outcome),
+        }
+    }
+
+    #[test]
+    fn sniffed_read_half_gives_back_sniffed_bytes_before_the_rest() {
+        let delegate =
+            ReadHalfWrapperMock::new().poll_read_result(b"def".to_vec(), Ok(Async::Ready(3)));
+        let mut subject = SniffedReadHalf::new(b"abc".to_vec(), Box::new(delegate));
+        let mut first_buf = [0u8; 2];
+        let mut second_buf = [0u8; 2];
+        let mut third_buf = [0u8; 3];
+
+        let first = subject.poll_read(&mut first_buf).unwrap();
+        let second = subject.poll_read(&mut second_buf).unwrap();
+        let third = subject.poll_read(&mut third_buf).unwrap();
+
+        assert_eq!((first, &first_buf[..]), (Async::Ready(2), &b"ab"[..]));
+        assert_eq!((second, &second_buf[..1]), (Async::Ready(1), &b"c"[..]));
+        assert_eq!((third, &third_buf[..]), (Async::Ready(3), &b"def"[..]));
+    }
+
+    #[test]
+    fn server_handshake_rejects_bad_upgrade_request() {
+        let port = find_free_port();
+        let server_handle = accept_one_with_server_handshake(port);
+        let mut client = StdTcpStream::connect(SocketAddr::new(localhost(), port)).unwrap();
+
+        client
+            .write_all(b"GET / HTTP/1.1\r\nHost: booga\r\n\r\n")
+            .unwrap();
+
+        let err = server_handle.join().unwrap().err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "Request does not ask to upgrade to websocket"
+        );
+    }
+
+    #[test]
+    fn client_handshake_rejects_server_that_does_not_upgrade() {
+        let port = find_free_port();
+        let listener = StdTcpListener::bind(SocketAddr::new(localhost(), port)).unwrap();
+        let server_handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut header = vec![];
+            let mut buf = [0u8; 1];
+            while !header.ends_with(HEADER_TERMINATOR) {
+                stream.read_exact(&mut buf).unwrap();
+                header.push(buf[0]);
+            }
+            stream.write_all(b"HTTP/1.1 404 Not Found\r\n\r\n").unwrap();
+        });
+        let address = SocketAddr::new(localhost(), port);
+
+        let result = TcpStream::connect(&address)
+            .and_then(|stream| WebSocketClientHandshake::new(stream, "localhost"))
+            .wait();
+
+        server_handle.join().unwrap();
+        let err = result.err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "Server refused WebSocket upgrade: 'HTTP/1.1 404 Not Found'"
+        );
+    }
+}
@@ -682,6 +682,7 @@ pub mod unshared_test_utils {
             .past_neighbors_result(Ok(None))
             .gas_price_result(Ok(1))
             .blockchain_service_url_result(Ok(None))
+            .blockchain_service_quorum_result(Ok(1))
             .min_hops_result(Ok(MIN_HOPS_FOR_TEST))
     }
 
@@ -7,6 +7,7 @@ use crate::neighborhood::{AccessibleGossipRecord, Neighborhood, DEFAULT_MIN_HOPS
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::cryptde::{CryptDE, PlainData};
 use crate::sub_lib::cryptde_null::CryptDENull;
+use crate::sub_lib::crypto_suite::CryptoSuite;
 use crate::sub_lib::neighborhood::{Hops, NeighborhoodConfig, NeighborhoodMode, NodeDescriptor};
 use crate::sub_lib::node_addr::NodeAddr;
 use crate::sub_lib::wallet::Wallet;
@@ -289,6 +290,7 @@ pub fn make_node_descriptor(ip_addr: IpAddr) -> NodeDescriptor {
         blockchain: Chain::EthRopsten,
         encryption_public_key: PublicKey::from(&b"bitcoin is real money"[..]),
         node_addr_opt: Some(NodeAddr::new(&ip_addr, &[1, 2, 3])),
+        crypto_suite: CryptoSuite::Curve25519,
     }
 }
 
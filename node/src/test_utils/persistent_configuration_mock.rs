@@ -21,6 +21,9 @@ pub struct PersistentConfigurationMock {
     blockchain_service_url_results: RefCell<Vec<Result<Option<String>, PersistentConfigError>>>,
     set_blockchain_service_url_params: Arc<Mutex<Vec<String>>>,
     set_blockchain_service_url_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    blockchain_service_quorum_results: RefCell<Vec<Result<u16, PersistentConfigError>>>,
+    set_blockchain_service_quorum_params: Arc<Mutex<Vec<u16>>>,
+    set_blockchain_service_quorum_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
     current_schema_version_results: RefCell<Vec<String>>,
     chain_name_params: Arc<Mutex<Vec<()>>>,
     chain_name_results: RefCell<Vec<String>>,
@@ -94,6 +97,20 @@ impl PersistentConfiguration for PersistentConfigurationMock {
             .remove(0)
     }
 
+    fn blockchain_service_quorum(&self) -> Result<u16, PersistentConfigError> {
+        Self::result_from(&self.blockchain_service_quorum_results)
+    }
+
+    fn set_blockchain_service_quorum(&mut self, quorum: u16) -> Result<(), PersistentConfigError> {
+        self.set_blockchain_service_quorum_params
+            .lock()
+            .unwrap()
+            .push(quorum);
+        self.set_blockchain_service_quorum_results
+            .borrow_mut()
+            .remove(0)
+    }
+
     fn current_schema_version(&self) -> String {
         Self::result_from(&self.current_schema_version_results)
     }
@@ -341,6 +358,31 @@ impl PersistentConfigurationMock {
         self
     }
 
+    pub fn blockchain_service_quorum_result(
+        self,
+        result: Result<u16, PersistentConfigError>,
+    ) -> Self {
+        self.blockchain_service_quorum_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
+    pub fn set_blockchain_service_quorum_params(mut self, params: &Arc<Mutex<Vec<u16>>>) -> Self {
+        self.set_blockchain_service_quorum_params = params.clone();
+        self
+    }
+
+    pub fn set_blockchain_service_quorum_result(
+        self,
+        result: Result<(), PersistentConfigError>,
+    ) -> Self {
+        self.set_blockchain_service_quorum_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
     pub fn current_schema_version_result(self, result: &str) -> Self {
         self.current_schema_version_results
             .borrow_mut()
@@ -5,11 +5,11 @@ use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::B
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::QualifiedPayablesMessage;
 use crate::accountant::ReportTransactionReceipts;
 use crate::accountant::{
-    ReceivedPayments, RequestTransactionReceipts, ScanError, ScanForPayables,
-    ScanForPendingPayables, ScanForReceivables, SentPayables,
+    ReceivedPayments, ReportConsumingWalletBalances, RequestTransactionReceipts, ScanError,
+    ScanForPayables, ScanForPendingPayables, ScanForReceivables, SentPayables,
 };
 use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
-use crate::blockchain::blockchain_bridge::RetrieveTransactions;
+use crate::blockchain::blockchain_bridge::{RequestConsumingWalletBalances, RetrieveTransactions};
 use crate::daemon::crash_notification::CrashNotification;
 use crate::daemon::DaemonBindMessage;
 use crate::neighborhood::gossip::Gossip_0v1;
@@ -18,6 +18,7 @@ use crate::sub_lib::accountant::AccountantSubs;
 use crate::sub_lib::accountant::ReportExitServiceProvidedMessage;
 use crate::sub_lib::accountant::ReportRoutingServiceProvidedMessage;
 use crate::sub_lib::accountant::ReportServicesConsumedMessage;
+use crate::sub_lib::accountant::ServiceReceipt_0v1;
 use crate::sub_lib::blockchain_bridge::BlockchainBridgeSubs;
 use crate::sub_lib::blockchain_bridge::OutboundPaymentsInstructions;
 use crate::sub_lib::dispatcher::InboundClientData;
@@ -33,14 +34,15 @@ use crate::sub_lib::neighborhood::NodeQueryResponseMetadata;
 use crate::sub_lib::neighborhood::RemoveNeighborMessage;
 use crate::sub_lib::neighborhood::RouteQueryMessage;
 use crate::sub_lib::neighborhood::RouteQueryResponse;
+use crate::sub_lib::neighborhood::ServedStreamCountMessage;
 use crate::sub_lib::neighborhood::UpdateNodeRecordMetadataMessage;
 use crate::sub_lib::neighborhood::{DispatcherNodeQueryMessage, GossipFailure_0v1};
 use crate::sub_lib::peer_actors::PeerActors;
-use crate::sub_lib::peer_actors::{BindMessage, NewPublicIp, StartMessage};
+use crate::sub_lib::peer_actors::{BindMessage, NewClandestinePorts, NewPublicIp, StartMessage};
 use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, InboundServerData};
 use crate::sub_lib::proxy_client::{DnsResolveFailure_0v1, ProxyClientSubs};
 use crate::sub_lib::proxy_server::{AddReturnRouteMessage, ClientRequestPayload_0v1};
-use crate::sub_lib::proxy_server::{AddRouteResultMessage, ProxyServerSubs};
+use crate::sub_lib::proxy_server::{AddRouteResultMessage, ProxyServerSubs, TallyServicesMessage};
 use crate::sub_lib::stream_handler_pool::DispatcherNodeQueryResponse;
 use crate::sub_lib::stream_handler_pool::TransmitDataMsg;
 use crate::sub_lib::ui_gateway::UiGatewaySubs;
@@ -139,9 +141,11 @@ recorder_message_handler_t_m_p!(ExpiredCoresPackage<DnsResolveFailure_0v1>);
 recorder_message_handler_t_m_p!(ExpiredCoresPackage<Gossip_0v1>);
 recorder_message_handler_t_m_p!(ExpiredCoresPackage<GossipFailure_0v1>);
 recorder_message_handler_t_m_p!(ExpiredCoresPackage<MessageType>);
+recorder_message_handler_t_m_p!(ExpiredCoresPackage<ServiceReceipt_0v1>);
 recorder_message_handler_t_m_p!(InboundClientData);
 recorder_message_handler_t_m_p!(InboundServerData);
 recorder_message_handler_t_m_p!(IncipientCoresPackage);
+recorder_message_handler_t_m_p!(NewClandestinePorts);
 recorder_message_handler_t_m_p!(NewPublicIp);
 recorder_message_handler_t_m_p!(NodeFromUiMessage);
 recorder_message_handler_t_m_p!(NodeToUiMessage);
@@ -153,10 +157,12 @@ recorder_message_handler_t_m_p!(QualifiedPayablesMessage);
 recorder_message_handler_t_m_p!(ReceivedPayments);
 recorder_message_handler_t_m_p!(RemoveNeighborMessage);
 recorder_message_handler_t_m_p!(RemoveStreamMsg);
+recorder_message_handler_t_m_p!(ReportConsumingWalletBalances);
 recorder_message_handler_t_m_p!(ReportExitServiceProvidedMessage);
 recorder_message_handler_t_m_p!(ReportRoutingServiceProvidedMessage);
 recorder_message_handler_t_m_p!(ReportServicesConsumedMessage);
 recorder_message_handler_t_m_p!(ReportTransactionReceipts);
+recorder_message_handler_t_m_p!(RequestConsumingWalletBalances);
 recorder_message_handler_t_m_p!(RequestTransactionReceipts);
 recorder_message_handler_t_m_p!(RetrieveTransactions);
 recorder_message_handler_t_m_p!(ScanError);
@@ -164,8 +170,10 @@ recorder_message_handler_t_m_p!(ScanForPayables);
 recorder_message_handler_t_m_p!(ScanForPendingPayables);
 recorder_message_handler_t_m_p!(ScanForReceivables);
 recorder_message_handler_t_m_p!(SentPayables);
+recorder_message_handler_t_m_p!(ServedStreamCountMessage);
 recorder_message_handler_t_m_p!(StartMessage);
 recorder_message_handler_t_m_p!(StreamShutdownMsg);
+recorder_message_handler_t_m_p!(TallyServicesMessage);
 recorder_message_handler_t_m_p!(TransmitDataMsg);
 recorder_message_handler_t_m_p!(UpdateNodeRecordMetadataMessage);
 
@@ -398,6 +406,7 @@ pub fn make_proxy_server_subs_from_recorder(addr: &Addr<Recorder>) -> ProxyServe
         stream_shutdown_sub: recipient!(addr, StreamShutdownMsg),
         node_from_ui: recipient!(addr, NodeFromUiMessage),
         route_result_sub: recipient!(addr, AddRouteResultMessage),
+        tally_services: recipient!(addr, TallyServicesMessage),
     }
 }
 
@@ -409,6 +418,7 @@ pub fn make_dispatcher_subs_from_recorder(addr: &Addr<Recorder>) -> DispatcherSu
         stream_shutdown_sub: recipient!(addr, StreamShutdownMsg),
         ui_sub: recipient!(addr, NodeFromUiMessage),
         new_ip_sub: recipient!(addr, NewPublicIp),
+        new_ports_sub: recipient!(addr, NewClandestinePorts),
     }
 }
 
@@ -419,6 +429,7 @@ pub fn make_hopper_subs_from_recorder(addr: &Addr<Recorder>) -> HopperSubs {
         from_hopper_client_no_lookup: recipient!(addr, NoLookupIncipientCoresPackage),
         from_dispatcher: recipient!(addr, InboundClientData),
         node_from_ui: recipient!(addr, NodeFromUiMessage),
+        config_change_msg_sub: recipient!(addr, ConfigChangeMsg),
     }
 }
 
@@ -429,6 +440,7 @@ pub fn make_proxy_client_subs_from_recorder(addr: &Addr<Recorder>) -> ProxyClien
         inbound_server_data: recipient!(addr, InboundServerData),
         dns_resolve_failed: recipient!(addr, DnsResolveFailure_0v1),
         node_from_ui: recipient!(addr, NodeFromUiMessage),
+        config_change_msg_sub: recipient!(addr, ConfigChangeMsg),
     }
 }
 
@@ -437,6 +449,7 @@ pub fn make_neighborhood_subs_from_recorder(addr: &Addr<Recorder>) -> Neighborho
         bind: recipient!(addr, BindMessage),
         start: recipient!(addr, StartMessage),
         new_public_ip: recipient!(addr, NewPublicIp),
+        new_clandestine_ports: recipient!(addr, NewClandestinePorts),
         route_query: recipient!(addr, RouteQueryMessage),
         update_node_record_metadata: recipient!(addr, UpdateNodeRecordMetadataMessage),
         from_hopper: recipient!(addr, ExpiredCoresPackage<Gossip_0v1>),
@@ -447,6 +460,7 @@ pub fn make_neighborhood_subs_from_recorder(addr: &Addr<Recorder>) -> Neighborho
         stream_shutdown_sub: recipient!(addr, StreamShutdownMsg),
         from_ui_message_sub: recipient!(addr, NodeFromUiMessage),
         connection_progress_sub: recipient!(addr, ConnectionProgressMessage),
+        served_stream_count_sub: recipient!(addr, ServedStreamCountMessage),
     }
 }
 
@@ -458,11 +472,13 @@ pub fn make_accountant_subs_from_recorder(addr: &Addr<Recorder>) -> AccountantSu
         report_routing_service_provided: recipient!(addr, ReportRoutingServiceProvidedMessage),
         report_exit_service_provided: recipient!(addr, ReportExitServiceProvidedMessage),
         report_services_consumed: recipient!(addr, ReportServicesConsumedMessage),
+        service_receipt: recipient!(addr, ExpiredCoresPackage<ServiceReceipt_0v1>),
         report_payable_payments_setup: recipient!(addr, BlockchainAgentWithContextMessage),
         report_inbound_payments: recipient!(addr, ReceivedPayments),
         init_pending_payable_fingerprints: recipient!(addr, PendingPayableFingerprintSeeds),
         report_transaction_receipts: recipient!(addr, ReportTransactionReceipts),
         report_sent_payments: recipient!(addr, SentPayables),
+        report_consuming_wallet_balances: recipient!(addr, ReportConsumingWalletBalances),
         scan_errors: recipient!(addr, ScanError),
         ui_message_sub: recipient!(addr, NodeFromUiMessage),
     }
@@ -484,6 +500,7 @@ pub fn make_blockchain_bridge_subs_from_recorder(addr: &Addr<Recorder>) -> Block
         retrieve_transactions: recipient!(addr, RetrieveTransactions),
         ui_sub: recipient!(addr, NodeFromUiMessage),
         request_transaction_receipts: recipient!(addr, RequestTransactionReceipts),
+        request_consuming_wallet_balances: recipient!(addr, RequestConsumingWalletBalances),
     }
 }
 
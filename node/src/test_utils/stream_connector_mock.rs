@@ -17,6 +17,7 @@ use tokio::prelude::Async;
 #[derive(Default)]
 pub struct StreamConnectorMock {
     connect_pair_params: Arc<Mutex<Vec<SocketAddr>>>,
+    connect_websocket_params: Arc<Mutex<Vec<SocketAddr>>>,
     connect_pair_results: RefCell<Vec<Result<ConnectionInfo, io::Error>>>,
     split_stream_results: RefCell<Vec<Option<ConnectionInfo>>>,
 }
@@ -28,6 +29,15 @@ impl StreamConnector for StreamConnectorMock {
         Box::new(result(connection_info_result))
     }
 
+    fn connect_websocket(&self, socket_addr: SocketAddr, _logger: &Logger) -> ConnectionInfoFuture {
+        self.connect_websocket_params
+            .lock()
+            .unwrap()
+            .push(socket_addr);
+        let connection_info_result = self.connect_pair_results.borrow_mut().remove(0);
+        Box::new(result(connection_info_result))
+    }
+
     fn connect_one(
         &self,
         _ip_addrs: Vec<IpAddr>,
@@ -50,6 +60,7 @@ impl StreamConnectorMock {
     pub fn new() -> StreamConnectorMock {
         Self {
             connect_pair_params: Arc::new(Mutex::new(vec![])),
+            connect_websocket_params: Arc::new(Mutex::new(vec![])),
             connect_pair_results: RefCell::new(vec![]),
             split_stream_results: RefCell::new(vec![]),
         }
@@ -105,6 +116,14 @@ impl StreamConnectorMock {
         self
     }
 
+    pub fn connect_websocket_params(
+        mut self,
+        params_arc: &Arc<Mutex<Vec<SocketAddr>>>,
+    ) -> StreamConnectorMock {
+        self.connect_websocket_params = params_arc.clone();
+        self
+    }
+
     pub fn connect_pair_result(
         self,
         result: Result<ConnectionInfo, io::Error>,
@@ -7,8 +7,8 @@ pub mod websocket_supervisor_mocks;
 
 use crate::daemon::DaemonBindMessage;
 use crate::sub_lib::peer_actors::BindMessage;
-use crate::sub_lib::ui_gateway::UiGatewayConfig;
 use crate::sub_lib::ui_gateway::UiGatewaySubs;
+use crate::sub_lib::ui_gateway::{load_or_create_ui_tokens, UiGatewayConfig};
 use crate::sub_lib::utils::NODE_MAILBOX_CAPACITY;
 use crate::ui_gateway::websocket_supervisor::{
     WebSocketSupervisor, WebSocketSupervisorFactory, WebsocketSupervisorFactoryReal,
@@ -24,11 +24,13 @@ use masq_lib::messages::UiCrashRequest;
 use masq_lib::ui_gateway::{MessageBody, NodeFromUiMessage, NodeToUiMessage};
 use masq_lib::utils::ExpectValue;
 use std::mem::replace;
+use std::path::PathBuf;
 
 pub const CRASH_KEY: &str = "UIGATEWAY";
 
 pub struct UiGateway {
     port: u16,
+    ui_token_file_opt: Option<PathBuf>,
     websocket_supervisor: Either<Box<dyn WebSocketSupervisorFactory>, Box<dyn WebSocketSupervisor>>,
     incoming_message_recipients: Vec<Recipient<NodeFromUiMessage>>,
     crashable: bool,
@@ -39,6 +41,7 @@ impl UiGateway {
     pub fn new(config: &UiGatewayConfig, crashable: bool) -> UiGateway {
         UiGateway {
             port: config.ui_port,
+            ui_token_file_opt: config.ui_token_file_opt.clone(),
             websocket_supervisor: Either::Left(Box::new(WebsocketSupervisorFactoryReal)),
             incoming_message_recipients: vec![],
             crashable,
@@ -96,13 +99,24 @@ impl UiGateway {
     }
 
     fn initiate_websocket_supervisor(&mut self, recipient: Recipient<NodeFromUiMessage>) {
+        let ui_tokens_opt = self.ui_token_file_opt.as_ref().map(|path| {
+            load_or_create_ui_tokens(path)
+                .unwrap_or_else(|e| panic!("Couldn't load UI tokens: {}", e))
+        });
+        if ui_tokens_opt.is_some() {
+            info!(
+                self.logger,
+                "UIs must present a token from {:?} to connect",
+                self.ui_token_file_opt.as_ref().expectv("UI token file")
+            );
+        }
         let ws = match self
             .websocket_supervisor
             .as_ref()
             .left()
             .as_ref()
             .expectv("WebSocket factory")
-            .make(self.port, recipient)
+            .make(self.port, ui_tokens_opt, recipient)
         {
             Ok(wss) => Either::Right(wss),
             Err(e) => panic!("Couldn't start WebSocketSupervisor: {:?}", e),
@@ -189,6 +203,7 @@ impl Handler<NodeFromUiMessage> for UiGateway {
 mod tests {
     use super::*;
     use crate::dispatcher;
+    use crate::sub_lib::ui_gateway::write_private_file;
     use crate::test_utils::recorder::peer_actors_builder;
     use crate::test_utils::recorder::{make_recorder, Recording};
     use crate::test_utils::unshared_test_utils::make_daemon_bind_message;
@@ -202,6 +217,8 @@ mod tests {
     use crossbeam_channel::{unbounded, Sender};
     use masq_lib::messages::{ToMessageBody, UiChangePasswordRequest};
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use masq_lib::ui_auth::UiTokens;
     use masq_lib::ui_gateway::MessagePath::FireAndForget;
     use masq_lib::ui_gateway::{MessageBody, MessagePath, MessageTarget};
     use masq_lib::utils::find_free_port;
@@ -232,6 +249,7 @@ mod tests {
         let subject = UiGateway::new(
             &UiGatewayConfig {
                 ui_port: find_free_port(),
+                ui_token_file_opt: None,
             },
             false,
         );
@@ -255,6 +273,7 @@ mod tests {
         let subject = UiGateway::new(
             &UiGatewayConfig {
                 ui_port: find_free_port(),
+                ui_token_file_opt: None,
             },
             false,
         );
@@ -289,6 +308,7 @@ mod tests {
         let subject = UiGateway::new(
             &UiGatewayConfig {
                 ui_port: find_free_port(),
+                ui_token_file_opt: None,
             },
             false,
         );
@@ -344,12 +364,20 @@ mod tests {
     fn outbound_ui_message_goes_only_to_websocket_supervisor() {
         let (accountant, _, accountant_recording_arc) = make_recorder();
         let send_msg_params_arc = Arc::new(Mutex::new(vec![]));
+        let make_params_arc = Arc::new(Mutex::new(vec![]));
         let websocket_supervisor =
             WebSocketSupervisorMock::new().send_msg_params(&send_msg_params_arc);
         let websocket_supervisor_factory = WebsocketSupervisorFactoryMock::default()
+            .make_params(&make_params_arc)
             .make_result(Ok(Box::new(websocket_supervisor)));
         let port = find_free_port();
-        let mut subject = UiGateway::new(&UiGatewayConfig { ui_port: port }, false);
+        let mut subject = UiGateway::new(
+            &UiGatewayConfig {
+                ui_port: port,
+                ui_token_file_opt: None,
+            },
+            false,
+        );
         subject.websocket_supervisor = Either::Left(
             Box::new(websocket_supervisor_factory) as Box<dyn WebSocketSupervisorFactory>
         );
@@ -374,6 +402,70 @@ mod tests {
         assert_eq!(accountant_recording.len(), 0);
         let send_parameters = send_msg_params_arc.lock().unwrap();
         assert_eq!(send_parameters[0], msg);
+        let make_params = make_params_arc.lock().unwrap();
+        assert_eq!(*make_params, vec![(port, None)]);
+    }
+
+    #[test]
+    fn websocket_supervisor_is_given_the_tokens_from_the_token_file() {
+        init_test_logging();
+        let home_dir = ensure_node_home_directory_exists(
+            "ui_gateway",
+            "websocket_supervisor_is_given_the_tokens_from_the_token_file",
+        );
+        let token_file = home_dir.join("ui_tokens");
+        write_private_file(&token_file, "admin=booga\nread-only=wooga\n").unwrap();
+        let make_params_arc = Arc::new(Mutex::new(vec![]));
+        let websocket_supervisor_factory = WebsocketSupervisorFactoryMock::default()
+            .make_params(&make_params_arc)
+            .make_result(Ok(Box::new(WebSocketSupervisorMock::new())));
+        let port = find_free_port();
+        let mut subject = UiGateway::new(
+            &UiGatewayConfig {
+                ui_port: port,
+                ui_token_file_opt: Some(token_file.clone()),
+            },
+            false,
+        );
+        subject.websocket_supervisor = Either::Left(
+            Box::new(websocket_supervisor_factory) as Box<dyn WebSocketSupervisorFactory>
+        );
+        let (recorder, _, _) = make_recorder();
+        let recipient = recorder.start().recipient();
+
+        subject.initiate_websocket_supervisor(recipient);
+
+        let make_params = make_params_arc.lock().unwrap();
+        assert_eq!(
+            *make_params,
+            vec![(port, Some(UiTokens::new("booga", "wooga")))]
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: UiGateway: UIs must present a token from {:?} to connect",
+            token_file
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "Couldn't load UI tokens: UI token file")]
+    fn websocket_supervisor_is_not_started_with_a_bad_token_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "ui_gateway",
+            "websocket_supervisor_is_not_started_with_a_bad_token_file",
+        );
+        let token_file = home_dir.join("ui_tokens");
+        write_private_file(&token_file, "admin=booga\n").unwrap();
+        let mut subject = UiGateway::new(
+            &UiGatewayConfig {
+                ui_port: find_free_port(),
+                ui_token_file_opt: Some(token_file),
+            },
+            false,
+        );
+        let (recorder, _, _) = make_recorder();
+        let recipient = recorder.start().recipient();
+
+        subject.initiate_websocket_supervisor(recipient);
     }
 
     #[test]
@@ -383,6 +475,7 @@ mod tests {
         let subject = UiGateway::new(
             &UiGatewayConfig {
                 ui_port: find_free_port(),
+                ui_token_file_opt: None,
             },
             false,
         );
@@ -426,7 +519,13 @@ mod tests {
             new_password: "bubbles".to_string(),
         }
         .tmb(12);
-        let subject = UiGateway::new(&UiGatewayConfig { ui_port: 123 }, false);
+        let subject = UiGateway::new(
+            &UiGatewayConfig {
+                ui_port: 123,
+                ui_token_file_opt: None,
+            },
+            false,
+        );
 
         let result = subject.deserialization_validator_with_crash_request_handler(msg_body);
 
@@ -441,7 +540,13 @@ mod tests {
             path: MessagePath::Conversation(45),
             payload: Err((1234, "We did it wrong".to_string())),
         };
-        let subject = UiGateway::new(&UiGatewayConfig { ui_port: 123 }, false);
+        let subject = UiGateway::new(
+            &UiGatewayConfig {
+                ui_port: 123,
+                ui_token_file_opt: None,
+            },
+            false,
+        );
 
         let result = subject.deserialization_validator_with_crash_request_handler(msg_body);
 
@@ -457,7 +562,13 @@ mod tests {
         }
         .tmb(0);
         let crashable = false;
-        let subject = UiGateway::new(&UiGatewayConfig { ui_port: 123 }, crashable);
+        let subject = UiGateway::new(
+            &UiGatewayConfig {
+                ui_port: 123,
+                ui_token_file_opt: None,
+            },
+            crashable,
+        );
 
         let result = subject.deserialization_validator_with_crash_request_handler(crash_request);
 
@@ -472,7 +583,13 @@ mod tests {
         }
         .tmb(0);
         let crashable = true;
-        let subject = UiGateway::new(&UiGatewayConfig { ui_port: 123 }, crashable);
+        let subject = UiGateway::new(
+            &UiGatewayConfig {
+                ui_port: 123,
+                ui_token_file_opt: None,
+            },
+            crashable,
+        );
 
         let result = subject.deserialization_validator_with_crash_request_handler(crash_request);
 
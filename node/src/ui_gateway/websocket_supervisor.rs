@@ -8,9 +8,10 @@ use futures::stream::SplitSink;
 use futures::Future;
 use futures::Sink;
 use futures::Stream;
-use masq_lib::constants::UNMARSHAL_ERROR;
+use masq_lib::constants::{UI_PERMISSION_ERROR, UNMARSHAL_ERROR};
 use masq_lib::logger::Logger;
 use masq_lib::messages::{ToMessageBody, UiUnmarshalError, NODE_UI_PROTOCOL};
+use masq_lib::ui_auth::{UiPermission, UiTokens, UI_TOKEN_HEADER};
 use masq_lib::ui_gateway::MessagePath::Conversation;
 use masq_lib::ui_gateway::MessageTarget::ClientId;
 use masq_lib::ui_gateway::{MessageBody, MessageTarget, NodeFromUiMessage, NodeToUiMessage};
@@ -68,9 +69,11 @@ pub struct WebSocketSupervisorReal {
 struct WebSocketSupervisorInner {
     port: u16,
     next_client_id: u64,
+    ui_tokens_opt: Option<UiTokens>,
     from_ui_message_sub: Recipient<NodeFromUiMessage>,
     client_id_by_socket_addr: HashMap<SocketAddr, u64>,
     socket_addr_by_client_id: HashMap<u64, SocketAddr>,
+    permission_by_client_id: HashMap<u64, UiPermission>,
     client_by_id: HashMap<u64, Box<dyn ClientWrapper>>,
 }
 
@@ -83,14 +86,17 @@ impl WebSocketSupervisor for WebSocketSupervisorReal {
 impl WebSocketSupervisorReal {
     pub fn new(
         port: u16,
+        ui_tokens_opt: Option<UiTokens>,
         from_ui_message_sub: Recipient<NodeFromUiMessage>,
     ) -> std::io::Result<Box<WebSocketSupervisorReal>> {
         let inner = Arc::new(Mutex::new(WebSocketSupervisorInner {
             port,
             next_client_id: 0,
+            ui_tokens_opt,
             from_ui_message_sub,
             client_id_by_socket_addr: HashMap::new(),
             socket_addr_by_client_id: HashMap::new(),
+            permission_by_client_id: HashMap::new(),
             client_by_id: HashMap::new(),
         }));
         let logger = Logger::new("WebSocketSupervisor");
@@ -200,30 +206,57 @@ impl WebSocketSupervisorReal {
         inner: Arc<Mutex<WebSocketSupervisorInner>>,
         logger: &Logger,
     ) {
-        if upgrade
+        if !upgrade
             .protocols()
             .contains(&String::from(NODE_UI_PROTOCOL))
         {
-            Self::accept_upgrade_request(upgrade, socket_addr, inner, logger);
-        } else {
             Self::reject_upgrade_request(upgrade, logger);
+            return;
+        }
+        match Self::permission_for(&upgrade, &inner) {
+            Some(permission) => {
+                Self::accept_upgrade_request(upgrade, socket_addr, permission, inner, logger)
+            }
+            None => Self::reject_unauthenticated_upgrade_request(upgrade, socket_addr, logger),
+        }
+    }
+
+    fn permission_for(
+        upgrade: &WsUpgrade<TcpStream, BytesMut>,
+        inner: &Arc<Mutex<WebSocketSupervisorInner>>,
+    ) -> Option<UiPermission> {
+        let locked_inner = inner.lock().expect("WebSocketSupervisor is poisoned");
+        let ui_tokens = match &locked_inner.ui_tokens_opt {
+            None => return Some(UiPermission::Admin),
+            Some(ui_tokens) => ui_tokens,
+        };
+        match upgrade.request.headers.get_raw(UI_TOKEN_HEADER) {
+            Some([token]) => match std::str::from_utf8(token) {
+                Ok(token) => ui_tokens.permission_of(token),
+                Err(_) => None,
+            },
+            _ => None,
         }
     }
 
     fn accept_upgrade_request(
         upgrade: WsUpgrade<TcpStream, BytesMut>,
         socket_addr: SocketAddr,
+        permission: UiPermission,
         inner: Arc<Mutex<WebSocketSupervisorInner>>,
         logger: &Logger,
     ) {
         let logger_clone = logger.clone();
-        info!(logger_clone, "UI connected at {}", socket_addr);
+        info!(
+            logger_clone,
+            "UI connected at {} with {:?} permission", socket_addr, permission
+        );
         let upgrade_future =
             upgrade
                 .use_protocol(NODE_UI_PROTOCOL)
                 .accept()
                 .map(move |(client, _)| {
-                    Self::handle_connection(client, &inner, &logger_clone, socket_addr);
+                    Self::handle_connection(client, &inner, &logger_clone, socket_addr, permission);
                 });
         tokio::spawn(upgrade_future.then(|result| {
             match result {
@@ -243,11 +276,26 @@ impl WebSocketSupervisorReal {
         tokio::spawn(upgrade.reject().then(|_| ok::<(), ()>(())));
     }
 
+    fn reject_unauthenticated_upgrade_request(
+        upgrade: WsUpgrade<TcpStream, BytesMut>,
+        socket_addr: SocketAddr,
+        logger: &Logger,
+    ) {
+        warning!(
+            logger,
+            "UI at {} attempted connection without a valid {} header; rejected",
+            socket_addr,
+            UI_TOKEN_HEADER
+        );
+        tokio::spawn(upgrade.reject().then(|_| ok::<(), ()>(())));
+    }
+
     fn handle_connection(
         client: Framed<TcpStream, MessageCodec<OwnedMessage>>,
         inner: &Arc<Mutex<WebSocketSupervisorInner>>,
         logger: &Logger,
         socket_addr: SocketAddr,
+        permission: UiPermission,
     ) {
         let logger_1 = logger.clone();
         let logger_2 = logger.clone();
@@ -267,6 +315,9 @@ impl WebSocketSupervisorReal {
         locked_inner
             .socket_addr_by_client_id
             .insert(client_id, socket_addr);
+        locked_inner
+            .permission_by_client_id
+            .insert(client_id, permission);
         locked_inner.client_by_id.insert(client_id, client_wrapper);
         let incoming_future = incoming
             .then(move |result| Self::handle_websocket_errors(result, &logger_2, socket_addr))
@@ -315,6 +366,34 @@ impl WebSocketSupervisorReal {
             }
         };
         match UiTrafficConverter::new_unmarshal_from_ui(message, client_id) {
+            Ok(from_ui_message) if !Self::is_permitted(&locked_inner, &from_ui_message) => {
+                warning!(
+                    logger,
+                    "Client {} at {} isn't permitted to send '{}' messages; refused",
+                    client_id,
+                    socket_addr,
+                    from_ui_message.body.opcode
+                );
+                let opcode = from_ui_message.body.opcode.clone();
+                Self::send_msg_safely(
+                    locked_inner,
+                    inner_arc,
+                    NodeToUiMessage {
+                        target: ClientId(client_id),
+                        body: MessageBody {
+                            payload: Err((
+                                UI_PERMISSION_ERROR,
+                                format!(
+                                    "This connection's token doesn't permit '{}' requests",
+                                    opcode
+                                ),
+                            )),
+                            ..from_ui_message.body
+                        },
+                    },
+                );
+                return ok::<(), ()>(());
+            }
             Ok(from_ui_message) => {
                 locked_inner
                     .from_ui_message_sub
@@ -385,6 +464,19 @@ impl WebSocketSupervisorReal {
         ok::<(), ()>(())
     }
 
+    fn is_permitted(
+        locked_inner: &MutexGuard<WebSocketSupervisorInner>,
+        from_ui_message: &NodeFromUiMessage,
+    ) -> bool {
+        match locked_inner
+            .permission_by_client_id
+            .get(&from_ui_message.client_id)
+        {
+            Some(permission) => permission.permits(&from_ui_message.body.opcode),
+            None => false,
+        }
+    }
+
     fn handle_close_message(
         inner_arc: &Arc<Mutex<WebSocketSupervisorInner>>,
         logger: &Logger,
@@ -492,6 +584,7 @@ impl WebSocketSupervisorReal {
             .client_by_id
             .remove(&client_id)
             .expectv("client");
+        let _ = locked_inner.permission_by_client_id.remove(&client_id);
         let socket_addr = locked_inner
             .socket_addr_by_client_id
             .remove(&client_id)
@@ -528,6 +621,7 @@ impl WebSocketSupervisorReal {
         logger: &Logger,
     ) {
         let _ = locked_inner.socket_addr_by_client_id.remove(&client_id);
+        let _ = locked_inner.permission_by_client_id.remove(&client_id);
         let mut client = match locked_inner.client_by_id.remove(&client_id) {
             Some(client) => client,
             None => panic!("WebSocketSupervisor got a disconnect from a client that has disappeared from the stable!"),
@@ -569,6 +663,7 @@ pub trait WebSocketSupervisorFactory: Send {
     fn make(
         &self,
         port: u16,
+        ui_tokens_opt: Option<UiTokens>,
         recipient: Recipient<NodeFromUiMessage>,
     ) -> std::io::Result<Box<dyn WebSocketSupervisor>>;
 }
@@ -579,9 +674,10 @@ impl WebSocketSupervisorFactory for WebsocketSupervisorFactoryReal {
     fn make(
         &self,
         port: u16,
+        ui_tokens_opt: Option<UiTokens>,
         recipient: Recipient<NodeFromUiMessage>,
     ) -> std::io::Result<Box<dyn WebSocketSupervisor>> {
-        WebSocketSupervisorReal::new(port, recipient)
+        WebSocketSupervisorReal::new(port, ui_tokens_opt, recipient)
             .map(|positive| positive as Box<dyn WebSocketSupervisor>)
     }
 }
@@ -597,12 +693,13 @@ mod tests {
     use futures::lazy;
     use masq_lib::constants::UNMARSHAL_ERROR;
     use masq_lib::messages::{
-        FromMessageBody, UiDescriptorResponse, UiShutdownRequest, UiStartOrder, UiUnmarshalError,
-        NODE_UI_PROTOCOL,
+        FromMessageBody, UiDescriptorRequest, UiDescriptorResponse, UiShutdownRequest,
+        UiShutdownResponse, UiStartOrder, UiUnmarshalError, NODE_UI_PROTOCOL,
     };
     use masq_lib::test_utils::logging::init_test_logging;
     use masq_lib::test_utils::logging::TestLogHandler;
     use masq_lib::test_utils::ui_connection::UiConnection;
+    use masq_lib::ui_auth::ui_token_headers;
     use masq_lib::ui_gateway::MessagePath::FireAndForget;
     use masq_lib::ui_gateway::NodeFromUiMessage;
     use masq_lib::ui_traffic_converter::UiTrafficConverter;
@@ -628,6 +725,9 @@ mod tests {
             locked_inner
                 .client_by_id
                 .insert(client_id, Box::new(mock_client));
+            locked_inner
+                .permission_by_client_id
+                .insert(client_id, UiPermission::Admin);
             client_id
         }
 
@@ -732,6 +832,33 @@ mod tests {
             .connect_insecure()
     }
 
+    fn make_client_with_token(port: u16, token: &str) -> Result<Client<TcpStream>, WebSocketError> {
+        ClientBuilder::new(format!("ws://127.0.0.1:{}", port).as_str())
+            .expect("ClientBuilder could not be built")
+            .add_protocol(NODE_UI_PROTOCOL)
+            .custom_headers(&ui_token_headers(token))
+            .connect_insecure()
+    }
+
+    fn start_subject_with_tokens(port: u16, ui_gateway: Recorder, system_name: &'static str) {
+        thread::spawn(move || {
+            let system = System::new(system_name);
+            let ui_message_sub = subs(ui_gateway);
+            let subject = lazy(move || {
+                let _subject = WebSocketSupervisorReal::new(
+                    port,
+                    Some(UiTokens::new("admin-token", "read-only-token")),
+                    ui_message_sub,
+                )
+                .unwrap();
+                Ok(())
+            });
+            actix::spawn(subject);
+            system.run();
+        });
+        wait_for_server(port);
+    }
+
     fn wait_for_client(port: u16, protocol: &str) -> Client<TcpStream> {
         let mut one_client_opt: Option<Client<TcpStream>> = None;
         wait_for(None, None, || match make_client(port, protocol) {
@@ -774,7 +901,7 @@ mod tests {
             let system = System::new("logs_pre_upgrade_connection_errors");
             let ui_message_sub = subs(ui_gateway);
             let subject = lazy(move || {
-                let _subject = WebSocketSupervisorReal::new(port, ui_message_sub).unwrap();
+                let _subject = WebSocketSupervisorReal::new(port, None, ui_message_sub).unwrap();
                 Ok(())
             });
             actix::spawn(subject);
@@ -803,6 +930,7 @@ mod tests {
                     &inner_arc,
                     &logger,
                     socket_addr,
+                    UiPermission::ReadOnly,
                 );
                 //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
                 Ok(())
@@ -841,6 +969,10 @@ mod tests {
                             &0
                         );
                         assert!(inner_accessible.client_by_id.get(&0).is_some());
+                        assert_eq!(
+                            inner_accessible.permission_by_client_id.get(&0),
+                            Some(&UiPermission::ReadOnly)
+                        );
                         ok::<(), ()>(())
                     });
                 match future.wait() {
@@ -874,7 +1006,7 @@ mod tests {
             let system = System::new("rejects_connection_attempt_with_improper_protocol_name");
             let ui_message_sub = subs(ui_gateway);
             let subject = lazy(move || {
-                let _subject = WebSocketSupervisorReal::new(port, ui_message_sub).unwrap();
+                let _subject = WebSocketSupervisorReal::new(port, None, ui_message_sub).unwrap();
                 Ok(())
             });
             actix::spawn(subject);
@@ -891,6 +1023,93 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rejects_connection_attempts_without_a_valid_token() {
+        init_test_logging();
+        let port = find_free_port();
+        let (ui_gateway, _, _) = make_recorder();
+        start_subject_with_tokens(
+            port,
+            ui_gateway,
+            "rejects_connection_attempts_without_a_valid_token",
+        );
+
+        make_client(port, NODE_UI_PROTOCOL).err().unwrap();
+        make_client_with_token(port, "admin-tokem").err().unwrap();
+        make_client_with_token(port, "").err().unwrap();
+
+        let tlh = TestLogHandler::new();
+        tlh.await_log_matching(
+            "WARN: WebSocketSupervisor: UI at 127\\.0\\.0\\.1:\\d+ attempted connection without a valid X-MASQ-UI-Token header; rejected",
+            1000,
+        );
+    }
+
+    #[test]
+    fn admin_client_may_send_anything() {
+        let port = find_free_port();
+        let (ui_gateway, ui_gateway_awaiter, ui_gateway_recording_arc) = make_recorder();
+        start_subject_with_tokens(port, ui_gateway, "admin_client_may_send_anything");
+        let mut client =
+            UiConnection::make_with_token(port, NODE_UI_PROTOCOL, "admin-token").unwrap();
+
+        client.send_with_context_id(UiShutdownRequest {}, 4321);
+
+        ui_gateway_awaiter.await_message_count(1);
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeFromUiMessage>(0),
+            &NodeFromUiMessage {
+                client_id: 0,
+                body: UiShutdownRequest {}.tmb(4321),
+            }
+        );
+    }
+
+    #[test]
+    fn read_only_client_may_read_but_is_refused_anything_else() {
+        init_test_logging();
+        let port = find_free_port();
+        let (ui_gateway, ui_gateway_awaiter, ui_gateway_recording_arc) = make_recorder();
+        start_subject_with_tokens(
+            port,
+            ui_gateway,
+            "read_only_client_may_read_but_is_refused_anything_else",
+        );
+        let mut client =
+            UiConnection::make_with_token(port, NODE_UI_PROTOCOL, "read-only-token").unwrap();
+
+        let error = client
+            .transact_with_context_id::<UiShutdownRequest, UiShutdownResponse>(
+                UiShutdownRequest {},
+                1234,
+            )
+            .err()
+            .unwrap();
+        client.send_with_context_id(UiDescriptorRequest {}, 2345);
+
+        assert_eq!(
+            error,
+            (
+                UI_PERMISSION_ERROR,
+                "This connection's token doesn't permit 'shutdown' requests".to_string()
+            )
+        );
+        ui_gateway_awaiter.await_message_count(1);
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeFromUiMessage>(0),
+            &NodeFromUiMessage {
+                client_id: 0,
+                body: UiDescriptorRequest {}.tmb(2345),
+            }
+        );
+        assert_eq!(ui_gateway_recording.len(), 1);
+        TestLogHandler::new().exists_log_matching(
+            "WARN: WebSocketSupervisor: Client 0 at 127\\.0\\.0\\.1:\\d+ isn't permitted to send 'shutdown' messages; refused",
+        );
+    }
+
     #[test]
     fn logs_unexpected_binary_ping_pong_websocket_messages() {
         init_test_logging();
@@ -901,7 +1120,7 @@ mod tests {
             let system = System::new("logs_unexpected_binary_ping_pong_websocket_messages");
             let ui_message_sub = subs(ui_gateway);
             let subject = lazy(move || {
-                let _subject = WebSocketSupervisorReal::new(port, ui_message_sub).unwrap();
+                let _subject = WebSocketSupervisorReal::new(port, None, ui_message_sub).unwrap();
                 Ok(())
             });
             actix::spawn(subject);
@@ -938,7 +1157,7 @@ mod tests {
             let system = System::new("can_connect_two_clients_and_receive_messages_from_them");
             let ui_message_sub = subs(ui_gateway);
             let subject = lazy(move || {
-                let _subject = WebSocketSupervisorReal::new(port, ui_message_sub).unwrap();
+                let _subject = WebSocketSupervisorReal::new(port, None, ui_message_sub).unwrap();
                 Ok(())
             });
             actix::spawn(subject);
@@ -1076,9 +1295,11 @@ mod tests {
         WebSocketSupervisorInner {
             port: 1234,
             next_client_id: 0,
+            ui_tokens_opt: None,
             from_ui_message_sub: ui_message_sub.start().recipient::<NodeFromUiMessage>(),
             client_id_by_socket_addr: Default::default(),
             socket_addr_by_client_id: Default::default(),
+            permission_by_client_id: Default::default(),
             client_by_id: Default::default(),
         }
     }
@@ -1234,9 +1455,11 @@ mod tests {
         let inner_arc = Arc::new(Mutex::new(WebSocketSupervisorInner {
             port: 0,
             next_client_id: 0,
+            ui_tokens_opt: None,
             from_ui_message_sub,
             client_id_by_socket_addr,
             socket_addr_by_client_id,
+            permission_by_client_id: HashMap::new(),
             client_by_id,
         }));
         let msg = NodeToUiMessage {
@@ -1310,7 +1533,7 @@ mod tests {
             let system = System::new("once_a_client_sends_a_close_no_more_data_is_accepted");
             let ui_message_sub = subs(ui_gateway);
             let subject = lazy(move || {
-                let subject = WebSocketSupervisorReal::new(port, ui_message_sub).unwrap();
+                let subject = WebSocketSupervisorReal::new(port, None, ui_message_sub).unwrap();
                 tx.send(subject.inner.clone()).unwrap();
                 Ok(())
             });
@@ -1383,7 +1606,7 @@ mod tests {
             let system = System::new("a_client_that_violates_the_protocol_is_terminated");
             let ui_message_sub = subs(ui_gateway);
             let subject = lazy(move || {
-                let _subject = WebSocketSupervisorReal::new(port, ui_message_sub).unwrap();
+                let _subject = WebSocketSupervisorReal::new(port, None, ui_message_sub).unwrap();
                 Ok(())
             });
             actix::spawn(subject);
@@ -1416,7 +1639,7 @@ mod tests {
         let ui_message_sub = subs(ui_gateway);
         let system = System::new("send_msg_sends_a_message_to_the_client");
         let lazy_future = lazy(move || {
-            let subject = WebSocketSupervisorReal::new(port, ui_message_sub).unwrap();
+            let subject = WebSocketSupervisorReal::new(port, None, ui_message_sub).unwrap();
             let one_mock_client = ClientWrapperMock::new()
                 .send_result(Ok(()))
                 .flush_result(Ok(()));
@@ -1457,7 +1680,7 @@ mod tests {
         let ui_message_sub = subs(ui_gateway);
         let system = System::new("send_msg_sends_a_message_to_the_client");
         let lazy_future = lazy(move || {
-            let subject = WebSocketSupervisorReal::new(port, ui_message_sub).unwrap();
+            let subject = WebSocketSupervisorReal::new(port, None, ui_message_sub).unwrap();
             let one_mock_client = ClientWrapperMock::new()
                 .send_result(Ok(()))
                 .flush_result(Ok(()));
@@ -1500,7 +1723,7 @@ mod tests {
         let ui_message_sub = subs(ui_gateway);
         let system = System::new("send_msg_sends_a_message_to_the_client");
         let lazy_future = lazy(move || {
-            let subject = WebSocketSupervisorReal::new(port, ui_message_sub).unwrap();
+            let subject = WebSocketSupervisorReal::new(port, None, ui_message_sub).unwrap();
             let one_mock_client = ClientWrapperMock::new()
                 .send_result(Ok(()))
                 .flush_result(Ok(()));
@@ -1558,7 +1781,7 @@ mod tests {
         let ui_message_sub = subs(ui_gateway);
         let system = System::new("send_msg_fails_to_look_up_client_to_send_to");
         let lazy_future = lazy(move || {
-            let subject = WebSocketSupervisorReal::new(port, ui_message_sub).unwrap();
+            let subject = WebSocketSupervisorReal::new(port, None, ui_message_sub).unwrap();
             let msg = NodeToUiMessage {
                 target: MessageTarget::ClientId(7),
                 body: MessageBody {
@@ -3,6 +3,7 @@
 
 use crate::ui_gateway::websocket_supervisor::{WebSocketSupervisor, WebSocketSupervisorFactory};
 use actix::Recipient;
+use masq_lib::ui_auth::UiTokens;
 use masq_lib::ui_gateway::{NodeFromUiMessage, NodeToUiMessage};
 use std::cell::RefCell;
 use std::sync::{Arc, Mutex};
@@ -34,12 +35,20 @@ impl WebSocketSupervisorMock {
     }
 }
 
+type MakeParams = (u16, Option<UiTokens>);
+
 #[derive(Default)]
 pub struct WebsocketSupervisorFactoryMock {
+    make_params: Arc<Mutex<Vec<MakeParams>>>,
     make_results: RefCell<Vec<std::io::Result<Box<dyn WebSocketSupervisor>>>>,
 }
 
 impl WebsocketSupervisorFactoryMock {
+    pub fn make_params(mut self, params: &Arc<Mutex<Vec<MakeParams>>>) -> Self {
+        self.make_params = params.clone();
+        self
+    }
+
     pub fn make_result(self, result: std::io::Result<Box<dyn WebSocketSupervisor>>) -> Self {
         self.make_results.borrow_mut().push(result);
         self
@@ -49,9 +58,11 @@ impl WebsocketSupervisorFactoryMock {
 impl WebSocketSupervisorFactory for WebsocketSupervisorFactoryMock {
     fn make(
         &self,
-        _port: u16,
+        port: u16,
+        ui_tokens_opt: Option<UiTokens>,
         _recipient: Recipient<NodeFromUiMessage>,
     ) -> std::io::Result<Box<dyn WebSocketSupervisor>> {
+        self.make_params.lock().unwrap().push((port, ui_tokens_opt));
         self.make_results.borrow_mut().remove(0)
     }
 }
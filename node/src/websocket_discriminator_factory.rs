@@ -0,0 +1,58 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::discriminator::Discriminator;
+use crate::discriminator::DiscriminatorFactory;
+use crate::websocket_framer::WebSocketFramer;
+use crate::websocket_masquerader::{WebSocketMasquerader, WebSocketRole};
+
+#[derive(Debug, Default)]
+pub struct WebSocketDiscriminatorFactory {}
+
+impl DiscriminatorFactory for WebSocketDiscriminatorFactory {
+    fn make(&self) -> Discriminator {
+        // Unmasking accepts both masked and unmasked frames, so the role doesn't matter here.
+        Discriminator::new(
+            Box::new(WebSocketFramer::new()),
+            vec![Box::new(WebSocketMasquerader::new(WebSocketRole::Server))],
+        )
+    }
+
+    fn duplicate(&self) -> Box<dyn DiscriminatorFactory> {
+        Box::new(WebSocketDiscriminatorFactory {})
+    }
+}
+
+impl WebSocketDiscriminatorFactory {
+    pub fn new() -> WebSocketDiscriminatorFactory {
+        WebSocketDiscriminatorFactory {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discriminator::UnmaskedChunk;
+    use crate::masquerader::Masquerader;
+
+    #[test]
+    fn discriminator_factory_duplicate_works() {
+        let subject = WebSocketDiscriminatorFactory::new();
+
+        subject.duplicate();
+
+        // no panic; test passes
+    }
+
+    #[test]
+    fn factory_makes_discriminator_that_unmasks_client_frames() {
+        let data = &b"I am contained in a WebSocket frame!"[..];
+        let masquerader = WebSocketMasquerader::new(WebSocketRole::Client);
+        let frame = masquerader.mask(data).unwrap();
+        let subject = WebSocketDiscriminatorFactory::new();
+        let mut discriminator = subject.make();
+
+        discriminator.add_data(&frame[..]);
+        let result = discriminator.take_chunk();
+
+        assert_eq!(result, Some(UnmaskedChunk::new(data.to_vec(), true, false)))
+    }
+}
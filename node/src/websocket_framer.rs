@@ -0,0 +1,203 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::framer::FramedChunk;
+use crate::sub_lib::framer::Framer;
+
+// Anything bigger than this is certainly not a CORES package; treat it as garbage.
+pub const MAX_WEBSOCKET_PAYLOAD_LEN: usize = 0x0100_0000;
+
+pub const WEBSOCKET_MASK_BIT: u8 = 0x80;
+pub const WEBSOCKET_FIN_BIT: u8 = 0x80;
+
+#[derive(Default)]
+pub struct WebSocketFramer {
+    data_so_far: Vec<u8>,
+}
+
+impl Framer for WebSocketFramer {
+    fn add_data(&mut self, data: &[u8]) {
+        self.data_so_far.extend(data);
+    }
+
+    fn take_frame(&mut self) -> Option<FramedChunk> {
+        let (header_len, payload_len) = match websocket_frame_lengths(&self.data_so_far) {
+            Ok(Some(lengths)) => lengths,
+            Ok(None) => return None,
+            Err(_) => {
+                self.data_so_far.clear();
+                return None;
+            }
+        };
+        let frame_len = header_len + payload_len;
+        if self.data_so_far.len() < frame_len {
+            return None;
+        }
+        let leftovers = self.data_so_far.split_off(frame_len);
+        let chunk = std::mem::replace(&mut self.data_so_far, leftovers);
+        Some(FramedChunk {
+            chunk,
+            last_chunk: true,
+        })
+    }
+}
+
+impl WebSocketFramer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+// Returns Ok(Some((header length, payload length))) if enough of the frame header is present to
+// know both, Ok(None) if more data is needed, and Err if the header describes an impossible frame.
+pub fn websocket_frame_lengths(data: &[u8]) -> Result<Option<(usize, usize)>, String> {
+    if data.len() < 2 {
+        return Ok(None);
+    }
+    let mask_len = if (data[1] & WEBSOCKET_MASK_BIT) != 0 {
+        4
+    } else {
+        0
+    };
+    let (length_len, payload_len) = match data[1] & 0x7F {
+        126 => {
+            if data.len() < 4 {
+                return Ok(None);
+            }
+            (2, ((data[2] as usize) << 8) | (data[3] as usize))
+        }
+        127 => {
+            if data.len() < 10 {
+                return Ok(None);
+            }
+            let mut length_bytes = [0u8; 8];
+            length_bytes.copy_from_slice(&data[2..10]);
+            let length = u64::from_be_bytes(length_bytes);
+            if length > MAX_WEBSOCKET_PAYLOAD_LEN as u64 {
+                return Err(format!(
+                    "WebSocket frame payload of {} bytes exceeds maximum of {}",
+                    length, MAX_WEBSOCKET_PAYLOAD_LEN
+                ));
+            }
+            (8, length as usize)
+        }
+        short_length => (0, short_length as usize),
+    };
+    Ok(Some((2 + length_len + mask_len, payload_len)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(MAX_WEBSOCKET_PAYLOAD_LEN, 0x0100_0000);
+        assert_eq!(WEBSOCKET_MASK_BIT, 0x80);
+        assert_eq!(WEBSOCKET_FIN_BIT, 0x80);
+    }
+
+    #[test]
+    fn returns_none_when_there_is_no_data() {
+        let mut subject = WebSocketFramer::new();
+
+        let result = subject.take_frame();
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn frames_a_short_unmasked_frame_and_retains_leftovers() {
+        let mut subject = WebSocketFramer::new();
+        subject.add_data(&[0x82, 0x03, 0x01, 0x02, 0x03, 0x82, 0x01]);
+
+        let first = subject.take_frame();
+        let second = subject.take_frame();
+        subject.add_data(&[0x04]);
+        let third = subject.take_frame();
+
+        assert_eq!(
+            first,
+            Some(FramedChunk {
+                chunk: vec![0x82, 0x03, 0x01, 0x02, 0x03],
+                last_chunk: true,
+            })
+        );
+        assert_eq!(second, None);
+        assert_eq!(
+            third,
+            Some(FramedChunk {
+                chunk: vec![0x82, 0x01, 0x04],
+                last_chunk: true,
+            })
+        );
+    }
+
+    #[test]
+    fn frames_a_masked_frame_with_sixteen_bit_length_delivered_in_pieces() {
+        let payload = vec![0xA5u8; 300];
+        let mut frame = vec![0x82, WEBSOCKET_MASK_BIT | 126, 0x01, 0x2C, 1, 2, 3, 4];
+        frame.extend(&payload);
+        let mut subject = WebSocketFramer::new();
+
+        subject.add_data(&frame[0..3]);
+        let first = subject.take_frame();
+        subject.add_data(&frame[3..100]);
+        let second = subject.take_frame();
+        subject.add_data(&frame[100..]);
+        let third = subject.take_frame();
+
+        assert_eq!(first, None);
+        assert_eq!(second, None);
+        assert_eq!(
+            third,
+            Some(FramedChunk {
+                chunk: frame,
+                last_chunk: true,
+            })
+        );
+    }
+
+    #[test]
+    fn frame_lengths_handles_sixty_four_bit_length() {
+        let data = [0x82, 127, 0, 0, 0, 0, 0, 1, 0, 0];
+
+        let result = websocket_frame_lengths(&data);
+
+        assert_eq!(result, Ok(Some((10, 0x0001_0000))));
+    }
+
+    #[test]
+    fn frame_lengths_rejects_absurd_payload_length() {
+        let data = [0x82, 127, 0, 0, 0, 1, 0, 0, 0, 0];
+
+        let result = websocket_frame_lengths(&data);
+
+        assert_eq!(
+            result,
+            Err(
+                "WebSocket frame payload of 4294967296 bytes exceeds maximum of 16777216"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn absurd_frame_causes_accumulated_data_to_be_discarded() {
+        let mut subject = WebSocketFramer::new();
+        subject.add_data(&[
+            0x82, 127, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 1, 2,
+        ]);
+
+        let first = subject.take_frame();
+        subject.add_data(&[0x82, 0x01, 0x09]);
+        let second = subject.take_frame();
+
+        assert_eq!(first, None);
+        assert_eq!(
+            second,
+            Some(FramedChunk {
+                chunk: vec![0x82, 0x01, 0x09],
+                last_chunk: true,
+            })
+        );
+    }
+}
@@ -0,0 +1,309 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::discriminator::UnmaskedChunk;
+use crate::masquerader::MasqueradeError;
+use crate::masquerader::Masquerader;
+use crate::websocket_framer::{websocket_frame_lengths, WEBSOCKET_FIN_BIT, WEBSOCKET_MASK_BIT};
+use masq_lib::logger::Logger;
+
+const WEBSOCKET_RESERVED_BITS: u8 = 0x70;
+const WEBSOCKET_OPCODE_BITS: u8 = 0x0F;
+const WEBSOCKET_OPCODE_BINARY: u8 = 0x02;
+const WEBSOCKET_OPCODE_CLOSE: u8 = 0x08;
+const WEBSOCKET_OPCODE_PING: u8 = 0x09;
+const WEBSOCKET_OPCODE_PONG: u8 = 0x0A;
+const WEBSOCKET_MAX_CONTROL_PAYLOAD_LEN: usize = 125;
+
+// RFC 6455 requires frames from the client to be masked and frames from the server to be unmasked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WebSocketRole {
+    Client,
+    Server,
+}
+
+pub struct WebSocketMasquerader {
+    role: WebSocketRole,
+    logger: Logger,
+}
+
+impl Masquerader for WebSocketMasquerader {
+    fn try_unmask(&self, item: &[u8]) -> Result<UnmaskedChunk, MasqueradeError> {
+        match self.unmask(item) {
+            Ok(chunk) => Ok(chunk),
+            Err(
+                err @ (MasqueradeError::NotThisMasquerader
+                | MasqueradeError::NothingToUnmask
+                | MasqueradeError::PeerClosing),
+            ) => Err(err),
+            Err(err) => {
+                error!(self.logger, "{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    fn mask(&self, data: &[u8]) -> Result<Vec<u8>, MasqueradeError> {
+        let mut frame = Vec::with_capacity(data.len() + 14);
+        frame.push(WEBSOCKET_FIN_BIT | WEBSOCKET_OPCODE_BINARY);
+        let mask_bit = match self.role {
+            WebSocketRole::Client => WEBSOCKET_MASK_BIT,
+            WebSocketRole::Server => 0x00,
+        };
+        if data.len() < 126 {
+            frame.push(mask_bit | (data.len() as u8));
+        } else if data.len() <= 0xFFFF {
+            frame.push(mask_bit | 126);
+            frame.extend(&(data.len() as u16).to_be_bytes());
+        } else {
+            frame.push(mask_bit | 127);
+            frame.extend(&(data.len() as u64).to_be_bytes());
+        }
+        match self.role {
+            WebSocketRole::Client => {
+                let masking_key: [u8; 4] = rand::random();
+                frame.extend(&masking_key);
+                frame.extend(Self::apply_masking_key(data, &masking_key));
+            }
+            WebSocketRole::Server => frame.extend(data),
+        }
+        Ok(frame)
+    }
+}
+
+impl WebSocketMasquerader {
+    pub fn new(role: WebSocketRole) -> WebSocketMasquerader {
+        WebSocketMasquerader {
+            role,
+            logger: Logger::new("WebSocketMasquerader"),
+        }
+    }
+
+    fn unmask(&self, item: &[u8]) -> Result<UnmaskedChunk, MasqueradeError> {
+        let (header_len, payload_len) = match websocket_frame_lengths(item) {
+            Ok(Some(lengths)) => lengths,
+            Ok(None) => return Err(MasqueradeError::NotThisMasquerader),
+            Err(e) => return Err(MasqueradeError::LowLevelDataError(e)),
+        };
+        if (item[0] & WEBSOCKET_RESERVED_BITS) != 0 {
+            return Err(MasqueradeError::NotThisMasquerader);
+        }
+        if item.len() != header_len + payload_len {
+            return Err(MasqueradeError::LowLevelDataError(format!(
+                "WebSocket frame should be {} bytes long, but is {}",
+                header_len + payload_len,
+                item.len()
+            )));
+        }
+        let opcode = item[0] & WEBSOCKET_OPCODE_BITS;
+        let is_final = (item[0] & WEBSOCKET_FIN_BIT) != 0;
+        let is_control_size = payload_len <= WEBSOCKET_MAX_CONTROL_PAYLOAD_LEN;
+        match opcode {
+            WEBSOCKET_OPCODE_BINARY if is_final => (),
+            // Only the reading half of the stream gets here, so a ping can't be answered; dropping
+            // it is allowed, and our traffic shows the peer that we're alive anyway
+            WEBSOCKET_OPCODE_PING | WEBSOCKET_OPCODE_PONG if is_final && is_control_size => {
+                return Err(MasqueradeError::NothingToUnmask)
+            }
+            WEBSOCKET_OPCODE_CLOSE if is_final && is_control_size => {
+                return Err(MasqueradeError::PeerClosing)
+            }
+            _ => {
+                return Err(MasqueradeError::MidLevelDataError(format!(
+                    "Expected a single-frame binary message, found opcode {:#x}{}",
+                    opcode,
+                    if is_final { "" } else { " without FIN" }
+                )))
+            }
+        }
+        let payload = &item[header_len..];
+        let data = if (item[1] & WEBSOCKET_MASK_BIT) != 0 {
+            let mut masking_key = [0u8; 4];
+            masking_key.copy_from_slice(&item[(header_len - 4)..header_len]);
+            Self::apply_masking_key(payload, &masking_key)
+        } else {
+            payload.to_vec()
+        };
+        Ok(UnmaskedChunk::new(data, true, false))
+    }
+
+    fn apply_masking_key(data: &[u8], masking_key: &[u8; 4]) -> Vec<u8> {
+        data.iter()
+            .enumerate()
+            .map(|(index, byte)| byte ^ masking_key[index % 4])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::logging::init_test_logging;
+    use masq_lib::test_utils::logging::TestLogHandler;
+
+    #[test]
+    fn server_masks_without_masking_key() {
+        let subject = WebSocketMasquerader::new(WebSocketRole::Server);
+
+        let result = subject.mask(&[1, 2, 3]).unwrap();
+
+        assert_eq!(result, vec![0x82, 0x03, 1, 2, 3]);
+    }
+
+    #[test]
+    fn client_masks_with_masking_key() {
+        let subject = WebSocketMasquerader::new(WebSocketRole::Client);
+
+        let result = subject.mask(&[1, 2, 3]).unwrap();
+
+        assert_eq!(result.len(), 9);
+        assert_eq!(&result[0..2], &[0x82, 0x83]);
+        let masking_key = &result[2..6];
+        assert_eq!(
+            &result[6..],
+            &[1 ^ masking_key[0], 2 ^ masking_key[1], 3 ^ masking_key[2]]
+        );
+    }
+
+    #[test]
+    fn mask_uses_extended_lengths_when_necessary() {
+        let subject = WebSocketMasquerader::new(WebSocketRole::Server);
+
+        let medium = subject.mask(&vec![0u8; 300]).unwrap();
+        let large = subject.mask(&vec![0u8; 70000]).unwrap();
+
+        assert_eq!(&medium[0..4], &[0x82, 126, 0x01, 0x2C]);
+        assert_eq!(medium.len(), 304);
+        assert_eq!(&large[0..10], &[0x82, 127, 0, 0, 0, 0, 0, 0x01, 0x11, 0x70]);
+        assert_eq!(large.len(), 70010);
+    }
+
+    #[test]
+    fn masked_and_unmasked_frames_round_trip_regardless_of_role() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        let client = WebSocketMasquerader::new(WebSocketRole::Client);
+        let server = WebSocketMasquerader::new(WebSocketRole::Server);
+
+        let from_client = server.try_unmask(&client.mask(&data).unwrap()).unwrap();
+        let from_server = client.try_unmask(&server.mask(&data).unwrap()).unwrap();
+
+        assert_eq!(from_client, UnmaskedChunk::new(data.clone(), true, false));
+        assert_eq!(from_server, UnmaskedChunk::new(data, true, false));
+    }
+
+    #[test]
+    fn frames_with_reserved_bits_are_not_for_this_masquerader() {
+        let subject = WebSocketMasquerader::new(WebSocketRole::Server);
+
+        let result = subject.try_unmask(&[0xC2, 0x01, 0x00]);
+
+        assert_eq!(result, Err(MasqueradeError::NotThisMasquerader));
+    }
+
+    #[test]
+    fn incomplete_frames_are_not_for_this_masquerader() {
+        let subject = WebSocketMasquerader::new(WebSocketRole::Server);
+
+        let result = subject.try_unmask(&[0x82]);
+
+        assert_eq!(result, Err(MasqueradeError::NotThisMasquerader));
+    }
+
+    #[test]
+    fn complains_about_frame_of_wrong_length() {
+        init_test_logging();
+        let subject = WebSocketMasquerader::new(WebSocketRole::Server);
+
+        let result = subject.try_unmask(&[0x82, 0x02, 0x01]);
+
+        assert_eq!(
+            result,
+            Err(MasqueradeError::LowLevelDataError(
+                "WebSocket frame should be 4 bytes long, but is 3".to_string()
+            ))
+        );
+        TestLogHandler::new().exists_log_containing(
+            "ERROR: WebSocketMasquerader: Low-level data error: WebSocket frame should be 4 bytes long, but is 3",
+        );
+    }
+
+    #[test]
+    fn complains_about_text_frames() {
+        let subject = WebSocketMasquerader::new(WebSocketRole::Server);
+
+        let result = subject.try_unmask(&[0x81, 0x01, 0x41]);
+
+        assert_eq!(
+            result,
+            Err(MasqueradeError::MidLevelDataError(
+                "Expected a single-frame binary message, found opcode 0x1".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn pings_are_dropped() {
+        let subject = WebSocketMasquerader::new(WebSocketRole::Server);
+
+        let unmasked_result = subject.try_unmask(&[0x89, 0x02, 0x68, 0x69]);
+        let masked_result = subject.try_unmask(&[0x89, 0x82, 0x01, 0x02, 0x03, 0x04, 0x69, 0x6B]);
+
+        assert_eq!(unmasked_result, Err(MasqueradeError::NothingToUnmask));
+        assert_eq!(masked_result, Err(MasqueradeError::NothingToUnmask));
+    }
+
+    #[test]
+    fn pongs_are_ignored() {
+        let subject = WebSocketMasquerader::new(WebSocketRole::Client);
+
+        let result = subject.try_unmask(&[0x8A, 0x00]);
+
+        assert_eq!(result, Err(MasqueradeError::NothingToUnmask));
+    }
+
+    #[test]
+    fn close_frames_announce_that_the_peer_is_closing() {
+        let subject = WebSocketMasquerader::new(WebSocketRole::Server);
+
+        let empty_result = subject.try_unmask(&[0x88, 0x00]);
+        let with_status_result = subject.try_unmask(&[0x88, 0x02, 0x03, 0xE8]);
+
+        assert_eq!(empty_result, Err(MasqueradeError::PeerClosing));
+        assert_eq!(with_status_result, Err(MasqueradeError::PeerClosing));
+    }
+
+    #[test]
+    fn complains_about_oversized_or_fragmented_control_frames() {
+        let subject = WebSocketMasquerader::new(WebSocketRole::Server);
+        let mut oversized_ping = vec![0x89, 126, 0x00, 126];
+        oversized_ping.extend(vec![0u8; 126]);
+
+        let oversized_result = subject.try_unmask(&oversized_ping);
+        let fragmented_result = subject.try_unmask(&[0x08, 0x00]);
+
+        assert_eq!(
+            oversized_result,
+            Err(MasqueradeError::MidLevelDataError(
+                "Expected a single-frame binary message, found opcode 0x9".to_string()
+            ))
+        );
+        assert_eq!(
+            fragmented_result,
+            Err(MasqueradeError::MidLevelDataError(
+                "Expected a single-frame binary message, found opcode 0x8 without FIN".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn complains_about_fragmented_frames() {
+        let subject = WebSocketMasquerader::new(WebSocketRole::Server);
+
+        let result = subject.try_unmask(&[0x02, 0x01, 0x41]);
+
+        assert_eq!(
+            result,
+            Err(MasqueradeError::MidLevelDataError(
+                "Expected a single-frame binary message, found opcode 0x2 without FIN".to_string()
+            ))
+        );
+    }
+}
@@ -69,6 +69,7 @@ fn financials_command_retrieves_payable_and_receivable_records() {
             ordered_by: TopRecordsOrdering::Balance,
         }),
         custom_queries_opt: None,
+        page_opt: None,
     };
     let mut client = UiConnection::new(port, NODE_UI_PROTOCOL);
     let before = SystemTime::now();
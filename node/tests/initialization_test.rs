@@ -82,6 +82,7 @@ fn initialization_sequence_integration() {
         stats_required: true,
         top_records_opt: None,
         custom_queries_opt: None,
+        page_opt: None,
     };
     let context_id = 1234;
 
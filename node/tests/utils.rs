@@ -474,7 +474,10 @@ impl MASQNode {
     }
 
     fn daemon_args() -> Vec<String> {
-        CommandConfig::new().opt("--initialization").args
+        CommandConfig::new()
+            .opt("--initialization")
+            .pair("--ui-authentication", "off")
+            .args
     }
 
     fn standard_args() -> Vec<String> {
@@ -485,6 +488,7 @@ impl MASQNode {
                 "CCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCC",
             )
             .pair("--log-level", "trace")
+            .pair("--ui-authentication", "off")
             .args
     }
 